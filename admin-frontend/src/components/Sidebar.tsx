@@ -79,6 +79,18 @@ export default function Sidebar({ isOpen, onClose }: SidebarProps) {
                       <path strokeLinecap="round" strokeLinejoin="round" d="M3.75 6A2.25 2.25 0 016 3.75h2.25A2.25 2.25 0 0110.5 6v2.25a2.25 2.25 0 01-2.25 2.25H6a2.25 2.25 0 01-2.25-2.25V6zM3.75 15.75A2.25 2.25 0 016 13.5h2.25a2.25 2.25 0 012.25 2.25V18a2.25 2.25 0 01-2.25 2.25H6A2.25 2.25 0 013.75 18v-2.25zM13.5 6a2.25 2.25 0 012.25-2.25H18A2.25 2.25 0 0120.25 6v2.25A2.25 2.25 0 0118 10.5h-2.25a2.25 2.25 0 01-2.25-2.25V6zM13.5 15.75a2.25 2.25 0 012.25-2.25H18a2.25 2.25 0 012.25 2.25V18A2.25 2.25 0 0118 20.25h-2.25A2.25 2.25 0 0113.5 18v-2.25z" />
                     </svg>
                     <span className="capitalize">{model.verbose_name_plural}</span>
+                    {model.count && (
+                      <span
+                        className={`ml-auto rounded-full bg-gray-100 px-2 py-0.5 text-xs ${
+                          model.count.stale ? 'text-gray-400' : 'text-gray-600'
+                        }`}
+                        title={`Counted ${new Date(model.count.counted_at).toLocaleString()}${
+                          model.count.stale ? ' (may be out of date)' : ''
+                        }`}
+                      >
+                        {model.count.count.toLocaleString()}
+                      </span>
+                    )}
                   </Link>
                 );
               })}
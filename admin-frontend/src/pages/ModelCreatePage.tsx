@@ -7,6 +7,16 @@ import ErrorAlert from '../components/ErrorAlert';
 import Breadcrumbs from '../components/Breadcrumbs';
 import FormField from '../components/FormField';
 
+/** Mirrors the server's `slugify`, so the preview matches what is saved. */
+function slugify(value: string, maxLength: number | null): string {
+  const slug = value
+    .toLowerCase()
+    .replace(/[^\p{L}\p{N}_\s-]/gu, '')
+    .replace(/[-\s]+/g, '-')
+    .replace(/^-+|-+$/g, '');
+  return maxLength ? slug.slice(0, maxLength).replace(/-+$/, '') : slug;
+}
+
 export default function ModelCreatePage() {
   const { app, model } = useParams<{ app: string; model: string }>();
   const navigate = useNavigate();
@@ -24,12 +34,31 @@ export default function ModelCreatePage() {
   const createMutation = useCreateObject(appLabel, modelName);
 
   const [formData, setFormData] = useState<Record<string, unknown>>({});
+  // Prepopulated fields the user has typed in, which stop following their
+  // sources.
+  const [editedSlugs, setEditedSlugs] = useState<Set<string>>(new Set());
 
   const handleFieldChange = useCallback(
     (name: string, value: unknown) => {
-      setFormData((prev) => ({ ...prev, [name]: value }));
+      const prepopulated = schema?.prepopulated_fields ?? {};
+      if (name in prepopulated) {
+        setEditedSlugs((prev) => new Set(prev).add(name));
+      }
+      setFormData((prev) => {
+        const next = { ...prev, [name]: value };
+        for (const [field, sources] of Object.entries(prepopulated)) {
+          if (!sources.includes(name) || editedSlugs.has(field)) continue;
+          const maxLength =
+            schema?.fields.find((f) => f.name === field)?.max_length ?? null;
+          next[field] = slugify(
+            sources.map((source) => String(next[source] ?? '')).join(' '),
+            maxLength,
+          );
+        }
+        return next;
+      });
     },
-    [],
+    [schema, editedSlugs],
   );
 
   const handleSubmit = useCallback(
@@ -42,8 +71,7 @@ export default function ModelCreatePage() {
           'success',
         );
         // Navigate to the edit page of the newly created object
-        const pkField =
-          schema?.fields.find((f) => f.primary_key)?.name ?? 'id';
+        const pkField = schema?.lookup_field ?? 'id';
         const newPk = created[pkField];
         if (newPk !== undefined) {
           navigate(`/${appLabel}/${modelName}/${newPk}/edit`);
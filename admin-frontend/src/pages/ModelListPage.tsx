@@ -90,8 +90,8 @@ export default function ModelListPage() {
     [],
   );
 
-  // Find PK field
-  const pkField = schema?.fields.find((f) => f.primary_key)?.name ?? 'id';
+  // Field identifying objects in URLs
+  const pkField = schema?.lookup_field ?? 'id';
 
   if (schemaLoading) {
     return <LoadingSpinner size="lg" className="mt-20" />;
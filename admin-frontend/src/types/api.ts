@@ -40,11 +40,18 @@ export interface AppModels {
   models: ModelInfo[];
 }
 
+export interface ModelCount {
+  count: number;
+  counted_at: string;
+  stale: boolean;
+}
+
 export interface ModelInfo {
   name: string;
   verbose_name: string;
   verbose_name_plural: string;
   url: string;
+  count?: ModelCount;
 }
 
 // ── Model Schema ────────────────────────────────────────────────────
@@ -56,24 +63,80 @@ export interface ModelSchemaResponse {
   verbose_name_plural: string;
   fields: FieldSchema[];
   list_display: string[];
+  list_columns: ListColumn[];
   search_fields: string[];
   ordering: string[];
   actions: string[];
   list_per_page: number;
+  quick_create_fields: string[];
+  scheduled_publishing: ScheduledPublishing | null;
+  /** Slug fields filled in from other fields, mapped to their sources. */
+  prepopulated_fields: Record<string, string[]>;
+  /** The field whose value identifies objects in URLs. */
+  lookup_field: string;
+}
+
+/** Datetime fields driving scheduled publishing. */
+export interface ScheduledPublishing {
+  publish_at: string;
+  unpublish_at: string | null;
 }
 
+/** Computed publishing state, under `publish_status` in list results. */
+export type PublishStatus = 'draft' | 'scheduled' | 'live' | 'expired';
+
 export interface FieldSchema {
   name: string;
   field_type: string;
   required: boolean;
   read_only: boolean;
   primary_key: boolean;
+  unique: boolean;
   max_length: number | null;
   label: string;
   help_text: string;
   choices: [string, string][] | null;
   is_relation: boolean;
   related_model: string | null;
+  default: unknown;
+}
+
+export interface QuickCreateResponse {
+  id: string | number;
+  label: string;
+}
+
+export type ColumnDataType =
+  | 'text'
+  | 'integer'
+  | 'decimal'
+  | 'boolean'
+  | 'date'
+  | 'date_time'
+  | 'time'
+  | 'choice'
+  | 'relation'
+  | 'json';
+
+export type ColumnAlign = 'left' | 'center' | 'right';
+
+export type ColumnLink = { type: 'detail' } | { type: 'related'; target: string };
+
+export interface ListColumn {
+  name: string;
+  label: string;
+  sortable: boolean;
+  sort_field: string | null;
+  data_type: ColumnDataType;
+  align: ColumnAlign;
+  link: ColumnLink | null;
+  humanized: boolean;
+}
+
+/** Exact and relative forms of a humanized datetime, under `_datetimes`. */
+export interface HumanizedDateTime {
+  iso: string;
+  relative: string;
 }
 
 // ── List Response (Paginated) ───────────────────────────────────────
@@ -81,6 +144,8 @@ export interface FieldSchema {
 export interface JsonListResponse {
   results: Record<string, unknown>[];
   count: number;
+  next: string | null;
+  previous: string | null;
   page: number;
   page_size: number;
   total_pages: number;
@@ -139,6 +204,106 @@ export interface BulkActionResponse {
   message: string;
 }
 
+// ── Permissions ─────────────────────────────────────────────────────
+
+export interface Permission {
+  codename: string;
+  name: string;
+  content_type: string;
+}
+
+export interface GroupPermissions {
+  name: string;
+  /** Granted permissions as "app_label.codename". */
+  permissions: string[];
+}
+
+export interface UserPermissions {
+  username: string;
+  is_superuser: boolean;
+  groups: string[];
+  /** Permissions granted to the user directly, as "app_label.codename". */
+  permissions: string[];
+}
+
+export interface PermissionMatrix {
+  permissions: Permission[];
+  groups: GroupPermissions[];
+  users: UserPermissions[];
+}
+
+export interface PermissionChange {
+  subject: 'group' | 'user';
+  name: string;
+  permission: string;
+  granted: boolean;
+}
+
+export interface PermissionMatrixUpdate {
+  changes: PermissionChange[];
+}
+
+export interface PermissionMatrixUpdateResponse {
+  applied: number;
+  matrix: PermissionMatrix;
+}
+
+// ── Documentation ───────────────────────────────────────────────────
+
+export interface FieldDoc {
+  name: string;
+  field_type: string;
+  label: string;
+  help_text: string;
+  required: boolean;
+  primary_key: boolean;
+  related_model: string | null;
+}
+
+export interface RelationDoc {
+  field: string;
+  model: string;
+  kind: 'many_to_one' | 'one_to_one' | 'many_to_many';
+  reverse: boolean;
+}
+
+export interface ModelDoc {
+  key: string;
+  app_label: string;
+  model_name: string;
+  verbose_name: string;
+  verbose_name_plural: string;
+  fields: FieldDoc[];
+  relations: RelationDoc[];
+}
+
+export interface UrlDoc {
+  route: string;
+  name: string | null;
+}
+
+export interface TemplateLibraryDoc {
+  name: string | null;
+  tags: string[];
+  filters: string[];
+}
+
+export interface AdminDocs {
+  models: ModelDoc[];
+  urls: UrlDoc[];
+  template_libraries: TemplateLibraryDoc[];
+}
+
+// ── Drafts ──────────────────────────────────────────────────────────
+
+export interface AdminDraft {
+  content_type: string;
+  object_id: string;
+  data: Record<string, unknown>;
+  saved_at: string;
+  expires_at: string;
+}
+
 // ── API Error ───────────────────────────────────────────────────────
 
 export interface ApiError {
@@ -0,0 +1,376 @@
+//! Background execution of long-running admin actions.
+//!
+//! A bulk action over thousands of objects can take longer than an HTTP
+//! request may. Actions that return `true` from
+//! [`AdminAction::is_long_running`] are therefore queued as an [`ActionJob`]:
+//! the action endpoint answers `202 Accepted` with the job, a spawned task
+//! runs the action one object at a time with [`run_action_job`], and the
+//! frontend polls the job for its progress, per-object success and error
+//! tallies, and can request cancellation in between objects.
+//!
+//! Jobs are kept in an [`ActionJobStore`]; [`InMemoryActionJobStore`] is the
+//! default.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::action_jobs::{
+//!     run_action_job, ActionJob, ActionJobStatus, ActionJobStore, InMemoryActionJobStore,
+//! };
+//! use django_rs_admin::actions::DeleteSelectedAction;
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let store = InMemoryActionJobStore::new();
+//! let ids = vec!["1".to_string(), "2".to_string()];
+//! let job = store
+//!     .create(ActionJob::new("token-1", "blog.article", "delete_selected", ids.len()))
+//!     .await
+//!     .unwrap();
+//!
+//! let job = run_action_job(&DeleteSelectedAction, &store, job, &ids).await;
+//! assert_eq!(job.status, ActionJobStatus::Completed);
+//! assert_eq!(job.succeeded, 2);
+//! assert_eq!(store.get(job.id).await.unwrap().unwrap().processed(), 2);
+//! # });
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::AdminAction;
+
+/// How many per-object errors a job records; later failures are only counted.
+pub const MAX_RECORDED_ERRORS: usize = 100;
+
+/// The lifecycle state of an [`ActionJob`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionJobStatus {
+    /// Waiting for the background task to start.
+    Queued,
+    /// Processing objects.
+    Running,
+    /// Every object was processed.
+    Completed,
+    /// Stopped early at the user's request.
+    Cancelled,
+}
+
+/// An object the action failed on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionJobError {
+    /// The primary key of the object.
+    pub id: String,
+    /// Why the action failed.
+    pub message: String,
+}
+
+/// A bulk action running in the background, with its progress.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionJob {
+    /// The job id, assigned by the store.
+    pub id: u64,
+    /// The token of the user who started the job.
+    pub owner: String,
+    /// The model in `"app.model"` format.
+    pub model_key: String,
+    /// The name of the action.
+    pub action: String,
+    /// The lifecycle state.
+    pub status: ActionJobStatus,
+    /// How many objects were selected.
+    pub total: usize,
+    /// How many objects the action succeeded on.
+    pub succeeded: usize,
+    /// How many objects the action failed on.
+    pub failed: usize,
+    /// The first [`MAX_RECORDED_ERRORS`] failures.
+    pub errors: Vec<ActionJobError>,
+    /// Whether the user asked to stop the job.
+    pub cancel_requested: bool,
+    /// When the job was queued.
+    pub created_at: DateTime<Utc>,
+    /// When the job completed or was cancelled.
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ActionJob {
+    /// Creates a queued job for `total` selected objects.
+    pub fn new(owner: &str, model_key: &str, action: &str, total: usize) -> Self {
+        Self {
+            id: 0,
+            owner: owner.to_string(),
+            model_key: model_key.to_string(),
+            action: action.to_string(),
+            status: ActionJobStatus::Queued,
+            total,
+            succeeded: 0,
+            failed: 0,
+            errors: Vec::new(),
+            cancel_requested: false,
+            created_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    /// Returns how many objects have been processed.
+    pub const fn processed(&self) -> usize {
+        self.succeeded + self.failed
+    }
+
+    /// Returns `true` once the job completed or was cancelled.
+    pub const fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            ActionJobStatus::Completed | ActionJobStatus::Cancelled
+        )
+    }
+}
+
+/// Storage for background action jobs and their progress.
+#[async_trait]
+pub trait ActionJobStore: Send + Sync {
+    /// Stores a new job, assigning its id, and returns the stored copy.
+    async fn create(&self, job: ActionJob) -> Result<ActionJob, String>;
+
+    /// Replaces a job's progress.
+    ///
+    /// A cancellation requested in the meantime is kept: `update` never
+    /// clears [`ActionJob::cancel_requested`].
+    async fn update(&self, job: &ActionJob) -> Result<(), String>;
+
+    /// Returns a job by id.
+    async fn get(&self, id: u64) -> Result<Option<ActionJob>, String>;
+
+    /// Asks a job to stop before its next object and returns it, or `None`
+    /// if there is no such job. Finished jobs are returned unchanged.
+    async fn request_cancel(&self, id: u64) -> Result<Option<ActionJob>, String>;
+}
+
+#[derive(Debug, Default)]
+struct Jobs {
+    next_id: u64,
+    by_id: HashMap<u64, ActionJob>,
+}
+
+/// In-memory implementation of [`ActionJobStore`].
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryActionJobStore {
+    jobs: Arc<RwLock<Jobs>>,
+}
+
+impl InMemoryActionJobStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored jobs.
+    pub fn len(&self) -> usize {
+        self.jobs.read().unwrap().by_id.len()
+    }
+
+    /// Returns `true` if no jobs are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl ActionJobStore for InMemoryActionJobStore {
+    async fn create(&self, mut job: ActionJob) -> Result<ActionJob, String> {
+        let mut jobs = self.jobs.write().unwrap();
+        jobs.next_id += 1;
+        job.id = jobs.next_id;
+        jobs.by_id.insert(job.id, job.clone());
+        drop(jobs);
+        Ok(job)
+    }
+
+    async fn update(&self, job: &ActionJob) -> Result<(), String> {
+        let mut jobs = self.jobs.write().unwrap();
+        let stored = jobs
+            .by_id
+            .get_mut(&job.id)
+            .ok_or_else(|| format!("Action job {} not found", job.id))?;
+        let cancel_requested = stored.cancel_requested || job.cancel_requested;
+        *stored = job.clone();
+        stored.cancel_requested = cancel_requested;
+        drop(jobs);
+        Ok(())
+    }
+
+    async fn get(&self, id: u64) -> Result<Option<ActionJob>, String> {
+        Ok(self.jobs.read().unwrap().by_id.get(&id).cloned())
+    }
+
+    async fn request_cancel(&self, id: u64) -> Result<Option<ActionJob>, String> {
+        let mut jobs = self.jobs.write().unwrap();
+        let job = jobs.by_id.get_mut(&id).map(|job| {
+            if !job.is_finished() {
+                job.cancel_requested = true;
+            }
+            job.clone()
+        });
+        drop(jobs);
+        Ok(job)
+    }
+}
+
+/// Runs `action` on each of `ids` in turn, recording progress in `store`
+/// after every object, and returns the finished job.
+///
+/// Before each object the stored job is checked for a cancellation request;
+/// if there is one, the job stops as [`ActionJobStatus::Cancelled`]. Store
+/// errors are logged and do not stop the job.
+pub async fn run_action_job(
+    action: &dyn AdminAction,
+    store: &dyn ActionJobStore,
+    mut job: ActionJob,
+    ids: &[String],
+) -> ActionJob {
+    job.status = ActionJobStatus::Running;
+    save_progress(store, &job).await;
+
+    for id in ids {
+        if matches!(store.get(job.id).await, Ok(Some(stored)) if stored.cancel_requested) {
+            job.cancel_requested = true;
+            job.status = ActionJobStatus::Cancelled;
+            break;
+        }
+        match action.execute_one(&job.model_key, id).await {
+            Ok(()) => job.succeeded += 1,
+            Err(e) => {
+                job.failed += 1;
+                if job.errors.len() < MAX_RECORDED_ERRORS {
+                    job.errors.push(ActionJobError {
+                        id: id.clone(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+        save_progress(store, &job).await;
+    }
+
+    if job.status == ActionJobStatus::Running {
+        job.status = ActionJobStatus::Completed;
+    }
+    job.finished_at = Some(Utc::now());
+    save_progress(store, &job).await;
+    job
+}
+
+async fn save_progress(store: &dyn ActionJobStore, job: &ActionJob) {
+    if let Err(e) = store.update(job).await {
+        tracing::warn!("Failed to save progress of action job {}: {e}", job.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::ActionResult;
+    use django_rs_core::DjangoError;
+
+    /// Fails on odd ids and cancels its own job after the second object.
+    struct OddFails {
+        store: InMemoryActionJobStore,
+        cancel_after: Option<usize>,
+    }
+
+    #[async_trait]
+    impl AdminAction for OddFails {
+        fn name(&self) -> &'static str {
+            "odd_fails"
+        }
+
+        fn description(&self) -> &'static str {
+            "Fails on odd ids"
+        }
+
+        async fn execute(
+            &self,
+            _model_key: &str,
+            selected_ids: &[String],
+        ) -> Result<ActionResult, DjangoError> {
+            Ok(ActionResult::success("ok", selected_ids.len()))
+        }
+
+        async fn execute_one(&self, _model_key: &str, id: &str) -> Result<(), DjangoError> {
+            let n: usize = id.parse().unwrap();
+            if Some(n) == self.cancel_after {
+                self.store.request_cancel(1).await.unwrap();
+            }
+            if n % 2 == 1 {
+                return Err(DjangoError::BadRequest(format!("{id} is odd")));
+            }
+            Ok(())
+        }
+    }
+
+    fn ids(n: usize) -> Vec<String> {
+        (1..=n).map(|i| i.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_run_tallies_successes_and_errors() {
+        let store = InMemoryActionJobStore::new();
+        let action = OddFails {
+            store: store.clone(),
+            cancel_after: None,
+        };
+        let job = store
+            .create(ActionJob::new("u", "blog.article", "odd_fails", 5))
+            .await
+            .unwrap();
+        assert_eq!(job.id, 1);
+
+        let job = run_action_job(&action, &store, job, &ids(5)).await;
+        assert_eq!(job.status, ActionJobStatus::Completed);
+        assert_eq!((job.succeeded, job.failed), (2, 3));
+        assert_eq!(job.errors[0].id, "1");
+        assert!(job.errors[0].message.contains("1 is odd"));
+        assert!(job.finished_at.is_some());
+        assert_eq!(store.get(1).await.unwrap().unwrap(), job);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_before_next_object() {
+        let store = InMemoryActionJobStore::new();
+        let action = OddFails {
+            store: store.clone(),
+            cancel_after: Some(2),
+        };
+        let job = store
+            .create(ActionJob::new("u", "blog.article", "odd_fails", 10))
+            .await
+            .unwrap();
+
+        let job = run_action_job(&action, &store, job, &ids(10)).await;
+        assert_eq!(job.status, ActionJobStatus::Cancelled);
+        assert_eq!(job.processed(), 2);
+        assert!(store.get(1).await.unwrap().unwrap().cancel_requested);
+
+        // A finished job cannot be cancelled again.
+        let finished = store.request_cancel(1).await.unwrap().unwrap();
+        assert_eq!(finished.status, ActionJobStatus::Cancelled);
+        assert!(store.request_cancel(99).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_cancellation_request() {
+        let store = InMemoryActionJobStore::new();
+        let job = store
+            .create(ActionJob::new("u", "blog.article", "a", 3))
+            .await
+            .unwrap();
+        store.request_cancel(job.id).await.unwrap();
+        store.update(&job).await.unwrap();
+        assert!(store.get(job.id).await.unwrap().unwrap().cancel_requested);
+    }
+}
@@ -93,6 +93,33 @@ pub trait AdminAction: Send + Sync {
         model_key: &str,
         selected_ids: &[String],
     ) -> Result<ActionResult, DjangoError>;
+
+    /// Returns `true` if the action should run as a background job.
+    ///
+    /// Long-running actions are queued by the admin API, which answers with a
+    /// job to poll instead of waiting for the action to finish. See
+    /// [`action_jobs`](crate::action_jobs).
+    fn is_long_running(&self) -> bool {
+        false
+    }
+
+    /// Executes the action on a single object.
+    ///
+    /// Background jobs call this once per selected object to report progress
+    /// and per-object errors. The default calls [`execute`](Self::execute)
+    /// with the one id and treats an unsuccessful result as an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DjangoError` if the action fails on this object.
+    async fn execute_one(&self, model_key: &str, id: &str) -> Result<(), DjangoError> {
+        let result = self.execute(model_key, &[id.to_string()]).await?;
+        if result.success {
+            Ok(())
+        } else {
+            Err(DjangoError::BadRequest(result.message))
+        }
+    }
 }
 
 /// Built-in action that deletes the selected objects.
@@ -175,6 +202,14 @@ impl ActionRegistry {
             .collect()
     }
 
+    /// Returns the action with the given name.
+    pub fn get(&self, action_name: &str) -> Option<&dyn AdminAction> {
+        self.actions
+            .iter()
+            .find(|a| a.name() == action_name)
+            .map(AsRef::as_ref)
+    }
+
     /// Finds and executes an action by name.
     pub async fn execute(
         &self,
@@ -183,9 +218,7 @@ impl ActionRegistry {
         selected_ids: &[String],
     ) -> Result<ActionResult, DjangoError> {
         let action = self
-            .actions
-            .iter()
-            .find(|a| a.name() == action_name)
+            .get(action_name)
             .ok_or_else(|| DjangoError::NotFound(format!("Action '{action_name}' not found")))?;
 
         action.execute(model_key, selected_ids).await
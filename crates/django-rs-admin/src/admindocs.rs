@@ -0,0 +1,400 @@
+//! Generated project documentation, the equivalent of
+//! `django.contrib.admindocs`.
+//!
+//! [`AdminDocs`] collects what a team needs to find its way around a
+//! project: every registered model with its fields and relations, the URL
+//! patterns of the project's URL configuration, and the template tags and filters that
+//! can be used in templates. The admin site serves it at `GET /docs/`.
+//!
+//! Models are documented from their [`ModelAdmin`]'s field schema, so
+//! admins built with [`FieldSchema::from_field_def`] carry the model's
+//! verbose names, help text and relation targets. URL patterns are taken
+//! from the resolver given to
+//! [`AdminSite::docs_urls`](crate::site::AdminSite::docs_urls).
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::admindocs::AdminDocs;
+//! use django_rs_admin::model_admin::{FieldSchema, ModelAdmin};
+//!
+//! let post = ModelAdmin::new("blog", "post").fields_schema(vec![
+//!     FieldSchema::new("title", "CharField").help_text("Shown in listings"),
+//!     FieldSchema::new("author", "ForeignKey").relation("auth.user"),
+//! ]);
+//! let docs = AdminDocs::new([&post], Vec::new());
+//!
+//! let model = &docs.models[0];
+//! assert_eq!(model.key, "blog.post");
+//! assert_eq!(model.relations[0].model, "auth.user");
+//! assert!(docs.template_libraries[0].filters.contains(&"date".to_string()));
+//! ```
+
+use django_rs_http::urls::resolver::{URLEntry, URLResolver};
+use django_rs_template::filters::default_registry;
+use django_rs_template::library::global_registry;
+use django_rs_template::tags::builtin_tag_names;
+use serde::{Deserialize, Serialize};
+
+use crate::model_admin::{FieldSchema, ModelAdmin};
+
+/// Documentation of a project's models, URLs and template libraries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminDocs {
+    /// The registered models, sorted by key.
+    pub models: Vec<ModelDoc>,
+    /// The URL patterns, in resolution order.
+    pub urls: Vec<UrlDoc>,
+    /// The built-in tags and filters, followed by each registered library
+    /// sorted by name.
+    pub template_libraries: Vec<TemplateLibraryDoc>,
+}
+
+impl AdminDocs {
+    /// Documents the given model admins and URL patterns, along with the
+    /// built-in template tags and filters and the libraries in the global
+    /// [library registry](django_rs_template::library::global_registry).
+    pub fn new<'a>(admins: impl IntoIterator<Item = &'a ModelAdmin>, urls: Vec<UrlDoc>) -> Self {
+        let mut admins: Vec<&ModelAdmin> = admins.into_iter().collect();
+        admins.sort_by_key(|admin| admin.model_key());
+        let models = admins
+            .iter()
+            .map(|admin| ModelDoc::new(admin, &admins))
+            .collect();
+        Self {
+            models,
+            urls,
+            template_libraries: template_libraries(),
+        }
+    }
+}
+
+/// Documentation of one registered model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDoc {
+    /// The model key, `"app_label.model_name"`.
+    pub key: String,
+    /// The application label.
+    pub app_label: String,
+    /// The model name.
+    pub model_name: String,
+    /// Human-readable name.
+    pub verbose_name: String,
+    /// Plural human-readable name.
+    pub verbose_name_plural: String,
+    /// The model's fields, in declaration order.
+    pub fields: Vec<FieldDoc>,
+    /// Relations to other models: this model's relational fields, then
+    /// fields of other registered models pointing at it.
+    pub relations: Vec<RelationDoc>,
+}
+
+impl ModelDoc {
+    /// Documents `admin`, finding reverse relations among `all`.
+    fn new(admin: &ModelAdmin, all: &[&ModelAdmin]) -> Self {
+        let key = admin.model_key();
+        let mut relations: Vec<RelationDoc> = admin
+            .fields_schema
+            .iter()
+            .filter_map(|field| {
+                Some(RelationDoc {
+                    field: field.name.clone(),
+                    model: field.related_model.clone()?,
+                    kind: RelationKind::from_field_type(&field.field_type),
+                    reverse: false,
+                })
+            })
+            .collect();
+        for other in all {
+            for field in &other.fields_schema {
+                if field
+                    .related_model
+                    .as_deref()
+                    .is_some_and(|target| target.eq_ignore_ascii_case(&key))
+                {
+                    relations.push(RelationDoc {
+                        field: field.name.clone(),
+                        model: other.model_key(),
+                        kind: RelationKind::from_field_type(&field.field_type),
+                        reverse: true,
+                    });
+                }
+            }
+        }
+        Self {
+            key,
+            app_label: admin.app_label.clone(),
+            model_name: admin.model_name.clone(),
+            verbose_name: admin.verbose_name.clone(),
+            verbose_name_plural: admin.verbose_name_plural.clone(),
+            fields: admin.fields_schema.iter().map(FieldDoc::from).collect(),
+            relations,
+        }
+    }
+}
+
+/// Documentation of one model field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldDoc {
+    /// The field name.
+    pub name: String,
+    /// The field type (e.g., "`CharField`", "`ForeignKey`").
+    pub field_type: String,
+    /// Human-readable label.
+    pub label: String,
+    /// Help text for the field.
+    pub help_text: String,
+    /// Whether the field is required.
+    pub required: bool,
+    /// Whether the field is the primary key.
+    pub primary_key: bool,
+    /// The target model for relational fields.
+    pub related_model: Option<String>,
+}
+
+impl From<&FieldSchema> for FieldDoc {
+    fn from(field: &FieldSchema) -> Self {
+        Self {
+            name: field.name.clone(),
+            field_type: field.field_type.clone(),
+            label: field.label.clone(),
+            help_text: field.help_text.clone(),
+            required: field.required,
+            primary_key: field.primary_key,
+            related_model: field.related_model.clone(),
+        }
+    }
+}
+
+/// A relation between two models.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationDoc {
+    /// The relational field. For reverse relations, the field on the other
+    /// model.
+    pub field: String,
+    /// The other model's key.
+    pub model: String,
+    /// The relation's cardinality, seen from the model holding the field.
+    pub kind: RelationKind,
+    /// Whether the field is on the other model, pointing at this one.
+    pub reverse: bool,
+}
+
+/// The cardinality of a relation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    /// A `ForeignKey`.
+    ManyToOne,
+    /// A `OneToOneField`.
+    OneToOne,
+    /// A `ManyToManyField`.
+    ManyToMany,
+}
+
+impl RelationKind {
+    /// Returns the kind of a relational field type; unknown types are
+    /// treated as foreign keys.
+    fn from_field_type(field_type: &str) -> Self {
+        match field_type {
+            "OneToOneField" => Self::OneToOne,
+            "ManyToManyField" => Self::ManyToMany,
+            _ => Self::ManyToOne,
+        }
+    }
+}
+
+/// Documentation of one URL pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlDoc {
+    /// The full route, including the prefixes of enclosing resolvers.
+    pub route: String,
+    /// The namespaced name used with `reverse`, if the pattern has one.
+    pub name: Option<String>,
+}
+
+impl UrlDoc {
+    /// Lists the patterns of `resolver` and its nested resolvers.
+    pub fn collect(resolver: &URLResolver) -> Vec<Self> {
+        let mut urls = Vec::new();
+        collect_urls(resolver, "", &[], &mut urls);
+        urls
+    }
+}
+
+fn collect_urls(resolver: &URLResolver, prefix: &str, namespaces: &[&str], urls: &mut Vec<UrlDoc>) {
+    let prefix = format!("{prefix}{}", resolver.pattern().route());
+    let mut namespaces = namespaces.to_vec();
+    namespaces.extend(resolver.namespace());
+    for entry in resolver.url_patterns() {
+        match entry {
+            URLEntry::Pattern(pattern) => urls.push(UrlDoc {
+                route: format!("{prefix}{}", pattern.route()),
+                name: pattern.name().map(|name| {
+                    namespaces
+                        .iter()
+                        .copied()
+                        .chain([name])
+                        .collect::<Vec<_>>()
+                        .join(":")
+                }),
+            }),
+            URLEntry::Resolver(child) => collect_urls(child, &prefix, &namespaces, urls),
+        }
+    }
+}
+
+/// The tags and filters of a template library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateLibraryDoc {
+    /// The name given to `{% load %}`; `None` for the built-ins.
+    pub name: Option<String>,
+    /// The tag names, sorted. Closing and intermediate tags (`endif`,
+    /// `else`, ...) are left out.
+    pub tags: Vec<String>,
+    /// The filter names, sorted.
+    pub filters: Vec<String>,
+}
+
+fn template_libraries() -> Vec<TemplateLibraryDoc> {
+    let mut tags: Vec<String> = builtin_tag_names()
+        .into_iter()
+        .filter(|tag| !tag.starts_with("end") && !matches!(*tag, "elif" | "else" | "empty"))
+        .map(String::from)
+        .collect();
+    tags.sort_unstable();
+    let mut libraries = vec![TemplateLibraryDoc {
+        name: None,
+        tags,
+        filters: default_registry()
+            .names()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    }];
+
+    let registry = global_registry()
+        .read()
+        .expect("library registry lock poisoned");
+    let mut names = registry.names();
+    names.sort_unstable();
+    for name in names {
+        let Some(library) = registry.get(name) else {
+            continue;
+        };
+        let mut tags: Vec<String> = library
+            .simple_tag_names()
+            .into_iter()
+            .chain(library.inclusion_tag_names())
+            .map(String::from)
+            .collect();
+        tags.sort_unstable();
+        let mut filters: Vec<String> = library
+            .filter_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        filters.sort_unstable();
+        libraries.push(TemplateLibraryDoc {
+            name: Some(name.to_string()),
+            tags,
+            filters,
+        });
+    }
+    drop(registry);
+    libraries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_http::urls::pattern::{path, RouteHandler};
+    use django_rs_http::urls::resolver::{include, root};
+    use django_rs_http::HttpResponse;
+    use std::sync::Arc;
+
+    fn handler() -> RouteHandler {
+        Arc::new(|_req| Box::pin(async { HttpResponse::ok("") }))
+    }
+
+    #[test]
+    fn test_model_docs_relations() {
+        let user = ModelAdmin::new("auth", "user").fields_schema(vec![FieldSchema::new(
+            "id",
+            "AutoField",
+        )
+        .primary_key()]);
+        let post = ModelAdmin::new("blog", "post").fields_schema(vec![
+            FieldSchema::new("title", "CharField").help_text("Shown in listings"),
+            FieldSchema::new("author", "ForeignKey").relation("auth.User"),
+            FieldSchema::new("tags", "ManyToManyField").relation("blog.tag"),
+        ]);
+        let docs = AdminDocs::new([&post, &user], Vec::new());
+
+        let keys: Vec<&str> = docs.models.iter().map(|m| m.key.as_str()).collect();
+        assert_eq!(keys, ["auth.user", "blog.post"]);
+
+        let post_doc = &docs.models[1];
+        assert_eq!(post_doc.fields[0].help_text, "Shown in listings");
+        assert_eq!(post_doc.relations.len(), 2);
+        assert_eq!(post_doc.relations[1].kind, RelationKind::ManyToMany);
+
+        let user_doc = &docs.models[0];
+        assert_eq!(
+            user_doc.relations,
+            vec![RelationDoc {
+                field: "author".to_string(),
+                model: "blog.post".to_string(),
+                kind: RelationKind::ManyToOne,
+                reverse: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_url_docs() {
+        let resolver = root(vec![
+            URLEntry::Pattern(path("", handler(), Some("home")).unwrap()),
+            URLEntry::Resolver(
+                include(
+                    "blog/",
+                    vec![
+                        URLEntry::Pattern(path("<int:id>/", handler(), Some("detail")).unwrap()),
+                        URLEntry::Pattern(path("feed/", handler(), None).unwrap()),
+                    ],
+                    Some("blog"),
+                    None,
+                )
+                .unwrap(),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            UrlDoc::collect(&resolver),
+            vec![
+                UrlDoc {
+                    route: String::new(),
+                    name: Some("home".to_string()),
+                },
+                UrlDoc {
+                    route: "blog/<int:id>/".to_string(),
+                    name: Some("blog:detail".to_string()),
+                },
+                UrlDoc {
+                    route: "blog/feed/".to_string(),
+                    name: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builtin_template_library() {
+        let builtins = &template_libraries()[0];
+        assert_eq!(builtins.name, None);
+        assert!(builtins.tags.contains(&"for".to_string()));
+        assert!(!builtins.tags.contains(&"endfor".to_string()));
+        assert!(builtins.filters.contains(&"slice".to_string()));
+    }
+}
@@ -7,10 +7,17 @@
 
 use std::collections::HashMap;
 
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use django_rs_forms::conditions::VisibilityRule;
+use django_rs_http::pagination::{PageUrls, PaginatedResponse};
 use serde::{Deserialize, Serialize};
 
+use crate::contrib::humanize::naturaltime_at;
 use crate::filters::{apply_filters, apply_search};
-use crate::model_admin::{FieldSchema, ModelAdmin};
+use crate::model_admin::{FieldSchema, ListColumn, ModelAdmin};
+use crate::model_counts::ModelCount;
+use crate::permission_matrix::{PermissionChange, PermissionMatrix};
+use crate::publishing::ScheduledPublishing;
 
 /// Query parameters for the list endpoint.
 ///
@@ -86,7 +93,8 @@ impl ListParams {
 /// A paginated JSON response for list views.
 ///
 /// Contains the result set along with pagination metadata that the React
-/// frontend uses to render page controls.
+/// frontend uses to render page controls. It is served as an [`AdminPage`],
+/// see [`into_page`](Self::into_page).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonListResponse {
     /// The model objects for the current page.
@@ -135,6 +143,25 @@ impl JsonListResponse {
         }
     }
 
+    /// Wraps this page in the standard [`PaginatedResponse`] envelope, with
+    /// `next` and `previous` linking to the pages of `urls`.
+    pub fn into_page(self, urls: &PageUrls) -> AdminPage {
+        AdminPage {
+            envelope: PaginatedResponse::new(
+                self.results,
+                self.count,
+                self.page,
+                self.page_size,
+                urls,
+            ),
+            page: self.page,
+            page_size: self.page_size,
+            total_pages: self.total_pages,
+            has_next: self.has_next,
+            has_previous: self.has_previous,
+        }
+    }
+
     /// Creates an empty paginated response.
     pub fn empty(page: usize, page_size: usize) -> Self {
         Self {
@@ -149,6 +176,28 @@ impl JsonListResponse {
     }
 }
 
+/// A page of an admin list as served by the API.
+///
+/// The standard [`PaginatedResponse`] envelope (`count`, `next`, `previous`,
+/// `results`), plus the page metadata of [`JsonListResponse`] the React
+/// frontend renders page controls from.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminPage {
+    /// The standard envelope; its links also go in the `Link` header.
+    #[serde(flatten)]
+    pub envelope: PaginatedResponse<serde_json::Value>,
+    /// The current page number (1-indexed).
+    pub page: usize,
+    /// The number of items per page.
+    pub page_size: usize,
+    /// Total number of pages.
+    pub total_pages: usize,
+    /// Whether there is a next page.
+    pub has_next: bool,
+    /// Whether there is a previous page.
+    pub has_previous: bool,
+}
+
 /// Response for the model list/index endpoint.
 ///
 /// Lists all registered models with their app labels, names, and admin URLs.
@@ -178,6 +227,9 @@ pub struct ModelInfo {
     pub verbose_name_plural: String,
     /// The API URL for this model's list view.
     pub url: String,
+    /// The cached object count, if the model shows one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<ModelCount>,
 }
 
 /// Schema response for a model, used by the React frontend for form rendering.
@@ -195,6 +247,8 @@ pub struct ModelSchemaResponse {
     pub fields: Vec<FieldSchema>,
     /// Fields displayed in the list view.
     pub list_display: Vec<String>,
+    /// Rendering and sorting metadata for each `list_display` column.
+    pub list_columns: Vec<ListColumn>,
     /// Fields that are searchable.
     pub search_fields: Vec<String>,
     /// Default ordering.
@@ -203,6 +257,25 @@ pub struct ModelSchemaResponse {
     pub actions: Vec<String>,
     /// Number of items per page.
     pub list_per_page: usize,
+    /// Fields accepted by the quick-create endpoint (empty when disabled).
+    pub quick_create_fields: Vec<String>,
+    /// Parent field of a tree model, whose list results carry `_depth`.
+    pub tree_parent_field: Option<String>,
+    /// Many-to-many fields edited with the two-panel selector.
+    pub filter_horizontal: Vec<String>,
+    /// Conditional visibility rules, as declarative condition trees.
+    pub visibility_rules: Vec<VisibilityRule>,
+    /// Datetime fields driving scheduled publishing; list results then carry
+    /// a `publish_status`.
+    pub scheduled_publishing: Option<ScheduledPublishing>,
+    /// Whether objects have a comment thread.
+    pub comments_enabled: bool,
+    /// Slug fields filled in from other fields, mapped to their sources.
+    #[serde(default)]
+    pub prepopulated_fields: HashMap<String, Vec<String>>,
+    /// The field whose value identifies objects in URLs.
+    #[serde(default)]
+    pub lookup_field: String,
 }
 
 impl ModelSchemaResponse {
@@ -215,14 +288,116 @@ impl ModelSchemaResponse {
             verbose_name_plural: admin.verbose_name_plural.clone(),
             fields: admin.fields_schema.clone(),
             list_display: admin.list_display.clone(),
+            list_columns: admin.list_columns(),
             search_fields: admin.search_fields.clone(),
             ordering: admin.ordering.clone(),
             actions: admin.action_names.clone(),
             list_per_page: admin.list_per_page,
+            quick_create_fields: admin.quick_create_form_fields(),
+            tree_parent_field: admin.tree_parent_field.clone(),
+            filter_horizontal: admin.filter_horizontal.clone(),
+            visibility_rules: admin.visibility_rules.clone(),
+            scheduled_publishing: admin.scheduled_publishing.clone(),
+            comments_enabled: admin.comments_enabled,
+            prepopulated_fields: admin.prepopulated_fields.clone(),
+            lookup_field: admin.lookup_field_name().to_string(),
         }
     }
 }
 
+/// Response for the quick-create endpoint.
+///
+/// Carries just enough for the frontend to add the new object to the
+/// parent form's select and choose it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickCreateResponse {
+    /// The new object's primary key.
+    pub id: serde_json::Value,
+    /// The label to show in the select.
+    pub label: String,
+}
+
+/// Request body for the bulk action endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkActionRequest {
+    /// The name of the action to run.
+    pub action: String,
+    /// The primary keys of the selected objects.
+    pub ids: Vec<String>,
+}
+
+/// Response for the bulk action endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkActionResponse {
+    /// The action that ran.
+    pub action: String,
+    /// The number of objects the action changed.
+    pub affected: usize,
+    /// A message to show the user.
+    pub message: String,
+}
+
+/// Request body for updating the permission matrix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionMatrixUpdate {
+    /// The grants and revocations to apply together.
+    pub changes: Vec<PermissionChange>,
+}
+
+/// Response for a permission matrix update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionMatrixUpdateResponse {
+    /// The number of changes that weren't already in effect.
+    pub applied: usize,
+    /// The matrix after the update.
+    pub matrix: PermissionMatrix,
+}
+
+/// One object in either panel of the `filter_horizontal` selector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationChoice {
+    /// The related object's primary key.
+    pub id: serde_json::Value,
+    /// The label to show in the list.
+    pub label: String,
+}
+
+/// Request body replacing the chosen objects of a `filter_horizontal` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetRelationRequest {
+    /// Primary keys of every chosen object.
+    pub ids: Vec<serde_json::Value>,
+}
+
+/// Request body registering a model on a running admin site.
+///
+/// Any other [`ModelAdmin`] option may be given alongside the app label and
+/// model name; options left out keep the defaults of [`ModelAdmin::new`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterModelRequest {
+    /// The application label (e.g., "blog").
+    pub app_label: String,
+    /// The model name in lowercase (e.g., "article").
+    pub model_name: String,
+    /// The remaining `ModelAdmin` options.
+    #[serde(flatten)]
+    pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RegisterModelRequest {
+    /// Builds the model admin, or describes why the options are invalid.
+    pub fn into_model_admin(self) -> Result<ModelAdmin, String> {
+        let mut config = serde_json::to_value(ModelAdmin::new(&self.app_label, &self.model_name))
+            .map_err(|e| e.to_string())?;
+        if let Some(config) = config.as_object_mut() {
+            config.extend(self.options);
+        }
+        let admin: ModelAdmin = serde_json::from_value(config).map_err(|e| e.to_string())?;
+        admin.check_lookup_field()?;
+        Ok(admin)
+    }
+}
+
 /// Current user info response for the `/api/admin/me/` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrentUserResponse {
@@ -290,6 +465,112 @@ pub async fn process_list_request(
     JsonListResponse::paginate(&ordered, params.page, page_size)
 }
 
+/// The requesting user's timezone and language, used to localize and
+/// humanize datetimes in list results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayContext {
+    /// The user's offset from UTC, in seconds east of UTC.
+    pub utc_offset: i32,
+    /// The language relative strings are translated into.
+    pub language: String,
+    /// The time relative strings are computed against.
+    pub now: DateTime<Utc>,
+}
+
+impl Default for DisplayContext {
+    fn default() -> Self {
+        Self::new(0, "en")
+    }
+}
+
+impl DisplayContext {
+    /// Creates a context for the given UTC offset (in seconds) and language,
+    /// relative to the current time.
+    pub fn new(utc_offset: i32, language: impl Into<String>) -> Self {
+        Self {
+            utc_offset,
+            language: language.into(),
+            now: Utc::now(),
+        }
+    }
+
+    /// Sets the time relative strings are computed against.
+    #[must_use]
+    pub const fn at(mut self, now: DateTime<Utc>) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Returns the user's timezone, or UTC if the offset is out of range.
+    pub fn timezone(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("UTC offset"))
+    }
+}
+
+/// Adds a `_datetimes` object to each result, holding the exact and the
+/// relative form of every humanized column's value.
+///
+/// For each [`ListColumn::humanized`] column with a datetime value, the entry
+/// is `{"iso": ..., "relative": ...}`: the timestamp converted to the user's
+/// timezone in RFC 3339 form, and a translated relative string such as
+/// `"3 hours ago"`. Naive timestamps are taken as UTC. Null and unparseable
+/// values are skipped; the original values are left untouched.
+pub fn humanize_datetimes(
+    admin: &ModelAdmin,
+    results: &mut [serde_json::Value],
+    context: &DisplayContext,
+) {
+    let columns: Vec<String> = admin
+        .list_columns()
+        .into_iter()
+        .filter(|c| c.humanized)
+        .map(|c| c.name)
+        .collect();
+    if columns.is_empty() {
+        return;
+    }
+    let timezone = context.timezone();
+
+    for obj in results {
+        let Some(map) = obj.as_object_mut() else {
+            continue;
+        };
+        let mut datetimes = serde_json::Map::new();
+        for name in &columns {
+            let Some(dt) = map
+                .get(name)
+                .and_then(serde_json::Value::as_str)
+                .and_then(parse_datetime)
+            else {
+                continue;
+            };
+            datetimes.insert(
+                name.clone(),
+                serde_json::json!({
+                    "iso": dt.with_timezone(&timezone).to_rfc3339(),
+                    "relative": naturaltime_at(dt, context.now, &context.language),
+                }),
+            );
+        }
+        map.insert(
+            "_datetimes".to_string(),
+            serde_json::Value::Object(datetimes),
+        );
+    }
+}
+
+/// Parses an RFC 3339 or naive (assumed UTC) timestamp.
+pub(crate) fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc())
+}
+
 /// Applies ordering to a list of JSON objects.
 ///
 /// Supports ascending and descending order. Prefix the field name with "-" for descending.
@@ -354,6 +635,7 @@ pub fn build_model_index(admins: &[&ModelAdmin], url_prefix: &str) -> ModelIndex
             verbose_name: admin.verbose_name.clone(),
             verbose_name_plural: admin.verbose_name_plural.clone(),
             url: format!("{}/{}/{}/", url_prefix, admin.app_label, admin.model_name),
+            count: None,
         };
         apps_map
             .entry(admin.app_label.clone())
@@ -374,6 +656,36 @@ pub fn build_model_index(admins: &[&ModelAdmin], url_prefix: &str) -> ModelIndex
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_register_model_request_keeps_defaults() {
+        let request: RegisterModelRequest = serde_json::from_str(
+            r#"{"app_label": "blog", "model_name": "article", "list_per_page": 25}"#,
+        )
+        .unwrap();
+        let admin = request.into_model_admin().unwrap();
+        assert_eq!(admin.model_key(), "blog.article");
+        assert_eq!(admin.list_per_page, 25);
+        assert_eq!(
+            admin.list_max_show_all,
+            ModelAdmin::new("blog", "article").list_max_show_all
+        );
+    }
+
+    #[test]
+    fn test_register_model_request_rejects_invalid_options() {
+        let request: RegisterModelRequest = serde_json::from_str(
+            r#"{"app_label": "blog", "model_name": "article", "list_per_page": "many"}"#,
+        )
+        .unwrap();
+        assert!(request.into_model_admin().is_err());
+
+        let request: RegisterModelRequest = serde_json::from_str(
+            r#"{"app_label": "blog", "model_name": "article", "lookup_field": "slug"}"#,
+        )
+        .unwrap();
+        assert!(request.into_model_admin().unwrap_err().contains("slug"));
+    }
+
     #[test]
     fn test_list_params_default() {
         let params = ListParams::default();
@@ -509,6 +821,11 @@ mod tests {
         assert_eq!(schema.fields.len(), 2);
         assert_eq!(schema.list_display, vec!["title", "author"]);
         assert_eq!(schema.search_fields, vec!["title"]);
+        assert_eq!(schema.list_columns.len(), 2);
+        assert!(schema.list_columns[0].sortable);
+        // "author" has no field schema, so it can't be sorted server-side
+        assert!(!schema.list_columns[1].sortable);
+        assert!(schema.quick_create_fields.is_empty());
     }
 
     #[test]
@@ -681,4 +998,46 @@ mod tests {
         let response = JsonListResponse::paginate(&items, 0, 10);
         assert_eq!(response.page, 1);
     }
+
+    #[test]
+    fn test_humanize_datetimes() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "published_at", "updated_at"])
+            .fields_schema(vec![
+                FieldSchema::new("title", "CharField"),
+                FieldSchema::new("published_at", "DateTimeField"),
+                FieldSchema::new("updated_at", "DateTimeField").optional(),
+            ]);
+        let now = DateTime::parse_from_rfc3339("2024-06-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let context = DisplayContext::new(2 * 3600, "en").at(now);
+        let mut results = vec![
+            serde_json::json!({
+                "title": "Hello",
+                "published_at": "2024-06-15T09:00:00Z",
+                "updated_at": null,
+            }),
+            serde_json::json!({
+                "title": "Draft",
+                "published_at": "2024-06-13 12:00:00",
+                "updated_at": "not a date",
+            }),
+        ];
+
+        humanize_datetimes(&admin, &mut results, &context);
+
+        assert_eq!(results[0]["published_at"], "2024-06-15T09:00:00Z");
+        assert_eq!(
+            results[0]["_datetimes"]["published_at"],
+            serde_json::json!({"iso": "2024-06-15T11:00:00+02:00", "relative": "3 hours ago"})
+        );
+        assert!(results[0]["_datetimes"].get("updated_at").is_none());
+        assert_eq!(
+            results[1]["_datetimes"]["published_at"]["relative"],
+            "2 days ago"
+        );
+        assert!(results[1]["_datetimes"].get("updated_at").is_none());
+        assert!(results[0]["_datetimes"].get("title").is_none());
+    }
 }
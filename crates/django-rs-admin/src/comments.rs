@@ -0,0 +1,309 @@
+//! Comment threads on admin objects.
+//!
+//! Models whose [`ModelAdmin`](crate::model_admin::ModelAdmin) enables
+//! [`comments`](crate::model_admin::ModelAdmin::comments) get a thread of
+//! comments on their detail page, for editorial discussion that happens inside
+//! the admin. Comments are keyed by content type and object id, carry their
+//! author and creation time, and may only be deleted by their author.
+//!
+//! `@name` mentions in a comment are extracted when it is posted; the admin
+//! site pushes a [`Mention`](crate::notifications::NotificationKind::Mention)
+//! notification to each mentioned user.
+//!
+//! [`InMemoryCommentStore`] is the default.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::comments::{AdminComment, CommentStore, InMemoryCommentStore};
+//!
+//! async fn example() {
+//!     let store = InMemoryCommentStore::new();
+//!     let comment = store
+//!         .add(AdminComment::new("blog.article", "7", "alice", "Ready for @bob to review"))
+//!         .await
+//!         .unwrap();
+//!     assert_eq!(comment.mentions, vec!["bob"]);
+//!
+//!     let thread = store.list("blog.article", "7").await.unwrap();
+//!     assert_eq!(thread.len(), 1);
+//!     assert!(store.delete("blog.article", "7", comment.id).await.unwrap());
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A comment on an admin object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminComment {
+    /// Identifier assigned by the store when the comment is added.
+    pub id: u64,
+    /// The content type identifier (e.g., "blog.article").
+    pub content_type: String,
+    /// The primary key of the commented object.
+    pub object_id: String,
+    /// The user who wrote the comment.
+    pub author: String,
+    /// The comment text.
+    pub body: String,
+    /// The users mentioned with `@name` in the body, in order of first mention.
+    pub mentions: Vec<String>,
+    /// When the comment was posted.
+    pub created_at: DateTime<Utc>,
+}
+
+impl AdminComment {
+    /// Creates a comment by `author` on an object, posted now.
+    pub fn new(content_type: &str, object_id: &str, author: &str, body: &str) -> Self {
+        Self {
+            id: 0,
+            content_type: content_type.to_string(),
+            object_id: object_id.to_string(),
+            author: author.to_string(),
+            body: body.to_string(),
+            mentions: extract_mentions(body),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Returns the names mentioned with `@name` in `text`, without duplicates.
+///
+/// A mention starts at an `@` that does not follow a letter, digit or `_`,
+/// so e-mail addresses are not mistaken for mentions. Names consist of
+/// letters, digits and `_`, `.`, `-`; a trailing `.` or `-` is treated as
+/// punctuation.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_admin::comments::extract_mentions;
+///
+/// assert_eq!(
+///     extract_mentions("@alice, can you ask @bob.smith? cc @alice"),
+///     vec!["alice", "bob.smith"]
+/// );
+/// assert!(extract_mentions("mail editor@example.com").is_empty());
+/// ```
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '.' | '-');
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_boundary = !previous.is_some_and(|p| p.is_alphanumeric() || p == '_');
+        previous = Some(c);
+        if c != '@' || !at_boundary {
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while let Some(&(j, n)) = chars.peek() {
+            if !is_name_char(n) {
+                break;
+            }
+            end = j + n.len_utf8();
+            previous = Some(n);
+            chars.next();
+        }
+        let name = text[start..end].trim_end_matches(['.', '-']);
+        if !name.is_empty() && !mentions.iter().any(|m| m == name) {
+            mentions.push(name.to_string());
+        }
+    }
+    mentions
+}
+
+/// Trait for comment storage backends.
+#[async_trait]
+pub trait CommentStore: Send + Sync {
+    /// Stores a comment, assigning its id, and returns the stored copy.
+    async fn add(&self, comment: AdminComment) -> Result<AdminComment, String>;
+
+    /// Returns the comments on an object, oldest first.
+    async fn list(&self, content_type: &str, object_id: &str) -> Result<Vec<AdminComment>, String>;
+
+    /// Returns one comment on an object, if it exists.
+    async fn get(
+        &self,
+        content_type: &str,
+        object_id: &str,
+        id: u64,
+    ) -> Result<Option<AdminComment>, String>;
+
+    /// Deletes a comment on an object, returning whether it existed.
+    async fn delete(&self, content_type: &str, object_id: &str, id: u64) -> Result<bool, String>;
+}
+
+/// Comment threads are keyed by `(content_type, object_id)`.
+type ThreadKey = (String, String);
+
+#[derive(Debug, Default)]
+struct Threads {
+    next_id: u64,
+    by_object: HashMap<ThreadKey, Vec<AdminComment>>,
+}
+
+/// In-memory implementation of [`CommentStore`].
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryCommentStore {
+    threads: Arc<RwLock<Threads>>,
+}
+
+impl InMemoryCommentStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored comments across all objects.
+    pub fn len(&self) -> usize {
+        self.threads
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .by_object
+            .values()
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Returns `true` if no comments are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn thread_key(content_type: &str, object_id: &str) -> ThreadKey {
+    (content_type.to_string(), object_id.to_string())
+}
+
+#[async_trait]
+impl CommentStore for InMemoryCommentStore {
+    async fn add(&self, mut comment: AdminComment) -> Result<AdminComment, String> {
+        let mut threads = self.threads.write().map_err(|e| e.to_string())?;
+        threads.next_id += 1;
+        comment.id = threads.next_id;
+        threads
+            .by_object
+            .entry(thread_key(&comment.content_type, &comment.object_id))
+            .or_default()
+            .push(comment.clone());
+        drop(threads);
+        Ok(comment)
+    }
+
+    async fn list(&self, content_type: &str, object_id: &str) -> Result<Vec<AdminComment>, String> {
+        let threads = self.threads.read().map_err(|e| e.to_string())?;
+        Ok(threads
+            .by_object
+            .get(&thread_key(content_type, object_id))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get(
+        &self,
+        content_type: &str,
+        object_id: &str,
+        id: u64,
+    ) -> Result<Option<AdminComment>, String> {
+        let threads = self.threads.read().map_err(|e| e.to_string())?;
+        Ok(threads
+            .by_object
+            .get(&thread_key(content_type, object_id))
+            .and_then(|thread| thread.iter().find(|c| c.id == id))
+            .cloned())
+    }
+
+    async fn delete(&self, content_type: &str, object_id: &str, id: u64) -> Result<bool, String> {
+        let mut threads = self.threads.write().map_err(|e| e.to_string())?;
+        let Some(thread) = threads
+            .by_object
+            .get_mut(&thread_key(content_type, object_id))
+        else {
+            return Ok(false);
+        };
+        let before = thread.len();
+        thread.retain(|c| c.id != id);
+        let removed = thread.len() < before;
+        drop(threads);
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_mentions() {
+        assert_eq!(extract_mentions("@alice please check"), vec!["alice"]);
+        assert_eq!(
+            extract_mentions("(@bob) and @carol-d. Thanks @bob!"),
+            vec!["bob", "carol-d"]
+        );
+        assert!(extract_mentions("write to team@example.com").is_empty());
+        assert!(extract_mentions("a lone @ sign").is_empty());
+        assert_eq!(extract_mentions("@émile ok"), vec!["émile"]);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_threads_are_per_object() {
+        let store = InMemoryCommentStore::new();
+        let first = store
+            .add(AdminComment::new("blog.article", "1", "alice", "First"))
+            .await
+            .unwrap();
+        let second = store
+            .add(AdminComment::new("blog.article", "1", "bob", "Second"))
+            .await
+            .unwrap();
+        store
+            .add(AdminComment::new("blog.article", "2", "alice", "Other"))
+            .await
+            .unwrap();
+        assert_ne!(first.id, second.id);
+
+        let thread = store.list("blog.article", "1").await.unwrap();
+        assert_eq!(
+            thread.iter().map(|c| c.body.as_str()).collect::<Vec<_>>(),
+            vec!["First", "Second"]
+        );
+        assert!(store.list("blog.comment", "1").await.unwrap().is_empty());
+        assert_eq!(store.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_get_and_delete() {
+        let store = InMemoryCommentStore::new();
+        let comment = store
+            .add(AdminComment::new("blog.article", "1", "alice", "Hi"))
+            .await
+            .unwrap();
+
+        assert!(store
+            .get("blog.article", "2", comment.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            store
+                .get("blog.article", "1", comment.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .author,
+            "alice"
+        );
+
+        assert!(!store.delete("blog.article", "2", comment.id).await.unwrap());
+        assert!(store.delete("blog.article", "1", comment.id).await.unwrap());
+        assert!(!store.delete("blog.article", "1", comment.id).await.unwrap());
+        assert!(store.is_empty());
+    }
+}
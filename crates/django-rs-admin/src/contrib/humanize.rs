@@ -4,6 +4,7 @@
 //! convert numbers, dates, and file sizes into human-readable strings.
 
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use django_rs_core::i18n::{self, catalog};
 
 /// Formats an integer with commas as thousand separators.
 ///
@@ -71,6 +72,9 @@ pub fn intword(value: i64) -> String {
 
 /// Converts a `DateTime<Utc>` to a human-readable relative time string.
 ///
+/// The result is translated into the thread's active language; see
+/// [`naturaltime_at`].
+///
 /// # Examples
 ///
 /// ```
@@ -81,11 +85,32 @@ pub fn intword(value: i64) -> String {
 /// assert_eq!(naturaltime(now), "just now");
 /// ```
 pub fn naturaltime(dt: DateTime<Utc>) -> String {
-    let now = Utc::now();
+    naturaltime_at(dt, Utc::now(), &i18n::get_language())
+}
+
+/// Converts a `DateTime<Utc>` to a relative time string as seen at `now`,
+/// translated into `language`.
+///
+/// The messages are looked up in the translation catalog: `"just now"`,
+/// `"%(delta)s ago"`, `"%(delta)s from now"` and plural pairs such as
+/// `"%(count)s hour"`/`"%(count)s hours"`. Missing translations fall back to
+/// English.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_admin::contrib::humanize::naturaltime_at;
+/// use chrono::{Duration, Utc};
+///
+/// let now = Utc::now();
+/// assert_eq!(naturaltime_at(now - Duration::hours(3), now, "en"), "3 hours ago");
+/// assert_eq!(naturaltime_at(now + Duration::days(2), now, "en"), "2 days from now");
+/// ```
+pub fn naturaltime_at(dt: DateTime<Utc>, now: DateTime<Utc>, language: &str) -> String {
     let diff = now.signed_duration_since(dt);
 
     if diff.num_seconds().abs() < 10 {
-        return "just now".to_string();
+        return catalog::translate(language, "just now").unwrap_or_else(|| "just now".to_string());
     }
 
     let (amount, in_past) = if diff.num_seconds() >= 0 {
@@ -94,62 +119,44 @@ pub fn naturaltime(dt: DateTime<Utc>) -> String {
         (-diff, false)
     };
 
-    let text = if amount.num_days() >= 365 {
-        let years = amount.num_days() / 365;
-        if years == 1 {
-            "1 year".to_string()
-        } else {
-            format!("{years} years")
-        }
-    } else if amount.num_days() >= 30 {
-        let months = amount.num_days() / 30;
-        if months == 1 {
-            "1 month".to_string()
-        } else {
-            format!("{months} months")
-        }
-    } else if amount.num_days() >= 7 {
-        let weeks = amount.num_days() / 7;
-        if weeks == 1 {
-            "1 week".to_string()
-        } else {
-            format!("{weeks} weeks")
-        }
-    } else if amount.num_days() >= 1 {
-        let days = amount.num_days();
-        if days == 1 {
-            "1 day".to_string()
-        } else {
-            format!("{days} days")
-        }
+    let days = amount.num_days();
+    let (count, singular, plural) = if days >= 365 {
+        (days / 365, "%(count)s year", "%(count)s years")
+    } else if days >= 30 {
+        (days / 30, "%(count)s month", "%(count)s months")
+    } else if days >= 7 {
+        (days / 7, "%(count)s week", "%(count)s weeks")
+    } else if days >= 1 {
+        (days, "%(count)s day", "%(count)s days")
     } else if amount.num_hours() >= 1 {
-        let hours = amount.num_hours();
-        if hours == 1 {
-            "1 hour".to_string()
-        } else {
-            format!("{hours} hours")
-        }
+        (amount.num_hours(), "%(count)s hour", "%(count)s hours")
     } else if amount.num_minutes() >= 1 {
-        let minutes = amount.num_minutes();
-        if minutes == 1 {
-            "1 minute".to_string()
-        } else {
-            format!("{minutes} minutes")
-        }
+        (
+            amount.num_minutes(),
+            "%(count)s minute",
+            "%(count)s minutes",
+        )
     } else {
-        let seconds = amount.num_seconds();
-        if seconds == 1 {
-            "1 second".to_string()
-        } else {
-            format!("{seconds} seconds")
-        }
+        (
+            amount.num_seconds(),
+            "%(count)s second",
+            "%(count)s seconds",
+        )
     };
 
-    if in_past {
-        format!("{text} ago")
+    let count_u64 = count.unsigned_abs();
+    let delta = catalog::translate_plural(language, singular, plural, count_u64)
+        .unwrap_or_else(|| (if count_u64 == 1 { singular } else { plural }).to_string())
+        .replace("%(count)s", &count.to_string());
+
+    let template = if in_past {
+        "%(delta)s ago"
     } else {
-        format!("{text} from now")
-    }
+        "%(delta)s from now"
+    };
+    catalog::translate(language, template)
+        .unwrap_or_else(|| template.to_string())
+        .replace("%(delta)s", &delta)
 }
 
 /// Converts a date to a human-readable string relative to today.
@@ -395,6 +402,28 @@ mod tests {
         assert!(result.contains("from now"));
     }
 
+    #[test]
+    fn test_naturaltime_at_translated() {
+        catalog::register_translations("x-humanize", vec![("%(delta)s ago", "hace %(delta)s")]);
+        catalog::register_plural_translations(
+            "x-humanize",
+            vec![(
+                "%(count)s hour",
+                "%(count)s hours",
+                "%(count)s hora",
+                "%(count)s horas",
+            )],
+        );
+        let now = Utc::now();
+        let dt = now - TimeDelta::hours(3);
+        assert_eq!(naturaltime_at(dt, now, "x-humanize"), "hace 3 horas");
+        assert_eq!(naturaltime_at(dt, now, "en"), "3 hours ago");
+        assert_eq!(
+            naturaltime_at(now + TimeDelta::minutes(1), now, "x-humanize"),
+            "1 minute from now"
+        );
+    }
+
     #[test]
     fn test_naturalday_today() {
         let today = Utc::now().date_naive();
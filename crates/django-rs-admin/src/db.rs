@@ -19,14 +19,16 @@
 //! let db = InMemoryAdminDb::new();
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 use crate::api::JsonListResponse;
-use crate::model_admin::ModelAdmin;
+use crate::model_admin::{AnnotationFunction, ModelAdmin};
+use crate::publishing::annotate_publish_status;
 
 /// Parameters for an admin list query.
 ///
@@ -40,7 +42,8 @@ pub struct AdminListParams {
     pub page_size: usize,
     /// Optional search query applied across `search_fields`.
     pub search: Option<String>,
-    /// Optional ordering field (prefix with "-" for descending).
+    /// Optional ordering: comma-separated fields, each prefixed with "-"
+    /// for descending.
     pub ordering: Option<String>,
     /// Field-value filters to apply.
     pub filters: HashMap<String, String>,
@@ -79,7 +82,7 @@ impl AdminListParams {
         self
     }
 
-    /// Sets the ordering field.
+    /// Sets the ordering, e.g. `"author,-published"`.
     #[must_use]
     pub fn ordering(mut self, field: impl Into<String>) -> Self {
         self.ordering = Some(field.into());
@@ -123,6 +126,53 @@ pub trait AdminDbExecutor: Send + Sync {
         params: &AdminListParams,
     ) -> Result<AdminListResult, String>;
 
+    /// Returns the number of objects of a model.
+    ///
+    /// The default implementation lists a one-object page and reads its
+    /// total count.
+    async fn count_objects(&self, admin: &ModelAdmin) -> Result<usize, String> {
+        let params = AdminListParams::new().page_size(1);
+        Ok(self.list_objects(admin, &params).await?.response.count)
+    }
+
+    /// Returns whether an object of a model has `value` in `field`.
+    ///
+    /// The default implementation lists a one-object page filtered on the
+    /// field.
+    async fn value_exists(
+        &self,
+        admin: &ModelAdmin,
+        field: &str,
+        value: &str,
+    ) -> Result<bool, String> {
+        let params = AdminListParams::new().page_size(1).filter(field, value);
+        Ok(self.list_objects(admin, &params).await?.response.count > 0)
+    }
+
+    /// Returns the primary key of the object of a model that has `value` in
+    /// `field`, or `None` if there is no such object.
+    ///
+    /// `field` should be unique. The default implementation lists a
+    /// one-object page filtered on the field.
+    async fn find_pk(
+        &self,
+        admin: &ModelAdmin,
+        field: &str,
+        value: &str,
+    ) -> Result<Option<String>, String> {
+        let params = AdminListParams::new().page_size(1).filter(field, value);
+        let result = self.list_objects(admin, &params).await?;
+        Ok(result
+            .response
+            .results
+            .first()
+            .and_then(|obj| obj.get(admin.pk_field_name()))
+            .map(|pk| match pk {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }))
+    }
+
     /// Fetches a single object by primary key.
     async fn get_object(&self, admin: &ModelAdmin, pk: &str) -> Result<serde_json::Value, String>;
 
@@ -149,6 +199,41 @@ pub trait AdminDbExecutor: Send + Sync {
     ///
     /// Returns `true` if the object was found and deleted.
     async fn delete_object(&self, admin: &ModelAdmin, pk: &str) -> Result<bool, String>;
+
+    /// Returns the primary keys related to an object through the many-to-many
+    /// `field`.
+    ///
+    /// The default implementation reads the field from the object, where it
+    /// is stored as an array of primary keys.
+    async fn get_relation(
+        &self,
+        admin: &ModelAdmin,
+        pk: &str,
+        field: &str,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let obj = self.get_object(admin, pk).await?;
+        match obj.get(field) {
+            None | Some(serde_json::Value::Null) => Ok(Vec::new()),
+            Some(serde_json::Value::Array(pks)) => Ok(pks.clone()),
+            Some(_) => Err(format!("Field '{field}' is not a many-to-many relation")),
+        }
+    }
+
+    /// Replaces the primary keys related to an object through the
+    /// many-to-many `field` with `pks`, as a single operation.
+    ///
+    /// The default implementation stores `pks` as an array in the field
+    /// through [`update_object`](Self::update_object).
+    async fn set_relation(
+        &self,
+        admin: &ModelAdmin,
+        pk: &str,
+        field: &str,
+        pks: &[serde_json::Value],
+    ) -> Result<(), String> {
+        let data = HashMap::from([(field.to_string(), serde_json::Value::from(pks.to_vec()))]);
+        self.update_object(admin, pk, &data).await.map(|_| ())
+    }
 }
 
 /// Storage entry for a model table in the in-memory database.
@@ -229,11 +314,7 @@ impl InMemoryAdminDb {
 
     /// Finds the PK field name from the admin configuration.
     fn pk_field(admin: &ModelAdmin) -> String {
-        admin
-            .fields_schema
-            .iter()
-            .find(|f| f.primary_key)
-            .map_or_else(|| "id".to_string(), |f| f.name.clone())
+        admin.pk_field_name().to_string()
     }
 }
 
@@ -293,30 +374,177 @@ fn apply_filters(
         .collect()
 }
 
-/// Applies ordering to a list of objects.
+/// Applies an ordering of comma-separated fields, each prefixed with `-`
+/// for descending, to a list of objects.
+///
+/// Objects equal on every field are ordered by `pk_field`, newest first as
+/// in Django's admin, so pages stay stable.
 fn apply_ordering(
     mut objects: Vec<serde_json::Value>,
     ordering: Option<&str>,
+    pk_field: &str,
 ) -> Vec<serde_json::Value> {
     let Some(ordering) = ordering else {
         return objects;
     };
-    let (field, descending) = ordering
-        .strip_prefix('-')
-        .map_or((ordering, false), |stripped| (stripped, true));
+    let mut keys: Vec<(&str, bool)> = ordering
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (field, descending) = field
+                .strip_prefix('-')
+                .map_or((field, false), |stripped| (stripped, true));
+            // `pk` is an alias for the primary key, which objects store
+            // under its real name.
+            (if field == "pk" { pk_field } else { field }, descending)
+        })
+        .collect();
+    if !keys.iter().any(|&(field, _)| field == pk_field) {
+        keys.push((pk_field, true));
+    }
     objects.sort_by(|a, b| {
-        let va = a.get(field);
-        let vb = b.get(field);
-        let cmp = compare_json_values(va, vb);
-        if descending {
-            cmp.reverse()
-        } else {
-            cmp
-        }
+        keys.iter()
+            .map(|&(field, descending)| {
+                let cmp = compare_json_values(a.get(field), b.get(field));
+                if descending {
+                    cmp.reverse()
+                } else {
+                    cmp
+                }
+            })
+            .find(|cmp| cmp.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
     });
     objects
 }
 
+/// Adds the admin's annotations to each object, aggregating the related
+/// objects in `tables`.
+fn apply_annotations(
+    admin: &ModelAdmin,
+    tables: &HashMap<String, ModelTable>,
+    objects: &mut [serde_json::Value],
+) {
+    // Foreign keys may hold the primary key as a number or a string.
+    let key = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let pk_field = admin.pk_field_name();
+    for annotation in &admin.annotations {
+        let mut groups: HashMap<String, Vec<&serde_json::Value>> = HashMap::new();
+        for related in tables
+            .get(&annotation.related_model)
+            .map_or(&[][..], |t| t.objects.as_slice())
+        {
+            let Some(target) = related
+                .get(&annotation.related_field)
+                .filter(|v| !v.is_null())
+            else {
+                continue;
+            };
+            let value = annotation
+                .value_field
+                .as_ref()
+                .and_then(|field| related.get(field))
+                .unwrap_or(&serde_json::Value::Null);
+            groups.entry(key(target)).or_default().push(value);
+        }
+        for obj in objects.iter_mut() {
+            let values = obj
+                .get(pk_field)
+                .and_then(|pk| groups.get(&key(pk)))
+                .map_or(&[][..], Vec::as_slice);
+            let value = aggregate(annotation.function, values);
+            if let Some(map) = obj.as_object_mut() {
+                map.insert(annotation.name.clone(), value);
+            }
+        }
+    }
+}
+
+/// Computes an annotation's value from the values of the related objects.
+fn aggregate(function: AnnotationFunction, values: &[&serde_json::Value]) -> serde_json::Value {
+    let present = || values.iter().copied().filter(|v| !v.is_null());
+    let numbers = || present().filter_map(serde_json::Value::as_f64);
+    match function {
+        AnnotationFunction::Count => serde_json::json!(values.len()),
+        AnnotationFunction::Sum if present().all(serde_json::Value::is_i64) => {
+            serde_json::json!(present().filter_map(serde_json::Value::as_i64).sum::<i64>())
+        }
+        AnnotationFunction::Sum => serde_json::json!(numbers().sum::<f64>()),
+        AnnotationFunction::Avg => {
+            let (sum, count) = numbers().fold((0.0, 0_u32), |(sum, n), v| (sum + v, n + 1));
+            if count == 0 {
+                serde_json::Value::Null
+            } else {
+                serde_json::json!(sum / f64::from(count))
+            }
+        }
+        AnnotationFunction::Min => present()
+            .min_by(|a, b| compare_json_values(Some(a), Some(b)))
+            .cloned()
+            .unwrap_or_default(),
+        AnnotationFunction::Max => present()
+            .max_by(|a, b| compare_json_values(Some(a), Some(b)))
+            .cloned()
+            .unwrap_or_default(),
+    }
+}
+
+/// Orders tree nodes depth-first and sets each node's `_depth`.
+///
+/// Siblings keep their relative order from `objects`. Nodes whose parent is
+/// not among `objects` (e.g. filtered out) are listed as roots.
+fn apply_tree_ordering(
+    objects: Vec<serde_json::Value>,
+    pk_field: &str,
+    parent_field: &str,
+) -> Vec<serde_json::Value> {
+    let key = |value: Option<&serde_json::Value>| {
+        value
+            .filter(|v| !v.is_null())
+            .map(serde_json::Value::to_string)
+    };
+    let pks: HashSet<String> = objects
+        .iter()
+        .filter_map(|obj| key(obj.get(pk_field)))
+        .collect();
+    let mut children: HashMap<Option<String>, Vec<usize>> = HashMap::new();
+    for (index, obj) in objects.iter().enumerate() {
+        let parent = key(obj.get(parent_field)).filter(|p| pks.contains(p));
+        children.entry(parent).or_default().push(index);
+    }
+
+    let mut slots: Vec<Option<serde_json::Value>> = objects.into_iter().map(Some).collect();
+    let mut ordered = Vec::with_capacity(slots.len());
+    let mut stack: Vec<(usize, usize)> = children
+        .get(&None)
+        .map(|roots| roots.iter().rev().map(|&i| (i, 0)).collect())
+        .unwrap_or_default();
+    while let Some((index, depth)) = stack.pop() {
+        let Some(mut obj) = slots[index].take() else {
+            continue;
+        };
+        if let Some(kids) = children.get(&key(obj.get(pk_field))) {
+            stack.extend(kids.iter().rev().map(|&i| (i, depth + 1)));
+        }
+        if let Some(map) = obj.as_object_mut() {
+            map.insert("_depth".to_string(), serde_json::json!(depth));
+        }
+        ordered.push(obj);
+    }
+    // Nodes on a parent cycle are unreachable from any root; list them last.
+    ordered.extend(slots.into_iter().flatten().map(|mut obj| {
+        if let Some(map) = obj.as_object_mut() {
+            map.insert("_depth".to_string(), serde_json::json!(0));
+        }
+        obj
+    }));
+    ordered
+}
+
 /// Compares two optional JSON values for ordering.
 fn compare_json_values(
     a: Option<&serde_json::Value>,
@@ -390,7 +618,10 @@ impl AdminDbExecutor for InMemoryAdminDb {
         params: &AdminListParams,
     ) -> Result<AdminListResult, String> {
         let model_key = admin.model_key();
-        let all_objects = self.all_objects(&model_key);
+        let mut all_objects = self.all_objects(&model_key);
+        // The computed publishing status can be filtered like a field
+        annotate_publish_status(admin, &mut all_objects, Utc::now());
+        apply_annotations(admin, &self.tables.read().unwrap(), &mut all_objects);
 
         // Collect filter choices from the unfiltered set
         let filter_field_names = list_filter_field_names(admin);
@@ -407,11 +638,20 @@ impl AdminDbExecutor for InMemoryAdminDb {
         };
 
         // Apply ordering
+        let pk_field = Self::pk_field(admin);
+        let default_ordering = admin.ordering.join(",");
         let ordering = params
             .ordering
             .as_deref()
-            .or_else(|| admin.ordering.first().map(String::as_str));
-        let ordered = apply_ordering(searched, ordering);
+            .or_else(|| (!default_ordering.is_empty()).then_some(default_ordering.as_str()));
+        let ordered = match (&admin.tree_parent_field, &params.ordering) {
+            (Some(parent_field), None) => apply_tree_ordering(
+                apply_ordering(searched, ordering, &pk_field),
+                &pk_field,
+                parent_field,
+            ),
+            _ => apply_ordering(searched, ordering, &pk_field),
+        };
 
         // Paginate
         let page_size = if params.page_size > 0 {
@@ -535,7 +775,7 @@ fn value_matches_pk(value: &serde_json::Value, pk: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model_admin::{FieldSchema, ModelAdmin};
+    use crate::model_admin::{FieldSchema, ListAnnotation, ModelAdmin};
 
     fn test_admin() -> ModelAdmin {
         ModelAdmin::new("blog", "article")
@@ -899,6 +1139,93 @@ mod tests {
         assert!(status_choices.contains(&"published".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_list_objects_multi_column_ordering() {
+        let db = InMemoryAdminDb::new();
+        let admin = test_admin();
+        for (title, status) in [
+            ("Bob", "draft"),
+            ("Alice", "published"),
+            ("Carol", "draft"),
+            ("Alice", "draft"),
+        ] {
+            let mut data = HashMap::new();
+            data.insert("title".to_string(), serde_json::json!(title));
+            data.insert("status".to_string(), serde_json::json!(status));
+            db.create_object(&admin, &data).await.unwrap();
+        }
+
+        let params = AdminListParams::new().ordering("status,-title");
+        let result = db.list_objects(&admin, &params).await.unwrap();
+        let ids: Vec<_> = result.response.results.iter().map(|o| &o["id"]).collect();
+        assert_eq!(ids, [3, 1, 4, 2]);
+
+        // Ties fall back to the primary key, newest first.
+        let params = AdminListParams::new().ordering("title");
+        let result = db.list_objects(&admin, &params).await.unwrap();
+        let ids: Vec<_> = result.response.results.iter().map(|o| &o["id"]).collect();
+        assert_eq!(ids, [4, 2, 1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_annotations() {
+        let db = InMemoryAdminDb::new();
+        let admin = test_admin()
+            .annotate(ListAnnotation::count(
+                "num_comments",
+                "blog.comment",
+                "article",
+            ))
+            .annotate(ListAnnotation::aggregate(
+                "total_votes",
+                AnnotationFunction::Sum,
+                "blog.comment",
+                "article",
+                "votes",
+            ))
+            .annotate(ListAnnotation::aggregate(
+                "best_votes",
+                AnnotationFunction::Max,
+                "blog.comment",
+                "article",
+                "votes",
+            ));
+        let comments = ModelAdmin::new("blog", "comment");
+        for title in ["Quiet", "Busy", "Mixed"] {
+            let mut data = HashMap::new();
+            data.insert("title".to_string(), serde_json::json!(title));
+            db.create_object(&admin, &data).await.unwrap();
+        }
+        for (article, votes) in [(2, 5), (2, 1), (3, 7), (2, 0)] {
+            let mut data = HashMap::new();
+            data.insert("article".to_string(), serde_json::json!(article));
+            data.insert("votes".to_string(), serde_json::json!(votes));
+            db.create_object(&comments, &data).await.unwrap();
+        }
+
+        let params = AdminListParams::new().ordering("-num_comments");
+        let result = db.list_objects(&admin, &params).await.unwrap();
+        let results = &result.response.results;
+        assert_eq!(results[0]["title"], "Busy");
+        assert_eq!(results[0]["num_comments"], 3);
+        assert_eq!(results[0]["total_votes"], 6);
+        assert_eq!(results[0]["best_votes"], 5);
+        assert_eq!(results[2]["title"], "Quiet");
+        assert_eq!(results[2]["num_comments"], 0);
+        assert_eq!(results[2]["total_votes"], 0);
+        assert!(results[2]["best_votes"].is_null());
+
+        let params = AdminListParams::new().ordering("best_votes");
+        let result = db.list_objects(&admin, &params).await.unwrap();
+        let titles: Vec<_> = result
+            .response
+            .results
+            .iter()
+            .map(|o| &o["title"])
+            .collect();
+        assert_eq!(titles, ["Busy", "Mixed", "Quiet"]);
+    }
+
     #[tokio::test]
     async fn test_list_objects_default_ordering() {
         let db = InMemoryAdminDb::new();
@@ -918,6 +1245,66 @@ mod tests {
         assert_eq!(result.response.results[2]["id"], 1);
     }
 
+    #[tokio::test]
+    async fn test_list_objects_tree_order_and_depth() {
+        let db = InMemoryAdminDb::new();
+        let admin = ModelAdmin::new("shop", "category")
+            .ordering(vec!["name"])
+            .tree_parent_field("parent_id");
+        for (name, parent) in [
+            ("Electronics", None),
+            ("Books", None),
+            ("Phones", Some(1)),
+            ("Android", Some(3)),
+            ("Audio", Some(1)),
+        ] {
+            let data = HashMap::from([
+                ("name".to_string(), serde_json::json!(name)),
+                ("parent_id".to_string(), serde_json::json!(parent)),
+            ]);
+            db.create_object(&admin, &data).await.unwrap();
+        }
+
+        let result = db
+            .list_objects(&admin, &AdminListParams::new())
+            .await
+            .unwrap();
+        let listing: Vec<(&str, u64)> = result
+            .response
+            .results
+            .iter()
+            .map(|obj| {
+                (
+                    obj["name"].as_str().unwrap(),
+                    obj["_depth"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            listing,
+            [
+                ("Books", 0),
+                ("Electronics", 0),
+                ("Audio", 1),
+                ("Phones", 1),
+                ("Android", 2),
+            ]
+        );
+
+        // An explicit ordering shows a flat list.
+        let params = AdminListParams::new().ordering("-id");
+        let result = db.list_objects(&admin, &params).await.unwrap();
+        assert_eq!(result.response.results[0]["name"], "Audio");
+        assert!(result.response.results[0].get("_depth").is_none());
+
+        // Nodes whose parent is filtered out become roots.
+        let params = AdminListParams::new().filter("parent_id", "1");
+        let result = db.list_objects(&admin, &params).await.unwrap();
+        let names: Vec<_> = result.response.results.iter().map(|o| &o["name"]).collect();
+        assert_eq!(names, ["Audio", "Phones"]);
+        assert!(result.response.results.iter().all(|o| o["_depth"] == 0));
+    }
+
     #[tokio::test]
     async fn test_list_objects_combined_search_and_filter() {
         let db = InMemoryAdminDb::new();
@@ -1042,19 +1429,34 @@ mod tests {
             serde_json::json!({"name": "A"}),
             serde_json::json!({"name": "B"}),
         ];
-        let result = apply_ordering(objects, Some("name"));
+        let result = apply_ordering(objects, Some("name"), "id");
         assert_eq!(result[0]["name"], "A");
         assert_eq!(result[1]["name"], "B");
         assert_eq!(result[2]["name"], "C");
     }
 
+    #[test]
+    fn test_apply_ordering_pk_alias() {
+        let objects = vec![
+            serde_json::json!({"slug": "b"}),
+            serde_json::json!({"slug": "c"}),
+            serde_json::json!({"slug": "a"}),
+        ];
+        let result = apply_ordering(objects.clone(), Some("pk"), "slug");
+        assert_eq!(result[0]["slug"], "a");
+        assert_eq!(result[2]["slug"], "c");
+        let result = apply_ordering(objects, Some("-pk"), "slug");
+        assert_eq!(result[0]["slug"], "c");
+        assert_eq!(result[2]["slug"], "a");
+    }
+
     #[test]
     fn test_apply_ordering_none() {
         let objects = vec![
             serde_json::json!({"name": "B"}),
             serde_json::json!({"name": "A"}),
         ];
-        let result = apply_ordering(objects.clone(), None);
+        let result = apply_ordering(objects.clone(), None, "id");
         assert_eq!(result, objects);
     }
 
@@ -0,0 +1,495 @@
+//! Autosaved drafts of unsaved change-form data.
+//!
+//! While an admin user edits an object, the React frontend periodically saves
+//! the form's current values as a draft, keyed by user, model, and primary key
+//! (`"new"` for an add form). On load, a pending draft can be offered for
+//! restore; saving the object or discarding the draft removes it. Drafts expire
+//! after a TTL so abandoned edits don't pile up.
+//!
+//! [`InMemoryDraftStore`] is the default. [`SessionDraftStore`] persists drafts
+//! through any [`SessionBackend`], in per-owner records kept apart from browser
+//! sessions.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use django_rs_admin::drafts::{DraftStore, InMemoryDraftStore};
+//!
+//! async fn example() {
+//!     let store = InMemoryDraftStore::new();
+//!     let data = HashMap::from([("title".to_string(), serde_json::json!("Half-writ"))]);
+//!     store.save("token-1", "blog.article", "new", data).await.unwrap();
+//!
+//!     let draft = store.get("token-1", "blog.article", "new").await.unwrap().unwrap();
+//!     assert_eq!(draft.data["title"], "Half-writ");
+//!     assert!(store.get("token-2", "blog.article", "new").await.unwrap().is_none());
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use django_rs_views::session::{SessionBackend, SessionData, SessionExpiry};
+use serde::{Deserialize, Serialize};
+
+/// The object id used for drafts of objects that haven't been created yet.
+pub const NEW_OBJECT_ID: &str = "new";
+
+/// How long a draft is kept when no TTL is configured.
+pub const DEFAULT_DRAFT_TTL_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+/// The prefix of the record key [`SessionDraftStore`] keeps an owner's drafts
+/// under. The `;` can't appear in a cookie value, so no browser session
+/// cookie can name one of these records.
+pub const DRAFT_RECORD_PREFIX: &str = "_admin_drafts;";
+
+/// A saved draft of change-form data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminDraft {
+    /// The content type identifier (e.g., "blog.article").
+    pub content_type: String,
+    /// The primary key of the object being edited, or `"new"`.
+    pub object_id: String,
+    /// The unsaved form values.
+    pub data: HashMap<String, serde_json::Value>,
+    /// When the draft was last saved.
+    pub saved_at: DateTime<Utc>,
+    /// When the draft will be discarded.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AdminDraft {
+    /// Creates a draft saved now that expires after `ttl`.
+    pub fn new(
+        content_type: &str,
+        object_id: &str,
+        data: HashMap<String, serde_json::Value>,
+        ttl: Duration,
+    ) -> Self {
+        let saved_at = Utc::now();
+        Self {
+            content_type: content_type.to_string(),
+            object_id: object_id.to_string(),
+            data,
+            saved_at,
+            expires_at: saved_at + ttl,
+        }
+    }
+
+    /// Returns `true` if the draft has expired.
+    pub fn is_expired(&self) -> bool {
+        Utc::now() >= self.expires_at
+    }
+}
+
+/// Trait for draft storage backends.
+///
+/// `owner` identifies the user the draft belongs to; drafts are never
+/// visible to other owners.
+#[async_trait]
+pub trait DraftStore: Send + Sync {
+    /// Saves (or replaces) the owner's draft for an object.
+    async fn save(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+        data: HashMap<String, serde_json::Value>,
+    ) -> Result<AdminDraft, String>;
+
+    /// Returns the owner's unexpired draft for an object, if any.
+    async fn get(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+    ) -> Result<Option<AdminDraft>, String>;
+
+    /// Discards the owner's draft for an object, returning whether one existed.
+    async fn discard(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+    ) -> Result<bool, String>;
+}
+
+/// Drafts are keyed by `(owner, content_type, object_id)`.
+type DraftKey = (String, String, String);
+
+/// In-memory implementation of [`DraftStore`].
+///
+/// Expired drafts are dropped lazily when read and whenever a draft is saved.
+#[derive(Debug, Clone)]
+pub struct InMemoryDraftStore {
+    drafts: Arc<RwLock<HashMap<DraftKey, AdminDraft>>>,
+    ttl: Duration,
+}
+
+impl InMemoryDraftStore {
+    /// Creates an empty store with the default TTL.
+    pub fn new() -> Self {
+        Self {
+            drafts: Arc::new(RwLock::new(HashMap::new())),
+            ttl: Duration::seconds(DEFAULT_DRAFT_TTL_SECONDS),
+        }
+    }
+
+    /// Sets how long drafts are kept.
+    #[must_use]
+    pub const fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the number of stored drafts, including expired ones not yet
+    /// dropped.
+    pub fn len(&self) -> usize {
+        self.drafts.read().unwrap().len()
+    }
+
+    /// Returns `true` if no drafts are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for InMemoryDraftStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn draft_key(owner: &str, content_type: &str, object_id: &str) -> DraftKey {
+    (
+        owner.to_string(),
+        content_type.to_string(),
+        object_id.to_string(),
+    )
+}
+
+#[async_trait]
+impl DraftStore for InMemoryDraftStore {
+    async fn save(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+        data: HashMap<String, serde_json::Value>,
+    ) -> Result<AdminDraft, String> {
+        let draft = AdminDraft::new(content_type, object_id, data, self.ttl);
+        let mut drafts = self.drafts.write().map_err(|e| e.to_string())?;
+        drafts.retain(|_, d| !d.is_expired());
+        drafts.insert(draft_key(owner, content_type, object_id), draft.clone());
+        drop(drafts);
+        Ok(draft)
+    }
+
+    async fn get(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+    ) -> Result<Option<AdminDraft>, String> {
+        let key = draft_key(owner, content_type, object_id);
+        let mut drafts = self.drafts.write().map_err(|e| e.to_string())?;
+        let draft = drafts.get(&key).cloned();
+        if draft.as_ref().is_some_and(AdminDraft::is_expired) {
+            drafts.remove(&key);
+            return Ok(None);
+        }
+        drop(drafts);
+        Ok(draft)
+    }
+
+    async fn discard(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+    ) -> Result<bool, String> {
+        let removed = self
+            .drafts
+            .write()
+            .map_err(|e| e.to_string())?
+            .remove(&draft_key(owner, content_type, object_id));
+        Ok(removed.is_some_and(|d| !d.is_expired()))
+    }
+}
+
+/// A [`DraftStore`] that keeps drafts in records of a [`SessionBackend`].
+///
+/// Each owner's drafts share one record keyed `_admin_drafts;<owner>` (see
+/// [`DRAFT_RECORD_PREFIX`]), with each draft under
+/// `_admin_draft:<content_type>:<object_id>`. The record isn't a browser
+/// session: its key can't be sent as a session cookie, and it expires one TTL
+/// after the last save, so `clear_expired` prunes abandoned records.
+pub struct SessionDraftStore {
+    backend: Arc<dyn SessionBackend>,
+    ttl: Duration,
+}
+
+impl SessionDraftStore {
+    /// Creates a store over the given session backend with the default TTL.
+    pub fn new(backend: Arc<dyn SessionBackend>) -> Self {
+        Self {
+            backend,
+            ttl: Duration::seconds(DEFAULT_DRAFT_TTL_SECONDS),
+        }
+    }
+
+    /// Sets how long drafts are kept.
+    #[must_use]
+    pub const fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    fn session_key(content_type: &str, object_id: &str) -> String {
+        format!("_admin_draft:{content_type}:{object_id}")
+    }
+
+    fn record_key(owner: &str) -> String {
+        format!("{DRAFT_RECORD_PREFIX}{owner}")
+    }
+
+    async fn load(&self, owner: &str) -> Result<Option<SessionData>, String> {
+        let key = Self::record_key(owner);
+        if self.backend.exists(&key).await.map_err(|e| e.to_string())? {
+            self.backend
+                .load(&key)
+                .await
+                .map(Some)
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl std::fmt::Debug for SessionDraftStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionDraftStore")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl DraftStore for SessionDraftStore {
+    async fn save(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+        data: HashMap<String, serde_json::Value>,
+    ) -> Result<AdminDraft, String> {
+        let draft = AdminDraft::new(content_type, object_id, data, self.ttl);
+        let mut session = self
+            .load(owner)
+            .await?
+            .unwrap_or_else(|| SessionData::new(Self::record_key(owner)));
+        let value = serde_json::to_value(&draft).map_err(|e| e.to_string())?;
+        session.set(&Self::session_key(content_type, object_id), value);
+        session.set_expiry(SessionExpiry::after(self.ttl));
+        self.backend
+            .save(&session)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(draft)
+    }
+
+    async fn get(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+    ) -> Result<Option<AdminDraft>, String> {
+        let Some(session) = self.load(owner).await? else {
+            return Ok(None);
+        };
+        let draft = session
+            .get(&Self::session_key(content_type, object_id))
+            .and_then(|v| serde_json::from_value::<AdminDraft>(v.clone()).ok());
+        Ok(draft.filter(|d| !d.is_expired()))
+    }
+
+    async fn discard(
+        &self,
+        owner: &str,
+        content_type: &str,
+        object_id: &str,
+    ) -> Result<bool, String> {
+        let Some(mut session) = self.load(owner).await? else {
+            return Ok(false);
+        };
+        let existed = session
+            .remove(&Self::session_key(content_type, object_id))
+            .is_some();
+        if existed {
+            self.backend
+                .save(&session)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(existed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_views::session::InMemorySessionBackend;
+
+    fn form(title: &str) -> HashMap<String, serde_json::Value> {
+        HashMap::from([("title".to_string(), serde_json::json!(title))])
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_save_get_discard() {
+        let store = InMemoryDraftStore::new();
+        store
+            .save("alice", "blog.article", "1", form("first"))
+            .await
+            .unwrap();
+        store
+            .save("alice", "blog.article", "1", form("second"))
+            .await
+            .unwrap();
+
+        let draft = store
+            .get("alice", "blog.article", "1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(draft.data["title"], "second");
+        assert_eq!(draft.object_id, "1");
+        assert_eq!(store.len(), 1);
+
+        assert!(store.discard("alice", "blog.article", "1").await.unwrap());
+        assert!(!store.discard("alice", "blog.article", "1").await.unwrap());
+        assert!(store
+            .get("alice", "blog.article", "1")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_drafts_are_per_owner_and_object() {
+        let store = InMemoryDraftStore::new();
+        store
+            .save("alice", "blog.article", NEW_OBJECT_ID, form("a"))
+            .await
+            .unwrap();
+        assert!(store
+            .get("bob", "blog.article", NEW_OBJECT_ID)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store
+            .get("alice", "blog.article", "1")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store
+            .get("alice", "blog.comment", NEW_OBJECT_ID)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_expired_drafts_are_dropped() {
+        let store = InMemoryDraftStore::new().ttl(Duration::seconds(-1));
+        store
+            .save("alice", "blog.article", "1", form("stale"))
+            .await
+            .unwrap();
+        assert!(store
+            .get("alice", "blog.article", "1")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_store_round_trip() {
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let store = SessionDraftStore::new(backend.clone());
+        store
+            .save("sess-1", "blog.article", "7", form("draft"))
+            .await
+            .unwrap();
+
+        let session = backend.load("_admin_drafts;sess-1").await.unwrap();
+        assert!(session.get("_admin_draft:blog.article:7").is_some());
+
+        let draft = store
+            .get("sess-1", "blog.article", "7")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(draft.data["title"], "draft");
+        assert!(store
+            .get("sess-2", "blog.article", "7")
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(store.discard("sess-1", "blog.article", "7").await.unwrap());
+        assert!(store
+            .get("sess-1", "blog.article", "7")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_store_ignores_expired_drafts() {
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let store = SessionDraftStore::new(backend).ttl(Duration::seconds(-1));
+        store
+            .save("sess-1", "blog.article", "7", form("stale"))
+            .await
+            .unwrap();
+        assert!(store
+            .get("sess-1", "blog.article", "7")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_store_drafts_are_not_reachable_by_username_key() {
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let store = SessionDraftStore::new(backend.clone());
+        store
+            .save("alice", "blog.article", "7", form("secret"))
+            .await
+            .unwrap();
+        assert!(!backend.exists("alice").await.unwrap());
+        assert!(DRAFT_RECORD_PREFIX.contains(';'));
+
+        // A browser session whose key is the username neither sees nor
+        // replaces the drafts.
+        let mut forged = SessionData::new("alice".to_string());
+        let fake = AdminDraft::new("blog.article", "7", form("forged"), Duration::hours(1));
+        forged.set(
+            "_admin_draft:blog.article:7",
+            serde_json::to_value(&fake).unwrap(),
+        );
+        backend.save(&forged).await.unwrap();
+
+        let draft = store
+            .get("alice", "blog.article", "7")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(draft.data["title"], "secret");
+    }
+}
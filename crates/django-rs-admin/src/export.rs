@@ -0,0 +1,782 @@
+//! CSV export of admin changelists.
+//!
+//! A [`CsvExport`] pages through the objects matching a changelist query in
+//! chunks of [`EXPORT_CHUNK_SIZE`] rows, so an export never holds more than one
+//! chunk in memory. The admin site streams small exports straight to the
+//! client with chunked transfer encoding. Exports of more than
+//! [`DEFAULT_EXPORT_ROW_THRESHOLD`] rows (configurable on the site) run as a
+//! background job instead: the file is written to an [`ExportStorage`] one
+//! chunk at a time and the requesting user gets an
+//! [`ExportReady`](crate::notifications::NotificationKind::ExportReady)
+//! notification linking to the download.
+//!
+//! [`FileExportStorage`] is the default storage: exports are written to disk
+//! and streamed back when downloaded, so they never sit in memory.
+//! [`InMemoryExportStorage`] suits tests.
+//!
+//! A [`LogExport`] streams the admin action log the same way, as CSV or JSON
+//! Lines, for audit teams.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use std::sync::Arc;
+//!
+//! use django_rs_admin::db::{AdminDbExecutor, AdminListParams, InMemoryAdminDb};
+//! use django_rs_admin::export::CsvExport;
+//! use django_rs_admin::model_admin::ModelAdmin;
+//!
+//! async fn example() {
+//!     let db = Arc::new(InMemoryAdminDb::new());
+//!     let admin = ModelAdmin::new("blog", "article").list_display(vec!["id", "title"]);
+//!     let data = HashMap::from([("title".to_string(), serde_json::json!("Hello, world"))]);
+//!     db.create_object(&admin, &data).await.unwrap();
+//!
+//!     let export = CsvExport::start(db, admin, AdminListParams::new()).await.unwrap();
+//!     assert_eq!(export.row_count(), 1);
+//!     let csv = export.write_all().await.unwrap();
+//!     assert_eq!(csv, b"id,title\r\n1,\"Hello, world\"\r\n");
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use async_trait::async_trait;
+use futures_util::stream::{self, Stream, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::api::JsonListResponse;
+use crate::db::{AdminDbExecutor, AdminListParams};
+use crate::log_entry::{LogEntry, LogEntryFilter, LogEntryStore};
+use crate::model_admin::ModelAdmin;
+
+/// How many rows an export fetches from the database at a time.
+pub const EXPORT_CHUNK_SIZE: usize = 500;
+
+/// Exports with more rows than this run as a background job by default.
+pub const DEFAULT_EXPORT_ROW_THRESHOLD: usize = 10_000;
+
+/// How many bytes [`FileExportStorage`] reads at a time when streaming an
+/// export back.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The content of a stored export, as a stream of chunks.
+pub type ExportReader = Pin<Box<dyn Stream<Item = Result<Vec<u8>, String>> + Send>>;
+
+/// Trait for storage of background exports.
+///
+/// An export is written chunk by chunk: [`create`](Self::create) starts an
+/// empty file and [`append`](Self::append) adds each chunk as it is
+/// rendered, so a storage backed by files or object storage never needs the
+/// whole export in memory. Files are namespaced by `owner`, the user who
+/// requested the export, so users can only download their own exports.
+#[async_trait]
+pub trait ExportStorage: Send + Sync {
+    /// Starts an empty export under `name`, replacing any file of that name.
+    async fn create(&self, owner: &str, name: &str) -> Result<(), String>;
+
+    /// Appends a chunk to the export `name`.
+    async fn append(&self, owner: &str, name: &str, chunk: &[u8]) -> Result<(), String>;
+
+    /// Deletes the export `name`, if it exists.
+    async fn remove(&self, owner: &str, name: &str) -> Result<(), String>;
+
+    /// Returns a stream of the content of an export, if it exists.
+    async fn open(&self, owner: &str, name: &str) -> Result<Option<ExportReader>, String>;
+}
+
+/// Reads a whole export into memory, for tests and small files.
+pub async fn read_export(reader: ExportReader) -> Result<Vec<u8>, String> {
+    let mut content = Vec::new();
+    let mut reader = reader;
+    while let Some(chunk) = reader.next().await {
+        content.extend_from_slice(&chunk?);
+    }
+    Ok(content)
+}
+
+/// Stored exports are keyed by `(owner, name)`.
+type FileKey = (String, String);
+
+/// In-memory implementation of [`ExportStorage`].
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryExportStorage {
+    files: Arc<RwLock<HashMap<FileKey, Vec<u8>>>>,
+}
+
+impl InMemoryExportStorage {
+    /// Creates an empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored files across all owners.
+    pub fn len(&self) -> usize {
+        self.files
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns `true` if no files are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl ExportStorage for InMemoryExportStorage {
+    async fn create(&self, owner: &str, name: &str) -> Result<(), String> {
+        let mut files = self.files.write().map_err(|e| e.to_string())?;
+        files.insert((owner.to_string(), name.to_string()), Vec::new());
+        drop(files);
+        Ok(())
+    }
+
+    async fn append(&self, owner: &str, name: &str, chunk: &[u8]) -> Result<(), String> {
+        let mut files = self.files.write().map_err(|e| e.to_string())?;
+        files
+            .get_mut(&(owner.to_string(), name.to_string()))
+            .ok_or_else(|| format!("Export '{name}' has not been created"))?
+            .extend_from_slice(chunk);
+        drop(files);
+        Ok(())
+    }
+
+    async fn remove(&self, owner: &str, name: &str) -> Result<(), String> {
+        let mut files = self.files.write().map_err(|e| e.to_string())?;
+        files.remove(&(owner.to_string(), name.to_string()));
+        drop(files);
+        Ok(())
+    }
+
+    async fn open(&self, owner: &str, name: &str) -> Result<Option<ExportReader>, String> {
+        let files = self.files.read().map_err(|e| e.to_string())?;
+        Ok(files
+            .get(&(owner.to_string(), name.to_string()))
+            .cloned()
+            .map(|content| Box::pin(stream::once(async { Ok(content) })) as ExportReader))
+    }
+}
+
+/// [`ExportStorage`] keeping each export in a file under a root directory.
+///
+/// Exports are stored at `root/<owner>/<name>`, with both components
+/// escaped so they can't name a path outside the owner's directory. Chunks
+/// are appended to the file as they are rendered and
+/// [`open`](ExportStorage::open) streams the file back 64 KiB at a time.
+#[derive(Debug, Clone)]
+pub struct FileExportStorage {
+    root: PathBuf,
+}
+
+impl FileExportStorage {
+    /// Creates a storage writing under `root`, which is created on demand.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the root directory.
+    pub fn root(&self) -> &std::path::Path {
+        &self.root
+    }
+
+    /// Returns the path of the export `name` of `owner`.
+    pub fn path(&self, owner: &str, name: &str) -> PathBuf {
+        self.root
+            .join(escape_path_component(owner))
+            .join(escape_path_component(name))
+    }
+}
+
+/// Escapes `component` into a single file name: bytes other than ASCII
+/// letters, digits, `-`, `_` and non-leading `.` become `%XX`.
+fn escape_path_component(component: &str) -> String {
+    let mut escaped = String::with_capacity(component.len());
+    for (i, byte) in component.bytes().enumerate() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' || (byte == b'.' && i > 0) {
+            escaped.push(char::from(byte));
+        } else {
+            let _ = write!(escaped, "%{byte:02X}");
+        }
+    }
+    if escaped.is_empty() {
+        escaped.push('%');
+    }
+    escaped
+}
+
+#[async_trait]
+impl ExportStorage for FileExportStorage {
+    async fn create(&self, owner: &str, name: &str) -> Result<(), String> {
+        let path = self.path(owner, name);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn append(&self, owner: &str, name: &str, chunk: &[u8]) -> Result<(), String> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(self.path(owner, name))
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => format!("Export '{name}' has not been created"),
+                _ => e.to_string(),
+            })?;
+        file.write_all(chunk).await.map_err(|e| e.to_string())?;
+        file.flush().await.map_err(|e| e.to_string())
+    }
+
+    async fn remove(&self, owner: &str, name: &str) -> Result<(), String> {
+        match tokio::fs::remove_file(self.path(owner, name)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn open(&self, owner: &str, name: &str) -> Result<Option<ExportReader>, String> {
+        let file = match tokio::fs::File::open(self.path(owner, name)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+        let chunks = stream::unfold(Some(file), |file| async move {
+            let mut file = file?;
+            let mut buf = vec![0; READ_CHUNK_SIZE];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(buf), Some(file)))
+                }
+                Err(e) => Some((Err(e.to_string()), None)),
+            }
+        });
+        Ok(Some(Box::pin(chunks)))
+    }
+}
+
+/// A CSV export of the objects matching a changelist query.
+///
+/// The first chunk is fetched by [`start`](Self::start), which tells the
+/// total row count before any output is produced; the rest are fetched as the
+/// export is consumed.
+pub struct CsvExport {
+    db: Arc<dyn AdminDbExecutor>,
+    admin: ModelAdmin,
+    params: AdminListParams,
+    columns: Vec<String>,
+    row_count: usize,
+    first_page: Option<JsonListResponse>,
+    has_next: bool,
+}
+
+impl CsvExport {
+    /// Starts an export of the objects matching `params`, ignoring its page
+    /// and page size.
+    pub async fn start(
+        db: Arc<dyn AdminDbExecutor>,
+        admin: ModelAdmin,
+        mut params: AdminListParams,
+    ) -> Result<Self, String> {
+        params.page = 1;
+        params.page_size = EXPORT_CHUNK_SIZE;
+        let first_page = db.list_objects(&admin, &params).await?.response;
+        let columns = export_columns(&admin, first_page.results.first());
+        Ok(Self {
+            db,
+            admin,
+            params,
+            columns,
+            row_count: first_page.count,
+            has_next: true,
+            first_page: Some(first_page),
+        })
+    }
+
+    /// Returns the number of rows the export will contain.
+    pub const fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Returns the exported columns, in order.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the export as a stream of CSV chunks: the header and first
+    /// chunk of rows, then one item per further chunk.
+    pub fn into_stream(self) -> impl Stream<Item = Result<String, String>> + Send {
+        stream::unfold(Some(self), |state| async move {
+            let mut export = state?;
+            match export.next_chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(export))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+
+    /// Runs the export to completion and returns the whole file.
+    pub async fn write_all(self) -> Result<Vec<u8>, String> {
+        let mut content = Vec::new();
+        let mut chunks = Box::pin(self.into_stream());
+        while let Some(chunk) = chunks.next().await {
+            content.extend_from_slice(chunk?.as_bytes());
+        }
+        Ok(content)
+    }
+
+    /// Runs the export to completion, writing each chunk to `storage` as
+    /// the export `name` of `owner`.
+    ///
+    /// If a chunk can't be rendered or stored, the partial file is removed.
+    pub async fn write_to(
+        self,
+        storage: &dyn ExportStorage,
+        owner: &str,
+        name: &str,
+    ) -> Result<(), String> {
+        storage.create(owner, name).await?;
+        let mut chunks = Box::pin(self.into_stream());
+        while let Some(chunk) = chunks.next().await {
+            let stored = match chunk {
+                Ok(chunk) => storage.append(owner, name, chunk.as_bytes()).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = stored {
+                if let Err(remove_error) = storage.remove(owner, name).await {
+                    tracing::warn!("Failed to remove partial export {name}: {remove_error}");
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the next chunk, or `None` once every page has been written.
+    async fn next_chunk(&mut self) -> Result<Option<String>, String> {
+        let mut chunk = String::new();
+        let page = match self.first_page.take() {
+            Some(page) => {
+                push_csv_record(&mut chunk, self.columns.iter().map(String::as_str));
+                page
+            }
+            None if self.has_next => {
+                self.params.page += 1;
+                self.db
+                    .list_objects(&self.admin, &self.params)
+                    .await?
+                    .response
+            }
+            None => return Ok(None),
+        };
+        self.has_next = page.has_next;
+        for row in &page.results {
+            let cells: Vec<String> = self
+                .columns
+                .iter()
+                .map(|column| csv_cell(row.get(column)))
+                .collect();
+            push_csv_record(&mut chunk, cells.iter().map(String::as_str));
+        }
+        Ok(Some(chunk))
+    }
+}
+
+impl std::fmt::Debug for CsvExport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CsvExport")
+            .field("model", &self.admin.model_key())
+            .field("columns", &self.columns)
+            .field("row_count", &self.row_count)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The columns of a CSV log export.
+pub const LOG_EXPORT_COLUMNS: [&str; 11] = [
+    "id",
+    "action_time",
+    "user_id",
+    "content_type",
+    "object_id",
+    "object_repr",
+    "action_flag",
+    "change_message",
+    "request_id",
+    "prev_hash",
+    "hash",
+];
+
+/// The file format of a [`LogExport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogExportFormat {
+    /// CSV with the [`LOG_EXPORT_COLUMNS`] header.
+    Csv,
+    /// One JSON-serialized [`LogEntry`] per line.
+    JsonLines,
+}
+
+impl LogExportFormat {
+    /// Parses a format name: `csv` or `jsonl`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Self::Csv),
+            "jsonl" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Content-Type` of an export in this format.
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::JsonLines => "application/x-ndjson",
+        }
+    }
+
+    /// Returns the file extension of an export in this format.
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// An export of the log entries matching a [`LogEntryFilter`], oldest first.
+///
+/// Entries are read [`EXPORT_CHUNK_SIZE`] at a time with
+/// [`LogEntryStore::entries_after`], so entries logged while the export runs
+/// are included and pruned ones are skipped, but none is written twice.
+pub struct LogExport {
+    store: Arc<dyn LogEntryStore>,
+    filter: LogEntryFilter,
+    format: LogExportFormat,
+    after_id: u64,
+    header_written: bool,
+}
+
+impl LogExport {
+    /// Creates an export of the entries in `store` matching `filter`.
+    pub fn new(
+        store: Arc<dyn LogEntryStore>,
+        filter: LogEntryFilter,
+        format: LogExportFormat,
+    ) -> Self {
+        Self {
+            store,
+            filter,
+            format,
+            after_id: 0,
+            header_written: false,
+        }
+    }
+
+    /// Returns the export as a stream of chunks, one per page of entries. A
+    /// CSV export always yields at least its header.
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<String, std::convert::Infallible>> + Send {
+        stream::unfold(self, |mut export| async move {
+            export.next_chunk().map(|chunk| (Ok(chunk), export))
+        })
+    }
+
+    /// Renders the next chunk, or `None` once every entry has been written.
+    fn next_chunk(&mut self) -> Option<String> {
+        let entries = self
+            .store
+            .entries_after(&self.filter, self.after_id, EXPORT_CHUNK_SIZE);
+        let mut chunk = String::new();
+        if self.format == LogExportFormat::Csv && !self.header_written {
+            push_csv_record(&mut chunk, LOG_EXPORT_COLUMNS.into_iter());
+        }
+        self.header_written = true;
+        let last = entries.last()?;
+        self.after_id = last.id;
+        for entry in &entries {
+            match self.format {
+                LogExportFormat::Csv => push_log_record(&mut chunk, entry),
+                LogExportFormat::JsonLines => {
+                    chunk.push_str(&serde_json::to_string(entry).unwrap_or_default());
+                    chunk.push('\n');
+                }
+            }
+        }
+        Some(chunk)
+    }
+}
+
+impl std::fmt::Debug for LogExport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogExport")
+            .field("filter", &self.filter)
+            .field("format", &self.format)
+            .field("after_id", &self.after_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Appends a log entry as a CSV record of [`LOG_EXPORT_COLUMNS`].
+fn push_log_record(out: &mut String, entry: &LogEntry) {
+    let (prev_hash, hash) = entry.chain.as_ref().map_or(("", ""), |link| {
+        (link.prev_hash.as_str(), link.hash.as_str())
+    });
+    let cells = [
+        entry.id.to_string(),
+        entry.action_time.to_rfc3339(),
+        entry.user_id.to_string(),
+        entry.content_type.clone(),
+        entry.object_id.clone(),
+        entry.object_repr.clone(),
+        entry.action_flag.as_u8().to_string(),
+        entry.change_message.clone(),
+        entry
+            .audit
+            .as_ref()
+            .and_then(|audit| audit.request_id.clone())
+            .unwrap_or_default(),
+        prev_hash.to_string(),
+        hash.to_string(),
+    ];
+    push_csv_record(out, cells.iter().map(String::as_str));
+}
+
+/// Returns the columns exported for a model: its `list_display` fields, else
+/// the fields in its schema, else the keys of `sample` in alphabetical order.
+fn export_columns(admin: &ModelAdmin, sample: Option<&serde_json::Value>) -> Vec<String> {
+    let listed: Vec<String> = admin
+        .list_display
+        .iter()
+        .filter(|field| *field != "__str__")
+        .cloned()
+        .collect();
+    if !listed.is_empty() {
+        return listed;
+    }
+    if !admin.fields_schema.is_empty() {
+        return admin
+            .fields_schema
+            .iter()
+            .map(|field| field.name.clone())
+            .collect();
+    }
+    let mut keys: Vec<String> = sample
+        .and_then(serde_json::Value::as_object)
+        .map(|object| object.keys().cloned().collect())
+        .unwrap_or_default();
+    keys.sort();
+    keys
+}
+
+/// Formats a JSON value as a CSV cell; missing values and nulls are empty.
+fn csv_cell(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Appends one CSV record, quoting fields as RFC 4180 requires.
+fn push_csv_record<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryAdminDb;
+
+    async fn seeded_db(admin: &ModelAdmin, rows: usize) -> Arc<InMemoryAdminDb> {
+        let db = Arc::new(InMemoryAdminDb::new());
+        for i in 0..rows {
+            let data = HashMap::from([
+                ("title".to_string(), serde_json::json!(format!("Post {i}"))),
+                ("views".to_string(), serde_json::json!(i)),
+            ]);
+            db.create_object(admin, &data).await.unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        let mut out = String::new();
+        push_csv_record(
+            &mut out,
+            ["plain", "a,b", "say \"hi\"", "two\nlines", ""].into_iter(),
+        );
+        assert_eq!(out, "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\r\n");
+    }
+
+    #[test]
+    fn test_csv_cell_values() {
+        assert_eq!(csv_cell(None), "");
+        assert_eq!(csv_cell(Some(&serde_json::Value::Null)), "");
+        assert_eq!(csv_cell(Some(&serde_json::json!(true))), "true");
+        assert_eq!(csv_cell(Some(&serde_json::json!(1.5))), "1.5");
+        assert_eq!(csv_cell(Some(&serde_json::json!(["a", 1]))), "[\"a\",1]");
+    }
+
+    #[test]
+    fn test_export_columns_fallbacks() {
+        let admin = ModelAdmin::new("blog", "article");
+        let sample = serde_json::json!({"title": "x", "id": 1});
+        assert_eq!(export_columns(&admin, Some(&sample)), vec!["id", "title"]);
+        assert!(export_columns(&admin, None).is_empty());
+
+        let admin = admin.list_display(vec!["__str__", "title"]);
+        assert_eq!(export_columns(&admin, Some(&sample)), vec!["title"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_streams_one_chunk_per_page() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["id", "title"])
+            .ordering(vec!["id"]);
+        let db = seeded_db(&admin, EXPORT_CHUNK_SIZE + 2).await;
+
+        let export = CsvExport::start(db, admin, AdminListParams::new().page(3))
+            .await
+            .unwrap();
+        assert_eq!(export.row_count(), EXPORT_CHUNK_SIZE + 2);
+        let chunks: Vec<String> = export.into_stream().map(Result::unwrap).collect().await;
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("id,title\r\n1,Post 0\r\n"));
+        assert_eq!(chunks[0].lines().count(), EXPORT_CHUNK_SIZE + 1);
+        assert_eq!(chunks[1], "501,Post 500\r\n502,Post 501\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_export_respects_search() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title"])
+            .search_fields(vec!["title"]);
+        let db = seeded_db(&admin, 12).await;
+
+        let export = CsvExport::start(db, admin, AdminListParams::new().search("Post 1"))
+            .await
+            .unwrap();
+        assert_eq!(export.row_count(), 3);
+        let csv = String::from_utf8(export.write_all().await.unwrap()).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_is_per_owner() {
+        let storage = InMemoryExportStorage::new();
+        storage.create("alice", "a.csv").await.unwrap();
+        storage.append("alice", "a.csv", b"x").await.unwrap();
+        storage.append("alice", "a.csv", b"y").await.unwrap();
+        let reader = storage.open("alice", "a.csv").await.unwrap().unwrap();
+        assert_eq!(read_export(reader).await.unwrap(), b"xy");
+        assert!(storage.open("bob", "a.csv").await.unwrap().is_none());
+        assert!(storage.append("bob", "a.csv", b"z").await.is_err());
+        assert_eq!(storage.len(), 1);
+
+        storage.remove("alice", "a.csv").await.unwrap();
+        assert!(storage.is_empty());
+    }
+
+    /// Records the size of every appended chunk.
+    #[derive(Default)]
+    struct ChunkSizes {
+        inner: InMemoryExportStorage,
+        sizes: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl ExportStorage for ChunkSizes {
+        async fn create(&self, owner: &str, name: &str) -> Result<(), String> {
+            self.inner.create(owner, name).await
+        }
+        async fn append(&self, owner: &str, name: &str, chunk: &[u8]) -> Result<(), String> {
+            self.sizes.lock().unwrap().push(chunk.len());
+            self.inner.append(owner, name, chunk).await
+        }
+        async fn remove(&self, owner: &str, name: &str) -> Result<(), String> {
+            self.inner.remove(owner, name).await
+        }
+        async fn open(&self, owner: &str, name: &str) -> Result<Option<ExportReader>, String> {
+            self.inner.open(owner, name).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_to_stores_each_chunk() {
+        let admin = ModelAdmin::new("blog", "article").list_display(vec!["id", "title"]);
+        let db = seeded_db(&admin, EXPORT_CHUNK_SIZE + 2).await;
+        let export = CsvExport::start(db.clone(), admin.clone(), AdminListParams::new())
+            .await
+            .unwrap();
+        let expected = export.write_all().await.unwrap();
+
+        let storage = ChunkSizes::default();
+        let export = CsvExport::start(db, admin, AdminListParams::new())
+            .await
+            .unwrap();
+        export.write_to(&storage, "alice", "a.csv").await.unwrap();
+        assert_eq!(storage.sizes.lock().unwrap().len(), 2);
+        let reader = storage.open("alice", "a.csv").await.unwrap().unwrap();
+        assert_eq!(read_export(reader).await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_streams_per_owner_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileExportStorage::new(dir.path());
+        let chunk = vec![b'x'; READ_CHUNK_SIZE];
+        storage.create("alice", "a.csv").await.unwrap();
+        storage.append("alice", "a.csv", &chunk).await.unwrap();
+        storage.append("alice", "a.csv", b"tail").await.unwrap();
+
+        let reader = storage.open("alice", "a.csv").await.unwrap().unwrap();
+        let chunks: Vec<Vec<u8>> = reader.map(Result::unwrap).collect().await;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], chunk);
+        assert_eq!(chunks[1], b"tail");
+
+        assert!(storage.open("bob", "a.csv").await.unwrap().is_none());
+        assert!(storage.append("bob", "a.csv", b"z").await.is_err());
+        // Names can't reach another owner's directory.
+        assert!(storage
+            .open("bob", "../alice/a.csv")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(storage
+            .path("bob", "../alice/a.csv")
+            .starts_with(dir.path().join("bob")));
+
+        storage.remove("alice", "a.csv").await.unwrap();
+        assert!(storage.open("alice", "a.csv").await.unwrap().is_none());
+        storage.remove("alice", "a.csv").await.unwrap();
+    }
+}
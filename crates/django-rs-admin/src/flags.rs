@@ -0,0 +1,282 @@
+//! Database-backed feature flags, toggled from the admin.
+//!
+//! Flags in `Settings::feature_flags` are fixed at deploy time. Flags saved
+//! in a [`FlagStore`] can be changed while the site runs: superusers turn
+//! them on, widen a percentage rollout or add per-user overrides through the
+//! admin's `/flags/` endpoint, which saves the flag and applies it to the
+//! process-wide [`FLAGS`](django_rs_core::flags::FLAGS) registry straight
+//! away. A stored flag replaces the settings flag of the same name until it
+//! is deleted.
+//!
+//! [`InMemoryFlagStore`] is the default. [`DatabaseFlagStore`] persists flags
+//! in a `django_feature_flags` table so they survive restarts; call
+//! [`load_flags`] at startup, and periodically when several workers share the
+//! table, to pick up flags changed by other processes.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//! use django_rs_admin::flags::{load_flags, FlagStore, InMemoryFlagStore};
+//! use django_rs_admin::site::AdminSite;
+//! use django_rs_core::flags::{flag_enabled, Flag, FlagContext, FLAGS};
+//!
+//! async fn example() {
+//!     let store = Arc::new(InMemoryFlagStore::new());
+//!     store.set("new_checkout", &Flag::percentage(10)).await.unwrap();
+//!     load_flags(store.as_ref(), &FLAGS).await.unwrap();
+//!
+//!     let site = AdminSite::new("admin").flag_store(store);
+//!     let enabled = flag_enabled("new_checkout", &FlagContext::for_user("42"));
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use django_rs_core::flags::{Flag, FlagRegistry};
+use django_rs_core::{DjangoError, SETTINGS};
+use django_rs_db::executor::DbExecutor;
+use django_rs_db::value::Value;
+use serde::{Deserialize, Serialize};
+
+/// Trait for feature flag storage backends.
+#[async_trait]
+pub trait FlagStore: Send + Sync {
+    /// Returns all stored flags, keyed by name.
+    async fn all(&self) -> Result<HashMap<String, Flag>, String>;
+
+    /// Saves a flag, replacing any stored flag of the same name.
+    async fn set(&self, name: &str, flag: &Flag) -> Result<(), String>;
+
+    /// Deletes a flag. Returns `false` if no flag of that name was stored.
+    async fn delete(&self, name: &str) -> Result<bool, String>;
+}
+
+/// In-memory implementation of [`FlagStore`].
+///
+/// Flags are lost on restart and not shared between processes.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryFlagStore {
+    flags: Arc<RwLock<HashMap<String, Flag>>>,
+}
+
+impl InMemoryFlagStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FlagStore for InMemoryFlagStore {
+    async fn all(&self) -> Result<HashMap<String, Flag>, String> {
+        self.flags
+            .read()
+            .map(|flags| flags.clone())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn set(&self, name: &str, flag: &Flag) -> Result<(), String> {
+        self.flags
+            .write()
+            .map_err(|e| e.to_string())?
+            .insert(name.to_string(), flag.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, name: &str) -> Result<bool, String> {
+        Ok(self
+            .flags
+            .write()
+            .map_err(|e| e.to_string())?
+            .remove(name)
+            .is_some())
+    }
+}
+
+/// A [`FlagStore`] backed by a [`DbExecutor`].
+///
+/// Stores each flag as JSON in a `django_feature_flags` table with columns:
+/// - `name TEXT PRIMARY KEY`
+/// - `flag TEXT` (JSON-serialized)
+pub struct DatabaseFlagStore {
+    db: Arc<dyn DbExecutor>,
+}
+
+impl DatabaseFlagStore {
+    /// Creates a store over the given executor.
+    pub fn new(db: Arc<dyn DbExecutor>) -> Self {
+        Self { db }
+    }
+
+    /// Creates the `django_feature_flags` table if it does not already
+    /// exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns the database error if the table cannot be created.
+    pub async fn create_table(&self) -> Result<(), DjangoError> {
+        let sql = "CREATE TABLE IF NOT EXISTS django_feature_flags (\
+            name TEXT PRIMARY KEY, \
+            flag TEXT NOT NULL\
+        )";
+        self.db.execute_sql(sql, &[]).await.map(|_| ())
+    }
+}
+
+impl std::fmt::Debug for DatabaseFlagStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatabaseFlagStore").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl FlagStore for DatabaseFlagStore {
+    async fn all(&self) -> Result<HashMap<String, Flag>, String> {
+        let rows = self
+            .db
+            .query("SELECT name, flag FROM django_feature_flags", &[])
+            .await
+            .map_err(|e| e.to_string())?;
+        rows.iter()
+            .map(|row| {
+                let name: String = row.get("name").map_err(|e| e.to_string())?;
+                let json: String = row.get("flag").map_err(|e| e.to_string())?;
+                let flag = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+                Ok((name, flag))
+            })
+            .collect()
+    }
+
+    async fn set(&self, name: &str, flag: &Flag) -> Result<(), String> {
+        let json = serde_json::to_string(flag).map_err(|e| e.to_string())?;
+        let sql = "INSERT INTO django_feature_flags (name, flag) VALUES ($1, $2) \
+                    ON CONFLICT(name) DO UPDATE SET flag = $2";
+        self.db
+            .execute_sql(sql, &[Value::String(name.to_string()), Value::String(json)])
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn delete(&self, name: &str) -> Result<bool, String> {
+        self.db
+            .execute_sql(
+                "DELETE FROM django_feature_flags WHERE name = $1",
+                &[Value::String(name.to_string())],
+            )
+            .await
+            .map(|deleted| deleted > 0)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Replaces the runtime flags of `registry` with the flags in `store`.
+/// Returns the number of flags loaded.
+///
+/// # Errors
+///
+/// Returns the store's error if the flags cannot be read; the registry is
+/// left unchanged.
+pub async fn load_flags(store: &dyn FlagStore, registry: &FlagRegistry) -> Result<usize, String> {
+    let flags = store.all().await?;
+    let count = flags.len();
+    registry.replace_all(flags);
+    Ok(count)
+}
+
+/// Where a flag listed by the admin is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagSource {
+    /// `Settings::feature_flags`; read-only in the admin.
+    Settings,
+    /// The flag store; changeable in the admin.
+    Database,
+}
+
+/// A flag as listed by the admin's `/flags/` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagStatus {
+    /// Where the flag in effect is defined.
+    pub source: FlagSource,
+    /// Whether a settings flag of the same name is replaced by a stored one.
+    pub overrides_settings: bool,
+    /// The flag's rules.
+    #[serde(flatten)]
+    pub flag: Flag,
+}
+
+/// Returns the flags in effect, keyed by name: the stored flags, and the
+/// settings flags they don't replace.
+pub fn flag_statuses(
+    stored: impl IntoIterator<Item = (String, Flag)>,
+) -> HashMap<String, FlagStatus> {
+    let settings_flags = if SETTINGS.is_configured() {
+        SETTINGS.get().feature_flags.clone()
+    } else {
+        HashMap::new()
+    };
+    let mut statuses: HashMap<String, FlagStatus> = stored
+        .into_iter()
+        .map(|(name, flag)| {
+            let status = FlagStatus {
+                source: FlagSource::Database,
+                overrides_settings: settings_flags.contains_key(&name),
+                flag,
+            };
+            (name, status)
+        })
+        .collect();
+    for (name, flag) in settings_flags {
+        statuses.entry(name).or_insert(FlagStatus {
+            source: FlagSource::Settings,
+            overrides_settings: false,
+            flag,
+        });
+    }
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_core::flags::FlagContext;
+
+    #[tokio::test]
+    async fn test_in_memory_store() {
+        let store = InMemoryFlagStore::new();
+        store.set("beta", &Flag::on()).await.unwrap();
+        store.set("beta", &Flag::percentage(5)).await.unwrap();
+        assert_eq!(store.all().await.unwrap()["beta"], Flag::percentage(5));
+        assert!(store.delete("beta").await.unwrap());
+        assert!(!store.delete("beta").await.unwrap());
+        assert!(store.all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_flags_replaces_runtime_flags() {
+        let store = InMemoryFlagStore::new();
+        store
+            .set("beta", &Flag::off().with_user("alice", true))
+            .await
+            .unwrap();
+        let registry = FlagRegistry::new();
+        registry.set("stale", Flag::on());
+
+        assert_eq!(load_flags(&store, &registry).await.unwrap(), 1);
+        assert!(registry.is_enabled("beta", &FlagContext::for_user("alice")));
+        assert!(!registry.is_enabled("stale", &FlagContext::for_user("alice")));
+    }
+
+    #[test]
+    fn test_flag_status_serializes_flat() {
+        let statuses = flag_statuses(HashMap::from([("beta".to_string(), Flag::on())]));
+        let json = serde_json::to_value(&statuses["beta"]).unwrap();
+        assert_eq!(json["source"], "database");
+        assert_eq!(json["enabled"], true);
+        assert_eq!(json["overrides_settings"], false);
+    }
+}
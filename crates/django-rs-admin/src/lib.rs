@@ -9,7 +9,11 @@
 //!   in the admin panel, with a builder pattern API
 //! - **REST API** ([`api`]) - JSON endpoints consumed by the React admin dashboard,
 //!   including paginated list views, schema introspection, and CRUD operations
+//! - **Documentation** ([`admindocs`]) - Browsable docs of the registered models,
+//!   URL patterns and template tags and filters, like `django.contrib.admindocs`
 //! - **Actions** ([`actions`]) - Bulk operations on selected model objects
+//! - **Action jobs** ([`action_jobs`]) - Long-running actions run in the
+//!   background, with progress polling and cancellation
 //! - **Filters** ([`filters`]) - List view filtering and searching
 //! - **Contrib modules** ([`contrib`]) - Reusable utilities including content types,
 //!   messages, humanize formatting, sitemaps, and static files management
@@ -17,6 +21,29 @@
 //! - **Database integration** ([`db`]) - CRUD operations backed by a database
 //!   executor, with pagination, search, and filtering support
 //! - **`LogEntry`** ([`log_entry`]) - Audit trail for admin actions (create, change, delete)
+//! - **Export** ([`export`]) - Streaming CSV export of changelists, with large
+//!   exports written to storage by a background job
+//! - **Drafts** ([`drafts`]) - Per-user autosave of unsaved change-form data
+//! - **Comments** ([`comments`]) - Comment threads on admin objects, with
+//!   `@mentions` that notify the mentioned users
+//! - **Feature flags** ([`flags`]) - Database-backed feature flags toggled from
+//!   the admin while the site runs
+//! - **Maintenance** ([`maintenance`]) - Site-wide maintenance mode and read-only
+//!   switch, toggled from the admin and enforced by a middleware
+//! - **Notifications** ([`notifications`]) - Per-user notification center with
+//!   read/unread state and a live server-sent events stream
+//! - **Permission matrix** ([`permission_matrix`]) - The groups × permissions
+//!   grid and per-user overrides, read and updated in one request
+//! - **Scheduled publishing** ([`publishing`]) - Draft/scheduled/live/expired
+//!   status computed from publish and unpublish times, with a "publish now" action
+//! - **Print views** ([`print`]) - Renders an object through a template to
+//!   standalone HTML, or PDF with the `pdf` feature, for invoices and reports
+//! - **Model counts** ([`model_counts`]) - Cached object counts for the
+//!   sidebar, invalidated by admin writes and the save and delete signals
+//! - **Registry** ([`registry`]) - The models served by a running admin,
+//!   registered and unregistered at runtime from Rust or a superuser endpoint
+//! - **Read replicas** ([`replica`]) - Routes list/detail reads to a replica and
+//!   writes to the primary, with fallback when the replica fails
 //!
 //! ## Architecture
 //!
@@ -41,11 +68,25 @@
 //! let router = site.into_axum_router();
 //! ```
 
+pub mod action_jobs;
 pub mod actions;
+pub mod admindocs;
 pub mod api;
+pub mod comments;
 pub mod contrib;
 pub mod db;
+pub mod drafts;
+pub mod export;
 pub mod filters;
+pub mod flags;
 pub mod log_entry;
+pub mod maintenance;
 pub mod model_admin;
+pub mod model_counts;
+pub mod notifications;
+pub mod permission_matrix;
+pub mod print;
+pub mod publishing;
+pub mod registry;
+pub mod replica;
 pub mod site;
@@ -4,10 +4,22 @@
 //! actions. Each time an admin user creates, changes, or deletes an object,
 //! a `LogEntry` is created to maintain an audit trail.
 //!
+//! Entries record the [`AuditContext`] in scope when they are written, so an
+//! admin action can be matched with the SQL statements that carried it out.
+//!
 //! The log entries are stored in memory via [`InMemoryLogEntryStore`], which is
 //! the default implementation. In a production deployment, a database-backed
 //! store could be used instead.
 //!
+//! The log is append-only. To keep it from growing without bound, a
+//! [`RetentionPolicy`] drops the oldest entries by age or count, either on
+//! demand via [`LogEntryStore::prune`] or periodically via
+//! [`spawn_retention_job`]. For compliance, a store can link entries in a
+//! chain of HMAC-SHA256 hashes, so that [`verify_chain`] detects entries that
+//! were altered or removed from the middle of the log by anyone without the
+//! chain's key. The admin site exports the
+//! log at `GET /log/export/`.
+//!
 //! # Examples
 //!
 //! ```
@@ -23,10 +35,18 @@
 //! ```
 
 use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 use chrono::{DateTime, Utc};
+use django_rs_db::AuditContext;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The previous hash of the first entry in a hash chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
 /// Action flag constants matching Django's `LogEntry.ADDITION`, `CHANGE`, `DELETION`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -94,6 +114,26 @@ pub struct LogEntry {
     pub action_flag: ActionFlag,
     /// A description of the changes made.
     pub change_message: String,
+    /// The [`AuditContext`] in scope when the action was logged, e.g. the
+    /// request id set by `AuditContextMiddleware`.
+    #[serde(default)]
+    pub audit: Option<AuditContext>,
+    /// The entry's link in the store's hash chain, if it keeps one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<ChainLink>,
+}
+
+/// An entry's link in a tamper-evident hash chain.
+///
+/// `hash` covers the entry's fields and `prev_hash`, the hash of the entry
+/// before it, so changing or removing an entry breaks every link after it.
+/// The hashes are keyed, so they cannot be recomputed without the key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLink {
+    /// The hash of the previous entry, or [`GENESIS_HASH`] for the first.
+    pub prev_hash: String,
+    /// The hex HMAC-SHA256 hash of this entry.
+    pub hash: String,
 }
 
 impl LogEntry {
@@ -112,6 +152,30 @@ impl LogEntry {
         self.action_flag == ActionFlag::Deletion
     }
 
+    /// Returns the hex HMAC-SHA256 of this entry's fields under `key`,
+    /// chained to `prev_hash`.
+    ///
+    /// The `chain` field itself is not covered.
+    pub fn chain_hash(&self, key: &[u8], prev_hash: &str) -> String {
+        let fields = serde_json::json!([
+            self.id,
+            self.action_time
+                .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+            self.user_id,
+            self.content_type,
+            self.object_id,
+            self.object_repr,
+            self.action_flag.as_u8(),
+            self.change_message,
+            self.audit,
+        ]);
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(prev_hash.as_bytes());
+        mac.update(b"\n");
+        mac.update(fields.to_string().as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+
     /// Returns a human-readable description of this log entry.
     pub fn description(&self) -> String {
         let action = self.action_flag.label();
@@ -137,6 +201,136 @@ impl std::fmt::Display for LogEntry {
     }
 }
 
+/// Checks the hash chain of `entries`, given oldest first, under the chain's
+/// `key`.
+///
+/// The first entry's `prev_hash` is trusted as the anchor, since the entries
+/// before it may have been pruned. Returns the id of the first entry that is
+/// unhashed, was altered, or does not follow its predecessor (an entry was
+/// removed or reordered).
+///
+/// # Errors
+///
+/// Returns the id of the first entry failing verification.
+pub fn verify_chain(entries: &[LogEntry], key: &[u8]) -> Result<(), u64> {
+    let mut expected_prev: Option<&str> = None;
+    for entry in entries {
+        let Some(link) = &entry.chain else {
+            return Err(entry.id);
+        };
+        if expected_prev.is_some_and(|prev| prev != link.prev_hash)
+            || entry.chain_hash(key, &link.prev_hash) != link.hash
+        {
+            return Err(entry.id);
+        }
+        expected_prev = Some(&link.hash);
+    }
+    Ok(())
+}
+
+/// Criteria selecting log entries, e.g. for an export.
+///
+/// Unset criteria match every entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogEntryFilter {
+    /// Only entries by this user.
+    pub user_id: Option<u64>,
+    /// Only entries for this content type (e.g. `"blog.article"`).
+    pub content_type: Option<String>,
+    /// Only entries at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl LogEntryFilter {
+    /// Returns `true` if `entry` meets every criterion.
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        self.user_id.map_or(true, |id| entry.user_id == id)
+            && self
+                .content_type
+                .as_ref()
+                .map_or(true, |ct| &entry.content_type == ct)
+            && self.since.map_or(true, |since| entry.action_time >= since)
+            && self.until.map_or(true, |until| entry.action_time < until)
+    }
+}
+
+/// How long log entries are kept.
+///
+/// Entries are dropped oldest first when they are older than `max_age` or
+/// when there are more than `max_entries`. A policy with neither keeps
+/// everything.
+///
+/// ```
+/// use django_rs_admin::log_entry::RetentionPolicy;
+///
+/// let policy = RetentionPolicy::new()
+///     .max_age(chrono::Duration::days(365))
+///     .max_entries(1_000_000);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Entries older than this are dropped.
+    pub max_age: Option<chrono::Duration>,
+    /// At most this many of the newest entries are kept.
+    pub max_entries: Option<usize>,
+}
+
+impl RetentionPolicy {
+    /// Creates a policy that keeps everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops entries older than `age`.
+    #[must_use]
+    pub const fn max_age(mut self, age: chrono::Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Keeps at most `count` entries.
+    #[must_use]
+    pub const fn max_entries(mut self, count: usize) -> Self {
+        self.max_entries = Some(count);
+        self
+    }
+}
+
+/// Spawns a task applying `policy` to `store` every `period`, starting now.
+///
+/// Dropping the returned handle leaves the job running; abort it to stop.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use django_rs_admin::log_entry::{spawn_retention_job, InMemoryLogEntryStore, RetentionPolicy};
+///
+/// # async fn example() {
+/// let store = Arc::new(InMemoryLogEntryStore::new());
+/// let policy = RetentionPolicy::new().max_age(chrono::Duration::days(90));
+/// let job = spawn_retention_job(store, policy, Duration::from_secs(3600));
+/// # job.abort();
+/// # }
+/// ```
+pub fn spawn_retention_job(
+    store: Arc<dyn LogEntryStore>,
+    policy: RetentionPolicy,
+    period: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let pruned = store.prune(&policy, Utc::now());
+            if pruned > 0 {
+                tracing::info!("Pruned {pruned} admin log entries");
+            }
+        }
+    })
+}
+
 /// Trait for log entry storage backends.
 ///
 /// Provides methods to create log entries and query history for objects or users.
@@ -186,6 +380,32 @@ pub trait LogEntryStore: Send + Sync {
     /// Returns the total number of log entries.
     fn count(&self) -> usize;
 
+    /// Returns up to `limit` entries matching `filter` with an id greater
+    /// than `after_id`, oldest first.
+    ///
+    /// Passing the last id of one page as `after_id` fetches the next, which
+    /// is how exports page through the log. The default implementation
+    /// filters the whole log from [`recent`](Self::recent); stores that can
+    /// query by id should override it.
+    fn entries_after(&self, filter: &LogEntryFilter, after_id: u64, limit: usize) -> Vec<LogEntry> {
+        let mut entries = self.recent(self.count());
+        entries.reverse();
+        entries
+            .into_iter()
+            .filter(|e| e.id > after_id && filter.matches(e))
+            .take(limit)
+            .collect()
+    }
+
+    /// Drops the entries `policy` does not keep as of `now`, returning how
+    /// many were dropped.
+    ///
+    /// The default implementation keeps every entry and returns 0.
+    fn prune(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> usize {
+        let _ = (policy, now);
+        0
+    }
+
     /// Clears all log entries.
     fn clear(&self);
 }
@@ -198,6 +418,22 @@ pub trait LogEntryStore: Send + Sync {
 pub struct InMemoryLogEntryStore {
     entries: Arc<RwLock<Vec<LogEntry>>>,
     next_id: Arc<AtomicU64>,
+    /// The store's hash chain, if it keeps one.
+    chain: Option<Arc<HashChain>>,
+}
+
+/// The key of a store's hash chain and the hash of the last entry written.
+struct HashChain {
+    key: Vec<u8>,
+    head: Mutex<String>,
+}
+
+impl std::fmt::Debug for HashChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashChain")
+            .field("head", &self.head)
+            .finish_non_exhaustive()
+    }
 }
 
 impl InMemoryLogEntryStore {
@@ -206,6 +442,24 @@ impl InMemoryLogEntryStore {
         Self {
             entries: Arc::new(RwLock::new(Vec::new())),
             next_id: Arc::new(AtomicU64::new(1)),
+            chain: None,
+        }
+    }
+
+    /// Creates a new empty store that links its entries in a hash chain
+    /// keyed with `key`.
+    ///
+    /// Keep the key away from anyone who can write to the log, e.g. derive
+    /// it from `SECRET_KEY`; with it, a forged chain can be recomputed. The
+    /// chain continues across pruning, so [`verify_chain`] still checks the
+    /// remaining entries.
+    pub fn with_hash_chain(key: &[u8]) -> Self {
+        Self {
+            chain: Some(Arc::new(HashChain {
+                key: key.to_vec(),
+                head: Mutex::new(GENESIS_HASH.to_string()),
+            })),
+            ..Self::new()
         }
     }
 
@@ -219,8 +473,11 @@ impl InMemoryLogEntryStore {
         action_flag: ActionFlag,
         change_message: &str,
     ) -> LogEntry {
+        // Ids are taken under the lock so entries are appended in id order,
+        // as the hash chain and `entries_after` need
+        let mut entries = self.entries.write().unwrap();
         let id = self.next_id.fetch_add(1, AtomicOrdering::Relaxed);
-        let entry = LogEntry {
+        let mut entry = LogEntry {
             id,
             action_time: Utc::now(),
             user_id,
@@ -229,8 +486,17 @@ impl InMemoryLogEntryStore {
             object_repr: object_repr.to_string(),
             action_flag,
             change_message: change_message.to_string(),
+            audit: AuditContext::current(),
+            chain: None,
         };
-        let mut entries = self.entries.write().unwrap();
+        if let Some(chain) = &self.chain {
+            let mut head = chain.head.lock().unwrap();
+            let hash = entry.chain_hash(&chain.key, &head);
+            entry.chain = Some(ChainLink {
+                prev_hash: std::mem::replace(&mut *head, hash.clone()),
+                hash,
+            });
+        }
         entries.push(entry.clone());
         entry
     }
@@ -367,12 +633,38 @@ impl LogEntryStore for InMemoryLogEntryStore {
         let mut entries = self.entries.write().unwrap();
         entries.clear();
     }
+
+    fn entries_after(&self, filter: &LogEntryFilter, after_id: u64, limit: usize) -> Vec<LogEntry> {
+        let entries = self.entries.read().unwrap();
+        let start = entries.partition_point(|e| e.id <= after_id);
+        entries[start..]
+            .iter()
+            .filter(|e| filter.matches(e))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn prune(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> usize {
+        let mut entries = self.entries.write().unwrap();
+        let mut expired = policy.max_age.map_or(0, |age| {
+            let cutoff = now - age;
+            entries.partition_point(|e| e.action_time < cutoff)
+        });
+        if let Some(max) = policy.max_entries {
+            expired = expired.max(entries.len().saturating_sub(max));
+        }
+        entries.drain(..expired);
+        expired
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const CHAIN_KEY: &[u8] = b"audit-chain-key";
+
     #[test]
     fn test_action_flag_values() {
         assert_eq!(ActionFlag::Addition.as_u8(), 1);
@@ -422,6 +714,8 @@ mod tests {
             object_repr: "Test Article".to_string(),
             action_flag: ActionFlag::Addition,
             change_message: String::new(),
+            audit: None,
+            chain: None,
         };
         assert!(entry.is_addition());
         assert!(!entry.is_change());
@@ -439,6 +733,8 @@ mod tests {
             object_repr: "Test Article".to_string(),
             action_flag: ActionFlag::Change,
             change_message: "Changed title".to_string(),
+            audit: None,
+            chain: None,
         };
         assert!(!entry.is_addition());
         assert!(entry.is_change());
@@ -456,6 +752,8 @@ mod tests {
             object_repr: "Test Article".to_string(),
             action_flag: ActionFlag::Deletion,
             change_message: String::new(),
+            audit: None,
+            chain: None,
         };
         assert!(!entry.is_addition());
         assert!(!entry.is_change());
@@ -473,6 +771,8 @@ mod tests {
             object_repr: "Test Article".to_string(),
             action_flag: ActionFlag::Addition,
             change_message: String::new(),
+            audit: None,
+            chain: None,
         };
         assert_eq!(entry.description(), "Addition: Test Article");
     }
@@ -488,6 +788,8 @@ mod tests {
             object_repr: "Test Article".to_string(),
             action_flag: ActionFlag::Change,
             change_message: "Changed title, body".to_string(),
+            audit: None,
+            chain: None,
         };
         assert_eq!(
             entry.description(),
@@ -506,6 +808,8 @@ mod tests {
             object_repr: "Test Article".to_string(),
             action_flag: ActionFlag::Addition,
             change_message: String::new(),
+            audit: None,
+            chain: None,
         };
         let display = format!("{entry}");
         assert!(display.contains("Addition"));
@@ -525,6 +829,8 @@ mod tests {
             object_repr: "Test".to_string(),
             action_flag: ActionFlag::Addition,
             change_message: "Created".to_string(),
+            audit: None,
+            chain: None,
         };
         let json = serde_json::to_string(&entry).unwrap();
         assert!(json.contains("\"content_type\":\"blog.article\""));
@@ -557,9 +863,20 @@ mod tests {
         assert_eq!(entry.object_repr, "My Article");
         assert_eq!(entry.action_flag, ActionFlag::Addition);
         assert_eq!(entry.change_message, "Created via admin");
+        assert!(entry.audit.is_none());
         assert_eq!(store.count(), 1);
     }
 
+    #[test]
+    fn test_store_records_audit_context() {
+        let store = InMemoryLogEntryStore::new();
+        let context = AuditContext::new().user_id("1").request_id("req-3");
+        let entry = context
+            .clone()
+            .sync_scope(|| store.log_change(1, "blog.article", "42", "My Article", ""));
+        assert_eq!(entry.audit, Some(context));
+    }
+
     #[test]
     fn test_store_log_change() {
         let store = InMemoryLogEntryStore::new();
@@ -769,9 +1086,168 @@ mod tests {
             object_repr: "Test".to_string(),
             action_flag: ActionFlag::Addition,
             change_message: String::new(),
+            audit: None,
+            chain: None,
         };
         let debug = format!("{entry:?}");
         assert!(debug.contains("LogEntry"));
         assert!(debug.contains("Addition"));
     }
+
+    #[test]
+    fn test_store_entries_after() {
+        let store = InMemoryLogEntryStore::new();
+        for i in 0..5 {
+            store.log_addition(i % 2, "blog.article", &i.to_string(), "Article", "");
+        }
+        store.log_addition(0, "blog.comment", "1", "Comment", "");
+
+        let all = store.entries_after(&LogEntryFilter::default(), 0, 100);
+        assert_eq!(
+            all.iter().map(|e| e.id).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6]
+        );
+
+        let page = store.entries_after(&LogEntryFilter::default(), 2, 2);
+        assert_eq!(page.iter().map(|e| e.id).collect::<Vec<_>>(), [3, 4]);
+
+        let filter = LogEntryFilter {
+            user_id: Some(0),
+            content_type: Some("blog.article".to_string()),
+            ..LogEntryFilter::default()
+        };
+        let matched = store.entries_after(&filter, 0, 100);
+        assert_eq!(matched.iter().map(|e| e.id).collect::<Vec<_>>(), [1, 3, 5]);
+    }
+
+    #[test]
+    fn test_filter_date_range() {
+        let store = InMemoryLogEntryStore::new();
+        let entry = store.log_addition(1, "blog.article", "1", "Article", "");
+        let at = entry.action_time;
+        let range = |since, until| LogEntryFilter {
+            since,
+            until,
+            ..LogEntryFilter::default()
+        };
+        assert!(range(Some(at), None).matches(&entry));
+        assert!(!range(None, Some(at)).matches(&entry));
+        assert!(range(None, Some(at + chrono::Duration::seconds(1))).matches(&entry));
+        assert!(!range(Some(at + chrono::Duration::seconds(1)), None).matches(&entry));
+    }
+
+    #[test]
+    fn test_prune_by_count() {
+        let store = InMemoryLogEntryStore::new();
+        for i in 0..5 {
+            store.log_addition(1, "blog.article", &i.to_string(), "Article", "");
+        }
+        let pruned = store.prune(&RetentionPolicy::new().max_entries(2), Utc::now());
+        assert_eq!(pruned, 3);
+        let ids: Vec<u64> = store.recent(10).iter().map(|e| e.id).collect();
+        assert_eq!(ids, [5, 4]);
+        assert_eq!(store.prune(&RetentionPolicy::new(), Utc::now()), 0);
+    }
+
+    #[test]
+    fn test_prune_by_age() {
+        let store = InMemoryLogEntryStore::new();
+        for i in 0..4 {
+            store.log_addition(1, "blog.article", &i.to_string(), "Article", "");
+        }
+        let now = Utc::now();
+        {
+            let mut entries = store.entries.write().unwrap();
+            entries[0].action_time = now - chrono::Duration::days(40);
+            entries[1].action_time = now - chrono::Duration::days(31);
+        }
+        let policy = RetentionPolicy::new().max_age(chrono::Duration::days(30));
+        assert_eq!(store.prune(&policy, now), 2);
+        assert_eq!(store.count(), 2);
+
+        // The stricter of the two limits applies
+        let policy = policy.max_entries(1);
+        assert_eq!(store.prune(&policy, now), 1);
+        assert_eq!(store.recent(1)[0].id, 4);
+    }
+
+    #[test]
+    fn test_hash_chain() {
+        let store = InMemoryLogEntryStore::with_hash_chain(CHAIN_KEY);
+        let first = store.log_addition(1, "blog.article", "1", "Article", "");
+        let second = store.log_change(1, "blog.article", "1", "Article", "Changed title");
+        let first_link = first.chain.as_ref().unwrap();
+        assert_eq!(first_link.prev_hash, GENESIS_HASH);
+        assert_eq!(second.chain.as_ref().unwrap().prev_hash, first_link.hash);
+
+        let entries = store.entries_after(&LogEntryFilter::default(), 0, 10);
+        assert_eq!(verify_chain(&entries, CHAIN_KEY), Ok(()));
+        // Nor does a chain checked, or forged, with another key
+        assert_eq!(verify_chain(&entries, b"other-key"), Err(1));
+
+        // Entries from a store without a chain do not verify
+        let plain = InMemoryLogEntryStore::new();
+        let entry = plain.log_addition(1, "blog.article", "1", "Article", "");
+        assert_eq!(verify_chain(&[entry], CHAIN_KEY), Err(1));
+    }
+
+    #[test]
+    fn test_hash_chain_detects_tampering() {
+        let store = InMemoryLogEntryStore::with_hash_chain(CHAIN_KEY);
+        for i in 0..4 {
+            store.log_addition(1, "blog.article", &i.to_string(), "Article", "");
+        }
+        let entries = store.entries_after(&LogEntryFilter::default(), 0, 10);
+
+        let mut altered = entries.clone();
+        altered[1].change_message = "Nothing to see here".to_string();
+        assert_eq!(verify_chain(&altered, CHAIN_KEY), Err(2));
+
+        let mut removed = entries;
+        removed.remove(2);
+        assert_eq!(verify_chain(&removed, CHAIN_KEY), Err(4));
+
+        // Pruning the oldest entries leaves a verifiable chain
+        store.prune(&RetentionPolicy::new().max_entries(2), Utc::now());
+        let remaining = store.entries_after(&LogEntryFilter::default(), 0, 10);
+        assert_eq!(verify_chain(&remaining, CHAIN_KEY), Ok(()));
+        let next = store.log_deletion(1, "blog.article", "9", "Article", "");
+        assert_eq!(
+            next.chain.unwrap().prev_hash,
+            remaining[1].chain.as_ref().unwrap().hash
+        );
+    }
+
+    #[test]
+    fn test_chain_serialization() {
+        let store = InMemoryLogEntryStore::with_hash_chain(CHAIN_KEY);
+        let entry = store.log_addition(1, "blog.article", "1", "Article", "");
+        let json = serde_json::to_string(&entry).unwrap();
+        let restored: LogEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_chain(&[restored], CHAIN_KEY), Ok(()));
+
+        let plain = InMemoryLogEntryStore::new().log_addition(1, "blog.article", "1", "A", "");
+        assert!(!serde_json::to_string(&plain).unwrap().contains("chain"));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_retention_job() {
+        let store = Arc::new(InMemoryLogEntryStore::new());
+        for i in 0..3 {
+            store.log_addition(1, "blog.article", &i.to_string(), "Article", "");
+        }
+        let job = spawn_retention_job(
+            store.clone(),
+            RetentionPolicy::new().max_entries(1),
+            std::time::Duration::from_secs(3600),
+        );
+        for _ in 0..100 {
+            if store.count() == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        job.abort();
+        assert_eq!(store.count(), 1);
+    }
 }
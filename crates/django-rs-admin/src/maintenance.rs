@@ -0,0 +1,553 @@
+//! Site-wide maintenance mode and read-only switch.
+//!
+//! A [`MaintenanceState`] is kept in a [`MaintenanceStore`] shared by the
+//! admin site and [`MaintenanceModeMiddleware`]. Staff toggle it through the
+//! admin's `/maintenance/` endpoint (or a deploy script writes the store
+//! directly), and every process reading the same store picks it up:
+//!
+//! - **Maintenance mode** answers non-staff requests with `503 Service
+//!   Unavailable` and a `Retry-After` header, while staff keep working.
+//! - **Read-only mode** rejects writes (any method other than `GET`, `HEAD`
+//!   and `OPTIONS`) from everyone, including staff, for the admin only or for
+//!   the whole site. Turn it on while a migration runs.
+//!
+//! [`InMemoryMaintenanceStore`] is the default. [`DatabaseMaintenanceStore`]
+//! persists the state in a `django_admin_maintenance` table so it survives
+//! restarts and is shared between workers.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//! use django_rs_admin::maintenance::{
+//!     InMemoryMaintenanceStore, MaintenanceModeMiddleware, MaintenanceState, MaintenanceStore,
+//! };
+//! use django_rs_admin::site::AdminSite;
+//!
+//! async fn example() {
+//!     let store = Arc::new(InMemoryMaintenanceStore::new());
+//!     let site = AdminSite::new("admin").maintenance_store(store.clone());
+//!     let middleware = MaintenanceModeMiddleware::new(store.clone());
+//!
+//!     store
+//!         .set(&MaintenanceState::enabled("Upgrading the database"))
+//!         .await
+//!         .unwrap();
+//! }
+//! ```
+
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use django_rs_core::DjangoError;
+use django_rs_db::executor::DbExecutor;
+use django_rs_db::query::compiler::DatabaseBackendType;
+use django_rs_db::value::Value;
+use django_rs_http::{HttpRequest, HttpResponse};
+use django_rs_views::middleware::Middleware;
+use django_rs_views::CurrentUser;
+use http::{HeaderValue, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+
+/// The `Retry-After` value, in seconds, when the state doesn't set one.
+pub const DEFAULT_RETRY_AFTER_SECONDS: u64 = 300;
+
+/// Which part of the site rejects writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadOnlyScope {
+    /// Writes are allowed.
+    #[default]
+    Off,
+    /// Only the admin rejects writes.
+    Admin,
+    /// The whole site, admin included, rejects writes.
+    Site,
+}
+
+/// The current maintenance settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceState {
+    /// Whether non-staff users are shut out.
+    pub enabled: bool,
+    /// Which part of the site rejects writes.
+    pub read_only: ReadOnlyScope,
+    /// The message shown to users who are turned away.
+    pub message: Option<String>,
+    /// Seconds clients should wait before retrying.
+    pub retry_after: Option<u64>,
+    /// When the state was last changed through the admin.
+    pub updated_at: Option<DateTime<Utc>>,
+    /// The username of whoever last changed the state through the admin.
+    pub updated_by: Option<String>,
+}
+
+impl MaintenanceState {
+    /// Returns a state with maintenance mode on and the given message.
+    pub fn enabled(message: &str) -> Self {
+        Self {
+            enabled: true,
+            message: Some(message.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Returns a state with writes rejected in `scope`.
+    pub fn read_only(scope: ReadOnlyScope) -> Self {
+        Self {
+            read_only: scope,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the `Retry-After` value in seconds.
+    pub fn retry_after_seconds(&self) -> u64 {
+        self.retry_after.unwrap_or(DEFAULT_RETRY_AFTER_SECONDS)
+    }
+
+    /// Returns `true` if writes are rejected for requests to `path`, given
+    /// the admin's URL prefix.
+    pub fn rejects_writes_to(&self, path: &str, admin_prefix: &str) -> bool {
+        match self.read_only {
+            ReadOnlyScope::Off => false,
+            ReadOnlyScope::Admin => is_under(path, admin_prefix),
+            ReadOnlyScope::Site => true,
+        }
+    }
+
+    /// Builds the 503 response sent to requests that are turned away.
+    pub fn unavailable_response(&self, default_message: &str) -> HttpResponse {
+        let message = self.message.as_deref().unwrap_or(default_message);
+        let mut response = HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, message);
+        response.headers_mut().insert(
+            http::header::RETRY_AFTER,
+            HeaderValue::from(self.retry_after_seconds()),
+        );
+        response
+    }
+}
+
+/// Returns `true` if `path` is `prefix` or lies below it, so `/api` covers
+/// `/api` and `/api/users` but not `/apix`.
+fn is_under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Returns `true` for methods that don't modify data.
+pub const fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Trait for maintenance state storage backends.
+#[async_trait]
+pub trait MaintenanceStore: Send + Sync {
+    /// Returns the current state; the default state if none was saved.
+    async fn get(&self) -> Result<MaintenanceState, String>;
+
+    /// Replaces the current state.
+    async fn set(&self, state: &MaintenanceState) -> Result<(), String>;
+}
+
+/// In-memory implementation of [`MaintenanceStore`].
+///
+/// The state is lost on restart and not shared between processes.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryMaintenanceStore {
+    state: Arc<RwLock<MaintenanceState>>,
+}
+
+impl InMemoryMaintenanceStore {
+    /// Creates a store with maintenance and read-only mode off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MaintenanceStore for InMemoryMaintenanceStore {
+    async fn get(&self) -> Result<MaintenanceState, String> {
+        self.state
+            .read()
+            .map(|state| state.clone())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn set(&self, state: &MaintenanceState) -> Result<(), String> {
+        *self.state.write().map_err(|e| e.to_string())? = state.clone();
+        Ok(())
+    }
+}
+
+/// A [`MaintenanceStore`] backed by a [`DbExecutor`].
+///
+/// Stores the state as JSON in a single row of a `django_admin_maintenance`
+/// table with columns:
+/// - `id INTEGER PRIMARY KEY` (always `1`)
+/// - `state TEXT` (JSON-serialized)
+pub struct DatabaseMaintenanceStore {
+    db: Arc<dyn DbExecutor>,
+}
+
+impl DatabaseMaintenanceStore {
+    /// Creates a store over the given executor.
+    pub fn new(db: Arc<dyn DbExecutor>) -> Self {
+        Self { db }
+    }
+
+    /// Creates the `django_admin_maintenance` table if it does not already
+    /// exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns the database error if the table cannot be created.
+    pub async fn create_table(&self) -> Result<(), DjangoError> {
+        let sql = "CREATE TABLE IF NOT EXISTS django_admin_maintenance (\
+            id INTEGER PRIMARY KEY, \
+            state TEXT NOT NULL\
+        )";
+        self.db.execute_sql(sql, &[]).await.map(|_| ())
+    }
+}
+
+impl std::fmt::Debug for DatabaseMaintenanceStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatabaseMaintenanceStore")
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl MaintenanceStore for DatabaseMaintenanceStore {
+    async fn get(&self) -> Result<MaintenanceState, String> {
+        let rows = self
+            .db
+            .query(
+                "SELECT state FROM django_admin_maintenance WHERE id = 1",
+                &[],
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(row) = rows.first() else {
+            return Ok(MaintenanceState::default());
+        };
+        let json: String = row.get("state").map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+
+    async fn set(&self, state: &MaintenanceState) -> Result<(), String> {
+        let json = serde_json::to_string(state).map_err(|e| e.to_string())?;
+        let sql = match self.db.backend_type() {
+            DatabaseBackendType::MySQL => {
+                "INSERT INTO django_admin_maintenance (id, state) VALUES (1, ?) \
+                 ON DUPLICATE KEY UPDATE state = VALUES(state)"
+            }
+            _ => {
+                "INSERT INTO django_admin_maintenance (id, state) VALUES (1, $1) \
+                 ON CONFLICT(id) DO UPDATE SET state = $1"
+            }
+        };
+        self.db
+            .execute_sql(sql, &[Value::String(json)])
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Decides whether a request comes from a staff user.
+pub type StaffCheck = Arc<dyn Fn(&HttpRequest) -> bool + Send + Sync>;
+
+/// Middleware enforcing the state of a [`MaintenanceStore`].
+///
+/// While maintenance mode is on, requests from non-staff users get a 503
+/// with `Retry-After`. While read-only mode covers a request's path, unsafe
+/// methods get a 503 whoever sends them. The admin's `maintenance/` endpoint
+/// and any exempt paths are always let through, so the switch can be turned
+/// back off.
+///
+/// By default a request is from staff when its [`CurrentUser`] is a
+/// logged-in staff user, as loaded by the authentication middleware; use
+/// [`with_staff_check`](Self::with_staff_check) to decide otherwise.
+///
+/// If the store cannot be read, the middleware fails closed: non-staff
+/// requests get a 503 as if maintenance mode were on. Use
+/// [`with_fail_open`](Self::with_fail_open) to let them through instead.
+#[derive(Clone)]
+pub struct MaintenanceModeMiddleware {
+    store: Arc<dyn MaintenanceStore>,
+    /// The URL prefix the admin is mounted under.
+    pub admin_prefix: String,
+    /// URL path prefixes that maintenance mode never blocks.
+    pub exempt_urls: Vec<String>,
+    /// Whether requests are let through when the store cannot be read.
+    pub fail_open: bool,
+    staff_check: StaffCheck,
+}
+
+impl MaintenanceModeMiddleware {
+    /// Creates the middleware over the given store, with the admin mounted
+    /// under `/api/admin`.
+    pub fn new(store: Arc<dyn MaintenanceStore>) -> Self {
+        Self {
+            store,
+            admin_prefix: "/api/admin".to_string(),
+            exempt_urls: Vec::new(),
+            fail_open: false,
+            staff_check: Arc::new(|request| {
+                request
+                    .extensions()
+                    .get::<CurrentUser>()
+                    .is_some_and(|user| user.is_authenticated() && user.is_staff)
+            }),
+        }
+    }
+
+    /// Sets the URL prefix the admin is mounted under.
+    #[must_use]
+    pub fn with_admin_prefix(mut self, prefix: &str) -> Self {
+        self.admin_prefix = prefix.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds URL path prefixes that maintenance mode never blocks, such as
+    /// health checks and static assets.
+    #[must_use]
+    pub fn with_exempt_urls(mut self, urls: Vec<String>) -> Self {
+        self.exempt_urls = urls;
+        self
+    }
+
+    /// Sets whether requests are let through when the store cannot be read,
+    /// rather than answered with a 503.
+    #[must_use]
+    pub const fn with_fail_open(mut self, fail_open: bool) -> Self {
+        self.fail_open = fail_open;
+        self
+    }
+
+    /// Sets how staff users are recognised.
+    #[must_use]
+    pub fn with_staff_check<F>(mut self, check: F) -> Self
+    where
+        F: Fn(&HttpRequest) -> bool + Send + Sync + 'static,
+    {
+        self.staff_check = Arc::new(check);
+        self
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        is_under(path, &format!("{}/maintenance", self.admin_prefix))
+            || self.exempt_urls.iter().any(|exempt| is_under(path, exempt))
+    }
+}
+
+impl std::fmt::Debug for MaintenanceModeMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaintenanceModeMiddleware")
+            .field("admin_prefix", &self.admin_prefix)
+            .field("exempt_urls", &self.exempt_urls)
+            .field("fail_open", &self.fail_open)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Middleware for MaintenanceModeMiddleware {
+    async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
+        if self.is_exempt(request.path()) {
+            return None;
+        }
+        let state = match self.store.get().await {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::warn!("Cannot read maintenance state: {e}");
+                if self.fail_open || (self.staff_check)(request) {
+                    return None;
+                }
+                return Some(MaintenanceState::default().unavailable_response(
+                    "The site is temporarily unavailable. Please try again later.",
+                ));
+            }
+        };
+
+        if state.enabled && !(self.staff_check)(request) {
+            return Some(state.unavailable_response(
+                "The site is down for maintenance. Please try again later.",
+            ));
+        }
+        if !is_safe_method(request.method())
+            && state.rejects_writes_to(request.path(), &self.admin_prefix)
+        {
+            return Some(state.unavailable_response(
+                "The site is read-only during maintenance. Please try again later.",
+            ));
+        }
+        None
+    }
+
+    async fn process_response(
+        &self,
+        _request: &HttpRequest,
+        response: HttpResponse,
+    ) -> HttpResponse {
+        response
+    }
+
+    async fn process_exception(
+        &self,
+        _request: &HttpRequest,
+        _error: &DjangoError,
+    ) -> Option<HttpResponse> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, path: &str, staff: bool) -> HttpRequest {
+        HttpRequest::builder()
+            .method(method)
+            .path(path)
+            .extension(CurrentUser::authenticated("alice").with_staff(staff))
+            .build()
+    }
+
+    #[test]
+    fn test_rejects_writes_to() {
+        let state = MaintenanceState::read_only(ReadOnlyScope::Admin);
+        assert!(state.rejects_writes_to("/api/admin/blog/post/", "/api/admin"));
+        assert!(!state.rejects_writes_to("/blog/comment/", "/api/admin"));
+        assert!(state.rejects_writes_to("/api/admin", "/api/admin/"));
+        assert!(!state.rejects_writes_to("/api/administrators/", "/api/admin"));
+        let state = MaintenanceState::read_only(ReadOnlyScope::Site);
+        assert!(state.rejects_writes_to("/blog/comment/", "/api/admin"));
+        assert!(!MaintenanceState::default().rejects_writes_to("/", "/api/admin"));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_maintenance_mode() {
+        let store = Arc::new(InMemoryMaintenanceStore::new());
+        let mw = MaintenanceModeMiddleware::new(store.clone())
+            .with_exempt_urls(vec!["/health/".to_string()]);
+        assert!(mw
+            .process_request(&mut request(Method::GET, "/", false))
+            .await
+            .is_none());
+
+        let mut state = MaintenanceState::enabled("Back soon");
+        state.retry_after = Some(60);
+        store.set(&state).await.unwrap();
+
+        let response = mw
+            .process_request(&mut request(Method::GET, "/", false))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[http::header::RETRY_AFTER], "60");
+        assert_eq!(response.content_bytes().unwrap(), b"Back soon");
+
+        assert!(mw
+            .process_request(&mut request(Method::POST, "/", true))
+            .await
+            .is_none());
+        let mut anonymous = HttpRequest::builder()
+            .path("/")
+            .meta("USER_IS_STAFF", "true")
+            .extension(CurrentUser::anonymous().with_staff(true))
+            .build();
+        assert!(mw.process_request(&mut anonymous).await.is_some());
+        assert!(mw
+            .process_request(&mut request(Method::GET, "/health/", false))
+            .await
+            .is_none());
+        assert!(mw
+            .process_request(&mut request(Method::GET, "/healthz/", false))
+            .await
+            .is_some());
+        assert!(mw
+            .process_request(&mut request(Method::PUT, "/api/admin/maintenance/", false))
+            .await
+            .is_none());
+    }
+
+    struct BrokenStore;
+
+    #[async_trait]
+    impl MaintenanceStore for BrokenStore {
+        async fn get(&self) -> Result<MaintenanceState, String> {
+            Err("connection refused".to_string())
+        }
+
+        async fn set(&self, _state: &MaintenanceState) -> Result<(), String> {
+            Err("connection refused".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_unreadable_store() {
+        let mw = MaintenanceModeMiddleware::new(Arc::new(BrokenStore));
+        let response = mw
+            .process_request(&mut request(Method::GET, "/", false))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(mw
+            .process_request(&mut request(Method::GET, "/", true))
+            .await
+            .is_none());
+        assert!(mw
+            .process_request(&mut request(Method::PUT, "/api/admin/maintenance/", false))
+            .await
+            .is_none());
+
+        let mw = mw.with_fail_open(true);
+        assert!(mw
+            .process_request(&mut request(Method::GET, "/", false))
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_read_only_applies_to_staff() {
+        let store = Arc::new(InMemoryMaintenanceStore::new());
+        store
+            .set(&MaintenanceState::read_only(ReadOnlyScope::Site))
+            .await
+            .unwrap();
+        let mw = MaintenanceModeMiddleware::new(store);
+
+        assert!(mw
+            .process_request(&mut request(Method::GET, "/blog/", false))
+            .await
+            .is_none());
+        let response = mw
+            .process_request(&mut request(Method::POST, "/blog/", true))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers()[http::header::RETRY_AFTER],
+            DEFAULT_RETRY_AFTER_SECONDS.to_string().as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_database_store_round_trip() {
+        let db: Arc<dyn DbExecutor> =
+            Arc::new(django_rs_db_backends::SqliteBackend::memory().unwrap());
+        let store = DatabaseMaintenanceStore::new(db);
+        store.create_table().await.unwrap();
+        assert_eq!(store.get().await.unwrap(), MaintenanceState::default());
+
+        let state = MaintenanceState::enabled("Upgrading");
+        store.set(&state).await.unwrap();
+        store.set(&state).await.unwrap();
+        assert_eq!(store.get().await.unwrap(), state);
+    }
+}
@@ -6,8 +6,20 @@
 
 use std::collections::HashMap;
 
+use django_rs_core::i18n::formats;
+use django_rs_db::encryption;
+use django_rs_db::fields::{FieldDef, FieldType};
+use django_rs_db::value::Value;
+use django_rs_forms::conditions::{self, VisibilityRule};
+use django_rs_template::thumbnails::ThumbnailSpec;
 use serde::{Deserialize, Serialize};
 
+use crate::publishing::{PublishStatus, ScheduledPublishing, PUBLISH_NOW_ACTION, STATUS_FIELD};
+
+/// The placeholder the admin shows instead of the ciphertext of an
+/// encrypted field.
+pub const ENCRYPTED_MASK: &str = "********";
+
 /// Configuration for how a model is displayed and managed in the admin panel.
 ///
 /// Mirrors Django's `ModelAdmin` class. Each registered model gets a `ModelAdmin`
@@ -70,6 +82,37 @@ pub struct ModelAdmin {
     pub prepopulated_fields: HashMap<String, Vec<String>>,
     /// Schema information about model fields (for React frontend introspection).
     pub fields_schema: Vec<FieldSchema>,
+    /// Explicit column metadata overriding what is derived from `fields_schema`.
+    pub column_overrides: Vec<ListColumn>,
+    /// Aggregates over related objects added to each list result, which
+    /// columns can display and sort by.
+    pub annotations: Vec<ListAnnotation>,
+    /// Fields accepted by the quick-create endpoint used by "add related" modals.
+    pub quick_create_fields: Vec<String>,
+    /// Template used by the print endpoint; the built-in layout if `None`.
+    pub print_template: Option<String>,
+    /// Thumbnail variants whose URLs are listed for image fields in detail
+    /// responses.
+    #[serde(skip)]
+    pub image_variants: Vec<ThumbnailSpec>,
+    /// Self-referencing parent field of a tree model; lists are then shown
+    /// as an indented tree.
+    pub tree_parent_field: Option<String>,
+    /// Many-to-many fields edited with the two-panel "available / chosen"
+    /// selector.
+    pub filter_horizontal: Vec<String>,
+    /// Rules showing a field only while a condition on the other values
+    /// holds.
+    pub visibility_rules: Vec<VisibilityRule>,
+    /// Datetime fields driving scheduled publishing.
+    pub scheduled_publishing: Option<ScheduledPublishing>,
+    /// Whether objects have a comment thread on their detail page.
+    pub comments_enabled: bool,
+    /// Whether the sidebar shows the model's object count.
+    pub show_count: bool,
+    /// Unique field identifying objects in admin URLs instead of the primary
+    /// key.
+    pub lookup_field: Option<String>,
 }
 
 impl ModelAdmin {
@@ -101,6 +144,18 @@ impl ModelAdmin {
             date_hierarchy: None,
             prepopulated_fields: HashMap::new(),
             fields_schema: Vec::new(),
+            column_overrides: Vec::new(),
+            annotations: Vec::new(),
+            quick_create_fields: Vec::new(),
+            print_template: None,
+            image_variants: Vec::new(),
+            tree_parent_field: None,
+            filter_horizontal: Vec::new(),
+            visibility_rules: Vec::new(),
+            scheduled_publishing: None,
+            comments_enabled: false,
+            show_count: true,
+            lookup_field: None,
         }
     }
 
@@ -227,6 +282,11 @@ impl ModelAdmin {
     }
 
     /// Sets prepopulated fields mapping.
+    ///
+    /// Maps each slug field to the fields it is built from. The frontend
+    /// fills the slug in as the sources are typed; on create, a slug left
+    /// blank is generated on the server and made unique. See
+    /// [`prepopulated_slugs`](Self::prepopulated_slugs).
     #[must_use]
     pub fn prepopulated_fields(mut self, fields: HashMap<String, Vec<String>>) -> Self {
         self.prepopulated_fields = fields;
@@ -240,10 +300,676 @@ impl ModelAdmin {
         self
     }
 
+    /// Overrides the column metadata for one `list_display` entry.
+    ///
+    /// Use this for computed columns (e.g. `"__str__"` or a display method)
+    /// to declare how they sort, via [`ListColumn::sort_field`], the way
+    /// Django's `admin_order_field` does.
+    #[must_use]
+    pub fn list_column(mut self, column: ListColumn) -> Self {
+        self.column_overrides.retain(|c| c.name != column.name);
+        self.column_overrides.push(column);
+        self
+    }
+
+    /// Adds an aggregate to each list result, replacing any annotation with
+    /// the same name.
+    ///
+    /// A `list_display` entry naming the annotation shows it as a sortable
+    /// column.
+    #[must_use]
+    pub fn annotate(mut self, annotation: ListAnnotation) -> Self {
+        self.annotations.retain(|a| a.name != annotation.name);
+        self.annotations.push(annotation);
+        self
+    }
+
+    /// Enables quick create, accepting only the given fields.
+    ///
+    /// The frontend uses this for the inline "add related object" modal on
+    /// foreign key fields that point at this model. Required fields the
+    /// object can't be saved without are added to the form; see
+    /// [`quick_create_form_fields`](Self::quick_create_form_fields).
+    #[must_use]
+    pub fn quick_create_fields(mut self, fields: Vec<&str>) -> Self {
+        self.quick_create_fields = fields.into_iter().map(String::from).collect();
+        self
+    }
+
+    /// Sets the template the print endpoint renders objects with, e.g. an
+    /// invoice layout. The template receives `object`, `fields` and `opts`.
+    #[must_use]
+    pub fn print_template(mut self, name: impl Into<String>) -> Self {
+        self.print_template = Some(name.into());
+        self
+    }
+
+    /// Sets the thumbnail variants exposed for `ImageField`s.
+    ///
+    /// Detail responses then include a `_variants` object mapping each image
+    /// field to its variant URLs, keyed by size, e.g. `"200x200_crop"`.
+    /// Requires a thumbnail backend on the [`AdminSite`](crate::site::AdminSite).
+    #[must_use]
+    pub fn image_variants(mut self, variants: Vec<ThumbnailSpec>) -> Self {
+        self.image_variants = variants;
+        self
+    }
+
+    /// Marks the model as a tree whose nodes point to their parent through
+    /// `field`.
+    ///
+    /// Unless another ordering is requested, list results are then returned
+    /// depth-first, siblings sorted by the default ordering, and each carries
+    /// a `_depth` for indentation.
+    #[must_use]
+    pub fn tree_parent_field(mut self, field: impl Into<String>) -> Self {
+        self.tree_parent_field = Some(field.into());
+        self
+    }
+
+    /// Edits the given many-to-many fields with the two-panel selector.
+    ///
+    /// The frontend then fetches the available and chosen objects of each
+    /// field page by page, and saves the whole selection at once.
+    #[must_use]
+    pub fn filter_horizontal(mut self, fields: Vec<&str>) -> Self {
+        self.filter_horizontal = fields.into_iter().map(String::from).collect();
+        self
+    }
+
+    /// Returns the related model key of a `filter_horizontal` field, or
+    /// `None` if `field` is not one or is not a many-to-many relation.
+    pub fn filter_horizontal_target(&self, field: &str) -> Option<&str> {
+        if !self.filter_horizontal.iter().any(|f| f == field) {
+            return None;
+        }
+        self.fields_schema
+            .iter()
+            .find(|f| f.name == field && f.field_type == "ManyToManyField")
+            .and_then(|f| f.related_model.as_deref())
+    }
+
+    /// Shows fields only while their rule's condition holds.
+    ///
+    /// The rules are delivered with the schema so the frontend can toggle
+    /// fields, and enforced on create and update by
+    /// [`apply_visibility_rules`](Self::apply_visibility_rules).
+    #[must_use]
+    pub fn visibility_rules(mut self, rules: Vec<VisibilityRule>) -> Self {
+        self.visibility_rules = rules;
+        self
+    }
+
+    /// Enforces the visibility rules on submitted `data`.
+    ///
+    /// Conditions are evaluated against `data`, falling back to the stored
+    /// `current` object on update. Values submitted for hidden fields are
+    /// ignored; on update, a hidden field that is stored is cleared to its
+    /// schema default, or `null`. Returns the hidden fields, sorted.
+    pub fn apply_visibility_rules(
+        &self,
+        data: &mut HashMap<String, serde_json::Value>,
+        current: Option<&serde_json::Value>,
+    ) -> Vec<String> {
+        let mut hidden: Vec<String> = conditions::hidden_fields(&self.visibility_rules, &|name| {
+            data.get(name)
+                .or_else(|| current.and_then(|obj| obj.get(name)))
+                .cloned()
+        })
+        .into_iter()
+        .collect();
+        hidden.sort();
+
+        for name in &hidden {
+            data.remove(name);
+            if current.and_then(|obj| obj.get(name)).is_some() {
+                let cleared = self
+                    .fields_schema
+                    .iter()
+                    .find(|f| &f.name == name)
+                    .and_then(|f| f.default.clone())
+                    .unwrap_or(serde_json::Value::Null);
+                data.insert(name.clone(), cleared);
+            }
+        }
+        hidden
+    }
+
+    /// Rewrites localized number, date and time strings in submitted `data`
+    /// to their canonical forms, using the input formats of `language`.
+    ///
+    /// Integer and float fields become JSON numbers, decimal fields become
+    /// strings such as `"1234.50"`, and date, datetime and time fields
+    /// become ISO 8601 strings, so `"1.234,5"` submitted in German is stored
+    /// as `1234.5`. Values that are not strings, blank strings and fields
+    /// of other types are left as they are. Returns the fields whose values
+    /// could not be read, mapped to an error message.
+    ///
+    /// Only call this when the client has said which language it submits
+    /// in: canonical input such as `"1.250"` reads differently in languages
+    /// that group thousands with `.`.
+    pub fn normalize_localized_input(
+        &self,
+        data: &mut HashMap<String, serde_json::Value>,
+        language: &str,
+    ) -> Result<(), HashMap<String, String>> {
+        let mut errors = HashMap::new();
+        for field in &self.fields_schema {
+            let Some(serde_json::Value::String(raw)) = data.get(&field.name) else {
+                continue;
+            };
+            if raw.trim().is_empty() {
+                continue;
+            }
+            let normalized = match ColumnDataType::from_field_type(&field.field_type) {
+                ColumnDataType::Integer => formats::parse_number(raw, language)
+                    .and_then(|n| {
+                        let (integer, fraction) = n.split_once('.').unwrap_or((&n, ""));
+                        fraction
+                            .bytes()
+                            .all(|b| b == b'0')
+                            .then(|| integer.parse::<i64>().ok())
+                            .flatten()
+                    })
+                    .map(serde_json::Value::from)
+                    .ok_or("Enter a whole number."),
+                ColumnDataType::Decimal if field.field_type == "DecimalField" => {
+                    formats::parse_number(raw, language)
+                        .map(serde_json::Value::String)
+                        .ok_or("Enter a number.")
+                }
+                ColumnDataType::Decimal => formats::parse_number(raw, language)
+                    .and_then(|n| n.parse::<f64>().ok())
+                    .map(serde_json::Value::from)
+                    .ok_or("Enter a number."),
+                ColumnDataType::Date => formats::parse_date(raw, language)
+                    .map(|date| serde_json::Value::String(date.format("%Y-%m-%d").to_string()))
+                    .ok_or("Enter a valid date."),
+                ColumnDataType::DateTime => chrono::DateTime::parse_from_rfc3339(raw.trim())
+                    .map(|dt| dt.to_rfc3339())
+                    .ok()
+                    .or_else(|| {
+                        formats::parse_datetime(raw, language)
+                            .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+                    })
+                    .map(serde_json::Value::String)
+                    .ok_or("Enter a valid date/time."),
+                ColumnDataType::Time => formats::parse_time(raw, language)
+                    .map(|time| serde_json::Value::String(time.format("%H:%M:%S%.f").to_string()))
+                    .ok_or("Enter a valid time."),
+                _ => continue,
+            };
+            match normalized {
+                Ok(value) => {
+                    data.insert(field.name.clone(), value);
+                }
+                Err(message) => {
+                    errors.insert(field.name.clone(), message.to_string());
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns the names of the encrypted fields in the schema.
+    fn encrypted_fields(&self) -> impl Iterator<Item = &str> {
+        self.fields_schema
+            .iter()
+            .filter(|f| {
+                matches!(
+                    f.field_type.as_str(),
+                    "EncryptedCharField" | "EncryptedTextField"
+                )
+            })
+            .map(|f| f.name.as_str())
+    }
+
+    /// Replaces the ciphertext stored in encrypted fields of `obj` with
+    /// [`ENCRYPTED_MASK`], so raw ciphertext never reaches the admin.
+    /// Values a data source has already decrypted are left as they are.
+    pub fn mask_encrypted_values(&self, obj: &mut serde_json::Value) {
+        let Some(map) = obj.as_object_mut() else {
+            return;
+        };
+        for name in self.encrypted_fields() {
+            if let Some(value) = map.get_mut(name) {
+                if value.as_str().is_some_and(encryption::is_encrypted) {
+                    *value = serde_json::Value::String(ENCRYPTED_MASK.to_string());
+                }
+            }
+        }
+    }
+
+    /// Drops encrypted fields submitted with the value [`ENCRYPTED_MASK`],
+    /// so saving a form that showed the mask keeps the stored value.
+    pub fn drop_masked_input(&self, data: &mut HashMap<String, serde_json::Value>) {
+        for name in self.encrypted_fields() {
+            if data.get(name).and_then(serde_json::Value::as_str) == Some(ENCRYPTED_MASK) {
+                data.remove(name);
+            }
+        }
+    }
+
+    /// Enables scheduled publishing: the object goes live at the time in
+    /// `publish_at` and, if given, is withdrawn at the time in `unpublish_at`.
+    ///
+    /// List results then carry a computed `publish_status` (`draft`,
+    /// `scheduled`, `live` or `expired`), which can be shown by adding it to
+    /// `list_display`. A `publish_status` list filter and the `publish_now`
+    /// bulk action are added. See [`publishing`](crate::publishing).
+    #[must_use]
+    pub fn scheduled_publishing(mut self, publish_at: &str, unpublish_at: Option<&str>) -> Self {
+        self.scheduled_publishing = Some(ScheduledPublishing {
+            publish_at: publish_at.to_string(),
+            unpublish_at: unpublish_at.map(String::from),
+        });
+        let has_filter = self
+            .list_filter
+            .iter()
+            .any(|f| matches!(f, ListFilter::Custom { name, .. } if name == STATUS_FIELD));
+        if !has_filter {
+            self.list_filter.push(ListFilter::Custom {
+                name: STATUS_FIELD.to_string(),
+                choices: PublishStatus::filter_choices(),
+            });
+        }
+        if !self.action_names.iter().any(|a| a == PUBLISH_NOW_ACTION) {
+            self.action_names.push(PUBLISH_NOW_ACTION.to_string());
+        }
+        self
+    }
+
+    /// Enables the comment thread on detail pages.
+    ///
+    /// Staff can then list, post and delete comments on each object, and
+    /// `@name` mentions notify the mentioned users. See
+    /// [`comments`](crate::comments).
+    #[must_use]
+    pub const fn comments(mut self, enabled: bool) -> Self {
+        self.comments_enabled = enabled;
+        self
+    }
+
+    /// Sets whether the sidebar shows the model's object count. Defaults to
+    /// `true`.
+    ///
+    /// Turn it off for tables too large to count quickly. See
+    /// [`model_counts`](crate::model_counts).
+    #[must_use]
+    pub const fn show_count(mut self, enabled: bool) -> Self {
+        self.show_count = enabled;
+        self
+    }
+
+    /// Sets the field identifying objects in admin URLs, such as a slug or
+    /// UUID field.
+    ///
+    /// The detail, update and delete endpoints, and the other endpoints
+    /// under an object's URL, then look objects up by this field; the field
+    /// must be unique (see [`check_lookup_field`](Self::check_lookup_field)).
+    #[must_use]
+    pub fn lookup_field(mut self, field: impl Into<String>) -> Self {
+        self.lookup_field = Some(field.into());
+        self
+    }
+
+    /// Returns the field identifying objects in admin URLs: the
+    /// [`lookup_field`](Self::lookup_field) if set, else the primary key.
+    pub fn lookup_field_name(&self) -> &str {
+        self.lookup_field
+            .as_deref()
+            .unwrap_or_else(|| self.pk_field_name())
+    }
+
+    /// Checks that the [`lookup_field`](Self::lookup_field) is a unique field
+    /// of the model.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the field is not in the field schema, or is
+    /// neither unique nor the primary key.
+    pub fn check_lookup_field(&self) -> Result<(), String> {
+        let Some(name) = &self.lookup_field else {
+            return Ok(());
+        };
+        let key = self.model_key();
+        match self.fields_schema.iter().find(|f| &f.name == name) {
+            Some(field) if field.unique || field.primary_key => Ok(()),
+            Some(_) => Err(format!("Lookup field '{name}' of '{key}' is not unique")),
+            None => Err(format!("Lookup field '{name}' is not a field of '{key}'")),
+        }
+    }
+
     /// Returns the model key in `"app_label.model_name"` format.
     pub fn model_key(&self) -> String {
         format!("{}.{}", self.app_label, self.model_name)
     }
+
+    /// Fills in the value types of the annotations over `related_model`
+    /// from `related`'s schema, returning whether any changed.
+    pub(crate) fn resolve_annotation_types(&mut self, related_model: &str, related: &Self) -> bool {
+        let mut changed = false;
+        for annotation in &mut self.annotations {
+            if annotation.related_model != related_model {
+                continue;
+            }
+            let value_type = annotation.value_field.as_ref().and_then(|value_field| {
+                related
+                    .fields_schema
+                    .iter()
+                    .find(|f| &f.name == value_field)
+                    .map(|f| ColumnDataType::from_field_type(&f.field_type))
+            });
+            if annotation.value_type != value_type {
+                annotation.value_type = value_type;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Returns the name of the primary key field, defaulting to `"id"`.
+    pub fn pk_field_name(&self) -> &str {
+        self.fields_schema
+            .iter()
+            .find(|f| f.primary_key)
+            .map_or("id", |f| f.name.as_str())
+    }
+
+    /// Returns a human-readable label for an object.
+    ///
+    /// Uses the object's `title` or `name` when present, falling back to
+    /// Django's default `"<verbose name> object (<pk>)"`.
+    pub fn object_label(&self, obj: &serde_json::Value) -> String {
+        if let Some(label) = obj
+            .get("title")
+            .or_else(|| obj.get("name"))
+            .and_then(|v| v.as_str())
+        {
+            return label.to_string();
+        }
+        let pk = match obj.get(self.pk_field_name()) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        format!("{} object ({pk})", self.verbose_name)
+    }
+
+    /// Returns the generated slug for each prepopulated field that is blank
+    /// in `data`.
+    ///
+    /// The source fields are joined with spaces, slugified with
+    /// [`slugify`](django_rs_core::utils::text::slugify) and cut to the
+    /// field's `max_length`. Fields whose sources are all blank are left
+    /// out. The slugs are not yet unique; see [`numbered_slug`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use django_rs_admin::model_admin::ModelAdmin;
+    ///
+    /// let admin = ModelAdmin::new("blog", "article").prepopulated_fields(HashMap::from([(
+    ///     "slug".to_string(),
+    ///     vec!["title".to_string()],
+    /// )]));
+    /// let data = HashMap::from([("title".to_string(), serde_json::json!("Hello, World!"))]);
+    /// assert_eq!(
+    ///     admin.prepopulated_slugs(&data),
+    ///     vec![("slug".to_string(), "hello-world".to_string())]
+    /// );
+    /// ```
+    pub fn prepopulated_slugs(
+        &self,
+        data: &HashMap<String, serde_json::Value>,
+    ) -> Vec<(String, String)> {
+        let text = |value: Option<&serde_json::Value>| match value {
+            Some(serde_json::Value::String(s)) => s.trim().to_string(),
+            Some(serde_json::Value::Number(n)) => n.to_string(),
+            _ => String::new(),
+        };
+        let mut slugs: Vec<(String, String)> = self
+            .prepopulated_fields
+            .iter()
+            .filter(|(field, _)| text(data.get(*field)).is_empty())
+            .filter_map(|(field, sources)| {
+                let source: Vec<String> = sources
+                    .iter()
+                    .map(|source| text(data.get(source)))
+                    .filter(|value| !value.is_empty())
+                    .collect();
+                let slug = django_rs_core::utils::text::slugify(&source.join(" "));
+                let slug = numbered_slug(&slug, 1, self.max_length(field));
+                (!slug.is_empty()).then(|| (field.clone(), slug))
+            })
+            .collect();
+        slugs.sort();
+        slugs
+    }
+
+    /// Returns the `max_length` of a field, from `fields_schema`.
+    pub fn max_length(&self, field: &str) -> Option<usize> {
+        self.fields_schema
+            .iter()
+            .find(|f| f.name == field)
+            .and_then(|f| f.max_length)
+    }
+
+    /// Returns the fields of the quick-create form: `quick_create_fields`,
+    /// followed by every other required field that has no default and isn't
+    /// prepopulated, so that the created object can be saved.
+    ///
+    /// Empty when quick create is disabled.
+    pub fn quick_create_form_fields(&self) -> Vec<String> {
+        if self.quick_create_fields.is_empty() {
+            return Vec::new();
+        }
+        let mut fields = self.quick_create_fields.clone();
+        for field in &self.fields_schema {
+            if field.required
+                && !field.primary_key
+                && field.default.is_none()
+                && !self.prepopulated_fields.contains_key(&field.name)
+                && !fields.contains(&field.name)
+            {
+                fields.push(field.name.clone());
+            }
+        }
+        fields
+    }
+
+    /// Validates a quick-create payload and fills in defaults.
+    ///
+    /// Only the [`quick_create_form_fields`](Self::quick_create_form_fields)
+    /// are accepted; required ones must be present unless their schema
+    /// declares a default. Every other field with a schema default is filled
+    /// in so the object can be saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if quick create is disabled for this model,
+    /// the payload contains a field outside the form, or a required field is
+    /// missing.
+    pub fn quick_create_data(
+        &self,
+        payload: &HashMap<String, serde_json::Value>,
+    ) -> Result<HashMap<String, serde_json::Value>, String> {
+        if self.quick_create_fields.is_empty() {
+            return Err(format!(
+                "Quick create is not enabled for '{}'",
+                self.model_key()
+            ));
+        }
+
+        let form_fields = self.quick_create_form_fields();
+        if let Some(field) = payload.keys().find(|k| !form_fields.contains(k)) {
+            return Err(format!("Field '{field}' is not accepted by quick create"));
+        }
+
+        let mut data = payload.clone();
+        for field in &self.fields_schema {
+            if field.primary_key || data.contains_key(&field.name) {
+                continue;
+            }
+            if let Some(default) = &field.default {
+                data.insert(field.name.clone(), default.clone());
+            } else if field.required && form_fields.contains(&field.name) {
+                return Err(format!("Field '{}' is required", field.name));
+            }
+        }
+        Ok(data)
+    }
+
+    /// Returns the column metadata for every entry in `list_display`.
+    ///
+    /// Columns without an explicit override are derived from `fields_schema`:
+    /// a column is sortable when it maps to a real model field, its data type
+    /// and alignment follow the field type, relation columns link to the
+    /// related object, and the `list_display_links` columns (or the first
+    /// column, when none are set) link to the object's detail view. Without
+    /// a `fields_schema`, every entry other than `__str__` is taken to be a
+    /// model field and sorts by itself.
+    pub fn list_columns(&self) -> Vec<ListColumn> {
+        self.list_display
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                if let Some(column) = self.column_overrides.iter().find(|c| &c.name == name) {
+                    return column.clone();
+                }
+                if name == STATUS_FIELD && self.scheduled_publishing.is_some() {
+                    return ListColumn::new(STATUS_FIELD)
+                        .label("status")
+                        .data_type(ColumnDataType::Choice);
+                }
+                if let Some(annotation) = self.annotations.iter().find(|a| &a.name == name) {
+                    return ListColumn::new(name.as_str())
+                        .sort_field(name.as_str())
+                        .data_type(annotation.function.data_type(annotation.value_type));
+                }
+
+                let mut column = self
+                    .fields_schema
+                    .iter()
+                    .find(|f| &f.name == name)
+                    .map_or_else(
+                        || {
+                            let column = ListColumn::new(name.as_str());
+                            if self.fields_schema.is_empty() && name != "__str__" {
+                                column.sort_field(name.as_str())
+                            } else {
+                                column
+                            }
+                        },
+                        ListColumn::from_field,
+                    );
+
+                let links_to_detail = if self.list_display_links.is_empty() {
+                    index == 0
+                } else {
+                    self.list_display_links.contains(name)
+                };
+                if links_to_detail {
+                    column.link = Some(ColumnLink::Detail);
+                }
+                column
+            })
+            .collect()
+    }
+
+    /// Maps a client-supplied ordering (e.g. `"-author"`) to the field the
+    /// database should sort by.
+    ///
+    /// Column names are translated through [`ListColumn::sort_field`]; model
+    /// fields and annotations that aren't displayed may still be used
+    /// directly. Returns `None` when the requested column isn't sortable.
+    pub fn resolve_ordering(&self, ordering: &str) -> Option<String> {
+        let (name, prefix) = ordering
+            .strip_prefix('-')
+            .map_or((ordering, ""), |stripped| (stripped, "-"));
+
+        let sort_field = self
+            .list_columns()
+            .into_iter()
+            .find(|c| c.name == name)
+            .map_or_else(
+                || {
+                    (self.fields_schema.iter().any(|f| f.name == name)
+                        || self.annotations.iter().any(|a| a.name == name))
+                    .then(|| name.to_string())
+                },
+                |c| c.sort_field,
+            )?;
+        Some(format!("{prefix}{sort_field}"))
+    }
+
+    /// Maps a multi-column ordering such as `"2,-0"`, as sent in the list
+    /// view's `o` parameter, to the fields the database should sort by.
+    ///
+    /// Each comma-separated entry is an index into `list_display` or a
+    /// column name, prefixed with `-` for descending, and is resolved with
+    /// [`resolve_ordering`](Self::resolve_ordering). Later entries for a
+    /// column already listed are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first entry that is out of range or not
+    /// sortable.
+    pub fn resolve_list_ordering(&self, ordering: &str) -> Result<Vec<String>, String> {
+        let mut fields: Vec<String> = Vec::new();
+        for entry in ordering.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, prefix) = entry
+                .strip_prefix('-')
+                .map_or((entry, ""), |stripped| (stripped, "-"));
+            let name = match name.parse::<usize>() {
+                Ok(index) => self
+                    .list_display
+                    .get(index)
+                    .ok_or_else(|| format!("Ordering index {index} is out of range"))?,
+                Err(_) => name,
+            };
+            let field = self
+                .resolve_ordering(&format!("{prefix}{name}"))
+                .ok_or_else(|| format!("Cannot sort by '{entry}'"))?;
+            let column = field.trim_start_matches('-');
+            if !fields.iter().any(|f| f.trim_start_matches('-') == column) {
+                fields.push(field);
+            }
+        }
+        Ok(fields)
+    }
+}
+
+/// Returns the `n`th candidate for a unique slug: `slug` itself for 1,
+/// then `slug-2`, `slug-3`, and so on, cut so the whole fits `max_length`.
+///
+/// ```
+/// use django_rs_admin::model_admin::numbered_slug;
+///
+/// assert_eq!(numbered_slug("hello-world", 1, None), "hello-world");
+/// assert_eq!(numbered_slug("hello-world", 2, None), "hello-world-2");
+/// assert_eq!(numbered_slug("hello-world", 12, Some(10)), "hello-w-12");
+/// ```
+pub fn numbered_slug(slug: &str, n: usize, max_length: Option<usize>) -> String {
+    let suffix = if n > 1 {
+        format!("-{n}")
+    } else {
+        String::new()
+    };
+    let base: String = max_length.map_or_else(
+        || slug.to_string(),
+        |max| {
+            slug.chars()
+                .take(max.saturating_sub(suffix.len()))
+                .collect()
+        },
+    );
+    format!("{}{suffix}", base.trim_end_matches('-'))
 }
 
 /// A grouping of fields in the admin detail/change view.
@@ -426,6 +1152,9 @@ pub struct FieldSchema {
     pub read_only: bool,
     /// Whether this field is the primary key.
     pub primary_key: bool,
+    /// Whether values of this field are unique.
+    #[serde(default)]
+    pub unique: bool,
     /// Maximum character length, if applicable.
     pub max_length: Option<usize>,
     /// Human-readable label.
@@ -438,6 +1167,9 @@ pub struct FieldSchema {
     pub is_relation: bool,
     /// The target model for relational fields (e.g., "auth.user").
     pub related_model: Option<String>,
+    /// The value used when the field is omitted on create, if any.
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
 }
 
 impl FieldSchema {
@@ -451,12 +1183,14 @@ impl FieldSchema {
             required: true,
             read_only: false,
             primary_key: false,
+            unique: false,
             max_length: None,
             label,
             help_text: String::new(),
             choices: None,
             is_relation: false,
             related_model: None,
+            default: None,
         }
     }
 
@@ -482,6 +1216,13 @@ impl FieldSchema {
         self
     }
 
+    /// Marks this field as unique.
+    #[must_use]
+    pub const fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
     /// Sets the maximum length.
     #[must_use]
     pub const fn max_length(mut self, len: usize) -> Self {
@@ -510,12 +1251,513 @@ impl FieldSchema {
         self.related_model = Some(related_model.into());
         self
     }
+
+    /// Sets the value used when the field is omitted on create.
+    #[must_use]
+    pub fn default_value(mut self, value: impl Into<serde_json::Value>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    /// Sets the allowed choices as `(value, label)` pairs.
+    #[must_use]
+    pub fn choices(mut self, choices: Vec<(String, String)>) -> Self {
+        self.choices = Some(choices);
+        self
+    }
+
+    /// Builds a schema entry from an ORM field definition.
+    ///
+    /// Carries over the type, nullability, uniqueness, length, label, help
+    /// text, choices, relation target and default of the model field.
+    pub fn from_field_def(field: &FieldDef) -> Self {
+        let mut schema = Self::new(field.name, field.field_type.class_name())
+            .label(field.verbose_name.clone())
+            .help_text(field.help_text.clone());
+        if field.primary_key {
+            schema = schema.primary_key();
+        }
+        if field.null || field.blank {
+            schema = schema.optional();
+        }
+        if !field.editable {
+            schema = schema.read_only();
+        }
+        if field.unique {
+            schema = schema.unique();
+        }
+        if let Some(len) = field.max_length {
+            schema = schema.max_length(len);
+        }
+        if let Some(choices) = &field.choices {
+            schema = schema.choices(
+                choices
+                    .iter()
+                    .map(|(value, label)| (value.to_string(), label.clone()))
+                    .collect(),
+            );
+        }
+        match &field.field_type {
+            FieldType::ForeignKey { to, .. }
+            | FieldType::OneToOneField { to, .. }
+            | FieldType::ManyToManyField { to, .. } => schema = schema.relation(to.clone()),
+            _ => {}
+        }
+        if let Some(default) = &field.default {
+            schema = schema.default_value(match default {
+                Value::Null => serde_json::Value::Null,
+                Value::Bool(b) => serde_json::Value::Bool(*b),
+                Value::Int(i) => serde_json::Value::from(*i),
+                Value::Float(f) => serde_json::Value::from(*f),
+                Value::Json(j) => j.clone(),
+                other => serde_json::Value::String(other.to_string()),
+            });
+        }
+        schema
+    }
+}
+
+/// Metadata describing one column of the admin list view.
+///
+/// Sent to the React frontend in the schema response so tables can render,
+/// format, and sort columns without per-model frontend code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListColumn {
+    /// The `list_display` entry this column renders.
+    pub name: String,
+    /// The column header label.
+    pub label: String,
+    /// Whether the list can be ordered by this column.
+    pub sortable: bool,
+    /// The field or annotation the database sorts by when ordering on this column.
+    pub sort_field: Option<String>,
+    /// The kind of value in this column, used for client-side formatting.
+    pub data_type: ColumnDataType,
+    /// Horizontal alignment hint for the column's cells.
+    pub align: ColumnAlign,
+    /// Where the column's cells link to, if anywhere.
+    pub link: Option<ColumnLink>,
+    /// Whether list results carry a localized timestamp and a relative
+    /// string ("3 hours ago") for this column, under `_datetimes`.
+    ///
+    /// The frontend shows the relative string and the exact timestamp as a
+    /// tooltip. See [`humanize_datetimes`](crate::api::humanize_datetimes).
+    #[serde(default)]
+    pub humanized: bool,
+}
+
+impl ListColumn {
+    /// Creates an unsortable text column, as used for computed values.
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let label = if name == "__str__" {
+            "object".to_string()
+        } else {
+            name.replace('_', " ")
+        };
+        Self {
+            name,
+            label,
+            sortable: false,
+            sort_field: None,
+            data_type: ColumnDataType::Text,
+            align: ColumnAlign::Left,
+            link: None,
+            humanized: false,
+        }
+    }
+
+    /// Creates a sortable column backed by a model field.
+    pub fn from_field(field: &FieldSchema) -> Self {
+        let data_type = if field.choices.is_some() {
+            ColumnDataType::Choice
+        } else if field.is_relation {
+            ColumnDataType::Relation
+        } else {
+            ColumnDataType::from_field_type(&field.field_type)
+        };
+        Self {
+            name: field.name.clone(),
+            label: field.label.clone(),
+            sortable: true,
+            sort_field: Some(field.name.clone()),
+            data_type,
+            align: data_type.default_align(),
+            link: field.related_model.clone().map(ColumnLink::Related),
+            humanized: matches!(data_type, ColumnDataType::DateTime),
+        }
+    }
+
+    /// Sets the column header label.
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Makes the column sortable by the given field or annotation.
+    #[must_use]
+    pub fn sort_field(mut self, field: impl Into<String>) -> Self {
+        self.sortable = true;
+        self.sort_field = Some(field.into());
+        self
+    }
+
+    /// Sets the data type, along with its default alignment and whether
+    /// values are humanized.
+    #[must_use]
+    pub const fn data_type(mut self, data_type: ColumnDataType) -> Self {
+        self.data_type = data_type;
+        self.align = data_type.default_align();
+        self.humanized = matches!(data_type, ColumnDataType::DateTime);
+        self
+    }
+
+    /// Sets whether values are humanized.
+    #[must_use]
+    pub const fn humanized(mut self, humanized: bool) -> Self {
+        self.humanized = humanized;
+        self
+    }
+
+    /// Sets the alignment hint.
+    #[must_use]
+    pub const fn align(mut self, align: ColumnAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets the link target.
+    #[must_use]
+    pub fn link(mut self, link: ColumnLink) -> Self {
+        self.link = Some(link);
+        self
+    }
+}
+
+/// An aggregate over the objects of another model that reference each
+/// listed object, like Django's `annotate(num_comments=Count("comment"))`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_admin::model_admin::{ListAnnotation, ModelAdmin};
+///
+/// let admin = ModelAdmin::new("blog", "article")
+///     .list_display(vec!["title", "num_comments"])
+///     .annotate(ListAnnotation::count("num_comments", "blog.comment", "article"));
+/// assert!(admin.list_columns()[1].sortable);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListAnnotation {
+    /// The name the value is added under.
+    pub name: String,
+    /// The aggregate function.
+    pub function: AnnotationFunction,
+    /// The model key of the related objects (e.g. `"blog.comment"`).
+    pub related_model: String,
+    /// The field of the related objects holding the listed object's key.
+    pub related_field: String,
+    /// The field of the related objects aggregated; unused by `Count`.
+    pub value_field: Option<String>,
+    /// The data type of `value_field`, filled in from the related model's
+    /// schema when both models are registered.
+    #[serde(default)]
+    pub value_type: Option<ColumnDataType>,
+}
+
+impl ListAnnotation {
+    /// Counts the related objects.
+    pub fn count(
+        name: impl Into<String>,
+        related_model: impl Into<String>,
+        related_field: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            function: AnnotationFunction::Count,
+            related_model: related_model.into(),
+            related_field: related_field.into(),
+            value_field: None,
+            value_type: None,
+        }
+    }
+
+    /// Aggregates `value_field` of the related objects with `function`.
+    pub fn aggregate(
+        name: impl Into<String>,
+        function: AnnotationFunction,
+        related_model: impl Into<String>,
+        related_field: impl Into<String>,
+        value_field: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            function,
+            related_model: related_model.into(),
+            related_field: related_field.into(),
+            value_field: Some(value_field.into()),
+            value_type: None,
+        }
+    }
+}
+
+/// The aggregate function of a [`ListAnnotation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationFunction {
+    /// The number of related objects.
+    Count,
+    /// The sum of the values; 0 without related objects.
+    Sum,
+    /// The average of the values; null without related objects.
+    Avg,
+    /// The smallest value; null without related objects.
+    Min,
+    /// The largest value; null without related objects.
+    Max,
+}
+
+impl AnnotationFunction {
+    /// Returns the data type of the column showing the aggregate of values
+    /// of `value_type`.
+    ///
+    /// `Min` and `Max` keep the type of the values, so the smallest date is
+    /// still a date; an unknown value type is taken to be numeric.
+    pub const fn data_type(self, value_type: Option<ColumnDataType>) -> ColumnDataType {
+        match (self, value_type) {
+            (Self::Count, _) | (Self::Sum, Some(ColumnDataType::Integer)) => {
+                ColumnDataType::Integer
+            }
+            (Self::Min | Self::Max, Some(value_type)) => value_type,
+            _ => ColumnDataType::Decimal,
+        }
+    }
+}
+
+/// The kind of value displayed in a list column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnDataType {
+    /// Free-form text.
+    Text,
+    /// Integer values.
+    Integer,
+    /// Decimal or floating-point values.
+    Decimal,
+    /// True/false values.
+    Boolean,
+    /// Calendar dates.
+    Date,
+    /// Dates with a time component.
+    DateTime,
+    /// Times of day.
+    Time,
+    /// Values drawn from a fixed set of choices.
+    Choice,
+    /// References to another model.
+    Relation,
+    /// Arbitrary JSON values.
+    Json,
+}
+
+impl ColumnDataType {
+    /// Maps a field type name (e.g. `"IntegerField"`) to a column data type.
+    pub fn from_field_type(field_type: &str) -> Self {
+        match field_type {
+            "AutoField"
+            | "BigAutoField"
+            | "SmallAutoField"
+            | "IntegerField"
+            | "BigIntegerField"
+            | "SmallIntegerField"
+            | "PositiveIntegerField"
+            | "PositiveBigIntegerField"
+            | "PositiveSmallIntegerField" => Self::Integer,
+            "FloatField" | "DecimalField" => Self::Decimal,
+            "BooleanField" | "NullBooleanField" => Self::Boolean,
+            "DateField" => Self::Date,
+            "DateTimeField" => Self::DateTime,
+            "TimeField" => Self::Time,
+            "ForeignKey" | "OneToOneField" | "ManyToManyField" => Self::Relation,
+            "JSONField" => Self::Json,
+            _ => Self::Text,
+        }
+    }
+
+    /// Returns the conventional alignment for this data type.
+    pub const fn default_align(self) -> ColumnAlign {
+        match self {
+            Self::Integer | Self::Decimal => ColumnAlign::Right,
+            Self::Boolean => ColumnAlign::Center,
+            _ => ColumnAlign::Left,
+        }
+    }
+}
+
+/// Horizontal alignment hint for a list column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnAlign {
+    /// Left-aligned cells.
+    Left,
+    /// Centered cells.
+    Center,
+    /// Right-aligned cells.
+    Right,
+}
+
+/// The target a list column's cells link to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "target")]
+pub enum ColumnLink {
+    /// The detail/change view of the row's object.
+    Detail,
+    /// The detail view of the related object, identified by model key (e.g. `"auth.user"`).
+    Related(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_filter_horizontal_target() {
+        let admin = ModelAdmin::new("blog", "article")
+            .fields_schema(vec![
+                FieldSchema::new("author", "ForeignKey").relation("auth.user"),
+                FieldSchema::new("tags", "ManyToManyField").relation("blog.tag"),
+                FieldSchema::new("editors", "ManyToManyField").relation("auth.user"),
+            ])
+            .filter_horizontal(vec!["tags", "author"]);
+        assert_eq!(admin.filter_horizontal_target("tags"), Some("blog.tag"));
+        assert_eq!(admin.filter_horizontal_target("author"), None);
+        assert_eq!(admin.filter_horizontal_target("editors"), None);
+    }
+
+    #[test]
+    fn test_apply_visibility_rules() {
+        use django_rs_forms::conditions::Condition;
+
+        let admin = ModelAdmin::new("accounts", "customer")
+            .fields_schema(vec![
+                FieldSchema::new("account_type", "CharField"),
+                FieldSchema::new("company_name", "CharField").default_value(""),
+                FieldSchema::new("vat_number", "CharField"),
+            ])
+            .visibility_rules(vec![
+                VisibilityRule::new("company_name", Condition::eq("account_type", "business")),
+                VisibilityRule::new("vat_number", Condition::eq("account_type", "business")),
+            ]);
+
+        // Create: hidden values are dropped.
+        let mut data = HashMap::from([
+            ("account_type".to_string(), serde_json::json!("personal")),
+            ("company_name".to_string(), serde_json::json!("Acme")),
+        ]);
+        let hidden = admin.apply_visibility_rules(&mut data, None);
+        assert_eq!(hidden, ["company_name", "vat_number"]);
+        assert_eq!(data.len(), 1);
+
+        // Update: conditions fall back to the stored object, and stored
+        // hidden values are cleared.
+        let current = serde_json::json!({
+            "account_type": "business",
+            "company_name": "Acme",
+            "vat_number": "DE123",
+        });
+        let mut data = HashMap::from([("company_name".to_string(), serde_json::json!("Acme2"))]);
+        assert!(admin
+            .apply_visibility_rules(&mut data, Some(&current))
+            .is_empty());
+        assert_eq!(data["company_name"], "Acme2");
+
+        let mut data = HashMap::from([("account_type".to_string(), serde_json::json!("personal"))]);
+        admin.apply_visibility_rules(&mut data, Some(&current));
+        assert_eq!(data["company_name"], "");
+        assert_eq!(data["vat_number"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_normalize_localized_input() {
+        let admin = ModelAdmin::new("shop", "order").fields_schema(vec![
+            FieldSchema::new("quantity", "IntegerField"),
+            FieldSchema::new("price", "DecimalField"),
+            FieldSchema::new("weight", "FloatField"),
+            FieldSchema::new("ships_on", "DateField"),
+            FieldSchema::new("placed_at", "DateTimeField"),
+            FieldSchema::new("note", "CharField"),
+        ]);
+        let mut data = HashMap::from([
+            ("quantity".to_string(), serde_json::json!("1.200")),
+            ("price".to_string(), serde_json::json!("1.234,50")),
+            ("weight".to_string(), serde_json::json!("0,75")),
+            ("ships_on".to_string(), serde_json::json!("03.04.2024")),
+            (
+                "placed_at".to_string(),
+                serde_json::json!("02.04.2024 09:15"),
+            ),
+            ("note".to_string(), serde_json::json!("1,5")),
+        ]);
+        admin.normalize_localized_input(&mut data, "de").unwrap();
+        assert_eq!(data["quantity"], 1200);
+        assert_eq!(data["price"], "1234.50");
+        assert_eq!(data["weight"], 0.75);
+        assert_eq!(data["ships_on"], "2024-04-03");
+        assert_eq!(data["placed_at"], "2024-04-02T09:15:00");
+        assert_eq!(data["note"], "1,5");
+
+        // Canonical values, numbers and blanks pass through in any language.
+        let mut data = HashMap::from([
+            ("quantity".to_string(), serde_json::json!(3)),
+            ("price".to_string(), serde_json::json!("")),
+            (
+                "placed_at".to_string(),
+                serde_json::json!("2024-04-02T09:15:00+02:00"),
+            ),
+        ]);
+        admin.normalize_localized_input(&mut data, "fr").unwrap();
+        assert_eq!(data["quantity"], 3);
+        assert_eq!(data["price"], "");
+        assert_eq!(data["placed_at"], "2024-04-02T09:15:00+02:00");
+
+        let mut data = HashMap::from([
+            ("quantity".to_string(), serde_json::json!("1,5")),
+            ("ships_on".to_string(), serde_json::json!("13/13/2024")),
+        ]);
+        let errors = admin
+            .normalize_localized_input(&mut data, "de")
+            .unwrap_err();
+        assert_eq!(errors["quantity"], "Enter a whole number.");
+        assert_eq!(errors["ships_on"], "Enter a valid date.");
+    }
+
+    #[test]
+    fn test_encrypted_values_are_masked() {
+        let admin = ModelAdmin::new("crm", "customer").fields_schema(vec![
+            FieldSchema::new("email", "EncryptedCharField"),
+            FieldSchema::new("notes", "EncryptedTextField"),
+            FieldSchema::new("name", "CharField"),
+        ]);
+        let mut obj = serde_json::json!({
+            "email": "enc$k1$c2VjcmV0",
+            "notes": "decrypted by the data source",
+            "name": "enc$k1$bm90IGVuY3J5cHRlZA",
+        });
+        admin.mask_encrypted_values(&mut obj);
+        assert_eq!(obj["email"], ENCRYPTED_MASK);
+        assert_eq!(obj["notes"], "decrypted by the data source");
+        assert_eq!(obj["name"], "enc$k1$bm90IGVuY3J5cHRlZA");
+
+        let mut data = HashMap::from([
+            ("email".to_string(), serde_json::json!(ENCRYPTED_MASK)),
+            ("notes".to_string(), serde_json::json!("new notes")),
+        ]);
+        admin.drop_masked_input(&mut data);
+        assert!(!data.contains_key("email"));
+        assert_eq!(data["notes"], "new notes");
+    }
+
     #[test]
     fn test_model_admin_new_defaults() {
         let admin = ModelAdmin::new("blog", "article");
@@ -533,6 +1775,7 @@ mod tests {
         assert!(!admin.save_on_top);
         assert!(admin.date_hierarchy.is_none());
         assert_eq!(admin.action_names, vec!["delete_selected"]);
+        assert!(!admin.comments_enabled);
     }
 
     #[test]
@@ -645,6 +1888,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prepopulated_slugs() {
+        let admin = ModelAdmin::new("blog", "event")
+            .fields_schema(vec![FieldSchema::new("slug", "SlugField").max_length(12)])
+            .prepopulated_fields(HashMap::from([
+                (
+                    "slug".to_string(),
+                    vec!["city".to_string(), "year".to_string()],
+                ),
+                ("code".to_string(), vec!["missing".to_string()]),
+            ]));
+        let data = |pairs: serde_json::Value| -> HashMap<String, serde_json::Value> {
+            serde_json::from_value(pairs).unwrap()
+        };
+
+        assert_eq!(
+            admin.prepopulated_slugs(&data(
+                serde_json::json!({"city": "São Paulo", "year": 2024})
+            )),
+            vec![("slug".to_string(), "são-paulo-20".to_string())]
+        );
+        assert_eq!(
+            admin.prepopulated_slugs(&data(serde_json::json!({"year": 2024, "slug": null}))),
+            vec![("slug".to_string(), "2024".to_string())]
+        );
+        assert!(admin
+            .prepopulated_slugs(&data(serde_json::json!({"city": "Lima", "slug": "lima"})))
+            .is_empty());
+        assert_eq!(numbered_slug("são-paulo-20", 3, Some(12)), "são-paulo-3");
+    }
+
     #[test]
     fn test_fieldset_new() {
         let fs = Fieldset::new(vec!["name", "email"]);
@@ -745,6 +2019,78 @@ mod tests {
         assert_eq!(schema.max_length, Some(100));
     }
 
+    #[test]
+    fn test_field_schema_from_field_def_with_choices() {
+        let field = FieldDef::new("status", FieldType::CharField)
+            .max_length(20)
+            .unique()
+            .verbose_name("Status")
+            .choices(vec![
+                (Value::from("draft"), "Draft".to_string()),
+                (Value::from("live"), "Published".to_string()),
+            ])
+            .default(Value::from("draft"));
+        let schema = FieldSchema::from_field_def(&field);
+        assert_eq!(schema.field_type, "CharField");
+        assert_eq!(schema.label, "Status");
+        assert_eq!(schema.max_length, Some(20));
+        assert!(schema.unique);
+        assert!(schema.required);
+        assert_eq!(
+            schema.choices,
+            Some(vec![
+                ("draft".to_string(), "Draft".to_string()),
+                ("live".to_string(), "Published".to_string()),
+            ])
+        );
+        assert_eq!(schema.default, Some(serde_json::json!("draft")));
+    }
+
+    #[test]
+    fn test_check_lookup_field() {
+        let admin = ModelAdmin::new("blog", "post").fields_schema(vec![
+            FieldSchema::new("id", "BigAutoField").primary_key(),
+            FieldSchema::new("slug", "SlugField").unique(),
+            FieldSchema::new("title", "CharField"),
+        ]);
+        assert!(admin.check_lookup_field().is_ok());
+        assert_eq!(admin.lookup_field_name(), "id");
+
+        let admin = admin.lookup_field("slug");
+        assert!(admin.check_lookup_field().is_ok());
+        assert_eq!(admin.lookup_field_name(), "slug");
+
+        let err = admin.clone().lookup_field("title").check_lookup_field();
+        assert_eq!(
+            err.unwrap_err(),
+            "Lookup field 'title' of 'blog.post' is not unique"
+        );
+        let err = admin.lookup_field("uuid").check_lookup_field();
+        assert_eq!(
+            err.unwrap_err(),
+            "Lookup field 'uuid' is not a field of 'blog.post'"
+        );
+    }
+
+    #[test]
+    fn test_field_schema_from_field_def_relation() {
+        let field = FieldDef::new(
+            "author",
+            FieldType::ForeignKey {
+                to: "auth.user".to_string(),
+                on_delete: django_rs_db::fields::OnDelete::Cascade,
+                related_name: None,
+            },
+        )
+        .nullable();
+        let schema = FieldSchema::from_field_def(&field);
+        assert_eq!(schema.field_type, "ForeignKey");
+        let data = FieldSchema::from_field_def(&FieldDef::new("data", FieldType::JsonField));
+        assert_eq!(data.field_type, "JSONField");
+        assert!(!schema.required);
+        assert_eq!(schema.related_model, Some("auth.user".to_string()));
+    }
+
     #[test]
     fn test_model_admin_fields_schema() {
         let admin = ModelAdmin::new("auth", "user").fields_schema(vec![
@@ -772,4 +2118,274 @@ mod tests {
         assert!(json.contains("\"app_label\":\"blog\""));
         assert!(json.contains("\"list_per_page\":10"));
     }
+
+    #[test]
+    fn test_list_columns_derived_from_schema() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "views", "published", "author", "__str__"])
+            .fields_schema(vec![
+                FieldSchema::new("id", "BigAutoField").primary_key(),
+                FieldSchema::new("title", "CharField"),
+                FieldSchema::new("views", "IntegerField"),
+                FieldSchema::new("published", "BooleanField"),
+                FieldSchema::new("author", "ForeignKey").relation("auth.user"),
+            ]);
+        let columns = admin.list_columns();
+        assert_eq!(columns.len(), 5);
+
+        assert!(columns[0].sortable);
+        assert_eq!(columns[0].link, Some(ColumnLink::Detail));
+        assert_eq!(columns[1].data_type, ColumnDataType::Integer);
+        assert_eq!(columns[1].align, ColumnAlign::Right);
+        assert_eq!(columns[2].align, ColumnAlign::Center);
+        assert_eq!(columns[3].data_type, ColumnDataType::Relation);
+        assert_eq!(
+            columns[3].link,
+            Some(ColumnLink::Related("auth.user".to_string()))
+        );
+        assert!(!columns[4].sortable);
+        assert!(columns[4].sort_field.is_none());
+    }
+
+    #[test]
+    fn test_list_columns_display_links() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["id", "title"])
+            .list_display_links(vec!["title"]);
+        let columns = admin.list_columns();
+        assert!(columns[0].link.is_none());
+        assert_eq!(columns[1].link, Some(ColumnLink::Detail));
+    }
+
+    #[test]
+    fn test_list_column_override() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["__str__", "comment_count"])
+            .list_column(
+                ListColumn::new("comment_count")
+                    .label("Comments")
+                    .sort_field("num_comments")
+                    .data_type(ColumnDataType::Integer),
+            );
+        let columns = admin.list_columns();
+        assert_eq!(columns[1].label, "Comments");
+        assert!(columns[1].sortable);
+        assert_eq!(columns[1].align, ColumnAlign::Right);
+    }
+
+    #[test]
+    fn test_resolve_ordering() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "comment_count", "__str__"])
+            .fields_schema(vec![
+                FieldSchema::new("title", "CharField"),
+                FieldSchema::new("created_at", "DateTimeField"),
+            ])
+            .list_column(ListColumn::new("comment_count").sort_field("num_comments"));
+        assert_eq!(admin.resolve_ordering("title"), Some("title".to_string()));
+        assert_eq!(
+            admin.resolve_ordering("-comment_count"),
+            Some("-num_comments".to_string())
+        );
+        assert_eq!(
+            admin.resolve_ordering("-created_at"),
+            Some("-created_at".to_string())
+        );
+        assert_eq!(admin.resolve_ordering("__str__"), None);
+        assert_eq!(admin.resolve_ordering("password"), None);
+
+        // Without a schema, the displayed columns stay sortable.
+        let admin = ModelAdmin::new("blog", "article").list_display(vec!["__str__", "title"]);
+        assert_eq!(admin.resolve_ordering("-title"), Some("-title".to_string()));
+        assert_eq!(admin.resolve_ordering("__str__"), None);
+        assert_eq!(admin.resolve_ordering("password"), None);
+        assert!(admin.list_columns()[1].sortable);
+    }
+
+    #[test]
+    fn test_resolve_list_ordering() {
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "comment_count", "__str__", "num_likes"])
+            .fields_schema(vec![
+                FieldSchema::new("title", "CharField"),
+                FieldSchema::new("created_at", "DateTimeField"),
+            ])
+            .list_column(ListColumn::new("comment_count").sort_field("num_comments"))
+            .annotate(ListAnnotation::count("num_likes", "blog.like", "article"));
+        assert_eq!(
+            admin.resolve_list_ordering("1,-0").unwrap(),
+            vec!["num_comments", "-title"]
+        );
+        assert_eq!(
+            admin.resolve_list_ordering("-3, created_at,0").unwrap(),
+            vec!["-num_likes", "created_at", "title"]
+        );
+        assert_eq!(
+            admin.resolve_list_ordering("0,-title").unwrap(),
+            vec!["title"]
+        );
+        assert!(admin.resolve_list_ordering("").unwrap().is_empty());
+        assert_eq!(
+            admin.resolve_list_ordering("0,9").unwrap_err(),
+            "Ordering index 9 is out of range"
+        );
+        assert_eq!(
+            admin.resolve_list_ordering("-2").unwrap_err(),
+            "Cannot sort by '-2'"
+        );
+        assert!(admin.resolve_list_ordering("password").is_err());
+    }
+
+    #[test]
+    fn test_annotation_columns() {
+        let admin = ModelAdmin::new("shop", "order")
+            .list_display(vec!["id", "line_count", "total"])
+            .annotate(ListAnnotation::count("line_count", "shop.line", "order"))
+            .annotate(ListAnnotation::aggregate(
+                "total",
+                AnnotationFunction::Sum,
+                "shop.line",
+                "order",
+                "amount",
+            ));
+        let columns = admin.list_columns();
+        assert_eq!(columns[1].sort_field.as_deref(), Some("line_count"));
+        assert_eq!(columns[1].data_type, ColumnDataType::Integer);
+        assert_eq!(columns[2].data_type, ColumnDataType::Decimal);
+        assert_eq!(admin.resolve_ordering("-total"), Some("-total".to_string()));
+
+        let admin = admin.annotate(ListAnnotation::count("total", "shop.refund", "order"));
+        assert_eq!(admin.annotations.len(), 2);
+        assert_eq!(admin.annotations[1].function, AnnotationFunction::Count);
+    }
+
+    #[test]
+    fn test_annotation_data_type() {
+        let date = Some(ColumnDataType::Date);
+        assert_eq!(
+            AnnotationFunction::Min.data_type(date),
+            ColumnDataType::Date
+        );
+        assert_eq!(
+            AnnotationFunction::Max.data_type(Some(ColumnDataType::Text)),
+            ColumnDataType::Text
+        );
+        assert_eq!(
+            AnnotationFunction::Max.data_type(None),
+            ColumnDataType::Decimal
+        );
+        assert_eq!(
+            AnnotationFunction::Sum.data_type(Some(ColumnDataType::Integer)),
+            ColumnDataType::Integer
+        );
+        assert_eq!(
+            AnnotationFunction::Avg.data_type(Some(ColumnDataType::Integer)),
+            ColumnDataType::Decimal
+        );
+        assert_eq!(
+            AnnotationFunction::Count.data_type(date),
+            ColumnDataType::Integer
+        );
+    }
+
+    #[test]
+    fn test_column_serialization() {
+        let column = ListColumn::new("author").link(ColumnLink::Related("auth.user".to_string()));
+        let json = serde_json::to_value(&column).unwrap();
+        assert_eq!(json["data_type"], "text");
+        assert_eq!(json["align"], "left");
+        assert_eq!(json["link"]["type"], "related");
+        assert_eq!(json["link"]["target"], "auth.user");
+        assert_eq!(json["humanized"], false);
+
+        let column = ListColumn::from_field(&FieldSchema::new("created_at", "DateTimeField"));
+        assert!(column.humanized);
+        assert!(!column.data_type(ColumnDataType::Date).humanized);
+    }
+
+    fn tag_admin() -> ModelAdmin {
+        ModelAdmin::new("blog", "tag")
+            .fields_schema(vec![
+                FieldSchema::new("id", "BigAutoField").primary_key(),
+                FieldSchema::new("name", "CharField"),
+                FieldSchema::new("color", "CharField").default_value("gray"),
+                FieldSchema::new("description", "TextField").optional(),
+            ])
+            .quick_create_fields(vec!["name"])
+    }
+
+    #[test]
+    fn test_quick_create_data_applies_defaults() {
+        let admin = tag_admin();
+        let mut payload = HashMap::new();
+        payload.insert("name".to_string(), serde_json::json!("rust"));
+
+        let data = admin.quick_create_data(&payload).unwrap();
+        assert_eq!(data["name"], "rust");
+        assert_eq!(data["color"], "gray");
+        assert!(!data.contains_key("description"));
+        assert!(!data.contains_key("id"));
+    }
+
+    #[test]
+    fn test_quick_create_data_rejects_other_fields() {
+        let admin = tag_admin();
+        let mut payload = HashMap::new();
+        payload.insert("name".to_string(), serde_json::json!("rust"));
+        payload.insert("color".to_string(), serde_json::json!("red"));
+
+        let err = admin.quick_create_data(&payload).unwrap_err();
+        assert!(err.contains("color"));
+    }
+
+    #[test]
+    fn test_quick_create_data_requires_fields() {
+        let admin = tag_admin();
+        let err = admin.quick_create_data(&HashMap::new()).unwrap_err();
+        assert!(err.contains("'name' is required"));
+
+        let disabled = ModelAdmin::new("blog", "tag");
+        assert!(disabled.quick_create_data(&HashMap::new()).is_err());
+        assert!(disabled.quick_create_form_fields().is_empty());
+    }
+
+    #[test]
+    fn test_quick_create_form_includes_required_fields() {
+        let admin = ModelAdmin::new("blog", "author")
+            .fields_schema(vec![
+                FieldSchema::new("id", "BigAutoField").primary_key(),
+                FieldSchema::new("name", "CharField"),
+                FieldSchema::new("email", "EmailField"),
+                FieldSchema::new("slug", "SlugField"),
+                FieldSchema::new("bio", "TextField").optional(),
+            ])
+            .prepopulated_fields(HashMap::from([(
+                "slug".to_string(),
+                vec!["name".to_string()],
+            )]))
+            .quick_create_fields(vec!["name"]);
+        assert_eq!(admin.quick_create_form_fields(), ["name", "email"]);
+
+        let payload = HashMap::from([("name".to_string(), serde_json::json!("Ann"))]);
+        let err = admin.quick_create_data(&payload).unwrap_err();
+        assert!(err.contains("'email' is required"));
+
+        let mut payload = payload;
+        payload.insert("email".to_string(), serde_json::json!("ann@example.com"));
+        assert!(admin.quick_create_data(&payload).is_ok());
+    }
+
+    #[test]
+    fn test_object_label() {
+        let admin = tag_admin();
+        assert_eq!(admin.pk_field_name(), "id");
+        assert_eq!(
+            admin.object_label(&serde_json::json!({"id": 3, "name": "rust"})),
+            "rust"
+        );
+        assert_eq!(
+            admin.object_label(&serde_json::json!({"id": 3})),
+            "tag object (3)"
+        );
+    }
 }
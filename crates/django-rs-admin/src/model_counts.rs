@@ -0,0 +1,325 @@
+//! Cached object counts for the admin sidebar.
+//!
+//! The index endpoint shows how many objects each model has. Counting every
+//! table on every page load is expensive, so counts are kept in a
+//! [`ModelCountCache`]. A count is served from the cache until it expires or
+//! is invalidated; after that the old count is still served, marked
+//! [`stale`](ModelCount::stale), while a background task recounts.
+//!
+//! Admin writes invalidate the count of their model. Writes made elsewhere
+//! are picked up through the `post_save` and `post_delete` signals (see
+//! [`ModelCountCache::connect_signals`]); these do not say which model
+//! changed, so they invalidate every count.
+//!
+//! Models with [`ModelAdmin::show_count`] turned off are never counted.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::model_counts::ModelCountCache;
+//!
+//! let cache = ModelCountCache::new();
+//! cache.set("blog.article", 42);
+//! assert_eq!(cache.get("blog.article").unwrap().count, 42);
+//!
+//! cache.invalidate("blog.article");
+//! let count = cache.get("blog.article").unwrap();
+//! assert_eq!(count.count, 42);
+//! assert!(count.stale);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use django_rs_signals::SIGNALS;
+use serde::{Deserialize, Serialize};
+
+use crate::db::AdminDbExecutor;
+use crate::model_admin::ModelAdmin;
+
+/// How long a count is served before it is refreshed.
+pub const DEFAULT_COUNT_TTL: Duration = Duration::from_secs(300);
+
+/// The object count of a model, as shown in the sidebar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCount {
+    /// The number of objects.
+    pub count: usize,
+    /// When the objects were counted.
+    pub counted_at: DateTime<Utc>,
+    /// Whether the model may have changed since it was counted.
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    count: usize,
+    counted_at: DateTime<Utc>,
+    /// Bumped by every invalidation.
+    generation: u64,
+    /// The generation the count was taken at.
+    counted_generation: u64,
+    refreshing: bool,
+}
+
+/// Cache of per-model object counts, keyed by `"app.model"`.
+///
+/// Clones share the same cache.
+#[derive(Debug, Clone)]
+pub struct ModelCountCache {
+    entries: Arc<RwLock<HashMap<String, Entry>>>,
+    ttl: Duration,
+}
+
+impl Default for ModelCountCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModelCountCache {
+    /// Creates an empty cache whose counts expire after [`DEFAULT_COUNT_TTL`].
+    pub fn new() -> Self {
+        Self::with_ttl(DEFAULT_COUNT_TTL)
+    }
+
+    /// Creates an empty cache whose counts expire after `ttl`.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    /// Returns how long a count is served before it is refreshed.
+    pub const fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the cached count of a model, if it was ever counted.
+    pub fn get(&self, model_key: &str) -> Option<ModelCount> {
+        let entry = *self.entries.read().unwrap().get(model_key)?;
+        let expired = (Utc::now() - entry.counted_at)
+            .to_std()
+            .is_ok_and(|age| age >= self.ttl);
+        Some(ModelCount {
+            count: entry.count,
+            counted_at: entry.counted_at,
+            stale: expired || entry.generation != entry.counted_generation,
+        })
+    }
+
+    /// Stores a fresh count for a model.
+    pub fn set(&self, model_key: &str, count: usize) {
+        let mut entries = self.entries.write().unwrap();
+        let generation = entries.get(model_key).map_or(0, |e| e.generation);
+        entries.insert(
+            model_key.to_string(),
+            Entry {
+                count,
+                counted_at: Utc::now(),
+                generation,
+                counted_generation: generation,
+                refreshing: false,
+            },
+        );
+    }
+
+    /// Marks the count of a model stale.
+    pub fn invalidate(&self, model_key: &str) {
+        if let Some(entry) = self.entries.write().unwrap().get_mut(model_key) {
+            entry.generation += 1;
+        }
+    }
+
+    /// Marks every count stale.
+    pub fn invalidate_all(&self) {
+        for entry in self.entries.write().unwrap().values_mut() {
+            entry.generation += 1;
+        }
+    }
+
+    /// Invalidates every count whenever the `post_save` or `post_delete`
+    /// signal is sent.
+    ///
+    /// `receiver_id` identifies the receivers; connecting again with the same
+    /// id replaces them.
+    pub fn connect_signals(&self, receiver_id: &str) {
+        let cache = self.clone();
+        SIGNALS.post_save.connect(
+            receiver_id,
+            Arc::new(move |_| {
+                cache.invalidate_all();
+                None
+            }),
+        );
+        let cache = self.clone();
+        SIGNALS.post_delete.connect(
+            receiver_id,
+            Arc::new(move |_| {
+                cache.invalidate_all();
+                None
+            }),
+        );
+    }
+
+    /// Disconnects the receivers added by
+    /// [`connect_signals`](Self::connect_signals).
+    pub fn disconnect_signals(&self, receiver_id: &str) {
+        SIGNALS.post_save.disconnect(receiver_id);
+        SIGNALS.post_delete.disconnect(receiver_id);
+    }
+
+    /// Returns the count to show for a model, or `None` if its count is
+    /// turned off or cannot be taken.
+    ///
+    /// A model that was never counted is counted now. A stale count is
+    /// returned as is, and refreshed by a background task.
+    pub async fn count(
+        &self,
+        db: &Arc<dyn AdminDbExecutor>,
+        admin: &ModelAdmin,
+    ) -> Option<ModelCount> {
+        if !admin.show_count {
+            return None;
+        }
+        let key = admin.model_key();
+        match self.get(&key) {
+            Some(count) if count.stale => {
+                if let Some(generation) = self.begin_refresh(&key) {
+                    let (cache, db, admin) = (self.clone(), db.clone(), admin.clone());
+                    tokio::spawn(async move {
+                        cache.refresh(db.as_ref(), &admin, generation).await;
+                    });
+                }
+                Some(count)
+            }
+            Some(count) => Some(count),
+            None => match db.count_objects(admin).await {
+                Ok(count) => {
+                    self.set(&key, count);
+                    self.get(&key)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to count {key} objects: {e}");
+                    None
+                }
+            },
+        }
+    }
+
+    /// Claims the refresh of a stale count, returning the generation being
+    /// counted, or `None` if a refresh is already running.
+    fn begin_refresh(&self, model_key: &str) -> Option<u64> {
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries.get_mut(model_key)?;
+        let claimed = !entry.refreshing;
+        entry.refreshing = true;
+        let generation = entry.generation;
+        drop(entries);
+        claimed.then_some(generation)
+    }
+
+    /// Recounts a model. The count stays stale if the model was invalidated
+    /// again while counting.
+    async fn refresh(&self, db: &dyn AdminDbExecutor, admin: &ModelAdmin, generation: u64) {
+        let key = admin.model_key();
+        let result = db.count_objects(admin).await;
+        if let Err(e) = &result {
+            tracing::warn!("Failed to count {key} objects: {e}");
+        }
+        let mut entries = self.entries.write().unwrap();
+        if let Some(entry) = entries.get_mut(&key) {
+            entry.refreshing = false;
+            if let Ok(count) = result {
+                entry.count = count;
+                entry.counted_at = Utc::now();
+                entry.counted_generation = generation;
+            }
+        }
+        drop(entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryAdminDb;
+    use django_rs_signals::{PostDelete, PostSave};
+
+    async fn add(db: &InMemoryAdminDb, admin: &ModelAdmin, title: &str) {
+        let data = HashMap::from([("title".to_string(), serde_json::json!(title))]);
+        db.create_object(admin, &data).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_count_is_cached_until_invalidated() {
+        let memory = Arc::new(InMemoryAdminDb::new());
+        let db: Arc<dyn AdminDbExecutor> = memory.clone();
+        let admin = ModelAdmin::new("blog", "article");
+        let cache = ModelCountCache::new();
+        add(&memory, &admin, "One").await;
+
+        let first = cache.count(&db, &admin).await.unwrap();
+        assert_eq!(first.count, 1);
+        assert!(!first.stale);
+
+        add(&memory, &admin, "Two").await;
+        assert_eq!(cache.count(&db, &admin).await.unwrap(), first);
+
+        cache.invalidate("blog.article");
+        let stale = cache.count(&db, &admin).await.unwrap();
+        assert_eq!(stale.count, 1);
+        assert!(stale.stale);
+
+        // The background refresh replaces the stale count.
+        for _ in 0..100 {
+            if !cache.get("blog.article").unwrap().stale {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let fresh = cache.count(&db, &admin).await.unwrap();
+        assert_eq!(fresh.count, 2);
+        assert!(!fresh.stale);
+    }
+
+    #[tokio::test]
+    async fn test_count_opt_out() {
+        let db: Arc<dyn AdminDbExecutor> = Arc::new(InMemoryAdminDb::new());
+        let admin = ModelAdmin::new("logs", "event").show_count(false);
+        let cache = ModelCountCache::new();
+        assert!(cache.count(&db, &admin).await.is_none());
+        assert!(cache.get("logs.event").is_none());
+    }
+
+    #[test]
+    fn test_expired_count_is_stale() {
+        let cache = ModelCountCache::with_ttl(Duration::ZERO);
+        cache.set("blog.article", 3);
+        assert!(cache.get("blog.article").unwrap().stale);
+    }
+
+    #[test]
+    fn test_signals_invalidate_all_counts() {
+        let cache = ModelCountCache::new();
+        cache.set("blog.article", 1);
+        cache.set("auth.user", 2);
+        cache.connect_signals("test_model_counts");
+
+        SIGNALS.post_save.dispatch(PostSave).unwrap();
+        assert!(cache.get("blog.article").unwrap().stale);
+        assert!(cache.get("auth.user").unwrap().stale);
+
+        cache.set("blog.article", 1);
+        SIGNALS.post_delete.dispatch(PostDelete).unwrap();
+        assert!(cache.get("blog.article").unwrap().stale);
+
+        cache.disconnect_signals("test_model_counts");
+        cache.set("blog.article", 1);
+        SIGNALS.post_save.dispatch(PostSave).unwrap();
+        assert!(!cache.get("blog.article").unwrap().stale);
+    }
+}
@@ -0,0 +1,340 @@
+//! Per-user notification center for the admin header.
+//!
+//! System events such as a finished import, a completed background action or
+//! a mention in a comment are pushed into a [`NotificationStore`] for a
+//! recipient. The React frontend lists them, shows an unread badge on the
+//! header bell and marks them read. Stores that support live updates hand out
+//! a broadcast receiver, which the admin site exposes as a server-sent events
+//! stream so the bell updates without polling.
+//!
+//! [`InMemoryNotificationStore`] is the default and supports live updates.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::notifications::{
+//!     AdminNotification, InMemoryNotificationStore, NotificationKind, NotificationStore,
+//! };
+//!
+//! async fn example() {
+//!     let store = InMemoryNotificationStore::new();
+//!     store
+//!         .push(
+//!             AdminNotification::new("token-1", NotificationKind::ImportFinished, "Imported 120 rows")
+//!                 .link("/blog/article/"),
+//!         )
+//!         .await
+//!         .unwrap();
+//!
+//!     assert_eq!(store.unread_count("token-1").await.unwrap(), 1);
+//!     assert_eq!(store.mark_all_read("token-1").await.unwrap(), 1);
+//!     assert_eq!(store.unread_count("token-1").await.unwrap(), 0);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// How many notifications are kept per recipient when no limit is configured.
+pub const DEFAULT_MAX_PER_RECIPIENT: usize = 200;
+
+/// How many live updates a slow subscriber may fall behind before missing some.
+const LIVE_CHANNEL_CAPACITY: usize = 64;
+
+/// The kind of event a notification reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A data import finished.
+    ImportFinished,
+    /// A background admin action completed.
+    ActionCompleted,
+    /// The recipient was mentioned in a comment.
+    Mention,
+    /// A background export finished and is ready to download.
+    ExportReady,
+    /// Any other system event.
+    System,
+}
+
+/// A notification shown in an admin user's notification center.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminNotification {
+    /// Identifier assigned by the store when the notification is pushed.
+    pub id: u64,
+    /// The user the notification is for.
+    pub recipient: String,
+    /// What kind of event this is.
+    pub kind: NotificationKind,
+    /// Human-readable message.
+    pub message: String,
+    /// Optional admin URL the notification links to.
+    pub link: Option<String>,
+    /// When the notification was created.
+    pub created_at: DateTime<Utc>,
+    /// Whether the recipient has read it.
+    pub read: bool,
+}
+
+impl AdminNotification {
+    /// Creates an unread notification for `recipient`, created now.
+    pub fn new(recipient: &str, kind: NotificationKind, message: &str) -> Self {
+        Self {
+            id: 0,
+            recipient: recipient.to_string(),
+            kind,
+            message: message.to_string(),
+            link: None,
+            created_at: Utc::now(),
+            read: false,
+        }
+    }
+
+    /// Sets the URL the notification links to.
+    #[must_use]
+    pub fn link(mut self, link: &str) -> Self {
+        self.link = Some(link.to_string());
+        self
+    }
+}
+
+/// Trait for notification storage backends.
+///
+/// `recipient` identifies the user; notifications are never visible to other
+/// recipients.
+#[async_trait]
+pub trait NotificationStore: Send + Sync {
+    /// Stores a notification, assigning its id, and returns the stored copy.
+    async fn push(&self, notification: AdminNotification) -> Result<AdminNotification, String>;
+
+    /// Returns the recipient's notifications, newest first.
+    async fn list(
+        &self,
+        recipient: &str,
+        unread_only: bool,
+        limit: usize,
+    ) -> Result<Vec<AdminNotification>, String>;
+
+    /// Returns how many of the recipient's notifications are unread.
+    async fn unread_count(&self, recipient: &str) -> Result<usize, String>;
+
+    /// Marks one notification read, returning whether it exists.
+    async fn mark_read(&self, recipient: &str, id: u64) -> Result<bool, String>;
+
+    /// Marks all of the recipient's notifications read, returning how many
+    /// were unread.
+    async fn mark_all_read(&self, recipient: &str) -> Result<usize, String>;
+
+    /// Subscribes to notifications as they are pushed, for all recipients.
+    ///
+    /// Returns `None` if the store does not support live updates.
+    fn subscribe(&self) -> Option<broadcast::Receiver<AdminNotification>> {
+        None
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inbox {
+    next_id: u64,
+    by_recipient: HashMap<String, Vec<AdminNotification>>,
+}
+
+/// In-memory implementation of [`NotificationStore`].
+///
+/// Only the newest notifications per recipient are kept; older ones are
+/// dropped when a new one is pushed.
+#[derive(Debug, Clone)]
+pub struct InMemoryNotificationStore {
+    inbox: Arc<RwLock<Inbox>>,
+    sender: broadcast::Sender<AdminNotification>,
+    max_per_recipient: usize,
+}
+
+impl InMemoryNotificationStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
+        Self {
+            inbox: Arc::new(RwLock::new(Inbox::default())),
+            sender,
+            max_per_recipient: DEFAULT_MAX_PER_RECIPIENT,
+        }
+    }
+
+    /// Sets how many notifications are kept per recipient.
+    #[must_use]
+    pub const fn max_per_recipient(mut self, max: usize) -> Self {
+        self.max_per_recipient = max;
+        self
+    }
+
+    /// Returns the number of stored notifications across all recipients.
+    pub fn len(&self) -> usize {
+        self.inbox
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .by_recipient
+            .values()
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Returns `true` if no notifications are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for InMemoryNotificationStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NotificationStore for InMemoryNotificationStore {
+    async fn push(&self, mut notification: AdminNotification) -> Result<AdminNotification, String> {
+        let mut inbox = self.inbox.write().map_err(|e| e.to_string())?;
+        inbox.next_id += 1;
+        notification.id = inbox.next_id;
+        let notifications = inbox
+            .by_recipient
+            .entry(notification.recipient.clone())
+            .or_default();
+        notifications.push(notification.clone());
+        let overflow = notifications.len().saturating_sub(self.max_per_recipient);
+        notifications.drain(..overflow);
+        drop(inbox);
+        // Nobody listening is not an error.
+        let _ = self.sender.send(notification.clone());
+        Ok(notification)
+    }
+
+    async fn list(
+        &self,
+        recipient: &str,
+        unread_only: bool,
+        limit: usize,
+    ) -> Result<Vec<AdminNotification>, String> {
+        let inbox = self.inbox.read().map_err(|e| e.to_string())?;
+        Ok(inbox
+            .by_recipient
+            .get(recipient)
+            .map(|notifications| {
+                notifications
+                    .iter()
+                    .rev()
+                    .filter(|n| !unread_only || !n.read)
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn unread_count(&self, recipient: &str) -> Result<usize, String> {
+        let inbox = self.inbox.read().map_err(|e| e.to_string())?;
+        Ok(inbox
+            .by_recipient
+            .get(recipient)
+            .map_or(0, |notifications| {
+                notifications.iter().filter(|n| !n.read).count()
+            }))
+    }
+
+    async fn mark_read(&self, recipient: &str, id: u64) -> Result<bool, String> {
+        let mut inbox = self.inbox.write().map_err(|e| e.to_string())?;
+        let notification = inbox
+            .by_recipient
+            .get_mut(recipient)
+            .and_then(|notifications| notifications.iter_mut().find(|n| n.id == id));
+        let found = notification.map(|n| n.read = true).is_some();
+        drop(inbox);
+        Ok(found)
+    }
+
+    async fn mark_all_read(&self, recipient: &str) -> Result<usize, String> {
+        let mut inbox = self.inbox.write().map_err(|e| e.to_string())?;
+        let mut marked = 0;
+        for notification in inbox.by_recipient.get_mut(recipient).into_iter().flatten() {
+            if !notification.read {
+                notification.read = true;
+                marked += 1;
+            }
+        }
+        drop(inbox);
+        Ok(marked)
+    }
+
+    fn subscribe(&self) -> Option<broadcast::Receiver<AdminNotification>> {
+        Some(self.sender.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mention(recipient: &str, message: &str) -> AdminNotification {
+        AdminNotification::new(recipient, NotificationKind::Mention, message)
+    }
+
+    #[tokio::test]
+    async fn test_push_assigns_ids_and_lists_newest_first() {
+        let store = InMemoryNotificationStore::new();
+        let first = store.push(mention("alice", "first")).await.unwrap();
+        let second = store.push(mention("alice", "second")).await.unwrap();
+        store.push(mention("bob", "other")).await.unwrap();
+        assert_eq!((first.id, second.id), (1, 2));
+
+        let listed = store.list("alice", false, 10).await.unwrap();
+        let messages: Vec<&str> = listed.iter().map(|n| n.message.as_str()).collect();
+        assert_eq!(messages, ["second", "first"]);
+        assert_eq!(store.list("alice", false, 1).await.unwrap().len(), 1);
+        assert!(store.list("carol", false, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_is_scoped_to_recipient() {
+        let store = InMemoryNotificationStore::new();
+        let n = store.push(mention("alice", "hi")).await.unwrap();
+        store.push(mention("alice", "again")).await.unwrap();
+
+        assert!(!store.mark_read("bob", n.id).await.unwrap());
+        assert!(store.mark_read("alice", n.id).await.unwrap());
+        assert_eq!(store.unread_count("alice").await.unwrap(), 1);
+        assert_eq!(
+            store.list("alice", true, 10).await.unwrap()[0].message,
+            "again"
+        );
+
+        assert_eq!(store.mark_all_read("alice").await.unwrap(), 1);
+        assert_eq!(store.unread_count("alice").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_max_per_recipient_drops_oldest() {
+        let store = InMemoryNotificationStore::new().max_per_recipient(2);
+        for message in ["a", "b", "c"] {
+            store.push(mention("alice", message)).await.unwrap();
+        }
+        assert_eq!(store.len(), 2);
+        let listed = store.list("alice", false, 10).await.unwrap();
+        assert_eq!(listed.last().unwrap().message, "b");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receives_pushed_notifications() {
+        let store = InMemoryNotificationStore::new();
+        let mut rx = store.subscribe().unwrap();
+        store.push(mention("alice", "live")).await.unwrap();
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.message, "live");
+        assert_eq!(received.id, 1);
+    }
+}
@@ -0,0 +1,657 @@
+//! Group and user permission matrix.
+//!
+//! Editing permissions one object at a time takes a request per group or
+//! user. The admin's `/permissions/` endpoint instead returns the whole
+//! groups × permissions grid, with each user's groups and directly granted
+//! permissions (their overrides), in one [`PermissionMatrix`], and accepts a
+//! list of [`PermissionChange`]s to apply.
+//!
+//! A batch of changes is applied all or nothing: if any change names an
+//! unknown group, user or permission, none are applied and every problem is
+//! reported. Changes that are already in effect are accepted and skipped.
+//!
+//! Permissions are named `"app_label.codename"`, as in
+//! [`has_perm`](django_rs_auth::permissions::has_perm).
+//!
+//! [`BackendPermissionStore`] edits the users of an
+//! [`AuthBackend`](django_rs_auth::backends::AuthBackend), so granting a user
+//! a permission changes what [`has_perm`](django_rs_auth::permissions::has_perm)
+//! returns for them. [`InMemoryPermissionStore`] keeps its own copies and
+//! suits tests.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use django_rs_admin::permission_matrix::{
+//!     BackendPermissionStore, PermissionChange, PermissionStore,
+//! };
+//! use django_rs_auth::backends::{AuthBackend, ModelBackend};
+//! use django_rs_auth::permissions::{generate_default_permissions, has_perm};
+//! use django_rs_auth::user::AbstractUser;
+//!
+//! async fn example() {
+//!     let backend = Arc::new(ModelBackend::new());
+//!     backend.add_user(AbstractUser::new("alice")).await;
+//!     let store = BackendPermissionStore::new(backend.clone())
+//!         .with_permissions(generate_default_permissions("blog", "post"));
+//!
+//!     let applied = store
+//!         .apply(&[PermissionChange::user("alice", "blog.change_post", true)])
+//!         .await
+//!         .unwrap();
+//!     assert_eq!(applied, 1);
+//!     let alice = backend.get_user("alice").await.unwrap().unwrap();
+//!     assert!(has_perm(&alice, "blog.change_post"));
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use django_rs_auth::backends::AuthBackend;
+use django_rs_auth::permissions::{Group, Permission};
+use django_rs_auth::user::AbstractUser;
+use serde::{Deserialize, Serialize};
+
+/// A group's row of the matrix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupPermissions {
+    /// The group name.
+    pub name: String,
+    /// The permissions granted to the group.
+    pub permissions: Vec<String>,
+}
+
+/// A user's groups and directly granted permissions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserPermissions {
+    /// The username.
+    pub username: String,
+    /// Whether the user has every permission regardless of the matrix.
+    pub is_superuser: bool,
+    /// The groups the user belongs to.
+    pub groups: Vec<String>,
+    /// The permissions granted to the user directly.
+    pub permissions: Vec<String>,
+}
+
+/// Every permission with the groups and users holding it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionMatrix {
+    /// The matrix columns, sorted by content type and codename. Rows name
+    /// them by [`permission_name`].
+    pub permissions: Vec<Permission>,
+    /// One row per group, sorted by name.
+    pub groups: Vec<GroupPermissions>,
+    /// One row per user, sorted by username.
+    pub users: Vec<UserPermissions>,
+}
+
+/// Whether a [`PermissionChange`] targets a group or a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixSubject {
+    /// A group's permissions.
+    Group,
+    /// A user's directly granted permissions.
+    User,
+}
+
+/// Grants or revokes one permission of a group or user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionChange {
+    /// Whether `name` is a group or a username.
+    pub subject: MatrixSubject,
+    /// The group name or username.
+    pub name: String,
+    /// The permission, as `"app_label.codename"`.
+    pub permission: String,
+    /// `true` to grant the permission, `false` to revoke it.
+    pub granted: bool,
+}
+
+impl PermissionChange {
+    /// Returns a change to a group's permissions.
+    pub fn group(name: &str, permission: &str, granted: bool) -> Self {
+        Self {
+            subject: MatrixSubject::Group,
+            name: name.to_string(),
+            permission: permission.to_string(),
+            granted,
+        }
+    }
+
+    /// Returns a change to a user's directly granted permissions.
+    pub fn user(username: &str, permission: &str, granted: bool) -> Self {
+        Self {
+            subject: MatrixSubject::User,
+            name: username.to_string(),
+            permission: permission.to_string(),
+            granted,
+        }
+    }
+}
+
+/// Why a batch of changes was not applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixUpdateError {
+    /// Some changes were invalid; one message per problem.
+    Invalid(Vec<String>),
+    /// The store failed.
+    Store(String),
+}
+
+impl fmt::Display for MatrixUpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(errors) => write!(f, "Invalid changes: {}", errors.join("; ")),
+            Self::Store(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for MatrixUpdateError {}
+
+/// Trait for storage backends of group and user permissions.
+#[async_trait]
+pub trait PermissionStore: Send + Sync {
+    /// Returns the current matrix.
+    async fn matrix(&self) -> Result<PermissionMatrix, String>;
+
+    /// Applies `changes` atomically, returning how many changed anything.
+    ///
+    /// Implementations must apply either every change or none.
+    async fn apply(&self, changes: &[PermissionChange]) -> Result<usize, MatrixUpdateError>;
+}
+
+/// Returns the `"app_label.codename"` name of a permission whose content
+/// type is `"app_label.model"`.
+pub fn permission_name(permission: &Permission) -> String {
+    let app_label = permission
+        .content_type
+        .split('.')
+        .next()
+        .unwrap_or_default();
+    format!("{app_label}.{}", permission.codename)
+}
+
+#[derive(Debug, Default)]
+struct MatrixState {
+    permissions: Vec<Permission>,
+    groups: Vec<Group>,
+    users: Vec<AbstractUser>,
+}
+
+impl MatrixState {
+    fn find_permission(&self, name: &str) -> Option<&Permission> {
+        self.permissions.iter().find(|p| permission_name(p) == name)
+    }
+
+    /// Returns the problems with `change`, if any.
+    fn check(&self, change: &PermissionChange) -> Option<String> {
+        let known_subject = match change.subject {
+            MatrixSubject::Group => self.groups.iter().any(|g| g.name == change.name),
+            MatrixSubject::User => self.users.iter().any(|u| u.username == change.name),
+        };
+        if !known_subject {
+            let subject = match change.subject {
+                MatrixSubject::Group => "Group",
+                MatrixSubject::User => "User",
+            };
+            return Some(format!("{subject} '{}' does not exist", change.name));
+        }
+        if self.find_permission(&change.permission).is_none() {
+            return Some(format!("Permission '{}' does not exist", change.permission));
+        }
+        None
+    }
+
+    /// Applies a checked change, returning whether it changed anything.
+    fn apply(&mut self, change: &PermissionChange) -> bool {
+        match change.subject {
+            MatrixSubject::Group => {
+                let permission = self
+                    .find_permission(&change.permission)
+                    .cloned()
+                    .expect("change was checked");
+                let group = self
+                    .groups
+                    .iter_mut()
+                    .find(|g| g.name == change.name)
+                    .expect("change was checked");
+                let held = group.permissions.contains(&permission);
+                if change.granted && !held {
+                    group.add_permission(permission);
+                } else if !change.granted && held {
+                    group.permissions.retain(|p| *p != permission);
+                } else {
+                    return false;
+                }
+                true
+            }
+            MatrixSubject::User => {
+                let user = self
+                    .users
+                    .iter_mut()
+                    .find(|u| u.username == change.name)
+                    .expect("change was checked");
+                let held = user.user_permissions.contains(&change.permission);
+                if change.granted && !held {
+                    user.user_permissions.push(change.permission.clone());
+                } else if !change.granted && held {
+                    user.user_permissions.retain(|p| *p != change.permission);
+                } else {
+                    return false;
+                }
+                true
+            }
+        }
+    }
+
+    fn to_matrix(&self) -> PermissionMatrix {
+        let mut permissions = self.permissions.clone();
+        permissions
+            .sort_by(|a, b| (&a.content_type, &a.codename).cmp(&(&b.content_type, &b.codename)));
+        let sorted = |names: Vec<String>| {
+            let mut names = names;
+            names.sort();
+            names
+        };
+        let mut groups: Vec<GroupPermissions> = self
+            .groups
+            .iter()
+            .map(|group| GroupPermissions {
+                name: group.name.clone(),
+                permissions: sorted(group.permissions.iter().map(permission_name).collect()),
+            })
+            .collect();
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut users: Vec<UserPermissions> = self
+            .users
+            .iter()
+            .map(|user| UserPermissions {
+                username: user.username.clone(),
+                is_superuser: user.is_superuser,
+                groups: sorted(user.groups.clone()),
+                permissions: sorted(user.user_permissions.clone()),
+            })
+            .collect();
+        users.sort_by(|a, b| a.username.cmp(&b.username));
+        PermissionMatrix {
+            permissions,
+            groups,
+            users,
+        }
+    }
+}
+
+/// In-memory implementation of [`PermissionStore`].
+///
+/// Changes are applied under a single write lock, so readers never see a
+/// batch half applied. The data is lost on restart.
+///
+/// This store keeps its own copies of the groups and users it is given:
+/// changes are not written back to them, so they are not seen by
+/// [`has_perm`](django_rs_auth::permissions::has_perm) for the users the site
+/// authenticates. It suits tests and demos; use [`BackendPermissionStore`] to
+/// edit real permissions.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryPermissionStore {
+    state: Arc<RwLock<MatrixState>>,
+}
+
+impl InMemoryPermissionStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds permissions to the matrix, skipping ones already present.
+    #[must_use]
+    pub fn with_permissions(self, permissions: Vec<Permission>) -> Self {
+        if let Ok(mut state) = self.state.write() {
+            for permission in permissions {
+                if !state.permissions.contains(&permission) {
+                    state.permissions.push(permission);
+                }
+            }
+        }
+        self
+    }
+
+    /// Adds a group, replacing any group with the same name.
+    #[must_use]
+    pub fn with_group(self, group: Group) -> Self {
+        if let Ok(mut state) = self.state.write() {
+            state.groups.retain(|g| g.name != group.name);
+            state.groups.push(group);
+        }
+        self
+    }
+
+    /// Adds a user, replacing any user with the same username.
+    #[must_use]
+    pub fn with_user(self, user: AbstractUser) -> Self {
+        if let Ok(mut state) = self.state.write() {
+            state.users.retain(|u| u.username != user.username);
+            state.users.push(user);
+        }
+        self
+    }
+
+    /// Returns the current groups, for permission checks with
+    /// [`has_perm_with_groups`](django_rs_auth::permissions::has_perm_with_groups).
+    pub fn groups(&self) -> Vec<Group> {
+        self.state
+            .read()
+            .map(|state| state.groups.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the user with the given username, with their current
+    /// permissions.
+    pub fn user(&self, username: &str) -> Option<AbstractUser> {
+        self.state
+            .read()
+            .ok()?
+            .users
+            .iter()
+            .find(|u| u.username == username)
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl PermissionStore for InMemoryPermissionStore {
+    async fn matrix(&self) -> Result<PermissionMatrix, String> {
+        self.state
+            .read()
+            .map(|state| state.to_matrix())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn apply(&self, changes: &[PermissionChange]) -> Result<usize, MatrixUpdateError> {
+        let mut state = self
+            .state
+            .write()
+            .map_err(|e| MatrixUpdateError::Store(e.to_string()))?;
+        let errors: Vec<String> = changes
+            .iter()
+            .filter_map(|change| state.check(change))
+            .collect();
+        if !errors.is_empty() {
+            return Err(MatrixUpdateError::Invalid(errors));
+        }
+        Ok(changes.iter().filter(|change| state.apply(change)).count())
+    }
+}
+
+/// [`PermissionStore`] over the users of an authentication backend.
+///
+/// User rows are read with [`AuthBackend::list_users`] and changed users are
+/// written back with [`AuthBackend::save_user`], so direct grants are seen by
+/// [`has_perm`](django_rs_auth::permissions::has_perm). Users have no group
+/// table to live in, so the groups are held by the store; check group grants
+/// with [`has_perm_with_groups`](django_rs_auth::permissions::has_perm_with_groups)
+/// against [`groups`](Self::groups), which names them by
+/// [`Permission::full_codename`].
+///
+/// Batches are validated before anything is written and applied one at a
+/// time. A backend that fails to save a user part way through leaves the
+/// users saved before it changed; the groups are only updated once every
+/// user is saved.
+#[derive(Clone)]
+pub struct BackendPermissionStore {
+    backend: Arc<dyn AuthBackend>,
+    permissions: Arc<RwLock<Vec<Permission>>>,
+    groups: Arc<RwLock<Vec<Group>>>,
+    /// Serializes batches, which await the backend between reading and
+    /// writing.
+    batch: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl fmt::Debug for BackendPermissionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendPermissionStore")
+            .field("backend", &"<dyn AuthBackend>")
+            .field("permissions", &self.permissions)
+            .field("groups", &self.groups)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BackendPermissionStore {
+    /// Creates a store editing the users of `backend`, with no permissions
+    /// or groups.
+    pub fn new(backend: Arc<dyn AuthBackend>) -> Self {
+        Self {
+            backend,
+            permissions: Arc::default(),
+            groups: Arc::default(),
+            batch: Arc::default(),
+        }
+    }
+
+    /// Adds permissions to the matrix, skipping ones already present.
+    #[must_use]
+    pub fn with_permissions(self, permissions: Vec<Permission>) -> Self {
+        if let Ok(mut existing) = self.permissions.write() {
+            for permission in permissions {
+                if !existing.contains(&permission) {
+                    existing.push(permission);
+                }
+            }
+        }
+        self
+    }
+
+    /// Adds a group, replacing any group with the same name.
+    #[must_use]
+    pub fn with_group(self, group: Group) -> Self {
+        if let Ok(mut groups) = self.groups.write() {
+            groups.retain(|g| g.name != group.name);
+            groups.push(group);
+        }
+        self
+    }
+
+    /// Returns the current groups, for permission checks with
+    /// [`has_perm_with_groups`](django_rs_auth::permissions::has_perm_with_groups).
+    pub fn groups(&self) -> Vec<Group> {
+        self.groups
+            .read()
+            .map(|groups| groups.clone())
+            .unwrap_or_default()
+    }
+
+    /// Loads the matrix state from the backend and the store.
+    async fn state(&self) -> Result<MatrixState, String> {
+        let users = self.backend.list_users().await.map_err(|e| e.to_string())?;
+        let permissions = self.permissions.read().map_err(|e| e.to_string())?.clone();
+        let groups = self.groups.read().map_err(|e| e.to_string())?.clone();
+        Ok(MatrixState {
+            permissions,
+            groups,
+            users,
+        })
+    }
+}
+
+#[async_trait]
+impl PermissionStore for BackendPermissionStore {
+    async fn matrix(&self) -> Result<PermissionMatrix, String> {
+        Ok(self.state().await?.to_matrix())
+    }
+
+    async fn apply(&self, changes: &[PermissionChange]) -> Result<usize, MatrixUpdateError> {
+        let _batch = self.batch.lock().await;
+        let mut state = self.state().await.map_err(MatrixUpdateError::Store)?;
+        let errors: Vec<String> = changes
+            .iter()
+            .filter_map(|change| state.check(change))
+            .collect();
+        if !errors.is_empty() {
+            return Err(MatrixUpdateError::Invalid(errors));
+        }
+        let before = state.users.clone();
+        let applied = changes.iter().filter(|change| state.apply(change)).count();
+        for user in &state.users {
+            let edited = before.iter().any(|old| {
+                old.username == user.username && old.user_permissions != user.user_permissions
+            });
+            if edited {
+                self.backend
+                    .save_user(user)
+                    .await
+                    .map_err(|e| MatrixUpdateError::Store(e.to_string()))?;
+            }
+        }
+        *self
+            .groups
+            .write()
+            .map_err(|e| MatrixUpdateError::Store(e.to_string()))? = state.groups;
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_auth::permissions::{generate_default_permissions, has_perm};
+
+    fn store() -> InMemoryPermissionStore {
+        let mut editors = Group::new("editors");
+        editors.add_permission(Permission::new("view_post", "Can view post", "blog.post"));
+        let mut alice = AbstractUser::new("alice");
+        alice.groups = vec!["editors".to_string()];
+        InMemoryPermissionStore::new()
+            .with_permissions(generate_default_permissions("blog", "post"))
+            .with_group(editors)
+            .with_group(Group::new("authors"))
+            .with_user(alice)
+    }
+
+    #[tokio::test]
+    async fn test_matrix() {
+        let matrix = store().matrix().await.unwrap();
+        let columns: Vec<String> = matrix.permissions.iter().map(permission_name).collect();
+        assert_eq!(
+            columns,
+            vec![
+                "blog.add_post",
+                "blog.change_post",
+                "blog.delete_post",
+                "blog.view_post"
+            ]
+        );
+        assert_eq!(matrix.groups[0].name, "authors");
+        assert_eq!(matrix.groups[1].permissions, vec!["blog.view_post"]);
+        assert_eq!(matrix.users[0].groups, vec!["editors"]);
+        assert!(matrix.users[0].permissions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_changes() {
+        let store = store();
+        let applied = store
+            .apply(&[
+                PermissionChange::group("editors", "blog.change_post", true),
+                PermissionChange::group("editors", "blog.view_post", true),
+                PermissionChange::group("editors", "blog.view_post", false),
+                PermissionChange::user("alice", "blog.delete_post", true),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(applied, 3);
+
+        let matrix = store.matrix().await.unwrap();
+        assert_eq!(matrix.groups[1].permissions, vec!["blog.change_post"]);
+        let alice = store.user("alice").unwrap();
+        assert!(has_perm(&alice, "blog.delete_post"));
+        assert!(!has_perm(&alice, "blog.add_post"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_batch_applies_nothing() {
+        let store = store();
+        let before = store.matrix().await.unwrap();
+        let err = store
+            .apply(&[
+                PermissionChange::group("editors", "blog.change_post", true),
+                PermissionChange::group("nobody", "blog.add_post", true),
+                PermissionChange::user("alice", "blog.publish_post", true),
+            ])
+            .await
+            .unwrap_err();
+        let MatrixUpdateError::Invalid(errors) = err else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(
+            errors,
+            vec![
+                "Group 'nobody' does not exist",
+                "Permission 'blog.publish_post' does not exist"
+            ]
+        );
+        assert_eq!(store.matrix().await.unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn test_backend_store_changes_has_perm() {
+        use django_rs_auth::backends::ModelBackend;
+        use django_rs_auth::permissions::has_perm_with_groups;
+
+        let backend = Arc::new(ModelBackend::new());
+        let mut alice = AbstractUser::new("alice");
+        alice.groups = vec!["editors".to_string()];
+        backend.add_user(alice).await;
+        let store = BackendPermissionStore::new(backend.clone())
+            .with_permissions(generate_default_permissions("blog", "post"))
+            .with_group(Group::new("editors"));
+
+        let applied = store
+            .apply(&[
+                PermissionChange::user("alice", "blog.delete_post", true),
+                PermissionChange::group("editors", "blog.change_post", true),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(applied, 2);
+        let alice = backend.get_user("alice").await.unwrap().unwrap();
+        assert!(has_perm(&alice, "blog.delete_post"));
+        assert!(!has_perm(&alice, "blog.change_post"));
+        assert!(has_perm_with_groups(
+            &alice,
+            "blog.post.change_post",
+            &store.groups()
+        ));
+        let matrix = store.matrix().await.unwrap();
+        assert_eq!(matrix.users[0].permissions, vec!["blog.delete_post"]);
+
+        let err = store
+            .apply(&[
+                PermissionChange::user("alice", "blog.delete_post", false),
+                PermissionChange::user("bob", "blog.add_post", true),
+            ])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MatrixUpdateError::Invalid(_)));
+        let alice = backend.get_user("alice").await.unwrap().unwrap();
+        assert!(has_perm(&alice, "blog.delete_post"));
+    }
+
+    #[test]
+    fn test_change_serialization() {
+        let change: PermissionChange = serde_json::from_str(
+            r#"{"subject": "user", "name": "alice", "permission": "blog.add_post", "granted": false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            change,
+            PermissionChange::user("alice", "blog.add_post", false)
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Print-friendly rendering of admin objects.
+//!
+//! The admin site exposes `GET /:app/:model/:pk/print/`, which renders a
+//! single object through a DTL template to a standalone HTML page suitable
+//! for printing, e.g. an invoice or a report. A model picks its template with
+//! [`ModelAdmin::print_template`]; without one, a built-in layout lists the
+//! object's fields in a table.
+//!
+//! Templates receive the following context:
+//!
+//! - `object` - the object's data as returned by the detail endpoint
+//! - `fields` - a list of `{name, label, value}` entries in schema order,
+//!   skipping excluded fields
+//! - `opts` - `app_label`, `model_name`, `verbose_name` and `model_key`
+//! - `generated_at` - the RFC 3339 time the page was rendered
+//!
+//! With the `pdf` feature, `?format=pdf` converts the HTML to PDF using a
+//! headless browser (see [`PdfRenderer`]).
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::model_admin::ModelAdmin;
+//! use django_rs_admin::print::render_print_html;
+//! use django_rs_template::engine::Engine;
+//!
+//! let engine = Engine::new();
+//! engine.add_string_template("invoice.html", "Invoice #{{ object.number }}");
+//! let admin = ModelAdmin::new("billing", "invoice").print_template("invoice.html");
+//!
+//! let object = serde_json::json!({"id": 1, "number": "2026-0042"});
+//! let html = render_print_html(&engine, &admin, &object).unwrap();
+//! assert_eq!(html, "Invoice #2026-0042");
+//! ```
+
+use std::collections::HashMap;
+
+use django_rs_core::DjangoError;
+use django_rs_template::context::{Context, ContextValue};
+use django_rs_template::engine::Engine;
+
+use crate::model_admin::ModelAdmin;
+
+/// Name under which the built-in print layout is registered.
+pub const DEFAULT_PRINT_TEMPLATE_NAME: &str = "admin/print_object.html";
+
+/// The built-in print layout: a title and a table of all fields.
+pub const DEFAULT_PRINT_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{ opts.verbose_name }} {{ object.id }}</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #ccc; padding: 0.4em; text-align: left; vertical-align: top; }
+th { width: 30%; }
+@media print { body { margin: 0; } }
+</style>
+</head>
+<body>
+<h1>{{ opts.verbose_name }} {{ object.id }}</h1>
+<table>
+{% for field in fields %}<tr><th>{{ field.label }}</th><td>{{ field.value }}</td></tr>
+{% endfor %}</table>
+<p><small>Generated {{ generated_at }}</small></p>
+</body>
+</html>
+"#;
+
+/// Builds the template context for printing `object`.
+pub fn print_context(admin: &ModelAdmin, object: &serde_json::Value) -> Context {
+    let mut fields: Vec<ContextValue> = Vec::new();
+    let mut push_field = |name: &str, label: &str, value: &serde_json::Value| {
+        let display = match value {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        fields.push(ContextValue::from(HashMap::from([
+            ("name".to_string(), ContextValue::from(name)),
+            ("label".to_string(), ContextValue::from(label)),
+            ("value".to_string(), ContextValue::from(display)),
+        ])));
+    };
+    let visible = |name: &str| !admin.exclude.iter().any(|e| e == name);
+    if admin.fields_schema.is_empty() {
+        if let Some(map) = object.as_object() {
+            for (name, value) in map.iter().filter(|(name, _)| visible(name)) {
+                push_field(name, &name.replace('_', " "), value);
+            }
+        }
+    } else {
+        for field in admin.fields_schema.iter().filter(|f| visible(&f.name)) {
+            let value = object.get(&field.name).unwrap_or(&serde_json::Value::Null);
+            push_field(&field.name, &field.label, value);
+        }
+    }
+
+    let mut context = Context::new();
+    context.set("object", ContextValue::from(object.clone()));
+    context.set("fields", ContextValue::List(fields));
+    context.set(
+        "opts",
+        ContextValue::from(HashMap::from([
+            ("app_label".to_string(), admin.app_label.clone()),
+            ("model_name".to_string(), admin.model_name.clone()),
+            ("verbose_name".to_string(), admin.verbose_name.clone()),
+            ("model_key".to_string(), admin.model_key()),
+        ])),
+    );
+    context.set(
+        "generated_at",
+        ContextValue::from(chrono::Utc::now().to_rfc3339()),
+    );
+    context
+}
+
+/// Renders `object` to standalone HTML with the model's print template.
+///
+/// Falls back to [`DEFAULT_PRINT_TEMPLATE`] when the model has no print
+/// template, registering it on `engine` if needed.
+#[allow(clippy::result_large_err)]
+pub fn render_print_html(
+    engine: &Engine,
+    admin: &ModelAdmin,
+    object: &serde_json::Value,
+) -> Result<String, DjangoError> {
+    let mut context = print_context(admin, object);
+    let name = admin.print_template.as_deref().unwrap_or_else(|| {
+        if engine.get_template(DEFAULT_PRINT_TEMPLATE_NAME).is_err() {
+            engine.add_string_template(DEFAULT_PRINT_TEMPLATE_NAME, DEFAULT_PRINT_TEMPLATE);
+        }
+        DEFAULT_PRINT_TEMPLATE_NAME
+    });
+    engine.render_to_string(name, &mut context)
+}
+
+/// Converts HTML to PDF by printing it with a headless browser.
+///
+/// By default this runs `chromium --headless --print-to-pdf`; any program
+/// accepting the same flags, such as `google-chrome`, can be configured.
+#[cfg(feature = "pdf")]
+#[derive(Debug, Clone)]
+pub struct PdfRenderer {
+    program: String,
+    args: Vec<String>,
+}
+
+#[cfg(feature = "pdf")]
+impl PdfRenderer {
+    /// Creates a renderer that runs the given browser executable.
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: vec![
+                "--headless".to_string(),
+                "--disable-gpu".to_string(),
+                "--no-pdf-header-footer".to_string(),
+            ],
+        }
+    }
+
+    /// Adds an extra command-line argument, e.g. `--no-sandbox`.
+    #[must_use]
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Renders `html` to PDF bytes.
+    ///
+    /// The page and the PDF are written to a private temporary directory
+    /// with a random name, which is removed when rendering finishes or fails.
+    #[allow(clippy::result_large_err)]
+    pub async fn render(&self, html: &str) -> Result<Vec<u8>, DjangoError> {
+        let dir = tempfile::Builder::new()
+            .prefix("django-rs-print-")
+            .tempdir()?;
+        let html_path = dir.path().join("page.html");
+        let pdf_path = dir.path().join("page.pdf");
+        tokio::fs::write(&html_path, html).await?;
+
+        let output = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .arg(format!("--print-to-pdf={}", pdf_path.display()))
+            .arg(format!("file://{}", html_path.display()))
+            .output()
+            .await
+            .map_err(|e| {
+                DjangoError::InternalServerError(format!("Cannot run '{}': {e}", self.program))
+            })?;
+        if !output.status.success() {
+            return Err(DjangoError::InternalServerError(format!(
+                "'{}' failed to render PDF: {}",
+                self.program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(tokio::fs::read(&pdf_path).await?)
+    }
+}
+
+#[cfg(feature = "pdf")]
+impl Default for PdfRenderer {
+    fn default() -> Self {
+        Self::new("chromium")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_admin::FieldSchema;
+
+    fn invoice() -> serde_json::Value {
+        serde_json::json!({"id": 7, "customer": "ACME <Corp>", "total": 12.5, "notes": null})
+    }
+
+    #[test]
+    fn test_default_template_lists_fields_in_schema_order() {
+        let admin = ModelAdmin::new("billing", "invoice")
+            .fields_schema(vec![
+                FieldSchema::new("customer", "CharField").label("Customer"),
+                FieldSchema::new("total", "DecimalField").label("Total"),
+                FieldSchema::new("notes", "TextField").label("Notes"),
+            ])
+            .exclude(vec!["notes"]);
+        let html = render_print_html(&Engine::new(), &admin, &invoice()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>invoice 7</h1>"));
+        assert!(html.contains("<th>Customer</th><td>ACME &lt;Corp&gt;</td>"));
+        assert!(html.contains("<th>Total</th><td>12.5</td>"));
+        assert!(!html.contains("Notes"));
+    }
+
+    #[test]
+    fn test_custom_print_template() {
+        let engine = Engine::new();
+        engine.add_string_template(
+            "billing/invoice_print.html",
+            "{{ opts.model_key }}: {{ object.customer }}{% for f in fields %} {{ f.name }}{% endfor %}",
+        );
+        let admin =
+            ModelAdmin::new("billing", "invoice").print_template("billing/invoice_print.html");
+        let html = render_print_html(&engine, &admin, &invoice()).unwrap();
+        assert_eq!(
+            html,
+            "billing.invoice: ACME &lt;Corp&gt; customer id notes total"
+        );
+    }
+
+    #[test]
+    fn test_missing_print_template_is_an_error() {
+        let admin = ModelAdmin::new("billing", "invoice").print_template("missing.html");
+        let err = render_print_html(&Engine::new(), &admin, &invoice()).unwrap_err();
+        assert!(matches!(err, DjangoError::TemplateDoesNotExist(_)));
+    }
+}
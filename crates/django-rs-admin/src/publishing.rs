@@ -0,0 +1,348 @@
+//! Scheduled publishing.
+//!
+//! Content such as articles or promotions often goes live at a set time and
+//! is withdrawn later. [`ModelAdmin::scheduled_publishing`] names the datetime
+//! fields holding those times, and the admin then:
+//!
+//! - computes a [`PublishStatus`] for every list result, under
+//!   `publish_status`;
+//! - filters the list by status with `?publish_status=scheduled`;
+//! - offers a `publish_now` bulk action ([`PublishNowAction`]) that makes
+//!   the selected objects live immediately.
+//!
+//! An object without a `publish_at` time is a draft. Timestamps are RFC 3339
+//! strings or naive datetimes, which are taken as UTC.
+//!
+//! # Examples
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use django_rs_admin::model_admin::ModelAdmin;
+//! use django_rs_admin::publishing::{publish_status, PublishStatus};
+//! use serde_json::json;
+//!
+//! let admin = ModelAdmin::new("blog", "article")
+//!     .scheduled_publishing("publish_at", Some("unpublish_at"));
+//! let config = admin.scheduled_publishing.as_ref().unwrap();
+//!
+//! let article = json!({"publish_at": "2024-06-01T09:00:00Z", "unpublish_at": null});
+//! let now = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+//! assert_eq!(publish_status(&article, config, now), PublishStatus::Scheduled);
+//! ```
+//!
+//! [`ModelAdmin::scheduled_publishing`]: crate::model_admin::ModelAdmin::scheduled_publishing
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use django_rs_core::DjangoError;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::{ActionResult, AdminAction};
+use crate::api::parse_datetime;
+use crate::db::AdminDbExecutor;
+use crate::model_admin::{FilterChoice, ModelAdmin};
+
+/// The list result key, filter parameter and column holding the computed
+/// status.
+pub const STATUS_FIELD: &str = "publish_status";
+
+/// The name of the bulk action publishing the selected objects.
+pub const PUBLISH_NOW_ACTION: &str = "publish_now";
+
+/// The datetime fields driving scheduled publishing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledPublishing {
+    /// The field holding when the object goes live; empty for drafts.
+    pub publish_at: String,
+    /// The field holding when the object is withdrawn, if the model has one.
+    pub unpublish_at: Option<String>,
+}
+
+/// The effective publishing state of an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishStatus {
+    /// No publish time is set.
+    Draft,
+    /// The publish time is in the future.
+    Scheduled,
+    /// Published and not yet withdrawn.
+    Live,
+    /// The unpublish time has passed.
+    Expired,
+}
+
+impl PublishStatus {
+    /// Every status, in lifecycle order.
+    pub const ALL: [Self; 4] = [Self::Draft, Self::Scheduled, Self::Live, Self::Expired];
+
+    /// Returns the status as used in results and query parameters.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Scheduled => "scheduled",
+            Self::Live => "live",
+            Self::Expired => "expired",
+        }
+    }
+
+    /// Returns the label shown in the status filter.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Draft => "Draft",
+            Self::Scheduled => "Scheduled",
+            Self::Live => "Live",
+            Self::Expired => "Expired",
+        }
+    }
+
+    /// Parses a status from its [`as_str`](Self::as_str) form.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == name)
+    }
+
+    /// Returns the status filter's choices.
+    pub fn filter_choices() -> Vec<FilterChoice> {
+        Self::ALL
+            .into_iter()
+            .map(|status| FilterChoice::new(status.label(), status.as_str()))
+            .collect()
+    }
+}
+
+/// Reads a datetime field of `obj`; missing, null and unparseable values
+/// give `None`.
+fn field_datetime(obj: &serde_json::Value, field: &str) -> Option<DateTime<Utc>> {
+    obj.get(field)
+        .and_then(serde_json::Value::as_str)
+        .and_then(parse_datetime)
+}
+
+/// Computes the publishing status of `obj` at `now`.
+pub fn publish_status(
+    obj: &serde_json::Value,
+    config: &ScheduledPublishing,
+    now: DateTime<Utc>,
+) -> PublishStatus {
+    let Some(publish_at) = field_datetime(obj, &config.publish_at) else {
+        return PublishStatus::Draft;
+    };
+    if publish_at > now {
+        return PublishStatus::Scheduled;
+    }
+    let expired = config
+        .unpublish_at
+        .as_deref()
+        .and_then(|field| field_datetime(obj, field))
+        .is_some_and(|unpublish_at| unpublish_at <= now);
+    if expired {
+        PublishStatus::Expired
+    } else {
+        PublishStatus::Live
+    }
+}
+
+/// Adds the `publish_status` of each result, if the admin has scheduled
+/// publishing.
+pub fn annotate_publish_status(
+    admin: &ModelAdmin,
+    results: &mut [serde_json::Value],
+    now: DateTime<Utc>,
+) {
+    let Some(config) = &admin.scheduled_publishing else {
+        return;
+    };
+    for obj in results {
+        let status = publish_status(obj, config, now);
+        if let Some(map) = obj.as_object_mut() {
+            map.insert(STATUS_FIELD.to_string(), status.as_str().into());
+        }
+    }
+}
+
+/// Makes the objects with the given primary keys live at `now`.
+///
+/// Each object not already live gets `publish_at` set to `now`, and its
+/// `unpublish_at` cleared if that has passed. Returns the number of objects
+/// changed.
+///
+/// # Errors
+///
+/// Returns an error if the admin has no scheduled publishing, or an object
+/// cannot be read or saved.
+pub async fn publish_now(
+    db: &dyn AdminDbExecutor,
+    admin: &ModelAdmin,
+    pks: &[String],
+    now: DateTime<Utc>,
+) -> Result<usize, String> {
+    let config = admin.scheduled_publishing.as_ref().ok_or_else(|| {
+        format!(
+            "Model '{}' does not use scheduled publishing",
+            admin.model_key()
+        )
+    })?;
+    let timestamp = now.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let mut changed = 0;
+    for pk in pks {
+        let obj = db.get_object(admin, pk).await?;
+        let status = publish_status(&obj, config, now);
+        if status == PublishStatus::Live {
+            continue;
+        }
+        let mut data = HashMap::new();
+        if status != PublishStatus::Expired {
+            data.insert(config.publish_at.clone(), timestamp.clone().into());
+        }
+        if let Some(field) = &config.unpublish_at {
+            if field_datetime(&obj, field).is_some_and(|unpublish_at| unpublish_at <= now) {
+                data.insert(field.clone(), serde_json::Value::Null);
+            }
+        }
+        db.update_object(admin, pk, &data).await?;
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// Bulk action publishing the selected objects now.
+///
+/// Registered by the [`AdminSite`](crate::site::AdminSite) for every model
+/// with scheduled publishing. See [`publish_now`].
+pub struct PublishNowAction {
+    admin: ModelAdmin,
+    db: Arc<dyn AdminDbExecutor>,
+}
+
+impl PublishNowAction {
+    /// Creates the action for the given model, saving through `db`.
+    pub fn new(admin: ModelAdmin, db: Arc<dyn AdminDbExecutor>) -> Self {
+        Self { admin, db }
+    }
+}
+
+impl std::fmt::Debug for PublishNowAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PublishNowAction")
+            .field("model", &self.admin.model_key())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AdminAction for PublishNowAction {
+    fn name(&self) -> &'static str {
+        PUBLISH_NOW_ACTION
+    }
+
+    fn description(&self) -> &'static str {
+        "Publish selected objects now"
+    }
+
+    async fn execute(
+        &self,
+        _model_key: &str,
+        selected_ids: &[String],
+    ) -> Result<ActionResult, DjangoError> {
+        let changed = publish_now(self.db.as_ref(), &self.admin, selected_ids, Utc::now())
+            .await
+            .map_err(DjangoError::DatabaseError)?;
+        Ok(ActionResult::success(
+            format!("Published {changed} of {} objects", selected_ids.len()),
+            changed,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryAdminDb;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn config() -> ScheduledPublishing {
+        ScheduledPublishing {
+            publish_at: "publish_at".to_string(),
+            unpublish_at: Some("unpublish_at".to_string()),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_publish_status() {
+        let status = |obj: serde_json::Value| publish_status(&obj, &config(), now());
+        assert_eq!(status(json!({"publish_at": null})), PublishStatus::Draft);
+        assert_eq!(status(json!({})), PublishStatus::Draft);
+        assert_eq!(
+            status(json!({"publish_at": "2024-05-02T00:00:00Z"})),
+            PublishStatus::Scheduled
+        );
+        assert_eq!(
+            status(json!({"publish_at": "2024-04-01T00:00:00", "unpublish_at": null})),
+            PublishStatus::Live
+        );
+        assert_eq!(
+            status(json!({
+                "publish_at": "2024-04-01T00:00:00Z",
+                "unpublish_at": "2024-05-01T12:00:00Z",
+            })),
+            PublishStatus::Expired
+        );
+    }
+
+    #[test]
+    fn test_publish_status_names() {
+        for status in PublishStatus::ALL {
+            assert_eq!(PublishStatus::from_name(status.as_str()), Some(status));
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                json!(status.as_str())
+            );
+        }
+        assert_eq!(PublishStatus::from_name("published"), None);
+    }
+
+    #[tokio::test]
+    async fn test_publish_now_action() {
+        let admin = ModelAdmin::new("blog", "article")
+            .scheduled_publishing("publish_at", Some("unpublish_at"));
+        let db = Arc::new(InMemoryAdminDb::new());
+        for (publish_at, unpublish_at) in [
+            (json!(null), json!(null)),
+            (json!("2999-01-01T00:00:00Z"), json!(null)),
+            (json!("2000-01-01T00:00:00Z"), json!("2001-01-01T00:00:00Z")),
+            (json!("2000-01-01T00:00:00Z"), json!(null)),
+        ] {
+            let data = HashMap::from([
+                ("publish_at".to_string(), publish_at),
+                ("unpublish_at".to_string(), unpublish_at),
+            ]);
+            db.create_object(&admin, &data).await.unwrap();
+        }
+
+        let action = PublishNowAction::new(admin.clone(), db.clone());
+        let ids: Vec<String> = (1..=4).map(|i| i.to_string()).collect();
+        let result = action.execute("blog.article", &ids).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.affected_count, 3);
+
+        let now = Utc::now();
+        for obj in db.all_objects("blog.article") {
+            assert_eq!(
+                publish_status(&obj, admin.scheduled_publishing.as_ref().unwrap(), now),
+                PublishStatus::Live
+            );
+        }
+
+        let plain = ModelAdmin::new("blog", "page");
+        assert!(publish_now(db.as_ref(), &plain, &ids, now).await.is_err());
+    }
+}
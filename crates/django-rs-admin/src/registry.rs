@@ -0,0 +1,305 @@
+//! Model registry shared by an admin site and its router.
+//!
+//! [`AdminSite::register`](crate::site::AdminSite::register) configures the
+//! models an admin starts with. Once
+//! [`into_axum_router`](crate::site::AdminSite::into_axum_router) has built
+//! the router, those models live in an [`AdminRegistry`], and every request
+//! looks its model up there. Models registered or unregistered through the
+//! registry — from Rust with a handle returned by
+//! [`AdminSite::registry`](crate::site::AdminSite::registry), or by a
+//! superuser through the `/registry/` endpoint — are served or dropped on
+//! the next request, without restarting the server.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_admin::model_admin::ModelAdmin;
+//! use django_rs_admin::site::AdminSite;
+//!
+//! let mut site = AdminSite::new("admin");
+//! site.register("blog.article", ModelAdmin::new("blog", "article"));
+//! let registry = site.registry();
+//! let router = site.into_axum_router();
+//!
+//! // Later, while the router is serving requests:
+//! registry.register("blog.comment", ModelAdmin::new("blog", "comment"));
+//! assert!(registry.is_registered("blog.article"));
+//! registry.unregister("blog.comment");
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::actions::ActionRegistry;
+use crate::db::AdminDbExecutor;
+use crate::model_admin::ModelAdmin;
+use crate::publishing::PublishNowAction;
+
+#[derive(Default)]
+struct Entries {
+    models: HashMap<String, Arc<ModelAdmin>>,
+    actions: HashMap<String, Arc<ActionRegistry>>,
+    /// The router's database, used by the actions added at registration.
+    db: Option<Arc<dyn AdminDbExecutor>>,
+}
+
+/// A thread-safe, cloneable handle to the models served by an admin router.
+///
+/// Clones share the same models. Lookups hand out `Arc`s, so a request that
+/// is already running keeps the configuration it started with when its
+/// model is replaced or unregistered.
+#[derive(Clone, Default)]
+pub struct AdminRegistry {
+    entries: Arc<RwLock<Entries>>,
+}
+
+impl AdminRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a model with the default actions, replacing any previous
+    /// registration under the same key.
+    ///
+    /// The `model_key` should be in `"app_label.model_name"` format.
+    pub fn register(&self, model_key: &str, admin: ModelAdmin) {
+        self.register_with_actions(model_key, admin, ActionRegistry::new());
+    }
+
+    /// Registers a model with the given actions, replacing any previous
+    /// registration under the same key.
+    ///
+    /// `publish_now` is added for models with scheduled publishing once the
+    /// registry is serving a router.
+    pub fn register_with_actions(
+        &self,
+        model_key: &str,
+        admin: ModelAdmin,
+        actions: ActionRegistry,
+    ) {
+        if let Err(e) = admin.check_lookup_field() {
+            tracing::error!("{e}");
+        }
+        self.insert(model_key, admin, actions);
+    }
+
+    /// Registers a model whose lookup field has already been checked.
+    pub(crate) fn insert(
+        &self,
+        model_key: &str,
+        mut admin: ModelAdmin,
+        mut actions: ActionRegistry,
+    ) {
+        let mut entries = self.entries.write().expect("admin registry lock poisoned");
+        // Annotation columns take the data type of the related field, so
+        // resolve them against the models already registered, and theirs
+        // against this one.
+        if admin
+            .annotations
+            .iter()
+            .any(|a| a.related_model == model_key)
+        {
+            let this = admin.clone();
+            admin.resolve_annotation_types(model_key, &this);
+        }
+        for (key, related) in &entries.models {
+            if key != model_key {
+                admin.resolve_annotation_types(key, related);
+            }
+        }
+        for (key, other) in &mut entries.models {
+            if key != model_key
+                && other
+                    .annotations
+                    .iter()
+                    .any(|a| a.related_model == model_key)
+            {
+                let mut updated = ModelAdmin::clone(other);
+                if updated.resolve_annotation_types(model_key, &admin) {
+                    *other = Arc::new(updated);
+                }
+            }
+        }
+        if admin.scheduled_publishing.is_some() {
+            if let Some(db) = &entries.db {
+                actions.register(Box::new(PublishNowAction::new(admin.clone(), db.clone())));
+            }
+        }
+        entries
+            .models
+            .insert(model_key.to_string(), Arc::new(admin));
+        entries
+            .actions
+            .insert(model_key.to_string(), Arc::new(actions));
+    }
+
+    /// Unregisters a model, returning its configuration if it was registered.
+    pub fn unregister(&self, model_key: &str) -> Option<Arc<ModelAdmin>> {
+        let mut entries = self.entries.write().expect("admin registry lock poisoned");
+        entries.actions.remove(model_key);
+        entries.models.remove(model_key)
+    }
+
+    /// Returns the `ModelAdmin` for a registered model, if any.
+    pub fn get(&self, model_key: &str) -> Option<Arc<ModelAdmin>> {
+        self.read().models.get(model_key).cloned()
+    }
+
+    /// Returns the action registry for a registered model, if any.
+    pub fn actions(&self, model_key: &str) -> Option<Arc<ActionRegistry>> {
+        self.read().actions.get(model_key).cloned()
+    }
+
+    /// Returns whether a model is registered.
+    pub fn is_registered(&self, model_key: &str) -> bool {
+        self.read().models.contains_key(model_key)
+    }
+
+    /// Returns the keys of all registered models, sorted.
+    pub fn model_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.read().models.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Returns all registered model admins, sorted by model key.
+    pub fn admins(&self) -> Vec<Arc<ModelAdmin>> {
+        let mut admins: Vec<(String, Arc<ModelAdmin>)> = self
+            .read()
+            .models
+            .iter()
+            .map(|(key, admin)| (key.clone(), admin.clone()))
+            .collect();
+        admins.sort_by(|a, b| a.0.cmp(&b.0));
+        admins.into_iter().map(|(_, admin)| admin).collect()
+    }
+
+    /// Returns the number of registered models.
+    pub fn len(&self) -> usize {
+        self.read().models.len()
+    }
+
+    /// Returns whether no models are registered.
+    pub fn is_empty(&self) -> bool {
+        self.read().models.is_empty()
+    }
+
+    /// Sets the database the router saves through, so later registrations
+    /// get the actions that need it.
+    pub(crate) fn bind_db(&self, db: Arc<dyn AdminDbExecutor>) {
+        self.entries
+            .write()
+            .expect("admin registry lock poisoned")
+            .db = Some(db);
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Entries> {
+        self.entries.read().expect("admin registry lock poisoned")
+    }
+}
+
+impl std::fmt::Debug for AdminRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminRegistry")
+            .field("models", &self.model_keys())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryAdminDb;
+    use crate::model_admin::{AnnotationFunction, ColumnDataType, FieldSchema, ListAnnotation};
+
+    #[test]
+    fn test_register_and_unregister() {
+        let registry = AdminRegistry::new();
+        registry.register("blog.article", ModelAdmin::new("blog", "article"));
+        registry.register("auth.user", ModelAdmin::new("auth", "user"));
+        assert_eq!(registry.model_keys(), vec!["auth.user", "blog.article"]);
+        assert!(registry.actions("blog.article").is_some());
+
+        let removed = registry.unregister("blog.article").unwrap();
+        assert_eq!(removed.model_name, "article");
+        assert!(!registry.is_registered("blog.article"));
+        assert!(registry.actions("blog.article").is_none());
+        assert!(registry.unregister("blog.article").is_none());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_clones_share_models() {
+        let registry = AdminRegistry::new();
+        let handle = registry.clone();
+        handle.register("blog.article", ModelAdmin::new("blog", "article"));
+        assert!(registry.is_registered("blog.article"));
+    }
+
+    #[test]
+    fn test_lookup_survives_replacement() {
+        let registry = AdminRegistry::new();
+        registry.register(
+            "blog.article",
+            ModelAdmin::new("blog", "article").list_per_page(10),
+        );
+        let admin = registry.get("blog.article").unwrap();
+        registry.register(
+            "blog.article",
+            ModelAdmin::new("blog", "article").list_per_page(50),
+        );
+        assert_eq!(admin.list_per_page, 10);
+        assert_eq!(registry.get("blog.article").unwrap().list_per_page, 50);
+    }
+
+    #[test]
+    fn test_annotation_types_follow_related_schema() {
+        let comment = ModelAdmin::new("blog", "comment").fields_schema(vec![
+            FieldSchema::new("article", "ForeignKey"),
+            FieldSchema::new("published_at", "DateTimeField"),
+            FieldSchema::new("likes", "IntegerField"),
+        ]);
+        let article = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "last_comment", "total_likes"])
+            .annotate(ListAnnotation::aggregate(
+                "last_comment",
+                AnnotationFunction::Max,
+                "blog.comment",
+                "article",
+                "published_at",
+            ))
+            .annotate(ListAnnotation::aggregate(
+                "total_likes",
+                AnnotationFunction::Sum,
+                "blog.comment",
+                "article",
+                "likes",
+            ));
+        let registry = AdminRegistry::new();
+        registry.register("blog.article", article);
+        let columns = registry.get("blog.article").unwrap().list_columns();
+        assert_eq!(columns[1].data_type, ColumnDataType::Decimal);
+
+        // Registering the related model later resolves the types too.
+        registry.register("blog.comment", comment);
+        let columns = registry.get("blog.article").unwrap().list_columns();
+        assert_eq!(columns[1].data_type, ColumnDataType::DateTime);
+        assert_eq!(columns[2].data_type, ColumnDataType::Integer);
+    }
+
+    #[test]
+    fn test_publish_now_needs_bound_db() {
+        let admin = ModelAdmin::new("blog", "post")
+            .scheduled_publishing("publish_at", Some("unpublish_at"));
+        let registry = AdminRegistry::new();
+        registry.register("blog.post", admin.clone());
+        let actions = registry.actions("blog.post").unwrap();
+        assert!(actions.get("publish_now").is_none());
+
+        registry.bind_db(Arc::new(InMemoryAdminDb::new()));
+        registry.register("blog.post", admin);
+        let actions = registry.actions("blog.post").unwrap();
+        assert!(actions.get("publish_now").is_some());
+    }
+}
@@ -0,0 +1,332 @@
+//! Read replica routing for admin queries.
+//!
+//! [`ReplicaRoutedAdminDb`] wraps two [`AdminDbExecutor`]s: list and detail
+//! reads go to a replica while creates, updates and deletes go to the primary.
+//! This mirrors a Django database router whose `db_for_read` returns a replica
+//! alias and `db_for_write` returns `"default"`.
+//!
+//! Replicas lag behind the primary, so a read issued right after a write may
+//! not see it. A staleness tolerance keeps reads of a recently written model
+//! on the primary until the replica is expected to have caught up. Replica
+//! errors fall back to the primary unless fallback is disabled.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use django_rs_admin::db::InMemoryAdminDb;
+//! use django_rs_admin::replica::ReplicaRoutedAdminDb;
+//!
+//! let db = ReplicaRoutedAdminDb::new(
+//!     Arc::new(InMemoryAdminDb::new()),
+//!     Arc::new(InMemoryAdminDb::new()),
+//! )
+//! .staleness_tolerance(Duration::from_secs(2));
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::db::{AdminDbExecutor, AdminListParams, AdminListResult};
+use crate::model_admin::ModelAdmin;
+
+/// An [`AdminDbExecutor`] that sends reads to a replica and writes to a primary.
+pub struct ReplicaRoutedAdminDb {
+    primary: Arc<dyn AdminDbExecutor>,
+    replica: Arc<dyn AdminDbExecutor>,
+    staleness_tolerance: Duration,
+    fallback_to_primary: bool,
+    /// When each model (by model key) was last written through this executor.
+    last_writes: Mutex<HashMap<String, Instant>>,
+}
+
+impl ReplicaRoutedAdminDb {
+    /// Creates a router over the given primary and replica executors.
+    ///
+    /// By default reads always go to the replica and fall back to the
+    /// primary when the replica returns an error.
+    pub fn new(primary: Arc<dyn AdminDbExecutor>, replica: Arc<dyn AdminDbExecutor>) -> Self {
+        Self {
+            primary,
+            replica,
+            staleness_tolerance: Duration::ZERO,
+            fallback_to_primary: true,
+            last_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long reads of a model stay on the primary after it is written.
+    ///
+    /// Set this to the expected replication lag so users see their own
+    /// changes immediately after saving.
+    #[must_use]
+    pub const fn staleness_tolerance(mut self, tolerance: Duration) -> Self {
+        self.staleness_tolerance = tolerance;
+        self
+    }
+
+    /// Sets whether a failed replica read is retried on the primary.
+    #[must_use]
+    pub const fn fallback_to_primary(mut self, fallback: bool) -> Self {
+        self.fallback_to_primary = fallback;
+        self
+    }
+
+    /// Returns the primary (write) executor.
+    pub fn primary(&self) -> &Arc<dyn AdminDbExecutor> {
+        &self.primary
+    }
+
+    /// Returns the replica (read) executor.
+    pub fn replica(&self) -> &Arc<dyn AdminDbExecutor> {
+        &self.replica
+    }
+
+    /// Returns `true` if reads of this model currently go to the primary
+    /// because it was written within the staleness tolerance.
+    pub fn reads_from_primary(&self, admin: &ModelAdmin) -> bool {
+        if self.staleness_tolerance.is_zero() {
+            return false;
+        }
+        let mut last_writes = self
+            .last_writes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let key = admin.model_key();
+        match last_writes.get(&key) {
+            Some(at) if at.elapsed() < self.staleness_tolerance => true,
+            Some(_) => {
+                last_writes.remove(&key);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn record_write(&self, admin: &ModelAdmin) {
+        if self.staleness_tolerance.is_zero() {
+            return;
+        }
+        self.last_writes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(admin.model_key(), Instant::now());
+    }
+}
+
+impl std::fmt::Debug for ReplicaRoutedAdminDb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplicaRoutedAdminDb")
+            .field("staleness_tolerance", &self.staleness_tolerance)
+            .field("fallback_to_primary", &self.fallback_to_primary)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AdminDbExecutor for ReplicaRoutedAdminDb {
+    async fn list_objects(
+        &self,
+        admin: &ModelAdmin,
+        params: &AdminListParams,
+    ) -> Result<AdminListResult, String> {
+        if self.reads_from_primary(admin) {
+            return self.primary.list_objects(admin, params).await;
+        }
+        match self.replica.list_objects(admin, params).await {
+            Err(_) if self.fallback_to_primary => self.primary.list_objects(admin, params).await,
+            result => result,
+        }
+    }
+
+    async fn get_object(&self, admin: &ModelAdmin, pk: &str) -> Result<serde_json::Value, String> {
+        if self.reads_from_primary(admin) {
+            return self.primary.get_object(admin, pk).await;
+        }
+        match self.replica.get_object(admin, pk).await {
+            Err(_) if self.fallback_to_primary => self.primary.get_object(admin, pk).await,
+            result => result,
+        }
+    }
+
+    async fn create_object(
+        &self,
+        admin: &ModelAdmin,
+        data: &HashMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        let result = self.primary.create_object(admin, data).await;
+        if result.is_ok() {
+            self.record_write(admin);
+        }
+        result
+    }
+
+    async fn update_object(
+        &self,
+        admin: &ModelAdmin,
+        pk: &str,
+        data: &HashMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        let result = self.primary.update_object(admin, pk, data).await;
+        if result.is_ok() {
+            self.record_write(admin);
+        }
+        result
+    }
+
+    async fn delete_object(&self, admin: &ModelAdmin, pk: &str) -> Result<bool, String> {
+        let result = self.primary.delete_object(admin, pk).await;
+        if result.is_ok() {
+            self.record_write(admin);
+        }
+        result
+    }
+
+    async fn get_relation(
+        &self,
+        admin: &ModelAdmin,
+        pk: &str,
+        field: &str,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        if self.reads_from_primary(admin) {
+            return self.primary.get_relation(admin, pk, field).await;
+        }
+        match self.replica.get_relation(admin, pk, field).await {
+            Err(_) if self.fallback_to_primary => self.primary.get_relation(admin, pk, field).await,
+            result => result,
+        }
+    }
+
+    async fn set_relation(
+        &self,
+        admin: &ModelAdmin,
+        pk: &str,
+        field: &str,
+        pks: &[serde_json::Value],
+    ) -> Result<(), String> {
+        let result = self.primary.set_relation(admin, pk, field, pks).await;
+        if result.is_ok() {
+            self.record_write(admin);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryAdminDb;
+    use serde_json::json;
+
+    fn admin() -> ModelAdmin {
+        ModelAdmin::new("blog", "article")
+    }
+
+    fn data(title: &str) -> HashMap<String, serde_json::Value> {
+        HashMap::from([("title".to_string(), json!(title))])
+    }
+
+    /// A replica that is always unavailable.
+    struct DownReplica;
+
+    #[async_trait]
+    impl AdminDbExecutor for DownReplica {
+        async fn list_objects(
+            &self,
+            _admin: &ModelAdmin,
+            _params: &AdminListParams,
+        ) -> Result<AdminListResult, String> {
+            Err("replica unavailable".to_string())
+        }
+
+        async fn get_object(
+            &self,
+            _admin: &ModelAdmin,
+            _pk: &str,
+        ) -> Result<serde_json::Value, String> {
+            Err("replica unavailable".to_string())
+        }
+
+        async fn create_object(
+            &self,
+            _admin: &ModelAdmin,
+            _data: &HashMap<String, serde_json::Value>,
+        ) -> Result<serde_json::Value, String> {
+            Err("replica unavailable".to_string())
+        }
+
+        async fn update_object(
+            &self,
+            _admin: &ModelAdmin,
+            _pk: &str,
+            _data: &HashMap<String, serde_json::Value>,
+        ) -> Result<serde_json::Value, String> {
+            Err("replica unavailable".to_string())
+        }
+
+        async fn delete_object(&self, _admin: &ModelAdmin, _pk: &str) -> Result<bool, String> {
+            Err("replica unavailable".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_go_to_primary_and_reads_to_replica() {
+        let primary = Arc::new(InMemoryAdminDb::new());
+        let replica = Arc::new(InMemoryAdminDb::new());
+        let db = ReplicaRoutedAdminDb::new(primary.clone(), replica.clone());
+        let admin = admin();
+
+        db.create_object(&admin, &data("Hello")).await.unwrap();
+        assert_eq!(primary.count("blog.article"), 1);
+        assert_eq!(replica.count("blog.article"), 0);
+
+        // The replica has not caught up, so the read sees nothing.
+        let result = db
+            .list_objects(&admin, &AdminListParams::default())
+            .await
+            .unwrap();
+        assert_eq!(result.response.count, 0);
+
+        replica.create_object(&admin, &data("Hello")).await.unwrap();
+        assert_eq!(db.get_object(&admin, "1").await.unwrap()["title"], "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_staleness_tolerance_reads_own_writes() {
+        let primary = Arc::new(InMemoryAdminDb::new());
+        let replica = Arc::new(InMemoryAdminDb::new());
+        let db = ReplicaRoutedAdminDb::new(primary, replica)
+            .staleness_tolerance(Duration::from_secs(60));
+        let admin = admin();
+        assert!(!db.reads_from_primary(&admin));
+
+        db.create_object(&admin, &data("Fresh")).await.unwrap();
+        assert!(db.reads_from_primary(&admin));
+        assert!(!db.reads_from_primary(&ModelAdmin::new("blog", "comment")));
+        assert_eq!(db.get_object(&admin, "1").await.unwrap()["title"], "Fresh");
+    }
+
+    #[tokio::test]
+    async fn test_replica_error_falls_back_to_primary() {
+        let primary = Arc::new(InMemoryAdminDb::new());
+        let admin = admin();
+        primary.create_object(&admin, &data("Hello")).await.unwrap();
+
+        let db = ReplicaRoutedAdminDb::new(primary.clone(), Arc::new(DownReplica));
+        let result = db
+            .list_objects(&admin, &AdminListParams::default())
+            .await
+            .unwrap();
+        assert_eq!(result.response.count, 1);
+
+        let strict =
+            ReplicaRoutedAdminDb::new(primary, Arc::new(DownReplica)).fallback_to_primary(false);
+        let err = strict.get_object(&admin, "1").await.unwrap_err();
+        assert_eq!(err, "replica unavailable");
+    }
+}
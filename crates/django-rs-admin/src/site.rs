@@ -4,24 +4,60 @@
 //! their [`ModelAdmin`] configurations. It generates an Axum router with all
 //! the REST API endpoints that the React admin frontend consumes.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::extract::{OriginalUri, Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::Router;
+use chrono::Utc;
+use django_rs_auth::backends::{AuthBackend, ModelBackend};
+use django_rs_auth::permissions::generate_default_permissions;
+use django_rs_core::flags::{Flag, FLAGS};
+use django_rs_core::DjangoError;
+use django_rs_http::pagination::PageUrls;
+use django_rs_http::urls::resolver::URLResolver;
+use django_rs_template::engine::Engine;
+use django_rs_template::thumbnails::ThumbnailBackend;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
-use crate::actions::ActionRegistry;
+use crate::action_jobs::{
+    run_action_job, ActionJob, ActionJobStatus, ActionJobStore, InMemoryActionJobStore,
+};
+use crate::actions::{ActionRegistry, AdminAction};
+use crate::admindocs::{AdminDocs, UrlDoc};
 use crate::api::{
-    build_model_index, CurrentUserResponse, LoginRequest, LoginResponse, ModelSchemaResponse,
+    build_model_index, humanize_datetimes, BulkActionRequest, BulkActionResponse,
+    CurrentUserResponse, DisplayContext, JsonListResponse, LoginRequest, LoginResponse,
+    ModelSchemaResponse, PermissionMatrixUpdate, PermissionMatrixUpdateResponse,
+    QuickCreateResponse, RegisterModelRequest, RelationChoice, SetRelationRequest,
 };
+use crate::comments::{AdminComment, CommentStore, InMemoryCommentStore};
 use crate::db::{AdminDbExecutor, AdminListParams, InMemoryAdminDb};
-use crate::log_entry::{InMemoryLogEntryStore, LogEntryStore};
-use crate::model_admin::ModelAdmin;
+use crate::drafts::{DraftStore, InMemoryDraftStore};
+use crate::export::{
+    CsvExport, ExportStorage, FileExportStorage, LogExport, LogExportFormat,
+    DEFAULT_EXPORT_ROW_THRESHOLD, EXPORT_CHUNK_SIZE,
+};
+use crate::flags::{flag_statuses, FlagStore, InMemoryFlagStore};
+use crate::log_entry::{InMemoryLogEntryStore, LogEntryFilter, LogEntryStore};
+use crate::maintenance::{
+    InMemoryMaintenanceStore, MaintenanceState, MaintenanceStore, ReadOnlyScope,
+};
+use crate::model_admin::{numbered_slug, ModelAdmin};
+use crate::model_counts::ModelCountCache;
+use crate::notifications::{
+    AdminNotification, InMemoryNotificationStore, NotificationKind, NotificationStore,
+};
+use crate::permission_matrix::{BackendPermissionStore, MatrixUpdateError, PermissionStore};
+#[cfg(feature = "pdf")]
+use crate::print::PdfRenderer;
+use crate::publishing::{annotate_publish_status, STATUS_FIELD};
+use crate::registry::AdminRegistry;
 
 /// The admin site, responsible for model registration and route generation.
 ///
@@ -54,6 +90,39 @@ pub struct AdminSite {
     db: Option<Arc<dyn AdminDbExecutor>>,
     /// Optional log entry store for audit trail.
     log_store: Option<Arc<dyn LogEntryStore>>,
+    /// Optional store for autosaved change-form drafts.
+    draft_store: Option<Arc<dyn DraftStore>>,
+    /// Optional store for the per-user notification center.
+    notification_store: Option<Arc<dyn NotificationStore>>,
+    /// Optional store for comment threads on objects.
+    comment_store: Option<Arc<dyn CommentStore>>,
+    /// Optional store for background action jobs.
+    action_job_store: Option<Arc<dyn ActionJobStore>>,
+    /// Optional storage for finished background exports.
+    export_storage: Option<Arc<dyn ExportStorage>>,
+    /// Exports with more rows than this run as a background job.
+    export_row_threshold: usize,
+    /// Optional store for the maintenance and read-only switches.
+    maintenance_store: Option<Arc<dyn MaintenanceStore>>,
+    /// Optional store for database-backed feature flags.
+    flag_store: Option<Arc<dyn FlagStore>>,
+    /// Optional store for group and user permissions.
+    permission_store: Option<Arc<dyn PermissionStore>>,
+    /// Optional backend whose users the default permission store edits.
+    auth_backend: Option<Arc<dyn AuthBackend>>,
+    /// Optional cache of the object counts shown in the sidebar.
+    model_counts: Option<ModelCountCache>,
+    /// Optional template engine for print views.
+    template_engine: Option<Arc<Engine>>,
+    /// Optional headless browser for PDF print views.
+    #[cfg(feature = "pdf")]
+    pdf_renderer: Option<PdfRenderer>,
+    /// Optional backend resolving image variant URLs.
+    thumbnail_backend: Option<Arc<dyn ThumbnailBackend>>,
+    /// URL patterns listed by the documentation endpoint.
+    url_docs: Vec<UrlDoc>,
+    /// The registry the router serves models from.
+    registry: AdminRegistry,
 }
 
 impl AdminSite {
@@ -69,6 +138,23 @@ impl AdminSite {
             action_registries: HashMap::new(),
             db: None,
             log_store: None,
+            draft_store: None,
+            notification_store: None,
+            comment_store: None,
+            action_job_store: None,
+            export_storage: None,
+            export_row_threshold: DEFAULT_EXPORT_ROW_THRESHOLD,
+            maintenance_store: None,
+            flag_store: None,
+            permission_store: None,
+            auth_backend: None,
+            model_counts: None,
+            template_engine: None,
+            #[cfg(feature = "pdf")]
+            pdf_renderer: None,
+            thumbnail_backend: None,
+            url_docs: Vec::new(),
+            registry: AdminRegistry::new(),
         }
     }
 
@@ -100,6 +186,145 @@ impl AdminSite {
         self
     }
 
+    /// Sets the store for autosaved change-form drafts.
+    #[must_use]
+    pub fn draft_store(mut self, store: Arc<dyn DraftStore>) -> Self {
+        self.draft_store = Some(store);
+        self
+    }
+
+    /// Sets the store backing the notification center.
+    ///
+    /// Keep a clone of the store to push notifications into it.
+    #[must_use]
+    pub fn notification_store(mut self, store: Arc<dyn NotificationStore>) -> Self {
+        self.notification_store = Some(store);
+        self
+    }
+
+    /// Sets the store for comment threads on objects of models with
+    /// [`ModelAdmin::comments`] enabled.
+    #[must_use]
+    pub fn comment_store(mut self, store: Arc<dyn CommentStore>) -> Self {
+        self.comment_store = Some(store);
+        self
+    }
+
+    /// Sets the store that tracks long-running actions run as background jobs.
+    #[must_use]
+    pub fn action_job_store(mut self, store: Arc<dyn ActionJobStore>) -> Self {
+        self.action_job_store = Some(store);
+        self
+    }
+
+    /// Sets the storage that background exports are written to.
+    ///
+    /// Defaults to a [`FileExportStorage`] under
+    /// `<temp dir>/django-rs-exports/<site name>`.
+    #[must_use]
+    pub fn export_storage(mut self, storage: Arc<dyn ExportStorage>) -> Self {
+        self.export_storage = Some(storage);
+        self
+    }
+
+    /// Sets how many rows an export may have before it runs as a background
+    /// job instead of streaming. Defaults to [`DEFAULT_EXPORT_ROW_THRESHOLD`].
+    #[must_use]
+    pub const fn export_row_threshold(mut self, rows: usize) -> Self {
+        self.export_row_threshold = rows;
+        self
+    }
+
+    /// Sets the store holding the maintenance and read-only switches.
+    ///
+    /// Share the store with a
+    /// [`MaintenanceModeMiddleware`](crate::maintenance::MaintenanceModeMiddleware)
+    /// so toggling it from the admin takes effect on the rest of the site.
+    #[must_use]
+    pub fn maintenance_store(mut self, store: Arc<dyn MaintenanceStore>) -> Self {
+        self.maintenance_store = Some(store);
+        self
+    }
+
+    /// Sets the store holding database-backed feature flags.
+    ///
+    /// Flags changed through the `/flags/` endpoint are saved here and
+    /// applied to the process-wide
+    /// [`FLAGS`](django_rs_core::flags::FLAGS) registry. Defaults to an
+    /// in-memory store.
+    #[must_use]
+    pub fn flag_store(mut self, store: Arc<dyn FlagStore>) -> Self {
+        self.flag_store = Some(store);
+        self
+    }
+
+    /// Sets the store behind the permission matrix endpoint.
+    ///
+    /// Defaults to a [`BackendPermissionStore`] over the
+    /// [`auth_backend`](Self::auth_backend), holding the add, change, delete
+    /// and view permissions of the registered models and no groups.
+    #[must_use]
+    pub fn permission_store(mut self, store: Arc<dyn PermissionStore>) -> Self {
+        self.permission_store = Some(store);
+        self
+    }
+
+    /// Sets the authentication backend whose users the default permission
+    /// store edits.
+    ///
+    /// Defaults to an empty [`ModelBackend`].
+    #[must_use]
+    pub fn auth_backend(mut self, backend: Arc<dyn AuthBackend>) -> Self {
+        self.auth_backend = Some(backend);
+        self
+    }
+
+    /// Sets the cache of the object counts shown by the index endpoint.
+    ///
+    /// Defaults to a [`ModelCountCache`] with the default expiry. Either way
+    /// the cache is connected to the `post_save` and `post_delete` signals.
+    #[must_use]
+    pub fn model_counts(mut self, cache: ModelCountCache) -> Self {
+        self.model_counts = Some(cache);
+        self
+    }
+
+    /// Sets the template engine that print views load templates from.
+    ///
+    /// Defaults to an engine with only the built-in print layout.
+    #[must_use]
+    pub fn template_engine(mut self, engine: Arc<Engine>) -> Self {
+        self.template_engine = Some(engine);
+        self
+    }
+
+    /// Sets the headless browser used to convert print views to PDF.
+    ///
+    /// Defaults to [`PdfRenderer::default`].
+    #[cfg(feature = "pdf")]
+    #[must_use]
+    pub fn pdf_renderer(mut self, renderer: PdfRenderer) -> Self {
+        self.pdf_renderer = Some(renderer);
+        self
+    }
+
+    /// Sets the backend that resolves the image variant URLs listed in
+    /// detail responses (see [`ModelAdmin::image_variants`]).
+    #[must_use]
+    pub fn thumbnail_backend(mut self, backend: Arc<dyn ThumbnailBackend>) -> Self {
+        self.thumbnail_backend = Some(backend);
+        self
+    }
+
+    /// Sets the URL configuration whose patterns the documentation endpoint lists.
+    ///
+    /// Without it, the endpoint documents models and template libraries only.
+    #[must_use]
+    pub fn docs_urls(mut self, resolver: &URLResolver) -> Self {
+        self.url_docs = UrlDoc::collect(resolver);
+        self
+    }
+
     /// Returns the site name.
     pub fn name(&self) -> &str {
         &self.name
@@ -119,6 +344,9 @@ impl AdminSite {
     ///
     /// The `model_key` should be in `"app_label.model_name"` format.
     pub fn register(&mut self, model_key: &str, admin: ModelAdmin) {
+        if let Err(e) = admin.check_lookup_field() {
+            tracing::error!("{e}");
+        }
         self.registered_models.insert(model_key.to_string(), admin);
         self.action_registries
             .insert(model_key.to_string(), ActionRegistry::new());
@@ -160,36 +388,153 @@ impl AdminSite {
         self.registered_models.contains_key(model_key)
     }
 
+    /// Returns a handle to the registry the router will serve models from.
+    ///
+    /// The models registered on the site move into it when the router is
+    /// built. From then on, models registered or unregistered through the
+    /// handle are served or dropped on the next request.
+    pub fn registry(&self) -> AdminRegistry {
+        self.registry.clone()
+    }
+
     /// Generates the Axum router with all admin API endpoints.
     ///
     /// The generated routes are:
     ///
     /// - `POST /login/` - Authenticate and get token
     /// - `POST /logout/` - Invalidate session
-    /// - `GET /` - List all registered models
+    /// - `GET /` - List all registered models with their cached object counts
     /// - `GET /me/` - Current user info
     /// - `GET /log/` - Recent log entries
     /// - `GET /log/:ct/:id/` - Log entries for a specific object
+    /// - `GET /log/export/` - Stream the log as CSV or JSON Lines (superusers only)
     /// - `GET /:app/:model/schema` - Model schema/introspection
     /// - `GET /:app/:model/` - List objects (paginated)
     /// - `POST /:app/:model/` - Create a new object
-    /// - `GET /:app/:model/:pk/` - Get single object
-    /// - `PUT /:app/:model/:pk/` - Update an object
+    /// - `POST /:app/:model/quick-create/` - Create from `quick_create_fields` only
+    /// - `GET /:app/:model/export/` - Stream the changelist as CSV, or queue a
+    ///   background export above the export row threshold
+    /// - `GET /exports/:name/` - Download a finished background export
+    /// - `GET /:app/:model/:pk/` - Get single object, with an `ETag`
+    /// - `PUT`/`PATCH /:app/:model/:pk/` - Update an object
     /// - `DELETE /:app/:model/:pk/` - Delete an object
+    /// - `GET /:app/:model/:pk/print/` - Printable HTML (`?format=pdf` with the `pdf` feature)
+    /// - `GET /:app/:model/:pk/draft/` - Get the current user's autosaved draft
+    /// - `PUT /:app/:model/:pk/draft/` - Autosave a draft (`pk` is `new` on add forms)
+    /// - `DELETE /:app/:model/:pk/draft/` - Discard a draft
+    /// - `GET /:app/:model/:pk/comments/` - The object's comment thread
+    /// - `POST /:app/:model/:pk/comments/` - Post a comment, notifying `@mentions`
+    /// - `DELETE /:app/:model/:pk/comments/:id/` - Delete one of your comments
     /// - `POST /:app/:model/action/` - Execute bulk action
+    ///   (`publish_now` is registered for models with scheduled publishing);
+    ///   long-running actions answer `202 Accepted` with a background job
+    /// - `GET /action-jobs/:id/` - Progress of one of your background actions
+    /// - `POST /action-jobs/:id/cancel/` - Stop a background action before its next object
+    /// - `GET /notifications/` - The current user's notifications and unread count
+    /// - `POST /notifications/:id/read/` - Mark a notification read
+    /// - `POST /notifications/read-all/` - Mark all notifications read
+    /// - `GET /notifications/stream/` - Server-sent events for new notifications
+    /// - `GET /maintenance/` - The maintenance and read-only switches
+    /// - `PUT /maintenance/` - Change the maintenance and read-only switches
+    /// - `GET /flags/` - Feature flags from settings and the flag store (superusers only)
+    /// - `PUT /flags/:name/` - Save a feature flag and apply it at once (superusers only)
+    /// - `DELETE /flags/:name/` - Delete a stored feature flag (superusers only)
+    /// - `GET /permissions/` - The groups × permissions matrix with user overrides
+    /// - `PATCH /permissions/` - Grant and revoke permissions, all or nothing
+    /// - `GET /registry/` - The registered models and their options (superusers only)
+    /// - `POST /registry/` - Register a model while the site runs (superusers only)
+    /// - `DELETE /registry/:app/:model/` - Unregister a model (superusers only)
+    /// - `GET /docs/` - Models, URL patterns and template tags and filters
+    ///
+    /// While read-only mode is on, the endpoints that create, change or delete
+    /// objects answer `503 Service Unavailable`. Updates and deletes with an
+    /// `If-Match` header that does not list the object's current `ETag`
+    /// answer `412 Precondition Failed`.
+    ///
+    /// Model routes are resolved against the site's [`registry`](Self::registry)
+    /// on every request, so models registered after the router is built are
+    /// served straight away.
+    #[allow(clippy::too_many_lines)]
     pub fn into_axum_router(self) -> Router {
         let db: Arc<dyn AdminDbExecutor> =
             self.db.unwrap_or_else(|| Arc::new(InMemoryAdminDb::new()));
         let log_store: Arc<dyn LogEntryStore> = self
             .log_store
             .unwrap_or_else(|| Arc::new(InMemoryLogEntryStore::new()));
+        let draft_store: Arc<dyn DraftStore> = self
+            .draft_store
+            .unwrap_or_else(|| Arc::new(InMemoryDraftStore::new()));
+        let notification_store: Arc<dyn NotificationStore> = self
+            .notification_store
+            .unwrap_or_else(|| Arc::new(InMemoryNotificationStore::new()));
+        let comment_store: Arc<dyn CommentStore> = self
+            .comment_store
+            .unwrap_or_else(|| Arc::new(InMemoryCommentStore::new()));
+        let action_job_store: Arc<dyn ActionJobStore> = self
+            .action_job_store
+            .unwrap_or_else(|| Arc::new(InMemoryActionJobStore::new()));
+        let export_storage: Arc<dyn ExportStorage> = self.export_storage.unwrap_or_else(|| {
+            let root = std::env::temp_dir()
+                .join("django-rs-exports")
+                .join(&self.name);
+            Arc::new(FileExportStorage::new(root))
+        });
+        let maintenance_store: Arc<dyn MaintenanceStore> = self
+            .maintenance_store
+            .unwrap_or_else(|| Arc::new(InMemoryMaintenanceStore::new()));
+        let flag_store: Arc<dyn FlagStore> = self
+            .flag_store
+            .unwrap_or_else(|| Arc::new(InMemoryFlagStore::new()));
+        let permission_store: Arc<dyn PermissionStore> =
+            self.permission_store.unwrap_or_else(|| {
+                let mut admins: Vec<&ModelAdmin> = self.registered_models.values().collect();
+                admins.sort_by_key(|admin| admin.model_key());
+                let permissions = admins
+                    .into_iter()
+                    .flat_map(|admin| {
+                        generate_default_permissions(&admin.app_label, &admin.model_name)
+                    })
+                    .collect();
+                let backend = self
+                    .auth_backend
+                    .unwrap_or_else(|| Arc::new(ModelBackend::new()));
+                Arc::new(BackendPermissionStore::new(backend).with_permissions(permissions))
+            });
+        let template_engine = self
+            .template_engine
+            .unwrap_or_else(|| Arc::new(Engine::new()));
+        let model_counts = self.model_counts.unwrap_or_default();
+        model_counts.connect_signals(&format!("django_rs_admin.model_counts.{}", self.name));
+
+        let registry = self.registry;
+        registry.bind_db(db.clone());
+        let mut action_registries = self.action_registries;
+        for (key, admin) in self.registered_models {
+            let actions = action_registries.remove(&key).unwrap_or_default();
+            registry.insert(&key, admin, actions);
+        }
 
         let shared = Arc::new(AdminSiteState {
-            registered_models: self.registered_models,
+            registry,
             url_prefix: self.url_prefix,
             name: self.name,
             db,
             log_store,
+            draft_store,
+            notification_store,
+            comment_store,
+            action_job_store,
+            export_storage,
+            export_row_threshold: self.export_row_threshold,
+            maintenance_store,
+            flag_store,
+            permission_store,
+            model_counts,
+            template_engine,
+            #[cfg(feature = "pdf")]
+            pdf_renderer: self.pdf_renderer.unwrap_or_default(),
+            thumbnail_backend: self.thumbnail_backend,
+            url_docs: self.url_docs,
         });
 
         Router::new()
@@ -198,12 +543,70 @@ impl AdminSite {
             .route("/", get(handle_index))
             .route("/me/", get(handle_me))
             .route("/log/", get(handle_log_recent))
+            .route("/log/export/", get(handle_log_export))
             .route("/log/{ct}/{id}/", get(handle_log_object))
+            .route("/notifications/", get(handle_notifications_list))
+            .route(
+                "/notifications/read-all/",
+                post(handle_notifications_read_all),
+            )
+            .route("/notifications/stream/", get(handle_notifications_stream))
+            .route("/notifications/{id}/read/", post(handle_notification_read))
+            .route(
+                "/maintenance/",
+                get(handle_maintenance_get).put(handle_maintenance_set),
+            )
+            .route("/flags/", get(handle_flags_list))
+            .route(
+                "/flags/{name}/",
+                axum::routing::put(handle_flag_set).delete(handle_flag_delete),
+            )
+            .route(
+                "/permissions/",
+                get(handle_permissions_get).patch(handle_permissions_update),
+            )
+            .route(
+                "/registry/",
+                get(handle_registry_list).post(handle_registry_register),
+            )
+            .route(
+                "/registry/{app}/{model}/",
+                axum::routing::delete(handle_registry_unregister),
+            )
+            .route("/exports/{name}/", get(handle_export_download))
+            .route("/action-jobs/{id}/", get(handle_action_job))
+            .route("/action-jobs/{id}/cancel/", post(handle_action_job_cancel))
+            .route("/docs/", get(handle_docs))
             .route("/{app}/{model}/schema", get(handle_schema))
             .route("/{app}/{model}/", get(handle_list).post(handle_create))
+            .route("/{app}/{model}/quick-create/", post(handle_quick_create))
+            .route("/{app}/{model}/export/", get(handle_export))
+            .route("/{app}/{model}/action/", post(handle_action))
             .route(
                 "/{app}/{model}/{pk}/",
-                get(handle_detail).put(handle_update).delete(handle_delete),
+                get(handle_detail)
+                    .put(handle_update)
+                    .patch(handle_update)
+                    .delete(handle_delete),
+            )
+            .route("/{app}/{model}/{pk}/print/", get(handle_print))
+            .route(
+                "/{app}/{model}/{pk}/relations/{field}/",
+                get(handle_relation_choices).patch(handle_set_relation),
+            )
+            .route(
+                "/{app}/{model}/{pk}/draft/",
+                get(handle_draft_get)
+                    .put(handle_draft_save)
+                    .delete(handle_draft_discard),
+            )
+            .route(
+                "/{app}/{model}/{pk}/comments/",
+                get(handle_comments_list).post(handle_comment_add),
+            )
+            .route(
+                "/{app}/{model}/{pk}/comments/{id}/",
+                axum::routing::delete(handle_comment_delete),
             )
             .with_state(shared)
     }
@@ -222,21 +625,43 @@ impl std::fmt::Debug for AdminSite {
 
 /// Shared state for Axum handlers.
 struct AdminSiteState {
-    registered_models: HashMap<String, ModelAdmin>,
+    registry: AdminRegistry,
     url_prefix: String,
     name: String,
     db: Arc<dyn AdminDbExecutor>,
     log_store: Arc<dyn LogEntryStore>,
+    draft_store: Arc<dyn DraftStore>,
+    notification_store: Arc<dyn NotificationStore>,
+    comment_store: Arc<dyn CommentStore>,
+    action_job_store: Arc<dyn ActionJobStore>,
+    export_storage: Arc<dyn ExportStorage>,
+    export_row_threshold: usize,
+    maintenance_store: Arc<dyn MaintenanceStore>,
+    flag_store: Arc<dyn FlagStore>,
+    permission_store: Arc<dyn PermissionStore>,
+    model_counts: ModelCountCache,
+    template_engine: Arc<Engine>,
+    #[cfg(feature = "pdf")]
+    pdf_renderer: PdfRenderer,
+    thumbnail_backend: Option<Arc<dyn ThumbnailBackend>>,
+    url_docs: Vec<UrlDoc>,
 }
 
 // ── Authentication Handlers ────────────────────────────────────────
 
+/// The prefix of the tokens issued by the development login, followed by the
+/// username the token belongs to.
+const DEV_TOKEN_PREFIX: &str = "django-rs-dev-token-";
+
+/// The token issued by the development login, whose user is a superuser.
+const DEV_ADMIN_TOKEN: &str = "django-rs-dev-token-admin";
+
 /// Handler for `POST /login/` - authenticate with username/password.
 async fn handle_login(axum::Json(payload): axum::Json<LoginRequest>) -> impl IntoResponse {
     // Hardcoded admin/admin for development
     if payload.username == "admin" && payload.password == "admin" {
         let response = LoginResponse {
-            token: "django-rs-dev-token-admin".to_string(),
+            token: DEV_ADMIN_TOKEN.to_string(),
             user: CurrentUserResponse {
                 username: "admin".to_string(),
                 email: "admin@example.com".to_string(),
@@ -264,10 +689,27 @@ async fn handle_logout() -> impl IntoResponse {
 
 // ── Index / Me Handlers ────────────────────────────────────────────
 
-/// Handler for `GET /` - list all registered models.
+/// Handler for `GET /` - list all registered models, with their cached
+/// object counts.
 async fn handle_index(State(state): State<Arc<AdminSiteState>>) -> impl IntoResponse {
-    let admins: Vec<&ModelAdmin> = state.registered_models.values().collect();
-    let index = build_model_index(&admins, &state.url_prefix);
+    let registered = state.registry.admins();
+    let admins: Vec<&ModelAdmin> = registered.iter().map(AsRef::as_ref).collect();
+    let mut index = build_model_index(&admins, &state.url_prefix);
+    let models = index
+        .apps
+        .iter_mut()
+        .flat_map(|app| {
+            let app_label = &app.app_label;
+            app.models
+                .iter_mut()
+                .map(move |model| (format!("{app_label}.{}", model.name), model))
+        })
+        .filter_map(|(key, model)| Some((state.registry.get(&key)?, model)));
+    let state = &state;
+    futures_util::future::join_all(models.map(|(admin, model)| async move {
+        model.count = state.model_counts.count(&state.db, &admin).await;
+    }))
+    .await;
     axum::Json(serde_json::json!({
         "site_name": state.name,
         "apps": index.apps,
@@ -313,6 +755,85 @@ async fn handle_log_object(
     axum::Json(serde_json::to_value(entries).unwrap_or_default())
 }
 
+/// Query parameters for the log export endpoint.
+#[derive(Debug, Deserialize)]
+struct LogExportQueryParams {
+    /// `csv` (the default) or `jsonl`.
+    format: Option<String>,
+    user: Option<u64>,
+    content_type: Option<String>,
+    /// RFC 3339 datetime or `YYYY-MM-DD`; inclusive.
+    since: Option<String>,
+    /// RFC 3339 datetime or `YYYY-MM-DD`; exclusive, but a date includes
+    /// that whole day.
+    until: Option<String>,
+}
+
+/// Handler for `GET /log/export/` - streams the log entries matching the
+/// query, oldest first.
+async fn handle_log_export(
+    State(state): State<Arc<AdminSiteState>>,
+    Query(query): Query<LogExportQueryParams>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    let bad_request = |error: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": error})),
+        )
+            .into_response()
+    };
+    let format_name = query.format.as_deref().unwrap_or("csv");
+    let Some(format) = LogExportFormat::parse(format_name) else {
+        return bad_request(format!("Unknown export format '{format_name}'"));
+    };
+    let mut filter = LogEntryFilter {
+        user_id: query.user,
+        content_type: query.content_type,
+        ..LogEntryFilter::default()
+    };
+    for (value, end_of_day, bound) in [
+        (query.since, false, &mut filter.since),
+        (query.until, true, &mut filter.until),
+    ] {
+        if let Some(value) = value {
+            match parse_log_bound(&value, end_of_day) {
+                Some(time) => *bound = Some(time),
+                None => return bad_request(format!("Invalid date '{value}'")),
+            }
+        }
+    }
+    let export = LogExport::new(state.log_store.clone(), filter, format);
+    (
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                format.content_type().to_string(),
+            ),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"admin_log.{}\"", format.extension()),
+            ),
+        ],
+        axum::body::Body::from_stream(export.into_stream()),
+    )
+        .into_response()
+}
+
+/// Parses an RFC 3339 datetime or a `YYYY-MM-DD` date, which is taken as the
+/// start of the day in UTC, or the start of the next day if `end_of_day`.
+fn parse_log_bound(value: &str, end_of_day: bool) -> Option<chrono::DateTime<Utc>> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let date = if end_of_day { date.succ_opt()? } else { date };
+    Some(date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
 // ── Schema / List / Detail / CRUD Handlers ─────────────────────────
 
 /// Query parameters for the list endpoint.
@@ -322,6 +843,24 @@ struct ListQueryParams {
     page_size: Option<usize>,
     search: Option<String>,
     ordering: Option<String>,
+    /// Multi-column ordering, e.g. `2,-0`; see [`ModelAdmin::resolve_list_ordering`].
+    o: Option<String>,
+    /// The user's offset from UTC in minutes east of UTC, for datetime display.
+    tz: Option<i32>,
+    /// The display language; defaults to the `Accept-Language` header.
+    lang: Option<String>,
+    /// Only objects in this publishing status, for scheduled publishing.
+    publish_status: Option<String>,
+}
+
+/// Query parameters for the create and update endpoints.
+#[derive(Debug, Deserialize)]
+struct InputQueryParams {
+    /// The language submitted values are formatted in. Without it, values
+    /// must be in their canonical forms; `Accept-Language` is not used, as
+    /// `"1.250"` means 1.25 to a client sending canonical JSON and 1250 in
+    /// German.
+    lang: Option<String>,
 }
 
 /// Handler for `GET /:app/:model/schema` - model schema introspection.
@@ -330,7 +869,7 @@ async fn handle_schema(
     Path((app, model)): Path<(String, String)>,
 ) -> impl IntoResponse {
     let key = format!("{app}.{model}");
-    state.registered_models.get(&key).map_or_else(
+    state.registry.get(&key).map_or_else(
         || {
             (
                 StatusCode::NOT_FOUND,
@@ -341,31 +880,55 @@ async fn handle_schema(
                 .into_response()
         },
         |admin| {
-            let schema = ModelSchemaResponse::from_model_admin(admin);
+            let schema = ModelSchemaResponse::from_model_admin(&admin);
             axum::Json(serde_json::to_value(schema).unwrap_or_default()).into_response()
         },
     )
 }
 
 /// Handler for `GET /:app/:model/` - list objects (paginated).
+///
+/// Datetime columns are humanized for the timezone in the `tz` parameter and
+/// the language in `lang` or the `Accept-Language` header.
 async fn handle_list(
     State(state): State<Arc<AdminSiteState>>,
     Path((app, model)): Path<(String, String)>,
     Query(query): Query<ListQueryParams>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let key = format!("{app}.{model}");
-    match state.registered_models.get(&key) {
+    match state.registry.get(&key) {
         Some(admin) => {
+            let display = DisplayContext::new(
+                query.tz.unwrap_or(0).saturating_mul(60),
+                request_language(query.lang.clone(), &headers),
+            );
+            let ordering =
+                match list_ordering(&admin, query.o.as_deref(), query.ordering.as_deref()) {
+                    Ok(ordering) => ordering,
+                    Err(error) => return invalid_ordering_response(&error),
+                };
             let params = AdminListParams {
                 page: query.page.unwrap_or(1),
                 page_size: query.page_size.unwrap_or(admin.list_per_page),
                 search: query.search,
-                ordering: query.ordering,
-                filters: HashMap::new(),
+                ordering,
+                filters: query
+                    .publish_status
+                    .into_iter()
+                    .map(|status| (STATUS_FIELD.to_string(), status))
+                    .collect(),
             };
-            match state.db.list_objects(admin, &params).await {
-                Ok(result) => axum::Json(serde_json::to_value(result.response).unwrap_or_default())
-                    .into_response(),
+            match state.db.list_objects(&admin, &params).await {
+                Ok(mut result) => {
+                    annotate_publish_status(&admin, &mut result.response.results, Utc::now());
+                    humanize_datetimes(&admin, &mut result.response.results, &display);
+                    for obj in &mut result.response.results {
+                        admin.mask_encrypted_values(obj);
+                    }
+                    paginated_json(result.response, &uri, &headers)
+                }
                 Err(e) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     axum::Json(serde_json::json!({"error": e})),
@@ -383,15 +946,143 @@ async fn handle_list(
     }
 }
 
+/// Returns a page of a list as JSON, linked to the other pages of the
+/// request through its `next` and `previous` fields and a `Link` header, as
+/// [`PaginatedResponse`](django_rs_http::pagination::PaginatedResponse) does.
+fn paginated_json(
+    response: JsonListResponse,
+    uri: &axum::http::Uri,
+    headers: &HeaderMap,
+) -> axum::response::Response {
+    let (mut parts, ()) = axum::http::Request::new(()).into_parts();
+    parts.uri = uri.clone();
+    parts.headers = headers.clone();
+    let request = django_rs_http::HttpRequest::from_axum(parts, Vec::new());
+    let page = response.into_page(&PageUrls::from_request(&request));
+    let mut http_response =
+        axum::Json(serde_json::to_value(&page).unwrap_or_default()).into_response();
+    if let Some(value) = page
+        .envelope
+        .links()
+        .header_value()
+        .and_then(|value| axum::http::HeaderValue::from_str(&value).ok())
+    {
+        http_response
+            .headers_mut()
+            .insert(axum::http::header::LINK, value);
+    }
+    http_response
+}
+
+/// Returns the preferred language of an `Accept-Language` header.
+fn accept_language(headers: &HeaderMap) -> Option<String> {
+    let header = headers.get("accept-language")?.to_str().ok()?;
+    let tag = header.split(',').next()?.split(';').next()?.trim();
+    (!tag.is_empty() && tag != "*").then(|| tag.to_string())
+}
+
+/// Returns the language of a request: the `lang` query parameter, then the
+/// `Accept-Language` header, then the active language.
+fn request_language(lang: Option<String>, headers: &HeaderMap) -> String {
+    lang.or_else(|| accept_language(headers))
+        .unwrap_or_else(django_rs_core::i18n::get_language)
+}
+
+/// Returns the ordering of a list request: the columns in `o` when given,
+/// otherwise the single `ordering` column if it is sortable.
+fn list_ordering(
+    admin: &ModelAdmin,
+    o: Option<&str>,
+    ordering: Option<&str>,
+) -> Result<Option<String>, String> {
+    o.map_or_else(
+        || Ok(ordering.and_then(|ordering| admin.resolve_ordering(ordering))),
+        |o| {
+            admin
+                .resolve_list_ordering(o)
+                .map(|fields| (!fields.is_empty()).then(|| fields.join(",")))
+        },
+    )
+}
+
+/// Returns a 400 response for a list request with an invalid ordering.
+fn invalid_ordering_response(error: &str) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(serde_json::json!({"error": error})),
+    )
+        .into_response()
+}
+
+/// Returns a 400 response listing the submitted values that could not be
+/// read in the request's language.
+fn invalid_input_response(errors: &HashMap<String, String>) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(serde_json::json!({
+            "error": "Invalid values",
+            "errors": errors,
+        })),
+    )
+        .into_response()
+}
+
+/// Resolves the object segment of an object URL to a primary key.
+///
+/// The segment is the primary key unless the model has a
+/// [`lookup_field`](ModelAdmin::lookup_field), in which case the object with
+/// that value is looked up. Unregistered models are left to the handler.
+#[allow(clippy::result_large_err)]
+async fn resolve_pk(
+    state: &AdminSiteState,
+    key: &str,
+    lookup: String,
+) -> Result<String, axum::response::Response> {
+    let Some(admin) = state.registry.get(key) else {
+        return Ok(lookup);
+    };
+    let Some(field) = &admin.lookup_field else {
+        return Ok(lookup);
+    };
+    let error = |status: StatusCode, error: String| {
+        (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+    };
+    if let Err(e) = admin.check_lookup_field() {
+        return Err(error(StatusCode::INTERNAL_SERVER_ERROR, e));
+    }
+    match state.db.find_pk(&admin, field, &lookup).await {
+        Ok(Some(pk)) => Ok(pk),
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            format!("Object with {field} '{lookup}' not found in '{key}'"),
+        )),
+        Err(e) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
 /// Handler for `GET /:app/:model/:pk/` - get single object.
+///
+/// The response carries an `ETag` that clients can send back in `If-Match`
+/// when updating or deleting the object.
 async fn handle_detail(
     State(state): State<Arc<AdminSiteState>>,
     Path((app, model, pk)): Path<(String, String, String)>,
 ) -> impl IntoResponse {
     let key = format!("{app}.{model}");
-    match state.registered_models.get(&key) {
-        Some(admin) => match state.db.get_object(admin, &pk).await {
-            Ok(obj) => axum::Json(obj).into_response(),
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.registry.get(&key) {
+        Some(admin) => match state.db.get_object(&admin, &pk).await {
+            Ok(mut obj) => {
+                let etag = object_etag(&obj);
+                admin.mask_encrypted_values(&mut obj);
+                if let Some(backend) = &state.thumbnail_backend {
+                    add_image_variants(backend.as_ref(), &admin, &mut obj);
+                }
+                ([(axum::http::header::ETAG, etag)], axum::Json(obj)).into_response()
+            }
             Err(e) => (
                 StatusCode::NOT_FOUND,
                 axum::Json(serde_json::json!({"error": e})),
@@ -408,16 +1099,166 @@ async fn handle_detail(
     }
 }
 
+/// Adds a `_variants` object with the variant URLs of each non-empty image
+/// field of `obj`.
+fn add_image_variants(
+    backend: &dyn ThumbnailBackend,
+    admin: &ModelAdmin,
+    obj: &mut serde_json::Value,
+) {
+    if admin.image_variants.is_empty() {
+        return;
+    }
+    let mut variants = serde_json::Map::new();
+    for field in admin
+        .fields_schema
+        .iter()
+        .filter(|f| f.field_type == "ImageField")
+    {
+        let Some(name) = obj.get(&field.name).and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+        let urls: serde_json::Map<String, serde_json::Value> = admin
+            .image_variants
+            .iter()
+            .filter_map(|spec| {
+                let url = backend.thumbnail_url(name, spec).ok()?;
+                Some((spec.suffix(), serde_json::Value::String(url)))
+            })
+            .collect();
+        variants.insert(field.name.clone(), serde_json::Value::Object(urls));
+    }
+    if let Some(map) = obj.as_object_mut() {
+        map.insert("_variants".to_string(), serde_json::Value::Object(variants));
+    }
+}
+
+/// Query parameters for the print endpoint.
+#[derive(Debug, Deserialize)]
+struct PrintQueryParams {
+    /// `html` (the default) or `pdf`.
+    format: Option<String>,
+}
+
+/// Handler for `GET /:app/:model/:pk/print/` - printable rendering of an object.
+async fn handle_print(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk)): Path<(String, String, String)>,
+    Query(query): Query<PrintQueryParams>,
+) -> axum::response::Response {
+    let key = format!("{app}.{model}");
+    let Some(admin) = state.registry.get(&key) else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": format!("Model '{key}' not found")
+            })),
+        )
+            .into_response();
+    };
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    let object = match state.db.get_object(&admin, &pk).await {
+        Ok(obj) => serde_json::to_value(obj).unwrap_or_default(),
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response()
+        }
+    };
+    let html = match crate::print::render_print_html(&state.template_engine, &admin, &object) {
+        Ok(html) => html,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response()
+        }
+    };
+    match query.format.as_deref() {
+        None | Some("html") => axum::response::Html(html).into_response(),
+        #[cfg(feature = "pdf")]
+        Some("pdf") => match state.pdf_renderer.render(&html).await {
+            Ok(pdf) => (
+                [
+                    (
+                        axum::http::header::CONTENT_TYPE,
+                        "application/pdf".to_string(),
+                    ),
+                    (
+                        axum::http::header::CONTENT_DISPOSITION,
+                        format!("inline; filename=\"{model}-{pk}.pdf\""),
+                    ),
+                ],
+                pdf,
+            )
+                .into_response(),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response(),
+        },
+        #[cfg(not(feature = "pdf"))]
+        Some("pdf") => (
+            StatusCode::NOT_IMPLEMENTED,
+            axum::Json(serde_json::json!({
+                "error": "PDF output requires the 'pdf' feature"
+            })),
+        )
+            .into_response(),
+        Some(other) => (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({
+                "error": format!("Unknown print format '{other}'")
+            })),
+        )
+            .into_response(),
+    }
+}
+
 /// Handler for `POST /:app/:model/` - create a new object.
+///
+/// Values of fields hidden by the model's visibility rules are ignored.
+/// With a `lang` parameter, numbers, dates and times may be submitted in
+/// that language's format; they are stored, and returned, in their
+/// canonical forms.
 async fn handle_create(
     State(state): State<Arc<AdminSiteState>>,
     Path((app, model)): Path<(String, String)>,
-    axum::Json(body): axum::Json<HashMap<String, serde_json::Value>>,
+    Query(query): Query<InputQueryParams>,
+    axum::Json(mut body): axum::Json<HashMap<String, serde_json::Value>>,
 ) -> impl IntoResponse {
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
     let key = format!("{app}.{model}");
-    match state.registered_models.get(&key) {
-        Some(admin) => match state.db.create_object(admin, &body).await {
-            Ok(obj) => {
+    if let Some(admin) = state.registry.get(&key) {
+        if let Some(language) = query.lang.as_deref() {
+            if let Err(errors) = admin.normalize_localized_input(&mut body, language) {
+                return invalid_input_response(&errors);
+            }
+        }
+        admin.apply_visibility_rules(&mut body, None);
+        if let Err(e) = fill_prepopulated_fields(state.db.as_ref(), &admin, &mut body).await {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response();
+        }
+    }
+    match state.registry.get(&key) {
+        Some(admin) => match state.db.create_object(&admin, &body).await {
+            Ok(mut obj) => {
                 let pk = obj.get("id").map(|v| v.to_string()).unwrap_or_default();
                 let repr = obj
                     .get("title")
@@ -425,9 +1266,11 @@ async fn handle_create(
                     .and_then(|v| v.as_str())
                     .unwrap_or("object")
                     .to_string();
+                state.model_counts.invalidate(&key);
                 state
                     .log_store
                     .log_addition(1, &key, &pk, &repr, "Created via admin");
+                admin.mask_encrypted_values(&mut obj);
                 (StatusCode::CREATED, axum::Json(obj)).into_response()
             }
             Err(e) => (
@@ -446,55 +1289,648 @@ async fn handle_create(
     }
 }
 
-/// Handler for `PUT /:app/:model/:pk/` - update an object.
-async fn handle_update(
+/// Fills in the blank prepopulated fields of `data` with slugs of their
+/// source fields, appending `-2`, `-3`, ... until no stored object has the
+/// same slug.
+async fn fill_prepopulated_fields(
+    db: &dyn AdminDbExecutor,
+    admin: &ModelAdmin,
+    data: &mut HashMap<String, serde_json::Value>,
+) -> Result<(), String> {
+    for (field, slug) in admin.prepopulated_slugs(data) {
+        let max_length = admin.max_length(&field);
+        let mut n = 1;
+        let mut candidate = slug.clone();
+        while db.value_exists(admin, &field, &candidate).await? {
+            n += 1;
+            candidate = numbered_slug(&slug, n, max_length);
+        }
+        data.insert(field, serde_json::Value::String(candidate));
+    }
+    Ok(())
+}
+
+/// Handler for `POST /:app/:model/quick-create/` - create an object from
+/// the model's `quick_create_fields`, returning `{id, label}`.
+async fn handle_quick_create(
     State(state): State<Arc<AdminSiteState>>,
-    Path((app, model, pk)): Path<(String, String, String)>,
+    Path((app, model)): Path<(String, String)>,
     axum::Json(body): axum::Json<HashMap<String, serde_json::Value>>,
 ) -> impl IntoResponse {
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
     let key = format!("{app}.{model}");
-    match state.registered_models.get(&key) {
-        Some(admin) => match state.db.update_object(admin, &pk, &body).await {
-            Ok(obj) => {
-                let repr = obj
-                    .get("title")
-                    .or_else(|| obj.get("name"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("object")
-                    .to_string();
-                let changed: Vec<String> = body.keys().cloned().collect();
-                let msg = format!("Changed {}", changed.join(", "));
-                state.log_store.log_change(1, &key, &pk, &repr, &msg);
-                axum::Json(obj).into_response()
-            }
-            Err(e) => (
-                StatusCode::NOT_FOUND,
-                axum::Json(serde_json::json!({"error": e})),
-            )
-                .into_response(),
-        },
-        None => (
+    let Some(admin) = state.registry.get(&key) else {
+        return (
             StatusCode::NOT_FOUND,
             axum::Json(serde_json::json!({
                 "error": format!("Model '{key}' not found")
             })),
         )
-            .into_response(),
+            .into_response();
+    };
+
+    if admin.quick_create_fields.is_empty() {
+        return (
+            StatusCode::FORBIDDEN,
+            axum::Json(serde_json::json!({
+                "error": format!("Quick create is not enabled for '{key}'")
+            })),
+        )
+            .into_response();
     }
-}
 
-/// Handler for `DELETE /:app/:model/:pk/` - delete an object.
-async fn handle_delete(
-    State(state): State<Arc<AdminSiteState>>,
+    let mut data = match admin.quick_create_data(&body) {
+        Ok(data) => data,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response()
+        }
+    };
+    if let Err(e) = fill_prepopulated_fields(state.db.as_ref(), &admin, &mut data).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response();
+    }
+
+    match state.db.create_object(&admin, &data).await {
+        Ok(obj) => {
+            let id = obj
+                .get(admin.pk_field_name())
+                .cloned()
+                .unwrap_or(serde_json::Value::Null);
+            state.model_counts.invalidate(&key);
+            let label = admin.object_label(&obj);
+            let pk = match &id {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            state
+                .log_store
+                .log_addition(1, &key, &pk, &label, "Created via quick create");
+            (
+                StatusCode::CREATED,
+                axum::Json(QuickCreateResponse { id, label }),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+/// Handler for `POST /:app/:model/action/` - run a bulk action on the
+/// selected objects.
+///
+/// Actions that are [long-running](AdminAction::is_long_running) are queued
+/// as a background job owned by the current user, and the handler answers
+/// `202 Accepted` with the job to poll.
+async fn handle_action(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model)): Path<(String, String)>,
+    headers: HeaderMap,
+    axum::Json(body): axum::Json<BulkActionRequest>,
+) -> impl IntoResponse {
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
+    let key = format!("{app}.{model}");
+    let Some(registry) = state.registry.actions(&key) else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": format!("Model '{key}' not found")
+            })),
+        )
+            .into_response();
+    };
+
+    if registry
+        .get(&body.action)
+        .is_some_and(AdminAction::is_long_running)
+    {
+        let Some(owner) = request_owner(&headers) else {
+            return authentication_required();
+        };
+        let job = ActionJob::new(owner, &key, &body.action, body.ids.len());
+        let job = match state.action_job_store.create(job).await {
+            Ok(job) => job,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(serde_json::json!({"error": e})),
+                )
+                    .into_response()
+            }
+        };
+        tokio::spawn(run_background_action(state.clone(), job.clone(), body.ids));
+        return (
+            StatusCode::ACCEPTED,
+            axum::Json(serde_json::json!({"status": "queued", "job": job})),
+        )
+            .into_response();
+    }
+
+    let result = registry.execute(&body.action, &key, &body.ids).await;
+    state.model_counts.invalidate(&key);
+    match result {
+        Ok(result) if result.success => axum::Json(BulkActionResponse {
+            action: body.action,
+            affected: result.affected_count,
+            message: result.message,
+        })
+        .into_response(),
+        Ok(result) => (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": result.message})),
+        )
+            .into_response(),
+        Err(DjangoError::NotFound(e)) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+/// Runs a queued action job and notifies its owner when it finishes.
+async fn run_background_action(state: Arc<AdminSiteState>, job: ActionJob, ids: Vec<String>) {
+    let Some(registry) = state.registry.actions(&job.model_key) else {
+        return;
+    };
+    let Some(action) = registry.get(&job.action) else {
+        return;
+    };
+    let job = run_action_job(action, state.action_job_store.as_ref(), job, &ids).await;
+    state.model_counts.invalidate(&job.model_key);
+
+    let outcome = match job.status {
+        ActionJobStatus::Cancelled => "was cancelled",
+        _ => "completed",
+    };
+    let notification = AdminNotification::new(
+        &job.owner,
+        NotificationKind::ActionCompleted,
+        &format!(
+            "Action '{}' {outcome}: {} succeeded, {} failed",
+            job.action, job.succeeded, job.failed
+        ),
+    )
+    .link(&format!("/action-jobs/{}/", job.id));
+    if let Err(e) = state.notification_store.push(notification).await {
+        tracing::warn!(
+            "Failed to notify {} of action job {}: {e}",
+            job.owner,
+            job.id
+        );
+    }
+}
+
+/// Returns the action job with the given id if the requesting user owns it,
+/// or the response to send instead.
+async fn owned_action_job(
+    state: &AdminSiteState,
+    headers: &HeaderMap,
+    id: u64,
+) -> Result<ActionJob, axum::response::Response> {
+    let Some(owner) = request_owner(headers) else {
+        return Err(authentication_required());
+    };
+    match state.action_job_store.get(id).await {
+        Ok(Some(job)) if job.owner == owner => Ok(job),
+        Ok(_) => Err((
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": format!("Action job {id} not found")})),
+        )
+            .into_response()),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response()),
+    }
+}
+
+/// Handler for `GET /action-jobs/:id/` - the progress of a background action.
+///
+/// Only the user who started the action can see its job.
+async fn handle_action_job(
+    State(state): State<Arc<AdminSiteState>>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    match owned_action_job(&state, &headers, id).await {
+        Ok(job) => axum::Json(job).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// Handler for `POST /action-jobs/:id/cancel/` - stop a background action
+/// before its next object.
+///
+/// Objects already processed stay processed. A finished job answers
+/// `409 Conflict`.
+async fn handle_action_job_cancel(
+    State(state): State<Arc<AdminSiteState>>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let job = match owned_action_job(&state, &headers, id).await {
+        Ok(job) => job,
+        Err(response) => return response,
+    };
+    if job.is_finished() {
+        return (
+            StatusCode::CONFLICT,
+            axum::Json(serde_json::json!({"error": format!("Action job {id} already finished")})),
+        )
+            .into_response();
+    }
+    match state.action_job_store.request_cancel(id).await {
+        Ok(Some(job)) => (StatusCode::ACCEPTED, axum::Json(job)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": format!("Action job {id} not found")})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+/// Handler for `PUT` and `PATCH /:app/:model/:pk/` - update an object.
+///
+/// Visibility rules are evaluated against the submitted values over the
+/// stored ones; fields they hide are ignored, and cleared if stored. An
+/// `If-Match` header that does not list the object's current `ETag` is
+/// rejected with 412 Precondition Failed. Localized values are read as on
+/// create.
+async fn handle_update(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk)): Path<(String, String, String)>,
+    Query(query): Query<InputQueryParams>,
+    headers: HeaderMap,
+    axum::Json(mut body): axum::Json<HashMap<String, serde_json::Value>>,
+) -> impl IntoResponse {
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
+    let key = format!("{app}.{model}");
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    if let Some(admin) = state.registry.get(&key) {
+        if let Some(response) = precondition_failed_response(&state, &admin, &pk, &headers).await {
+            return response;
+        }
+        admin.drop_masked_input(&mut body);
+        if let Some(language) = query.lang.as_deref() {
+            if let Err(errors) = admin.normalize_localized_input(&mut body, language) {
+                return invalid_input_response(&errors);
+            }
+        }
+        if !admin.visibility_rules.is_empty() {
+            if let Ok(current) = state.db.get_object(&admin, &pk).await {
+                admin.apply_visibility_rules(&mut body, Some(&current));
+            }
+        }
+    }
+    match state.registry.get(&key) {
+        Some(admin) => match state.db.update_object(&admin, &pk, &body).await {
+            Ok(mut obj) => {
+                let repr = obj
+                    .get("title")
+                    .or_else(|| obj.get("name"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("object")
+                    .to_string();
+                let changed: Vec<String> = body.keys().cloned().collect();
+                let msg = format!("Changed {}", changed.join(", "));
+                state.log_store.log_change(1, &key, &pk, &repr, &msg);
+                let etag = object_etag(&obj);
+                admin.mask_encrypted_values(&mut obj);
+                ([(axum::http::header::ETAG, etag)], axum::Json(obj)).into_response()
+            }
+            Err(e) => (
+                StatusCode::NOT_FOUND,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response(),
+        },
+        None => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": format!("Model '{key}' not found")
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Returns the strong `ETag` of an object: a hash of its JSON serialization.
+fn object_etag(obj: &serde_json::Value) -> String {
+    let digest = Sha256::digest(obj.to_string().as_bytes());
+    format!("\"{digest:x}\"")
+}
+
+/// Returns `true` if an `If-Match` header value lists `etag`.
+///
+/// Weak tags never match, as `If-Match` uses strong comparison.
+fn if_match_satisfied(header: &str, etag: &str) -> bool {
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// Returns a 412 response if the request has an `If-Match` header that does
+/// not list the current `ETag` of the object.
+///
+/// The precondition cannot hold if the object cannot be loaded: a missing
+/// object answers 404 and a failed lookup 500.
+async fn precondition_failed_response(
+    state: &AdminSiteState,
+    admin: &ModelAdmin,
+    pk: &str,
+    headers: &HeaderMap,
+) -> Option<axum::response::Response> {
+    let header = headers.get(axum::http::header::IF_MATCH)?;
+    let current = match state.db.get_object(admin, pk).await {
+        Ok(current) => current,
+        Err(e) => {
+            let status = match state
+                .db
+                .value_exists(admin, admin.pk_field_name(), pk)
+                .await
+            {
+                Ok(false) => StatusCode::NOT_FOUND,
+                Ok(true) | Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return Some((status, axum::Json(serde_json::json!({"error": e}))).into_response());
+        }
+    };
+    let etag = object_etag(&current);
+    if header
+        .to_str()
+        .is_ok_and(|header| if_match_satisfied(header, &etag))
+    {
+        return None;
+    }
+    Some(
+        (
+            StatusCode::PRECONDITION_FAILED,
+            [(axum::http::header::ETAG, etag)],
+            axum::Json(serde_json::json!({
+                "error": "The object was changed since it was loaded"
+            })),
+        )
+            .into_response(),
+    )
+}
+
+/// Query parameters for the relation selector endpoint.
+#[derive(Debug, Deserialize)]
+struct RelationQueryParams {
+    /// `available` (the default) or `chosen`.
+    side: Option<String>,
+    search: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+}
+
+/// Looks up the admins of a model and of the target of its
+/// `filter_horizontal` field.
+#[allow(clippy::result_large_err)]
+fn relation_admins(
+    state: &AdminSiteState,
+    key: &str,
+    field: &str,
+) -> Result<(Arc<ModelAdmin>, Arc<ModelAdmin>), axum::response::Response> {
+    let not_found = |error: String| {
+        (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({ "error": error })),
+        )
+            .into_response()
+    };
+    let admin = state
+        .registry
+        .get(key)
+        .ok_or_else(|| not_found(format!("Model '{key}' not found")))?;
+    let target_key = admin.filter_horizontal_target(field).ok_or_else(|| {
+        not_found(format!(
+            "Field '{field}' of '{key}' is not a filter_horizontal relation"
+        ))
+    })?;
+    let target = state
+        .registry
+        .get(target_key)
+        .ok_or_else(|| not_found(format!("Model '{target_key}' not found")))?;
+    Ok((admin, target))
+}
+
+/// Normalizes a primary key for comparison, so `1` and `"1"` match.
+fn pk_key(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Handler for `GET /:app/:model/:pk/relations/:field/` - one panel of the
+/// `filter_horizontal` selector.
+///
+/// Lists the related model's objects that are chosen (`side=chosen`) or not
+/// (`side=available`, the default) for the object, narrowed by the related
+/// model's `search_fields` and paginated. The available side only considers
+/// the first `list_max_show_all` matches, which also caps `page_size`.
+async fn handle_relation_choices(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk, field)): Path<(String, String, String, String)>,
+    Query(query): Query<RelationQueryParams>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let key = format!("{app}.{model}");
+    let (admin, target) = match relation_admins(&state, &key, &field) {
+        Ok(admins) => admins,
+        Err(response) => return response,
+    };
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    let want_chosen = match query.side.as_deref() {
+        None | Some("available") => false,
+        Some("chosen") => true,
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": format!("Unknown side '{other}'")
+                })),
+            )
+                .into_response()
+        }
+    };
+
+    let chosen: HashSet<String> = match state.db.get_relation(&admin, &pk, &field).await {
+        Ok(pks) => pks.iter().map(pk_key).collect(),
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response()
+        }
+    };
+    let limit = target.list_max_show_all.max(1);
+    let mut params = AdminListParams::new().page_size(limit);
+    params.search = query.search;
+    let target_pk = target.pk_field_name();
+    let mut choices: Vec<serde_json::Value> = Vec::new();
+    loop {
+        let response = match state.db.list_objects(&target, &params).await {
+            Ok(result) => result.response,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(serde_json::json!({"error": e})),
+                )
+                    .into_response()
+            }
+        };
+        choices.extend(response.results.iter().filter_map(|obj| {
+            let id = obj.get(target_pk)?;
+            (chosen.contains(&pk_key(id)) == want_chosen).then(|| {
+                serde_json::to_value(RelationChoice {
+                    id: id.clone(),
+                    label: target.object_label(obj),
+                })
+                .unwrap_or_default()
+            })
+        }));
+        // Saving replaces the whole selection, so the chosen panel must list
+        // every chosen object. The available one is narrowed with `search`.
+        if !want_chosen || !response.has_next {
+            break;
+        }
+        params.page += 1;
+    }
+    let response = JsonListResponse::paginate(
+        &choices,
+        query.page.unwrap_or(1),
+        query.page_size.unwrap_or(target.list_per_page).min(limit),
+    );
+    paginated_json(response, &uri, &headers)
+}
+
+/// Handler for `PATCH /:app/:model/:pk/relations/:field/` - replace the
+/// chosen objects of a `filter_horizontal` field.
+///
+/// Every id must belong to an existing related object; duplicates are
+/// dropped. The relation is replaced as a whole, or not at all.
+async fn handle_set_relation(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk, field)): Path<(String, String, String, String)>,
+    axum::Json(body): axum::Json<SetRelationRequest>,
+) -> axum::response::Response {
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
+    let key = format!("{app}.{model}");
+    let (admin, target) = match relation_admins(&state, &key, &field) {
+        Ok(admins) => admins,
+        Err(response) => return response,
+    };
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+
+    let mut seen = HashSet::new();
+    let mut ids = Vec::with_capacity(body.ids.len());
+    for id in body.ids {
+        let id_key = pk_key(&id);
+        if seen.contains(&id_key) {
+            continue;
+        }
+        if state.db.get_object(&target, &id_key).await.is_err() {
+            return (
+                StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": format!("No '{}' object with pk {id_key}", target.model_key())
+                })),
+            )
+                .into_response();
+        }
+        seen.insert(id_key);
+        ids.push(id);
+    }
+
+    if let Err(e) = state.db.set_relation(&admin, &pk, &field, &ids).await {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response();
+    }
+    let repr = state
+        .db
+        .get_object(&admin, &pk)
+        .await
+        .map_or_else(|_| "object".to_string(), |obj| admin.object_label(&obj));
+    state
+        .log_store
+        .log_change(1, &key, &pk, &repr, &format!("Changed {field}"));
+    axum::Json(serde_json::json!({ "ids": ids })).into_response()
+}
+
+/// Handler for `DELETE /:app/:model/:pk/` - delete an object.
+///
+/// Honors `If-Match` like [`handle_update`].
+async fn handle_delete(
+    State(state): State<Arc<AdminSiteState>>,
     Path((app, model, pk)): Path<(String, String, String)>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
     let key = format!("{app}.{model}");
-    match state.registered_models.get(&key) {
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.registry.get(&key) {
         Some(admin) => {
+            if let Some(response) =
+                precondition_failed_response(&state, &admin, &pk, &headers).await
+            {
+                return response;
+            }
             // Try to get the object repr before deleting
             let repr = state
                 .db
-                .get_object(admin, &pk)
+                .get_object(&admin, &pk)
                 .await
                 .ok()
                 .and_then(|obj| {
@@ -505,8 +1941,9 @@ async fn handle_delete(
                 })
                 .unwrap_or_else(|| format!("{key} object"));
 
-            match state.db.delete_object(admin, &pk).await {
+            match state.db.delete_object(&admin, &pk).await {
                 Ok(true) => {
+                    state.model_counts.invalidate(&key);
                     state.log_store.log_deletion(1, &key, &pk, &repr, "");
                     StatusCode::NO_CONTENT.into_response()
                 }
@@ -532,30 +1969,1000 @@ async fn handle_delete(
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::model_admin::FieldSchema;
+// ── Maintenance Handlers ───────────────────────────────────────────
 
-    #[test]
-    fn test_admin_site_new() {
-        let site = AdminSite::new("admin");
-        assert_eq!(site.name(), "admin");
-        assert_eq!(site.url_prefix_str(), "/api/admin");
-        assert_eq!(site.model_count(), 0);
-        assert!(site.static_dir_path().is_none());
+/// Returns a 503 response if the admin is in read-only mode.
+///
+/// If the maintenance state cannot be read, writes are rejected too, like
+/// [`MaintenanceModeMiddleware`](crate::maintenance::MaintenanceModeMiddleware)
+/// does by default.
+async fn read_only_response(state: &AdminSiteState) -> Option<axum::response::Response> {
+    let maintenance = match state.maintenance_store.get().await {
+        Ok(maintenance) => maintenance,
+        Err(e) => {
+            tracing::warn!("Cannot read maintenance state: {e}");
+            return Some(
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    axum::Json(serde_json::json!({
+                        "error": "The admin cannot read its maintenance state",
+                    })),
+                )
+                    .into_response(),
+            );
+        }
+    };
+    if maintenance.read_only == ReadOnlyScope::Off {
+        return None;
     }
+    let message = maintenance
+        .message
+        .as_deref()
+        .unwrap_or("The admin is read-only during maintenance");
+    Some(
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(
+                axum::http::header::RETRY_AFTER,
+                maintenance.retry_after_seconds().to_string(),
+            )],
+            axum::Json(serde_json::json!({"error": message, "read_only": true})),
+        )
+            .into_response(),
+    )
+}
 
-    #[test]
-    fn test_admin_site_custom_prefix() {
-        let site = AdminSite::new("admin").url_prefix("/custom/api");
-        assert_eq!(site.url_prefix_str(), "/custom/api");
+/// Handler for `GET /maintenance/` - the current maintenance state.
+async fn handle_maintenance_get(State(state): State<Arc<AdminSiteState>>) -> impl IntoResponse {
+    match state.maintenance_store.get().await {
+        Ok(maintenance) => axum::Json(maintenance).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
     }
+}
 
-    #[test]
-    fn test_admin_site_static_dir() {
-        let site = AdminSite::new("admin").static_dir(PathBuf::from("/static"));
-        assert_eq!(site.static_dir_path(), Some(&PathBuf::from("/static")));
+/// Handler for `PUT /maintenance/` - replace the maintenance state.
+///
+/// Only superusers may change it. Records when and by whom (the username)
+/// the state was changed.
+async fn handle_maintenance_set(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+    axum::Json(mut maintenance): axum::Json<MaintenanceState>,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    maintenance.updated_at = Some(chrono::Utc::now());
+    maintenance.updated_by = request_owner(&headers).map(str::to_string);
+    match state.maintenance_store.set(&maintenance).await {
+        Ok(()) => axum::Json(maintenance).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+// ── Permission Handlers ────────────────────────────────────────────
+
+/// Handler for `GET /permissions/` - the permission matrix.
+async fn handle_permissions_get(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    match state.permission_store.matrix().await {
+        Ok(matrix) => axum::Json(matrix).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+/// Handler for `PATCH /permissions/` - apply grants and revocations.
+///
+/// If any change is invalid, none are applied and the response lists every
+/// problem under `errors`.
+async fn handle_permissions_update(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+    axum::Json(body): axum::Json<PermissionMatrixUpdate>,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    if let Some(response) = read_only_response(&state).await {
+        return response;
+    }
+    let applied = match state.permission_store.apply(&body.changes).await {
+        Ok(applied) => applied,
+        Err(MatrixUpdateError::Invalid(errors)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": "No changes were applied",
+                    "errors": errors,
+                })),
+            )
+                .into_response();
+        }
+        Err(MatrixUpdateError::Store(e)) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response();
+        }
+    };
+    match state.permission_store.matrix().await {
+        Ok(matrix) => {
+            axum::Json(PermissionMatrixUpdateResponse { applied, matrix }).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+// ── Registry Handlers ──────────────────────────────────────────────
+
+/// Returns the error response for a request not made by a superuser.
+fn superuser_required(headers: &HeaderMap) -> Option<axum::response::Response> {
+    match bearer_token(headers) {
+        None => Some(authentication_required()),
+        Some(DEV_ADMIN_TOKEN) => None,
+        Some(_) => Some(
+            (
+                StatusCode::FORBIDDEN,
+                axum::Json(serde_json::json!({"error": "Superuser access required"})),
+            )
+                .into_response(),
+        ),
+    }
+}
+
+/// Handler for `GET /registry/` - the registered models and their options.
+async fn handle_registry_list(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    let models: serde_json::Map<String, serde_json::Value> = state
+        .registry
+        .model_keys()
+        .into_iter()
+        .filter_map(|key| {
+            let admin = state.registry.get(&key)?;
+            Some((key, serde_json::to_value(admin.as_ref()).ok()?))
+        })
+        .collect();
+    axum::Json(serde_json::json!({"models": models})).into_response()
+}
+
+/// Handler for `POST /registry/` - register a model while the site runs.
+///
+/// Answers `409 Conflict` if the model is already registered.
+async fn handle_registry_register(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+    axum::Json(body): axum::Json<RegisterModelRequest>,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    let admin = match body.into_model_admin() {
+        Ok(admin) => admin,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response()
+        }
+    };
+    let key = admin.model_key();
+    if state.registry.is_registered(&key) {
+        return (
+            StatusCode::CONFLICT,
+            axum::Json(serde_json::json!({
+                "error": format!("Model '{key}' is already registered")
+            })),
+        )
+            .into_response();
+    }
+    state.registry.register(&key, admin.clone());
+    tracing::info!("Registered '{key}' on the '{}' admin", state.name);
+    (StatusCode::CREATED, axum::Json(admin)).into_response()
+}
+
+/// Handler for `DELETE /registry/:app/:model/` - unregister a model while
+/// the site runs.
+async fn handle_registry_unregister(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    let key = format!("{app}.{model}");
+    if state.registry.unregister(&key).is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": format!("Model '{key}' not found")
+            })),
+        )
+            .into_response();
+    }
+    state.model_counts.invalidate(&key);
+    tracing::info!("Unregistered '{key}' from the '{}' admin", state.name);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+// ── Feature Flag Handlers ──────────────────────────────────────────
+
+/// Handler for `GET /flags/` - the feature flags in effect and where each
+/// is defined.
+async fn handle_flags_list(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    match state.flag_store.all().await {
+        Ok(stored) => {
+            axum::Json(serde_json::json!({"flags": flag_statuses(stored)})).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+/// Handler for `PUT /flags/:name/` - save a feature flag and apply it to
+/// this process.
+async fn handle_flag_set(
+    State(state): State<Arc<AdminSiteState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    axum::Json(flag): axum::Json<Flag>,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    if let Err(e) = state.flag_store.set(&name, &flag).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response();
+    }
+    FLAGS.set(&name, flag.clone());
+    tracing::info!(
+        "Feature flag '{name}' changed from the '{}' admin",
+        state.name
+    );
+    axum::Json(flag).into_response()
+}
+
+/// Handler for `DELETE /flags/:name/` - delete a stored feature flag, so
+/// the settings flag of the same name (if any) applies again.
+async fn handle_flag_delete(
+    State(state): State<Arc<AdminSiteState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(response) = superuser_required(&headers) {
+        return response;
+    }
+    match state.flag_store.delete(&name).await {
+        Ok(true) => {
+            FLAGS.remove(&name);
+            tracing::info!(
+                "Feature flag '{name}' deleted from the '{}' admin",
+                state.name
+            );
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": format!("Feature flag '{name}' not found")
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+// ── Documentation Handlers ─────────────────────────────────────────
+
+/// Handler for `GET /docs/` - generated documentation of the registered
+/// models, URL patterns and template libraries.
+async fn handle_docs(State(state): State<Arc<AdminSiteState>>) -> impl IntoResponse {
+    axum::Json(AdminDocs::new(
+        state.registry.admins().iter().map(AsRef::as_ref),
+        state.url_docs.clone(),
+    ))
+}
+
+// ── Export Handlers ────────────────────────────────────────────────
+
+/// Query parameters for the export endpoint.
+#[derive(Debug, Deserialize)]
+struct ExportQueryParams {
+    search: Option<String>,
+    ordering: Option<String>,
+    /// Multi-column ordering, as for the list endpoint.
+    o: Option<String>,
+    /// Only objects in this publishing status, for scheduled publishing.
+    publish_status: Option<String>,
+}
+
+/// Handler for `GET /:app/:model/export/` - export the changelist as CSV.
+///
+/// Exports up to the site's export row threshold are streamed as they are
+/// read from the database. Larger exports answer `202 Accepted`; a background
+/// task writes the file to the export storage and notifies the user with a
+/// link to `/exports/:name/`.
+async fn handle_export(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model)): Path<(String, String)>,
+    Query(query): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let key = format!("{app}.{model}");
+    let Some(admin) = state.registry.get(&key) else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": format!("Model '{key}' not found")
+            })),
+        )
+            .into_response();
+    };
+    let Some(owner) = request_owner(&headers) else {
+        return authentication_required();
+    };
+    let ordering = match list_ordering(&admin, query.o.as_deref(), query.ordering.as_deref()) {
+        Ok(ordering) => ordering,
+        Err(error) => return invalid_ordering_response(&error),
+    };
+    let params = AdminListParams {
+        page: 1,
+        page_size: EXPORT_CHUNK_SIZE,
+        search: query.search,
+        ordering,
+        filters: query
+            .publish_status
+            .into_iter()
+            .map(|status| (STATUS_FIELD.to_string(), status))
+            .collect(),
+    };
+    let export = match CsvExport::start(state.db.clone(), (*admin).clone(), params).await {
+        Ok(export) => export,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({"error": e})),
+            )
+                .into_response()
+        }
+    };
+
+    if export.row_count() > state.export_row_threshold {
+        let rows = export.row_count();
+        let name = format!(
+            "{app}_{model}_{}.csv",
+            Utc::now().format("%Y%m%dT%H%M%S%3f")
+        );
+        tokio::spawn(run_export_job(
+            export,
+            state.export_storage.clone(),
+            state.notification_store.clone(),
+            owner.to_string(),
+            name.clone(),
+            admin.verbose_name_plural.clone(),
+        ));
+        return (
+            StatusCode::ACCEPTED,
+            axum::Json(serde_json::json!({"status": "queued", "rows": rows, "file": name})),
+        )
+            .into_response();
+    }
+
+    (
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "text/csv; charset=utf-8".to_string(),
+            ),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{app}_{model}.csv\""),
+            ),
+        ],
+        axum::body::Body::from_stream(export.into_stream()),
+    )
+        .into_response()
+}
+
+/// Writes a queued export to storage and notifies its owner of the outcome.
+async fn run_export_job(
+    export: CsvExport,
+    storage: Arc<dyn ExportStorage>,
+    notifications: Arc<dyn NotificationStore>,
+    owner: String,
+    name: String,
+    label: String,
+) {
+    let rows = export.row_count();
+    let result = export.write_to(storage.as_ref(), &owner, &name).await;
+    let notification = match result {
+        Ok(()) => AdminNotification::new(
+            &owner,
+            NotificationKind::ExportReady,
+            &format!("Your export of {rows} {label} is ready"),
+        )
+        .link(&format!("/exports/{name}/")),
+        Err(e) => {
+            tracing::warn!("Export {name} failed: {e}");
+            AdminNotification::new(
+                &owner,
+                NotificationKind::System,
+                &format!("Your export of {label} failed: {e}"),
+            )
+        }
+    };
+    if let Err(e) = notifications.push(notification).await {
+        tracing::warn!("Failed to notify {owner} of export {name}: {e}");
+    }
+}
+
+/// Handler for `GET /exports/:name/` - download a finished background export.
+///
+/// Only the user who requested the export can download it.
+async fn handle_export_download(
+    State(state): State<Arc<AdminSiteState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let Some(owner) = request_owner(&headers) else {
+        return authentication_required();
+    };
+    match state.export_storage.open(owner, &name).await {
+        Ok(Some(reader)) => (
+            [
+                (
+                    axum::http::header::CONTENT_TYPE,
+                    "text/csv; charset=utf-8".to_string(),
+                ),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{name}\""),
+                ),
+            ],
+            axum::body::Body::from_stream(reader),
+        )
+            .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": format!("Export '{name}' not found")})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+// ── Draft Handlers ─────────────────────────────────────────────────
+
+/// Returns the bearer token sent with the request.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Returns the username a token was issued to, or `None` if the token is
+/// not one the site issued.
+fn token_username(token: &str) -> Option<&str> {
+    token
+        .strip_prefix(DEV_TOKEN_PREFIX)
+        .filter(|username| !username.is_empty())
+}
+
+/// Returns the username of the admin user making the request.
+///
+/// Drafts, comments and notifications are keyed by this name, never by the
+/// token itself, so tokens are not stored or shown to other users.
+fn request_owner(headers: &HeaderMap) -> Option<&str> {
+    bearer_token(headers).and_then(token_username)
+}
+
+/// Checks the model is registered and the request is authenticated,
+/// returning the model key and draft owner, or the error status and message.
+fn draft_target(
+    state: &AdminSiteState,
+    headers: &HeaderMap,
+    app: &str,
+    model: &str,
+) -> Result<(String, String), (StatusCode, String)> {
+    let key = format!("{app}.{model}");
+    if !state.registry.is_registered(&key) {
+        return Err((StatusCode::NOT_FOUND, format!("Model '{key}' not found")));
+    }
+    let owner = request_owner(headers).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            "Authentication required".to_string(),
+        )
+    })?;
+    Ok((key, owner.to_string()))
+}
+
+/// Handler for `GET /:app/:model/:pk/draft/` - the current user's draft.
+async fn handle_draft_get(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let (key, owner) = match draft_target(&state, &headers, &app, &model) {
+        Ok(target) => target,
+        Err((status, error)) => {
+            return (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+        }
+    };
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.draft_store.get(&owner, &key, &pk).await {
+        Ok(Some(draft)) => axum::Json(draft).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "No draft saved"})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+/// Handler for `PUT /:app/:model/:pk/draft/` - autosave the change form.
+async fn handle_draft_save(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    axum::Json(body): axum::Json<HashMap<String, serde_json::Value>>,
+) -> impl IntoResponse {
+    let (key, owner) = match draft_target(&state, &headers, &app, &model) {
+        Ok(target) => target,
+        Err((status, error)) => {
+            return (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+        }
+    };
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.draft_store.save(&owner, &key, &pk, body).await {
+        Ok(draft) => axum::Json(draft).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+/// Handler for `DELETE /:app/:model/:pk/draft/` - discard a draft.
+async fn handle_draft_discard(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let (key, owner) = match draft_target(&state, &headers, &app, &model) {
+        Ok(target) => target,
+        Err((status, error)) => {
+            return (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+        }
+    };
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.draft_store.discard(&owner, &key, &pk).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+    }
+}
+
+// ── Comment Handlers ───────────────────────────────────────────────
+
+/// Request body for posting a comment.
+#[derive(Debug, Deserialize)]
+struct CommentRequest {
+    body: String,
+}
+
+/// Checks the model is registered with comments enabled and the request is
+/// authenticated, returning the model admin and the comment author, or the
+/// error status and message.
+fn comment_target(
+    state: &AdminSiteState,
+    headers: &HeaderMap,
+    app: &str,
+    model: &str,
+) -> Result<(Arc<ModelAdmin>, String), (StatusCode, String)> {
+    let key = format!("{app}.{model}");
+    let Some(admin) = state.registry.get(&key) else {
+        return Err((StatusCode::NOT_FOUND, format!("Model '{key}' not found")));
+    };
+    if !admin.comments_enabled {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Comments are not enabled for '{key}'"),
+        ));
+    }
+    let author = request_owner(headers).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            "Authentication required".to_string(),
+        )
+    })?;
+    Ok((admin, author.to_string()))
+}
+
+fn comment_store_error(error: &str) -> axum::response::Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        axum::Json(serde_json::json!({"error": error})),
+    )
+        .into_response()
+}
+
+/// Handler for `GET /:app/:model/:pk/comments/` - the object's comments,
+/// oldest first.
+async fn handle_comments_list(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let (admin, _) = match comment_target(&state, &headers, &app, &model) {
+        Ok(target) => target,
+        Err((status, error)) => {
+            return (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+        }
+    };
+    let pk = match resolve_pk(&state, &admin.model_key(), pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.comment_store.list(&admin.model_key(), &pk).await {
+        Ok(results) => axum::Json(serde_json::json!({"results": results})).into_response(),
+        Err(e) => comment_store_error(&e),
+    }
+}
+
+/// Handler for `POST /:app/:model/:pk/comments/` - post a comment.
+///
+/// Each user mentioned with `@name`, other than the author, gets a mention
+/// notification linking to the object.
+async fn handle_comment_add(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, lookup)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    axum::Json(payload): axum::Json<CommentRequest>,
+) -> impl IntoResponse {
+    let (admin, author) = match comment_target(&state, &headers, &app, &model) {
+        Ok(target) => target,
+        Err((status, error)) => {
+            return (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+        }
+    };
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({"error": "Comment body cannot be empty"})),
+        )
+            .into_response();
+    }
+    let pk = match resolve_pk(&state, &admin.model_key(), lookup.clone()).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    if let Err(e) = state.db.get_object(&admin, &pk).await {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": e})),
+        )
+            .into_response();
+    }
+
+    let key = admin.model_key();
+    let comment = match state
+        .comment_store
+        .add(AdminComment::new(&key, &pk, &author, body))
+        .await
+    {
+        Ok(comment) => comment,
+        Err(e) => return comment_store_error(&e),
+    };
+
+    let message = format!(
+        "{author} mentioned you in a comment on {} {pk}",
+        admin.verbose_name
+    );
+    let link = format!("/{app}/{model}/{lookup}/");
+    for recipient in comment.mentions.iter().filter(|m| **m != author) {
+        let notification =
+            AdminNotification::new(recipient, NotificationKind::Mention, &message).link(&link);
+        if let Err(e) = state.notification_store.push(notification).await {
+            tracing::warn!("Failed to notify {recipient} of a mention: {e}");
+        }
+    }
+
+    (StatusCode::CREATED, axum::Json(comment)).into_response()
+}
+
+/// Handler for `DELETE /:app/:model/:pk/comments/:id/` - delete a comment.
+///
+/// Only the comment's author may delete it.
+async fn handle_comment_delete(
+    State(state): State<Arc<AdminSiteState>>,
+    Path((app, model, pk, id)): Path<(String, String, String, u64)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let (admin, author) = match comment_target(&state, &headers, &app, &model) {
+        Ok(target) => target,
+        Err((status, error)) => {
+            return (status, axum::Json(serde_json::json!({"error": error}))).into_response()
+        }
+    };
+    let key = admin.model_key();
+    let pk = match resolve_pk(&state, &key, pk).await {
+        Ok(pk) => pk,
+        Err(response) => return response,
+    };
+    match state.comment_store.get(&key, &pk, id).await {
+        Ok(Some(comment)) if comment.author != author => {
+            return (
+                StatusCode::FORBIDDEN,
+                axum::Json(serde_json::json!({
+                    "error": "Only the author can delete a comment"
+                })),
+            )
+                .into_response();
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                axum::Json(serde_json::json!({"error": "Comment not found"})),
+            )
+                .into_response();
+        }
+        Err(e) => return comment_store_error(&e),
+    }
+    match state.comment_store.delete(&key, &pk, id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => comment_store_error(&e),
+    }
+}
+
+// ── Notification Handlers ──────────────────────────────────────────
+
+/// Query parameters for the notification list endpoint.
+#[derive(Debug, Deserialize)]
+struct NotificationQueryParams {
+    unread: Option<bool>,
+    limit: Option<usize>,
+}
+
+/// Query parameters for the notification stream.
+///
+/// Browsers' `EventSource` cannot send an `Authorization` header, so the
+/// stream also accepts the token as a query parameter.
+#[derive(Debug, Deserialize)]
+struct NotificationStreamParams {
+    token: Option<String>,
+}
+
+fn authentication_required() -> axum::response::Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        axum::Json(serde_json::json!({"error": "Authentication required"})),
+    )
+        .into_response()
+}
+
+fn notification_store_error(error: &str) -> axum::response::Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        axum::Json(serde_json::json!({"error": error})),
+    )
+        .into_response()
+}
+
+/// Handler for `GET /notifications/` - the current user's notifications.
+async fn handle_notifications_list(
+    State(state): State<Arc<AdminSiteState>>,
+    Query(query): Query<NotificationQueryParams>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(owner) = request_owner(&headers) else {
+        return authentication_required();
+    };
+    let store = &state.notification_store;
+    let results = match store
+        .list(
+            owner,
+            query.unread.unwrap_or(false),
+            query.limit.unwrap_or(20),
+        )
+        .await
+    {
+        Ok(results) => results,
+        Err(e) => return notification_store_error(&e),
+    };
+    match store.unread_count(owner).await {
+        Ok(unread_count) => axum::Json(serde_json::json!({
+            "results": results,
+            "unread_count": unread_count,
+        }))
+        .into_response(),
+        Err(e) => notification_store_error(&e),
+    }
+}
+
+/// Handler for `POST /notifications/:id/read/` - mark a notification read.
+async fn handle_notification_read(
+    State(state): State<Arc<AdminSiteState>>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(owner) = request_owner(&headers) else {
+        return authentication_required();
+    };
+    match state.notification_store.mark_read(owner, id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({"error": "Notification not found"})),
+        )
+            .into_response(),
+        Err(e) => notification_store_error(&e),
+    }
+}
+
+/// Handler for `POST /notifications/read-all/` - mark all notifications read.
+async fn handle_notifications_read_all(
+    State(state): State<Arc<AdminSiteState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(owner) = request_owner(&headers) else {
+        return authentication_required();
+    };
+    match state.notification_store.mark_all_read(owner).await {
+        Ok(marked) => axum::Json(serde_json::json!({"marked": marked})).into_response(),
+        Err(e) => notification_store_error(&e),
+    }
+}
+
+/// Handler for `GET /notifications/stream/` - live notifications as SSE.
+///
+/// Each new notification for the current user is sent as a `notification`
+/// event. If the connection falls behind and updates are dropped, a `resync`
+/// event tells the client to refetch the list.
+async fn handle_notifications_stream(
+    State(state): State<Arc<AdminSiteState>>,
+    Query(params): Query<NotificationStreamParams>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use tokio::sync::broadcast::error::RecvError;
+
+    let Some(owner) = request_owner(&headers)
+        .or_else(|| params.token.as_deref().and_then(token_username))
+        .map(str::to_string)
+    else {
+        return authentication_required();
+    };
+    let Some(receiver) = state.notification_store.subscribe() else {
+        return (
+            StatusCode::NOT_IMPLEMENTED,
+            axum::Json(serde_json::json!({
+                "error": "The notification store does not support live updates"
+            })),
+        )
+            .into_response();
+    };
+
+    let events =
+        futures_util::stream::unfold((receiver, owner), |(mut receiver, owner)| async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(n) if n.recipient == owner => {
+                        Event::default().event("notification").json_data(&n)
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => Ok(Event::default().event("resync").data("")),
+                    Err(RecvError::Closed) => return None,
+                };
+                return Some((event, (receiver, owner)));
+            }
+        });
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_admin::FieldSchema;
+    use django_rs_template::thumbnails::{variant_name, ThumbnailSpec};
+
+    const ALICE: &str = "django-rs-dev-token-alice";
+    const BOB: &str = "django-rs-dev-token-bob";
+    const EDITOR: &str = "django-rs-dev-token-editor";
+
+    #[test]
+    fn test_admin_site_new() {
+        let site = AdminSite::new("admin");
+        assert_eq!(site.name(), "admin");
+        assert_eq!(site.url_prefix_str(), "/api/admin");
+        assert_eq!(site.model_count(), 0);
+        assert!(site.static_dir_path().is_none());
+    }
+
+    #[test]
+    fn test_admin_site_custom_prefix() {
+        let site = AdminSite::new("admin").url_prefix("/custom/api");
+        assert_eq!(site.url_prefix_str(), "/custom/api");
+    }
+
+    #[test]
+    fn test_admin_site_static_dir() {
+        let site = AdminSite::new("admin").static_dir(PathBuf::from("/static"));
+        assert_eq!(site.static_dir_path(), Some(&PathBuf::from("/static")));
     }
 
     #[test]
@@ -567,119 +2974,1775 @@ mod tests {
         assert_eq!(site.model_count(), 1);
     }
 
-    #[test]
-    fn test_admin_site_unregister() {
+    #[test]
+    fn test_admin_site_unregister() {
+        let mut site = AdminSite::new("admin");
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        assert!(site.is_registered("blog.article"));
+        site.unregister("blog.article");
+        assert!(!site.is_registered("blog.article"));
+        assert_eq!(site.model_count(), 0);
+    }
+
+    #[test]
+    fn test_admin_site_get_model_admin() {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.article",
+            ModelAdmin::new("blog", "article").list_per_page(25),
+        );
+        let admin = site.get_model_admin("blog.article").unwrap();
+        assert_eq!(admin.list_per_page, 25);
+    }
+
+    #[test]
+    fn test_admin_site_get_model_admin_not_found() {
+        let site = AdminSite::new("admin");
+        assert!(site.get_model_admin("blog.article").is_none());
+    }
+
+    #[test]
+    fn test_admin_site_registered_models() {
+        let mut site = AdminSite::new("admin");
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        site.register("auth.user", ModelAdmin::new("auth", "user"));
+        let mut models = site.registered_models();
+        models.sort_unstable();
+        assert_eq!(models, vec!["auth.user", "blog.article"]);
+    }
+
+    #[test]
+    fn test_admin_site_action_registry() {
+        let mut site = AdminSite::new("admin");
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        let registry = site.get_action_registry("blog.article").unwrap();
+        assert_eq!(registry.action_names(), vec!["delete_selected"]);
+    }
+
+    #[test]
+    fn test_admin_site_action_registry_not_found() {
+        let site = AdminSite::new("admin");
+        assert!(site.get_action_registry("blog.article").is_none());
+    }
+
+    #[test]
+    fn test_admin_site_unregister_removes_action_registry() {
+        let mut site = AdminSite::new("admin");
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        site.unregister("blog.article");
+        assert!(site.get_action_registry("blog.article").is_none());
+    }
+
+    #[test]
+    fn test_admin_site_debug() {
+        let mut site = AdminSite::new("admin");
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        let debug = format!("{site:?}");
+        assert!(debug.contains("AdminSite"));
+        assert!(debug.contains("admin"));
+    }
+
+    #[test]
+    fn test_admin_site_into_router() {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.article",
+            ModelAdmin::new("blog", "article").fields_schema(vec![
+                FieldSchema::new("id", "BigAutoField").primary_key(),
+                FieldSchema::new("title", "CharField").max_length(200),
+            ]),
+        );
+        // Should not panic
+        let _router = site.into_axum_router();
+    }
+
+    #[test]
+    fn test_admin_site_register_overwrite() {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.article",
+            ModelAdmin::new("blog", "article").list_per_page(10),
+        );
+        site.register(
+            "blog.article",
+            ModelAdmin::new("blog", "article").list_per_page(50),
+        );
+        assert_eq!(site.model_count(), 1);
+        let admin = site.get_model_admin("blog.article").unwrap();
+        assert_eq!(admin.list_per_page, 50);
+    }
+
+    #[test]
+    fn test_admin_site_multiple_registrations() {
+        let mut site = AdminSite::new("admin");
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        site.register("blog.comment", ModelAdmin::new("blog", "comment"));
+        site.register("auth.user", ModelAdmin::new("auth", "user"));
+        assert_eq!(site.model_count(), 3);
+    }
+
+    #[test]
+    fn test_admin_site_with_db_and_log_store() {
+        let db = Arc::new(InMemoryAdminDb::new());
+        let log_store = Arc::new(InMemoryLogEntryStore::new());
+        let mut site = AdminSite::new("admin").db(db).log_store(log_store);
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        let _router = site.into_axum_router();
+    }
+
+    async fn quick_create(
+        site: AdminSite,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        use tower::ServiceExt;
+
+        let request = axum::http::Request::post(uri)
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let response = site.into_axum_router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn tag_site() -> AdminSite {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.tag",
+            ModelAdmin::new("blog", "tag")
+                .fields_schema(vec![
+                    FieldSchema::new("id", "BigAutoField").primary_key(),
+                    FieldSchema::new("name", "CharField"),
+                    FieldSchema::new("color", "CharField").default_value("gray"),
+                ])
+                .quick_create_fields(vec!["name"]),
+        );
+        site.register("blog.article", ModelAdmin::new("blog", "article"));
+        site
+    }
+
+    #[tokio::test]
+    async fn test_quick_create_returns_id_and_label() {
+        let (status, body) =
+            quick_create(tag_site(), "/blog/tag/quick-create/", r#"{"name": "rust"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["id"], 1);
+        assert_eq!(body["label"], "rust");
+    }
+
+    #[tokio::test]
+    async fn test_quick_create_rejects_unlisted_field() {
+        let (status, body) = quick_create(
+            tag_site(),
+            "/blog/tag/quick-create/",
+            r#"{"name": "rust", "color": "red"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("color"));
+    }
+
+    #[tokio::test]
+    async fn test_quick_create_disabled() {
+        let (status, _) = quick_create(tag_site(), "/blog/article/quick-create/", "{}").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = quick_create(tag_site(), "/blog/missing/quick-create/", "{}").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_index_shows_cached_counts() {
+        async fn index_count(router: &Router, name: &str) -> serde_json::Value {
+            let (_, body) = draft_request(router, "GET", "/", None, "").await;
+            let index: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            index["apps"][0]["models"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|m| m["name"] == name)
+                .unwrap()["count"]
+                .clone()
+        }
+
+        let cache = ModelCountCache::new();
+        let mut site = tag_site().model_counts(cache.clone());
+        site.register(
+            "blog.event",
+            ModelAdmin::new("blog", "event").show_count(false),
+        );
+        let router = site.into_axum_router();
+
+        let tag = index_count(&router, "tag").await;
+        assert_eq!(
+            (tag["count"].clone(), tag["stale"].clone()),
+            (0.into(), false.into())
+        );
+        assert!(index_count(&router, "event").await.is_null());
+
+        let (status, _) =
+            draft_request(&router, "POST", "/blog/tag/", None, r#"{"name": "rust"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(cache.get("blog.tag").unwrap().stale);
+
+        // The stale count is served while it is refreshed in the background.
+        let tag = index_count(&router, "tag").await;
+        assert_eq!(
+            (tag["count"].clone(), tag["stale"].clone()),
+            (0.into(), true.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_fills_unique_prepopulated_slug() {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.page",
+            ModelAdmin::new("blog", "page").prepopulated_fields(HashMap::from([(
+                "slug".to_string(),
+                vec!["title".to_string()],
+            )])),
+        );
+        let router = site.into_axum_router();
+        let create = |body: &'static str| {
+            let router = router.clone();
+            async move {
+                let (status, body) =
+                    draft_request(&router, "POST", "/blog/page/", None, body).await;
+                assert_eq!(status, StatusCode::CREATED);
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()["slug"].clone()
+            }
+        };
+
+        assert_eq!(create(r#"{"title": "Hello, World!"}"#).await, "hello-world");
+        assert_eq!(
+            create(r#"{"title": "Hello World", "slug": ""}"#).await,
+            "hello-world-2"
+        );
+        assert_eq!(
+            create(r#"{"title": "Hello World", "slug": "hi"}"#).await,
+            "hi"
+        );
+        assert_eq!(create(r#"{"title": "hello world"}"#).await, "hello-world-3");
+
+        let (_, body) = draft_request(&router, "GET", "/blog/page/schema", None, "").await;
+        let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            schema["prepopulated_fields"],
+            serde_json::json!({"slug": ["title"]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_and_update_read_localized_input() {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "shop.product",
+            ModelAdmin::new("shop", "product").fields_schema(vec![
+                FieldSchema::new("id", "BigAutoField").primary_key(),
+                FieldSchema::new("price", "DecimalField"),
+                FieldSchema::new("available_from", "DateField"),
+            ]),
+        );
+        let router = site.into_axum_router();
+
+        let (status, body) = draft_request(
+            &router,
+            "POST",
+            "/shop/product/?lang=de",
+            None,
+            r#"{"price": "1.299,95", "available_from": "01.02.2025"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let obj: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(obj["price"], "1299.95");
+        assert_eq!(obj["available_from"], "2025-02-01");
+
+        let uri = format!("/shop/product/{}/?lang=en-GB", obj["id"]);
+        let (status, body) = draft_request(
+            &router,
+            "PATCH",
+            &uri,
+            None,
+            r#"{"available_from": "15/03/2025"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let obj: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(obj["available_from"], "2025-03-15");
+
+        let (status, body) =
+            draft_request(&router, "PATCH", &uri, None, r#"{"price": "12,5"}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["errors"]["price"], "Enter a number.");
+
+        // Without `lang`, values are canonical whatever Accept-Language says.
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/shop/product/")
+            .header("content-type", "application/json")
+            .header("accept-language", "de-DE,de;q=0.9")
+            .body(axum::body::Body::from(r#"{"price": "1.250"}"#))
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(router.clone(), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let obj: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(obj["price"], "1.250");
+    }
+
+    #[tokio::test]
+    async fn test_lookup_field_addresses_objects_by_slug() {
+        let schema = vec![
+            FieldSchema::new("id", "BigAutoField").primary_key(),
+            FieldSchema::new("slug", "SlugField").unique(),
+            FieldSchema::new("title", "CharField"),
+        ];
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.page",
+            ModelAdmin::new("blog", "page")
+                .fields_schema(schema.clone())
+                .lookup_field("slug"),
+        );
+        site.register(
+            "blog.note",
+            ModelAdmin::new("blog", "note")
+                .fields_schema(schema)
+                .lookup_field("title"),
+        );
+        let router = site.into_axum_router();
+        for body in [
+            r#"{"slug": "about", "title": "About"}"#,
+            r#"{"slug": "contact", "title": "Contact"}"#,
+        ] {
+            let (status, _) = draft_request(&router, "POST", "/blog/page/", None, body).await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (status, body) = draft_request(&router, "GET", "/blog/page/contact/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let obj: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(obj["id"], 2);
+        let (status, _) = draft_request(&router, "GET", "/blog/page/2/", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = draft_request(
+            &router,
+            "PATCH",
+            "/blog/page/about/",
+            None,
+            r#"{"title": "About us"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let obj: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            (obj["id"].clone(), obj["title"].clone()),
+            (1.into(), "About us".into())
+        );
+
+        let (status, _) = draft_request(&router, "DELETE", "/blog/page/about/", None, "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, body) = draft_request(&router, "GET", "/blog/page/about/", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            error["error"],
+            "Object with slug 'about' not found in 'blog.page'"
+        );
+
+        let (_, body) = draft_request(&router, "GET", "/blog/page/schema", None, "").await;
+        let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(schema["lookup_field"], "slug");
+
+        // A lookup field that is not unique is refused.
+        let (status, _) =
+            draft_request(&router, "POST", "/blog/note/", None, r#"{"title": "Todo"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = draft_request(&router, "GET", "/blog/note/Todo/", None, "").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_print_renders_object_html() {
+        let engine = Arc::new(Engine::new());
+        engine.add_string_template("label.html", "<p>Label: {{ object.name }}</p>");
+        let mut site = tag_site().template_engine(engine);
+        site.register(
+            "blog.label",
+            ModelAdmin::new("blog", "label").print_template("label.html"),
+        );
+        let router = site.into_axum_router();
+        let (status, _) =
+            draft_request(&router, "POST", "/blog/tag/", None, r#"{"name": "rust"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = draft_request(&router, "GET", "/blog/tag/1/print/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let html = String::from_utf8(body).unwrap();
+        assert!(html.contains("<th>name</th><td>rust</td>"));
+
+        let (status, _) = draft_request(
+            &router,
+            "POST",
+            "/blog/label/",
+            None,
+            r#"{"name": "urgent"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, body) = draft_request(&router, "GET", "/blog/label/1/print/", None, "").await;
+        assert_eq!(body, b"<p>Label: urgent</p>");
+
+        let (status, _) =
+            draft_request(&router, "GET", "/blog/tag/1/print/?format=docx", None, "").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = draft_request(&router, "GET", "/blog/tag/9/print/", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_list_humanizes_datetime_columns() {
+        let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.event",
+            ModelAdmin::new("blog", "event")
+                .list_display(vec!["name", "starts_at"])
+                .fields_schema(vec![
+                    FieldSchema::new("id", "BigAutoField").primary_key(),
+                    FieldSchema::new("name", "CharField"),
+                    FieldSchema::new("starts_at", "DateTimeField"),
+                ]),
+        );
+        let router = site.into_axum_router();
+        let (status, _) = draft_request(
+            &router,
+            "POST",
+            "/blog/event/",
+            None,
+            r#"{"name": "launch", "starts_at": "2020-01-01T00:00:00Z"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) =
+            draft_request(&router, "GET", "/blog/event/?tz=-300&lang=en", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let display = &body["results"][0]["_datetimes"]["starts_at"];
+        assert_eq!(display["iso"], "2019-12-31T19:00:00-05:00");
+        assert!(display["relative"].as_str().unwrap().ends_with("years ago"));
+    }
+
+    #[test]
+    fn test_accept_language() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(accept_language(&headers), None);
+        headers.insert(
+            "accept-language",
+            "fr-CA,fr;q=0.9,en;q=0.8".parse().unwrap(),
+        );
+        assert_eq!(accept_language(&headers), Some("fr-CA".to_string()));
+        headers.insert("accept-language", "*".parse().unwrap());
+        assert_eq!(accept_language(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_detail_lists_image_variants() {
+        struct Thumbs;
+        impl ThumbnailBackend for Thumbs {
+            fn thumbnail_url(
+                &self,
+                name: &str,
+                spec: &ThumbnailSpec,
+            ) -> Result<String, django_rs_core::DjangoError> {
+                Ok(format!("/media/{}", variant_name(name, spec)))
+            }
+        }
+
+        let mut site = AdminSite::new("admin").thumbnail_backend(Arc::new(Thumbs));
+        site.register(
+            "shop.product",
+            ModelAdmin::new("shop", "product")
+                .fields_schema(vec![
+                    FieldSchema::new("name", "CharField"),
+                    FieldSchema::new("photo", "ImageField"),
+                    FieldSchema::new("banner", "ImageField"),
+                ])
+                .image_variants(vec![
+                    ThumbnailSpec::new(64, 64, true),
+                    ThumbnailSpec::new(400, 300, false),
+                ]),
+        );
+        let router = site.into_axum_router();
+        let (status, _) = draft_request(
+            &router,
+            "POST",
+            "/shop/product/",
+            None,
+            r#"{"name": "Mug", "photo": "products/mug.jpg", "banner": ""}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = draft_request(&router, "GET", "/shop/product/1/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body["_variants"],
+            serde_json::json!({"photo": {
+                "64x64_crop": "/media/products/mug.64x64_crop.jpg",
+                "400x300": "/media/products/mug.400x300.jpg",
+            }})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detail_etag_and_if_match() {
+        use tower::ServiceExt;
+
+        async fn send(
+            router: &Router,
+            method: &str,
+            if_match: Option<&str>,
+            body: &str,
+        ) -> axum::response::Response {
+            let mut request = axum::http::Request::builder()
+                .method(method)
+                .uri("/blog/tag/1/")
+                .header("content-type", "application/json");
+            if let Some(tag) = if_match {
+                request = request.header("if-match", tag);
+            }
+            let request = request
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap();
+            router.clone().oneshot(request).await.unwrap()
+        }
+        fn etag(response: &axum::response::Response) -> String {
+            response.headers()["etag"].to_str().unwrap().to_string()
+        }
+
+        let router = tag_site().into_axum_router();
+        let (status, _) =
+            draft_request(&router, "POST", "/blog/tag/", None, r#"{"name": "rust"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let response = send(&router, "GET", None, "").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let loaded = etag(&response);
+        assert!(loaded.starts_with('"') && loaded.ends_with('"'));
+        assert_eq!(etag(&send(&router, "GET", None, "").await), loaded);
+
+        let response = send(&router, "PATCH", Some(&loaded), r#"{"name": "go"}"#).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let updated = etag(&response);
+        assert_ne!(updated, loaded);
+        assert_eq!(etag(&send(&router, "GET", None, "").await), updated);
+
+        // A client still holding the old tag loses the race
+        let response = send(&router, "PUT", Some(&loaded), r#"{"name": "zig"}"#).await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(etag(&response), updated);
+        let response = send(&router, "DELETE", Some(&loaded), "").await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        let response = send(&router, "PUT", Some("*"), r#"{"name": "zig"}"#).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let current = etag(&response);
+        let weak = format!("W/{current}");
+        let response = send(&router, "DELETE", Some(&weak), "").await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        let response = send(&router, "DELETE", Some(&current), "").await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = send(&router, "PUT", Some("*"), r#"{"name": "zig"}"#).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// A database whose every query fails.
+    struct BrokenDb;
+
+    #[async_trait::async_trait]
+    impl AdminDbExecutor for BrokenDb {
+        async fn list_objects(
+            &self,
+            _admin: &ModelAdmin,
+            _params: &AdminListParams,
+        ) -> Result<crate::db::AdminListResult, String> {
+            Err("connection refused".to_string())
+        }
+
+        async fn get_object(
+            &self,
+            _admin: &ModelAdmin,
+            _pk: &str,
+        ) -> Result<serde_json::Value, String> {
+            Err("connection refused".to_string())
+        }
+
+        async fn create_object(
+            &self,
+            _admin: &ModelAdmin,
+            _data: &HashMap<String, serde_json::Value>,
+        ) -> Result<serde_json::Value, String> {
+            Err("connection refused".to_string())
+        }
+
+        async fn update_object(
+            &self,
+            _admin: &ModelAdmin,
+            _pk: &str,
+            _data: &HashMap<String, serde_json::Value>,
+        ) -> Result<serde_json::Value, String> {
+            Err("connection refused".to_string())
+        }
+
+        async fn delete_object(&self, _admin: &ModelAdmin, _pk: &str) -> Result<bool, String> {
+            Err("connection refused".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_if_match_with_failed_lookup() {
+        let mut site = AdminSite::new("admin").db(Arc::new(BrokenDb));
+        site.register("blog.tag", ModelAdmin::new("blog", "tag"));
+        let router = site.into_axum_router();
+        let request = axum::http::Request::builder()
+            .method("DELETE")
+            .uri("/blog/tag/1/")
+            .header("if-match", "*")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(router, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_docs_endpoint() {
+        use django_rs_http::urls::pattern::path;
+        use django_rs_http::urls::resolver::{root, URLEntry};
+
+        let handler: django_rs_http::urls::pattern::RouteHandler =
+            Arc::new(|_req| Box::pin(async { django_rs_http::HttpResponse::ok("") }));
+        let urls = root(vec![URLEntry::Pattern(
+            path("tags/<int:id>/", handler, Some("tag-detail")).unwrap(),
+        )])
+        .unwrap();
+        let router = tag_site().docs_urls(&urls).into_axum_router();
+
+        let (status, body) = draft_request(&router, "GET", "/docs/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let docs: AdminDocs = serde_json::from_slice(&body).unwrap();
+        let keys: Vec<&str> = docs.models.iter().map(|m| m.key.as_str()).collect();
+        assert_eq!(keys, ["blog.article", "blog.tag"]);
+        assert_eq!(docs.models[1].fields[2].name, "color");
+        assert_eq!(docs.urls[0].route, "tags/<int:id>/");
+        assert_eq!(docs.urls[0].name.as_deref(), Some("tag-detail"));
+    }
+
+    #[tokio::test]
+    async fn test_permission_matrix_endpoint() {
+        let router = tag_site().into_axum_router();
+        let (status, _) = draft_request(&router, "GET", "/permissions/", None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = draft_request(&router, "GET", "/permissions/", Some(EDITOR), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let admin = Some(DEV_ADMIN_TOKEN);
+        let (status, body) = draft_request(&router, "GET", "/permissions/", admin, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let matrix: crate::permission_matrix::PermissionMatrix =
+            serde_json::from_slice(&body).unwrap();
+        assert_eq!(matrix.permissions.len(), 8);
+        assert_eq!(matrix.permissions[0].content_type, "blog.article");
+
+        // The default store edits the users of the site's auth backend.
+        let backend = Arc::new(ModelBackend::new());
+        backend
+            .add_user(django_rs_auth::user::AbstractUser::new("alice"))
+            .await;
+        let router = tag_site().auth_backend(backend.clone()).into_axum_router();
+        let changes = r#"{"changes": [
+            {"subject": "user", "name": "alice", "permission": "blog.add_tag", "granted": true},
+            {"subject": "user", "name": "alice", "permission": "blog.view_tag", "granted": true}
+        ]}"#;
+        let (status, _) = draft_request(&router, "PATCH", "/permissions/", None, changes).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) =
+            draft_request(&router, "PATCH", "/permissions/", Some(EDITOR), changes).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = draft_request(&router, "PATCH", "/permissions/", admin, changes).await;
+        assert_eq!(status, StatusCode::OK);
+        let response: PermissionMatrixUpdateResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.applied, 2);
+        assert_eq!(
+            response.matrix.users[0].permissions,
+            vec!["blog.add_tag", "blog.view_tag"]
+        );
+        let alice = backend.get_user("alice").await.unwrap().unwrap();
+        assert!(django_rs_auth::permissions::has_perm(
+            &alice,
+            "blog.add_tag"
+        ));
+        assert!(!django_rs_auth::permissions::has_perm(
+            &alice,
+            "blog.delete_tag"
+        ));
+
+        let invalid = r#"{"changes": [
+            {"subject": "user", "name": "alice", "permission": "blog.add_tag", "granted": false},
+            {"subject": "user", "name": "ghost", "permission": "blog.add_tag", "granted": true}
+        ]}"#;
+        let (status, body) = draft_request(&router, "PATCH", "/permissions/", admin, invalid).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["errors"][0], "User 'ghost' does not exist");
+        let (_, body) = draft_request(&router, "GET", "/permissions/", admin, "").await;
+        let matrix: crate::permission_matrix::PermissionMatrix =
+            serde_json::from_slice(&body).unwrap();
+        assert_eq!(matrix.users[0].permissions.len(), 2);
+        let alice = backend.get_user("alice").await.unwrap().unwrap();
+        assert!(django_rs_auth::permissions::has_perm(
+            &alice,
+            "blog.add_tag"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_endpoint_and_read_only_mode() {
+        let store = Arc::new(InMemoryMaintenanceStore::new());
+        let router = tag_site()
+            .maintenance_store(store.clone())
+            .into_axum_router();
+
+        let (status, _) = draft_request(
+            &router,
+            "PUT",
+            "/maintenance/",
+            None,
+            r#"{"read_only": "admin"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = draft_request(
+            &router,
+            "PUT",
+            "/maintenance/",
+            Some(EDITOR),
+            r#"{"read_only": "admin"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = draft_request(
+            &router,
+            "PUT",
+            "/maintenance/",
+            Some(DEV_ADMIN_TOKEN),
+            r#"{"read_only": "admin", "retry_after": 30}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let state: MaintenanceState = serde_json::from_slice(&body).unwrap();
+        assert_eq!(state.updated_by.as_deref(), Some("admin"));
+        assert_eq!(store.get().await.unwrap().read_only, ReadOnlyScope::Admin);
+
+        let (status, body) =
+            draft_request(&router, "POST", "/blog/article/", None, r#"{"title": "x"}"#).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["read_only"], true);
+        let (status, _) = draft_request(&router, "GET", "/blog/article/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = draft_request(&router, "GET", "/maintenance/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["retry_after"], 30);
+        assert_eq!(body["updated_by"], "admin");
+
+        draft_request(&router, "PUT", "/maintenance/", Some(DEV_ADMIN_TOKEN), "{}").await;
+        let (status, _) =
+            draft_request(&router, "POST", "/blog/article/", None, r#"{"title": "x"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_visibility_rules_enforced() {
+        use django_rs_forms::conditions::{Condition, VisibilityRule};
+
         let mut site = AdminSite::new("admin");
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        assert!(site.is_registered("blog.article"));
-        site.unregister("blog.article");
-        assert!(!site.is_registered("blog.article"));
-        assert_eq!(site.model_count(), 0);
+        site.register(
+            "accounts.customer",
+            ModelAdmin::new("accounts", "customer")
+                .fields_schema(vec![
+                    FieldSchema::new("id", "BigAutoField").primary_key(),
+                    FieldSchema::new("account_type", "CharField"),
+                    FieldSchema::new("company_name", "CharField"),
+                ])
+                .visibility_rules(vec![VisibilityRule::new(
+                    "company_name",
+                    Condition::eq("account_type", "business"),
+                )]),
+        );
+        let router = site.into_axum_router();
+        let send = |method: &'static str, uri: &'static str, body: &'static str| {
+            let router = router.clone();
+            async move {
+                let (_, body) = draft_request(&router, method, uri, None, body).await;
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let schema = send("GET", "/accounts/customer/schema", "").await;
+        assert_eq!(
+            schema["visibility_rules"],
+            serde_json::json!([{
+                "field": "company_name",
+                "when": {"op": "eq", "field": "account_type", "value": "business"}
+            }])
+        );
+
+        let obj = send(
+            "POST",
+            "/accounts/customer/",
+            r#"{"account_type": "personal", "company_name": "Acme"}"#,
+        )
+        .await;
+        assert!(obj.get("company_name").is_none());
+
+        let obj = send(
+            "PUT",
+            "/accounts/customer/1/",
+            r#"{"account_type": "business", "company_name": "Acme"}"#,
+        )
+        .await;
+        assert_eq!(obj["company_name"], "Acme");
+
+        let obj = send(
+            "PUT",
+            "/accounts/customer/1/",
+            r#"{"account_type": "personal"}"#,
+        )
+        .await;
+        assert_eq!(obj["company_name"], serde_json::Value::Null);
     }
 
-    #[test]
-    fn test_admin_site_get_model_admin() {
+    #[tokio::test]
+    async fn test_filter_horizontal_relation_endpoints() {
         let mut site = AdminSite::new("admin");
+        site.register(
+            "blog.tag",
+            ModelAdmin::new("blog", "tag")
+                .fields_schema(vec![
+                    FieldSchema::new("id", "BigAutoField").primary_key(),
+                    FieldSchema::new("name", "CharField"),
+                ])
+                .search_fields(vec!["name"])
+                .ordering(vec!["name"])
+                .list_max_show_all(3),
+        );
         site.register(
             "blog.article",
-            ModelAdmin::new("blog", "article").list_per_page(25),
+            ModelAdmin::new("blog", "article")
+                .fields_schema(vec![
+                    FieldSchema::new("id", "BigAutoField").primary_key(),
+                    FieldSchema::new("title", "CharField"),
+                    FieldSchema::new("tags", "ManyToManyField").relation("blog.tag"),
+                ])
+                .filter_horizontal(vec!["tags"]),
         );
-        let admin = site.get_model_admin("blog.article").unwrap();
-        assert_eq!(admin.list_per_page, 25);
+        let router = site.into_axum_router();
+        for name in ["rust", "python", "go", "ruby"] {
+            let body = format!(r#"{{"name": "{name}"}}"#);
+            draft_request(&router, "POST", "/blog/tag/", None, &body).await;
+        }
+        draft_request(
+            &router,
+            "POST",
+            "/blog/article/",
+            None,
+            r#"{"title": "Hi"}"#,
+        )
+        .await;
+        let get = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let (status, body) = draft_request(&router, "GET", uri, None, "").await;
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+        let labels = |body: &serde_json::Value| -> Vec<String> {
+            body["results"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|c| c["label"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let (_, schema) = get("/blog/article/schema").await;
+        assert_eq!(schema["filter_horizontal"], serde_json::json!(["tags"]));
+
+        let (status, body) = get("/blog/article/1/relations/tags/?page_size=50").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(labels(&body), ["go", "python", "ruby"]);
+        assert_eq!(body["page_size"], 3);
+
+        let (status, body) = draft_request(
+            &router,
+            "PATCH",
+            "/blog/article/1/relations/tags/",
+            None,
+            r#"{"ids": [1, 4, 1]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["ids"], serde_json::json!([1, 4]));
+
+        let (_, body) = get("/blog/article/1/relations/tags/?side=chosen").await;
+        assert_eq!(labels(&body), ["ruby", "rust"]);
+        assert_eq!(body["results"][0]["id"], 4);
+        let (_, body) = get("/blog/article/1/relations/tags/?search=o&page_size=1").await;
+        assert_eq!(labels(&body), ["go"]);
+        assert_eq!(body["count"], 2);
+        assert_eq!(body["has_next"], true);
+        assert_eq!(
+            body["next"],
+            "http://localhost/blog/article/1/relations/tags/?search=o&page_size=1&page=2"
+        );
+
+        // Unknown ids leave the relation untouched.
+        let (status, _) = draft_request(
+            &router,
+            "PATCH",
+            "/blog/article/1/relations/tags/",
+            None,
+            r#"{"ids": [2, 99]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (_, body) = get("/blog/article/1/relations/tags/?side=chosen").await;
+        assert_eq!(labels(&body), ["ruby", "rust"]);
+
+        let (status, _) = get("/blog/article/1/relations/title/").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get("/blog/article/9/relations/tags/").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_admin_site_get_model_admin_not_found() {
-        let site = AdminSite::new("admin");
-        assert!(site.get_model_admin("blog.article").is_none());
+    #[tokio::test]
+    async fn test_scheduled_publishing_filter_and_publish_now() {
+        let db = Arc::new(InMemoryAdminDb::new());
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "publish_status"])
+            .scheduled_publishing("publish_at", None);
+        for (title, publish_at) in [
+            ("draft", serde_json::Value::Null),
+            ("scheduled", serde_json::json!("2999-01-01T00:00:00Z")),
+            ("live", serde_json::json!("2000-01-01T00:00:00Z")),
+        ] {
+            let data = HashMap::from([
+                ("title".to_string(), serde_json::json!(title)),
+                ("publish_at".to_string(), publish_at),
+            ]);
+            db.create_object(&admin, &data).await.unwrap();
+        }
+        let mut site = AdminSite::new("admin").db(db);
+        site.register("blog.article", admin);
+        let router = site.into_axum_router();
+
+        let list = |query: &'static str| {
+            let router = router.clone();
+            async move {
+                let (status, body) =
+                    draft_request(&router, "GET", &format!("/blog/article/{query}"), None, "")
+                        .await;
+                assert_eq!(status, StatusCode::OK);
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                body["results"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|obj| {
+                        (
+                            obj["title"].as_str().unwrap().to_string(),
+                            obj["publish_status"].as_str().unwrap().to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            list("?publish_status=scheduled").await,
+            vec![("scheduled".to_string(), "scheduled".to_string())]
+        );
+        assert_eq!(list("").await.len(), 3);
+
+        let (status, body) = draft_request(
+            &router,
+            "POST",
+            "/blog/article/action/",
+            None,
+            r#"{"action": "publish_now", "ids": ["1", "2"]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["affected"], 2);
+        assert_eq!(list("?publish_status=live").await.len(), 3);
+
+        let (status, _) = draft_request(
+            &router,
+            "POST",
+            "/blog/article/action/",
+            None,
+            r#"{"action": "archive", "ids": ["1"]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_admin_site_registered_models() {
-        let mut site = AdminSite::new("admin");
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        site.register("auth.user", ModelAdmin::new("auth", "user"));
-        let mut models = site.registered_models();
-        models.sort_unstable();
-        assert_eq!(models, vec!["auth.user", "blog.article"]);
+    async fn draft_request(
+        router: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (StatusCode, Vec<u8>) {
+        use tower::ServiceExt;
+
+        let mut request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        let request = request
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, bytes.to_vec())
     }
 
-    #[test]
-    fn test_admin_site_action_registry() {
-        let mut site = AdminSite::new("admin");
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        let registry = site.get_action_registry("blog.article").unwrap();
-        assert_eq!(registry.action_names(), vec!["delete_selected"]);
+    #[tokio::test]
+    async fn test_draft_autosave_round_trip() {
+        let router = tag_site().into_axum_router();
+        let uri = "/blog/article/new/draft/";
+
+        let (status, _) = draft_request(&router, "GET", uri, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = draft_request(
+            &router,
+            "PUT",
+            uri,
+            Some(ALICE),
+            r#"{"title": "Work in progress"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let saved: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(saved["object_id"], "new");
+        assert_eq!(saved["content_type"], "blog.article");
+
+        let (status, body) = draft_request(&router, "GET", uri, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let draft: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(draft["data"]["title"], "Work in progress");
+
+        // Drafts are private to the user who saved them.
+        let (status, _) = draft_request(&router, "GET", uri, Some(BOB), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = draft_request(&router, "DELETE", uri, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = draft_request(&router, "GET", uri, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_admin_site_action_registry_not_found() {
-        let site = AdminSite::new("admin");
-        assert!(site.get_action_registry("blog.article").is_none());
+    #[tokio::test]
+    async fn test_draft_requires_auth_and_registered_model() {
+        let router = tag_site().into_axum_router();
+        let (status, _) = draft_request(&router, "PUT", "/blog/article/1/draft/", None, "{}").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) =
+            draft_request(&router, "GET", "/blog/missing/1/draft/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_admin_site_unregister_removes_action_registry() {
-        let mut site = AdminSite::new("admin");
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        site.unregister("blog.article");
-        assert!(site.get_action_registry("blog.article").is_none());
+    async fn notifications_site() -> (AdminSite, Arc<InMemoryNotificationStore>) {
+        use crate::notifications::{AdminNotification, NotificationKind};
+
+        let store = Arc::new(InMemoryNotificationStore::new());
+        for (recipient, message) in [
+            ("alice", "Import finished"),
+            ("alice", "You were mentioned"),
+            ("bob", "Hi"),
+        ] {
+            store
+                .push(AdminNotification::new(
+                    recipient,
+                    NotificationKind::System,
+                    message,
+                ))
+                .await
+                .unwrap();
+        }
+        let site = tag_site().notification_store(store.clone());
+        (site, store)
     }
 
-    #[test]
-    fn test_admin_site_debug() {
-        let mut site = AdminSite::new("admin");
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        let debug = format!("{site:?}");
-        assert!(debug.contains("AdminSite"));
-        assert!(debug.contains("admin"));
+    #[tokio::test]
+    async fn test_notifications_list_and_mark_read() {
+        let (site, _) = notifications_site().await;
+        let router = site.into_axum_router();
+
+        let (status, _) = draft_request(&router, "GET", "/notifications/", None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, body) =
+            draft_request(&router, "GET", "/notifications/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["unread_count"], 2);
+        assert_eq!(body["results"][0]["message"], "You were mentioned");
+
+        // Bob's notification (id 3) is not Alice's to mark.
+        let (status, _) =
+            draft_request(&router, "POST", "/notifications/3/read/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) =
+            draft_request(&router, "POST", "/notifications/1/read/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (_, body) = draft_request(
+            &router,
+            "GET",
+            "/notifications/?unread=true",
+            Some(ALICE),
+            "",
+        )
+        .await;
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["unread_count"], 1);
+        assert_eq!(body["results"].as_array().unwrap().len(), 1);
+
+        let (_, body) =
+            draft_request(&router, "POST", "/notifications/read-all/", Some(ALICE), "").await;
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["marked"], 1);
     }
 
-    #[test]
-    fn test_admin_site_into_router() {
-        let mut site = AdminSite::new("admin");
-        site.register(
-            "blog.article",
-            ModelAdmin::new("blog", "article").fields_schema(vec![
-                FieldSchema::new("id", "BigAutoField").primary_key(),
-                FieldSchema::new("title", "CharField").max_length(200),
-            ]),
-        );
-        // Should not panic
-        let _router = site.into_axum_router();
+    #[tokio::test]
+    async fn test_notifications_stream_sends_own_events() {
+        use crate::notifications::{AdminNotification, NotificationKind};
+        use futures_util::StreamExt;
+        use tower::ServiceExt;
+
+        let (site, store) = notifications_site().await;
+        let request = axum::http::Request::builder()
+            .uri("/notifications/stream/?token=django-rs-dev-token-alice")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = site.into_axum_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        store
+            .push(AdminNotification::new(
+                "bob",
+                NotificationKind::Mention,
+                "not for alice",
+            ))
+            .await
+            .unwrap();
+        store
+            .push(AdminNotification::new(
+                "alice",
+                NotificationKind::ImportFinished,
+                "done",
+            ))
+            .await
+            .unwrap();
+
+        let mut body = response.into_body().into_data_stream();
+        let frame = body.next().await.unwrap().unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(frame.starts_with("event: notification\n"));
+        assert!(frame.contains(r#""message":"done""#));
     }
 
-    #[test]
-    fn test_admin_site_register_overwrite() {
-        let mut site = AdminSite::new("admin");
-        site.register(
-            "blog.article",
-            ModelAdmin::new("blog", "article").list_per_page(10),
+    #[tokio::test]
+    async fn test_comment_thread_with_mentions() {
+        let db = Arc::new(InMemoryAdminDb::new());
+        let admin = ModelAdmin::new("blog", "article").comments(true);
+        let data = HashMap::from([("title".to_string(), serde_json::json!("Hello"))]);
+        db.create_object(&admin, &data).await.unwrap();
+        let notifications = Arc::new(InMemoryNotificationStore::new());
+        let mut site = AdminSite::new("admin")
+            .db(db)
+            .notification_store(notifications.clone());
+        site.register("blog.article", admin);
+        site.register("blog.tag", ModelAdmin::new("blog", "tag"));
+        let router = site.into_axum_router();
+        let uri = "/blog/article/1/comments/";
+
+        let (status, _) = draft_request(&router, "GET", uri, None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = draft_request(&router, "GET", uri, Some("alice"), "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) =
+            draft_request(&router, "GET", "/blog/tag/1/comments/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = draft_request(
+            &router,
+            "POST",
+            "/blog/article/9/comments/",
+            Some(ALICE),
+            r#"{"body": "Anyone?"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) =
+            draft_request(&router, "POST", uri, Some(ALICE), r#"{"body": "  "}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = draft_request(
+            &router,
+            "POST",
+            uri,
+            Some(ALICE),
+            r#"{"body": "@bob please check the intro, thanks @alice"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let comment: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(comment["author"], "alice");
+        assert_eq!(comment["mentions"], serde_json::json!(["bob", "alice"]));
+        let id = comment["id"].as_u64().unwrap();
+
+        assert_eq!(notifications.unread_count("bob").await.unwrap(), 1);
+        assert_eq!(notifications.unread_count("alice").await.unwrap(), 0);
+        let mention = &notifications.list("bob", false, 10).await.unwrap()[0];
+        assert_eq!(mention.kind, NotificationKind::Mention);
+        assert_eq!(mention.link.as_deref(), Some("/blog/article/1/"));
+        let (status, body) = draft_request(&router, "GET", "/notifications/", Some(BOB), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let inbox: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(inbox["unread_count"], 1);
+
+        let (status, body) = draft_request(&router, "GET", uri, Some(BOB), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let thread: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(thread["results"].as_array().unwrap().len(), 1);
+
+        let comment_uri = format!("{uri}{id}/");
+        let (status, _) = draft_request(&router, "DELETE", &comment_uri, Some(BOB), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = draft_request(&router, "DELETE", &comment_uri, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = draft_request(&router, "DELETE", &comment_uri, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    async fn export_site(
+        rows: usize,
+        threshold: usize,
+    ) -> (AdminSite, Arc<InMemoryNotificationStore>) {
+        let db = Arc::new(InMemoryAdminDb::new());
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["id", "title"])
+            .search_fields(vec!["title"]);
+        for i in 0..rows {
+            let data =
+                HashMap::from([("title".to_string(), serde_json::json!(format!("Post {i}")))]);
+            db.create_object(&admin, &data).await.unwrap();
+        }
+        let notifications = Arc::new(InMemoryNotificationStore::new());
+        let mut site = AdminSite::new("admin")
+            .db(db)
+            .notification_store(notifications.clone())
+            .export_row_threshold(threshold);
+        site.register("blog.article", admin);
+        (site, notifications)
+    }
+
+    #[tokio::test]
+    async fn test_export_streams_csv() {
+        let (site, _) = export_site(3, 10).await;
+        let router = site.into_axum_router();
+
+        let (status, _) = draft_request(&router, "GET", "/blog/article/export/", None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = draft_request(&router, "GET", "/blog/tag/export/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = draft_request(
+            &router,
+            "GET",
+            "/blog/article/export/?search=Post%201",
+            Some(ALICE),
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(String::from_utf8(body).unwrap(), "id,title\r\n2,Post 1\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_list_links_pages() {
+        use tower::ServiceExt;
+
+        let (site, _) = export_site(5, 10).await;
+        let router = site.into_axum_router();
+        let request = axum::http::Request::builder()
+            .uri("/blog/article/?page=2&page_size=2&search=Post")
+            .header(axum::http::header::HOST, "admin.example.com")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(
+            response.headers()[axum::http::header::LINK],
+            "<http://admin.example.com/blog/article/?page_size=2&search=Post>; rel=\"first\", \
+             <http://admin.example.com/blog/article/?page_size=2&search=Post>; rel=\"prev\", \
+             <http://admin.example.com/blog/article/?page_size=2&search=Post&page=3>; rel=\"next\", \
+             <http://admin.example.com/blog/article/?page_size=2&search=Post&page=3>; rel=\"last\""
         );
-        site.register(
-            "blog.article",
-            ModelAdmin::new("blog", "article").list_per_page(50),
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["count"], 5);
+        assert_eq!(body["page"], 2);
+        assert_eq!(body["total_pages"], 3);
+        assert_eq!(
+            body["next"],
+            "http://admin.example.com/blog/article/?page_size=2&search=Post&page=3"
+        );
+        assert_eq!(
+            body["previous"],
+            "http://admin.example.com/blog/article/?page_size=2&search=Post"
         );
-        assert_eq!(site.model_count(), 1);
-        let admin = site.get_model_admin("blog.article").unwrap();
-        assert_eq!(admin.list_per_page, 50);
     }
 
-    #[test]
-    fn test_admin_site_multiple_registrations() {
-        let mut site = AdminSite::new("admin");
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        site.register("blog.comment", ModelAdmin::new("blog", "comment"));
-        site.register("auth.user", ModelAdmin::new("auth", "user"));
-        assert_eq!(site.model_count(), 3);
+    #[tokio::test]
+    async fn test_list_multi_column_ordering() {
+        let db = Arc::new(InMemoryAdminDb::new());
+        let admin = ModelAdmin::new("blog", "article")
+            .list_display(vec!["title", "status"])
+            .fields_schema(vec![
+                FieldSchema::new("title", "CharField"),
+                FieldSchema::new("status", "CharField"),
+            ]);
+        for (title, status) in [("Bob", "draft"), ("Alice", "published"), ("Carol", "draft")] {
+            let data = HashMap::from([
+                ("title".to_string(), serde_json::json!(title)),
+                ("status".to_string(), serde_json::json!(status)),
+            ]);
+            db.create_object(&admin, &data).await.unwrap();
+        }
+        let mut site = AdminSite::new("admin").db(db);
+        site.register("blog.article", admin);
+        let router = site.into_axum_router();
+
+        let titles = |body: Vec<u8>| {
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            body["results"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|o| o["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        let (status, body) =
+            draft_request(&router, "GET", "/blog/article/?o=1,0", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(titles(body), ["Bob", "Carol", "Alice"]);
+
+        let (_, body) = draft_request(
+            &router,
+            "GET",
+            "/blog/article/?o=status,-title",
+            Some(ALICE),
+            "",
+        )
+        .await;
+        assert_eq!(titles(body), ["Carol", "Bob", "Alice"]);
+
+        let (status, body) =
+            draft_request(&router, "GET", "/blog/article/?o=1,5", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "Ordering index 5 is out of range");
     }
 
-    #[test]
-    fn test_admin_site_with_db_and_log_store() {
-        let db = Arc::new(InMemoryAdminDb::new());
-        let log_store = Arc::new(InMemoryLogEntryStore::new());
-        let mut site = AdminSite::new("admin").db(db).log_store(log_store);
-        site.register("blog.article", ModelAdmin::new("blog", "article"));
-        let _router = site.into_axum_router();
+    #[tokio::test]
+    async fn test_log_export() {
+        let store = Arc::new(InMemoryLogEntryStore::with_hash_chain(b"key"));
+        store.log_addition(1, "blog.article", "1", "First, post", "");
+        store.log_change(2, "blog.article", "1", "First, post", "Changed title");
+        store.log_addition(1, "blog.comment", "7", "Comment", "");
+        let router = AdminSite::new("admin").log_store(store).into_axum_router();
+
+        let (status, _) = draft_request(&router, "GET", "/log/export/", None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = draft_request(&router, "GET", "/log/export/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = draft_request(
+            &router,
+            "GET",
+            "/log/export/?user=1",
+            Some(DEV_ADMIN_TOKEN),
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let csv = String::from_utf8(body).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], crate::export::LOG_EXPORT_COLUMNS.join(","));
+        assert!(lines[1].starts_with("1,"));
+        assert!(lines[1].contains(",\"First, post\",1,,,"));
+        assert!(lines[2].starts_with("3,"));
+
+        let (status, body) = draft_request(
+            &router,
+            "GET",
+            "/log/export/?format=jsonl&content_type=blog.article",
+            Some(DEV_ADMIN_TOKEN),
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let entries: Vec<crate::log_entry::LogEntry> = String::from_utf8(body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].change_message, "Changed title");
+        assert_eq!(crate::log_entry::verify_chain(&entries, b"key"), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_log_export_date_range() {
+        let store = Arc::new(InMemoryLogEntryStore::new());
+        store.log_addition(1, "blog.article", "1", "Article", "");
+        let router = AdminSite::new("admin").log_store(store).into_axum_router();
+        let today = Utc::now().date_naive();
+
+        let uri = format!("/log/export/?format=jsonl&since={today}&until={today}");
+        let (status, body) = draft_request(&router, "GET", &uri, Some(DEV_ADMIN_TOKEN), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(String::from_utf8(body).unwrap().lines().count(), 1);
+
+        let tomorrow = today.succ_opt().unwrap();
+        let uri = format!("/log/export/?format=jsonl&since={tomorrow}");
+        let (status, body) = draft_request(&router, "GET", &uri, Some(DEV_ADMIN_TOKEN), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_empty());
+
+        let (status, _) = draft_request(
+            &router,
+            "GET",
+            "/log/export/?since=yesterday",
+            Some(DEV_ADMIN_TOKEN),
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = draft_request(
+            &router,
+            "GET",
+            "/log/export/?format=xml",
+            Some(DEV_ADMIN_TOKEN),
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_large_export_runs_in_background() {
+        let (site, notifications) = export_site(5, 2).await;
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(FileExportStorage::new(dir.path()));
+        let router = site.export_storage(storage.clone()).into_axum_router();
+
+        let (status, body) =
+            draft_request(&router, "GET", "/blog/article/export/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let queued: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(queued["status"], "queued");
+        assert_eq!(queued["rows"], 5);
+
+        let mut ready = Vec::new();
+        for _ in 0..100 {
+            ready = notifications.list("alice", false, 10).await.unwrap();
+            if !ready.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].kind, NotificationKind::ExportReady);
+        let link = ready[0].link.clone().unwrap();
+        assert_eq!(
+            link,
+            format!("/exports/{}/", queued["file"].as_str().unwrap())
+        );
+
+        let (status, _) = draft_request(&router, "GET", &link, Some(BOB), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, body) = draft_request(&router, "GET", &link, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(String::from_utf8(body).unwrap().lines().count(), 6);
+        assert!(storage
+            .path("alice", queued["file"].as_str().unwrap())
+            .is_file());
+    }
+
+    /// A long-running action that processes one object per permit released
+    /// on its gate, and fails on the id `"bad"`.
+    struct GatedAction {
+        gate: Arc<tokio::sync::Semaphore>,
+    }
+
+    #[async_trait::async_trait]
+    impl AdminAction for GatedAction {
+        fn name(&self) -> &'static str {
+            "reindex"
+        }
+
+        fn description(&self) -> &'static str {
+            "Reindex the selected articles"
+        }
+
+        async fn execute(
+            &self,
+            _model_key: &str,
+            selected_ids: &[String],
+        ) -> Result<crate::actions::ActionResult, DjangoError> {
+            Ok(crate::actions::ActionResult::success(
+                "Reindexed",
+                selected_ids.len(),
+            ))
+        }
+
+        fn is_long_running(&self) -> bool {
+            true
+        }
+
+        async fn execute_one(&self, _model_key: &str, id: &str) -> Result<(), DjangoError> {
+            self.gate.acquire().await.unwrap().forget();
+            if id == "bad" {
+                return Err(DjangoError::BadRequest("cannot reindex".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    async fn poll_action_job(
+        router: &Router,
+        uri: &str,
+        done: impl Fn(&serde_json::Value) -> bool,
+    ) -> serde_json::Value {
+        for _ in 0..100 {
+            let (status, body) = draft_request(router, "GET", uri, Some(ALICE), "").await;
+            assert_eq!(status, StatusCode::OK);
+            let job: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            if done(&job) {
+                return job;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("action job at {uri} did not progress");
+    }
+
+    #[tokio::test]
+    async fn test_long_running_action_runs_in_background() {
+        let gate = Arc::new(tokio::sync::Semaphore::new(0));
+        let notifications = Arc::new(InMemoryNotificationStore::new());
+        let mut site = tag_site().notification_store(notifications.clone());
+        site.get_action_registry_mut("blog.article")
+            .unwrap()
+            .register(Box::new(GatedAction { gate: gate.clone() }));
+        let router = site.into_axum_router();
+        let body = r#"{"action": "reindex", "ids": ["1", "bad", "3", "4"]}"#;
+
+        let (status, _) = draft_request(&router, "POST", "/blog/article/action/", None, body).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, queued) =
+            draft_request(&router, "POST", "/blog/article/action/", Some(ALICE), body).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let queued: serde_json::Value = serde_json::from_slice(&queued).unwrap();
+        assert_eq!(queued["status"], "queued");
+        assert_eq!(queued["job"]["total"], 4);
+        let uri = format!("/action-jobs/{}/", queued["job"]["id"]);
+
+        let (status, _) = draft_request(&router, "GET", &uri, Some(BOB), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        gate.add_permits(2);
+        let job = poll_action_job(&router, &uri, |job| job["failed"] == 1).await;
+        assert_eq!(job["status"], "running");
+        assert_eq!(job["succeeded"], 1);
+        assert_eq!(job["errors"][0]["id"], "bad");
+
+        let cancel = format!("{uri}cancel/");
+        let (status, _) = draft_request(&router, "POST", &cancel, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        gate.add_permits(2);
+        let job = poll_action_job(&router, &uri, |job| job["status"] == "cancelled").await;
+        assert!(job["succeeded"].as_u64().unwrap() < 3);
+
+        let (status, _) = draft_request(&router, "POST", &cancel, Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let mut finished = Vec::new();
+        for _ in 0..100 {
+            finished = notifications.list("alice", false, 10).await.unwrap();
+            if !finished.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].kind, NotificationKind::ActionCompleted);
+        assert_eq!(finished[0].link.as_deref(), Some(uri.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_registry_endpoints_require_superuser() {
+        let router = tag_site().into_axum_router();
+        let (status, _) = draft_request(&router, "GET", "/registry/", None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) =
+            draft_request(&router, "DELETE", "/registry/blog/tag/", Some(ALICE), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) =
+            draft_request(&router, "GET", "/registry/", Some(DEV_ADMIN_TOKEN), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["models"]["blog.tag"]["quick_create_fields"][0], "name");
+    }
+
+    #[tokio::test]
+    async fn test_registry_endpoints_register_and_unregister() {
+        let router = tag_site().into_axum_router();
+        let token = Some(DEV_ADMIN_TOKEN);
+        let (status, _) = draft_request(&router, "GET", "/shop/product/", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let body = r#"{"app_label": "shop", "model_name": "product", "list_per_page": 5}"#;
+        let (status, created) = draft_request(&router, "POST", "/registry/", token, body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let created: serde_json::Value = serde_json::from_slice(&created).unwrap();
+        assert_eq!(created["list_per_page"], 5);
+        let (status, _) = draft_request(&router, "POST", "/registry/", token, body).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, list) = draft_request(&router, "GET", "/shop/product/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let list: serde_json::Value = serde_json::from_slice(&list).unwrap();
+        assert_eq!(list["page_size"], 5);
+
+        let uri = "/registry/shop/product/";
+        let (status, _) = draft_request(&router, "DELETE", uri, token, "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = draft_request(&router, "DELETE", uri, token, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = draft_request(&router, "GET", "/shop/product/", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_registry_handle_changes_running_router() {
+        let site = tag_site();
+        let registry = site.registry();
+        let router = site.into_axum_router();
+        assert!(registry.is_registered("blog.tag"));
+
+        registry.register("shop.product", ModelAdmin::new("shop", "product"));
+        let (status, index) = draft_request(&router, "GET", "/", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let index: serde_json::Value = serde_json::from_slice(&index).unwrap();
+        assert!(index["apps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|app| app["app_label"] == "shop"));
+
+        registry.unregister("blog.tag");
+        let (status, _) = draft_request(&router, "GET", "/blog/tag/schema", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_flag_endpoints_toggle_runtime_flags() {
+        use django_rs_core::flags::{flag_enabled, FlagContext};
+
+        let store = Arc::new(InMemoryFlagStore::new());
+        let router = AdminSite::new("admin")
+            .flag_store(store.clone())
+            .into_axum_router();
+        let token = Some(DEV_ADMIN_TOKEN);
+        let uri = "/flags/site_test_checkout/";
+        let alice = FlagContext::for_user("alice");
+
+        let (status, _) = draft_request(&router, "GET", "/flags/", None, "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = draft_request(&router, "PUT", uri, Some(ALICE), "{}").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let body = r#"{"users": {"alice": true}}"#;
+        let (status, _) = draft_request(&router, "PUT", uri, token, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(flag_enabled("site_test_checkout", &alice));
+        assert!(store
+            .all()
+            .await
+            .unwrap()
+            .contains_key("site_test_checkout"));
+
+        let (status, list) = draft_request(&router, "GET", "/flags/", token, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let list: serde_json::Value = serde_json::from_slice(&list).unwrap();
+        let flag = &list["flags"]["site_test_checkout"];
+        assert_eq!(flag["source"], "database");
+        assert_eq!(flag["users"]["alice"], true);
+
+        let (status, _) = draft_request(&router, "DELETE", uri, token, "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(!flag_enabled("site_test_checkout", &alice));
+        let (status, _) = draft_request(&router, "DELETE", uri, token, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 }
@@ -84,6 +84,33 @@ pub trait AuthBackend: Send + Sync {
     /// Retrieves a user by their unique identifier (e.g., username).
     async fn get_user(&self, user_id: &str) -> Result<Option<AbstractUser>, DjangoError>;
 
+    /// Retrieves a user by email address.
+    ///
+    /// Used to link social logins to existing accounts. Backends that cannot
+    /// search by email return `Ok(None)`.
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<AbstractUser>, DjangoError> {
+        let _ = email;
+        Ok(None)
+    }
+
+    /// Returns every user the backend stores.
+    ///
+    /// Used by tools that edit users in bulk, such as the admin's permission
+    /// matrix. Backends that cannot list users return an empty list.
+    async fn list_users(&self) -> Result<Vec<AbstractUser>, DjangoError> {
+        Ok(Vec::new())
+    }
+
+    /// Persists changes to a user, such as a new password.
+    ///
+    /// Backends that cannot store users return an error.
+    async fn save_user(&self, user: &AbstractUser) -> Result<(), DjangoError> {
+        Err(DjangoError::ImproperlyConfigured(format!(
+            "This authentication backend cannot save user '{}'",
+            user.username
+        )))
+    }
+
     /// Checks if a user has a specific permission.
     fn has_perm(&self, user: &AbstractUser, perm: &str) -> bool;
 
@@ -148,6 +175,27 @@ impl AuthBackend for ModelBackend {
         Ok(users.iter().find(|u| u.username == user_id).cloned())
     }
 
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<AbstractUser>, DjangoError> {
+        let users = self.users.read().await;
+        Ok(users
+            .iter()
+            .find(|u| !u.email.is_empty() && u.email.eq_ignore_ascii_case(email))
+            .cloned())
+    }
+
+    async fn list_users(&self) -> Result<Vec<AbstractUser>, DjangoError> {
+        Ok(self.users.read().await.clone())
+    }
+
+    async fn save_user(&self, user: &AbstractUser) -> Result<(), DjangoError> {
+        let mut users = self.users.write().await;
+        match users.iter_mut().find(|u| u.username == user.username) {
+            Some(existing) => *existing = user.clone(),
+            None => users.push(user.clone()),
+        }
+        Ok(())
+    }
+
     fn has_perm(&self, user: &AbstractUser, perm: &str) -> bool {
         crate::permissions::has_perm(user, perm)
     }
@@ -256,13 +304,10 @@ pub fn login(session: &mut SessionData, user: &AbstractUser) {
         SESSION_BACKEND_KEY,
         serde_json::Value::String("django_rs.auth.backends.ModelBackend".to_string()),
     );
-    // Store a portion of the password hash to detect password changes.
-    // Uses first 40 chars to capture algorithm + salt for reliable detection.
-    let end = std::cmp::min(user.base.password.len(), 40);
-    let hash_fragment = &user.base.password[..end];
+    // Store an HMAC of the password hash to detect password changes.
     session.set(
         SESSION_HASH_KEY,
-        serde_json::Value::String(hash_fragment.to_string()),
+        serde_json::Value::String(crate::session_auth::session_auth_hash(&user.base.password)),
     );
 }
 
@@ -385,6 +430,22 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_model_backend_get_user_by_email() {
+        let backend = ModelBackend::new();
+        backend.add_user(AbstractUser::new("nomail")).await;
+        let mut user = AbstractUser::new("alice");
+        user.email = "Alice@Example.com".to_string();
+        backend.add_user(user).await;
+
+        let found = backend
+            .get_user_by_email("alice@example.com")
+            .await
+            .unwrap();
+        assert_eq!(found.unwrap().username, "alice");
+        assert!(backend.get_user_by_email("").await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_model_backend_has_perm() {
         let backend = ModelBackend::new();
@@ -6,10 +6,25 @@
 //! ## How it works
 //!
 //! 1. On GET/HEAD/OPTIONS/TRACE requests, a CSRF cookie is set on the response.
+//!    The secret is available to views as `META["CSRF_COOKIE"]`, and a masked
+//!    token for forms as `META["CSRF_TOKEN"]` (what `{% csrf_token %}` renders).
 //! 2. On POST/PUT/PATCH/DELETE requests, the middleware validates that the request
 //!    includes a valid CSRF token (via header or form field) matching the cookie.
 //! 3. Requests without a valid token receive a 403 Forbidden response.
 //!
+//! ## Per-view control
+//!
+//! Routes are resolved before the middleware runs, so the middleware knows
+//! which view a request is for:
+//!
+//! - [`csrf_exempt`] skips the check for one view (e.g. a webhook)
+//! - [`csrf_protect`] checks one view when the middleware is not installed
+//! - [`ensure_csrf_cookie`] always sends the cookie, e.g. for pages that post
+//!   via JavaScript without rendering a form
+//!
+//! After logging a user in, [`rotate_token`] replaces the secret so a token
+//! seen before login cannot be used afterwards; `login_to_session` does this.
+//!
 //! ## Token Masking
 //!
 //! Tokens are XOR-masked before being sent to the client to prevent BREACH attacks
@@ -17,10 +32,13 @@
 
 use async_trait::async_trait;
 use django_rs_core::error::DjangoError;
+use django_rs_core::{Settings, SETTINGS};
+use django_rs_http::urls::pattern::{RouteHandler, URLPattern};
 use django_rs_http::{HttpRequest, HttpResponse};
 use django_rs_views::middleware::Middleware;
 use rand::RngCore;
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
 /// The length of a CSRF token in bytes (produces 64-char hex string).
 const CSRF_TOKEN_LENGTH: usize = 32;
@@ -32,7 +50,8 @@ const CSRF_FORM_FIELD: &str = "csrfmiddlewaretoken";
 ///
 /// Sets a CSRF cookie on safe requests and validates the CSRF token
 /// on state-changing requests. Views can be exempt from CSRF checking
-/// by adding their paths to the exempt list.
+/// by adding their paths to the exempt list, or by wrapping them in
+/// [`csrf_exempt`].
 #[derive(Debug, Clone)]
 pub struct CsrfMiddleware {
     /// Name of the CSRF cookie.
@@ -68,6 +87,36 @@ impl CsrfMiddleware {
         Self::default()
     }
 
+    /// Creates a `CsrfMiddleware` using `CSRF_COOKIE_NAME` and
+    /// `CSRF_TRUSTED_ORIGINS` from the given settings.
+    ///
+    /// ```
+    /// use django_rs_auth::csrf::CsrfMiddleware;
+    /// use django_rs_core::Settings;
+    /// use django_rs_views::server::DjangoApp;
+    ///
+    /// let settings = Settings::default();
+    /// let app = DjangoApp::new(settings.clone())
+    ///     .middleware(CsrfMiddleware::from_settings(&settings));
+    /// ```
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            cookie_name: settings.csrf_cookie_name.clone(),
+            trusted_origins: settings.csrf_trusted_origins.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the middleware configured from the global settings, or the
+    /// defaults before settings are configured.
+    fn configured() -> Self {
+        if SETTINGS.is_configured() {
+            Self::from_settings(SETTINGS.get())
+        } else {
+            Self::default()
+        }
+    }
+
     /// Adds a path to the CSRF exempt list.
     pub fn add_exempt_path(&mut self, path: &str) {
         self.exempt_paths.insert(path.to_string());
@@ -147,52 +196,97 @@ impl CsrfMiddleware {
         }
         cookie
     }
-}
-
-#[async_trait]
-impl Middleware for CsrfMiddleware {
-    async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
-        // Safe methods don't need CSRF validation
-        if Self::is_safe_method(request.method()) {
-            return None;
-        }
 
-        // Check exempt paths
-        if self.exempt_paths.contains(request.path()) {
-            return None;
-        }
+    /// Attaches the request's [`CsrfToken`] and exposes it in META.
+    ///
+    /// The cookie will be sent if the request is safe and has none yet.
+    fn prepare(&self, request: &mut HttpRequest) -> CsrfToken {
+        let cookie = self.get_csrf_cookie(request);
+        let send_cookie = cookie.is_none() && Self::is_safe_method(request.method());
+        let token = CsrfToken::new(cookie.unwrap_or_else(generate_csrf_token), send_cookie);
+        token.expose(request);
+        request.extensions_mut().insert(token.clone());
+        token
+    }
 
-        // Check trusted origins
-        if self.is_origin_trusted(request) {
+    /// Returns why the request fails the CSRF check, if it does.
+    ///
+    /// Safe methods, exempt paths and trusted origins always pass.
+    fn rejection(&self, request: &HttpRequest) -> Option<&'static str> {
+        if Self::is_safe_method(request.method())
+            || self.exempt_paths.contains(request.path())
+            || self.is_origin_trusted(request)
+        {
             return None;
         }
 
         // Get the CSRF cookie
         let Some(cookie_token) = self.get_csrf_cookie(request) else {
-            return Some(HttpResponse::forbidden("CSRF cookie not set."));
+            return Some("CSRF cookie not set.");
         };
 
         // Get the request token (from header or form)
         let Some(request_token) = self.get_request_token(request) else {
-            return Some(HttpResponse::forbidden("CSRF token missing."));
+            return Some("CSRF token missing.");
         };
 
         // Validate the token
         if !validate_csrf_token(&request_token, &cookie_token) {
-            return Some(HttpResponse::forbidden("CSRF token invalid."));
+            return Some("CSRF token invalid.");
         }
 
         None
     }
 
+    /// Sets the CSRF cookie on the response if `token` needs sending.
+    fn apply_cookie(&self, token: &CsrfToken, mut response: HttpResponse) -> HttpResponse {
+        if token.send_cookie() {
+            if let Ok(value) = http::HeaderValue::from_str(&self.build_cookie(&token.secret())) {
+                response
+                    .headers_mut()
+                    .append(http::header::SET_COOKIE, value);
+            }
+        }
+        response
+    }
+}
+
+#[async_trait]
+impl Middleware for CsrfMiddleware {
+    async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
+        // Expose the token, so `{% csrf_token %}` renders the one the cookie
+        // will carry
+        self.prepare(request);
+
+        // Patterns marked with `csrf_exempt` skip validation
+        if request
+            .resolver_match()
+            .is_some_and(|m| m.extensions.get::<CsrfExempt>().is_some())
+        {
+            return None;
+        }
+
+        self.rejection(request).map(HttpResponse::forbidden)
+    }
+
     async fn process_response(
         &self,
         request: &HttpRequest,
-        mut response: HttpResponse,
+        response: HttpResponse,
     ) -> HttpResponse {
-        // Set CSRF cookie on safe requests if not already set
+        if let Some(token) = request.extensions().get::<CsrfToken>() {
+            return self.apply_cookie(token, response);
+        }
+
+        // `process_request` did not run: set the cookie on safe requests if
+        // not already set
+        let mut response = response;
         if Self::is_safe_method(request.method()) && self.get_csrf_cookie(request).is_none() {
-            let token = generate_csrf_token();
+            let token = request
+                .meta()
+                .get("CSRF_COOKIE")
+                .cloned()
+                .unwrap_or_else(generate_csrf_token);
             if let Ok(value) = http::HeaderValue::from_str(&self.build_cookie(&token)) {
                 response
                     .headers_mut()
@@ -211,6 +305,189 @@ impl Middleware for CsrfMiddleware {
     }
 }
 
+// ── Per-request token ───────────────────────────────────────────────────
+
+/// The CSRF secret of the current request, attached as a request extension
+/// by [`CsrfMiddleware`] (or a CSRF view decorator).
+///
+/// Request extensions are shared with the view's copy of the request, so a
+/// secret rotated by the view is the one the response's cookie carries.
+#[derive(Debug, Clone)]
+pub struct CsrfToken {
+    state: Arc<Mutex<CsrfTokenState>>,
+}
+
+#[derive(Debug)]
+struct CsrfTokenState {
+    secret: String,
+    send_cookie: bool,
+}
+
+impl CsrfToken {
+    fn new(secret: String, send_cookie: bool) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CsrfTokenState {
+                secret,
+                send_cookie,
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CsrfTokenState> {
+        self.state.lock().expect("CSRF token lock poisoned")
+    }
+
+    /// Returns the unmasked secret, as stored in the cookie.
+    pub fn secret(&self) -> String {
+        self.lock().secret.clone()
+    }
+
+    /// Returns a freshly masked token for embedding in a page.
+    pub fn masked(&self) -> String {
+        mask_csrf_token(&self.secret())
+    }
+
+    /// Returns whether the response must set the CSRF cookie.
+    pub fn send_cookie(&self) -> bool {
+        self.lock().send_cookie
+    }
+
+    /// Makes the response set the CSRF cookie.
+    pub fn force_cookie(&self) {
+        self.lock().send_cookie = true;
+    }
+
+    /// Replaces the secret with a new one, which the response will send.
+    fn rotate(&self) {
+        let mut state = self.lock();
+        state.secret = generate_csrf_token();
+        state.send_cookie = true;
+    }
+
+    /// Writes `CSRF_COOKIE` and `CSRF_TOKEN` into the request's META.
+    fn expose(&self, request: &mut HttpRequest) {
+        let secret = self.secret();
+        let masked = mask_csrf_token(&secret);
+        let meta = request.meta_mut();
+        meta.insert("CSRF_COOKIE".to_string(), secret);
+        meta.insert("CSRF_TOKEN".to_string(), masked);
+    }
+}
+
+/// Returns a masked CSRF token for the request, and makes sure the response
+/// sets the cookie it validates against.
+///
+/// Returns `None` if neither [`CsrfMiddleware`] nor a CSRF view decorator
+/// handled the request. This mirrors Django's `get_token()`.
+pub fn get_token(request: &HttpRequest) -> Option<String> {
+    let token = request.extensions().get::<CsrfToken>()?;
+    token.force_cookie();
+    Some(token.masked())
+}
+
+/// Replaces the request's CSRF secret with a new one.
+///
+/// Call this when the user's privileges change, e.g. on login, so that a
+/// token obtained before cannot be replayed after. The response sets the
+/// new cookie, and templates rendered from `request` afterwards use the new
+/// token. This mirrors Django's `rotate_token()`.
+pub fn rotate_token(request: &mut HttpRequest) {
+    let Some(token) = request.extensions().get::<CsrfToken>().cloned() else {
+        return;
+    };
+    token.rotate();
+    token.expose(request);
+}
+
+// ── View decorators ─────────────────────────────────────────────────────
+
+/// Route metadata marking a URL pattern as exempt from CSRF validation.
+///
+/// Attached by [`csrf_exempt`] and read by [`CsrfMiddleware`] from the
+/// request's resolver match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsrfExempt;
+
+/// Exempts a URL pattern from [`CsrfMiddleware`] validation.
+///
+/// The pattern is marked with [`CsrfExempt`] metadata, which the middleware
+/// finds on requests that resolve to it. Use this for endpoints
+/// authenticated by other means, such as signed webhooks. This mirrors
+/// Django's `@csrf_exempt`.
+///
+/// ```
+/// use std::sync::Arc;
+/// use django_rs_auth::csrf::{csrf_exempt, is_csrf_exempt};
+/// use django_rs_http::urls::pattern::path;
+/// use django_rs_http::HttpResponse;
+///
+/// let webhook = csrf_exempt(
+///     path(
+///         "webhook/",
+///         Arc::new(|_req| Box::pin(async { HttpResponse::ok("received") })),
+///         None,
+///     )
+///     .unwrap(),
+/// );
+/// assert!(is_csrf_exempt(&webhook));
+/// ```
+pub fn csrf_exempt(pattern: URLPattern) -> URLPattern {
+    pattern.with_extension(CsrfExempt)
+}
+
+/// Returns `true` if `pattern` was marked with [`csrf_exempt`].
+pub fn is_csrf_exempt(pattern: &URLPattern) -> bool {
+    pattern.extensions().get::<CsrfExempt>().is_some()
+}
+
+/// Applies CSRF protection to a single view.
+///
+/// Behaves like [`CsrfMiddleware`], configured from the global settings, for
+/// this view only: unsafe requests without a valid token get a 403, and safe
+/// requests receive the cookie. When the middleware is installed it has
+/// already checked the request, so the view is called directly. This mirrors
+/// Django's `@csrf_protect`.
+pub fn csrf_protect(view: RouteHandler) -> RouteHandler {
+    decorate(view, true)
+}
+
+/// Makes a view always send the CSRF cookie, without checking the request.
+///
+/// Use this for pages whose forms are posted via JavaScript and that never
+/// render `{% csrf_token %}`. This mirrors Django's `@ensure_csrf_cookie`.
+pub fn ensure_csrf_cookie(view: RouteHandler) -> RouteHandler {
+    decorate(view, false)
+}
+
+/// Wraps `view` with CSRF handling: validation if `enforce`, otherwise a
+/// forced cookie.
+fn decorate(view: RouteHandler, enforce: bool) -> RouteHandler {
+    Arc::new(move |mut request: HttpRequest| {
+        let view = Arc::clone(&view);
+        Box::pin(async move {
+            if let Some(token) = request.extensions().get::<CsrfToken>() {
+                // The middleware already ran for this request
+                if !enforce {
+                    token.force_cookie();
+                }
+                return view(request).await;
+            }
+
+            let middleware = CsrfMiddleware::configured();
+            let token = middleware.prepare(&mut request);
+            if enforce {
+                if let Some(reason) = middleware.rejection(&request) {
+                    return HttpResponse::forbidden(reason);
+                }
+            } else {
+                token.force_cookie();
+            }
+            let response = view(request).await;
+            middleware.apply_cookie(&token, response)
+        })
+    })
+}
+
 /// Generates a cryptographically random CSRF token as a 64-character hex string.
 ///
 /// Uses the OS random number generator for secure randomness.
@@ -290,7 +567,7 @@ pub fn validate_csrf_token(request_token: &str, cookie_token: &str) -> bool {
 }
 
 /// Constant-time byte comparison to prevent timing attacks.
-fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
         return false;
     }
@@ -328,6 +605,7 @@ fn hex_decode(hex: &str) -> Option<Vec<u8>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use django_rs_http::urls::pattern::path;
 
     // ── Token generation tests ──────────────────────────────────────
 
@@ -568,6 +846,23 @@ mod tests {
         assert!(response.headers().get(http::header::SET_COOKIE).is_some());
     }
 
+    #[tokio::test]
+    async fn test_csrf_middleware_cookie_matches_exposed_token() {
+        let mw = CsrfMiddleware::new();
+        let mut request = HttpRequest::builder().method(http::Method::GET).build();
+        assert!(mw.process_request(&mut request).await.is_none());
+        let token = request.meta().get("CSRF_COOKIE").unwrap().clone();
+        assert_eq!(token.len(), 64);
+
+        let response = mw
+            .process_response(&request, HttpResponse::ok("test"))
+            .await;
+        let cookie = response.headers()[http::header::SET_COOKIE]
+            .to_str()
+            .unwrap();
+        assert!(cookie.starts_with(&format!("csrftoken={token};")));
+    }
+
     #[tokio::test]
     async fn test_csrf_middleware_no_duplicate_cookie() {
         let mw = CsrfMiddleware::new();
@@ -640,4 +935,170 @@ mod tests {
         let result = mw.process_request(&mut request).await;
         assert!(result.is_none());
     }
+
+    // ── Per-request token and view decorator tests ─────────────────
+
+    fn ok_view(body: &'static str) -> RouteHandler {
+        Arc::new(move |_req| Box::pin(async move { HttpResponse::ok(body) }))
+    }
+
+    fn resolve_to(request: &mut HttpRequest, pattern: &URLPattern) {
+        request.set_resolver_match(django_rs_http::urls::resolver::ResolverMatch {
+            func: Arc::clone(pattern.callback()),
+            args: Vec::new(),
+            kwargs: std::collections::HashMap::new(),
+            url_name: None,
+            app_names: Vec::new(),
+            namespaces: Vec::new(),
+            route: pattern.route().to_string(),
+            extensions: pattern.extensions().clone(),
+        });
+    }
+
+    /// Copies `request` the way the middleware pipeline hands it to a view.
+    fn view_copy(request: &HttpRequest) -> HttpRequest {
+        let mut builder = HttpRequest::builder().method(request.method().clone());
+        for (name, value) in request.headers() {
+            builder = builder.header(name.as_str(), value.to_str().unwrap());
+        }
+        for (key, value) in request.meta() {
+            builder = builder.meta(key, value);
+        }
+        let mut copy = builder.build();
+        *copy.extensions_mut() = request.extensions().clone();
+        copy
+    }
+
+    fn set_cookie(response: &HttpResponse) -> Option<String> {
+        response
+            .headers()
+            .get(http::header::SET_COOKIE)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_csrf_middleware_exposes_masked_token() {
+        let mw = CsrfMiddleware::new();
+        let secret = generate_csrf_token();
+        let mut request = HttpRequest::builder()
+            .header("cookie", &format!("csrftoken={secret}"))
+            .build();
+        assert!(mw.process_request(&mut request).await.is_none());
+        let masked = request.meta()["CSRF_TOKEN"].clone();
+        assert_ne!(masked, secret);
+        assert!(validate_csrf_token(&masked, &secret));
+        assert_eq!(
+            get_token(&request).map(|t| unmask_csrf_token(&t)),
+            Some(secret)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csrf_exempt_view_skips_middleware() {
+        let mw = CsrfMiddleware::new();
+        let hook = csrf_exempt(path("hook/", ok_view("hook"), None).unwrap());
+        let mut request = HttpRequest::builder().method(http::Method::POST).build();
+        resolve_to(&mut request, &hook);
+        assert!(mw.process_request(&mut request).await.is_none());
+
+        // The same handler under an unmarked pattern is still checked.
+        let other = path("other/", Arc::clone(hook.callback()), None).unwrap();
+        assert!(!is_csrf_exempt(&other));
+        let mut request = HttpRequest::builder().method(http::Method::POST).build();
+        resolve_to(&mut request, &other);
+        assert!(mw.process_request(&mut request).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_csrf_protect_without_middleware() {
+        let view = csrf_protect(ok_view("saved"));
+
+        let request = HttpRequest::builder().method(http::Method::POST).build();
+        assert_eq!(view(request).await.status(), http::StatusCode::FORBIDDEN);
+
+        let secret = generate_csrf_token();
+        let request = HttpRequest::builder()
+            .method(http::Method::POST)
+            .header("cookie", &format!("csrftoken={secret}"))
+            .header("x-csrftoken", &mask_csrf_token(&secret))
+            .build();
+        let response = view(request).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(set_cookie(&response).is_none());
+
+        let response = view(HttpRequest::builder().build()).await;
+        assert!(set_cookie(&response).unwrap().starts_with("csrftoken="));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_csrf_cookie_resends_existing_cookie() {
+        let view = ensure_csrf_cookie(ok_view("page"));
+        let secret = generate_csrf_token();
+        let request = HttpRequest::builder()
+            .header("cookie", &format!("csrftoken={secret}"))
+            .build();
+        let response = view(request).await;
+        assert!(set_cookie(&response).unwrap().contains(&secret));
+
+        // Unsafe requests are not checked
+        let request = HttpRequest::builder().method(http::Method::POST).build();
+        let response = view(request).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_csrf_cookie_under_middleware() {
+        let mw = CsrfMiddleware::new();
+        let view = ensure_csrf_cookie(ok_view("page"));
+        let secret = generate_csrf_token();
+        let mut request = HttpRequest::builder()
+            .header("cookie", &format!("csrftoken={secret}"))
+            .build();
+        assert!(mw.process_request(&mut request).await.is_none());
+        let response = view(view_copy(&request)).await;
+        let response = mw.process_response(&request, response).await;
+        let cookies: Vec<_> = response
+            .headers()
+            .get_all(http::header::SET_COOKIE)
+            .iter()
+            .collect();
+        assert_eq!(cookies.len(), 1);
+        assert!(cookies[0].to_str().unwrap().contains(&secret));
+    }
+
+    #[tokio::test]
+    async fn test_login_rotates_token_and_cookie() {
+        let mw = CsrfMiddleware::new();
+        let secret = generate_csrf_token();
+        let mut request = HttpRequest::builder()
+            .method(http::Method::POST)
+            .header("cookie", &format!("csrftoken={secret}"))
+            .header("x-csrftoken", &mask_csrf_token(&secret))
+            .build();
+        assert!(mw.process_request(&mut request).await.is_none());
+
+        // The view works on its own copy of the request
+        let mut view_request = view_copy(&request);
+        let user = crate::user::AbstractUser::new("alice");
+        crate::session_auth::login_to_session(&mut view_request, &user);
+        let rotated = view_request.meta()["CSRF_COOKIE"].clone();
+        assert_ne!(rotated, secret);
+        assert!(validate_csrf_token(
+            &view_request.meta()["CSRF_TOKEN"],
+            &rotated
+        ));
+
+        let response = mw
+            .process_response(&request, HttpResponse::ok("welcome"))
+            .await;
+        assert!(set_cookie(&response).unwrap().contains(&rotated));
+    }
+
+    #[test]
+    fn test_rotate_token_without_middleware_is_noop() {
+        let mut request = HttpRequest::builder().build();
+        rotate_token(&mut request);
+        assert!(request.meta().get("CSRF_COOKIE").is_none());
+        assert!(get_token(&request).is_none());
+    }
 }
@@ -11,7 +11,10 @@
 //! - **Permission and group system** with RBAC support (`permissions`)
 //! - **CSRF protection middleware** (`csrf`)
 //! - **Security middleware** for host validation and security headers (`security`)
+//! - **Social login** with OAuth2/OpenID Connect providers such as Google and
+//!   GitHub (`social`; the `oidc` feature adds an HTTP client for it)
 //! - **Auth view configuration types** and token generators (`views`)
+//! - **Mountable auth URL set** like `django.contrib.auth.urls` (`urls`)
 //!
 //! ## Design Principles
 //!
@@ -19,6 +22,10 @@
 //! executed via `tokio::task::spawn_blocking` to avoid blocking the async runtime.
 //! All traits are `Send + Sync` to enable safe concurrent access.
 
+// - result_large_err: DjangoError is the framework error type
+// - doc_markdown: backtick requirements for protocol names (OAuth2, OpenID) are too strict
+#![allow(clippy::result_large_err, clippy::doc_markdown)]
+
 pub mod backends;
 pub mod csrf;
 pub mod forms;
@@ -26,12 +33,17 @@ pub mod hashers;
 pub mod permissions;
 pub mod security;
 pub mod session_auth;
+pub mod social;
+pub mod urls;
 pub mod user;
 pub mod views;
 
 // Re-exports for convenience
 pub use backends::{authenticate, login, logout, AuthBackend, Credentials, ModelBackend};
-pub use csrf::{generate_csrf_token, validate_csrf_token, CsrfMiddleware};
+pub use csrf::{
+    csrf_exempt, csrf_protect, ensure_csrf_cookie, generate_csrf_token, get_token, rotate_token,
+    validate_csrf_token, CsrfMiddleware, CsrfToken,
+};
 pub use forms::{
     AuthenticationForm, PasswordChangeForm, PasswordResetForm, SetPasswordForm, UserCreationForm,
 };
@@ -40,10 +52,14 @@ pub use permissions::{has_module_perms, has_perm, has_perms, Group, Permission};
 pub use security::SecurityMiddleware;
 pub use session_auth::{
     get_user_from_request, get_user_from_session, is_authenticated, login_to_session,
-    logout_from_session,
+    logout_from_session, update_session_auth_hash, SessionAuthMiddleware, SessionHashVerifier,
 };
+pub use social::{social_urls, OidcBackend, OidcProvider, SocialUrls, SocialUserPolicy};
+pub use urls::{auth_urls, AuthUrls};
 pub use user::{AbstractBaseUser, AbstractUser, AnonymousUser};
 pub use views::{
-    login_view, logout_view, password_change_view, DefaultTokenGenerator, LoginConfig,
-    LogoutConfig, PasswordChangeConfig, PasswordResetConfig, TokenGenerator,
+    decode_uid, encode_uid, login_view, logout_view, password_change_view,
+    password_reset_confirm_view, password_reset_view, DefaultTokenGenerator, LoginConfig,
+    LogoutConfig, PasswordChangeConfig, PasswordResetConfig, PasswordResetConfirmConfig,
+    TokenGenerator,
 };
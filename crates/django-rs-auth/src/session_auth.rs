@@ -3,7 +3,7 @@
 //! This module connects the authentication system to the session framework,
 //! providing functions to:
 //!
-//! - Store authentication state in request META (which maps to session data)
+//! - Store authentication state in the request's session
 //! - Clear authentication state on logout
 //! - Load a user from session data by querying an auth backend
 //!
@@ -12,47 +12,85 @@
 //! Authentication state is stored using three session keys:
 //! - `_auth_user_id` - The authenticated user's username/identifier
 //! - `_auth_user_backend` - The backend class that authenticated the user
-//! - `_auth_user_hash` - A fragment of the password hash for invalidation
+//! - `_auth_user_hash` - An HMAC of the password hash for invalidation
 //!
-//! ## META Integration
+//! ## Password Changes
 //!
-//! The session middleware serializes session data into request META entries.
-//! This module reads/writes session data through META to avoid direct
-//! session coupling, following the same pattern established in Wave 7.
-
-use django_rs_http::HttpRequest;
+//! The stored hash is keyed with `SECRET_KEY`, so it cannot be forged, and is
+//! derived from the password hash, so every session stops verifying once the
+//! password changes. [`SessionAuthMiddleware`], and the views crate's
+//! `AuthenticationMiddleware` given a [`SessionHashVerifier`], check it on
+//! every request; call [`update_session_auth_hash`] after changing the
+//! password in a view to keep the session that made the change logged in.
+//!
+//! ## Session Integration
+//!
+//! Session data is read and written through
+//! [`SessionData::from_request`] and [`SessionData::save_to_request`], so a
+//! login or logout in a view reaches the session middleware even though the
+//! view handles a copy of the request. The `USER_AUTHENTICATED` flag is kept
+//! in META for code that checks the string flag.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, SETTINGS};
+use django_rs_http::{HttpRequest, HttpResponse};
+use django_rs_views::middleware::Middleware;
 use django_rs_views::session::SessionData;
+use django_rs_views::{CurrentUser, SessionVerifier};
 
 use crate::backends::AuthBackend;
+use crate::csrf::rotate_token;
 use crate::user::AbstractUser;
 
 /// Session key for storing the authenticated user's ID.
 const SESSION_USER_KEY: &str = "_auth_user_id";
 /// Session key for the authentication backend class path.
 const SESSION_BACKEND_KEY: &str = "_auth_user_backend";
-/// Session key for the password hash fragment (to detect password changes).
+/// Session key for the password hash HMAC (to detect password changes).
 const SESSION_HASH_KEY: &str = "_auth_user_hash";
 /// META key indicating whether the current user is authenticated.
 const META_USER_AUTHENTICATED: &str = "USER_AUTHENTICATED";
 
+/// META key holding the authenticated user's ID.
+const META_USER_ID: &str = "USER_ID";
+
 /// Computes the session authentication hash from a user's password hash.
 ///
-/// Uses the first 40 characters of the password hash as a fingerprint.
-/// This captures enough of the hash (including salt and algorithm-specific
-/// parameters) to reliably detect when a password has been changed.
-fn session_auth_hash(password_hash: &str) -> String {
-    let end = std::cmp::min(password_hash.len(), 40);
-    password_hash[..end].to_string()
+/// The hash is keyed with the configured `SECRET_KEY`; before settings are
+/// configured an empty key is used.
+pub(crate) fn session_auth_hash(password_hash: &str) -> String {
+    let secret_key = if SETTINGS.is_configured() {
+        SETTINGS.get().secret_key.as_str()
+    } else {
+        ""
+    };
+    crate::user::session_auth_hash(password_hash, secret_key)
 }
 
-/// Stores user authentication state in the request META.
+/// Returns the [`CurrentUser`] attached to requests made by `user`.
+fn current_user(user: &AbstractUser) -> CurrentUser {
+    CurrentUser::authenticated(user.username.clone()).with_staff(user.is_staff)
+}
+
+/// Reads a string value from the request's session.
+fn session_string(request: &HttpRequest, key: &str) -> Option<String> {
+    SessionData::from_request(request)
+        .data
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+/// Stores user authentication state in the request's session.
 ///
 /// After calling this, the session middleware will persist the auth data
-/// when it processes the response. The following META entries are set:
+/// when it processes the response. `USER_AUTHENTICATED` is set to `"true"`
+/// in META and the request's [`CurrentUser`] is replaced, so templates the
+/// view renders afterwards see the new user.
 ///
-/// - `SESSION_DATA` is updated with auth keys serialized as JSON
-/// - `SESSION_MODIFIED` is set to `"true"` to trigger persistence
-/// - `USER_AUTHENTICATED` is set to `"true"` for downstream middleware/views
+/// The CSRF token is rotated as well (see [`rotate_token`]).
 ///
 /// This mirrors Django's `django.contrib.auth.login()`.
 pub fn login_to_session(request: &mut HttpRequest, user: &AbstractUser) {
@@ -68,67 +106,65 @@ pub fn login_to_session_with_backend(
     user: &AbstractUser,
     backend: &str,
 ) {
-    let hash_fragment = session_auth_hash(&user.base.password);
-
-    // Build the session data by merging auth keys into existing session data
-    let meta = request.meta_mut();
-
-    // Parse existing session data
-    let session_data_str = meta
-        .get("SESSION_DATA")
-        .cloned()
-        .unwrap_or_else(|| "{}".to_string());
-    let mut data: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(&session_data_str).unwrap_or_default();
-
-    // Set auth keys
-    data.insert(
-        SESSION_USER_KEY.to_string(),
+    let auth_hash = session_auth_hash(&user.base.password);
+
+    let mut session = SessionData::from_request(request);
+    session.set(
+        SESSION_USER_KEY,
         serde_json::Value::String(user.username.clone()),
     );
-    data.insert(
-        SESSION_BACKEND_KEY.to_string(),
+    session.set(
+        SESSION_BACKEND_KEY,
         serde_json::Value::String(backend.to_string()),
     );
-    data.insert(
-        SESSION_HASH_KEY.to_string(),
-        serde_json::Value::String(hash_fragment),
-    );
+    session.set(SESSION_HASH_KEY, serde_json::Value::String(auth_hash));
+    session.save_to_request(request);
+    request
+        .meta_mut()
+        .insert(META_USER_AUTHENTICATED.to_string(), "true".to_string());
+    request.extensions_mut().insert(current_user(user));
 
-    // Serialize back and mark as modified
-    let updated_json = serde_json::to_string(&data).unwrap_or_else(|_| "{}".to_string());
-    meta.insert("SESSION_DATA".to_string(), updated_json);
-    meta.insert("SESSION_MODIFIED".to_string(), "true".to_string());
-    meta.insert(META_USER_AUTHENTICATED.to_string(), "true".to_string());
+    rotate_token(request);
 }
 
-/// Clears authentication state from the request META.
+/// Clears authentication state from the request's session.
 ///
 /// Removes all auth-related keys from the session data and marks the
-/// session as modified. Sets `USER_AUTHENTICATED` to `"false"`.
+/// session as modified. Sets `USER_AUTHENTICATED` to `"false"` and the
+/// request's [`CurrentUser`] to anonymous.
 ///
 /// This mirrors Django's `django.contrib.auth.logout()`.
 pub fn logout_from_session(request: &mut HttpRequest) {
-    let meta = request.meta_mut();
-
-    // Parse existing session data
-    let session_data_str = meta
-        .get("SESSION_DATA")
-        .cloned()
-        .unwrap_or_else(|| "{}".to_string());
-    let mut data: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(&session_data_str).unwrap_or_default();
-
-    // Remove auth keys
-    data.remove(SESSION_USER_KEY);
-    data.remove(SESSION_BACKEND_KEY);
-    data.remove(SESSION_HASH_KEY);
-
-    // Serialize back and mark as modified
-    let updated_json = serde_json::to_string(&data).unwrap_or_else(|_| "{}".to_string());
-    meta.insert("SESSION_DATA".to_string(), updated_json);
-    meta.insert("SESSION_MODIFIED".to_string(), "true".to_string());
-    meta.insert(META_USER_AUTHENTICATED.to_string(), "false".to_string());
+    let mut session = SessionData::from_request(request);
+    session.remove(SESSION_USER_KEY);
+    session.remove(SESSION_BACKEND_KEY);
+    session.remove(SESSION_HASH_KEY);
+    session.modified = true;
+    session.save_to_request(request);
+    request
+        .meta_mut()
+        .insert(META_USER_AUTHENTICATED.to_string(), "false".to_string());
+    request.extensions_mut().insert(CurrentUser::anonymous());
+}
+
+/// Refreshes the session auth hash after the user's password changed.
+///
+/// Changing a password invalidates every session of that user. Calling this
+/// with the updated user stores the new hash in the current session, so the
+/// session that made the change stays logged in while all others are logged
+/// out. Nothing happens if the session belongs to a different user.
+///
+/// This mirrors Django's `django.contrib.auth.update_session_auth_hash()`.
+pub fn update_session_auth_hash(request: &mut HttpRequest, user: &AbstractUser) {
+    let mut session = SessionData::from_request(request);
+    if session.data.get(SESSION_USER_KEY).and_then(|v| v.as_str()) != Some(user.username.as_str()) {
+        return;
+    }
+    session.set(
+        SESSION_HASH_KEY,
+        serde_json::Value::String(session_auth_hash(&user.base.password)),
+    );
+    session.save_to_request(request);
 }
 
 /// Checks whether the current request has an authenticated user.
@@ -141,37 +177,22 @@ pub fn is_authenticated(request: &HttpRequest) -> bool {
         .is_some_and(|v| v == "true")
 }
 
-/// Retrieves the authenticated user's ID from the session data in META.
+/// Retrieves the authenticated user's ID from the request's session.
 ///
 /// Returns `None` if no user is logged in or if the session data is missing.
 pub fn get_user_id_from_meta(request: &HttpRequest) -> Option<String> {
-    let session_data_str = request.meta().get("SESSION_DATA")?;
-    let data: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(session_data_str).ok()?;
-    data.get(SESSION_USER_KEY)
-        .and_then(|v| v.as_str())
-        .map(String::from)
+    session_string(request, SESSION_USER_KEY)
 }
 
-/// Retrieves the authentication backend name from the session data in META.
+/// Retrieves the authentication backend name from the request's session.
 pub fn get_backend_from_meta(request: &HttpRequest) -> Option<String> {
-    let session_data_str = request.meta().get("SESSION_DATA")?;
-    let data: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(session_data_str).ok()?;
-    data.get(SESSION_BACKEND_KEY)
-        .and_then(|v| v.as_str())
-        .map(String::from)
+    session_string(request, SESSION_BACKEND_KEY)
 }
 
-/// Retrieves the session auth hash from the session data in META.
+/// Retrieves the session auth hash from the request's session.
 #[cfg(test)]
 fn get_session_hash_from_meta(request: &HttpRequest) -> Option<String> {
-    let session_data_str = request.meta().get("SESSION_DATA")?;
-    let data: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(session_data_str).ok()?;
-    data.get(SESSION_HASH_KEY)
-        .and_then(|v| v.as_str())
-        .map(String::from)
+    session_string(request, SESSION_HASH_KEY)
 }
 
 /// Loads the authenticated user from session data by querying an auth backend.
@@ -201,40 +222,146 @@ pub async fn get_user_from_session(
     // Query backend for the user
     let user = backend.get_user(user_id).await.ok()??;
 
-    // Verify the session auth hash matches current password
+    // A mismatch means the password has changed since the session was created
+    session_hash_matches(stored_hash, &user).then_some(user)
+}
+
+/// Returns `true` if `stored_hash` is the session auth hash of the user's
+/// current password.
+fn session_hash_matches(stored_hash: &str, user: &AbstractUser) -> bool {
     let current_hash = session_auth_hash(&user.base.password);
-    if constant_time_eq(stored_hash.as_bytes(), current_hash.as_bytes()) {
-        Some(user)
-    } else {
-        // Password has changed since session was created
-        None
-    }
+    constant_time_eq(stored_hash.as_bytes(), current_hash.as_bytes())
 }
 
-/// Loads the authenticated user from request META by querying an auth backend.
+/// Loads the authenticated user from the request's session by querying an
+/// auth backend.
 ///
-/// Convenience function that reads session data from META and delegates
-/// to [`get_user_from_session`].
+/// Convenience function that reads the session with
+/// [`SessionData::from_request`] and delegates to [`get_user_from_session`].
 pub async fn get_user_from_request(
     request: &HttpRequest,
     backend: &dyn AuthBackend,
 ) -> Option<AbstractUser> {
-    let session_data_str = request.meta().get("SESSION_DATA")?;
-    let data: std::collections::HashMap<String, serde_json::Value> =
-        serde_json::from_str(session_data_str).ok()?;
+    let session = SessionData::from_request(request);
+    get_user_from_session(&session, backend).await
+}
 
-    // Build a temporary SessionData from the META
-    let session_key = request
-        .meta()
-        .get("SESSION_KEY")
-        .cloned()
-        .unwrap_or_default();
-    let mut session = SessionData::new(session_key);
-    for (k, v) in data {
-        session.set(&k, v);
+/// Verifies sessions for the views crate's `AuthenticationMiddleware` by
+/// loading their users from an auth backend.
+///
+/// A session passes if its user exists, is active, and its
+/// `_auth_user_hash` matches the user's current password.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use django_rs_auth::backends::ModelBackend;
+/// use django_rs_auth::session_auth::SessionHashVerifier;
+/// use django_rs_views::AuthenticationMiddleware;
+///
+/// let verifier = SessionHashVerifier::new(Arc::new(ModelBackend::new()));
+/// let middleware = AuthenticationMiddleware::new().with_verifier(Arc::new(verifier));
+/// ```
+#[derive(Clone)]
+pub struct SessionHashVerifier {
+    backend: Arc<dyn AuthBackend>,
+}
+
+impl SessionHashVerifier {
+    /// Creates a verifier that loads users from `backend`.
+    pub fn new(backend: Arc<dyn AuthBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+impl std::fmt::Debug for SessionHashVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionHashVerifier")
+            .finish_non_exhaustive()
     }
+}
 
-    get_user_from_session(&session, backend).await
+#[async_trait]
+impl SessionVerifier for SessionHashVerifier {
+    async fn verify(&self, user_id: &str, session_hash: &str) -> Option<CurrentUser> {
+        let user = self.backend.get_user(user_id).await.ok()??;
+        (user.base.is_active && session_hash_matches(session_hash, &user))
+            .then(|| current_user(&user))
+    }
+}
+
+/// Middleware that loads the user from the session and verifies its auth hash.
+///
+/// This is the session-verifying counterpart of the views crate's
+/// `AuthenticationMiddleware`: besides attaching a [`CurrentUser`] and
+/// populating `META["USER_ID"]` and `META["USER_AUTHENTICATED"]`, it loads the user from the backend and
+/// checks the session auth hash against the current password. Sessions
+/// whose hash no longer matches, for example because the password was
+/// changed elsewhere, are logged out.
+///
+/// This middleware must be placed after `SessionMiddleware` in the pipeline.
+#[derive(Clone)]
+pub struct SessionAuthMiddleware {
+    backend: Arc<dyn AuthBackend>,
+}
+
+impl SessionAuthMiddleware {
+    /// Creates a middleware that loads users from `backend`.
+    pub fn new(backend: Arc<dyn AuthBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+impl std::fmt::Debug for SessionAuthMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionAuthMiddleware")
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Middleware for SessionAuthMiddleware {
+    async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
+        if get_user_id_from_meta(request).is_none() {
+            request
+                .meta_mut()
+                .insert(META_USER_AUTHENTICATED.to_string(), "false".to_string());
+            request.extensions_mut().insert(CurrentUser::anonymous());
+            return None;
+        }
+
+        match get_user_from_request(request, self.backend.as_ref()).await {
+            Some(user) if user.base.is_active => {
+                let meta = request.meta_mut();
+                meta.insert(META_USER_ID.to_string(), user.username.clone());
+                meta.insert(META_USER_AUTHENTICATED.to_string(), "true".to_string());
+                request.extensions_mut().insert(current_user(&user));
+            }
+            _ => {
+                logout_from_session(request);
+                request.meta_mut().remove(META_USER_ID);
+                request.extensions_mut().insert(CurrentUser::anonymous());
+            }
+        }
+        None
+    }
+
+    async fn process_response(
+        &self,
+        _request: &HttpRequest,
+        response: HttpResponse,
+    ) -> HttpResponse {
+        response
+    }
+
+    async fn process_exception(
+        &self,
+        _request: &HttpRequest,
+        _error: &DjangoError,
+    ) -> Option<HttpResponse> {
+        None
+    }
 }
 
 /// Constant-time byte comparison to prevent timing attacks.
@@ -326,7 +453,7 @@ mod tests {
         assert!(hash.is_some());
         let hash = hash.unwrap();
         assert!(!hash.is_empty());
-        // Hash fragment should match the first 10 chars of the password hash
+        // The stored hash should be the HMAC of the password hash
         let expected = session_auth_hash(&user.base.password);
         assert_eq!(hash, expected);
     }
@@ -383,9 +510,17 @@ mod tests {
 
         login_to_session(&mut request, &user);
         assert!(is_authenticated(&request));
+        assert_eq!(
+            request.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::authenticated("alice"))
+        );
 
         logout_from_session(&mut request);
         assert!(!is_authenticated(&request));
+        assert_eq!(
+            request.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::anonymous())
+        );
     }
 
     #[tokio::test]
@@ -589,30 +724,149 @@ mod tests {
     // ── session_auth_hash tests ─────────────────────────────────────
 
     #[test]
-    fn test_session_auth_hash_long_password() {
-        let hash = session_auth_hash("$argon2id$v=19$m=19456,t=2,p=1$abc$def");
-        assert_eq!(hash.len(), 38); // Full hash is 38 chars, so all taken
-        assert_eq!(hash, "$argon2id$v=19$m=19456,t=2,p=1$abc$def");
+    fn test_session_auth_hash_is_hmac() {
+        let password_hash = "$argon2id$v=19$m=19456,t=2,p=1$abc$def";
+        let hash = session_auth_hash(password_hash);
+        assert_eq!(hash.len(), 64);
+        assert!(!hash.contains("argon2"));
+        assert_eq!(hash, session_auth_hash(password_hash));
     }
 
     #[test]
-    fn test_session_auth_hash_very_long_password() {
-        let long_hash = "$argon2id$v=19$m=19456,t=2,p=1$abcdefghijklmnop$qrstuvwxyz1234567890";
-        let hash = session_auth_hash(long_hash);
-        assert_eq!(hash.len(), 40);
-        assert_eq!(hash, &long_hash[..40]);
+    fn test_session_auth_hash_differs_per_password() {
+        assert_ne!(
+            session_auth_hash("$argon2id$v=19$m=19456,t=2,p=1$abc$def"),
+            session_auth_hash("$argon2id$v=19$m=19456,t=2,p=1$abc$xyz")
+        );
     }
 
     #[test]
-    fn test_session_auth_hash_short_password() {
-        let hash = session_auth_hash("short");
-        assert_eq!(hash, "short");
+    fn test_session_auth_hash_matches_user_method() {
+        let mut user = AbstractUser::new("alice");
+        user.base.password = "pbkdf2_sha256$1$salt$hash".to_string();
+        assert_eq!(
+            session_auth_hash(&user.base.password),
+            user.base.get_session_auth_hash("")
+        );
     }
 
-    #[test]
-    fn test_session_auth_hash_empty() {
-        let hash = session_auth_hash("");
-        assert_eq!(hash, "");
+    // ── update_session_auth_hash tests ──────────────────────────────
+
+    #[tokio::test]
+    async fn test_update_session_auth_hash_keeps_session_valid() {
+        let backend = crate::backends::ModelBackend::new();
+        let mut user = create_test_user("alice", "pass123").await;
+        let mut current = make_request();
+        let mut other = make_request();
+        login_to_session(&mut current, &user);
+        login_to_session(&mut other, &user);
+
+        user.set_password("newpass456").await.unwrap();
+        backend.add_user(user.clone()).await;
+        update_session_auth_hash(&mut current, &user);
+
+        assert!(get_user_from_request(&current, &backend).await.is_some());
+        assert!(get_user_from_request(&other, &backend).await.is_none());
+        assert_eq!(
+            current.meta().get("SESSION_MODIFIED"),
+            Some(&"true".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_session_auth_hash_ignores_other_user() {
+        let alice = create_test_user("alice", "pass123").await;
+        let bob = create_test_user("bob", "pass456").await;
+        let mut request = make_request();
+        login_to_session(&mut request, &alice);
+        let before = get_session_hash_from_meta(&request);
+
+        update_session_auth_hash(&mut request, &bob);
+        assert_eq!(get_session_hash_from_meta(&request), before);
+    }
+
+    // ── SessionAuthMiddleware tests ─────────────────────────────────
+
+    #[tokio::test]
+    async fn test_middleware_authenticates_valid_session() {
+        let backend = Arc::new(crate::backends::ModelBackend::new());
+        let user = create_test_user("alice", "pass123").await;
+        backend.add_user(user.clone()).await;
+        let mut request = make_request();
+        login_to_session(&mut request, &user);
+        request.meta_mut().remove(META_USER_AUTHENTICATED);
+
+        let middleware = SessionAuthMiddleware::new(backend);
+        assert!(middleware.process_request(&mut request).await.is_none());
+        assert!(is_authenticated(&request));
+        assert_eq!(request.meta().get(META_USER_ID), Some(&"alice".to_string()));
+        assert_eq!(
+            request.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::authenticated("alice"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_logs_out_after_password_change() {
+        let backend = Arc::new(crate::backends::ModelBackend::new());
+        let user = create_test_user("alice", "pass123").await;
+        let mut request = make_request();
+        login_to_session(&mut request, &user);
+        backend
+            .add_user(create_test_user("alice", "changed456").await)
+            .await;
+
+        let middleware = SessionAuthMiddleware::new(backend);
+        assert!(middleware.process_request(&mut request).await.is_none());
+        assert!(!is_authenticated(&request));
+        assert!(get_user_id_from_meta(&request).is_none());
+        assert!(request.meta().get(META_USER_ID).is_none());
+        assert_eq!(
+            request.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::anonymous())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_anonymous_session() {
+        let middleware = SessionAuthMiddleware::new(Arc::new(crate::backends::ModelBackend::new()));
+        let mut request = make_request();
+        assert!(middleware.process_request(&mut request).await.is_none());
+        assert!(!is_authenticated(&request));
+    }
+
+    // ── SessionHashVerifier tests ───────────────────────────────────
+
+    #[tokio::test]
+    async fn test_authentication_middleware_flushes_session_after_password_change() {
+        use django_rs_views::AuthenticationMiddleware;
+
+        async fn middleware_for(user: AbstractUser) -> AuthenticationMiddleware {
+            let backend = Arc::new(crate::backends::ModelBackend::new());
+            backend.add_user(user).await;
+            AuthenticationMiddleware::new()
+                .with_verifier(Arc::new(SessionHashVerifier::new(backend)))
+        }
+
+        let user = create_test_user("alice", "pass123").await;
+        let mut request = make_request();
+        login_to_session(&mut request, &user);
+        middleware_for(user.clone())
+            .await
+            .process_request(&mut request)
+            .await;
+        assert!(is_authenticated(&request));
+        assert_eq!(request.meta().get(META_USER_ID), Some(&"alice".to_string()));
+
+        let mut request = make_request();
+        login_to_session(&mut request, &user);
+        middleware_for(create_test_user("alice", "changed456").await)
+            .await
+            .process_request(&mut request)
+            .await;
+        assert!(!is_authenticated(&request));
+        assert!(request.meta().get(META_USER_ID).is_none());
+        assert!(SessionData::from_request(&request).is_empty());
     }
 
     // ── constant_time_eq tests ──────────────────────────────────────
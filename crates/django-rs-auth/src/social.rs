@@ -0,0 +1,1918 @@
+//! Social login with OAuth2 and OpenID Connect.
+//!
+//! [`OidcBackend`] runs the authorization-code flow against the configured
+//! providers. It redirects the user to the provider with a `state`, a `nonce`
+//! and a PKCE challenge kept in the session, exchanges the code the provider
+//! sends back for tokens, and maps the provider's claims to a local user,
+//! linking or creating it as its [`SocialUserPolicy`] allows. The user is
+//! then logged into the session like [`login_view`](crate::views::login_view)
+//! does.
+//!
+//! [`social_urls`] returns the views to mount, for example under
+//! `accounts/social/`:
+//!
+//! | Route | Name |
+//! |-------|------|
+//! | `<provider>/login/` | `social_login` |
+//! | `<provider>/callback/` | `social_callback` |
+//!
+//! ## Providers
+//!
+//! Google and GitHub come with their endpoints preconfigured; any other
+//! OpenID Connect provider is configured with its issuer, and its endpoints
+//! are fetched from its discovery document by [`OidcBackend::discover`].
+//! Providers are usually read from `settings.social_auth_providers`:
+//!
+//! ```toml
+//! [social_auth_providers.google]
+//! kind = "google"
+//! client_id = "1234.apps.googleusercontent.com"
+//! client_secret = { secret = { provider = "env", name = "GOOGLE_CLIENT_SECRET" } }
+//!
+//! [social_auth_providers.corp]
+//! kind = "oidc"
+//! issuer = "https://sso.example.com"
+//! client_id = "django-rs"
+//! client_secret = { secret = { provider = "env", name = "CORP_CLIENT_SECRET" } }
+//! ```
+//!
+//! HTTP requests to the providers go through an [`OAuthHttpClient`]; the
+//! `oidc` feature provides one built on `reqwest`, used by default.
+//!
+//! ## ID Tokens
+//!
+//! The ID token is received directly from the token endpoint over TLS, so,
+//! as OpenID Connect Core 1.0 §3.1.3.7 allows, its signature is not checked.
+//! Its issuer, audience, expiry and nonce are.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use django_rs_auth::backends::ModelBackend;
+//! use django_rs_auth::social::{social_urls, OidcBackend, OidcProvider, SocialUrls};
+//! use django_rs_http::urls::resolver::{include, root, URLEntry};
+//!
+//! let backend = OidcBackend::new(Arc::new(ModelBackend::new()))
+//!     .provider(OidcProvider::google("client-id", "client-secret"))
+//!     .provider(OidcProvider::github("client-id", "client-secret"));
+//! let urls = SocialUrls::new("/accounts/social/", backend);
+//! let social = include("accounts/social/", social_urls(urls).unwrap(), None, None).unwrap();
+//! let resolver = root(vec![URLEntry::Resolver(social)]).unwrap();
+//! assert!(resolver.resolve("accounts/social/google/callback/").is_ok());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+use django_rs_core::settings::SocialProviderSettings;
+use django_rs_core::{DjangoError, DjangoResult, Settings};
+use django_rs_http::urls::pattern::{path, RouteHandler};
+use django_rs_http::urls::resolver::URLEntry;
+use django_rs_http::{HttpRequest, HttpResponse, HttpResponseRedirect, QueryDict};
+use django_rs_views::session::SessionData;
+
+use crate::backends::AuthBackend;
+use crate::csrf::constant_time_eq;
+use crate::session_auth;
+use crate::user::AbstractUser;
+
+/// The backend name stored in the session for users logged in by
+/// [`OidcBackend`].
+pub const BACKEND_PATH: &str = "django_rs.auth.social.OidcBackend";
+
+/// Prefix of the session keys holding a login in progress, one per provider.
+const SESSION_PENDING_PREFIX: &str = "_social_auth_";
+
+/// How many numbered suffixes are tried to find a free username.
+const MAX_USERNAME_ATTEMPTS: usize = 100;
+
+/// The kind of provider, which decides its default endpoints and how its
+/// user information is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// Google, an OpenID Connect provider.
+    Google,
+    /// GitHub, which speaks plain OAuth2 and has no ID tokens.
+    GitHub,
+    /// Any other OpenID Connect provider.
+    Oidc,
+}
+
+impl ProviderKind {
+    /// Parses a provider kind as written in settings: `"google"`, `"github"`
+    /// or `"oidc"`.
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind.to_ascii_lowercase().as_str() {
+            "google" => Some(Self::Google),
+            "github" => Some(Self::GitHub),
+            "oidc" => Some(Self::Oidc),
+            _ => None,
+        }
+    }
+}
+
+/// The configuration of an OAuth2 or OpenID Connect provider.
+#[derive(Clone)]
+pub struct OidcProvider {
+    /// The name used in URLs and to link accounts (e.g. `"google"`).
+    pub name: String,
+    /// The kind of provider.
+    pub kind: ProviderKind,
+    /// The OAuth2 client ID.
+    pub client_id: String,
+    /// The OAuth2 client secret.
+    pub client_secret: String,
+    /// The expected `iss` claim of ID tokens; empty skips the check.
+    pub issuer: String,
+    /// Where users are sent to log in.
+    pub authorization_endpoint: String,
+    /// Where the authorization code is exchanged for tokens.
+    pub token_endpoint: String,
+    /// Where user information is fetched with the access token; may be empty
+    /// for OpenID Connect providers whose ID tokens carry the claims.
+    pub userinfo_endpoint: String,
+    /// The scopes to request.
+    pub scopes: Vec<String>,
+}
+
+impl std::fmt::Debug for OidcProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OidcProvider")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("client_id", &self.client_id)
+            .field("issuer", &self.issuer)
+            .field("authorization_endpoint", &self.authorization_endpoint)
+            .field("token_endpoint", &self.token_endpoint)
+            .field("userinfo_endpoint", &self.userinfo_endpoint)
+            .field("scopes", &self.scopes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OidcProvider {
+    /// Creates the `"google"` provider.
+    pub fn google(client_id: &str, client_secret: &str) -> Self {
+        Self {
+            name: "google".to_string(),
+            kind: ProviderKind::Google,
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            issuer: "https://accounts.google.com".to_string(),
+            authorization_endpoint: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            token_endpoint: "https://oauth2.googleapis.com/token".to_string(),
+            userinfo_endpoint: "https://openidconnect.googleapis.com/v1/userinfo".to_string(),
+            scopes: vec!["openid".into(), "email".into(), "profile".into()],
+        }
+    }
+
+    /// Creates the `"github"` provider.
+    pub fn github(client_id: &str, client_secret: &str) -> Self {
+        Self {
+            name: "github".to_string(),
+            kind: ProviderKind::GitHub,
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            issuer: String::new(),
+            authorization_endpoint: "https://github.com/login/oauth/authorize".to_string(),
+            token_endpoint: "https://github.com/login/oauth/access_token".to_string(),
+            userinfo_endpoint: "https://api.github.com/user".to_string(),
+            scopes: vec!["read:user".into(), "user:email".into()],
+        }
+    }
+
+    /// Creates a generic OpenID Connect provider.
+    ///
+    /// Its endpoints are fetched from the issuer's discovery document by
+    /// [`OidcBackend::discover`], unless set with [`endpoints`](Self::endpoints).
+    pub fn oidc(name: &str, issuer: &str, client_id: &str, client_secret: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: ProviderKind::Oidc,
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            issuer: issuer.trim_end_matches('/').to_string(),
+            authorization_endpoint: String::new(),
+            token_endpoint: String::new(),
+            userinfo_endpoint: String::new(),
+            scopes: vec!["openid".into(), "email".into(), "profile".into()],
+        }
+    }
+
+    /// Creates a provider from its settings entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ImproperlyConfigured` if the kind is unknown, or if a generic
+    /// OpenID Connect provider has neither an issuer nor endpoints.
+    pub fn from_settings(name: &str, settings: &SocialProviderSettings) -> DjangoResult<Self> {
+        let kind = ProviderKind::parse(&settings.kind).ok_or_else(|| {
+            DjangoError::ImproperlyConfigured(format!(
+                "Social auth provider '{name}' has unknown kind '{}'",
+                settings.kind
+            ))
+        })?;
+        let (id, secret) = (&settings.client_id, &settings.client_secret);
+        let mut provider = match kind {
+            ProviderKind::Google => Self::google(id, secret),
+            ProviderKind::GitHub => Self::github(id, secret),
+            ProviderKind::Oidc => Self::oidc(name, &settings.issuer, id, secret),
+        };
+        provider.name = name.to_string();
+        if !settings.issuer.is_empty() {
+            provider.issuer = settings.issuer.trim_end_matches('/').to_string();
+        }
+        for (field, value) in [
+            (
+                &mut provider.authorization_endpoint,
+                &settings.authorization_endpoint,
+            ),
+            (&mut provider.token_endpoint, &settings.token_endpoint),
+            (&mut provider.userinfo_endpoint, &settings.userinfo_endpoint),
+        ] {
+            if !value.is_empty() {
+                field.clone_from(value);
+            }
+        }
+        if !settings.scopes.is_empty() {
+            provider.scopes.clone_from(&settings.scopes);
+        }
+        if provider.needs_discovery() && provider.issuer.is_empty() {
+            return Err(DjangoError::ImproperlyConfigured(format!(
+                "Social auth provider '{name}' needs an issuer or its endpoints"
+            )));
+        }
+        Ok(provider)
+    }
+
+    /// Sets the authorization, token and user info endpoints.
+    #[must_use]
+    pub fn endpoints(mut self, authorization: &str, token: &str, userinfo: &str) -> Self {
+        self.authorization_endpoint = authorization.to_string();
+        self.token_endpoint = token.to_string();
+        self.userinfo_endpoint = userinfo.to_string();
+        self
+    }
+
+    /// Sets the scopes to request.
+    #[must_use]
+    pub fn scopes(mut self, scopes: &[&str]) -> Self {
+        self.scopes = scopes.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Returns `true` if the endpoints must be discovered before logging in.
+    pub fn needs_discovery(&self) -> bool {
+        self.authorization_endpoint.is_empty() || self.token_endpoint.is_empty()
+    }
+
+    /// Fills the missing endpoints from the issuer's
+    /// `/.well-known/openid-configuration` document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be fetched or lacks the
+    /// authorization or token endpoint.
+    pub async fn discover(&mut self, client: &dyn OAuthHttpClient) -> DjangoResult<()> {
+        let url = format!("{}/.well-known/openid-configuration", self.issuer);
+        let document = client.get_json(&url, None).await?;
+        let endpoint = |key: &str| {
+            document
+                .get(key)
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        for (field, key) in [
+            (&mut self.authorization_endpoint, "authorization_endpoint"),
+            (&mut self.token_endpoint, "token_endpoint"),
+            (&mut self.userinfo_endpoint, "userinfo_endpoint"),
+        ] {
+            if field.is_empty() {
+                *field = endpoint(key);
+            }
+        }
+        if self.needs_discovery() {
+            return Err(DjangoError::ImproperlyConfigured(format!(
+                "The discovery document at {url} has no authorization or token endpoint"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The user information a provider returned for a login.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocialIdentity {
+    /// The provider name.
+    pub provider: String,
+    /// The provider's stable identifier for the user (`sub`, or GitHub's id).
+    pub subject: String,
+    /// The user's email address, if shared.
+    pub email: Option<String>,
+    /// Whether the provider verified the email address.
+    pub email_verified: bool,
+    /// The user's preferred username, if any.
+    pub username: Option<String>,
+    /// The user's given name.
+    pub first_name: String,
+    /// The user's family name.
+    pub last_name: String,
+    /// All claims returned by the provider.
+    pub claims: serde_json::Value,
+}
+
+impl SocialIdentity {
+    /// Reads an identity from OpenID Connect claims.
+    fn from_oidc_claims(provider: &str, claims: serde_json::Value) -> DjangoResult<Self> {
+        let text = |key: &str| claims.get(key).and_then(|v| v.as_str()).map(String::from);
+        let subject = text("sub").ok_or_else(|| {
+            DjangoError::SuspiciousOperation(format!("{provider} returned no subject"))
+        })?;
+        let email_verified = match claims.get("email_verified") {
+            Some(serde_json::Value::Bool(verified)) => *verified,
+            Some(serde_json::Value::String(verified)) => verified == "true",
+            _ => false,
+        };
+        let (first_name, last_name) = match (text("given_name"), text("family_name")) {
+            (None, None) => split_name(text("name").as_deref().unwrap_or_default()),
+            (first, last) => (first.unwrap_or_default(), last.unwrap_or_default()),
+        };
+        Ok(Self {
+            provider: provider.to_string(),
+            subject,
+            email: text("email").filter(|email| !email.is_empty()),
+            email_verified,
+            username: text("preferred_username"),
+            first_name,
+            last_name,
+            claims,
+        })
+    }
+}
+
+/// Decides which local user a social login maps to.
+#[derive(Debug, Clone)]
+pub struct SocialUserPolicy {
+    /// Whether an identity with no linked user gets a new user.
+    pub create_users: bool,
+    /// Whether an identity with no linked user is linked to the existing
+    /// user with the same email, if the provider verified it.
+    pub link_verified_email: bool,
+    /// If not empty, only identities with a verified email in one of these
+    /// domains may log in.
+    pub allowed_email_domains: Vec<String>,
+}
+
+impl Default for SocialUserPolicy {
+    fn default() -> Self {
+        Self {
+            create_users: true,
+            link_verified_email: false,
+            allowed_email_domains: Vec::new(),
+        }
+    }
+}
+
+impl SocialUserPolicy {
+    /// Returns `true` if the identity's email domain is allowed.
+    fn allows(&self, identity: &SocialIdentity) -> bool {
+        if self.allowed_email_domains.is_empty() {
+            return true;
+        }
+        let Some(email) = identity
+            .email
+            .as_deref()
+            .filter(|_| identity.email_verified)
+        else {
+            return false;
+        };
+        let domain = email.rsplit_once('@').map_or("", |(_, domain)| domain);
+        self.allowed_email_domains
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+    }
+}
+
+/// A link between a provider identity and a local user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocialAccount {
+    /// The provider name.
+    pub provider: String,
+    /// The provider's identifier for the user.
+    pub subject: String,
+    /// The local username.
+    pub username: String,
+    /// When the link was made.
+    pub linked_at: DateTime<Utc>,
+}
+
+/// Storage for the links between provider identities and local users.
+#[async_trait]
+pub trait SocialAccountStore: Send + Sync {
+    /// Returns the link for a provider identity.
+    async fn find(&self, provider: &str, subject: &str) -> DjangoResult<Option<SocialAccount>>;
+
+    /// Stores a link, replacing any link for the same provider identity.
+    async fn link(&self, account: SocialAccount) -> DjangoResult<()>;
+
+    /// Returns the links of a local user.
+    async fn accounts_for_user(&self, username: &str) -> DjangoResult<Vec<SocialAccount>>;
+}
+
+/// In-memory implementation of [`SocialAccountStore`].
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySocialAccountStore {
+    accounts: Arc<RwLock<Vec<SocialAccount>>>,
+}
+
+impl InMemorySocialAccountStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SocialAccountStore for InMemorySocialAccountStore {
+    async fn find(&self, provider: &str, subject: &str) -> DjangoResult<Option<SocialAccount>> {
+        let accounts = self.accounts.read().await;
+        Ok(accounts
+            .iter()
+            .find(|a| a.provider == provider && a.subject == subject)
+            .cloned())
+    }
+
+    async fn link(&self, account: SocialAccount) -> DjangoResult<()> {
+        let mut accounts = self.accounts.write().await;
+        accounts.retain(|a| !(a.provider == account.provider && a.subject == account.subject));
+        accounts.push(account);
+        drop(accounts);
+        Ok(())
+    }
+
+    async fn accounts_for_user(&self, username: &str) -> DjangoResult<Vec<SocialAccount>> {
+        let accounts = self.accounts.read().await;
+        Ok(accounts
+            .iter()
+            .filter(|a| a.username == username)
+            .cloned()
+            .collect())
+    }
+}
+
+/// The HTTP requests [`OidcBackend`] makes to providers.
+#[async_trait]
+pub trait OAuthHttpClient: Send + Sync {
+    /// POSTs a form and returns the JSON response body.
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> DjangoResult<serde_json::Value>;
+
+    /// GETs a JSON document, with a bearer token if given.
+    async fn get_json(&self, url: &str, bearer: Option<&str>) -> DjangoResult<serde_json::Value>;
+}
+
+/// An [`OAuthHttpClient`] built on `reqwest`.
+#[cfg(feature = "oidc")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestClient {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "oidc")]
+impl ReqwestClient {
+    /// Creates a client.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn send(request: reqwest::RequestBuilder) -> DjangoResult<serde_json::Value> {
+        let response = request
+            .header(reqwest::header::ACCEPT, "application/json")
+            .header(reqwest::header::USER_AGENT, "django-rs")
+            .send()
+            .await
+            .map_err(|e| DjangoError::InternalServerError(format!("OAuth request failed: {e}")))?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.map_err(|e| {
+            DjangoError::InternalServerError(format!("Invalid OAuth response: {e}"))
+        })?;
+        // Token endpoints report errors in the body, which the backend reads.
+        if status.is_server_error() || (status.is_client_error() && body.get("error").is_none()) {
+            return Err(DjangoError::InternalServerError(format!(
+                "OAuth request failed with status {status}"
+            )));
+        }
+        Ok(body)
+    }
+}
+
+#[cfg(feature = "oidc")]
+#[async_trait]
+impl OAuthHttpClient for ReqwestClient {
+    async fn post_form(&self, url: &str, form: &[(&str, &str)]) -> DjangoResult<serde_json::Value> {
+        Self::send(self.client.post(url).form(form)).await
+    }
+
+    async fn get_json(&self, url: &str, bearer: Option<&str>) -> DjangoResult<serde_json::Value> {
+        let mut request = self.client.get(url);
+        if let Some(token) = bearer {
+            request = request.bearer_auth(token);
+        }
+        Self::send(request).await
+    }
+}
+
+/// A login in progress, kept in the session between the redirect to the
+/// provider and the callback.
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingLogin {
+    state: String,
+    nonce: String,
+    code_verifier: String,
+    next: Option<String>,
+}
+
+/// A completed social login.
+#[derive(Debug, Clone)]
+pub struct SocialLogin {
+    /// The local user, now logged into the session.
+    pub user: AbstractUser,
+    /// The identity the provider returned.
+    pub identity: SocialIdentity,
+    /// Where the user asked to go after logging in.
+    pub next: Option<String>,
+}
+
+/// Authenticates users through OAuth2 and OpenID Connect providers.
+///
+/// Users are loaded from, created in and linked through the wrapped
+/// [`AuthBackend`], which must support
+/// [`save_user`](AuthBackend::save_user) for new users.
+pub struct OidcBackend {
+    providers: HashMap<String, OidcProvider>,
+    users: Arc<dyn AuthBackend>,
+    accounts: Arc<dyn SocialAccountStore>,
+    client: Option<Arc<dyn OAuthHttpClient>>,
+    policy: SocialUserPolicy,
+}
+
+impl std::fmt::Debug for OidcBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut providers: Vec<&String> = self.providers.keys().collect();
+        providers.sort();
+        f.debug_struct("OidcBackend")
+            .field("providers", &providers)
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OidcBackend {
+    /// Creates a backend with no providers that loads and saves users with
+    /// `users`.
+    ///
+    /// Account links are kept in an [`InMemorySocialAccountStore`]. With the
+    /// `oidc` feature, providers are reached with a [`ReqwestClient`].
+    pub fn new(users: Arc<dyn AuthBackend>) -> Self {
+        #[cfg(feature = "oidc")]
+        let client: Option<Arc<dyn OAuthHttpClient>> = Some(Arc::new(ReqwestClient::new()));
+        #[cfg(not(feature = "oidc"))]
+        let client = None;
+        Self {
+            providers: HashMap::new(),
+            users,
+            accounts: Arc::new(InMemorySocialAccountStore::new()),
+            client,
+            policy: SocialUserPolicy::default(),
+        }
+    }
+
+    /// Adds a provider, replacing any provider with the same name.
+    #[must_use]
+    pub fn provider(mut self, provider: OidcProvider) -> Self {
+        self.providers.insert(provider.name.clone(), provider);
+        self
+    }
+
+    /// Adds the providers in `settings.social_auth_providers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ImproperlyConfigured` if a provider entry is invalid.
+    pub fn providers_from_settings(mut self, settings: &Settings) -> DjangoResult<Self> {
+        for (name, provider_settings) in &settings.social_auth_providers {
+            self = self.provider(OidcProvider::from_settings(name, provider_settings)?);
+        }
+        Ok(self)
+    }
+
+    /// Sets the store for account links.
+    #[must_use]
+    pub fn account_store(mut self, store: Arc<dyn SocialAccountStore>) -> Self {
+        self.accounts = store;
+        self
+    }
+
+    /// Sets the HTTP client used to reach providers.
+    #[must_use]
+    pub fn http_client(mut self, client: Arc<dyn OAuthHttpClient>) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the policy for linking and creating users.
+    #[must_use]
+    pub fn policy(mut self, policy: SocialUserPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns a provider by name.
+    pub fn get_provider(&self, name: &str) -> Option<&OidcProvider> {
+        self.providers.get(name)
+    }
+
+    /// Fetches the endpoints of the providers configured only with an issuer.
+    ///
+    /// Call this once at startup, before serving logins.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a discovery document cannot be fetched or is
+    /// incomplete.
+    pub async fn discover(mut self) -> DjangoResult<Self> {
+        let client = self.client()?.clone();
+        for provider in self.providers.values_mut() {
+            if provider.needs_discovery() {
+                provider.discover(client.as_ref()).await?;
+            }
+        }
+        Ok(self)
+    }
+
+    fn client(&self) -> DjangoResult<&Arc<dyn OAuthHttpClient>> {
+        self.client.as_ref().ok_or_else(|| {
+            DjangoError::ImproperlyConfigured(
+                "No OAuth HTTP client configured; enable the `oidc` feature or set one \
+                 with OidcBackend::http_client"
+                    .to_string(),
+            )
+        })
+    }
+
+    fn find_provider(&self, name: &str) -> DjangoResult<&OidcProvider> {
+        let provider = self.providers.get(name).ok_or_else(|| {
+            DjangoError::NotFound(format!("Unknown social auth provider '{name}'"))
+        })?;
+        if provider.needs_discovery() {
+            return Err(DjangoError::ImproperlyConfigured(format!(
+                "The endpoints of provider '{name}' have not been discovered; \
+                 call OidcBackend::discover at startup"
+            )));
+        }
+        Ok(provider)
+    }
+
+    /// Starts a login: stores a new `state`, `nonce` and PKCE verifier in the
+    /// request's session and returns the provider URL to redirect the user to.
+    ///
+    /// `redirect_uri` is the absolute URL of the callback view, and must be
+    /// registered with the provider. `next` is where the user goes after
+    /// logging in; only local paths are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` for an unknown provider, or `ImproperlyConfigured`
+    /// if its endpoints are unknown.
+    pub fn authorization_url(
+        &self,
+        request: &mut HttpRequest,
+        provider: &str,
+        redirect_uri: &str,
+        next: Option<&str>,
+    ) -> DjangoResult<String> {
+        let provider = self.find_provider(provider)?;
+        let pending = PendingLogin {
+            state: random_token(),
+            nonce: random_token(),
+            code_verifier: random_token(),
+            next: next.filter(|next| is_local_path(next)).map(String::from),
+        };
+
+        let mut query = QueryDict::new_mutable();
+        let scope = provider.scopes.join(" ");
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(pending.code_verifier.as_bytes()));
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", provider.client_id.as_str()),
+            ("redirect_uri", redirect_uri),
+            ("scope", scope.as_str()),
+            ("state", pending.state.as_str()),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        if provider.kind != ProviderKind::GitHub {
+            params.push(("nonce", pending.nonce.as_str()));
+        }
+        for (key, value) in params {
+            query.append(key, value)?;
+        }
+
+        let mut session = SessionData::from_request(request);
+        session.set_as(&pending_key(&provider.name), &pending)?;
+        session.save_to_request(request);
+
+        let separator = if provider.authorization_endpoint.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        Ok(format!(
+            "{}{separator}{}",
+            provider.authorization_endpoint,
+            query.urlencode()
+        ))
+    }
+
+    /// Completes a login from the provider's callback request: checks the
+    /// `state`, exchanges the code for tokens, reads the user's identity,
+    /// finds or creates the local user and logs it into the session.
+    ///
+    /// `redirect_uri` must be the one passed to
+    /// [`authorization_url`](Self::authorization_url).
+    ///
+    /// # Errors
+    ///
+    /// - `NotFound` for an unknown provider
+    /// - `SuspiciousOperation` if no login is in progress, the `state` does
+    ///   not match, or the ID token is invalid
+    /// - `BadRequest` if the provider reports an error
+    /// - `PermissionDenied` if the policy refuses the identity or the user is
+    ///   inactive
+    pub async fn complete(
+        &self,
+        request: &mut HttpRequest,
+        provider: &str,
+        redirect_uri: &str,
+    ) -> DjangoResult<SocialLogin> {
+        let provider = self.find_provider(provider)?;
+
+        // The pending login is single-use, whatever the outcome.
+        let mut session = SessionData::from_request(request);
+        let pending: Option<PendingLogin> = session.get_as(&pending_key(&provider.name))?;
+        session.remove(&pending_key(&provider.name));
+        session.save_to_request(request);
+        let pending = pending.ok_or_else(|| {
+            DjangoError::SuspiciousOperation(format!(
+                "No {} login is in progress in this session",
+                provider.name
+            ))
+        })?;
+
+        let params = request.get();
+        if let Some(error) = params.get("error") {
+            return Err(DjangoError::BadRequest(format!(
+                "{} refused the login: {error}",
+                provider.name
+            )));
+        }
+        let state = params.get("state").unwrap_or_default();
+        if !constant_time_eq(state.as_bytes(), pending.state.as_bytes()) {
+            return Err(DjangoError::SuspiciousOperation(
+                "The login state does not match".to_string(),
+            ));
+        }
+        let code = params
+            .get("code")
+            .ok_or_else(|| DjangoError::BadRequest("The callback has no code".to_string()))?
+            .to_string();
+
+        let client = self.client()?;
+        let tokens = client
+            .post_form(
+                &provider.token_endpoint,
+                &[
+                    ("grant_type", "authorization_code"),
+                    ("code", &code),
+                    ("redirect_uri", redirect_uri),
+                    ("client_id", &provider.client_id),
+                    ("client_secret", &provider.client_secret),
+                    ("code_verifier", &pending.code_verifier),
+                ],
+            )
+            .await?;
+        if let Some(error) = tokens.get("error").and_then(|e| e.as_str()) {
+            let description = tokens
+                .get("error_description")
+                .and_then(|d| d.as_str())
+                .unwrap_or(error);
+            return Err(DjangoError::BadRequest(format!(
+                "{} refused the code: {description}",
+                provider.name
+            )));
+        }
+        let access_token = tokens
+            .get("access_token")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| {
+                DjangoError::BadRequest(format!("{} returned no access token", provider.name))
+            })?;
+
+        let identity = match provider.kind {
+            ProviderKind::GitHub => {
+                github_identity(provider, client.as_ref(), access_token).await?
+            }
+            ProviderKind::Google | ProviderKind::Oidc => {
+                let id_token = tokens.get("id_token").and_then(|t| t.as_str());
+                oidc_identity(
+                    provider,
+                    client.as_ref(),
+                    access_token,
+                    id_token,
+                    &pending.nonce,
+                )
+                .await?
+            }
+        };
+        if !self.policy.allows(&identity) {
+            return Err(DjangoError::PermissionDenied(format!(
+                "This {} account is not allowed to log in",
+                provider.name
+            )));
+        }
+
+        let user = self.user_for_identity(&identity).await?;
+        if !user.base.is_active {
+            return Err(DjangoError::PermissionDenied(format!(
+                "User '{}' is inactive",
+                user.username
+            )));
+        }
+
+        let mut session = SessionData::from_request(request);
+        session.cycle_key();
+        session.save_to_request(request);
+        session_auth::login_to_session_with_backend(request, &user, BACKEND_PATH);
+
+        Ok(SocialLogin {
+            user,
+            identity,
+            next: pending.next,
+        })
+    }
+
+    /// Returns the local user for an identity, linking or creating one as the
+    /// policy allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermissionDenied` if the identity has no user and the policy
+    /// does not allow linking or creating one, or an error from the stores.
+    pub async fn user_for_identity(&self, identity: &SocialIdentity) -> DjangoResult<AbstractUser> {
+        if let Some(account) = self
+            .accounts
+            .find(&identity.provider, &identity.subject)
+            .await?
+        {
+            if let Some(user) = self.users.get_user(&account.username).await? {
+                return Ok(user);
+            }
+        }
+
+        if self.policy.link_verified_email && identity.email_verified {
+            if let Some(email) = &identity.email {
+                if let Some(user) = self.users.get_user_by_email(email).await? {
+                    self.link(identity, &user.username).await?;
+                    return Ok(user);
+                }
+            }
+        }
+
+        if !self.policy.create_users {
+            return Err(DjangoError::PermissionDenied(format!(
+                "No user is linked to this {} account",
+                identity.provider
+            )));
+        }
+        let mut user = AbstractUser::new(self.free_username(identity).await?);
+        user.email = identity.email.clone().unwrap_or_default();
+        user.first_name.clone_from(&identity.first_name);
+        user.last_name.clone_from(&identity.last_name);
+        user.base.set_unusable_password();
+        self.users.save_user(&user).await?;
+        self.link(identity, &user.username).await?;
+        Ok(user)
+    }
+
+    async fn link(&self, identity: &SocialIdentity, username: &str) -> DjangoResult<()> {
+        self.accounts
+            .link(SocialAccount {
+                provider: identity.provider.clone(),
+                subject: identity.subject.clone(),
+                username: username.to_string(),
+                linked_at: Utc::now(),
+            })
+            .await
+    }
+
+    /// Picks an unused username for a new user, from the identity's username
+    /// or email, with a numbered suffix if taken.
+    ///
+    /// After [`MAX_USERNAME_ATTEMPTS`] taken names, falls back to
+    /// `<provider>_<subject>`, and fails if that is taken too.
+    async fn free_username(&self, identity: &SocialIdentity) -> DjangoResult<String> {
+        let fallback = format!("{}_{}", identity.provider, identity.subject);
+        let base = identity
+            .username
+            .as_deref()
+            .or_else(|| {
+                identity
+                    .email
+                    .as_deref()
+                    .and_then(|email| email.split('@').next())
+            })
+            .map(sanitize_username)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| sanitize_username(&fallback));
+
+        for attempt in 1..=MAX_USERNAME_ATTEMPTS {
+            let candidate = if attempt == 1 {
+                base.clone()
+            } else {
+                format!("{base}{attempt}")
+            };
+            if self.users.get_user(&candidate).await?.is_none() {
+                return Ok(candidate);
+            }
+        }
+        let fallback = sanitize_username(&fallback);
+        if self.users.get_user(&fallback).await?.is_none() {
+            return Ok(fallback);
+        }
+        Err(DjangoError::InternalServerError(format!(
+            "No free username for {} user '{}'",
+            identity.provider, identity.subject
+        )))
+    }
+}
+
+/// Reads an OpenID Connect identity from the ID token, completed with the
+/// user info endpoint when the token lacks an email.
+///
+/// The ID token is required when the `openid` scope was requested, since
+/// only it proves the login was issued for this client and nonce.
+async fn oidc_identity(
+    provider: &OidcProvider,
+    client: &dyn OAuthHttpClient,
+    access_token: &str,
+    id_token: Option<&str>,
+    nonce: &str,
+) -> DjangoResult<SocialIdentity> {
+    let mut claims = match id_token {
+        Some(token) => id_token_claims(provider, token, nonce)?,
+        None if provider.scopes.iter().any(|scope| scope == "openid") => {
+            return Err(DjangoError::SuspiciousOperation(format!(
+                "{} returned no ID token for an openid login",
+                provider.name
+            )));
+        }
+        None => serde_json::Value::Object(serde_json::Map::new()),
+    };
+    if claims.get("email").is_none() && !provider.userinfo_endpoint.is_empty() {
+        let userinfo = client
+            .get_json(&provider.userinfo_endpoint, Some(access_token))
+            .await?;
+        if let (Some(sub), Some(info_sub)) = (claims.get("sub"), userinfo.get("sub")) {
+            if sub != info_sub {
+                return Err(DjangoError::SuspiciousOperation(format!(
+                    "{} returned user info for another subject",
+                    provider.name
+                )));
+            }
+        }
+        if let (Some(claims), serde_json::Value::Object(info)) = (claims.as_object_mut(), userinfo)
+        {
+            for (key, value) in info {
+                claims.entry(key).or_insert(value);
+            }
+        }
+    }
+    SocialIdentity::from_oidc_claims(&provider.name, claims)
+}
+
+/// Decodes an ID token and checks its issuer, audience, expiry and nonce.
+fn id_token_claims(
+    provider: &OidcProvider,
+    id_token: &str,
+    nonce: &str,
+) -> DjangoResult<serde_json::Value> {
+    let invalid = |reason: &str| {
+        DjangoError::SuspiciousOperation(format!(
+            "Invalid ID token from {}: {reason}",
+            provider.name
+        ))
+    };
+    let payload = id_token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| invalid("malformed"))?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|_| invalid("malformed"))?;
+    let claims: serde_json::Value =
+        serde_json::from_slice(&payload).map_err(|_| invalid("malformed"))?;
+
+    let issuer = claims
+        .get("iss")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let bare = |url: &str| {
+        url.trim_start_matches("https://")
+            .trim_end_matches('/')
+            .to_string()
+    };
+    if !provider.issuer.is_empty() && bare(issuer) != bare(&provider.issuer) {
+        return Err(invalid("wrong issuer"));
+    }
+    let audience_ok = match claims.get("aud") {
+        Some(serde_json::Value::String(aud)) => *aud == provider.client_id,
+        Some(serde_json::Value::Array(auds)) => auds
+            .iter()
+            .any(|aud| aud.as_str() == Some(provider.client_id.as_str())),
+        _ => false,
+    };
+    if !audience_ok {
+        return Err(invalid("wrong audience"));
+    }
+    let expires = claims.get("exp").and_then(serde_json::Value::as_i64);
+    if expires.map_or(true, |exp| exp <= Utc::now().timestamp()) {
+        return Err(invalid("expired"));
+    }
+    let token_nonce = claims
+        .get("nonce")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if !constant_time_eq(token_nonce.as_bytes(), nonce.as_bytes()) {
+        return Err(invalid("wrong nonce"));
+    }
+    Ok(claims)
+}
+
+/// Reads a GitHub identity from the user endpoint, with the primary email
+/// from the emails endpoint.
+async fn github_identity(
+    provider: &OidcProvider,
+    client: &dyn OAuthHttpClient,
+    access_token: &str,
+) -> DjangoResult<SocialIdentity> {
+    let user = client
+        .get_json(&provider.userinfo_endpoint, Some(access_token))
+        .await?;
+    let subject = match user.get("id") {
+        Some(serde_json::Value::Number(id)) => id.to_string(),
+        Some(serde_json::Value::String(id)) => id.clone(),
+        _ => {
+            return Err(DjangoError::SuspiciousOperation(format!(
+                "{} returned no user id",
+                provider.name
+            )))
+        }
+    };
+    let text = |key: &str| user.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    // The public profile email may be hidden or unverified; the emails
+    // endpoint says which address is primary and verified.
+    let emails_url = format!(
+        "{}/emails",
+        provider.userinfo_endpoint.trim_end_matches('/')
+    );
+    let primary = client
+        .get_json(&emails_url, Some(access_token))
+        .await
+        .ok()
+        .and_then(|emails| {
+            let entry = emails.as_array()?.iter().find(|entry| {
+                entry.get("primary").and_then(serde_json::Value::as_bool) == Some(true)
+            })?;
+            let email = entry.get("email")?.as_str()?.to_string();
+            let verified = entry.get("verified").and_then(serde_json::Value::as_bool);
+            Some((email, verified.unwrap_or(false)))
+        });
+    let (email, email_verified) = match primary {
+        Some((email, verified)) => (Some(email), verified),
+        None => (text("email"), false),
+    };
+    let (first_name, last_name) = split_name(&text("name").unwrap_or_default());
+
+    Ok(SocialIdentity {
+        provider: provider.name.clone(),
+        subject,
+        email,
+        email_verified,
+        username: text("login"),
+        first_name,
+        last_name,
+        claims: user,
+    })
+}
+
+/// Splits a display name into given and family names at the first space.
+fn split_name(name: &str) -> (String, String) {
+    match name.trim().split_once(' ') {
+        Some((first, last)) => (first.to_string(), last.trim().to_string()),
+        None => (name.trim().to_string(), String::new()),
+    }
+}
+
+/// Keeps the characters Django allows in usernames, up to 150 of them.
+fn sanitize_username(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || "@.+-_".contains(*c))
+        .take(150)
+        .collect()
+}
+
+/// Returns `true` for a path on this site, which is safe to redirect to.
+fn is_local_path(url: &str) -> bool {
+    url.starts_with('/') && !url.starts_with("//") && !url.starts_with("/\\")
+}
+
+fn pending_key(provider: &str) -> String {
+    format!("{SESSION_PENDING_PREFIX}{provider}")
+}
+
+/// Returns 32 random bytes, URL-safe base64 encoded.
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Configuration for the URL set returned by [`social_urls`].
+pub struct SocialUrls {
+    /// Where users go after logging in, unless they asked for a `next` page.
+    pub success_url: String,
+    /// The URL parameter naming the page to go to after logging in.
+    pub redirect_field_name: String,
+    /// The template named in the response when a login fails.
+    pub error_template: String,
+    /// The scheme and host of callback URLs (e.g. `https://example.com`),
+    /// for sites behind a proxy; by default they are built from the request.
+    pub redirect_base: Option<String>,
+    base: String,
+    backend: Arc<OidcBackend>,
+}
+
+impl std::fmt::Debug for SocialUrls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SocialUrls")
+            .field("success_url", &self.success_url)
+            .field("redirect_field_name", &self.redirect_field_name)
+            .field("error_template", &self.error_template)
+            .field("redirect_base", &self.redirect_base)
+            .field("base", &self.base)
+            .field("backend", &self.backend)
+            .finish()
+    }
+}
+
+impl SocialUrls {
+    /// Creates the default configuration for routes mounted at `mount_point`
+    /// (e.g. `"/accounts/social/"`).
+    pub fn new(mount_point: &str, backend: OidcBackend) -> Self {
+        Self {
+            success_url: "/".to_string(),
+            redirect_field_name: "next".to_string(),
+            error_template: "registration/social_login_error.html".to_string(),
+            redirect_base: None,
+            base: format!("/{}/", mount_point.trim_matches('/')).replace("//", "/"),
+            backend: Arc::new(backend),
+        }
+    }
+
+    /// Sets where users go after logging in.
+    #[must_use]
+    pub fn success_url(mut self, url: &str) -> Self {
+        self.success_url = url.to_string();
+        self
+    }
+
+    /// Sets the template named in failed login responses.
+    #[must_use]
+    pub fn error_template(mut self, template: &str) -> Self {
+        self.error_template = template.to_string();
+        self
+    }
+
+    /// Sets the scheme and host of callback URLs.
+    #[must_use]
+    pub fn redirect_base(mut self, base: &str) -> Self {
+        self.redirect_base = Some(base.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Returns the absolute callback URL of a provider.
+    fn callback_url(&self, request: &HttpRequest, provider: &str) -> String {
+        let path = format!("{}{provider}/callback/", self.base);
+        self.redirect_base.as_ref().map_or_else(
+            || request.build_absolute_uri(Some(&path)),
+            |base| format!("{base}{path}"),
+        )
+    }
+
+    /// The response for a failed login.
+    fn error_response(&self, error: &DjangoError) -> HttpResponse {
+        let body = serde_json::json!({
+            "error": error.to_string(),
+            "template": self.error_template,
+        })
+        .to_string();
+        match error {
+            DjangoError::NotFound(_) => HttpResponse::not_found(body),
+            DjangoError::PermissionDenied(_) => HttpResponse::forbidden(body),
+            DjangoError::BadRequest(_) | DjangoError::SuspiciousOperation(_) => {
+                HttpResponse::bad_request(body)
+            }
+            _ => HttpResponse::server_error(body),
+        }
+    }
+}
+
+/// Returns URL patterns for the social login and callback views.
+///
+/// `<provider>/login/` redirects to the provider, remembering the `next`
+/// parameter; `<provider>/callback/` completes the login and redirects to
+/// `next` or the success URL.
+///
+/// # Errors
+///
+/// Returns an error if a route fails to compile.
+pub fn social_urls(config: SocialUrls) -> DjangoResult<Vec<URLEntry>> {
+    let config = Arc::new(config);
+
+    let login = handler(&config, |mut req, c| async move {
+        let provider = provider_kwarg(&req);
+        let redirect_uri = c.callback_url(&req, &provider);
+        let next = req.get().get(&c.redirect_field_name).map(String::from);
+        match c
+            .backend
+            .authorization_url(&mut req, &provider, &redirect_uri, next.as_deref())
+        {
+            Ok(url) => HttpResponseRedirect::new(&url),
+            Err(e) => c.error_response(&e),
+        }
+    });
+    let callback = handler(&config, |mut req, c| async move {
+        let provider = provider_kwarg(&req);
+        let redirect_uri = c.callback_url(&req, &provider);
+        match c.backend.complete(&mut req, &provider, &redirect_uri).await {
+            Ok(login) => HttpResponseRedirect::new(login.next.as_deref().unwrap_or(&c.success_url)),
+            Err(e) => c.error_response(&e),
+        }
+    });
+
+    Ok(vec![
+        URLEntry::Pattern(path("<str:provider>/login/", login, Some("social_login"))?),
+        URLEntry::Pattern(path(
+            "<str:provider>/callback/",
+            callback,
+            Some("social_callback"),
+        )?),
+    ])
+}
+
+fn provider_kwarg(request: &HttpRequest) -> String {
+    request
+        .resolver_match()
+        .and_then(|m| m.kwargs.get("provider").cloned())
+        .unwrap_or_default()
+}
+
+/// Wraps an async view over the shared config into a [`RouteHandler`].
+fn handler<F, Fut>(config: &Arc<SocialUrls>, view: F) -> RouteHandler
+where
+    F: Fn(HttpRequest, Arc<SocialUrls>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = HttpResponse> + Send + 'static,
+{
+    let config = Arc::clone(config);
+    Arc::new(move |req| Box::pin(view(req, Arc::clone(&config))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::backends::ModelBackend;
+    use django_rs_http::urls::resolver::{include, root, URLResolver};
+
+    /// Answers each URL with a canned JSON document and records the forms
+    /// posted to it.
+    #[derive(Default)]
+    struct FakeClient {
+        responses: Mutex<HashMap<String, serde_json::Value>>,
+        forms: Mutex<Vec<HashMap<String, String>>>,
+    }
+
+    impl FakeClient {
+        fn respond(self, url: &str, body: serde_json::Value) -> Self {
+            self.responses.lock().unwrap().insert(url.to_string(), body);
+            self
+        }
+
+        fn response(&self, url: &str) -> DjangoResult<serde_json::Value> {
+            self.responses
+                .lock()
+                .unwrap()
+                .get(url)
+                .cloned()
+                .ok_or_else(|| DjangoError::InternalServerError(format!("no response for {url}")))
+        }
+    }
+
+    #[async_trait]
+    impl OAuthHttpClient for FakeClient {
+        async fn post_form(
+            &self,
+            url: &str,
+            form: &[(&str, &str)],
+        ) -> DjangoResult<serde_json::Value> {
+            self.forms.lock().unwrap().push(
+                form.iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect(),
+            );
+            self.response(url)
+        }
+
+        async fn get_json(
+            &self,
+            url: &str,
+            _bearer: Option<&str>,
+        ) -> DjangoResult<serde_json::Value> {
+            self.response(url)
+        }
+    }
+
+    const REDIRECT_URI: &str = "https://app.example.com/accounts/social/corp/callback/";
+
+    fn corp() -> OidcProvider {
+        OidcProvider::oidc("corp", "https://sso.example.com", "client-1", "secret").endpoints(
+            "https://sso.example.com/authorize",
+            "https://sso.example.com/token",
+            "",
+        )
+    }
+
+    fn id_token(claims: &serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"RS256"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+        format!("{header}.{payload}.signature")
+    }
+
+    fn token_response(nonce: &str, sub: &str, email: &str) -> serde_json::Value {
+        let claims = serde_json::json!({
+            "iss": "https://sso.example.com",
+            "aud": "client-1",
+            "exp": Utc::now().timestamp() + 300,
+            "nonce": nonce,
+            "sub": sub,
+            "email": email,
+            "email_verified": true,
+            "name": "Alice Liddell",
+        });
+        serde_json::json!({"access_token": "at", "id_token": id_token(&claims)})
+    }
+
+    fn query_param(url: &str, key: &str) -> String {
+        let query = url.split_once('?').unwrap().1;
+        QueryDict::parse(query).get(key).unwrap().to_string()
+    }
+
+    /// Starts a login and returns the provider URL and the session data.
+    fn start(backend: &OidcBackend, next: Option<&str>) -> (String, String) {
+        let mut request = HttpRequest::builder()
+            .path("/accounts/social/corp/login/")
+            .build();
+        let url = backend
+            .authorization_url(&mut request, "corp", REDIRECT_URI, next)
+            .unwrap();
+        (url, request.meta().get("SESSION_DATA").cloned().unwrap())
+    }
+
+    fn callback(session: &str, query: &str) -> HttpRequest {
+        HttpRequest::builder()
+            .path("/accounts/social/corp/callback/")
+            .query_string(query)
+            .meta("SESSION_DATA", session)
+            .build()
+    }
+
+    /// Runs a whole login of the identity `sub`, whose ID token carries
+    /// `email`.
+    async fn run_login(
+        users: Arc<ModelBackend>,
+        accounts: Arc<InMemorySocialAccountStore>,
+        policy: SocialUserPolicy,
+        sub: &str,
+        email: &str,
+    ) -> (DjangoResult<SocialLogin>, HttpRequest) {
+        let pending_backend = OidcBackend::new(users.clone()).provider(corp());
+        let (url, session) = start(&pending_backend, Some("/dashboard/"));
+        let nonce = query_param(&url, "nonce");
+        let client = FakeClient::default().respond(
+            "https://sso.example.com/token",
+            token_response(&nonce, sub, email),
+        );
+        let backend = OidcBackend::new(users)
+            .provider(corp())
+            .account_store(accounts)
+            .http_client(Arc::new(client))
+            .policy(policy);
+        let query = format!("state={}&code=c0de", query_param(&url, "state"));
+        let mut request = callback(&session, &query);
+        let result = backend.complete(&mut request, "corp", REDIRECT_URI).await;
+        (result, request)
+    }
+
+    #[test]
+    fn test_provider_from_settings() {
+        let google = OidcProvider::from_settings(
+            "google",
+            &SocialProviderSettings {
+                kind: "google".to_string(),
+                client_id: "id".to_string(),
+                scopes: vec!["openid".to_string()],
+                ..SocialProviderSettings::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(google.kind, ProviderKind::Google);
+        assert_eq!(google.token_endpoint, "https://oauth2.googleapis.com/token");
+        assert_eq!(google.scopes, vec!["openid"]);
+        assert!(!format!("{google:?}").contains("client_secret"));
+
+        let generic = OidcProvider::from_settings(
+            "corp",
+            &SocialProviderSettings {
+                kind: "oidc".to_string(),
+                issuer: "https://sso.example.com/".to_string(),
+                ..SocialProviderSettings::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(generic.issuer, "https://sso.example.com");
+        assert!(generic.needs_discovery());
+
+        for kind in ["oidc", "facebook"] {
+            let settings = SocialProviderSettings {
+                kind: kind.to_string(),
+                ..SocialProviderSettings::default()
+            };
+            assert!(matches!(
+                OidcProvider::from_settings("x", &settings),
+                Err(DjangoError::ImproperlyConfigured(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discover_fills_endpoints() {
+        let client = FakeClient::default().respond(
+            "https://sso.example.com/.well-known/openid-configuration",
+            serde_json::json!({
+                "authorization_endpoint": "https://sso.example.com/auth",
+                "token_endpoint": "https://sso.example.com/token",
+                "userinfo_endpoint": "https://sso.example.com/userinfo",
+            }),
+        );
+        let backend = OidcBackend::new(Arc::new(ModelBackend::new()))
+            .provider(OidcProvider::oidc(
+                "corp",
+                "https://sso.example.com",
+                "id",
+                "s",
+            ))
+            .http_client(Arc::new(client));
+        let mut request = HttpRequest::builder().build();
+        assert!(matches!(
+            backend.authorization_url(&mut request, "corp", REDIRECT_URI, None),
+            Err(DjangoError::ImproperlyConfigured(_))
+        ));
+
+        let backend = backend.discover().await.unwrap();
+        let provider = backend.get_provider("corp").unwrap();
+        assert_eq!(
+            provider.authorization_endpoint,
+            "https://sso.example.com/auth"
+        );
+        assert_eq!(
+            provider.userinfo_endpoint,
+            "https://sso.example.com/userinfo"
+        );
+    }
+
+    #[test]
+    fn test_authorization_url_uses_state_nonce_and_pkce() {
+        let backend = OidcBackend::new(Arc::new(ModelBackend::new())).provider(corp());
+        let (url, session) = start(&backend, Some("//evil.example.com/"));
+        assert!(url.starts_with("https://sso.example.com/authorize?"));
+        assert_eq!(query_param(&url, "client_id"), "client-1");
+        assert_eq!(query_param(&url, "redirect_uri"), REDIRECT_URI);
+        assert_eq!(query_param(&url, "scope"), "openid email profile");
+        assert_eq!(query_param(&url, "code_challenge_method"), "S256");
+
+        let session: serde_json::Value = serde_json::from_str(&session).unwrap();
+        let pending = &session["_social_auth_corp"];
+        assert_eq!(pending["state"], query_param(&url, "state"));
+        assert_eq!(pending["nonce"], query_param(&url, "nonce"));
+        assert_eq!(pending["next"], serde_json::Value::Null);
+        let verifier = pending["code_verifier"].as_str().unwrap();
+        assert_eq!(
+            query_param(&url, "code_challenge"),
+            URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+        );
+
+        let mut request = HttpRequest::builder().build();
+        assert!(matches!(
+            backend.authorization_url(&mut request, "nope", REDIRECT_URI, None),
+            Err(DjangoError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_complete_creates_links_and_logs_in_user() {
+        let users = Arc::new(ModelBackend::new());
+        users.add_user(AbstractUser::new("alice")).await;
+        let accounts = Arc::new(InMemorySocialAccountStore::new());
+
+        let (result, request) = run_login(
+            users.clone(),
+            accounts.clone(),
+            SocialUserPolicy::default(),
+            "u-1",
+            "alice@example.com",
+        )
+        .await;
+        let login = result.unwrap();
+        assert_eq!(login.user.username, "alice2");
+        assert_eq!(login.user.first_name, "Alice");
+        assert_eq!(login.user.last_name, "Liddell");
+        assert!(!login.user.base.has_usable_password());
+        assert_eq!(login.next.as_deref(), Some("/dashboard/"));
+        assert_eq!(
+            session_auth::get_user_id_from_meta(&request).as_deref(),
+            Some("alice2")
+        );
+        assert_eq!(
+            session_auth::get_backend_from_meta(&request).as_deref(),
+            Some(BACKEND_PATH)
+        );
+        let session = request.meta().get("SESSION_DATA").unwrap();
+        assert!(!session.contains("_social_auth_corp"));
+
+        // The next login of the same identity finds the linked user.
+        let (result, _) = run_login(
+            users.clone(),
+            accounts.clone(),
+            SocialUserPolicy::default(),
+            "u-1",
+            "changed@example.com",
+        )
+        .await;
+        assert_eq!(result.unwrap().user.username, "alice2");
+        assert_eq!(accounts.accounts_for_user("alice2").await.unwrap().len(), 1);
+        assert!(users.get_user("changed").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_complete_falls_back_to_provider_username() {
+        let users = Arc::new(ModelBackend::new());
+        users.add_user(AbstractUser::new("alice")).await;
+        for attempt in 2..=MAX_USERNAME_ATTEMPTS {
+            users
+                .add_user(AbstractUser::new(format!("alice{attempt}")))
+                .await;
+        }
+        let accounts = Arc::new(InMemorySocialAccountStore::new());
+
+        let (result, _) = run_login(
+            users.clone(),
+            accounts.clone(),
+            SocialUserPolicy::default(),
+            "u-8",
+            "alice@example.com",
+        )
+        .await;
+        assert_eq!(result.unwrap().user.username, "corp_u-8");
+
+        // Once the fallback is taken as well, no user is created.
+        users.add_user(AbstractUser::new("corp_u-9")).await;
+        let (result, _) = run_login(
+            users.clone(),
+            accounts.clone(),
+            SocialUserPolicy::default(),
+            "u-9",
+            "alice@example.com",
+        )
+        .await;
+        assert!(matches!(result, Err(DjangoError::InternalServerError(_))));
+        assert!(accounts
+            .accounts_for_user("corp_u-9")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_complete_applies_user_policy() {
+        let users = Arc::new(ModelBackend::new());
+        let mut bob = AbstractUser::new("bob");
+        bob.email = "bob@example.com".to_string();
+        users.add_user(bob).await;
+
+        let linking = SocialUserPolicy {
+            create_users: false,
+            link_verified_email: true,
+            ..SocialUserPolicy::default()
+        };
+        let (result, _) = run_login(
+            users.clone(),
+            Arc::new(InMemorySocialAccountStore::new()),
+            linking.clone(),
+            "u-2",
+            "bob@example.com",
+        )
+        .await;
+        assert_eq!(result.unwrap().user.username, "bob");
+
+        let (result, _) = run_login(
+            users.clone(),
+            Arc::new(InMemorySocialAccountStore::new()),
+            linking,
+            "u-3",
+            "carol@example.com",
+        )
+        .await;
+        assert!(matches!(result, Err(DjangoError::PermissionDenied(_))));
+
+        let domains = SocialUserPolicy {
+            allowed_email_domains: vec!["corp.example.com".to_string()],
+            ..SocialUserPolicy::default()
+        };
+        let (result, _) = run_login(
+            users,
+            Arc::new(InMemorySocialAccountStore::new()),
+            domains,
+            "u-4",
+            "dave@example.com",
+        )
+        .await;
+        assert!(matches!(result, Err(DjangoError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_complete_rejects_forged_callbacks() {
+        let users: Arc<dyn AuthBackend> = Arc::new(ModelBackend::new());
+        let pending_backend = OidcBackend::new(users.clone()).provider(corp());
+
+        // No login in progress.
+        let mut request = callback("{}", "state=x&code=c");
+        let result = pending_backend
+            .complete(&mut request, "corp", REDIRECT_URI)
+            .await;
+        assert!(matches!(result, Err(DjangoError::SuspiciousOperation(_))));
+
+        // Wrong state.
+        let (_, session) = start(&pending_backend, None);
+        let mut request = callback(&session, "state=forged&code=c");
+        let result = pending_backend
+            .complete(&mut request, "corp", REDIRECT_URI)
+            .await;
+        assert!(matches!(result, Err(DjangoError::SuspiciousOperation(_))));
+
+        // ID token issued for another login.
+        let (url, session) = start(&pending_backend, None);
+        let client = FakeClient::default().respond(
+            "https://sso.example.com/token",
+            token_response("other-nonce", "u-1", "a@example.com"),
+        );
+        let backend = OidcBackend::new(users.clone())
+            .provider(corp())
+            .http_client(Arc::new(client));
+        let query = format!("state={}&code=c", query_param(&url, "state"));
+        let mut request = callback(&session, &query);
+        let err = backend
+            .complete(&mut request, "corp", REDIRECT_URI)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("wrong nonce"), "{err}");
+
+        // No ID token although the openid scope was requested.
+        let (url, session) = start(&pending_backend, None);
+        let client = FakeClient::default()
+            .respond(
+                "https://sso.example.com/token",
+                serde_json::json!({"access_token": "at"}),
+            )
+            .respond(
+                "https://sso.example.com/userinfo",
+                serde_json::json!({"sub": "u-1", "email": "a@example.com"}),
+            );
+        let provider = corp().endpoints(
+            "https://sso.example.com/authorize",
+            "https://sso.example.com/token",
+            "https://sso.example.com/userinfo",
+        );
+        let backend = OidcBackend::new(users)
+            .provider(provider)
+            .http_client(Arc::new(client));
+        let query = format!("state={}&code=c", query_param(&url, "state"));
+        let mut request = callback(&session, &query);
+        let err = backend
+            .complete(&mut request, "corp", REDIRECT_URI)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DjangoError::SuspiciousOperation(_)));
+        assert!(err.to_string().contains("no ID token"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_github_login_uses_primary_email() {
+        let users = Arc::new(ModelBackend::new());
+        let pending_backend =
+            OidcBackend::new(users.clone()).provider(OidcProvider::github("gh-id", "gh-secret"));
+        let mut request = HttpRequest::builder().build();
+        let url = pending_backend
+            .authorization_url(&mut request, "github", REDIRECT_URI, None)
+            .unwrap();
+        assert!(url.starts_with("https://github.com/login/oauth/authorize?"));
+        assert!(!url.contains("nonce="));
+        let session = request.meta().get("SESSION_DATA").cloned().unwrap();
+
+        let client = Arc::new(
+            FakeClient::default()
+                .respond(
+                    "https://github.com/login/oauth/access_token",
+                    serde_json::json!({"access_token": "gho_1"}),
+                )
+                .respond(
+                    "https://api.github.com/user",
+                    serde_json::json!({"id": 42, "login": "octo cat", "name": "Mona Lisa", "email": null}),
+                )
+                .respond(
+                    "https://api.github.com/user/emails",
+                    serde_json::json!([
+                        {"email": "old@example.com", "primary": false, "verified": true},
+                        {"email": "mona@example.com", "primary": true, "verified": true},
+                    ]),
+                ),
+        );
+        let backend = OidcBackend::new(users)
+            .provider(OidcProvider::github("gh-id", "gh-secret"))
+            .http_client(client.clone());
+        let query = format!("state={}&code=c0de", query_param(&url, "state"));
+        let mut request = callback(&session, &query);
+        let login = backend
+            .complete(&mut request, "github", REDIRECT_URI)
+            .await
+            .unwrap();
+        assert_eq!(login.identity.subject, "42");
+        assert_eq!(login.identity.email.as_deref(), Some("mona@example.com"));
+        assert!(login.identity.email_verified);
+        assert_eq!(login.user.username, "octocat");
+
+        let forms = client.forms.lock().unwrap().clone();
+        assert_eq!(forms[0]["code"], "c0de");
+        assert_eq!(forms[0]["client_secret"], "gh-secret");
+        assert_eq!(forms[0]["code_verifier"].len(), 43);
+    }
+
+    fn resolver(urls: SocialUrls) -> URLResolver {
+        let social = include("accounts/social/", social_urls(urls).unwrap(), None, None).unwrap();
+        root(vec![URLEntry::Resolver(social)]).unwrap()
+    }
+
+    async fn dispatch(resolver: &URLResolver, mut request: HttpRequest) -> HttpResponse {
+        let path = request.path().trim_start_matches('/').to_string();
+        let resolver_match = resolver.resolve(&path).unwrap();
+        let func = resolver_match.func.clone();
+        request.set_resolver_match(resolver_match);
+        func(request).await
+    }
+
+    #[tokio::test]
+    async fn test_social_urls() {
+        let backend = OidcBackend::new(Arc::new(ModelBackend::new())).provider(corp());
+        let resolver = resolver(
+            SocialUrls::new("accounts/social", backend).redirect_base("https://app.example.com/"),
+        );
+        for (route, name) in [
+            ("accounts/social/corp/login/", "social_login"),
+            ("accounts/social/corp/callback/", "social_callback"),
+        ] {
+            let m = resolver.resolve(route).unwrap();
+            assert_eq!(m.url_name.as_deref(), Some(name), "{route}");
+        }
+
+        let request = HttpRequest::builder()
+            .path("/accounts/social/corp/login/")
+            .build();
+        let response = dispatch(&resolver, request).await;
+        let location = response
+            .headers()
+            .get("location")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(query_param(location, "redirect_uri"), REDIRECT_URI);
+
+        let request = HttpRequest::builder()
+            .path("/accounts/social/nope/login/")
+            .build();
+        let response = dispatch(&resolver, request).await;
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+
+        let request = HttpRequest::builder()
+            .path("/accounts/social/corp/callback/")
+            .query_string("state=x&code=y")
+            .build();
+        let response = dispatch(&resolver, request).await;
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response.content_bytes().unwrap()).unwrap();
+        assert_eq!(body["template"], "registration/social_login_error.html");
+    }
+
+    #[tokio::test]
+    async fn test_social_login_through_session_pipeline() {
+        use django_rs_views::middleware::{MiddlewarePipeline, ViewHandler};
+        use django_rs_views::session::{InMemorySessionBackend, SessionMiddleware};
+
+        let users = Arc::new(ModelBackend::new());
+        let client = Arc::new(FakeClient::default());
+        let backend = OidcBackend::new(users)
+            .provider(corp())
+            .http_client(client.clone());
+        let resolver = Arc::new(resolver(
+            SocialUrls::new("accounts/social", backend).redirect_base("https://app.example.com/"),
+        ));
+        let handler: ViewHandler = Box::new(move |request| {
+            let resolver = Arc::clone(&resolver);
+            Box::pin(async move {
+                if request.path() == "/whoami/" {
+                    let user = session_auth::get_user_id_from_meta(&request);
+                    return HttpResponse::ok(user.unwrap_or_default());
+                }
+                dispatch(&resolver, request).await
+            })
+        });
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(SessionMiddleware::new(InMemorySessionBackend::new()));
+        let session_cookie = |response: &HttpResponse| {
+            let cookie = response.headers()["set-cookie"].to_str().unwrap();
+            cookie.split(';').next().unwrap().to_string()
+        };
+
+        let request = HttpRequest::builder()
+            .path("/accounts/social/corp/login/")
+            .query_string("next=/dashboard/")
+            .build();
+        let response = pipeline.process(request, &handler).await;
+        let location = response.headers()["location"].to_str().unwrap().to_string();
+        let cookie = session_cookie(&response);
+
+        // The provider answers the token request for this login's nonce.
+        client.responses.lock().unwrap().insert(
+            "https://sso.example.com/token".to_string(),
+            token_response(&query_param(&location, "nonce"), "u-1", "a@example.com"),
+        );
+        let request = HttpRequest::builder()
+            .path("/accounts/social/corp/callback/")
+            .query_string(&format!("state={}&code=c", query_param(&location, "state")))
+            .header("cookie", &cookie)
+            .build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+        assert_eq!(response.headers()["location"], "/dashboard/");
+        let cookie = session_cookie(&response);
+
+        let request = HttpRequest::builder()
+            .path("/whoami/")
+            .header("cookie", &cookie)
+            .build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.content_bytes().unwrap(), b"a".as_slice());
+    }
+}
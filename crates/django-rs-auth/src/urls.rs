@@ -0,0 +1,401 @@
+//! Ready-made URL patterns for the authentication views.
+//!
+//! [`auth_urls`] returns the same routes as Django's `django.contrib.auth.urls`,
+//! meant to be mounted with `include()`:
+//!
+//! | Route | Name |
+//! |-------|------|
+//! | `login/` | `login` |
+//! | `logout/` | `logout` |
+//! | `password_change/` | `password_change` |
+//! | `password_change/done/` | `password_change_done` |
+//! | `password_reset/` | `password_reset` |
+//! | `password_reset/done/` | `password_reset_done` |
+//! | `reset/<uidb64>/<token>/` | `password_reset_confirm` |
+//! | `reset/done/` | `password_reset_complete` |
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_auth::backends::ModelBackend;
+//! use django_rs_auth::urls::{auth_urls, AuthUrls};
+//! use django_rs_http::urls::resolver::{include, root, URLEntry};
+//!
+//! let urls = AuthUrls::new("/accounts/").backend(Box::new(ModelBackend::new()));
+//! let accounts = include("accounts/", auth_urls(urls).unwrap(), None, None).unwrap();
+//! let resolver = root(vec![URLEntry::Resolver(accounts)]).unwrap();
+//! assert!(resolver.resolve("accounts/login/").is_ok());
+//! ```
+
+use std::sync::Arc;
+
+use django_rs_core::DjangoResult;
+use django_rs_http::urls::pattern::{path, RouteHandler};
+use django_rs_http::urls::resolver::URLEntry;
+use django_rs_http::{HttpRequest, HttpResponse};
+
+use crate::backends::AuthBackend;
+use crate::views::{
+    login_view, logout_view, password_change_view, password_reset_confirm_view,
+    password_reset_view, LoginConfig, LogoutConfig, PasswordChangeConfig, PasswordResetConfig,
+    PasswordResetConfirmConfig,
+};
+
+/// Configuration for the URL set returned by [`auth_urls`].
+///
+/// Each view keeps its own config type; [`AuthUrls::new`] points the
+/// post-action redirects at the routes under the given mount point, so the
+/// flow works as soon as it is included there.
+pub struct AuthUrls {
+    /// Configuration for `login/`.
+    pub login: LoginConfig,
+    /// Configuration for `logout/`.
+    pub logout: LogoutConfig,
+    /// Configuration for `password_change/`.
+    pub password_change: PasswordChangeConfig,
+    /// Configuration for `password_reset/`.
+    pub password_reset: PasswordResetConfig,
+    /// Configuration for `reset/<uidb64>/<token>/`.
+    pub password_reset_confirm: PasswordResetConfirmConfig,
+    /// Template for `password_change/done/`.
+    pub password_change_done_template: String,
+    /// Template for `password_reset/done/`.
+    pub password_reset_done_template: String,
+    /// Template for `reset/done/`.
+    pub password_reset_complete_template: String,
+    /// Backends used to authenticate and load users.
+    backends: Vec<Box<dyn AuthBackend>>,
+}
+
+impl std::fmt::Debug for AuthUrls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthUrls")
+            .field("login", &self.login)
+            .field("logout", &self.logout)
+            .field("password_change", &self.password_change)
+            .field("password_reset", &self.password_reset)
+            .field("password_reset_confirm", &self.password_reset_confirm)
+            .field("backends", &self.backends.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuthUrls {
+    /// Creates the default configuration for routes mounted at `mount_point`
+    /// (e.g. `"/accounts/"`).
+    pub fn new(mount_point: &str) -> Self {
+        let base = format!("/{}/", mount_point.trim_matches('/')).replace("//", "/");
+        Self {
+            login: LoginConfig::default(),
+            logout: LogoutConfig::default(),
+            password_change: PasswordChangeConfig {
+                success_url: format!("{base}password_change/done/"),
+                ..PasswordChangeConfig::default()
+            },
+            password_reset: PasswordResetConfig {
+                success_url: format!("{base}password_reset/done/"),
+                ..PasswordResetConfig::default()
+            },
+            password_reset_confirm: PasswordResetConfirmConfig {
+                success_url: format!("{base}reset/done/"),
+                ..PasswordResetConfirmConfig::default()
+            },
+            password_change_done_template: "registration/password_change_done.html".to_string(),
+            password_reset_done_template: "registration/password_reset_done.html".to_string(),
+            password_reset_complete_template: "registration/password_reset_complete.html"
+                .to_string(),
+            backends: Vec::new(),
+        }
+    }
+
+    /// Adds an authentication backend.
+    #[must_use]
+    pub fn backend(mut self, backend: Box<dyn AuthBackend>) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
+    /// Sets the login view configuration.
+    #[must_use]
+    pub fn login(mut self, config: LoginConfig) -> Self {
+        self.login = config;
+        self
+    }
+
+    /// Sets the logout view configuration.
+    #[must_use]
+    pub fn logout(mut self, config: LogoutConfig) -> Self {
+        self.logout = config;
+        self
+    }
+
+    /// Sets the password change view configuration.
+    #[must_use]
+    pub fn password_change(mut self, config: PasswordChangeConfig) -> Self {
+        self.password_change = config;
+        self
+    }
+
+    /// Sets the password reset view configuration.
+    #[must_use]
+    pub fn password_reset(mut self, config: PasswordResetConfig) -> Self {
+        self.password_reset = config;
+        self
+    }
+
+    /// Sets the password reset confirmation view configuration.
+    #[must_use]
+    pub fn password_reset_confirm(mut self, config: PasswordResetConfirmConfig) -> Self {
+        self.password_reset_confirm = config;
+        self
+    }
+}
+
+/// Returns URL patterns for the login, logout, password change, and
+/// password reset views.
+///
+/// This mirrors Django's `django.contrib.auth.urls`.
+///
+/// # Errors
+///
+/// Returns an error if a route fails to compile.
+pub fn auth_urls(config: AuthUrls) -> DjangoResult<Vec<URLEntry>> {
+    let config = Arc::new(config);
+
+    let login = handler(&config, |req, c| async move {
+        login_view(req, &c.login, &c.backends).await
+    });
+    let logout = handler(&config, |req, c| async move {
+        logout_view(req, &c.logout).await
+    });
+    let password_change = handler(&config, |req, c| async move {
+        match c.backends.first() {
+            Some(backend) => password_change_view(req, &c.password_change, backend.as_ref()).await,
+            None => HttpResponse::server_error("No authentication backend configured."),
+        }
+    });
+    let password_change_done = handler(&config, |_req, c| async move {
+        template_response(&c.password_change_done_template)
+    });
+    let password_reset = handler(&config, |req, c| async move {
+        password_reset_view(req, &c.password_reset, &c.backends).await
+    });
+    let password_reset_done = handler(&config, |_req, c| async move {
+        template_response(&c.password_reset_done_template)
+    });
+    let password_reset_confirm = handler(&config, |req, c| async move {
+        let kwarg = |name: &str| {
+            req.resolver_match()
+                .and_then(|m| m.kwargs.get(name).cloned())
+                .unwrap_or_default()
+        };
+        let (uidb64, token) = (kwarg("uidb64"), kwarg("token"));
+        password_reset_confirm_view(
+            req,
+            &uidb64,
+            &token,
+            &c.password_reset_confirm,
+            c.password_reset.token_generator.as_ref(),
+            &c.backends,
+        )
+        .await
+    });
+    let password_reset_complete = handler(&config, |_req, c| async move {
+        template_response(&c.password_reset_complete_template)
+    });
+
+    Ok(vec![
+        URLEntry::Pattern(path("login/", login, Some("login"))?),
+        URLEntry::Pattern(path("logout/", logout, Some("logout"))?),
+        URLEntry::Pattern(path(
+            "password_change/",
+            password_change,
+            Some("password_change"),
+        )?),
+        URLEntry::Pattern(path(
+            "password_change/done/",
+            password_change_done,
+            Some("password_change_done"),
+        )?),
+        URLEntry::Pattern(path(
+            "password_reset/",
+            password_reset,
+            Some("password_reset"),
+        )?),
+        URLEntry::Pattern(path(
+            "password_reset/done/",
+            password_reset_done,
+            Some("password_reset_done"),
+        )?),
+        URLEntry::Pattern(path(
+            "reset/<str:uidb64>/<str:token>/",
+            password_reset_confirm,
+            Some("password_reset_confirm"),
+        )?),
+        URLEntry::Pattern(path(
+            "reset/done/",
+            password_reset_complete,
+            Some("password_reset_complete"),
+        )?),
+    ])
+}
+
+/// Wraps an async view over the shared config into a [`RouteHandler`].
+fn handler<F, Fut>(config: &Arc<AuthUrls>, view: F) -> RouteHandler
+where
+    F: Fn(HttpRequest, Arc<AuthUrls>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = HttpResponse> + Send + 'static,
+{
+    let config = Arc::clone(config);
+    Arc::new(move |req| Box::pin(view(req, Arc::clone(&config))))
+}
+
+/// Response for the static "done" pages, which only name their template.
+fn template_response(template_name: &str) -> HttpResponse {
+    let body = serde_json::json!({ "template": template_name });
+    HttpResponse::ok(body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ModelBackend;
+    use crate::user::AbstractUser;
+    use crate::views::{encode_uid, DefaultTokenGenerator, TokenGenerator};
+    use django_rs_http::urls::resolver::{include, root, URLResolver};
+
+    fn resolver(urls: AuthUrls) -> URLResolver {
+        let accounts = include("accounts/", auth_urls(urls).unwrap(), None, None).unwrap();
+        root(vec![URLEntry::Resolver(accounts)]).unwrap()
+    }
+
+    async fn dispatch(resolver: &URLResolver, mut request: HttpRequest) -> HttpResponse {
+        let path = request.path().trim_start_matches('/').to_string();
+        let resolver_match = resolver.resolve(&path).unwrap();
+        let func = resolver_match.func.clone();
+        request.set_resolver_match(resolver_match);
+        func(request).await
+    }
+
+    fn body_json(response: &HttpResponse) -> serde_json::Value {
+        serde_json::from_slice(&response.content_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_auth_urls_names() {
+        let resolver = resolver(AuthUrls::new("/accounts/"));
+        for (route, name) in [
+            ("accounts/login/", "login"),
+            ("accounts/logout/", "logout"),
+            ("accounts/password_change/", "password_change"),
+            ("accounts/password_change/done/", "password_change_done"),
+            ("accounts/password_reset/", "password_reset"),
+            ("accounts/password_reset/done/", "password_reset_done"),
+            ("accounts/reset/abc/1-2/", "password_reset_confirm"),
+            ("accounts/reset/done/", "password_reset_complete"),
+        ] {
+            let m = resolver.resolve(route).unwrap();
+            assert_eq!(m.url_name.as_deref(), Some(name), "{route}");
+        }
+    }
+
+    #[test]
+    fn test_auth_urls_success_urls_follow_mount_point() {
+        let urls = AuthUrls::new("accounts");
+        assert_eq!(
+            urls.password_change.success_url,
+            "/accounts/password_change/done/"
+        );
+        assert_eq!(
+            urls.password_reset.success_url,
+            "/accounts/password_reset/done/"
+        );
+        assert_eq!(
+            urls.password_reset_confirm.success_url,
+            "/accounts/reset/done/"
+        );
+
+        let root_urls = AuthUrls::new("/");
+        assert_eq!(root_urls.password_reset_confirm.success_url, "/reset/done/");
+    }
+
+    #[tokio::test]
+    async fn test_login_page_uses_configured_template() {
+        let urls = AuthUrls::new("/accounts/").login(LoginConfig {
+            template_name: "custom/login.html".to_string(),
+            ..LoginConfig::default()
+        });
+        let resolver = resolver(urls);
+        let request = HttpRequest::builder().path("/accounts/login/").build();
+        let response = dispatch(&resolver, request).await;
+        assert_eq!(body_json(&response)["template"], "custom/login.html");
+    }
+
+    #[tokio::test]
+    async fn test_done_pages_render_templates() {
+        let resolver = resolver(AuthUrls::new("/accounts/"));
+        let request = HttpRequest::builder().path("/accounts/reset/done/").build();
+        let response = dispatch(&resolver, request).await;
+        assert_eq!(
+            body_json(&response)["template"],
+            "registration/password_reset_complete.html"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_redirects_to_done() {
+        let resolver = resolver(AuthUrls::new("/accounts/"));
+        let request = HttpRequest::builder()
+            .method(http::Method::POST)
+            .path("/accounts/password_reset/")
+            .content_type("application/x-www-form-urlencoded")
+            .body(b"email=alice%40example.com".to_vec())
+            .build();
+        let response = dispatch(&resolver, request).await;
+        assert_eq!(
+            response.headers().get("location").unwrap(),
+            "/accounts/password_reset/done/"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_confirm_link_validity() {
+        let backend = ModelBackend::new();
+        let user = AbstractUser::new("alice");
+        backend.add_user(user.clone()).await;
+
+        let generator = DefaultTokenGenerator::new("secret");
+        let token = generator.make_token(&user);
+        let urls = AuthUrls::new("/accounts/")
+            .password_reset(PasswordResetConfig {
+                token_generator: Box::new(generator),
+                ..PasswordResetConfig::default()
+            })
+            .backend(Box::new(backend));
+        let resolver = resolver(urls);
+
+        let valid = HttpRequest::builder()
+            .path(&format!("/accounts/reset/{}/{token}/", encode_uid("alice")))
+            .build();
+        let response = dispatch(&resolver, valid).await;
+        assert_eq!(body_json(&response)["validlink"], true);
+
+        let invalid = HttpRequest::builder()
+            .path(&format!("/accounts/reset/{}/{token}/", encode_uid("bob")))
+            .build();
+        let response = dispatch(&resolver, invalid).await;
+        assert_eq!(body_json(&response)["validlink"], false);
+
+        let set_password = HttpRequest::builder()
+            .method(http::Method::POST)
+            .path(&format!("/accounts/reset/{}/{token}/", encode_uid("alice")))
+            .content_type("application/x-www-form-urlencoded")
+            .body(b"new_password1=s3cret-Pass&new_password2=s3cret-Pass".to_vec())
+            .build();
+        let response = dispatch(&resolver, set_password).await;
+        assert_eq!(
+            response.headers().get("location").unwrap(),
+            "/accounts/reset/done/"
+        );
+    }
+}
@@ -13,7 +13,32 @@
 
 use chrono::{DateTime, Utc};
 use django_rs_core::error::DjangoError;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Key salt for the session auth hash, matching Django's so hashes are
+/// interchangeable with sessions created by Django for the same secret.
+const SESSION_AUTH_HASH_SALT: &str =
+    "django.contrib.auth.models.AbstractBaseUser.get_session_auth_hash";
+
+/// Computes the session auth hash for a password hash.
+///
+/// This is Django's `salted_hmac`: HMAC-SHA256 over the password hash, keyed
+/// with `SHA256(salt + secret_key)`, hex-encoded.
+pub(crate) fn session_auth_hash(password_hash: &str, secret_key: &str) -> String {
+    use std::fmt::Write;
+    let key = Sha256::digest(format!("{SESSION_AUTH_HASH_SALT}{secret_key}").as_bytes());
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC can take key of any size");
+    mac.update(password_hash.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::with_capacity(64), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
 
 /// Base user model with password and login tracking.
 ///
@@ -81,6 +106,15 @@ impl AbstractBaseUser {
     pub fn has_usable_password(&self) -> bool {
         crate::hashers::is_password_usable(&self.password)
     }
+
+    /// Returns an HMAC of the password hash, stored in the session at login.
+    ///
+    /// Changing the password changes this value, so sessions created before
+    /// the change no longer verify. Mirrors Django's
+    /// `AbstractBaseUser.get_session_auth_hash()`.
+    pub fn get_session_auth_hash(&self, secret_key: &str) -> String {
+        session_auth_hash(&self.password, secret_key)
+    }
 }
 
 /// Full-featured user model with identity fields, groups, and permissions.
@@ -250,6 +284,20 @@ mod tests {
         assert!(user.is_active);
     }
 
+    #[test]
+    fn test_get_session_auth_hash() {
+        let mut user = AbstractBaseUser::new();
+        user.password = "pbkdf2_sha256$600000$salt$hash".to_string();
+        let hash = user.get_session_auth_hash("secret");
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(hash, user.get_session_auth_hash("secret"));
+        assert_ne!(hash, user.get_session_auth_hash("other-secret"));
+
+        user.password = "pbkdf2_sha256$600000$salt$changed".to_string();
+        assert_ne!(hash, user.get_session_auth_hash("secret"));
+    }
+
     #[tokio::test]
     async fn test_base_user_set_password() {
         let mut user = AbstractBaseUser::new();
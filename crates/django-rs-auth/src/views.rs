@@ -2,7 +2,8 @@
 //!
 //! This module provides configuration structs for authentication-related views
 //! (login, logout, password change, password reset) and a token generation
-//! system for password reset flows.
+//! system for password reset flows. The views are mounted as a ready-made URL
+//! set by [`auth_urls`](crate::urls::auth_urls).
 //!
 //! ## Token Generation
 //!
@@ -11,10 +12,15 @@
 //! constant-time comparison to prevent timing attacks.
 
 use async_trait::async_trait;
+use base64::Engine;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use std::sync::Arc;
 
+use django_rs_core::DjangoError;
+use django_rs_forms::fields::FormFieldDef;
 use django_rs_http::{HttpRequest, HttpResponse, HttpResponseRedirect};
+use django_rs_template::{Context, ContextValue, Engine as TemplateEngine};
 
 use crate::backends::{AuthBackend, Credentials};
 use crate::forms::AuthenticationForm;
@@ -93,6 +99,14 @@ pub struct PasswordResetConfig {
     pub success_url: String,
     /// The token generator used for creating and verifying reset tokens.
     pub token_generator: Box<dyn TokenGenerator>,
+    /// The engine that renders the email subject and body templates.
+    pub template_engine: Arc<TemplateEngine>,
+    /// Delivers the rendered email. When `None`, no email is sent.
+    pub mailer: Option<Arc<dyn PasswordResetMailer>>,
+    /// The sender address, or `None` for the mailer's default.
+    pub from_email: Option<String>,
+    /// The domain used in the reset link instead of the request's host.
+    pub domain_override: Option<String>,
 }
 
 impl std::fmt::Debug for PasswordResetConfig {
@@ -103,7 +117,13 @@ impl std::fmt::Debug for PasswordResetConfig {
             .field("subject_template_name", &self.subject_template_name)
             .field("success_url", &self.success_url)
             .field("token_generator", &"<dyn TokenGenerator>")
-            .finish()
+            .field(
+                "mailer",
+                &self.mailer.as_ref().map(|_| "<dyn PasswordResetMailer>"),
+            )
+            .field("from_email", &self.from_email)
+            .field("domain_override", &self.domain_override)
+            .finish_non_exhaustive()
     }
 }
 
@@ -115,6 +135,45 @@ impl Default for PasswordResetConfig {
             subject_template_name: "registration/password_reset_subject.txt".to_string(),
             success_url: "/password_reset/done/".to_string(),
             token_generator: Box::new(DefaultTokenGenerator::new("default-secret-key")),
+            template_engine: Arc::new(TemplateEngine::new()),
+            mailer: None,
+            from_email: None,
+            domain_override: None,
+        }
+    }
+}
+
+/// Delivers password reset emails.
+///
+/// The auth crate does not own an email transport, so applications plug in
+/// their own, typically a thin wrapper around an email backend.
+#[async_trait]
+pub trait PasswordResetMailer: Send + Sync {
+    /// Sends one rendered email to a single recipient.
+    async fn send_mail(
+        &self,
+        subject: &str,
+        body: &str,
+        from_email: Option<&str>,
+        to: &str,
+    ) -> Result<(), DjangoError>;
+}
+
+/// Configuration for the password reset confirmation view, which sets a new
+/// password from the emailed link.
+#[derive(Debug, Clone)]
+pub struct PasswordResetConfirmConfig {
+    /// The template to render for the set-password form.
+    pub template_name: String,
+    /// The URL to redirect to after the password is reset.
+    pub success_url: String,
+}
+
+impl Default for PasswordResetConfirmConfig {
+    fn default() -> Self {
+        Self {
+            template_name: "registration/password_reset_confirm.html".to_string(),
+            success_url: "/reset/done/".to_string(),
         }
     }
 }
@@ -229,6 +288,23 @@ impl TokenGenerator for DefaultTokenGenerator {
     }
 }
 
+/// Encodes a user identifier for use in a password reset URL.
+///
+/// This mirrors Django's `urlsafe_base64_encode(force_bytes(user.pk))`.
+pub fn encode_uid(uid: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(uid)
+}
+
+/// Decodes a user identifier produced by [`encode_uid`].
+///
+/// Returns `None` if the value is not valid base64 or not UTF-8.
+pub fn decode_uid(uidb64: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(uidb64)
+        .ok()?;
+    String::from_utf8(bytes).ok()
+}
+
 /// Constant-time byte comparison.
 fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
@@ -278,17 +354,7 @@ pub async fn login_view(
     if *request.method() == http::Method::GET {
         // Return a form schema as JSON for rendering
         let form = AuthenticationForm::new();
-        let fields: Vec<serde_json::Value> = form
-            .field_defs()
-            .iter()
-            .map(|f| {
-                serde_json::json!({
-                    "name": f.name,
-                    "label": f.label,
-                    "required": f.required,
-                })
-            })
-            .collect();
+        let fields = form_fields_json(form.field_defs());
 
         let body = serde_json::json!({
             "form": {
@@ -384,11 +450,12 @@ pub async fn logout_view(mut request: HttpRequest, config: &LogoutConfig) -> Htt
 /// Password change view: authenticated users change their password.
 ///
 /// Requires the user to be authenticated. On GET, returns the form schema.
-/// On POST, validates old password, then sets the new password.
+/// On POST, validates old password, then sets and saves the new password
+/// and updates the session auth hash so the current session stays valid.
 ///
 /// This mirrors Django's `PasswordChangeView`.
 pub async fn password_change_view(
-    request: HttpRequest,
+    mut request: HttpRequest,
     config: &PasswordChangeConfig,
     backend: &dyn AuthBackend,
 ) -> HttpResponse {
@@ -399,17 +466,7 @@ pub async fn password_change_view(
 
     if *request.method() == http::Method::GET {
         let form = crate::forms::PasswordChangeForm::new();
-        let fields: Vec<serde_json::Value> = form
-            .field_defs()
-            .iter()
-            .map(|f| {
-                serde_json::json!({
-                    "name": f.name,
-                    "label": f.label,
-                    "required": f.required,
-                })
-            })
-            .collect();
+        let fields = form_fields_json(form.field_defs());
         let body = serde_json::json!({
             "form": { "fields": fields },
             "template": config.template_name,
@@ -451,14 +508,224 @@ pub async fn password_change_view(
             }
         }
 
-        // Password change is validated; in a full implementation we would
-        // save the new password to the database. Return success redirect.
+        let mut user = user;
+        let new_password = form.get_new_password().unwrap_or_default();
+        if user.set_password(&new_password).await.is_err()
+            || backend.save_user(&user).await.is_err()
+        {
+            return HttpResponse::server_error("Error saving password.");
+        }
+
+        // Keep this session logged in; every other session of the user
+        // fails hash verification from now on.
+        session_auth::update_session_auth_hash(&mut request, &user);
+        HttpResponseRedirect::new(&config.success_url)
+    } else {
+        HttpResponse::not_allowed(&["GET", "POST"])
+    }
+}
+
+/// Password reset view: asks for an email address to send a reset link to.
+///
+/// On GET, returns the form schema. On POST, validates the address, emails a
+/// reset link to every active user with that address and a usable password,
+/// and redirects to the success URL. Like Django, it redirects whether or not
+/// the address belongs to an account, so it can't be used to discover users.
+///
+/// The subject and body are rendered from `subject_template_name` and
+/// `email_template_name` with `email`, `domain`, `site_name`, `protocol`,
+/// `uid`, `token` and `user` in the context, then handed to the configured
+/// [`PasswordResetMailer`]. Delivery failures are logged, not reported.
+///
+/// This mirrors Django's `PasswordResetView`.
+pub async fn password_reset_view(
+    request: HttpRequest,
+    config: &PasswordResetConfig,
+    backends: &[Box<dyn AuthBackend>],
+) -> HttpResponse {
+    if *request.method() == http::Method::GET {
+        let form = crate::forms::PasswordResetForm::new();
+        let body = serde_json::json!({
+            "form": { "fields": form_fields_json(form.field_defs()) },
+            "template": config.template_name,
+        });
+        return HttpResponse::ok(body.to_string());
+    }
+
+    if *request.method() == http::Method::POST {
+        let mut form = crate::forms::PasswordResetForm::new();
+        form.bind(request.post());
+
+        if !form.is_valid().await {
+            let body = serde_json::json!({
+                "errors": form.errors(),
+                "template": config.template_name,
+            });
+            return HttpResponse::bad_request(body.to_string());
+        }
+
+        if let (Some(mailer), Some(email)) = (&config.mailer, form.get_email()) {
+            for user in reset_users(&email, backends).await {
+                if let Err(e) = send_reset_email(&request, config, mailer.as_ref(), &user).await {
+                    tracing::error!(user = %user.username, error = %e, "Failed to send password reset email");
+                }
+            }
+        }
+
+        HttpResponseRedirect::new(&config.success_url)
+    } else {
+        HttpResponse::not_allowed(&["GET", "POST"])
+    }
+}
+
+/// Finds the accounts a reset email should go to: active users with a usable
+/// password whose address matches, at most one per backend.
+async fn reset_users(email: &str, backends: &[Box<dyn AuthBackend>]) -> Vec<AbstractUser> {
+    let mut users = Vec::new();
+    for backend in backends {
+        if let Ok(Some(user)) = backend.get_user_by_email(email).await {
+            if user.base.is_active
+                && user.base.has_usable_password()
+                && !users
+                    .iter()
+                    .any(|u: &AbstractUser| u.username == user.username)
+            {
+                users.push(user);
+            }
+        }
+    }
+    users
+}
+
+/// Renders the reset email for `user` and sends it through `mailer`.
+async fn send_reset_email(
+    request: &HttpRequest,
+    config: &PasswordResetConfig,
+    mailer: &dyn PasswordResetMailer,
+    user: &AbstractUser,
+) -> Result<(), DjangoError> {
+    let domain = config
+        .domain_override
+        .clone()
+        .unwrap_or_else(|| request.get_host().to_string());
+    let mut user_value = std::collections::HashMap::new();
+    user_value.insert(
+        "username".to_string(),
+        ContextValue::from(user.username.as_str()),
+    );
+    user_value.insert("email".to_string(), ContextValue::from(user.email.as_str()));
+
+    let mut context = Context::new();
+    context.set("email", ContextValue::from(user.email.as_str()));
+    context.set("domain", ContextValue::from(domain.as_str()));
+    context.set("site_name", ContextValue::from(domain.as_str()));
+    context.set("protocol", ContextValue::from(request.scheme()));
+    context.set("uid", ContextValue::from(encode_uid(&user.username)));
+    context.set(
+        "token",
+        ContextValue::from(config.token_generator.make_token(user)),
+    );
+    context.set("user", ContextValue::Dict(user_value));
+
+    let engine = &config.template_engine;
+    let subject = engine.render_to_string(&config.subject_template_name, &mut context)?;
+    // Email subjects must not contain newlines.
+    let subject = subject.lines().collect::<String>();
+    let body = engine.render_to_string(&config.email_template_name, &mut context)?;
+    mailer
+        .send_mail(&subject, &body, config.from_email.as_deref(), &user.email)
+        .await
+}
+
+/// Password reset confirmation view: sets a new password from a reset link.
+///
+/// The link carries the user identifier encoded with [`encode_uid`] and a
+/// token from the [`TokenGenerator`]. When the link is invalid or expired the
+/// response reports `"validlink": false` instead of showing the form.
+///
+/// This mirrors Django's `PasswordResetConfirmView`.
+pub async fn password_reset_confirm_view(
+    request: HttpRequest,
+    uidb64: &str,
+    token: &str,
+    config: &PasswordResetConfirmConfig,
+    token_generator: &dyn TokenGenerator,
+    backends: &[Box<dyn AuthBackend>],
+) -> HttpResponse {
+    let mut found_user = None;
+    if let Some(uid) = decode_uid(uidb64) {
+        for backend in backends {
+            if let Ok(Some(found)) = backend.get_user(&uid).await {
+                found_user = Some((found, backend));
+                break;
+            }
+        }
+    }
+    let found_user = found_user.filter(|(user, _)| token_generator.check_token(user, token));
+    let valid_link = found_user.is_some();
+
+    if *request.method() == http::Method::GET {
+        let body = if valid_link {
+            let form = crate::forms::SetPasswordForm::new();
+            serde_json::json!({
+                "form": { "fields": form_fields_json(form.field_defs()) },
+                "template": config.template_name,
+                "validlink": true,
+            })
+        } else {
+            serde_json::json!({
+                "template": config.template_name,
+                "validlink": false,
+            })
+        };
+        return HttpResponse::ok(body.to_string());
+    }
+
+    if *request.method() == http::Method::POST {
+        let Some((mut user, backend)) = found_user else {
+            let body = serde_json::json!({
+                "template": config.template_name,
+                "validlink": false,
+            });
+            return HttpResponse::bad_request(body.to_string());
+        };
+
+        let mut form = crate::forms::SetPasswordForm::new();
+        form.bind(request.post());
+
+        if !form.is_valid().await {
+            let body = serde_json::json!({ "errors": form.errors() });
+            return HttpResponse::bad_request(body.to_string());
+        }
+
+        // The token signs the password hash, so saving the new password
+        // also makes the link unusable.
+        let new_password = form.get_new_password().unwrap_or_default();
+        if user.set_password(&new_password).await.is_err()
+            || backend.save_user(&user).await.is_err()
+        {
+            return HttpResponse::server_error("Error saving password.");
+        }
         HttpResponseRedirect::new(&config.success_url)
     } else {
         HttpResponse::not_allowed(&["GET", "POST"])
     }
 }
 
+/// Serializes form field definitions into the schema sent to the frontend.
+fn form_fields_json(field_defs: &[FormFieldDef]) -> Vec<serde_json::Value> {
+    field_defs
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "name": f.name,
+                "label": f.label,
+                "required": f.required,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -509,6 +776,30 @@ mod tests {
         assert!(config.subject_template_name.contains("subject"));
     }
 
+    #[test]
+    fn test_password_reset_confirm_config_default() {
+        let config = PasswordResetConfirmConfig::default();
+        assert_eq!(
+            config.template_name,
+            "registration/password_reset_confirm.html"
+        );
+        assert_eq!(config.success_url, "/reset/done/");
+    }
+
+    // ── uid encoding tests ──────────────────────────────────────────
+
+    #[test]
+    fn test_encode_decode_uid_roundtrip() {
+        let encoded = encode_uid("alice@example.com");
+        assert!(!encoded.contains('/') && !encoded.contains('='));
+        assert_eq!(decode_uid(&encoded).as_deref(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn test_decode_uid_invalid() {
+        assert!(decode_uid("!!not base64!!").is_none());
+    }
+
     // ── DefaultTokenGenerator tests ─────────────────────────────────
 
     #[test]
@@ -882,4 +1173,160 @@ mod tests {
         assert!(body.contains("old_password"));
         assert!(body.contains("new_password1"));
     }
+
+    #[tokio::test]
+    async fn test_password_change_view_keeps_current_session() {
+        let backend = crate::ModelBackend::new();
+        let mut user = AbstractUser::new("alice");
+        user.set_password("oldpass123").await.unwrap();
+        backend.add_user(user.clone()).await;
+        let config = PasswordChangeConfig::default();
+
+        let mut other_session = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
+        session_auth::login_to_session(&mut other_session, &user);
+        let mut request = HttpRequest::builder()
+            .method(http::Method::POST)
+            .path("/accounts/password_change/")
+            .content_type("application/x-www-form-urlencoded")
+            .body(
+                b"old_password=oldpass123&new_password1=N3w-passw0rd!&new_password2=N3w-passw0rd!"
+                    .to_vec(),
+            )
+            .meta("SESSION_DATA", "{}")
+            .build();
+        session_auth::login_to_session(&mut request, &user);
+
+        let response = password_change_view(request, &config, &backend).await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+
+        let saved = backend.get_user("alice").await.unwrap().unwrap();
+        assert!(saved.check_password("N3w-passw0rd!").await.unwrap());
+        assert!(
+            session_auth::get_user_from_request(&other_session, &backend)
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_confirm_view_sets_password() {
+        let backends: Vec<Box<dyn AuthBackend>> = vec![Box::new(
+            create_backend_with_user("alice", "oldpass123").await,
+        )];
+        let user = backends[0].get_user("alice").await.unwrap().unwrap();
+        let generator = DefaultTokenGenerator::new("secret");
+        let token = generator.make_token(&user);
+        let uidb64 = encode_uid("alice");
+        let config = PasswordResetConfirmConfig::default();
+        let post = || {
+            HttpRequest::builder()
+                .method(http::Method::POST)
+                .content_type("application/x-www-form-urlencoded")
+                .body(b"new_password1=N3w-passw0rd!&new_password2=N3w-passw0rd!".to_vec())
+                .build()
+        };
+
+        let response =
+            password_reset_confirm_view(post(), &uidb64, &token, &config, &generator, &backends)
+                .await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+
+        let credentials = Credentials::with_username("alice", "N3w-passw0rd!");
+        let logged_in = crate::backends::authenticate(&credentials, &backends)
+            .await
+            .unwrap();
+        assert_eq!(logged_in.unwrap().username, "alice");
+
+        // The link stops working once used.
+        let response =
+            password_reset_confirm_view(post(), &uidb64, &token, &config, &generator, &backends)
+                .await;
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    /// Collects sent emails as `(subject, body, to)`.
+    #[derive(Default)]
+    struct RecordingMailer {
+        sent: std::sync::Mutex<Vec<(String, String, String)>>,
+    }
+
+    #[async_trait]
+    impl PasswordResetMailer for RecordingMailer {
+        async fn send_mail(
+            &self,
+            subject: &str,
+            body: &str,
+            _from_email: Option<&str>,
+            to: &str,
+        ) -> Result<(), DjangoError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((subject.to_string(), body.to_string(), to.to_string()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_view_sends_email() {
+        let backend = create_backend_with_user("alice", "oldpass123").await;
+        let mut user = backend.get_user("alice").await.unwrap().unwrap();
+        user.email = "alice@example.com".to_string();
+        backend.save_user(&user).await.unwrap();
+        let mut inactive = AbstractUser::new("bob");
+        inactive.email = "bob@example.com".to_string();
+        inactive.base.is_active = false;
+        backend.add_user(inactive).await;
+        let backends: Vec<Box<dyn AuthBackend>> = vec![Box::new(backend)];
+
+        let engine = TemplateEngine::new();
+        engine.add_string_template("subject.txt", "Reset on {{ site_name }}\n");
+        engine.add_string_template(
+            "email.txt",
+            "{{ protocol }}://{{ domain }}/reset/{{ uid }}/{{ token }}/ for {{ user.username }}",
+        );
+        let mailer = Arc::new(RecordingMailer::default());
+        let config = PasswordResetConfig {
+            email_template_name: "email.txt".to_string(),
+            subject_template_name: "subject.txt".to_string(),
+            token_generator: Box::new(DefaultTokenGenerator::new("secret")),
+            template_engine: Arc::new(engine),
+            mailer: Some(mailer.clone()),
+            ..PasswordResetConfig::default()
+        };
+        let post = |email: &str| {
+            HttpRequest::builder()
+                .method(http::Method::POST)
+                .path("/password_reset/")
+                .meta("HTTP_HOST", "example.com")
+                .content_type("application/x-www-form-urlencoded")
+                .body(format!("email={}", email.replace('@', "%40")).into_bytes())
+                .build()
+        };
+
+        let response = password_reset_view(post("alice@example.com"), &config, &backends).await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+        let sent = mailer.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let (subject, body, to) = &sent[0];
+        assert_eq!(subject, "Reset on example.com");
+        assert_eq!(to, "alice@example.com");
+
+        // The link in the body must be accepted by the confirm view.
+        let path = body
+            .strip_prefix("http://example.com/reset/")
+            .and_then(|rest| rest.strip_suffix("/ for alice"))
+            .unwrap();
+        let (uidb64, token) = path.split_once('/').unwrap();
+        assert_eq!(decode_uid(uidb64).as_deref(), Some("alice"));
+        let user = backends[0].get_user("alice").await.unwrap().unwrap();
+        assert!(config.token_generator.check_token(&user, token));
+
+        // Unknown and inactive accounts get the same redirect but no email.
+        for email in ["nobody@example.com", "bob@example.com"] {
+            let response = password_reset_view(post(email), &config, &backends).await;
+            assert_eq!(response.status(), http::StatusCode::FOUND);
+        }
+        assert_eq!(mailer.sent.lock().unwrap().len(), 1);
+    }
 }
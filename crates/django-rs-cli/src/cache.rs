@@ -10,6 +10,15 @@
 //! - [`FileCache`] - Filesystem-based cache with async I/O
 //! - [`DummyCache`] - No-op cache for testing
 //!
+//! [`VersionedCache`] wraps any backend with Django's key prefixing and
+//! versioning (`prefix:version:key`), including `incr_version` and a
+//! configurable key function.
+//!
+//! A [`VersionedCache`] is also a [`PageCacheBackend`], so the views crate's
+//! `CacheMiddleware` can keep full pages in any backend with
+//! [`with_cache`](django_rs_views::middleware::builtin::CacheMiddleware::with_cache).
+//! Each page is read and written with one `get_many` and one `set_many`.
+//!
 //! ## Usage
 //!
 //! ```rust,no_run
@@ -34,6 +43,7 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
 use django_rs_core::DjangoError;
+use django_rs_views::PageCacheBackend;
 
 /// A value that can be stored in a cache backend.
 ///
@@ -128,6 +138,320 @@ pub trait CacheBackend: Send + Sync {
     /// Returns the new value after incrementing. If the key does not exist
     /// or is not an integer, returns an error.
     async fn incr(&self, key: &str, delta: i64) -> Result<i64, DjangoError>;
+
+    /// Deletes multiple values from the cache at once.
+    ///
+    /// Returns the number of keys that existed and were deleted.
+    async fn delete_many(&self, keys: &[&str]) -> Result<usize, DjangoError> {
+        let mut deleted = 0;
+        for &key in keys {
+            if self.delete(key).await? {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Deletes every key matching a glob pattern (`*` and `?` wildcards).
+    ///
+    /// Returns the number of keys deleted. Backends that cannot enumerate
+    /// their keys return an error; this is the default.
+    async fn delete_pattern(&self, pattern: &str) -> Result<usize, DjangoError> {
+        Err(DjangoError::ImproperlyConfigured(format!(
+            "This cache backend does not support delete_pattern('{pattern}')"
+        )))
+    }
+}
+
+/// A function that builds the final cache key from `(key, key_prefix, version)`.
+///
+/// This mirrors Django's `KEY_FUNCTION` cache setting.
+pub type KeyFunc = Arc<dyn Fn(&str, &str, i64) -> String + Send + Sync>;
+
+/// The default key function, producing `prefix:version:key`.
+///
+/// This mirrors Django's `django.core.cache.backends.base.default_key_func`.
+pub fn default_key_func(key: &str, key_prefix: &str, version: i64) -> String {
+    format!("{key_prefix}:{version}:{key}")
+}
+
+/// Matches `text` against a glob `pattern` where `*` matches any run of
+/// characters and `?` matches exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A cache front-end that applies key prefixing and versioning to a backend.
+///
+/// Every key passed in is transformed with the configured [`KeyFunc`]
+/// (by default `prefix:version:key`) before it reaches the backend, so
+/// bumping the version of a key with [`incr_version`](Self::incr_version)
+/// invalidates it without touching other versions. Methods take an optional
+/// `version`; `None` uses the cache's default version. This mirrors the key
+/// handling of Django's `BaseCache`.
+///
+/// ```rust,no_run
+/// use django_rs_cli::cache::{CacheValue, InMemoryCache, VersionedCache};
+///
+/// async fn example() {
+///     let cache = VersionedCache::new(InMemoryCache::new()).with_key_prefix("site");
+///     cache.set("greeting", CacheValue::Integer(1), None, None).await.unwrap();
+///     let version = cache.incr_version("greeting", None).await.unwrap();
+///     assert_eq!(version, 2);
+///     assert!(cache.get("greeting", Some(1)).await.unwrap().is_none());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct VersionedCache<B: CacheBackend> {
+    backend: B,
+    /// The prefix passed to the key function for every key.
+    pub key_prefix: String,
+    /// The version used when a method is called without one.
+    pub version: i64,
+    key_func: KeyFunc,
+}
+
+impl<B: CacheBackend + std::fmt::Debug> std::fmt::Debug for VersionedCache<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VersionedCache")
+            .field("backend", &self.backend)
+            .field("key_prefix", &self.key_prefix)
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B: CacheBackend> VersionedCache<B> {
+    /// Wraps a backend with an empty key prefix, version 1, and the
+    /// [`default_key_func`].
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            key_prefix: String::new(),
+            version: 1,
+            key_func: Arc::new(default_key_func),
+        }
+    }
+
+    /// Sets the key prefix.
+    #[must_use]
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.key_prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets the default version.
+    #[must_use]
+    pub const fn with_version(mut self, version: i64) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Replaces the key function.
+    #[must_use]
+    pub fn with_key_func<F>(mut self, key_func: F) -> Self
+    where
+        F: Fn(&str, &str, i64) -> String + Send + Sync + 'static,
+    {
+        self.key_func = Arc::new(key_func);
+        self
+    }
+
+    /// Returns the wrapped backend.
+    pub const fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Builds the backend key for `key` at the given (or default) version.
+    pub fn make_key(&self, key: &str, version: Option<i64>) -> String {
+        (self.key_func)(key, &self.key_prefix, version.unwrap_or(self.version))
+    }
+
+    /// Retrieves a value.
+    pub async fn get(
+        &self,
+        key: &str,
+        version: Option<i64>,
+    ) -> Result<Option<CacheValue>, DjangoError> {
+        self.backend.get(&self.make_key(key, version)).await
+    }
+
+    /// Stores a value with an optional TTL.
+    pub async fn set(
+        &self,
+        key: &str,
+        value: CacheValue,
+        ttl: Option<Duration>,
+        version: Option<i64>,
+    ) -> Result<(), DjangoError> {
+        self.backend
+            .set(&self.make_key(key, version), value, ttl)
+            .await
+    }
+
+    /// Deletes a value, returning `true` if it existed.
+    pub async fn delete(&self, key: &str, version: Option<i64>) -> Result<bool, DjangoError> {
+        self.backend.delete(&self.make_key(key, version)).await
+    }
+
+    /// Checks whether a key exists.
+    pub async fn has_key(&self, key: &str, version: Option<i64>) -> Result<bool, DjangoError> {
+        self.backend.has_key(&self.make_key(key, version)).await
+    }
+
+    /// Increments an integer value by `delta`.
+    pub async fn incr(
+        &self,
+        key: &str,
+        delta: i64,
+        version: Option<i64>,
+    ) -> Result<i64, DjangoError> {
+        self.backend.incr(&self.make_key(key, version), delta).await
+    }
+
+    /// Retrieves multiple values in one backend call.
+    ///
+    /// The returned map is keyed by the caller's keys, not the backend keys.
+    pub async fn get_many(
+        &self,
+        keys: &[&str],
+        version: Option<i64>,
+    ) -> Result<HashMap<String, CacheValue>, DjangoError> {
+        let made: Vec<String> = keys.iter().map(|k| self.make_key(k, version)).collect();
+        let made_refs: Vec<&str> = made.iter().map(String::as_str).collect();
+        let mut found = self.backend.get_many(&made_refs).await?;
+
+        Ok(keys
+            .iter()
+            .zip(&made)
+            .filter_map(|(key, made_key)| {
+                found
+                    .remove(made_key)
+                    .map(|value| ((*key).to_string(), value))
+            })
+            .collect())
+    }
+
+    /// Stores multiple values in one backend call.
+    pub async fn set_many(
+        &self,
+        values: &HashMap<String, CacheValue>,
+        ttl: Option<Duration>,
+        version: Option<i64>,
+    ) -> Result<(), DjangoError> {
+        let made: HashMap<String, CacheValue> = values
+            .iter()
+            .map(|(k, v)| (self.make_key(k, version), v.clone()))
+            .collect();
+        self.backend.set_many(&made, ttl).await
+    }
+
+    /// Deletes multiple values, returning how many existed.
+    pub async fn delete_many(
+        &self,
+        keys: &[&str],
+        version: Option<i64>,
+    ) -> Result<usize, DjangoError> {
+        let made: Vec<String> = keys.iter().map(|k| self.make_key(k, version)).collect();
+        let made_refs: Vec<&str> = made.iter().map(String::as_str).collect();
+        self.backend.delete_many(&made_refs).await
+    }
+
+    /// Deletes every key matching `pattern` at the given version.
+    ///
+    /// The pattern is passed through the key function, so `"user:*"`
+    /// matches `prefix:version:user:*` with the default key function.
+    pub async fn delete_pattern(
+        &self,
+        pattern: &str,
+        version: Option<i64>,
+    ) -> Result<usize, DjangoError> {
+        self.backend
+            .delete_pattern(&self.make_key(pattern, version))
+            .await
+    }
+
+    /// Moves a key to `version + delta`, returning the new version.
+    ///
+    /// The value keeps no TTL at the new version. Returns
+    /// [`DjangoError::NotFound`] if the key does not exist.
+    pub async fn incr_version_by(
+        &self,
+        key: &str,
+        delta: i64,
+        version: Option<i64>,
+    ) -> Result<i64, DjangoError> {
+        let version = version.unwrap_or(self.version);
+        let value = self
+            .get(key, Some(version))
+            .await?
+            .ok_or_else(|| DjangoError::NotFound(format!("Cache key '{key}' does not exist")))?;
+
+        let new_version = version + delta;
+        self.set(key, value, None, Some(new_version)).await?;
+        self.delete(key, Some(version)).await?;
+        Ok(new_version)
+    }
+
+    /// Moves a key to the next version, returning the new version.
+    ///
+    /// This mirrors Django's `cache.incr_version()`.
+    pub async fn incr_version(&self, key: &str, version: Option<i64>) -> Result<i64, DjangoError> {
+        self.incr_version_by(key, 1, version).await
+    }
+
+    /// Moves a key to the previous version, returning the new version.
+    pub async fn decr_version(&self, key: &str, version: Option<i64>) -> Result<i64, DjangoError> {
+        self.incr_version_by(key, -1, version).await
+    }
+}
+
+/// Stores pages at the cache's default version, as [`CacheValue::Bytes`].
+#[async_trait]
+impl<B: CacheBackend> PageCacheBackend for VersionedCache<B> {
+    async fn get_many(&self, keys: &[&str]) -> Result<HashMap<String, Vec<u8>>, DjangoError> {
+        Ok(Self::get_many(self, keys, None)
+            .await?
+            .into_iter()
+            .filter_map(|(key, value)| match value {
+                CacheValue::Bytes(bytes) => Some((key, bytes)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn set_many(
+        &self,
+        entries: HashMap<String, Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<(), DjangoError> {
+        let values = entries
+            .into_iter()
+            .map(|(key, bytes)| (key, CacheValue::Bytes(bytes)))
+            .collect();
+        Self::set_many(self, &values, Some(timeout), None).await
+    }
 }
 
 /// An entry in the in-memory cache, wrapping a value with its expiration time.
@@ -269,6 +593,21 @@ impl CacheBackend for InMemoryCache {
             ))),
         }
     }
+
+    async fn delete_many(&self, keys: &[&str]) -> Result<usize, DjangoError> {
+        let mut store = self.store.write().await;
+        Ok(keys
+            .iter()
+            .filter(|&&key| store.remove(key).is_some())
+            .count())
+    }
+
+    async fn delete_pattern(&self, pattern: &str) -> Result<usize, DjangoError> {
+        let mut store = self.store.write().await;
+        let before = store.len();
+        store.retain(|key, _| !glob_match(pattern, key));
+        Ok(before - store.len())
+    }
 }
 
 /// An async database-backed cache.
@@ -335,6 +674,14 @@ impl CacheBackend for DatabaseCache {
     async fn incr(&self, key: &str, delta: i64) -> Result<i64, DjangoError> {
         self.inner.incr(key, delta).await
     }
+
+    async fn delete_many(&self, keys: &[&str]) -> Result<usize, DjangoError> {
+        self.inner.delete_many(keys).await
+    }
+
+    async fn delete_pattern(&self, pattern: &str) -> Result<usize, DjangoError> {
+        self.inner.delete_pattern(pattern).await
+    }
 }
 
 /// A filesystem-based cache backend using async I/O.
@@ -546,6 +893,14 @@ impl CacheBackend for DummyCache {
             "Cache key '{key}' does not exist (DummyCache)"
         )))
     }
+
+    async fn delete_many(&self, _keys: &[&str]) -> Result<usize, DjangoError> {
+        Ok(0)
+    }
+
+    async fn delete_pattern(&self, _pattern: &str) -> Result<usize, DjangoError> {
+        Ok(0)
+    }
 }
 
 #[cfg(test)]
@@ -975,6 +1330,185 @@ mod tests {
         cache.clear().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_dummy_cache_delete_pattern() {
+        let cache = DummyCache;
+        assert_eq!(cache.delete_pattern("*").await.unwrap(), 0);
+    }
+
+    // ── Batch and pattern tests ───────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_inmemory_delete_many() {
+        let cache = InMemoryCache::new();
+        cache.set("a", CacheValue::Integer(1), None).await.unwrap();
+        cache.set("b", CacheValue::Integer(2), None).await.unwrap();
+
+        let deleted = cache.delete_many(&["a", "b", "c"]).await.unwrap();
+        assert_eq!(deleted, 2);
+        assert!(!cache.has_key("a").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_inmemory_delete_pattern() {
+        let cache = InMemoryCache::new();
+        cache
+            .set("user:1", CacheValue::Integer(1), None)
+            .await
+            .unwrap();
+        cache
+            .set("user:2", CacheValue::Integer(2), None)
+            .await
+            .unwrap();
+        cache
+            .set("post:1", CacheValue::Integer(3), None)
+            .await
+            .unwrap();
+
+        assert_eq!(cache.delete_pattern("user:*").await.unwrap(), 2);
+        assert!(cache.has_key("post:1").await.unwrap());
+        assert!(!cache.has_key("user:1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_file_cache_delete_many_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FileCache::new(dir.path().to_path_buf());
+        cache.set("a", CacheValue::Integer(1), None).await.unwrap();
+
+        assert_eq!(cache.delete_many(&["a", "b"]).await.unwrap(), 1);
+        assert!(cache.delete_pattern("*").await.is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("user:*", "user:42"));
+        assert!(glob_match("*:1:*", "site:1:home"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("a?c", "abbc"));
+        assert!(!glob_match("user:*", "post:1"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+    }
+
+    // ── VersionedCache tests ──────────────────────────────────────────
+
+    #[test]
+    fn test_default_key_func() {
+        assert_eq!(default_key_func("k", "site", 3), "site:3:k");
+        assert_eq!(default_key_func("k", "", 1), ":1:k");
+    }
+
+    #[tokio::test]
+    async fn test_versioned_cache_keys_are_prefixed() {
+        let cache = VersionedCache::new(InMemoryCache::new()).with_key_prefix("site");
+        cache
+            .set("key", CacheValue::Integer(1), None, None)
+            .await
+            .unwrap();
+
+        assert!(cache.backend().has_key("site:1:key").await.unwrap());
+        assert_eq!(
+            cache.get("key", None).await.unwrap(),
+            Some(CacheValue::Integer(1))
+        );
+        assert!(cache.get("key", Some(2)).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_cache_custom_key_func() {
+        let cache = VersionedCache::new(InMemoryCache::new())
+            .with_version(7)
+            .with_key_func(|key, prefix, version| format!("{prefix}|{key}|v{version}"));
+        assert_eq!(cache.make_key("k", None), "|k|v7");
+        assert_eq!(cache.make_key("k", Some(2)), "|k|v2");
+    }
+
+    #[tokio::test]
+    async fn test_versioned_cache_incr_version() {
+        let cache = VersionedCache::new(InMemoryCache::new());
+        cache
+            .set("key", CacheValue::String("v".to_string()), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(cache.incr_version("key", None).await.unwrap(), 2);
+        assert!(cache.get("key", None).await.unwrap().is_none());
+        assert!(cache.get("key", Some(2)).await.unwrap().is_some());
+
+        assert_eq!(cache.decr_version("key", Some(2)).await.unwrap(), 1);
+        assert!(cache.get("key", None).await.unwrap().is_some());
+
+        assert!(cache.incr_version("missing", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_cache_many() {
+        let cache = VersionedCache::new(InMemoryCache::new()).with_key_prefix("p");
+        let mut values = HashMap::new();
+        values.insert("a".to_string(), CacheValue::Integer(1));
+        values.insert("b".to_string(), CacheValue::Integer(2));
+        cache.set_many(&values, None, Some(3)).await.unwrap();
+
+        let found = cache.get_many(&["a", "b", "c"], Some(3)).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found.get("a"), Some(&CacheValue::Integer(1)));
+        assert!(cache.get_many(&["a"], None).await.unwrap().is_empty());
+
+        assert_eq!(cache.delete_many(&["a", "b"], Some(3)).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_versioned_cache_delete_pattern_respects_version() {
+        let cache = VersionedCache::new(InMemoryCache::new());
+        for version in [1, 2] {
+            cache
+                .set("user:1", CacheValue::Integer(version), None, Some(version))
+                .await
+                .unwrap();
+        }
+        cache
+            .set("post:1", CacheValue::Integer(0), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(cache.delete_pattern("user:*", None).await.unwrap(), 1);
+        assert!(cache.has_key("user:1", Some(2)).await.unwrap());
+        assert!(cache.has_key("post:1", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_cache_stores_cached_pages() {
+        use django_rs_http::{HttpRequest, HttpResponse};
+        use django_rs_views::middleware::builtin::CacheMiddleware;
+        use django_rs_views::Middleware;
+
+        let backend = InMemoryCache::new();
+        let pages = |version| {
+            let cache = VersionedCache::new(backend.clone())
+                .with_key_prefix("site")
+                .with_version(version);
+            CacheMiddleware::new(600).with_cache(Arc::new(cache))
+        };
+        let mw = pages(1);
+        let mut request = HttpRequest::builder()
+            .method(http::Method::GET)
+            .path("/page/")
+            .build();
+        assert!(mw.process_request(&mut request).await.is_none());
+        mw.process_response(&request, HttpResponse::ok("page"))
+            .await;
+        assert!(backend.has_key("site:1::/page/:body").await.unwrap());
+
+        let hit = mw.process_request(&mut request).await.unwrap();
+        assert_eq!(hit.headers().get("x-cache").unwrap(), "HIT");
+        assert_eq!(hit.content_bytes().unwrap(), b"page");
+
+        // A new default version misses every page cached under the old one.
+        assert!(pages(2).process_request(&mut request).await.is_none());
+    }
+
     // ── Default trait tests ───────────────────────────────────────────
 
     #[tokio::test]
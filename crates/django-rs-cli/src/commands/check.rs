@@ -3,10 +3,17 @@
 //! Runs system checks to identify potential problems with the project
 //! configuration. This mirrors Django's `check` command.
 
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::path::Path;
+
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db::registry::{ModelRegistry, MODELS};
+use django_rs_db_migrations::{MigrationLoader, MigrationRecorder};
 
 use crate::command::ManagementCommand;
+use crate::commands::showmigrations::load_recorder;
 
 /// Runs system checks to validate project configuration.
 ///
@@ -51,10 +58,35 @@ impl std::fmt::Display for CheckLevel {
     }
 }
 
-/// Runs system checks against the given settings.
+impl From<django_rs_core::checks::CheckMessage> for CheckMessage {
+    fn from(message: django_rs_core::checks::CheckMessage) -> Self {
+        use django_rs_core::checks::CheckLevel as CoreLevel;
+        let level = match message.level {
+            CoreLevel::Debug | CoreLevel::Info => CheckLevel::Info,
+            CoreLevel::Warning => CheckLevel::Warning,
+            CoreLevel::Error => CheckLevel::Error,
+            CoreLevel::Critical => CheckLevel::Critical,
+        };
+        Self {
+            level,
+            msg: message.msg,
+            hint: message.hint,
+            id: message.id.unwrap_or_default(),
+        }
+    }
+}
+
+/// Runs system checks against the given settings and the models of the
+/// [model registry](django_rs_db::registry).
 ///
 /// Returns a list of check messages identifying potential issues.
 pub fn run_checks(settings: &Settings) -> Vec<CheckMessage> {
+    run_checks_for(settings, &MODELS)
+}
+
+/// Runs system checks against the given settings and the models of
+/// `models`.
+pub fn run_checks_for(settings: &Settings, models: &ModelRegistry) -> Vec<CheckMessage> {
     let mut messages = Vec::new();
 
     // Check: SECRET_KEY should not be empty
@@ -97,6 +129,26 @@ pub fn run_checks(settings: &Settings) -> Vec<CheckMessage> {
         });
     }
 
+    // Check: options relying on session state behind a connection pooler
+    messages.extend(
+        django_rs_core::checks::check_database_pool_modes(settings)
+            .into_iter()
+            .map(CheckMessage::from),
+    );
+
+    // Check: metadata of the registered models, the settings they need, and
+    // relations the installed routers split across databases
+    let models = models.models();
+    messages.extend(
+        django_rs_db::checks::check_models(&models)
+            .into_iter()
+            .chain(django_rs_db::checks::check_model_settings(
+                &models, settings,
+            ))
+            .chain(django_rs_db::checks::check_installed_router(&models))
+            .map(CheckMessage::from),
+    );
+
     // Check: HSTS
     if settings.secure_ssl_redirect && settings.secure_hsts_seconds == 0 {
         messages.push(CheckMessage {
@@ -110,6 +162,27 @@ pub fn run_checks(settings: &Settings) -> Vec<CheckMessage> {
     messages
 }
 
+/// Warns about applied migrations whose file changed after they were applied.
+///
+/// `checksums` holds the current migration file checksums. Editing an applied
+/// migration has no effect on databases that already ran it, so the schema
+/// silently drifts between environments.
+pub fn check_migration_checksums<S: BuildHasher>(
+    recorder: &MigrationRecorder,
+    checksums: &HashMap<(String, String), String, S>,
+) -> Vec<CheckMessage> {
+    recorder
+        .checksum_mismatches(checksums)
+        .into_iter()
+        .map(|(app, name)| CheckMessage {
+            level: CheckLevel::Warning,
+            msg: format!("Migration {app}.{name} was modified after it was applied"),
+            hint: Some("Revert the edit and put the change in a new migration instead".to_string()),
+            id: "migrations.W001".to_string(),
+        })
+        .collect()
+}
+
 #[async_trait]
 impl ManagementCommand for CheckCommand {
     fn name(&self) -> &'static str {
@@ -134,14 +207,39 @@ impl ManagementCommand for CheckCommand {
                 .action(clap::ArgAction::SetTrue)
                 .help("Run deployment checks"),
         )
+        .arg(
+            clap::Arg::new("database")
+                .long("database")
+                .default_value("default")
+                .help("Database alias whose applied migrations are checked"),
+        )
+        .arg(
+            clap::Arg::new("migrations-dir")
+                .long("migrations-dir")
+                .help("Path to migrations directory")
+                .default_value("migrations"),
+        )
     }
 
     async fn handle(
         &self,
-        _matches: &clap::ArgMatches,
+        matches: &clap::ArgMatches,
         settings: &Settings,
     ) -> Result<(), DjangoError> {
-        let messages = run_checks(settings);
+        let mut messages = run_checks(settings);
+
+        let database = matches
+            .get_one::<String>("database")
+            .map_or("default", String::as_str);
+        let migrations_dir = matches
+            .get_one::<String>("migrations-dir")
+            .map_or("migrations", String::as_str);
+        if settings.databases.contains_key(database) && Path::new(migrations_dir).is_dir() {
+            let mut loader = MigrationLoader::new(migrations_dir);
+            loader.load()?;
+            let recorder = load_recorder(settings, database).await?;
+            messages.extend(check_migration_checksums(&recorder, &loader.checksums()));
+        }
 
         if messages.is_empty() {
             tracing::info!("System check identified no issues");
@@ -186,6 +284,26 @@ impl ManagementCommand for CheckCommand {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_check_migration_checksums() {
+        use django_rs_db_migrations::AppliedMigration;
+
+        let mut recorder = MigrationRecorder::new();
+        recorder.apply_record(
+            AppliedMigration::new("blog", "0001_initial").checksum(Some("old".into())),
+        );
+        let key = ("blog".to_string(), "0001_initial".to_string());
+
+        let unchanged = HashMap::from([(key.clone(), "old".to_string())]);
+        assert!(check_migration_checksums(&recorder, &unchanged).is_empty());
+
+        let edited = HashMap::from([(key, "new".to_string())]);
+        let messages = check_migration_checksums(&recorder, &edited);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, "migrations.W001");
+        assert_eq!(messages[0].level, CheckLevel::Warning);
+    }
+
     #[test]
     fn test_check_empty_secret_key() {
         let settings = Settings::default();
@@ -253,4 +371,92 @@ mod tests {
         let messages = run_checks(&settings);
         assert!(messages.iter().any(|m| m.id == "security.W003"));
     }
+
+    #[test]
+    fn test_check_session_options_under_transaction_pooling() {
+        let mut settings = Settings {
+            secret_key: "secret".to_string(),
+            ..Settings::default()
+        };
+        let db = settings.databases.entry("default".to_string()).or_default();
+        db.engine = "django_rs.db.backends.postgresql".to_string();
+        db.options
+            .insert("pool_mode".to_string(), "transaction".to_string());
+        db.options.insert(
+            "init_command".to_string(),
+            "SET work_mem = '64MB'".to_string(),
+        );
+
+        let messages = run_checks(&settings);
+        let message = messages
+            .iter()
+            .find(|m| m.id == "database.E004")
+            .expect("session option flagged");
+        assert_eq!(message.level, CheckLevel::Error);
+        assert!(message.msg.contains("init_command"));
+    }
+
+    #[test]
+    fn test_check_registered_models() {
+        use django_rs_db::fields::{FieldDef, FieldType};
+        use django_rs_db::model::{Model, ModelMeta};
+        use django_rs_db::query::compiler::{InheritanceType, OrderBy, Row};
+        use django_rs_db::value::Value;
+        use std::sync::LazyLock;
+
+        struct Ticket;
+
+        impl Model for Ticket {
+            fn meta() -> &'static ModelMeta {
+                static META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+                    app_label: "support",
+                    model_name: "ticket",
+                    db_table: "support_ticket".to_string(),
+                    verbose_name: "ticket".to_string(),
+                    verbose_name_plural: "tickets".to_string(),
+                    ordering: vec![OrderBy::desc("opened")],
+                    unique_together: vec![],
+                    indexes: vec![],
+                    abstract_model: false,
+                    fields: vec![FieldDef::new("id", FieldType::BigAutoField).primary_key()],
+                    constraints: vec![],
+                    inheritance_type: InheritanceType::None,
+                    db_schema: None,
+                });
+                &META
+            }
+
+            fn table_name() -> &'static str {
+                "support_ticket"
+            }
+
+            fn app_label() -> &'static str {
+                "support"
+            }
+
+            fn pk(&self) -> Option<&Value> {
+                None
+            }
+
+            fn set_pk(&mut self, _value: Value) {}
+
+            fn field_values(&self) -> Vec<(&'static str, Value)> {
+                vec![]
+            }
+
+            fn from_row(_row: &Row) -> Result<Self, DjangoError> {
+                Ok(Self)
+            }
+        }
+
+        let models = ModelRegistry::new();
+        models.register::<Ticket>();
+        let messages = run_checks_for(&Settings::default(), &models);
+        let message = messages
+            .iter()
+            .find(|m| m.id == "models.E015")
+            .expect("invalid ordering flagged");
+        assert_eq!(message.level, CheckLevel::Error);
+        assert!(message.msg.contains("support.ticket"));
+    }
 }
@@ -2,50 +2,307 @@
 //!
 //! Serializes model data to JSON for backup or fixture creation.
 //! This mirrors Django's `dumpdata` command.
+//!
+//! Rows are read from the `--database` alias in batches and written one
+//! object at a time, so dumping a table of millions of rows never holds it
+//! in memory. `--output` files ending in `.gz` or `.zst` are compressed, and
+//! `--format jsonl` (or a `.jsonl` output) writes one object per line.
+//!
+//! Models are taken from the [model registry](django_rs_db::registry), which
+//! gives the table and primary key of each.
+//!
+//! With `--as-migration app_label.ModelName`, the current rows are emitted as
+//! a data migration instead, so reference tables can be version-controlled and
+//! applied through the migration graph.
+
+use std::collections::BTreeMap;
+use std::path::Path;
 
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db::query::deserialize::from_row;
+use django_rs_db::registry::{ModelRegistry, MODELS};
+use django_rs_db::{DatabaseBackendType, DbExecutor, ModelMeta, Value};
+use django_rs_db_migrations::serializer::{
+    generate_migration_name, migration_file_path, next_migration_number, SerializableOperation,
+};
+use django_rs_db_migrations::{MigrationLoader, SerializableMigration};
 
 use crate::command::ManagementCommand;
-use crate::serialization::{PrettyJsonSerializer, Serializer};
+use crate::fixtures::{connect_database, FixtureFormat, FixtureOutput, FixtureWriter};
+
+/// Number of rows fetched per query when streaming a table.
+pub const DUMP_BATCH_SIZE: usize = 1000;
 
 /// Outputs serialized model data to stdout or a file.
 ///
 /// Takes an optional `app_label.ModelName` argument to restrict output
-/// to a specific model. Supports `--indent` for pretty-printed output
-/// and `--output` to write to a file instead of stdout.
+/// to a specific model. Supports `--indent` for pretty-printed output,
+/// `--format` to choose between a JSON array and JSON lines, and
+/// `--output` to write to a (possibly compressed) file instead of stdout.
+/// With `--as-migration`, writes a data migration for a single model
+/// instead.
 pub struct DumpdataCommand;
 
 /// Serializes the given objects and writes them to the specified output.
 ///
 /// If `output_path` is `None`, writes to stdout. If `indent` is true,
-/// uses pretty-printed JSON formatting.
+/// uses pretty-printed JSON formatting. Output paths ending in `.gz` or
+/// `.zst` are compressed; the returned string is always the uncompressed
+/// JSON.
 pub async fn dump_data(
     objects: &[serde_json::Value],
     output_path: Option<&str>,
     indent: bool,
 ) -> Result<String, DjangoError> {
-    let serializer: Box<dyn Serializer> = if indent {
-        Box::new(PrettyJsonSerializer)
-    } else {
-        Box::new(crate::serialization::JsonSerializer)
-    };
-
-    let result = serializer.serialize(objects)?;
+    let mut writer = FixtureWriter::new(Vec::new(), FixtureFormat::Json, indent);
+    for object in objects {
+        writer.write_object(object)?;
+    }
+    let result = String::from_utf8(writer.finish()?)
+        .map_err(|e| DjangoError::SerializationError(e.to_string()))?;
 
     if let Some(path) = output_path {
-        tokio::fs::write(path, &result).await.map_err(|e| {
-            DjangoError::IoError(std::io::Error::new(
-                e.kind(),
-                format!("Failed to write to {path}: {e}"),
-            ))
-        })?;
+        let owned_path = path.to_string();
+        let data = result.clone();
+        tokio::task::spawn_blocking(move || -> Result<(), DjangoError> {
+            let mut output = FixtureOutput::create(Path::new(&owned_path))?;
+            std::io::Write::write_all(&mut output, data.as_bytes())?;
+            output.finish()?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| DjangoError::InternalServerError(e.to_string()))??;
         tracing::info!("Data written to {path}");
     }
 
     Ok(result)
 }
 
+/// A model to dump: its `app_label.model_name` label, database table and
+/// primary key column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpTarget {
+    /// The fixture label, e.g. `blog.post`.
+    pub label: String,
+    /// The table holding the rows, e.g. `blog_post`.
+    pub table: String,
+    /// The primary key column, e.g. `id`.
+    pub pk: String,
+}
+
+impl DumpTarget {
+    /// Creates the target for the model described by `meta`.
+    pub fn for_meta(meta: &ModelMeta) -> Self {
+        Self {
+            label: format!("{}.{}", meta.app_label, meta.model_name.to_lowercase()),
+            table: meta.db_table.clone(),
+            pk: meta.pk_column().to_string(),
+        }
+    }
+}
+
+/// Lists the model tables of the database, in name order.
+///
+/// The migration history table is left out.
+pub async fn list_tables(db: &dyn DbExecutor) -> Result<Vec<String>, DjangoError> {
+    let sql = match db.backend_type() {
+        DatabaseBackendType::SQLite => {
+            "SELECT name FROM sqlite_master WHERE type = 'table' \
+             AND name NOT LIKE 'sqlite_%' ORDER BY name"
+        }
+        DatabaseBackendType::PostgreSQL => {
+            "SELECT table_name AS name FROM information_schema.tables \
+             WHERE table_schema = current_schema() AND table_type = 'BASE TABLE' \
+             ORDER BY table_name"
+        }
+        DatabaseBackendType::MySQL => {
+            "SELECT table_name AS name FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE' \
+             ORDER BY table_name"
+        }
+    };
+    let rows = db.query(sql, &[]).await?;
+    let mut tables = Vec::with_capacity(rows.len());
+    for row in rows {
+        let name: String = row.get("name")?;
+        if name != "django_migrations" {
+            tables.push(name);
+        }
+    }
+    Ok(tables)
+}
+
+/// Resolves `app_label` / `app_label.ModelName` specifiers to the models
+/// of `models` to dump. With no specifiers, every registered model whose
+/// table exists in the database is dumped.
+///
+/// # Errors
+///
+/// Returns an error if a specifier names no registered app or model.
+pub async fn resolve_targets(
+    db: &dyn DbExecutor,
+    models: &ModelRegistry,
+    specs: &[&str],
+) -> Result<Vec<DumpTarget>, DjangoError> {
+    if specs.is_empty() {
+        let tables = list_tables(db).await?;
+        return Ok(models
+            .models()
+            .into_iter()
+            .filter(|m| !m.abstract_model && tables.contains(&m.db_table))
+            .map(DumpTarget::for_meta)
+            .collect());
+    }
+    let mut targets = Vec::new();
+    for spec in specs {
+        match parse_model_specifier(spec) {
+            (app, Some(model)) => {
+                let meta = models.get_model(&app, &model).ok_or_else(|| {
+                    DjangoError::ImproperlyConfigured(format!("Unknown model: {app}.{model}"))
+                })?;
+                targets.push(DumpTarget::for_meta(meta));
+            }
+            (app, None) => {
+                let app_models = models.app_models(&app);
+                if app_models.is_empty() {
+                    return Err(DjangoError::ImproperlyConfigured(format!(
+                        "No installed app with label '{app}'"
+                    )));
+                }
+                targets.extend(app_models.into_iter().map(DumpTarget::for_meta));
+            }
+        }
+    }
+    Ok(targets)
+}
+
+/// Reads the rows of one model in primary key order, [`DUMP_BATCH_SIZE`]
+/// at a time.
+///
+/// Batches are selected by key (`WHERE pk > last`) rather than by offset,
+/// so every batch is an index range scan however deep into the table it is.
+pub struct ModelRows<'a> {
+    target: &'a DumpTarget,
+    last_pk: Option<Value>,
+    done: bool,
+}
+
+impl<'a> ModelRows<'a> {
+    /// Starts reading the rows of `target`.
+    pub fn new(target: &'a DumpTarget) -> Self {
+        Self {
+            target,
+            last_pk: None,
+            done: false,
+        }
+    }
+
+    /// Returns the next batch as fixture objects, or `None` once all rows
+    /// have been read.
+    pub async fn next_batch(
+        &mut self,
+        db: &dyn DbExecutor,
+    ) -> Result<Option<Vec<serde_json::Value>>, DjangoError> {
+        if self.done {
+            return Ok(None);
+        }
+        let backend = db.backend_type();
+        let table = backend.quote_table_name(&self.target.table);
+        let pk_column = &self.target.pk;
+        let placeholder = if backend == DatabaseBackendType::PostgreSQL {
+            "$1"
+        } else {
+            "?"
+        };
+        let rows = match &self.last_pk {
+            None => {
+                let sql = format!(
+                    "SELECT * FROM {table} ORDER BY \"{pk_column}\" LIMIT {DUMP_BATCH_SIZE}"
+                );
+                db.query(&sql, &[]).await?
+            }
+            Some(pk) => {
+                let sql = format!(
+                    "SELECT * FROM {table} WHERE \"{pk_column}\" > {placeholder} \
+                     ORDER BY \"{pk_column}\" LIMIT {DUMP_BATCH_SIZE}"
+                );
+                db.query(&sql, std::slice::from_ref(pk)).await?
+            }
+        };
+        self.done = rows.len() < DUMP_BATCH_SIZE;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        self.last_pk = rows
+            .last()
+            .and_then(|row| row.get_value(pk_column))
+            .cloned();
+        rows.iter()
+            .map(|row| {
+                let mut fields: serde_json::Map<String, serde_json::Value> = from_row(row)?;
+                let pk = fields.remove(pk_column).unwrap_or(serde_json::Value::Null);
+                Ok(serde_json::json!({
+                    "model": self.target.label,
+                    "pk": pk,
+                    "fields": fields,
+                }))
+            })
+            .collect::<Result<Vec<_>, DjangoError>>()
+            .map(Some)
+    }
+}
+
+/// Streams the rows of `targets` to `output` and returns the number of
+/// objects dumped per model label.
+///
+/// Rows are fetched in batches while a blocking task serializes and
+/// (if the output is compressed) compresses them, so memory use is bounded
+/// by the batch size rather than the table size.
+pub async fn dump_models(
+    db: &dyn DbExecutor,
+    targets: &[DumpTarget],
+    output: FixtureOutput,
+    format: FixtureFormat,
+    indent: bool,
+) -> Result<BTreeMap<String, usize>, DjangoError> {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<serde_json::Value>(DUMP_BATCH_SIZE);
+    let writer = tokio::task::spawn_blocking(move || -> Result<(), DjangoError> {
+        let mut writer = FixtureWriter::new(output, format, indent);
+        while let Some(object) = rx.blocking_recv() {
+            writer.write_object(&object)?;
+        }
+        writer.finish()?.finish()?;
+        Ok(())
+    });
+
+    let mut counts = BTreeMap::new();
+    let read = async {
+        for target in targets {
+            let mut rows = ModelRows::new(target);
+            let mut count = 0;
+            while let Some(batch) = rows.next_batch(db).await? {
+                count += batch.len();
+                for object in batch {
+                    if tx.send(object).await.is_err() {
+                        // The writer failed; its error is reported below.
+                        return Ok(());
+                    }
+                }
+            }
+            counts.insert(target.label.clone(), count);
+        }
+        Ok::<_, DjangoError>(())
+    };
+    let read_result = read.await;
+    drop(tx);
+    writer
+        .await
+        .map_err(|e| DjangoError::InternalServerError(e.to_string()))??;
+    read_result?;
+    Ok(counts)
+}
+
 /// Parses a model specifier string into (app_label, model_name).
 ///
 /// The specifier can be either `app_label` (all models in the app) or
@@ -58,6 +315,173 @@ pub fn parse_model_specifier(spec: &str) -> (String, Option<String>) {
     }
 }
 
+/// Builds a data migration that inserts the given fixture objects.
+///
+/// `objects` use the fixture format produced by `dumpdata`
+/// (`{"model": ..., "pk": ..., "fields": {...}}`). The migration contains a
+/// single `RunSQL` operation: forwards inserts every row into `table` in one
+/// statement, backwards deletes the inserted primary keys (stored in
+/// `pk_column`) again. Identifiers and literals are quoted for `backend`.
+///
+/// # Errors
+///
+/// Returns an error if `objects` is empty or an object is missing its `pk`.
+pub fn build_data_migration(
+    backend: DatabaseBackendType,
+    app_label: &str,
+    name: &str,
+    table: &str,
+    pk_column: &str,
+    dependencies: Vec<(String, String)>,
+    objects: &[serde_json::Value],
+) -> Result<SerializableMigration, DjangoError> {
+    if objects.is_empty() {
+        return Err(DjangoError::SerializationError(format!(
+            "No rows to include in data migration for table '{table}'"
+        )));
+    }
+
+    // Union of field names across all rows, in first-seen order.
+    let mut columns: Vec<&str> = Vec::new();
+    for obj in objects {
+        if let Some(fields) = obj.get("fields").and_then(serde_json::Value::as_object) {
+            for key in fields.keys() {
+                if !columns.contains(&key.as_str()) {
+                    columns.push(key);
+                }
+            }
+        }
+    }
+
+    let mut rows = Vec::with_capacity(objects.len());
+    let mut pks = Vec::with_capacity(objects.len());
+    for obj in objects {
+        let pk = obj.get("pk").ok_or_else(|| {
+            DjangoError::SerializationError(format!("Fixture object has no 'pk': {obj}"))
+        })?;
+        let pk = sql_literal(backend, pk);
+        let fields = obj.get("fields");
+        let mut values = vec![pk.clone()];
+        values.extend(columns.iter().map(|col| {
+            fields
+                .and_then(|f| f.get(*col))
+                .map_or_else(|| "NULL".to_string(), |value| sql_literal(backend, value))
+        }));
+        rows.push(format!("({})", values.join(", ")));
+        pks.push(pk);
+    }
+
+    let column_list = std::iter::once(pk_column)
+        .chain(columns.iter().copied())
+        .map(|c| backend.quote_name(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let table = backend.quote_table_name(table);
+
+    let sql_forwards = format!(
+        "INSERT INTO {table} ({column_list}) VALUES\n{};",
+        rows.join(",\n")
+    );
+    let sql_backwards = format!(
+        "DELETE FROM {table} WHERE {} IN ({});",
+        backend.quote_name(pk_column),
+        pks.join(", ")
+    );
+
+    Ok(SerializableMigration {
+        app_label: app_label.to_string(),
+        name: name.to_string(),
+        dependencies,
+        initial: false,
+        atomic: true,
+        operations: vec![SerializableOperation::RunSQL {
+            sql_forwards,
+            sql_backwards,
+        }],
+    })
+}
+
+/// Renders a JSON value as a SQL literal for `backend`.
+///
+/// Arrays and objects are stored as their JSON text, matching how JSON
+/// columns are written by the ORM.
+fn sql_literal(backend: DatabaseBackendType, value: &serde_json::Value) -> String {
+    let quote = |s: &str| {
+        let s = s.replace('\'', "''");
+        // MySQL reads backslashes in string literals as escapes
+        if backend == DatabaseBackendType::MySQL {
+            format!("'{}'", s.replace('\\', "\\\\"))
+        } else {
+            format!("'{s}'")
+        }
+    };
+    match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => quote(s),
+        other => quote(&other.to_string()),
+    }
+}
+
+/// Writes a data migration of `objects`, rows of the model described by
+/// `meta`, into `migrations_dir`, with SQL for `backend`.
+///
+/// The migration depends on the app's current leaf migrations so it runs
+/// after the table has been created. Returns the path of the written file.
+pub fn write_data_migration(
+    migrations_dir: &Path,
+    backend: DatabaseBackendType,
+    meta: &ModelMeta,
+    custom_name: Option<&str>,
+    objects: &[serde_json::Value],
+) -> Result<std::path::PathBuf, DjangoError> {
+    let app_label = meta.app_label;
+    let graph = MigrationLoader::new(migrations_dir).load()?;
+    let dependencies = graph.leaf_nodes(app_label);
+
+    let default_name = format!("{}_data", meta.model_name.to_lowercase());
+    let number = next_migration_number(migrations_dir, app_label);
+    let name = generate_migration_name(number, Some(custom_name.unwrap_or(&default_name)));
+
+    let migration = build_data_migration(
+        backend,
+        app_label,
+        &name,
+        &meta.db_table,
+        meta.pk_column(),
+        dependencies,
+        objects,
+    )?;
+    let path = migration_file_path(migrations_dir, app_label, &name);
+    migration.write_to_file(&path)?;
+    Ok(path)
+}
+
+/// Reads the current rows of the model described by `meta` and writes them
+/// as a data migration into `migrations_dir`, with SQL for the database they
+/// were read from.
+pub async fn dump_as_migration(
+    db: &dyn DbExecutor,
+    meta: &ModelMeta,
+    migrations_dir: &Path,
+    custom_name: Option<&str>,
+) -> Result<std::path::PathBuf, DjangoError> {
+    let target = DumpTarget::for_meta(meta);
+    let mut rows = ModelRows::new(&target);
+    let mut objects = Vec::new();
+    while let Some(batch) = rows.next_batch(db).await? {
+        objects.extend(batch);
+    }
+    write_data_migration(
+        migrations_dir,
+        db.backend_type(),
+        meta,
+        custom_name,
+        &objects,
+    )
+}
+
 #[async_trait]
 impl ManagementCommand for DumpdataCommand {
     fn name(&self) -> &'static str {
@@ -80,11 +504,17 @@ impl ManagementCommand for DumpdataCommand {
                 .action(clap::ArgAction::SetTrue)
                 .help("Use pretty-printed JSON output"),
         )
+        .arg(
+            clap::Arg::new("format")
+                .long("format")
+                .value_parser(["json", "jsonl"])
+                .help("Output format (default: inferred from --output, else json)"),
+        )
         .arg(
             clap::Arg::new("output")
                 .long("output")
                 .short('o')
-                .help("Output file path (default: stdout)"),
+                .help("Output file path, compressed if it ends in .gz or .zst (default: stdout)"),
         )
         .arg(
             clap::Arg::new("database")
@@ -92,50 +522,91 @@ impl ManagementCommand for DumpdataCommand {
                 .default_value("default")
                 .help("Database alias to dump from"),
         )
+        .arg(
+            clap::Arg::new("as-migration")
+                .long("as-migration")
+                .action(clap::ArgAction::SetTrue)
+                .help("Write the rows of a single app_label.ModelName as a data migration"),
+        )
+        .arg(
+            clap::Arg::new("name")
+                .short('n')
+                .long("name")
+                .help("Name for the generated data migration"),
+        )
+        .arg(
+            clap::Arg::new("migrations-dir")
+                .long("migrations-dir")
+                .help("Path to migrations directory")
+                .default_value("migrations"),
+        )
     }
 
     async fn handle(
         &self,
         matches: &clap::ArgMatches,
-        _settings: &Settings,
+        settings: &Settings,
     ) -> Result<(), DjangoError> {
         let indent = matches.get_flag("indent");
         let output = matches.get_one::<String>("output").map(String::as_str);
         let database = matches
             .get_one::<String>("database")
             .map_or("default", String::as_str);
-        let app_labels: Vec<&String> = matches
+        let app_labels: Vec<&str> = matches
             .get_many::<String>("app_label")
-            .map_or_else(Vec::new, Iterator::collect);
+            .map_or_else(Vec::new, |specs| specs.map(String::as_str).collect());
+        let format = match matches.get_one::<String>("format") {
+            Some(name) => FixtureFormat::from_name(name)?,
+            None => output.map_or(FixtureFormat::Json, |path| {
+                FixtureFormat::from_path(Path::new(path))
+            }),
+        };
 
         tracing::info!("Dumping data from database '{database}'");
 
-        if app_labels.is_empty() {
-            tracing::info!("Dumping all models");
-        } else {
-            for spec in &app_labels {
-                let (app, model) = parse_model_specifier(spec);
-                if let Some(model_name) = &model {
-                    tracing::info!("Dumping {app}.{model_name}");
-                } else {
-                    tracing::info!("Dumping all models in app '{app}'");
-                }
-            }
+        if matches.get_flag("as-migration") {
+            let [spec] = app_labels.as_slice() else {
+                return Err(DjangoError::ImproperlyConfigured(
+                    "--as-migration requires exactly one app_label.ModelName".into(),
+                ));
+            };
+            let (app, Some(model_name)) = parse_model_specifier(spec) else {
+                return Err(DjangoError::ImproperlyConfigured(format!(
+                    "--as-migration requires app_label.ModelName, got '{spec}'"
+                )));
+            };
+            let meta = MODELS.get_model(&app, &model_name).ok_or_else(|| {
+                DjangoError::ImproperlyConfigured(format!("Unknown model: {spec}"))
+            })?;
+            let db = connect_database(settings, database)?;
+            let migrations_dir = matches
+                .get_one::<String>("migrations-dir")
+                .map_or("migrations", String::as_str);
+            let path = dump_as_migration(
+                db.as_ref(),
+                meta,
+                Path::new(migrations_dir),
+                matches.get_one::<String>("name").map(String::as_str),
+            )
+            .await?;
+            tracing::info!("Created data migration: {}", path.display());
+            return Ok(());
         }
 
-        // In a full implementation, this would query the database and serialize
-        // the results. For now, we produce an empty array placeholder.
-        let objects: Vec<serde_json::Value> = Vec::new();
-        let serialized = dump_data(&objects, output, indent).await?;
+        let db = connect_database(settings, database)?;
+        let targets = resolve_targets(db.as_ref(), &MODELS, &app_labels).await?;
+        let destination = match output {
+            Some(path) => FixtureOutput::create(Path::new(path))?,
+            None => FixtureOutput::stdout(),
+        };
+        let counts = dump_models(db.as_ref(), &targets, destination, format, indent).await?;
 
-        if output.is_none() {
-            // Write to stdout via spawn_blocking to avoid blocking the runtime
-            let output_clone = serialized;
-            tokio::task::spawn_blocking(move || {
-                println!("{output_clone}");
-            })
-            .await
-            .map_err(|e| DjangoError::InternalServerError(e.to_string()))?;
+        for (label, count) in &counts {
+            tracing::info!("Dumped {count} object(s) from {label}");
+        }
+        if let Some(path) = output {
+            let total: usize = counts.values().sum();
+            tracing::info!("{total} object(s) written to {path}");
         }
 
         Ok(())
@@ -145,6 +616,8 @@ impl ManagementCommand for DumpdataCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use django_rs_db::fields::{FieldDef, FieldType};
+    use django_rs_db::query::compiler::InheritanceType;
     use serde_json::json;
 
     #[test]
@@ -202,4 +675,348 @@ mod tests {
         assert_eq!(cmd.name(), "dumpdata");
         assert_eq!(cmd.help(), "Serialize model data to JSON");
     }
+
+    fn model(
+        app_label: &'static str,
+        model_name: &'static str,
+        db_table: &str,
+        pk: FieldDef,
+    ) -> &'static ModelMeta {
+        Box::leak(Box::new(ModelMeta {
+            app_label,
+            model_name,
+            db_table: db_table.to_string(),
+            verbose_name: model_name.to_string(),
+            verbose_name_plural: format!("{model_name}s"),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields: vec![pk.primary_key()],
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        }))
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn blog_models() -> ModelRegistry {
+        let models = ModelRegistry::new();
+        for (app, name, table) in [
+            ("blog", "post", "blog_post"),
+            ("blog", "tag", "blog_tag"),
+            ("blog_extra", "note", "blog_extra_note"),
+            ("shop", "item", "shop_item"),
+            ("shop", "order", "shop_order"),
+        ] {
+            models.register_meta(model(
+                app,
+                name,
+                table,
+                FieldDef::new("id", FieldType::AutoField),
+            ));
+        }
+        models
+    }
+
+    #[test]
+    fn test_dump_target_for_meta() {
+        let meta = model(
+            "geo",
+            "Country",
+            "countries",
+            FieldDef::new("code", FieldType::CharField),
+        );
+        assert_eq!(
+            DumpTarget::for_meta(meta),
+            DumpTarget {
+                label: "geo.country".into(),
+                table: "countries".into(),
+                pk: "code".into(),
+            }
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn blog_database(posts: usize) -> django_rs_db_backends::SqliteBackend {
+        let db = django_rs_db_backends::SqliteBackend::memory().unwrap();
+        for sql in [
+            "CREATE TABLE blog_post (id INTEGER PRIMARY KEY, title TEXT NOT NULL)",
+            "CREATE TABLE blog_tag (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+            "CREATE TABLE blog_extra_note (id INTEGER PRIMARY KEY)",
+            "CREATE TABLE shop_item (id INTEGER PRIMARY KEY)",
+            "CREATE TABLE django_migrations (id INTEGER PRIMARY KEY)",
+            "INSERT INTO blog_tag (id, name) VALUES (1, 'rust')",
+        ] {
+            db.execute_sql(sql, &[]).await.unwrap();
+        }
+        let insert = format!(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < {posts}) \
+             INSERT INTO blog_post (id, title) SELECT i, 'post ' || i FROM n"
+        );
+        db.execute_sql(&insert, &[]).await.unwrap();
+        db
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_resolve_targets() {
+        let db = blog_database(1).await;
+        let models = blog_models();
+        let labels = |targets: Vec<DumpTarget>| -> Vec<String> {
+            targets.into_iter().map(|t| t.label).collect()
+        };
+        // shop.order has no table yet.
+        assert_eq!(
+            labels(resolve_targets(&db, &models, &[]).await.unwrap()),
+            ["blog.post", "blog.tag", "blog_extra.note", "shop.item"]
+        );
+        assert_eq!(
+            labels(resolve_targets(&db, &models, &["blog"]).await.unwrap()),
+            ["blog.post", "blog.tag"]
+        );
+        assert_eq!(
+            labels(resolve_targets(&db, &models, &["shop.Item"]).await.unwrap()),
+            ["shop.item"]
+        );
+        assert!(resolve_targets(&db, &models, &["news"]).await.is_err());
+        assert!(resolve_targets(&db, &models, &["shop.Cart"]).await.is_err());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_model_rows_batches_by_key() {
+        let db = blog_database(DUMP_BATCH_SIZE + 5).await;
+        let target = DumpTarget::for_meta(blog_models().get_model("blog", "Post").unwrap());
+        let mut rows = ModelRows::new(&target);
+
+        let first = rows.next_batch(&db).await.unwrap().unwrap();
+        assert_eq!(first.len(), DUMP_BATCH_SIZE);
+        assert_eq!(
+            first[0],
+            json!({"model": "blog.post", "pk": 1, "fields": {"title": "post 1"}})
+        );
+        let second = rows.next_batch(&db).await.unwrap().unwrap();
+        assert_eq!(second.len(), 5);
+        assert_eq!(second[0]["pk"], DUMP_BATCH_SIZE + 1);
+        assert!(rows.next_batch(&db).await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dump_models_to_compressed_file() {
+        let db = blog_database(2500).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blog.jsonl.gz");
+        let targets = resolve_targets(&db, &blog_models(), &["blog"])
+            .await
+            .unwrap();
+
+        let output = FixtureOutput::create(&path).unwrap();
+        let counts = dump_models(&db, &targets, output, FixtureFormat::JsonLines, false)
+            .await
+            .unwrap();
+        assert_eq!(counts["blog.post"], 2500);
+        assert_eq!(counts["blog.tag"], 1);
+
+        let mut labels = Vec::new();
+        crate::fixtures::read_fixture_objects(
+            crate::fixtures::open_fixture(&path).unwrap(),
+            |object| {
+                labels.push(object["model"].as_str().unwrap().to_string());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(labels.len(), 2501);
+        assert_eq!(labels.last().unwrap(), "blog.tag");
+    }
+
+    #[tokio::test]
+    async fn test_dump_data_to_compressed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump.json.zst");
+
+        let objects = vec![json!({"pk": 1})];
+        let result = dump_data(&objects, path.to_str(), false).await.unwrap();
+        assert_eq!(result, "[{\"pk\":1}]");
+
+        let compressed = std::fs::read(&path).unwrap();
+        assert_ne!(compressed, result.as_bytes());
+        assert_eq!(
+            zstd::decode_all(&compressed[..]).unwrap(),
+            result.as_bytes()
+        );
+    }
+
+    fn country_fixtures() -> Vec<serde_json::Value> {
+        vec![
+            json!({"model": "geo.country", "pk": 1, "fields": {"code": "FR", "name": "France"}}),
+            json!({"model": "geo.country", "pk": 2, "fields": {"code": "CI", "name": "Côte d'Ivoire"}}),
+        ]
+    }
+
+    #[test]
+    fn test_build_data_migration_sql() {
+        let migration = build_data_migration(
+            DatabaseBackendType::SQLite,
+            "geo",
+            "0002_country_data",
+            "geo_country",
+            "id",
+            vec![("geo".to_string(), "0001_initial".to_string())],
+            &country_fixtures(),
+        )
+        .unwrap();
+
+        assert_eq!(migration.dependencies.len(), 1);
+        let SerializableOperation::RunSQL {
+            sql_forwards,
+            sql_backwards,
+        } = &migration.operations[0]
+        else {
+            panic!("Expected RunSQL");
+        };
+        assert!(
+            sql_forwards.starts_with("INSERT INTO \"geo_country\" (\"id\", \"code\", \"name\")")
+        );
+        assert!(sql_forwards.contains("(1, 'FR', 'France')"));
+        assert!(sql_forwards.contains("'Côte d''Ivoire'"));
+        assert_eq!(
+            sql_backwards,
+            "DELETE FROM \"geo_country\" WHERE \"id\" IN (1, 2);"
+        );
+    }
+
+    #[test]
+    fn test_build_data_migration_empty() {
+        let result = build_data_migration(
+            DatabaseBackendType::SQLite,
+            "geo",
+            "0002_data",
+            "geo_country",
+            "id",
+            vec![],
+            &[],
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_data_migration_quotes_for_backend() {
+        let objects = [json!({"pk": 1, "fields": {"say \"hi\"": "C:\\temp"}})];
+        let migration = build_data_migration(
+            DatabaseBackendType::MySQL,
+            "geo",
+            "0002_data",
+            "geo.country",
+            "id",
+            vec![],
+            &objects,
+        )
+        .unwrap();
+        let SerializableOperation::RunSQL { sql_forwards, .. } = &migration.operations[0] else {
+            panic!("Expected RunSQL");
+        };
+        assert_eq!(
+            sql_forwards,
+            "INSERT INTO \"geo\".\"country\" (\"id\", \"say \"\"hi\"\"\") VALUES\n(1, 'C:\\\\temp');"
+        );
+    }
+
+    #[test]
+    fn test_sql_literal() {
+        let sqlite = DatabaseBackendType::SQLite;
+        assert_eq!(sql_literal(sqlite, &json!(null)), "NULL");
+        assert_eq!(sql_literal(sqlite, &json!(true)), "TRUE");
+        assert_eq!(sql_literal(sqlite, &json!(2.5)), "2.5");
+        assert_eq!(sql_literal(sqlite, &json!({"a": 1})), "'{\"a\":1}'");
+        assert_eq!(sql_literal(sqlite, &json!("a\\b")), "'a\\b'");
+        assert_eq!(
+            sql_literal(DatabaseBackendType::MySQL, &json!("it's a\\b")),
+            "'it''s a\\\\b'"
+        );
+    }
+
+    fn write_initial(dir: &Path) {
+        let initial = SerializableMigration {
+            app_label: "geo".into(),
+            name: "0001_initial".into(),
+            dependencies: vec![],
+            initial: true,
+            atomic: true,
+            operations: vec![],
+        };
+        initial
+            .write_to_file(&migration_file_path(dir, "geo", "0001_initial"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_write_data_migration_depends_on_leaf() {
+        let dir = tempfile::tempdir().unwrap();
+        write_initial(dir.path());
+        let meta = model(
+            "geo",
+            "Country",
+            "geo_country",
+            FieldDef::new("id", FieldType::AutoField),
+        );
+
+        let path = write_data_migration(
+            dir.path(),
+            DatabaseBackendType::SQLite,
+            meta,
+            None,
+            &country_fixtures(),
+        )
+        .unwrap();
+        assert!(path.ends_with("geo/0002_country_data.json"));
+
+        let written = SerializableMigration::read_from_file(&path).unwrap();
+        assert_eq!(
+            written.dependencies,
+            vec![("geo".to_string(), "0001_initial".to_string())]
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dump_as_migration_uses_model_table_and_key() {
+        let db = django_rs_db_backends::SqliteBackend::memory().unwrap();
+        for sql in [
+            "CREATE TABLE countries (code TEXT PRIMARY KEY, name TEXT NOT NULL)",
+            "INSERT INTO countries (code, name) VALUES ('FR', 'France'), ('CI', 'Ivory Coast')",
+        ] {
+            db.execute_sql(sql, &[]).await.unwrap();
+        }
+        let dir = tempfile::tempdir().unwrap();
+        write_initial(dir.path());
+        let meta = model(
+            "geo",
+            "Country",
+            "countries",
+            FieldDef::new("code", FieldType::CharField),
+        );
+
+        let path = dump_as_migration(&db, meta, dir.path(), None)
+            .await
+            .unwrap();
+        let written = SerializableMigration::read_from_file(&path).unwrap();
+        let SerializableOperation::RunSQL {
+            sql_forwards,
+            sql_backwards,
+        } = &written.operations[0]
+        else {
+            panic!("Expected RunSQL");
+        };
+        assert_eq!(
+            sql_forwards,
+            "INSERT INTO \"countries\" (\"code\", \"name\") VALUES\n('CI', 'Ivory Coast'),\n('FR', 'France');"
+        );
+        assert_eq!(
+            sql_backwards,
+            "DELETE FROM \"countries\" WHERE \"code\" IN ('CI', 'FR');"
+        );
+    }
 }
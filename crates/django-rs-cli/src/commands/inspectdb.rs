@@ -7,8 +7,10 @@ use std::fmt::Write as _;
 
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db::{DatabaseBackendType, DbExecutor, Value};
 
 use crate::command::ManagementCommand;
+use crate::fixtures::connect_database;
 
 /// Introspects the database and generates Model code.
 ///
@@ -36,6 +38,8 @@ pub struct ColumnInfo {
 pub struct TableInfo {
     /// The table name.
     pub name: String,
+    /// The schema the table lives in, when inspecting a specific schema.
+    pub schema: Option<String>,
     /// The columns in this table.
     pub columns: Vec<ColumnInfo>,
 }
@@ -111,17 +115,162 @@ pub fn table_name_to_struct_name(table_name: &str) -> String {
         .collect()
 }
 
+/// Reads the tables of `schema` and their columns, in name order.
+///
+/// Without a schema, the connection's default schema is read: PostgreSQL's
+/// `current_schema()` or MySQL's current database. `only` restricts the
+/// result to the named tables when it is not empty.
+///
+/// # Errors
+///
+/// Returns an error if a schema is given for SQLite, which has none, or if
+/// a query fails.
+pub async fn introspect_tables(
+    db: &dyn DbExecutor,
+    schema: Option<&str>,
+    only: &[&str],
+) -> Result<Vec<TableInfo>, DjangoError> {
+    let backend = db.backend_type();
+    if backend == DatabaseBackendType::SQLite && schema.is_some() {
+        return Err(DjangoError::ImproperlyConfigured(
+            "SQLite databases have no schemas to inspect".into(),
+        ));
+    }
+
+    let mut params = Vec::new();
+    let sql = match backend {
+        DatabaseBackendType::SQLite => "SELECT name FROM sqlite_master WHERE type = 'table' \
+             AND name NOT LIKE 'sqlite_%' ORDER BY name"
+            .to_string(),
+        _ => format!(
+            "SELECT table_name AS name FROM information_schema.tables \
+             WHERE table_schema = {} AND table_type = 'BASE TABLE' ORDER BY table_name",
+            schema_filter(backend, schema, &mut params)
+        ),
+    };
+    let mut tables = Vec::new();
+    for row in db.query(&sql, &params).await? {
+        let name: String = row.get("name")?;
+        if only.is_empty() || only.contains(&name.as_str()) {
+            let columns = introspect_columns(db, schema, &name).await?;
+            tables.push(TableInfo {
+                name,
+                schema: schema.map(String::from),
+                columns,
+            });
+        }
+    }
+    Ok(tables)
+}
+
+/// Reads the columns of `table`, in table order.
+async fn introspect_columns(
+    db: &dyn DbExecutor,
+    schema: Option<&str>,
+    table: &str,
+) -> Result<Vec<ColumnInfo>, DjangoError> {
+    let backend = db.backend_type();
+    let mut params = Vec::new();
+    let sql = match backend {
+        DatabaseBackendType::SQLite => format!(
+            "SELECT name, type AS data_type, \"notnull\" = 0 AS nullable, pk > 0 AS primary_key \
+             FROM pragma_table_info('{}') ORDER BY cid",
+            table.replace('\'', "''")
+        ),
+        DatabaseBackendType::PostgreSQL => {
+            let schema = schema_filter(backend, schema, &mut params);
+            params.push(Value::from(table));
+            let table = format!("${}", params.len());
+            format!(
+                "SELECT c.column_name AS name, c.data_type AS data_type, \
+                 c.is_nullable = 'YES' AS nullable, EXISTS (\
+                 SELECT 1 FROM information_schema.table_constraints tc \
+                 JOIN information_schema.key_column_usage k \
+                 ON k.constraint_name = tc.constraint_name AND k.table_schema = tc.table_schema \
+                 WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_schema = c.table_schema \
+                 AND tc.table_name = c.table_name AND k.column_name = c.column_name\
+                 ) AS primary_key \
+                 FROM information_schema.columns c \
+                 WHERE c.table_schema = {schema} AND c.table_name = {table} ORDER BY c.ordinal_position"
+            )
+        }
+        DatabaseBackendType::MySQL => {
+            let schema = schema_filter(backend, schema, &mut params);
+            params.push(Value::from(table));
+            format!(
+                "SELECT column_name AS name, column_type AS data_type, \
+                 is_nullable = 'YES' AS nullable, column_key = 'PRI' AS primary_key \
+                 FROM information_schema.columns \
+                 WHERE table_schema = {schema} AND table_name = ? ORDER BY ordinal_position"
+            )
+        }
+    };
+    db.query(&sql, &params)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(ColumnInfo {
+                name: row.get("name")?,
+                data_type: row.get("data_type")?,
+                nullable: flag(row.get_value("nullable")),
+                primary_key: flag(row.get_value("primary_key")),
+                foreign_key: None,
+            })
+        })
+        .collect()
+}
+
+/// Reads a yes/no column, which SQLite and MySQL return as an integer.
+fn flag(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Int(i)) => *i != 0,
+        _ => false,
+    }
+}
+
+/// Returns the SQL naming the schema to inspect, binding `schema` as the
+/// first parameter when one is given.
+fn schema_filter(
+    backend: DatabaseBackendType,
+    schema: Option<&str>,
+    params: &mut Vec<Value>,
+) -> &'static str {
+    match (schema, backend) {
+        (Some(schema), DatabaseBackendType::PostgreSQL) => {
+            params.push(Value::from(schema));
+            "$1"
+        }
+        (Some(schema), _) => {
+            params.push(Value::from(schema));
+            "?"
+        }
+        (None, DatabaseBackendType::PostgreSQL) => "current_schema()",
+        (None, _) => "DATABASE()",
+    }
+}
+
 /// Generates a Rust model struct and trait implementation from a `TableInfo`.
 pub fn generate_model_code(table: &TableInfo) -> String {
     let struct_name = table_name_to_struct_name(&table.name);
     let mut code = String::new();
 
     // Struct definition
+    let qualified_name = table.schema.as_ref().map_or_else(
+        || table.name.clone(),
+        |schema| format!("{schema}.{}", table.name),
+    );
     let _ = writeln!(
         code,
-        "/// Auto-generated model for the `{}` table.",
-        table.name
+        "/// Auto-generated model for the `{qualified_name}` table."
     );
+    if let Some(ref schema) = table.schema {
+        let _ = writeln!(
+            code,
+            "///\n/// Use `#[model(table = \"{}\", db_schema = \"{schema}\")]`.",
+            table.name
+        );
+    }
     let _ = writeln!(code, "pub struct {struct_name} {{");
 
     for col in &table.columns {
@@ -160,39 +309,38 @@ impl ManagementCommand for InspectdbCommand {
                 .default_value("default")
                 .help("Database alias to inspect"),
         )
+        .arg(
+            clap::Arg::new("schema")
+                .long("schema")
+                .help("Only inspect tables in this schema (PostgreSQL, MySQL)"),
+        )
     }
 
     async fn handle(
         &self,
         matches: &clap::ArgMatches,
-        _settings: &Settings,
+        settings: &Settings,
     ) -> Result<(), DjangoError> {
         let database = matches
             .get_one::<String>("database")
             .map_or("default", String::as_str);
-        let tables: Vec<&String> = matches
+        let tables: Vec<&str> = matches
             .get_many::<String>("table")
-            .map_or_else(Vec::new, Iterator::collect);
+            .map_or_else(Vec::new, |tables| tables.map(String::as_str).collect());
 
-        tracing::info!("Inspecting database '{database}'");
+        let schema = matches.get_one::<String>("schema").map(String::as_str);
 
-        if tables.is_empty() {
-            tracing::info!("Inspecting all tables");
-        } else {
-            tracing::info!(
-                "Inspecting tables: {}",
-                tables
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
+        tracing::info!("Inspecting database '{database}'");
+        if let Some(schema) = schema {
+            tracing::info!("Inspecting schema '{schema}'");
         }
 
-        // In a full implementation, this would connect to the database and
-        // introspect the schema. For demonstration, we show what the output
-        // format looks like.
-        tracing::info!("Database inspection complete. In a full implementation, model code would be printed to stdout.");
+        let db = connect_database(settings, database)?;
+        let tables = introspect_tables(db.as_ref(), schema, &tables).await?;
+        let code: Vec<String> = tables.iter().map(generate_model_code).collect();
+        let mut stdout = std::io::stdout().lock();
+        std::io::Write::write_all(&mut stdout, code.join("\n").as_bytes())?;
+        tracing::info!("Inspected {} table(s)", tables.len());
 
         Ok(())
     }
@@ -247,6 +395,7 @@ mod tests {
     fn test_generate_model_code() {
         let table = TableInfo {
             name: "blog_post".to_string(),
+            schema: None,
             columns: vec![
                 ColumnInfo {
                     name: "id".to_string(),
@@ -283,6 +432,7 @@ mod tests {
     fn test_generate_model_code_with_nullable_pk() {
         let table = TableInfo {
             name: "test".to_string(),
+            schema: None,
             columns: vec![ColumnInfo {
                 name: "id".to_string(),
                 data_type: "INTEGER".to_string(),
@@ -298,6 +448,68 @@ mod tests {
         assert!(!code.contains("Option"));
     }
 
+    #[test]
+    fn test_generate_model_code_with_schema() {
+        let table = TableInfo {
+            name: "invoice".to_string(),
+            schema: Some("billing".to_string()),
+            columns: vec![],
+        };
+
+        let code = generate_model_code(&table);
+        assert!(code.contains("model for the `billing.invoice` table"));
+        assert!(code.contains("#[model(table = \"invoice\", db_schema = \"billing\")]"));
+        assert!(code.contains("pub struct Invoice"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_introspect_tables() {
+        let db = django_rs_db_backends::SqliteBackend::memory().unwrap();
+        for sql in [
+            "CREATE TABLE blog_post (id INTEGER PRIMARY KEY, title TEXT NOT NULL, body TEXT)",
+            "CREATE TABLE blog_tag (id INTEGER PRIMARY KEY)",
+        ] {
+            db.execute_sql(sql, &[]).await.unwrap();
+        }
+
+        let tables = introspect_tables(&db, None, &[]).await.unwrap();
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["blog_post", "blog_tag"]);
+        let columns = &tables[0].columns;
+        assert_eq!(columns.len(), 3);
+        assert!(columns[0].primary_key && !columns[1].primary_key);
+        assert_eq!(columns[1].data_type, "TEXT");
+        assert!(!columns[1].nullable && columns[2].nullable);
+
+        let tables = introspect_tables(&db, None, &["blog_tag"]).await.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert!(introspect_tables(&db, Some("billing"), &[]).await.is_err());
+    }
+
+    #[test]
+    fn test_schema_filter() {
+        let mut params = Vec::new();
+        assert_eq!(
+            schema_filter(DatabaseBackendType::PostgreSQL, None, &mut params),
+            "current_schema()"
+        );
+        assert_eq!(
+            schema_filter(DatabaseBackendType::MySQL, None, &mut params),
+            "DATABASE()"
+        );
+        assert!(params.is_empty());
+        assert_eq!(
+            schema_filter(
+                DatabaseBackendType::PostgreSQL,
+                Some("billing"),
+                &mut params
+            ),
+            "$1"
+        );
+        assert_eq!(params, vec![Value::from("billing")]);
+    }
+
     #[test]
     fn test_command_metadata() {
         let cmd = InspectdbCommand;
@@ -2,73 +2,358 @@
 //!
 //! Loads serialized data from fixture files (JSON) into the database.
 //! This mirrors Django's `loaddata` command.
+//!
+//! Fixtures are read one object at a time, so files of millions of rows are
+//! never held in memory. Files ending in `.gz` or `.zst` are decompressed on
+//! the fly, and both JSON arrays and JSON lines are accepted. Each file is
+//! loaded in one transaction with foreign key checks deferred to its end,
+//! so rows may reference objects that appear later in the same file.
+//!
+//! Objects are matched to models of the
+//! [model registry](django_rs_db::registry), which gives their table,
+//! primary key and columns.
+
+use std::collections::BTreeMap;
+use std::path::Path;
 
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db::fields::FieldType;
+use django_rs_db::registry::{ModelRegistry, MODELS};
+use django_rs_db::transactions::TransactionManager;
+use django_rs_db::{DatabaseBackendType, DbExecutor, ModelMeta, SqlCompiler, Value};
 
 use crate::command::ManagementCommand;
-use crate::serialization::{JsonSerializer, Serializer};
+use crate::fixtures::{connect_database, open_fixture, read_fixture_objects};
+
+/// Number of objects between two progress messages.
+pub const LOAD_PROGRESS_INTERVAL: usize = 10_000;
+
+/// Extensions tried, in order, for a fixture name given without one.
+const FIXTURE_EXTENSIONS: &[&str] = &[
+    "json",
+    "jsonl",
+    "json.gz",
+    "json.zst",
+    "jsonl.gz",
+    "jsonl.zst",
+];
 
 /// Loads data from fixture files into the database.
 ///
 /// Reads JSON fixture files, deserializes their content, and inserts
 /// the objects into the appropriate database tables. Supports loading
-/// multiple fixtures in a single invocation.
+/// multiple fixtures in a single invocation, into the `--database` alias.
 pub struct LoaddataCommand;
 
 /// Loads fixture data from a JSON file at the given path.
 ///
-/// Returns the parsed objects as a vector of JSON values.
+/// The file may be a JSON array or JSON lines, optionally gzip- or
+/// zstd-compressed. Returns the parsed objects as a vector of JSON values.
 pub async fn load_fixture_file(path: &str) -> Result<Vec<serde_json::Value>, DjangoError> {
-    let content = tokio::fs::read_to_string(path).await.map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            DjangoError::NotFound(format!("Fixture file not found: {path}"))
-        } else {
-            DjangoError::IoError(e)
-        }
-    })?;
-
-    let serializer = JsonSerializer;
-    serializer.deserialize(&content)
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut objects = Vec::new();
+        read_fixture_objects(open_fixture(Path::new(&path))?, |object| {
+            objects.push(object);
+            Ok(())
+        })?;
+        Ok(objects)
+    })
+    .await
+    .map_err(|e| DjangoError::InternalServerError(e.to_string()))?
 }
 
 /// Searches for a fixture file in the configured fixture directories and
 /// the standard locations (the current directory, and `<app>/fixtures/`).
 ///
+/// A name without a fixture extension is tried with `.json`, `.jsonl` and
+/// their `.gz`/`.zst` compressed variants, in that order.
+///
 /// Returns the resolved path, or `None` if the fixture was not found.
 pub fn find_fixture(name: &str, fixture_dirs: &[String]) -> Option<String> {
     // Check if the name is already a path to an existing file
-    let path = std::path::Path::new(name);
+    let path = Path::new(name);
     if path.exists() && path.is_file() {
         return Some(name.to_string());
     }
 
-    // Try adding .json extension
-    let with_ext = if path
-        .extension()
-        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    let lower = name.to_ascii_lowercase();
+    let candidates: Vec<String> = if FIXTURE_EXTENSIONS
+        .iter()
+        .any(|ext| lower.ends_with(&format!(".{ext}")))
     {
-        name.to_string()
+        vec![name.to_string()]
     } else {
-        format!("{name}.json")
+        FIXTURE_EXTENSIONS
+            .iter()
+            .map(|ext| format!("{name}.{ext}"))
+            .collect()
     };
 
-    let path = std::path::Path::new(&with_ext);
-    if path.exists() && path.is_file() {
-        return Some(with_ext);
+    for candidate in &candidates {
+        let path = Path::new(candidate);
+        if path.exists() && path.is_file() {
+            return Some(candidate.clone());
+        }
     }
 
     // Search configured fixture directories
     for dir in fixture_dirs {
-        let candidate = std::path::Path::new(dir).join(&with_ext);
-        if candidate.exists() && candidate.is_file() {
-            return candidate.to_str().map(String::from);
+        for candidate in &candidates {
+            let candidate = Path::new(dir).join(candidate);
+            if candidate.exists() && candidate.is_file() {
+                return candidate.to_str().map(String::from);
+            }
         }
     }
 
     None
 }
 
+/// The outcome of loading one fixture file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadSummary {
+    /// Number of objects loaded per model label (e.g. `blog.post`).
+    pub per_model: BTreeMap<String, usize>,
+}
+
+impl LoadSummary {
+    /// Returns the total number of objects loaded.
+    pub fn total(&self) -> usize {
+        self.per_model.values().sum()
+    }
+}
+
+/// Loads the fixture file at `path` into `db`.
+///
+/// Objects are parsed by a blocking task and inserted as they arrive, into
+/// the tables of their models in `models`; an object whose primary key
+/// already exists replaces that row. The whole file is loaded in one
+/// transaction with foreign key checks deferred until its end, so the order
+/// of objects within the file does not matter. If any object fails to load,
+/// or a foreign key is left dangling, nothing from the file is kept.
+pub async fn load_fixture(
+    db: &dyn DbExecutor,
+    models: &ModelRegistry,
+    path: &Path,
+) -> Result<LoadSummary, DjangoError> {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<serde_json::Value>(1024);
+    let owned_path = path.to_path_buf();
+    let reader = tokio::task::spawn_blocking(move || {
+        read_fixture_objects(open_fixture(&owned_path)?, |object| {
+            // A closed channel means the loader stopped on an error, which
+            // is reported instead of this one.
+            tx.blocking_send(object)
+                .map_err(|_| DjangoError::InternalServerError("Fixture load aborted".into()))
+        })
+    });
+
+    let backend = db.backend_type();
+    let tm = TransactionManager::new(db);
+    tm.begin().await?;
+    let mut summary = LoadSummary::default();
+    let mut loaded_models: Vec<&ModelMeta> = Vec::new();
+    let load = async {
+        for sql in defer_constraints_sql(backend) {
+            tm.execute_sql(sql, &[]).await?;
+        }
+        let mut loaded = 0;
+        while let Some(object) = rx.recv().await {
+            let (label, meta) = insert_object(&tm, backend, models, object).await?;
+            if !loaded_models.iter().any(|m| std::ptr::eq(*m, meta)) {
+                loaded_models.push(meta);
+            }
+            *summary.per_model.entry(label).or_insert(0) += 1;
+            loaded += 1;
+            if loaded % LOAD_PROGRESS_INTERVAL == 0 {
+                tracing::info!("{}: {loaded} object(s) loaded", path.display());
+            }
+        }
+        for sql in restore_constraints_sql(backend) {
+            tm.execute_sql(sql, &[]).await?;
+        }
+        if backend == DatabaseBackendType::MySQL {
+            check_foreign_keys(&tm, models, &loaded_models).await?;
+        }
+        Ok::<_, DjangoError>(())
+    };
+    let result = load.await;
+    rx.close();
+    let read = reader
+        .await
+        .map_err(|e| DjangoError::InternalServerError(e.to_string()))?;
+
+    // Deferred foreign keys are checked on commit; a violation fails it and
+    // leaves the transaction open.
+    let result = match (result, read) {
+        (Ok(()), Ok(_)) => tm.commit().await,
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    match result {
+        Ok(()) => Ok(summary),
+        Err(e) => {
+            tm.rollback().await?;
+            for sql in restore_constraints_sql(backend) {
+                db.execute_sql(sql, &[]).await?;
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Statements that postpone foreign key checks until the transaction ends.
+///
+/// On PostgreSQL this relies on the constraints being `DEFERRABLE`, as the
+/// schema editor creates them. MySQL cannot defer constraints, so its checks
+/// are switched off for the load instead, and [`check_foreign_keys`] looks
+/// for dangling references before the commit.
+fn defer_constraints_sql(backend: DatabaseBackendType) -> &'static [&'static str] {
+    match backend {
+        DatabaseBackendType::SQLite => &["PRAGMA defer_foreign_keys = ON"],
+        DatabaseBackendType::PostgreSQL => &["SET CONSTRAINTS ALL DEFERRED"],
+        DatabaseBackendType::MySQL => &["SET FOREIGN_KEY_CHECKS = 0"],
+    }
+}
+
+/// Statements that undo [`defer_constraints_sql`] once a file is loaded.
+fn restore_constraints_sql(backend: DatabaseBackendType) -> &'static [&'static str] {
+    match backend {
+        DatabaseBackendType::MySQL => &["SET FOREIGN_KEY_CHECKS = 1"],
+        DatabaseBackendType::SQLite | DatabaseBackendType::PostgreSQL => &[],
+    }
+}
+
+/// Fails if a foreign key of the rows of `loaded` points to no row, as
+/// Django's `check_constraints` does for backends that cannot defer them.
+///
+/// Relations to models outside `models` are not checked.
+async fn check_foreign_keys(
+    db: &dyn DbExecutor,
+    models: &ModelRegistry,
+    loaded: &[&ModelMeta],
+) -> Result<(), DjangoError> {
+    let backend = db.backend_type();
+    for meta in loaded {
+        for field in &meta.fields {
+            let (FieldType::ForeignKey { to, .. } | FieldType::OneToOneField { to, .. }) =
+                &field.field_type
+            else {
+                continue;
+            };
+            let Some(target) = models.resolve(to) else {
+                continue;
+            };
+            let sql = format!(
+                "SELECT t.\"{pk}\" AS pk, t.\"{column}\" AS value FROM {table} t \
+                 LEFT JOIN {target_table} r ON t.\"{column}\" = r.\"{target_pk}\" \
+                 WHERE t.\"{column}\" IS NOT NULL AND r.\"{target_pk}\" IS NULL LIMIT 1",
+                pk = meta.pk_column(),
+                column = field.column,
+                table = backend.quote_table_name(&meta.db_table),
+                target_table = backend.quote_table_name(&target.db_table),
+                target_pk = target.pk_column(),
+            );
+            if let Some(row) = db.query(&sql, &[]).await?.first() {
+                return Err(DjangoError::IntegrityError(format!(
+                    "The row in table '{}' with primary key '{}' has an invalid foreign key: \
+                     {}.{} contains a value '{}' that does not have a corresponding value in {}.{}",
+                    meta.db_table,
+                    row.get_value("pk")
+                        .map_or_else(String::new, ToString::to_string),
+                    meta.db_table,
+                    field.column,
+                    row.get_value("value")
+                        .map_or_else(String::new, ToString::to_string),
+                    target.db_table,
+                    target.pk_column(),
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Inserts or replaces one fixture object and returns its model label and
+/// metadata.
+///
+/// Fields are matched to the model's fields by name, or by column (so both
+/// `author` and `author_id` name a foreign key).
+async fn insert_object(
+    db: &dyn DbExecutor,
+    backend: DatabaseBackendType,
+    models: &ModelRegistry,
+    object: serde_json::Value,
+) -> Result<(String, &'static ModelMeta), DjangoError> {
+    let serde_json::Value::Object(mut object) = object else {
+        return Err(DjangoError::SerializationError(format!(
+            "Fixture entry is not an object: {object}"
+        )));
+    };
+    let Some(serde_json::Value::String(label)) = object.remove("model") else {
+        return Err(DjangoError::SerializationError(
+            "Fixture object has no 'model'".into(),
+        ));
+    };
+    let Some((app_label, model_name)) = label.split_once('.') else {
+        return Err(DjangoError::SerializationError(format!(
+            "Invalid model label '{label}' (expected app_label.model_name)"
+        )));
+    };
+    let meta = models.get_model(app_label, model_name).ok_or_else(|| {
+        DjangoError::SerializationError(format!("Invalid model identifier: '{label}'"))
+    })?;
+    let pk = object.remove("pk").ok_or_else(|| {
+        DjangoError::SerializationError(format!("Fixture object for '{label}' has no 'pk'"))
+    })?;
+    let fields = match object.remove("fields") {
+        Some(serde_json::Value::Object(fields)) => fields,
+        None => serde_json::Map::new(),
+        Some(other) => {
+            return Err(DjangoError::SerializationError(format!(
+                "Fixture 'fields' of '{label}' is not an object: {other}"
+            )))
+        }
+    };
+
+    let pk_column = meta.pk_column();
+    let mut values = vec![(pk_column, fixture_value(pk))];
+    for (name, value) in fields {
+        let field = meta
+            .fields
+            .iter()
+            .find(|f| f.name == name || f.column == name)
+            .ok_or_else(|| {
+                DjangoError::SerializationError(format!("'{label}' has no field named '{name}'"))
+            })?;
+        values.push((field.column.as_str(), fixture_value(value)));
+    }
+    let update: Vec<&str> = values[1..].iter().map(|(column, _)| *column).collect();
+    let (sql, params) =
+        SqlCompiler::new(backend).compile_upsert(&meta.db_table, &values, &[pk_column], &update);
+    if backend == DatabaseBackendType::MySQL {
+        db.execute_sql(&sql, &params).await?;
+    } else {
+        db.query(&sql, &params).await?;
+    }
+    Ok((label, meta))
+}
+
+/// Converts a fixture field value to a database value.
+///
+/// Arrays and objects are stored as JSON.
+fn fixture_value(value: serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .map_or_else(|| Value::Float(n.as_f64().unwrap_or_default()), Value::Int),
+        serde_json::Value::String(s) => Value::String(s),
+        other => Value::Json(other),
+    }
+}
+
 #[async_trait]
 impl ManagementCommand for LoaddataCommand {
     fn name(&self) -> &'static str {
@@ -97,7 +382,7 @@ impl ManagementCommand for LoaddataCommand {
     async fn handle(
         &self,
         matches: &clap::ArgMatches,
-        _settings: &Settings,
+        settings: &Settings,
     ) -> Result<(), DjangoError> {
         let fixtures: Vec<&String> = matches
             .get_many::<String>("fixture")
@@ -108,26 +393,34 @@ impl ManagementCommand for LoaddataCommand {
 
         tracing::info!("Loading data into database '{database}'");
 
-        let mut total_objects = 0;
+        // Resolve every fixture before touching the database.
+        let resolved = fixtures
+            .iter()
+            .map(|name| {
+                find_fixture(name, &[])
+                    .ok_or_else(|| DjangoError::NotFound(format!("Fixture not found: {name}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        for fixture_name in &fixtures {
-            let resolved = find_fixture(fixture_name, &[]).ok_or_else(|| {
-                DjangoError::NotFound(format!("Fixture not found: {fixture_name}"))
-            })?;
-
-            tracing::info!("Loading fixture: {resolved}");
+        let db = connect_database(settings, database)?;
+        let mut total_objects = 0;
 
-            let objects = load_fixture_file(&resolved).await?;
-            let count = objects.len();
-            total_objects += count;
+        for path in &resolved {
+            tracing::info!("Loading fixture: {path}");
 
-            tracing::info!("Loaded {count} object(s) from {resolved}");
+            let summary = load_fixture(db.as_ref(), &MODELS, Path::new(path)).await?;
+            for (label, count) in &summary.per_model {
+                tracing::info!("  {label}: {count} object(s)");
+            }
+            total_objects += summary.total();
 
-            // In a full implementation, this would insert/update each object
-            // in the database based on its model and pk.
+            tracing::info!("Loaded {} object(s) from {path}", summary.total());
         }
 
-        tracing::info!("Loaded {total_objects} object(s) total");
+        tracing::info!(
+            "Installed {total_objects} object(s) from {} fixture(s)",
+            resolved.len()
+        );
 
         Ok(())
     }
@@ -136,6 +429,10 @@ impl ManagementCommand for LoaddataCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "sqlite")]
+    use django_rs_db::fields::{FieldDef, OnDelete};
+    #[cfg(feature = "sqlite")]
+    use django_rs_db::query::compiler::InheritanceType;
     use serde_json::json;
 
     #[tokio::test]
@@ -209,6 +506,230 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_find_fixture_compressed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("big.jsonl.zst"), "").unwrap();
+
+        let dirs = vec![dir.path().to_str().unwrap().to_string()];
+        let result = find_fixture("big", &dirs).unwrap();
+        assert!(result.ends_with("big.jsonl.zst"));
+        assert!(find_fixture("big.json.gz", &dirs).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_load_fixture_file_compressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, br#"[{"model": "auth.user", "pk": 1}]"#).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let objects = load_fixture_file(path.to_str().unwrap()).await.unwrap();
+        assert_eq!(objects, vec![json!({"model": "auth.user", "pk": 1})]);
+    }
+
+    #[test]
+    fn test_fixture_value() {
+        assert_eq!(fixture_value(json!(null)), Value::Null);
+        assert_eq!(fixture_value(json!(3)), Value::Int(3));
+        assert_eq!(fixture_value(json!(2.5)), Value::Float(2.5));
+        assert_eq!(fixture_value(json!("x")), Value::String("x".into()));
+        assert_eq!(fixture_value(json!([1])), Value::Json(json!([1])));
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn model(model_name: &'static str, fields: Vec<FieldDef>) -> &'static ModelMeta {
+        Box::leak(Box::new(ModelMeta {
+            app_label: "blog",
+            model_name,
+            db_table: format!("blog_{model_name}"),
+            verbose_name: model_name.to_string(),
+            verbose_name_plural: format!("{model_name}s"),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields,
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        }))
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn blog_models() -> ModelRegistry {
+        let models = ModelRegistry::new();
+        models.register_meta(model(
+            "author",
+            vec![
+                FieldDef::new("id", FieldType::AutoField).primary_key(),
+                FieldDef::new("name", FieldType::CharField),
+            ],
+        ));
+        models.register_meta(model(
+            "post",
+            vec![
+                FieldDef::new("id", FieldType::AutoField).primary_key(),
+                FieldDef::new("title", FieldType::CharField),
+                FieldDef::new(
+                    "author",
+                    FieldType::ForeignKey {
+                        to: "blog.author".into(),
+                        on_delete: OnDelete::Cascade,
+                        related_name: None,
+                    },
+                )
+                .column("author_id"),
+            ],
+        ));
+        models
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn blog_database() -> django_rs_db_backends::SqliteBackend {
+        let db = django_rs_db_backends::SqliteBackend::memory().unwrap();
+        db.execute_sql(
+            "CREATE TABLE blog_author (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+            &[],
+        )
+        .await
+        .unwrap();
+        db.execute_sql(
+            "CREATE TABLE blog_post (id INTEGER PRIMARY KEY, title TEXT NOT NULL, \
+             author_id INTEGER NOT NULL REFERENCES blog_author (id))",
+            &[],
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn write_lines(path: &std::path::Path, objects: &[serde_json::Value]) {
+        let mut lines = String::new();
+        for object in objects {
+            lines.push_str(&object.to_string());
+            lines.push('\n');
+        }
+        std::fs::write(path, lines).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_load_fixture_defers_foreign_keys() {
+        let db = blog_database().await;
+        let models = blog_models();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blog.jsonl");
+        // The post references an author that only appears later in the file.
+        write_lines(
+            &path,
+            &[
+                json!({"model": "blog.post", "pk": 1, "fields": {"title": "Hi", "author_id": 7}}),
+                json!({"model": "blog.post", "pk": 2, "fields": {"title": "Yo", "author": 7}}),
+                json!({"model": "blog.Author", "pk": 7, "fields": {"name": "Ann"}}),
+            ],
+        );
+
+        let summary = load_fixture(&db, &models, &path).await.unwrap();
+        assert_eq!(summary.per_model["blog.post"], 2);
+        assert_eq!(summary.per_model["blog.Author"], 1);
+        assert_eq!(summary.total(), 3);
+
+        // Loading again replaces the rows instead of failing on their keys.
+        write_lines(
+            &path,
+            &[
+                json!({"model": "blog.post", "pk": 1, "fields": {"title": "Edited", "author_id": 7}}),
+            ],
+        );
+        load_fixture(&db, &models, &path).await.unwrap();
+        let row = db
+            .query_one("SELECT title FROM blog_post WHERE id = 1", &[])
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String>("title").unwrap(), "Edited");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_load_fixture_rolls_back_dangling_foreign_key() {
+        let db = blog_database().await;
+        let models = blog_models();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blog.json");
+        std::fs::write(
+            &path,
+            json!([
+                {"model": "blog.author", "pk": 1, "fields": {"name": "Ann"}},
+                {"model": "blog.post", "pk": 1, "fields": {"title": "Hi", "author_id": 99}},
+            ])
+            .to_string(),
+        )
+        .unwrap();
+
+        assert!(load_fixture(&db, &models, &path).await.is_err());
+        let row = db
+            .query_one("SELECT COUNT(*) AS n FROM blog_author", &[])
+            .await
+            .unwrap();
+        assert_eq!(row.get::<i64>("n").unwrap(), 0);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_load_fixture_invalid_object() {
+        let db = blog_database().await;
+        let models = blog_models();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.jsonl");
+        write_lines(&path, &[json!({"model": "blog", "pk": 1})]);
+
+        let result = load_fixture(&db, &models, &path).await;
+        assert!(matches!(result, Err(DjangoError::SerializationError(_))));
+
+        write_lines(&path, &[json!({"model": "blog.comment", "pk": 1})]);
+        let result = load_fixture(&db, &models, &path).await;
+        assert!(matches!(result, Err(DjangoError::SerializationError(_))));
+
+        write_lines(
+            &path,
+            &[json!({"model": "blog.author", "pk": 1, "fields": {"nickname": "A"}})],
+        );
+        let result = load_fixture(&db, &models, &path).await;
+        assert!(matches!(result, Err(DjangoError::SerializationError(_))));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_check_foreign_keys() {
+        let db = blog_database().await;
+        let models = blog_models();
+        let post = models.get_model("blog", "post").unwrap();
+        db.execute_sql("PRAGMA foreign_keys = OFF", &[])
+            .await
+            .unwrap();
+        db.execute_sql(
+            "INSERT INTO blog_post (id, title, author_id) VALUES (3, 'Lost', 42)",
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let err = check_foreign_keys(&db, &models, &[post]).await.unwrap_err();
+        let DjangoError::IntegrityError(message) = err else {
+            panic!("expected an integrity error");
+        };
+        assert!(message.contains("primary key '3'"), "{message}");
+        assert!(message.contains("value '42'"), "{message}");
+
+        db.execute_sql("INSERT INTO blog_author (id, name) VALUES (42, 'Ann')", &[])
+            .await
+            .unwrap();
+        check_foreign_keys(&db, &models, &[post]).await.unwrap();
+    }
+
     #[test]
     fn test_command_metadata() {
         let cmd = LoaddataCommand;
@@ -5,8 +5,20 @@
 //! Detects changes between project states, generates operations, and serializes
 //! them to JSON migration files.
 
+use std::path::Path;
+
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db::registry::{ModelRegistry, MODELS};
+use django_rs_db_migrations::autodetect::MigrationFieldDef;
+use django_rs_db_migrations::operations::Operation;
+use django_rs_db_migrations::serializer::{
+    generate_migration_name, merge_migration, migration_file_path, next_migration_number,
+};
+use django_rs_db_migrations::{
+    InteractiveQuestioner, MigrationAutodetector, MigrationLoader, MigrationQuestioner,
+    ModelOptions, ModelState, NonInteractiveQuestioner, ProjectState, SerializableMigration,
+};
 
 use crate::command::ManagementCommand;
 
@@ -15,9 +27,191 @@ use crate::command::ManagementCommand;
 /// Compares the current model state with the migration history and
 /// generates new migration files for any differences found. Supports
 /// `--dry-run` to preview without writing, `--empty` to create a blank
-/// migration, and `-n` to specify a custom name.
+/// migration, `--merge` to resolve diverged migration histories, and `-n`
+/// to specify a custom name. Ambiguous changes (possible
+/// renames, NOT NULL fields without a default) are asked about on the
+/// terminal unless `--noinput` is given.
 pub struct MakemigrationsCommand;
 
+/// Writes a merge migration for every app with more than one leaf migration.
+///
+/// Only the apps in `app_labels` are merged, or all apps if it is empty.
+/// Returns the number of merge migrations created (or that would be created
+/// under `dry_run`).
+pub fn merge_conflicts(
+    migrations_dir: &Path,
+    app_labels: &[&String],
+    name: Option<&str>,
+    dry_run: bool,
+) -> Result<usize, DjangoError> {
+    let graph = MigrationLoader::new(migrations_dir).load()?;
+    let mut merged = 0;
+    for (app_label, leaves) in graph.conflicts() {
+        if !app_labels.is_empty() && !app_labels.iter().any(|l| **l == app_label) {
+            continue;
+        }
+        let migration = merge_migration(&app_label, &leaves, name);
+        let leaf_names: Vec<&str> = leaves.iter().map(|(_, n)| n.as_str()).collect();
+        tracing::info!("Merging {app_label}: {}", leaf_names.join(", "));
+        if dry_run {
+            tracing::info!(
+                "Would create: {app_label}/migrations/{}.json",
+                migration.name
+            );
+        } else {
+            let path = migration_file_path(migrations_dir, &app_label, &migration.name);
+            migration.write_to_file(&path)?;
+            tracing::info!("Created new merge migration {}", path.display());
+        }
+        merged += 1;
+    }
+    Ok(merged)
+}
+
+/// Builds the project state of the concrete models in `registry`.
+pub fn registry_state(registry: &ModelRegistry) -> ProjectState {
+    let mut state = ProjectState::new();
+    for meta in registry.models() {
+        if meta.abstract_model {
+            continue;
+        }
+        let fields = meta
+            .fields
+            .iter()
+            .map(|field| MigrationFieldDef {
+                name: field.name.to_string(),
+                column: field.column.clone(),
+                field_type: field.field_type.clone(),
+                primary_key: field.primary_key,
+                null: field.null,
+                default: field.default.clone(),
+                unique: field.unique,
+                db_index: field.db_index,
+                max_length: field.max_length,
+            })
+            .collect();
+        let default_table = format!("{}_{}", meta.app_label, meta.model_name);
+        // The model state qualifies its table with the schema itself
+        let table = meta
+            .db_schema
+            .as_ref()
+            .and_then(|schema| meta.db_table.strip_prefix(&format!("{schema}.")))
+            .unwrap_or(&meta.db_table);
+        let options = ModelOptions {
+            db_table: (table != default_table).then(|| table.to_string()),
+            unique_together: meta
+                .unique_together
+                .iter()
+                .map(|group| group.iter().map(ToString::to_string).collect())
+                .collect(),
+            indexes: meta.indexes.clone(),
+            db_schema: meta.db_schema.clone(),
+        };
+        state.add_model(
+            ModelState::new(meta.app_label, meta.model_name, fields).with_options(options),
+        );
+    }
+    state
+}
+
+/// Writes a migration for every app whose models in `registry` differ from
+/// the state its existing migrations in `migrations_dir` build.
+///
+/// Only the apps in `app_labels` are considered, or all apps if it is empty.
+/// Ambiguous changes are resolved by `questioner`. Returns the migrations
+/// written (or that would be written under `dry_run`), sorted by app label.
+///
+/// # Errors
+///
+/// Returns an error if the existing migrations cannot be loaded, if the
+/// questioner aborts, or if a migration file cannot be written.
+pub fn make_migrations(
+    migrations_dir: &Path,
+    registry: &ModelRegistry,
+    app_labels: &[&String],
+    name: Option<&str>,
+    dry_run: bool,
+    questioner: &mut dyn MigrationQuestioner,
+) -> Result<Vec<SerializableMigration>, DjangoError> {
+    let mut loader = MigrationLoader::new(migrations_dir);
+    let graph = loader.load()?;
+    let mut from_state = ProjectState::new();
+    for key in graph.topological_order()? {
+        if let Some(info) = loader.migrations().get(&key) {
+            for op in SerializableMigration::read_from_file(&info.path)?.to_operations() {
+                op.state_forwards(&key.0, &mut from_state);
+            }
+        }
+    }
+
+    let autodetector = MigrationAutodetector::new(from_state, registry_state(registry));
+    let mut changes: Vec<(String, Vec<Box<dyn Operation>>)> = autodetector
+        .detect_changes_with(questioner)?
+        .into_iter()
+        .filter(|(app_label, _)| app_labels.is_empty() || app_labels.contains(&app_label))
+        .collect();
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut written = Vec::new();
+    for (app_label, operations) in changes {
+        let dependencies = graph.leaf_nodes(&app_label);
+        let number = next_migration_number(migrations_dir, &app_label);
+        let migration_name = generate_migration_name(number, name);
+        let migration = SerializableMigration::from_operations(
+            &app_label,
+            &migration_name,
+            dependencies.clone(),
+            dependencies.is_empty(),
+            &operations,
+        );
+        if dry_run {
+            tracing::info!("Would create: {app_label}/migrations/{migration_name}.json");
+        } else {
+            let path = migration_file_path(migrations_dir, &app_label, &migration_name);
+            migration.write_to_file(&path)?;
+            tracing::info!("Created: {}", path.display());
+        }
+        written.push(migration);
+    }
+    Ok(written)
+}
+
+/// Writes an empty migration for each app in `app_labels`.
+fn write_empty_migrations(
+    migrations_dir: &Path,
+    app_labels: &[&String],
+    name: Option<&str>,
+    dry_run: bool,
+) -> Result<(), DjangoError> {
+    if app_labels.is_empty() {
+        return Err(DjangoError::DatabaseError(
+            "You must supply at least one app label when using --empty".into(),
+        ));
+    }
+
+    for app_label in app_labels {
+        let number = next_migration_number(migrations_dir, app_label);
+        let migration_name = generate_migration_name(number, name);
+        let migration = SerializableMigration {
+            app_label: (*app_label).clone(),
+            name: migration_name.clone(),
+            dependencies: vec![],
+            initial: false,
+            atomic: true,
+            operations: vec![],
+        };
+
+        if dry_run {
+            tracing::info!("Would create: {app_label}/migrations/{migration_name}.json");
+        } else {
+            let path = migration_file_path(migrations_dir, app_label, &migration_name);
+            migration.write_to_file(&path)?;
+            tracing::info!("Created: {}", path.display());
+        }
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl ManagementCommand for MakemigrationsCommand {
     fn name(&self) -> &'static str {
@@ -47,12 +241,24 @@ impl ManagementCommand for MakemigrationsCommand {
                 .action(clap::ArgAction::SetTrue)
                 .help("Create an empty migration"),
         )
+        .arg(
+            clap::Arg::new("merge")
+                .long("merge")
+                .action(clap::ArgAction::SetTrue)
+                .help("Create merge migrations for apps with conflicting migrations"),
+        )
         .arg(
             clap::Arg::new("name")
                 .short('n')
                 .long("name")
                 .help("Name for the generated migration"),
         )
+        .arg(
+            clap::Arg::new("noinput")
+                .long("noinput")
+                .action(clap::ArgAction::SetTrue)
+                .help("Never prompt; renames are not assumed and NOT NULL fields need a default"),
+        )
         .arg(
             clap::Arg::new("migrations-dir")
                 .long("migrations-dir")
@@ -80,46 +286,42 @@ impl ManagementCommand for MakemigrationsCommand {
             tracing::info!("Dry run mode: no files will be written");
         }
 
-        if empty {
-            // Create an empty migration for each specified app
-            if app_labels.is_empty() {
-                return Err(DjangoError::DatabaseError(
-                    "You must supply at least one app label when using --empty".into(),
-                ));
+        if matches.get_flag("merge") {
+            let merged = merge_conflicts(
+                Path::new(migrations_dir),
+                &app_labels,
+                name.map(String::as_str),
+                dry_run,
+            )?;
+            if merged == 0 {
+                tracing::info!("No conflicts detected to merge.");
             }
+            return Ok(());
+        }
 
-            for app_label in &app_labels {
-                let number = django_rs_db_migrations::serializer::next_migration_number(
-                    std::path::Path::new(migrations_dir),
-                    app_label,
-                );
-                let migration_name = django_rs_db_migrations::serializer::generate_migration_name(
-                    number,
-                    name.map(String::as_str),
-                );
-
-                let migration = django_rs_db_migrations::SerializableMigration {
-                    app_label: (*app_label).clone(),
-                    name: migration_name.clone(),
-                    dependencies: vec![],
-                    initial: false,
-                    operations: vec![],
-                };
-
-                if dry_run {
-                    tracing::info!("Would create: {app_label}/migrations/{migration_name}.json");
-                } else {
-                    let path = django_rs_db_migrations::serializer::migration_file_path(
-                        std::path::Path::new(migrations_dir),
-                        app_label,
-                        &migration_name,
-                    );
-                    migration.write_to_file(&path)?;
-                    tracing::info!("Created: {}", path.display());
-                }
-            }
+        let conflicts = MigrationLoader::new(migrations_dir).load()?.conflicts();
+        if !conflicts.is_empty() {
+            let details: Vec<String> = conflicts
+                .iter()
+                .map(|(app, leaves)| {
+                    let names: Vec<&str> = leaves.iter().map(|(_, n)| n.as_str()).collect();
+                    format!("{} in {app}", names.join(", "))
+                })
+                .collect();
+            return Err(DjangoError::DatabaseError(format!(
+                "Conflicting migrations detected; multiple leaf nodes in the migration graph: \
+                 ({}). To fix them run 'makemigrations --merge'",
+                details.join("; ")
+            )));
+        }
 
-            return Ok(());
+        if empty {
+            return write_empty_migrations(
+                Path::new(migrations_dir),
+                &app_labels,
+                name.map(String::as_str),
+                dry_run,
+            );
         }
 
         if app_labels.is_empty() {
@@ -135,16 +337,245 @@ impl ManagementCommand for MakemigrationsCommand {
             );
         }
 
-        // In a full implementation, this would:
-        // 1. Build the current ProjectState from registered models
-        // 2. Build the "from" state by replaying existing migrations
-        // 3. Run MigrationAutodetector to diff them
-        // 4. Serialize detected operations to JSON files
-        //
-        // For now, we log that no changes were detected since the model
-        // registry is not yet wired in.
-        tracing::info!("No changes detected");
+        let mut non_interactive = NonInteractiveQuestioner::new();
+        let mut interactive;
+        let questioner: &mut dyn MigrationQuestioner = if matches.get_flag("noinput") {
+            &mut non_interactive
+        } else {
+            interactive = InteractiveQuestioner::stdio();
+            &mut interactive
+        };
+        let written = make_migrations(
+            Path::new(migrations_dir),
+            &MODELS,
+            &app_labels,
+            name.map(String::as_str),
+            dry_run,
+            questioner,
+        )?;
+        if written.is_empty() {
+            tracing::info!("No changes detected");
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_db::fields::{FieldDef, FieldType};
+    use django_rs_db::model::ModelMeta;
+    use django_rs_db::query::compiler::InheritanceType;
+    use std::sync::LazyLock;
+
+    fn post_meta(fields: Vec<FieldDef>) -> ModelMeta {
+        ModelMeta {
+            app_label: "blog",
+            model_name: "post",
+            db_table: "blog_post".to_string(),
+            verbose_name: "post".to_string(),
+            verbose_name_plural: "posts".to_string(),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields,
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        }
+    }
+
+    static POST: LazyLock<ModelMeta> = LazyLock::new(|| {
+        post_meta(vec![
+            FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+            FieldDef::new("title", FieldType::CharField).max_length(100),
+        ])
+    });
+
+    static POST_WITH_SLUG: LazyLock<ModelMeta> = LazyLock::new(|| {
+        post_meta(vec![
+            FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+            FieldDef::new("title", FieldType::CharField).max_length(100),
+            FieldDef::new("slug", FieldType::SlugField).max_length(50),
+        ])
+    });
+
+    fn write_branches(dir: &Path) {
+        let migration = |name: &str, deps: Vec<(String, String)>| SerializableMigration {
+            app_label: "blog".into(),
+            name: name.into(),
+            initial: deps.is_empty(),
+            atomic: true,
+            dependencies: deps,
+            operations: vec![],
+        };
+        let initial = ("blog".to_string(), "0001_initial".to_string());
+        for m in [
+            migration("0001_initial", vec![]),
+            migration("0002_add_author", vec![initial.clone()]),
+            migration("0002_add_tags", vec![initial]),
+        ] {
+            m.write_to_file(&migration_file_path(dir, "blog", &m.name))
+                .unwrap();
+        }
+    }
+
+    async fn run(args: &[&str]) -> Result<(), DjangoError> {
+        let cmd = MakemigrationsCommand;
+        let cli = clap::Command::new("test")
+            .subcommand(cmd.add_arguments(clap::Command::new("makemigrations")));
+        let matches = cli
+            .try_get_matches_from(["test", "makemigrations"].iter().chain(args))
+            .unwrap();
+        let (_, sub_matches) = matches.subcommand().unwrap();
+        cmd.handle(sub_matches, &Settings::default()).await
+    }
+
+    #[tokio::test]
+    async fn test_makemigrations_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        write_branches(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+
+        run(&["--merge", "--migrations-dir", dir_arg])
+            .await
+            .unwrap();
+
+        let path = migration_file_path(dir.path(), "blog", "0003_merge_add_author_add_tags");
+        let merge = SerializableMigration::read_from_file(&path).unwrap();
+        assert_eq!(merge.dependencies.len(), 2);
+        let graph = MigrationLoader::new(dir.path()).load().unwrap();
+        assert!(graph.conflicts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_makemigrations_merge_dry_run() {
+        let dir = tempfile::tempdir().unwrap();
+        write_branches(dir.path());
+
+        let merged = merge_conflicts(dir.path(), &[], None, true).unwrap();
+        assert_eq!(merged, 1);
+        let graph = MigrationLoader::new(dir.path()).load().unwrap();
+        assert_eq!(graph.conflicts().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_makemigrations_reports_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        write_branches(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+
+        let err = run(&["--migrations-dir", dir_arg]).await.unwrap_err();
+        assert!(err.to_string().contains("makemigrations --merge"));
+    }
+
+    #[test]
+    fn test_make_migrations_writes_initial_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ModelRegistry::new();
+        registry.register_meta(&POST);
+        let mut questioner = NonInteractiveQuestioner::new();
+
+        let written = make_migrations(
+            dir.path(),
+            &registry,
+            &[],
+            Some("initial"),
+            false,
+            &mut questioner,
+        )
+        .unwrap();
+        assert_eq!(written.len(), 1);
+        assert!(written[0].initial);
+        let path = migration_file_path(dir.path(), "blog", "0001_initial");
+        assert_eq!(
+            SerializableMigration::read_from_file(&path)
+                .unwrap()
+                .operations
+                .len(),
+            1
+        );
+
+        let again =
+            make_migrations(dir.path(), &registry, &[], None, false, &mut questioner).unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn test_make_migrations_keeps_db_schema() {
+        static INVOICE: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+            app_label: "billing",
+            model_name: "invoice",
+            db_table: "billing.invoice".to_string(),
+            db_schema: Some("billing".to_string()),
+            ..post_meta(vec![
+                FieldDef::new("id", FieldType::BigAutoField).primary_key()
+            ])
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ModelRegistry::new();
+        registry.register_meta(&INVOICE);
+        let mut questioner = NonInteractiveQuestioner::new();
+        make_migrations(
+            dir.path(),
+            &registry,
+            &[],
+            Some("initial"),
+            false,
+            &mut questioner,
+        )
+        .unwrap();
+
+        let sql = crate::commands::sqlmigrate::generate_migration_sql(
+            dir.path(),
+            "billing",
+            "0001",
+            "django_rs.db.backends.postgresql",
+            false,
+        )
+        .unwrap();
+        assert!(
+            sql.iter()
+                .any(|s| s.contains("CREATE TABLE \"billing\".\"invoice\"")),
+            "{sql:?}"
+        );
+
+        let again =
+            make_migrations(dir.path(), &registry, &[], None, false, &mut questioner).unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn test_make_migrations_noinput_rejects_not_null_field_without_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ModelRegistry::new();
+        registry.register_meta(&POST);
+        let mut questioner = NonInteractiveQuestioner::new();
+        make_migrations(
+            dir.path(),
+            &registry,
+            &[],
+            Some("initial"),
+            false,
+            &mut questioner,
+        )
+        .unwrap();
+
+        let registry = ModelRegistry::new();
+        registry.register_meta(&POST_WITH_SLUG);
+        let err =
+            make_migrations(dir.path(), &registry, &[], None, false, &mut questioner).unwrap_err();
+        assert!(err.to_string().contains("non-nullable field"));
+    }
+
+    #[tokio::test]
+    async fn test_makemigrations_noinput_does_not_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_arg = dir.path().to_str().unwrap();
+        run(&["--noinput", "--migrations-dir", dir_arg])
+            .await
+            .unwrap();
+    }
+}
@@ -115,7 +115,9 @@ impl ManagementCommand for MigrateCommand {
         // Build the executor with the SQLite schema editor (default)
         let schema_editor: Box<dyn django_rs_db_migrations::SchemaEditor> =
             Box::new(django_rs_db_migrations::SqliteSchemaEditor);
-        let executor = django_rs_db_migrations::MigrationExecutor::new(schema_editor);
+        let executor = django_rs_db_migrations::MigrationExecutor::new(schema_editor)
+            .with_checksums(loader.checksums())
+            .with_non_atomic(loader.non_atomic());
 
         // Build the migration plan
         let plan = executor.make_plan(&graph, target.as_ref())?;
@@ -128,7 +130,16 @@ impl ManagementCommand for MigrateCommand {
         tracing::info!("Planned {} migration(s)", plan.len());
         for step in &plan.steps {
             let direction = if step.backwards { "Unapply" } else { "Apply" };
-            tracing::info!("  {direction} {}.{}", step.migration.0, step.migration.1);
+            let atomic = if executor.is_atomic(&step.migration) {
+                ""
+            } else {
+                " (non-atomic)"
+            };
+            tracing::info!(
+                "  {direction} {}.{}{atomic}",
+                step.migration.0,
+                step.migration.1
+            );
         }
 
         tracing::info!(
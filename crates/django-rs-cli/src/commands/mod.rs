@@ -14,11 +14,15 @@ pub mod inspectdb;
 pub mod loaddata;
 pub mod makemigrations;
 pub mod migrate;
+pub mod optimizemigration;
 pub mod runserver;
 pub mod showmigrations;
 pub mod sqlflush;
 pub mod sqlmigrate;
+pub mod startapp;
+pub mod startproject;
 pub mod test_cmd;
+pub mod validatetemplates;
 
 pub use check::CheckCommand;
 pub use collectstatic::CollectstaticCommand;
@@ -30,11 +34,15 @@ pub use inspectdb::InspectdbCommand;
 pub use loaddata::LoaddataCommand;
 pub use makemigrations::MakemigrationsCommand;
 pub use migrate::MigrateCommand;
+pub use optimizemigration::OptimizemigrationCommand;
 pub use runserver::RunserverCommand;
 pub use showmigrations::ShowmigrationsCommand;
 pub use sqlflush::SqlflushCommand;
 pub use sqlmigrate::SqlmigrateCommand;
+pub use startapp::StartappCommand;
+pub use startproject::StartprojectCommand;
 pub use test_cmd::TestCommand;
+pub use validatetemplates::ValidatetemplatesCommand;
 
 use crate::command::CommandRegistry;
 
@@ -43,6 +51,7 @@ pub fn register_builtin_commands(registry: &mut CommandRegistry) {
     registry.register(Box::new(RunserverCommand));
     registry.register(Box::new(MigrateCommand));
     registry.register(Box::new(MakemigrationsCommand));
+    registry.register(Box::new(OptimizemigrationCommand));
     registry.register(Box::new(CheckCommand));
     registry.register(Box::new(ShowmigrationsCommand));
     registry.register(Box::new(CreatesuperuserCommand));
@@ -55,4 +64,7 @@ pub fn register_builtin_commands(registry: &mut CommandRegistry) {
     registry.register(Box::new(SqlmigrateCommand));
     registry.register(Box::new(SqlflushCommand));
     registry.register(Box::new(FindstaticCommand));
+    registry.register(Box::new(ValidatetemplatesCommand));
+    registry.register(Box::new(StartprojectCommand));
+    registry.register(Box::new(StartappCommand));
 }
@@ -0,0 +1,220 @@
+//! The `optimizemigration` management command.
+//!
+//! Runs the migration optimizer over a single migration file and rewrites it.
+//! This mirrors Django's `optimizemigration` command.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, Settings};
+use django_rs_db_migrations::{MigrationLoader, MigrationSquasher, SerializableMigration};
+
+use crate::command::ManagementCommand;
+
+/// Optimizes the operations of a single migration.
+///
+/// Takes an app label and a migration name (or a unique prefix of one),
+/// merges or drops redundant operations and writes the result back to the
+/// migration file. With `--check` nothing is written; the command fails if
+/// the migration could be optimized.
+pub struct OptimizemigrationCommand;
+
+/// Finds the migration file for `app_label` whose name is or starts with `name`.
+pub fn find_migration(
+    migrations_dir: &Path,
+    app_label: &str,
+    name: &str,
+) -> Result<std::path::PathBuf, DjangoError> {
+    let mut loader = MigrationLoader::new(migrations_dir);
+    loader.load()?;
+    if let Some(info) = loader
+        .migrations()
+        .get(&(app_label.to_string(), name.to_string()))
+    {
+        return Ok(info.path.clone());
+    }
+
+    let mut matches: Vec<_> = loader
+        .migrations()
+        .values()
+        .filter(|info| info.app_label == app_label && info.name.starts_with(name))
+        .collect();
+    match matches.len() {
+        0 => Err(DjangoError::ConfigurationError(format!(
+            "Cannot find a migration matching '{name}' from app '{app_label}'."
+        ))),
+        1 => Ok(matches.remove(0).path.clone()),
+        _ => Err(DjangoError::ConfigurationError(format!(
+            "More than one migration matches '{name}' in app '{app_label}'. \
+             Please be more specific."
+        ))),
+    }
+}
+
+#[async_trait]
+impl ManagementCommand for OptimizemigrationCommand {
+    fn name(&self) -> &'static str {
+        "optimizemigration"
+    }
+
+    fn help(&self) -> &'static str {
+        "Optimize the operations of a single migration"
+    }
+
+    fn add_arguments(&self, cmd: clap::Command) -> clap::Command {
+        cmd.arg(
+            clap::Arg::new("app_label")
+                .help("App label of the migration")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("migration_name")
+                .help("Migration name or unique prefix (e.g. 0002)")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("check")
+                .long("check")
+                .action(clap::ArgAction::SetTrue)
+                .help("Fail if the migration can be optimized, without rewriting it"),
+        )
+        .arg(
+            clap::Arg::new("migrations-dir")
+                .long("migrations-dir")
+                .help("Path to migrations directory")
+                .default_value("migrations"),
+        )
+    }
+
+    async fn handle(
+        &self,
+        matches: &clap::ArgMatches,
+        _settings: &Settings,
+    ) -> Result<(), DjangoError> {
+        let app_label = matches
+            .get_one::<String>("app_label")
+            .ok_or_else(|| DjangoError::ConfigurationError("app_label is required".to_string()))?;
+        let migration_name = matches.get_one::<String>("migration_name").ok_or_else(|| {
+            DjangoError::ConfigurationError("migration_name is required".to_string())
+        })?;
+        let check = matches.get_flag("check");
+        let migrations_dir = matches
+            .get_one::<String>("migrations-dir")
+            .map_or("migrations", String::as_str);
+
+        let path = find_migration(Path::new(migrations_dir), app_label, migration_name)?;
+        let migration = SerializableMigration::read_from_file(&path)?;
+        let optimized = MigrationSquasher::optimize_migration(&migration);
+
+        let before = migration.operations.len();
+        let after = optimized.operations.len();
+        if after == before {
+            tracing::info!("No optimizations possible.");
+            return Ok(());
+        }
+        if check {
+            return Err(DjangoError::ConfigurationError(format!(
+                "Migration {app_label}.{} can be optimized from {before} to {after} operations.",
+                migration.name
+            )));
+        }
+
+        optimized.write_to_file(&path)?;
+        tracing::info!("Optimized from {before} operations to {after} operations.");
+        tracing::info!("Optimized migration {}", path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_db::fields::FieldType;
+    use django_rs_db_migrations::autodetect::MigrationFieldDef;
+    use django_rs_db_migrations::serializer::{migration_file_path, SerializableOperation};
+
+    fn write_migration(dir: &Path) -> std::path::PathBuf {
+        let migration = SerializableMigration {
+            app_label: "blog".into(),
+            name: "0002_post_title".into(),
+            dependencies: vec![],
+            initial: false,
+            atomic: true,
+            operations: vec![
+                SerializableOperation::AddField {
+                    model_name: "post".into(),
+                    field: MigrationFieldDef::new("title", FieldType::CharField),
+                    preserve_default: true,
+                },
+                SerializableOperation::RemoveField {
+                    model_name: "post".into(),
+                    field_name: "title".into(),
+                },
+            ],
+        };
+        let path = migration_file_path(dir, "blog", &migration.name);
+        migration.write_to_file(&path).unwrap();
+        path
+    }
+
+    async fn run(args: &[&str]) -> Result<(), DjangoError> {
+        let cmd = OptimizemigrationCommand;
+        let cli = clap::Command::new("test")
+            .subcommand(cmd.add_arguments(clap::Command::new("optimizemigration")));
+        let matches = cli
+            .try_get_matches_from(["test", "optimizemigration"].iter().chain(args))
+            .unwrap();
+        let (_, sub_matches) = matches.subcommand().unwrap();
+        cmd.handle(sub_matches, &Settings::default()).await
+    }
+
+    #[test]
+    fn test_command_metadata() {
+        let cmd = OptimizemigrationCommand;
+        assert_eq!(cmd.name(), "optimizemigration");
+        assert_eq!(cmd.help(), "Optimize the operations of a single migration");
+    }
+
+    #[tokio::test]
+    async fn test_optimizemigration_rewrites_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_migration(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+
+        run(&["blog", "0002", "--migrations-dir", dir_arg])
+            .await
+            .unwrap();
+
+        let migration = SerializableMigration::read_from_file(&path).unwrap();
+        assert!(migration.operations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_optimizemigration_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_migration(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+
+        let result = run(&[
+            "blog",
+            "0002_post_title",
+            "--check",
+            "--migrations-dir",
+            dir_arg,
+        ])
+        .await;
+        assert!(result.is_err());
+        let migration = SerializableMigration::read_from_file(&path).unwrap();
+        assert_eq!(migration.operations.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_optimizemigration_unknown_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        write_migration(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+
+        let result = run(&["blog", "0009", "--migrations-dir", dir_arg]).await;
+        assert!(result.is_err());
+    }
+}
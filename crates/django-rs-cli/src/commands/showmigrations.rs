@@ -3,17 +3,125 @@
 //! Displays the status of all migrations. This mirrors Django's
 //! `showmigrations` command.
 
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::BuildHasher;
+use std::path::Path;
+
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db_migrations::{MigrationGraph, MigrationLoader, MigrationRecorder};
 
 use crate::command::ManagementCommand;
 
 /// Lists all migrations and their applied/unapplied status.
 ///
 /// Shows a tree of migrations organized by app, with markers indicating
-/// which migrations have been applied to the database.
+/// which migrations have been applied to the database. At `--verbosity 2`
+/// each applied migration also shows its schema history: when and by whom
+/// it was applied, how long it took, the django-rs version, and whether the
+/// file has changed since.
 pub struct ShowmigrationsCommand;
 
+/// Loads the applied migrations recorded in the given database.
+///
+/// Only SQLite databases can be read; for other engines, or when the
+/// database file does not exist yet, the recorder is empty. The database is
+/// only read: a missing `django_migrations` table is not created, and an
+/// older one is not upgraded.
+pub async fn load_recorder(
+    settings: &Settings,
+    database: &str,
+) -> Result<MigrationRecorder, DjangoError> {
+    let mut recorder = MigrationRecorder::new();
+    let Some(db) = settings.databases.get(database) else {
+        return Err(DjangoError::ConfigurationError(format!(
+            "Unknown database '{database}'"
+        )));
+    };
+    if !db.engine.contains("sqlite") {
+        tracing::warn!(
+            "Cannot read applied migrations from '{}' databases; showing all as unapplied",
+            db.engine
+        );
+        return Ok(recorder);
+    }
+    if !Path::new(&db.name).exists() {
+        return Ok(recorder);
+    }
+    #[cfg(feature = "sqlite")]
+    {
+        let backend = django_rs_db_backends::SqliteBackend::open(&db.name)?;
+        recorder.read_from_db(&backend).await?;
+    }
+    #[cfg(not(feature = "sqlite"))]
+    tracing::warn!("Reading SQLite databases requires the 'sqlite' feature");
+    Ok(recorder)
+}
+
+/// Formats the migration status lines for the given apps (all apps if empty).
+///
+/// Migrations are listed per app in dependency order, marked `[X]` when
+/// applied. At verbosity 2 or more, applied migrations include their schema
+/// history, and migrations whose file checksum differs from the recorded one
+/// are flagged.
+pub fn format_migrations<S: BuildHasher>(
+    graph: &MigrationGraph,
+    checksums: &HashMap<(String, String), String, S>,
+    recorder: &MigrationRecorder,
+    app_labels: &[String],
+    verbosity: u8,
+) -> Result<Vec<String>, DjangoError> {
+    let order = graph.topological_order()?;
+    let mut apps: Vec<&str> = order
+        .iter()
+        .map(|(app, _)| app.as_str())
+        .filter(|app| app_labels.is_empty() || app_labels.iter().any(|l| l == app))
+        .collect();
+    apps.sort_unstable();
+    apps.dedup();
+
+    let mut lines = Vec::new();
+    for app in apps {
+        lines.push(app.to_string());
+        for key in order.iter().filter(|(a, _)| a == app) {
+            if !recorder.is_applied(key) {
+                lines.push(format!(" [ ] {}", key.1));
+                continue;
+            }
+            let mut line = format!(" [X] {}", key.1);
+            if verbosity >= 2 {
+                if let Some(record) = recorder.record(key) {
+                    let mut details = Vec::new();
+                    if let Some(applied) = &record.applied {
+                        details.push(format!("applied {applied}"));
+                    }
+                    if let Some(ms) = record.duration_ms {
+                        details.push(format!("in {ms}ms"));
+                    }
+                    if let Some(by) = &record.applied_by {
+                        details.push(format!("by {by}"));
+                    }
+                    if let Some(version) = &record.version {
+                        details.push(format!("django-rs {version}"));
+                    }
+                    if !details.is_empty() {
+                        let _ = write!(line, " ({})", details.join(", "));
+                    }
+                    if let (Some(recorded), Some(current)) = (&record.checksum, checksums.get(key))
+                    {
+                        if recorded != current {
+                            line.push_str(" [modified since applied]");
+                        }
+                    }
+                }
+            }
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
 #[async_trait]
 impl ManagementCommand for ShowmigrationsCommand {
     fn name(&self) -> &'static str {
@@ -43,28 +151,173 @@ impl ManagementCommand for ShowmigrationsCommand {
                 .action(clap::ArgAction::SetTrue)
                 .help("Show planned migration order"),
         )
+        .arg(
+            clap::Arg::new("verbosity")
+                .long("verbosity")
+                .short('v')
+                .default_value("1")
+                .help("Verbosity level; 2 shows the schema history of applied migrations"),
+        )
+        .arg(
+            clap::Arg::new("migrations-dir")
+                .long("migrations-dir")
+                .help("Path to migrations directory")
+                .default_value("migrations"),
+        )
     }
 
     async fn handle(
         &self,
         matches: &clap::ArgMatches,
-        _settings: &Settings,
+        settings: &Settings,
     ) -> Result<(), DjangoError> {
         let database = matches
             .get_one::<String>("database")
             .map_or("default", String::as_str);
         let plan = matches.get_flag("plan");
+        let verbosity: u8 = matches
+            .get_one::<String>("verbosity")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1);
+        let migrations_dir = matches
+            .get_one::<String>("migrations-dir")
+            .map_or("migrations", String::as_str);
+        let app_labels: Vec<String> = matches
+            .get_many::<String>("app_label")
+            .map(|labels| labels.cloned().collect())
+            .unwrap_or_default();
 
         tracing::info!("Showing migrations for database '{database}'");
 
+        let mut loader = MigrationLoader::new(migrations_dir);
+        let graph = loader.load()?;
+        if graph.is_empty() {
+            tracing::info!("No migrations found");
+            return Ok(());
+        }
+        let recorder = load_recorder(settings, database).await?;
+
         if plan {
-            tracing::info!("Showing migration plan");
+            for (app, name) in graph.topological_order()? {
+                let marker = if recorder.is_applied(&(app.clone(), name.clone())) {
+                    "[X]"
+                } else {
+                    "[ ]"
+                };
+                tracing::info!("{marker}  {app}.{name}");
+            }
+            return Ok(());
         }
 
-        // In a full implementation, this would query the migration table
-        // and display the status of each migration.
-        tracing::info!("No migrations found");
+        let checksums = loader.checksums();
+        for line in format_migrations(&graph, &checksums, &recorder, &app_labels, verbosity)? {
+            tracing::info!("{line}");
+        }
+        for (app, name) in recorder.checksum_mismatches(&checksums) {
+            tracing::warn!("Migration {app}.{name} has been modified since it was applied");
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_db_migrations::AppliedMigration;
+
+    fn graph() -> MigrationGraph {
+        let mut graph = MigrationGraph::new();
+        graph.add_node("blog", "0001_initial", true);
+        graph.add_node("blog", "0002_post_title", false);
+        graph.add_node("auth", "0001_initial", true);
+        graph
+            .add_dependency(
+                ("blog".into(), "0002_post_title".into()),
+                ("blog".into(), "0001_initial".into()),
+            )
+            .unwrap();
+        graph
+    }
+
+    fn recorder() -> MigrationRecorder {
+        let mut recorder = MigrationRecorder::new();
+        recorder.apply_record(AppliedMigration {
+            applied: Some("2026-01-02 03:04:05".into()),
+            applied_by: Some("deploy@web1".into()),
+            version: Some("0.1.0".into()),
+            duration_ms: Some(12),
+            ..AppliedMigration::new("blog", "0001_initial").checksum(Some("old".into()))
+        });
+        recorder
+    }
+
+    #[test]
+    fn test_format_migrations_status() {
+        let lines = format_migrations(&graph(), &HashMap::new(), &recorder(), &[], 1).unwrap();
+        assert_eq!(
+            lines,
+            [
+                "auth",
+                " [ ] 0001_initial",
+                "blog",
+                " [X] 0001_initial",
+                " [ ] 0002_post_title",
+            ]
+        );
+
+        let blog_only =
+            format_migrations(&graph(), &HashMap::new(), &recorder(), &["blog".into()], 1).unwrap();
+        assert_eq!(blog_only[0], "blog");
+        assert_eq!(blog_only.len(), 3);
+    }
+
+    #[test]
+    fn test_format_migrations_verbose_history() {
+        let checksums = HashMap::from([(
+            ("blog".to_string(), "0001_initial".to_string()),
+            "new".to_string(),
+        )]);
+        let lines =
+            format_migrations(&graph(), &checksums, &recorder(), &["blog".into()], 2).unwrap();
+        assert_eq!(
+            lines[1],
+            " [X] 0001_initial (applied 2026-01-02 03:04:05, in 12ms, by deploy@web1, \
+             django-rs 0.1.0) [modified since applied]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_recorder_missing_database_file() {
+        let mut settings = Settings::default();
+        if let Some(db) = settings.databases.get_mut("default") {
+            db.name = "/nonexistent/db.sqlite3".to_string();
+        }
+        let recorder = load_recorder(&settings, "default").await.unwrap();
+        assert!(recorder.applied().is_empty());
+        assert!(load_recorder(&settings, "other").await.is_err());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_load_recorder_does_not_create_history_table() {
+        use django_rs_db_backends::{DatabaseBackend, SqliteBackend};
+        use django_rs_db_migrations::MigrationRecorder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.sqlite3");
+        let backend = SqliteBackend::open(path.to_str().unwrap()).unwrap();
+        backend
+            .execute("CREATE TABLE \"blog_post\" (\"id\" INTEGER)", &[])
+            .await
+            .unwrap();
+
+        let mut settings = Settings::default();
+        if let Some(db) = settings.databases.get_mut("default") {
+            db.name = path.to_str().unwrap().to_string();
+        }
+        let recorder = load_recorder(&settings, "default").await.unwrap();
+        assert!(recorder.applied().is_empty());
+        assert!(!MigrationRecorder::table_exists(&backend).await.unwrap());
+    }
+}
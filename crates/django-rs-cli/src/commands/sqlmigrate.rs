@@ -3,9 +3,17 @@
 //! Shows the SQL that would be executed for a specific migration.
 //! This mirrors Django's `sqlmigrate` command.
 
+use std::collections::HashSet;
+use std::path::Path;
+
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use django_rs_db_migrations::{
+    Migration, MigrationExecutor, MigrationLoader, MySqlSchemaEditor, PostgresSchemaEditor,
+    ProjectState, SchemaEditor, SerializableMigration, SqliteSchemaEditor,
+};
 
+use super::optimizemigration::find_migration;
 use crate::command::ManagementCommand;
 
 /// Displays the SQL for a specific migration.
@@ -15,21 +23,78 @@ use crate::command::ManagementCommand;
 /// `--backwards` to show the reverse SQL.
 pub struct SqlmigrateCommand;
 
-/// Generates SQL statements for a migration's operations.
+/// Returns the schema editor for a database engine name.
+fn schema_editor_for(engine: &str) -> Box<dyn SchemaEditor> {
+    if engine.contains("postgresql") {
+        Box::new(PostgresSchemaEditor)
+    } else if engine.contains("mysql") {
+        Box::new(MySqlSchemaEditor)
+    } else {
+        Box::new(SqliteSchemaEditor)
+    }
+}
+
+/// Loads a migration file as an in-memory [`Migration`].
+fn load_migration(path: &Path) -> Result<Migration, DjangoError> {
+    let file = SerializableMigration::read_from_file(path)?;
+    let mut migration = Migration::new(file.app_label.clone(), file.name.clone());
+    migration.operations = file.to_operations();
+    migration.atomic = file.atomic;
+    Ok(migration)
+}
+
+/// Generates the SQL statements for a migration's operations.
 ///
-/// In a full implementation, this would load the migration file and use the
-/// schema editor to produce SQL. For now, it generates a placeholder comment.
+/// Loads the migration (by name or unique prefix) from `migrations_dir`,
+/// rebuilds the project state from the migrations it depends on and renders
+/// each operation through the schema editor for `engine`. With `backwards`
+/// the SQL reverses the migration instead.
+///
+/// # Errors
+///
+/// Returns an error if the migration cannot be found or loaded, if it has
+/// operations that cannot be represented as SQL, or if an operation cannot
+/// be reversed.
 pub fn generate_migration_sql(
+    migrations_dir: &Path,
     app_label: &str,
     migration_name: &str,
+    engine: &str,
     backwards: bool,
-) -> Vec<String> {
-    let direction = if backwards { "reverse" } else { "forward" };
-    vec![
-        format!("-- SQL for {direction} migration {app_label}.{migration_name}"),
-        format!("-- (In a full implementation, this would contain actual SQL statements)"),
-        format!("-- Generated by django-rs sqlmigrate for {app_label}/{migration_name}"),
-    ]
+) -> Result<Vec<String>, DjangoError> {
+    let path = find_migration(migrations_dir, app_label, migration_name)?;
+    let migration = load_migration(&path)?;
+
+    let mut loader = MigrationLoader::new(migrations_dir);
+    let graph = loader.load()?;
+
+    // Every migration the target depends on, directly or transitively.
+    let mut ancestors = HashSet::new();
+    let mut pending = graph.dependencies(&migration.key());
+    while let Some(key) = pending.pop() {
+        if ancestors.insert(key.clone()) {
+            pending.extend(graph.dependencies(&key));
+        }
+    }
+
+    let mut state = ProjectState::new();
+    for key in graph.topological_order()? {
+        if !ancestors.contains(&key) {
+            continue;
+        }
+        let info = loader.migrations().get(&key).ok_or_else(|| {
+            DjangoError::ConfigurationError(format!(
+                "Dependency {}.{} of {}.{} not found",
+                key.0, key.1, migration.app_label, migration.name
+            ))
+        })?;
+        for op in load_migration(&info.path)?.operations {
+            op.state_forwards(&key.0, &mut state);
+        }
+    }
+
+    let executor = MigrationExecutor::new(schema_editor_for(engine));
+    executor.collect_sql(&migration, &state, backwards)
 }
 
 #[async_trait]
@@ -65,12 +130,18 @@ impl ManagementCommand for SqlmigrateCommand {
                 .default_value("default")
                 .help("Database alias"),
         )
+        .arg(
+            clap::Arg::new("migrations-dir")
+                .long("migrations-dir")
+                .help("Path to migrations directory")
+                .default_value("migrations"),
+        )
     }
 
     async fn handle(
         &self,
         matches: &clap::ArgMatches,
-        _settings: &Settings,
+        settings: &Settings,
     ) -> Result<(), DjangoError> {
         let app_label = matches
             .get_one::<String>("app_label")
@@ -79,8 +150,24 @@ impl ManagementCommand for SqlmigrateCommand {
             DjangoError::ConfigurationError("migration_name is required".to_string())
         })?;
         let backwards = matches.get_flag("backwards");
+        let database = matches
+            .get_one::<String>("database")
+            .map_or("default", String::as_str);
+        let migrations_dir = matches
+            .get_one::<String>("migrations-dir")
+            .map_or("migrations", String::as_str);
 
-        let sql_statements = generate_migration_sql(app_label, migration_name, backwards);
+        let db_settings = settings.databases.get(database).ok_or_else(|| {
+            DjangoError::ConfigurationError(format!("Database '{database}' not configured"))
+        })?;
+
+        let sql_statements = generate_migration_sql(
+            Path::new(migrations_dir),
+            app_label,
+            migration_name,
+            &db_settings.engine,
+            backwards,
+        )?;
 
         for stmt in &sql_statements {
             tracing::info!("{stmt}");
@@ -93,21 +180,84 @@ impl ManagementCommand for SqlmigrateCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use django_rs_db::fields::FieldType;
+    use django_rs_db_migrations::autodetect::MigrationFieldDef;
+    use django_rs_db_migrations::serializer::{migration_file_path, SerializableOperation};
+    use django_rs_db_migrations::ModelOptions;
+
+    const SQLITE: &str = "django_rs.db.backends.sqlite3";
+    const POSTGRES: &str = "django_rs.db.backends.postgresql";
+    const MYSQL: &str = "django_rs.db.backends.mysql";
+
+    fn write_migrations(dir: &Path) {
+        let initial = SerializableMigration {
+            app_label: "blog".into(),
+            name: "0001_initial".into(),
+            dependencies: vec![],
+            initial: true,
+            atomic: true,
+            operations: vec![SerializableOperation::CreateModel {
+                name: "post".into(),
+                fields: vec![MigrationFieldDef::new("id", FieldType::BigAutoField).primary_key()],
+                options: ModelOptions::default(),
+            }],
+        };
+        let title = SerializableMigration {
+            app_label: "blog".into(),
+            name: "0002_post_title".into(),
+            dependencies: vec![("blog".into(), "0001_initial".into())],
+            initial: false,
+            atomic: true,
+            operations: vec![SerializableOperation::AddField {
+                model_name: "post".into(),
+                field: MigrationFieldDef::new("title", FieldType::CharField).max_length(200),
+                preserve_default: true,
+            }],
+        };
+        for migration in [initial, title] {
+            let path = migration_file_path(dir, "blog", &migration.name);
+            migration.write_to_file(&path).unwrap();
+        }
+    }
 
     #[test]
     fn test_generate_migration_sql_forward() {
-        let stmts = generate_migration_sql("blog", "0001_initial", false);
-        assert!(!stmts.is_empty());
-        assert!(stmts[0].contains("forward"));
-        assert!(stmts[0].contains("blog"));
-        assert!(stmts[0].contains("0001_initial"));
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let stmts =
+            generate_migration_sql(dir.path(), "blog", "0001_initial", SQLITE, false).unwrap();
+        assert_eq!(stmts.first().unwrap(), "BEGIN;");
+        assert!(stmts.contains(&"-- Create model post".to_string()));
+        assert!(stmts.iter().any(|s| s.starts_with("CREATE TABLE")));
+        assert_eq!(stmts.last().unwrap(), "COMMIT;");
     }
 
     #[test]
     fn test_generate_migration_sql_backwards() {
-        let stmts = generate_migration_sql("blog", "0001_initial", true);
-        assert!(!stmts.is_empty());
-        assert!(stmts[0].contains("reverse"));
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let stmts = generate_migration_sql(dir.path(), "blog", "0002", POSTGRES, true).unwrap();
+        assert!(stmts.contains(&"-- Undo: Add field title to post".to_string()));
+        assert!(stmts
+            .iter()
+            .any(|s| s.contains("DROP COLUMN") && s.contains("title")));
+    }
+
+    #[test]
+    fn test_generate_migration_sql_mysql_not_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let stmts = generate_migration_sql(dir.path(), "blog", "0002", MYSQL, false).unwrap();
+        assert!(!stmts.iter().any(|s| s == "BEGIN;" || s == "COMMIT;"));
+        assert!(stmts.iter().any(|s| s.contains("ADD COLUMN")));
+    }
+
+    #[test]
+    fn test_generate_migration_sql_unknown_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let result = generate_migration_sql(dir.path(), "blog", "0009", SQLITE, false);
+        assert!(result.is_err());
     }
 
     #[test]
@@ -117,33 +267,51 @@ mod tests {
         assert_eq!(cmd.help(), "Show SQL for a specific migration");
     }
 
-    #[tokio::test]
-    async fn test_sqlmigrate_handle() {
+    async fn run(args: &[&str]) -> Result<(), DjangoError> {
         let cmd = SqlmigrateCommand;
         let cli = clap::Command::new("test")
             .subcommand(cmd.add_arguments(clap::Command::new("sqlmigrate")));
         let matches = cli
-            .try_get_matches_from(["test", "sqlmigrate", "blog", "0001_initial"])
+            .try_get_matches_from(["test", "sqlmigrate"].iter().chain(args))
             .unwrap();
         let (_, sub_matches) = matches.subcommand().unwrap();
+        cmd.handle(sub_matches, &Settings::default()).await
+    }
 
-        let settings = Settings::default();
-        let result = cmd.handle(sub_matches, &settings).await;
-        assert!(result.is_ok());
+    #[tokio::test]
+    async fn test_sqlmigrate_handle() {
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+        run(&["blog", "0001_initial", "--migrations-dir", dir_arg])
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
     async fn test_sqlmigrate_backwards() {
-        let cmd = SqlmigrateCommand;
-        let cli = clap::Command::new("test")
-            .subcommand(cmd.add_arguments(clap::Command::new("sqlmigrate")));
-        let matches = cli
-            .try_get_matches_from(["test", "sqlmigrate", "blog", "0001_initial", "--backwards"])
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+        run(&["blog", "0002", "--backwards", "--migrations-dir", dir_arg])
+            .await
             .unwrap();
-        let (_, sub_matches) = matches.subcommand().unwrap();
+    }
 
-        let settings = Settings::default();
-        let result = cmd.handle(sub_matches, &settings).await;
-        assert!(result.is_ok());
+    #[tokio::test]
+    async fn test_sqlmigrate_unknown_database() {
+        let dir = tempfile::tempdir().unwrap();
+        write_migrations(dir.path());
+        let dir_arg = dir.path().to_str().unwrap();
+        let result = run(&[
+            "blog",
+            "0001",
+            "--database",
+            "replica",
+            "--migrations-dir",
+            dir_arg,
+        ])
+        .await;
+        assert!(result.is_err());
     }
 }
@@ -0,0 +1,126 @@
+//! The `startapp` management command.
+//!
+//! Creates a new app skeleton: a library crate with models, views, URL
+//! patterns and a namespaced template directory. This mirrors Django's
+//! `startapp` command.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, Settings};
+
+use crate::command::ManagementCommand;
+use crate::scaffold::{validate_name, write_template, APP_TEMPLATE};
+
+/// Creates an app crate from the embedded app template.
+///
+/// Inside a project created by `startproject`, the app is written to
+/// `apps/<name>/`, which the project's workspace picks up. Elsewhere it is
+/// written to `<name>/`, or to the given directory.
+pub struct StartappCommand;
+
+/// Returns where `startapp` writes an app named `name` when no directory is
+/// given.
+pub fn default_app_dir(name: &str) -> PathBuf {
+    let apps = Path::new("apps");
+    if apps.is_dir() {
+        apps.join(name)
+    } else {
+        PathBuf::from(name)
+    }
+}
+
+/// Writes a new app named `name` into `target`.
+///
+/// Returns the written file paths.
+pub async fn start_app(name: &str, target: &Path) -> Result<Vec<PathBuf>, DjangoError> {
+    validate_name(name, "app")?;
+    write_template(
+        APP_TEMPLATE,
+        target,
+        &[
+            ("app_name", name),
+            ("django_rs_version", env!("CARGO_PKG_VERSION")),
+        ],
+    )
+    .await
+}
+
+#[async_trait]
+impl ManagementCommand for StartappCommand {
+    fn name(&self) -> &'static str {
+        "startapp"
+    }
+
+    fn help(&self) -> &'static str {
+        "Create a new app in the current project"
+    }
+
+    fn add_arguments(&self, cmd: clap::Command) -> clap::Command {
+        cmd.arg(
+            clap::Arg::new("name")
+                .help("Name of the app (a valid crate name)")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("directory")
+                .help("Directory to create the app in (defaults to ./apps/<name> or ./<name>)"),
+        )
+    }
+
+    async fn handle(
+        &self,
+        matches: &clap::ArgMatches,
+        _settings: &Settings,
+    ) -> Result<(), DjangoError> {
+        let name = matches
+            .get_one::<String>("name")
+            .ok_or_else(|| DjangoError::ConfigurationError("App name is required".into()))?;
+        let target = matches
+            .get_one::<String>("directory")
+            .map_or_else(|| default_app_dir(name), PathBuf::from);
+
+        start_app(name, &target).await?;
+        tracing::info!(
+            "Created app '{name}' in {}. Add \"{name}\" to installed_apps and depend on it \
+             from the project to mount its URLs.",
+            target.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_metadata() {
+        let cmd = StartappCommand;
+        assert_eq!(cmd.name(), "startapp");
+        assert_eq!(cmd.help(), "Create a new app in the current project");
+    }
+
+    #[tokio::test]
+    async fn test_start_app() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("apps").join("blog");
+        start_app("blog", &target).await.unwrap();
+
+        for file in [
+            "Cargo.toml",
+            "src/lib.rs",
+            "src/models.rs",
+            "src/views.rs",
+            "src/urls.rs",
+            "templates/blog/index.html",
+        ] {
+            assert!(target.join(file).is_file(), "missing {file}");
+        }
+        let lib = std::fs::read_to_string(target.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub const APP_LABEL: &str = \"blog\";"));
+
+        // A second app can't overwrite the first.
+        assert!(start_app("blog", &target).await.is_err());
+    }
+}
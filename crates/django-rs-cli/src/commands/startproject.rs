@@ -0,0 +1,138 @@
+//! The `startproject` management command.
+//!
+//! Creates a new project skeleton: a cargo workspace with `settings.toml`,
+//! a URL configuration, a sample model, base templates and a `.gitignore`.
+//! This mirrors Django's `startproject` command.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, Settings};
+
+use crate::command::ManagementCommand;
+use crate::scaffold::{random_secret_key, validate_name, write_template, PROJECT_TEMPLATE};
+
+/// Creates a project directory from the embedded project template.
+///
+/// The project is written to `<name>/`, or to the given directory, which
+/// must not exist or be empty.
+pub struct StartprojectCommand;
+
+/// Writes a new project named `name` into `target`.
+///
+/// Returns the written file paths.
+pub async fn start_project(name: &str, target: &Path) -> Result<Vec<PathBuf>, DjangoError> {
+    validate_name(name, "project")?;
+    let secret_key = random_secret_key();
+    write_template(
+        PROJECT_TEMPLATE,
+        target,
+        &[
+            ("project_name", name),
+            ("secret_key", &secret_key),
+            ("django_rs_version", env!("CARGO_PKG_VERSION")),
+        ],
+    )
+    .await
+}
+
+#[async_trait]
+impl ManagementCommand for StartprojectCommand {
+    fn name(&self) -> &'static str {
+        "startproject"
+    }
+
+    fn help(&self) -> &'static str {
+        "Create a new django-rs project"
+    }
+
+    fn add_arguments(&self, cmd: clap::Command) -> clap::Command {
+        cmd.arg(
+            clap::Arg::new("name")
+                .help("Name of the project (a valid crate name)")
+                .required(true),
+        )
+        .arg(
+            clap::Arg::new("directory")
+                .help("Directory to create the project in (defaults to ./<name>)"),
+        )
+    }
+
+    async fn handle(
+        &self,
+        matches: &clap::ArgMatches,
+        _settings: &Settings,
+    ) -> Result<(), DjangoError> {
+        let name = matches
+            .get_one::<String>("name")
+            .ok_or_else(|| DjangoError::ConfigurationError("Project name is required".into()))?;
+        let target = matches
+            .get_one::<String>("directory")
+            .map_or_else(|| PathBuf::from(name), PathBuf::from);
+
+        let files = start_project(name, &target).await?;
+        tracing::info!(
+            "Created project '{name}' in {} ({} files). Run `cargo run` there to start it.",
+            target.display(),
+            files.len()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_metadata() {
+        let cmd = StartprojectCommand;
+        assert_eq!(cmd.name(), "startproject");
+        assert_eq!(cmd.help(), "Create a new django-rs project");
+    }
+
+    #[tokio::test]
+    async fn test_start_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("shop");
+        start_project("shop", &target).await.unwrap();
+
+        for file in [
+            "Cargo.toml",
+            "settings.toml",
+            ".gitignore",
+            "src/main.rs",
+            "src/urls.rs",
+            "src/models.rs",
+            "templates/base.html",
+            "templates/index.html",
+        ] {
+            assert!(target.join(file).is_file(), "missing {file}");
+        }
+
+        let manifest = std::fs::read_to_string(target.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"shop\""));
+        assert!(manifest.contains(env!("CARGO_PKG_VERSION")));
+        let models = std::fs::read_to_string(target.join("src/models.rs")).unwrap();
+        assert!(models.contains("#[model(app = \"shop\""));
+
+        // The generated settings load, with a fresh secret key.
+        let settings =
+            django_rs_core::settings_loader::from_toml_file(target.join("settings.toml")).unwrap();
+        assert_eq!(settings.secret_key.len(), 50);
+        assert_eq!(settings.databases["default"].name, "shop.sqlite3");
+        assert_eq!(settings.templates[0].dirs, vec![PathBuf::from("templates")]);
+
+        // HTML template tags are left for the template engine.
+        let base = std::fs::read_to_string(target.join("templates/base.html")).unwrap();
+        assert!(base.contains("{% block content %}"));
+    }
+
+    #[tokio::test]
+    async fn test_start_project_invalid_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("my-shop");
+        assert!(start_project("my-shop", &target).await.is_err());
+        assert!(!target.exists());
+    }
+}
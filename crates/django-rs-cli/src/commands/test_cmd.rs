@@ -2,17 +2,35 @@
 //!
 //! Runs the project test suite by shelling out to `cargo test`. This mirrors
 //! Django's `test` command which delegates to the configured test runner.
+//!
+//! Tests can be selected by name (`-k`) and by tag (`--tag`,
+//! `--exclude-tag`). Rust tests have no tag attribute, so a test carries a
+//! tag when a segment of its path is the tag (`mod slow { ... }`) or its
+//! function name starts with `{tag}_` or ends with `_{tag}`.
+//!
+//! With `--parallel N` the selected tests are split into `N` shards run by
+//! concurrent `cargo test` processes. Each shard sees its number in
+//! [`TEST_SHARD_ENV`] and uses its own test databases (see
+//! [`django_rs_test::test_database`]). SQLite test databases are removed
+//! before and after the run unless `--keepdb` is given.
+
+use std::collections::BTreeSet;
 
 use async_trait::async_trait;
 use django_rs_core::{DjangoError, Settings};
+use tokio::task::JoinSet;
 
 use crate::command::ManagementCommand;
 
+pub use django_rs_core::settings::TEST_SHARD_ENV;
+
 /// Runs the project test suite.
 ///
-/// Executes `cargo test` with the supplied arguments. Supports `--app` to
-/// restrict testing to a specific crate, and `--verbosity` to control output.
-/// Additional arguments after `--` are forwarded directly to `cargo test`.
+/// Executes `cargo test` with the supplied arguments. The positional
+/// argument restricts testing to a specific crate, `-k` selects tests by
+/// name, `--tag`/`--exclude-tag` by tag, and `--parallel` splits the run into
+/// shards. Additional arguments after `--` are forwarded directly to the
+/// test binaries.
 pub struct TestCommand;
 
 /// Builds the argument list for `cargo test` based on the parsed CLI arguments.
@@ -51,6 +69,143 @@ pub fn build_cargo_test_args(
     args
 }
 
+/// Builds the `cargo test` arguments listing the tests of the given crate
+/// (the whole workspace if `None`).
+pub fn build_cargo_list_args(app_label: Option<&str>) -> Vec<String> {
+    let mut args = build_cargo_test_args(app_label, 1, false, &[]);
+    args.extend(["--", "--list", "--format", "terse"].map(String::from));
+    args
+}
+
+/// Parses the output of `cargo test -- --list --format terse` into sorted,
+/// de-duplicated test names.
+pub fn parse_test_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .map(str::to_string)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Returns `true` if the test at `path` carries `tag`.
+///
+/// A test carries a tag when a `::` segment of its path equals the tag, or
+/// its function name starts with `{tag}_` or ends with `_{tag}`.
+pub fn has_tag(path: &str, tag: &str) -> bool {
+    let name = path.rsplit("::").next().unwrap_or(path);
+    path.split("::").any(|segment| segment == tag)
+        || name.starts_with(&format!("{tag}_"))
+        || name.ends_with(&format!("_{tag}"))
+}
+
+/// Selects the tests matching any of `patterns` (all if empty), carrying any
+/// of `tags` (all if empty) and none of `exclude_tags`.
+pub fn select_tests(
+    tests: &[String],
+    patterns: &[String],
+    tags: &[String],
+    exclude_tags: &[String],
+) -> Vec<String> {
+    tests
+        .iter()
+        .filter(|test| patterns.is_empty() || patterns.iter().any(|p| test.contains(p.as_str())))
+        .filter(|test| tags.is_empty() || tags.iter().any(|tag| has_tag(test, tag)))
+        .filter(|test| !exclude_tags.iter().any(|tag| has_tag(test, tag)))
+        .cloned()
+        .collect()
+}
+
+/// Splits `tests` round-robin into at most `shards` non-empty shards.
+pub fn shard_tests(tests: &[String], shards: usize) -> Vec<Vec<String>> {
+    let mut result = vec![Vec::new(); shards.clamp(1, tests.len().max(1))];
+    let count = result.len();
+    for (i, test) in tests.iter().enumerate() {
+        result[i % count].push(test.clone());
+    }
+    result.retain(|shard| !shard.is_empty());
+    result
+}
+
+/// Removes the SQLite test databases of every configured database for the
+/// given shards.
+fn destroy_test_databases(
+    settings: &Settings,
+    shards: &[Option<usize>],
+) -> Result<(), DjangoError> {
+    #[cfg(feature = "sqlite")]
+    for db in settings.databases.values() {
+        for &shard in shards {
+            if django_rs_test::test_database::destroy_test_database(db, shard)? {
+                tracing::debug!(
+                    "Destroyed test database {}",
+                    django_rs_test::test_database::test_database_name(&db.name, shard)
+                );
+            }
+        }
+    }
+    #[cfg(not(feature = "sqlite"))]
+    let _ = (settings, shards);
+    Ok(())
+}
+
+/// Lists the tests of the given crate by running `cargo test -- --list`.
+async fn list_tests(app_label: Option<&str>) -> Result<Vec<String>, DjangoError> {
+    let output = tokio::process::Command::new("cargo")
+        .args(build_cargo_list_args(app_label))
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .await
+        .map_err(|e| DjangoError::InternalServerError(format!("Failed to list tests: {e}")))?;
+    if !output.status.success() {
+        return Err(DjangoError::InternalServerError(format!(
+            "Listing tests failed with exit code: {}",
+            output.status.code().unwrap_or(-1)
+        )));
+    }
+    Ok(parse_test_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Runs one `cargo test` process per argument list, concurrently.
+///
+/// Each process gets its 1-based shard number in [`TEST_SHARD_ENV`] when
+/// there is more than one. With `failfast`, the remaining processes are
+/// killed as soon as one fails. Returns the failed shard numbers.
+async fn run_shards(runs: Vec<Vec<String>>, failfast: bool) -> Result<Vec<usize>, DjangoError> {
+    let sharded = runs.len() > 1;
+    let mut tasks = JoinSet::new();
+    for (i, args) in runs.into_iter().enumerate() {
+        let mut command = tokio::process::Command::new("cargo");
+        command.args(&args).kill_on_drop(true);
+        if sharded {
+            command.env(TEST_SHARD_ENV, (i + 1).to_string());
+        }
+        let mut child = command.spawn().map_err(|e| {
+            DjangoError::InternalServerError(format!("Failed to run cargo test: {e}"))
+        })?;
+        tasks.spawn(async move { (i + 1, child.wait().await) });
+    }
+
+    let mut failed = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (shard, status) =
+            joined.map_err(|e| DjangoError::InternalServerError(e.to_string()))?;
+        let status = status.map_err(|e| {
+            DjangoError::InternalServerError(format!("Failed to run cargo test: {e}"))
+        })?;
+        if !status.success() {
+            failed.push(shard);
+            if failfast {
+                tasks.abort_all();
+                break;
+            }
+        }
+    }
+    failed.sort_unstable();
+    Ok(failed)
+}
+
 #[async_trait]
 impl ManagementCommand for TestCommand {
     fn name(&self) -> &'static str {
@@ -80,6 +235,38 @@ impl ManagementCommand for TestCommand {
                 .action(clap::ArgAction::SetTrue)
                 .help("Stop on first test failure"),
         )
+        .arg(
+            clap::Arg::new("pattern")
+                .short('k')
+                .long("pattern")
+                .action(clap::ArgAction::Append)
+                .help("Only run tests whose name contains this pattern (repeatable)"),
+        )
+        .arg(
+            clap::Arg::new("tag")
+                .long("tag")
+                .action(clap::ArgAction::Append)
+                .help("Only run tests carrying this tag (repeatable)"),
+        )
+        .arg(
+            clap::Arg::new("exclude_tag")
+                .long("exclude-tag")
+                .action(clap::ArgAction::Append)
+                .help("Skip tests carrying this tag (repeatable)"),
+        )
+        .arg(
+            clap::Arg::new("parallel")
+                .long("parallel")
+                .default_value("1")
+                .value_parser(clap::value_parser!(usize))
+                .help("Split the tests into this many concurrently running shards"),
+        )
+        .arg(
+            clap::Arg::new("keepdb")
+                .long("keepdb")
+                .action(clap::ArgAction::SetTrue)
+                .help("Keep test databases between runs"),
+        )
         .arg(
             clap::Arg::new("extra")
                 .last(true)
@@ -91,37 +278,82 @@ impl ManagementCommand for TestCommand {
     async fn handle(
         &self,
         matches: &clap::ArgMatches,
-        _settings: &Settings,
+        settings: &Settings,
     ) -> Result<(), DjangoError> {
+        let strings = |id: &str| -> Vec<String> {
+            matches
+                .get_many::<String>(id)
+                .map_or_else(Vec::new, |vals| vals.cloned().collect())
+        };
         let app_label = matches.get_one::<String>("app_label").map(String::as_str);
         let verbosity: u8 = matches
             .get_one::<String>("verbosity")
             .and_then(|v| v.parse().ok())
             .unwrap_or(1);
         let failfast = matches.get_flag("failfast");
-        let extra_args: Vec<String> = matches
-            .get_many::<String>("extra")
-            .map_or_else(Vec::new, |vals| vals.cloned().collect());
+        let keepdb = matches.get_flag("keepdb");
+        let parallel = matches.get_one::<usize>("parallel").copied().unwrap_or(1);
+        let patterns = strings("pattern");
+        let tags = strings("tag");
+        let exclude_tags = strings("exclude_tag");
+        let extra_args = strings("extra");
 
-        let args = build_cargo_test_args(app_label, verbosity, failfast, &extra_args);
+        let runs = if parallel <= 1 && tags.is_empty() && exclude_tags.is_empty() {
+            let filters: Vec<String> = patterns.into_iter().chain(extra_args).collect();
+            vec![build_cargo_test_args(
+                app_label, verbosity, failfast, &filters,
+            )]
+        } else {
+            let tests = select_tests(
+                &list_tests(app_label).await?,
+                &patterns,
+                &tags,
+                &exclude_tags,
+            );
+            if tests.is_empty() {
+                tracing::info!("No tests matched");
+                return Ok(());
+            }
+            let shards = shard_tests(&tests, parallel);
+            tracing::info!("Running {} tests in {} shard(s)", tests.len(), shards.len());
+            shards
+                .into_iter()
+                .map(|shard| {
+                    let filters: Vec<String> = std::iter::once("--exact".to_string())
+                        .chain(shard)
+                        .chain(extra_args.iter().cloned())
+                        .collect();
+                    build_cargo_test_args(app_label, verbosity, failfast, &filters)
+                })
+                .collect()
+        };
 
-        tracing::info!("Running: cargo {}", args.join(" "));
+        let shard_numbers: Vec<Option<usize>> = if runs.len() > 1 {
+            (1..=runs.len()).map(Some).collect()
+        } else {
+            vec![None]
+        };
+        if !keepdb {
+            destroy_test_databases(settings, &shard_numbers)?;
+        }
+        for args in &runs {
+            tracing::debug!("Running: cargo {}", args.join(" "));
+        }
 
-        let status = tokio::process::Command::new("cargo")
-            .args(&args)
-            .status()
-            .await
-            .map_err(|e| {
-                DjangoError::InternalServerError(format!("Failed to run cargo test: {e}"))
-            })?;
+        let failed = run_shards(runs, failfast).await;
+        if !keepdb {
+            destroy_test_databases(settings, &shard_numbers)?;
+        }
 
-        if status.success() {
+        let failed = failed?;
+        if failed.is_empty() {
             tracing::info!("All tests passed");
             Ok(())
         } else {
+            let shards: Vec<String> = failed.iter().map(ToString::to_string).collect();
             Err(DjangoError::InternalServerError(format!(
-                "Tests failed with exit code: {}",
-                status.code().unwrap_or(-1)
+                "Tests failed in shard(s): {}",
+                shards.join(", ")
             )))
         }
     }
@@ -158,6 +390,110 @@ mod tests {
         assert!(args.contains(&"test_name".to_string()));
     }
 
+    #[test]
+    fn test_build_cargo_list_args() {
+        let args = build_cargo_list_args(Some("shop"));
+        assert_eq!(
+            args,
+            vec![
+                "test",
+                "--package",
+                "shop",
+                "--",
+                "--list",
+                "--format",
+                "terse"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_test_list() {
+        let output = "models::tests::test_save: test\n\
+                      views::slow::test_export: test\n\
+                      bench_sort: benchmark\n\
+                      models::tests::test_save: test\n\
+                      src/lib.rs - Order (line 12): test\n";
+        assert_eq!(
+            parse_test_list(output),
+            vec![
+                "models::tests::test_save",
+                "src/lib.rs - Order (line 12)",
+                "views::slow::test_export",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_tag() {
+        assert!(has_tag("views::slow::test_export", "slow"));
+        assert!(has_tag("views::tests::slow_test_export", "slow"));
+        assert!(has_tag("views::tests::test_export_slow", "slow"));
+        assert!(!has_tag("views::tests::test_slowness", "slow"));
+    }
+
+    #[test]
+    fn test_select_tests() {
+        let tests: Vec<String> = [
+            "models::tests::test_save",
+            "models::tests::test_save_slow",
+            "views::integration::test_list",
+            "views::tests::test_detail",
+        ]
+        .map(String::from)
+        .to_vec();
+        let s = |v: &[&str]| v.iter().map(|x| (*x).to_string()).collect::<Vec<_>>();
+
+        assert_eq!(select_tests(&tests, &[], &[], &[]), tests);
+        assert_eq!(
+            select_tests(&tests, &s(&["save"]), &[], &s(&["slow"])),
+            s(&["models::tests::test_save"])
+        );
+        assert_eq!(
+            select_tests(&tests, &[], &s(&["integration", "slow"]), &[]),
+            s(&[
+                "models::tests::test_save_slow",
+                "views::integration::test_list"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_shard_tests() {
+        let tests: Vec<String> = (1..=5).map(|i| format!("t{i}")).collect();
+        let shards = shard_tests(&tests, 2);
+        assert_eq!(shards, vec![vec!["t1", "t3", "t5"], vec!["t2", "t4"]]);
+        assert_eq!(shard_tests(&tests[..2], 4).len(), 2);
+        assert_eq!(shard_tests(&tests, 0).len(), 1);
+        assert!(shard_tests(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_command_arguments() {
+        let cmd = TestCommand.add_arguments(clap::Command::new("test"));
+        let matches = cmd
+            .try_get_matches_from([
+                "test",
+                "-k",
+                "save",
+                "--tag",
+                "slow",
+                "--parallel",
+                "4",
+                "--keepdb",
+            ])
+            .unwrap();
+        assert_eq!(
+            matches
+                .get_many::<String>("pattern")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec!["save"]
+        );
+        assert_eq!(matches.get_one::<usize>("parallel"), Some(&4));
+        assert!(matches.get_flag("keepdb"));
+    }
+
     #[test]
     fn test_command_metadata() {
         let cmd = TestCommand;
@@ -0,0 +1,137 @@
+//! The `validatetemplates` management command.
+//!
+//! Parses every template of every configured template engine and reports
+//! syntax errors, unknown tags and filters, missing `{% extends %}` targets
+//! and overridden blocks that no parent defines. It exits with an error when
+//! any issue is found, so it can gate deploys in CI.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, Settings};
+use django_rs_template::loaders::AppDirectoriesLoader;
+use django_rs_template::validation::{validate_templates, TemplateIssue};
+use django_rs_template::Engine;
+
+use crate::command::ManagementCommand;
+
+/// Validates all templates without rendering them.
+pub struct ValidatetemplatesCommand;
+
+/// Validates the templates of every engine in `settings.templates`.
+///
+/// Engines with `app_dirs` enabled also search `<app>/templates/` for each
+/// installed app, where a dotted app name maps to a relative directory
+/// (`myapp.blog` to `myapp/blog`), as laid out by `startapp`.
+pub fn validate_configured_templates(settings: &Settings) -> Vec<TemplateIssue> {
+    let mut issues = Vec::new();
+    for template_settings in &settings.templates {
+        let mut engine = Engine::from_settings(template_settings);
+        if template_settings.app_dirs {
+            let app_dirs = settings
+                .installed_apps
+                .iter()
+                .map(|app| app.split('.').collect::<PathBuf>())
+                .collect();
+            engine.add_loader(Box::new(AppDirectoriesLoader::new(app_dirs)));
+        }
+        issues.extend(validate_templates(&engine));
+    }
+    issues
+}
+
+#[async_trait]
+impl ManagementCommand for ValidatetemplatesCommand {
+    fn name(&self) -> &'static str {
+        "validatetemplates"
+    }
+
+    fn help(&self) -> &'static str {
+        "Check all templates for syntax errors and broken references"
+    }
+
+    async fn handle(
+        &self,
+        _matches: &clap::ArgMatches,
+        settings: &Settings,
+    ) -> Result<(), DjangoError> {
+        let issues = validate_configured_templates(settings);
+
+        if issues.is_empty() {
+            tracing::info!("Template validation identified no issues");
+            return Ok(());
+        }
+
+        for issue in &issues {
+            tracing::error!("{issue}");
+        }
+        Err(DjangoError::ConfigurationError(format!(
+            "Template validation found {} issue(s)",
+            issues.len()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_core::settings::TemplateSettings;
+    use django_rs_template::validation::IssueKind;
+
+    #[test]
+    fn test_validate_configured_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.html"),
+            "{% block body %}{% endblock %}",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("blog")).unwrap();
+        std::fs::write(
+            dir.path().join("blog/post.html"),
+            r#"{% extends "base.html" %}{% block body %}{{ post|nosuch }}{% endblock %}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.html"), "{% for %}").unwrap();
+
+        let settings = Settings {
+            templates: vec![TemplateSettings {
+                dirs: vec![dir.path().to_path_buf()],
+                ..TemplateSettings::default()
+            }],
+            ..Settings::default()
+        };
+        let issues = validate_configured_templates(&settings);
+        let found: Vec<(&str, IssueKind)> = issues
+            .iter()
+            .map(|issue| (issue.template.as_str(), issue.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("blog/post.html", IssueKind::UnknownFilter),
+                ("broken.html", IssueKind::SyntaxError),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_fails_on_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("page.html"), r#"{% extends "gone.html" %}"#).unwrap();
+        let settings = Settings {
+            templates: vec![TemplateSettings {
+                dirs: vec![dir.path().to_path_buf()],
+                ..TemplateSettings::default()
+            }],
+            ..Settings::default()
+        };
+
+        let cmd = ValidatetemplatesCommand;
+        let matches = cmd
+            .add_arguments(clap::Command::new("validatetemplates"))
+            .get_matches_from(["validatetemplates"]);
+        let err = cmd.handle(&matches, &settings).await.unwrap_err();
+        assert!(err.to_string().contains("1 issue(s)"));
+    }
+}
@@ -0,0 +1,554 @@
+//! Streaming fixture files.
+//!
+//! Fixtures with millions of rows cannot be held in memory as one JSON
+//! document. This module reads and writes them one object at a time, used by
+//! the `dumpdata` and `loaddata` commands.
+//!
+//! ## Formats
+//!
+//! - [`FixtureFormat::Json`] - a JSON array, as produced by Django
+//! - [`FixtureFormat::JsonLines`] - one JSON object per line (`.jsonl`)
+//!
+//! Files ending in `.gz` are gzip-compressed and files ending in `.zst` are
+//! zstd-compressed; the format is taken from the extension before that, so
+//! `data.jsonl.zst` is zstd-compressed JSON lines.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use django_rs_core::{DjangoError, Settings};
+use django_rs_db::DbExecutor;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+
+/// The layout of objects in a fixture file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixtureFormat {
+    /// A single JSON array of objects.
+    #[default]
+    Json,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+impl FixtureFormat {
+    /// Parses a format name as given to `--format` (`json` or `jsonl`).
+    pub fn from_name(name: &str) -> Result<Self, DjangoError> {
+        match name {
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
+            other => Err(DjangoError::SerializationError(format!(
+                "Unknown fixture format '{other}' (expected 'json' or 'jsonl')"
+            ))),
+        }
+    }
+
+    /// Infers the format from a file name, ignoring any compression suffix.
+    pub fn from_path(path: &Path) -> Self {
+        let name = path.to_string_lossy();
+        let name = FixtureCompression::from_path(path).strip_suffix(&name);
+        if name.to_ascii_lowercase().ends_with(".jsonl") {
+            Self::JsonLines
+        } else {
+            Self::Json
+        }
+    }
+}
+
+/// The compression applied to a fixture file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixtureCompression {
+    /// Plain text.
+    #[default]
+    None,
+    /// gzip (`.gz`).
+    Gzip,
+    /// zstd (`.zst`).
+    Zstd,
+}
+
+impl FixtureCompression {
+    /// Infers the compression from a file name's last extension.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("gz") => Self::Gzip,
+            Some("zst" | "zstd") => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+
+    /// Returns the file name extension for this compression, if any.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gz"),
+            Self::Zstd => Some("zst"),
+        }
+    }
+
+    fn strip_suffix(self, name: &str) -> &str {
+        name.rfind('.')
+            .filter(|_| self != Self::None)
+            .map_or(name, |dot| &name[..dot])
+    }
+}
+
+/// Opens a fixture file for reading, decompressing it if its extension
+/// says so.
+pub fn open_fixture(path: &Path) -> Result<Box<dyn BufRead + Send>, DjangoError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            DjangoError::NotFound(format!("Fixture file not found: {}", path.display()))
+        } else {
+            DjangoError::IoError(e)
+        }
+    })?;
+    let reader: Box<dyn BufRead + Send> = match FixtureCompression::from_path(path) {
+        FixtureCompression::None => Box::new(BufReader::new(file)),
+        FixtureCompression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        FixtureCompression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+    };
+    Ok(reader)
+}
+
+/// A fixture destination: stdout or a file, possibly compressed.
+///
+/// Compressed streams must be terminated, so call [`finish`](Self::finish)
+/// once everything has been written.
+pub struct FixtureOutput {
+    inner: OutputInner,
+}
+
+enum OutputInner {
+    Stdout(BufWriter<io::Stdout>),
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl FixtureOutput {
+    /// Writes to stdout, uncompressed.
+    pub fn stdout() -> Self {
+        Self {
+            inner: OutputInner::Stdout(BufWriter::new(io::stdout())),
+        }
+    }
+
+    /// Creates (or truncates) the file at `path`, compressing it if its
+    /// extension says so.
+    pub fn create(path: &Path) -> Result<Self, DjangoError> {
+        let file = File::create(path).map_err(|e| {
+            DjangoError::IoError(io::Error::new(
+                e.kind(),
+                format!("Failed to write to {}: {e}", path.display()),
+            ))
+        })?;
+        let file = BufWriter::new(file);
+        let inner = match FixtureCompression::from_path(path) {
+            FixtureCompression::None => OutputInner::Plain(file),
+            FixtureCompression::Gzip => {
+                OutputInner::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+            }
+            FixtureCompression::Zstd => OutputInner::Zstd(zstd::Encoder::new(file, 0)?),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Flushes the output, writing the compression trailer if needed.
+    pub fn finish(self) -> io::Result<()> {
+        match self.inner {
+            OutputInner::Stdout(mut w) => w.flush(),
+            OutputInner::Plain(mut w) => w.flush(),
+            OutputInner::Gzip(w) => w.finish()?.flush(),
+            OutputInner::Zstd(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for FixtureOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            OutputInner::Stdout(w) => w.write(buf),
+            OutputInner::Plain(w) => w.write(buf),
+            OutputInner::Gzip(w) => w.write(buf),
+            OutputInner::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.inner {
+            OutputInner::Stdout(w) => w.flush(),
+            OutputInner::Plain(w) => w.flush(),
+            OutputInner::Gzip(w) => w.flush(),
+            OutputInner::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Writes fixture objects to `W` one at a time.
+///
+/// In [`FixtureFormat::Json`] the output is the same as serializing the
+/// whole array at once (pretty-printed if `indent` is set); in
+/// [`FixtureFormat::JsonLines`] each object is written compactly on its own
+/// line and `indent` is ignored.
+pub struct FixtureWriter<W: Write> {
+    inner: W,
+    format: FixtureFormat,
+    indent: bool,
+    count: usize,
+}
+
+impl<W: Write> FixtureWriter<W> {
+    /// Creates a writer producing `format` into `inner`.
+    pub fn new(inner: W, format: FixtureFormat, indent: bool) -> Self {
+        Self {
+            inner,
+            format,
+            indent,
+            count: 0,
+        }
+    }
+
+    /// Returns the number of objects written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes one object.
+    pub fn write_object(&mut self, object: &serde_json::Value) -> Result<(), DjangoError> {
+        match self.format {
+            FixtureFormat::JsonLines => {
+                serde_json::to_writer(&mut self.inner, object)
+                    .map_err(|e| DjangoError::SerializationError(e.to_string()))?;
+                self.inner.write_all(b"\n")?;
+            }
+            FixtureFormat::Json if self.indent => {
+                self.inner
+                    .write_all(if self.count == 0 { b"[\n" } else { b",\n" })?;
+                let pretty = serde_json::to_string_pretty(object)
+                    .map_err(|e| DjangoError::SerializationError(e.to_string()))?;
+                // JSON strings never contain raw newlines, so indenting every
+                // line nests the object one level inside the array.
+                for (i, line) in pretty.lines().enumerate() {
+                    if i > 0 {
+                        self.inner.write_all(b"\n")?;
+                    }
+                    self.inner.write_all(b"  ")?;
+                    self.inner.write_all(line.as_bytes())?;
+                }
+            }
+            FixtureFormat::Json => {
+                self.inner
+                    .write_all(if self.count == 0 { b"[" } else { b"," })?;
+                serde_json::to_writer(&mut self.inner, object)
+                    .map_err(|e| DjangoError::SerializationError(e.to_string()))?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Terminates the document and returns the inner writer.
+    pub fn finish(mut self) -> Result<W, DjangoError> {
+        if self.format == FixtureFormat::Json {
+            let end: &[u8] = match (self.count, self.indent) {
+                (0, _) => b"[]",
+                (_, true) => b"\n]",
+                (_, false) => b"]",
+            };
+            self.inner.write_all(end)?;
+        }
+        Ok(self.inner)
+    }
+}
+
+/// Reads fixture objects from `reader`, calling `f` with each one as soon as
+/// it has been parsed.
+///
+/// Both formats are accepted: input starting with `[` is read as a JSON
+/// array, anything else as a sequence of objects (JSON lines). Returns the
+/// number of objects read. Reading stops at the first error, including one
+/// returned by `f`.
+pub fn read_fixture_objects<R, F>(mut reader: R, mut f: F) -> Result<usize, DjangoError>
+where
+    R: BufRead,
+    F: FnMut(serde_json::Value) -> Result<(), DjangoError>,
+{
+    let is_array = loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if let Some(i) = buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            let first = buf[i];
+            reader.consume(i);
+            break first == b'[';
+        }
+        let len = buf.len();
+        reader.consume(len);
+    };
+
+    if !is_array {
+        let mut count = 0;
+        for object in serde_json::Deserializer::from_reader(reader).into_iter() {
+            f(object.map_err(|e| DjangoError::SerializationError(e.to_string()))?)?;
+            count += 1;
+        }
+        return Ok(count);
+    }
+
+    let mut visitor = ArrayVisitor {
+        f: &mut f,
+        error: None,
+    };
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    let result = (&mut visitor).deserialize(&mut de).and_then(|count| {
+        de.end()?;
+        Ok(count)
+    });
+    match (result, visitor.error) {
+        (_, Some(error)) => Err(error),
+        (Ok(count), None) => Ok(count),
+        (Err(e), None) => Err(DjangoError::SerializationError(e.to_string())),
+    }
+}
+
+/// Visits the elements of a JSON array without collecting them.
+struct ArrayVisitor<'f, F> {
+    f: &'f mut F,
+    /// The error returned by `f`, which serde can only carry as a string.
+    error: Option<DjangoError>,
+}
+
+impl<'de, F> DeserializeSeed<'de> for &mut ArrayVisitor<'_, F>
+where
+    F: FnMut(serde_json::Value) -> Result<(), DjangoError>,
+{
+    type Value = usize;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for &mut ArrayVisitor<'_, F>
+where
+    F: FnMut(serde_json::Value) -> Result<(), DjangoError>,
+{
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an array of fixture objects")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(object) = seq.next_element::<serde_json::Value>()? {
+            if let Err(e) = (self.f)(object) {
+                let message = e.to_string();
+                self.error = Some(e);
+                return Err(de::Error::custom(message));
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Connects to the database configured under `alias`.
+///
+/// Only SQLite databases can be opened from the command line; other engines
+/// are reported as [`DjangoError::ImproperlyConfigured`].
+pub fn connect_database(
+    settings: &Settings,
+    alias: &str,
+) -> Result<Box<dyn DbExecutor>, DjangoError> {
+    let Some(db) = settings.databases.get(alias) else {
+        return Err(DjangoError::ConfigurationError(format!(
+            "Unknown database '{alias}'"
+        )));
+    };
+    if !db.engine.contains("sqlite") {
+        return Err(DjangoError::ImproperlyConfigured(format!(
+            "Cannot connect to '{}' databases from the command line",
+            db.engine
+        )));
+    }
+    #[cfg(feature = "sqlite")]
+    {
+        Ok(Box::new(django_rs_db_backends::SqliteBackend::open(
+            &db.name,
+        )?))
+    }
+    #[cfg(not(feature = "sqlite"))]
+    Err(DjangoError::ImproperlyConfigured(
+        "Connecting to SQLite databases requires the 'sqlite' feature".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn objects() -> Vec<serde_json::Value> {
+        vec![
+            json!({"model": "blog.post", "pk": 1, "fields": {"title": "a\nb"}}),
+            json!({"model": "blog.post", "pk": 2, "fields": {"title": "c", "tags": [1, 2]}}),
+        ]
+    }
+
+    fn write_all(format: FixtureFormat, indent: bool) -> String {
+        let mut writer = FixtureWriter::new(Vec::new(), format, indent);
+        for object in objects() {
+            writer.write_object(&object).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    fn read_all(data: &[u8]) -> Vec<serde_json::Value> {
+        let mut read = Vec::new();
+        read_fixture_objects(data, |object| {
+            read.push(object);
+            Ok(())
+        })
+        .unwrap();
+        read
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            FixtureFormat::from_path(Path::new("data.json")),
+            FixtureFormat::Json
+        );
+        assert_eq!(
+            FixtureFormat::from_path(Path::new("data.jsonl")),
+            FixtureFormat::JsonLines
+        );
+        assert_eq!(
+            FixtureFormat::from_path(Path::new("data.jsonl.zst")),
+            FixtureFormat::JsonLines
+        );
+        assert_eq!(
+            FixtureFormat::from_path(Path::new("data.json.gz")),
+            FixtureFormat::Json
+        );
+        assert!(FixtureFormat::from_name("xml").is_err());
+    }
+
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(
+            FixtureCompression::from_path(Path::new("a.json")),
+            FixtureCompression::None
+        );
+        assert_eq!(
+            FixtureCompression::from_path(Path::new("a.json.GZ")),
+            FixtureCompression::Gzip
+        );
+        assert_eq!(
+            FixtureCompression::from_path(Path::new("a.jsonl.zst")),
+            FixtureCompression::Zstd
+        );
+    }
+
+    #[test]
+    fn test_writer_matches_whole_document_serialization() {
+        assert_eq!(
+            write_all(FixtureFormat::Json, false),
+            serde_json::to_string(&objects()).unwrap()
+        );
+        assert_eq!(
+            write_all(FixtureFormat::Json, true),
+            serde_json::to_string_pretty(&objects()).unwrap()
+        );
+        let empty = FixtureWriter::new(Vec::new(), FixtureFormat::Json, true);
+        assert_eq!(empty.finish().unwrap(), b"[]");
+    }
+
+    #[test]
+    fn test_writer_json_lines() {
+        let output = write_all(FixtureFormat::JsonLines, true);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(lines[1]).unwrap(),
+            objects()[1]
+        );
+    }
+
+    #[test]
+    fn test_read_both_formats() {
+        for (format, indent) in [
+            (FixtureFormat::Json, false),
+            (FixtureFormat::Json, true),
+            (FixtureFormat::JsonLines, false),
+        ] {
+            let data = write_all(format, indent);
+            assert_eq!(read_all(data.as_bytes()), objects(), "{format:?}");
+        }
+        assert!(read_all(b"  \n").is_empty());
+        assert!(read_all(b" []").is_empty());
+    }
+
+    #[test]
+    fn test_read_stops_at_callback_error() {
+        let data = write_all(FixtureFormat::Json, false);
+        let mut seen = 0;
+        let result = read_fixture_objects(data.as_bytes(), |_| {
+            seen += 1;
+            Err(DjangoError::IntegrityError("duplicate".into()))
+        });
+        assert!(matches!(result, Err(DjangoError::IntegrityError(_))));
+        assert_eq!(seen, 1);
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        let result = read_fixture_objects(&b"[{\"pk\": 1},"[..], |_| Ok(()));
+        assert!(matches!(result, Err(DjangoError::SerializationError(_))));
+        let result = read_fixture_objects(&b"[] trailing"[..], |_| Ok(()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_compressed_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["dump.json", "dump.json.gz", "dump.jsonl.zst"] {
+            let path = dir.path().join(name);
+            let mut writer = FixtureWriter::new(
+                FixtureOutput::create(&path).unwrap(),
+                FixtureFormat::from_path(&path),
+                false,
+            );
+            for object in objects() {
+                writer.write_object(&object).unwrap();
+            }
+            writer.finish().unwrap().finish().unwrap();
+
+            let raw = std::fs::read(&path).unwrap();
+            assert_eq!(raw.first() == Some(&b'['), name == "dump.json", "{name}");
+
+            let mut read = Vec::new();
+            read_fixture_objects(open_fixture(&path).unwrap(), |object| {
+                read.push(object);
+                Ok(())
+            })
+            .unwrap();
+            assert_eq!(read, objects(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_open_fixture_not_found() {
+        let result = open_fixture(Path::new("/nonexistent/fixture.json.gz"));
+        assert!(matches!(result, Err(DjangoError::NotFound(_))));
+    }
+}
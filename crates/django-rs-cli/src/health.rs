@@ -0,0 +1,590 @@
+//! Liveness and readiness endpoints for orchestrators such as Kubernetes.
+//!
+//! [`health_urls`] returns two URL patterns meant to be mounted at the root
+//! of a project:
+//!
+//! | Route | Name | Meaning |
+//! |-------|------|---------|
+//! | `healthz` | `healthz` | The process is up (liveness) |
+//! | `readyz` | `readyz` | The dependencies are reachable (readiness) |
+//!
+//! Each endpoint runs its [`HealthProbe`]s concurrently, each under its own
+//! timeout, and answers `200 OK` when all pass or `503 Service Unavailable`
+//! otherwise. The JSON body reports every probe:
+//!
+//! ```json
+//! {
+//!   "status": "error",
+//!   "checks": {
+//!     "cache": {"status": "ok", "duration_ms": 1},
+//!     "database": {"status": "error", "duration_ms": 2000, "error": "timed out after 2000ms"}
+//!   }
+//! }
+//! ```
+//!
+//! Built-in probes cover the database ([`DatabaseProbe`]), the cache
+//! ([`CacheProbe`]) and pending migrations ([`MigrationsProbe`]);
+//! [`FnProbe`] wraps any async closure.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use django_rs_cli::cache::InMemoryCache;
+//! use django_rs_cli::health::{health_urls, CacheProbe, FnProbe, HealthChecks};
+//! use django_rs_http::urls::resolver::root;
+//!
+//! let checks = HealthChecks::new()
+//!     .readiness(CacheProbe::new(Arc::new(InMemoryCache::new())))
+//!     .readiness_with_timeout(
+//!         FnProbe::new("search", || async { Ok(()) }),
+//!         Duration::from_millis(500),
+//!     );
+//! let resolver = root(health_urls(checks).unwrap()).unwrap();
+//! assert!(resolver.resolve("readyz").is_ok());
+//! ```
+
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, DjangoResult};
+use django_rs_db_backends::DatabaseBackend;
+use django_rs_db_migrations::{MigrationLoader, MigrationRecorder};
+use django_rs_http::urls::pattern::{path, RouteHandler};
+use django_rs_http::urls::resolver::URLEntry;
+use django_rs_http::{HttpResponse, JsonResponse};
+
+use crate::cache::{CacheBackend, CacheValue};
+
+/// The timeout applied to probes registered without one.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A check of one dependency, run by the health endpoints.
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    /// Returns the name reported in the response body (e.g., "database").
+    fn name(&self) -> &str;
+
+    /// Runs the check, returning a description of the failure if it fails.
+    async fn check(&self) -> Result<(), String>;
+}
+
+/// Checks that the database answers a trivial query.
+pub struct DatabaseProbe {
+    backend: Arc<dyn DatabaseBackend>,
+}
+
+impl DatabaseProbe {
+    /// Creates a probe running `SELECT 1` against `backend`.
+    pub fn new(backend: Arc<dyn DatabaseBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for DatabaseProbe {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        self.backend
+            .query("SELECT 1", &[])
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Checks that the cache stores and returns a value.
+pub struct CacheProbe {
+    cache: Arc<dyn CacheBackend>,
+}
+
+/// The key written by [`CacheProbe`].
+const CACHE_PROBE_KEY: &str = "django_rs:health_check";
+
+impl CacheProbe {
+    /// Creates a probe writing and reading back a short-lived key in `cache`.
+    pub fn new(cache: Arc<dyn CacheBackend>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for CacheProbe {
+    fn name(&self) -> &'static str {
+        "cache"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let token = CacheValue::Integer(chrono::Utc::now().timestamp_micros());
+        self.cache
+            .set(
+                CACHE_PROBE_KEY,
+                token.clone(),
+                Some(Duration::from_secs(60)),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        match self.cache.get(CACHE_PROBE_KEY).await {
+            Ok(Some(value)) if value == token => Ok(()),
+            Ok(_) => Err("cache did not return the value just stored".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Checks that every known migration has been applied to the database.
+///
+/// The probe only reads `django_migrations`, so it runs under a role without
+/// DDL rights; a missing table means every migration is pending.
+pub struct MigrationsProbe {
+    backend: Arc<dyn DatabaseBackend>,
+    expected: Vec<(String, String)>,
+}
+
+impl MigrationsProbe {
+    /// Creates a probe expecting the given `(app_label, name)` migrations to
+    /// be recorded in `django_migrations`.
+    pub fn new(backend: Arc<dyn DatabaseBackend>, expected: Vec<(String, String)>) -> Self {
+        Self { backend, expected }
+    }
+
+    /// Creates a probe expecting every migration found in `migrations_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the migration files cannot be loaded.
+    pub fn from_dir(
+        backend: Arc<dyn DatabaseBackend>,
+        migrations_dir: impl AsRef<Path>,
+    ) -> Result<Self, DjangoError> {
+        let graph = MigrationLoader::new(migrations_dir.as_ref()).load()?;
+        Ok(Self::new(backend, graph.topological_order()?))
+    }
+}
+
+#[async_trait]
+impl HealthProbe for MigrationsProbe {
+    fn name(&self) -> &'static str {
+        "migrations"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let mut recorder = MigrationRecorder::new();
+        recorder
+            .read_from_db(self.backend.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let pending: Vec<String> = self
+            .expected
+            .iter()
+            .filter(|key| !recorder.is_applied(key))
+            .map(|(app, name)| format!("{app}.{name}"))
+            .collect();
+        if pending.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} unapplied migration(s): {}",
+                pending.len(),
+                pending.join(", ")
+            ))
+        }
+    }
+}
+
+type ProbeFn =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> + Send + Sync>;
+
+/// A probe running an async closure.
+pub struct FnProbe {
+    name: String,
+    check: ProbeFn,
+}
+
+impl FnProbe {
+    /// Creates a probe named `name` that passes when `check` returns `Ok`.
+    pub fn new<F, Fut>(name: &str, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            check: Box::new(move || Box::pin(check())),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthProbe for FnProbe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        (self.check)().await
+    }
+}
+
+/// A probe with the timeout it runs under.
+#[derive(Clone)]
+struct TimedProbe {
+    probe: Arc<dyn HealthProbe>,
+    timeout: Duration,
+}
+
+/// The probes run by the endpoints returned from [`health_urls`].
+///
+/// Liveness probes should only fail when restarting the process would help;
+/// with none registered, `healthz` always answers `200 OK`.
+#[derive(Clone, Default)]
+pub struct HealthChecks {
+    liveness: Vec<TimedProbe>,
+    readiness: Vec<TimedProbe>,
+}
+
+impl HealthChecks {
+    /// Creates an empty set of checks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a liveness probe with the [default timeout](DEFAULT_PROBE_TIMEOUT).
+    #[must_use]
+    pub fn liveness(self, probe: impl HealthProbe + 'static) -> Self {
+        self.liveness_with_timeout(probe, DEFAULT_PROBE_TIMEOUT)
+    }
+
+    /// Adds a liveness probe that fails if it takes longer than `timeout`.
+    #[must_use]
+    pub fn liveness_with_timeout(
+        mut self,
+        probe: impl HealthProbe + 'static,
+        timeout: Duration,
+    ) -> Self {
+        self.liveness.push(TimedProbe {
+            probe: Arc::new(probe),
+            timeout,
+        });
+        self
+    }
+
+    /// Adds a readiness probe with the [default timeout](DEFAULT_PROBE_TIMEOUT).
+    #[must_use]
+    pub fn readiness(self, probe: impl HealthProbe + 'static) -> Self {
+        self.readiness_with_timeout(probe, DEFAULT_PROBE_TIMEOUT)
+    }
+
+    /// Adds a readiness probe that fails if it takes longer than `timeout`.
+    #[must_use]
+    pub fn readiness_with_timeout(
+        mut self,
+        probe: impl HealthProbe + 'static,
+        timeout: Duration,
+    ) -> Self {
+        self.readiness.push(TimedProbe {
+            probe: Arc::new(probe),
+            timeout,
+        });
+        self
+    }
+}
+
+/// Runs `probes` concurrently and builds the endpoint response.
+async fn run_probes(probes: &[TimedProbe]) -> HttpResponse {
+    let handles: Vec<_> = probes
+        .iter()
+        .cloned()
+        .map(|TimedProbe { probe, timeout }| {
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let result = tokio::time::timeout(timeout, probe.check())
+                    .await
+                    .unwrap_or_else(|_| Err(format!("timed out after {}ms", timeout.as_millis())));
+                (probe.name().to_string(), result, started.elapsed())
+            })
+        })
+        .collect();
+
+    let mut healthy = true;
+    let mut checks = serde_json::Map::new();
+    for (handle, timed) in handles.into_iter().zip(probes) {
+        let (name, result, elapsed) = handle.await.unwrap_or_else(|e| {
+            (
+                timed.probe.name().to_string(),
+                Err(format!("probe panicked: {e}")),
+                Duration::ZERO,
+            )
+        });
+        let mut check = serde_json::json!({
+            "status": if result.is_ok() { "ok" } else { "error" },
+            "duration_ms": u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        });
+        if let Err(error) = result {
+            tracing::warn!("Health probe '{name}' failed: {error}");
+            check["error"] = serde_json::Value::String(error);
+            healthy = false;
+        }
+        checks.insert(name, check);
+    }
+
+    let (status, label) = if healthy {
+        (http::StatusCode::OK, "ok")
+    } else {
+        (http::StatusCode::SERVICE_UNAVAILABLE, "error")
+    };
+    let mut response = JsonResponse::with_status(
+        status,
+        &serde_json::json!({ "status": label, "checks": checks }),
+    );
+    response.headers_mut().insert(
+        http::header::CACHE_CONTROL,
+        http::HeaderValue::from_static("no-store"),
+    );
+    response
+}
+
+/// Returns the `healthz` (liveness) and `readyz` (readiness) URL patterns.
+///
+/// # Errors
+///
+/// Returns an error if a route fails to compile.
+pub fn health_urls(checks: HealthChecks) -> DjangoResult<Vec<URLEntry>> {
+    let checks = Arc::new(checks);
+
+    let liveness = Arc::clone(&checks);
+    let healthz: RouteHandler = Arc::new(move |_req| {
+        let checks = Arc::clone(&liveness);
+        Box::pin(async move { run_probes(&checks.liveness).await })
+    });
+    let readyz: RouteHandler = Arc::new(move |_req| {
+        let checks = Arc::clone(&checks);
+        Box::pin(async move { run_probes(&checks.readiness).await })
+    });
+
+    Ok(vec![
+        URLEntry::Pattern(path("healthz", healthz, Some("healthz"))?),
+        URLEntry::Pattern(path("readyz", readyz, Some("readyz"))?),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{DummyCache, InMemoryCache};
+    #[cfg(feature = "sqlite")]
+    use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
+    #[cfg(feature = "sqlite")]
+    use django_rs_db::{value::Value, Row};
+    #[cfg(feature = "sqlite")]
+    use django_rs_db_backends::Transaction;
+    use django_rs_http::urls::resolver::{root, URLResolver};
+    use django_rs_http::HttpRequest;
+
+    async fn get(resolver: &URLResolver, route: &str) -> (http::StatusCode, serde_json::Value) {
+        let resolver_match = resolver.resolve(route).unwrap();
+        let response = (resolver_match.func)(HttpRequest::builder().build()).await;
+        let body = serde_json::from_slice(&response.content_bytes().unwrap()).unwrap();
+        (response.status(), body)
+    }
+
+    fn urls(checks: HealthChecks) -> URLResolver {
+        root(health_urls(checks).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_healthz_without_probes_is_ok() {
+        let resolver = urls(
+            HealthChecks::new().readiness(FnProbe::new("down", || async {
+                Err("unreachable".to_string())
+            })),
+        );
+        let (status, body) = get(&resolver, "healthz").await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(body, serde_json::json!({"status": "ok", "checks": {}}));
+    }
+
+    #[tokio::test]
+    async fn test_readyz_reports_each_probe() {
+        let resolver = urls(
+            HealthChecks::new()
+                .readiness(CacheProbe::new(Arc::new(InMemoryCache::new())))
+                .readiness(FnProbe::new("search", || async {
+                    Err("connection refused".to_string())
+                })),
+        );
+        let (status, body) = get(&resolver, "readyz").await;
+        assert_eq!(status, http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "error");
+        assert_eq!(body["checks"]["cache"]["status"], "ok");
+        assert!(body["checks"]["cache"].get("error").is_none());
+        assert_eq!(body["checks"]["search"]["status"], "error");
+        assert_eq!(body["checks"]["search"]["error"], "connection refused");
+    }
+
+    #[tokio::test]
+    async fn test_probe_timeout() {
+        let resolver = urls(HealthChecks::new().readiness_with_timeout(
+            FnProbe::new("slow", || async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            }),
+            Duration::from_millis(20),
+        ));
+        let (status, body) = get(&resolver, "readyz").await;
+        assert_eq!(status, http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"]["slow"]["error"], "timed out after 20ms");
+    }
+
+    #[tokio::test]
+    async fn test_cache_probe_detects_dummy_cache() {
+        let probe = CacheProbe::new(Arc::new(DummyCache));
+        assert!(probe.check().await.unwrap_err().contains("did not return"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_database_and_migrations_probes() {
+        let backend: Arc<dyn DatabaseBackend> =
+            Arc::new(django_rs_db_backends::SqliteBackend::memory().unwrap());
+        assert!(DatabaseProbe::new(Arc::clone(&backend))
+            .check()
+            .await
+            .is_ok());
+
+        let expected = vec![
+            ("blog".to_string(), "0001_initial".to_string()),
+            ("blog".to_string(), "0002_post_title".to_string()),
+        ];
+        let probe = MigrationsProbe::new(Arc::clone(&backend), expected);
+        assert_eq!(
+            probe.check().await.unwrap_err(),
+            "2 unapplied migration(s): blog.0001_initial, blog.0002_post_title"
+        );
+
+        MigrationRecorder::new()
+            .ensure_table(backend.as_ref())
+            .await
+            .unwrap();
+
+        for name in ["0001_initial", "0002_post_title"] {
+            backend
+                .execute(&MigrationRecorder::record_applied_sql("blog", name), &[])
+                .await
+                .unwrap();
+        }
+        assert!(probe.check().await.is_ok());
+    }
+
+    /// A SQLite backend that logs every statement it runs.
+    #[cfg(feature = "sqlite")]
+    struct LoggingBackend {
+        inner: django_rs_db_backends::SqliteBackend,
+        statements: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[cfg(feature = "sqlite")]
+    impl LoggingBackend {
+        fn log(&self, sql: &str) {
+            self.statements.lock().unwrap().push(sql.to_string());
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[async_trait]
+    impl DatabaseBackend for LoggingBackend {
+        fn vendor(&self) -> &str {
+            self.inner.vendor()
+        }
+
+        fn backend_type(&self) -> DatabaseBackendType {
+            self.inner.backend_type()
+        }
+
+        async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
+            self.log(sql);
+            self.inner.execute(sql, params).await
+        }
+
+        async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
+            self.log(sql);
+            self.inner.query(sql, params).await
+        }
+
+        async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
+            self.log(sql);
+            self.inner.query_one(sql, params).await
+        }
+
+        async fn begin_transaction(&self) -> Result<Transaction, DjangoError> {
+            self.inner.begin_transaction().await
+        }
+
+        async fn commit(&self) -> Result<(), DjangoError> {
+            self.inner.commit().await
+        }
+
+        async fn rollback(&self) -> Result<(), DjangoError> {
+            self.inner.rollback().await
+        }
+
+        fn compiler(&self) -> SqlCompiler {
+            self.inner.compiler()
+        }
+
+        async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+            self.inner.pin().await
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_migrations_probe_issues_no_ddl() {
+        let backend = Arc::new(LoggingBackend {
+            inner: django_rs_db_backends::SqliteBackend::memory().unwrap(),
+            statements: std::sync::Mutex::new(Vec::new()),
+        });
+        let probe = MigrationsProbe::new(
+            backend.clone(),
+            vec![("blog".to_string(), "0001_initial".to_string())],
+        );
+
+        // No table yet: everything is pending, and the table isn't created
+        assert_eq!(
+            probe.check().await.unwrap_err(),
+            "1 unapplied migration(s): blog.0001_initial"
+        );
+        assert!(!MigrationRecorder::table_exists(&backend.inner)
+            .await
+            .unwrap());
+
+        backend
+            .inner
+            .execute(MigrationRecorder::ensure_schema_sql_sqlite(), &[])
+            .await
+            .unwrap();
+        backend
+            .inner
+            .execute(
+                &MigrationRecorder::record_applied_sql("blog", "0001_initial"),
+                &[],
+            )
+            .await
+            .unwrap();
+        assert!(probe.check().await.is_ok());
+
+        let statements = backend.statements.lock().unwrap();
+        assert!(!statements.is_empty());
+        assert!(statements
+            .iter()
+            .all(|sql| sql.trim_start().to_uppercase().starts_with("SELECT")));
+    }
+}
@@ -0,0 +1,367 @@
+//! Image variant generation on top of file storage.
+//!
+//! [`ImageStorage`] wraps any [`Storage`] and produces resized variants of
+//! stored images on demand. Variants are saved next to their original under a
+//! deterministic name (see [`variant_name`]), so a variant is generated once
+//! and then served like any other stored file.
+//!
+//! `ImageStorage` also implements [`ThumbnailBackend`], so it can be set on a
+//! template engine to back the `{% thumbnail %}` tag. Since templates render
+//! synchronously, missing variants are generated in the background and the
+//! tag points at the original image until its variant is ready.
+//!
+//! This module requires the `image` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use django_rs_cli::files::FileSystemStorage;
+//! use django_rs_cli::images::ImageStorage;
+//!
+//! # async fn example() -> Result<(), django_rs_core::DjangoError> {
+//! let storage = FileSystemStorage::new("/var/www/media".into(), "/media/");
+//! let images = ImageStorage::new(Arc::new(storage));
+//! let name = images.thumbnail("photos/cat.jpg", 200, 200, true).await?;
+//! assert_eq!(name, "photos/cat.200x200_crop.jpg");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+use django_rs_core::DjangoError;
+use django_rs_template::thumbnails::{variant_name, ThumbnailBackend, ThumbnailSpec};
+use image::imageops::FilterType;
+use image::ImageFormat;
+
+use crate::files::Storage;
+
+/// A storage wrapper that generates and caches resized image variants.
+///
+/// Cloning is cheap; clones share the underlying storage and variant cache.
+#[derive(Clone)]
+pub struct ImageStorage {
+    storage: Arc<dyn Storage>,
+    /// Variants known to exist in the storage.
+    generated: Arc<Mutex<HashSet<String>>>,
+    /// Per-variant locks held while generating, so each variant is only
+    /// generated once while different variants are generated in parallel.
+    generating: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+    /// Variants scheduled for background generation by the template tag.
+    pending: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ImageStorage {
+    /// Creates an image storage on top of `storage`.
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            storage,
+            generated: Arc::new(Mutex::new(HashSet::new())),
+            generating: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Returns the underlying storage.
+    pub fn storage(&self) -> &Arc<dyn Storage> {
+        &self.storage
+    }
+
+    /// Returns the name of the `width` x `height` variant of the image `name`,
+    /// generating it first if it does not exist yet.
+    ///
+    /// With `crop`, the image is scaled to cover the box and the overflow is
+    /// cut off; otherwise it is scaled to fit inside the box, keeping its
+    /// aspect ratio. Images are never enlarged when fitting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the original cannot be read or is not a supported
+    /// image, or if the variant cannot be saved.
+    pub async fn thumbnail(
+        &self,
+        name: &str,
+        width: u32,
+        height: u32,
+        crop: bool,
+    ) -> Result<String, DjangoError> {
+        self.variant(name, &ThumbnailSpec::new(width, height, crop))
+            .await
+    }
+
+    /// Like [`thumbnail`](Self::thumbnail), but returns the variant's URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`thumbnail`](Self::thumbnail).
+    pub async fn thumbnail_url(
+        &self,
+        name: &str,
+        width: u32,
+        height: u32,
+        crop: bool,
+    ) -> Result<String, DjangoError> {
+        let variant = self.thumbnail(name, width, height, crop).await?;
+        Ok(self.storage.url(&variant))
+    }
+
+    /// Returns the name of the `spec` variant of `name`, generating it if
+    /// needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`thumbnail`](Self::thumbnail).
+    pub async fn variant(&self, name: &str, spec: &ThumbnailSpec) -> Result<String, DjangoError> {
+        let variant = variant_name(name, spec);
+        if self.is_generated(&variant)? {
+            return Ok(variant);
+        }
+
+        // Serialize generation of this variant so concurrent requests don't
+        // resize the same image twice or race on the variant's name.
+        let variant_lock = Arc::clone(lock(&self.generating)?.entry(variant.clone()).or_default());
+        let guard = variant_lock.lock().await;
+        let result = match self.storage.exists(&variant).await {
+            Ok(true) => Ok(()),
+            Ok(false) => self.generate(name, &variant, spec).await,
+            Err(e) => Err(e),
+        };
+        if result.is_ok() {
+            lock(&self.generated)?.insert(variant.clone());
+        }
+        drop(guard);
+        // Later callers see the variant as generated, or retry a failure
+        // under a fresh lock.
+        lock(&self.generating)?.remove(&variant);
+        result.map(|()| variant)
+    }
+
+    fn is_generated(&self, variant: &str) -> Result<bool, DjangoError> {
+        Ok(lock(&self.generated)?.contains(variant))
+    }
+
+    async fn generate(
+        &self,
+        name: &str,
+        variant: &str,
+        spec: &ThumbnailSpec,
+    ) -> Result<(), DjangoError> {
+        let original = self.storage.open(name).await?;
+        let spec = *spec;
+        let encoded = tokio::task::spawn_blocking(move || resize(&original, &spec))
+            .await
+            .map_err(|e| {
+                DjangoError::InternalServerError(format!("Thumbnail task failed: {e}"))
+            })??;
+        let saved = self.storage.save(variant, &encoded).await?;
+        if saved != variant {
+            return Err(DjangoError::InternalServerError(format!(
+                "Storage saved variant '{variant}' as '{saved}'"
+            )));
+        }
+        tracing::debug!("Generated image variant {variant}");
+        Ok(())
+    }
+}
+
+/// Locks one of the variant caches.
+fn lock<T>(mutex: &Mutex<T>) -> Result<std::sync::MutexGuard<'_, T>, DjangoError> {
+    mutex
+        .lock()
+        .map_err(|_| DjangoError::InternalServerError("Image variant cache poisoned".into()))
+}
+
+impl std::fmt::Debug for ImageStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImageStorage").finish_non_exhaustive()
+    }
+}
+
+/// Decodes `data`, resizes it per `spec` and re-encodes it in its original
+/// format.
+fn resize(data: &[u8], spec: &ThumbnailSpec) -> Result<Vec<u8>, DjangoError> {
+    let invalid = |e: image::ImageError| DjangoError::BadRequest(format!("Invalid image: {e}"));
+    let format = image::guess_format(data).map_err(invalid)?;
+    let img = image::load_from_memory_with_format(data, format).map_err(invalid)?;
+    let resized = if spec.crop {
+        img.resize_to_fill(spec.width, spec.height, FilterType::Lanczos3)
+    } else if img.width() <= spec.width && img.height() <= spec.height {
+        img
+    } else {
+        img.resize(spec.width, spec.height, FilterType::Lanczos3)
+    };
+    let resized = if format == ImageFormat::Jpeg {
+        image::DynamicImage::ImageRgb8(resized.to_rgb8())
+    } else {
+        resized
+    };
+    let mut out = Cursor::new(Vec::new());
+    resized
+        .write_to(&mut out, format)
+        .map_err(|e| DjangoError::InternalServerError(format!("Cannot encode image: {e}")))?;
+    Ok(out.into_inner())
+}
+
+impl ThumbnailBackend for ImageStorage {
+    /// Returns the variant's URL once it has been generated.
+    ///
+    /// Until then, the variant is scheduled for generation in the background
+    /// and the original image's URL is returned, so the URL always points at
+    /// an existing file.
+    fn thumbnail_url(&self, name: &str, spec: &ThumbnailSpec) -> Result<String, DjangoError> {
+        let variant = variant_name(name, spec);
+        if self.is_generated(&variant)? {
+            return Ok(self.storage.url(&variant));
+        }
+        if lock(&self.pending)?.insert(variant.clone()) {
+            let Ok(handle) = tokio::runtime::Handle::try_current() else {
+                tracing::warn!("No runtime to generate thumbnail {variant}");
+                lock(&self.pending)?.remove(&variant);
+                return Ok(self.storage.url(name));
+            };
+            let images = self.clone();
+            let (name, spec) = (name.to_string(), *spec);
+            handle.spawn(async move {
+                if let Err(e) = images.variant(&name, &spec).await {
+                    tracing::warn!("Cannot generate thumbnail of {name}: {e}");
+                }
+                if let Ok(mut pending) = lock(&images.pending) {
+                    pending.remove(&variant);
+                }
+            });
+        }
+        Ok(self.storage.url(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::FileSystemStorage;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let img = image::RgbImage::from_pixel(width, height, image::Rgb([200, 10, 10]));
+        let mut out = Cursor::new(Vec::new());
+        img.write_to(&mut out, ImageFormat::Png).unwrap();
+        out.into_inner()
+    }
+
+    fn dimensions(data: &[u8]) -> (u32, u32) {
+        let img = image::load_from_memory(data).unwrap();
+        (img.width(), img.height())
+    }
+
+    async fn setup() -> (tempfile::TempDir, ImageStorage) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileSystemStorage::new(dir.path().to_path_buf(), "/media/");
+        storage
+            .save("photos/cat.png", &png(400, 200))
+            .await
+            .unwrap();
+        (dir, ImageStorage::new(Arc::new(storage)))
+    }
+
+    #[tokio::test]
+    async fn test_thumbnail_fit_and_crop() {
+        let (_dir, images) = setup().await;
+
+        let fit = images
+            .thumbnail("photos/cat.png", 100, 100, false)
+            .await
+            .unwrap();
+        assert_eq!(fit, "photos/cat.100x100.png");
+        let data = images.storage().open(&fit).await.unwrap();
+        assert_eq!(dimensions(&data), (100, 50));
+
+        let crop = images
+            .thumbnail("photos/cat.png", 100, 100, true)
+            .await
+            .unwrap();
+        assert_eq!(crop, "photos/cat.100x100_crop.png");
+        let data = images.storage().open(&crop).await.unwrap();
+        assert_eq!(dimensions(&data), (100, 100));
+
+        let large = images
+            .thumbnail("photos/cat.png", 800, 800, false)
+            .await
+            .unwrap();
+        let data = images.storage().open(&large).await.unwrap();
+        assert_eq!(dimensions(&data), (400, 200));
+    }
+
+    #[tokio::test]
+    async fn test_thumbnail_is_cached() {
+        let (_dir, images) = setup().await;
+        let first = images
+            .thumbnail_url("photos/cat.png", 50, 50, true)
+            .await
+            .unwrap();
+        let second = images
+            .thumbnail_url("photos/cat.png", 50, 50, true)
+            .await
+            .unwrap();
+        assert_eq!(first, "/media/photos/cat.50x50_crop.png");
+        assert_eq!(first, second);
+        assert!(!images
+            .storage()
+            .exists("photos/cat_1.50x50_crop.png")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_thumbnails() {
+        let (_dir, images) = setup().await;
+        let (a, b, c) = tokio::join!(
+            images.thumbnail("photos/cat.png", 40, 40, false),
+            images.thumbnail("photos/cat.png", 40, 40, false),
+            images.thumbnail("photos/cat.png", 30, 30, false),
+        );
+        assert_eq!(a.unwrap(), "photos/cat.40x40.png");
+        assert_eq!(b.unwrap(), "photos/cat.40x40.png");
+        assert_eq!(c.unwrap(), "photos/cat.30x30.png");
+        assert!(!images
+            .storage()
+            .exists("photos/cat_1.40x40.png")
+            .await
+            .unwrap());
+        assert!(images.generating.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_thumbnail_errors() {
+        let (_dir, images) = setup().await;
+        let missing = images.thumbnail("photos/dog.png", 50, 50, false).await;
+        assert!(matches!(missing, Err(DjangoError::NotFound(_))));
+
+        images
+            .storage()
+            .save("notes.png", b"not an image")
+            .await
+            .unwrap();
+        let invalid = images.thumbnail("notes.png", 50, 50, false).await;
+        assert!(matches!(invalid, Err(DjangoError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_thumbnail_backend_generates_in_background() {
+        let (_dir, images) = setup().await;
+        let backend: Arc<dyn ThumbnailBackend> = Arc::new(images.clone());
+        let spec = ThumbnailSpec::new(20, 20, true);
+        let url = backend.thumbnail_url("photos/cat.png", &spec).unwrap();
+        assert_eq!(url, "/media/photos/cat.png");
+
+        for _ in 0..100 {
+            if images.is_generated("photos/cat.20x20_crop.png").unwrap() {
+                let url = backend.thumbnail_url("photos/cat.png", &spec).unwrap();
+                assert_eq!(url, "/media/photos/cat.20x20_crop.png");
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("thumbnail was not generated");
+    }
+}
@@ -9,6 +9,10 @@
 //! - **Caching** - Async cache backends (in-memory, database, filesystem, dummy)
 //! - **Email** - Async email sending with multiple backends (SMTP, console, file, in-memory)
 //! - **File storage** - Async file storage abstraction with filesystem backend
+//! - **Fixtures** - Streaming, optionally compressed fixture files for `dumpdata`/`loaddata`
+//! - **Health checks** - `healthz`/`readyz` endpoints with database, cache, and migration probes
+//! - **Images** - On-demand thumbnail variants of stored images (`image` feature)
+//! - **Scaffolding** - `startproject` and `startapp` skeletons from embedded templates
 //! - **Serialization** - JSON serialization for data import/export
 //!
 //! ## Design Principles
@@ -50,14 +54,25 @@ pub mod command;
 pub mod commands;
 pub mod email;
 pub mod files;
+pub mod fixtures;
+pub mod health;
+#[cfg(feature = "image")]
+pub mod images;
+pub mod scaffold;
 pub mod serialization;
 
 // Re-export primary types at the crate root for convenience.
-pub use cache::{CacheBackend, CacheValue, DatabaseCache, DummyCache, FileCache, InMemoryCache};
+pub use cache::{
+    CacheBackend, CacheValue, DatabaseCache, DummyCache, FileCache, InMemoryCache, KeyFunc,
+    VersionedCache,
+};
 pub use command::{CommandRegistry, ManagementCommand};
 pub use email::{
     get_connection, send_mail, send_mass_mail, Attachment, ConsoleBackend, EmailBackend,
     EmailMessage, FileBackend, InMemoryBackend, SmtpBackend,
 };
 pub use files::{FileSystemStorage, Storage, UploadedFile};
+pub use health::{health_urls, HealthChecks, HealthProbe};
+#[cfg(feature = "image")]
+pub use images::ImageStorage;
 pub use serialization::{JsonSerializer, PrettyJsonSerializer, Serializer};
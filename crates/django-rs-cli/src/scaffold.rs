@@ -0,0 +1,182 @@
+//! Project and app scaffolding.
+//!
+//! The `startproject` and `startapp` commands write skeletons from templates
+//! embedded in the binary. Each template file's path and contents may refer
+//! to variables as `{{ name }}`; only the variables passed to [`render`] are
+//! replaced, so template tags meant for the generated project's own HTML
+//! templates pass through untouched.
+
+use std::path::{Path, PathBuf};
+
+use django_rs_core::DjangoError;
+use rand::Rng;
+
+/// One file of an embedded skeleton.
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateFile {
+    /// The path of the generated file, relative to the target directory.
+    pub path: &'static str,
+    /// The file's template contents.
+    pub contents: &'static str,
+}
+
+macro_rules! template_files {
+    ($dir:literal: $($path:literal => $source:literal),* $(,)?) => {
+        &[$(TemplateFile {
+            path: $path,
+            contents: include_str!(concat!("../templates/", $dir, "/", $source)),
+        }),*]
+    };
+}
+
+/// The skeleton written by `startproject`: a cargo workspace with settings,
+/// URL configuration, a sample model and base templates.
+pub const PROJECT_TEMPLATE: &[TemplateFile] = template_files!("project":
+    "Cargo.toml" => "Cargo.toml.tpl",
+    "settings.toml" => "settings.toml.tpl",
+    ".gitignore" => "gitignore.tpl",
+    "src/main.rs" => "src/main.rs.tpl",
+    "src/urls.rs" => "src/urls.rs.tpl",
+    "src/models.rs" => "src/models.rs.tpl",
+    "templates/base.html" => "templates/base.html.tpl",
+    "templates/index.html" => "templates/index.html.tpl",
+    "apps/.gitkeep" => "gitkeep.tpl",
+);
+
+/// The skeleton written by `startapp`: a library crate with models, views,
+/// URL patterns and a namespaced template directory.
+pub const APP_TEMPLATE: &[TemplateFile] = template_files!("app":
+    "Cargo.toml" => "Cargo.toml.tpl",
+    "src/lib.rs" => "src/lib.rs.tpl",
+    "src/models.rs" => "src/models.rs.tpl",
+    "src/views.rs" => "src/views.rs.tpl",
+    "src/urls.rs" => "src/urls.rs.tpl",
+    "templates/{{ app_name }}/index.html" => "templates/index.html.tpl",
+);
+
+/// Replaces each `{{ name }}` of `vars` in `template` with its value.
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{ {name} }}}}"), value)
+        })
+}
+
+/// Checks that `name` can be used as a crate and module name.
+///
+/// # Errors
+///
+/// Returns [`DjangoError::ConfigurationError`] if `name` is not a lowercase
+/// Rust identifier, or is a keyword or the name of a framework crate.
+pub fn validate_name(name: &str, kind: &str) -> Result<(), DjangoError> {
+    const RESERVED: &str = "as async await break const continue crate dyn else enum extern \
+        false fn for if impl in let loop match mod move mut pub ref return self static struct \
+        super trait true type unsafe use where while test core std alloc django_rs tokio";
+
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(DjangoError::ConfigurationError(format!(
+            "'{name}' is not a valid {kind} name. Use lowercase letters, digits and underscores, \
+             starting with a letter."
+        )));
+    }
+    if RESERVED.split_whitespace().any(|word| word == name) {
+        return Err(DjangoError::ConfigurationError(format!(
+            "'{name}' conflicts with a Rust keyword or crate name. Choose another {kind} name."
+        )));
+    }
+    Ok(())
+}
+
+/// Returns a random 50-character secret key for a new project's settings.
+pub fn random_secret_key() -> String {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789!@#$%^&*(-_=+)";
+    let mut rng = rand::thread_rng();
+    (0..50)
+        .map(|_| char::from(CHARS[rng.gen_range(0..CHARS.len())]))
+        .collect()
+}
+
+/// Writes `files` into `target`, rendering their paths and contents with
+/// `vars`. Returns the written paths.
+///
+/// # Errors
+///
+/// Returns [`DjangoError::ConfigurationError`] if `target` exists and is not
+/// an empty directory, or an I/O error if a file cannot be written.
+pub async fn write_template(
+    files: &[TemplateFile],
+    target: &Path,
+    vars: &[(&str, &str)],
+) -> Result<Vec<PathBuf>, DjangoError> {
+    if target.exists() {
+        let mut entries = tokio::fs::read_dir(target).await?;
+        if entries.next_entry().await?.is_some() {
+            return Err(DjangoError::ConfigurationError(format!(
+                "'{}' already exists and is not empty",
+                target.display()
+            )));
+        }
+    }
+
+    let mut written = Vec::with_capacity(files.len());
+    for file in files {
+        let dest = target.join(render(file.path, vars));
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&dest, render(file.contents, vars)).await?;
+        written.push(dest);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_replaces_only_given_vars() {
+        let text = render(
+            "name = \"{{ project_name }}\" {{ other }} {{project_name}}",
+            &[("project_name", "shop")],
+        );
+        assert_eq!(text, "name = \"shop\" {{ other }} {{project_name}}");
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("shop", "project").is_ok());
+        assert!(validate_name("my_shop2", "project").is_ok());
+        for bad in [
+            "",
+            "2shop",
+            "_shop",
+            "my-shop",
+            "Shop",
+            "my shop",
+            "self",
+            "django_rs",
+        ] {
+            assert!(validate_name(bad, "project").is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_random_secret_key() {
+        let key = random_secret_key();
+        assert_eq!(key.len(), 50);
+        assert!(!key.contains('"') && !key.contains('\\'));
+        assert_ne!(key, random_secret_key());
+    }
+
+    #[tokio::test]
+    async fn test_write_template_refuses_non_empty_target() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("existing"), "").unwrap();
+        let result = write_template(APP_TEMPLATE, dir.path(), &[]).await;
+        assert!(matches!(result, Err(DjangoError::ConfigurationError(_))));
+    }
+}
@@ -0,0 +1,8 @@
+[package]
+name = "{{ app_name }}"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+django-rs = "{{ django_rs_version }}"
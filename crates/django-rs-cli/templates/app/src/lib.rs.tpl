@@ -0,0 +1,12 @@
+//! The {{ app_name }} app.
+//!
+//! Add `"{{ app_name }}"` to `installed_apps` in `settings.toml`, depend on
+//! this crate from the project, and mount [`urls::urlpatterns`] in the
+//! project's URL configuration.
+
+pub mod models;
+pub mod urls;
+pub mod views;
+
+/// The app label used in `installed_apps` and model metadata.
+pub const APP_LABEL: &str = "{{ app_name }}";
@@ -0,0 +1,22 @@
+//! Models of the {{ app_name }} app.
+//!
+//! Each struct deriving `Model` maps to a database table. Run
+//! `makemigrations {{ app_name }}` after changing them.
+
+// The derive refers to these crates by name.
+use django_rs::{core as django_rs_core, db as django_rs_db};
+use django_rs::prelude::*;
+
+/// A sample model; rename or replace it.
+#[derive(Debug, Clone, ModelDerive)]
+#[model(app = "{{ app_name }}", ordering = ["name"])]
+pub struct Item {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    #[field(max_length = 200)]
+    pub name: String,
+
+    #[field(blank, default = "")]
+    pub description: String,
+}
@@ -0,0 +1,18 @@
+//! URL patterns of the {{ app_name }} app.
+
+use std::sync::Arc;
+
+use django_rs::http::urls::pattern::path;
+use django_rs::http::urls::resolver::URLEntry;
+use django_rs::prelude::*;
+
+use crate::views;
+
+/// Returns the app's URL patterns, to be mounted with `include()`.
+pub fn urlpatterns(engine: Arc<Engine>) -> DjangoResult<Vec<URLEntry>> {
+    let index = Arc::new(move |request: HttpRequest| {
+        let engine = Arc::clone(&engine);
+        Box::pin(async move { views::index(&request, &engine) }) as django_rs::http::BoxFuture
+    });
+    Ok(vec![URLEntry::Pattern(path("", index, Some("index"))?)])
+}
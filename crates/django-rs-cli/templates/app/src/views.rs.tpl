@@ -0,0 +1,12 @@
+//! Views of the {{ app_name }} app.
+
+use django_rs::prelude::*;
+
+/// Renders the app's index page.
+pub fn index(_request: &HttpRequest, engine: &Engine) -> HttpResponse {
+    let mut ctx = Context::new();
+    match engine.render_to_string("{{ app_name }}/index.html", &mut ctx) {
+        Ok(html) => HttpResponse::ok(html),
+        Err(e) => HttpResponse::server_error(format!("Template error: {e}")),
+    }
+}
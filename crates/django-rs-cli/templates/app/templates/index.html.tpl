@@ -0,0 +1,6 @@
+{% extends "base.html" %}
+
+{% block content %}
+<h1>{{ app_name }}</h1>
+<p>Edit <code>templates/{{ app_name }}/index.html</code> to change this page.</p>
+{% endblock %}
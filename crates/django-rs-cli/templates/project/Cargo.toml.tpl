@@ -0,0 +1,13 @@
+[package]
+name = "{{ project_name }}"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+# Apps created with `startapp` live in `apps/` and join the workspace.
+[workspace]
+members = ["apps/*"]
+
+[dependencies]
+django-rs = { version = "{{ django_rs_version }}", features = ["sqlite"] }
+tokio = { version = "1", features = ["full"] }
@@ -0,0 +1,4 @@
+/target
+**/target
+*.sqlite3
+.env
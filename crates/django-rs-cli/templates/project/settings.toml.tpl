@@ -0,0 +1,33 @@
+# Settings for the {{ project_name }} project.
+#
+# Settings left out keep their defaults. DJANGO_SECRET_KEY, DJANGO_DEBUG,
+# DJANGO_ALLOWED_HOSTS and DJANGO_LOG_LEVEL override the values below.
+
+# SECURITY WARNING: keep the secret key used in production secret, and
+# don't run with debug turned on in production.
+debug = true
+secret_key = "{{ secret_key }}"
+allowed_hosts = ["localhost", "127.0.0.1"]
+log_level = "info"
+
+root_urlconf = "{{ project_name }}.urls"
+installed_apps = [
+    "django_rs.auth",
+    "django_rs.admin",
+]
+
+language_code = "en-us"
+time_zone = "UTC"
+use_tz = true
+
+static_url = "/static/"
+
+[databases.default]
+engine = "django_rs.db.backends.sqlite3"
+name = "{{ project_name }}.sqlite3"
+
+[[templates]]
+backend = "django_rs.template.backends.tera"
+dirs = ["templates"]
+app_dirs = true
+options = {}
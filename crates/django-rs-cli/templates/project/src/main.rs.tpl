@@ -0,0 +1,28 @@
+//! The {{ project_name }} project.
+//!
+//! Start the development server with `cargo run`, then open
+//! <http://127.0.0.1:8000/>.
+
+mod models;
+mod urls;
+
+use std::sync::Arc;
+
+use django_rs::core::settings_loader;
+use django_rs::prelude::*;
+
+#[tokio::main]
+async fn main() -> Result<(), DjangoError> {
+    let settings = settings_loader::from_toml_file_with_env("settings.toml")?;
+    let engine = settings
+        .templates
+        .first()
+        .map(Engine::from_settings)
+        .unwrap_or_default();
+    let engine = Arc::new(engine);
+
+    DjangoApp::new(settings)
+        .urls(urls::urlpatterns(Arc::clone(&engine))?)
+        .run("127.0.0.1:8000")
+        .await
+}
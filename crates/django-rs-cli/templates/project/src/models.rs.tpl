@@ -0,0 +1,24 @@
+//! Models of the {{ project_name }} project.
+//!
+//! Each struct deriving `Model` maps to a database table. Run
+//! `makemigrations` after changing them.
+
+#![allow(dead_code)]
+
+// The derive refers to these crates by name.
+use django_rs::{core as django_rs_core, db as django_rs_db};
+use django_rs::prelude::*;
+
+/// A sample model; rename or replace it.
+#[derive(Debug, Clone, ModelDerive)]
+#[model(app = "{{ project_name }}", ordering = ["name"])]
+pub struct Item {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    #[field(max_length = 200)]
+    pub name: String,
+
+    #[field(blank, default = "")]
+    pub description: String,
+}
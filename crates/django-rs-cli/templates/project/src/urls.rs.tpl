@@ -0,0 +1,30 @@
+//! URL configuration for the {{ project_name }} project.
+//!
+//! Add a pattern for each view, or mount an app's patterns with
+//! `include("blog/", blog::urls::urlpatterns(engine)?, Some("blog"), None)`.
+
+use std::sync::Arc;
+
+use django_rs::http::urls::pattern::{path, RouteHandler};
+use django_rs::http::urls::resolver::{root, URLEntry, URLResolver};
+use django_rs::http::HttpResponse;
+use django_rs::prelude::*;
+
+/// Returns the project's root URL resolver.
+pub fn urlpatterns(engine: Arc<Engine>) -> DjangoResult<URLResolver> {
+    root(vec![URLEntry::Pattern(path("", index(engine), Some("index"))?)])
+}
+
+/// Renders the welcome page.
+fn index(engine: Arc<Engine>) -> RouteHandler {
+    Arc::new(move |_request| {
+        let engine = Arc::clone(&engine);
+        Box::pin(async move {
+            let mut ctx = Context::new();
+            match engine.render_to_string("index.html", &mut ctx) {
+                Ok(html) => HttpResponse::ok(html),
+                Err(e) => HttpResponse::server_error(format!("Template error: {e}")),
+            }
+        })
+    })
+}
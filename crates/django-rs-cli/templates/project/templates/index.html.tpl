@@ -0,0 +1,7 @@
+{% extends "base.html" %}
+
+{% block content %}
+<h1>The install worked successfully!</h1>
+<p>You are seeing this page because <code>{{ project_name }}</code> is running
+with debug enabled. Edit <code>src/urls.rs</code> to add your own views.</p>
+{% endblock %}
@@ -0,0 +1,48 @@
+//! Builds a project and app generated by `startproject` and `startapp`.
+//!
+//! The skeleton is patched to use this checkout of `django-rs` and pinned to
+//! the workspace's lock file, so the build runs offline against the crates
+//! already fetched for the workspace.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+use django_rs_cli::commands::startapp::start_app;
+use django_rs_cli::commands::startproject::start_project;
+
+#[tokio::test]
+async fn test_generated_project_and_app_build() {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("mysite");
+    start_project("mysite", &project).await.unwrap();
+    start_app("blog", &project.join("apps/blog")).await.unwrap();
+
+    let manifest = project.join("Cargo.toml");
+    let mut contents = std::fs::read_to_string(&manifest).unwrap();
+    write!(
+        contents,
+        "\n[patch.crates-io]\ndjango-rs = {{ path = {:?} }}\n",
+        workspace.join("django-rs").canonicalize().unwrap()
+    )
+    .unwrap();
+    std::fs::write(&manifest, contents).unwrap();
+    std::fs::copy(workspace.join("Cargo.lock"), project.join("Cargo.lock")).unwrap();
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(["build", "--workspace", "--offline", "--quiet"])
+        .current_dir(&project)
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("scaffold"),
+        )
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "generated project failed to build:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
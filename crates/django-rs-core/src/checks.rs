@@ -8,7 +8,8 @@
 //! - [`CheckMessage`]: A diagnostic message from a check (with level, message, hint, etc.).
 //! - [`CheckLevel`]: Severity level (Debug, Info, Warning, Error, Critical).
 //! - [`CheckRegistry`]: Registry for check functions with tag-based filtering.
-//! - Built-in checks: `SECRET_KEY` set, `DEBUG` is false in production, `ALLOWED_HOSTS` set.
+//! - Built-in checks: `SECRET_KEY` set, `DEBUG` is false in production, `ALLOWED_HOSTS` set,
+//!   and database options compatible with the declared connection pool mode.
 //!
 //! ## Examples
 //!
@@ -33,7 +34,7 @@
 //! assert!(!messages.is_empty());
 //! ```
 
-use crate::settings::Settings;
+use crate::settings::{PoolMode, Settings};
 
 /// Severity level for a check message.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -199,6 +200,7 @@ impl CheckRegistry {
         registry.register(check_secret_key, &["security"]);
         registry.register(check_debug_production, &["security"]);
         registry.register(check_allowed_hosts, &["security"]);
+        registry.register(check_database_pool_modes, &["database"]);
         registry
     }
 
@@ -309,6 +311,77 @@ fn check_allowed_hosts(settings: &Settings) -> Vec<CheckMessage> {
     messages
 }
 
+/// Checks each database's options against its declared `pool_mode`.
+///
+/// Under transaction or statement pooling, options that set session state
+/// would only apply to whichever server connection happened to run them.
+pub fn check_database_pool_modes(settings: &Settings) -> Vec<CheckMessage> {
+    let mut aliases: Vec<_> = settings.databases.iter().collect();
+    aliases.sort_by_key(|(alias, _)| alias.as_str());
+
+    let mut messages = Vec::new();
+    for (alias, db) in aliases {
+        let obj = format!("DATABASES['{alias}']");
+        let mode = match db.pool_mode() {
+            Ok(Some(mode)) => mode,
+            Ok(None) => continue,
+            Err(value) => {
+                messages.push(CheckMessage::error(
+                    format!("Database '{alias}' has an unknown pool_mode '{value}'."),
+                    Some("Use 'session', 'transaction' or 'statement'."),
+                    Some(&obj),
+                    Some("database.E002"),
+                ));
+                continue;
+            }
+        };
+        if !mode.shares_connections() {
+            continue;
+        }
+        if !db.engine.contains("postgres") {
+            messages.push(CheckMessage::error(
+                format!(
+                    "Database '{alias}' declares {} pooling, which is only supported \
+                     for PostgreSQL.",
+                    mode.as_str()
+                ),
+                Some("Remove pool_mode or switch the engine to PostgreSQL."),
+                Some(&obj),
+                Some("database.E003"),
+            ));
+        }
+        for option in PoolMode::SESSION_OPTIONS {
+            if db.options.get(option).is_some_and(|v| !v.trim().is_empty()) {
+                messages.push(CheckMessage::error(
+                    format!(
+                        "Database '{alias}' sets '{option}', which is session state lost \
+                         under {} pooling.",
+                        mode.as_str()
+                    ),
+                    Some(
+                        "Set it for the database role instead (ALTER ROLE ... SET), \
+                         or qualify names in queries.",
+                    ),
+                    Some(&obj),
+                    Some("database.E004"),
+                ));
+            }
+        }
+        if mode == PoolMode::Statement {
+            messages.push(CheckMessage::warning(
+                format!(
+                    "Database '{alias}' uses statement pooling, which refuses \
+                     multi-statement transactions."
+                ),
+                Some("Use transaction pooling if the application uses atomic blocks."),
+                Some(&obj),
+                Some("database.W001"),
+            ));
+        }
+    }
+    messages
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -594,7 +667,7 @@ mod tests {
     #[test]
     fn test_registry_with_builtins() {
         let registry = CheckRegistry::with_builtins();
-        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.len(), 4);
 
         // Default settings: debug=true, empty secret_key, no allowed_hosts
         let settings = Settings::default();
@@ -624,4 +697,79 @@ mod tests {
             .iter()
             .any(|m| m.id.as_deref() == Some("security.E001")));
     }
+
+    // ── Pool modes ──────────────────────────────────────────────────
+
+    fn pooled_settings(engine: &str, options: &[(&str, &str)]) -> Settings {
+        let db = crate::settings::DatabaseSettings {
+            engine: engine.to_string(),
+            options: options
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+            ..crate::settings::DatabaseSettings::default()
+        };
+        Settings {
+            databases: std::collections::HashMap::from([("default".to_string(), db)]),
+            ..Settings::default()
+        }
+    }
+
+    fn ids(messages: &[CheckMessage]) -> Vec<&str> {
+        messages.iter().filter_map(|m| m.id.as_deref()).collect()
+    }
+
+    #[test]
+    fn test_check_pool_mode_compatible() {
+        let pg = "django_rs.db.backends.postgresql";
+        for options in [
+            vec![],
+            vec![("pool_mode", "transaction")],
+            vec![("pool_mode", "session"), ("search_path", "app")],
+        ] {
+            let settings = pooled_settings(pg, &options);
+            assert!(
+                check_database_pool_modes(&settings).is_empty(),
+                "{options:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_pool_mode_problems() {
+        let pg = "django_rs.db.backends.postgresql";
+        let settings = pooled_settings(
+            pg,
+            &[
+                ("pool_mode", "transaction"),
+                ("search_path", "app"),
+                ("time_zone", "UTC"),
+            ],
+        );
+        let messages = check_database_pool_modes(&settings);
+        assert_eq!(ids(&messages), vec!["database.E004", "database.E004"]);
+        assert!(messages[0].msg.contains("'search_path'"));
+        assert_eq!(messages[0].obj.as_deref(), Some("DATABASES['default']"));
+
+        let settings = pooled_settings(pg, &[("pool_mode", "statement")]);
+        assert_eq!(
+            ids(&check_database_pool_modes(&settings)),
+            vec!["database.W001"]
+        );
+
+        let settings = pooled_settings(pg, &[("pool_mode", "txn")]);
+        assert_eq!(
+            ids(&check_database_pool_modes(&settings)),
+            vec!["database.E002"]
+        );
+
+        let settings = pooled_settings(
+            "django_rs.db.backends.sqlite3",
+            &[("pool_mode", "transaction")],
+        );
+        assert_eq!(
+            ids(&check_database_pool_modes(&settings)),
+            vec!["database.E003"]
+        );
+    }
 }
@@ -0,0 +1,305 @@
+//! Feature flags for gradual rollouts.
+//!
+//! A [`Flag`] is either on for everyone, on for a percentage of users, or
+//! off, with per-user overrides that win over both. Flags come from two
+//! places:
+//!
+//! - **Settings** — `Settings::feature_flags`, fixed at startup.
+//! - **Runtime** — flags set on the global [`FLAGS`] registry, usually by the
+//!   admin's `/flags/` endpoint from flags saved in the database. A runtime
+//!   flag replaces the settings flag of the same name, so a rollout can be
+//!   widened or rolled back without a deploy.
+//!
+//! Flags are evaluated against a [`FlagContext`] describing who is asking.
+//! Anything implementing [`FlagSubject`] can supply one; `HttpRequest` does,
+//! so views call [`flag_enabled`] with the request, and templates use
+//! `{% ifflag "name" %}`.
+//!
+//! Percentage rollouts hash the flag name with the user id, so a user stays
+//! in or out of a rollout across requests and processes, and different flags
+//! pick different users. Anonymous users have no stable id and only see a
+//! percentage flag once it reaches 100.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_core::flags::{Flag, FlagContext, FlagRegistry};
+//!
+//! let flags = FlagRegistry::new();
+//! flags.set("new_checkout", Flag::percentage(25).with_user("alice", true));
+//!
+//! assert!(flags.is_enabled("new_checkout", &FlagContext::for_user("alice")));
+//! assert!(!flags.is_enabled("new_checkout", &FlagContext::anonymous()));
+//! assert!(!flags.is_enabled("unknown", &FlagContext::for_user("alice")));
+//! ```
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::settings::SETTINGS;
+
+/// A named feature flag's rollout rules.
+///
+/// Rules are checked in order: a per-user override, then `enabled`, then
+/// `percentage`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Flag {
+    /// Whether the flag is on for everyone.
+    pub enabled: bool,
+    /// The percentage of users (0-100) the flag is on for, when not
+    /// `enabled`.
+    pub percentage: Option<u8>,
+    /// Per-user overrides, keyed by user id.
+    pub users: HashMap<String, bool>,
+}
+
+impl Flag {
+    /// Returns a flag that is on for everyone.
+    pub fn on() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns a flag that is off for everyone.
+    pub fn off() -> Self {
+        Self::default()
+    }
+
+    /// Returns a flag that is on for `percentage` percent of users.
+    ///
+    /// Values above 100 are treated as 100.
+    pub fn percentage(percentage: u8) -> Self {
+        Self {
+            percentage: Some(percentage.min(100)),
+            ..Self::default()
+        }
+    }
+
+    /// Turns the flag on or off for one user, whatever the other rules say.
+    #[must_use]
+    pub fn with_user(mut self, user: impl Into<String>, enabled: bool) -> Self {
+        self.users.insert(user.into(), enabled);
+        self
+    }
+
+    /// Returns `true` if the flag named `name` is on in `context`.
+    ///
+    /// The name seeds the percentage bucket, so it must be the name the flag
+    /// is registered under.
+    pub fn is_active(&self, name: &str, context: &FlagContext) -> bool {
+        if let Some(user) = &context.user {
+            if let Some(&enabled) = self.users.get(user) {
+                return enabled;
+            }
+        }
+        if self.enabled {
+            return true;
+        }
+        match (self.percentage, &context.user) {
+            (Some(percentage), _) if percentage >= 100 => true,
+            (Some(percentage), Some(user)) => rollout_bucket(name, user) < u32::from(percentage),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the stable 0-99 rollout bucket of `user` for the flag `name`.
+fn rollout_bucket(name: &str, user: &str) -> u32 {
+    let digest = Sha256::digest(format!("{name}:{user}").as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % 100
+}
+
+/// Who a flag is being evaluated for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlagContext {
+    /// The id of the logged-in user, or `None` for an anonymous request.
+    pub user: Option<String>,
+}
+
+impl FlagContext {
+    /// Returns the context for an anonymous request.
+    pub const fn anonymous() -> Self {
+        Self { user: None }
+    }
+
+    /// Returns the context for a logged-in user.
+    pub fn for_user(user: impl Into<String>) -> Self {
+        Self {
+            user: Some(user.into()),
+        }
+    }
+}
+
+/// A value that flags can be evaluated against, such as a request.
+pub trait FlagSubject {
+    /// Returns the context flags are evaluated in.
+    fn flag_context(&self) -> FlagContext;
+}
+
+impl FlagSubject for FlagContext {
+    fn flag_context(&self) -> FlagContext {
+        self.clone()
+    }
+}
+
+/// Flags set at runtime, layered over the flags in settings.
+///
+/// The process-wide registry is [`FLAGS`]; separate registries are mostly
+/// useful in tests.
+#[derive(Debug, Default)]
+pub struct FlagRegistry {
+    runtime: RwLock<HashMap<String, Flag>>,
+}
+
+impl FlagRegistry {
+    /// Creates a registry with no runtime flags.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a runtime flag, replacing any settings flag of the same name.
+    pub fn set(&self, name: &str, flag: Flag) {
+        self.runtime
+            .write()
+            .expect("flag registry lock poisoned")
+            .insert(name.to_string(), flag);
+    }
+
+    /// Removes a runtime flag, so the settings flag of the same name (if
+    /// any) applies again. Returns the removed flag.
+    pub fn remove(&self, name: &str) -> Option<Flag> {
+        self.runtime
+            .write()
+            .expect("flag registry lock poisoned")
+            .remove(name)
+    }
+
+    /// Replaces all runtime flags, e.g. after reloading them from the
+    /// database.
+    pub fn replace_all(&self, flags: HashMap<String, Flag>) {
+        *self.runtime.write().expect("flag registry lock poisoned") = flags;
+    }
+
+    /// Returns the runtime flags.
+    pub fn runtime_flags(&self) -> HashMap<String, Flag> {
+        self.runtime
+            .read()
+            .expect("flag registry lock poisoned")
+            .clone()
+    }
+
+    /// Returns the flag named `name`: the runtime flag if one is set,
+    /// otherwise the flag from settings.
+    pub fn get(&self, name: &str) -> Option<Flag> {
+        if let Some(flag) = self
+            .runtime
+            .read()
+            .expect("flag registry lock poisoned")
+            .get(name)
+        {
+            return Some(flag.clone());
+        }
+        if SETTINGS.is_configured() {
+            SETTINGS.get().feature_flags.get(name).cloned()
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the flag named `name` is on for `subject`.
+    ///
+    /// Unknown flags are off.
+    pub fn is_enabled<S: FlagSubject + ?Sized>(&self, name: &str, subject: &S) -> bool {
+        self.get(name)
+            .is_some_and(|flag| flag.is_active(name, &subject.flag_context()))
+    }
+}
+
+/// The process-wide flag registry.
+pub static FLAGS: Lazy<FlagRegistry> = Lazy::new(FlagRegistry::new);
+
+/// Returns `true` if the flag named `name` is on for `subject`, typically
+/// the current request.
+///
+/// ```
+/// use django_rs_core::flags::{flag_enabled, FlagContext};
+///
+/// assert!(!flag_enabled("new_checkout", &FlagContext::for_user("alice")));
+/// ```
+pub fn flag_enabled<S: FlagSubject + ?Sized>(name: &str, subject: &S) -> bool {
+    FLAGS.is_enabled(name, subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boolean_flags() {
+        let alice = FlagContext::for_user("alice");
+        assert!(Flag::on().is_active("f", &alice));
+        assert!(Flag::on().is_active("f", &FlagContext::anonymous()));
+        assert!(!Flag::off().is_active("f", &alice));
+    }
+
+    #[test]
+    fn test_user_overrides_win() {
+        let flag = Flag::on().with_user("bob", false);
+        assert!(!flag.is_active("f", &FlagContext::for_user("bob")));
+        assert!(flag.is_active("f", &FlagContext::for_user("alice")));
+
+        let flag = Flag::off().with_user("alice", true);
+        assert!(flag.is_active("f", &FlagContext::for_user("alice")));
+        assert!(!flag.is_active("f", &FlagContext::anonymous()));
+    }
+
+    #[test]
+    fn test_percentage_rollout_is_stable_and_proportional() {
+        let flag = Flag::percentage(30);
+        let enabled = (0..1000)
+            .filter(|i| flag.is_active("rollout", &FlagContext::for_user(i.to_string())))
+            .count();
+        assert!((200..400).contains(&enabled), "{enabled} of 1000 enabled");
+
+        let user = FlagContext::for_user("42");
+        let first = flag.is_active("rollout", &user);
+        assert!((0..10).all(|_| flag.is_active("rollout", &user) == first));
+
+        assert!(!Flag::percentage(0).is_active("rollout", &user));
+        assert!(Flag::percentage(100).is_active("rollout", &FlagContext::anonymous()));
+        assert!(!Flag::percentage(99).is_active("rollout", &FlagContext::anonymous()));
+        assert_eq!(Flag::percentage(150).percentage, Some(100));
+    }
+
+    #[test]
+    fn test_registry_runtime_flags() {
+        let flags = FlagRegistry::new();
+        let alice = FlagContext::for_user("alice");
+        assert!(!flags.is_enabled("beta", &alice));
+
+        flags.set("beta", Flag::on());
+        assert!(flags.is_enabled("beta", &alice));
+        assert_eq!(flags.runtime_flags().len(), 1);
+
+        assert_eq!(flags.remove("beta"), Some(Flag::on()));
+        assert!(!flags.is_enabled("beta", &alice));
+
+        flags.replace_all(HashMap::from([("gamma".to_string(), Flag::on())]));
+        assert!(flags.is_enabled("gamma", &alice));
+    }
+
+    #[test]
+    fn test_flag_deserializes_with_defaults() {
+        let flag: Flag = serde_json::from_str(r#"{"percentage": 10}"#).unwrap();
+        assert_eq!(flag, Flag::percentage(10));
+        let flag: Flag = serde_json::from_str(r#"{"users": {"alice": true}}"#).unwrap();
+        assert_eq!(flag, Flag::off().with_user("alice", true));
+    }
+}
@@ -7,6 +7,8 @@
 //! - **Lazy translations**: `gettext_lazy()` defers translation until the string is used.
 //! - **Language activation**: Thread-local `activate()`, `deactivate()`, `get_language()`.
 //! - **Timezone support**: `activate_timezone()`, `localtime()`, `now()`.
+//! - **Input formats**: Locale-specific decimal separators and date formats for
+//!   parsing user input (`formats::parse_number()`, `formats::parse_date()`).
 //!
 //! ## Quick Start
 //!
@@ -29,6 +31,7 @@
 //! ```
 
 pub mod catalog;
+pub mod formats;
 pub mod lazy;
 pub mod timezone;
 
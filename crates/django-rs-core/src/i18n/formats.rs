@@ -0,0 +1,327 @@
+//! Locale-specific input formats for numbers, dates and times.
+//!
+//! This mirrors Django's `django/conf/locale/*/formats.py` and
+//! `django.utils.formats`: each locale defines its decimal and thousand
+//! separators and the date formats people type, so `1.234,56` is read as
+//! `1234.56` in German and `03/04/2024` is the 3rd of April in British
+//! English but March 4th in American English.
+//!
+//! The ISO forms (`1234.56`, `2024-04-03`, `2024-04-03T14:30:00`, `14:30`)
+//! are accepted in every locale. Locales are looked up by language code, then
+//! by the language without its region (`de-at` falls back to `de`), then
+//! fall back to `en`.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! use django_rs_core::i18n::formats;
+//!
+//! assert_eq!(formats::parse_number("1.234,56", "de").as_deref(), Some("1234.56"));
+//! assert_eq!(formats::parse_number("1,234.56", "en").as_deref(), Some("1234.56"));
+//!
+//! let date = formats::parse_date("03/04/2024", "en-gb").unwrap();
+//! assert_eq!(date.to_string(), "2024-04-03");
+//! let date = formats::parse_date("03/04/2024", "en").unwrap();
+//! assert_eq!(date.to_string(), "2024-03-04");
+//! ```
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// The input formats of one locale.
+///
+/// Date and time formats use `chrono`'s `strftime` syntax, which matches
+/// the `%`-formats Django's locale files use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormats {
+    /// The character between the integer and fractional parts of a number.
+    pub decimal_separator: char,
+    /// The character grouping the digits of a number's integer part in
+    /// threes.
+    pub thousand_separator: char,
+    /// The formats a date may be entered in, tried in order.
+    pub date_input_formats: &'static [&'static str],
+    /// The formats a time of day may be entered in, tried in order.
+    pub time_input_formats: &'static [&'static str],
+}
+
+/// The ISO time formats, accepted in every locale.
+const TIME_INPUT_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M:%S%.f", "%H:%M"];
+
+const EN: LocaleFormats = LocaleFormats {
+    decimal_separator: '.',
+    thousand_separator: ',',
+    date_input_formats: &[
+        "%Y-%m-%d",
+        "%m/%d/%Y",
+        "%m/%d/%y",
+        "%b %d %Y",
+        "%b %d, %Y",
+        "%d %b %Y",
+        "%d %b, %Y",
+        "%B %d %Y",
+        "%B %d, %Y",
+        "%d %B %Y",
+        "%d %B, %Y",
+    ],
+    time_input_formats: TIME_INPUT_FORMATS,
+};
+
+const EN_GB: LocaleFormats = LocaleFormats {
+    date_input_formats: &[
+        "%Y-%m-%d",
+        "%d/%m/%Y",
+        "%d/%m/%y",
+        "%d %b %Y",
+        "%d %b, %Y",
+        "%d %B %Y",
+        "%d %B, %Y",
+    ],
+    ..EN
+};
+
+const DE: LocaleFormats = LocaleFormats {
+    decimal_separator: ',',
+    thousand_separator: '.',
+    date_input_formats: &["%Y-%m-%d", "%d.%m.%Y", "%d.%m.%y"],
+    time_input_formats: TIME_INPUT_FORMATS,
+};
+
+const FR: LocaleFormats = LocaleFormats {
+    decimal_separator: ',',
+    thousand_separator: '\u{a0}',
+    date_input_formats: &["%Y-%m-%d", "%d/%m/%Y", "%d/%m/%y", "%d.%m.%Y", "%d.%m.%y"],
+    time_input_formats: TIME_INPUT_FORMATS,
+};
+
+const ES: LocaleFormats = LocaleFormats {
+    date_input_formats: &["%Y-%m-%d", "%d/%m/%Y", "%d/%m/%y"],
+    ..DE
+};
+
+const IT: LocaleFormats = LocaleFormats {
+    date_input_formats: &[
+        "%Y-%m-%d", "%d/%m/%Y", "%Y/%m/%d", "%d-%m-%Y", "%d/%m/%y", "%d-%m-%y",
+    ],
+    ..DE
+};
+
+const NL: LocaleFormats = LocaleFormats {
+    date_input_formats: &["%Y-%m-%d", "%d-%m-%Y", "%d-%m-%y", "%d/%m/%Y", "%d/%m/%y"],
+    ..DE
+};
+
+const PT: LocaleFormats = LocaleFormats {
+    date_input_formats: &["%Y-%m-%d", "%d/%m/%Y", "%d/%m/%y"],
+    ..DE
+};
+
+const PL: LocaleFormats = LocaleFormats {
+    thousand_separator: ' ',
+    date_input_formats: &["%Y-%m-%d", "%d.%m.%Y", "%d.%m.%y"],
+    ..DE
+};
+
+const RU: LocaleFormats = LocaleFormats {
+    thousand_separator: '\u{a0}',
+    ..PL
+};
+
+const SV: LocaleFormats = LocaleFormats {
+    thousand_separator: '\u{a0}',
+    date_input_formats: &["%Y-%m-%d", "%m/%d/%Y", "%m/%d/%y"],
+    ..DE
+};
+
+/// Returns the input formats for `language`.
+///
+/// The language code is matched case-insensitively, with `_` read as `-`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::i18n::formats::get_format;
+///
+/// assert_eq!(get_format("de-AT").decimal_separator, ',');
+/// assert_eq!(get_format("en_GB").date_input_formats[1], "%d/%m/%Y");
+/// assert_eq!(get_format("xx").decimal_separator, '.');
+/// ```
+pub fn get_format(language: &str) -> &'static LocaleFormats {
+    let code = language.trim().to_ascii_lowercase().replace('_', "-");
+    lookup(&code)
+        .or_else(|| lookup(code.split('-').next().unwrap_or_default()))
+        .unwrap_or(&EN)
+}
+
+fn lookup(code: &str) -> Option<&'static LocaleFormats> {
+    Some(match code {
+        "en" | "en-us" => &EN,
+        "en-gb" | "en-au" | "en-ie" | "en-nz" => &EN_GB,
+        "de" => &DE,
+        "fr" => &FR,
+        "es" => &ES,
+        "it" => &IT,
+        "nl" => &NL,
+        "pt" | "pt-br" => &PT,
+        "pl" => &PL,
+        "ru" => &RU,
+        "sv" => &SV,
+        _ => return None,
+    })
+}
+
+/// Parses a number entered in `language`'s format, returning it in the
+/// canonical form: digits with an optional `-` sign and `.` decimal point.
+///
+/// Thousand separators are optional but must group the integer part in
+/// threes, so `1,5` is not read as `15` in English. Spaces are accepted
+/// wherever a locale groups digits with a (non-breaking) space. Returns
+/// `None` if the input is not a number in the locale or in canonical form.
+pub fn parse_number(input: &str, language: &str) -> Option<String> {
+    let input = input.trim();
+    let formats = get_format(language);
+    localized_number(
+        input,
+        formats.decimal_separator,
+        Some(formats.thousand_separator),
+    )
+    .or_else(|| localized_number(input, '.', None))
+}
+
+fn localized_number(input: &str, decimal: char, thousand: Option<char>) -> Option<String> {
+    let (sign, unsigned) = input.strip_prefix('-').map_or_else(
+        || ("", input.strip_prefix('+').unwrap_or(input)),
+        |rest| ("-", rest),
+    );
+    let (integer, fraction) = match unsigned.split_once(decimal) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    let groups: Vec<&str> = match thousand {
+        Some(separator) if separator.is_whitespace() => {
+            integer.split([' ', '\u{a0}', '\u{202f}']).collect()
+        }
+        Some(separator) => integer.split(separator).collect(),
+        None => vec![integer],
+    };
+    let (first, rest) = groups.split_first()?;
+    let valid_groups = is_digits(first)
+        && (rest.is_empty() || first.len() <= 3)
+        && rest
+            .iter()
+            .all(|group| group.len() == 3 && is_digits(group));
+    if !valid_groups || fraction.is_some_and(|f| !is_digits(f)) {
+        return None;
+    }
+
+    let mut number = format!("{sign}{}", groups.concat());
+    if let Some(fraction) = fraction {
+        number.push('.');
+        number.push_str(fraction);
+    }
+    Some(number)
+}
+
+/// Parses `input` as a date in `format`.
+///
+/// As in Python's `strptime`, `%Y` only matches a four-digit year, so
+/// `03/04/24` is not read as the year 24 when `%d/%m/%Y` is tried before
+/// `%d/%m/%y`.
+fn parse_date_as(input: &str, format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(input, format)
+        .ok()
+        .filter(|date| !format.contains("%Y") || date.year() >= 1000)
+}
+
+/// Parses a date entered in one of `language`'s date input formats.
+pub fn parse_date(input: &str, language: &str) -> Option<NaiveDate> {
+    let input = input.trim();
+    get_format(language)
+        .date_input_formats
+        .iter()
+        .find_map(|format| parse_date_as(input, format))
+}
+
+/// Parses a time of day entered in one of `language`'s time input formats.
+pub fn parse_time(input: &str, language: &str) -> Option<NaiveTime> {
+    let input = input.trim();
+    get_format(language)
+        .time_input_formats
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(input, format).ok())
+}
+
+/// Parses a date and time entered in `language`'s formats.
+///
+/// Accepts a date and a time in the locale's input formats separated by a
+/// space or a `T`, or a date alone, which is read as midnight.
+pub fn parse_datetime(input: &str, language: &str) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    let combined = input
+        .split_once('T')
+        .or_else(|| input.rsplit_once(' '))
+        .and_then(|(date, time)| {
+            Some(parse_date(date, language)?.and_time(parse_time(time, language)?))
+        });
+    combined.or_else(|| parse_date(input, language)?.and_hms_opt(0, 0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number_per_locale() {
+        assert_eq!(
+            parse_number("1.234.567,89", "de").as_deref(),
+            Some("1234567.89")
+        );
+        assert_eq!(parse_number("-0,5", "es").as_deref(), Some("-0.5"));
+        assert_eq!(
+            parse_number("1\u{a0}234,5", "fr").as_deref(),
+            Some("1234.5")
+        );
+        assert_eq!(parse_number("1 234,5", "fr").as_deref(), Some("1234.5"));
+        assert_eq!(parse_number("12,345", "en").as_deref(), Some("12345"));
+        assert_eq!(parse_number("42", "pl").as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_parse_number_accepts_canonical_form() {
+        assert_eq!(parse_number("1234.56", "de").as_deref(), Some("1234.56"));
+        assert_eq!(parse_number("+7.25", "fr").as_deref(), Some("7.25"));
+    }
+
+    #[test]
+    fn test_parse_number_rejects_bad_grouping() {
+        assert_eq!(parse_number("1,5", "en"), None);
+        assert_eq!(parse_number("1.23,4", "de"), None);
+        assert_eq!(parse_number("12a", "en"), None);
+        assert_eq!(parse_number("", "en"), None);
+        assert_eq!(parse_number("1,", "de"), None);
+    }
+
+    #[test]
+    fn test_parse_dates_and_times() {
+        let date = NaiveDate::from_ymd_opt(2024, 4, 3).unwrap();
+        assert_eq!(parse_date("03.04.2024", "de"), Some(date));
+        assert_eq!(parse_date("03/04/24", "fr"), Some(date));
+        assert_eq!(parse_date("2024-04-03", "ru"), Some(date));
+        assert_eq!(parse_date("04/03/2024", "en-US"), Some(date));
+        assert_eq!(parse_date("Apr 3, 2024", "en"), Some(date));
+        assert_eq!(parse_date("31.02.2024", "de"), None);
+
+        assert_eq!(
+            parse_time("14:30", "de"),
+            NaiveTime::from_hms_opt(14, 30, 0)
+        );
+        let datetime = date.and_hms_opt(14, 30, 0).unwrap();
+        assert_eq!(parse_datetime("03.04.2024 14:30", "de"), Some(datetime));
+        assert_eq!(parse_datetime("2024-04-03T14:30:00", "de"), Some(datetime));
+        assert_eq!(
+            parse_datetime("03/04/2024", "en-gb"),
+            date.and_hms_opt(0, 0, 0)
+        );
+    }
+}
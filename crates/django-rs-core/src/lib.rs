@@ -6,14 +6,17 @@
 //! ## Modules
 //!
 //! - [`error`] - Error types and result aliases
+//! - [`flags`] - Feature flags with percentage rollouts and per-user overrides
 //! - [`utils`] - Utility types (`MultiValueDict`, `LazyObject`, text helpers)
 //! - [`settings`] - Framework settings and global configuration
 //! - [`settings_loader`] - Load settings from TOML, JSON, and environment variables
+//! - [`secrets`] - Secrets providers (env, file, Vault) for settings values
 //! - [`apps`] - Application registry and lifecycle management
 //! - [`logging`] - Tracing-based logging integration
+//! - `otel` - OpenTelemetry span export and trace propagation (requires the `otel` feature)
 //! - [`signing`] - Cryptographic signing (HMAC-SHA256, timestamps, serialization)
 //! - [`checks`] - System check framework for configuration validation
-//! - [`i18n`] - Internationalization and localization (translation catalogs, timezone)
+//! - [`i18n`] - Internationalization and localization (translation catalogs, timezone, input formats)
 
 // Allow large error type (DjangoError is shared across the project).
 #![allow(clippy::result_large_err)]
@@ -21,8 +24,12 @@
 pub mod apps;
 pub mod checks;
 pub mod error;
+pub mod flags;
 pub mod i18n;
 pub mod logging;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod secrets;
 pub mod settings;
 pub mod settings_loader;
 pub mod signing;
@@ -57,3 +57,90 @@ pub fn setup_logging(settings: &Settings) {
 pub fn request_span(request_id: &str) -> tracing::Span {
     tracing::info_span!("request", id = request_id)
 }
+
+/// Replaces literal values in `sql` with `?` so the statement can be
+/// attached to spans and logs without leaking data.
+///
+/// String literals (`'...'`) and numeric literals are replaced; identifiers,
+/// including quoted (`"..."`) and backticked ones, and bind placeholders such
+/// as `$1` are kept.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::logging::sanitize_sql;
+///
+/// assert_eq!(
+///     sanitize_sql("SELECT * FROM \"user\" WHERE name = 'bob' AND age > 30"),
+///     "SELECT * FROM \"user\" WHERE name = ? AND age > ?"
+/// );
+/// ```
+pub fn sanitize_sql(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    // Whether the previous character can continue an identifier or a
+    // placeholder, in which case digits are part of it.
+    let mut in_word = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // Skip to the closing quote; '' is an escaped quote.
+                while let Some(c) = chars.next() {
+                    if c == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                out.push('?');
+                in_word = false;
+            }
+            '"' | '`' => {
+                out.push(c);
+                for q in chars.by_ref() {
+                    out.push(q);
+                    if q == c {
+                        break;
+                    }
+                }
+                in_word = false;
+            }
+            '0'..='9' if !in_word => {
+                while chars
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+                {
+                    chars.next();
+                }
+                out.push('?');
+            }
+            _ => {
+                out.push(c);
+                in_word = c.is_alphanumeric() || matches!(c, '_' | '$' | '?');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_sql_literals() {
+        assert_eq!(
+            sanitize_sql("UPDATE t SET note = 'it''s', price = 9.99 WHERE id = 7"),
+            "UPDATE t SET note = ?, price = ? WHERE id = ?"
+        );
+        assert_eq!(sanitize_sql("SELECT -1, 'a'"), "SELECT -?, ?");
+    }
+
+    #[test]
+    fn test_sanitize_sql_keeps_identifiers_and_placeholders() {
+        let sql = "SELECT \"col1\", `t2`.x3 FROM app_model2 WHERE a = $1 AND b = ?2 LIMIT ?";
+        assert_eq!(sanitize_sql(sql), sql);
+    }
+}
@@ -0,0 +1,184 @@
+//! OpenTelemetry tracing integration.
+//!
+//! The framework records [`tracing`] spans for request handling, each
+//! middleware phase, template rendering and every database query. This module
+//! exports those spans over OTLP/HTTP and links incoming requests to their
+//! caller's trace through the W3C `traceparent` header.
+//!
+//! Everything is configured from [`Settings::otel`]:
+//!
+//! ```toml
+//! [otel]
+//! enabled = true
+//! endpoint = "http://collector:4318/v1/traces"
+//! service_name = "shop"
+//! sample_ratio = 0.25
+//! ```
+//!
+//! This module requires the `otel` feature.
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+use crate::error::DjangoError;
+use crate::settings::{OtelSettings, Settings};
+
+/// Flushes and shuts down the span exporter when dropped.
+///
+/// Keep the guard alive for as long as the application runs.
+#[derive(Debug)]
+pub struct OtelGuard {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                tracing::warn!("Failed to shut down OpenTelemetry exporter: {e}");
+            }
+        }
+    }
+}
+
+/// Sets up the global tracing subscriber, exporting spans over OTLP when
+/// `settings.otel.enabled` is set.
+///
+/// Logging behaves as in [`setup_logging`](crate::logging::setup_logging);
+/// the OpenTelemetry layer is added on top of it. When export is disabled,
+/// this is equivalent to `setup_logging`.
+///
+/// # Errors
+///
+/// Returns [`DjangoError::ImproperlyConfigured`] if the exporter cannot be
+/// built from the settings.
+pub fn setup_tracing(settings: &Settings) -> Result<OtelGuard, DjangoError> {
+    let provider = if settings.otel.enabled {
+        Some(build_provider(&settings.otel)?)
+    } else {
+        None
+    };
+    let otel_layer = provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer("django-rs")));
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let filter = EnvFilter::try_new(&settings.log_level).unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt_layer = if settings.debug {
+        fmt::layer()
+            .with_target(true)
+            .with_file(true)
+            .with_line_number(true)
+            .pretty()
+            .boxed()
+    } else {
+        fmt::layer().with_target(true).json().boxed()
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(otel_layer)
+        .try_init()
+        .ok();
+
+    Ok(OtelGuard { provider })
+}
+
+fn build_provider(settings: &OtelSettings) -> Result<SdkTracerProvider, DjangoError> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&settings.endpoint)
+        .with_timeout(Duration::from_secs(settings.timeout))
+        .with_headers(settings.headers.clone())
+        .build()
+        .map_err(|e| {
+            DjangoError::ImproperlyConfigured(format!("Invalid OpenTelemetry exporter: {e}"))
+        })?;
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        settings.sample_ratio.clamp(0.0, 1.0),
+    )));
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(settings.service_name.clone())
+                .build(),
+        )
+        .build())
+}
+
+/// Reads a W3C trace context from request headers.
+struct HeaderExtractor<'a, S>(&'a HashMap<String, String, S>);
+
+impl<S: BuildHasher> Extractor for HeaderExtractor<'_, S> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
+
+/// Makes `span` a child of the trace described by the `traceparent` and
+/// `tracestate` entries of `headers`, if present and valid.
+///
+/// Header names are matched case-insensitively. Returns `true` if a remote
+/// parent was found.
+pub fn set_parent_from_headers<S: BuildHasher>(
+    span: &tracing::Span,
+    headers: &HashMap<String, String, S>,
+) -> bool {
+    use opentelemetry::trace::TraceContextExt;
+
+    let context = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+    if !context.span().span_context().is_valid() {
+        return false;
+    }
+    let _ = span.set_parent(context);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_parent_from_headers() {
+        let span = tracing::info_span!("request");
+        let mut headers = HashMap::new();
+        assert!(!set_parent_from_headers(&span, &headers));
+
+        headers.insert("traceparent".to_string(), "garbage".to_string());
+        assert!(!set_parent_from_headers(&span, &headers));
+
+        headers.insert(
+            "Traceparent".to_string(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+        );
+        headers.remove("traceparent");
+        assert!(set_parent_from_headers(&span, &headers));
+    }
+
+    #[test]
+    fn test_setup_tracing_disabled() {
+        let guard = setup_tracing(&Settings::default()).unwrap();
+        assert!(guard.provider.is_none());
+    }
+}
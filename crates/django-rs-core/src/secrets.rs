@@ -0,0 +1,604 @@
+//! Secrets providers for settings values.
+//!
+//! Any string setting may be given as a reference to a secret instead of a
+//! literal, so that passwords and keys never live in committed configuration
+//! files. A reference is a table with a single `secret` key:
+//!
+//! ```toml
+//! secret_key = { secret = { provider = "file", path = "/run/secrets/django_secret_key" } }
+//!
+//! [databases.default]
+//! engine = "django_rs.db.backends.postgresql"
+//! password = { secret = { provider = "env", name = "DB_PASS" } }
+//! ```
+//!
+//! References are resolved by a [`SecretResolver`] when settings are loaded
+//! (see [`settings_loader`](crate::settings_loader)). The `provider` key
+//! selects a [`SecretProvider`]; the remaining keys are passed to it.
+//! Built-in providers:
+//!
+//! | `provider` | Keys | Value |
+//! |---|---|---|
+//! | `env` | `name` | The environment variable `name` |
+//! | `file` | `path` | The contents of the file, without a trailing newline |
+//! | `vault` | `path`, `key` | Field `key` of a Vault secret (requires the `vault` feature) |
+//!
+//! Resolved values are cached per resolver. [`SecretResolver::refresh`]
+//! re-resolves them and calls the hooks registered with
+//! [`SecretResolver::on_change`] for every secret whose value changed, e.g. to
+//! reconnect with a rotated database password.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_core::secrets::SecretResolver;
+//! use django_rs_core::settings_loader;
+//!
+//! std::env::set_var("DOCS_DB_PASS", "s3cret");
+//! let resolver = SecretResolver::new();
+//! let settings = settings_loader::from_toml_str_with_secrets(
+//!     r#"
+//!     [databases.default]
+//!     password = { secret = { provider = "env", name = "DOCS_DB_PASS" } }
+//!     "#,
+//!     &resolver,
+//! )
+//! .unwrap();
+//! assert_eq!(settings.databases["default"].password, "s3cret");
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use crate::error::DjangoError;
+
+/// A reference to a secret: the provider to ask and its parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SecretRef {
+    /// The name of the provider, e.g. `"env"`.
+    pub provider: String,
+    /// Provider-specific parameters, e.g. `name = "DB_PASS"`.
+    pub params: BTreeMap<String, String>,
+}
+
+impl SecretRef {
+    /// Creates a reference to a secret held by `provider`.
+    pub fn new(provider: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a provider parameter.
+    #[must_use]
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns a required provider parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the parameter is missing.
+    pub fn require(&self, key: &str) -> Result<&str, DjangoError> {
+        self.params.get(key).map(String::as_str).ok_or_else(|| {
+            DjangoError::ConfigurationError(format!(
+                "Secret from '{}' requires a '{key}' key",
+                self.provider
+            ))
+        })
+    }
+
+    /// Parses a settings value as a secret reference.
+    ///
+    /// A reference is an object whose only key is `secret`, holding an
+    /// object with a string `provider` key and string parameters. Anything
+    /// else is a literal value, for which `Ok(None)` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the `secret` object is malformed.
+    pub fn from_json(value: &serde_json::Value) -> Result<Option<Self>, DjangoError> {
+        let Some(object) = value.as_object() else {
+            return Ok(None);
+        };
+        let (1, Some(secret)) = (object.len(), object.get("secret")) else {
+            return Ok(None);
+        };
+        let invalid = |reason: &str| {
+            DjangoError::ConfigurationError(format!("Invalid secret reference {secret}: {reason}"))
+        };
+        let secret = secret
+            .as_object()
+            .ok_or_else(|| invalid("expected a table"))?;
+        let provider = secret
+            .get("provider")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| invalid("missing a string 'provider' key"))?;
+        let mut params = BTreeMap::new();
+        for (key, value) in secret.iter().filter(|(key, _)| *key != "provider") {
+            let value = value
+                .as_str()
+                .ok_or_else(|| invalid(&format!("'{key}' is not a string")))?;
+            params.insert(key.clone(), value.to_string());
+        }
+        Ok(Some(Self {
+            provider: provider.to_string(),
+            params,
+        }))
+    }
+}
+
+impl std::fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.provider)?;
+        for (key, value) in &self.params {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A source of secret values.
+pub trait SecretProvider: Send + Sync {
+    /// Returns the value of the referenced secret.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the secret cannot be read.
+    fn resolve(&self, reference: &SecretRef) -> Result<String, DjangoError>;
+}
+
+/// Reads secrets from environment variables (`provider = "env"`, `name`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvProvider;
+
+impl SecretProvider for EnvProvider {
+    fn resolve(&self, reference: &SecretRef) -> Result<String, DjangoError> {
+        let name = reference.require("name")?;
+        std::env::var(name).map_err(|_| {
+            DjangoError::ConfigurationError(format!("Environment variable '{name}' is not set"))
+        })
+    }
+}
+
+/// Reads secrets from files (`provider = "file"`, `path`), such as Docker or
+/// Kubernetes secret mounts.
+///
+/// Relative paths are resolved against the provider's base directory, the
+/// working directory by default. A single trailing newline is removed.
+#[derive(Debug, Clone, Default)]
+pub struct FileProvider {
+    base_dir: Option<PathBuf>,
+}
+
+impl FileProvider {
+    /// Creates a provider resolving relative paths against the working directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves relative paths against `dir` instead.
+    #[must_use]
+    pub fn base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+}
+
+impl SecretProvider for FileProvider {
+    fn resolve(&self, reference: &SecretRef) -> Result<String, DjangoError> {
+        let path = PathBuf::from(reference.require("path")?);
+        let path = match &self.base_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        };
+        let mut content = std::fs::read_to_string(&path).map_err(|e| {
+            DjangoError::ConfigurationError(format!(
+                "Failed to read secret file '{}': {e}",
+                path.display()
+            ))
+        })?;
+        if content.ends_with('\n') {
+            content.pop();
+            if content.ends_with('\r') {
+                content.pop();
+            }
+        }
+        Ok(content)
+    }
+}
+
+/// Reads secrets from Vault (`provider = "vault"`, `path`, `key`).
+///
+/// `path` is the API path below `/v1/`, e.g. `secret/data/myapp/db` for a KV
+/// version 2 engine mounted at `secret`. Both KV versions are understood.
+/// Requests are blocking, so settings with Vault references should be loaded
+/// before the async runtime starts.
+#[cfg(feature = "vault")]
+#[derive(Debug, Clone)]
+pub struct VaultProvider {
+    address: String,
+    token: String,
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "vault")]
+impl VaultProvider {
+    /// Creates a provider for the Vault server at `address`, e.g.
+    /// `https://vault.example.com:8200`.
+    pub fn new(address: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            address: address.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Creates a provider from the `VAULT_ADDR` and `VAULT_TOKEN` environment
+    /// variables, if both are set.
+    pub fn from_env() -> Option<Self> {
+        let address = std::env::var("VAULT_ADDR").ok()?;
+        let token = std::env::var("VAULT_TOKEN").ok()?;
+        Some(Self::new(address, token))
+    }
+}
+
+#[cfg(feature = "vault")]
+impl SecretProvider for VaultProvider {
+    fn resolve(&self, reference: &SecretRef) -> Result<String, DjangoError> {
+        let path = reference.require("path")?.trim_start_matches('/');
+        let key = reference.require("key")?;
+        let error = |e: &dyn std::fmt::Display| {
+            DjangoError::ConfigurationError(format!("Failed to read Vault secret '{path}': {e}"))
+        };
+        let body: serde_json::Value = self
+            .client
+            .get(format!("{}/v1/{path}", self.address))
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::json)
+            .map_err(|e| error(&e))?;
+        let data = &body["data"];
+        // KV version 2 nests the secret under `data.data`.
+        let data = if data["data"].is_object() {
+            &data["data"]
+        } else {
+            data
+        };
+        match &data[key] {
+            serde_json::Value::String(value) => Ok(value.clone()),
+            serde_json::Value::Null => Err(error(&format!("no key '{key}'"))),
+            other => Ok(other.to_string()),
+        }
+    }
+}
+
+/// A callback run by [`SecretResolver::refresh`] when a secret changed.
+pub type SecretChangeHook = Arc<dyn Fn(&SecretRef, &str) + Send + Sync>;
+
+/// Resolves secret references with a set of named providers, caching the
+/// values.
+///
+/// [`new`](Self::new) registers the `env` and `file` providers, and `vault`
+/// when the `vault` feature is enabled and `VAULT_ADDR` and `VAULT_TOKEN` are
+/// set.
+pub struct SecretResolver {
+    providers: RwLock<HashMap<String, Arc<dyn SecretProvider>>>,
+    cache: Mutex<BTreeMap<SecretRef, String>>,
+    hooks: RwLock<Vec<SecretChangeHook>>,
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretResolver {
+    /// Creates a resolver with the built-in providers.
+    pub fn new() -> Self {
+        let resolver = Self::empty()
+            .with_provider("env", EnvProvider)
+            .with_provider("file", FileProvider::new());
+        #[cfg(feature = "vault")]
+        let resolver = match VaultProvider::from_env() {
+            Some(vault) => resolver.with_provider("vault", vault),
+            None => resolver,
+        };
+        resolver
+    }
+
+    /// Creates a resolver without any providers.
+    pub fn empty() -> Self {
+        Self {
+            providers: RwLock::new(HashMap::new()),
+            cache: Mutex::new(BTreeMap::new()),
+            hooks: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Registers `provider` for references with `provider = name`, replacing
+    /// any provider of that name.
+    #[must_use]
+    pub fn with_provider(self, name: &str, provider: impl SecretProvider + 'static) -> Self {
+        self.register(name, Arc::new(provider));
+        self
+    }
+
+    /// Registers `provider` for references with `provider = name`.
+    pub fn register(&self, name: &str, provider: Arc<dyn SecretProvider>) {
+        self.providers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name.to_string(), provider);
+    }
+
+    /// Registers a hook called by [`refresh`](Self::refresh) with each
+    /// reference whose value changed and its new value.
+    pub fn on_change(&self, hook: impl Fn(&SecretRef, &str) + Send + Sync + 'static) {
+        self.hooks
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::new(hook));
+    }
+
+    /// Returns the value of a secret, from the cache if it was resolved before.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if no provider is registered under
+    /// `reference.provider`, the provider fails, or a thread panicked while
+    /// holding the resolver's cache.
+    pub fn resolve(&self, reference: &SecretRef) -> Result<String, DjangoError> {
+        if let Some(value) = self.cache.lock().map_err(poisoned)?.get(reference) {
+            return Ok(value.clone());
+        }
+        let value = self.fetch(reference)?;
+        self.cache
+            .lock()
+            .map_err(poisoned)?
+            .insert(reference.clone(), value.clone());
+        Ok(value)
+    }
+
+    /// Replaces every secret reference in a settings value with the secret.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first malformed reference or resolution error.
+    pub fn resolve_json(&self, value: serde_json::Value) -> Result<serde_json::Value, DjangoError> {
+        if let Some(reference) = SecretRef::from_json(&value)? {
+            return self.resolve(&reference).map(serde_json::Value::String);
+        }
+        match value {
+            serde_json::Value::Object(map) => map
+                .into_iter()
+                .map(|(key, value)| Ok((key, self.resolve_json(value)?)))
+                .collect::<Result<_, DjangoError>>()
+                .map(serde_json::Value::Object),
+            serde_json::Value::Array(items) => items
+                .into_iter()
+                .map(|item| self.resolve_json(item))
+                .collect::<Result<_, DjangoError>>()
+                .map(serde_json::Value::Array),
+            other => Ok(other),
+        }
+    }
+
+    /// Re-resolves every cached secret and runs the change hooks for those
+    /// whose value changed. Returns the changed references.
+    ///
+    /// A secret that fails to resolve keeps its cached value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first resolution error, after all other secrets were
+    /// refreshed.
+    pub fn refresh(&self) -> Result<Vec<SecretRef>, DjangoError> {
+        let cached: Vec<(SecretRef, String)> = self
+            .cache
+            .lock()
+            .map_err(poisoned)?
+            .iter()
+            .map(|(reference, value)| (reference.clone(), value.clone()))
+            .collect();
+        let mut changed = Vec::new();
+        let mut first_error = None;
+        for (reference, old) in cached {
+            match self.fetch(&reference) {
+                Ok(value) if value != old => {
+                    self.cache
+                        .lock()
+                        .map_err(poisoned)?
+                        .insert(reference.clone(), value.clone());
+                    let hooks = self.hooks.read().map_err(poisoned)?.clone();
+                    for hook in &hooks {
+                        hook(&reference, &value);
+                    }
+                    changed.push(reference);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        first_error.map_or(Ok(changed), Err)
+    }
+
+    /// Forgets all cached values, so the next resolution asks the providers.
+    pub fn clear_cache(&self) {
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Asks the provider for a secret, bypassing the cache.
+    fn fetch(&self, reference: &SecretRef) -> Result<String, DjangoError> {
+        let provider = self
+            .providers
+            .read()
+            .map_err(poisoned)?
+            .get(&reference.provider)
+            .cloned()
+            .ok_or_else(|| {
+                DjangoError::ConfigurationError(format!(
+                    "Unknown secrets provider '{}'",
+                    reference.provider
+                ))
+            })?;
+        provider.resolve(reference)
+    }
+}
+
+/// The error returned when a thread panicked while holding the resolver's
+/// state.
+fn poisoned<T>(_: PoisonError<T>) -> DjangoError {
+    DjangoError::ConfigurationError(
+        "The secret resolver is unusable after a panic while it was locked".into(),
+    )
+}
+
+impl std::fmt::Debug for SecretResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut providers: Vec<String> = self
+            .providers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        providers.sort();
+        let cached = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len();
+        f.debug_struct("SecretResolver")
+            .field("providers", &providers)
+            .field("cached", &cached)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A provider returning a configurable value and counting lookups.
+    #[derive(Default)]
+    struct CountingProvider {
+        value: Mutex<String>,
+        calls: AtomicUsize,
+    }
+
+    impl SecretProvider for Arc<CountingProvider> {
+        fn resolve(&self, _reference: &SecretRef) -> Result<String, DjangoError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.value.lock().unwrap().clone())
+        }
+    }
+
+    #[test]
+    fn test_secret_ref_from_json() {
+        let value = serde_json::json!({"secret": {"provider": "env", "name": "DB_PASS"}});
+        assert_eq!(
+            SecretRef::from_json(&value).unwrap(),
+            Some(SecretRef::new("env").param("name", "DB_PASS"))
+        );
+        let literal = |value| SecretRef::from_json(&value).unwrap().is_none();
+        assert!(literal(serde_json::json!("literal")));
+        // A table that merely looks like a reference is kept as data.
+        assert!(literal(serde_json::json!({"from": "env", "name": "x"})));
+        assert!(literal(serde_json::json!({"secret": "x", "name": "y"})));
+
+        let malformed = |value| SecretRef::from_json(&value).unwrap_err().to_string();
+        assert!(malformed(serde_json::json!({"secret": "x"})).contains("expected a table"));
+        assert!(malformed(serde_json::json!({"secret": {"name": "x"}})).contains("'provider'"));
+        assert!(
+            malformed(serde_json::json!({"secret": {"provider": "env", "port": 1}}))
+                .contains("'port' is not a string")
+        );
+    }
+
+    #[test]
+    fn test_env_provider() {
+        std::env::set_var("DJANGO_RS_TEST_SECRET_ENV", "from-env");
+        let reference = SecretRef::new("env").param("name", "DJANGO_RS_TEST_SECRET_ENV");
+        assert_eq!(EnvProvider.resolve(&reference).unwrap(), "from-env");
+
+        let missing = SecretRef::new("env").param("name", "DJANGO_RS_TEST_SECRET_UNSET");
+        assert!(EnvProvider.resolve(&missing).is_err());
+        assert!(EnvProvider.resolve(&SecretRef::new("env")).is_err());
+    }
+
+    #[test]
+    fn test_file_provider_strips_trailing_newline() {
+        let dir = std::env::temp_dir().join(format!("django_rs_secrets_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("db_pass"), "hunter2\n").unwrap();
+
+        let provider = FileProvider::new().base_dir(&dir);
+        let reference = SecretRef::new("file").param("path", "db_pass");
+        assert_eq!(provider.resolve(&reference).unwrap(), "hunter2");
+        let missing = SecretRef::new("file").param("path", "nope");
+        assert!(provider.resolve(&missing).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_json_replaces_nested_references() {
+        let provider = Arc::new(CountingProvider::default());
+        *provider.value.lock().unwrap() = "pw".to_string();
+        let resolver = SecretResolver::empty().with_provider("test", provider.clone());
+
+        let settings = serde_json::json!({
+            "debug": true,
+            "databases": {"default": {"password": {"secret": {"provider": "test"}}, "port": 5432}},
+            "hosts": [{"secret": {"provider": "test"}}],
+        });
+        let values = resolver.resolve_json(settings).unwrap();
+        assert_eq!(values["databases"]["default"]["password"], "pw");
+        assert_eq!(values["databases"]["default"]["port"], 5432);
+        assert_eq!(values["hosts"][0], "pw");
+        // The second reference is answered from the cache.
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        let err = resolver
+            .resolve_json(serde_json::json!({"password": {"secret": {"provider": "nope"}}}))
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown secrets provider 'nope'"));
+    }
+
+    #[test]
+    fn test_refresh_runs_hooks_for_changed_secrets() {
+        let provider = Arc::new(CountingProvider::default());
+        *provider.value.lock().unwrap() = "old".to_string();
+        let resolver = SecretResolver::empty().with_provider("test", provider.clone());
+        let reference = SecretRef::new("test").param("name", "db");
+        assert_eq!(resolver.resolve(&reference).unwrap(), "old");
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_hook = seen.clone();
+        resolver.on_change(move |reference, value| {
+            seen_in_hook
+                .lock()
+                .unwrap()
+                .push(format!("{reference} -> {value}"));
+        });
+
+        assert!(resolver.refresh().unwrap().is_empty());
+        *provider.value.lock().unwrap() = "new".to_string();
+        assert_eq!(resolver.refresh().unwrap(), vec![reference.clone()]);
+        assert_eq!(resolver.resolve(&reference).unwrap(), "new");
+        assert_eq!(*seen.lock().unwrap(), vec!["test name=db -> new"]);
+    }
+}
@@ -10,6 +10,12 @@ use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 
+use crate::flags::Flag;
+
+/// The environment variable holding the shard number the `test` command
+/// assigned to a test process.
+pub const TEST_SHARD_ENV: &str = "DJANGO_RS_TEST_SHARD";
+
 /// Database connection configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseSettings {
@@ -26,9 +32,82 @@ pub struct DatabaseSettings {
     /// The database port.
     pub port: u16,
     /// Additional engine-specific options.
+    ///
+    /// `PostgreSQL` honours `search_path`, a comma-separated list of schemas
+    /// set on every connection for this alias. `pool_mode` declares that
+    /// connections go through an external pooler such as `PgBouncer` (see
+    /// [`PoolMode`]).
     pub options: HashMap<String, String>,
 }
 
+impl DatabaseSettings {
+    /// Returns the declared `pool_mode` option, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns the option's value if it isn't a known pool mode.
+    pub fn pool_mode(&self) -> Result<Option<PoolMode>, String> {
+        match self.options.get("pool_mode").map(|v| v.trim()) {
+            None | Some("") => Ok(None),
+            Some(name) => PoolMode::from_name(name)
+                .map(Some)
+                .ok_or_else(|| name.to_string()),
+        }
+    }
+}
+
+/// How an external connection pooler such as `PgBouncer` hands out server
+/// connections, declared per alias with `options["pool_mode"]`.
+///
+/// Under [`Transaction`](Self::Transaction) and
+/// [`Statement`](Self::Statement) pooling, consecutive transactions may run
+/// on different server connections, so anything kept in the session (`SET`
+/// parameters, prepared statements, cursors, `LISTEN`) is lost or leaks to
+/// other clients. Backends then run in a compatibility mode that keeps no
+/// session state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolMode {
+    /// A client keeps its server connection until it disconnects.
+    Session,
+    /// A client holds a server connection for one transaction.
+    Transaction,
+    /// A client holds a server connection for one statement; multi-statement
+    /// transactions are refused.
+    Statement,
+}
+
+impl PoolMode {
+    /// Option values that set per-connection session state, which doesn't
+    /// survive transaction or statement pooling.
+    pub const SESSION_OPTIONS: [&'static str; 4] =
+        ["search_path", "time_zone", "sql_mode", "init_command"];
+
+    /// Parses a mode from its [`as_str`](Self::as_str) form.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "session" => Some(Self::Session),
+            "transaction" => Some(Self::Transaction),
+            "statement" => Some(Self::Statement),
+            _ => None,
+        }
+    }
+
+    /// Returns the mode's name, as used by `PgBouncer`'s `pool_mode`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Session => "session",
+            Self::Transaction => "transaction",
+            Self::Statement => "statement",
+        }
+    }
+
+    /// Returns `true` if session state can't be relied on.
+    pub const fn shares_connections(self) -> bool {
+        !matches!(self, Self::Session)
+    }
+}
+
 impl Default for DatabaseSettings {
     fn default() -> Self {
         Self {
@@ -91,6 +170,86 @@ impl Default for CacheSettings {
     }
 }
 
+/// OpenTelemetry tracing configuration.
+///
+/// Only used when the `otel` feature is enabled; see
+/// [`setup_tracing`](crate::otel::setup_tracing).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtelSettings {
+    /// Whether spans are exported.
+    pub enabled: bool,
+    /// The OTLP/HTTP traces endpoint.
+    pub endpoint: String,
+    /// The `service.name` resource attribute.
+    pub service_name: String,
+    /// The fraction of new traces to sample, from 0.0 to 1.0. Requests
+    /// carrying a `traceparent` header follow the caller's decision.
+    pub sample_ratio: f64,
+    /// Extra headers sent with every export request (e.g. API keys).
+    pub headers: HashMap<String, String>,
+    /// Export timeout in seconds.
+    pub timeout: u64,
+}
+
+impl Default for OtelSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318/v1/traces".to_string(),
+            service_name: "django-rs".to_string(),
+            sample_ratio: 1.0,
+            headers: HashMap::new(),
+            timeout: 10,
+        }
+    }
+}
+
+/// An `OAuth2` or `OpenID Connect` provider for social login.
+///
+/// Providers are keyed by the name used in their login URLs; see
+/// `django_rs_auth::social`. Empty endpoints fall back to the defaults for
+/// the provider's `kind`, or to `OpenID Connect` discovery from `issuer`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SocialProviderSettings {
+    /// The provider type: `"google"`, `"github"` or `"oidc"`.
+    pub kind: String,
+    /// The `OAuth2` client ID.
+    pub client_id: String,
+    /// The `OAuth2` client secret.
+    pub client_secret: String,
+    /// The issuer URL, required for generic `OpenID Connect` providers.
+    pub issuer: String,
+    /// Overrides the authorization endpoint.
+    pub authorization_endpoint: String,
+    /// Overrides the token endpoint.
+    pub token_endpoint: String,
+    /// Overrides the user info endpoint.
+    pub userinfo_endpoint: String,
+    /// The scopes to request; empty requests the provider's defaults.
+    pub scopes: Vec<String>,
+}
+
+/// A key for encrypted model fields; see `django_rs_db::encryption`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldEncryptionKey {
+    /// The key's name, stored with every value it encrypts. Must not
+    /// contain `$`.
+    pub id: String,
+    /// The 32-byte AES-256 key, base64-encoded.
+    pub key: String,
+}
+
+impl std::fmt::Debug for FieldEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FieldEncryptionKey")
+            .field("id", &self.id)
+            .field("key", &"********")
+            .finish()
+    }
+}
+
 /// The complete set of framework settings.
 ///
 /// This mirrors Django's `settings` module with sensible defaults. Use
@@ -106,6 +265,7 @@ impl Default for CacheSettings {
 /// assert_eq!(settings.language_code, "en-us");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Settings {
     // ── Core ─────────────────────────────────────────────────────────
     /// Whether debug mode is enabled.
@@ -152,6 +312,8 @@ pub struct Settings {
     pub authentication_backends: Vec<String>,
     /// Password hasher dotted paths, in order of preference.
     pub password_hashers: Vec<String>,
+    /// Social login providers, keyed by name (e.g. "google").
+    pub social_auth_providers: HashMap<String, SocialProviderSettings>,
 
     // ── Security ─────────────────────────────────────────────────────
     /// The name of the CSRF cookie.
@@ -166,10 +328,28 @@ pub struct Settings {
     pub session_cookie_name: String,
     /// The session cookie max age in seconds.
     pub session_cookie_age: u64,
+    /// Keys for encrypted model fields, newest first. The first key
+    /// encrypts new values; the others only decrypt values written before
+    /// it was rotated in.
+    pub field_encryption_keys: Vec<FieldEncryptionKey>,
+    /// The key for the blind indexes that make encrypted fields searchable.
+    /// Required when a model declares a `hash_field`.
+    pub field_hash_key: String,
+    /// Whether encrypted fields read values that are not encrypted as
+    /// plaintext instead of rejecting them. Turn it on only while migrating
+    /// a plain text column to an encrypted field.
+    pub field_encryption_accept_plaintext: bool,
 
     // ── Internationalization ─────────────────────────────────────────
     /// The language code (e.g. "en-us").
     pub language_code: String,
+    /// The languages users can choose, as `(code, name)` pairs.
+    pub languages: Vec<(String, String)>,
+    /// The name of the cookie that stores the chosen language.
+    pub language_cookie_name: String,
+    /// The language cookie max age in seconds. `None` means a browser-session
+    /// cookie.
+    pub language_cookie_age: Option<u64>,
     /// The default time zone (e.g. "UTC").
     pub time_zone: String,
     /// Whether to use timezone-aware datetimes.
@@ -186,11 +366,18 @@ pub struct Settings {
     // ── Logging ──────────────────────────────────────────────────────
     /// The log level (e.g. "info", "debug", "warn").
     pub log_level: String,
+    /// OpenTelemetry tracing configuration.
+    pub otel: OtelSettings,
 
     // ── Cache ────────────────────────────────────────────────────────
     /// Cache backend configurations, keyed by alias (e.g. "default").
     pub caches: HashMap<String, CacheSettings>,
 
+    // ── Feature flags ────────────────────────────────────────────────
+    /// Feature flags, keyed by name. Flags set at runtime through
+    /// [`FLAGS`](crate::flags::FLAGS) take precedence.
+    pub feature_flags: HashMap<String, Flag>,
+
     // ── Escape hatch ─────────────────────────────────────────────────
     /// Custom settings that don't fit into the above categories.
     pub extra: HashMap<String, serde_json::Value>,
@@ -242,6 +429,7 @@ impl Default for Settings {
                 "django_rs.auth.hashers.Argon2PasswordHasher".to_string(),
                 "django_rs.auth.hashers.BCryptPasswordHasher".to_string(),
             ],
+            social_auth_providers: HashMap::new(),
 
             // Security
             csrf_cookie_name: "csrftoken".to_string(),
@@ -250,9 +438,15 @@ impl Default for Settings {
             secure_hsts_seconds: 0,
             session_cookie_name: "sessionid".to_string(),
             session_cookie_age: 1_209_600, // 2 weeks
+            field_encryption_keys: Vec::new(),
+            field_hash_key: String::new(),
+            field_encryption_accept_plaintext: false,
 
             // Internationalization
             language_code: "en-us".to_string(),
+            languages: vec![("en".to_string(), "English".to_string())],
+            language_cookie_name: "django_language".to_string(),
+            language_cookie_age: None,
             time_zone: "UTC".to_string(),
             use_tz: true,
 
@@ -263,10 +457,14 @@ impl Default for Settings {
 
             // Logging
             log_level: "info".to_string(),
+            otel: OtelSettings::default(),
 
             // Cache
             caches,
 
+            // Feature flags
+            feature_flags: HashMap::new(),
+
             // Extra
             extra: HashMap::new(),
         }
@@ -10,6 +10,10 @@
 //! 2. Load from a TOML or JSON file (overriding defaults).
 //! 3. Apply environment variable overrides (highest priority).
 //!
+//! String values in the file may be secret references such as
+//! `{ secret = { provider = "env", name = "DB_PASS" } }`; they are resolved
+//! while loading (see [`crate::secrets`]).
+//!
 //! ## Environment Variable Mapping
 //!
 //! Environment variables are mapped from `DJANGO_<SETTING_NAME>` format:
@@ -43,24 +47,44 @@
 use std::path::Path;
 
 use crate::error::DjangoError;
+use crate::secrets::SecretResolver;
 use crate::settings::Settings;
 
 /// Loads settings from a TOML string.
 ///
 /// The TOML is deserialized directly into a [`Settings`] struct. Any fields
-/// not present in the TOML will use the default values.
+/// not present in the TOML will use the default values. Secret references
+/// are resolved with the built-in providers (see [`crate::secrets`]).
 ///
 /// # Errors
 ///
-/// Returns an error if the TOML is malformed or cannot be deserialized.
+/// Returns an error if the TOML is malformed, cannot be deserialized, or
+/// references a secret that cannot be resolved.
 pub fn from_toml_str(toml_str: &str) -> Result<Settings, DjangoError> {
+    from_toml_str_with_secrets(toml_str, &SecretResolver::new())
+}
+
+/// Loads settings from a TOML string, resolving secret references with
+/// `resolver`.
+///
+/// Keep the resolver to [`refresh`](SecretResolver::refresh) the secrets
+/// later.
+///
+/// # Errors
+///
+/// Returns an error if the TOML is malformed, cannot be deserialized, or
+/// references a secret that cannot be resolved.
+pub fn from_toml_str_with_secrets(
+    toml_str: &str,
+    resolver: &SecretResolver,
+) -> Result<Settings, DjangoError> {
     // We use a two-step approach: deserialize the TOML into a serde_json::Value,
     // then merge it with the default settings. This lets us keep defaults for
     // any settings not specified in the TOML.
     let toml_value: toml::Value = toml::from_str(toml_str)
         .map_err(|e| DjangoError::ConfigurationError(format!("Failed to parse TOML: {e}")))?;
 
-    let json_value = toml_to_json(toml_value);
+    let json_value = resolver.resolve_json(toml_to_json(toml_value))?;
     let default_json = serde_json::to_value(Settings::default()).map_err(|e| {
         DjangoError::ConfigurationError(format!("Failed to serialize default settings: {e}"))
     })?;
@@ -99,12 +123,31 @@ pub fn from_toml_file_with_env(path: impl AsRef<Path>) -> Result<Settings, Djang
 
 /// Loads settings from a JSON string.
 ///
+/// Secret references are resolved with the built-in providers (see
+/// [`crate::secrets`]).
+///
 /// # Errors
 ///
-/// Returns an error if the JSON is malformed or cannot be deserialized.
+/// Returns an error if the JSON is malformed, cannot be deserialized, or
+/// references a secret that cannot be resolved.
 pub fn from_json_str(json_str: &str) -> Result<Settings, DjangoError> {
+    from_json_str_with_secrets(json_str, &SecretResolver::new())
+}
+
+/// Loads settings from a JSON string, resolving secret references with
+/// `resolver`.
+///
+/// # Errors
+///
+/// Returns an error if the JSON is malformed, cannot be deserialized, or
+/// references a secret that cannot be resolved.
+pub fn from_json_str_with_secrets(
+    json_str: &str,
+    resolver: &SecretResolver,
+) -> Result<Settings, DjangoError> {
     let json_value: serde_json::Value = serde_json::from_str(json_str)
         .map_err(|e| DjangoError::ConfigurationError(format!("Failed to parse JSON: {e}")))?;
+    let json_value = resolver.resolve_json(json_value)?;
 
     let default_json = serde_json::to_value(Settings::default()).map_err(|e| {
         DjangoError::ConfigurationError(format!("Failed to serialize default settings: {e}"))
@@ -374,6 +417,25 @@ mod tests {
 
     // ── JSON loading ────────────────────────────────────────────────
 
+    #[test]
+    fn test_from_toml_str_resolves_secrets() {
+        std::env::set_var("DJANGO_RS_TEST_LOADER_DB_PASS", "pa55");
+        let toml = r#"
+secret_key = { secret = { provider = "env", name = "DJANGO_RS_TEST_LOADER_DB_PASS" } }
+
+[databases.default]
+engine = "django_rs.db.backends.postgresql"
+password = { secret = { provider = "env", name = "DJANGO_RS_TEST_LOADER_DB_PASS" } }
+"#;
+        let settings = from_toml_str(toml).unwrap();
+        assert_eq!(settings.secret_key, "pa55");
+        assert_eq!(settings.databases["default"].password, "pa55");
+
+        let err =
+            from_toml_str(r#"secret_key = { secret = { provider = "nowhere" } }"#).unwrap_err();
+        assert!(err.to_string().contains("Unknown secrets provider"));
+    }
+
     #[test]
     fn test_from_json_str_basic() {
         let json = r#"{
@@ -1,9 +1,13 @@
 //! String utility functions.
 //!
 //! These functions mirror common Django text utilities like `slugify`,
-//! `Truncator.chars`, `Truncator.words`, `capfirst`, and `strip_tags`.
+//! `Truncator.chars`, `Truncator.words`, `capfirst`, `strip_tags`,
+//! `get_text_list`, `wrap`, and `compress_string`.
 
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use regex::Regex;
+use std::io::Write;
 use std::sync::OnceLock;
 
 /// Converts a string to a URL-friendly slug.
@@ -124,6 +128,305 @@ pub fn strip_tags(s: &str) -> String {
     tag_re.replace_all(s, "").into_owned()
 }
 
+/// Truncates HTML content to a maximum number of visible characters,
+/// preserving and properly closing any open HTML tags.
+///
+/// Tags don't count toward the limit. This backs the `truncatechars_html`
+/// template filter.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::truncate_html_chars;
+///
+/// assert_eq!(
+///     truncate_html_chars("<p>Hello <b>world</b></p>", 8),
+///     "<p>Hello <b>wo ...</b></p>"
+/// );
+/// ```
+pub fn truncate_html_chars(html: &str, max_chars: usize) -> String {
+    let mut result = String::new();
+    let mut visible_count = 0;
+    let mut open_tags: Vec<String> = Vec::new();
+    let mut chars = html.chars().peekable();
+    let ellipsis = " ...";
+
+    while let Some(c) = chars.next() {
+        if visible_count >= max_chars {
+            break;
+        }
+
+        if c == '<' {
+            // Read the entire tag
+            let mut tag = String::from('<');
+            for tc in chars.by_ref() {
+                tag.push(tc);
+                if tc == '>' {
+                    break;
+                }
+            }
+
+            track_html_tag(&tag, &mut open_tags);
+            result.push_str(&tag);
+        } else {
+            result.push(c);
+            visible_count += 1;
+        }
+    }
+
+    if visible_count >= max_chars && chars.peek().is_some() {
+        result.push_str(ellipsis);
+    }
+
+    close_html_tags(&mut result, &open_tags);
+    result
+}
+
+/// Truncates HTML content to a maximum number of words,
+/// preserving and properly closing any open HTML tags.
+///
+/// This mirrors Django's `Truncator.words(n, html=True)` and backs the
+/// `truncatewords_html` template filter.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::truncate_html_words;
+///
+/// assert_eq!(
+///     truncate_html_words("<p>one <em>two three</em> four</p>", 2),
+///     "<p>one <em>two ...</em></p>"
+/// );
+/// ```
+pub fn truncate_html_words(html: &str, max_words: usize) -> String {
+    let mut result = String::new();
+    let mut word_count = 0;
+    let mut open_tags: Vec<String> = Vec::new();
+    let mut chars = html.chars().peekable();
+    let mut in_word = false;
+    let ellipsis = " ...";
+    let mut truncated = false;
+
+    while let Some(c) = chars.next() {
+        if c == '<' {
+            // Read the entire tag
+            let mut tag = String::from('<');
+            for tc in chars.by_ref() {
+                tag.push(tc);
+                if tc == '>' {
+                    break;
+                }
+            }
+
+            track_html_tag(&tag, &mut open_tags);
+            result.push_str(&tag);
+            in_word = false;
+        } else if c.is_whitespace() {
+            if in_word {
+                in_word = false;
+            }
+            // Only add whitespace if we haven't hit the limit
+            if word_count < max_words {
+                result.push(c);
+            }
+        } else {
+            if !in_word {
+                word_count += 1;
+                in_word = true;
+                if word_count > max_words {
+                    // We've started a new word past the limit; stop
+                    truncated = true;
+                    break;
+                }
+            }
+            result.push(c);
+        }
+    }
+
+    // Trim trailing whitespace, then add the ellipsis if content was cut
+    let mut final_result = result.trim_end().to_string();
+    if truncated || chars.peek().is_some() {
+        final_result.push_str(ellipsis);
+    }
+    close_html_tags(&mut final_result, &open_tags);
+    final_result
+}
+
+/// Updates the stack of open tags for a raw tag such as `<b class="x">`
+/// or `</b>`. Self-closing and void elements leave the stack unchanged.
+fn track_html_tag(tag: &str, open_tags: &mut Vec<String>) {
+    let tag_inner = tag.trim_start_matches('<').trim_end_matches('>').trim();
+    if let Some(closing) = tag_inner.strip_prefix('/') {
+        let tag_name = closing.split_whitespace().next().unwrap_or("");
+        if let Some(pos) = open_tags.iter().rposition(|t| t == tag_name) {
+            open_tags.remove(pos);
+        }
+        return;
+    }
+
+    let tag_name = tag_inner.split_whitespace().next().unwrap_or("");
+    if !tag_inner.ends_with('/') && !tag_name.is_empty() && !is_void_element(tag_name) {
+        open_tags.push(tag_name.to_string());
+    }
+}
+
+/// Appends closing tags for every open tag, innermost first.
+fn close_html_tags(result: &mut String, open_tags: &[String]) {
+    for tag in open_tags.iter().rev() {
+        result.push_str("</");
+        result.push_str(tag);
+        result.push('>');
+    }
+}
+
+/// Returns true if the given tag name is an HTML void element (self-closing).
+fn is_void_element(tag: &str) -> bool {
+    matches!(
+        tag.to_lowercase().as_str(),
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "param"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+/// Joins a list of items into a human-readable string with a final
+/// conjunction, e.g. `"a, b and c"`.
+///
+/// This mirrors Django's `django.utils.text.get_text_list`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::get_text_list;
+///
+/// assert_eq!(get_text_list(&["a", "b", "c"], "and"), "a, b and c");
+/// assert_eq!(get_text_list(&["a", "b"], "or"), "a or b");
+/// assert_eq!(get_text_list(&["a"], "or"), "a");
+/// assert_eq!(get_text_list::<&str>(&[], "or"), "");
+/// ```
+pub fn get_text_list<S: AsRef<str>>(items: &[S], last_word: &str) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.as_ref().to_string(),
+        [init @ .., last] => {
+            let head: Vec<&str> = init.iter().map(AsRef::as_ref).collect();
+            format!("{} {last_word} {}", head.join(", "), last.as_ref())
+        }
+    }
+}
+
+/// Word-wraps text so that no line exceeds `width` characters.
+///
+/// Existing line breaks are preserved, and words longer than `width` are
+/// left on a line of their own rather than split. This mirrors Django's
+/// `django.utils.text.wrap`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::wrap;
+///
+/// assert_eq!(wrap("the quick brown fox", 10), "the quick\nbrown fox");
+/// assert_eq!(wrap("one\ntwo three", 5), "one\ntwo\nthree");
+/// ```
+pub fn wrap(text: &str, width: usize) -> String {
+    let mut lines = Vec::new();
+    for source_line in text.lines() {
+        let mut line = String::new();
+        let mut line_len = 0;
+        for word in source_line.split_whitespace() {
+            let word_len = word.chars().count();
+            if line_len > 0 && line_len + 1 + word_len > width {
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            if line_len > 0 {
+                line.push(' ');
+                line_len += 1;
+            }
+            line.push_str(word);
+            line_len += word_len;
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Normalizes `\r\n` and lone `\r` line endings to `\n`.
+///
+/// This mirrors Django's `django.utils.text.normalize_newlines`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::normalize_newlines;
+///
+/// assert_eq!(normalize_newlines("a\r\nb\rc\nd"), "a\nb\nc\nd");
+/// ```
+pub fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Converts the letters in a phone number to their keypad digits.
+///
+/// This mirrors Django's `django.utils.text.phone2numeric`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::phone2numeric;
+///
+/// assert_eq!(phone2numeric("1-800-COLLECT"), "1-800-2655328");
+/// ```
+pub fn phone2numeric(phone: &str) -> String {
+    phone
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'A' | 'B' | 'C' => '2',
+            'D' | 'E' | 'F' => '3',
+            'G' | 'H' | 'I' => '4',
+            'J' | 'K' | 'L' => '5',
+            'M' | 'N' | 'O' => '6',
+            'P' | 'Q' | 'R' | 'S' => '7',
+            'T' | 'U' | 'V' => '8',
+            'W' | 'X' | 'Y' | 'Z' => '9',
+            other => other,
+        })
+        .collect()
+}
+
+/// Gzip-compresses a byte string.
+///
+/// This mirrors Django's `django.utils.text.compress_string`, used by
+/// `GZipMiddleware`.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::utils::text::compress_string;
+///
+/// let compressed = compress_string(&b"a".repeat(1000));
+/// assert!(compressed.len() < 100);
+/// assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+/// ```
+pub fn compress_string(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec cannot fail
+    encoder.write_all(data).expect("in-memory write");
+    encoder.finish().expect("in-memory write")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,4 +563,76 @@ mod tests {
             "link"
         );
     }
+
+    // ── truncate_html ────────────────────────────────────────────────
+
+    #[test]
+    fn test_truncate_html_words_closes_tags() {
+        assert_eq!(
+            truncate_html_words("<p>one <b>two</b> three</p>", 2),
+            "<p>one <b>two</b> ...</p>"
+        );
+    }
+
+    #[test]
+    fn test_truncate_html_words_short() {
+        assert_eq!(truncate_html_words("<p>one two</p>", 5), "<p>one two</p>");
+    }
+
+    #[test]
+    fn test_truncate_html_chars_void_elements() {
+        assert_eq!(truncate_html_chars("ab<br>cdef", 3), "ab<br>c ...");
+    }
+
+    // ── get_text_list ────────────────────────────────────────────────
+
+    #[test]
+    fn test_get_text_list() {
+        assert_eq!(get_text_list(&["a", "b", "c", "d"], "or"), "a, b, c or d");
+        let owned = vec!["x".to_string(), "y".to_string()];
+        assert_eq!(get_text_list(&owned, "and"), "x and y");
+    }
+
+    // ── wrap ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_wrap_long_word() {
+        assert_eq!(
+            wrap("a supercalifragilistic b", 5),
+            "a\nsupercalifragilistic\nb"
+        );
+    }
+
+    #[test]
+    fn test_wrap_preserves_blank_lines() {
+        assert_eq!(wrap("one two\n\nthree", 20), "one two\n\nthree");
+    }
+
+    // ── normalize_newlines ───────────────────────────────────────────
+
+    #[test]
+    fn test_normalize_newlines() {
+        assert_eq!(normalize_newlines("a\r\n\r\nb"), "a\n\nb");
+        assert_eq!(normalize_newlines("plain"), "plain");
+    }
+
+    // ── phone2numeric / compress_string ──────────────────────────────
+
+    #[test]
+    fn test_phone2numeric_lowercase() {
+        assert_eq!(phone2numeric("abc def"), "222 333");
+    }
+
+    #[test]
+    fn test_compress_string_roundtrip() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let data = b"hello hello hello hello";
+        let mut decoded = Vec::new();
+        GzDecoder::new(compress_string(data).as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
 }
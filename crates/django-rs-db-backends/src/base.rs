@@ -4,11 +4,36 @@
 //! implementations must satisfy, along with the [`Transaction`] wrapper
 //! for managing database transactions.
 
+pub use django_rs_core::settings::PoolMode;
 use django_rs_core::DjangoError;
 use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
 use django_rs_db::value::Value;
 use django_rs_db::Row;
 
+/// Creates the span a backend runs a query in.
+///
+/// The statement is recorded with literals stripped (see
+/// [`sanitize_sql`](django_rs_core::logging::sanitize_sql)), and only when
+/// the span is enabled.
+#[cfg_attr(
+    not(any(feature = "postgres", feature = "sqlite", feature = "mysql")),
+    allow(dead_code)
+)]
+pub(crate) fn query_span(system: &'static str, sql: &str) -> tracing::Span {
+    let span = tracing::info_span!(
+        "db.query",
+        db.system = system,
+        db.statement = tracing::field::Empty
+    );
+    if !span.is_disabled() {
+        span.record(
+            "db.statement",
+            django_rs_core::logging::sanitize_sql(sql).as_str(),
+        );
+    }
+    span
+}
+
 /// A database transaction wrapper.
 ///
 /// Transactions are obtained from [`DatabaseBackend::begin_transaction`] and
@@ -79,6 +104,81 @@ pub trait DatabaseBackend: Send + Sync {
 
     /// Returns a SQL compiler configured for this backend's dialect.
     fn compiler(&self) -> SqlCompiler;
+
+    /// Returns a handle that runs every statement on one connection.
+    ///
+    /// Pooled backends may take a different connection for each statement,
+    /// so session state such as an open transaction or an advisory lock
+    /// would not carry over from one call to the next. Work that relies on
+    /// it goes through the pinned handle, which keeps its connection until
+    /// dropped.
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError>;
+}
+
+/// A backend already bound to one connection, handed out again by its own
+/// [`DatabaseBackend::pin`].
+#[cfg_attr(
+    not(any(feature = "postgres", feature = "sqlite", feature = "mysql")),
+    allow(dead_code)
+)]
+pub(crate) struct Pinned<'a>(pub(crate) &'a dyn DatabaseBackend);
+
+#[async_trait::async_trait]
+impl DatabaseBackend for Pinned<'_> {
+    fn vendor(&self) -> &str {
+        self.0.vendor()
+    }
+
+    fn backend_type(&self) -> DatabaseBackendType {
+        self.0.backend_type()
+    }
+
+    async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
+        self.0.execute(sql, params).await
+    }
+
+    async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
+        self.0.query(sql, params).await
+    }
+
+    async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
+        self.0.query_one(sql, params).await
+    }
+
+    async fn begin_transaction(&self) -> Result<Transaction, DjangoError> {
+        self.0.begin_transaction().await
+    }
+
+    async fn commit(&self) -> Result<(), DjangoError> {
+        self.0.commit().await
+    }
+
+    async fn rollback(&self) -> Result<(), DjangoError> {
+        self.0.rollback().await
+    }
+
+    fn compiler(&self) -> SqlCompiler {
+        self.0.compiler()
+    }
+
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+        Ok(Box::new(Pinned(self.0)))
+    }
+}
+
+/// Returns the only row of a result set, for
+/// [`DatabaseBackend::query_one`].
+#[cfg_attr(not(any(feature = "postgres", feature = "mysql")), allow(dead_code))]
+pub(crate) fn single_row(rows: Vec<Row>) -> Result<Row, DjangoError> {
+    let count = rows.len();
+    let mut rows = rows.into_iter();
+    match (rows.next(), count) {
+        (None, _) => Err(DjangoError::DoesNotExist("No rows returned".to_string())),
+        (Some(row), 1) => Ok(row),
+        _ => Err(DjangoError::MultipleObjectsReturned(format!(
+            "Expected 1 row, got {count}"
+        ))),
+    }
 }
 
 /// Configuration for connecting to a database.
@@ -101,6 +201,8 @@ pub struct DatabaseConfig {
     pub password: Option<String>,
     /// Additional connection options.
     pub options: std::collections::HashMap<String, String>,
+    /// Statements run on every new connection, after the session options.
+    pub init_commands: Vec<String>,
 }
 
 impl DatabaseConfig {
@@ -114,6 +216,7 @@ impl DatabaseConfig {
             user: None,
             password: None,
             options: std::collections::HashMap::new(),
+            init_commands: Vec::new(),
         }
     }
 
@@ -127,6 +230,7 @@ impl DatabaseConfig {
             user: None,
             password: None,
             options: std::collections::HashMap::new(),
+            init_commands: Vec::new(),
         }
     }
 
@@ -146,6 +250,7 @@ impl DatabaseConfig {
             user: Some(user.into()),
             password: Some(password.into()),
             options: std::collections::HashMap::new(),
+            init_commands: Vec::new(),
         }
     }
 
@@ -165,7 +270,193 @@ impl DatabaseConfig {
             user: Some(user.into()),
             password: Some(password.into()),
             options: std::collections::HashMap::new(),
+            init_commands: Vec::new(),
+        }
+    }
+
+    /// Sets the PostgreSQL `search_path` for connections to this database.
+    ///
+    /// Stored in `options["search_path"]` as a comma-separated list, so it
+    /// can also be given per alias in the `DATABASES` settings.
+    #[must_use]
+    pub fn with_search_path<I, S>(mut self, schemas: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let schemas: Vec<String> = schemas.into_iter().map(Into::into).collect();
+        self.options
+            .insert("search_path".to_string(), schemas.join(","));
+        self
+    }
+
+    /// Returns the configured `search_path` schemas, in order.
+    ///
+    /// Empty when no `search_path` option is set.
+    pub fn search_path(&self) -> Vec<String> {
+        self.options
+            .get("search_path")
+            .map(|path| {
+                path.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets the session time zone applied to every new connection.
+    ///
+    /// Stored in `options["time_zone"]`. PostgreSQL runs `SET TIME ZONE`,
+    /// MySQL sets `time_zone`; SQLite has no session time zone and ignores it.
+    #[must_use]
+    pub fn with_time_zone(mut self, tz: impl Into<String>) -> Self {
+        self.options.insert("time_zone".to_string(), tz.into());
+        self
+    }
+
+    /// Sets the MySQL `sql_mode` for every new connection, e.g.
+    /// `"STRICT_ALL_TABLES"`.
+    ///
+    /// Stored in `options["sql_mode"]`; ignored by other backends.
+    #[must_use]
+    pub fn with_sql_mode(mut self, mode: impl Into<String>) -> Self {
+        self.options.insert("sql_mode".to_string(), mode.into());
+        self
+    }
+
+    /// Adds a statement to run on every new connection, after the time zone
+    /// and `sql_mode` have been applied.
+    ///
+    /// Call it once per statement; each is sent to the database as given.
+    #[must_use]
+    pub fn with_init_command(mut self, sql: impl Into<String>) -> Self {
+        self.init_commands.push(sql.into());
+        self
+    }
+
+    /// Returns the statements each backend runs when it opens a connection,
+    /// in execution order.
+    ///
+    /// The PostgreSQL `search_path` is not included: it is passed as a
+    /// startup option instead (see [`search_path`](Self::search_path)).
+    pub fn session_statements(&self) -> Vec<String> {
+        let mut statements = Vec::new();
+        if let Some(tz) = self.option("time_zone") {
+            match self.backend {
+                DatabaseBackendType::PostgreSQL => {
+                    statements.push(format!("SET TIME ZONE {}", quote_literal(tz)));
+                }
+                DatabaseBackendType::MySQL => {
+                    statements.push(format!("SET time_zone = {}", quote_literal(tz)));
+                }
+                DatabaseBackendType::SQLite => {}
+            }
         }
+        if let Some(mode) = self.option("sql_mode") {
+            if self.backend == DatabaseBackendType::MySQL {
+                statements.push(format!("SET SESSION sql_mode = {}", quote_literal(mode)));
+            }
+        }
+        // `options["init_command"]` comes from the `DATABASES` settings and is
+        // sent whole, like the statements of `with_init_command`.
+        statements.extend(self.option("init_command").map(String::from));
+        statements.extend(self.init_commands.iter().cloned());
+        statements
+    }
+
+    /// Declares that connections go through an external pooler such as
+    /// PgBouncer running in the given mode.
+    ///
+    /// Stored in `options["pool_mode"]`. Under transaction or statement
+    /// pooling the PostgreSQL backend keeps no session state: it refuses
+    /// session options and sends every query as an unnamed statement.
+    #[must_use]
+    pub fn with_pool_mode(mut self, mode: PoolMode) -> Self {
+        self.options
+            .insert("pool_mode".to_string(), mode.as_str().to_string());
+        self
+    }
+
+    /// Returns the declared pool mode; `None` when none is set or the value
+    /// is not a known mode.
+    pub fn pool_mode(&self) -> Option<PoolMode> {
+        self.option("pool_mode").and_then(PoolMode::from_name)
+    }
+
+    /// Returns `true` if a pooler may run consecutive transactions on
+    /// different server connections, so session state can't be used.
+    pub fn shares_connections(&self) -> bool {
+        self.pool_mode().is_some_and(PoolMode::shares_connections)
+    }
+
+    /// Returns the set options that configure session state, which
+    /// transaction and statement pooling don't preserve.
+    pub fn session_options(&self) -> Vec<&'static str> {
+        PoolMode::SESSION_OPTIONS
+            .into_iter()
+            .filter(|key| match *key {
+                "init_command" => self.option(key).is_some() || !self.init_commands.is_empty(),
+                _ => self.option(key).is_some(),
+            })
+            .collect()
+    }
+
+    /// Returns a non-blank option value.
+    fn option(&self, key: &str) -> Option<&str> {
+        self.options
+            .get(key)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// Quotes `value` as a SQL string literal.
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Fires the `connection_created` signal for a freshly set-up connection.
+#[cfg_attr(
+    not(any(feature = "postgres", feature = "sqlite", feature = "mysql")),
+    allow(dead_code)
+)]
+pub(crate) fn connection_created(vendor: &str, database: &str) {
+    dispatch(
+        &django_rs_signals::SIGNALS.connection_created,
+        django_rs_signals::ConnectionCreated {
+            vendor: vendor.to_string(),
+            database: database.to_string(),
+        },
+    );
+}
+
+/// Dispatches a database notification through the custom signal named
+/// after its channel.
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+pub(crate) fn notification_received(channel: &str, payload: &str, process_id: i32) {
+    let notification: Box<dyn std::any::Any + Send + Sync> =
+        Box::new(django_rs_signals::DbNotification {
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+            process_id,
+        });
+    dispatch(
+        &django_rs_signals::SIGNALS.get_or_create_custom(channel),
+        notification,
+    );
+}
+
+/// Dispatches a payload through `signal`'s queue, if it has one. A payload
+/// a full queue hands back is delivered inline rather than lost.
+#[cfg_attr(
+    not(any(feature = "postgres", feature = "sqlite", feature = "mysql")),
+    allow(dead_code)
+)]
+fn dispatch<T: Send + 'static>(signal: &django_rs_signals::Signal<T>, payload: T) {
+    if let Err(full) = signal.dispatch(payload) {
+        signal.send(&full.into_inner());
     }
 }
 
@@ -218,4 +509,110 @@ mod tests {
         assert_eq!(cfg.backend, DatabaseBackendType::MySQL);
         assert_eq!(cfg.port, Some(3306));
     }
+
+    #[test]
+    fn test_database_config_search_path() {
+        let cfg = DatabaseConfig::postgres("mydb", "localhost", 5432, "user", "pass");
+        assert!(cfg.search_path().is_empty());
+
+        let cfg = cfg.with_search_path(["billing", "public"]);
+        assert_eq!(cfg.options["search_path"], "billing,public");
+        assert_eq!(cfg.search_path(), vec!["billing", "public"]);
+
+        let mut cfg = DatabaseConfig::sqlite_memory();
+        cfg.options
+            .insert("search_path".into(), " tenant_a , , public".into());
+        assert_eq!(cfg.search_path(), vec!["tenant_a", "public"]);
+    }
+
+    #[test]
+    fn test_database_config_session_statements() {
+        let cfg = DatabaseConfig::postgres("mydb", "localhost", 5432, "user", "pass")
+            .with_time_zone("Europe/Paris")
+            .with_sql_mode("STRICT_ALL_TABLES")
+            .with_init_command("SET statement_timeout = 5000")
+            .with_init_command("SET application_name = 'web; api'");
+        assert_eq!(
+            cfg.session_statements(),
+            vec![
+                "SET TIME ZONE 'Europe/Paris'",
+                "SET statement_timeout = 5000",
+                "SET application_name = 'web; api'"
+            ]
+        );
+
+        let cfg = DatabaseConfig::mysql("mydb", "localhost", 3306, "root", "secret")
+            .with_time_zone("+00:00")
+            .with_sql_mode("STRICT_ALL_TABLES");
+        assert_eq!(
+            cfg.session_statements(),
+            vec![
+                "SET time_zone = '+00:00'",
+                "SET SESSION sql_mode = 'STRICT_ALL_TABLES'"
+            ]
+        );
+
+        let mut cfg = DatabaseConfig::sqlite_memory()
+            .with_time_zone("UTC")
+            .with_init_command("PRAGMA cache_size=-2000");
+        cfg.options
+            .insert("init_command".into(), "PRAGMA temp_store=MEMORY".into());
+        assert_eq!(
+            cfg.session_statements(),
+            vec!["PRAGMA temp_store=MEMORY", "PRAGMA cache_size=-2000"]
+        );
+        assert!(DatabaseConfig::sqlite_memory()
+            .session_statements()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_database_config_pool_mode() {
+        let cfg = DatabaseConfig::postgres("app", "pgbouncer", 6432, "u", "p");
+        assert_eq!(cfg.pool_mode(), None);
+        assert!(!cfg.shares_connections());
+
+        let cfg = cfg
+            .with_pool_mode(PoolMode::Transaction)
+            .with_time_zone("UTC")
+            .with_search_path(["app"]);
+        assert_eq!(cfg.pool_mode(), Some(PoolMode::Transaction));
+        assert!(cfg.shares_connections());
+        assert_eq!(cfg.session_options(), vec!["search_path", "time_zone"]);
+        let cfg = cfg.with_init_command("SET work_mem = '64MB'");
+        assert_eq!(
+            cfg.session_options(),
+            vec!["search_path", "time_zone", "init_command"]
+        );
+
+        let cfg = cfg.with_pool_mode(PoolMode::Session);
+        assert!(!cfg.shares_connections());
+    }
+
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!(quote_literal("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_notification_received_dispatches_custom_signal() {
+        use std::sync::{Arc, Mutex};
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        django_rs_signals::SIGNALS
+            .get_or_create_custom("test_base_notify")
+            .connect(
+                "collect",
+                Arc::new(move |payload| {
+                    if let Some(n) = payload.downcast_ref::<django_rs_signals::DbNotification>() {
+                        sink.lock().unwrap().push((n.payload.clone(), n.process_id));
+                    }
+                    None
+                }),
+            );
+
+        notification_received("test_base_notify", "user:7", 42);
+        assert_eq!(*received.lock().unwrap(), vec![("user:7".to_string(), 42)]);
+    }
 }
@@ -52,11 +52,11 @@ pub mod postgresql;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 
-pub use base::{DatabaseBackend, DatabaseConfig, Transaction};
+pub use base::{DatabaseBackend, DatabaseConfig, PoolMode, Transaction};
 pub use django_rs_db::DbExecutor;
 #[cfg(feature = "mysql")]
 pub use mysql::MySqlBackend;
 #[cfg(feature = "postgres")]
-pub use postgresql::PostgresBackend;
+pub use postgresql::{PgListener, PostgresBackend};
 #[cfg(feature = "sqlite")]
 pub use sqlite::SqliteBackend;
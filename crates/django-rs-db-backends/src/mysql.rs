@@ -4,11 +4,18 @@
 //! [`DatabaseBackend`](crate::base::DatabaseBackend) trait using `mysql_async`
 //! for fully asynchronous MySQL operations with connection pooling.
 
-use crate::base::{DatabaseBackend, DatabaseConfig, Transaction};
+use crate::base::{
+    connection_created, query_span, single_row, DatabaseBackend, DatabaseConfig, Pinned,
+    Transaction,
+};
 use django_rs_core::DjangoError;
+use django_rs_db::audit::annotate_sql;
 use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
 use django_rs_db::value::Value;
 use django_rs_db::Row;
+use std::collections::BTreeSet;
+use std::sync::{Mutex, PoisonError};
+use tracing::Instrument;
 
 /// A MySQL database backend.
 ///
@@ -16,12 +23,17 @@ use django_rs_db::Row;
 /// connection pooling.
 pub struct MySqlBackend {
     pool: mysql_async::Pool,
+    /// Server ids of the connections `connection_created` was fired for.
+    seen: Mutex<BTreeSet<u32>>,
 }
 
 impl MySqlBackend {
     /// Creates a new `MySqlBackend` from a `mysql_async::Pool`.
     pub const fn new(pool: mysql_async::Pool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            seen: Mutex::new(BTreeSet::new()),
+        }
     }
 
     /// Creates a new backend from a connection URL.
@@ -31,19 +43,73 @@ impl MySqlBackend {
     pub fn from_url(url: &str) -> Result<Self, DjangoError> {
         let opts = mysql_async::Opts::from_url(url)
             .map_err(|e| DjangoError::OperationalError(format!("Invalid MySQL URL: {e}")))?;
-        Ok(Self {
-            pool: mysql_async::Pool::new(opts),
-        })
+        Ok(Self::new(mysql_async::Pool::new(opts)))
     }
 
     /// Creates a new backend from a [`DatabaseConfig`].
+    ///
+    /// The config's [`session_statements`](DatabaseConfig::session_statements)
+    /// (`time_zone`, `sql_mode`, init commands) are run by the driver on
+    /// every new connection, one statement at a time.
     pub fn from_config(config: &DatabaseConfig) -> Result<Self, DjangoError> {
         let host = config.host.as_deref().unwrap_or("localhost");
         let port = config.port.unwrap_or(3306);
         let user = config.user.as_deref().unwrap_or("root");
         let password = config.password.as_deref().unwrap_or("");
         let url = format!("mysql://{user}:{password}@{host}:{port}/{}", config.name);
-        Self::from_url(&url)
+        let opts = mysql_async::Opts::from_url(&url)
+            .map_err(|e| DjangoError::OperationalError(format!("Invalid MySQL URL: {e}")))?;
+        let opts = mysql_async::OptsBuilder::from_opts(opts).init(config.session_statements());
+        Ok(Self::new(mysql_async::Pool::new(opts)))
+    }
+
+    /// Takes a connection from the pool.
+    ///
+    /// `mysql_async` has no post-create hook, so the `connection_created`
+    /// signal fires the first time the pool hands out a connection.
+    async fn conn(&self) -> Result<mysql_async::Conn, DjangoError> {
+        let conn =
+            self.pool.get_conn().await.map_err(|e| {
+                DjangoError::OperationalError(format!("MySQL connection error: {e}"))
+            })?;
+        let fresh = self
+            .seen
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(conn.id());
+        if fresh {
+            connection_created("mysql", conn.opts().db_name().unwrap_or_default());
+        }
+        Ok(conn)
+    }
+
+    /// Executes a statement on `conn`, returning the number of rows affected.
+    async fn execute_on(
+        conn: &mut mysql_async::Conn,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<u64, DjangoError> {
+        use mysql_async::prelude::Queryable;
+
+        conn.exec_drop(sql, Self::values_to_params(params))
+            .await
+            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
+        Ok(conn.affected_rows())
+    }
+
+    /// Runs a query on `conn`, returning its rows.
+    async fn query_on(
+        conn: &mut mysql_async::Conn,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<Vec<Row>, DjangoError> {
+        use mysql_async::prelude::Queryable;
+
+        let rows: Vec<mysql_async::Row> = conn
+            .exec(sql, Self::values_to_params(params))
+            .await
+            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
+        Ok(rows.into_iter().map(Self::convert_row).collect())
     }
 
     /// Converts ORM `Value` types to `mysql_async` parameter values.
@@ -124,38 +190,21 @@ impl DatabaseBackend for MySqlBackend {
     }
 
     async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
-        use mysql_async::prelude::Queryable;
-
-        let mut conn =
-            self.pool.get_conn().await.map_err(|e| {
-                DjangoError::OperationalError(format!("MySQL connection error: {e}"))
-            })?;
-
-        let mysql_params = Self::values_to_params(params);
-        let result = conn
-            .exec_drop(sql, mysql_params)
-            .await
-            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
-
-        let _ = result;
-        Ok(conn.affected_rows())
+        async move {
+            let mut conn = self.conn().await?;
+            Self::execute_on(&mut conn, sql, params).await
+        }
+        .instrument(query_span("mysql", sql))
+        .await
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
-        use mysql_async::prelude::Queryable;
-
-        let mut conn =
-            self.pool.get_conn().await.map_err(|e| {
-                DjangoError::OperationalError(format!("MySQL connection error: {e}"))
-            })?;
-
-        let mysql_params = Self::values_to_params(params);
-        let rows: Vec<mysql_async::Row> = conn
-            .exec(sql, mysql_params)
-            .await
-            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
-
-        Ok(rows.into_iter().map(Self::convert_row).collect())
+        async move {
+            let mut conn = self.conn().await?;
+            Self::query_on(&mut conn, sql, params).await
+        }
+        .instrument(query_span("mysql", sql))
+        .await
     }
 
     async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
@@ -188,6 +237,74 @@ impl DatabaseBackend for MySqlBackend {
     fn compiler(&self) -> SqlCompiler {
         SqlCompiler::new(DatabaseBackendType::MySQL)
     }
+
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+        Ok(Box::new(PinnedMySql {
+            conn: tokio::sync::Mutex::new(self.conn().await?),
+        }))
+    }
+}
+
+/// A pooled connection held for the lifetime of the handle, returned by
+/// [`MySqlBackend::pin`](DatabaseBackend::pin).
+struct PinnedMySql {
+    conn: tokio::sync::Mutex<mysql_async::Conn>,
+}
+
+#[async_trait::async_trait]
+impl DatabaseBackend for PinnedMySql {
+    fn vendor(&self) -> &str {
+        "mysql"
+    }
+
+    fn backend_type(&self) -> DatabaseBackendType {
+        DatabaseBackendType::MySQL
+    }
+
+    async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
+        async move {
+            let mut conn = self.conn.lock().await;
+            MySqlBackend::execute_on(&mut conn, sql, params).await
+        }
+        .instrument(query_span("mysql", sql))
+        .await
+    }
+
+    async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
+        async move {
+            let mut conn = self.conn.lock().await;
+            MySqlBackend::query_on(&mut conn, sql, params).await
+        }
+        .instrument(query_span("mysql", sql))
+        .await
+    }
+
+    async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
+        single_row(self.query(sql, params).await?)
+    }
+
+    async fn begin_transaction(&self) -> Result<Transaction, DjangoError> {
+        self.execute("BEGIN", &[]).await?;
+        Ok(Transaction::new(Box::new(())))
+    }
+
+    async fn commit(&self) -> Result<(), DjangoError> {
+        self.execute("COMMIT", &[]).await?;
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DjangoError> {
+        self.execute("ROLLBACK", &[]).await?;
+        Ok(())
+    }
+
+    fn compiler(&self) -> SqlCompiler {
+        SqlCompiler::new(DatabaseBackendType::MySQL)
+    }
+
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+        Ok(Box::new(Pinned(self)))
+    }
 }
 
 #[async_trait::async_trait]
@@ -197,32 +314,37 @@ impl django_rs_db::DbExecutor for MySqlBackend {
     }
 
     async fn execute_sql(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
-        self.execute(sql, params).await
+        self.execute(&annotate_sql(sql, DatabaseBackendType::MySQL), params)
+            .await
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
-        DatabaseBackend::query(self, sql, params).await
+        DatabaseBackend::query(self, &annotate_sql(sql, DatabaseBackendType::MySQL), params).await
     }
 
     async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
-        DatabaseBackend::query_one(self, sql, params).await
+        DatabaseBackend::query_one(self, &annotate_sql(sql, DatabaseBackendType::MySQL), params)
+            .await
     }
 
     async fn insert_returning_id(&self, sql: &str, params: &[Value]) -> Result<Value, DjangoError> {
-        use mysql_async::prelude::Queryable;
+        let sql = annotate_sql(sql, DatabaseBackendType::MySQL);
+        let span = query_span("mysql", &sql);
+        async move {
+            use mysql_async::prelude::Queryable;
 
-        let mut conn =
-            self.pool.get_conn().await.map_err(|e| {
-                DjangoError::OperationalError(format!("MySQL connection error: {e}"))
-            })?;
+            let mut conn = self.conn().await?;
 
-        let mysql_params = Self::values_to_params(params);
-        conn.exec_drop(sql, mysql_params)
-            .await
-            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
+            let mysql_params = Self::values_to_params(params);
+            conn.exec_drop(&sql, mysql_params)
+                .await
+                .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
 
-        let last_id = conn.last_insert_id().unwrap_or(0);
-        Ok(Value::Int(last_id as i64))
+            let last_id = conn.last_insert_id().unwrap_or(0);
+            Ok(Value::Int(last_id as i64))
+        }
+        .instrument(span)
+        .await
     }
 }
 
@@ -3,12 +3,45 @@
 //! This module provides the [`PostgresBackend`] which implements the
 //! [`DatabaseBackend`](crate::base::DatabaseBackend) trait using connection
 //! pooling via `deadpool-postgres`.
-
-use crate::base::{DatabaseBackend, DatabaseConfig, Transaction};
+//!
+//! [`PostgresBackend::listen`] opens a dedicated connection that subscribes
+//! to `NOTIFY` channels and dispatches each notification through the custom
+//! signal named after its channel, as a
+//! [`DbNotification`](django_rs_signals::DbNotification). Together with
+//! [`DbExecutor::notify`](django_rs_db::DbExecutor::notify), this gives
+//! processes sharing a database a cheap way to invalidate caches or push
+//! live updates to each other.
+//!
+//! # Transaction pooling
+//!
+//! Behind PgBouncer in transaction or statement mode, consecutive
+//! transactions may run on different server connections. Declare the mode
+//! with [`DatabaseConfig::with_pool_mode`] (or `options["pool_mode"]`) and the
+//! backend keeps no session state:
+//!
+//! - session options (`search_path`, `time_zone`, `init_command`) are refused
+//!   when the backend is created, since a `SET` would only reach one server
+//!   connection;
+//! - queries run as unnamed statements, parsed, bound and executed in one
+//!   round trip, instead of as named prepared statements;
+//! - [`listen`](PostgresBackend::listen) needs a direct connection to
+//!   PostgreSQL, given with
+//!   [`with_listen_config`](PostgresBackend::with_listen_config).
+//!
+//! Parameters are then sent with the type of their [`Value`] (`Int` as
+//! `bigint`, `String` as `text`, ...) rather than the type PostgreSQL infers,
+//! so raw SQL comparing them with columns of other types needs a cast.
+
+use crate::base::{
+    connection_created, notification_received, query_span, single_row, DatabaseBackend,
+    DatabaseConfig, Pinned, PoolMode, Transaction,
+};
 use django_rs_core::DjangoError;
+use django_rs_db::audit::annotate_sql;
 use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
 use django_rs_db::value::Value;
 use django_rs_db::Row;
+use tracing::Instrument;
 
 /// A PostgreSQL database backend.
 ///
@@ -17,20 +50,131 @@ use django_rs_db::Row;
 /// JSONB, and UUID.
 pub struct PostgresBackend {
     pool: deadpool_postgres::Pool,
+    /// Connection settings for dedicated (unpooled) listener connections.
+    listen_config: Option<tokio_postgres::Config>,
+    /// Whether an external pooler shares server connections between
+    /// transactions, so no session state may be used.
+    shares_connections: bool,
 }
 
 impl PostgresBackend {
     /// Creates a new `PostgresBackend` from a `deadpool-postgres` pool.
+    ///
+    /// Call [`with_listen_config`](Self::with_listen_config) to enable
+    /// [`listen`](Self::listen).
     pub const fn new(pool: deadpool_postgres::Pool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            listen_config: None,
+            shares_connections: false,
+        }
+    }
+
+    /// Sets whether an external pooler in transaction or statement mode
+    /// sits between the pool and PostgreSQL; see the
+    /// [module documentation](self#transaction-pooling).
+    ///
+    /// Backends created with [`from_config`](Self::from_config) take this
+    /// from the config's pool mode.
+    #[must_use]
+    pub const fn with_transaction_pooling(mut self, enabled: bool) -> Self {
+        self.shares_connections = enabled;
+        self
+    }
+
+    /// Returns `true` if the backend runs in transaction pooling
+    /// compatibility mode.
+    pub const fn transaction_pooling(&self) -> bool {
+        self.shares_connections
+    }
+
+    /// Sets the connection settings used by [`listen`](Self::listen).
+    ///
+    /// Backends created with [`from_config`](Self::from_config) already have
+    /// them.
+    #[must_use]
+    pub fn with_listen_config(mut self, config: tokio_postgres::Config) -> Self {
+        self.listen_config = Some(config);
+        self
+    }
+
+    /// Opens a dedicated connection listening on `channels`.
+    ///
+    /// Every notification received is sent through the custom signal named
+    /// after its channel, with a
+    /// [`DbNotification`](django_rs_signals::DbNotification) payload. The
+    /// connection stays open until the returned [`PgListener`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::ConfigurationError`] if the backend has no
+    /// connection settings, or an operational error if the connection or a
+    /// `LISTEN` fails.
+    pub async fn listen(&self, channels: &[&str]) -> Result<PgListener, DjangoError> {
+        let config = self.listen_config.as_ref().ok_or_else(|| {
+            let message = if self.shares_connections {
+                "PostgresBackend::listen needs a session, which transaction pooling \
+                 doesn't keep; call with_listen_config with a direct connection to PostgreSQL"
+            } else {
+                "PostgresBackend::listen requires connection settings; \
+                 create the backend with from_config or call with_listen_config"
+            };
+            DjangoError::ConfigurationError(message.to_string())
+        })?;
+        let (client, mut connection) = config
+            .connect(tokio_postgres::NoTls)
+            .await
+            .map_err(|e| DjangoError::OperationalError(format!("Listener connection: {e}")))?;
+
+        // Notifications only arrive through the connection's message stream,
+        // so the listener drives the connection itself.
+        let task = tokio::spawn(async move {
+            while let Some(message) = std::future::poll_fn(|cx| connection.poll_message(cx)).await {
+                match message {
+                    Ok(tokio_postgres::AsyncMessage::Notification(n)) => {
+                        notification_received(n.channel(), n.payload(), n.process_id());
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("PostgreSQL listener connection failed: {e}");
+                        break;
+                    }
+                }
+            }
+        });
+
+        let listener = PgListener { client, task };
+        for channel in channels {
+            listener.listen(channel).await?;
+        }
+        Ok(listener)
     }
 
     /// Creates a new backend from a [`DatabaseConfig`].
     ///
+    /// Each new pooled connection runs the config's
+    /// [`session_statements`](DatabaseConfig::session_statements) and then
+    /// fires the `connection_created` signal.
+    ///
+    /// If the config declares transaction or statement pooling, the backend
+    /// runs in [compatibility mode](self#transaction-pooling).
+    ///
     /// # Errors
     ///
-    /// Returns an error if the pool cannot be created.
+    /// Returns an error if the pool cannot be created, or if the config sets
+    /// session options under transaction or statement pooling.
     pub fn from_config(config: &DatabaseConfig) -> Result<Self, DjangoError> {
+        let shares_connections = config.shares_connections();
+        let session_options = config.session_options();
+        if shares_connections && !session_options.is_empty() {
+            return Err(DjangoError::ConfigurationError(format!(
+                "Options {} set session state, which {} pooling doesn't keep; \
+                 set them on the database role instead",
+                session_options.join(", "),
+                config.pool_mode().map_or("transaction", PoolMode::as_str),
+            )));
+        }
+
         let mut pg_config = deadpool_postgres::Config::new();
         pg_config.dbname = Some(config.name.clone());
         pg_config.host = config.host.clone();
@@ -38,14 +182,52 @@ impl PostgresBackend {
         pg_config.user = config.user.clone();
         pg_config.password = config.password.clone();
 
+        // Applied at connection startup, so every pooled connection sees it.
+        let search_path = config.search_path();
+        if !search_path.is_empty() {
+            pg_config.options = Some(format!("-c search_path={}", search_path.join(",")));
+        }
+
+        // Session setup runs once per physical connection, before it is
+        // handed out by the pool.
+        let session_statements = config.session_statements();
+        let database = config.name.clone();
         let pool = pg_config
-            .create_pool(
-                Some(deadpool_postgres::Runtime::Tokio1),
-                tokio_postgres::NoTls,
-            )
+            .builder(tokio_postgres::NoTls)
+            .map_err(|e| DjangoError::OperationalError(format!("Failed to create pool: {e}")))?
+            .runtime(deadpool_postgres::Runtime::Tokio1)
+            .post_create(deadpool_postgres::Hook::async_fn(move |client, _| {
+                let session_statements = session_statements.clone();
+                let database = database.clone();
+                Box::pin(async move {
+                    for statement in &session_statements {
+                        client
+                            .batch_execute(statement)
+                            .await
+                            .map_err(deadpool_postgres::HookError::Backend)?;
+                    }
+                    connection_created("postgresql", &database);
+                    Ok(())
+                })
+            }))
+            .build()
             .map_err(|e| DjangoError::OperationalError(format!("Failed to create pool: {e}")))?;
 
-        Ok(Self { pool })
+        // LISTEN holds a session, so it can't go through the pooler.
+        let listen_config = if shares_connections {
+            None
+        } else {
+            Some(
+                pg_config
+                    .get_pg_config()
+                    .map_err(|e| DjangoError::ConfigurationError(format!("Invalid config: {e}")))?,
+            )
+        };
+        Ok(Self {
+            pool,
+            listen_config,
+            shares_connections,
+        })
     }
 
     /// Converts ORM `Value` types to `tokio-postgres` parameter references.
@@ -94,6 +276,124 @@ impl PostgresBackend {
             .collect()
     }
 
+    /// Returns the type a parameter is sent as when statements are unnamed.
+    ///
+    /// Values without their own type (`Null`, `List`) are sent as `unknown`,
+    /// leaving PostgreSQL to infer it. So are hstore and range values, which
+    /// are sent as their text form: PostgreSQL has no assignment cast from
+    /// `text` to `hstore` or a range type.
+    fn value_sql_type(value: &Value) -> tokio_postgres::types::Type {
+        use tokio_postgres::types::Type;
+        match value {
+            Value::Null | Value::List(_) | Value::HStore(_) | Value::Range { .. } => Type::UNKNOWN,
+            Value::Bool(_) => Type::BOOL,
+            Value::Int(_) | Value::Duration(_) => Type::INT8,
+            Value::Float(_) => Type::FLOAT8,
+            Value::String(_) => Type::TEXT,
+            Value::Bytes(_) => Type::BYTEA,
+            Value::Date(_) => Type::DATE,
+            Value::DateTime(_) => Type::TIMESTAMP,
+            Value::DateTimeTz(_) => Type::TIMESTAMPTZ,
+            Value::Time(_) => Type::TIME,
+            Value::Uuid(_) => Type::UUID,
+            Value::Json(_) => Type::JSONB,
+        }
+    }
+
+    /// Takes a connection from the pool.
+    async fn client(&self) -> Result<deadpool_postgres::Object, DjangoError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| DjangoError::OperationalError(format!("Pool error: {e}")))
+    }
+
+    /// Executes a statement on `client`, returning the number of rows
+    /// affected.
+    async fn execute_on(
+        client: &tokio_postgres::Client,
+        unnamed: bool,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<u64, DjangoError> {
+        if unnamed {
+            return Self::query_unnamed(client, sql, params)
+                .await
+                .map(|(_, affected)| affected);
+        }
+
+        let sql_params = Self::value_to_sql_params(params);
+        let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = sql_params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync))
+            .collect();
+
+        client
+            .execute(sql, &param_refs)
+            .await
+            .map_err(database_error)
+    }
+
+    /// Runs a query on `client`, returning its rows.
+    async fn query_on(
+        client: &tokio_postgres::Client,
+        unnamed: bool,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<Vec<Row>, DjangoError> {
+        if unnamed {
+            return Self::query_unnamed(client, sql, params)
+                .await
+                .map(|(rows, _)| rows);
+        }
+
+        let sql_params = Self::value_to_sql_params(params);
+        let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = sql_params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync))
+            .collect();
+
+        let rows = client
+            .query(sql, &param_refs)
+            .await
+            .map_err(database_error)?;
+
+        Ok(rows.iter().map(Self::convert_row).collect())
+    }
+
+    /// Runs `sql` as an unnamed statement in one round trip, returning the
+    /// rows and the number of rows affected.
+    ///
+    /// Unlike `query`, which prepares a named statement first, this keeps
+    /// nothing on the server connection.
+    async fn query_unnamed(
+        client: &tokio_postgres::Client,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<(Vec<Row>, u64), DjangoError> {
+        let sql_params = Self::value_to_sql_params(params);
+        let typed = sql_params.iter().zip(params).map(|(param, value)| {
+            (
+                param.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync),
+                Self::value_sql_type(value),
+            )
+        });
+        let stream = client
+            .query_typed_raw(sql, typed)
+            .await
+            .map_err(database_error)?;
+        let mut stream = std::pin::pin!(stream);
+
+        let mut rows = Vec::new();
+        while let Some(row) =
+            std::future::poll_fn(|cx| futures_core::Stream::poll_next(stream.as_mut(), cx)).await
+        {
+            rows.push(Self::convert_row(&row.map_err(database_error)?));
+        }
+        let affected = stream.rows_affected().unwrap_or_default();
+        Ok((rows, affected))
+    }
+
     /// Converts a `tokio_postgres::Row` to our generic `Row`.
     fn convert_row(pg_row: &tokio_postgres::Row) -> Row {
         let columns: Vec<String> = pg_row
@@ -195,6 +495,76 @@ impl PostgresBackend {
     }
 }
 
+/// A dedicated connection subscribed to `NOTIFY` channels.
+///
+/// Created by [`PostgresBackend::listen`]. Dropping the listener closes the
+/// connection and stops dispatching.
+pub struct PgListener {
+    client: tokio_postgres::Client,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl PgListener {
+    /// Starts listening on another channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an operational error if the `LISTEN` fails.
+    pub async fn listen(&self, channel: &str) -> Result<(), DjangoError> {
+        self.client
+            .batch_execute(&format!("LISTEN {}", quote_identifier(channel)))
+            .await
+            .map_err(|e| DjangoError::OperationalError(format!("LISTEN {channel}: {e}")))
+    }
+
+    /// Stops listening on a channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an operational error if the `UNLISTEN` fails.
+    pub async fn unlisten(&self, channel: &str) -> Result<(), DjangoError> {
+        self.client
+            .batch_execute(&format!("UNLISTEN {}", quote_identifier(channel)))
+            .await
+            .map_err(|e| DjangoError::OperationalError(format!("UNLISTEN {channel}: {e}")))
+    }
+
+    /// Returns `true` while the connection is open and dispatching.
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for PgListener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl std::fmt::Debug for PgListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PgListener")
+            .field("running", &self.is_running())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Converts a driver error, appending the SQLSTATE code of server errors so
+/// callers can tell failures apart (see
+/// [`is_serialization_failure`](django_rs_db::transactions::is_serialization_failure)).
+fn database_error(e: tokio_postgres::Error) -> DjangoError {
+    DjangoError::DatabaseError(e.code().map_or_else(
+        || format!("{e}"),
+        |code| format!("{e} (SQLSTATE {})", code.code()),
+    ))
+}
+
+/// Quotes a channel name for `LISTEN`/`UNLISTEN`, which take an identifier
+/// rather than a parameter.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
 #[async_trait::async_trait]
 impl DatabaseBackend for PostgresBackend {
     fn vendor(&self) -> &str {
@@ -206,43 +576,21 @@ impl DatabaseBackend for PostgresBackend {
     }
 
     async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
-        let client = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| DjangoError::OperationalError(format!("Pool error: {e}")))?;
-
-        let sql_params = Self::value_to_sql_params(params);
-        let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = sql_params
-            .iter()
-            .map(|p| p.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync))
-            .collect();
-
-        client
-            .execute(sql, &param_refs)
-            .await
-            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))
+        async move {
+            let client = self.client().await?;
+            Self::execute_on(&client, self.shares_connections, sql, params).await
+        }
+        .instrument(query_span("postgresql", sql))
+        .await
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
-        let client = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| DjangoError::OperationalError(format!("Pool error: {e}")))?;
-
-        let sql_params = Self::value_to_sql_params(params);
-        let param_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = sql_params
-            .iter()
-            .map(|p| p.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync))
-            .collect();
-
-        let rows = client
-            .query(sql, &param_refs)
-            .await
-            .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
-
-        Ok(rows.iter().map(Self::convert_row).collect())
+        async move {
+            let client = self.client().await?;
+            Self::query_on(&client, self.shares_connections, sql, params).await
+        }
+        .instrument(query_span("postgresql", sql))
+        .await
     }
 
     async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
@@ -275,6 +623,70 @@ impl DatabaseBackend for PostgresBackend {
     fn compiler(&self) -> SqlCompiler {
         SqlCompiler::new(DatabaseBackendType::PostgreSQL)
     }
+
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+        Ok(Box::new(PinnedPostgres {
+            client: self.client().await?,
+            shares_connections: self.shares_connections,
+        }))
+    }
+}
+
+/// A pooled connection held for the lifetime of the handle, returned by
+/// [`PostgresBackend::pin`](DatabaseBackend::pin).
+struct PinnedPostgres {
+    client: deadpool_postgres::Object,
+    shares_connections: bool,
+}
+
+#[async_trait::async_trait]
+impl DatabaseBackend for PinnedPostgres {
+    fn vendor(&self) -> &str {
+        "postgresql"
+    }
+
+    fn backend_type(&self) -> DatabaseBackendType {
+        DatabaseBackendType::PostgreSQL
+    }
+
+    async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
+        PostgresBackend::execute_on(&self.client, self.shares_connections, sql, params)
+            .instrument(query_span("postgresql", sql))
+            .await
+    }
+
+    async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
+        PostgresBackend::query_on(&self.client, self.shares_connections, sql, params)
+            .instrument(query_span("postgresql", sql))
+            .await
+    }
+
+    async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
+        single_row(self.query(sql, params).await?)
+    }
+
+    async fn begin_transaction(&self) -> Result<Transaction, DjangoError> {
+        self.execute("BEGIN", &[]).await?;
+        Ok(Transaction::new(Box::new(())))
+    }
+
+    async fn commit(&self) -> Result<(), DjangoError> {
+        self.execute("COMMIT", &[]).await?;
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DjangoError> {
+        self.execute("ROLLBACK", &[]).await?;
+        Ok(())
+    }
+
+    fn compiler(&self) -> SqlCompiler {
+        SqlCompiler::new(DatabaseBackendType::PostgreSQL)
+    }
+
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+        Ok(Box::new(Pinned(self)))
+    }
 }
 
 #[async_trait::async_trait]
@@ -284,20 +696,43 @@ impl django_rs_db::DbExecutor for PostgresBackend {
     }
 
     async fn execute_sql(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
-        self.execute(sql, params).await
+        self.execute(&annotate_sql(sql, DatabaseBackendType::PostgreSQL), params)
+            .await
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
-        DatabaseBackend::query(self, sql, params).await
+        DatabaseBackend::query(
+            self,
+            &annotate_sql(sql, DatabaseBackendType::PostgreSQL),
+            params,
+        )
+        .await
     }
 
     async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
-        DatabaseBackend::query_one(self, sql, params).await
+        DatabaseBackend::query_one(
+            self,
+            &annotate_sql(sql, DatabaseBackendType::PostgreSQL),
+            params,
+        )
+        .await
+    }
+
+    async fn notify(&self, channel: &str, payload: &str) -> Result<(), DjangoError> {
+        self.execute(
+            "SELECT pg_notify($1, $2)",
+            &[Value::from(channel), Value::from(payload)],
+        )
+        .await
+        .map(|_| ())
     }
 
     async fn insert_returning_id(&self, sql: &str, params: &[Value]) -> Result<Value, DjangoError> {
         // PostgreSQL supports RETURNING; append it to the SQL
-        let sql_returning = format!("{sql} RETURNING id");
+        let sql_returning = annotate_sql(
+            &format!("{sql} RETURNING id"),
+            DatabaseBackendType::PostgreSQL,
+        );
         let rows = DatabaseBackend::query(self, &sql_returning, params).await?;
         if let Some(row) = rows.into_iter().next() {
             Ok(row.get::<Value>("id")?)
@@ -372,6 +807,66 @@ mod tests {
         assert!(sql.contains("$1"));
     }
 
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("cache"), "\"cache\"");
+        assert_eq!(quote_identifier("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_value_sql_type() {
+        use tokio_postgres::types::Type;
+        assert_eq!(PostgresBackend::value_sql_type(&Value::Int(1)), Type::INT8);
+        assert_eq!(
+            PostgresBackend::value_sql_type(&Value::from("a")),
+            Type::TEXT
+        );
+        assert_eq!(PostgresBackend::value_sql_type(&Value::Null), Type::UNKNOWN);
+    }
+
+    #[test]
+    fn test_value_sql_type_hstore_and_range_are_inferred() {
+        use tokio_postgres::types::{ToSql, Type};
+        let hstore = Value::HStore(std::collections::HashMap::from([(
+            "a".to_string(),
+            "1".to_string(),
+        )]));
+        let range = Value::Range {
+            lower: Some(Box::new(Value::Int(1))),
+            lower_inclusive: true,
+            upper: Some(Box::new(Value::Int(10))),
+            upper_inclusive: false,
+        };
+        for value in [hstore, range] {
+            let ty = PostgresBackend::value_sql_type(&value);
+            assert_eq!(ty, Type::UNKNOWN);
+            // The text form these are sent as must be accepted for that type
+            assert!(<String as ToSql>::accepts(&ty));
+        }
+    }
+
+    #[test]
+    fn test_from_config_refuses_session_options_under_transaction_pooling() {
+        let cfg = DatabaseConfig::postgres("app", "localhost", 6432, "u", "p")
+            .with_pool_mode(PoolMode::Transaction)
+            .with_search_path(["app"]);
+        let err = PostgresBackend::from_config(&cfg).err().unwrap();
+        assert!(
+            matches!(&err, DjangoError::ConfigurationError(msg) if msg.contains("search_path")),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_config_transaction_pooling() {
+        let cfg = DatabaseConfig::postgres("app", "localhost", 6432, "u", "p")
+            .with_pool_mode(PoolMode::Transaction);
+        let backend = PostgresBackend::from_config(&cfg).unwrap();
+        assert!(backend.transaction_pooling());
+        let err = backend.listen(&["cache"]).await.unwrap_err();
+        assert!(err.to_string().contains("transaction pooling"), "{err}");
+    }
+
     #[test]
     fn test_config_to_backend_type() {
         let cfg = DatabaseConfig::postgres("testdb", "localhost", 5432, "user", "pass");
@@ -9,14 +9,18 @@
 //! - In-memory database support via `:memory:` path (great for testing)
 //! - Simple `Mutex`-based concurrency control
 
-use crate::base::{DatabaseBackend, Transaction};
+use crate::base::{
+    connection_created, query_span, DatabaseBackend, DatabaseConfig, Pinned, Transaction,
+};
 use django_rs_core::DjangoError;
+use django_rs_db::audit::annotate_sql;
 use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
 use django_rs_db::value::Value;
 use django_rs_db::Row;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tracing::Instrument;
 
 /// A SQLite database backend.
 ///
@@ -40,7 +44,26 @@ impl SqliteBackend {
     ///
     /// Returns an error if the database cannot be opened.
     pub fn open(path: impl Into<PathBuf>) -> Result<Self, DjangoError> {
-        let path = path.into();
+        Self::open_with(path.into(), &[])
+    }
+
+    /// Opens the database described by a [`DatabaseConfig`].
+    ///
+    /// The config's [`session_statements`](DatabaseConfig::session_statements)
+    /// (its `init_command`, e.g. extra `PRAGMA`s) run right after the default
+    /// pragmas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or an init command
+    /// fails.
+    pub fn from_config(config: &DatabaseConfig) -> Result<Self, DjangoError> {
+        Self::open_with(PathBuf::from(&config.name), &config.session_statements())
+    }
+
+    /// Opens the database, runs `init` on the new connection and fires the
+    /// `connection_created` signal.
+    fn open_with(path: PathBuf, init: &[String]) -> Result<Self, DjangoError> {
         let conn = if path.to_str() == Some(":memory:") {
             rusqlite::Connection::open_in_memory()
         } else {
@@ -51,6 +74,12 @@ impl SqliteBackend {
         // Enable WAL mode for better concurrent read performance
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")
             .map_err(|e| DjangoError::OperationalError(format!("Failed to set pragmas: {e}")))?;
+        for stmt in init {
+            conn.execute_batch(stmt).map_err(|e| {
+                DjangoError::OperationalError(format!("Init command `{stmt}` failed: {e}"))
+            })?;
+        }
+        connection_created("sqlite", &path.to_string_lossy());
 
         Ok(Self {
             path,
@@ -152,6 +181,7 @@ impl DatabaseBackend for SqliteBackend {
     }
 
     async fn execute(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
+        let span = query_span("sqlite", sql);
         let conn = self.conn.clone();
         let sql = sql.to_string();
         let params = params.to_vec();
@@ -167,11 +197,13 @@ impl DatabaseBackend for SqliteBackend {
                 .map_err(|e| DjangoError::DatabaseError(format!("{e}")))?;
             Ok(count as u64)
         })
+        .instrument(span)
         .await
         .map_err(|e| DjangoError::DatabaseError(format!("Task join error: {e}")))?
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
+        let span = query_span("sqlite", sql);
         let conn = self.conn.clone();
         let sql = sql.to_string();
         let params = params.to_vec();
@@ -199,6 +231,7 @@ impl DatabaseBackend for SqliteBackend {
 
             Ok(rows)
         })
+        .instrument(span)
         .await
         .map_err(|e| DjangoError::DatabaseError(format!("Task join error: {e}")))?
     }
@@ -233,6 +266,11 @@ impl DatabaseBackend for SqliteBackend {
     fn compiler(&self) -> SqlCompiler {
         SqlCompiler::new(DatabaseBackendType::SQLite)
     }
+
+    async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+        // There is only the one connection.
+        Ok(Box::new(Pinned(self)))
+    }
 }
 
 #[async_trait::async_trait]
@@ -242,20 +280,32 @@ impl django_rs_db::DbExecutor for SqliteBackend {
     }
 
     async fn execute_sql(&self, sql: &str, params: &[Value]) -> Result<u64, DjangoError> {
-        self.execute(sql, params).await
+        self.execute(&annotate_sql(sql, DatabaseBackendType::SQLite), params)
+            .await
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>, DjangoError> {
-        DatabaseBackend::query(self, sql, params).await
+        DatabaseBackend::query(
+            self,
+            &annotate_sql(sql, DatabaseBackendType::SQLite),
+            params,
+        )
+        .await
     }
 
     async fn query_one(&self, sql: &str, params: &[Value]) -> Result<Row, DjangoError> {
-        DatabaseBackend::query_one(self, sql, params).await
+        DatabaseBackend::query_one(
+            self,
+            &annotate_sql(sql, DatabaseBackendType::SQLite),
+            params,
+        )
+        .await
     }
 
     async fn insert_returning_id(&self, sql: &str, params: &[Value]) -> Result<Value, DjangoError> {
+        let sql = annotate_sql(sql, DatabaseBackendType::SQLite);
+        let span = query_span("sqlite", &sql);
         let conn = self.conn.clone();
-        let sql = sql.to_string();
         let params = params.to_vec();
 
         tokio::task::spawn_blocking(move || {
@@ -269,6 +319,7 @@ impl django_rs_db::DbExecutor for SqliteBackend {
             let id = conn.last_insert_rowid();
             Ok(Value::Int(id))
         })
+        .instrument(span)
         .await
         .map_err(|e| DjangoError::DatabaseError(format!("Task join error: {e}")))?
     }
@@ -286,6 +337,15 @@ mod tests {
         assert_eq!(backend.backend_type(), DatabaseBackendType::SQLite);
     }
 
+    #[tokio::test]
+    async fn test_sqlite_notify_unsupported() {
+        use django_rs_db::DbExecutor;
+
+        let backend = SqliteBackend::memory().unwrap();
+        let err = backend.notify("cache", "flush").await.unwrap_err();
+        assert!(matches!(err, DjangoError::DatabaseError(_)));
+    }
+
     #[tokio::test]
     async fn test_sqlite_create_table() {
         let backend = SqliteBackend::memory().unwrap();
@@ -581,6 +641,50 @@ mod tests {
         assert_eq!(backend.path().to_str().unwrap(), ":memory:");
     }
 
+    #[tokio::test]
+    async fn test_sqlite_from_config_init_command() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let fired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&fired);
+        django_rs_signals::SIGNALS.connection_created.connect(
+            "test_sqlite_from_config_init_command",
+            Arc::new(move |created: &django_rs_signals::ConnectionCreated| {
+                if created.vendor == "sqlite" {
+                    flag.store(true, Ordering::SeqCst);
+                }
+                None
+            }),
+        );
+
+        let config = DatabaseConfig::sqlite_memory()
+            .with_init_command("PRAGMA user_version = 7")
+            .with_init_command("PRAGMA foreign_keys = OFF")
+            .with_init_command("CREATE TEMP TABLE note (body TEXT DEFAULT 'a; b')");
+        let backend = SqliteBackend::from_config(&config).unwrap();
+        django_rs_signals::SIGNALS
+            .connection_created
+            .disconnect("test_sqlite_from_config_init_command");
+        assert!(fired.load(Ordering::SeqCst));
+
+        let row = backend.query_one("PRAGMA user_version", &[]).await.unwrap();
+        assert_eq!(row.get_by_index::<i64>(0).unwrap(), 7);
+        let row = backend.query_one("PRAGMA foreign_keys", &[]).await.unwrap();
+        assert_eq!(row.get_by_index::<i64>(0).unwrap(), 0);
+        backend
+            .execute("INSERT INTO note DEFAULT VALUES", &[])
+            .await
+            .unwrap();
+        let row = backend
+            .query_one("SELECT body FROM note", &[])
+            .await
+            .unwrap();
+        assert_eq!(row.get_by_index::<String>(0).unwrap(), "a; b");
+
+        let bad = DatabaseConfig::sqlite_memory().with_init_command("NOT SQL");
+        assert!(SqliteBackend::from_config(&bad).is_err());
+    }
+
     #[tokio::test]
     async fn test_sqlite_compiled_sql_execution() {
         // Test that SQL generated by the compiler actually works in SQLite
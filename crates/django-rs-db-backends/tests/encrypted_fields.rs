@@ -0,0 +1,291 @@
+//! Integration tests for encrypted fields on a real in-memory database.
+//!
+//! These tests verify that encrypted values are stored as ciphertext,
+//! decrypted on load, found through their blind index, and re-encrypted by
+//! key rotation.
+
+use django_rs_core::DjangoError;
+use django_rs_db::encryption::{self, FieldKeyring};
+use django_rs_db::executor::{save_model, DbExecutor};
+use django_rs_db::fields::{FieldDef, FieldType};
+use django_rs_db::model::{Model, ModelMeta};
+use django_rs_db::query::compiler::{InheritanceType, Row};
+use django_rs_db::query::lookups::{Lookup, Q};
+use django_rs_db::value::Value;
+use django_rs_db::Manager;
+use django_rs_db_backends::{DatabaseBackend, SqliteBackend};
+
+#[derive(Debug, Clone)]
+struct Customer {
+    id: Value,
+    email: String,
+    email_hash: String,
+    notes: Option<String>,
+}
+
+impl Model for Customer {
+    fn meta() -> &'static ModelMeta {
+        use std::sync::OnceLock;
+        static META: OnceLock<ModelMeta> = OnceLock::new();
+        META.get_or_init(|| ModelMeta {
+            app_label: "crm",
+            model_name: "customer",
+            db_table: "crm_customer".to_string(),
+            verbose_name: "customer".to_string(),
+            verbose_name_plural: "customers".to_string(),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields: vec![
+                FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                FieldDef::new(
+                    "email",
+                    FieldType::EncryptedCharField {
+                        hash_field: Some("email_hash".to_string()),
+                    },
+                )
+                .max_length(254),
+                FieldDef::new("email_hash", FieldType::CharField).max_length(64),
+                FieldDef::new("notes", FieldType::EncryptedTextField { hash_field: None })
+                    .nullable(),
+            ],
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        })
+    }
+
+    fn table_name() -> &'static str {
+        "crm_customer"
+    }
+
+    fn app_label() -> &'static str {
+        "crm"
+    }
+
+    fn pk(&self) -> Option<&Value> {
+        match self.id {
+            Value::Null => None,
+            ref id => Some(id),
+        }
+    }
+
+    fn set_pk(&mut self, value: Value) {
+        self.id = value;
+    }
+
+    fn field_values(&self) -> Vec<(&'static str, Value)> {
+        vec![
+            ("id", self.id.clone()),
+            ("email", Value::String(self.email.clone())),
+            ("email_hash", Value::String(self.email_hash.clone())),
+            (
+                "notes",
+                self.notes.clone().map_or(Value::Null, Value::String),
+            ),
+        ]
+    }
+
+    fn from_row(row: &Row) -> Result<Self, DjangoError> {
+        Ok(Self {
+            id: Value::Int(row.get("id")?),
+            email: encryption::decrypt_column(row, Self::table_name(), "email")?,
+            email_hash: row.get("email_hash")?,
+            notes: encryption::decrypt_column(row, Self::table_name(), "notes")?,
+        })
+    }
+}
+
+const OLD_KEY: [u8; 32] = [1; 32];
+const NEW_KEY: [u8; 32] = [2; 32];
+
+fn keyring(keys: &[(&str, [u8; 32])]) -> FieldKeyring {
+    keys.iter()
+        .fold(FieldKeyring::new(), |keyring, (id, key)| {
+            keyring.with_key(*id, key).unwrap()
+        })
+        .with_hash_key("hash-key")
+}
+
+async fn setup() -> SqliteBackend {
+    let db = SqliteBackend::memory().unwrap();
+    db.execute(
+        "CREATE TABLE crm_customer (id INTEGER PRIMARY KEY AUTOINCREMENT, email TEXT NOT NULL, \
+         email_hash VARCHAR(64) NOT NULL, notes TEXT NULL)",
+        &[],
+    )
+    .await
+    .unwrap();
+    db
+}
+
+async fn raw_email(db: &SqliteBackend, id: &Value) -> String {
+    let rows = DbExecutor::query(
+        db,
+        "SELECT email FROM crm_customer WHERE id = ?",
+        std::slice::from_ref(id),
+    )
+    .await
+    .unwrap();
+    rows[0].get("email").unwrap()
+}
+
+/// The keyring is global, so tests that set it run one at a time.
+static KEYRING_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Saves ada, with notes, and grace, without.
+async fn save_customers(db: &SqliteBackend) -> Customer {
+    let mut ada = Customer {
+        id: Value::Null,
+        email: "ada@example.com".to_string(),
+        email_hash: String::new(),
+        notes: Some("prefers email".to_string()),
+    };
+    save_model(&mut ada, db).await.unwrap();
+    Manager::<Customer>::new()
+        .create(vec![
+            ("email", Value::String("grace@example.com".to_string())),
+            ("notes", Value::Null),
+        ])
+        .create_exec(db)
+        .await
+        .unwrap();
+    ada
+}
+
+fn email_is(email: &str) -> Q {
+    Q::filter("email", Lookup::Exact(Value::String(email.to_string())))
+}
+
+#[tokio::test]
+async fn test_encrypted_field_blind_index() {
+    let _guard = KEYRING_LOCK.lock().await;
+    encryption::set_keyring(keyring(&[("old", OLD_KEY)]));
+    let db = setup().await;
+    let customers = Manager::<Customer>::new();
+    let ada = save_customers(&db).await;
+
+    let stored = raw_email(&db, &ada.id).await;
+    assert_eq!(encryption::key_id(&stored), Some("old"));
+    assert!(!stored.contains("ada"));
+
+    let found = customers
+        .filter(email_is("ada@example.com"))
+        .get_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(found.email, "ada@example.com");
+    assert_eq!(found.notes.as_deref(), Some("prefers email"));
+
+    let found = customers
+        .filter(Q::filter(
+            "email",
+            Lookup::In(vec![
+                Value::String("grace@example.com".to_string()),
+                Value::String("nobody@example.com".to_string()),
+            ]),
+        ))
+        .execute_query(&db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].notes, None);
+
+    // Updating the email updates its blind index too.
+    customers
+        .filter(Q::filter("id", Lookup::Exact(ada.id.clone())))
+        .update(vec![(
+            "email",
+            Value::String("ada@lovelace.org".to_string()),
+        )])
+        .update_exec(&db)
+        .await
+        .unwrap();
+    let found = customers
+        .filter(email_is("ada@lovelace.org"))
+        .get_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(found.id, ada.id);
+
+    // Lookups the encrypted fields cannot answer fail when the query runs.
+    let contains = customers.filter(Q::filter("email", Lookup::Contains("ada".to_string())));
+    assert!(matches!(
+        contains.execute_query(&db).await,
+        Err(DjangoError::DatabaseError(_))
+    ));
+    let unindexed = customers.all().exclude(Q::filter(
+        "notes",
+        Lookup::Exact(Value::String("prefers email".to_string())),
+    ));
+    assert!(unindexed.count_exec(&db).await.is_err());
+    let no_notes = customers
+        .filter(Q::filter("notes", Lookup::IsNull(true)))
+        .count_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(no_notes, 1);
+}
+
+#[tokio::test]
+async fn test_encrypted_field_key_rotation() {
+    let _guard = KEYRING_LOCK.lock().await;
+    encryption::set_keyring(keyring(&[("old", OLD_KEY)]));
+    let db = setup().await;
+    let customers = Manager::<Customer>::new();
+    let ada = save_customers(&db).await;
+
+    // Rotate: the new key encrypts, the old one still decrypts.
+    encryption::set_keyring(keyring(&[("new", NEW_KEY), ("old", OLD_KEY)]));
+    assert_eq!(customers.all().execute_query(&db).await.unwrap().len(), 2);
+    let rotated = encryption::rotate_keys::<Customer>(&db).await.unwrap();
+    assert_eq!(rotated, 2);
+    assert_eq!(encryption::rotate_keys::<Customer>(&db).await.unwrap(), 0);
+    assert_eq!(
+        encryption::key_id(&raw_email(&db, &ada.id).await),
+        Some("new")
+    );
+
+    // Once rotated, the old key can be dropped.
+    encryption::set_keyring(keyring(&[("new", NEW_KEY)]));
+    let found = customers
+        .filter(email_is("ada@example.com"))
+        .get_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(found.notes.as_deref(), Some("prefers email"));
+}
+
+#[tokio::test]
+async fn test_encrypted_field_rejects_planted_values() {
+    let _guard = KEYRING_LOCK.lock().await;
+    encryption::set_keyring(keyring(&[("old", OLD_KEY)]));
+    let db = setup().await;
+    let customers = Manager::<Customer>::new();
+    let ada = save_customers(&db).await;
+
+    // A value planted in the column, in plain text or copied from another
+    // column, is rejected.
+    for planted in [
+        Value::String("eve@example.com".to_string()),
+        Value::String(
+            encryption::keyring()
+                .unwrap()
+                .encrypt("crm_customer", "notes", "x")
+                .unwrap(),
+        ),
+    ] {
+        db.execute(
+            "UPDATE crm_customer SET email = ? WHERE id = ?",
+            &[planted, ada.id.clone()],
+        )
+        .await
+        .unwrap();
+        let loaded = customers
+            .filter(Q::filter("id", Lookup::Exact(ada.id.clone())))
+            .get_exec(&db)
+            .await;
+        assert!(matches!(loaded, Err(DjangoError::DatabaseError(_))));
+    }
+}
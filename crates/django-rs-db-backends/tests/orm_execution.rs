@@ -8,7 +8,7 @@ use django_rs_core::{DjangoError, DjangoResult};
 use django_rs_db::executor::{
     create_model, delete_model, refresh_model, save_model, DbExecutor, ModelLifecycleHooks,
 };
-use django_rs_db::fields::{FieldDef, FieldType};
+use django_rs_db::fields::{FieldDef, FieldType, OnDelete};
 use django_rs_db::model::{Model, ModelMeta};
 use django_rs_db::query::compiler::{InheritanceType, OrderBy, Row};
 use django_rs_db::query::lookups::{Lookup, Q};
@@ -48,6 +48,7 @@ impl Model for User {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -136,6 +137,7 @@ impl Model for Product {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -214,6 +216,7 @@ impl Model for HookedProduct {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -440,6 +443,41 @@ async fn test_qs_execute_filter_in() {
     assert_eq!(users.len(), 2);
 }
 
+#[tokio::test]
+async fn test_qs_execute_filter_tuple() {
+    let db = setup_user_db().await;
+    seed_users(&db).await;
+    let mgr = django_rs_db::Manager::<User>::new();
+    let users = mgr
+        .all()
+        .filter_tuple_in(
+            &["name", "age"],
+            vec![
+                vec![Value::from("Alice"), Value::from(30)],
+                vec![Value::from("Bob"), Value::from(99)],
+                vec![Value::from("Eve"), Value::from(22)],
+            ],
+        )
+        .execute_query(&db)
+        .await
+        .unwrap();
+    let names: Vec<&str> = users.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, vec!["Alice", "Eve"]);
+
+    // Keyset resume point after (age 28, "Diana"), ordered by age then name.
+    let users = mgr
+        .filter(Q::tuple(
+            ["age", "name"],
+            django_rs_db::TupleLookup::Gt(vec![Value::from(28), Value::from("Diana")]),
+        ))
+        .order_by(vec![OrderBy::asc("age"), OrderBy::asc("name")])
+        .execute_query(&db)
+        .await
+        .unwrap();
+    let names: Vec<&str> = users.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, vec!["Alice", "Charlie"]);
+}
+
 #[tokio::test]
 async fn test_qs_execute_filter_range() {
     let db = setup_user_db().await;
@@ -978,6 +1016,61 @@ async fn test_qs_create_exec_no_pending() {
         .is_err());
 }
 
+// ── upsert_exec ───────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_qs_upsert_exec_inserts_then_updates() {
+    let db = SqliteBackend::memory().unwrap();
+    db.execute("CREATE TABLE auth_user (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, age INTEGER NOT NULL, email TEXT NOT NULL UNIQUE)", &[]).await.unwrap();
+    let mgr = django_rs_db::Manager::<User>::new();
+
+    let created = mgr
+        .upsert(
+            &["email"],
+            vec![
+                ("email", Value::from("alice@example.com")),
+                ("name", Value::from("Alice")),
+                ("age", Value::from(30)),
+            ],
+        )
+        .upsert_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(created.id, 1);
+    assert_eq!(created.name, "Alice");
+
+    let updated = mgr
+        .upsert(
+            &["email"],
+            vec![
+                ("email", Value::from("alice@example.com")),
+                ("name", Value::from("Alice Smith")),
+                ("age", Value::from(31)),
+            ],
+        )
+        .upsert_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(updated.id, 1);
+    assert_eq!(updated.name, "Alice Smith");
+    assert_eq!(updated.age, 31);
+    assert_eq!(mgr.all().count_exec(&db).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_qs_upsert_exec_requires_unique_values() {
+    let db = setup_user_db().await;
+    let mgr = django_rs_db::Manager::<User>::new();
+    let err = mgr
+        .upsert(&["email"], vec![("name", Value::from("Alice"))])
+        .upsert_exec(&db)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("'email'"));
+    assert!(mgr.upsert(&[], vec![]).upsert_exec(&db).await.is_err());
+    assert!(mgr.all().upsert_exec(&db).await.is_err());
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // MODEL CRUD TESTS
 // ═══════════════════════════════════════════════════════════════════════
@@ -1409,6 +1502,7 @@ async fn test_isnull_filter() {
                 fields: vec![],
                 constraints: vec![],
                 inheritance_type: InheritanceType::None,
+                db_schema: None,
             });
             &META
         }
@@ -1811,10 +1905,19 @@ impl Model for Post {
             fields: vec![
                 FieldDef::new("id", FieldType::BigAutoField).primary_key(),
                 FieldDef::new("title", FieldType::CharField).max_length(200),
-                FieldDef::new("author_id", FieldType::BigIntegerField),
+                FieldDef::new(
+                    "author",
+                    FieldType::ForeignKey {
+                        to: "auth.User".to_string(),
+                        on_delete: OnDelete::Cascade,
+                        related_name: None,
+                    },
+                )
+                .column("author_id"),
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -1952,6 +2055,87 @@ async fn test_select_related_with_filter() {
     assert!(results.iter().all(|p| p.author_id == 1));
 }
 
+/// Routes the `auth` app to the `users` database.
+struct AuthRouter;
+
+impl django_rs_db::DatabaseRouter for AuthRouter {
+    fn db_for_read(&self, app_label: &str, _model_name: &str) -> Option<String> {
+        (app_label == "auth").then(|| "users".to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_cross_database_select_related() {
+    use django_rs_db::query::compiler::SelectRelatedField;
+    use std::collections::HashMap;
+
+    // Users live on their own database; posts keep their author ids.
+    let users_db = setup_post_db().await;
+    seed_posts(&users_db).await;
+    let posts_db = SqliteBackend::memory().unwrap();
+    posts_db
+        .execute(
+            "CREATE TABLE blog_post (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL, author_id INTEGER NOT NULL)",
+            &[],
+        )
+        .await
+        .unwrap();
+    for (title, author_id) in [("First Post", 1_i64), ("Third Post", 2)] {
+        posts_db
+            .execute(
+                "INSERT INTO blog_post (title, author_id) VALUES (?, ?)",
+                &[Value::from(title), Value::from(author_id)],
+            )
+            .await
+            .unwrap();
+    }
+
+    let mut router = django_rs_db::RouterChain::new();
+    router.add_router(Box::new(AuthRouter));
+    let author = SelectRelatedField {
+        field_name: "author".to_string(),
+        related_table: "auth_user".to_string(),
+        fk_column: "author_id".to_string(),
+        related_column: "id".to_string(),
+        alias: "author".to_string(),
+    };
+    let connections: HashMap<&str, &dyn DbExecutor> =
+        HashMap::from([("users", &users_db as &dyn DbExecutor)]);
+
+    let joined = django_rs_db::Manager::<Post>::new()
+        .all()
+        .select_related_with(vec![author.clone()]);
+    let err = joined
+        .execute_with_related(&posts_db, &router, &connections)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("'auth.user' is on database 'users'"), "{err}");
+    assert!(err.contains("separate_query"), "{err}");
+
+    let separate = django_rs_db::Manager::<Post>::new()
+        .all()
+        .separate_query(vec!["author"])
+        .select_related_with(vec![author]);
+    let (posts, related) = separate
+        .execute_with_related(&posts_db, &router, &connections)
+        .await
+        .unwrap();
+    assert_eq!(posts.len(), 2);
+    let mut names: Vec<String> = related["author"]
+        .iter()
+        .map(|row| row.get("name").unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Alice", "Bob"]);
+
+    let no_connection = HashMap::new();
+    assert!(separate
+        .execute_with_related(&posts_db, &router, &no_connection)
+        .await
+        .is_err());
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // PREFETCH_RELATED INTEGRATION TESTS
 // ═══════════════════════════════════════════════════════════════════════
@@ -2045,6 +2229,7 @@ impl Model for Restaurant {
                 parent_link_column: "user_id".to_string(),
                 parent_pk_column: "id".to_string(),
             },
+            db_schema: None,
         });
         &META
     }
@@ -2147,6 +2332,7 @@ impl Model for ProxyUser {
             inheritance_type: InheritanceType::Proxy {
                 parent_table: "auth_user".to_string(),
             },
+            db_schema: None,
         });
         &META
     }
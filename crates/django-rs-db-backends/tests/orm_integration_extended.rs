@@ -69,6 +69,7 @@ impl Model for Employee {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -168,6 +169,7 @@ impl Model for Gadget {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -217,6 +219,108 @@ impl Model for Gadget {
     }
 }
 
+/// Ticket model with `auto_now_add`/`auto_now` timestamps.
+#[derive(Debug, Clone)]
+struct Ticket {
+    pk_value: Value,
+    id: i64,
+    title: String,
+    created_at: Option<chrono::NaiveDateTime>,
+    updated_at: Option<chrono::NaiveDateTime>,
+}
+
+impl Ticket {
+    fn new(title: &str) -> Self {
+        Self {
+            pk_value: Value::Null,
+            id: 0,
+            title: title.to_string(),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+}
+
+impl Model for Ticket {
+    fn meta() -> &'static ModelMeta {
+        use std::sync::LazyLock;
+        static META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+            app_label: "support",
+            model_name: "ticket",
+            db_table: "support_ticket".to_string(),
+            verbose_name: "ticket".to_string(),
+            verbose_name_plural: "tickets".to_string(),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields: vec![
+                FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                FieldDef::new("title", FieldType::CharField).max_length(100),
+                FieldDef::new("created_at", FieldType::DateTimeField).auto_now_add(),
+                FieldDef::new("updated_at", FieldType::DateTimeField).auto_now(),
+            ],
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        });
+        &META
+    }
+
+    fn table_name() -> &'static str {
+        "support_ticket"
+    }
+    fn app_label() -> &'static str {
+        "support"
+    }
+
+    fn pk(&self) -> Option<&Value> {
+        if self.id == 0 {
+            None
+        } else {
+            Some(&self.pk_value)
+        }
+    }
+
+    fn set_pk(&mut self, value: Value) {
+        if let Value::Int(id) = &value {
+            self.id = *id;
+        }
+        self.pk_value = value;
+    }
+
+    fn pk_field_name() -> &'static str {
+        "id"
+    }
+
+    fn field_values(&self) -> Vec<(&'static str, Value)> {
+        vec![
+            ("id", Value::Int(self.id)),
+            ("title", Value::String(self.title.clone())),
+            ("created_at", Value::from(self.created_at)),
+            ("updated_at", Value::from(self.updated_at)),
+        ]
+    }
+
+    fn from_row(row: &Row) -> Result<Self, DjangoError> {
+        let id: i64 = row.get("id")?;
+        Ok(Self {
+            pk_value: Value::Int(id),
+            id,
+            title: row.get("title")?,
+            created_at: None,
+            updated_at: None,
+        })
+    }
+
+    fn apply_auto_timestamps(&mut self, now: chrono::NaiveDateTime, created: bool) {
+        if created {
+            self.created_at = Some(now);
+        }
+        self.updated_at = Some(now);
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // SETUP HELPERS
 // ═══════════════════════════════════════════════════════════════════════
@@ -253,6 +357,41 @@ async fn setup_gadget_db() -> SqliteBackend {
     db
 }
 
+async fn setup_ticket_db() -> SqliteBackend {
+    let db = SqliteBackend::memory().unwrap();
+    db.execute(
+        "CREATE TABLE support_ticket (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT, \
+            title TEXT NOT NULL, \
+            created_at TEXT, \
+            updated_at TEXT\
+        )",
+        &[],
+    )
+    .await
+    .unwrap();
+    db
+}
+
+/// Returns the `(created_at, updated_at)` columns of every ticket, as text.
+async fn ticket_timestamps(db: &SqliteBackend) -> Vec<(Option<String>, Option<String>)> {
+    DbExecutor::query(
+        db,
+        "SELECT created_at, updated_at FROM support_ticket ORDER BY id",
+        &[],
+    )
+    .await
+    .unwrap()
+    .iter()
+    .map(|row| {
+        (
+            row.get("created_at").unwrap(),
+            row.get("updated_at").unwrap(),
+        )
+    })
+    .collect()
+}
+
 async fn setup_gadget_db_unique_name() -> SqliteBackend {
     let db = SqliteBackend::memory().unwrap();
     db.execute(
@@ -585,6 +724,62 @@ async fn test_aggregate_count_with_filter() {
     assert_eq!(count, 3); // Alice, Bob, Grace
 }
 
+#[tokio::test]
+async fn test_aggregate_string_and_json_agg() {
+    use django_rs_db::query::expressions::{json_agg, string_agg};
+
+    let db = setup_employee_db().await;
+    seed_employees(&db).await;
+    let mgr = django_rs_db::Manager::<Employee>::new();
+    let (sql, params) = mgr.all().aggregate_sql(
+        vec![
+            (
+                "names".to_string(),
+                string_agg("name", ", ", vec![("salary".to_string(), true)])
+                    .filter(Q::filter(
+                        "department",
+                        Lookup::Exact(Value::from("Engineering")),
+                    ))
+                    .into_expression(),
+            ),
+            (
+                "departments".to_string(),
+                json_agg("department", vec![("department".to_string(), false)])
+                    .distinct()
+                    .into_expression(),
+            ),
+            (
+                "inactive".to_string(),
+                string_agg("department", ",", vec![])
+                    .distinct()
+                    .filter(Q::filter("active", Lookup::Exact(Value::Bool(false))))
+                    .into_expression(),
+            ),
+        ],
+        DatabaseBackendType::SQLite,
+    );
+    let rows = DbExecutor::query(&db, &sql, &params).await.unwrap();
+    assert_eq!(rows[0].get::<String>("names").unwrap(), "Grace, Alice, Bob");
+    assert_eq!(
+        rows[0].get::<String>("departments").unwrap(),
+        r#"["Engineering","Marketing","Sales"]"#
+    );
+    let inactive = rows[0].get::<String>("inactive").unwrap();
+    assert!(inactive == "Sales,Marketing" || inactive == "Marketing,Sales");
+
+    // SQLite can't join distinct values with another separator.
+    let (sql, params) = mgr.all().aggregate_sql(
+        vec![(
+            "departments".to_string(),
+            string_agg("department", "|", vec![])
+                .distinct()
+                .into_expression(),
+        )],
+        DatabaseBackendType::SQLite,
+    );
+    assert!(DbExecutor::query(&db, &sql, &params).await.is_err());
+}
+
 #[tokio::test]
 async fn test_exists_true() {
     let db = setup_employee_db().await;
@@ -1082,6 +1277,82 @@ async fn test_update_or_create_multiple_lookup_fields() {
     assert_eq!(emp.salary, 100000); // updated
 }
 
+#[tokio::test]
+async fn test_bulk_create_sets_auto_timestamps() {
+    let db = setup_ticket_db().await;
+    let mut tickets = vec![Ticket::new("Login broken"), Ticket::new("Typo")];
+    let opts = django_rs_db::BulkCreateOptions::default();
+    django_rs_db::bulk_create(&mut tickets, &opts, &db)
+        .await
+        .unwrap();
+    assert!(tickets[0].created_at.is_some());
+    assert_eq!(tickets[0].created_at, tickets[0].updated_at);
+    for (created, updated) in ticket_timestamps(&db).await {
+        assert!(created.is_some() && updated.is_some());
+    }
+
+    let mut skipped = vec![Ticket::new("Imported")];
+    let opts = django_rs_db::BulkCreateOptions {
+        skip_auto_now: true,
+        ..Default::default()
+    };
+    django_rs_db::bulk_create(&mut skipped, &opts, &db)
+        .await
+        .unwrap();
+    assert_eq!(ticket_timestamps(&db).await[2], (None, None));
+}
+
+#[tokio::test]
+async fn test_bulk_update_and_queryset_update_touch_auto_now() {
+    let db = setup_ticket_db().await;
+    db.execute(
+        "INSERT INTO support_ticket (title, created_at, updated_at) \
+         VALUES ('Old', '2000-01-01T00:00:00', '2000-01-01T00:00:00')",
+        &[],
+    )
+    .await
+    .unwrap();
+    let old = Some("2000-01-01T00:00:00".to_string());
+
+    let mut ticket = Ticket::new("Renamed");
+    ticket.set_pk(Value::Int(1));
+    let opts = django_rs_db::BulkUpdateOptions::default();
+    django_rs_db::bulk_update(&[ticket], &["title"], &opts, &db)
+        .await
+        .unwrap();
+    let (created, updated) = ticket_timestamps(&db).await.remove(0);
+    assert_eq!(created, old);
+    assert_ne!(updated, old);
+
+    let mgr = django_rs_db::Manager::<Ticket>::new();
+    mgr.all()
+        .update(vec![("updated_at", Value::from("2000-01-01T00:00:00"))])
+        .update_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(ticket_timestamps(&db).await[0].1, old);
+
+    mgr.all()
+        .update(vec![("title", Value::from("Again"))])
+        .update_exec(&db)
+        .await
+        .unwrap();
+    assert_ne!(ticket_timestamps(&db).await[0].1, old);
+
+    mgr.all()
+        .update(vec![("updated_at", Value::from("2000-01-01T00:00:00"))])
+        .update_exec(&db)
+        .await
+        .unwrap();
+    mgr.all()
+        .skip_auto_now()
+        .update(vec![("title", Value::from("Quiet"))])
+        .update_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(ticket_timestamps(&db).await[0].1, old);
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // SECTION 3: TRANSACTION TESTS (~15 tests)
 // ═══════════════════════════════════════════════════════════════════════
@@ -2037,3 +2308,129 @@ fn test_savepoint_default() {
     let sp = django_rs_db::transactions::Savepoint::default();
     assert!(sp.name.starts_with("sp_"));
 }
+
+// ── Deserializing into user structs ───────────────────────────────────
+
+#[tokio::test]
+async fn test_fetch_as_with_annotation() {
+    use django_rs_db::query::expressions::core::Expression;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Payroll {
+        name: String,
+        double_salary: i64,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Missing {
+        #[allow(dead_code)]
+        bonus: i64,
+    }
+
+    let db = setup_employee_db().await;
+    seed_employees(&db).await;
+    let mgr = django_rs_db::Manager::<Employee>::new();
+    let rows: Vec<Payroll> = mgr
+        .filter(Q::filter("name", Lookup::Exact(Value::from("Alice"))))
+        .annotate(
+            "double_salary",
+            Expression::f("salary") * Expression::value(2),
+        )
+        .fetch_as(&db)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Alice");
+
+    let alice = mgr
+        .filter(Q::filter("name", Lookup::Exact(Value::from("Alice"))))
+        .get_exec(&db)
+        .await
+        .unwrap();
+    assert_eq!(rows[0].double_salary, alice.salary * 2);
+    let err = mgr.all().fetch_as::<Missing>(&db).await.unwrap_err();
+    assert!(err.to_string().contains("missing column `bonus`"), "{err}");
+
+    // An annotation named after a field fails when the query runs.
+    let err = mgr
+        .all()
+        .annotate("salary", Expression::value(0))
+        .fetch_as::<Payroll>(&db)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("conflicts with a field"), "{err}");
+}
+
+// ── Distinct date periods ─────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_dates_and_datetimes_exec() {
+    use chrono::{FixedOffset, NaiveDate};
+    use django_rs_db::{DateKind, DateTimeKind};
+
+    let db = setup_ticket_db().await;
+    db.execute(
+        "INSERT INTO support_ticket (title, created_at) VALUES \
+         ('A', '2025-03-10T08:15:00'), ('B', '2025-03-28 23:30:00'), \
+         ('C', '2026-01-05 12:00:00 UTC'), ('D', NULL)",
+        &[],
+    )
+    .await
+    .unwrap();
+    let mgr = django_rs_db::Manager::<Ticket>::new();
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let years = mgr
+        .all()
+        .dates_exec(&db, "created_at", DateKind::Year, false)
+        .await
+        .unwrap();
+    assert_eq!(years, vec![date(2025, 1, 1), date(2026, 1, 1)]);
+
+    let months = mgr
+        .all()
+        .dates_exec(&db, "created_at", DateKind::Month, true)
+        .await
+        .unwrap();
+    assert_eq!(months, vec![date(2026, 1, 1), date(2025, 3, 1)]);
+
+    // 2025-03-10 and 2025-03-28 fall in different weeks, starting Monday
+    let weeks = mgr
+        .filter(Q::filter("title", Lookup::In(vec!["A".into(), "B".into()])))
+        .dates_exec(&db, "created_at", DateKind::Week, false)
+        .await
+        .unwrap();
+    assert_eq!(weeks, vec![date(2025, 3, 10), date(2025, 3, 24)]);
+
+    // In +02:00, 23:30 UTC on the 28th is already the 29th
+    let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+    let days = mgr
+        .filter(Q::filter("title", Lookup::Exact(Value::from("B"))))
+        .datetimes_exec(&db, "created_at", DateTimeKind::Day, false, Some(tz))
+        .await
+        .unwrap();
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].to_rfc3339(), "2025-03-29T00:00:00+02:00");
+
+    let hours = mgr
+        .all()
+        .datetimes_exec(&db, "created_at", DateTimeKind::Hour, false, None)
+        .await
+        .unwrap();
+    let hours: Vec<String> = hours.iter().map(chrono::DateTime::to_rfc3339).collect();
+    assert_eq!(
+        hours,
+        vec![
+            "2025-03-10T08:00:00+00:00",
+            "2025-03-28T23:00:00+00:00",
+            "2026-01-05T12:00:00+00:00",
+        ]
+    );
+
+    assert!(mgr
+        .none()
+        .dates_exec(&db, "created_at", DateKind::Year, false)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,268 @@
+//! Integration tests for tree models on a real in-memory database.
+//!
+//! These tests run the recursive CTEs generated by `TreeModel` and verify
+//! navigation, depth-first ordering and subtree moves.
+
+use django_rs_core::DjangoError;
+use django_rs_db::executor::create_model;
+use django_rs_db::fields::{FieldDef, FieldType, OnDelete};
+use django_rs_db::model::{Model, ModelMeta};
+use django_rs_db::query::compiler::{InheritanceType, Row};
+use django_rs_db::query::tree::TreeModel;
+use django_rs_db::value::Value;
+use django_rs_db_backends::{DatabaseBackend, SqliteBackend};
+
+#[derive(Debug, Clone)]
+struct Category {
+    id: Value,
+    name: String,
+    parent_id: Option<i64>,
+    position: i64,
+}
+
+impl Model for Category {
+    fn meta() -> &'static ModelMeta {
+        use std::sync::OnceLock;
+        static META: OnceLock<ModelMeta> = OnceLock::new();
+        META.get_or_init(|| ModelMeta {
+            app_label: "shop",
+            model_name: "category",
+            db_table: "shop_category".to_string(),
+            verbose_name: "category".to_string(),
+            verbose_name_plural: "categories".to_string(),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields: vec![
+                FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                FieldDef::new("name", FieldType::CharField).max_length(100),
+                FieldDef::new(
+                    "parent_id",
+                    FieldType::ForeignKey {
+                        to: "shop.category".to_string(),
+                        on_delete: OnDelete::Cascade,
+                        related_name: Some("children".to_string()),
+                    },
+                )
+                .nullable(),
+                FieldDef::new("position", FieldType::IntegerField),
+            ],
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        })
+    }
+
+    fn table_name() -> &'static str {
+        "shop_category"
+    }
+
+    fn app_label() -> &'static str {
+        "shop"
+    }
+
+    fn pk(&self) -> Option<&Value> {
+        match self.id {
+            Value::Null => None,
+            ref id => Some(id),
+        }
+    }
+
+    fn set_pk(&mut self, value: Value) {
+        self.id = value;
+    }
+
+    fn field_values(&self) -> Vec<(&'static str, Value)> {
+        vec![
+            ("id", self.id.clone()),
+            ("name", Value::String(self.name.clone())),
+            ("parent_id", self.parent_id.map_or(Value::Null, Value::Int)),
+            ("position", Value::Int(self.position)),
+        ]
+    }
+
+    fn from_row(row: &Row) -> Result<Self, DjangoError> {
+        Ok(Self {
+            id: Value::Int(row.get("id")?),
+            name: row.get("name")?,
+            parent_id: row.get("parent_id")?,
+            position: row.get("position")?,
+        })
+    }
+}
+
+impl TreeModel for Category {
+    fn order_field() -> Option<&'static str> {
+        Some("position")
+    }
+}
+
+#[tokio::test]
+async fn test_descendants_order_with_long_keys() {
+    let (db, nodes) = setup().await;
+    // Siblings at the same position are ordered by key, which must not be
+    // cut short once it has more than ten digits.
+    for (id, name) in [
+        (10_000_000_001_i64, "Tablets"),
+        (9_999_999_999, "Cameras"),
+        (20_000_000_001, "Lenses"),
+    ] {
+        let parent = if name == "Lenses" { 9_999_999_999 } else { 1 };
+        db.execute(
+            "INSERT INTO shop_category (id, name, parent_id, position) VALUES (?, ?, ?, 5)",
+            &[Value::Int(id), Value::from(name), Value::Int(parent)],
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(
+        names(&nodes[0].descendants(&db).await.unwrap()),
+        ["Phones", "Android", "Laptops", "Cameras", "Lenses", "Tablets"]
+    );
+}
+
+fn names(nodes: &[Category]) -> Vec<&str> {
+    nodes.iter().map(|c| c.name.as_str()).collect()
+}
+
+/// Builds:
+///
+/// ```text
+/// Electronics
+///   Phones
+///     Android
+///   Laptops
+/// Books
+/// ```
+async fn setup() -> (SqliteBackend, Vec<Category>) {
+    let db = SqliteBackend::memory().unwrap();
+    db.execute(
+        "CREATE TABLE shop_category (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, \
+         parent_id INTEGER NULL REFERENCES shop_category(id), position INTEGER NOT NULL)",
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let mut nodes = Vec::new();
+    for (name, parent, position) in [
+        ("Electronics", None, 0),
+        ("Books", None, 1),
+        ("Laptops", Some(1), 1),
+        ("Phones", Some(1), 0),
+        ("Android", Some(4), 0),
+    ] {
+        let mut node = Category {
+            id: Value::Null,
+            name: name.to_string(),
+            parent_id: parent,
+            position,
+        };
+        create_model(&mut node, &db).await.unwrap();
+        nodes.push(node);
+    }
+    (db, nodes)
+}
+
+#[tokio::test]
+async fn test_tree_navigation() {
+    let (db, nodes) = setup().await;
+    let (electronics, phones, android) = (&nodes[0], &nodes[3], &nodes[4]);
+
+    assert_eq!(
+        names(&electronics.children(&db).await.unwrap()),
+        ["Phones", "Laptops"]
+    );
+    assert_eq!(names(&phones.siblings(&db).await.unwrap()), ["Laptops"]);
+    assert_eq!(names(&electronics.siblings(&db).await.unwrap()), ["Books"]);
+    assert_eq!(android.parent(&db).await.unwrap().unwrap().name, "Phones");
+    assert!(electronics.parent(&db).await.unwrap().is_none());
+
+    assert_eq!(
+        names(&android.ancestors(&db).await.unwrap()),
+        ["Electronics", "Phones"]
+    );
+    assert!(electronics.ancestors(&db).await.unwrap().is_empty());
+    assert_eq!(
+        names(&electronics.descendants(&db).await.unwrap()),
+        ["Phones", "Android", "Laptops"]
+    );
+}
+
+#[tokio::test]
+async fn test_tree_listing_depths() {
+    let (db, _) = setup().await;
+    let tree = Category::tree(&db).await.unwrap();
+    let listing: Vec<(usize, &str)> = tree
+        .iter()
+        .map(|entry| (entry.depth, entry.node.name.as_str()))
+        .collect();
+    assert_eq!(
+        listing,
+        [
+            (0, "Electronics"),
+            (1, "Phones"),
+            (2, "Android"),
+            (1, "Laptops"),
+            (0, "Books"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_move_subtree() {
+    let (db, nodes) = setup().await;
+    let mut phones = nodes[3].clone();
+
+    phones
+        .move_to(Some(Value::Int(2)), None, &db)
+        .await
+        .unwrap();
+    assert_eq!(phones.parent_id, Some(2));
+    let books = &nodes[1];
+    assert_eq!(
+        names(&books.descendants(&db).await.unwrap()),
+        ["Phones", "Android"]
+    );
+    assert_eq!(
+        names(&nodes[4].ancestors(&db).await.unwrap()),
+        ["Books", "Phones"]
+    );
+
+    // Moving back to the front of Electronics' children renumbers siblings.
+    phones
+        .move_to(Some(Value::Int(1)), Some(0), &db)
+        .await
+        .unwrap();
+    let children = nodes[0].children(&db).await.unwrap();
+    assert_eq!(names(&children), ["Phones", "Laptops"]);
+    assert_eq!(
+        children.iter().map(|c| c.position).collect::<Vec<_>>(),
+        [0, 1]
+    );
+
+    // Moving to the roots.
+    phones.move_to(None, Some(1), &db).await.unwrap();
+    let roots: Vec<String> = Category::tree(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|entry| entry.depth == 0)
+        .map(|entry| entry.node.name)
+        .collect();
+    assert_eq!(roots, ["Electronics", "Phones", "Books"]);
+}
+
+#[tokio::test]
+async fn test_move_into_own_subtree_is_rejected() {
+    let (db, nodes) = setup().await;
+    let mut electronics = nodes[0].clone();
+    for target in [1, 4] {
+        let err = electronics
+            .move_to(Some(Value::Int(target)), None, &db)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DjangoError::DatabaseError(_)));
+    }
+}
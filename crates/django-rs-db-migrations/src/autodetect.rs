@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 
+use django_rs_core::DjangoError;
 use django_rs_db::fields::FieldType;
 use django_rs_db::model::Index;
 use django_rs_db::value::Value;
@@ -14,6 +15,7 @@ use crate::operations::{
     AddField, AddIndex, AlterField, AlterUniqueTogether, CreateModel, DeleteModel, Operation,
     RemoveField, RemoveIndex, RenameField,
 };
+use crate::questioner::MigrationQuestioner;
 
 /// A snapshot of the entire project's model state at a point in time.
 ///
@@ -49,6 +51,9 @@ pub struct ModelOptions {
     pub unique_together: Vec<Vec<String>>,
     /// Database indexes.
     pub indexes: Vec<Index>,
+    /// The database schema holding the table (PostgreSQL), if set.
+    #[serde(default)]
+    pub db_schema: Option<String>,
 }
 
 /// The state of a single model at a point in time.
@@ -91,11 +96,19 @@ impl ModelState {
     }
 
     /// Returns the database table name for this model.
+    ///
+    /// When a `db_schema` is set the name is schema-qualified, e.g.
+    /// `billing.invoice`.
     pub fn db_table(&self) -> String {
-        self.options
+        let table = self
+            .options
             .db_table
             .clone()
-            .unwrap_or_else(|| format!("{}_{}", self.app_label, self.name))
+            .unwrap_or_else(|| format!("{}_{}", self.app_label, self.name));
+        match self.options.db_schema {
+            Some(ref schema) => format!("{schema}.{table}"),
+            None => table,
+        }
     }
 }
 
@@ -236,9 +249,11 @@ impl MigrationFieldDef {
 /// - Added fields (creates `AddField`)
 /// - Removed fields (creates `RemoveField`)
 /// - Altered fields (creates `AlterField`)
-/// - Renamed fields (heuristic: same type + one removed + one added)
+/// - Renamed fields (asked through a [`MigrationQuestioner`], or by heuristic:
+///   same type + one removed + one added)
 /// - Changed `unique_together` (creates `AlterUniqueTogether`)
-/// - Added/removed indexes (creates `AddIndex` / `RemoveIndex`)
+/// - Added/removed indexes (creates `AddIndex` / `RemoveIndex`), and changed
+///   ones (a `RemoveIndex` followed by an `AddIndex`)
 pub struct MigrationAutodetector {
     /// The old project state (before changes).
     pub from_state: ProjectState,
@@ -257,7 +272,35 @@ impl MigrationAutodetector {
 
     /// Detects differences between the two states and returns operations
     /// grouped by app label.
+    ///
+    /// Nothing is asked: a model with exactly one removed and one added field
+    /// of the same type is treated as a rename, and new NOT NULL fields are
+    /// added as declared. Use [`detect_changes_with`](Self::detect_changes_with)
+    /// to resolve these through a [`MigrationQuestioner`].
     pub fn detect_changes(&self) -> HashMap<String, Vec<Box<dyn Operation>>> {
+        self.detect(None).unwrap_or_default()
+    }
+
+    /// Detects differences between the two states, asking `questioner` about
+    /// possible renames and about NOT NULL fields added without a default.
+    ///
+    /// Confirmed renames become `RenameField` operations, and one-off
+    /// defaults are attached to the `AddField` with `preserve_default` unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns the questioner's error if it declines to provide a default.
+    pub fn detect_changes_with(
+        &self,
+        questioner: &mut dyn MigrationQuestioner,
+    ) -> Result<HashMap<String, Vec<Box<dyn Operation>>>, DjangoError> {
+        self.detect(Some(questioner))
+    }
+
+    fn detect(
+        &self,
+        mut questioner: Option<&mut dyn MigrationQuestioner>,
+    ) -> Result<HashMap<String, Vec<Box<dyn Operation>>>, DjangoError> {
         let mut result: HashMap<String, Vec<Box<dyn Operation>>> = HashMap::new();
 
         // 1. Detect new models
@@ -300,60 +343,81 @@ impl MigrationAutodetector {
                     .map(|f| (f.name.as_str(), f))
                     .collect();
 
-                let mut added: Vec<&MigrationFieldDef> = Vec::new();
-                let mut removed: Vec<&MigrationFieldDef> = Vec::new();
-
-                // Find removed fields
-                for (name, field) in &old_fields {
-                    if !new_fields.contains_key(name) {
-                        removed.push(field);
-                    }
-                }
+                // Walk the field lists rather than the maps so that questions
+                // and operations come out in declaration order.
+                let added: Vec<&MigrationFieldDef> = new_model
+                    .fields
+                    .iter()
+                    .filter(|f| !old_fields.contains_key(f.name.as_str()))
+                    .collect();
+                let removed: Vec<&MigrationFieldDef> = old_model
+                    .fields
+                    .iter()
+                    .filter(|f| !new_fields.contains_key(f.name.as_str()))
+                    .collect();
 
-                // Find added fields
-                for (name, field) in &new_fields {
-                    if !old_fields.contains_key(name) {
-                        added.push(field);
+                let renames = match questioner.as_deref_mut() {
+                    Some(q) => ask_renames(&new_model.name, &added, &removed, q),
+                    // Without a questioner, only a lone removed/added pair of
+                    // the same type is taken to be a rename.
+                    None if added.len() == 1
+                        && removed.len() == 1
+                        && field_types_match(&added[0].field_type, &removed[0].field_type) =>
+                    {
+                        vec![(removed[0], added[0])]
                     }
-                }
-
-                // Detect renames (heuristic: same type, one removed + one added)
-                let mut renamed_old: Vec<String> = Vec::new();
-                let mut renamed_new: Vec<String> = Vec::new();
-
-                if added.len() == 1 && removed.len() == 1 {
-                    let a = added[0];
-                    let r = removed[0];
-                    if field_types_match(&a.field_type, &r.field_type) {
-                        result
-                            .entry(key.0.clone())
-                            .or_default()
-                            .push(Box::new(RenameField {
-                                model_name: new_model.name.clone(),
-                                old_name: r.name.clone(),
-                                new_name: a.name.clone(),
-                            }));
-                        renamed_old.push(r.name.clone());
-                        renamed_new.push(a.name.clone());
+                    None => Vec::new(),
+                };
+
+                for (old_field, new_field) in &renames {
+                    let ops = result.entry(key.0.clone()).or_default();
+                    ops.push(Box::new(RenameField {
+                        model_name: new_model.name.clone(),
+                        old_name: old_field.name.clone(),
+                        new_name: new_field.name.clone(),
+                    }));
+                    let mut old_as_new = (*old_field).clone();
+                    old_as_new.name.clone_from(&new_field.name);
+                    old_as_new.column.clone_from(&new_field.name);
+                    if fields_differ(&old_as_new, new_field) {
+                        ops.push(Box::new(AlterField {
+                            model_name: new_model.name.clone(),
+                            field_name: new_field.name.clone(),
+                            field: (*new_field).clone(),
+                        }));
                     }
                 }
 
                 // Emit AddField for truly new fields
                 for field in &added {
-                    if !renamed_new.contains(&field.name) {
-                        result
-                            .entry(key.0.clone())
-                            .or_default()
-                            .push(Box::new(AddField {
-                                model_name: new_model.name.clone(),
-                                field: (*field).clone(),
-                            }));
+                    if renames.iter().any(|(_, n)| n.name == field.name) {
+                        continue;
                     }
+                    let mut field = (*field).clone();
+                    let mut preserve_default = true;
+                    if let Some(q) = questioner.as_deref_mut() {
+                        if needs_default(&field) {
+                            if let Some(default) =
+                                q.ask_not_null_addition(&new_model.name, &field)?
+                            {
+                                field.default = Some(default);
+                                preserve_default = false;
+                            }
+                        }
+                    }
+                    result
+                        .entry(key.0.clone())
+                        .or_default()
+                        .push(Box::new(AddField {
+                            model_name: new_model.name.clone(),
+                            field,
+                            preserve_default,
+                        }));
                 }
 
                 // Emit RemoveField for truly removed fields
                 for field in &removed {
-                    if !renamed_old.contains(&field.name) {
+                    if !renames.iter().any(|(o, _)| o.name == field.name) {
                         result
                             .entry(key.0.clone())
                             .or_default()
@@ -391,24 +455,13 @@ impl MigrationAutodetector {
                         }));
                 }
 
-                // Detect index changes
-                let old_idx_names: Vec<Option<&str>> = old_model
-                    .options
-                    .indexes
-                    .iter()
-                    .map(|i| i.name.as_deref())
-                    .collect();
-                let new_idx_names: Vec<Option<&str>> = new_model
-                    .options
-                    .indexes
-                    .iter()
-                    .map(|i| i.name.as_deref())
-                    .collect();
-
-                // Removed indexes
+                // Detect index changes. Indexes are matched by name; one whose
+                // fields, expressions or condition changed is dropped and
+                // recreated, as an index cannot be altered in place.
+                // Removed or changed indexes
                 for idx in &old_model.options.indexes {
                     if let Some(ref idx_name) = idx.name {
-                        if !new_idx_names.contains(&Some(idx_name.as_str())) {
+                        if index_named(&new_model.options.indexes, idx_name) != Some(idx) {
                             result
                                 .entry(key.0.clone())
                                 .or_default()
@@ -420,10 +473,10 @@ impl MigrationAutodetector {
                     }
                 }
 
-                // Added indexes
+                // Added or changed indexes
                 for idx in &new_model.options.indexes {
                     if let Some(ref idx_name) = idx.name {
-                        if !old_idx_names.contains(&Some(idx_name.as_str())) {
+                        if index_named(&old_model.options.indexes, idx_name) != Some(idx) {
                             result
                                 .entry(key.0.clone())
                                 .or_default()
@@ -437,10 +490,65 @@ impl MigrationAutodetector {
             }
         }
 
-        result
+        Ok(result)
     }
 }
 
+/// Returns the index called `name`.
+fn index_named<'a>(indexes: &'a [Index], name: &str) -> Option<&'a Index> {
+    indexes.iter().find(|i| i.name.as_deref() == Some(name))
+}
+
+/// Asks about every same-typed (removed, added) pair on a model, pairing each
+/// field at most once.
+fn ask_renames<'a>(
+    model_name: &str,
+    added: &[&'a MigrationFieldDef],
+    removed: &[&'a MigrationFieldDef],
+    questioner: &mut dyn MigrationQuestioner,
+) -> Vec<(&'a MigrationFieldDef, &'a MigrationFieldDef)> {
+    let mut renames: Vec<(&MigrationFieldDef, &MigrationFieldDef)> = Vec::new();
+    for new_field in added {
+        for old_field in removed {
+            if renames.iter().any(|(o, _)| o.name == old_field.name)
+                || !is_rename_candidate(old_field, new_field)
+            {
+                continue;
+            }
+            if questioner.ask_rename(model_name, old_field, new_field) {
+                renames.push((old_field, new_field));
+                break;
+            }
+        }
+    }
+    renames
+}
+
+/// Returns `true` if `new` could be `old` under a different name.
+fn is_rename_candidate(old: &MigrationFieldDef, new: &MigrationFieldDef) -> bool {
+    field_types_match(&old.field_type, &new.field_type)
+        && old.null == new.null
+        && old.primary_key == new.primary_key
+        && old.unique == new.unique
+        && old.db_index == new.db_index
+        && old.max_length == new.max_length
+        && old.default == new.default
+}
+
+/// Returns `true` if adding `field` to a table with rows needs a default.
+fn needs_default(field: &MigrationFieldDef) -> bool {
+    !field.null
+        && field.default.is_none()
+        && !field.primary_key
+        && !matches!(
+            field.field_type,
+            FieldType::AutoField
+                | FieldType::BigAutoField
+                | FieldType::ManyToManyField { .. }
+                | FieldType::GeneratedField { .. }
+        )
+}
+
 /// Checks if two field types are structurally the same (for rename detection).
 fn field_types_match(a: &FieldType, b: &FieldType) -> bool {
     std::mem::discriminant(a) == std::mem::discriminant(b)
@@ -501,6 +609,16 @@ mod tests {
         assert_eq!(model.db_table(), "custom_table");
     }
 
+    #[test]
+    fn test_model_state_db_table_with_schema() {
+        let model = ModelState::new("billing", "invoice", vec![]).with_options(ModelOptions {
+            db_table: Some("invoice".into()),
+            db_schema: Some("billing".into()),
+            ..ModelOptions::default()
+        });
+        assert_eq!(model.db_table(), "billing.invoice");
+    }
+
     // ── MigrationFieldDef tests ─────────────────────────────────────
 
     #[test]
@@ -819,6 +937,39 @@ mod tests {
         assert!(ops.iter().any(|op| op.describe().contains("Remove index")));
     }
 
+    #[test]
+    fn test_detect_changed_index_condition() {
+        let with_indexes = |indexes| {
+            let mut state = ProjectState::new();
+            state.add_model(
+                ModelState::new("blog", "post", vec![]).with_options(ModelOptions {
+                    indexes,
+                    ..ModelOptions::default()
+                }),
+            );
+            state
+        };
+        let old = with_indexes(vec![
+            Index::new("idx_title", vec!["title"]),
+            Index::new("idx_slug", vec!["slug"]),
+        ]);
+        let new_state = with_indexes(vec![
+            Index::new("idx_title", vec!["title"]).condition("\"deleted_at\" IS NULL"),
+            Index::new("idx_slug", vec!["slug"]),
+        ]);
+
+        let detector = MigrationAutodetector::new(old, new_state);
+        let changes = detector.detect_changes();
+        let ops: Vec<String> = changes["blog"].iter().map(|op| op.describe()).collect();
+        assert_eq!(
+            ops,
+            vec![
+                "Remove index idx_title from post",
+                "Add index idx_title on post"
+            ]
+        );
+    }
+
     // ── Autodetector: no changes ────────────────────────────────────
 
     #[test]
@@ -916,6 +1067,173 @@ mod tests {
         assert_eq!(remove_count, 2);
     }
 
+    // ── Autodetector: questioner ────────────────────────────────────
+
+    /// Answers renames from a list of `(old, new)` names and supplies a
+    /// fixed default, recording every question asked.
+    #[derive(Default)]
+    struct ScriptedQuestioner {
+        renames: Vec<(&'static str, &'static str)>,
+        default: Option<Value>,
+        asked: Vec<String>,
+    }
+
+    impl MigrationQuestioner for ScriptedQuestioner {
+        fn ask_rename(
+            &mut self,
+            _model_name: &str,
+            old_field: &MigrationFieldDef,
+            new_field: &MigrationFieldDef,
+        ) -> bool {
+            self.asked
+                .push(format!("rename {} {}", old_field.name, new_field.name));
+            self.renames
+                .iter()
+                .any(|(o, n)| *o == old_field.name && *n == new_field.name)
+        }
+
+        fn ask_not_null_addition(
+            &mut self,
+            _model_name: &str,
+            field: &MigrationFieldDef,
+        ) -> Result<Option<Value>, DjangoError> {
+            self.asked.push(format!("default {}", field.name));
+            Ok(self.default.clone())
+        }
+    }
+
+    fn single_model(fields: Vec<MigrationFieldDef>) -> ProjectState {
+        let mut state = ProjectState::new();
+        state.add_model(ModelState::new("blog", "post", fields));
+        state
+    }
+
+    fn descriptions(ops: &[Box<dyn Operation>]) -> Vec<String> {
+        ops.iter().map(|op| op.describe()).collect()
+    }
+
+    #[test]
+    fn test_questioner_confirms_renames() {
+        let old = single_model(vec![
+            make_field("title", FieldType::CharField).max_length(200),
+            make_field("slug", FieldType::SlugField),
+        ]);
+        let new_state = single_model(vec![
+            make_field("headline", FieldType::CharField).max_length(200),
+            make_field("url_path", FieldType::SlugField),
+        ]);
+
+        let mut questioner = ScriptedQuestioner {
+            renames: vec![("title", "headline")],
+            ..ScriptedQuestioner::default()
+        };
+        let changes = MigrationAutodetector::new(old, new_state)
+            .detect_changes_with(&mut questioner)
+            .unwrap();
+        assert_eq!(
+            descriptions(&changes["blog"]),
+            vec![
+                "Rename field title to headline on post",
+                "Add field url_path to post",
+                "Remove field slug from post",
+            ]
+        );
+        assert_eq!(
+            questioner.asked,
+            vec![
+                "rename title headline",
+                "rename slug url_path",
+                "default url_path"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_questioner_skips_mismatched_rename_candidates() {
+        let old = single_model(vec![
+            make_field("title", FieldType::CharField).max_length(100)
+        ]);
+        let new_state = single_model(vec![
+            make_field("headline", FieldType::CharField).max_length(200)
+        ]);
+
+        let mut questioner = ScriptedQuestioner {
+            default: Some(Value::from("")),
+            ..ScriptedQuestioner::default()
+        };
+        MigrationAutodetector::new(old, new_state)
+            .detect_changes_with(&mut questioner)
+            .unwrap();
+        assert_eq!(questioner.asked, vec!["default headline"]);
+    }
+
+    #[test]
+    fn test_questioner_one_off_default() {
+        let old = single_model(vec![make_field("id", FieldType::BigAutoField).primary_key()]);
+        let new_state = single_model(vec![
+            make_field("id", FieldType::BigAutoField).primary_key(),
+            make_field("views", FieldType::IntegerField),
+            make_field("notes", FieldType::TextField).nullable(),
+        ]);
+
+        let mut questioner = ScriptedQuestioner {
+            default: Some(Value::Int(0)),
+            ..ScriptedQuestioner::default()
+        };
+        let changes = MigrationAutodetector::new(old.clone(), new_state)
+            .detect_changes_with(&mut questioner)
+            .unwrap();
+        assert_eq!(questioner.asked, vec!["default views"]);
+
+        let ops = &changes["blog"];
+        let sqls = ops[0]
+            .database_forwards(
+                "blog",
+                &crate::schema_editor::SqliteSchemaEditor,
+                &ProjectState::new(),
+                &ProjectState::new(),
+            )
+            .unwrap();
+        assert!(sqls[0].contains("DEFAULT 0"), "{}", sqls[0]);
+
+        // The one-off default is not kept in the migrated state.
+        let mut state = old;
+        for op in ops {
+            op.state_forwards("blog", &mut state);
+        }
+        let model = &state.models[&("blog".to_string(), "post".to_string())];
+        let views = model.fields.iter().find(|f| f.name == "views").unwrap();
+        assert!(views.default.is_none());
+    }
+
+    #[test]
+    fn test_non_interactive_questioner_rejects_not_null_addition() {
+        let old = single_model(vec![]);
+        let new_state = single_model(vec![make_field("views", FieldType::IntegerField)]);
+        let result = MigrationAutodetector::new(old, new_state)
+            .detect_changes_with(&mut crate::questioner::NonInteractiveQuestioner::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rename_with_changed_options_adds_alter_field() {
+        let old = single_model(vec![
+            make_field("title", FieldType::CharField).max_length(100)
+        ]);
+        let new_state = single_model(vec![
+            make_field("headline", FieldType::CharField).max_length(200)
+        ]);
+
+        let changes = MigrationAutodetector::new(old, new_state).detect_changes();
+        assert_eq!(
+            descriptions(&changes["blog"]),
+            vec![
+                "Rename field title to headline on post",
+                "Alter field headline on post",
+            ]
+        );
+    }
+
     // ── Autodetector: default change ────────────────────────────────
 
     #[test]
@@ -2,7 +2,9 @@
 //!
 //! The [`MigrationExecutor`] takes a [`MigrationPlan`] and applies or reverts
 //! migrations in the correct order. The [`MigrationRecorder`] tracks which
-//! migrations have been applied in the `django_migrations` table.
+//! migrations have been applied in the `django_migrations` table, along with
+//! an audit trail for each: the migration file's checksum, how long it took,
+//! who applied it and with which django-rs version.
 //!
 //! ## Async Execution
 //!
@@ -11,14 +13,29 @@
 //! [`DatabaseBackend`](django_rs_db_backends::DatabaseBackend) and executes
 //! each generated SQL statement. The recorder persists applied migrations
 //! to the `django_migrations` table.
+//!
+//! Each migration runs in its own transaction, together with its
+//! `django_migrations` row, when the backend supports transactional DDL
+//! (PostgreSQL and SQLite). Migrations marked non-atomic (see
+//! [`Migration::non_atomic`]) run statement by statement instead, which
+//! `CREATE INDEX CONCURRENTLY` requires.
+//!
+//! While a plan runs, the executor holds a database-wide [`MigrationLock`], so
+//! two processes deploying at the same time apply each migration once: the
+//! second waits for the first, then skips the migrations it finds already
+//! recorded. The lock and the transactions belong to a database session, so
+//! the whole plan runs on one connection taken with
+//! [`DatabaseBackend::pin`](django_rs_db_backends::DatabaseBackend::pin).
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use django_rs_core::DjangoError;
+use django_rs_db::value::Value;
 use django_rs_db_backends::DatabaseBackend;
 
 use crate::autodetect::ProjectState;
-use crate::migration::MigrationGraph;
+use crate::migration::{Migration, MigrationGraph};
 use crate::operations::Operation;
 use crate::schema_editor::SchemaEditor;
 
@@ -95,6 +112,10 @@ pub struct MigrationExecutor {
     schema_editor: Box<dyn SchemaEditor>,
     /// The recorder tracking applied migrations.
     recorder: MigrationRecorder,
+    /// Migration file checksums, recorded when a migration is applied.
+    checksums: HashMap<(String, String), String>,
+    /// Migrations that must not run inside a transaction.
+    non_atomic: HashSet<(String, String)>,
 }
 
 impl MigrationExecutor {
@@ -103,6 +124,8 @@ impl MigrationExecutor {
         Self {
             schema_editor,
             recorder: MigrationRecorder::new(),
+            checksums: HashMap::new(),
+            non_atomic: HashSet::new(),
         }
     }
 
@@ -114,9 +137,33 @@ impl MigrationExecutor {
         Self {
             schema_editor,
             recorder,
+            checksums: HashMap::new(),
+            non_atomic: HashSet::new(),
         }
     }
 
+    /// Sets the migration file checksums to record, usually from
+    /// [`MigrationLoader::checksums`](crate::loader::MigrationLoader::checksums).
+    #[must_use]
+    pub fn with_checksums(mut self, checksums: HashMap<(String, String), String>) -> Self {
+        self.checksums = checksums;
+        self
+    }
+
+    /// Sets the migrations to run outside a transaction, usually from
+    /// [`MigrationLoader::non_atomic`](crate::loader::MigrationLoader::non_atomic).
+    #[must_use]
+    pub fn with_non_atomic(mut self, non_atomic: HashSet<(String, String)>) -> Self {
+        self.non_atomic = non_atomic;
+        self
+    }
+
+    /// Returns whether a migration runs in a transaction (when the backend
+    /// supports transactional DDL).
+    pub fn is_atomic(&self, key: &(String, String)) -> bool {
+        !self.non_atomic.contains(key)
+    }
+
     /// Creates a migration plan to reach the target state from the current state.
     ///
     /// If `target` is `None`, applies all unapplied migrations. If `target` is
@@ -242,6 +289,90 @@ impl MigrationExecutor {
         Ok(all_sql)
     }
 
+    /// Returns the SQL that applying a single migration would run, or with
+    /// `backwards` the SQL that unapplying it would run, as printed by
+    /// `sqlmigrate`.
+    ///
+    /// `state` is the project state before the migration. Each operation's
+    /// statements follow a comment naming the operation, and the whole output
+    /// is wrapped in `BEGIN;` / `COMMIT;` when the migration is atomic and the
+    /// backend supports transactional DDL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the operations that cannot be expressed as
+    /// SQL (see [`Operation::reduces_to_sql`]), or any error raised while
+    /// generating the statements, e.g. for an irreversible operation.
+    pub fn collect_sql(
+        &self,
+        migration: &Migration,
+        state: &ProjectState,
+        backwards: bool,
+    ) -> Result<Vec<String>, DjangoError> {
+        let unsupported: Vec<String> = migration
+            .operations
+            .iter()
+            .filter(|op| !op.reduces_to_sql())
+            .map(|op| op.describe())
+            .collect();
+        if !unsupported.is_empty() {
+            return Err(DjangoError::DatabaseError(format!(
+                "Migration {}.{} has operations that cannot be represented as SQL: {}",
+                migration.app_label,
+                migration.name,
+                unsupported.join(", ")
+            )));
+        }
+
+        // The state before and after each operation, in forward order.
+        let mut states = Vec::with_capacity(migration.operations.len());
+        let mut current = state.clone();
+        for op in &migration.operations {
+            let before = current.clone();
+            op.state_forwards(&migration.app_label, &mut current);
+            states.push((before, current.clone()));
+        }
+
+        let atomic = migration.atomic
+            && self
+                .schema_editor
+                .backend_type()
+                .supports_transactional_ddl();
+        let mut sql = Vec::new();
+        if atomic {
+            sql.push("BEGIN;".to_string());
+        }
+        let mut steps: Vec<_> = migration.operations.iter().zip(&states).collect();
+        if backwards {
+            steps.reverse();
+        }
+        for (op, (before, after)) in steps {
+            sql.push("--".to_string());
+            let statements = if backwards {
+                sql.push(format!("-- Undo: {}", op.describe()));
+                op.database_backwards(&migration.app_label, &*self.schema_editor, before, after)?
+            } else {
+                sql.push(format!("-- {}", op.describe()));
+                op.database_forwards(&migration.app_label, &*self.schema_editor, before, after)?
+            };
+            sql.push("--".to_string());
+            if statements.is_empty() {
+                sql.push("-- (no-op)".to_string());
+            }
+            for statement in statements {
+                if statement.starts_with("--") || statement.ends_with(';') {
+                    sql.push(statement);
+                } else {
+                    sql.push(format!("{statement};"));
+                }
+            }
+        }
+        if atomic {
+            sql.push("COMMIT;".to_string());
+        }
+        Ok(sql)
+    }
+
     /// Returns a reference to the recorder.
     pub fn recorder(&self) -> &MigrationRecorder {
         &self.recorder
@@ -256,10 +387,22 @@ impl MigrationExecutor {
     ///
     /// For each step in the plan, generates SQL via the schema editor, executes
     /// each statement against the backend, and records the migration in the
-    /// `django_migrations` table.
+    /// `django_migrations` table. Atomic migrations run in a transaction on
+    /// backends with transactional DDL, so a failing migration leaves neither
+    /// schema changes nor a record behind.
+    ///
+    /// The plan runs on a single pinned connection, under the
+    /// [`MigrationLock`]. Once it is acquired, forward steps already recorded
+    /// in the database (by a concurrent deploy) are skipped.
     ///
     /// If `fake` is `true`, the migration is recorded as applied without
     /// executing the SQL statements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a statement fails, if an atomic migration contains
+    /// a statement that cannot run in a transaction (`CONCURRENTLY`), or if
+    /// the lock cannot be acquired.
     pub async fn execute_against_db(
         &mut self,
         plan: &MigrationPlan,
@@ -268,9 +411,41 @@ impl MigrationExecutor {
         backend: &dyn DatabaseBackend,
         fake: bool,
     ) -> Result<Vec<String>, DjangoError> {
+        // The lock and each BEGIN/COMMIT must reach the same session
+        let conn = backend.pin().await?;
+        let backend = &*conn;
+
         // Ensure the django_migrations table exists
         self.recorder.ensure_table(backend).await?;
 
+        MigrationLock::acquire(backend).await?;
+        let result = self
+            .execute_locked(plan, operations, initial_state, backend, fake)
+            .await;
+        let released = MigrationLock::release(backend).await;
+        let all_sql = result?;
+        released?;
+        Ok(all_sql)
+    }
+
+    /// Runs the plan for [`execute_against_db`](Self::execute_against_db)
+    /// while holding the migration lock.
+    async fn execute_locked(
+        &mut self,
+        plan: &MigrationPlan,
+        operations: &std::collections::HashMap<(String, String), Vec<Box<dyn Operation>>>,
+        initial_state: &ProjectState,
+        backend: &dyn DatabaseBackend,
+        fake: bool,
+    ) -> Result<Vec<String>, DjangoError> {
+        let mut recorded: HashMap<(String, String), AppliedMigration> = self
+            .recorder
+            .fetch_records(backend)
+            .await?
+            .into_iter()
+            .map(|record| (record.key(), record))
+            .collect();
+
         let mut all_sql = Vec::new();
         let mut state = initial_state.clone();
 
@@ -284,6 +459,7 @@ impl MigrationExecutor {
 
             let from_state = state.clone();
             let mut step_sql = Vec::new();
+            let started = Instant::now();
 
             if step.backwards {
                 // Generate backwards SQL
@@ -308,36 +484,257 @@ impl MigrationExecutor {
                     )?;
                     step_sql.extend(sql);
                 }
+
+                // Applied by another process while we waited for the lock
+                if let Some(record) = recorded.remove(&step.migration) {
+                    self.recorder.apply_record(record);
+                    continue;
+                }
             }
 
-            // Execute unless faking
-            if !fake {
-                for sql in &step_sql {
-                    // Skip SQL comment lines (e.g. SQLite recreation hints)
-                    if sql.starts_with("--") {
-                        continue;
-                    }
-                    backend.execute(sql, &[]).await?;
+            let atomic = !fake
+                && self.is_atomic(&step.migration)
+                && backend.backend_type().supports_transactional_ddl();
+            if atomic {
+                if let Some(sql) = step_sql.iter().find(|sql| sql.contains("CONCURRENTLY")) {
+                    return Err(DjangoError::DatabaseError(format!(
+                        "Migration {}.{} runs in a transaction, which `{sql}` cannot; \
+                         mark the migration non-atomic",
+                        step.migration.0, step.migration.1
+                    )));
                 }
+                backend.begin_transaction().await?;
             }
+            let result = self.run_step(step, &step_sql, backend, fake, started).await;
+            let record = if atomic {
+                match result {
+                    Ok(record) => {
+                        backend.commit().await?;
+                        record
+                    }
+                    Err(e) => {
+                        // Surface the migration's error, not the rollback's
+                        let _ = backend.rollback().await;
+                        return Err(e);
+                    }
+                }
+            } else {
+                result?
+            };
             all_sql.extend(step_sql);
 
-            // Update in-memory state and database record
-            if step.backwards {
-                self.recorder.unapply(&step.migration);
-                self.recorder
-                    .unrecord_from_db(backend, &step.migration.0, &step.migration.1)
-                    .await?;
-            } else {
-                self.recorder.apply(step.migration.clone());
-                self.recorder
-                    .record_to_db(backend, &step.migration.0, &step.migration.1)
-                    .await?;
+            // Update in-memory state
+            match record {
+                Some(record) => self.recorder.apply_record(record),
+                None => self.recorder.unapply(&step.migration),
             }
         }
 
         Ok(all_sql)
     }
+
+    /// Executes one step's statements (unless faking) and updates its
+    /// `django_migrations` row.
+    ///
+    /// Returns the record written for a forward step, `None` for a backward
+    /// one.
+    async fn run_step(
+        &self,
+        step: &MigrationStep,
+        step_sql: &[String],
+        backend: &dyn DatabaseBackend,
+        fake: bool,
+        started: Instant,
+    ) -> Result<Option<AppliedMigration>, DjangoError> {
+        // Execute unless faking
+        if !fake {
+            for sql in step_sql {
+                // Skip SQL comment lines (e.g. SQLite recreation hints)
+                if sql.starts_with("--") {
+                    continue;
+                }
+                backend.execute(sql, &[]).await?;
+            }
+        }
+
+        if step.backwards {
+            self.recorder
+                .unrecord_from_db(backend, &step.migration.0, &step.migration.1)
+                .await?;
+            Ok(None)
+        } else {
+            let record = AppliedMigration::new(&step.migration.0, &step.migration.1)
+                .checksum(self.checksums.get(&step.migration).cloned())
+                .duration(started.elapsed());
+            self.recorder.record_applied_to_db(backend, &record).await?;
+            Ok(Some(record))
+        }
+    }
+}
+
+/// The database-wide lock held while migrations are applied.
+///
+/// On PostgreSQL this is a session-level advisory lock, on MySQL a named
+/// `GET_LOCK` lock. Both wait until the lock is free. SQLite needs no lock:
+/// it allows a single writer, and the migrating process is local.
+///
+/// Both locks belong to the connection that took them, so [`acquire`] and
+/// [`release`] must run on the same pinned backend (see
+/// [`DatabaseBackend::pin`]). Behind a pooler in transaction mode there is no
+/// such session; migrate over a direct connection instead.
+///
+/// [`acquire`]: Self::acquire
+/// [`release`]: Self::release
+pub struct MigrationLock;
+
+impl MigrationLock {
+    /// The PostgreSQL advisory lock key (the ASCII bytes of `"djrsmigr"`).
+    pub const ADVISORY_KEY: i64 = 0x646a_7273_6d69_6772;
+
+    /// The MySQL lock name.
+    pub const NAME: &'static str = "django_migrations";
+
+    /// Returns the SQL that acquires the lock on `vendor`, if it needs one.
+    pub fn acquire_sql(vendor: &str) -> Option<String> {
+        match vendor {
+            "postgresql" => Some(format!("SELECT pg_advisory_lock({})", Self::ADVISORY_KEY)),
+            "mysql" => Some(format!("SELECT GET_LOCK('{}', -1) AS acquired", Self::NAME)),
+            _ => None,
+        }
+    }
+
+    /// Returns the SQL that releases the lock on `vendor`, if it needs one.
+    pub fn release_sql(vendor: &str) -> Option<String> {
+        match vendor {
+            "postgresql" => Some(format!("SELECT pg_advisory_unlock({})", Self::ADVISORY_KEY)),
+            "mysql" => Some(format!("SELECT RELEASE_LOCK('{}')", Self::NAME)),
+            _ => None,
+        }
+    }
+
+    /// Acquires the lock, waiting while another process holds it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock query fails or MySQL refuses the lock.
+    pub async fn acquire(backend: &dyn DatabaseBackend) -> Result<(), DjangoError> {
+        let Some(sql) = Self::acquire_sql(backend.vendor()) else {
+            return Ok(());
+        };
+        let row = backend.query_one(&sql, &[]).await?;
+        if backend.vendor() == "mysql" && row.get::<Value>("acquired")? != Value::Int(1) {
+            return Err(DjangoError::DatabaseError(format!(
+                "Could not acquire the '{}' migration lock",
+                Self::NAME
+            )));
+        }
+        Ok(())
+    }
+
+    /// Releases the lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the unlock query fails.
+    pub async fn release(backend: &dyn DatabaseBackend) -> Result<(), DjangoError> {
+        if let Some(sql) = Self::release_sql(backend.vendor()) {
+            backend.query(&sql, &[]).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The django-rs version recorded with each applied migration.
+pub const DJANGO_RS_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The audit columns added to `django_migrations`, with their SQL types.
+///
+/// Tables created before these columns existed are upgraded in place by
+/// [`MigrationRecorder::ensure_table`].
+const HISTORY_COLUMNS: &[(&str, &str)] = &[
+    ("checksum", "VARCHAR(64) NULL"),
+    ("duration_ms", "BIGINT NULL"),
+    ("applied_by", "VARCHAR(255) NULL"),
+    ("version", "VARCHAR(32) NULL"),
+];
+
+/// A row of the `django_migrations` schema history table.
+///
+/// Rows written before the audit columns existed have `None` for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// The app label.
+    pub app: String,
+    /// The migration name.
+    pub name: String,
+    /// When the migration was applied, as reported by the database.
+    pub applied: Option<String>,
+    /// SHA-256 checksum of the migration file when it was applied.
+    pub checksum: Option<String>,
+    /// How long applying the migration took, in milliseconds.
+    pub duration_ms: Option<i64>,
+    /// The `user@host` that applied the migration.
+    pub applied_by: Option<String>,
+    /// The django-rs version that applied the migration.
+    pub version: Option<String>,
+}
+
+impl AppliedMigration {
+    /// Creates a record for a migration applied now, by the current user and
+    /// host, with this django-rs version.
+    pub fn new(app: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            app: app.into(),
+            name: name.into(),
+            applied: None,
+            checksum: None,
+            duration_ms: None,
+            applied_by: Some(current_applier()),
+            version: Some(DJANGO_RS_VERSION.to_string()),
+        }
+    }
+
+    /// Sets the migration file checksum.
+    #[must_use]
+    pub fn checksum(mut self, checksum: Option<String>) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    /// Sets how long the migration took to apply.
+    #[must_use]
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration_ms = Some(i64::try_from(duration.as_millis()).unwrap_or(i64::MAX));
+        self
+    }
+
+    /// Returns the `(app, name)` key of the migration.
+    pub fn key(&self) -> (String, String) {
+        (self.app.clone(), self.name.clone())
+    }
+}
+
+/// Returns `user@host` for the process applying migrations.
+fn current_applier() -> String {
+    let env = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    format!(
+        "{}@{}",
+        env(&["USER", "USERNAME"]),
+        env(&["HOSTNAME", "COMPUTERNAME"])
+    )
+}
+
+/// Quotes an optional string as a SQL literal.
+fn sql_literal(value: Option<&str>) -> String {
+    value.map_or_else(
+        || "NULL".to_string(),
+        |v| format!("'{}'", v.replace('\'', "''")),
+    )
 }
 
 /// Tracks which migrations have been applied.
@@ -349,6 +746,8 @@ impl MigrationExecutor {
 pub struct MigrationRecorder {
     /// Set of applied migration keys.
     applied_migrations: HashSet<(String, String)>,
+    /// Schema history rows for applied migrations, when known.
+    records: HashMap<(String, String), AppliedMigration>,
 }
 
 impl MigrationRecorder {
@@ -356,6 +755,7 @@ impl MigrationRecorder {
     pub fn new() -> Self {
         Self {
             applied_migrations: HashSet::new(),
+            records: HashMap::new(),
         }
     }
 
@@ -368,7 +768,11 @@ impl MigrationRecorder {
                 \"id\" BIGSERIAL PRIMARY KEY, \
                 \"app\" VARCHAR(255) NOT NULL, \
                 \"name\" VARCHAR(255) NOT NULL, \
-                \"applied\" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP\
+                \"applied\" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, \
+                \"checksum\" VARCHAR(64) NULL, \
+                \"duration_ms\" BIGINT NULL, \
+                \"applied_by\" VARCHAR(255) NULL, \
+                \"version\" VARCHAR(32) NULL\
             )"
         .to_string()]
     }
@@ -379,7 +783,11 @@ impl MigrationRecorder {
             \"id\" INTEGER PRIMARY KEY AUTOINCREMENT, \
             \"app\" TEXT NOT NULL, \
             \"name\" TEXT NOT NULL, \
-            \"applied\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP\
+            \"applied\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP, \
+            \"checksum\" TEXT NULL, \
+            \"duration_ms\" INTEGER NULL, \
+            \"applied_by\" TEXT NULL, \
+            \"version\" TEXT NULL\
         )"
     }
 
@@ -388,9 +796,47 @@ impl MigrationRecorder {
         self.applied_migrations.insert(key);
     }
 
+    /// Records a migration as applied with its history row (in-memory only).
+    pub fn apply_record(&mut self, record: AppliedMigration) {
+        let key = record.key();
+        self.applied_migrations.insert(key.clone());
+        self.records.insert(key, record);
+    }
+
     /// Records a migration as unapplied (in-memory only).
     pub fn unapply(&mut self, key: &(String, String)) {
         self.applied_migrations.remove(key);
+        self.records.remove(key);
+    }
+
+    /// Returns the schema history row for an applied migration, if known.
+    pub fn record(&self, key: &(String, String)) -> Option<&AppliedMigration> {
+        self.records.get(key)
+    }
+
+    /// Returns the applied migrations whose file changed since they were
+    /// applied, sorted by key.
+    ///
+    /// `checksums` holds the current file checksums, e.g. from
+    /// [`MigrationLoader::checksums`](crate::loader::MigrationLoader::checksums).
+    /// Migrations recorded without a checksum are never reported.
+    pub fn checksum_mismatches<S: std::hash::BuildHasher>(
+        &self,
+        checksums: &HashMap<(String, String), String, S>,
+    ) -> Vec<(String, String)> {
+        let mut mismatched: Vec<_> = self
+            .records
+            .iter()
+            .filter(|(key, record)| {
+                matches!(
+                    (&record.checksum, checksums.get(*key)),
+                    (Some(recorded), Some(current)) if recorded != current
+                )
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        mismatched.sort();
+        mismatched
     }
 
     /// Returns the set of applied migrations.
@@ -411,6 +857,23 @@ impl MigrationRecorder {
         )
     }
 
+    /// Returns the SQL to record a migration as applied with its audit columns.
+    pub fn record_migration_sql(record: &AppliedMigration) -> String {
+        format!(
+            "INSERT INTO \"django_migrations\" \
+             (\"app\", \"name\", \"applied\", \"checksum\", \"duration_ms\", \"applied_by\", \"version\") \
+             VALUES ({}, {}, CURRENT_TIMESTAMP, {}, {}, {}, {})",
+            sql_literal(Some(&record.app)),
+            sql_literal(Some(&record.name)),
+            sql_literal(record.checksum.as_deref()),
+            record
+                .duration_ms
+                .map_or_else(|| "NULL".to_string(), |ms| ms.to_string()),
+            sql_literal(record.applied_by.as_deref()),
+            sql_literal(record.version.as_deref()),
+        )
+    }
+
     /// Returns the SQL to record a migration as unapplied.
     pub fn record_unapplied_sql(app_label: &str, name: &str) -> String {
         format!(
@@ -430,6 +893,20 @@ impl MigrationRecorder {
             _ => Self::ensure_schema_sql()[0].clone(),
         };
         backend.execute(&sql, &[]).await?;
+        self.upgrade_table(backend).await
+    }
+
+    /// Adds any audit columns missing from a `django_migrations` table
+    /// created by an older version.
+    async fn upgrade_table(&self, backend: &dyn DatabaseBackend) -> Result<(), DjangoError> {
+        for (column, sql_type) in HISTORY_COLUMNS {
+            let probe = format!("SELECT \"{column}\" FROM \"django_migrations\" LIMIT 1");
+            if backend.query(&probe, &[]).await.is_err() {
+                let alter =
+                    format!("ALTER TABLE \"django_migrations\" ADD COLUMN \"{column}\" {sql_type}");
+                backend.execute(&alter, &[]).await?;
+            }
+        }
         Ok(())
     }
 
@@ -439,12 +916,73 @@ impl MigrationRecorder {
     /// If the table does not exist, it is created first.
     pub async fn load_from_db(&mut self, backend: &dyn DatabaseBackend) -> Result<(), DjangoError> {
         self.ensure_table(backend).await?;
+        let records = self.fetch_records(backend).await?;
+
+        self.applied_migrations.clear();
+        self.records.clear();
+        for record in records {
+            self.apply_record(record);
+        }
+
+        Ok(())
+    }
+
+    /// Loads applied migrations from the database without changing its
+    /// schema.
+    ///
+    /// Unlike [`load_from_db`](Self::load_from_db), the `django_migrations`
+    /// table is never created or upgraded, so this works for roles without DDL
+    /// rights. A missing table reads as no migrations applied.
+    pub async fn read_from_db(&mut self, backend: &dyn DatabaseBackend) -> Result<(), DjangoError> {
+        let records = if Self::table_exists(backend).await? {
+            self.fetch_records(backend).await?
+        } else {
+            Vec::new()
+        };
 
+        self.applied_migrations.clear();
+        self.records.clear();
+        for record in records {
+            self.apply_record(record);
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the `django_migrations` table exists, by asking the
+    /// database catalog.
+    pub async fn table_exists(backend: &dyn DatabaseBackend) -> Result<bool, DjangoError> {
+        let sql = match backend.vendor() {
+            "sqlite" => {
+                "SELECT name FROM sqlite_master \
+                 WHERE type = 'table' AND name = 'django_migrations'"
+            }
+            "mysql" => {
+                "SELECT table_name AS name FROM information_schema.tables \
+                 WHERE table_schema = DATABASE() AND table_name = 'django_migrations'"
+            }
+            _ => {
+                "SELECT table_name AS name FROM information_schema.tables \
+                 WHERE table_schema = current_schema() AND table_name = 'django_migrations'"
+            }
+        };
+        Ok(!backend.query(sql, &[]).await?.is_empty())
+    }
+
+    /// Reads the rows of the `django_migrations` table, without changing the
+    /// in-memory set.
+    ///
+    /// Audit columns missing from a table created by an older version read as
+    /// `None`.
+    pub async fn fetch_records(
+        &self,
+        backend: &dyn DatabaseBackend,
+    ) -> Result<Vec<AppliedMigration>, DjangoError> {
         let rows = backend
-            .query("SELECT \"app\", \"name\" FROM \"django_migrations\"", &[])
+            .query("SELECT * FROM \"django_migrations\"", &[])
             .await?;
 
-        self.applied_migrations.clear();
+        let mut records = Vec::with_capacity(rows.len());
         for row in &rows {
             let app: String = row
                 .get("app")
@@ -452,9 +990,34 @@ impl MigrationRecorder {
             let name: String = row
                 .get("name")
                 .map_err(|_| DjangoError::DatabaseError("Missing 'name' column".into()))?;
-            self.applied_migrations.insert((app, name));
+            let applied = match row.get::<Value>("applied") {
+                Ok(Value::String(s)) => Some(s),
+                Ok(Value::Null) | Err(_) => None,
+                Ok(other) => Some(other.to_string()),
+            };
+            records.push(AppliedMigration {
+                app,
+                name,
+                applied,
+                checksum: row.get("checksum").unwrap_or_default(),
+                duration_ms: row.get("duration_ms").unwrap_or_default(),
+                applied_by: row.get("applied_by").unwrap_or_default(),
+                version: row.get("version").unwrap_or_default(),
+            });
         }
 
+        Ok(records)
+    }
+
+    /// Records a migration as applied in the database, with its audit columns.
+    pub async fn record_applied_to_db(
+        &self,
+        backend: &dyn DatabaseBackend,
+        record: &AppliedMigration,
+    ) -> Result<(), DjangoError> {
+        backend
+            .execute(&Self::record_migration_sql(record), &[])
+            .await?;
         Ok(())
     }
 
@@ -487,10 +1050,11 @@ impl MigrationRecorder {
 mod tests {
     use super::*;
     use crate::autodetect::{MigrationFieldDef, ModelOptions};
-    use crate::migration::MigrationGraph;
-    use crate::operations::{AddField, CreateModel, RunSQL};
-    use crate::schema_editor::PostgresSchemaEditor;
+    use crate::operations::{AddField, CreateModel, RunRust, RunSQL};
+    use crate::schema_editor::{MySqlSchemaEditor, PostgresSchemaEditor};
     use django_rs_db::fields::FieldType;
+    use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
+    use django_rs_db_backends::Transaction;
 
     // ── MigrationStep tests ─────────────────────────────────────────
 
@@ -577,6 +1141,49 @@ mod tests {
         assert!(sql.contains("0001_initial"));
     }
 
+    #[test]
+    fn test_recorder_record_migration_sql() {
+        let mut record = AppliedMigration::new("blog", "0001_initial")
+            .checksum(Some("abc".into()))
+            .duration(Duration::from_millis(42));
+        record.applied_by = Some("o'brien@db1".into());
+        let sql = MigrationRecorder::record_migration_sql(&record);
+        assert!(sql.contains("'abc', 42, 'o''brien@db1'"));
+        assert!(sql.contains(&format!("'{DJANGO_RS_VERSION}'")));
+
+        let bare = MigrationRecorder::record_migration_sql(&AppliedMigration {
+            checksum: None,
+            ..AppliedMigration::new("blog", "0002")
+        });
+        assert!(bare.contains("CURRENT_TIMESTAMP, NULL, NULL"));
+    }
+
+    #[test]
+    fn test_recorder_checksum_mismatches() {
+        let mut recorder = MigrationRecorder::new();
+        recorder.apply_record(AppliedMigration::new("blog", "0001").checksum(Some("aaa".into())));
+        recorder.apply_record(AppliedMigration::new("blog", "0002").checksum(Some("bbb".into())));
+        recorder.apply_record(AppliedMigration::new("blog", "0003"));
+        assert!(recorder.is_applied(&("blog".into(), "0003".into())));
+
+        let current = HashMap::from([
+            (("blog".to_string(), "0001".to_string()), "aaa".to_string()),
+            (
+                ("blog".to_string(), "0002".to_string()),
+                "changed".to_string(),
+            ),
+            (("blog".to_string(), "0003".to_string()), "ccc".to_string()),
+        ]);
+        assert_eq!(
+            recorder.checksum_mismatches(&current),
+            vec![("blog".to_string(), "0002".to_string())]
+        );
+
+        recorder.unapply(&("blog".into(), "0002".into()));
+        assert!(recorder.record(&("blog".into(), "0002".into())).is_none());
+        assert!(recorder.checksum_mismatches(&current).is_empty());
+    }
+
     #[test]
     fn test_recorder_default() {
         let recorder = MigrationRecorder::default();
@@ -718,6 +1325,7 @@ mod tests {
         let ops2: Vec<Box<dyn Operation>> = vec![Box::new(AddField {
             model_name: "post".into(),
             field: MigrationFieldDef::new("title", FieldType::CharField).max_length(200),
+            preserve_default: true,
         })];
 
         let mut operations = std::collections::HashMap::new();
@@ -779,4 +1387,240 @@ mod tests {
         let result = executor.execute_plan(&plan, &operations, &state);
         assert!(result.is_err());
     }
+
+    fn post_migration() -> Migration {
+        Migration::new("blog", "0001_initial")
+            .add_operation(Box::new(CreateModel {
+                name: "post".into(),
+                fields: vec![MigrationFieldDef::new("id", FieldType::BigAutoField).primary_key()],
+                options: ModelOptions::default(),
+            }))
+            .add_operation(Box::new(AddField {
+                model_name: "post".into(),
+                field: MigrationFieldDef::new("title", FieldType::CharField).max_length(200),
+                preserve_default: true,
+            }))
+    }
+
+    #[test]
+    fn test_executor_collect_sql_forwards() {
+        let executor = MigrationExecutor::new(Box::new(PostgresSchemaEditor));
+        let sql = executor
+            .collect_sql(&post_migration(), &ProjectState::new(), false)
+            .unwrap();
+        assert_eq!(sql.first().unwrap(), "BEGIN;");
+        assert_eq!(sql.last().unwrap(), "COMMIT;");
+        let create = sql
+            .iter()
+            .position(|s| s == "-- Create model post")
+            .unwrap();
+        let add = sql
+            .iter()
+            .position(|s| s.starts_with("-- Add field title"))
+            .unwrap();
+        assert!(create < add);
+        assert!(sql[create + 2].starts_with("CREATE TABLE"));
+        assert!(sql[create + 2].ends_with(';'));
+        assert!(sql[add + 2].contains("ADD COLUMN"));
+    }
+
+    #[test]
+    fn test_executor_collect_sql_backwards() {
+        let executor = MigrationExecutor::new(Box::new(PostgresSchemaEditor));
+        let sql = executor
+            .collect_sql(&post_migration(), &ProjectState::new(), true)
+            .unwrap();
+        let drop_column = sql.iter().position(|s| s.contains("DROP COLUMN")).unwrap();
+        let drop_table = sql
+            .iter()
+            .position(|s| s.starts_with("DROP TABLE"))
+            .unwrap();
+        assert!(drop_column < drop_table);
+        assert!(sql.contains(&"-- Undo: Create model post".to_string()));
+    }
+
+    #[test]
+    fn test_executor_collect_sql_no_transaction_on_mysql() {
+        let executor = MigrationExecutor::new(Box::new(MySqlSchemaEditor));
+        let sql = executor
+            .collect_sql(&post_migration(), &ProjectState::new(), false)
+            .unwrap();
+        assert!(!sql.iter().any(|s| s == "BEGIN;" || s == "COMMIT;"));
+        assert!(sql.iter().any(|s| s.starts_with("CREATE TABLE")));
+    }
+
+    #[test]
+    fn test_executor_collect_sql_non_atomic_migration() {
+        let executor = MigrationExecutor::new(Box::new(PostgresSchemaEditor));
+        let sql = executor
+            .collect_sql(&post_migration().non_atomic(), &ProjectState::new(), false)
+            .unwrap();
+        assert!(!sql.iter().any(|s| s == "BEGIN;" || s == "COMMIT;"));
+        assert!(sql.iter().any(|s| s.starts_with("CREATE TABLE")));
+    }
+
+    #[test]
+    fn test_executor_with_non_atomic() {
+        let key = ("blog".to_string(), "0002_concurrent_index".to_string());
+        let executor = MigrationExecutor::new(Box::new(PostgresSchemaEditor))
+            .with_non_atomic(HashSet::from([key.clone()]));
+        assert!(!executor.is_atomic(&key));
+        assert!(executor.is_atomic(&("blog".into(), "0001_initial".into())));
+    }
+
+    /// A pooled backend that logs which connection each statement ran on.
+    #[derive(Default)]
+    struct PooledLog {
+        statements: std::sync::Mutex<Vec<(&'static str, String)>>,
+    }
+
+    impl PooledLog {
+        fn log(&self, conn: &'static str, sql: &str) -> Vec<django_rs_db::Row> {
+            self.statements
+                .lock()
+                .unwrap()
+                .push((conn, sql.to_string()));
+            if sql.starts_with("SELECT pg_advisory") {
+                vec![django_rs_db::Row::new(
+                    vec!["locked".into()],
+                    vec![Value::Null],
+                )]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    struct Conn<'a>(&'a PooledLog, &'static str);
+
+    #[async_trait::async_trait]
+    impl DatabaseBackend for Conn<'_> {
+        fn vendor(&self) -> &str {
+            "postgresql"
+        }
+
+        fn backend_type(&self) -> DatabaseBackendType {
+            DatabaseBackendType::PostgreSQL
+        }
+
+        async fn execute(&self, sql: &str, _: &[Value]) -> Result<u64, DjangoError> {
+            self.0.log(self.1, sql);
+            Ok(0)
+        }
+
+        async fn query(
+            &self,
+            sql: &str,
+            _: &[Value],
+        ) -> Result<Vec<django_rs_db::Row>, DjangoError> {
+            Ok(self.0.log(self.1, sql))
+        }
+
+        async fn query_one(
+            &self,
+            sql: &str,
+            _: &[Value],
+        ) -> Result<django_rs_db::Row, DjangoError> {
+            self.0
+                .log(self.1, sql)
+                .pop()
+                .ok_or_else(|| DjangoError::DoesNotExist(sql.to_string()))
+        }
+
+        async fn begin_transaction(&self) -> Result<Transaction, DjangoError> {
+            self.0.log(self.1, "BEGIN");
+            Ok(Transaction::new(Box::new(())))
+        }
+
+        async fn commit(&self) -> Result<(), DjangoError> {
+            self.0.log(self.1, "COMMIT");
+            Ok(())
+        }
+
+        async fn rollback(&self) -> Result<(), DjangoError> {
+            self.0.log(self.1, "ROLLBACK");
+            Ok(())
+        }
+
+        fn compiler(&self) -> SqlCompiler {
+            SqlCompiler::new(DatabaseBackendType::PostgreSQL)
+        }
+
+        async fn pin(&self) -> Result<Box<dyn DatabaseBackend + '_>, DjangoError> {
+            Ok(Box::new(Conn(self.0, "pinned")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_against_db_runs_on_one_connection() {
+        let log = PooledLog::default();
+        let mut executor = MigrationExecutor::new(Box::new(PostgresSchemaEditor));
+        let mut plan = MigrationPlan::new();
+        plan.add_step(MigrationStep::forward("blog", "0001"));
+        let ops: Vec<Box<dyn Operation>> = vec![Box::new(CreateModel {
+            name: "post".into(),
+            fields: vec![MigrationFieldDef::new("id", FieldType::BigAutoField).primary_key()],
+            options: ModelOptions::default(),
+        })];
+        let mut operations = HashMap::new();
+        operations.insert(("blog".into(), "0001".into()), ops);
+
+        executor
+            .execute_against_db(
+                &plan,
+                &operations,
+                &ProjectState::new(),
+                &Conn(&log, "pool"),
+                false,
+            )
+            .await
+            .unwrap();
+
+        let statements = log.statements.into_inner().unwrap();
+        assert!(statements.iter().all(|(conn, _)| *conn == "pinned"));
+        let position = |prefix: &str| {
+            statements
+                .iter()
+                .position(|(_, sql)| sql.starts_with(prefix))
+                .unwrap()
+        };
+        assert!(position("SELECT pg_advisory_lock") < position("BEGIN"));
+        assert!(position("BEGIN") < position("CREATE TABLE \"blog_post\""));
+        assert!(position("CREATE TABLE \"blog_post\"") < position("COMMIT"));
+        assert!(position("COMMIT") < position("SELECT pg_advisory_unlock"));
+    }
+
+    #[test]
+    fn test_migration_lock_sql() {
+        let acquire = MigrationLock::acquire_sql("postgresql").unwrap();
+        assert!(acquire.starts_with("SELECT pg_advisory_lock("));
+        assert!(acquire.contains(&MigrationLock::ADVISORY_KEY.to_string()));
+        assert!(MigrationLock::release_sql("postgresql")
+            .unwrap()
+            .starts_with("SELECT pg_advisory_unlock("));
+        assert!(MigrationLock::acquire_sql("mysql")
+            .unwrap()
+            .contains("GET_LOCK('django_migrations', -1)"));
+        assert!(MigrationLock::release_sql("mysql")
+            .unwrap()
+            .contains("RELEASE_LOCK('django_migrations')"));
+        assert!(MigrationLock::acquire_sql("sqlite").is_none());
+        assert!(MigrationLock::release_sql("sqlite").is_none());
+    }
+
+    #[test]
+    fn test_executor_collect_sql_rejects_run_rust() {
+        let executor = MigrationExecutor::new(Box::new(PostgresSchemaEditor));
+        let migration = post_migration().add_operation(Box::new(RunRust {
+            description: "populate slugs".into(),
+            forwards: Box::new(|| Ok(())),
+            backwards: None,
+        }));
+        let err = executor
+            .collect_sql(&migration, &ProjectState::new(), false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("blog.0001_initial"));
+        assert!(err.contains("Run Rust: populate slugs"));
+    }
 }
@@ -10,6 +10,7 @@
 //! - [`Migration`] is a named set of [`Operation`]s belonging to an app.
 //! - [`MigrationGraph`] resolves dependency ordering across apps.
 //! - [`MigrationAutodetector`] diffs two [`ProjectState`]s to produce operations.
+//! - [`MigrationQuestioner`] resolves ambiguous changes such as renames.
 //! - [`SchemaEditor`] translates operations into backend-specific DDL.
 //! - [`MigrationExecutor`] applies or reverts a plan of migrations.
 //! - [`MigrationSquasher`] combines migrations into an optimized single migration.
@@ -20,8 +21,9 @@
 //! - [`loader`] - `MigrationLoader` for filesystem discovery
 //! - [`operations`] - `Operation` trait and all concrete operations
 //! - [`schema_editor`] - `SchemaEditor` trait and PostgreSQL/SQLite/MySQL implementations
-//! - [`executor`] - `MigrationExecutor`, `MigrationPlan`, `MigrationRecorder`
+//! - [`executor`] - `MigrationExecutor`, `MigrationPlan`, `MigrationRecorder`, `MigrationLock`
 //! - [`autodetect`] - `MigrationAutodetector`, `ProjectState`, `ModelState`
+//! - [`questioner`] - `MigrationQuestioner`, interactive and non-interactive questioners
 //! - [`squash`] - `MigrationSquasher`
 
 // Clippy overrides appropriate for a DDL generation / migration crate.
@@ -47,16 +49,21 @@ pub mod executor;
 pub mod loader;
 pub mod migration;
 pub mod operations;
+pub mod questioner;
 pub mod schema_editor;
 pub mod serializer;
 pub mod squash;
 
 // Re-export key types at the crate root.
 pub use autodetect::{MigrationAutodetector, ModelOptions, ModelState, ProjectState};
-pub use executor::{MigrationExecutor, MigrationPlan, MigrationRecorder, MigrationStep};
+pub use executor::{
+    AppliedMigration, MigrationExecutor, MigrationLock, MigrationPlan, MigrationRecorder,
+    MigrationStep,
+};
 pub use loader::MigrationLoader;
 pub use migration::{Migration, MigrationGraph};
 pub use operations::Operation;
+pub use questioner::{InteractiveQuestioner, MigrationQuestioner, NonInteractiveQuestioner};
 pub use schema_editor::{
     MySqlSchemaEditor, PostgresSchemaEditor, SchemaEditor, SqliteSchemaEditor,
 };
@@ -4,7 +4,7 @@
 //! and builds a [`MigrationGraph`] from them. This mirrors Django's
 //! `MigrationLoader`.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use django_rs_core::DjangoError;
@@ -27,6 +27,27 @@ pub struct MigrationFileInfo {
     pub dependencies: Vec<(String, String)>,
     /// Whether this is an initial migration.
     pub initial: bool,
+    /// Whether the migration runs in a transaction (`"atomic"`, default
+    /// `true`).
+    pub atomic: bool,
+    /// SHA-256 checksum of the file contents, hex-encoded.
+    pub checksum: String,
+}
+
+/// Returns the hex-encoded SHA-256 checksum of a migration file's contents.
+///
+/// The checksum is stored when a migration is applied, so a file edited
+/// afterwards can be detected.
+pub fn migration_checksum(contents: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+
+    Sha256::digest(contents)
+        .iter()
+        .fold(String::with_capacity(64), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
 }
 
 /// Discovers and loads migrations from the filesystem.
@@ -144,6 +165,11 @@ impl MigrationLoader {
             .and_then(serde_json::Value::as_bool)
             .unwrap_or(false);
 
+        let atomic = json
+            .get("atomic")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(true);
+
         let dependencies = json
             .get("dependencies")
             .and_then(|v| v.as_array())
@@ -170,6 +196,8 @@ impl MigrationLoader {
             path: path.to_path_buf(),
             dependencies,
             initial,
+            atomic,
+            checksum: migration_checksum(content.as_bytes()),
         })
     }
 
@@ -198,6 +226,24 @@ impl MigrationLoader {
         &self.migrations
     }
 
+    /// Returns the file checksum of each discovered migration.
+    pub fn checksums(&self) -> HashMap<(String, String), String> {
+        self.migrations
+            .iter()
+            .map(|(key, info)| (key.clone(), info.checksum.clone()))
+            .collect()
+    }
+
+    /// Returns the migrations marked `"atomic": false`, which the executor
+    /// runs outside a transaction.
+    pub fn non_atomic(&self) -> HashSet<(String, String)> {
+        self.migrations
+            .iter()
+            .filter(|(_, info)| !info.atomic)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
     /// Returns the migrations directory.
     pub fn migrations_dir(&self) -> &Path {
         &self.migrations_dir
@@ -320,6 +366,31 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_loader_non_atomic() {
+        let dir = create_temp_dir();
+        let app_dir = dir.join("blog");
+        fs::create_dir_all(&app_dir).unwrap();
+        fs::write(
+            app_dir.join("0001_initial.json"),
+            r#"{"initial": true, "dependencies": [], "operations": []}"#,
+        )
+        .unwrap();
+        fs::write(
+            app_dir.join("0002_index.json"),
+            r#"{"atomic": false, "dependencies": [["blog", "0001_initial"]], "operations": []}"#,
+        )
+        .unwrap();
+
+        let mut loader = MigrationLoader::new(&dir);
+        loader.load().unwrap();
+        assert_eq!(
+            loader.non_atomic(),
+            HashSet::from([("blog".to_string(), "0002_index".to_string())])
+        );
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_loader_discover_multiple_apps() {
         let dir = create_temp_dir();
@@ -408,9 +479,19 @@ mod tests {
             path: PathBuf::from("/tmp/blog/0001_initial.json"),
             dependencies: vec![("auth".into(), "0001_initial".into())],
             initial: true,
+            atomic: true,
+            checksum: migration_checksum(b"{}"),
         };
         assert_eq!(info.app_label, "blog");
         assert!(info.initial);
         assert_eq!(info.dependencies.len(), 1);
     }
+
+    #[test]
+    fn test_migration_checksum() {
+        let checksum = migration_checksum(b"{}");
+        assert_eq!(checksum.len(), 64);
+        assert_eq!(checksum, migration_checksum(b"{}"));
+        assert_ne!(checksum, migration_checksum(b"{ }"));
+    }
 }
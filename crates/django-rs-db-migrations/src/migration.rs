@@ -4,7 +4,7 @@
 //! [`Operation`]s. The [`MigrationGraph`] manages the dependency DAG between
 //! migrations across all apps, enabling topological ordering.
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 use django_rs_core::DjangoError;
 
@@ -26,6 +26,9 @@ pub struct Migration {
     pub operations: Vec<Box<dyn Operation>>,
     /// Whether this is the initial migration for the app.
     pub initial: bool,
+    /// Whether the operations run in a single transaction, on backends
+    /// with transactional DDL.
+    pub atomic: bool,
 }
 
 impl Migration {
@@ -37,6 +40,7 @@ impl Migration {
             dependencies: Vec::new(),
             operations: Vec::new(),
             initial: false,
+            atomic: true,
         }
     }
 
@@ -46,6 +50,16 @@ impl Migration {
         self
     }
 
+    /// Runs this migration's operations outside a transaction.
+    ///
+    /// Needed for statements PostgreSQL refuses inside a transaction, such as
+    /// `CREATE INDEX CONCURRENTLY`. A failure part-way leaves the earlier
+    /// operations applied.
+    pub fn non_atomic(mut self) -> Self {
+        self.atomic = false;
+        self
+    }
+
     /// Adds a dependency on another migration.
     pub fn depends_on(mut self, app_label: impl Into<String>, name: impl Into<String>) -> Self {
         self.dependencies.push((app_label.into(), name.into()));
@@ -206,6 +220,27 @@ impl MigrationGraph {
         leaves
     }
 
+    /// Returns the apps whose migration history has diverged.
+    ///
+    /// An app conflicts when more than one of its migrations has no
+    /// dependent migration in the same app, which happens when two branches
+    /// each add a migration on top of the same parent. The result maps each
+    /// conflicting app label to its leaf migrations, sorted. This mirrors
+    /// Django's `MigrationLoader.detect_conflicts()`.
+    pub fn conflicts(&self) -> BTreeMap<String, Vec<(String, String)>> {
+        let mut leaves: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+        for (key, children) in &self.forward_edges {
+            if !children.iter().any(|child| child.0 == key.0) {
+                leaves.entry(key.0.clone()).or_default().push(key.clone());
+            }
+        }
+        leaves.retain(|_, app_leaves| app_leaves.len() > 1);
+        for app_leaves in leaves.values_mut() {
+            app_leaves.sort();
+        }
+        leaves
+    }
+
     /// Returns the root nodes (migrations with no dependencies) for a given app.
     pub fn root_nodes(&self, app_label: &str) -> Vec<(String, String)> {
         let mut roots = Vec::new();
@@ -565,4 +600,45 @@ mod tests {
         let order = g.topological_order().unwrap();
         assert_eq!(order.len(), 3);
     }
+
+    #[test]
+    fn test_graph_conflicts() {
+        let mut graph = MigrationGraph::new();
+        graph.add_node("blog", "0001_initial", true);
+        graph.add_node("blog", "0002_add_author", false);
+        graph.add_node("blog", "0002_add_tags", false);
+        graph.add_node("shop", "0001_initial", true);
+        graph.add_node("shop", "0002_link_blog", false);
+        for child in ["0002_add_author", "0002_add_tags"] {
+            graph
+                .add_dependency(
+                    ("blog".into(), child.into()),
+                    ("blog".into(), "0001_initial".into()),
+                )
+                .unwrap();
+        }
+        graph
+            .add_dependency(
+                ("shop".into(), "0002_link_blog".into()),
+                ("shop".into(), "0001_initial".into()),
+            )
+            .unwrap();
+        // A dependent in another app does not make a migration a non-leaf.
+        graph
+            .add_dependency(
+                ("shop".into(), "0002_link_blog".into()),
+                ("blog".into(), "0002_add_tags".into()),
+            )
+            .unwrap();
+
+        let conflicts = graph.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts["blog"],
+            vec![
+                ("blog".to_string(), "0002_add_author".to_string()),
+                ("blog".to_string(), "0002_add_tags".to_string()),
+            ]
+        );
+    }
 }
@@ -9,6 +9,7 @@ use django_rs_db::model::Index;
 
 use crate::autodetect::{MigrationFieldDef, ModelOptions, ModelState, ProjectState};
 use crate::schema_editor::SchemaEditor;
+use crate::serializer::SerializableOperation;
 
 /// A single migration operation that can be applied forwards or backwards.
 ///
@@ -41,12 +42,29 @@ pub trait Operation: Send + Sync {
 
     /// Returns whether this operation is reversible.
     fn reversible(&self) -> bool;
+
+    /// Returns whether this operation's effect can be expressed as SQL.
+    ///
+    /// Operations that run application code, such as [`RunRust`], return
+    /// `false`; `sqlmigrate` refuses to print SQL for migrations containing
+    /// them rather than silently leaving them out.
+    fn reduces_to_sql(&self) -> bool {
+        true
+    }
+
+    /// Returns the form this operation is written to a migration file in.
+    ///
+    /// Operations that cannot be written to a file, such as [`RunRust`],
+    /// return `None` and are left out of generated migrations.
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        None
+    }
 }
 
 /// Creates a new database table.
 ///
 /// Generates a `CREATE TABLE` statement with all specified fields and
-/// constraints.
+/// constraints, followed by a `CREATE INDEX` for each of the model's indexes.
 #[derive(Debug, Clone)]
 pub struct CreateModel {
     /// The model name.
@@ -58,6 +76,10 @@ pub struct CreateModel {
 }
 
 impl Operation for CreateModel {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_create_model(self))
+    }
+
     fn describe(&self) -> String {
         format!("Create model {}", self.name)
     }
@@ -83,7 +105,12 @@ impl Operation for CreateModel {
         let model = to_state.models.get(&key).ok_or_else(|| {
             DjangoError::DatabaseError(format!("Model {} not found in state", self.name))
         })?;
-        Ok(schema_editor.create_table(model))
+        let mut sqls = schema_editor.create_table(model);
+        let table_name = model.db_table();
+        for index in &model.options.indexes {
+            sqls.extend(schema_editor.create_index(&table_name, index));
+        }
+        Ok(sqls)
     }
 
     fn database_backwards(
@@ -113,6 +140,10 @@ pub struct DeleteModel {
 }
 
 impl Operation for DeleteModel {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_delete_model(self))
+    }
+
     fn describe(&self) -> String {
         format!("Delete model {}", self.name)
     }
@@ -161,9 +192,16 @@ pub struct AddField {
     pub model_name: String,
     /// The field to add.
     pub field: MigrationFieldDef,
+    /// Whether the field's default is kept in the model state. `false` for
+    /// a one-off default that only populates existing rows.
+    pub preserve_default: bool,
 }
 
 impl Operation for AddField {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_add_field(self))
+    }
+
     fn describe(&self) -> String {
         format!("Add field {} to {}", self.field.name, self.model_name)
     }
@@ -171,7 +209,11 @@ impl Operation for AddField {
     fn state_forwards(&self, app_label: &str, state: &mut ProjectState) {
         let key = (app_label.to_string(), self.model_name.clone());
         if let Some(model) = state.models.get_mut(&key) {
-            model.fields.push(self.field.clone());
+            let mut field = self.field.clone();
+            if !self.preserve_default {
+                field.default = None;
+            }
+            model.fields.push(field);
         }
     }
 
@@ -215,6 +257,10 @@ pub struct RemoveField {
 }
 
 impl Operation for RemoveField {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_remove_field(self))
+    }
+
     fn describe(&self) -> String {
         format!("Remove field {} from {}", self.field_name, self.model_name)
     }
@@ -279,6 +325,10 @@ pub struct AlterField {
 }
 
 impl Operation for AlterField {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_alter_field(self))
+    }
+
     fn describe(&self) -> String {
         format!("Alter field {} on {}", self.field_name, self.model_name)
     }
@@ -358,6 +408,10 @@ pub struct RenameField {
 }
 
 impl Operation for RenameField {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_rename_field(self))
+    }
+
     fn describe(&self) -> String {
         format!(
             "Rename field {} to {} on {}",
@@ -414,6 +468,10 @@ pub struct AddIndex {
 }
 
 impl Operation for AddIndex {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_add_index(self))
+    }
+
     fn describe(&self) -> String {
         format!(
             "Add index {} on {}",
@@ -468,6 +526,10 @@ pub struct RemoveIndex {
 }
 
 impl Operation for RemoveIndex {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_remove_index(self))
+    }
+
     fn describe(&self) -> String {
         format!("Remove index {} from {}", self.index_name, self.model_name)
     }
@@ -531,6 +593,10 @@ pub struct AlterUniqueTogether {
 }
 
 impl Operation for AlterUniqueTogether {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_alter_unique_together(self))
+    }
+
     fn describe(&self) -> String {
         format!("Alter unique_together for {}", self.model_name)
     }
@@ -704,6 +770,10 @@ pub struct RunSQL {
 }
 
 impl Operation for RunSQL {
+    fn to_serializable(&self) -> Option<SerializableOperation> {
+        Some(SerializableOperation::from_run_sql(self))
+    }
+
     fn describe(&self) -> String {
         "Run SQL".to_string()
     }
@@ -799,6 +869,10 @@ impl Operation for RunRust {
     fn reversible(&self) -> bool {
         self.backwards.is_some()
     }
+
+    fn reduces_to_sql(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -860,6 +934,35 @@ mod tests {
         assert!(sqls[0].contains("CREATE TABLE"));
     }
 
+    #[test]
+    fn test_create_model_database_forwards_creates_indexes() {
+        let op = CreateModel {
+            name: "user".into(),
+            fields: vec![
+                make_field("id", FieldType::BigAutoField).primary_key(),
+                make_field("email", FieldType::CharField).max_length(254),
+            ],
+            options: ModelOptions {
+                indexes: vec![Index::new("uniq_user_email_lower", vec![])
+                    .expression("LOWER(\"email\")")
+                    .condition("\"deleted_at\" IS NULL")
+                    .unique(true)],
+                ..ModelOptions::default()
+            },
+        };
+        let mut state = ProjectState::new();
+        op.state_forwards("accounts", &mut state);
+        let sqls = op
+            .database_forwards("accounts", &pg_editor(), &ProjectState::new(), &state)
+            .unwrap();
+        assert!(sqls[0].contains("CREATE TABLE"));
+        assert_eq!(
+            sqls.last().unwrap(),
+            "CREATE UNIQUE INDEX \"uniq_user_email_lower\" ON \"accounts_user\" USING btree \
+             (LOWER(\"email\")) WHERE \"deleted_at\" IS NULL"
+        );
+    }
+
     #[test]
     fn test_create_model_reversible() {
         let op = CreateModel {
@@ -933,6 +1036,7 @@ mod tests {
         let op = AddField {
             model_name: "post".into(),
             field: make_field("title", FieldType::CharField),
+            preserve_default: true,
         };
         assert_eq!(op.describe(), "Add field title to post");
     }
@@ -944,6 +1048,7 @@ mod tests {
         let op = AddField {
             model_name: "post".into(),
             field: make_field("title", FieldType::CharField),
+            preserve_default: true,
         };
         op.state_forwards("blog", &mut state);
         let model = state.models.get(&("blog".into(), "post".into())).unwrap();
@@ -955,6 +1060,7 @@ mod tests {
         let op = AddField {
             model_name: "post".into(),
             field: make_field("title", FieldType::CharField).max_length(200),
+            preserve_default: true,
         };
         let sqls = op
             .database_forwards(
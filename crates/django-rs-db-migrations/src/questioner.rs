@@ -0,0 +1,394 @@
+//! Questions the autodetector asks while generating migrations.
+//!
+//! Some model changes are ambiguous from the states alone: a removed field and
+//! an added field of the same type may be a rename, and a new NOT NULL column
+//! needs a value for the rows that already exist. The autodetector hands these
+//! decisions to a [`MigrationQuestioner`]. This mirrors Django's
+//! `MigrationQuestioner`, `InteractiveMigrationQuestioner`, and
+//! `NonInteractiveMigrationQuestioner`.
+
+use std::io::{self, BufRead, Write};
+
+use django_rs_core::DjangoError;
+use django_rs_db::fields::FieldType;
+use django_rs_db::value::Value;
+
+use crate::autodetect::MigrationFieldDef;
+
+/// Answers the autodetector's questions about ambiguous changes.
+pub trait MigrationQuestioner {
+    /// Asks whether `old_field` on `model_name` was renamed to `new_field`.
+    ///
+    /// Only asked for pairs with the same type and options, so answering
+    /// `true` produces a `RenameField` instead of a drop and an add.
+    fn ask_rename(
+        &mut self,
+        model_name: &str,
+        old_field: &MigrationFieldDef,
+        new_field: &MigrationFieldDef,
+    ) -> bool;
+
+    /// Asks for a one-off default for a NOT NULL field added without one.
+    ///
+    /// The value is used to populate existing rows and is not kept on the
+    /// field afterwards. Returning `Ok(None)` adds the field unchanged;
+    /// returning an error aborts detection.
+    fn ask_not_null_addition(
+        &mut self,
+        model_name: &str,
+        field: &MigrationFieldDef,
+    ) -> Result<Option<Value>, DjangoError>;
+}
+
+/// A questioner for `makemigrations --noinput`.
+///
+/// Renames are only assumed when `assume_renames` is set, and adding a
+/// NOT NULL field without a default is an error, since there is nobody to
+/// ask for one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonInteractiveQuestioner {
+    /// Treat every rename candidate as a rename.
+    pub assume_renames: bool,
+}
+
+impl NonInteractiveQuestioner {
+    /// Creates a questioner that never assumes renames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether rename candidates are treated as renames.
+    pub fn assume_renames(mut self, assume_renames: bool) -> Self {
+        self.assume_renames = assume_renames;
+        self
+    }
+}
+
+impl MigrationQuestioner for NonInteractiveQuestioner {
+    fn ask_rename(
+        &mut self,
+        _model_name: &str,
+        _old_field: &MigrationFieldDef,
+        _new_field: &MigrationFieldDef,
+    ) -> bool {
+        self.assume_renames
+    }
+
+    fn ask_not_null_addition(
+        &mut self,
+        model_name: &str,
+        field: &MigrationFieldDef,
+    ) -> Result<Option<Value>, DjangoError> {
+        Err(DjangoError::DatabaseError(format!(
+            "Field '{}' on model '{model_name}' not migrated: it is impossible to add a \
+             non-nullable field without specifying a default.",
+            field.name
+        )))
+    }
+}
+
+/// A questioner that prompts on a terminal.
+///
+/// Generic over its input and output so it can be driven by scripted
+/// answers; [`InteractiveQuestioner::stdio`] uses stdin and stdout.
+pub struct InteractiveQuestioner<R, W> {
+    input: R,
+    output: W,
+}
+
+impl InteractiveQuestioner<io::StdinLock<'static>, io::Stdout> {
+    /// Creates a questioner reading from stdin and writing to stdout.
+    pub fn stdio() -> Self {
+        Self::new(io::stdin().lock(), io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> InteractiveQuestioner<R, W> {
+    /// Creates a questioner over the given input and output.
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Returns the output the prompts were written to.
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Writes `prompt` and reads one trimmed line, or `None` at end of input.
+    fn prompt(&mut self, prompt: &str) -> Option<String> {
+        let _ = write!(self.output, "{prompt}");
+        let _ = self.output.flush();
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim().to_string()),
+        }
+    }
+
+    /// Asks a yes/no question, returning `default` for an empty answer.
+    fn boolean_input(&mut self, question: &str, default: bool) -> bool {
+        loop {
+            let Some(answer) = self.prompt(question) else {
+                return default;
+            };
+            match answer.to_lowercase().as_str() {
+                "" => return default,
+                "y" | "yes" => return true,
+                "n" | "no" => return false,
+                _ => {
+                    let _ = writeln!(self.output, "Please answer yes or no.");
+                }
+            }
+        }
+    }
+
+    /// Asks the user to pick one of `choices`, returning its 1-based index.
+    fn choice_input(&mut self, question: &str, choices: &[&str]) -> Option<usize> {
+        let _ = writeln!(self.output, "{question}");
+        for (i, choice) in choices.iter().enumerate() {
+            let _ = writeln!(self.output, " {}) {choice}", i + 1);
+        }
+        loop {
+            let answer = self.prompt("Select an option: ")?;
+            match answer.parse::<usize>() {
+                Ok(n) if (1..=choices.len()).contains(&n) => return Some(n),
+                _ => {
+                    let _ = writeln!(self.output, "Please select a valid option.");
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> MigrationQuestioner for InteractiveQuestioner<R, W> {
+    fn ask_rename(
+        &mut self,
+        model_name: &str,
+        old_field: &MigrationFieldDef,
+        new_field: &MigrationFieldDef,
+    ) -> bool {
+        self.boolean_input(
+            &format!(
+                "Was {model_name}.{} renamed to {model_name}.{} (a {})? [y/N] ",
+                old_field.name,
+                new_field.name,
+                new_field.field_type.class_name()
+            ),
+            false,
+        )
+    }
+
+    fn ask_not_null_addition(
+        &mut self,
+        model_name: &str,
+        field: &MigrationFieldDef,
+    ) -> Result<Option<Value>, DjangoError> {
+        let abort = || {
+            DjangoError::DatabaseError(format!(
+                "Field '{}' on model '{model_name}' not migrated: make the field \
+                 nullable or give it a default.",
+                field.name
+            ))
+        };
+
+        let choice = self.choice_input(
+            &format!(
+                "It is impossible to add a non-nullable field '{}' to {model_name} without \
+                 specifying a default. This is because the database needs something to \
+                 populate existing rows.\nPlease select a fix:",
+                field.name
+            ),
+            &[
+                "Provide a one-off default now (will be set on all existing rows)",
+                "Quit and manually define a default value or make the field nullable",
+            ],
+        );
+        if choice != Some(1) {
+            return Err(abort());
+        }
+
+        loop {
+            let answer = self
+                .prompt("Please enter the default value as a literal: ")
+                .ok_or_else(abort)?;
+            match parse_default(&field.field_type, &answer) {
+                Ok(value) => return Ok(Some(value)),
+                Err(message) => {
+                    let _ = writeln!(self.output, "{message}");
+                }
+            }
+        }
+    }
+}
+
+/// Parses a one-off default typed at a prompt into a value for `field_type`.
+///
+/// Numbers and booleans are parsed as such, `now` is accepted for date and
+/// time fields, and strings may optionally be quoted.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_db::fields::FieldType;
+/// use django_rs_db::value::Value;
+/// use django_rs_db_migrations::questioner::parse_default;
+///
+/// assert_eq!(parse_default(&FieldType::IntegerField, "0"), Ok(Value::Int(0)));
+/// assert_eq!(
+///     parse_default(&FieldType::CharField, "'draft'"),
+///     Ok(Value::String("draft".into()))
+/// );
+/// assert!(parse_default(&FieldType::BooleanField, "maybe").is_err());
+/// ```
+pub fn parse_default(field_type: &FieldType, input: &str) -> Result<Value, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Please enter some value, or 'null' for NULL.".into());
+    }
+    if input.eq_ignore_ascii_case("null") || input.eq_ignore_ascii_case("none") {
+        return Ok(Value::Null);
+    }
+
+    match field_type {
+        FieldType::AutoField
+        | FieldType::BigAutoField
+        | FieldType::IntegerField
+        | FieldType::BigIntegerField
+        | FieldType::SmallIntegerField
+        | FieldType::ForeignKey { .. }
+        | FieldType::OneToOneField { .. } => input
+            .parse::<i64>()
+            .map(Value::Int)
+            .map_err(|_| format!("'{input}' is not a valid integer.")),
+        FieldType::FloatField | FieldType::DecimalField { .. } => input
+            .parse::<f64>()
+            .map(Value::Float)
+            .map_err(|_| format!("'{input}' is not a valid number.")),
+        FieldType::BooleanField => match input.to_lowercase().as_str() {
+            "true" | "1" => Ok(Value::Bool(true)),
+            "false" | "0" => Ok(Value::Bool(false)),
+            _ => Err(format!("'{input}' is not a valid boolean.")),
+        },
+        FieldType::DateField if input.eq_ignore_ascii_case("now") => {
+            Ok(Value::Date(chrono::Utc::now().date_naive()))
+        }
+        FieldType::DateTimeField if input.eq_ignore_ascii_case("now") => {
+            Ok(Value::DateTimeTz(chrono::Utc::now()))
+        }
+        FieldType::TimeField if input.eq_ignore_ascii_case("now") => {
+            Ok(Value::Time(chrono::Utc::now().time()))
+        }
+        FieldType::JsonField => serde_json::from_str(input)
+            .map(Value::Json)
+            .map_err(|e| format!("'{input}' is not valid JSON: {e}")),
+        _ => Ok(Value::String(unquote(input).to_string())),
+    }
+}
+
+/// Strips one pair of matching single or double quotes.
+fn unquote(input: &str) -> &str {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = input
+            .strip_prefix(quote)
+            .and_then(|s| s.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn questioner(answers: &str) -> InteractiveQuestioner<&[u8], Vec<u8>> {
+        InteractiveQuestioner::new(answers.as_bytes(), Vec::new())
+    }
+
+    #[test]
+    fn test_non_interactive_renames_follow_policy() {
+        let old = MigrationFieldDef::new("title", FieldType::CharField);
+        let new = MigrationFieldDef::new("headline", FieldType::CharField);
+        assert!(!NonInteractiveQuestioner::new().ask_rename("post", &old, &new));
+        assert!(NonInteractiveQuestioner::new()
+            .assume_renames(true)
+            .ask_rename("post", &old, &new));
+    }
+
+    #[test]
+    fn test_non_interactive_not_null_addition_errors() {
+        let field = MigrationFieldDef::new("author", FieldType::CharField);
+        let err = NonInteractiveQuestioner::new()
+            .ask_not_null_addition("post", &field)
+            .unwrap_err();
+        assert!(err.to_string().contains("'author' on model 'post'"));
+    }
+
+    #[test]
+    fn test_interactive_rename_prompt() {
+        let old = MigrationFieldDef::new("title", FieldType::CharField);
+        let new = MigrationFieldDef::new("headline", FieldType::CharField);
+
+        let mut q = questioner("maybe\ny\n");
+        assert!(q.ask_rename("post", &old, &new));
+        let output = String::from_utf8(q.into_output()).unwrap();
+        assert!(output.contains("Was post.title renamed to post.headline (a CharField)?"));
+        assert!(output.contains("Please answer yes or no."));
+
+        assert!(!questioner("\n").ask_rename("post", &old, &new));
+        assert!(!questioner("").ask_rename("post", &old, &new));
+    }
+
+    #[test]
+    fn test_interactive_one_off_default() {
+        let field = MigrationFieldDef::new("views", FieldType::IntegerField);
+        let mut q = questioner("1\nlots\n0\n");
+        assert_eq!(
+            q.ask_not_null_addition("post", &field).unwrap(),
+            Some(Value::Int(0))
+        );
+        let output = String::from_utf8(q.into_output()).unwrap();
+        assert!(output.contains("'lots' is not a valid integer."));
+    }
+
+    #[test]
+    fn test_interactive_quit_aborts() {
+        let field = MigrationFieldDef::new("views", FieldType::IntegerField);
+        assert!(questioner("2\n")
+            .ask_not_null_addition("post", &field)
+            .is_err());
+        assert!(questioner("")
+            .ask_not_null_addition("post", &field)
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_default_by_type() {
+        assert_eq!(
+            parse_default(&FieldType::BooleanField, "True"),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            parse_default(&FieldType::FloatField, "1.5"),
+            Ok(Value::Float(1.5))
+        );
+        assert_eq!(
+            parse_default(&FieldType::TextField, "\"hello\""),
+            Ok(Value::String("hello".into()))
+        );
+        assert_eq!(
+            parse_default(&FieldType::JsonField, "{\"a\": 1}"),
+            Ok(Value::Json(serde_json::json!({"a": 1})))
+        );
+        assert_eq!(
+            parse_default(&FieldType::CharField, "null"),
+            Ok(Value::Null)
+        );
+        assert!(matches!(
+            parse_default(&FieldType::DateTimeField, "now"),
+            Ok(Value::DateTimeTz(_))
+        ));
+        assert!(parse_default(&FieldType::CharField, "  ").is_err());
+    }
+}
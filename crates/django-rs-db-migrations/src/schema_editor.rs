@@ -109,6 +109,7 @@ impl SchemaEditor for PostgresSchemaEditor {
 
     fn create_table(&self, model: &ModelState) -> Vec<String> {
         let table_name = model.db_table();
+        let table = self.backend_type().quote_table_name(&table_name);
         let mut col_defs: Vec<String> = Vec::new();
         let mut constraints: Vec<String> = Vec::new();
 
@@ -125,7 +126,8 @@ impl SchemaEditor for PostgresSchemaEditor {
             {
                 let target_table = fk_target_table(to);
                 constraints.push(format!(
-                    "FOREIGN KEY (\"{}\") REFERENCES \"{}\" (\"id\") ON DELETE {}",
+                    "FOREIGN KEY (\"{}\") REFERENCES \"{}\" (\"id\") ON DELETE {} \
+                     DEFERRABLE INITIALLY IMMEDIATE",
                     fd.column,
                     target_table,
                     on_delete_sql(*on_delete)
@@ -139,7 +141,8 @@ impl SchemaEditor for PostgresSchemaEditor {
             {
                 let target_table = fk_target_table(to);
                 constraints.push(format!(
-                    "FOREIGN KEY (\"{}\") REFERENCES \"{}\" (\"id\") ON DELETE {}",
+                    "FOREIGN KEY (\"{}\") REFERENCES \"{}\" (\"id\") ON DELETE {} \
+                     DEFERRABLE INITIALLY IMMEDIATE",
                     fd.column,
                     target_table,
                     on_delete_sql(*on_delete)
@@ -150,25 +153,31 @@ impl SchemaEditor for PostgresSchemaEditor {
         let mut all_parts = col_defs;
         all_parts.extend(constraints);
         let body = all_parts.join(", ");
-        vec![format!("CREATE TABLE \"{table_name}\" ({body})")]
+        let mut stmts = Vec::new();
+        if let Some(ref schema) = model.options.db_schema {
+            stmts.push(format!("CREATE SCHEMA IF NOT EXISTS \"{schema}\""));
+        }
+        stmts.push(format!("CREATE TABLE {table} ({body})"));
+        stmts
     }
 
     fn drop_table(&self, table_name: &str) -> Vec<String> {
-        vec![format!("DROP TABLE IF EXISTS \"{table_name}\"")]
+        let table = self.backend_type().quote_table_name(table_name);
+        vec![format!("DROP TABLE IF EXISTS {table}")]
     }
 
     fn add_column(&self, table_name: &str, field: &FieldDef) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         let col_sql = self.column_sql(field);
         vec![format!(
-            "ALTER TABLE \"{table_name}\" ADD COLUMN \"{}\" {col_sql}",
+            "ALTER TABLE {table} ADD COLUMN \"{}\" {col_sql}",
             field.column
         )]
     }
 
     fn drop_column(&self, table_name: &str, column_name: &str) -> Vec<String> {
-        vec![format!(
-            "ALTER TABLE \"{table_name}\" DROP COLUMN \"{column_name}\""
-        )]
+        let table = self.backend_type().quote_table_name(table_name);
+        vec![format!("ALTER TABLE {table} DROP COLUMN \"{column_name}\"")]
     }
 
     fn alter_column(
@@ -177,21 +186,22 @@ impl SchemaEditor for PostgresSchemaEditor {
         _old_field: &FieldDef,
         new_field: &FieldDef,
     ) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         let mut stmts = Vec::new();
         let col = &new_field.column;
         let type_sql = pg_type_sql(&new_field.field_type, new_field.max_length);
 
         stmts.push(format!(
-            "ALTER TABLE \"{table_name}\" ALTER COLUMN \"{col}\" TYPE {type_sql}"
+            "ALTER TABLE {table} ALTER COLUMN \"{col}\" TYPE {type_sql}"
         ));
 
         if new_field.null {
             stmts.push(format!(
-                "ALTER TABLE \"{table_name}\" ALTER COLUMN \"{col}\" DROP NOT NULL"
+                "ALTER TABLE {table} ALTER COLUMN \"{col}\" DROP NOT NULL"
             ));
         } else {
             stmts.push(format!(
-                "ALTER TABLE \"{table_name}\" ALTER COLUMN \"{col}\" SET NOT NULL"
+                "ALTER TABLE {table} ALTER COLUMN \"{col}\" SET NOT NULL"
             ));
         }
 
@@ -205,11 +215,11 @@ impl SchemaEditor for PostgresSchemaEditor {
                 _ => "NULL".to_string(),
             };
             stmts.push(format!(
-                "ALTER TABLE \"{table_name}\" ALTER COLUMN \"{col}\" SET DEFAULT {def}"
+                "ALTER TABLE {table} ALTER COLUMN \"{col}\" SET DEFAULT {def}"
             ));
         } else {
             stmts.push(format!(
-                "ALTER TABLE \"{table_name}\" ALTER COLUMN \"{col}\" DROP DEFAULT"
+                "ALTER TABLE {table} ALTER COLUMN \"{col}\" DROP DEFAULT"
             ));
         }
 
@@ -217,12 +227,14 @@ impl SchemaEditor for PostgresSchemaEditor {
     }
 
     fn rename_column(&self, table_name: &str, old_name: &str, new_name: &str) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         vec![format!(
-            "ALTER TABLE \"{table_name}\" RENAME COLUMN \"{old_name}\" TO \"{new_name}\""
+            "ALTER TABLE {table} RENAME COLUMN \"{old_name}\" TO \"{new_name}\""
         )]
     }
 
     fn create_index(&self, table_name: &str, index: &Index) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         let idx_name = index.name.as_deref().unwrap_or("unnamed_index");
         let unique = if index.unique { "UNIQUE " } else { "" };
         let concurrently = if index.concurrently {
@@ -237,7 +249,7 @@ impl SchemaEditor for PostgresSchemaEditor {
         let using = index.index_type.sql_using_clause();
 
         let mut sql = format!(
-            "CREATE {unique}INDEX {concurrently}\"{idx_name}\" ON \"{table_name}\" {using} ({})",
+            "CREATE {unique}INDEX {concurrently}\"{idx_name}\" ON {table} {using} ({})",
             index_cols.join(", ")
         );
 
@@ -259,23 +271,26 @@ impl SchemaEditor for PostgresSchemaEditor {
     }
 
     fn add_unique_constraint(&self, table_name: &str, columns: &[&str]) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         let cols: Vec<String> = columns.iter().map(|c| format!("\"{c}\"")).collect();
         let constraint_name = format!("{table_name}_{}_{}", columns.join("_"), "uniq");
         vec![format!(
-            "ALTER TABLE \"{table_name}\" ADD CONSTRAINT \"{constraint_name}\" UNIQUE ({})",
+            "ALTER TABLE {table} ADD CONSTRAINT \"{constraint_name}\" UNIQUE ({})",
             cols.join(", ")
         )]
     }
 
     fn add_constraint(&self, table_name: &str, constraint_sql: &str) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         vec![format!(
-            "ALTER TABLE \"{table_name}\" ADD CONSTRAINT {constraint_sql}"
+            "ALTER TABLE {table} ADD CONSTRAINT {constraint_sql}"
         )]
     }
 
     fn drop_constraint(&self, table_name: &str, constraint_name: &str) -> Vec<String> {
+        let table = self.backend_type().quote_table_name(table_name);
         vec![format!(
-            "ALTER TABLE \"{table_name}\" DROP CONSTRAINT \"{constraint_name}\""
+            "ALTER TABLE {table} DROP CONSTRAINT \"{constraint_name}\""
         )]
     }
 
@@ -310,7 +325,10 @@ fn pg_type_sql(field_type: &FieldType, max_length: Option<usize>) -> String {
             let len = max_length.unwrap_or(255);
             format!("VARCHAR({len})")
         }
-        FieldType::TextField => "TEXT".to_string(),
+        // Ciphertext is longer than the plaintext `max_length` allows.
+        FieldType::TextField
+        | FieldType::EncryptedCharField { .. }
+        | FieldType::EncryptedTextField { .. } => "TEXT".to_string(),
         FieldType::IntegerField => "INTEGER".to_string(),
         FieldType::BigIntegerField => "BIGINT".to_string(),
         FieldType::SmallIntegerField => "SMALLINT".to_string(),
@@ -543,7 +561,9 @@ fn sqlite_type_sql(field_type: &FieldType) -> &'static str {
         | FieldType::UrlField
         | FieldType::SlugField
         | FieldType::FilePathField
-        | FieldType::IpAddressField => "TEXT",
+        | FieldType::IpAddressField
+        | FieldType::EncryptedCharField { .. }
+        | FieldType::EncryptedTextField { .. } => "TEXT",
         FieldType::IntegerField
         | FieldType::BigIntegerField
         | FieldType::SmallIntegerField
@@ -582,7 +602,7 @@ impl SchemaEditor for MySqlSchemaEditor {
     }
 
     fn create_table(&self, model: &ModelState) -> Vec<String> {
-        let table_name = model.db_table();
+        let table = mysql_quote_table(&model.db_table());
         let mut col_defs: Vec<String> = Vec::new();
         let mut constraints: Vec<String> = Vec::new();
 
@@ -623,25 +643,31 @@ impl SchemaEditor for MySqlSchemaEditor {
         let mut all_parts = col_defs;
         all_parts.extend(constraints);
         let body = all_parts.join(", ");
-        vec![format!("CREATE TABLE `{table_name}` ({body})")]
+        let mut stmts = Vec::new();
+        if let Some(ref schema) = model.options.db_schema {
+            stmts.push(format!("CREATE SCHEMA IF NOT EXISTS `{schema}`"));
+        }
+        stmts.push(format!("CREATE TABLE {table} ({body})"));
+        stmts
     }
 
     fn drop_table(&self, table_name: &str) -> Vec<String> {
-        vec![format!("DROP TABLE IF EXISTS `{table_name}`")]
+        let table = mysql_quote_table(table_name);
+        vec![format!("DROP TABLE IF EXISTS {table}")]
     }
 
     fn add_column(&self, table_name: &str, field: &FieldDef) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         let col_sql = self.column_sql(field);
         vec![format!(
-            "ALTER TABLE `{table_name}` ADD COLUMN `{}` {col_sql}",
+            "ALTER TABLE {table} ADD COLUMN `{}` {col_sql}",
             field.column
         )]
     }
 
     fn drop_column(&self, table_name: &str, column_name: &str) -> Vec<String> {
-        vec![format!(
-            "ALTER TABLE `{table_name}` DROP COLUMN `{column_name}`"
-        )]
+        let table = mysql_quote_table(table_name);
+        vec![format!("ALTER TABLE {table} DROP COLUMN `{column_name}`")]
     }
 
     fn alter_column(
@@ -650,27 +676,51 @@ impl SchemaEditor for MySqlSchemaEditor {
         _old_field: &FieldDef,
         new_field: &FieldDef,
     ) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         let col_sql = self.column_sql(new_field);
         vec![format!(
-            "ALTER TABLE `{table_name}` MODIFY COLUMN `{}` {col_sql}",
+            "ALTER TABLE {table} MODIFY COLUMN `{}` {col_sql}",
             new_field.column
         )]
     }
 
     fn rename_column(&self, table_name: &str, old_name: &str, new_name: &str) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         vec![format!(
-            "ALTER TABLE `{table_name}` RENAME COLUMN `{old_name}` TO `{new_name}`"
+            "ALTER TABLE {table} RENAME COLUMN `{old_name}` TO `{new_name}`"
         )]
     }
 
     fn create_index(&self, table_name: &str, index: &Index) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         let idx_name = index.name.as_deref().unwrap_or("unnamed_index");
         let unique = if index.unique { "UNIQUE " } else { "" };
-        let cols: Vec<String> = index.fields.iter().map(|f| format!("`{f}`")).collect();
-        vec![format!(
-            "CREATE {unique}INDEX `{idx_name}` ON `{table_name}` ({})",
-            cols.join(", ")
-        )]
+
+        // MySQL has no partial indexes. A full unique index would reject rows
+        // the condition excludes, so it is skipped; a full plain index still
+        // serves the same queries.
+        let mut stmts = Vec::new();
+        if let Some(ref cond) = index.condition {
+            if index.unique {
+                return vec![format!(
+                    "-- MySQL: skipped unique index `{idx_name}`, partial indexes are not supported (WHERE {cond})"
+                )];
+            }
+            stmts.push(format!(
+                "-- MySQL: index `{idx_name}` covers all rows, partial indexes are not supported (WHERE {cond})"
+            ));
+        }
+
+        // Expressions are functional key parts (MySQL 8.0.13+), which must be
+        // wrapped in their own parentheses.
+        let mut index_cols: Vec<String> = index.fields.iter().map(|f| format!("`{f}`")).collect();
+        index_cols.extend(index.expressions.iter().map(|e| format!("({e})")));
+
+        stmts.push(format!(
+            "CREATE {unique}INDEX `{idx_name}` ON {table} ({})",
+            index_cols.join(", ")
+        ));
+        stmts
     }
 
     fn drop_index(&self, index_name: &str) -> Vec<String> {
@@ -678,23 +728,26 @@ impl SchemaEditor for MySqlSchemaEditor {
     }
 
     fn add_unique_constraint(&self, table_name: &str, columns: &[&str]) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         let cols: Vec<String> = columns.iter().map(|c| format!("`{c}`")).collect();
         let constraint_name = format!("{table_name}_{}_{}", columns.join("_"), "uniq");
         vec![format!(
-            "ALTER TABLE `{table_name}` ADD CONSTRAINT `{constraint_name}` UNIQUE ({})",
+            "ALTER TABLE {table} ADD CONSTRAINT `{constraint_name}` UNIQUE ({})",
             cols.join(", ")
         )]
     }
 
     fn add_constraint(&self, table_name: &str, constraint_sql: &str) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         vec![format!(
-            "ALTER TABLE `{table_name}` ADD CONSTRAINT {constraint_sql}"
+            "ALTER TABLE {table} ADD CONSTRAINT {constraint_sql}"
         )]
     }
 
     fn drop_constraint(&self, table_name: &str, constraint_name: &str) -> Vec<String> {
+        let table = mysql_quote_table(table_name);
         vec![format!(
-            "ALTER TABLE `{table_name}` DROP CONSTRAINT `{constraint_name}`"
+            "ALTER TABLE {table} DROP CONSTRAINT `{constraint_name}`"
         )]
     }
 
@@ -726,6 +779,15 @@ impl SchemaEditor for MySqlSchemaEditor {
     }
 }
 
+/// Quotes a table name with backticks, as `` `schema`.`table` `` when it is
+/// schema-qualified.
+fn mysql_quote_table(name: &str) -> String {
+    match name.split_once('.') {
+        Some((schema, table)) => format!("`{schema}`.`{table}`"),
+        None => format!("`{name}`"),
+    }
+}
+
 /// Returns the MySQL type name for a field type.
 fn mysql_type_sql(field_type: &FieldType, max_length: Option<usize>) -> String {
     match field_type {
@@ -738,7 +800,9 @@ fn mysql_type_sql(field_type: &FieldType, max_length: Option<usize>) -> String {
             let len = max_length.unwrap_or(255);
             format!("VARCHAR({len})")
         }
-        FieldType::TextField => "LONGTEXT".to_string(),
+        FieldType::TextField
+        | FieldType::EncryptedCharField { .. }
+        | FieldType::EncryptedTextField { .. } => "LONGTEXT".to_string(),
         FieldType::IntegerField => "INT".to_string(),
         FieldType::BigIntegerField => "BIGINT".to_string(),
         FieldType::SmallIntegerField => "SMALLINT".to_string(),
@@ -781,7 +845,7 @@ fn mysql_type_sql(field_type: &FieldType, max_length: Option<usize>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::autodetect::MigrationFieldDef;
+    use crate::autodetect::{MigrationFieldDef, ModelOptions};
     use django_rs_db::model::IndexType;
 
     fn pg() -> PostgresSchemaEditor {
@@ -1038,6 +1102,53 @@ mod tests {
         let sqls = pg().create_table(&model);
         assert!(sqls[0].contains("FOREIGN KEY"));
         assert!(sqls[0].contains("CASCADE"));
+        assert!(sqls[0].contains("DEFERRABLE INITIALLY IMMEDIATE"));
+    }
+
+    #[test]
+    fn test_pg_create_table_in_schema() {
+        let model = make_model(
+            "billing",
+            "invoice",
+            vec![make_field("id", FieldType::BigAutoField).primary_key()],
+        )
+        .with_options(ModelOptions {
+            db_table: Some("invoice".into()),
+            db_schema: Some("billing".into()),
+            ..ModelOptions::default()
+        });
+        let sqls = pg().create_table(&model);
+        assert_eq!(sqls[0], "CREATE SCHEMA IF NOT EXISTS \"billing\"");
+        assert!(sqls[1].starts_with("CREATE TABLE \"billing\".\"invoice\" ("));
+        assert_eq!(
+            pg().drop_table("billing.invoice"),
+            vec!["DROP TABLE IF EXISTS \"billing\".\"invoice\"".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_mysql_create_table_in_schema() {
+        let model = make_model(
+            "billing",
+            "invoice",
+            vec![make_field("id", FieldType::BigAutoField).primary_key()],
+        )
+        .with_options(ModelOptions {
+            db_table: Some("invoice".into()),
+            db_schema: Some("billing".into()),
+            ..ModelOptions::default()
+        });
+        let sqls = mysql().create_table(&model);
+        assert_eq!(sqls[0], "CREATE SCHEMA IF NOT EXISTS `billing`");
+        assert!(sqls[1].starts_with("CREATE TABLE `billing`.`invoice` ("));
+        assert_eq!(
+            mysql().drop_table("billing.invoice"),
+            vec!["DROP TABLE IF EXISTS `billing`.`invoice`".to_string()]
+        );
+        assert_eq!(
+            mysql().drop_column("billing.invoice", "note"),
+            vec!["ALTER TABLE `billing`.`invoice` DROP COLUMN `note`".to_string()]
+        );
     }
 
     // ── PostgreSQL DROP TABLE ───────────────────────────────────────
@@ -1439,6 +1550,29 @@ mod tests {
         assert!(sqls[0].contains("CREATE INDEX `idx_title`"));
     }
 
+    #[test]
+    fn test_mysql_create_index_expression() {
+        let idx = Index::new("idx_lower_email", vec!["tenant_id"]).expression("LOWER(email)");
+        let sqls = mysql().create_index("users", &idx);
+        assert_eq!(
+            sqls,
+            vec!["CREATE INDEX `idx_lower_email` ON `users` (`tenant_id`, (LOWER(email)))"]
+        );
+    }
+
+    #[test]
+    fn test_mysql_create_index_partial_fallback() {
+        let idx = Index::new("idx_live", vec!["email"]).condition("deleted_at IS NULL");
+        let sqls = mysql().create_index("users", &idx);
+        assert_eq!(sqls.len(), 2);
+        assert!(sqls[0].starts_with("-- MySQL"));
+        assert_eq!(sqls[1], "CREATE INDEX `idx_live` ON `users` (`email`)");
+
+        let sqls = mysql().create_index("users", &idx.unique(true));
+        assert_eq!(sqls.len(), 1);
+        assert!(sqls[0].starts_with("-- MySQL: skipped unique index `idx_live`"));
+    }
+
     #[test]
     fn test_mysql_drop_index() {
         let sqls = mysql().drop_index("idx_title");
@@ -1653,6 +1787,22 @@ mod tests {
         assert!(sqls[0].contains("WHERE \"is_active\" = TRUE"));
     }
 
+    #[test]
+    fn test_sqlite_create_index_expression_partial() {
+        let idx = Index::new("uniq_live_email", vec![])
+            .expression("LOWER(\"email\")")
+            .condition("\"deleted_at\" IS NULL")
+            .unique(true);
+        let sqls = sqlite().create_index("users", &idx);
+        assert_eq!(
+            sqls,
+            vec![
+                "CREATE UNIQUE INDEX \"uniq_live_email\" ON \"users\" (LOWER(\"email\")) \
+                 WHERE \"deleted_at\" IS NULL"
+            ]
+        );
+    }
+
     #[test]
     fn test_sqlite_create_index_partial() {
         let idx = Index {
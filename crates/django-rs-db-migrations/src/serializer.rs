@@ -30,6 +30,10 @@ pub struct SerializableMigration {
     /// Whether this is the initial migration for the app.
     #[serde(default)]
     pub initial: bool,
+    /// Whether the migration runs in a transaction. Only written when
+    /// `false`.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub atomic: bool,
     /// The operations to apply.
     pub operations: Vec<SerializableOperation>,
 }
@@ -59,6 +63,9 @@ pub enum SerializableOperation {
         model_name: String,
         /// The field definition.
         field: MigrationFieldDef,
+        /// Whether the default is kept after populating existing rows.
+        #[serde(default = "default_true")]
+        preserve_default: bool,
     },
     /// Remove a field/column from a model.
     RemoveField {
@@ -147,6 +154,7 @@ impl SerializableMigration {
             name: name.to_string(),
             dependencies,
             initial,
+            atomic: true,
             operations: serializable_ops,
         }
     }
@@ -183,14 +191,9 @@ impl SerializableMigration {
 impl SerializableOperation {
     /// Attempts to convert a trait-object `Operation` to a serializable form.
     ///
-    /// Uses the `describe()` method to determine the operation type and extract
-    /// relevant fields. Returns `None` for unsupported operations (e.g. `RunRust`).
+    /// Returns `None` for unsupported operations (e.g. `RunRust`).
     fn from_operation(op: &dyn Operation) -> Option<Self> {
-        // We use describe() to identify the operation type, but since we can't
-        // downcast trait objects without Any bounds, we can't extract fields.
-        // Callers should use the concrete from_create_model, from_add_field, etc.
-        let _desc = op.describe();
-        None
+        op.to_serializable()
     }
 
     /// Creates a serializable operation from a concrete `CreateModel`.
@@ -214,6 +217,7 @@ impl SerializableOperation {
         Self::AddField {
             model_name: op.model_name.clone(),
             field: op.field.clone(),
+            preserve_default: op.preserve_default,
         }
     }
 
@@ -288,9 +292,14 @@ impl SerializableOperation {
                 options: options.clone(),
             }),
             Self::DeleteModel { name } => Box::new(DeleteModel { name: name.clone() }),
-            Self::AddField { model_name, field } => Box::new(AddField {
+            Self::AddField {
+                model_name,
+                field,
+                preserve_default,
+            } => Box::new(AddField {
                 model_name: model_name.clone(),
                 field: field.clone(),
+                preserve_default: *preserve_default,
             }),
             Self::RemoveField {
                 model_name,
@@ -366,6 +375,56 @@ pub fn generate_migration_name(number: u32, custom_name: Option<&str>) -> String
     }
 }
 
+/// Builds a merge migration that joins the given leaf migrations of an app.
+///
+/// The merge migration has no operations and depends on every leaf, so the
+/// diverged branches become a single history again. It is numbered after
+/// the highest-numbered leaf and named after the leaves, e.g.
+/// `0003_merge_add_author_add_tags`, unless `custom_name` is given. This
+/// mirrors `makemigrations --merge`.
+pub fn merge_migration(
+    app_label: &str,
+    leaves: &[(String, String)],
+    custom_name: Option<&str>,
+) -> SerializableMigration {
+    let number = leaves
+        .iter()
+        .filter_map(|(_, name)| name.split('_').next()?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let name = custom_name.map_or_else(
+        || {
+            let mut parts: Vec<&str> = leaves
+                .iter()
+                .map(|(_, name)| {
+                    name.split_once('_')
+                        .filter(|(num, _)| num.parse::<u32>().is_ok())
+                        .map_or(name.as_str(), |(_, rest)| rest)
+                })
+                .collect();
+            parts.sort_unstable();
+            let joined = format!("merge_{}", parts.join("_"));
+            // Keep generated names readable, like Django does.
+            if joined.len() > 52 {
+                format!("merge_{}", chrono::Utc::now().format("%Y%m%d_%H%M"))
+            } else {
+                joined
+            }
+        },
+        ToString::to_string,
+    );
+
+    SerializableMigration {
+        app_label: app_label.to_string(),
+        name: format!("{number:04}_{name}"),
+        dependencies: leaves.to_vec(),
+        initial: false,
+        atomic: true,
+        operations: vec![],
+    }
+}
+
 /// Determines the next migration number for an app by scanning existing files.
 pub fn next_migration_number(migrations_dir: &Path, app_label: &str) -> u32 {
     let app_dir = migrations_dir.join(app_label);
@@ -398,6 +457,17 @@ pub fn migration_file_path(migrations_dir: &Path, app_label: &str, name: &str) -
     migrations_dir.join(app_label).join(format!("{name}.json"))
 }
 
+/// Serde default for flags that are on unless a migration file says otherwise.
+const fn default_true() -> bool {
+    true
+}
+
+/// Skips serializing flags left at their `true` default.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_true(value: &bool) -> bool {
+    *value
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,6 +487,7 @@ mod tests {
             name: "0001_initial".into(),
             dependencies: vec![],
             initial: true,
+            atomic: true,
             operations: vec![SerializableOperation::CreateModel {
                 name: "post".into(),
                 fields: vec![
@@ -436,6 +507,19 @@ mod tests {
         assert_eq!(deserialized.operations.len(), 1);
     }
 
+    #[test]
+    fn test_serialize_non_atomic_migration() {
+        let mut migration = merge_migration("blog", &[], Some("concurrent_index"));
+        let json = migration.to_json().unwrap();
+        assert!(!json.contains("atomic"));
+        assert!(SerializableMigration::from_json(&json).unwrap().atomic);
+
+        migration.atomic = false;
+        let json = migration.to_json().unwrap();
+        assert!(json.contains("\"atomic\": false"));
+        assert!(!SerializableMigration::from_json(&json).unwrap().atomic);
+    }
+
     #[test]
     fn test_serializable_migration_all_operations() {
         let migration = SerializableMigration {
@@ -443,6 +527,7 @@ mod tests {
             name: "0002_changes".into(),
             dependencies: vec![("myapp".into(), "0001_initial".into())],
             initial: false,
+            atomic: true,
             operations: vec![
                 SerializableOperation::CreateModel {
                     name: "user".into(),
@@ -455,6 +540,7 @@ mod tests {
                 SerializableOperation::AddField {
                     model_name: "user".into(),
                     field: make_field("email", FieldType::EmailField).max_length(254),
+                    preserve_default: true,
                 },
                 SerializableOperation::RemoveField {
                     model_name: "user".into(),
@@ -533,6 +619,7 @@ mod tests {
         let op = SerializableOperation::AddField {
             model_name: "post".into(),
             field: make_field("title", FieldType::CharField).max_length(200),
+            preserve_default: true,
         };
         let boxed = op.to_operation();
         assert!(boxed.describe().contains("Add field"));
@@ -616,9 +703,13 @@ mod tests {
         let op = AddField {
             model_name: "post".into(),
             field: make_field("title", FieldType::CharField).max_length(200),
+            preserve_default: true,
         };
         let ser = SerializableOperation::from_add_field(&op);
-        if let SerializableOperation::AddField { model_name, field } = ser {
+        if let SerializableOperation::AddField {
+            model_name, field, ..
+        } = ser
+        {
             assert_eq!(model_name, "post");
             assert_eq!(field.name, "title");
         } else {
@@ -710,6 +801,7 @@ mod tests {
             name: "0001_initial".into(),
             dependencies: vec![],
             initial: true,
+            atomic: true,
             operations: vec![SerializableOperation::CreateModel {
                 name: "post".into(),
                 fields: vec![
@@ -829,4 +921,19 @@ mod tests {
         assert_eq!(deserialized.fields, vec!["email".to_string()]);
         assert!(deserialized.unique);
     }
+
+    #[test]
+    fn test_merge_migration() {
+        let leaves = vec![
+            ("blog".to_string(), "0002_add_tags".to_string()),
+            ("blog".to_string(), "0003_add_author".to_string()),
+        ];
+        let merge = merge_migration("blog", &leaves, None);
+        assert_eq!(merge.name, "0004_merge_add_author_add_tags");
+        assert_eq!(merge.dependencies, leaves);
+        assert!(merge.operations.is_empty());
+
+        let named = merge_migration("blog", &leaves, Some("join_branches"));
+        assert_eq!(named.name, "0004_join_branches");
+    }
 }
@@ -8,6 +8,7 @@ use crate::operations::{
     AddField, AddIndex, AlterField, AlterUniqueTogether, CreateModel, DeleteModel, Operation,
     RemoveField, RemoveIndex, RenameField, RunSQL,
 };
+use crate::serializer::{SerializableMigration, SerializableOperation};
 use django_rs_db::model::Index;
 
 /// Combines multiple migrations into a single optimized migration.
@@ -41,6 +42,39 @@ impl MigrationSquasher {
         result
     }
 
+    /// Optimizes the operations of a single migration.
+    ///
+    /// This is what the `optimizemigration` command runs. `AddField`
+    /// operations that do not preserve their default cannot be represented
+    /// as a [`SquashableOp`]; they are kept in place and operations are
+    /// only optimized within the runs between them.
+    pub fn optimize_migration(migration: &SerializableMigration) -> SerializableMigration {
+        let mut operations = Vec::with_capacity(migration.operations.len());
+        let mut run = Vec::new();
+        for op in &migration.operations {
+            if let Some(squashable) = SquashableOp::from_serializable(op) {
+                run.push(squashable);
+            } else {
+                operations.extend(
+                    Self::squash(std::mem::take(&mut run))
+                        .into_iter()
+                        .map(SquashableOp::into_serializable),
+                );
+                operations.push(op.clone());
+            }
+        }
+        operations.extend(
+            Self::squash(run)
+                .into_iter()
+                .map(SquashableOp::into_serializable),
+        );
+
+        SerializableMigration {
+            operations,
+            ..migration.clone()
+        }
+    }
+
     /// Runs a single optimization pass.
     fn optimize_pass(operations: Vec<SquashableOp>) -> Vec<SquashableOp> {
         let mut result: Vec<SquashableOp> = Vec::new();
@@ -300,6 +334,150 @@ pub enum SquashableOp {
 }
 
 impl SquashableOp {
+    /// Converts a serialized operation into a squashable one.
+    ///
+    /// Returns `None` for an `AddField` that does not preserve its default,
+    /// which has no squashable equivalent.
+    pub fn from_serializable(op: &SerializableOperation) -> Option<Self> {
+        Some(match op.clone() {
+            SerializableOperation::CreateModel {
+                name,
+                fields,
+                options,
+            } => SquashableOp::CreateModel {
+                name,
+                fields,
+                options,
+            },
+            SerializableOperation::DeleteModel { name } => SquashableOp::DeleteModel { name },
+            SerializableOperation::AddField {
+                model_name,
+                field,
+                preserve_default: true,
+            } => SquashableOp::AddField { model_name, field },
+            SerializableOperation::AddField { .. } => return None,
+            SerializableOperation::RemoveField {
+                model_name,
+                field_name,
+            } => SquashableOp::RemoveField {
+                model_name,
+                field_name,
+            },
+            SerializableOperation::AlterField {
+                model_name,
+                field_name,
+                field,
+            } => SquashableOp::AlterField {
+                model_name,
+                field_name,
+                field,
+            },
+            SerializableOperation::RenameField {
+                model_name,
+                old_name,
+                new_name,
+            } => SquashableOp::RenameField {
+                model_name,
+                old_name,
+                new_name,
+            },
+            SerializableOperation::AddIndex { model_name, index } => {
+                SquashableOp::AddIndex { model_name, index }
+            }
+            SerializableOperation::RemoveIndex {
+                model_name,
+                index_name,
+            } => SquashableOp::RemoveIndex {
+                model_name,
+                index_name,
+            },
+            SerializableOperation::AlterUniqueTogether {
+                model_name,
+                unique_together,
+            } => SquashableOp::AlterUniqueTogether {
+                model_name,
+                unique_together,
+            },
+            SerializableOperation::RunSQL {
+                sql_forwards,
+                sql_backwards,
+            } => SquashableOp::RunSQL {
+                sql_forwards,
+                sql_backwards,
+            },
+        })
+    }
+
+    /// Converts this squashable operation back into its serialized form.
+    pub fn into_serializable(self) -> SerializableOperation {
+        match self {
+            SquashableOp::CreateModel {
+                name,
+                fields,
+                options,
+            } => SerializableOperation::CreateModel {
+                name,
+                fields,
+                options,
+            },
+            SquashableOp::DeleteModel { name } => SerializableOperation::DeleteModel { name },
+            SquashableOp::AddField { model_name, field } => SerializableOperation::AddField {
+                model_name,
+                field,
+                preserve_default: true,
+            },
+            SquashableOp::RemoveField {
+                model_name,
+                field_name,
+            } => SerializableOperation::RemoveField {
+                model_name,
+                field_name,
+            },
+            SquashableOp::AlterField {
+                model_name,
+                field_name,
+                field,
+            } => SerializableOperation::AlterField {
+                model_name,
+                field_name,
+                field,
+            },
+            SquashableOp::RenameField {
+                model_name,
+                old_name,
+                new_name,
+            } => SerializableOperation::RenameField {
+                model_name,
+                old_name,
+                new_name,
+            },
+            SquashableOp::AddIndex { model_name, index } => {
+                SerializableOperation::AddIndex { model_name, index }
+            }
+            SquashableOp::RemoveIndex {
+                model_name,
+                index_name,
+            } => SerializableOperation::RemoveIndex {
+                model_name,
+                index_name,
+            },
+            SquashableOp::AlterUniqueTogether {
+                model_name,
+                unique_together,
+            } => SerializableOperation::AlterUniqueTogether {
+                model_name,
+                unique_together,
+            },
+            SquashableOp::RunSQL {
+                sql_forwards,
+                sql_backwards,
+            } => SerializableOperation::RunSQL {
+                sql_forwards,
+                sql_backwards,
+            },
+        }
+    }
+
     /// Converts this squashable operation to a boxed `dyn Operation`.
     pub fn to_operation(self) -> Box<dyn Operation> {
         match self {
@@ -313,9 +491,11 @@ impl SquashableOp {
                 options,
             }),
             SquashableOp::DeleteModel { name } => Box::new(DeleteModel { name }),
-            SquashableOp::AddField { model_name, field } => {
-                Box::new(AddField { model_name, field })
-            }
+            SquashableOp::AddField { model_name, field } => Box::new(AddField {
+                model_name,
+                field,
+                preserve_default: true,
+            }),
             SquashableOp::RemoveField {
                 model_name,
                 field_name,
@@ -788,4 +968,62 @@ mod tests {
             panic!("Expected CreateModel for post");
         }
     }
+
+    // ── optimize_migration ──────────────────────────────────────────
+
+    #[test]
+    fn test_optimize_migration() {
+        let migration = SerializableMigration {
+            app_label: "blog".into(),
+            name: "0002_auto".into(),
+            dependencies: vec![("blog".into(), "0001_initial".into())],
+            initial: false,
+            atomic: true,
+            operations: vec![
+                SerializableOperation::AddField {
+                    model_name: "post".into(),
+                    field: make_field("title", FieldType::CharField),
+                    preserve_default: true,
+                },
+                SerializableOperation::AlterField {
+                    model_name: "post".into(),
+                    field_name: "title".into(),
+                    field: make_field("title", FieldType::TextField),
+                },
+                SerializableOperation::AddField {
+                    model_name: "post".into(),
+                    field: make_field("slug", FieldType::SlugField),
+                    preserve_default: false,
+                },
+                SerializableOperation::AddField {
+                    model_name: "post".into(),
+                    field: make_field("draft", FieldType::BooleanField),
+                    preserve_default: true,
+                },
+                SerializableOperation::RemoveField {
+                    model_name: "post".into(),
+                    field_name: "draft".into(),
+                },
+            ],
+        };
+
+        let optimized = MigrationSquasher::optimize_migration(&migration);
+        assert_eq!(optimized.name, "0002_auto");
+        assert_eq!(optimized.dependencies, migration.dependencies);
+        assert_eq!(optimized.operations.len(), 2);
+        match &optimized.operations[0] {
+            SerializableOperation::AddField { field, .. } => {
+                assert_eq!(field.name, "title");
+                assert!(matches!(field.field_type, FieldType::TextField));
+            }
+            other => panic!("expected AddField, got {other:?}"),
+        }
+        assert!(matches!(
+            &optimized.operations[1],
+            SerializableOperation::AddField {
+                preserve_default: false,
+                ..
+            }
+        ));
+    }
 }
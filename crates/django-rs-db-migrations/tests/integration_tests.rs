@@ -147,6 +147,7 @@ async fn test_execute_multiple_migrations_in_sequence() {
     let ops2: Vec<Box<dyn Operation>> = vec![Box::new(AddField {
         model_name: "post".into(),
         field: make_field("title", FieldType::CharField).max_length(200),
+        preserve_default: true,
     })];
 
     let mut operations2 = HashMap::new();
@@ -492,6 +493,7 @@ fn test_serialization_roundtrip_create_model() {
         name: "0001_initial".into(),
         dependencies: vec![],
         initial: true,
+        atomic: true,
         operations: vec![SerializableOperation::CreateModel {
             name: "post".into(),
             fields: vec![
@@ -526,10 +528,12 @@ fn test_serialization_roundtrip_multiple_operations() {
         name: "0002_changes".into(),
         dependencies: vec![("myapp".into(), "0001_initial".into())],
         initial: false,
+        atomic: true,
         operations: vec![
             SerializableOperation::AddField {
                 model_name: "post".into(),
                 field: make_field("slug", FieldType::SlugField).max_length(100),
+                preserve_default: true,
             },
             SerializableOperation::RemoveField {
                 model_name: "post".into(),
@@ -565,6 +569,7 @@ fn test_file_write_read_roundtrip() {
         name: "0001_initial".into(),
         dependencies: vec![],
         initial: true,
+        atomic: true,
         operations: vec![SerializableOperation::CreateModel {
             name: "product".into(),
             fields: vec![
@@ -874,6 +879,7 @@ fn test_serializable_ops_to_real_operations() {
         SerializableOperation::AddField {
             model_name: "user".into(),
             field: make_field("email", FieldType::EmailField).max_length(254),
+            preserve_default: true,
         },
         SerializableOperation::RemoveField {
             model_name: "user".into(),
@@ -908,6 +914,7 @@ async fn test_end_to_end_serialize_load_execute() {
         name: "0001_initial".into(),
         dependencies: vec![],
         initial: true,
+        atomic: true,
         operations: vec![SerializableOperation::CreateModel {
             name: "product".into(),
             fields: vec![
@@ -1021,6 +1028,7 @@ async fn test_add_field_backward_drops_column() {
     let ops: Vec<Box<dyn Operation>> = vec![Box::new(AddField {
         model_name: "post".into(),
         field: make_field("title", FieldType::CharField).max_length(200),
+        preserve_default: true,
     })];
 
     let mut operations = HashMap::new();
@@ -1186,6 +1194,7 @@ async fn test_execute_two_step_plan() {
     let ops2: Vec<Box<dyn Operation>> = vec![Box::new(AddField {
         model_name: "post".into(),
         field: make_field("body", FieldType::TextField).nullable(),
+        preserve_default: true,
     })];
 
     let mut operations = HashMap::new();
@@ -1264,6 +1273,7 @@ async fn test_data_survives_add_field_migration() {
     let ops2: Vec<Box<dyn Operation>> = vec![Box::new(AddField {
         model_name: "post".into(),
         field: make_field("body", FieldType::TextField).nullable(),
+        preserve_default: true,
     })];
 
     let mut ops2_map = HashMap::new();
@@ -1390,6 +1400,7 @@ fn test_serializable_migration_with_dependencies() {
             ("auth".into(), "0001_initial".into()),
         ],
         initial: false,
+        atomic: true,
         operations: vec![],
     };
 
@@ -1512,6 +1523,7 @@ fn test_serialize_alter_unique_together() {
         name: "0003_unique".into(),
         dependencies: vec![("blog".into(), "0002_changes".into())],
         initial: false,
+        atomic: true,
         operations: vec![SerializableOperation::AlterUniqueTogether {
             model_name: "post".into(),
             unique_together: vec![vec!["author".into(), "slug".into()]],
@@ -1553,6 +1565,7 @@ fn test_serialize_field_with_defaults() {
         name: "0001".into(),
         dependencies: vec![],
         initial: true,
+        atomic: true,
         operations: vec![SerializableOperation::CreateModel {
             name: "config".into(),
             fields: vec![
@@ -1583,3 +1596,218 @@ fn test_serialize_field_with_defaults() {
         panic!("Expected CreateModel");
     }
 }
+
+// ── 44. Schema history audit columns ────────────────────────────────────
+
+#[tokio::test]
+async fn test_execute_records_schema_history() {
+    let backend = SqliteBackend::memory().unwrap();
+    let key = ("blog".to_string(), "0001_initial".to_string());
+    let mut executor =
+        sqlite_executor().with_checksums(HashMap::from([(key.clone(), "c0ffee".to_string())]));
+
+    let mut plan = MigrationPlan::new();
+    plan.add_step(MigrationStep::forward("blog", "0001_initial"));
+    let ops: Vec<Box<dyn Operation>> = vec![Box::new(RunSQL {
+        sql_forwards: "CREATE TABLE \"audit_probe\" (\"id\" INTEGER)".into(),
+        sql_backwards: String::new(),
+    })];
+    let mut operations = HashMap::new();
+    operations.insert(key.clone(), ops);
+
+    executor
+        .execute_against_db(&plan, &operations, &ProjectState::new(), &backend, false)
+        .await
+        .unwrap();
+
+    let mut recorder = MigrationRecorder::new();
+    recorder.load_from_db(&backend).await.unwrap();
+    let record = recorder.record(&key).unwrap();
+    assert_eq!(record.checksum.as_deref(), Some("c0ffee"));
+    assert!(record.duration_ms.is_some());
+    assert!(record.applied_by.as_deref().unwrap().contains('@'));
+    assert_eq!(
+        record.version.as_deref(),
+        Some(django_rs_db_migrations::executor::DJANGO_RS_VERSION)
+    );
+    assert!(record.applied.is_some());
+}
+
+#[tokio::test]
+async fn test_ensure_table_upgrades_old_history_table() {
+    let backend = SqliteBackend::memory().unwrap();
+    backend
+        .execute(
+            "CREATE TABLE \"django_migrations\" (\
+                \"id\" INTEGER PRIMARY KEY AUTOINCREMENT, \
+                \"app\" TEXT NOT NULL, \
+                \"name\" TEXT NOT NULL, \
+                \"applied\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            &[],
+        )
+        .await
+        .unwrap();
+    backend
+        .execute(
+            &MigrationRecorder::record_applied_sql("blog", "0001_initial"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let mut recorder = MigrationRecorder::new();
+    recorder.load_from_db(&backend).await.unwrap();
+    let record = recorder
+        .record(&("blog".into(), "0001_initial".into()))
+        .unwrap();
+    assert_eq!(record.checksum, None);
+    assert_eq!(record.applied_by, None);
+}
+
+#[tokio::test]
+async fn test_read_from_db_leaves_old_history_table_alone() {
+    let backend = SqliteBackend::memory().unwrap();
+    let mut recorder = MigrationRecorder::new();
+    recorder.read_from_db(&backend).await.unwrap();
+    assert!(recorder.applied().is_empty());
+    assert!(!table_exists(&backend, "django_migrations").await);
+
+    backend
+        .execute(
+            "CREATE TABLE \"django_migrations\" (\
+                \"id\" INTEGER PRIMARY KEY AUTOINCREMENT, \
+                \"app\" TEXT NOT NULL, \
+                \"name\" TEXT NOT NULL, \
+                \"applied\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            &[],
+        )
+        .await
+        .unwrap();
+    backend
+        .execute(
+            &MigrationRecorder::record_applied_sql("blog", "0001_initial"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    recorder.read_from_db(&backend).await.unwrap();
+    let record = recorder
+        .record(&("blog".into(), "0001_initial".into()))
+        .unwrap();
+    assert_eq!(record.checksum, None);
+    assert!(record.applied.is_some());
+    let columns = backend
+        .query(
+            "SELECT name FROM pragma_table_info('django_migrations')",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(columns.len(), 4);
+}
+
+// ── Atomic migrations and the migration lock ────────────────────────────
+
+/// A migration that creates `blog_post`, then runs `sql`.
+fn create_then_sql(sql: &str) -> HashMap<(String, String), Vec<Box<dyn Operation>>> {
+    let ops: Vec<Box<dyn Operation>> = vec![
+        Box::new(CreateModel {
+            name: "post".into(),
+            fields: vec![make_field("id", FieldType::BigAutoField).primary_key()],
+            options: ModelOptions::default(),
+        }),
+        Box::new(RunSQL {
+            sql_forwards: sql.into(),
+            sql_backwards: String::new(),
+        }),
+    ];
+    HashMap::from([(("blog".into(), "0001_initial".into()), ops)])
+}
+
+async fn table_exists(backend: &SqliteBackend, table: &str) -> bool {
+    !backend
+        .query(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name=?",
+            &[Value::from(table)],
+        )
+        .await
+        .unwrap()
+        .is_empty()
+}
+
+#[tokio::test]
+async fn test_failed_atomic_migration_is_rolled_back() {
+    let backend = SqliteBackend::memory().unwrap();
+    let mut executor = sqlite_executor();
+    let mut plan = MigrationPlan::new();
+    plan.add_step(MigrationStep::forward("blog", "0001_initial"));
+
+    let result = executor
+        .execute_against_db(
+            &plan,
+            &create_then_sql("NOT VALID SQL"),
+            &ProjectState::new(),
+            &backend,
+            false,
+        )
+        .await;
+    assert!(result.is_err());
+    assert!(!table_exists(&backend, "blog_post").await);
+
+    let mut recorder = MigrationRecorder::new();
+    recorder.load_from_db(&backend).await.unwrap();
+    assert!(recorder.applied().is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_non_atomic_migration_keeps_earlier_statements() {
+    let backend = SqliteBackend::memory().unwrap();
+    let key = ("blog".to_string(), "0001_initial".to_string());
+    let mut executor = sqlite_executor().with_non_atomic([key.clone()].into());
+    let mut plan = MigrationPlan::new();
+    plan.add_step(MigrationStep::forward("blog", "0001_initial"));
+
+    let result = executor
+        .execute_against_db(
+            &plan,
+            &create_then_sql("NOT VALID SQL"),
+            &ProjectState::new(),
+            &backend,
+            false,
+        )
+        .await;
+    assert!(result.is_err());
+    assert!(table_exists(&backend, "blog_post").await);
+    assert!(!executor.recorder().is_applied(&key));
+}
+
+#[tokio::test]
+async fn test_concurrent_deploy_skips_recorded_migrations() {
+    let backend = SqliteBackend::memory().unwrap();
+    let mut plan = MigrationPlan::new();
+    plan.add_step(MigrationStep::forward("blog", "0001_initial"));
+    let operations = create_then_sql("CREATE INDEX \"blog_post_idx\" ON \"blog_post\" (\"id\")");
+
+    // Both replicas planned against an empty database.
+    let mut first = sqlite_executor();
+    let mut second = sqlite_executor();
+    first
+        .execute_against_db(&plan, &operations, &ProjectState::new(), &backend, false)
+        .await
+        .unwrap();
+    let sql = second
+        .execute_against_db(&plan, &operations, &ProjectState::new(), &backend, false)
+        .await
+        .unwrap();
+
+    assert!(sql.is_empty());
+    assert!(second
+        .recorder()
+        .is_applied(&("blog".into(), "0001_initial".into())));
+    let rows = backend
+        .query("SELECT COUNT(*) AS n FROM django_migrations", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<i64>("n").unwrap(), 1);
+}
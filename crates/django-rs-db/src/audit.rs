@@ -0,0 +1,208 @@
+//! Audit context attached to every query issued for a request or task.
+//!
+//! An [`AuditContext`] records who is acting (user id), on whose behalf
+//! (request id) and why (reason). Running a future inside
+//! [`AuditContext::scope`] makes the context current for everything that
+//! future does: the database backends append it to each statement as a
+//! [sqlcommenter](https://google.github.io/sqlcommenter/) comment, and signal
+//! receivers or audit log writers read it with [`AuditContext::current`].
+//!
+//! The context is task-local, so it does not leak between concurrent requests.
+//! Work moved to another task with `tokio::spawn` must be scoped again.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::audit::AuditContext;
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let context = AuditContext::new().user_id("42").request_id("req-7");
+//! context
+//!     .scope(async {
+//!         let current = AuditContext::current().unwrap();
+//!         assert_eq!(current.user_id.as_deref(), Some("42"));
+//!     })
+//!     .await;
+//! assert!(AuditContext::current().is_none());
+//! # });
+//! ```
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+use crate::query::comment::QueryComment;
+use crate::query::compiler::DatabaseBackendType;
+
+tokio::task_local! {
+    static CURRENT: AuditContext;
+}
+
+/// Attribution for the statements run by a request or background task.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditContext {
+    /// The id of the user the work is done for.
+    pub user_id: Option<String>,
+    /// An identifier correlating the statements of one request or job.
+    pub request_id: Option<String>,
+    /// Why the work is being done, e.g. `"gdpr-erasure"`.
+    pub reason: Option<String>,
+}
+
+impl AuditContext {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the acting user's id.
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Sets the request or job identifier.
+    pub fn request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Sets the reason for the work.
+    pub fn reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Returns `true` if no field is set.
+    pub fn is_empty(&self) -> bool {
+        self.user_id.is_none() && self.request_id.is_none() && self.reason.is_none()
+    }
+
+    /// Returns the context of the current task, if one is in scope.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Runs `future` with this context as the current one.
+    ///
+    /// Scopes nest: an inner scope replaces the outer context until it
+    /// completes. To add a reason to the context already in effect, extend
+    /// [`current`](Self::current):
+    ///
+    /// ```
+    /// use django_rs_db::audit::AuditContext;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// AuditContext::new().user_id("42").scope(async {
+    ///     AuditContext::current()
+    ///         .unwrap_or_default()
+    ///         .reason("bulk-reindex")
+    ///         .scope(async {
+    ///             let current = AuditContext::current().unwrap();
+    ///             assert_eq!(current.user_id.as_deref(), Some("42"));
+    ///             assert_eq!(current.reason.as_deref(), Some("bulk-reindex"));
+    ///         })
+    ///         .await;
+    /// })
+    /// .await;
+    /// # });
+    /// ```
+    pub fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        CURRENT.scope(self, future)
+    }
+
+    /// Runs the closure `f` with this context as the current one.
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        CURRENT.sync_scope(self, f)
+    }
+
+    /// Returns the context as sqlcommenter tags: `user_id`, `request_id` and
+    /// `reason`, each only if set.
+    pub fn to_comment(&self) -> QueryComment {
+        let mut comment = QueryComment::default();
+        let fields = [
+            ("user_id", &self.user_id),
+            ("request_id", &self.request_id),
+            ("reason", &self.reason),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                comment.add_tag(key, value);
+            }
+        }
+        comment
+    }
+}
+
+/// Appends the current [`AuditContext`] to `sql` as a sqlcommenter comment.
+///
+/// Backends call this on every statement before running it. Without a context
+/// in scope, or with an empty one, `sql` is returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_db::audit::{annotate_sql, AuditContext};
+/// use django_rs_db::query::compiler::DatabaseBackendType;
+///
+/// let sql = AuditContext::new().user_id("42").sync_scope(|| {
+///     annotate_sql("SELECT 1", DatabaseBackendType::SQLite)
+/// });
+/// assert_eq!(sql, "SELECT 1 /*user_id='42'*/");
+/// assert_eq!(annotate_sql("SELECT 1", DatabaseBackendType::SQLite), "SELECT 1");
+/// ```
+pub fn annotate_sql(sql: &str, backend: DatabaseBackendType) -> String {
+    match AuditContext::current() {
+        Some(context) if !context.is_empty() => {
+            context.to_comment().apply(sql.to_string(), backend)
+        }
+        _ => sql.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_comment_skips_unset_fields() {
+        let context = AuditContext::new().user_id("7").reason("clean up");
+        assert_eq!(
+            context.to_comment().render_tags().unwrap(),
+            "/*reason='clean%20up',user_id='7'*/"
+        );
+        assert!(AuditContext::new().to_comment().is_empty());
+    }
+
+    #[test]
+    fn test_annotate_sql_escapes_values() {
+        let sql = AuditContext::new()
+            .reason("*/ DROP TABLE t; /*")
+            .sync_scope(|| annotate_sql("DELETE FROM t;", DatabaseBackendType::PostgreSQL));
+        assert!(sql.starts_with("DELETE FROM t /*reason='"), "{sql}");
+        assert!(sql.ends_with("'*/;"), "{sql}");
+        assert_eq!(sql.matches("*/").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scope_is_task_local_and_nests() {
+        let outer = AuditContext::new().user_id("1").request_id("r1");
+        outer
+            .scope(async {
+                let inner = AuditContext::current().unwrap().reason("import");
+                inner
+                    .scope(async {
+                        let current = AuditContext::current().unwrap();
+                        assert_eq!(current.request_id.as_deref(), Some("r1"));
+                        assert_eq!(current.reason.as_deref(), Some("import"));
+                    })
+                    .await;
+                assert_eq!(AuditContext::current().unwrap().reason, None);
+
+                let spawned = tokio::spawn(async { AuditContext::current() });
+                assert!(spawned.await.unwrap().is_none());
+            })
+            .await;
+        assert!(AuditContext::current().is_none());
+    }
+}
@@ -0,0 +1,771 @@
+//! System checks for model metadata.
+//!
+//! Mirrors the model checks Django runs from `manage.py check`. Models are
+//! registered in the [model registry](crate::registry) at startup, and
+//! [`check_registered_models`] checks them; it has the
+//! [`CheckFn`](django_rs_core::checks::CheckFn) signature, so it can also be
+//! added to a [`CheckRegistry`](django_rs_core::checks::CheckRegistry).
+//!
+//! | ID | Level | Problem |
+//! |----|-------|---------|
+//! | `fields.E001` | Error | Field name ends with an underscore |
+//! | `fields.E002` | Error | Field name contains `__` |
+//! | `fields.E003` | Error | Field named `pk` |
+//! | `fields.W001` | Warning | Field name or column is an SQL reserved word |
+//! | `fields.E120` | Error | `CharField` without `max_length` |
+//! | `fields.E300` | Error | Relation to a model that is not registered or is abstract |
+//! | `fields.E302` | Error | Reverse accessor clashes with a field of the target model |
+//! | `fields.E304` | Error | Two relations to a model share a reverse accessor |
+//! | `fields.E306` | Error | `related_name` is not a valid identifier |
+//! | `fields.E320` | Error | `on_delete=SET_NULL` on a non-nullable field |
+//! | `fields.E321` | Error | `on_delete=SET_DEFAULT` on a field without a default |
+//! | `fields.W390` | Warning | Relation to a model read from another database |
+//! | `models.E015` | Error | `ordering` refers to a nonexistent field |
+//! | `models.E028` | Error | Several models use the same `db_table` |
+//! | `fields.E910` | Error | Encrypted field with a `hash_field` but no `field_hash_key` setting |
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::checks::check_models;
+//! use django_rs_db::fields::{FieldDef, FieldType};
+//! use django_rs_db::model::ModelMeta;
+//! use django_rs_db::query::compiler::{InheritanceType, OrderBy};
+//!
+//! let meta = ModelMeta {
+//!     app_label: "blog",
+//!     model_name: "post",
+//!     db_table: "blog_post".to_string(),
+//!     verbose_name: "post".to_string(),
+//!     verbose_name_plural: "posts".to_string(),
+//!     ordering: vec![OrderBy::desc("published")],
+//!     unique_together: vec![],
+//!     indexes: vec![],
+//!     abstract_model: false,
+//!     fields: vec![
+//!         FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+//!         FieldDef::new("title", FieldType::CharField),
+//!     ],
+//!     constraints: vec![],
+//!     inheritance_type: InheritanceType::None,
+//!     db_schema: None,
+//! };
+//!
+//! let ids: Vec<_> = check_models(&[&meta])
+//!     .into_iter()
+//!     .filter_map(|message| message.id)
+//!     .collect();
+//! assert_eq!(ids, ["fields.E120", "models.E015"]);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::PoisonError;
+
+use django_rs_core::checks::CheckMessage;
+use django_rs_core::Settings;
+
+use crate::fields::{FieldDef, FieldType, OnDelete};
+use crate::model::{Model, ModelMeta};
+use crate::query::compiler::InheritanceType;
+use crate::router::{RouterChain, ROUTER};
+
+/// SQL keywords reserved by PostgreSQL, SQLite or MySQL that are likely to be
+/// picked as field names.
+const RESERVED_WORDS: &[&str] = &[
+    "all",
+    "and",
+    "as",
+    "asc",
+    "between",
+    "by",
+    "case",
+    "check",
+    "column",
+    "constraint",
+    "create",
+    "default",
+    "delete",
+    "desc",
+    "distinct",
+    "drop",
+    "else",
+    "end",
+    "except",
+    "exists",
+    "from",
+    "group",
+    "having",
+    "in",
+    "index",
+    "insert",
+    "into",
+    "is",
+    "join",
+    "key",
+    "like",
+    "limit",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "set",
+    "table",
+    "then",
+    "to",
+    "union",
+    "unique",
+    "update",
+    "user",
+    "values",
+    "when",
+    "where",
+];
+
+/// Registers a model in [`MODELS`](crate::registry::MODELS) to be checked by
+/// [`check_registered_models`].
+///
+/// Registering a model twice has no effect.
+pub fn register_model<M: Model>() {
+    crate::registry::MODELS.register::<M>();
+}
+
+/// Returns the models in [`MODELS`](crate::registry::MODELS), in
+/// registration order.
+pub fn registered_models() -> Vec<&'static ModelMeta> {
+    crate::registry::MODELS.models()
+}
+
+/// Checks the models in [`MODELS`](crate::registry::MODELS), the settings
+/// they depend on, and their relations across the databases of
+/// [`ROUTER`].
+pub fn check_registered_models(settings: &Settings) -> Vec<CheckMessage> {
+    let models = registered_models();
+    let mut messages = check_models(&models);
+    messages.extend(check_model_settings(&models, settings));
+    messages.extend(check_installed_router(&models));
+    messages
+}
+
+/// Checks the relations of `models` against the routers in [`ROUTER`].
+pub fn check_installed_router(models: &[&ModelMeta]) -> Vec<CheckMessage> {
+    let router = ROUTER.read().unwrap_or_else(PoisonError::into_inner);
+    check_database_relations(models, &router)
+}
+
+/// Checks that the relations of `models` stay on one database.
+///
+/// A relation whose target `router` reads from another database than its
+/// model can't be followed with a JOIN: `select_related` and subqueries
+/// would read the target's table on the wrong database. Such relations must
+/// be loaded with `prefetch_related` or
+/// [`QuerySet::separate_query`](crate::query::QuerySet::separate_query).
+pub fn check_database_relations(models: &[&ModelMeta], router: &RouterChain) -> Vec<CheckMessage> {
+    let mut messages = Vec::new();
+    for meta in models.iter().filter(|m| !m.abstract_model) {
+        let db = router.db_for_read(meta.app_label, meta.model_name);
+        for field in &meta.fields {
+            let (FieldType::ForeignKey { to, .. }
+            | FieldType::OneToOneField { to, .. }
+            | FieldType::ManyToManyField { to, .. }) = &field.field_type
+            else {
+                continue;
+            };
+            let Some(target) = resolve_target(models, to).filter(|t| !t.abstract_model) else {
+                continue;
+            };
+            let target_db = router.db_for_read(target.app_label, target.model_name);
+            if target_db != db {
+                let obj = field_label(meta, field);
+                messages.push(CheckMessage::warning(
+                    format!(
+                        "Field '{obj}' relates '{}' (database '{db}') to '{}' (database \
+                         '{target_db}'), and a JOIN cannot span databases.",
+                        model_label(meta),
+                        model_label(target),
+                    ),
+                    Some(
+                        "Load the relation with prefetch_related() or separate_query(), or \
+                         route both models to the same database.",
+                    ),
+                    Some(&obj),
+                    Some("fields.W390"),
+                ));
+            }
+        }
+    }
+    messages
+}
+
+/// Checks the settings `models` depend on: encrypted fields with a
+/// `hash_field` need `field_hash_key`.
+pub fn check_model_settings(models: &[&ModelMeta], settings: &Settings) -> Vec<CheckMessage> {
+    if !settings.field_hash_key.is_empty() {
+        return Vec::new();
+    }
+    models
+        .iter()
+        .flat_map(|meta| meta.fields.iter().map(move |field| (meta, field)))
+        .filter(|(_, field)| crate::encryption::hash_field(field).is_some())
+        .map(|(meta, field)| {
+            let obj = field_label(meta, field);
+            CheckMessage::error(
+                format!("Encrypted field '{obj}' has a hash_field, but field_hash_key is not set."),
+                Some("Set field_hash_key to a random secret used only for blind indexes."),
+                Some(&obj),
+                Some("fields.E910"),
+            )
+        })
+        .collect()
+}
+
+/// Checks the metadata of `models`, and the relations between them.
+///
+/// Relations are resolved against `models` only, by `"app_label.model_name"`
+/// (case-insensitively) or by table name.
+pub fn check_models(models: &[&ModelMeta]) -> Vec<CheckMessage> {
+    let mut messages = Vec::new();
+    for meta in models {
+        for field in &meta.fields {
+            messages.extend(check_field_name(meta, field));
+            messages.extend(check_field_options(meta, field));
+        }
+        messages.extend(check_ordering(meta));
+    }
+    messages.extend(check_relations(models));
+    messages.extend(check_db_tables(models));
+    messages
+}
+
+/// Returns the `app_label.model_name` label of a model.
+fn model_label(meta: &ModelMeta) -> String {
+    format!("{}.{}", meta.app_label, meta.model_name)
+}
+
+/// Returns the `app_label.model_name.field` label of a field.
+fn field_label(meta: &ModelMeta, field: &FieldDef) -> String {
+    format!("{}.{}", model_label(meta), field.name)
+}
+
+fn check_field_name(meta: &ModelMeta, field: &FieldDef) -> Vec<CheckMessage> {
+    let obj = field_label(meta, field);
+    let mut messages = Vec::new();
+    if field.name.ends_with('_') {
+        messages.push(CheckMessage::error(
+            format!("Field name '{obj}' must not end with an underscore."),
+            None,
+            Some(&obj),
+            Some("fields.E001"),
+        ));
+    }
+    if field.name.contains("__") {
+        messages.push(CheckMessage::error(
+            format!("Field name '{obj}' must not contain \"__\"."),
+            Some("Lookups use \"__\" to separate field names."),
+            Some(&obj),
+            Some("fields.E002"),
+        ));
+    }
+    if field.name == "pk" {
+        messages.push(CheckMessage::error(
+            format!("'pk' is a reserved word that cannot be used as a field name ({obj})."),
+            None,
+            Some(&obj),
+            Some("fields.E003"),
+        ));
+    }
+    let reserved = [field.name, field.column.as_str()]
+        .into_iter()
+        .find(|name| RESERVED_WORDS.contains(&name.to_ascii_lowercase().as_str()));
+    if let Some(name) = reserved {
+        messages.push(CheckMessage::warning(
+            format!("'{obj}' uses the SQL reserved word '{name}' as its name or column."),
+            Some("Set db_column to another name, or quote it in raw SQL."),
+            Some(&obj),
+            Some("fields.W001"),
+        ));
+    }
+    messages
+}
+
+fn check_field_options(meta: &ModelMeta, field: &FieldDef) -> Vec<CheckMessage> {
+    let obj = field_label(meta, field);
+    let mut messages = Vec::new();
+    if matches!(field.field_type, FieldType::CharField) && field.max_length.is_none() {
+        messages.push(CheckMessage::error(
+            format!("CharField '{obj}' must define a 'max_length' attribute."),
+            Some("Use a TextField for text without a length limit."),
+            Some(&obj),
+            Some("fields.E120"),
+        ));
+    }
+    let on_delete = match &field.field_type {
+        FieldType::ForeignKey { on_delete, .. } | FieldType::OneToOneField { on_delete, .. } => {
+            *on_delete
+        }
+        _ => return messages,
+    };
+    if on_delete == OnDelete::SetNull && !field.null {
+        messages.push(CheckMessage::error(
+            format!("Field '{obj}' specifies on_delete=SET_NULL, but cannot be null."),
+            Some("Set null=true on the field, or change the on_delete rule."),
+            Some(&obj),
+            Some("fields.E320"),
+        ));
+    }
+    if on_delete == OnDelete::SetDefault && field.default.is_none() {
+        messages.push(CheckMessage::error(
+            format!("Field '{obj}' specifies on_delete=SET_DEFAULT, but has no default value."),
+            Some("Set a default value, or change the on_delete rule."),
+            Some(&obj),
+            Some("fields.E321"),
+        ));
+    }
+    messages
+}
+
+fn check_ordering(meta: &ModelMeta) -> Vec<CheckMessage> {
+    let obj = model_label(meta);
+    meta.ordering
+        .iter()
+        .filter_map(|order| {
+            let name = order.column.as_str();
+            if name == "?" || name == "pk" {
+                return None;
+            }
+            let first = name.split("__").next().unwrap_or(name);
+            let field = meta
+                .fields
+                .iter()
+                .find(|f| f.name == first || f.column == first);
+            let valid = match field {
+                Some(field) => first == name || field.is_relation(),
+                None => false,
+            };
+            (!valid).then(|| {
+                CheckMessage::error(
+                    format!("'ordering' of '{obj}' refers to the nonexistent field '{name}'."),
+                    None,
+                    Some(&obj),
+                    Some("models.E015"),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Finds the model a relation points to.
+fn resolve_target<'a>(models: &[&'a ModelMeta], to: &str) -> Option<&'a ModelMeta> {
+    models
+        .iter()
+        .copied()
+        .find(|m| model_label(m).eq_ignore_ascii_case(to) || m.db_table.eq_ignore_ascii_case(to))
+}
+
+fn check_relations(models: &[&ModelMeta]) -> Vec<CheckMessage> {
+    let mut messages = Vec::new();
+    // (target label, accessor) -> the first relation using it
+    let mut accessors: HashMap<(String, String), String> = HashMap::new();
+
+    for meta in models.iter().filter(|m| !m.abstract_model) {
+        for field in &meta.fields {
+            let (to, related_name) = match &field.field_type {
+                FieldType::ForeignKey {
+                    to, related_name, ..
+                }
+                | FieldType::OneToOneField {
+                    to, related_name, ..
+                }
+                | FieldType::ManyToManyField {
+                    to, related_name, ..
+                } => (to, related_name.as_deref()),
+                _ => continue,
+            };
+            let obj = field_label(meta, field);
+
+            let Some(target) = resolve_target(models, to).filter(|t| !t.abstract_model) else {
+                messages.push(CheckMessage::error(
+                    format!(
+                        "Field '{obj}' defines a relation with model '{to}', which is either \
+                         not registered, or is abstract."
+                    ),
+                    Some("Register the target model with register_model()."),
+                    Some(&obj),
+                    Some("fields.E300"),
+                ));
+                continue;
+            };
+
+            if related_name.is_some_and(|name| name.ends_with('+')) {
+                continue;
+            }
+            if let Some(name) = related_name {
+                if !is_identifier(name) {
+                    messages.push(CheckMessage::error(
+                        format!("The related_name '{name}' of '{obj}' is not a valid identifier."),
+                        None,
+                        Some(&obj),
+                        Some("fields.E306"),
+                    ));
+                    continue;
+                }
+            }
+            let accessor = related_name.map_or_else(
+                || match field.field_type {
+                    FieldType::OneToOneField { .. } => meta.model_name.to_ascii_lowercase(),
+                    _ => format!("{}_set", meta.model_name.to_ascii_lowercase()),
+                },
+                str::to_string,
+            );
+
+            let target_label = model_label(target);
+            if target.fields.iter().any(|f| f.name == accessor) {
+                messages.push(CheckMessage::error(
+                    format!(
+                        "Reverse accessor '{accessor}' for '{obj}' clashes with field name \
+                         '{target_label}.{accessor}'."
+                    ),
+                    Some("Rename the field, or add or change a related_name."),
+                    Some(&obj),
+                    Some("fields.E302"),
+                ));
+            }
+            match accessors.entry((target_label.clone(), accessor.clone())) {
+                std::collections::hash_map::Entry::Occupied(first) => {
+                    messages.push(CheckMessage::error(
+                        format!(
+                            "Reverse accessor '{target_label}.{accessor}' for '{obj}' clashes \
+                             with the reverse accessor for '{}'.",
+                            first.get()
+                        ),
+                        Some("Add or change a related_name on one of the fields."),
+                        Some(&obj),
+                        Some("fields.E304"),
+                    ));
+                }
+                std::collections::hash_map::Entry::Vacant(slot) => {
+                    slot.insert(obj);
+                }
+            }
+        }
+    }
+    messages
+}
+
+fn check_db_tables(models: &[&ModelMeta]) -> Vec<CheckMessage> {
+    let mut tables: Vec<(&str, Vec<String>)> = Vec::new();
+    for meta in models {
+        if meta.abstract_model || matches!(meta.inheritance_type, InheritanceType::Proxy { .. }) {
+            continue;
+        }
+        let table = meta.db_table.as_str();
+        match tables
+            .iter_mut()
+            .find(|(t, _)| t.eq_ignore_ascii_case(table))
+        {
+            Some((_, labels)) => labels.push(model_label(meta)),
+            None => tables.push((table, vec![model_label(meta)])),
+        }
+    }
+    tables
+        .into_iter()
+        .filter(|(_, labels)| labels.len() > 1)
+        .map(|(table, labels)| {
+            CheckMessage::error(
+                format!(
+                    "db_table '{table}' is used by multiple models: {}.",
+                    labels.join(", ")
+                ),
+                None,
+                Some(table),
+                Some("models.E028"),
+            )
+        })
+        .collect()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::compiler::OrderBy;
+    use crate::value::Value;
+
+    fn meta(app_label: &'static str, model_name: &'static str, fields: Vec<FieldDef>) -> ModelMeta {
+        ModelMeta {
+            app_label,
+            model_name,
+            db_table: format!("{app_label}_{model_name}"),
+            verbose_name: model_name.to_string(),
+            verbose_name_plural: format!("{model_name}s"),
+            ordering: vec![],
+            unique_together: vec![],
+            indexes: vec![],
+            abstract_model: false,
+            fields,
+            constraints: vec![],
+            inheritance_type: InheritanceType::None,
+            db_schema: None,
+        }
+    }
+
+    fn id() -> FieldDef {
+        FieldDef::new("id", FieldType::BigAutoField).primary_key()
+    }
+
+    fn fk(
+        name: &'static str,
+        to: &str,
+        on_delete: OnDelete,
+        related_name: Option<&str>,
+    ) -> FieldDef {
+        FieldDef::new(
+            name,
+            FieldType::ForeignKey {
+                to: to.to_string(),
+                on_delete,
+                related_name: related_name.map(String::from),
+            },
+        )
+    }
+
+    fn ids(messages: &[CheckMessage]) -> Vec<&str> {
+        messages.iter().filter_map(|m| m.id.as_deref()).collect()
+    }
+
+    #[test]
+    fn test_valid_models_have_no_issues() {
+        let author = meta(
+            "blog",
+            "author",
+            vec![
+                id(),
+                FieldDef::new("name", FieldType::CharField).max_length(100),
+            ],
+        );
+        let mut post = meta(
+            "blog",
+            "post",
+            vec![
+                id(),
+                fk("author", "blog.Author", OnDelete::Cascade, None),
+                fk(
+                    "editor",
+                    "blog_author",
+                    OnDelete::SetNull,
+                    Some("edited_posts"),
+                )
+                .nullable(),
+            ],
+        );
+        post.ordering = vec![OrderBy::desc("id"), OrderBy::asc("author__name")];
+        assert!(check_models(&[&author, &post]).is_empty());
+    }
+
+    #[test]
+    fn test_field_names() {
+        let model = meta(
+            "shop",
+            "item",
+            vec![
+                id(),
+                FieldDef::new("price_", FieldType::IntegerField),
+                FieldDef::new("unit__size", FieldType::IntegerField),
+                FieldDef::new("pk", FieldType::IntegerField),
+                FieldDef::new("order", FieldType::IntegerField),
+                FieldDef::new("position", FieldType::IntegerField).column("group"),
+                FieldDef::new("sku", FieldType::CharField),
+            ],
+        );
+        let messages = check_models(&[&model]);
+        assert_eq!(
+            ids(&messages),
+            [
+                "fields.E001",
+                "fields.E002",
+                "fields.E003",
+                "fields.W001",
+                "fields.W001",
+                "fields.E120"
+            ]
+        );
+        assert_eq!(messages[0].obj.as_deref(), Some("shop.item.price_"));
+    }
+
+    #[test]
+    fn test_on_delete_rules() {
+        let user = meta("auth", "user", vec![id()]);
+        let model = meta(
+            "blog",
+            "post",
+            vec![
+                id(),
+                fk("owner", "auth.user", OnDelete::SetNull, Some("owned")),
+                fk(
+                    "reviewer",
+                    "auth.user",
+                    OnDelete::SetDefault,
+                    Some("reviewed"),
+                ),
+                fk("editor", "auth.user", OnDelete::SetDefault, Some("edited"))
+                    .default(Value::Int(1)),
+            ],
+        );
+        assert_eq!(
+            ids(&check_models(&[&user, &model])),
+            ["fields.E320", "fields.E321"]
+        );
+    }
+
+    #[test]
+    fn test_ordering_refers_to_fields() {
+        let mut model = meta(
+            "blog",
+            "post",
+            vec![id(), FieldDef::new("title", FieldType::TextField)],
+        );
+        model.ordering = vec![
+            OrderBy::asc("?"),
+            OrderBy::asc("pk"),
+            OrderBy::desc("title"),
+            OrderBy::asc("published"),
+            OrderBy::asc("title__length"),
+        ];
+        let messages = check_models(&[&model]);
+        assert_eq!(ids(&messages), ["models.E015", "models.E015"]);
+        assert!(messages[0].msg.contains("'published'"));
+        assert!(messages[1].msg.contains("'title__length'"));
+    }
+
+    #[test]
+    fn test_relation_checks() {
+        let user = meta(
+            "auth",
+            "user",
+            vec![id(), FieldDef::new("comment_set", FieldType::IntegerField)],
+        );
+        let comment = meta(
+            "blog",
+            "comment",
+            vec![
+                id(),
+                fk("author", "auth.User", OnDelete::Cascade, None),
+                fk("tag", "blog.tag", OnDelete::Cascade, None),
+            ],
+        );
+        let post = meta(
+            "blog",
+            "post",
+            vec![
+                id(),
+                fk("author", "auth.user", OnDelete::Cascade, Some("posts")),
+                fk("editor", "auth.user", OnDelete::Cascade, Some("posts")),
+                fk(
+                    "reviewer",
+                    "auth.user",
+                    OnDelete::Cascade,
+                    Some("reviewed-posts"),
+                ),
+                fk("owner", "auth.user", OnDelete::Cascade, Some("+")),
+            ],
+        );
+        let messages = check_models(&[&user, &comment, &post]);
+        assert_eq!(
+            ids(&messages),
+            ["fields.E302", "fields.E300", "fields.E304", "fields.E306"]
+        );
+        assert_eq!(messages[1].obj.as_deref(), Some("blog.comment.tag"));
+        assert!(messages[2].msg.contains("'blog.post.author'"));
+    }
+
+    #[test]
+    fn test_cross_database_relations() {
+        struct AnalyticsRouter;
+
+        impl crate::router::DatabaseRouter for AnalyticsRouter {
+            fn db_for_read(&self, app_label: &str, _model_name: &str) -> Option<String> {
+                (app_label == "analytics").then(|| "analytics".to_string())
+            }
+        }
+
+        let user = meta("auth", "user", vec![id()]);
+        let profile = meta(
+            "auth",
+            "profile",
+            vec![id(), fk("user", "auth.user", OnDelete::Cascade, None)],
+        );
+        let event = meta(
+            "analytics",
+            "event",
+            vec![id(), fk("user", "auth.user", OnDelete::Cascade, None)],
+        );
+        let models = [&user, &profile, &event];
+        assert!(check_database_relations(&models, &RouterChain::new()).is_empty());
+
+        let mut router = RouterChain::new();
+        router.add_router(Box::new(AnalyticsRouter));
+        let messages = check_database_relations(&models, &router);
+        assert_eq!(ids(&messages), ["fields.W390"]);
+        assert_eq!(messages[0].obj.as_deref(), Some("analytics.event.user"));
+        assert!(messages[0].msg.contains("(database 'analytics')"));
+        assert!(check_installed_router(&models).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_db_tables() {
+        let post = meta("blog", "post", vec![id()]);
+        let mut article = meta("news", "article", vec![id()]);
+        article.db_table = "blog_post".to_string();
+        let mut proxy = meta("news", "featured", vec![id()]);
+        proxy.db_table = "blog_post".to_string();
+        proxy.inheritance_type = InheritanceType::Proxy {
+            parent_table: "blog_post".to_string(),
+        };
+
+        let messages = check_models(&[&post, &article, &proxy]);
+        assert_eq!(ids(&messages), ["models.E028"]);
+        assert!(messages[0].msg.contains("blog.post, news.article"));
+    }
+
+    #[test]
+    fn test_hash_field_needs_hash_key() {
+        let customer = meta(
+            "crm",
+            "customer",
+            vec![
+                id(),
+                FieldDef::new(
+                    "email",
+                    FieldType::EncryptedCharField {
+                        hash_field: Some("email_hash".to_string()),
+                    },
+                ),
+                FieldDef::new("notes", FieldType::EncryptedTextField { hash_field: None }),
+            ],
+        );
+        let settings = Settings {
+            secret_key: "s3cret".to_string(),
+            ..Settings::default()
+        };
+        let messages = check_model_settings(&[&customer], &settings);
+        assert_eq!(ids(&messages), ["fields.E910"]);
+        assert_eq!(messages[0].obj.as_deref(), Some("crm.customer.email"));
+
+        let settings = Settings {
+            field_hash_key: "blind".to_string(),
+            ..settings
+        };
+        assert!(check_model_settings(&[&customer], &settings).is_empty());
+    }
+}
@@ -0,0 +1,833 @@
+//! Encrypted model fields.
+//!
+//! Values of [`EncryptedCharField`](FieldType::EncryptedCharField) and
+//! [`EncryptedTextField`](FieldType::EncryptedTextField) fields are encrypted
+//! with AES-256-GCM before they are written and decrypted when a model is
+//! loaded, so the database only ever holds ciphertext. Encryption applies on
+//! the same write paths as [`timestamps`](crate::timestamps): `save_model`,
+//! `create_model`, `bulk_create`, `bulk_update`, `Manager::create`,
+//! `Manager::upsert` and `QuerySet::update`.
+//!
+//! Each stored value names the key that encrypted it:
+//!
+//! ```text
+//! enc$<key id>$<base64 of nonce and ciphertext>
+//! ```
+//!
+//! The table and column are authenticated along with the value, so a
+//! ciphertext copied into another column or table fails to decrypt. The
+//! row's primary key is not bound: `QuerySet::update` writes one ciphertext
+//! to every matching row, and an automatic key is only known after the
+//! insert.
+//!
+//! # Keys
+//!
+//! Keys come from `Settings::field_encryption_keys`, newest first; like any
+//! string setting, a key may be a secret reference resolved from the
+//! environment or a secrets provider. The first key encrypts new values and
+//! every listed key can decrypt. To rotate, put a new key first, then run
+//! [`rotate_keys`] for each model to re-encrypt its rows before removing the
+//! old key. A keyring can also be installed directly with [`set_keyring`].
+//!
+//! Values without the `enc$` prefix are rejected, since an attacker who can
+//! write to the database could otherwise plant plaintext. To switch a plain
+//! text column to an encrypted field, set
+//! `Settings::field_encryption_accept_plaintext` (or
+//! [`FieldKeyring::accept_plaintext`]) while [`rotate_keys`] encrypts its
+//! rows, then turn it off again.
+//!
+//! # Lookups
+//!
+//! Because the same plaintext encrypts differently each time, encrypted
+//! columns cannot be searched. A field can instead name a `hash_field`: a
+//! plain `CharField` (64 characters) on the same model that receives a
+//! blind index, a keyed HMAC-SHA256 of the value, on every write. `exact` and
+//! `in` filters on the encrypted field are rewritten to compare the blind
+//! index. `isnull` works on any encrypted field; every other lookup, and
+//! `exact` or `in` on a field without a hash field, is an error when the
+//! query runs. The HMAC key is `Settings::field_hash_key`, which must be set
+//! when a model declares a hash field.
+//!
+//! ```ignore
+//! #[derive(Model)]
+//! #[model(app = "crm")]
+//! pub struct Customer {
+//!     #[field(primary_key, auto)]
+//!     pub id: i64,
+//!     #[field(encrypted, max_length = 254, hash_field = "email_hash")]
+//!     pub email: String,
+//!     #[field(max_length = 64, editable = false, db_index)]
+//!     pub email_hash: String,
+//!     #[field(encrypted)]
+//!     pub notes: String,
+//! }
+//!
+//! let customer = Customer::objects()
+//!     .filter(Q::filter("email", Lookup::Exact("ada@example.com".into())))
+//!     .get(&db)
+//!     .await?;
+//! ```
+
+use std::sync::{Arc, RwLock};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use django_rs_core::settings::Settings;
+use django_rs_core::{DjangoError, DjangoResult, SETTINGS};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::executor::DbExecutor;
+use crate::fields::{FieldDef, FieldType};
+use crate::model::Model;
+use crate::query::compiler::{FromValue, Query, Row, SelectColumn, SqlCompiler, WhereNode};
+use crate::query::lookups::Lookup;
+use crate::router::record_write;
+use crate::value::Value;
+
+/// The prefix of every encrypted value.
+const PREFIX: &str = "enc$";
+
+/// The length of an AES-GCM nonce.
+const NONCE_LEN: usize = 12;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The keys used to encrypt fields and compute their blind indexes.
+pub struct FieldKeyring {
+    /// `(id, cipher)` pairs, newest first.
+    keys: Vec<(String, Aes256Gcm)>,
+    hash_key: Option<Vec<u8>>,
+    accept_plaintext: bool,
+}
+
+impl FieldKeyring {
+    /// Creates a keyring with no keys.
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            hash_key: None,
+            accept_plaintext: false,
+        }
+    }
+
+    /// Adds a 32-byte AES-256 key. Keys are added newest first: the first
+    /// one encrypts, the others only decrypt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not 32 bytes, or its id is empty,
+    /// contains `$` or is already used.
+    pub fn with_key(mut self, id: impl Into<String>, key: &[u8]) -> DjangoResult<Self> {
+        let id = id.into();
+        if id.is_empty() || id.contains('$') {
+            return Err(DjangoError::ImproperlyConfigured(format!(
+                "Field encryption key id '{id}' must be non-empty and must not contain '$'"
+            )));
+        }
+        if self.keys.iter().any(|(existing, _)| *existing == id) {
+            return Err(DjangoError::ImproperlyConfigured(format!(
+                "Field encryption key id '{id}' is used twice"
+            )));
+        }
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| {
+            DjangoError::ImproperlyConfigured(format!(
+                "Field encryption key '{id}' must be 32 bytes, got {}",
+                key.len()
+            ))
+        })?;
+        self.keys.push((id, cipher));
+        Ok(self)
+    }
+
+    /// Sets the key for blind indexes.
+    #[must_use]
+    pub fn with_hash_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.hash_key = Some(key.into());
+        self
+    }
+
+    /// Sets whether values without the `enc$` prefix are read as plaintext
+    /// instead of rejected. Turn it on only while migrating a plain text
+    /// column to an encrypted field.
+    #[must_use]
+    pub const fn accept_plaintext(mut self, accept: bool) -> Self {
+        self.accept_plaintext = accept;
+        self
+    }
+
+    /// Builds a keyring from `field_encryption_keys`, `field_hash_key` and
+    /// `field_encryption_accept_plaintext`.
+    /// The keyring has no hash key when `field_hash_key` is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is not valid base64 or not 32 bytes long.
+    pub fn from_settings(settings: &Settings) -> DjangoResult<Self> {
+        let mut keyring = Self::new();
+        for key in &settings.field_encryption_keys {
+            let bytes = BASE64.decode(key.key.trim()).map_err(|e| {
+                DjangoError::ImproperlyConfigured(format!(
+                    "Field encryption key '{}' is not valid base64: {e}",
+                    key.id
+                ))
+            })?;
+            keyring = keyring.with_key(key.id.clone(), &bytes)?;
+        }
+        if !settings.field_hash_key.is_empty() {
+            keyring = keyring.with_hash_key(settings.field_hash_key.as_bytes());
+        }
+        Ok(keyring.accept_plaintext(settings.field_encryption_accept_plaintext))
+    }
+
+    /// Returns the id of the key that encrypts new values.
+    pub fn current_key_id(&self) -> Option<&str> {
+        self.keys.first().map(|(id, _)| id.as_str())
+    }
+
+    /// Encrypts `plaintext` with the current key, for `column` of `table`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keyring has no keys.
+    pub fn encrypt(&self, table: &str, column: &str, plaintext: &str) -> DjangoResult<String> {
+        let (id, cipher) = self.keys.first().ok_or_else(|| {
+            DjangoError::ImproperlyConfigured(
+                "No field encryption keys are configured; set field_encryption_keys".to_string(),
+            )
+        })?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = associated_data(table, column);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| DjangoError::InternalServerError("Field encryption failed".to_string()))?;
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{PREFIX}{id}${}", BASE64.encode(payload)))
+    }
+
+    /// Decrypts a value stored in `column` of `table`. Values without the
+    /// `enc$` prefix are returned as they are if the keyring
+    /// [accepts plaintext](Self::accept_plaintext).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not encrypted and plaintext is not
+    /// accepted, was encrypted with a key that is not in the keyring or for
+    /// another column, or has been tampered with.
+    pub fn decrypt(&self, table: &str, column: &str, stored: &str) -> DjangoResult<String> {
+        let Some((id, payload)) = split_encrypted(stored) else {
+            if self.accept_plaintext {
+                return Ok(stored.to_string());
+            }
+            return Err(DjangoError::DatabaseError(format!(
+                "Encrypted column '{table}.{column}' holds a value that is not encrypted"
+            )));
+        };
+        let cipher = self
+            .keys
+            .iter()
+            .find(|(key_id, _)| key_id == id)
+            .map(|(_, cipher)| cipher)
+            .ok_or_else(|| {
+                DjangoError::ImproperlyConfigured(format!(
+                    "Field encryption key '{id}' is not configured"
+                ))
+            })?;
+        let invalid =
+            || DjangoError::DatabaseError(format!("Invalid encrypted value for key '{id}'"));
+        let payload = BASE64.decode(payload).map_err(|_| invalid())?;
+        if payload.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let aad = associated_data(table, column);
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| invalid())?;
+        String::from_utf8(plaintext).map_err(|_| invalid())
+    }
+
+    /// Returns `true` if `stored` is not encrypted with the current key.
+    pub fn needs_rotation(&self, stored: &str) -> bool {
+        key_id(stored) != self.current_key_id()
+    }
+
+    /// Re-encrypts a value stored in `column` of `table` with the current
+    /// key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be decrypted or the keyring has
+    /// no keys.
+    pub fn rotate(&self, table: &str, column: &str, stored: &str) -> DjangoResult<String> {
+        self.encrypt(table, column, &self.decrypt(table, column, stored)?)
+    }
+
+    /// Returns the blind index of `plaintext` for the field named `field`,
+    /// as 64 hex digits. The field name is part of the hash, so equal values
+    /// in different fields have different indexes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no hash key is configured.
+    pub fn blind_index(&self, field: &str, plaintext: &str) -> DjangoResult<String> {
+        let key = self.hash_key.as_deref().ok_or_else(|| {
+            DjangoError::ImproperlyConfigured(
+                "Encrypted fields with a hash_field need field_hash_key".to_string(),
+            )
+        })?;
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts any key");
+        mac.update(field.as_bytes());
+        mac.update(&[0]);
+        mac.update(plaintext.as_bytes());
+        Ok(format!("{:x}", mac.finalize().into_bytes()))
+    }
+}
+
+impl Default for FieldKeyring {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for FieldKeyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids: Vec<&str> = self.keys.iter().map(|(id, _)| id.as_str()).collect();
+        f.debug_struct("FieldKeyring")
+            .field("keys", &ids)
+            .field("hash_key", &self.hash_key.is_some())
+            .field("accept_plaintext", &self.accept_plaintext)
+            .finish()
+    }
+}
+
+/// Returns the associated data that binds a ciphertext to its column.
+fn associated_data(table: &str, column: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(table.len() + column.len() + 1);
+    aad.extend_from_slice(table.as_bytes());
+    aad.push(0);
+    aad.extend_from_slice(column.as_bytes());
+    aad
+}
+
+/// Splits an encrypted value into its key id and payload.
+fn split_encrypted(stored: &str) -> Option<(&str, &str)> {
+    stored.strip_prefix(PREFIX)?.split_once('$')
+}
+
+/// Returns the id of the key that encrypted `stored`, or `None` if it is not
+/// encrypted.
+pub fn key_id(stored: &str) -> Option<&str> {
+    split_encrypted(stored).map(|(id, _)| id)
+}
+
+/// Returns `true` if `stored` is an encrypted value.
+pub fn is_encrypted(stored: &str) -> bool {
+    split_encrypted(stored).is_some()
+}
+
+static KEYRING: RwLock<Option<Arc<FieldKeyring>>> = RwLock::new(None);
+
+/// Installs the keyring used for encrypted fields, replacing the one built
+/// from settings.
+pub fn set_keyring(keyring: FieldKeyring) {
+    *KEYRING.write().expect("keyring lock poisoned") = Some(Arc::new(keyring));
+}
+
+/// Returns the keyring used for encrypted fields, building it from the
+/// global settings the first time.
+///
+/// # Errors
+///
+/// Returns an error if no keyring was installed and the settings are not
+/// configured or hold an invalid key.
+pub fn keyring() -> DjangoResult<Arc<FieldKeyring>> {
+    if let Some(keyring) = KEYRING.read().expect("keyring lock poisoned").as_ref() {
+        return Ok(Arc::clone(keyring));
+    }
+    if !SETTINGS.is_configured() {
+        return Err(DjangoError::ImproperlyConfigured(
+            "Encrypted fields need configured settings or set_keyring()".to_string(),
+        ));
+    }
+    let keyring = Arc::new(FieldKeyring::from_settings(SETTINGS.get())?);
+    let mut slot = KEYRING.write().expect("keyring lock poisoned");
+    Ok(Arc::clone(slot.get_or_insert(keyring)))
+}
+
+/// Returns the name of the field holding `field`'s blind index, if it is an
+/// encrypted field with one.
+pub fn hash_field(field: &FieldDef) -> Option<&str> {
+    match &field.field_type {
+        FieldType::EncryptedCharField { hash_field }
+        | FieldType::EncryptedTextField { hash_field } => hash_field.as_deref(),
+        _ => None,
+    }
+}
+
+/// Returns `true` if any of `fields` is encrypted.
+pub fn has_encrypted_fields(fields: &[FieldDef]) -> bool {
+    fields.iter().any(|f| f.field_type.is_encrypted())
+}
+
+/// Encrypts the values of encrypted fields in a row about to be written to
+/// `table`, and sets their blind indexes.
+///
+/// # Errors
+///
+/// Returns an error if an encrypted field has a value other than a string
+/// or NULL, its hash field is not one of `fields`, or the keyring cannot
+/// encrypt.
+pub fn encrypt_values(
+    table: &str,
+    fields: &[FieldDef],
+    values: &mut Vec<(&'static str, Value)>,
+) -> DjangoResult<()> {
+    let mut keyring = None;
+    for field in fields.iter().filter(|f| f.field_type.is_encrypted()) {
+        let Some(index) = values.iter().position(|(name, _)| *name == field.name) else {
+            continue;
+        };
+        let keyring = match &keyring {
+            Some(keyring) => keyring,
+            None => keyring.insert(self::keyring()?),
+        };
+        let (stored, hash) = match &values[index].1 {
+            Value::Null => (Value::Null, Value::Null),
+            Value::String(plaintext) => {
+                let hash = if hash_field(field).is_some() {
+                    Value::String(keyring.blind_index(field.name, plaintext)?)
+                } else {
+                    Value::Null
+                };
+                (
+                    Value::String(keyring.encrypt(table, &field.column, plaintext)?),
+                    hash,
+                )
+            }
+            other => {
+                return Err(DjangoError::DatabaseError(format!(
+                    "Encrypted field '{}' needs a string value, got {other:?}",
+                    field.name
+                )));
+            }
+        };
+        values[index].1 = stored;
+        if let Some(hash_name) = hash_field(field) {
+            let hash_def = fields.iter().find(|f| f.name == hash_name).ok_or_else(|| {
+                DjangoError::ImproperlyConfigured(format!(
+                    "Hash field '{hash_name}' of encrypted field '{}' is not a field of the model",
+                    field.name
+                ))
+            })?;
+            match values.iter_mut().find(|(name, _)| *name == hash_def.name) {
+                Some(entry) => entry.1 = hash,
+                None => values.push((hash_def.name, hash)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites `exact` and `in` lookups on encrypted fields that have a hash
+/// field into lookups on their blind indexes.
+///
+/// # Errors
+///
+/// Returns an error if a lookup other than `isnull` is made on an encrypted
+/// field and cannot be rewritten: it is not `exact` or `in`, the field has
+/// no hash field, or no hash key is configured.
+pub fn rewrite_lookups(fields: &[FieldDef], node: WhereNode) -> DjangoResult<WhereNode> {
+    Ok(match node {
+        WhereNode::Condition { column, lookup } => {
+            let Some(field) = fields
+                .iter()
+                .find(|f| f.field_type.is_encrypted() && (f.name == column || f.column == column))
+            else {
+                return Ok(WhereNode::Condition { column, lookup });
+            };
+            let hash_column = || -> DjangoResult<String> {
+                let hash_name = hash_field(field).ok_or_else(|| {
+                    DjangoError::DatabaseError(format!(
+                        "Encrypted field '{}' has no hash_field, so it can only be filtered \
+                         with isnull",
+                        field.name
+                    ))
+                })?;
+                Ok(fields
+                    .iter()
+                    .find(|f| f.name == hash_name)
+                    .map_or(hash_name, |f| f.column.as_str())
+                    .to_string())
+            };
+            let index = |value: Value| -> DjangoResult<Value> {
+                match value {
+                    Value::String(plaintext) => Ok(Value::String(
+                        keyring()?.blind_index(field.name, &plaintext)?,
+                    )),
+                    other => Ok(other),
+                }
+            };
+            match lookup {
+                Lookup::IsNull(_) => WhereNode::Condition { column, lookup },
+                Lookup::Exact(value) => WhereNode::Condition {
+                    column: hash_column()?,
+                    lookup: Lookup::Exact(index(value)?),
+                },
+                Lookup::In(values) => WhereNode::Condition {
+                    column: hash_column()?,
+                    lookup: Lookup::In(values.into_iter().map(index).collect::<DjangoResult<_>>()?),
+                },
+                _ => {
+                    return Err(DjangoError::DatabaseError(format!(
+                        "Encrypted field '{}' only supports the exact, in and isnull lookups",
+                        field.name
+                    )))
+                }
+            }
+        }
+        WhereNode::And(nodes) => WhereNode::And(
+            nodes
+                .into_iter()
+                .map(|n| rewrite_lookups(fields, n))
+                .collect::<DjangoResult<_>>()?,
+        ),
+        WhereNode::Or(nodes) => WhereNode::Or(
+            nodes
+                .into_iter()
+                .map(|n| rewrite_lookups(fields, n))
+                .collect::<DjangoResult<_>>()?,
+        ),
+        WhereNode::Not(inner) => WhereNode::Not(Box::new(rewrite_lookups(fields, *inner)?)),
+        other => other,
+    })
+}
+
+/// Decrypts a value loaded from `column` of `table`.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be decrypted.
+pub fn decrypt_value(table: &str, column: &str, value: Value) -> DjangoResult<Value> {
+    match value {
+        Value::String(stored) => Ok(Value::String(keyring()?.decrypt(table, column, &stored)?)),
+        other => Ok(other),
+    }
+}
+
+/// Reads and decrypts an encrypted column of `row`, loaded from `table`.
+/// Use this instead of [`Row::get`] in a hand-written [`Model::from_row`];
+/// `#[field(encrypted)]` does it for derived models.
+///
+/// # Errors
+///
+/// Returns an error if the column is missing, cannot be decrypted, or its
+/// plaintext cannot be converted to `T`.
+pub fn decrypt_column<T: FromValue>(row: &Row, table: &str, column: &str) -> DjangoResult<T> {
+    T::from_value(&decrypt_value(table, column, row.get::<Value>(column)?)?)
+}
+
+/// Re-encrypts the encrypted fields of every `M` row that is not encrypted
+/// with the current key, and fills in their blind indexes. Returns the
+/// number of rows updated.
+///
+/// Run it after putting a new key first in `field_encryption_keys`, and
+/// remove the old key once it has finished. Rows are updated one by one, so
+/// it can be interrupted and run again. Plaintext rows are encrypted only
+/// while the keyring [accepts plaintext](FieldKeyring::accept_plaintext).
+///
+/// # Errors
+///
+/// Returns an error if a value cannot be decrypted or a query fails.
+pub async fn rotate_keys<M: Model>(db: &dyn DbExecutor) -> DjangoResult<u64> {
+    let fields = &M::meta().fields;
+    let encrypted: Vec<&FieldDef> = fields
+        .iter()
+        .filter(|f| f.field_type.is_encrypted())
+        .collect();
+    if encrypted.is_empty() {
+        return Ok(0);
+    }
+    let keyring = keyring()?;
+    let compiler = SqlCompiler::new(db.backend_type());
+    let pk_name = M::pk_field_name();
+
+    let mut query = Query::new(M::table_name());
+    query.select = std::iter::once(pk_name.to_string())
+        .chain(encrypted.iter().map(|f| f.column.clone()))
+        .map(SelectColumn::Column)
+        .collect();
+    let (sql, params) = compiler.compile_select(&query);
+    let rows = db.query(&sql, &params).await?;
+
+    let mut updated = 0;
+    for row in rows {
+        let mut values: Vec<(&'static str, Value)> = Vec::new();
+        for field in &encrypted {
+            if let Some(Value::String(stored)) = row.get_value(&field.column) {
+                if keyring.needs_rotation(stored) {
+                    let plaintext = keyring.decrypt(M::table_name(), &field.column, stored)?;
+                    values.push((field.name, Value::String(plaintext)));
+                }
+            }
+        }
+        if values.is_empty() {
+            continue;
+        }
+        encrypt_values(M::table_name(), fields, &mut values)?;
+        let where_clause = WhereNode::Condition {
+            column: pk_name.to_string(),
+            lookup: Lookup::Exact(row.get::<Value>(pk_name)?),
+        };
+        let (sql, params) = compiler.compile_update(M::table_name(), &values, &where_clause);
+        record_write();
+        db.execute_sql(&sql, &params).await?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: [u8; 32] = [1; 32];
+    const KEY_2: [u8; 32] = [2; 32];
+    const TABLE: &str = "crm_customer";
+
+    fn old_keyring() -> FieldKeyring {
+        FieldKeyring::new()
+            .with_key("k1", &KEY_1)
+            .unwrap()
+            .with_hash_key("hash-key")
+    }
+
+    fn rotated_keyring() -> FieldKeyring {
+        FieldKeyring::new()
+            .with_key("k2", &KEY_2)
+            .unwrap()
+            .with_key("k1", &KEY_1)
+            .unwrap()
+            .with_hash_key("hash-key")
+    }
+
+    fn fields() -> Vec<FieldDef> {
+        vec![
+            FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+            FieldDef::new(
+                "email",
+                FieldType::EncryptedCharField {
+                    hash_field: Some("email_hash".to_string()),
+                },
+            )
+            .max_length(254),
+            FieldDef::new("email_hash", FieldType::CharField).max_length(64),
+            FieldDef::new("notes", FieldType::EncryptedTextField { hash_field: None }).nullable(),
+        ]
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let keyring = old_keyring();
+        let stored = keyring.encrypt(TABLE, "email", "ada@example.com").unwrap();
+        assert!(stored.starts_with("enc$k1$"));
+        assert!(!stored.contains("ada"));
+        assert_ne!(
+            stored,
+            keyring.encrypt(TABLE, "email", "ada@example.com").unwrap()
+        );
+        assert_eq!(
+            keyring.decrypt(TABLE, "email", &stored).unwrap(),
+            "ada@example.com"
+        );
+
+        let mut tampered = stored;
+        tampered.replace_range(tampered.len() - 4.., "AAAA");
+        assert!(keyring.decrypt(TABLE, "email", &tampered).is_err());
+        assert!(FieldKeyring::new().with_key("short", &[0; 16]).is_err());
+        assert!(FieldKeyring::new().with_key("a$b", &KEY_1).is_err());
+        assert!(format!("{keyring:?}").contains("k1"));
+    }
+
+    #[test]
+    fn test_ciphertext_is_bound_to_its_column() {
+        let keyring = old_keyring();
+        let stored = keyring.encrypt(TABLE, "email", "ada@example.com").unwrap();
+        assert!(keyring.decrypt(TABLE, "notes", &stored).is_err());
+        assert!(keyring.decrypt("crm_lead", "email", &stored).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_needs_opt_in() {
+        let err = old_keyring()
+            .decrypt(TABLE, "email", "legacy plaintext")
+            .unwrap_err();
+        assert!(matches!(err, DjangoError::DatabaseError(_)));
+        let keyring = old_keyring().accept_plaintext(true);
+        assert_eq!(
+            keyring.decrypt(TABLE, "email", "legacy plaintext").unwrap(),
+            "legacy plaintext"
+        );
+        let rotated = keyring.rotate(TABLE, "email", "legacy plaintext").unwrap();
+        assert_eq!(key_id(&rotated), Some("k1"));
+        assert_eq!(
+            old_keyring().decrypt(TABLE, "email", &rotated).unwrap(),
+            "legacy plaintext"
+        );
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let stored = old_keyring().encrypt(TABLE, "notes", "secret").unwrap();
+        let keyring = rotated_keyring();
+        assert_eq!(keyring.decrypt(TABLE, "notes", &stored).unwrap(), "secret");
+        assert!(keyring.needs_rotation(&stored));
+        assert!(keyring.needs_rotation("plaintext"));
+
+        let rotated = keyring.rotate(TABLE, "notes", &stored).unwrap();
+        assert_eq!(key_id(&rotated), Some("k2"));
+        assert!(!keyring.needs_rotation(&rotated));
+        assert!(old_keyring().decrypt(TABLE, "notes", &rotated).is_err());
+    }
+
+    #[test]
+    fn test_blind_index() {
+        let keyring = old_keyring();
+        let index = keyring.blind_index("email", "ada@example.com").unwrap();
+        assert_eq!(index.len(), 64);
+        assert_eq!(
+            index,
+            rotated_keyring()
+                .blind_index("email", "ada@example.com")
+                .unwrap()
+        );
+        assert_ne!(
+            index,
+            keyring
+                .blind_index("backup_email", "ada@example.com")
+                .unwrap()
+        );
+        assert!(FieldKeyring::new().blind_index("email", "x").is_err());
+    }
+
+    #[test]
+    fn test_from_settings() {
+        let settings = Settings {
+            secret_key: "s3cret".to_string(),
+            field_hash_key: "blind".to_string(),
+            field_encryption_keys: vec![django_rs_core::settings::FieldEncryptionKey {
+                id: "2024".to_string(),
+                key: BASE64.encode(KEY_1),
+            }],
+            ..Settings::default()
+        };
+        let keyring = FieldKeyring::from_settings(&settings).unwrap();
+        assert_eq!(keyring.current_key_id(), Some("2024"));
+        assert!(keyring.blind_index("email", "x").is_ok());
+        assert!(keyring.decrypt(TABLE, "email", "x").is_err());
+
+        let keyring = FieldKeyring::from_settings(&Settings {
+            field_encryption_accept_plaintext: true,
+            ..settings.clone()
+        })
+        .unwrap();
+        assert_eq!(keyring.decrypt(TABLE, "email", "x").unwrap(), "x");
+
+        // The secret key is not used for blind indexes.
+        let settings = Settings {
+            field_hash_key: String::new(),
+            ..settings
+        };
+        let keyring = FieldKeyring::from_settings(&settings).unwrap();
+        assert!(keyring.blind_index("email", "x").is_err());
+
+        let settings = Settings {
+            field_encryption_keys: vec![django_rs_core::settings::FieldEncryptionKey {
+                id: "bad".to_string(),
+                key: "not base64!".to_string(),
+            }],
+            ..Settings::default()
+        };
+        assert!(FieldKeyring::from_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_values_and_rewrite_lookups() {
+        set_keyring(rotated_keyring());
+        let fields = fields();
+        let mut values = vec![
+            ("email", Value::String("ada@example.com".to_string())),
+            ("notes", Value::Null),
+        ];
+        encrypt_values(TABLE, &fields, &mut values).unwrap();
+        let Value::String(ref stored) = values[0].1 else {
+            panic!("expected ciphertext");
+        };
+        assert_eq!(key_id(stored), Some("k2"));
+        assert_eq!(values[1].1, Value::Null);
+        let hash = rotated_keyring()
+            .blind_index("email", "ada@example.com")
+            .unwrap();
+        assert_eq!(values[2], ("email_hash", Value::String(hash.clone())));
+        assert_eq!(
+            decrypt_value(TABLE, "email", values[0].1.clone()).unwrap(),
+            Value::String("ada@example.com".to_string())
+        );
+
+        let mut values = vec![("notes", Value::Int(1))];
+        assert!(encrypt_values(TABLE, &fields, &mut values).is_err());
+
+        let node = rewrite_lookups(
+            &fields,
+            WhereNode::Not(Box::new(WhereNode::Condition {
+                column: "email".to_string(),
+                lookup: Lookup::Exact(Value::String("ada@example.com".to_string())),
+            })),
+        )
+        .unwrap();
+        let WhereNode::Not(inner) = node else {
+            panic!("expected NOT");
+        };
+        let WhereNode::Condition { column, lookup } = *inner else {
+            panic!("expected a condition");
+        };
+        assert_eq!(column, "email_hash");
+        assert_eq!(lookup, Lookup::Exact(Value::String(hash)));
+
+        let node = rewrite_lookups(
+            &fields,
+            WhereNode::Condition {
+                column: "email".to_string(),
+                lookup: Lookup::IsNull(true),
+            },
+        )
+        .unwrap();
+        assert!(matches!(node, WhereNode::Condition { ref column, .. } if column == "email"));
+
+        let contains = WhereNode::Condition {
+            column: "email".to_string(),
+            lookup: Lookup::Contains("ada".to_string()),
+        };
+        assert!(rewrite_lookups(&fields, contains).is_err());
+        let notes = WhereNode::Or(vec![WhereNode::Condition {
+            column: "notes".to_string(),
+            lookup: Lookup::Exact(Value::String("x".to_string())),
+        }]);
+        assert!(rewrite_lookups(&fields, notes).is_err());
+    }
+}
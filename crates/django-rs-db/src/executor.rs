@@ -14,9 +14,12 @@
 //! [`ModelLifecycleHooks`] trait. If a model implements this trait, the
 //! appropriate hook methods are called before and after each operation.
 
+use crate::encryption;
 use crate::model::Model;
 use crate::query::compiler::{DatabaseBackendType, Row, SqlCompiler, WhereNode};
 use crate::query::lookups::Lookup;
+use crate::router::record_write;
+use crate::timestamps;
 use crate::value::Value;
 use django_rs_core::{DjangoError, DjangoResult};
 
@@ -61,6 +64,19 @@ pub trait DbExecutor: Send + Sync {
             ))
         }
     }
+
+    /// Sends `payload` to every session listening on `channel`.
+    ///
+    /// Backends with a publish/subscribe mechanism, such as PostgreSQL's
+    /// `NOTIFY`, override this. Inside a transaction, the notification is
+    /// delivered on commit. The default implementation returns an error.
+    async fn notify(&self, channel: &str, payload: &str) -> DjangoResult<()> {
+        let _ = payload;
+        Err(DjangoError::DatabaseError(format!(
+            "The {:?} backend does not support notifications (channel '{channel}')",
+            self.backend_type()
+        )))
+    }
 }
 
 /// Optional lifecycle hooks for model CRUD operations.
@@ -101,34 +117,44 @@ pub trait ModelLifecycleHooks: Model {
 ///
 /// If the primary key is set (non-None), performs an UPDATE of all fields.
 /// If the primary key is None, performs an INSERT and sets the PK from the
-/// returned value.
+/// returned value. Automatic timestamps are set on the instance and in the
+/// written row; see [`timestamps`](crate::timestamps).
 ///
 /// # Errors
 ///
 /// Returns an error if the database operation fails.
 pub async fn save_model<M: Model>(model: &mut M, db: &dyn DbExecutor) -> DjangoResult<()> {
     let compiler = SqlCompiler::new(db.backend_type());
+    let now = timestamps::now();
 
     if model.pk().is_some() {
         // UPDATE: set all non-pk fields WHERE pk = value
+        model.apply_auto_timestamps(now, false);
         let pk_value = model.pk().unwrap().clone();
         let pk_name = M::pk_field_name();
-        let fields: Vec<(&'static str, Value)> = model.non_pk_field_values();
+        let mut fields: Vec<(&'static str, Value)> = model.non_pk_field_values();
 
         if fields.is_empty() {
             return Ok(());
         }
+        timestamps::stamp_update(&M::meta().fields, &mut fields, now);
+        encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut fields)?;
 
         let where_clause = WhereNode::Condition {
             column: pk_name.to_string(),
             lookup: Lookup::Exact(pk_value),
         };
         let (sql, params) = compiler.compile_update(M::table_name(), &fields, &where_clause);
+        record_write();
         db.execute_sql(&sql, &params).await?;
     } else {
         // INSERT: insert non-pk fields, retrieve the auto-generated PK
-        let fields: Vec<(&'static str, Value)> = model.non_pk_field_values();
+        model.apply_auto_timestamps(now, true);
+        let mut fields: Vec<(&'static str, Value)> = model.non_pk_field_values();
+        timestamps::stamp_insert(&M::meta().fields, &mut fields, now);
+        encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut fields)?;
         let (sql, params) = compiler.compile_insert(M::table_name(), &fields);
+        record_write();
         let pk = db.insert_returning_id(&sql, &params).await?;
         model.set_pk(pk);
     }
@@ -152,15 +178,21 @@ pub async fn save_model_with_hooks<M: ModelLifecycleHooks>(
 /// Creates a new model instance in the database via INSERT.
 ///
 /// Always performs an INSERT regardless of whether the PK is set.
-/// Sets the PK from the returned value.
+/// Sets the PK from the returned value, and automatic timestamps as in
+/// [`save_model`].
 ///
 /// # Errors
 ///
 /// Returns an error if the INSERT fails.
 pub async fn create_model<M: Model>(model: &mut M, db: &dyn DbExecutor) -> DjangoResult<()> {
     let compiler = SqlCompiler::new(db.backend_type());
-    let fields: Vec<(&'static str, Value)> = model.non_pk_field_values();
+    let now = timestamps::now();
+    model.apply_auto_timestamps(now, true);
+    let mut fields: Vec<(&'static str, Value)> = model.non_pk_field_values();
+    timestamps::stamp_insert(&M::meta().fields, &mut fields, now);
+    encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut fields)?;
     let (sql, params) = compiler.compile_insert(M::table_name(), &fields);
+    record_write();
     let pk = db.insert_returning_id(&sql, &params).await?;
     model.set_pk(pk);
     Ok(())
@@ -194,6 +226,7 @@ pub async fn delete_model<M: Model>(model: &M, db: &dyn DbExecutor) -> DjangoRes
         lookup: Lookup::Exact(pk_value.clone()),
     };
     let (sql, params) = compiler.compile_delete(M::table_name(), &where_clause);
+    record_write();
     db.execute_sql(&sql, &params).await
 }
 
@@ -268,6 +301,7 @@ mod tests {
                     fields: vec![FieldDef::new("id", FieldType::BigAutoField).primary_key()],
                     constraints: vec![],
                     inheritance_type: crate::query::compiler::InheritanceType::None,
+                    db_schema: None,
                 });
                 &META
             }
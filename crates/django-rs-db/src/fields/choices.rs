@@ -0,0 +1,161 @@
+//! Typed choices for model fields.
+//!
+//! A Rust enum implementing [`Choices`] (usually via `#[derive(Choices)]`)
+//! describes a fixed set of stored values and their human-readable labels.
+//! This mirrors Django's `TextChoices` and `IntegerChoices`.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::fields::{Choices, FieldDef, FieldType};
+//! use django_rs_db::value::Value;
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! enum Status {
+//!     Draft,
+//!     Published,
+//! }
+//!
+//! impl Choices for Status {
+//!     fn variants() -> &'static [Self] {
+//!         &[Self::Draft, Self::Published]
+//!     }
+//!
+//!     fn value(&self) -> Value {
+//!         match self {
+//!             Self::Draft => Value::from("draft"),
+//!             Self::Published => Value::from("published"),
+//!         }
+//!     }
+//!
+//!     fn label(&self) -> &'static str {
+//!         match self {
+//!             Self::Draft => "Draft",
+//!             Self::Published => "Published",
+//!         }
+//!     }
+//! }
+//!
+//! assert_eq!(Status::from_value(&Value::from("draft")), Some(Status::Draft));
+//! assert_eq!(Status::label_for(&Value::from("published")), Some("Published"));
+//!
+//! let field = FieldDef::new("status", FieldType::CharField).choices_from::<Status>();
+//! assert_eq!(field.max_length, Some(9));
+//! assert!(field.validate(&Value::from("archived")).is_err());
+//! ```
+
+use crate::fields::FieldType;
+use crate::value::Value;
+
+/// A fixed set of values a field may hold, each with a display label.
+pub trait Choices: Sized + Clone + 'static {
+    /// Returns every variant, in declaration order.
+    fn variants() -> &'static [Self];
+
+    /// Returns the value stored in the database for this variant.
+    fn value(&self) -> Value;
+
+    /// Returns the human-readable label for this variant.
+    fn label(&self) -> &'static str;
+
+    /// Returns all choices as `(value, label)` pairs.
+    fn choices() -> Vec<(Value, String)> {
+        Self::variants()
+            .iter()
+            .map(|v| (v.value(), v.label().to_string()))
+            .collect()
+    }
+
+    /// Returns the variant whose stored value is `value`, if any.
+    fn from_value(value: &Value) -> Option<Self> {
+        Self::variants()
+            .iter()
+            .find(|v| v.value() == *value)
+            .cloned()
+    }
+
+    /// Returns the label for a stored value, if it is one of the choices.
+    fn label_for(value: &Value) -> Option<&'static str> {
+        Self::from_value(value).map(|v| v.label())
+    }
+
+    /// Returns the field type used to store these choices.
+    ///
+    /// `IntegerField` when every value is an integer, `CharField` otherwise.
+    fn field_type() -> FieldType {
+        if Self::variants()
+            .iter()
+            .all(|v| matches!(v.value(), Value::Int(_)))
+        {
+            FieldType::IntegerField
+        } else {
+            FieldType::CharField
+        }
+    }
+
+    /// Returns the length of the longest string value, if any are strings.
+    fn max_length() -> Option<usize> {
+        Self::variants()
+            .iter()
+            .filter_map(|v| match v.value() {
+                Value::String(s) => Some(s.chars().count()),
+                _ => None,
+            })
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Priority {
+        Low,
+        High,
+    }
+
+    impl Choices for Priority {
+        fn variants() -> &'static [Self] {
+            &[Self::Low, Self::High]
+        }
+
+        fn value(&self) -> Value {
+            match self {
+                Self::Low => Value::Int(1),
+                Self::High => Value::Int(2),
+            }
+        }
+
+        fn label(&self) -> &'static str {
+            match self {
+                Self::Low => "Low",
+                Self::High => "High",
+            }
+        }
+    }
+
+    #[test]
+    fn test_choices_pairs() {
+        assert_eq!(
+            Priority::choices(),
+            vec![
+                (Value::Int(1), "Low".to_string()),
+                (Value::Int(2), "High".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_value_and_label() {
+        assert_eq!(Priority::from_value(&Value::Int(2)), Some(Priority::High));
+        assert_eq!(Priority::from_value(&Value::Int(3)), None);
+        assert_eq!(Priority::label_for(&Value::Int(1)), Some("Low"));
+    }
+
+    #[test]
+    fn test_integer_choices_field_type() {
+        assert!(matches!(Priority::field_type(), FieldType::IntegerField));
+        assert_eq!(Priority::max_length(), None);
+    }
+}
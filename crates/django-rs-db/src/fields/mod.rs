@@ -4,6 +4,8 @@
 //! describe model fields and their database column mappings. These mirror
 //! Django's `django.db.models.fields` module.
 
+pub mod choices;
 pub mod types;
 
+pub use choices::Choices;
 pub use types::{FieldDef, FieldType, OnDelete};
@@ -4,6 +4,9 @@
 //! [`FieldType`] variant corresponds to a Django model field type, and
 //! [`FieldDef`] captures all metadata about a single model field.
 
+use django_rs_core::{DjangoError, ValidationError};
+
+use crate::fields::Choices;
 use crate::validators::Validator;
 use crate::value::Value;
 
@@ -64,6 +67,19 @@ pub enum FieldType {
     IpAddressField,
     /// File system path.
     FilePathField,
+    /// A string encrypted before storage and decrypted on load; see
+    /// [`encryption`](crate::encryption). `max_length` applies to the
+    /// plaintext.
+    EncryptedCharField {
+        /// The field holding the value's blind index, for exact lookups.
+        hash_field: Option<String>,
+    },
+    /// Unlimited-length text encrypted before storage and decrypted on load;
+    /// see [`encryption`](crate::encryption).
+    EncryptedTextField {
+        /// The field holding the value's blind index, for exact lookups.
+        hash_field: Option<String>,
+    },
     /// Many-to-one relationship.
     ForeignKey {
         /// The target model name (e.g. "auth.User").
@@ -180,6 +196,10 @@ pub struct FieldDef {
     pub validators: Vec<Box<dyn Validator>>,
     /// Whether the field is editable in forms.
     pub editable: bool,
+    /// Whether the field is set to the current time on every save.
+    pub auto_now: bool,
+    /// Whether the field is set to the current time when the row is created.
+    pub auto_now_add: bool,
 }
 
 impl FieldDef {
@@ -204,6 +224,8 @@ impl FieldDef {
             choices: None,
             validators: Vec::new(),
             editable: true,
+            auto_now: false,
+            auto_now_add: false,
         }
     }
 
@@ -249,6 +271,39 @@ impl FieldDef {
         self
     }
 
+    /// Sets whether the field is editable.
+    ///
+    /// Non-editable fields are left out of model forms and ignored by
+    /// [`Model::from_json`](crate::model::Model::from_json).
+    #[must_use]
+    pub const fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Sets the field to the current time on every save, including bulk
+    /// writes and queryset updates. See [`timestamps`](crate::timestamps).
+    ///
+    /// As in Django, this makes the field non-editable and blank.
+    #[must_use]
+    pub const fn auto_now(mut self) -> Self {
+        self.auto_now = true;
+        self.editable = false;
+        self.blank = true;
+        self
+    }
+
+    /// Sets the field to the current time when the row is created.
+    ///
+    /// As in Django, this makes the field non-editable and blank.
+    #[must_use]
+    pub const fn auto_now_add(mut self) -> Self {
+        self.auto_now_add = true;
+        self.editable = false;
+        self.blank = true;
+        self
+    }
+
     /// Sets the default value for this field.
     #[must_use]
     pub fn default(mut self, value: impl Into<Value>) -> Self {
@@ -270,6 +325,72 @@ impl FieldDef {
         self
     }
 
+    /// Sets the allowed values as `(value, label)` pairs.
+    #[must_use]
+    pub fn choices(mut self, choices: Vec<(Value, String)>) -> Self {
+        self.choices = Some(choices);
+        self
+    }
+
+    /// Sets the allowed values from a [`Choices`] enum.
+    ///
+    /// Also sets `max_length` to fit the longest string value, unless one is
+    /// already set.
+    #[must_use]
+    pub fn choices_from<C: Choices>(mut self) -> Self {
+        self.choices = Some(C::choices());
+        if self.max_length.is_none() {
+            self.max_length = C::max_length();
+        }
+        self
+    }
+
+    /// Returns the display label for `value`.
+    ///
+    /// This backs the generated `get_<field>_display()` methods: the label
+    /// of the matching choice, or the value itself if there is none. NULL
+    /// without a choice of its own displays as an empty string.
+    pub fn display_value(&self, value: &Value) -> String {
+        self.choices
+            .as_ref()
+            .and_then(|choices| choices.iter().find(|(v, _)| v == value))
+            .map_or_else(
+                || match value {
+                    Value::Null => String::new(),
+                    other => other.to_string(),
+                },
+                |(_, label)| label.clone(),
+            )
+    }
+
+    /// Validates `value` against this field's choices and validators.
+    ///
+    /// NULL and empty strings skip the choices check, as in Django.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with code `invalid_choice` if the value is
+    /// not one of the choices, or the first validator error.
+    pub fn validate(&self, value: &Value) -> Result<(), DjangoError> {
+        let is_empty = match value {
+            Value::Null => true,
+            Value::String(s) => s.is_empty(),
+            _ => false,
+        };
+        if let Some(ref choices) = self.choices {
+            if !is_empty && !choices.iter().any(|(v, _)| v == value) {
+                return Err(DjangoError::ValidationError(ValidationError::new(
+                    format!("Value '{value}' is not a valid choice."),
+                    "invalid_choice",
+                )));
+            }
+        }
+        for validator in &self.validators {
+            validator.validate(value)?;
+        }
+        Ok(())
+    }
+
     /// Returns `true` if this field represents a relational field.
     pub const fn is_relation(&self) -> bool {
         matches!(
@@ -287,6 +408,48 @@ impl FieldDef {
 }
 
 impl FieldType {
+    /// Returns the name of the Django field class this type corresponds to,
+    /// e.g. `"JSONField"` for [`FieldType::JsonField`].
+    pub const fn class_name(&self) -> &'static str {
+        match self {
+            Self::AutoField => "AutoField",
+            Self::BigAutoField => "BigAutoField",
+            Self::CharField => "CharField",
+            Self::TextField => "TextField",
+            Self::IntegerField => "IntegerField",
+            Self::BigIntegerField => "BigIntegerField",
+            Self::SmallIntegerField => "SmallIntegerField",
+            Self::FloatField => "FloatField",
+            Self::DecimalField { .. } => "DecimalField",
+            Self::BooleanField => "BooleanField",
+            Self::DateField => "DateField",
+            Self::DateTimeField => "DateTimeField",
+            Self::TimeField => "TimeField",
+            Self::DurationField => "DurationField",
+            Self::UuidField => "UUIDField",
+            Self::BinaryField => "BinaryField",
+            Self::JsonField => "JSONField",
+            Self::EmailField => "EmailField",
+            Self::UrlField => "URLField",
+            Self::SlugField => "SlugField",
+            Self::IpAddressField => "GenericIPAddressField",
+            Self::FilePathField => "FilePathField",
+            Self::EncryptedCharField { .. } => "EncryptedCharField",
+            Self::EncryptedTextField { .. } => "EncryptedTextField",
+            Self::ForeignKey { .. } => "ForeignKey",
+            Self::OneToOneField { .. } => "OneToOneField",
+            Self::ManyToManyField { .. } => "ManyToManyField",
+            Self::ArrayField { .. } => "ArrayField",
+            Self::HStoreField => "HStoreField",
+            Self::IntegerRangeField => "IntegerRangeField",
+            Self::BigIntegerRangeField => "BigIntegerRangeField",
+            Self::FloatRangeField => "DecimalRangeField",
+            Self::DateRangeField => "DateRangeField",
+            Self::DateTimeRangeField => "DateTimeRangeField",
+            Self::GeneratedField { .. } => "GeneratedField",
+        }
+    }
+
     /// Returns the SQL column type for the given field type on PostgreSQL.
     ///
     /// This is used by schema generation and migration tools.
@@ -295,7 +458,9 @@ impl FieldType {
             Self::AutoField => "SERIAL".to_string(),
             Self::BigAutoField => "BIGSERIAL".to_string(),
             Self::CharField => "VARCHAR".to_string(),
-            Self::TextField => "TEXT".to_string(),
+            Self::TextField | Self::EncryptedCharField { .. } | Self::EncryptedTextField { .. } => {
+                "TEXT".to_string()
+            }
             Self::IntegerField => "INTEGER".to_string(),
             Self::BigIntegerField => "BIGINT".to_string(),
             Self::SmallIntegerField => "SMALLINT".to_string(),
@@ -330,6 +495,14 @@ impl FieldType {
             Self::GeneratedField { output_field, .. } => output_field.pg_column_type(),
         }
     }
+
+    /// Returns `true` for the encrypted field types.
+    pub const fn is_encrypted(&self) -> bool {
+        matches!(
+            self,
+            Self::EncryptedCharField { .. } | Self::EncryptedTextField { .. }
+        )
+    }
 }
 
 #[cfg(test)]
@@ -584,4 +757,49 @@ mod tests {
         assert_eq!(FieldType::BinaryField.pg_column_type(), "BYTEA");
         assert_eq!(FieldType::IpAddressField.pg_column_type(), "INET");
     }
+
+    fn status_field() -> FieldDef {
+        FieldDef::new("status", FieldType::CharField).choices(vec![
+            (Value::from("d"), "Draft".to_string()),
+            (Value::from("p"), "Published".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_choice() {
+        let f = status_field();
+        assert!(f.validate(&Value::from("p")).is_ok());
+        assert!(f.validate(&Value::Null).is_ok());
+        assert!(f.validate(&Value::from("")).is_ok());
+
+        let err = f.validate(&Value::from("x")).unwrap_err();
+        match err {
+            DjangoError::ValidationError(e) => {
+                assert_eq!(e.code, "invalid_choice");
+                assert_eq!(e.message, "Value 'x' is not a valid choice.");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_runs_validators() {
+        let mut f = FieldDef::new("name", FieldType::CharField);
+        f.validators
+            .push(Box::new(crate::validators::MaxLengthValidator::new(3)));
+        assert!(f.validate(&Value::from("abc")).is_ok());
+        assert!(f.validate(&Value::from("abcd")).is_err());
+    }
+
+    #[test]
+    fn test_display_value() {
+        let f = status_field();
+        assert_eq!(f.display_value(&Value::from("d")), "Draft");
+        assert_eq!(f.display_value(&Value::from("zz")), "zz");
+        assert_eq!(f.display_value(&Value::Null), "");
+        assert_eq!(
+            FieldDef::new("n", FieldType::IntegerField).display_value(&Value::Int(4)),
+            "4"
+        );
+    }
 }
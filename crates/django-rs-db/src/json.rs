@@ -0,0 +1,773 @@
+//! JSON serialization of model instances.
+//!
+//! [`Model::to_json`] and [`Model::from_json`] map instances to and from JSON
+//! objects keyed by field name, so API views don't have to spell out the
+//! mapping for every model.
+//!
+//! Serializing can be limited to a subset of fields, and foreign keys can be
+//! [expanded](Expand) from their primary key into the related object:
+//!
+//! ```ignore
+//! let (posts, related) = Post::objects()
+//!     .all()
+//!     .separate_query(vec!["author"])
+//!     .execute_with_related(&db, &router, &connections)
+//!     .await?;
+//! let expand = Expand::new().rows("author", &related["author"], "id");
+//! let body: Vec<_> = posts
+//!     .iter()
+//!     .map(|p| p.to_json(Some(&["id", "title", "author"]), &expand))
+//!     .collect();
+//! // [{"id": 1, "title": "Hello", "author": {"id": 7, "username": "alice"}}, ...]
+//! ```
+//!
+//! Values are written in the same form as [`from_row`](crate::query::deserialize::from_row)
+//! reads them: dates, times and UUIDs as strings, durations as whole
+//! microseconds. Deserializing parses them back according to each field's
+//! [`FieldType`].
+//!
+//! Deserializing honors [`FieldDef::editable`]: primary keys, generated
+//! fields and non-editable fields (including `auto_now`/`auto_now_add`
+//! timestamps) are never read from the input. They are left NULL when the
+//! field is nullable and empty (`0`, `""`, ...) otherwise, to be filled in
+//! when the instance is saved.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use django_rs_core::{DjangoError, DjangoResult, ValidationError};
+use serde_json::{Map, Value as Json};
+
+use crate::fields::{FieldDef, FieldType};
+use crate::model::Model;
+use crate::query::compiler::Row;
+use crate::query::deserialize::value_to_json;
+use crate::value::Value;
+
+/// The related objects to nest in place of foreign keys when serializing.
+///
+/// Each expanded field maps the related objects' primary keys to their JSON.
+/// A foreign key that is not expanded, or whose related object was not
+/// given, is serialized as its primary key.
+#[derive(Debug, Clone, Default)]
+pub struct Expand {
+    /// Field name -> serialized primary key -> related object.
+    relations: HashMap<String, HashMap<String, Json>>,
+}
+
+impl Expand {
+    /// Creates an empty expansion: every foreign key is serialized as its
+    /// primary key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expands `field` into the matching instance of `related`.
+    ///
+    /// The related instances are serialized with all their fields and
+    /// keyed by their primary key field.
+    pub fn objects<R: Model>(mut self, field: &str, related: &[R]) -> Self {
+        let pk_name = R::pk_field_name();
+        let objects = related.iter().map(|obj| {
+            let key = obj
+                .field_values()
+                .into_iter()
+                .find(|(name, _)| *name == pk_name)
+                .map_or(Json::Null, |(_, value)| value_to_json(&value));
+            (key, obj.to_json(None, &Self::new()))
+        });
+        self.insert(field, objects);
+        self
+    }
+
+    /// Expands `field` into the matching row of `rows`, keyed by
+    /// `key_column`.
+    ///
+    /// Meant for the related rows returned by
+    /// [`QuerySet::execute_with_related`](crate::query::QuerySet::execute_with_related)
+    /// and [`QuerySet::execute_with_prefetch`](crate::query::QuerySet::execute_with_prefetch).
+    /// Each row becomes an object of all its columns.
+    pub fn rows(mut self, field: &str, rows: &[Row], key_column: &str) -> Self {
+        let objects = rows.iter().map(|row| {
+            let object: Map<String, Json> = row
+                .columns()
+                .iter()
+                .zip(row.values())
+                .map(|(column, value)| (column.clone(), value_to_json(value)))
+                .collect();
+            let key = object.get(key_column).cloned().unwrap_or(Json::Null);
+            (key, Json::Object(object))
+        });
+        self.insert(field, objects);
+        self
+    }
+
+    /// Expands `field` into `objects`, keeping the first object of each key.
+    fn insert(&mut self, field: &str, objects: impl Iterator<Item = (Json, Json)>) {
+        let mut by_key = HashMap::new();
+        for (key, object) in objects {
+            by_key.entry(key.to_string()).or_insert(object);
+        }
+        self.relations.insert(field.to_string(), by_key);
+    }
+
+    /// Returns the related object for `key` if `field` is expanded.
+    fn get(&self, field: &str, key: &Json) -> Option<&Json> {
+        if key.is_null() {
+            return None;
+        }
+        self.relations.get(field)?.get(&key.to_string())
+    }
+}
+
+/// Serializes `instance` to a JSON object. See [`Model::to_json`].
+pub fn to_json<M: Model>(instance: &M, fields: Option<&[&str]>, expand: &Expand) -> Json {
+    let meta = M::meta();
+    let object: Map<String, Json> = instance
+        .field_values()
+        .into_iter()
+        .filter(|(name, _)| fields.map_or(true, |fields| fields.contains(name)))
+        .map(|(name, value)| {
+            let json = value_to_json(&value);
+            let is_relation = meta.fields.iter().any(|f| {
+                f.name == name
+                    && matches!(
+                        f.field_type,
+                        FieldType::ForeignKey { .. } | FieldType::OneToOneField { .. }
+                    )
+            });
+            let json = if is_relation {
+                expand.get(name, &json).cloned().unwrap_or(json)
+            } else {
+                json
+            };
+            (name.to_string(), json)
+        })
+        .collect();
+    Json::Object(object)
+}
+
+/// Builds an instance from a JSON object. See [`Model::from_json`].
+///
+/// # Errors
+///
+/// Returns a `SerializationError` if `json` is not an object, and a
+/// `ValidationError` listing every editable field that is missing, NULL
+/// when not nullable, or of the wrong type.
+pub fn from_json<M: Model>(json: &Json) -> DjangoResult<M> {
+    let Json::Object(object) = json else {
+        return Err(DjangoError::SerializationError(format!(
+            "Cannot deserialize `{}` from JSON: expected an object",
+            M::meta().model_name
+        )));
+    };
+
+    let mut columns = Vec::new();
+    let mut values = Vec::new();
+    let mut field_errors: HashMap<String, Vec<ValidationError>> = HashMap::new();
+    for field in &M::meta().fields {
+        if matches!(field.field_type, FieldType::ManyToManyField { .. }) {
+            continue;
+        }
+        match field_from_json(field, object.get(field.name)) {
+            Ok(value) => {
+                columns.push(field.name.to_string());
+                values.push(value);
+            }
+            Err(e) => field_errors
+                .entry(field.name.to_string())
+                .or_default()
+                .push(e),
+        }
+    }
+    if !field_errors.is_empty() {
+        return Err(DjangoError::ValidationError(
+            ValidationError::with_field_errors(field_errors),
+        ));
+    }
+    M::from_row(&Row::new(columns, values))
+}
+
+/// Reads one field's value from the input, or fills in a read-only one.
+fn field_from_json(field: &FieldDef, input: Option<&Json>) -> Result<Value, ValidationError> {
+    let read_only = !field.editable
+        || field.primary_key
+        || matches!(field.field_type, FieldType::GeneratedField { .. });
+    if read_only {
+        return Ok(if field.null {
+            Value::Null
+        } else {
+            empty_value(&field.field_type)
+        });
+    }
+
+    let input = match input {
+        Some(input) => input.clone(),
+        None => match &field.default {
+            Some(default) => value_to_json(default),
+            None if field.null || field.blank => Json::Null,
+            None => return Err(ValidationError::new("This field is required.", "required")),
+        },
+    };
+    if input.is_null() {
+        return if field.null {
+            Ok(Value::Null)
+        } else if field.blank {
+            Ok(empty_value(&field.field_type))
+        } else {
+            Err(ValidationError::new("This field cannot be null.", "null"))
+        };
+    }
+    json_to_value(&field.field_type, &input).map_err(|message| {
+        ValidationError::new(message, "invalid").with_param("value", input.to_string())
+    })
+}
+
+/// Converts a JSON value to the [`Value`] a field of `field_type` stores.
+///
+/// Numbers and booleans may also be given as strings. Returns a message
+/// describing the expected input if `json` can't be converted.
+pub fn json_to_value(field_type: &FieldType, json: &Json) -> Result<Value, String> {
+    if json.is_null() {
+        return Ok(Value::Null);
+    }
+    let text = json.as_str();
+    let invalid = |expected: &str| format!("Expected {expected}, got {json}.");
+    match field_type {
+        FieldType::AutoField
+        | FieldType::BigAutoField
+        | FieldType::IntegerField
+        | FieldType::BigIntegerField
+        | FieldType::SmallIntegerField
+        | FieldType::ForeignKey { .. }
+        | FieldType::OneToOneField { .. }
+        | FieldType::ManyToManyField { .. } => json
+            .as_i64()
+            .or_else(|| text.and_then(|s| s.trim().parse().ok()))
+            .map(Value::Int)
+            .ok_or_else(|| invalid("an integer")),
+        FieldType::FloatField | FieldType::DecimalField { .. } => json
+            .as_f64()
+            .or_else(|| text.and_then(|s| s.trim().parse().ok()))
+            .map(Value::Float)
+            .ok_or_else(|| invalid("a number")),
+        FieldType::BooleanField => match (json.as_bool(), text) {
+            (Some(b), _) => Ok(Value::Bool(b)),
+            (None, Some("true" | "1")) => Ok(Value::Bool(true)),
+            (None, Some("false" | "0")) => Ok(Value::Bool(false)),
+            _ => Err(invalid("a boolean")),
+        },
+        FieldType::DateField => text
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            .map(Value::Date)
+            .ok_or_else(|| invalid("a date (YYYY-MM-DD)")),
+        FieldType::DateTimeField => text
+            .and_then(parse_datetime)
+            .map(Value::DateTime)
+            .ok_or_else(|| invalid("a datetime (YYYY-MM-DDTHH:MM:SS)")),
+        FieldType::TimeField => text
+            .and_then(|s| NaiveTime::parse_from_str(s, "%H:%M:%S%.f").ok())
+            .map(Value::Time)
+            .ok_or_else(|| invalid("a time (HH:MM:SS)")),
+        FieldType::DurationField => json
+            .as_i64()
+            .map(|us| Value::Duration(chrono::Duration::microseconds(us)))
+            .ok_or_else(|| invalid("a duration in microseconds")),
+        FieldType::UuidField => text
+            .and_then(|s| uuid::Uuid::parse_str(s).ok())
+            .map(Value::Uuid)
+            .ok_or_else(|| invalid("a UUID")),
+        FieldType::BinaryField => json
+            .as_array()
+            .and_then(|bytes| {
+                bytes
+                    .iter()
+                    .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect::<Option<Vec<u8>>>()
+            })
+            .map(Value::Bytes)
+            .ok_or_else(|| invalid("an array of bytes")),
+        FieldType::JsonField => Ok(Value::Json(json.clone())),
+        FieldType::ArrayField { base_field, .. } => json
+            .as_array()
+            .ok_or_else(|| invalid("an array"))?
+            .iter()
+            .map(|item| json_to_value(base_field, item))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::List),
+        FieldType::HStoreField => json
+            .as_object()
+            .and_then(|map| {
+                map.iter()
+                    .map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect::<Option<HashMap<_, _>>>()
+            })
+            .map(Value::HStore)
+            .ok_or_else(|| invalid("an object of strings")),
+        FieldType::IntegerRangeField | FieldType::BigIntegerRangeField => {
+            range_from_json(&FieldType::BigIntegerField, json)
+        }
+        FieldType::FloatRangeField => range_from_json(&FieldType::FloatField, json),
+        FieldType::DateRangeField => range_from_json(&FieldType::DateField, json),
+        FieldType::DateTimeRangeField => {
+            let Value::Range {
+                lower,
+                lower_inclusive,
+                upper,
+                upper_inclusive,
+            } = range_from_json(&FieldType::DateTimeField, json)?
+            else {
+                unreachable!("range_from_json returns a range");
+            };
+            let to_utc = |bound: Option<Box<Value>>| {
+                bound.map(|b| match *b {
+                    Value::DateTime(dt) => Box::new(Value::DateTimeTz(dt.and_utc())),
+                    other => Box::new(other),
+                })
+            };
+            Ok(Value::Range {
+                lower: to_utc(lower),
+                lower_inclusive,
+                upper: to_utc(upper),
+                upper_inclusive,
+            })
+        }
+        FieldType::GeneratedField { output_field, .. } => json_to_value(output_field, json),
+        FieldType::CharField
+        | FieldType::TextField
+        | FieldType::EmailField
+        | FieldType::UrlField
+        | FieldType::SlugField
+        | FieldType::IpAddressField
+        | FieldType::FilePathField
+        | FieldType::EncryptedCharField { .. }
+        | FieldType::EncryptedTextField { .. } => match json {
+            Json::String(s) => Ok(Value::String(s.clone())),
+            Json::Number(n) => Ok(Value::String(n.to_string())),
+            _ => Err(invalid("a string")),
+        },
+    }
+}
+
+/// Parses a naive datetime, converting RFC 3339 input with an offset to UTC.
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc).naive_utc())
+        })
+}
+
+/// Parses a `{"lower", "lower_inclusive", "upper", "upper_inclusive"}`
+/// object with bounds of type `bound_type`.
+fn range_from_json(bound_type: &FieldType, json: &Json) -> Result<Value, String> {
+    let object = json
+        .as_object()
+        .ok_or_else(|| format!("Expected a range object, got {json}."))?;
+    let bound = |key: &str| -> Result<Option<Box<Value>>, String> {
+        match object.get(key) {
+            None | Some(Json::Null) => Ok(None),
+            Some(b) => json_to_value(bound_type, b).map(|v| Some(Box::new(v))),
+        }
+    };
+    let inclusive =
+        |key: &str, default: bool| object.get(key).and_then(Json::as_bool).unwrap_or(default);
+    Ok(Value::Range {
+        lower: bound("lower")?,
+        lower_inclusive: inclusive("lower_inclusive", true),
+        upper: bound("upper")?,
+        upper_inclusive: inclusive("upper_inclusive", false),
+    })
+}
+
+/// The value a non-nullable read-only field starts out with.
+fn empty_value(field_type: &FieldType) -> Value {
+    match field_type {
+        FieldType::AutoField
+        | FieldType::BigAutoField
+        | FieldType::IntegerField
+        | FieldType::BigIntegerField
+        | FieldType::SmallIntegerField
+        | FieldType::ForeignKey { .. }
+        | FieldType::OneToOneField { .. }
+        | FieldType::ManyToManyField { .. } => Value::Int(0),
+        FieldType::FloatField | FieldType::DecimalField { .. } => Value::Float(0.0),
+        FieldType::BooleanField => Value::Bool(false),
+        FieldType::DateField => Value::Date(NaiveDate::default()),
+        FieldType::DateTimeField => Value::DateTime(NaiveDateTime::default()),
+        FieldType::TimeField => Value::Time(NaiveTime::default()),
+        FieldType::DurationField => Value::Duration(chrono::Duration::zero()),
+        FieldType::UuidField => Value::Uuid(uuid::Uuid::nil()),
+        FieldType::BinaryField => Value::Bytes(Vec::new()),
+        FieldType::JsonField => Value::Json(Json::Null),
+        FieldType::ArrayField { .. } => Value::List(Vec::new()),
+        FieldType::HStoreField => Value::HStore(HashMap::new()),
+        FieldType::IntegerRangeField
+        | FieldType::BigIntegerRangeField
+        | FieldType::FloatRangeField
+        | FieldType::DateRangeField
+        | FieldType::DateTimeRangeField => Value::Range {
+            lower: None,
+            lower_inclusive: true,
+            upper: None,
+            upper_inclusive: false,
+        },
+        FieldType::GeneratedField { output_field, .. } => empty_value(output_field),
+        FieldType::CharField
+        | FieldType::TextField
+        | FieldType::EmailField
+        | FieldType::UrlField
+        | FieldType::SlugField
+        | FieldType::IpAddressField
+        | FieldType::FilePathField
+        | FieldType::EncryptedCharField { .. }
+        | FieldType::EncryptedTextField { .. } => Value::String(String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelMeta;
+    use crate::query::compiler::InheritanceType;
+    use crate::OnDelete;
+
+    #[derive(Debug)]
+    struct Author {
+        id: i64,
+        username: String,
+    }
+
+    impl Model for Author {
+        fn meta() -> &'static ModelMeta {
+            use std::sync::LazyLock;
+            static META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+                app_label: "blog",
+                model_name: "author",
+                db_table: "blog_author".to_string(),
+                verbose_name: "author".to_string(),
+                verbose_name_plural: "authors".to_string(),
+                ordering: vec![],
+                unique_together: vec![],
+                indexes: vec![],
+                abstract_model: false,
+                fields: vec![
+                    FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                    FieldDef::new("username", FieldType::CharField),
+                ],
+                constraints: vec![],
+                inheritance_type: InheritanceType::None,
+                db_schema: None,
+            });
+            &META
+        }
+
+        fn table_name() -> &'static str {
+            "blog_author"
+        }
+
+        fn app_label() -> &'static str {
+            "blog"
+        }
+
+        fn pk(&self) -> Option<&Value> {
+            None
+        }
+
+        fn set_pk(&mut self, value: Value) {
+            if let Value::Int(id) = value {
+                self.id = id;
+            }
+        }
+
+        fn field_values(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("id", Value::Int(self.id)),
+                ("username", Value::String(self.username.clone())),
+            ]
+        }
+
+        fn from_row(row: &Row) -> Result<Self, DjangoError> {
+            Ok(Self {
+                id: row.get("id")?,
+                username: row.get("username")?,
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct Post {
+        id: i64,
+        title: String,
+        rating: Option<i64>,
+        published_on: NaiveDate,
+        created_at: NaiveDateTime,
+        author: i64,
+    }
+
+    impl Model for Post {
+        fn meta() -> &'static ModelMeta {
+            use std::sync::LazyLock;
+            static META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+                app_label: "blog",
+                model_name: "post",
+                db_table: "blog_post".to_string(),
+                verbose_name: "post".to_string(),
+                verbose_name_plural: "posts".to_string(),
+                ordering: vec![],
+                unique_together: vec![],
+                indexes: vec![],
+                abstract_model: false,
+                fields: vec![
+                    FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                    FieldDef::new("title", FieldType::CharField),
+                    FieldDef::new("rating", FieldType::IntegerField).nullable(),
+                    FieldDef::new("published_on", FieldType::DateField),
+                    FieldDef::new("created_at", FieldType::DateTimeField).auto_now_add(),
+                    FieldDef::new(
+                        "author",
+                        FieldType::ForeignKey {
+                            to: "blog_author".to_string(),
+                            on_delete: OnDelete::Cascade,
+                            related_name: None,
+                        },
+                    ),
+                ],
+                constraints: vec![],
+                inheritance_type: InheritanceType::None,
+                db_schema: None,
+            });
+            &META
+        }
+
+        fn table_name() -> &'static str {
+            "blog_post"
+        }
+
+        fn app_label() -> &'static str {
+            "blog"
+        }
+
+        fn pk(&self) -> Option<&Value> {
+            None
+        }
+
+        fn set_pk(&mut self, value: Value) {
+            if let Value::Int(id) = value {
+                self.id = id;
+            }
+        }
+
+        fn field_values(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("id", Value::Int(self.id)),
+                ("title", Value::String(self.title.clone())),
+                ("rating", Value::from(self.rating)),
+                ("published_on", Value::Date(self.published_on)),
+                ("created_at", Value::DateTime(self.created_at)),
+                ("author", Value::Int(self.author)),
+            ]
+        }
+
+        fn from_row(row: &Row) -> Result<Self, DjangoError> {
+            let date = |name: &str| match row.get::<Value>(name)? {
+                Value::Date(d) => Ok(d),
+                other => Err(DjangoError::DatabaseError(format!(
+                    "Expected Date, got {other:?}"
+                ))),
+            };
+            let datetime = |name: &str| match row.get::<Value>(name)? {
+                Value::DateTime(dt) => Ok(dt),
+                other => Err(DjangoError::DatabaseError(format!(
+                    "Expected DateTime, got {other:?}"
+                ))),
+            };
+            Ok(Self {
+                id: row.get("id")?,
+                title: row.get("title")?,
+                rating: row.get("rating")?,
+                published_on: date("published_on")?,
+                created_at: datetime("created_at")?,
+                author: row.get("author")?,
+            })
+        }
+    }
+
+    fn post() -> Post {
+        Post {
+            id: 3,
+            title: "Hello".to_string(),
+            rating: None,
+            published_on: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            created_at: NaiveDate::from_ymd_opt(2024, 5, 1)
+                .unwrap()
+                .and_hms_opt(12, 30, 0)
+                .unwrap(),
+            author: 7,
+        }
+    }
+
+    #[test]
+    fn test_to_json_all_fields() {
+        assert_eq!(
+            post().to_json(None, &Expand::new()),
+            serde_json::json!({
+                "id": 3,
+                "title": "Hello",
+                "rating": null,
+                "published_on": "2024-05-01",
+                "created_at": "2024-05-01T12:30:00",
+                "author": 7,
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_json_field_selection() {
+        assert_eq!(
+            post().to_json(Some(&["id", "title"]), &Expand::new()),
+            serde_json::json!({"id": 3, "title": "Hello"})
+        );
+    }
+
+    #[test]
+    fn test_to_json_expands_objects() {
+        let authors = [
+            Author {
+                id: 5,
+                username: "bob".to_string(),
+            },
+            Author {
+                id: 7,
+                username: "alice".to_string(),
+            },
+        ];
+        let expand = Expand::new().objects("author", &authors);
+        assert_eq!(
+            post().to_json(Some(&["author"]), &expand),
+            serde_json::json!({"author": {"id": 7, "username": "alice"}})
+        );
+    }
+
+    #[test]
+    fn test_to_json_expands_rows() {
+        let rows = [Row::new(
+            vec!["id".into(), "username".into()],
+            vec![Value::Int(7), Value::from("alice")],
+        )];
+        let expand = Expand::new().rows("author", &rows, "id");
+        assert_eq!(
+            post().to_json(Some(&["author"]), &expand),
+            serde_json::json!({"author": {"id": 7, "username": "alice"}})
+        );
+    }
+
+    #[test]
+    fn test_to_json_unmatched_expansion_keeps_pk() {
+        let expand = Expand::new().objects::<Author>("author", &[]);
+        assert_eq!(
+            post().to_json(Some(&["author"]), &expand),
+            serde_json::json!({"author": 7})
+        );
+    }
+
+    #[test]
+    fn test_from_json_parses_by_field_type() {
+        let post = Post::from_json(&serde_json::json!({
+            "title": "Hello",
+            "rating": "4",
+            "published_on": "2024-05-01",
+            "author": 7,
+        }))
+        .unwrap();
+        assert_eq!(post.title, "Hello");
+        assert_eq!(post.rating, Some(4));
+        assert_eq!(
+            post.published_on,
+            NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()
+        );
+        assert_eq!(post.author, 7);
+    }
+
+    #[test]
+    fn test_from_json_ignores_read_only_fields() {
+        let post = Post::from_json(&serde_json::json!({
+            "id": 99,
+            "title": "Hello",
+            "published_on": "2024-05-01",
+            "created_at": "2020-01-01T00:00:00",
+            "author": 7,
+        }))
+        .unwrap();
+        assert_eq!(post.id, 0);
+        assert_eq!(post.created_at, NaiveDateTime::default());
+        assert_eq!(post.rating, None);
+    }
+
+    #[test]
+    fn test_from_json_round_trip() {
+        let json = post().to_json(None, &Expand::new());
+        let copy = Post::from_json(&json).unwrap();
+        assert_eq!(copy.title, "Hello");
+        assert_eq!(copy.published_on, post().published_on);
+        assert_eq!(copy.author, 7);
+    }
+
+    #[test]
+    fn test_from_json_collects_field_errors() {
+        let err = Post::from_json(&serde_json::json!({
+            "title": null,
+            "published_on": "yesterday",
+        }))
+        .unwrap_err();
+        let DjangoError::ValidationError(e) = err else {
+            panic!("expected a validation error, got {err:?}");
+        };
+        assert_eq!(e.field_errors["title"][0].code, "null");
+        assert_eq!(e.field_errors["published_on"][0].code, "invalid");
+        assert_eq!(e.field_errors["author"][0].code, "required");
+        assert!(!e.field_errors.contains_key("rating"));
+    }
+
+    #[test]
+    fn test_from_json_rejects_non_object() {
+        assert!(matches!(
+            Post::from_json(&serde_json::json!([1, 2])),
+            Err(DjangoError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_json_to_value_types() {
+        assert_eq!(
+            json_to_value(&FieldType::BooleanField, &serde_json::json!("true")),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            json_to_value(
+                &FieldType::DateTimeField,
+                &serde_json::json!("2024-05-01T12:30:00+02:00")
+            ),
+            Ok(Value::DateTime(
+                NaiveDate::from_ymd_opt(2024, 5, 1)
+                    .unwrap()
+                    .and_hms_opt(10, 30, 0)
+                    .unwrap()
+            ))
+        );
+        assert_eq!(
+            json_to_value(
+                &FieldType::ArrayField {
+                    base_field: Box::new(FieldType::IntegerField),
+                    size: None,
+                },
+                &serde_json::json!([1, 2]),
+            ),
+            Ok(Value::List(vec![Value::Int(1), Value::Int(2)]))
+        );
+        assert!(json_to_value(&FieldType::IntegerField, &serde_json::json!("x")).is_err());
+    }
+}
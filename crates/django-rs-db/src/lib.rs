@@ -19,7 +19,13 @@
 //! - [`fields`] - Field definitions ([`FieldDef`](fields::FieldDef)) and types
 //! - [`value`] - The backend-agnostic [`Value`](value::Value) enum
 //! - [`query`] - Query building, lookups, expressions, and compilation
+//! - [`checks`] - System checks for model metadata
+//! - [`registry`] - The [`ModelRegistry`](registry::ModelRegistry) of installed models
+//! - [`json`] - JSON serialization of model instances ([`Expand`](json::Expand))
+//! - [`audit`] - Per-request [`AuditContext`](audit::AuditContext) attached to every query
 //! - [`constraints`] - Database constraints (CHECK, UNIQUE, EXCLUDE)
+//! - [`encryption`] - Encrypted fields, key rotation and blind-index lookups
+//! - [`timestamps`] - `auto_now`/`auto_now_add` handling and [`TimeStampedModel`](timestamps::TimeStampedModel)
 //! - [`validators`] - Field validators
 
 // These clippy lints are intentionally allowed for the ORM crate:
@@ -51,37 +57,51 @@
 // significant_drop_tightening: false positives with async Mutex guards
 #![allow(clippy::significant_drop_tightening)]
 
+pub mod audit;
+pub mod checks;
 pub mod constraints;
+pub mod encryption;
 pub mod executor;
 pub mod fields;
+pub mod json;
 pub mod model;
 pub mod query;
+pub mod registry;
 pub mod router;
+pub mod timestamps;
 pub mod transactions;
 pub mod validators;
 pub mod value;
 
 // Re-export the most commonly used types at the crate root.
+pub use audit::AuditContext;
 pub use constraints::{CheckConstraint, Constraint, ExclusionConstraint, UniqueConstraint};
 pub use executor::{
     create_model, create_model_with_hooks, delete_model, delete_model_with_hooks, refresh_model,
     save_model, save_model_with_hooks, DbExecutor, ModelLifecycleHooks,
 };
 pub use fields::{FieldDef, FieldType, OnDelete};
+pub use json::Expand;
 pub use model::{BloomIndex, BrinIndex, GinIndex, GistIndex, Index, IndexType, SpGistIndex};
 pub use model::{Model, ModelMeta};
 pub use query::expressions::search::{
     SearchQuery, SearchQueryType, SearchRank, SearchVector, TrigramSimilarity,
 };
 pub use query::{
-    AggregateFunc, CompoundQuery, CompoundType, DatabaseBackendType, Exists, Expression,
-    InheritanceType, Lookup, Manager, OrderBy, OuterRef, PrefetchRelatedField, PrefetchResult,
-    Query, QuerySet, Row, SelectColumn, SelectRelatedField, SqlCompiler, SubqueryExpression, When,
-    WhereNode, WindowExpression, WindowFrame, WindowFrameBound, WindowFrameType, WindowFunction, Q,
+    AggregateFunc, CompoundQuery, CompoundType, DatabaseBackendType, DateKind, DateTimeKind,
+    Exists, Expression, InheritanceType, Lookup, Manager, OrderBy, OuterRef, PrefetchRelatedField,
+    PrefetchResult, Query, QuerySet, Row, SelectColumn, SelectRelatedField, SqlCompiler,
+    SubqueryExpression, TupleLookup, When, WhereNode, WindowExpression, WindowFrame,
+    WindowFrameBound, WindowFrameType, WindowFunction, Q,
 };
-pub use router::{DatabaseEntry, DatabaseRouter, DatabasesConfig, RouterChain};
+pub use router::{DatabaseEntry, DatabaseRouter, DatabasesConfig, PrimaryPin, RouterChain};
+pub use timestamps::TimeStampedModel;
 pub use validators::Validator;
-pub use value::Value;
+pub use value::{Value, ValueType};
+
+// Re-exported so code generated by `#[derive(Model)]` can name chrono types
+// without the deriving crate depending on chrono.
+pub use chrono;
 
 // Re-export new modules at the crate root for convenience.
 pub use query::bulk::{
@@ -90,5 +110,7 @@ pub use query::bulk::{
 pub use query::custom_lookups::{CustomLookup, LookupRegistry, Transform, TransformOutput};
 pub use query::raw::{RawQuerySet, RawSql};
 pub use transactions::{
-    atomic, atomic_with_isolation, IsolationLevel, Savepoint, TransactionManager,
+    atomic, atomic_retry, atomic_with_isolation, atomic_without_savepoints,
+    is_serialization_failure, retry_metrics, IsolationLevel, RetryMetrics, Savepoint,
+    TransactionManager,
 };
@@ -7,10 +7,13 @@
 //! [`ModelMeta`] captures the equivalent of Django's `class Meta` options,
 //! including table name, ordering, indexes, and constraints.
 
+use std::collections::HashMap;
+
 use crate::fields::FieldDef;
+use crate::json::Expand;
 use crate::query::compiler::{InheritanceType, OrderBy};
 use crate::value::Value;
-use django_rs_core::DjangoError;
+use django_rs_core::{DjangoError, ValidationError};
 
 /// A database row abstraction used for constructing model instances.
 ///
@@ -54,6 +57,7 @@ pub use crate::query::compiler::Row;
 ///             fields: vec![],
 ///             constraints: vec![],
 ///             inheritance_type: InheritanceType::None,
+///             db_schema: None,
 ///         });
 ///         &META
 ///     }
@@ -81,8 +85,19 @@ pub trait Model: Send + Sync + 'static {
     fn meta() -> &'static ModelMeta;
 
     /// Returns the database table name.
+    ///
+    /// For models with a [`db_schema`](Model::db_schema) this is the
+    /// schema-qualified `schema.table` name.
     fn table_name() -> &'static str;
 
+    /// Returns the database schema the model's table lives in, if any.
+    ///
+    /// Set with `#[model(db_schema = "billing")]`. Schemas are a PostgreSQL
+    /// (and MySQL database) concept; `None` uses the connection's default.
+    fn db_schema() -> Option<&'static str> {
+        None
+    }
+
     /// Returns the application label this model belongs to.
     fn app_label() -> &'static str;
 
@@ -137,6 +152,81 @@ pub trait Model: Send + Sync + 'static {
     fn child_field_values(&self) -> Vec<(&'static str, Value)> {
         self.non_pk_field_values()
     }
+
+    /// Sets the instance's `auto_now` (and, when `created`, `auto_now_add`)
+    /// fields to `now`.
+    ///
+    /// Called by the save paths before writing, so the instance matches the
+    /// stored row. `#[derive(Model)]` generates this; the default does
+    /// nothing, in which case only the written values are stamped. See
+    /// [`timestamps`](crate::timestamps).
+    fn apply_auto_timestamps(&mut self, now: chrono::NaiveDateTime, created: bool) {
+        let _ = (now, created);
+    }
+
+    /// Serializes this instance to a JSON object keyed by field name.
+    ///
+    /// `fields` limits the output to the named fields (in model order).
+    /// Foreign keys are written as their primary key unless `expand` holds
+    /// the related object. See [`json`](crate::json).
+    fn to_json(&self, fields: Option<&[&str]>, expand: &Expand) -> serde_json::Value
+    where
+        Self: Sized,
+    {
+        crate::json::to_json(self, fields, expand)
+    }
+
+    /// Builds an unsaved instance from a JSON object keyed by field name.
+    ///
+    /// Only editable fields are read; the primary key, generated and
+    /// non-editable fields are left empty. Missing fields take their
+    /// default. See [`json`](crate::json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` whose `field_errors` lists every field
+    /// that is missing, NULL when not nullable, or of the wrong type.
+    fn from_json(json: &serde_json::Value) -> Result<Self, DjangoError>
+    where
+        Self: Sized,
+    {
+        crate::json::from_json(json)
+    }
+
+    /// Validates every field value with [`FieldDef::validate`].
+    ///
+    /// Mirrors Django's `Model.clean_fields()`: choices and validators are
+    /// checked for all fields, and failures are collected per field.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` whose `field_errors` lists every invalid
+    /// field.
+    ///
+    /// [`FieldDef::validate`]: crate::fields::FieldDef::validate
+    fn clean_fields(&self) -> Result<(), DjangoError> {
+        let meta = Self::meta();
+        let mut field_errors: HashMap<String, Vec<ValidationError>> = HashMap::new();
+        for (name, value) in self.field_values() {
+            let Some(field) = meta.fields.iter().find(|f| f.name == name) else {
+                continue;
+            };
+            match field.validate(&value) {
+                Ok(()) => {}
+                Err(DjangoError::ValidationError(e)) => {
+                    field_errors.entry(name.to_string()).or_default().push(e);
+                }
+                Err(other) => return Err(other),
+            }
+        }
+        if field_errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DjangoError::ValidationError(
+                ValidationError::with_field_errors(field_errors),
+            ))
+        }
+    }
 }
 
 /// Metadata about a model, equivalent to Django's `class Meta`.
@@ -169,10 +259,42 @@ pub struct ModelMeta {
     pub constraints: Vec<crate::constraints::BoxedConstraint>,
     /// The type of model inheritance.
     pub inheritance_type: InheritanceType,
+    /// The database schema holding the table, if not the default one.
+    /// [`db_table`](Self::db_table) is then qualified with it.
+    pub db_schema: Option<String>,
+}
+
+impl ModelMeta {
+    /// Returns the primary key field's column, `"id"` when no field is
+    /// marked as the primary key.
+    pub fn pk_column(&self) -> &str {
+        self.fields
+            .iter()
+            .find(|f| f.primary_key)
+            .map_or("id", |f| f.column.as_str())
+    }
 }
 
 /// A database index definition.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+///
+/// Besides plain column indexes, an index can cover SQL expressions
+/// (a functional index such as `LOWER("email")`) and be restricted to the
+/// rows matching a condition (a partial index):
+///
+/// ```
+/// use django_rs_db::model::Index;
+///
+/// let idx = Index::new("uniq_live_email", vec![])
+///     .expression("LOWER(\"email\")")
+///     .condition("\"deleted_at\" IS NULL")
+///     .unique(true);
+/// assert_eq!(
+///     idx.create_sql("users"),
+///     "CREATE UNIQUE INDEX \"uniq_live_email\" ON \"users\" USING btree (LOWER(\"email\")) \
+///      WHERE \"deleted_at\" IS NULL"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Index {
     /// Optional name for the index.
     pub name: Option<String>,
@@ -200,6 +322,38 @@ pub struct Index {
 }
 
 impl Index {
+    /// Creates a named B-tree index on `fields`.
+    pub fn new(name: impl Into<String>, fields: Vec<&str>) -> Self {
+        Self {
+            name: Some(name.into()),
+            fields: fields.into_iter().map(String::from).collect(),
+            unique: false,
+            index_type: IndexType::BTree,
+            concurrently: false,
+            expressions: Vec::new(),
+            include: Vec::new(),
+            condition: None,
+        }
+    }
+
+    /// Adds an SQL expression to index, after the columns.
+    pub fn expression(mut self, expression: impl Into<String>) -> Self {
+        self.expressions.push(expression.into());
+        self
+    }
+
+    /// Restricts the index to rows matching the SQL `condition`.
+    pub fn condition(mut self, condition: impl Into<String>) -> Self {
+        self.condition = Some(condition.into());
+        self
+    }
+
+    /// Sets whether the index enforces uniqueness.
+    pub const fn unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
     /// Generates the CREATE INDEX DDL statement for this index.
     pub fn create_sql(&self, table: &str) -> String {
         let unique_str = if self.unique { "UNIQUE " } else { "" };
@@ -492,6 +646,7 @@ mod tests {
                 ],
                 constraints: vec![],
                 inheritance_type: InheritanceType::None,
+                db_schema: None,
             });
             &META
         }
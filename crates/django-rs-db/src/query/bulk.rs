@@ -10,10 +10,13 @@
 //! Bulk operations minimize round trips to the database by batching multiple
 //! operations into a single (or few) SQL statements.
 
+use crate::encryption;
 use crate::executor::DbExecutor;
 use crate::model::Model;
 use crate::query::compiler::{DatabaseBackendType, SqlCompiler, WhereNode};
 use crate::query::lookups::Lookup;
+use crate::router::record_write;
+use crate::timestamps;
 use crate::value::Value;
 use django_rs_core::{DjangoError, DjangoResult};
 
@@ -33,6 +36,9 @@ pub struct BulkCreateOptions {
     /// Unique fields that define the conflict target. Required when
     /// `update_conflicts` or `ignore_conflicts` is true.
     pub unique_fields: Vec<&'static str>,
+    /// If true, `auto_now` and `auto_now_add` fields are written as given
+    /// instead of being set to the current time.
+    pub skip_auto_now: bool,
 }
 
 /// Options for `bulk_update` operations.
@@ -40,6 +46,8 @@ pub struct BulkCreateOptions {
 pub struct BulkUpdateOptions {
     /// Number of objects to update per batch. None means all at once.
     pub batch_size: Option<usize>,
+    /// If true, `auto_now` fields are not added to the updated fields.
+    pub skip_auto_now: bool,
 }
 
 /// Compiles a multi-row INSERT statement for bulk_create.
@@ -190,6 +198,10 @@ pub fn compile_bulk_update(
 ///
 /// Inserts multiple model instances in batched INSERT statements.
 /// Returns the number of rows inserted.
+///
+/// All objects get the same automatic timestamps, unless
+/// [`BulkCreateOptions::skip_auto_now`] is set; see
+/// [`timestamps`](crate::timestamps).
 pub async fn bulk_create<M: Model>(
     objects: &mut [M],
     options: &BulkCreateOptions,
@@ -201,9 +213,24 @@ pub async fn bulk_create<M: Model>(
 
     let batch_size = options.batch_size.unwrap_or(objects.len());
     let mut total_inserted = 0u64;
+    let now = timestamps::now();
 
     for chunk in objects.chunks_mut(batch_size) {
-        let rows: Vec<Vec<(&str, Value)>> = chunk.iter().map(Model::non_pk_field_values).collect();
+        let rows: Vec<Vec<(&str, Value)>> = chunk
+            .iter_mut()
+            .map(|obj| {
+                let mut values = if options.skip_auto_now {
+                    obj.non_pk_field_values()
+                } else {
+                    obj.apply_auto_timestamps(now, true);
+                    let mut values = obj.non_pk_field_values();
+                    timestamps::stamp_insert(&M::meta().fields, &mut values, now);
+                    values
+                };
+                encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut values)?;
+                Ok(values)
+            })
+            .collect::<DjangoResult<_>>()?;
 
         let (sql, params) = compile_bulk_insert(M::table_name(), &rows, options, db.backend_type());
 
@@ -211,6 +238,7 @@ pub async fn bulk_create<M: Model>(
             continue;
         }
 
+        record_write();
         let affected = db.execute_sql(&sql, &params).await?;
         total_inserted += affected;
     }
@@ -223,6 +251,10 @@ pub async fn bulk_create<M: Model>(
 /// Updates specific fields on multiple model instances. Each object must
 /// have a primary key set.
 ///
+/// The model's `auto_now` fields are updated to the current time along with
+/// `fields`, unless [`BulkUpdateOptions::skip_auto_now`] is set. The objects
+/// themselves are not modified.
+///
 /// Returns the total number of rows affected.
 pub async fn bulk_update<M: Model>(
     objects: &[M],
@@ -234,6 +266,28 @@ pub async fn bulk_update<M: Model>(
         return Ok(0);
     }
 
+    let now = timestamps::now();
+    let mut fields = fields.to_vec();
+    if !options.skip_auto_now {
+        for name in timestamps::auto_now_fields(&M::meta().fields) {
+            if !fields.contains(&name) {
+                fields.push(name);
+            }
+        }
+    }
+    // Updating an encrypted field also updates its blind index.
+    for field in &M::meta().fields {
+        let Some(hash_name) = encryption::hash_field(field) else {
+            continue;
+        };
+        let hash_def = M::meta().fields.iter().find(|f| f.name == hash_name);
+        if let Some(hash_def) = hash_def {
+            if fields.contains(&field.name) && !fields.contains(&hash_def.name) {
+                fields.push(hash_def.name);
+            }
+        }
+    }
+
     // Build (pk, field_values) pairs
     let pk_and_fields: Vec<(Value, Vec<(&str, Value)>)> = objects
         .iter()
@@ -243,7 +297,12 @@ pub async fn bulk_update<M: Model>(
                     "bulk_update requires all objects to have a primary key set".to_string(),
                 )
             })?;
-            Ok((pk.clone(), obj.field_values()))
+            let mut values = obj.field_values();
+            if !options.skip_auto_now {
+                timestamps::stamp_update(&M::meta().fields, &mut values, now);
+            }
+            encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut values)?;
+            Ok((pk.clone(), values))
         })
         .collect::<DjangoResult<Vec<_>>>()?;
 
@@ -251,13 +310,14 @@ pub async fn bulk_update<M: Model>(
         M::table_name(),
         M::pk_field_name(),
         &pk_and_fields,
-        fields,
+        &fields,
         options.batch_size,
         db.backend_type(),
     );
 
     let mut total = 0u64;
     for (sql, params) in &statements {
+        record_write();
         total += db.execute_sql(sql, params).await?;
     }
 
@@ -323,6 +383,7 @@ pub async fn get_or_create<M: Model>(
     }
 
     let (insert_sql, insert_params) = compiler.compile_insert(M::table_name(), &create_fields);
+    record_write();
     let pk = db.insert_returning_id(&insert_sql, &insert_params).await?;
 
     // Fetch the created object
@@ -392,6 +453,7 @@ pub async fn update_or_create<M: Model>(
             let update_fields: Vec<(&str, Value)> = defaults.to_vec();
             let (update_sql, update_params) =
                 compiler.compile_update(M::table_name(), &update_fields, &where_clause);
+            record_write();
             db.execute_sql(&update_sql, &update_params).await?;
 
             // Re-fetch to get updated values
@@ -426,6 +488,7 @@ pub async fn update_or_create<M: Model>(
         }
 
         let (insert_sql, insert_params) = compiler.compile_insert(M::table_name(), &create_fields);
+        record_write();
         let pk = db.insert_returning_id(&insert_sql, &insert_params).await?;
 
         // Fetch the created object
@@ -486,6 +549,7 @@ mod tests {
                 ],
                 constraints: vec![],
                 inheritance_type: crate::query::compiler::InheritanceType::None,
+                db_schema: None,
             });
             &META
         }
@@ -866,6 +930,27 @@ mod tests {
         assert!(stmts[0].0.contains("VALUES"));
     }
 
+    #[tokio::test]
+    async fn test_bulk_create_pins_current_scope() {
+        use crate::router::PrimaryPin;
+        use std::sync::Arc;
+
+        let db = MockDb::new(DatabaseBackendType::PostgreSQL);
+        let pin = Arc::new(PrimaryPin::new());
+        let mut items = vec![Item {
+            id: 0,
+            name: "Alice".to_string(),
+            price: 10,
+        }];
+
+        Arc::clone(&pin)
+            .scope(bulk_create(&mut items, &BulkCreateOptions::default(), &db))
+            .await
+            .unwrap();
+        assert!(pin.has_written());
+        assert!(pin.is_pinned());
+    }
+
     #[tokio::test]
     async fn test_bulk_create_with_batch_size() {
         let db = MockDb::new(DatabaseBackendType::PostgreSQL);
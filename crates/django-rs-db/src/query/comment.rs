@@ -0,0 +1,240 @@
+//! SQL comments and optimizer hints attached to generated queries.
+//!
+//! [`QueryComment`] carries `key=value` tags rendered in the
+//! [sqlcommenter](https://google.github.io/sqlcommenter/) format, so tools such
+//! as `pg_stat_statements` or slow query logs can attribute a statement to the
+//! code that issued it, plus optional optimizer hints placed where each backend
+//! expects them.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::query::comment::QueryComment;
+//! use django_rs_db::query::compiler::DatabaseBackendType;
+//!
+//! let mut comment = QueryComment::default();
+//! comment.add("controller=PostList");
+//! let sql = comment.apply("SELECT 1".to_string(), DatabaseBackendType::PostgreSQL);
+//! assert_eq!(sql, "SELECT 1 /*controller='PostList'*/");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::compiler::DatabaseBackendType;
+
+/// Comment tags and optimizer hints for a single query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryComment {
+    tags: BTreeMap<String, String>,
+    hints: Vec<String>,
+}
+
+impl QueryComment {
+    /// Adds tags from a comma-separated `key=value` list, e.g.
+    /// `"controller=PostList,action=index"`.
+    ///
+    /// A part without `=` is stored under the `comment` key. Adding an
+    /// existing key replaces its value.
+    pub fn add(&mut self, comment: &str) {
+        for part in comment.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((key, value)) => self.add_tag(key.trim(), value.trim()),
+                None => self.add_tag("comment", part),
+            }
+        }
+    }
+
+    /// Adds a single tag.
+    pub fn add_tag(&mut self, key: &str, value: &str) {
+        self.tags.insert(key.to_string(), value.to_string());
+    }
+
+    /// Adds an optimizer hint, such as `INDEX(auth_user idx_name)`.
+    ///
+    /// Hints are inserted verbatim (minus any comment delimiters), so they
+    /// must never contain user input. Delimiters are stripped until none are
+    /// left, so removing one can't join its neighbours into another.
+    pub fn add_hint(&mut self, hint: &str) {
+        let mut hint = hint.to_string();
+        while hint.contains("*/") || hint.contains("/*") {
+            hint = hint.replace("*/", "").replace("/*", "");
+        }
+        self.hints.push(hint);
+    }
+
+    /// Returns the tags in key order.
+    pub const fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// Returns the optimizer hints.
+    pub fn hints(&self) -> &[String] {
+        &self.hints
+    }
+
+    /// Returns `true` if there are no tags or hints.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.hints.is_empty()
+    }
+
+    /// Renders the tags as a sqlcommenter comment, e.g.
+    /// `/*action='index',controller='PostList'*/`.
+    ///
+    /// Keys and values are URL-encoded, so the comment can't be terminated
+    /// early by its contents.
+    pub fn render_tags(&self) -> Option<String> {
+        if self.tags.is_empty() {
+            return None;
+        }
+        let body: Vec<String> = self
+            .tags
+            .iter()
+            .map(|(key, value)| format!("{}='{}'", url_encode(key), url_encode(value)))
+            .collect();
+        Some(format!("/*{}*/", body.join(",")))
+    }
+
+    /// Adds the hints and tag comment to a compiled statement.
+    ///
+    /// Tags are appended at the end of the statement, as sqlcommenter
+    /// specifies. Hints go where the backend's planner reads them: MySQL
+    /// after the leading keyword (`SELECT /*+ ... */`), PostgreSQL (via
+    /// `pg_hint_plan`) as a leading comment. SQLite has no optimizer hints,
+    /// so they are dropped.
+    pub fn apply(&self, sql: String, backend: DatabaseBackendType) -> String {
+        if self.is_empty() {
+            return sql;
+        }
+
+        let mut sql = if self.hints.is_empty() {
+            sql
+        } else {
+            let hint = format!("/*+ {} */", self.hints.join(" "));
+            match backend {
+                DatabaseBackendType::MySQL => match sql.find(' ') {
+                    Some(pos) => format!("{} {hint}{}", &sql[..pos], &sql[pos..]),
+                    None => format!("{sql} {hint}"),
+                },
+                DatabaseBackendType::PostgreSQL => format!("{hint} {sql}"),
+                DatabaseBackendType::SQLite => sql,
+            }
+        };
+
+        if let Some(tags) = self.render_tags() {
+            let had_semicolon = sql.ends_with(';');
+            if had_semicolon {
+                sql.pop();
+            }
+            let _ = write!(sql, " {tags}");
+            if had_semicolon {
+                sql.push(';');
+            }
+        }
+        sql
+    }
+}
+
+/// Percent-encodes everything except unreserved URL characters.
+fn url_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_comment_leaves_sql_unchanged() {
+        let comment = QueryComment::default();
+        assert!(comment.is_empty());
+        assert_eq!(
+            comment.apply("SELECT 1".to_string(), DatabaseBackendType::MySQL),
+            "SELECT 1"
+        );
+    }
+
+    #[test]
+    fn test_tags_sorted_and_encoded() {
+        let mut comment = QueryComment::default();
+        comment.add("route=/posts/{id}, controller=PostList");
+        comment.add_tag("framework", "django-rs");
+        assert_eq!(
+            comment.render_tags().unwrap(),
+            "/*controller='PostList',framework='django-rs',route='%2Fposts%2F%7Bid%7D'*/"
+        );
+    }
+
+    #[test]
+    fn test_tags_cannot_close_comment() {
+        let mut comment = QueryComment::default();
+        comment.add_tag("x", "*/ DROP TABLE users; /*");
+        let rendered = comment.render_tags().unwrap();
+        assert_eq!(rendered.matches("*/").count(), 1);
+    }
+
+    #[test]
+    fn test_free_form_comment() {
+        let mut comment = QueryComment::default();
+        comment.add("nightly report");
+        assert_eq!(
+            comment.tags().get("comment").map(String::as_str),
+            Some("nightly report")
+        );
+    }
+
+    #[test]
+    fn test_comment_before_semicolon() {
+        let mut comment = QueryComment::default();
+        comment.add("a=b");
+        assert_eq!(
+            comment.apply("DELETE FROM t;".to_string(), DatabaseBackendType::SQLite),
+            "DELETE FROM t /*a='b'*/;"
+        );
+    }
+
+    #[test]
+    fn test_hint_placement_per_backend() {
+        let mut comment = QueryComment::default();
+        comment.add_hint("INDEX(t idx_t_a)");
+        let sql = || "SELECT * FROM t".to_string();
+        assert_eq!(
+            comment.apply(sql(), DatabaseBackendType::MySQL),
+            "SELECT /*+ INDEX(t idx_t_a) */ * FROM t"
+        );
+        assert_eq!(
+            comment.apply(sql(), DatabaseBackendType::PostgreSQL),
+            "/*+ INDEX(t idx_t_a) */ SELECT * FROM t"
+        );
+        assert_eq!(
+            comment.apply(sql(), DatabaseBackendType::SQLite),
+            "SELECT * FROM t"
+        );
+    }
+
+    #[test]
+    fn test_hint_strips_terminator() {
+        let mut comment = QueryComment::default();
+        comment.add_hint("SeqScan(t) */ DROP TABLE t; /*");
+        assert_eq!(comment.hints()[0], "SeqScan(t)  DROP TABLE t; ");
+    }
+
+    #[test]
+    fn test_hint_strips_nested_delimiters() {
+        let mut comment = QueryComment::default();
+        comment.add_hint("**//");
+        comment.add_hint("//**");
+        comment.add_hint("a/*/**//b");
+        assert_eq!(comment.hints(), ["", "", "a/b"]);
+        let sql = comment.apply("SELECT 1".to_string(), DatabaseBackendType::PostgreSQL);
+        assert_eq!(sql.matches("*/").count(), 1);
+    }
+}
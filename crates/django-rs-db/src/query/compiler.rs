@@ -7,12 +7,14 @@
 //!
 //! This is the equivalent of Django's `django.db.models.sql.compiler`.
 
+use super::expressions::aggregates::{OrderedAggregate, OrderedAggregateFunc};
 use super::expressions::window::{WindowExpression, WindowFunction};
 use super::expressions::Expression;
-use super::lookups::{Lookup, Q};
+use super::lookups::{Lookup, TupleLookup, Q};
 use crate::value::Value;
 use django_rs_core::DjangoError;
 use std::collections::HashMap;
+use std::hash::BuildHasher;
 
 /// The type of database backend, used by the compiler to generate
 /// backend-specific SQL syntax.
@@ -26,6 +28,104 @@ pub enum DatabaseBackendType {
     MySQL,
 }
 
+impl DatabaseBackendType {
+    /// Quotes a table name for use in SQL.
+    ///
+    /// A schema-qualified `schema.table` name becomes `"schema"."table"`.
+    /// SQLite has no schemas, so there the dotted name is quoted as a single
+    /// identifier, matching the table the schema editor creates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::DatabaseBackendType;
+    ///
+    /// let pg = DatabaseBackendType::PostgreSQL;
+    /// assert_eq!(pg.quote_table_name("billing.invoice"), "\"billing\".\"invoice\"");
+    /// assert_eq!(pg.quote_table_name("blog_post"), "\"blog_post\"");
+    /// assert_eq!(
+    ///     DatabaseBackendType::SQLite.quote_table_name("billing.invoice"),
+    ///     "\"billing.invoice\""
+    /// );
+    /// ```
+    pub fn quote_table_name(self, name: &str) -> String {
+        match (self, name.split_once('.')) {
+            (Self::PostgreSQL | Self::MySQL, Some((schema, table))) => {
+                format!("{}.{}", self.quote_name(schema), self.quote_name(table))
+            }
+            _ => self.quote_name(name),
+        }
+    }
+
+    /// Quotes a column or other identifier for use in SQL, doubling any
+    /// `"` inside it. Every backend quotes with `"`, as the compiler does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::DatabaseBackendType;
+    ///
+    /// let pg = DatabaseBackendType::PostgreSQL;
+    /// assert_eq!(pg.quote_name("title"), "\"title\"");
+    /// assert_eq!(pg.quote_name("say \"hi\""), "\"say \"\"hi\"\"\"");
+    /// ```
+    #[allow(clippy::unused_self)]
+    pub fn quote_name(self, name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    /// Returns whether schema changes can run inside a transaction.
+    ///
+    /// MySQL implicitly commits on every DDL statement, so wrapping a
+    /// migration in `BEGIN`/`COMMIT` there would give no atomicity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::DatabaseBackendType;
+    ///
+    /// assert!(DatabaseBackendType::PostgreSQL.supports_transactional_ddl());
+    /// assert!(!DatabaseBackendType::MySQL.supports_transactional_ddl());
+    /// ```
+    pub const fn supports_transactional_ddl(self) -> bool {
+        matches!(self, Self::PostgreSQL | Self::SQLite)
+    }
+
+    /// Returns whether multi-column lookups compile to row values such as
+    /// `("a", "b") IN ((?, ?))`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::DatabaseBackendType;
+    ///
+    /// assert!(DatabaseBackendType::PostgreSQL.supports_row_values());
+    /// assert!(DatabaseBackendType::MySQL.supports_row_values());
+    /// ```
+    pub const fn supports_row_values(self) -> bool {
+        matches!(self, Self::PostgreSQL | Self::SQLite | Self::MySQL)
+    }
+
+    /// Returns whether ordering comparisons of row values, such as
+    /// `("a", "b") > (?, ?)`, can use an index on the columns.
+    ///
+    /// MySQL range-optimizes row values in `=` and `IN` but not in `<`, `<=`,
+    /// `>` or `>=`, so there those comparisons are expanded into the
+    /// equivalent OR-of-ANDs instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::DatabaseBackendType;
+    ///
+    /// assert!(DatabaseBackendType::PostgreSQL.indexes_row_comparisons());
+    /// assert!(!DatabaseBackendType::MySQL.indexes_row_comparisons());
+    /// ```
+    pub const fn indexes_row_comparisons(self) -> bool {
+        matches!(self, Self::PostgreSQL | Self::SQLite)
+    }
+}
+
 /// A column ordering direction.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OrderBy {
@@ -86,6 +186,44 @@ pub enum WhereNode {
     Or(Vec<WhereNode>),
     /// Logical NOT of a condition.
     Not(Box<WhereNode>),
+    /// Membership in the rows of a single-column subquery
+    /// (`column IN (SELECT ...)`).
+    InSubquery {
+        /// The column name.
+        column: String,
+        /// The subquery, which must select exactly one column.
+        query: Box<Query>,
+    },
+    /// A column compared to a column of the enclosing query, used to
+    /// correlate a subquery (`"inner"."column" = "outer"."outer_column"`).
+    OuterRef {
+        /// The table of the subquery.
+        table: String,
+        /// The column of the subquery.
+        column: String,
+        /// The table of the enclosing query, filled in when the subquery is
+        /// attached to it.
+        outer_table: Option<String>,
+        /// The column of the enclosing query.
+        outer_column: String,
+    },
+    /// A boolean expression such as `EXISTS (...)`.
+    Expression(Expression),
+    /// A lookup applied to a computed expression rather than a column, as
+    /// produced by filtering on an [`alias`](super::queryset::QuerySet::alias).
+    ExpressionLookup {
+        /// The expression being tested.
+        expr: Expression,
+        /// The lookup type.
+        lookup: Lookup,
+    },
+    /// A lookup on several columns compared as a row value.
+    Tuple {
+        /// The column names, in the order of the values in each row.
+        columns: Vec<String>,
+        /// The lookup type.
+        lookup: TupleLookup,
+    },
 }
 
 impl WhereNode {
@@ -99,10 +237,131 @@ impl WhereNode {
             Q::And(children) => Self::And(children.iter().map(Self::from_q).collect()),
             Q::Or(children) => Self::Or(children.iter().map(Self::from_q).collect()),
             Q::Not(inner) => Self::Not(Box::new(Self::from_q(inner))),
+            Q::Tuple { fields, lookup } => Self::Tuple {
+                columns: fields.clone(),
+                lookup: lookup.clone(),
+            },
+        }
+    }
+
+    /// Sets the outer table of every unresolved [`WhereNode::OuterRef`] in
+    /// this tree.
+    ///
+    /// Nested subqueries are not searched: their references were resolved
+    /// against their own enclosing query when they were attached to it.
+    pub fn resolve_outer_refs(&mut self, outer: &str) {
+        match self {
+            Self::And(children) | Self::Or(children) => {
+                for child in children {
+                    child.resolve_outer_refs(outer);
+                }
+            }
+            Self::Not(inner) => inner.resolve_outer_refs(outer),
+            Self::OuterRef { outer_table, .. } => {
+                if outer_table.is_none() {
+                    *outer_table = Some(outer.to_string());
+                }
+            }
+            Self::Condition { .. }
+            | Self::InSubquery { .. }
+            | Self::Expression(_)
+            | Self::ExpressionLookup { .. }
+            | Self::Tuple { .. } => {}
+        }
+    }
+
+    /// Returns `true` if the tree may refer to the column or annotation
+    /// `name`. Subqueries are not searched.
+    pub fn references(&self, name: &str) -> bool {
+        match self {
+            Self::Condition { column, .. } | Self::InSubquery { column, .. } => column == name,
+            Self::And(children) | Self::Or(children) => {
+                children.iter().any(|child| child.references(name))
+            }
+            Self::Not(inner) => inner.references(name),
+            Self::OuterRef { .. } => false,
+            Self::Expression(expr) | Self::ExpressionLookup { expr, .. } => expr.references(name),
+            Self::Tuple { columns, .. } => columns.iter().any(|column| column == name),
+        }
+    }
+
+    /// Replaces conditions on the given aliases with lookups on their
+    /// expressions, since `WHERE` cannot refer to unselected aliases.
+    pub fn resolve_aliases<S: BuildHasher>(&mut self, aliases: &HashMap<String, Expression, S>) {
+        match self {
+            Self::And(children) | Self::Or(children) => {
+                for child in children {
+                    child.resolve_aliases(aliases);
+                }
+            }
+            Self::Not(inner) => inner.resolve_aliases(aliases),
+            Self::Condition { column, lookup } => {
+                if let Some(expr) = aliases.get(column.as_str()) {
+                    *self = Self::ExpressionLookup {
+                        expr: expr.clone(),
+                        lookup: lookup.clone(),
+                    };
+                }
+            }
+            Self::InSubquery { .. }
+            | Self::OuterRef { .. }
+            | Self::Expression(_)
+            | Self::ExpressionLookup { .. }
+            | Self::Tuple { .. } => {}
         }
     }
 }
 
+/// Builds a single-column lookup from its value.
+type LookupFn = fn(Value) -> Lookup;
+
+/// Expands a multi-column lookup into single-column conditions, for backends
+/// without row values.
+///
+/// `IN` becomes an OR of per-row ANDs, and a comparison such as
+/// `(a, b, c) > (x, y, z)` becomes
+/// `a > x OR (a = x AND b > y) OR (a = x AND b = y AND c > z)`.
+fn expand_tuple_lookup(columns: &[String], lookup: &TupleLookup) -> WhereNode {
+    let equal = |values: &[Value]| -> Vec<WhereNode> {
+        columns
+            .iter()
+            .zip(values)
+            .map(|(column, value)| WhereNode::Condition {
+                column: column.clone(),
+                lookup: Lookup::Exact(value.clone()),
+            })
+            .collect()
+    };
+    let (values, strict, inclusive): (&[Value], LookupFn, LookupFn) = match lookup {
+        TupleLookup::Exact(values) => return WhereNode::And(equal(values)),
+        TupleLookup::In(rows) => {
+            return WhereNode::Or(
+                rows.iter()
+                    .map(|values| WhereNode::And(equal(values)))
+                    .collect(),
+            )
+        }
+        TupleLookup::Gt(values) => (values, Lookup::Gt, Lookup::Gt),
+        TupleLookup::Gte(values) => (values, Lookup::Gt, Lookup::Gte),
+        TupleLookup::Lt(values) => (values, Lookup::Lt, Lookup::Lt),
+        TupleLookup::Lte(values) => (values, Lookup::Lt, Lookup::Lte),
+    };
+    let last = columns.len() - 1;
+    WhereNode::Or(
+        (0..columns.len())
+            .map(|i| {
+                let compare = if i == last { inclusive } else { strict };
+                let mut conditions = equal(&values[..i]);
+                conditions.push(WhereNode::Condition {
+                    column: columns[i].clone(),
+                    lookup: compare(values[i].clone()),
+                });
+                WhereNode::And(conditions)
+            })
+            .collect(),
+    )
+}
+
 /// A JOIN clause in the query AST.
 #[derive(Debug, Clone)]
 pub struct Join {
@@ -249,6 +508,8 @@ pub struct Query {
     pub distinct: bool,
     /// Named annotations (computed columns).
     pub annotations: HashMap<String, Expression>,
+    /// Named expressions usable in filters and ordering but not selected.
+    pub aliases: HashMap<String, Expression>,
     /// Named aggregates.
     pub aggregates: HashMap<String, Expression>,
     /// Compound queries (UNION, INTERSECT, EXCEPT).
@@ -276,6 +537,7 @@ impl Query {
             offset: None,
             distinct: false,
             annotations: HashMap::new(),
+            aliases: HashMap::new(),
             aggregates: HashMap::new(),
             compound_queries: Vec::new(),
             select_related: Vec::new(),
@@ -283,6 +545,67 @@ impl Query {
             inheritance: InheritanceType::None,
         }
     }
+
+    /// Returns a copy of the query reduced to what decides which rows match,
+    /// for `count()` and `exists()`.
+    ///
+    /// Ordering and `select_related` joins are dropped: a `LEFT JOIN` on a
+    /// foreign key never adds or removes rows. Annotations are dropped too,
+    /// except those that filters, grouping or the kept annotations refer to.
+    /// A `DISTINCT` query keeps all its annotations, since they are part of
+    /// the rows it de-duplicates. The select list and slicing are left to the
+    /// caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::{OrderBy, Query, WhereNode};
+    /// use django_rs_db::query::expressions::Expression;
+    /// use django_rs_db::query::lookups::{Lookup, Q};
+    ///
+    /// let mut query = Query::new("blog_post");
+    /// query.annotations.insert("upper_title".into(), Expression::func("UPPER", vec![Expression::col("title")]));
+    /// query.annotations.insert("words".into(), Expression::func("LENGTH", vec![Expression::col("body")]));
+    /// query.where_clause = Some(WhereNode::from_q(&Q::filter("words", Lookup::Gt(100.into()))));
+    /// query.order_by = vec![OrderBy::desc("published")];
+    ///
+    /// let count = query.for_count();
+    /// assert!(count.order_by.is_empty());
+    /// assert_eq!(count.annotations.keys().collect::<Vec<_>>(), ["words"]);
+    /// ```
+    pub fn for_count(&self) -> Self {
+        let mut query = self.clone();
+        query.order_by.clear();
+        query.select_related.clear();
+        if self.distinct {
+            return query;
+        }
+
+        let filtered_on = |name: &str| {
+            self.where_clause
+                .as_ref()
+                .is_some_and(|w| w.references(name))
+                || self.having.as_ref().is_some_and(|h| h.references(name))
+                || self.group_by.iter().any(|column| column == name)
+                || self.aliases.values().any(|alias| alias.references(name))
+        };
+        let mut kept: HashMap<String, Expression> = HashMap::new();
+        loop {
+            let before = kept.len();
+            for (name, expr) in &self.annotations {
+                if !kept.contains_key(name)
+                    && (filtered_on(name) || kept.values().any(|e| e.references(name)))
+                {
+                    kept.insert(name.clone(), expr.clone());
+                }
+            }
+            if kept.len() == before {
+                break;
+            }
+        }
+        query.annotations = kept;
+        query
+    }
 }
 
 /// A generic database row for passing data between backends and the ORM.
@@ -315,6 +638,23 @@ impl Row {
         &self.columns
     }
 
+    /// Returns the values, in column order.
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Deserializes the row into any serde type, by column name for structs
+    /// and by position for tuples.
+    ///
+    /// See [`deserialize`](crate::query::deserialize) for the conversions.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` naming the missing or mismatched column.
+    pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> Result<T, DjangoError> {
+        crate::query::deserialize::from_row(self)
+    }
+
     /// Returns the number of columns.
     pub fn len(&self) -> usize {
         self.columns.len()
@@ -442,6 +782,103 @@ impl FromValue for uuid::Uuid {
     }
 }
 
+impl FromValue for i16 {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::Int(i) => i16::try_from(*i).map_err(|e| {
+                DjangoError::DatabaseError(format!("Int value out of i16 range: {e}"))
+            }),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected Int, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        f64::from_value(value).map(|f| f as f32)
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::Bytes(v) => Ok(v.clone()),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected Bytes, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for chrono::NaiveDate {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::Date(v) => Ok(*v),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected Date, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for chrono::NaiveDateTime {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::DateTime(v) => Ok(*v),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected DateTime, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for chrono::DateTime<chrono::Utc> {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::DateTimeTz(v) => Ok(*v),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected DateTimeTz, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for chrono::NaiveTime {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::Time(v) => Ok(*v),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected Time, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for chrono::Duration {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::Duration(v) => Ok(*v),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected Duration, got {value:?}"
+            ))),
+        }
+    }
+}
+
+impl FromValue for serde_json::Value {
+    fn from_value(value: &Value) -> Result<Self, DjangoError> {
+        match value {
+            Value::Json(v) => Ok(v.clone()),
+            _ => Err(DjangoError::DatabaseError(format!(
+                "Expected Json, got {value:?}"
+            ))),
+        }
+    }
+}
+
 impl FromValue for Value {
     fn from_value(value: &Value) -> Result<Self, DjangoError> {
         Ok(value.clone())
@@ -473,13 +910,50 @@ impl SqlCompiler {
     }
 
     /// Returns a parameter placeholder for the given 1-based index.
-    fn placeholder(&self, index: usize) -> String {
+    pub(crate) fn placeholder(&self, index: usize) -> String {
         match self.backend {
             DatabaseBackendType::PostgreSQL => format!("${index}"),
             DatabaseBackendType::SQLite | DatabaseBackendType::MySQL => "?".to_string(),
         }
     }
 
+    /// Compiles `query` as a subquery of a statement that already has
+    /// `params`, appending the subquery's parameters.
+    ///
+    /// PostgreSQL placeholders are renumbered to continue after the
+    /// existing parameters.
+    fn compile_subquery(&self, query: &Query, params: &mut Vec<Value>) -> String {
+        let (sql, sub_params) = self.compile_select(query);
+        let sql = self.renumber_placeholders(&sql, params.len());
+        params.extend(sub_params);
+        sql
+    }
+
+    /// Shifts `$n` placeholders in `sql` by `offset` on PostgreSQL.
+    fn renumber_placeholders(&self, sql: &str, offset: usize) -> String {
+        if self.backend != DatabaseBackendType::PostgreSQL || offset == 0 {
+            return sql.to_string();
+        }
+        let mut out = String::with_capacity(sql.len() + 8);
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            out.push(c);
+            if c != '$' {
+                continue;
+            }
+            let mut digits = String::new();
+            while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                digits.push(*d);
+                chars.next();
+            }
+            match digits.parse::<usize>() {
+                Ok(n) => out.push_str(&(n + offset).to_string()),
+                Err(_) => out.push_str(&digits),
+            }
+        }
+        out
+    }
+
     /// Compiles a SELECT query into SQL and parameters.
     ///
     /// Handles select_related JOINs, multi-table inheritance JOINs,
@@ -513,7 +987,7 @@ impl SqlCompiler {
                 .map(|col| match col {
                     SelectColumn::Column(name) => format!("\"{name}\""),
                     SelectColumn::TableColumn(table, name) => {
-                        format!("\"{table}\".\"{name}\"")
+                        format!("{}.\"{name}\"", self.backend.quote_table_name(table))
                     }
                     SelectColumn::Expression(expr, alias) => {
                         let expr_sql = self.compile_expression(expr, &mut params);
@@ -537,7 +1011,8 @@ impl SqlCompiler {
         }
 
         // FROM
-        sql.push_str(&format!(" FROM \"{effective_table}\""));
+        let quoted_table = self.backend.quote_table_name(effective_table);
+        sql.push_str(&format!(" FROM {quoted_table}"));
 
         // Multi-table inheritance JOIN (child joins parent)
         if let InheritanceType::MultiTable {
@@ -547,17 +1022,18 @@ impl SqlCompiler {
         } = &query.inheritance
         {
             sql.push_str(&format!(
-                " INNER JOIN \"{parent_table}\" ON \"{effective_table}\".\"{parent_link_column}\" = \"{parent_table}\".\"{parent_pk_column}\""
+                " INNER JOIN {parent} ON {quoted_table}.\"{parent_link_column}\" = {parent}.\"{parent_pk_column}\"",
+                parent = self.backend.quote_table_name(parent_table),
             ));
         }
 
         // select_related JOINs (LEFT OUTER JOIN for each related field)
         for sr in &query.select_related {
             sql.push_str(&format!(
-                " LEFT JOIN \"{}\" AS \"{}\" ON \"{}\".\"{}\" = \"{}\".\"{}\"",
-                sr.related_table,
+                " LEFT JOIN {} AS \"{}\" ON {}.\"{}\" = \"{}\".\"{}\"",
+                self.backend.quote_table_name(&sr.related_table),
                 sr.alias,
-                effective_table,
+                quoted_table,
                 sr.fk_column,
                 sr.alias,
                 sr.related_column,
@@ -568,9 +1044,9 @@ impl SqlCompiler {
         for join in &query.joins {
             let alias = join.alias.as_deref().unwrap_or(&join.table);
             sql.push_str(&format!(
-                " {} \"{}\" AS \"{}\" ON ",
+                " {} {} AS \"{}\" ON ",
                 join.join_type.sql_keyword(),
-                join.table,
+                self.backend.quote_table_name(&join.table),
                 alias
             ));
             self.compile_where_node(&join.on, &mut sql, &mut params);
@@ -579,7 +1055,13 @@ impl SqlCompiler {
         // WHERE
         if let Some(ref where_clause) = query.where_clause {
             sql.push_str(" WHERE ");
-            self.compile_where_node(where_clause, &mut sql, &mut params);
+            if query.aliases.is_empty() {
+                self.compile_where_node(where_clause, &mut sql, &mut params);
+            } else {
+                let mut resolved = where_clause.clone();
+                resolved.resolve_aliases(&query.aliases);
+                self.compile_where_node(&resolved, &mut sql, &mut params);
+            }
         }
 
         // GROUP BY (only real group_by columns, not the __select_related__ hack)
@@ -613,7 +1095,11 @@ impl SqlCompiler {
                         Some(false) => " NULLS LAST",
                         None => "",
                     };
-                    format!("\"{}\"{dir}{nulls}", o.column)
+                    let column = query.aliases.get(&o.column).map_or_else(
+                        || format!("\"{}\"", o.column),
+                        |expr| self.compile_expression(expr, &mut params),
+                    );
+                    format!("{column}{dir}{nulls}")
                 })
                 .collect();
             sql.push_str(&format!(" ORDER BY {}", orders.join(", ")));
@@ -647,6 +1133,7 @@ impl SqlCompiler {
             offset: None,
             distinct: query.distinct,
             annotations: query.annotations.clone(),
+            aliases: query.aliases.clone(),
             aggregates: query.aggregates.clone(),
             compound_queries: Vec::new(),
             select_related: query.select_related.clone(),
@@ -659,27 +1146,10 @@ impl SqlCompiler {
         // Append each compound query
         for cq in &query.compound_queries {
             let keyword = cq.compound_type.sql_keyword(self.backend);
-            let (other_sql, other_params) = self.compile_select(&cq.other);
-
-            // For PostgreSQL, re-number the placeholders ($1, $2, ...) so they
-            // continue from where the previous query left off.
-            let renumbered_sql =
-                if self.backend == DatabaseBackendType::PostgreSQL && !other_params.is_empty() {
-                    let offset = params.len();
-                    let mut result_sql = other_sql;
-                    // Replace from highest to lowest to avoid $1 -> $11 collisions
-                    for i in (1..=other_params.len()).rev() {
-                        let old = format!("${i}");
-                        let new = format!("${}", i + offset);
-                        result_sql = result_sql.replace(&old, &new);
-                    }
-                    result_sql
-                } else {
-                    other_sql
-                };
-
-            sql.push_str(&format!(" {keyword} {renumbered_sql}"));
-            params.extend(other_params);
+            // For PostgreSQL, the placeholders ($1, $2, ...) continue from
+            // where the previous query left off.
+            let other_sql = self.compile_subquery(&cq.other, &mut params);
+            sql.push_str(&format!(" {keyword} {other_sql}"));
         }
 
         // ORDER BY on the compound result
@@ -741,8 +1211,8 @@ impl SqlCompiler {
                 .collect();
 
             let sql = format!(
-                "SELECT * FROM \"{}\" WHERE \"{}\" IN ({})",
-                pf.related_table,
+                "SELECT * FROM {} WHERE \"{}\" IN ({})",
+                self.backend.quote_table_name(&pf.related_table),
                 pf.related_column,
                 placeholders.join(", ")
             );
@@ -795,8 +1265,8 @@ impl SqlCompiler {
             .collect();
 
         let sql = format!(
-            "INSERT INTO \"{}\" ({}) VALUES ({})",
-            table,
+            "INSERT INTO {} ({}) VALUES ({})",
+            self.backend.quote_table_name(table),
             columns.join(", "),
             placeholders.join(", ")
         );
@@ -804,6 +1274,76 @@ impl SqlCompiler {
         (sql, params)
     }
 
+    /// Compiles an INSERT that updates the existing row when it conflicts on
+    /// the `unique_by` columns.
+    ///
+    /// On conflict, the `update_fields` columns take the inserted values; if
+    /// there are none, the row is left as is but still returned. PostgreSQL
+    /// and SQLite return the resulting row with `RETURNING *`; MySQL has no
+    /// `RETURNING`, so the row has to be selected afterwards.
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::{DatabaseBackendType, SqlCompiler};
+    /// use django_rs_db::value::Value;
+    ///
+    /// let compiler = SqlCompiler::new(DatabaseBackendType::PostgreSQL);
+    /// let (sql, params) = compiler.compile_upsert(
+    ///     "api_key",
+    ///     &[("key", Value::from("abc")), ("hits", Value::from(1))],
+    ///     &["key"],
+    ///     &["hits"],
+    /// );
+    /// assert_eq!(
+    ///     sql,
+    ///     "INSERT INTO \"api_key\" (\"key\", \"hits\") VALUES ($1, $2) \
+    ///      ON CONFLICT (\"key\") DO UPDATE SET \"hits\" = EXCLUDED.\"hits\" RETURNING *"
+    /// );
+    /// assert_eq!(params.len(), 2);
+    /// ```
+    pub fn compile_upsert(
+        &self,
+        table: &str,
+        fields: &[(&str, Value)],
+        unique_by: &[&str],
+        update_fields: &[&str],
+    ) -> (String, Vec<Value>) {
+        let (mut sql, params) = self.compile_insert(table, fields);
+        // Assigning a key column to itself makes the conflicting row count
+        // as updated, so that it is returned.
+        let update_fields = if update_fields.is_empty() {
+            &unique_by[..1.min(unique_by.len())]
+        } else {
+            update_fields
+        };
+
+        match self.backend {
+            DatabaseBackendType::PostgreSQL | DatabaseBackendType::SQLite => {
+                let target: Vec<String> = unique_by.iter().map(|f| format!("\"{f}\"")).collect();
+                let set_parts: Vec<String> = update_fields
+                    .iter()
+                    .map(|f| format!("\"{f}\" = EXCLUDED.\"{f}\""))
+                    .collect();
+                sql.push_str(&format!(
+                    " ON CONFLICT ({}) DO UPDATE SET {} RETURNING *",
+                    target.join(", "),
+                    set_parts.join(", ")
+                ));
+            }
+            DatabaseBackendType::MySQL => {
+                let set_parts: Vec<String> = update_fields
+                    .iter()
+                    .map(|f| format!("\"{f}\" = VALUES(\"{f}\")"))
+                    .collect();
+                sql.push_str(&format!(
+                    " ON DUPLICATE KEY UPDATE {}",
+                    set_parts.join(", ")
+                ));
+            }
+        }
+
+        (sql, params)
+    }
+
     /// Compiles an UPDATE statement.
     pub fn compile_update(
         &self,
@@ -822,7 +1362,11 @@ impl SqlCompiler {
             })
             .collect();
 
-        let mut sql = format!("UPDATE \"{}\" SET {} WHERE ", table, set_parts.join(", "));
+        let mut sql = format!(
+            "UPDATE {} SET {} WHERE ",
+            self.backend.quote_table_name(table),
+            set_parts.join(", ")
+        );
 
         self.compile_where_node(where_clause, &mut sql, &mut params);
 
@@ -832,7 +1376,10 @@ impl SqlCompiler {
     /// Compiles a DELETE statement.
     pub fn compile_delete(&self, table: &str, where_clause: &WhereNode) -> (String, Vec<Value>) {
         let mut params = Vec::new();
-        let mut sql = format!("DELETE FROM \"{table}\" WHERE ");
+        let mut sql = format!(
+            "DELETE FROM {} WHERE ",
+            self.backend.quote_table_name(table)
+        );
         self.compile_where_node(where_clause, &mut sql, &mut params);
         (sql, params)
     }
@@ -854,7 +1401,11 @@ impl SqlCompiler {
     fn compile_where_node(&self, node: &WhereNode, sql: &mut String, params: &mut Vec<Value>) {
         match node {
             WhereNode::Condition { column, lookup } => {
-                self.compile_lookup(column, lookup, sql, params);
+                self.compile_lookup(&format!("\"{column}\""), lookup, sql, params);
+            }
+            WhereNode::ExpressionLookup { expr, lookup } => {
+                let expr_sql = self.compile_expression(expr, params);
+                self.compile_lookup(&expr_sql, lookup, sql, params);
             }
             WhereNode::And(children) => {
                 if children.is_empty() {
@@ -889,10 +1440,84 @@ impl SqlCompiler {
                 self.compile_where_node(inner, sql, params);
                 sql.push(')');
             }
+            WhereNode::InSubquery { column, query } => {
+                let sub_sql = self.compile_subquery(query, params);
+                sql.push_str(&format!("\"{column}\" IN ({sub_sql})"));
+            }
+            WhereNode::OuterRef {
+                table,
+                column,
+                outer_table,
+                outer_column,
+            } => {
+                let outer = outer_table.as_deref().map_or_else(
+                    || format!("\"{outer_column}\""),
+                    |t| format!("{}.\"{outer_column}\"", self.backend.quote_table_name(t)),
+                );
+                sql.push_str(&format!(
+                    "{}.\"{column}\" = {outer}",
+                    self.backend.quote_table_name(table)
+                ));
+            }
+            WhereNode::Expression(expr) => {
+                sql.push_str(&self.compile_expression(expr, params));
+            }
+            WhereNode::Tuple { columns, lookup } => {
+                let ordering = !matches!(lookup, TupleLookup::Exact(_) | TupleLookup::In(_));
+                if self.backend.supports_row_values()
+                    && (!ordering || self.backend.indexes_row_comparisons())
+                {
+                    self.compile_row_lookup(columns, lookup, sql, params);
+                } else {
+                    let expanded = expand_tuple_lookup(columns, lookup);
+                    self.compile_where_node(&expanded, sql, params);
+                }
+            }
         }
     }
 
+    /// Compiles a multi-column lookup as a row value comparison.
+    fn compile_row_lookup(
+        &self,
+        columns: &[String],
+        lookup: &TupleLookup,
+        sql: &mut String,
+        params: &mut Vec<Value>,
+    ) {
+        let mut row = |values: &[Value]| {
+            let placeholders: Vec<String> = values
+                .iter()
+                .map(|value| {
+                    params.push(value.clone());
+                    self.placeholder(params.len())
+                })
+                .collect();
+            format!("({})", placeholders.join(", "))
+        };
+        let quoted: Vec<String> = columns.iter().map(|c| format!("\"{c}\"")).collect();
+        let lhs = format!("({})", quoted.join(", "));
+        let (op, rhs) = match lookup {
+            TupleLookup::In(rows) if rows.is_empty() => {
+                sql.push_str("1=0");
+                return;
+            }
+            TupleLookup::In(rows) => {
+                let rows: Vec<String> = rows.iter().map(|values| row(values)).collect();
+                ("IN", format!("({})", rows.join(", ")))
+            }
+            TupleLookup::Exact(values) => ("=", row(values)),
+            TupleLookup::Gt(values) => (">", row(values)),
+            TupleLookup::Gte(values) => (">=", row(values)),
+            TupleLookup::Lt(values) => ("<", row(values)),
+            TupleLookup::Lte(values) => ("<=", row(values)),
+        };
+        sql.push_str(&format!("{lhs} {op} {rhs}"));
+    }
+
     /// Compiles a single lookup into SQL.
+    ///
+    /// `column` is the already-quoted SQL of the left-hand side, either a
+    /// column or a compiled expression.
     fn compile_lookup(
         &self,
         column: &str,
@@ -903,32 +1528,32 @@ impl SqlCompiler {
         match lookup {
             Lookup::Exact(val) => {
                 if val.is_null() {
-                    sql.push_str(&format!("\"{column}\" IS NULL"));
+                    sql.push_str(&format!("{column} IS NULL"));
                 } else {
                     params.push(val.clone());
                     let ph = self.placeholder(params.len());
-                    sql.push_str(&format!("\"{column}\" = {ph}"));
+                    sql.push_str(&format!("{column} = {ph}"));
                 }
             }
             Lookup::IExact(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("LOWER(\"{column}\") = LOWER({ph})"));
+                sql.push_str(&format!("LOWER({column}) = LOWER({ph})"));
             }
             Lookup::Contains(val) => {
                 params.push(Value::String(format!("%{val}%")));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" LIKE {ph}"));
+                sql.push_str(&format!("{column} LIKE {ph}"));
             }
             Lookup::IContains(val) => {
                 params.push(Value::String(format!("%{val}%")));
                 let ph = self.placeholder(params.len());
                 match self.backend {
                     DatabaseBackendType::PostgreSQL => {
-                        sql.push_str(&format!("\"{column}\" ILIKE {ph}"));
+                        sql.push_str(&format!("{column} ILIKE {ph}"));
                     }
                     _ => {
-                        sql.push_str(&format!("LOWER(\"{column}\") LIKE LOWER({ph})"));
+                        sql.push_str(&format!("LOWER({column}) LIKE LOWER({ph})"));
                     }
                 }
             }
@@ -940,59 +1565,59 @@ impl SqlCompiler {
                         self.placeholder(params.len())
                     })
                     .collect();
-                sql.push_str(&format!("\"{column}\" IN ({})", placeholders.join(", ")));
+                sql.push_str(&format!("{column} IN ({})", placeholders.join(", ")));
             }
             Lookup::Gt(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" > {ph}"));
+                sql.push_str(&format!("{column} > {ph}"));
             }
             Lookup::Gte(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" >= {ph}"));
+                sql.push_str(&format!("{column} >= {ph}"));
             }
             Lookup::Lt(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" < {ph}"));
+                sql.push_str(&format!("{column} < {ph}"));
             }
             Lookup::Lte(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" <= {ph}"));
+                sql.push_str(&format!("{column} <= {ph}"));
             }
             Lookup::StartsWith(val) => {
                 params.push(Value::String(format!("{val}%")));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" LIKE {ph}"));
+                sql.push_str(&format!("{column} LIKE {ph}"));
             }
             Lookup::IStartsWith(val) => {
                 params.push(Value::String(format!("{val}%")));
                 let ph = self.placeholder(params.len());
                 match self.backend {
                     DatabaseBackendType::PostgreSQL => {
-                        sql.push_str(&format!("\"{column}\" ILIKE {ph}"));
+                        sql.push_str(&format!("{column} ILIKE {ph}"));
                     }
                     _ => {
-                        sql.push_str(&format!("LOWER(\"{column}\") LIKE LOWER({ph})"));
+                        sql.push_str(&format!("LOWER({column}) LIKE LOWER({ph})"));
                     }
                 }
             }
             Lookup::EndsWith(val) => {
                 params.push(Value::String(format!("%{val}")));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" LIKE {ph}"));
+                sql.push_str(&format!("{column} LIKE {ph}"));
             }
             Lookup::IEndsWith(val) => {
                 params.push(Value::String(format!("%{val}")));
                 let ph = self.placeholder(params.len());
                 match self.backend {
                     DatabaseBackendType::PostgreSQL => {
-                        sql.push_str(&format!("\"{column}\" ILIKE {ph}"));
+                        sql.push_str(&format!("{column} ILIKE {ph}"));
                     }
                     _ => {
-                        sql.push_str(&format!("LOWER(\"{column}\") LIKE LOWER({ph})"));
+                        sql.push_str(&format!("LOWER({column}) LIKE LOWER({ph})"));
                     }
                 }
             }
@@ -1001,13 +1626,13 @@ impl SqlCompiler {
                 let ph_low = self.placeholder(params.len());
                 params.push(high.clone());
                 let ph_high = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" BETWEEN {ph_low} AND {ph_high}"));
+                sql.push_str(&format!("{column} BETWEEN {ph_low} AND {ph_high}"));
             }
             Lookup::IsNull(is_null) => {
                 if *is_null {
-                    sql.push_str(&format!("\"{column}\" IS NULL"));
+                    sql.push_str(&format!("{column} IS NULL"));
                 } else {
-                    sql.push_str(&format!("\"{column}\" IS NOT NULL"));
+                    sql.push_str(&format!("{column} IS NOT NULL"));
                 }
             }
             Lookup::Regex(pattern) => {
@@ -1015,13 +1640,13 @@ impl SqlCompiler {
                 let ph = self.placeholder(params.len());
                 match self.backend {
                     DatabaseBackendType::PostgreSQL => {
-                        sql.push_str(&format!("\"{column}\" ~ {ph}"));
+                        sql.push_str(&format!("{column} ~ {ph}"));
                     }
                     DatabaseBackendType::MySQL => {
-                        sql.push_str(&format!("\"{column}\" REGEXP {ph}"));
+                        sql.push_str(&format!("{column} REGEXP {ph}"));
                     }
                     DatabaseBackendType::SQLite => {
-                        sql.push_str(&format!("\"{column}\" REGEXP {ph}"));
+                        sql.push_str(&format!("{column} REGEXP {ph}"));
                     }
                 }
             }
@@ -1030,13 +1655,13 @@ impl SqlCompiler {
                 let ph = self.placeholder(params.len());
                 match self.backend {
                     DatabaseBackendType::PostgreSQL => {
-                        sql.push_str(&format!("\"{column}\" ~* {ph}"));
+                        sql.push_str(&format!("{column} ~* {ph}"));
                     }
                     DatabaseBackendType::MySQL => {
-                        sql.push_str(&format!("\"{column}\" REGEXP {ph}"));
+                        sql.push_str(&format!("{column} REGEXP {ph}"));
                     }
                     DatabaseBackendType::SQLite => {
-                        sql.push_str(&format!("\"{column}\" REGEXP {ph}"));
+                        sql.push_str(&format!("{column} REGEXP {ph}"));
                     }
                 }
             }
@@ -1045,77 +1670,75 @@ impl SqlCompiler {
             Lookup::ArrayContains(vals) => {
                 params.push(Value::List(vals.clone()));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" @> {ph}"));
+                sql.push_str(&format!("{column} @> {ph}"));
             }
             Lookup::ArrayContainedBy(vals) => {
                 params.push(Value::List(vals.clone()));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" <@ {ph}"));
+                sql.push_str(&format!("{column} <@ {ph}"));
             }
             Lookup::ArrayOverlap(vals) => {
                 params.push(Value::List(vals.clone()));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" && {ph}"));
+                sql.push_str(&format!("{column} && {ph}"));
             }
             Lookup::ArrayLen(n) => {
-                sql.push_str(&format!("array_length(\"{column}\", 1) = {n}"));
+                sql.push_str(&format!("array_length({column}, 1) = {n}"));
             }
 
             // ── PostgreSQL hstore lookups ────────────────────────────────
             Lookup::HasKey(key) => {
                 params.push(Value::String(key.clone()));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" ? {ph}"));
+                sql.push_str(&format!("{column} ? {ph}"));
             }
             Lookup::HasKeys(keys) => {
                 params.push(Value::List(
                     keys.iter().map(|k| Value::String(k.clone())).collect(),
                 ));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" ?& {ph}"));
+                sql.push_str(&format!("{column} ?& {ph}"));
             }
             Lookup::HasAnyKeys(keys) => {
                 params.push(Value::List(
                     keys.iter().map(|k| Value::String(k.clone())).collect(),
                 ));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" ?| {ph}"));
+                sql.push_str(&format!("{column} ?| {ph}"));
             }
 
             // ── PostgreSQL range lookups ─────────────────────────────────
             Lookup::RangeContains(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" @> {ph}"));
+                sql.push_str(&format!("{column} @> {ph}"));
             }
             Lookup::RangeContainedBy(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" <@ {ph}"));
+                sql.push_str(&format!("{column} <@ {ph}"));
             }
             Lookup::RangeOverlap(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" && {ph}"));
+                sql.push_str(&format!("{column} && {ph}"));
             }
             Lookup::FullyLt(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" << {ph}"));
+                sql.push_str(&format!("{column} << {ph}"));
             }
             Lookup::FullyGt(val) => {
                 params.push(val.clone());
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!("\"{column}\" >> {ph}"));
+                sql.push_str(&format!("{column} >> {ph}"));
             }
 
             // ── PostgreSQL full-text search ──────────────────────────────
             Lookup::Search(query) => {
                 params.push(Value::String(query.clone()));
                 let ph = self.placeholder(params.len());
-                sql.push_str(&format!(
-                    "to_tsvector(\"{column}\") @@ plainto_tsquery({ph})"
-                ));
+                sql.push_str(&format!("to_tsvector({column}) @@ plainto_tsquery({ph})"));
             }
         }
     }
@@ -1146,6 +1769,9 @@ impl SqlCompiler {
                 let distinct_str = if *distinct { "DISTINCT " } else { "" };
                 format!("{}({distinct_str}{field_sql})", func.sql_name())
             }
+            Expression::OrderedAggregate(aggregate) => {
+                self.compile_ordered_aggregate(aggregate, params)
+            }
             Expression::Case { whens, default } => {
                 let mut sql = "CASE".to_string();
                 for when in whens {
@@ -1165,8 +1791,7 @@ impl SqlCompiler {
                 sql
             }
             Expression::Subquery(query) => {
-                let (sub_sql, sub_params) = self.compile_select(query);
-                params.extend(sub_params);
+                let sub_sql = self.compile_subquery(query, params);
                 format!("({sub_sql})")
             }
             Expression::OuterRef(column) => {
@@ -1183,8 +1808,7 @@ impl SqlCompiler {
                     "__exists__".to_string(),
                 )];
                 exists_query.order_by.clear();
-                let (sub_sql, sub_params) = self.compile_select(&exists_query);
-                params.extend(sub_params);
+                let sub_sql = self.compile_subquery(&exists_query, params);
                 if *negated {
                     format!("NOT EXISTS ({sub_sql})")
                 } else {
@@ -1235,6 +1859,100 @@ impl SqlCompiler {
         }
     }
 
+    /// Compiles a STRING_AGG, ARRAY_AGG or JSON_AGG aggregate for the
+    /// backend.
+    ///
+    /// PostgreSQL and SQLite apply the filter with `FILTER (WHERE ...)`;
+    /// MySQL, which has no FILTER clause, aggregates
+    /// `CASE WHEN condition THEN value END` so non-matching rows become NULLs
+    /// that `GROUP_CONCAT` skips.
+    fn compile_ordered_aggregate(
+        &self,
+        aggregate: &OrderedAggregate,
+        params: &mut Vec<Value>,
+    ) -> String {
+        let distinct = if aggregate.distinct { "DISTINCT " } else { "" };
+        let order_by = if aggregate.ordering.is_empty() {
+            String::new()
+        } else {
+            let orders: Vec<String> = aggregate
+                .ordering
+                .iter()
+                .map(|(col, desc)| {
+                    let dir = if *desc { "DESC" } else { "ASC" };
+                    format!("\"{col}\" {dir}")
+                })
+                .collect();
+            format!(" ORDER BY {}", orders.join(", "))
+        };
+
+        if self.backend == DatabaseBackendType::MySQL {
+            let condition = aggregate.filter.as_ref().map(|filter| {
+                let mut sql = String::new();
+                self.compile_where_node(&WhereNode::from_q(filter), &mut sql, params);
+                sql
+            });
+            let mut value = self.compile_expression(&aggregate.field, params);
+            let separator = match &aggregate.func {
+                OrderedAggregateFunc::StringAgg { delimiter } => delimiter.clone(),
+                OrderedAggregateFunc::ArrayAgg | OrderedAggregateFunc::JsonAgg => {
+                    value = format!("JSON_EXTRACT(JSON_ARRAY({value}), '$[0]')");
+                    ",".to_string()
+                }
+            };
+            if let Some(condition) = condition {
+                value = format!("CASE WHEN {condition} THEN {value} END");
+            }
+            // SEPARATOR only takes a string literal, not a placeholder.
+            let separator = separator.replace('\\', "\\\\").replace('\'', "''");
+            let sql = format!("GROUP_CONCAT({distinct}{value}{order_by} SEPARATOR '{separator}')");
+            return match aggregate.func {
+                OrderedAggregateFunc::StringAgg { .. } => sql,
+                OrderedAggregateFunc::ArrayAgg | OrderedAggregateFunc::JsonAgg => {
+                    format!("CAST(CONCAT('[', {sql}, ']') AS JSON)")
+                }
+            };
+        }
+
+        let value = self.compile_expression(&aggregate.field, params);
+        // SQLite rejects a separator on a DISTINCT GROUP_CONCAT, but the
+        // default one is a comma. Other separators are passed on, so SQLite
+        // refuses the query instead of it returning joined values that can't
+        // be split again.
+        let mut sql = match (&aggregate.func, self.backend) {
+            (OrderedAggregateFunc::StringAgg { delimiter }, DatabaseBackendType::SQLite)
+                if aggregate.distinct && delimiter == "," =>
+            {
+                format!("GROUP_CONCAT({distinct}{value}{order_by})")
+            }
+            (OrderedAggregateFunc::StringAgg { delimiter }, backend) => {
+                params.push(Value::String(delimiter.clone()));
+                let name = if backend == DatabaseBackendType::SQLite {
+                    "GROUP_CONCAT"
+                } else {
+                    "STRING_AGG"
+                };
+                let placeholder = self.placeholder(params.len());
+                format!("{name}({distinct}{value}, {placeholder}{order_by})")
+            }
+            (OrderedAggregateFunc::ArrayAgg, DatabaseBackendType::PostgreSQL) => {
+                format!("ARRAY_AGG({distinct}{value}{order_by})")
+            }
+            (OrderedAggregateFunc::JsonAgg, DatabaseBackendType::PostgreSQL) => {
+                format!("JSON_AGG({distinct}{value}{order_by})")
+            }
+            (OrderedAggregateFunc::ArrayAgg | OrderedAggregateFunc::JsonAgg, _) => {
+                format!("JSON_GROUP_ARRAY({distinct}{value}{order_by})")
+            }
+        };
+        if let Some(filter) = &aggregate.filter {
+            sql.push_str(" FILTER (WHERE ");
+            self.compile_where_node(&WhereNode::from_q(filter), &mut sql, params);
+            sql.push(')');
+        }
+        sql
+    }
+
     /// Compiles a window expression into SQL.
     fn compile_window_expression(
         &self,
@@ -1454,6 +2172,80 @@ mod tests {
 
     // ── SELECT compilation tests ─────────────────────────────────────
 
+    fn tuple_where(compiler: &SqlCompiler, q: &Q) -> (String, Vec<Value>) {
+        let mut query = Query::new("membership");
+        query.where_clause = Some(WhereNode::from_q(q));
+        let (sql, params) = compiler.compile_select(&query);
+        let clause = sql.split_once(" WHERE ").unwrap().1.to_string();
+        (clause, params)
+    }
+
+    #[test]
+    fn test_tuple_in_row_values() {
+        let q = Q::tuple_in(
+            ["org_id", "user_id"],
+            vec![
+                vec![Value::from(1), Value::from(2)],
+                vec![Value::from(1), Value::from(3)],
+            ],
+        );
+        let (sql, params) = tuple_where(&sqlite(), &q);
+        assert_eq!(sql, r#"("org_id", "user_id") IN ((?, ?), (?, ?))"#);
+        assert_eq!(
+            params,
+            vec![
+                Value::from(1),
+                Value::from(2),
+                Value::from(1),
+                Value::from(3)
+            ]
+        );
+        let (sql, _) = tuple_where(&pg(), &Q::tuple_in(["a", "b"], vec![]));
+        assert_eq!(sql, "1=0");
+    }
+
+    #[test]
+    fn test_tuple_in_row_values_mysql() {
+        let q = Q::tuple_in(
+            ["org_id", "user_id"],
+            vec![
+                vec![Value::from(1), Value::from(2)],
+                vec![Value::from(1), Value::from(3)],
+            ],
+        );
+        let (sql, params) = tuple_where(&mysql(), &q);
+        assert_eq!(sql, r#"("org_id", "user_id") IN ((?, ?), (?, ?))"#);
+        assert_eq!(params.len(), 4);
+    }
+
+    #[test]
+    fn test_tuple_comparison() {
+        let q = Q::tuple(
+            ["created", "id"],
+            TupleLookup::Gt(vec![Value::from("2024-01-01"), Value::from(7)]),
+        );
+        let (sql, params) = tuple_where(&pg(), &q);
+        assert_eq!(sql, r#"("created", "id") > ($1, $2)"#);
+        assert_eq!(params.len(), 2);
+
+        let q = Q::tuple(
+            ["a", "b", "c"],
+            TupleLookup::Lte(vec![Value::from(1), Value::from(2), Value::from(3)]),
+        );
+        let (sql, params) = tuple_where(&mysql(), &q);
+        assert_eq!(
+            sql,
+            r#"(("a" < ?) OR ("a" = ? AND "b" < ?) OR ("a" = ? AND "b" = ? AND "c" <= ?))"#
+        );
+        assert_eq!(params, [1, 1, 2, 1, 2, 3].map(Value::from).to_vec(),);
+    }
+
+    #[test]
+    #[should_panic(expected = "got a row of 1 value(s)")]
+    fn test_tuple_lookup_checks_arity() {
+        let _ = Q::tuple_in(["a", "b"], vec![vec![Value::from(1)]]);
+    }
+
     #[test]
     fn test_simple_select_pg() {
         let query = Query::new("users");
@@ -1808,6 +2600,44 @@ mod tests {
         assert_eq!(params.len(), 2);
     }
 
+    #[test]
+    fn test_upsert_sqlite() {
+        let fields: Vec<(&str, Value)> = vec![
+            ("email", Value::from("bob@test.com")),
+            ("name", Value::from("Bob")),
+        ];
+        let (sql, params) = sqlite().compile_upsert("users", &fields, &["email"], &["name"]);
+        assert_eq!(
+            sql,
+            "INSERT INTO \"users\" (\"email\", \"name\") VALUES (?, ?) \
+             ON CONFLICT (\"email\") DO UPDATE SET \"name\" = EXCLUDED.\"name\" RETURNING *"
+        );
+        assert_eq!(params.len(), 2);
+    }
+
+    #[test]
+    fn test_upsert_mysql() {
+        let fields: Vec<(&str, Value)> = vec![
+            ("email", Value::from("bob@test.com")),
+            ("name", Value::from("Bob")),
+        ];
+        let (sql, _) = mysql().compile_upsert("users", &fields, &["email"], &["name"]);
+        assert_eq!(
+            sql,
+            "INSERT INTO \"users\" (\"email\", \"name\") VALUES (?, ?) \
+             ON DUPLICATE KEY UPDATE \"name\" = VALUES(\"name\")"
+        );
+    }
+
+    #[test]
+    fn test_upsert_without_update_fields_still_returns_row() {
+        let fields: Vec<(&str, Value)> = vec![("email", Value::from("bob@test.com"))];
+        let (sql, _) = pg().compile_upsert("users", &fields, &["email"], &[]);
+        assert!(sql.ends_with(
+            "ON CONFLICT (\"email\") DO UPDATE SET \"email\" = EXCLUDED.\"email\" RETURNING *"
+        ));
+    }
+
     // ── UPDATE compilation tests ─────────────────────────────────────
 
     #[test]
@@ -1861,6 +2691,27 @@ mod tests {
         assert_eq!(sql, "DELETE FROM \"users\" WHERE \"id\" = ?");
     }
 
+    #[test]
+    fn test_schema_qualified_table_pg() {
+        let query = Query::new("billing.invoice");
+        let (sql, _) = pg().compile_select(&query);
+        assert_eq!(sql, "SELECT * FROM \"billing\".\"invoice\"");
+
+        let where_clause = WhereNode::Condition {
+            column: "id".to_string(),
+            lookup: Lookup::Exact(Value::from(1)),
+        };
+        let (sql, _) = pg().compile_delete("billing.invoice", &where_clause);
+        assert_eq!(sql, "DELETE FROM \"billing\".\"invoice\" WHERE \"id\" = $1");
+    }
+
+    #[test]
+    fn test_schema_qualified_table_sqlite() {
+        let query = Query::new("billing.invoice");
+        let (sql, _) = sqlite().compile_select(&query);
+        assert_eq!(sql, "SELECT * FROM \"billing.invoice\"");
+    }
+
     // ── Expression compilation tests ─────────────────────────────────
 
     #[test]
@@ -2687,4 +3538,44 @@ mod tests {
         assert!(sql.contains("LEFT JOIN \"myapp_profile\" AS \"profile\""));
         assert!(sql.contains("\"auth_user\".\"profile_id\" = \"profile\".\"id\""));
     }
+
+    #[test]
+    fn test_subquery_expression_renumbers_pg_placeholders() {
+        let mut inner = Query::new("comments");
+        inner.select = vec![SelectColumn::Column("post_id".to_string())];
+        inner.where_clause = Some(WhereNode::And(
+            (0..10)
+                .map(|i| WhereNode::Condition {
+                    column: format!("c{i}"),
+                    lookup: Lookup::Exact(Value::from(i)),
+                })
+                .collect(),
+        ));
+        let mut query = Query::new("posts");
+        query.where_clause = Some(WhereNode::And(vec![
+            WhereNode::Condition {
+                column: "a".to_string(),
+                lookup: Lookup::Exact(Value::from("x")),
+            },
+            WhereNode::Condition {
+                column: "b".to_string(),
+                lookup: Lookup::Exact(Value::from("y")),
+            },
+            WhereNode::InSubquery {
+                column: "id".to_string(),
+                query: Box::new(inner),
+            },
+        ]));
+        let (sql, params) = pg().compile_select(&query);
+        assert_eq!(params.len(), 12);
+        assert!(sql.contains("\"a\" = $1 AND \"b\" = $2 AND \"id\" IN (SELECT"));
+        assert!(sql.contains("\"c0\" = $3 AND"));
+        assert!(sql.contains("\"c9\" = $12)"));
+    }
+
+    #[test]
+    fn test_renumber_placeholders() {
+        assert_eq!(pg().renumber_placeholders("$1, $10, $", 3), "$4, $13, $");
+        assert_eq!(sqlite().renumber_placeholders("?, ?", 3), "?, ?");
+    }
 }
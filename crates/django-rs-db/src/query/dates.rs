@@ -0,0 +1,302 @@
+//! Date truncation for [`QuerySet::dates_exec`] and
+//! [`QuerySet::datetimes_exec`].
+//!
+//! These list the distinct periods (years, months, ...) a date field takes in
+//! a queryset, which is what date-based archive views link to. Truncation is
+//! done by the database: `DATE_TRUNC` on PostgreSQL, `DATETIME` modifiers and
+//! `STRFTIME` on SQLite, and `DATE_FORMAT` on MySQL.
+//!
+//! [`QuerySet::dates_exec`]: super::queryset::QuerySet::dates_exec
+//! [`QuerySet::datetimes_exec`]: super::queryset::QuerySet::datetimes_exec
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use django_rs_core::{DjangoError, DjangoResult};
+
+use super::compiler::DatabaseBackendType;
+use crate::value::Value;
+
+/// The period a date field is truncated to by
+/// [`QuerySet::dates_exec`](super::queryset::QuerySet::dates_exec).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateKind {
+    /// January 1st of the year.
+    Year,
+    /// The first day of the month.
+    Month,
+    /// The Monday of the week.
+    Week,
+    /// The day itself.
+    Day,
+}
+
+/// The period a datetime field is truncated to by
+/// [`QuerySet::datetimes_exec`](super::queryset::QuerySet::datetimes_exec).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateTimeKind {
+    /// Midnight on January 1st of the year.
+    Year,
+    /// Midnight on the first day of the month.
+    Month,
+    /// Midnight on the Monday of the week.
+    Week,
+    /// Midnight of the day.
+    Day,
+    /// The start of the hour.
+    Hour,
+    /// The start of the minute.
+    Minute,
+    /// The second, without fractions.
+    Second,
+}
+
+impl From<DateKind> for DateTimeKind {
+    fn from(kind: DateKind) -> Self {
+        match kind {
+            DateKind::Year => Self::Year,
+            DateKind::Month => Self::Month,
+            DateKind::Week => Self::Week,
+            DateKind::Day => Self::Day,
+        }
+    }
+}
+
+impl DateTimeKind {
+    /// Returns the `DATE_TRUNC` precision name.
+    const fn postgres_precision(self) -> &'static str {
+        match self {
+            Self::Year => "year",
+            Self::Month => "month",
+            Self::Week => "week",
+            Self::Day => "day",
+            Self::Hour => "hour",
+            Self::Minute => "minute",
+            Self::Second => "second",
+        }
+    }
+}
+
+/// Returns SQL truncating `column` to `kind`.
+///
+/// The result is a date if `as_date`, otherwise a datetime. With an `offset`,
+/// the (UTC) column is first shifted to that time zone, so periods follow
+/// its wall clock.
+pub(crate) fn trunc_sql(
+    column: &str,
+    kind: DateTimeKind,
+    as_date: bool,
+    offset: Option<FixedOffset>,
+    backend: DatabaseBackendType,
+) -> String {
+    let column = format!("\"{column}\"");
+    let seconds = offset.map_or(0, |o| o.local_minus_utc());
+    match backend {
+        DatabaseBackendType::PostgreSQL => {
+            let local = if seconds == 0 {
+                column
+            } else {
+                format!("({column} + INTERVAL '{seconds} seconds')")
+            };
+            let trunc = format!("DATE_TRUNC('{}', {local})", kind.postgres_precision());
+            if as_date {
+                format!("CAST({trunc} AS DATE)")
+            } else {
+                trunc
+            }
+        }
+        DatabaseBackendType::SQLite => {
+            // Only the first 19 characters are a format SQLite's date
+            // functions understand; stored UTC datetimes carry a suffix.
+            let mut local = format!("SUBSTR({column}, 1, 19)");
+            if seconds != 0 {
+                local = format!("DATETIME({local}, '{seconds:+} seconds')");
+            }
+            let function = if as_date { "DATE" } else { "DATETIME" };
+            match kind {
+                DateTimeKind::Year => format!("{function}({local}, 'start of year')"),
+                DateTimeKind::Month => format!("{function}({local}, 'start of month')"),
+                DateTimeKind::Week => format!(
+                    "{function}({local}, 'start of day', '-' || \
+                     ((CAST(STRFTIME('%w', {local}) AS INTEGER) + 6) % 7) || ' days')"
+                ),
+                DateTimeKind::Day => format!("{function}({local}, 'start of day')"),
+                DateTimeKind::Hour => format!("STRFTIME('%Y-%m-%d %H:00:00', {local})"),
+                DateTimeKind::Minute => format!("STRFTIME('%Y-%m-%d %H:%M:00', {local})"),
+                DateTimeKind::Second => format!("STRFTIME('%Y-%m-%d %H:%M:%S', {local})"),
+            }
+        }
+        DatabaseBackendType::MySQL => {
+            let local = if seconds == 0 {
+                column
+            } else {
+                format!("DATE_ADD({column}, INTERVAL {seconds} SECOND)")
+            };
+            let format = match kind {
+                DateTimeKind::Year => "%Y-01-01 00:00:00",
+                DateTimeKind::Month => "%Y-%m-01 00:00:00",
+                DateTimeKind::Week | DateTimeKind::Day => "%Y-%m-%d 00:00:00",
+                DateTimeKind::Hour => "%Y-%m-%d %H:00:00",
+                DateTimeKind::Minute => "%Y-%m-%d %H:%i:00",
+                DateTimeKind::Second => "%Y-%m-%d %H:%i:%s",
+            };
+            // Weeks are found by stepping back to Monday first
+            let local = if kind == DateTimeKind::Week {
+                format!("DATE_SUB({local}, INTERVAL WEEKDAY({local}) DAY)")
+            } else {
+                local
+            };
+            let trunc = format!("DATE_FORMAT({local}, '{format}')");
+            let data_type = if as_date { "DATE" } else { "DATETIME" };
+            format!("CAST({trunc} AS {data_type})")
+        }
+    }
+}
+
+/// Converts a truncated date returned by the database.
+///
+/// SQLite and some MySQL drivers return text rather than typed values.
+pub(crate) fn date_from_value(value: &Value) -> DjangoResult<NaiveDate> {
+    match value {
+        Value::Date(date) => Ok(*date),
+        Value::DateTime(datetime) => Ok(datetime.date()),
+        Value::DateTimeTz(datetime) => Ok(datetime.date_naive()),
+        Value::String(s) => s
+            .get(..10)
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            .ok_or_else(|| DjangoError::DatabaseError(format!("Expected Date, got {s:?}"))),
+        _ => Err(DjangoError::DatabaseError(format!(
+            "Expected Date, got {value:?}"
+        ))),
+    }
+}
+
+/// Converts a truncated datetime returned by the database to a datetime in
+/// `offset`.
+///
+/// The database has already shifted the value to `offset`'s wall clock.
+pub(crate) fn datetime_from_value(
+    value: &Value,
+    offset: FixedOffset,
+) -> DjangoResult<DateTime<FixedOffset>> {
+    let naive = match value {
+        Value::DateTime(datetime) => *datetime,
+        Value::DateTimeTz(datetime) => datetime.naive_utc(),
+        Value::Date(date) => date.and_time(chrono::NaiveTime::MIN),
+        Value::String(s) => NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+            .map_err(|_| DjangoError::DatabaseError(format!("Expected DateTime, got {s:?}")))?,
+        _ => {
+            return Err(DjangoError::DatabaseError(format!(
+                "Expected DateTime, got {value:?}"
+            )))
+        }
+    };
+    naive
+        .and_local_timezone(offset)
+        .single()
+        .ok_or_else(|| DjangoError::DatabaseError(format!("Invalid datetime {naive}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(hours: i32) -> Option<FixedOffset> {
+        FixedOffset::east_opt(hours * 3600)
+    }
+
+    #[test]
+    fn test_trunc_sql_postgres() {
+        let sql = trunc_sql(
+            "pub_date",
+            DateTimeKind::Month,
+            true,
+            None,
+            DatabaseBackendType::PostgreSQL,
+        );
+        assert_eq!(sql, "CAST(DATE_TRUNC('month', \"pub_date\") AS DATE)");
+
+        let sql = trunc_sql(
+            "pub_date",
+            DateTimeKind::Hour,
+            false,
+            offset(2),
+            DatabaseBackendType::PostgreSQL,
+        );
+        assert_eq!(
+            sql,
+            "DATE_TRUNC('hour', (\"pub_date\" + INTERVAL '7200 seconds'))"
+        );
+    }
+
+    #[test]
+    fn test_trunc_sql_sqlite() {
+        let sql = trunc_sql(
+            "pub_date",
+            DateTimeKind::Year,
+            true,
+            None,
+            DatabaseBackendType::SQLite,
+        );
+        assert_eq!(sql, "DATE(SUBSTR(\"pub_date\", 1, 19), 'start of year')");
+
+        let sql = trunc_sql(
+            "pub_date",
+            DateTimeKind::Minute,
+            false,
+            offset(-5),
+            DatabaseBackendType::SQLite,
+        );
+        assert_eq!(
+            sql,
+            "STRFTIME('%Y-%m-%d %H:%M:00', DATETIME(SUBSTR(\"pub_date\", 1, 19), '-18000 seconds'))"
+        );
+    }
+
+    #[test]
+    fn test_trunc_sql_mysql() {
+        let sql = trunc_sql(
+            "pub_date",
+            DateTimeKind::Year,
+            false,
+            None,
+            DatabaseBackendType::MySQL,
+        );
+        assert_eq!(
+            sql,
+            "CAST(DATE_FORMAT(\"pub_date\", '%Y-01-01 00:00:00') AS DATETIME)"
+        );
+
+        let sql = trunc_sql(
+            "pub_date",
+            DateTimeKind::Week,
+            true,
+            None,
+            DatabaseBackendType::MySQL,
+        );
+        assert_eq!(
+            sql,
+            "CAST(DATE_FORMAT(DATE_SUB(\"pub_date\", INTERVAL WEEKDAY(\"pub_date\") DAY), \
+             '%Y-%m-%d 00:00:00') AS DATE)"
+        );
+    }
+
+    #[test]
+    fn test_date_from_value() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        assert_eq!(date_from_value(&Value::Date(date)).unwrap(), date);
+        assert_eq!(
+            date_from_value(&Value::String("2026-02-01".to_string())).unwrap(),
+            date
+        );
+        assert!(date_from_value(&Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_datetime_from_value() {
+        let tz = offset(2).unwrap();
+        let datetime =
+            datetime_from_value(&Value::String("2026-02-01 10:00:00".to_string()), tz).unwrap();
+        assert_eq!(datetime.to_rfc3339(), "2026-02-01T10:00:00+02:00");
+        assert!(datetime_from_value(&Value::String("garbage".to_string()), tz).is_err());
+    }
+}
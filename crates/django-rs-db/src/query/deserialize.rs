@@ -0,0 +1,396 @@
+//! Deserializing result rows into arbitrary serde types.
+//!
+//! Report queries often select annotations and aggregates that don't map to
+//! a [`Model`](crate::model::Model). [`from_row`] turns such a [`Row`] into
+//! any `T: DeserializeOwned` instead:
+//!
+//! - structs and maps are filled **by column name**; extra columns are
+//!   ignored, and a missing column is reported by name;
+//! - tuples and sequences are filled **by position**.
+//!
+//! Each column value is converted to JSON first, so anything that
+//! deserializes from JSON works: dates and UUIDs from their string forms,
+//! enums from strings, nested structs from JSON columns. Integer columns
+//! also deserialize into `bool`, since SQLite stores booleans as `0`/`1`.
+//! Durations become whole microseconds.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::query::compiler::Row;
+//! use django_rs_db::query::deserialize::from_row;
+//! use django_rs_db::value::Value;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct AuthorStats {
+//!     author: String,
+//!     post_count: i64,
+//! }
+//!
+//! let row = Row::new(
+//!     vec!["author".into(), "post_count".into()],
+//!     vec![Value::from("alice"), Value::from(3_i64)],
+//! );
+//! let stats: AuthorStats = from_row(&row).unwrap();
+//! assert_eq!(stats.post_count, 3);
+//! ```
+
+use std::fmt;
+
+use django_rs_core::{DjangoError, DjangoResult};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::query::compiler::Row;
+use crate::value::Value;
+
+/// Deserializes a result row into `T`.
+///
+/// # Errors
+///
+/// Returns [`DjangoError::SerializationError`] naming the offending column
+/// if a column `T` needs is missing or its value has the wrong type.
+pub fn from_row<T: DeserializeOwned>(row: &Row) -> DjangoResult<T> {
+    T::deserialize(RowDeserializer { row }).map_err(|e| {
+        DjangoError::SerializationError(format!(
+            "Cannot deserialize row into `{}`: {e}",
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+/// Converts a database value to JSON for deserialization.
+pub(crate) fn value_to_json(value: &Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+
+    match value {
+        Value::Null => Json::Null,
+        Value::Bool(b) => Json::Bool(*b),
+        Value::Int(i) => Json::from(*i),
+        Value::Float(f) => Json::from(*f),
+        Value::String(s) => Json::String(s.clone()),
+        Value::Bytes(b) => Json::from(b.clone()),
+        Value::Date(d) => Json::String(d.to_string()),
+        Value::DateTime(dt) => Json::String(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string()),
+        Value::DateTimeTz(dt) => Json::String(dt.to_rfc3339()),
+        Value::Time(t) => Json::String(t.to_string()),
+        Value::Duration(d) => d.num_microseconds().map_or(Json::Null, Json::from),
+        Value::Uuid(u) => Json::String(u.to_string()),
+        Value::Json(j) => j.clone(),
+        Value::List(items) => Json::Array(items.iter().map(value_to_json).collect()),
+        Value::HStore(map) => Json::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), Json::String(v.clone())))
+                .collect(),
+        ),
+        Value::Range {
+            lower,
+            lower_inclusive,
+            upper,
+            upper_inclusive,
+        } => serde_json::json!({
+            "lower": lower.as_deref().map_or(Json::Null, value_to_json),
+            "lower_inclusive": lower_inclusive,
+            "upper": upper.as_deref().map_or(Json::Null, value_to_json),
+            "upper_inclusive": upper_inclusive,
+        }),
+    }
+}
+
+/// Error raised while deserializing a row.
+#[derive(Debug)]
+struct RowError(String);
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RowError {}
+
+impl de::Error for RowError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self(format!("missing column `{field}`"))
+    }
+}
+
+/// Deserializes a whole row, as a map by column name or a sequence.
+struct RowDeserializer<'a> {
+    row: &'a Row,
+}
+
+impl<'de> de::Deserializer<'de> for RowDeserializer<'_> {
+    type Error = RowError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        visitor.visit_map(RowAccess::new(self.row))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        visitor.visit_seq(RowAccess::new(self.row))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+/// Walks the columns of a row, for both map and sequence access.
+struct RowAccess<'a> {
+    row: &'a Row,
+    index: usize,
+}
+
+impl<'a> RowAccess<'a> {
+    const fn new(row: &'a Row) -> Self {
+        Self { row, index: 0 }
+    }
+
+    /// Returns the deserializer of the column at `index` and advances.
+    fn next_column(&mut self) -> ColumnDeserializer<'a> {
+        let column = &self.row.columns()[self.index];
+        let value = &self.row.values()[self.index];
+        self.index += 1;
+        ColumnDeserializer {
+            column,
+            value: value_to_json(value),
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for RowAccess<'_> {
+    type Error = RowError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, RowError> {
+        let Some(column) = self.row.columns().get(self.index) else {
+            return Ok(None);
+        };
+        seed.deserialize(column.as_str().into_deserializer())
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, RowError> {
+        seed.deserialize(self.next_column())
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.len() - self.index)
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for RowAccess<'_> {
+    type Error = RowError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, RowError> {
+        if self.index >= self.row.len() {
+            return Ok(None);
+        }
+        seed.deserialize(self.next_column()).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.len() - self.index)
+    }
+}
+
+/// Deserializes one column value, prefixing errors with the column name.
+struct ColumnDeserializer<'a> {
+    column: &'a str,
+    value: serde_json::Value,
+}
+
+impl ColumnDeserializer<'_> {
+    fn wrap<T>(&self, result: Result<T, serde_json::Error>) -> Result<T, RowError> {
+        result.map_err(|e| RowError(format!("column `{}`: {e}", self.column)))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ColumnDeserializer<'_> {
+    type Error = RowError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        let result = self.value.clone().deserialize_any(visitor);
+        self.wrap(result)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        if let Some(i) = self.value.as_i64() {
+            return visitor.visit_bool(i != 0);
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        if self.value.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        let result = self.value.clone().deserialize_enum(name, variants, visitor);
+        self.wrap(result)
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    fn row(columns: &[&str], values: Vec<Value>) -> Row {
+        Row::new(columns.iter().map(|c| (*c).to_string()).collect(), values)
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Draft,
+        Published,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Report {
+        author: String,
+        total: i32,
+        average: f64,
+        published: bool,
+        status: Status,
+        last_post: Option<chrono::NaiveDate>,
+        #[serde(default)]
+        note: Option<String>,
+    }
+
+    #[test]
+    fn test_from_row_struct_by_column_name() {
+        let row = row(
+            &[
+                "status",
+                "average",
+                "author",
+                "total",
+                "published",
+                "last_post",
+                "extra",
+            ],
+            vec![
+                Value::from("published"),
+                Value::Int(4),
+                Value::from("alice"),
+                Value::Int(12),
+                Value::Int(1),
+                Value::Date(chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()),
+                Value::Null,
+            ],
+        );
+        let report: Report = from_row(&row).unwrap();
+        assert_eq!(
+            report,
+            Report {
+                author: "alice".into(),
+                total: 12,
+                average: 4.0,
+                published: true,
+                status: Status::Published,
+                last_post: chrono::NaiveDate::from_ymd_opt(2024, 5, 1),
+                note: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_row_tuple_by_position() {
+        let row = row(&["name", "n"], vec![Value::from("bob"), Value::Null]);
+        let (name, n): (String, Option<i64>) = from_row(&row).unwrap();
+        assert_eq!(name, "bob");
+        assert_eq!(n, None);
+    }
+
+    #[test]
+    fn test_from_row_errors_name_the_column() {
+        #[derive(Debug, Deserialize)]
+        struct Totals {
+            #[allow(dead_code)]
+            total: i64,
+        }
+
+        let err = from_row::<Totals>(&row(&["count"], vec![Value::Int(1)])).unwrap_err();
+        assert!(err.to_string().contains("missing column `total`"), "{err}");
+
+        let err = from_row::<Totals>(&row(&["total"], vec![Value::from("many")])).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("column `total`"), "{msg}");
+        assert!(msg.contains("Totals"), "{msg}");
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        assert_eq!(value_to_json(&Value::Null), serde_json::Value::Null);
+        assert_eq!(
+            value_to_json(&Value::List(vec![Value::Int(1), Value::from("a")])),
+            serde_json::json!([1, "a"])
+        );
+        assert_eq!(
+            value_to_json(&Value::Duration(chrono::Duration::seconds(2))),
+            serde_json::json!(2_000_000)
+        );
+    }
+}
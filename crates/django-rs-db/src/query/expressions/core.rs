@@ -22,6 +22,7 @@ use crate::query::lookups::Q;
 use crate::value::Value;
 use std::ops;
 
+use super::aggregates::OrderedAggregate;
 use super::window::WindowExpression;
 
 /// A query expression that produces a value in the context of a SQL query.
@@ -55,6 +56,9 @@ pub enum Expression {
         /// Optional FILTER clause.
         filter: Option<Box<Q>>,
     },
+    /// An aggregate collecting a group's values: STRING_AGG, ARRAY_AGG or
+    /// JSON_AGG, with optional DISTINCT, ordering and FILTER.
+    OrderedAggregate(Box<OrderedAggregate>),
     /// A CASE ... WHEN ... THEN ... ELSE ... END expression.
     Case {
         /// The WHEN/THEN branches.
@@ -214,6 +218,49 @@ impl Expression {
     pub fn raw(sql: impl Into<String>, params: Vec<Value>) -> Self {
         Self::RawSQL(sql.into(), params)
     }
+
+    /// Returns `true` if the expression may refer to the column or
+    /// annotation `name`.
+    ///
+    /// Subqueries are not searched, since their names resolve against their
+    /// own table. Raw SQL is assumed to refer to every name it contains.
+    pub fn references(&self, name: &str) -> bool {
+        match self {
+            Self::Col(column) | Self::F(column) => column == name,
+            Self::Value(_) | Self::Subquery(_) | Self::OuterRef(_) | Self::Exists { .. } => false,
+            Self::RawSQL(sql, _) => sql.contains(name),
+            Self::Func { args, .. } => args.iter().any(|arg| arg.references(name)),
+            Self::Aggregate { field, filter, .. } => {
+                field.references(name) || filter.as_ref().is_some_and(|q| q.references(name))
+            }
+            Self::OrderedAggregate(aggregate) => {
+                aggregate.field.references(name)
+                    || aggregate.ordering.iter().any(|(column, _)| column == name)
+                    || aggregate
+                        .filter
+                        .as_ref()
+                        .is_some_and(|q| q.references(name))
+            }
+            Self::Case { whens, default } => {
+                whens
+                    .iter()
+                    .any(|when| when.condition.references(name) || when.then.references(name))
+                    || default.as_ref().is_some_and(|e| e.references(name))
+            }
+            Self::Window(window) => {
+                window.partition_by.iter().any(|column| column == name)
+                    || window.order_by.iter().any(|(column, _)| column == name)
+            }
+            Self::Extract { expr, .. }
+            | Self::DateTrunc { expr, .. }
+            | Self::Cast { expr, .. }
+            | Self::Collate { expr, .. } => expr.references(name),
+            Self::Add(left, right)
+            | Self::Sub(left, right)
+            | Self::Mul(left, right)
+            | Self::Div(left, right) => left.references(name) || right.references(name),
+        }
+    }
 }
 
 impl ops::Add for Expression {
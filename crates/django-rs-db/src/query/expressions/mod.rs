@@ -7,11 +7,13 @@
 //! # Submodules
 //!
 //! - [`core`] - Core expression types: F, Value, Func, Aggregate, Case/When, arithmetic
+//! - [`aggregates`] - StringAgg, ArrayAgg and JsonAgg with ordering, DISTINCT and FILTER
 //! - [`subquery`] - Subquery, OuterRef, Exists expressions for correlated subqueries
 //! - [`window`] - Window expressions and window functions (ROW_NUMBER, RANK, etc.)
 //! - [`functions`] - Database functions (Coalesce, Upper, Lower, Round, Now, Cast, etc.)
 //! - [`search`] - PostgreSQL full-text search (SearchVector, SearchQuery, SearchRank, TrigramSimilarity)
 
+pub mod aggregates;
 pub mod core;
 pub mod functions;
 pub mod search;
@@ -19,6 +21,9 @@ pub mod subquery;
 pub mod window;
 
 // Re-export core types at the expressions level for backward compatibility.
+pub use self::aggregates::{
+    array_agg, json_agg, string_agg, OrderedAggregate, OrderedAggregateFunc,
+};
 pub use self::core::{AggregateFunc, Expression, When};
 pub use self::functions::*;
 pub use self::search::{SearchQuery, SearchQueryType, SearchRank, SearchVector, TrigramSimilarity};
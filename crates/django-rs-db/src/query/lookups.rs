@@ -103,6 +103,104 @@ pub enum Lookup {
     Search(String),
 }
 
+impl Lookup {
+    /// The lookup names understood by [`Lookup::from_name`].
+    pub const NAMES: &'static [&'static str] = &[
+        "exact",
+        "iexact",
+        "contains",
+        "icontains",
+        "in",
+        "gt",
+        "gte",
+        "lt",
+        "lte",
+        "startswith",
+        "istartswith",
+        "endswith",
+        "iendswith",
+        "range",
+        "isnull",
+        "regex",
+        "iregex",
+    ];
+
+    /// Builds the lookup named `name` (e.g. `"icontains"`) for `value`.
+    ///
+    /// String lookups use the value's text form, `in` accepts a list or a
+    /// single value, `range` needs a two-item list and `isnull` a boolean.
+    /// Returns `None` for unknown names or values of the wrong shape.
+    pub fn from_name(name: &str, value: Value) -> Option<Self> {
+        let text = |value: Value| match value {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        Some(match name {
+            "exact" => Self::Exact(value),
+            "iexact" => Self::IExact(value),
+            "contains" => Self::Contains(text(value)),
+            "icontains" => Self::IContains(text(value)),
+            "in" => match value {
+                Value::List(values) => Self::In(values),
+                other => Self::In(vec![other]),
+            },
+            "gt" => Self::Gt(value),
+            "gte" => Self::Gte(value),
+            "lt" => Self::Lt(value),
+            "lte" => Self::Lte(value),
+            "startswith" => Self::StartsWith(text(value)),
+            "istartswith" => Self::IStartsWith(text(value)),
+            "endswith" => Self::EndsWith(text(value)),
+            "iendswith" => Self::IEndsWith(text(value)),
+            "range" => match value {
+                Value::List(values) if values.len() == 2 => {
+                    let mut values = values.into_iter();
+                    Self::Range(values.next()?, values.next()?)
+                }
+                _ => return None,
+            },
+            "isnull" => Self::IsNull(value.as_bool()?),
+            "regex" => Self::Regex(text(value)),
+            "iregex" => Self::IRegex(text(value)),
+            _ => return None,
+        })
+    }
+}
+
+/// A lookup comparing several columns at once as a row value, such as
+/// `(org_id, user_id) IN ((1, 2), (1, 3))`.
+///
+/// Comparisons are lexicographic: `(a, b) > (x, y)` holds if `a > x`, or
+/// `a = x` and `b > y`, which is the resume condition of keyset pagination
+/// over a multi-column ordering.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TupleLookup {
+    /// Equality (`(a, b) = (x, y)`).
+    Exact(Vec<Value>),
+    /// Membership (`(a, b) IN ((x, y), ...)`).
+    In(Vec<Vec<Value>>),
+    /// Greater than (`(a, b) > (x, y)`).
+    Gt(Vec<Value>),
+    /// Greater than or equal (`(a, b) >= (x, y)`).
+    Gte(Vec<Value>),
+    /// Less than (`(a, b) < (x, y)`).
+    Lt(Vec<Value>),
+    /// Less than or equal (`(a, b) <= (x, y)`).
+    Lte(Vec<Value>),
+}
+
+impl TupleLookup {
+    /// Returns the rows of values the columns are compared with.
+    pub fn rows(&self) -> &[Vec<Value>] {
+        match self {
+            Self::In(rows) => rows,
+            Self::Exact(row) | Self::Gt(row) | Self::Gte(row) | Self::Lt(row) | Self::Lte(row) => {
+                std::slice::from_ref(row)
+            }
+        }
+    }
+}
+
 /// A composable query filter, equivalent to Django's `Q` object.
 ///
 /// `Q` objects can be combined using `&` (AND), `|` (OR), and `!` (NOT)
@@ -122,6 +220,13 @@ pub enum Q {
     Or(Vec<Q>),
     /// Logical negation of a condition.
     Not(Box<Q>),
+    /// A lookup on several fields compared as a row value.
+    Tuple {
+        /// The field names, in the order of the values in each row.
+        fields: Vec<String>,
+        /// The lookup operation.
+        lookup: TupleLookup,
+    },
 }
 
 impl Q {
@@ -133,6 +238,79 @@ impl Q {
         }
     }
 
+    /// Creates a lookup on several fields compared as a row value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fields` is empty or a row of `lookup` does not have one
+    /// value per field.
+    pub fn tuple<S: Into<String>>(
+        fields: impl IntoIterator<Item = S>,
+        lookup: TupleLookup,
+    ) -> Self {
+        let fields: Vec<String> = fields.into_iter().map(Into::into).collect();
+        assert!(
+            !fields.is_empty(),
+            "a tuple lookup needs at least one field"
+        );
+        for row in lookup.rows() {
+            assert_eq!(
+                row.len(),
+                fields.len(),
+                "tuple lookup on {fields:?} got a row of {} value(s)",
+                row.len()
+            );
+        }
+        Self::Tuple { fields, lookup }
+    }
+
+    /// Keeps rows whose `fields` equal one of `rows`, e.g.
+    /// `(org_id, user_id) IN ((1, 2), (1, 3))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fields` is empty or a row does not have one value per field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_db::query::compiler::{DatabaseBackendType, Query, SqlCompiler, WhereNode};
+    /// use django_rs_db::query::lookups::Q;
+    /// use django_rs_db::value::Value;
+    ///
+    /// let q = Q::tuple_in(
+    ///     ["org_id", "user_id"],
+    ///     vec![vec![Value::from(1), Value::from(2)], vec![Value::from(1), Value::from(3)]],
+    /// );
+    /// let mut query = Query::new("membership");
+    /// query.where_clause = Some(WhereNode::from_q(&q));
+    /// let (sql, params) = SqlCompiler::new(DatabaseBackendType::PostgreSQL).compile_select(&query);
+    /// assert!(sql.ends_with(r#"WHERE ("org_id", "user_id") IN (($1, $2), ($3, $4))"#));
+    /// assert_eq!(params.len(), 4);
+    /// ```
+    pub fn tuple_in<S: Into<String>>(
+        fields: impl IntoIterator<Item = S>,
+        rows: Vec<Vec<Value>>,
+    ) -> Self {
+        Self::tuple(fields, TupleLookup::In(rows))
+    }
+
+    /// Creates a filter from a Django-style lookup path such as
+    /// `"title__icontains"` or `"author__name"`.
+    ///
+    /// If the last `__` segment names a built-in lookup it is applied to the
+    /// rest of the path; otherwise the whole path is compared with `exact`.
+    /// Returns `None` if `value` doesn't fit the lookup (e.g. `range` without
+    /// two values).
+    pub fn from_path(path: &str, value: Value) -> Option<Self> {
+        if let Some((field, name)) = path.rsplit_once("__") {
+            if Lookup::NAMES.contains(&name) {
+                return Lookup::from_name(name, value).map(|lookup| Self::filter(field, lookup));
+            }
+        }
+        Some(Self::filter(path, Lookup::Exact(value)))
+    }
+
     /// Returns `true` if this is an empty AND (always true).
     pub fn is_empty(&self) -> bool {
         match self {
@@ -140,6 +318,18 @@ impl Q {
             _ => false,
         }
     }
+
+    /// Returns `true` if the filter is on the field or annotation `name`.
+    pub fn references(&self, name: &str) -> bool {
+        match self {
+            Self::Filter { field, .. } => field == name,
+            Self::And(children) | Self::Or(children) => {
+                children.iter().any(|child| child.references(name))
+            }
+            Self::Not(inner) => inner.references(name),
+            Self::Tuple { fields, .. } => fields.iter().any(|field| field == name),
+        }
+    }
 }
 
 impl ops::BitAnd for Q {
@@ -204,6 +394,27 @@ impl ops::Not for Q {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_q_from_path() {
+        assert_eq!(
+            Q::from_path("title__icontains", Value::from("rust")),
+            Some(Q::filter("title", Lookup::IContains("rust".into())))
+        );
+        assert_eq!(
+            Q::from_path("author__name", Value::from("Ann")),
+            Some(Q::filter("author__name", Lookup::Exact(Value::from("Ann"))))
+        );
+        assert_eq!(
+            Q::from_path("tag__in", Value::from("a")),
+            Some(Q::filter("tag", Lookup::In(vec![Value::from("a")])))
+        );
+        assert_eq!(
+            Q::from_path("deleted_at__isnull", Value::from(true)),
+            Some(Q::filter("deleted_at", Lookup::IsNull(true)))
+        );
+        assert_eq!(Q::from_path("age__range", Value::from(3)), None);
+    }
+
     #[test]
     fn test_simple_filter() {
         let q = Q::filter("name", Lookup::Exact(Value::from("Alice")));
@@ -3,29 +3,39 @@
 //! This module contains the complete query pipeline:
 //!
 //! - [`lookups`] - Q objects and lookup types for filtering
+//! - [`comment`] - SQL comments and optimizer hints on generated queries
 //! - [`expressions`] - F-objects, aggregates, and computed expressions
 //! - [`compiler`] - Query AST and SQL compilation
 //! - [`queryset`] - QuerySet and Manager for lazy query building
+//! - [`dates`] - Date truncation for `dates_exec` / `datetimes_exec`
 //! - [`raw`] - Raw SQL query support
 //! - [`bulk`] - Bulk create, bulk update, get_or_create, update_or_create
 //! - [`custom_lookups`] - Custom lookup and transform registry
+//! - [`tree`] - Tree navigation for self-referencing models via recursive CTEs
 
 pub mod bulk;
+pub mod comment;
 pub mod compiler;
 pub mod custom_lookups;
+pub mod dates;
+pub mod deserialize;
 pub mod expressions;
 pub mod lookups;
 pub mod queryset;
 pub mod raw;
+pub mod tree;
 
+pub use comment::QueryComment;
 pub use compiler::{
     CompoundQuery, CompoundType, DatabaseBackendType, InheritanceType, OrderBy,
     PrefetchRelatedField, Query, Row, SelectColumn, SelectRelatedField, SqlCompiler, WhereNode,
 };
+pub use dates::{DateKind, DateTimeKind};
 pub use expressions::{AggregateFunc, Expression, When};
 pub use expressions::{
     Exists, OuterRef, SubqueryExpression, WindowExpression, WindowFrame, WindowFrameBound,
     WindowFrameType, WindowFunction,
 };
-pub use lookups::{Lookup, Q};
+pub use lookups::{Lookup, TupleLookup, Q};
 pub use queryset::{Manager, PrefetchResult, QuerySet};
+pub use tree::{TreeModel, TreeNode, TreeQuery};
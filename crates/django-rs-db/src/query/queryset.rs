@@ -14,15 +14,22 @@
 //! // QuerySets are lazy — they build a Query AST without executing anything.
 //! ```
 
+use super::comment::QueryComment;
 use super::compiler::{
     CompoundQuery, CompoundType, DatabaseBackendType, InheritanceType, OrderBy,
     PrefetchRelatedField, Query, SelectColumn, SelectRelatedField, SqlCompiler, WhereNode,
 };
-use super::expressions::Expression;
-use super::lookups::Q;
+use super::dates::{self, DateKind, DateTimeKind};
+use super::expressions::{Exists, Expression, OuterRef};
+use super::lookups::{Lookup, Q};
+use crate::encryption;
 use crate::executor::DbExecutor;
+use crate::fields::FieldType;
 use crate::model::Model;
+use crate::router::{record_write, RouterChain};
+use crate::timestamps;
 use crate::value::Value;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use django_rs_core::{DjangoError, DjangoResult};
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -88,6 +95,26 @@ impl<M: Model> Manager<M> {
         qs.pending_create = Some(fields);
         qs
     }
+
+    /// Shortcut for inserting a record, or updating the existing one that
+    /// has the same `unique_by` values, via the queryset.
+    ///
+    /// On conflict, every given field outside `unique_by` is overwritten,
+    /// except `auto_now_add` timestamps. Run it with
+    /// [`QuerySet::upsert_exec`], which returns the resulting row. This suits
+    /// "create or refresh" patterns such as cached API results, idempotency
+    /// keys and counters.
+    ///
+    /// `unique_by` must name columns covered by a unique constraint.
+    pub fn upsert(
+        &self,
+        unique_by: &[&'static str],
+        values: Vec<(&'static str, Value)>,
+    ) -> QuerySet<M> {
+        let mut qs = self.all();
+        qs.pending_upsert = Some((unique_by.to_vec(), values));
+        qs
+    }
 }
 
 /// A lazy, composable database query.
@@ -107,10 +134,83 @@ pub struct QuerySet<M: Model> {
     is_none: bool,
     /// Pending create operation fields.
     pending_create: Option<Vec<(&'static str, Value)>>,
+    /// Pending upsert operation.
+    pending_upsert: Option<PendingUpsert>,
     /// Pending update operation fields.
     pending_update: Option<Vec<(&'static str, Value)>>,
     /// Whether this is a delete operation.
     pending_delete: bool,
+    /// SQL comment tags and optimizer hints added to every statement.
+    comment: QueryComment,
+    /// Whether create and update statements leave automatic timestamps alone.
+    skip_auto_now: bool,
+    /// The models embedded as subqueries, for the cross-database check.
+    subquery_models: Vec<SubqueryModel>,
+    /// The `select_related` fields loaded with a separate query.
+    separate_fields: Vec<String>,
+    /// The `select_related` relations loaded with a separate query instead
+    /// of a JOIN.
+    separate_relations: Vec<SelectRelatedField>,
+    /// The first error found while building the query, returned when it is
+    /// executed.
+    error: Option<BuildError>,
+}
+
+/// An error found while building a queryset.
+///
+/// Builder methods stay infallible; the error is kept here and returned by
+/// every execution method instead.
+#[derive(Debug)]
+struct BuildError {
+    kind: fn(String) -> DjangoError,
+    message: String,
+}
+
+impl BuildError {
+    fn new(error: DjangoError) -> Self {
+        match error {
+            DjangoError::ImproperlyConfigured(message) => Self {
+                kind: DjangoError::ImproperlyConfigured,
+                message,
+            },
+            DjangoError::DatabaseError(message) => Self {
+                kind: DjangoError::DatabaseError,
+                message,
+            },
+            other => Self {
+                kind: DjangoError::DatabaseError,
+                message: other.to_string(),
+            },
+        }
+    }
+
+    fn to_error(&self) -> DjangoError {
+        (self.kind)(self.message.clone())
+    }
+}
+
+/// The conflict columns and the fields of a pending upsert.
+type PendingUpsert = (Vec<&'static str>, Vec<(&'static str, Value)>);
+
+/// A model embedded in a queryset as a subquery.
+#[derive(Debug, Clone)]
+struct SubqueryModel {
+    app_label: &'static str,
+    model_name: &'static str,
+    using: Option<String>,
+}
+
+impl SubqueryModel {
+    /// Describes the model of `qs` and the models embedded in it.
+    fn all_of<R: Model>(qs: &QuerySet<R>) -> Vec<Self> {
+        let mut models = vec![Self {
+            app_label: R::app_label(),
+            model_name: R::meta().model_name,
+            using: qs.using.clone(),
+        }];
+        models.extend(qs.subquery_models.iter().cloned());
+        models
+    }
 }
 
 impl<M: Model> QuerySet<M> {
@@ -122,11 +222,31 @@ impl<M: Model> QuerySet<M> {
             using,
             is_none: false,
             pending_create: None,
+            pending_upsert: None,
             pending_update: None,
             pending_delete: false,
+            comment: QueryComment::default(),
+            skip_auto_now: false,
+            subquery_models: Vec::new(),
+            separate_fields: Vec::new(),
+            separate_relations: Vec::new(),
+            error: None,
         }
     }
 
+    /// Keeps `error` to be returned when the queryset is executed, unless an
+    /// earlier one is already kept.
+    fn defer_error(&mut self, error: DjangoError) {
+        self.error.get_or_insert_with(|| BuildError::new(error));
+    }
+
+    /// Returns the error found while building the queryset, if any.
+    fn check_built(&self) -> DjangoResult<()> {
+        self.error
+            .as_ref()
+            .map_or(Ok(()), |error| Err(error.to_error()))
+    }
+
     /// Returns a reference to the underlying query AST.
     pub const fn query(&self) -> &Query {
         &self.query
@@ -154,30 +274,208 @@ impl<M: Model> QuerySet<M> {
         self
     }
 
+    /// Tags every statement this queryset generates with a SQL comment.
+    ///
+    /// Takes comma-separated `key=value` pairs, rendered in the sqlcommenter
+    /// format at the end of the statement so DBAs can attribute queries in
+    /// `pg_stat_statements` or slow query logs to the calling code.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let qs = Post::objects().all().comment("controller=PostList,action=index");
+    /// // SELECT ... FROM "blog_post" /*action='index',controller='PostList'*/
+    /// ```
+    #[must_use]
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comment.add(comment);
+        self
+    }
+
+    /// Adds a backend-specific optimizer hint, e.g. `INDEX(blog_post idx)`.
+    ///
+    /// MySQL reads hints after the leading keyword and PostgreSQL (with the
+    /// `pg_hint_plan` extension) from a leading comment; SQLite ignores them.
+    /// The hint is inserted verbatim, so it must never contain user input.
+    #[must_use]
+    pub fn hint(mut self, hint: &str) -> Self {
+        self.comment.add_hint(hint);
+        self
+    }
+
+    /// Returns the comment tags and hints attached to this queryset.
+    pub const fn query_comment(&self) -> &QueryComment {
+        &self.comment
+    }
+
+    /// Applies the comment and hints to compiled SQL.
+    fn with_comment(
+        &self,
+        (sql, params): (String, Vec<Value>),
+        backend: DatabaseBackendType,
+    ) -> (String, Vec<Value>) {
+        (self.comment.apply(sql, backend), params)
+    }
+
     // ── Filtering methods (lazy) ─────────────────────────────────────
 
+    /// ANDs `node` into the WHERE clause.
+    fn push_where(&mut self, node: WhereNode) {
+        self.query.where_clause = Some(match self.query.where_clause.take() {
+            Some(existing) => WhereNode::And(vec![existing, node]),
+            None => node,
+        });
+    }
+
+    /// ANDs `q`, or its negation, into the WHERE clause, comparing the blind
+    /// indexes of encrypted fields; see [`encryption`](crate::encryption).
+    ///
+    /// A lookup that cannot be made on an encrypted field is kept as the
+    /// queryset's error, and the WHERE clause made to match no rows.
+    fn push_q(&mut self, q: &Q, negate: bool) {
+        match encryption::rewrite_lookups(&M::meta().fields, WhereNode::from_q(q)) {
+            Ok(node) if negate => self.push_where(WhereNode::Not(Box::new(node))),
+            Ok(node) => self.push_where(node),
+            Err(error) => {
+                self.defer_error(error);
+                self.push_where(WhereNode::Or(Vec::new()));
+            }
+        }
+    }
+
     /// Adds a filter condition. Returns a new queryset.
+    ///
+    /// A lookup an encrypted field does not support makes the queryset
+    /// return an error when it is executed.
     #[must_use]
     pub fn filter(mut self, q: Q) -> Self {
-        let new_node = WhereNode::from_q(&q);
-        self.query.where_clause = Some(match self.query.where_clause.take() {
-            Some(existing) => WhereNode::And(vec![existing, new_node]),
-            None => new_node,
-        });
+        self.push_q(&q, false);
         self
     }
 
     /// Adds an exclusion condition (NOT). Returns a new queryset.
+    ///
+    /// A lookup an encrypted field does not support makes the queryset
+    /// return an error when it is executed.
     #[must_use]
     pub fn exclude(mut self, q: Q) -> Self {
-        let new_node = WhereNode::Not(Box::new(WhereNode::from_q(&q)));
-        self.query.where_clause = Some(match self.query.where_clause.take() {
-            Some(existing) => WhereNode::And(vec![existing, new_node]),
-            None => new_node,
+        self.push_q(&q, true);
+        self
+    }
+
+    /// Keeps rows whose `fields`, taken together, equal one of `rows`.
+    ///
+    /// Compiles to `(org_id, user_id) IN ((?, ?), ...)` where the backend
+    /// [supports row values](DatabaseBackendType::supports_row_values), which
+    /// all built-in backends do, and to the equivalent OR of ANDs elsewhere. Use [`Q::tuple`] for tuple
+    /// comparisons such as the resume point of keyset pagination.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fields` is empty or a row does not have one value per field.
+    #[must_use]
+    pub fn filter_tuple_in(self, fields: &[&str], rows: Vec<Vec<Value>>) -> Self {
+        self.filter(Q::tuple_in(fields.iter().copied(), rows))
+    }
+
+    /// Keeps rows whose `column` is among the values selected by `subquery`.
+    ///
+    /// Compiles to `column IN (SELECT ...)`. The subquery must select a
+    /// single column, e.g. via `.values(vec!["id"])`; a subquery that still
+    /// selects `*` is projected onto its primary key. This is the
+    /// equivalent of Django's `filter(author_id__in=users.values("id"))`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subquery selects more than one column.
+    pub fn filter_in<R: Model>(
+        mut self,
+        column: &str,
+        subquery: QuerySet<R>,
+    ) -> DjangoResult<Self> {
+        self.subquery_models
+            .extend(SubqueryModel::all_of(&subquery));
+        let query = self.correlate(subquery.into_subquery()?);
+        self.push_where(WhereNode::InSubquery {
+            column: column.to_string(),
+            query: Box::new(query),
         });
+        Ok(self)
+    }
+
+    /// Keeps rows for which `related` returns at least one row.
+    ///
+    /// Compiles to `EXISTS (SELECT 1 ...)`. Use
+    /// [`filter_outer_ref`](Self::filter_outer_ref) on `related` to correlate
+    /// it with this queryset. This is the equivalent of Django's
+    /// `filter(Exists(related))`.
+    #[must_use]
+    pub fn filter_exists<R: Model>(mut self, related: QuerySet<R>) -> Self {
+        self.subquery_models.extend(SubqueryModel::all_of(&related));
+        let exists = Exists::new(self.correlate(related.query));
+        self.push_where(WhereNode::Expression(exists.into_expression()));
         self
     }
 
+    /// Keeps rows for which `related` returns no rows (`NOT EXISTS`).
+    #[must_use]
+    pub fn exclude_exists<R: Model>(mut self, related: QuerySet<R>) -> Self {
+        self.subquery_models.extend(SubqueryModel::all_of(&related));
+        let exists = Exists::new(self.correlate(related.query)).negate();
+        self.push_where(WhereNode::Expression(exists.into_expression()));
+        self
+    }
+
+    /// Compares `column` to a column of the enclosing query.
+    ///
+    /// Only meaningful on a queryset passed to
+    /// [`filter_exists`](Self::filter_exists) or
+    /// [`filter_in`](Self::filter_in), where the reference is resolved to the
+    /// outer table. This is the equivalent of Django's
+    /// `filter(post=OuterRef("pk"))`.
+    #[must_use]
+    pub fn filter_outer_ref(mut self, column: &str, outer: OuterRef) -> Self {
+        self.push_where(WhereNode::OuterRef {
+            table: M::table_name().to_string(),
+            column: column.to_string(),
+            outer_table: None,
+            outer_column: outer.column().to_string(),
+        });
+        self
+    }
+
+    /// Resolves the outer references of a subquery's `query` against this
+    /// queryset's table.
+    fn correlate(&self, mut query: Query) -> Query {
+        if let Some(where_clause) = &mut query.where_clause {
+            where_clause.resolve_outer_refs(&self.query.table);
+        }
+        query
+    }
+
+    /// Prepares this queryset's query for use as a single-column subquery.
+    ///
+    /// Ordering is dropped unless the subquery is sliced, since it does not
+    /// affect membership.
+    fn into_subquery(self) -> DjangoResult<Query> {
+        let mut query = self.query;
+        let selects_all = |q: &Query| q.select.iter().all(|c| matches!(c, SelectColumn::Star));
+        if selects_all(&query) && query.annotations.is_empty() && query.select_related.is_empty() {
+            query.select = vec![SelectColumn::Column(M::pk_field_name().to_string())];
+        }
+        let columns = query.select.len() + query.annotations.len() + query.select_related.len();
+        if selects_all(&query) || columns != 1 {
+            return Err(DjangoError::DatabaseError(format!(
+                "Subquery on \"{}\" must select exactly one column; use values() to pick it",
+                query.table
+            )));
+        }
+        if query.limit.is_none() && query.offset.is_none() {
+            query.order_by.clear();
+        }
+        Ok(query)
+    }
+
     /// Sets the ordering. Returns a new queryset.
     #[must_use]
     pub fn order_by(mut self, fields: Vec<OrderBy>) -> Self {
@@ -249,12 +547,60 @@ impl<M: Model> QuerySet<M> {
     }
 
     /// Adds an annotation (computed expression with an alias).
+    ///
+    /// If `name` is already used by a model field, another annotation or an
+    /// [`alias`](Self::alias), the annotation is not added and the queryset
+    /// returns an error when it is executed.
     #[must_use]
     pub fn annotate(mut self, name: impl Into<String>, expr: Expression) -> Self {
-        self.query.annotations.insert(name.into(), expr);
+        let name = name.into();
+        match self.check_annotation_name(&name) {
+            Ok(()) => {
+                self.query.annotations.insert(name, expr);
+            }
+            Err(error) => self.defer_error(error),
+        }
+        self
+    }
+
+    /// Names an expression for use in filters and ordering without selecting it.
+    ///
+    /// Filtering or ordering on `name` compiles to the expression itself. This
+    /// is the equivalent of Django's `QuerySet.alias()`. If `name` is already
+    /// used by a model field, an annotation or another alias, the alias is not
+    /// added and the queryset returns an error when it is executed.
+    #[must_use]
+    pub fn alias(mut self, name: impl Into<String>, expr: Expression) -> Self {
+        let name = name.into();
+        match self.check_annotation_name(&name) {
+            Ok(()) => {
+                self.query.aliases.insert(name, expr);
+            }
+            Err(error) => self.defer_error(error),
+        }
         self
     }
 
+    /// Checks that an annotation or alias name is free.
+    fn check_annotation_name(&self, name: &str) -> DjangoResult<()> {
+        let clashes_with_field = M::meta()
+            .fields
+            .iter()
+            .any(|f| f.name == name || f.column == name);
+        if clashes_with_field {
+            return Err(DjangoError::DatabaseError(format!(
+                "The annotation '{name}' conflicts with a field on the model \"{}\"",
+                M::meta().model_name
+            )));
+        }
+        if self.query.annotations.contains_key(name) || self.query.aliases.contains_key(name) {
+            return Err(DjangoError::DatabaseError(format!(
+                "The annotation '{name}' is already defined on this queryset"
+            )));
+        }
+        Ok(())
+    }
+
     /// Adds `select_related` fields (controls JOIN behavior).
     ///
     /// When select_related fields are configured with `select_related_with()`,
@@ -280,12 +626,114 @@ impl<M: Model> QuerySet<M> {
     /// Each entry provides the field name, related table, FK column, related PK column,
     /// and a table alias for the JOIN. The SQL compiler generates LEFT OUTER JOINs
     /// and the result set includes columns from the joined tables.
+    ///
+    /// Fields named in [`separate_query`](Self::separate_query) are not
+    /// joined but loaded by
+    /// [`execute_with_related`](Self::execute_with_related).
     #[must_use]
     pub fn select_related_with(mut self, fields: Vec<SelectRelatedField>) -> Self {
-        self.query.select_related.extend(fields);
+        for field in fields {
+            if self.separate_fields.contains(&field.field_name) {
+                self.separate_relations.push(field);
+            } else {
+                self.query.select_related.push(field);
+            }
+        }
+        self
+    }
+
+    /// Loads the named `select_related` relations with a separate query on
+    /// the related model's own database instead of a JOIN.
+    ///
+    /// This is the opt-in for relations to models routed to another
+    /// database, which [`check_databases`](Self::check_databases) otherwise
+    /// refuses. The related rows are returned by
+    /// [`execute_with_related`](Self::execute_with_related) under the field
+    /// name.
+    #[must_use]
+    pub fn separate_query(mut self, fields: Vec<&str>) -> Self {
+        for name in fields {
+            if !self.separate_fields.iter().any(|f| f == name) {
+                self.separate_fields.push(name.to_string());
+            }
+            let (moved, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.query.select_related)
+                .into_iter()
+                .partition(|field| field.field_name == name);
+            self.query.select_related = kept;
+            self.separate_relations.extend(moved);
+        }
         self
     }
 
+    /// Returns the `select_related` relations loaded with a separate query.
+    pub fn separate_relations(&self) -> &[SelectRelatedField] {
+        &self.separate_relations
+    }
+
+    /// Checks that every relation this queryset joins or embeds lives on the
+    /// database it reads from.
+    ///
+    /// The queryset reads from its [`using`](Self::using) alias, or else the
+    /// one `router` picks for the model. A `select_related` relation to a
+    /// model routed to another database can't be joined, and a subquery on
+    /// another database can't be embedded: the SQL would silently read the
+    /// tables of the wrong database. Relations loaded with
+    /// [`separate_query`](Self::separate_query) are exempt, as are
+    /// `select_related` fields that aren't a `ForeignKey` or `OneToOneField`
+    /// of the model, whose target is unknown.
+    ///
+    /// The `fields.W390` system check
+    /// ([`check_database_relations`](crate::checks::check_database_relations))
+    /// reports such relations of the installed models up front.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DjangoError::DatabaseError`] naming both models and their
+    /// databases.
+    pub fn check_databases(&self, router: &RouterChain) -> DjangoResult<()> {
+        let meta = M::meta();
+        let db = self.read_db(router);
+
+        for field in &self.query.select_related {
+            let Some((app_label, model_name)) = relation_target::<M>(field) else {
+                continue;
+            };
+            let related_db = router.db_for_read(&app_label, &model_name);
+            if related_db != db {
+                return Err(DjangoError::DatabaseError(format!(
+                    "Cannot select_related '{}' from '{}.{}' (database '{db}'): \
+                     '{app_label}.{model_name}' is on database '{related_db}', and a JOIN \
+                     cannot span databases. Use prefetch_related() or separate_query([\"{}\"]) \
+                     to load it with a separate query.",
+                    field.field_name, meta.app_label, meta.model_name, field.field_name,
+                )));
+            }
+        }
+
+        for model in &self.subquery_models {
+            let related_db = model
+                .using
+                .clone()
+                .unwrap_or_else(|| router.db_for_read(model.app_label, model.model_name));
+            if related_db != db {
+                return Err(DjangoError::DatabaseError(format!(
+                    "Cannot filter '{}.{}' (database '{db}') by a subquery on '{}.{}' \
+                     (database '{related_db}'): a subquery cannot span databases. Evaluate \
+                     the subquery first and filter on its values with Lookup::In.",
+                    meta.app_label, meta.model_name, model.app_label, model.model_name,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the alias this queryset reads from.
+    fn read_db(&self, router: &RouterChain) -> String {
+        self.using
+            .clone()
+            .unwrap_or_else(|| router.db_for_read(M::app_label(), M::meta().model_name))
+    }
+
     /// Adds `prefetch_related` fields.
     ///
     /// This simpler version stores field names as hints. For full functionality
@@ -313,12 +761,23 @@ impl<M: Model> QuerySet<M> {
     }
 
     /// Sets fields for an update operation.
+    ///
+    /// The model's `auto_now` fields are set to the current time unless given
+    /// in `fields` or disabled with [`skip_auto_now`](Self::skip_auto_now).
     #[must_use]
     pub fn update(mut self, fields: Vec<(&'static str, Value)>) -> Self {
         self.pending_update = Some(fields);
         self
     }
 
+    /// Leaves `auto_now` and `auto_now_add` fields out of create and update
+    /// statements. See [`timestamps`](crate::timestamps).
+    #[must_use]
+    pub const fn skip_auto_now(mut self) -> Self {
+        self.skip_auto_now = true;
+        self
+    }
+
     /// Marks this queryset for deletion.
     #[must_use]
     pub fn delete(mut self) -> Self {
@@ -378,39 +837,103 @@ impl<M: Model> QuerySet<M> {
     /// Compiles the queryset to SQL for the given backend.
     ///
     /// This is useful for debugging and testing. In production, the backend
-    /// calls this internally during execution.
+    /// calls this internally during execution. Values written to encrypted
+    /// fields are shown unencrypted.
     pub fn to_sql(&self, backend: DatabaseBackendType) -> (String, Vec<Value>) {
+        let compiled = self
+            .compile(backend, false)
+            .expect("statements compile without error unless encrypting");
+        self.with_comment(compiled, backend)
+    }
+
+    /// Compiles a create, upsert or update for execution, encrypting the
+    /// values written to encrypted fields.
+    fn write_sql(&self, backend: DatabaseBackendType) -> DjangoResult<(String, Vec<Value>)> {
+        let compiled = self.compile(backend, true)?;
+        Ok(self.with_comment(compiled, backend))
+    }
+
+    /// Compiles the statement for [`to_sql`](Self::to_sql), without comments.
+    fn compile(
+        &self,
+        backend: DatabaseBackendType,
+        encrypt: bool,
+    ) -> DjangoResult<(String, Vec<Value>)> {
         if self.is_none {
-            return ("SELECT * FROM \"__none__\" WHERE 1=0".to_string(), vec![]);
+            return Ok(("SELECT * FROM \"__none__\" WHERE 1=0".to_string(), vec![]));
         }
 
         let compiler = SqlCompiler::new(backend);
 
         if let Some(ref fields) = self.pending_create {
-            return compiler.compile_insert(&self.query.table, fields);
+            let mut fields = fields.clone();
+            if !self.skip_auto_now {
+                timestamps::stamp_insert(&M::meta().fields, &mut fields, timestamps::now());
+            }
+            if encrypt {
+                encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut fields)?;
+            }
+            return Ok(compiler.compile_insert(&self.query.table, &fields));
+        }
+
+        if let Some((ref unique_by, ref fields)) = self.pending_upsert {
+            let mut fields = fields.clone();
+            if !self.skip_auto_now {
+                timestamps::stamp_insert(&M::meta().fields, &mut fields, timestamps::now());
+            }
+            if encrypt {
+                encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut fields)?;
+            }
+            let update_fields: Vec<&str> = fields
+                .iter()
+                .map(|(name, _)| *name)
+                .filter(|name| {
+                    !unique_by.contains(name)
+                        && !M::meta()
+                            .fields
+                            .iter()
+                            .any(|f| f.name == *name && f.auto_now_add)
+                })
+                .collect();
+            return Ok(compiler.compile_upsert(
+                &self.query.table,
+                &fields,
+                unique_by,
+                &update_fields,
+            ));
         }
 
         if let Some(ref fields) = self.pending_update {
+            let mut fields = fields.clone();
+            if !self.skip_auto_now {
+                timestamps::fill_auto_now(&M::meta().fields, &mut fields, timestamps::now());
+            }
+            if encrypt {
+                encryption::encrypt_values(M::table_name(), &M::meta().fields, &mut fields)?;
+            }
             if let Some(ref where_clause) = self.query.where_clause {
-                return compiler.compile_update(&self.query.table, fields, where_clause);
+                return Ok(compiler.compile_update(&self.query.table, &fields, where_clause));
             }
             // Update without WHERE — update all rows
             let where_all = WhereNode::And(vec![]);
-            return compiler.compile_update(&self.query.table, fields, &where_all);
+            return Ok(compiler.compile_update(&self.query.table, &fields, &where_all));
         }
 
         if self.pending_delete {
             if let Some(ref where_clause) = self.query.where_clause {
-                return compiler.compile_delete(&self.query.table, where_clause);
+                return Ok(compiler.compile_delete(&self.query.table, where_clause));
             }
             let where_all = WhereNode::And(vec![]);
-            return compiler.compile_delete(&self.query.table, &where_all);
+            return Ok(compiler.compile_delete(&self.query.table, &where_all));
         }
 
-        compiler.compile_select(&self.query)
+        Ok(compiler.compile_select(&self.query))
     }
 
     /// Compiles a COUNT query.
+    ///
+    /// Ordering, `select_related` joins and annotations no filter refers to
+    /// are left out; see [`Query::for_count`].
     pub fn count_sql(&self, backend: DatabaseBackendType) -> (String, Vec<Value>) {
         if self.is_none {
             return (
@@ -418,7 +941,20 @@ impl<M: Model> QuerySet<M> {
                 vec![],
             );
         }
-        let mut count_query = self.query.clone();
+        let mut count_query = self.query.for_count();
+        count_query.limit = None;
+        count_query.offset = None;
+        if count_query.distinct {
+            // DISTINCT applies to the selected row, so count the rows it keeps
+            let (inner_sql, params) = SqlCompiler::new(backend).compile_select(&count_query);
+            return self.with_comment(
+                (
+                    format!("SELECT COUNT(*) AS \"count\" FROM ({inner_sql}) AS \"subquery\""),
+                    params,
+                ),
+                backend,
+            );
+        }
         count_query.select = vec![SelectColumn::Expression(
             Expression::aggregate(
                 super::expressions::AggregateFunc::Count,
@@ -426,13 +962,16 @@ impl<M: Model> QuerySet<M> {
             ),
             "count".to_string(),
         )];
-        count_query.order_by.clear();
-        count_query.limit = None;
-        count_query.offset = None;
-        SqlCompiler::new(backend).compile_select(&count_query)
+        self.with_comment(
+            SqlCompiler::new(backend).compile_select(&count_query),
+            backend,
+        )
     }
 
     /// Compiles an EXISTS query.
+    ///
+    /// The inner query is reduced like the one of
+    /// [`count_sql`](Self::count_sql).
     pub fn exists_sql(&self, backend: DatabaseBackendType) -> (String, Vec<Value>) {
         if self.is_none {
             return (
@@ -440,22 +979,29 @@ impl<M: Model> QuerySet<M> {
                 vec![],
             );
         }
-        let mut exists_query = self.query.clone();
-        exists_query.select = vec![SelectColumn::Expression(
+        let (inner_sql, params) = SqlCompiler::new(backend).compile_select(&self.exists_query());
+        self.with_comment((format!("SELECT EXISTS({inner_sql})"), params), backend)
+    }
+
+    /// Returns the query selecting `1` for the first matching row.
+    fn exists_query(&self) -> Query {
+        let mut query = self.query.for_count();
+        query.select = vec![SelectColumn::Expression(
             Expression::value(1),
             "__exists__".to_string(),
         )];
-        exists_query.order_by.clear();
-        exists_query.limit = Some(1);
-        let (inner_sql, params) = SqlCompiler::new(backend).compile_select(&exists_query);
-        (format!("SELECT EXISTS({inner_sql})"), params)
+        query.limit = Some(1);
+        query
     }
 
     /// Compiles a query to get the first result.
     pub fn first_sql(&self, backend: DatabaseBackendType) -> (String, Vec<Value>) {
         let mut first_query = self.query.clone();
         first_query.limit = Some(1);
-        SqlCompiler::new(backend).compile_select(&first_query)
+        self.with_comment(
+            SqlCompiler::new(backend).compile_select(&first_query),
+            backend,
+        )
     }
 
     /// Compiles a query to get the last result.
@@ -466,14 +1012,20 @@ impl<M: Model> QuerySet<M> {
             order.descending = !order.descending;
         }
         last_query.limit = Some(1);
-        SqlCompiler::new(backend).compile_select(&last_query)
+        self.with_comment(
+            SqlCompiler::new(backend).compile_select(&last_query),
+            backend,
+        )
     }
 
     /// Compiles a query for `.get()` (expects exactly one result).
     pub fn get_sql(&self, backend: DatabaseBackendType) -> (String, Vec<Value>) {
         let mut get_query = self.query.clone();
         get_query.limit = Some(2); // Get 2 to detect MultipleObjectsReturned
-        SqlCompiler::new(backend).compile_select(&get_query)
+        self.with_comment(
+            SqlCompiler::new(backend).compile_select(&get_query),
+            backend,
+        )
     }
 
     /// Compiles an aggregate query.
@@ -490,7 +1042,76 @@ impl<M: Model> QuerySet<M> {
         agg_query.order_by.clear();
         agg_query.limit = None;
         agg_query.offset = None;
-        SqlCompiler::new(backend).compile_select(&agg_query)
+        self.with_comment(
+            SqlCompiler::new(backend).compile_select(&agg_query),
+            backend,
+        )
+    }
+
+    /// Compiles a query for [`dates_exec`](Self::dates_exec).
+    pub fn dates_sql(
+        &self,
+        field: &str,
+        kind: DateKind,
+        descending: bool,
+        backend: DatabaseBackendType,
+    ) -> (String, Vec<Value>) {
+        let trunc = dates::trunc_sql(field, kind.into(), true, None, backend);
+        self.periods_sql(field, trunc, descending, backend)
+    }
+
+    /// Compiles a query for [`datetimes_exec`](Self::datetimes_exec).
+    pub fn datetimes_sql(
+        &self,
+        field: &str,
+        kind: DateTimeKind,
+        descending: bool,
+        tz: Option<FixedOffset>,
+        backend: DatabaseBackendType,
+    ) -> (String, Vec<Value>) {
+        let trunc = dates::trunc_sql(field, kind, false, tz, backend);
+        self.periods_sql(field, trunc, descending, backend)
+    }
+
+    /// Compiles the distinct `<trunc>` values of the rows where `field` is
+    /// set.
+    ///
+    /// The queryset's ordering and `select_related` joins are left out, as
+    /// for [`count_sql`](Self::count_sql), but annotations `field` or the
+    /// filters refer to are kept. The values are de-duplicated by an outer
+    /// query, so the annotations still selected don't split them.
+    fn periods_sql(
+        &self,
+        field: &str,
+        trunc: String,
+        descending: bool,
+        backend: DatabaseBackendType,
+    ) -> (String, Vec<Value>) {
+        let mut query = self.query.clone();
+        let not_null = WhereNode::from_q(&Q::filter(field, Lookup::IsNull(false)));
+        query.where_clause = Some(match query.where_clause.take() {
+            Some(existing) => WhereNode::And(vec![existing, not_null]),
+            None => not_null,
+        });
+        let mut query = query.for_count();
+        query.select = vec![SelectColumn::Expression(
+            Expression::RawSQL(trunc, vec![]),
+            "datefield".to_string(),
+        )];
+        query.limit = None;
+        query.offset = None;
+        let (inner_sql, params) = SqlCompiler::new(backend).compile_select(&query);
+        let direction = if descending { "DESC" } else { "ASC" };
+        self.with_comment(
+            (
+                format!(
+                    "SELECT DISTINCT \"datefield\" FROM ({inner_sql}) AS \"periods\" \
+                     ORDER BY \"datefield\" {direction}"
+                ),
+                params,
+            ),
+            backend,
+        )
     }
 
     // ── Async execution methods ───────────────────────────────────────
@@ -500,6 +1121,7 @@ impl<M: Model> QuerySet<M> {
     /// Compiles the query to SQL using the backend's dialect, sends it,
     /// and maps the returned rows to model instances via `M::from_row()`.
     pub async fn execute_query(&self, db: &dyn DbExecutor) -> DjangoResult<Vec<M>> {
+        self.check_built()?;
         if self.is_none {
             return Ok(Vec::new());
         }
@@ -509,10 +1131,66 @@ impl<M: Model> QuerySet<M> {
         rows.iter().map(M::from_row).collect()
     }
 
+    /// Executes the query and deserializes each row into `T` by column name.
+    ///
+    /// Unlike [`execute_query`](Self::execute_query), `T` need not be a model:
+    /// any `serde::Deserialize` struct works, which suits report queries
+    /// built from `values()` and `annotate()`. Columns `T` doesn't declare are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` naming the column if one that `T`
+    /// needs is missing or has the wrong type.
+    pub async fn fetch_as<T: serde::de::DeserializeOwned>(
+        &self,
+        db: &dyn DbExecutor,
+    ) -> DjangoResult<Vec<T>> {
+        self.check_built()?;
+        if self.is_none {
+            return Ok(Vec::new());
+        }
+
+        let (sql, params) = self.to_sql(db.backend_type());
+        let rows = db.query(&sql, &params).await?;
+        rows.iter().map(super::compiler::Row::deserialize).collect()
+    }
+
+    /// Returns the database's execution plan for this query.
+    ///
+    /// Runs the query under `EXPLAIN` (`EXPLAIN QUERY PLAN` on SQLite) and
+    /// returns the plan one line per row: the `detail` column on SQLite, all
+    /// columns separated by spaces elsewhere. The format is backend specific
+    /// and meant for reading or for checks such as
+    /// `django_rs_test::assert_queries::assert_index_used`.
+    pub async fn explain(&self, db: &dyn DbExecutor) -> DjangoResult<String> {
+        self.check_built()?;
+        let backend = db.backend_type();
+        let (sql, params) = self.to_sql(backend);
+        let prefix = match backend {
+            DatabaseBackendType::SQLite => "EXPLAIN QUERY PLAN",
+            DatabaseBackendType::PostgreSQL | DatabaseBackendType::MySQL => "EXPLAIN",
+        };
+        let rows = db.query(&format!("{prefix} {sql}"), &params).await?;
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|row| match row.get::<String>("detail") {
+                Ok(detail) => detail,
+                Err(_) => (0..row.columns().len())
+                    .filter_map(|i| row.get_by_index::<Value>(i).ok())
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
     /// Returns the count of matching records.
     ///
     /// Runs a `SELECT COUNT(*)` query.
     pub async fn count_exec(&self, db: &dyn DbExecutor) -> DjangoResult<i64> {
+        self.check_built()?;
         if self.is_none {
             return Ok(0);
         }
@@ -528,25 +1206,74 @@ impl<M: Model> QuerySet<M> {
 
     /// Returns whether any records match the query.
     pub async fn exists_exec(&self, db: &dyn DbExecutor) -> DjangoResult<bool> {
+        self.check_built()?;
         if self.is_none {
             return Ok(false);
         }
 
-        let mut first_query = self.query.clone();
-        first_query.select = vec![SelectColumn::Expression(
-            Expression::value(1),
-            "__exists__".to_string(),
-        )];
-        first_query.order_by.clear();
-        first_query.limit = Some(1);
-
-        let (sql, params) = SqlCompiler::new(db.backend_type()).compile_select(&first_query);
+        let (sql, params) = self.with_comment(
+            SqlCompiler::new(db.backend_type()).compile_select(&self.exists_query()),
+            db.backend_type(),
+        );
         let rows = db.query(&sql, &params).await?;
         Ok(!rows.is_empty())
     }
 
+    /// Returns the distinct dates `field` takes, truncated to `kind`.
+    ///
+    /// Equivalent to Django's `QuerySet.dates()`: the truncation and
+    /// de-duplication run in the database, e.g. `SELECT DISTINCT
+    /// DATE_TRUNC('month', "pub_date")`. Rows where `field` is NULL are
+    /// skipped. Dates are sorted ascending, or newest first if `descending`.
+    pub async fn dates_exec(
+        &self,
+        db: &dyn DbExecutor,
+        field: &str,
+        kind: DateKind,
+        descending: bool,
+    ) -> DjangoResult<Vec<NaiveDate>> {
+        self.check_built()?;
+        if self.is_none {
+            return Ok(Vec::new());
+        }
+
+        let (sql, params) = self.dates_sql(field, kind, descending, db.backend_type());
+        let rows = db.query(&sql, &params).await?;
+        rows.iter()
+            .map(|row| dates::date_from_value(&row.get_by_index::<Value>(0)?))
+            .collect()
+    }
+
+    /// Returns the distinct datetimes `field` takes, truncated to `kind`.
+    ///
+    /// Equivalent to Django's `QuerySet.datetimes()`. Stored values are taken
+    /// to be UTC; with `tz`, they are truncated on that offset's wall clock
+    /// (a day in `+02:00` starts at 22:00 UTC) and returned in it. Without
+    /// it, they are returned in UTC.
+    pub async fn datetimes_exec(
+        &self,
+        db: &dyn DbExecutor,
+        field: &str,
+        kind: DateTimeKind,
+        descending: bool,
+        tz: Option<FixedOffset>,
+    ) -> DjangoResult<Vec<DateTime<FixedOffset>>> {
+        self.check_built()?;
+        if self.is_none {
+            return Ok(Vec::new());
+        }
+
+        let (sql, params) = self.datetimes_sql(field, kind, descending, tz, db.backend_type());
+        let offset = tz.unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"));
+        let rows = db.query(&sql, &params).await?;
+        rows.iter()
+            .map(|row| dates::datetime_from_value(&row.get_by_index::<Value>(0)?, offset))
+            .collect()
+    }
+
     /// Returns the first matching record, or `None` if no records match.
     pub async fn first_exec(&self, db: &dyn DbExecutor) -> DjangoResult<Option<M>> {
+        self.check_built()?;
         if self.is_none {
             return Ok(None);
         }
@@ -564,6 +1291,7 @@ impl<M: Model> QuerySet<M> {
     /// Returns `DoesNotExist` if no records match, or
     /// `MultipleObjectsReturned` if more than one record matches.
     pub async fn get_exec(&self, db: &dyn DbExecutor) -> DjangoResult<M> {
+        self.check_built()?;
         if self.is_none {
             return Err(DjangoError::DoesNotExist(format!(
                 "{} matching query does not exist.",
@@ -591,6 +1319,7 @@ impl<M: Model> QuerySet<M> {
     ///
     /// The queryset must have been prepared with `.update(fields)`.
     pub async fn update_exec(&self, db: &dyn DbExecutor) -> DjangoResult<u64> {
+        self.check_built()?;
         if self.is_none {
             return Ok(0);
         }
@@ -601,7 +1330,8 @@ impl<M: Model> QuerySet<M> {
             ));
         }
 
-        let (sql, params) = self.to_sql(db.backend_type());
+        let (sql, params) = self.write_sql(db.backend_type())?;
+        record_write();
         db.execute_sql(&sql, &params).await
     }
 
@@ -609,6 +1339,7 @@ impl<M: Model> QuerySet<M> {
     ///
     /// The queryset must have been prepared with `.delete()`.
     pub async fn delete_exec(&self, db: &dyn DbExecutor) -> DjangoResult<u64> {
+        self.check_built()?;
         if self.is_none {
             return Ok(0);
         }
@@ -621,6 +1352,7 @@ impl<M: Model> QuerySet<M> {
         }
 
         let (sql, params) = self.to_sql(db.backend_type());
+        record_write();
         db.execute_sql(&sql, &params).await
     }
 
@@ -628,6 +1360,7 @@ impl<M: Model> QuerySet<M> {
     ///
     /// The queryset must have been prepared via `Manager::create(fields)`.
     pub async fn create_exec(&self, db: &dyn DbExecutor) -> DjangoResult<Value> {
+        self.check_built()?;
         if self.pending_create.is_none() {
             return Err(DjangoError::DatabaseError(
                 "No pending create fields. Call Manager::create(fields) before .create_exec()"
@@ -635,10 +1368,67 @@ impl<M: Model> QuerySet<M> {
             ));
         }
 
-        let (sql, params) = self.to_sql(db.backend_type());
+        let (sql, params) = self.write_sql(db.backend_type())?;
+        record_write();
         db.insert_returning_id(&sql, &params).await
     }
 
+    /// Runs an upsert and returns the inserted or updated record.
+    ///
+    /// The queryset must have been prepared via `Manager::upsert(unique_by,
+    /// values)`. On MySQL, which has no `RETURNING`, the record is selected
+    /// by its `unique_by` values after the upsert.
+    pub async fn upsert_exec(&self, db: &dyn DbExecutor) -> DjangoResult<M> {
+        self.check_built()?;
+        let Some((ref unique_by, ref fields)) = self.pending_upsert else {
+            return Err(DjangoError::DatabaseError(
+                "No pending upsert. Call Manager::upsert(unique_by, values) before .upsert_exec()"
+                    .to_string(),
+            ));
+        };
+        let mut key = Vec::with_capacity(unique_by.len());
+        for column in unique_by {
+            let value = fields
+                .iter()
+                .find(|(name, _)| name == column)
+                .map(|(_, value)| value.clone())
+                .ok_or_else(|| {
+                    DjangoError::DatabaseError(format!(
+                        "upsert() is missing a value for the unique field '{column}'"
+                    ))
+                })?;
+            key.push(WhereNode::Condition {
+                column: (*column).to_string(),
+                lookup: super::lookups::Lookup::Exact(value),
+            });
+        }
+        if key.is_empty() {
+            return Err(DjangoError::DatabaseError(
+                "upsert() needs at least one unique field".to_string(),
+            ));
+        }
+
+        let (sql, params) = self.write_sql(db.backend_type())?;
+        record_write();
+        let rows = if db.backend_type() == DatabaseBackendType::MySQL {
+            db.execute_sql(&sql, &params).await?;
+            let mut query = Query::new(&self.query.table);
+            query.where_clause = Some(WhereNode::And(key));
+            query.limit = Some(1);
+            let (sql, params) = SqlCompiler::new(db.backend_type()).compile_select(&query);
+            db.query(&sql, &params).await?
+        } else {
+            db.query(&sql, &params).await?
+        };
+        match rows.first() {
+            Some(row) => M::from_row(row),
+            None => Err(DjangoError::DatabaseError(format!(
+                "upsert() into {} returned no row",
+                M::table_name()
+            ))),
+        }
+    }
+
     /// Executes the main query and then runs prefetch_related batch queries.
     ///
     /// Returns a tuple of `(models, prefetch_cache)` where `prefetch_cache` is a
@@ -650,6 +1440,7 @@ impl<M: Model> QuerySet<M> {
         &self,
         db: &dyn DbExecutor,
     ) -> DjangoResult<(Vec<M>, HashMap<String, Vec<super::compiler::Row>>)> {
+        self.check_built()?;
         if self.is_none {
             return Ok((Vec::new(), HashMap::new()));
         }
@@ -672,6 +1463,7 @@ impl<M: Model> QuerySet<M> {
 
         let mut prefetch_cache = HashMap::new();
         for (field_name, pf_sql, pf_params) in prefetch_queries {
+            let pf_sql = self.comment.apply(pf_sql, db.backend_type());
             let pf_rows = db.query(&pf_sql, &pf_params).await?;
             prefetch_cache.insert(field_name, pf_rows);
         }
@@ -679,6 +1471,92 @@ impl<M: Model> QuerySet<M> {
         Ok((models, prefetch_cache))
     }
 
+    /// Executes the query after [`check_databases`](Self::check_databases),
+    /// then loads the [`separate_query`](Self::separate_query) relations.
+    ///
+    /// The main query and its `prefetch_related` queries run on `db`. Each
+    /// separate relation is fetched with
+    /// `SELECT * FROM related WHERE related_column IN (...)` over the
+    /// foreign keys of the results, on the connection in `connections` for
+    /// the alias `router` picks for the related model. Returns the models and
+    /// the related rows keyed by field name, as
+    /// [`execute_with_prefetch`](Self::execute_with_prefetch) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the check fails, a query fails, or `connections`
+    /// has no connection for a related model's database.
+    pub async fn execute_with_related(
+        &self,
+        db: &dyn DbExecutor,
+        router: &RouterChain,
+        connections: &HashMap<&str, &dyn DbExecutor>,
+    ) -> DjangoResult<(Vec<M>, HashMap<String, Vec<super::compiler::Row>>)> {
+        self.check_built()?;
+        self.check_databases(router)?;
+        if self.is_none {
+            return Ok((Vec::new(), HashMap::new()));
+        }
+
+        let (sql, params) = self.to_sql(db.backend_type());
+        let rows = db.query(&sql, &params).await?;
+        let models: Vec<M> = rows
+            .iter()
+            .map(M::from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let pk_values: Vec<Value> = models.iter().filter_map(|m| m.pk().cloned()).collect();
+        let compiler = SqlCompiler::new(db.backend_type());
+        let mut related_cache = HashMap::new();
+        for (field_name, pf_sql, pf_params) in
+            compiler.compile_prefetch_queries(&self.query.prefetch_related, &pk_values)
+        {
+            let pf_sql = self.comment.apply(pf_sql, db.backend_type());
+            related_cache.insert(field_name, db.query(&pf_sql, &pf_params).await?);
+        }
+
+        for field in &self.separate_relations {
+            let alias = relation_target::<M>(field).map_or_else(
+                || self.read_db(router),
+                |(app_label, model_name)| router.db_for_read(&app_label, &model_name),
+            );
+            let related_db = connections.get(alias.as_str()).copied().ok_or_else(|| {
+                DjangoError::DatabaseError(format!(
+                    "No connection for database '{alias}' to load '{}'",
+                    field.field_name
+                ))
+            })?;
+
+            let mut keys: Vec<Value> = Vec::new();
+            for row in &rows {
+                let key: Value = row.get(&field.fk_column)?;
+                if key != Value::Null && !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            let related_compiler = SqlCompiler::new(related_db.backend_type());
+            let lookup = PrefetchRelatedField {
+                field_name: field.field_name.clone(),
+                related_table: field.related_table.clone(),
+                source_column: field.fk_column.clone(),
+                related_column: field.related_column.clone(),
+            };
+            let related_rows = match related_compiler
+                .compile_prefetch_queries(&[lookup], &keys)
+                .pop()
+            {
+                Some((_, rel_sql, rel_params)) => {
+                    let rel_sql = self.comment.apply(rel_sql, related_db.backend_type());
+                    related_db.query(&rel_sql, &rel_params).await?
+                }
+                None => Vec::new(),
+            };
+            related_cache.insert(field.field_name.clone(), related_rows);
+        }
+
+        Ok((models, related_cache))
+    }
+
     /// Sets the inheritance type on the underlying query.
     ///
     /// This configures how the SQL compiler generates queries for models
@@ -717,6 +1595,24 @@ impl<M: Model> PrefetchResult<M> {
     }
 }
 
+/// Returns the `(app_label, model_name)` a `select_related` field of `M`
+/// points to, if it is a declared `ForeignKey` or `OneToOneField`.
+fn relation_target<M: Model>(field: &SelectRelatedField) -> Option<(String, String)> {
+    let def = M::meta()
+        .fields
+        .iter()
+        .find(|f| f.name == field.field_name || f.column == field.fk_column)?;
+    let (FieldType::ForeignKey { to, .. } | FieldType::OneToOneField { to, .. }) = &def.field_type
+    else {
+        return None;
+    };
+    Some(match to.split_once('.') {
+        _ if to == "self" => (M::app_label().to_string(), M::meta().model_name.to_string()),
+        Some((app_label, model)) => (app_label.to_string(), model.to_lowercase()),
+        None => (M::app_label().to_string(), to.to_lowercase()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -753,6 +1649,7 @@ mod tests {
                 ],
                 constraints: vec![],
                 inheritance_type: crate::query::compiler::InheritanceType::None,
+                db_schema: None,
             });
             &META
         }
@@ -790,6 +1687,57 @@ mod tests {
         }
     }
 
+    // A second model for subquery tests
+    struct Post {
+        id: i64,
+    }
+
+    impl Model for Post {
+        fn meta() -> &'static ModelMeta {
+            use std::sync::LazyLock;
+            static META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+                app_label: "blog",
+                model_name: "post",
+                db_table: "blog_post".to_string(),
+                verbose_name: "post".to_string(),
+                verbose_name_plural: "posts".to_string(),
+                ordering: vec![],
+                unique_together: vec![],
+                indexes: vec![],
+                abstract_model: false,
+                fields: vec![
+                    FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                    FieldDef::new("author_id", FieldType::BigIntegerField),
+                    FieldDef::new("title", FieldType::CharField).max_length(100),
+                ],
+                constraints: vec![],
+                inheritance_type: crate::query::compiler::InheritanceType::None,
+                db_schema: None,
+            });
+            &META
+        }
+        fn table_name() -> &'static str {
+            "blog_post"
+        }
+        fn app_label() -> &'static str {
+            "blog"
+        }
+        fn pk(&self) -> Option<&Value> {
+            None
+        }
+        fn set_pk(&mut self, value: Value) {
+            if let Value::Int(id) = value {
+                self.id = id;
+            }
+        }
+        fn field_values(&self) -> Vec<(&'static str, Value)> {
+            vec![("id", Value::Int(self.id))]
+        }
+        fn from_row(row: &Row) -> Result<Self, django_rs_core::DjangoError> {
+            Ok(Post { id: row.get("id")? })
+        }
+    }
+
     fn pg() -> DatabaseBackendType {
         DatabaseBackendType::PostgreSQL
     }
@@ -901,6 +1849,113 @@ mod tests {
         assert!(sql.contains("LIMIT 1"));
     }
 
+    #[test]
+    fn test_queryset_dates_sql() {
+        let mgr = Manager::<User>::new();
+        let qs = mgr
+            .all()
+            .filter(Q::filter("age", Lookup::Gt(Value::from(18))))
+            .order_by(vec![OrderBy::asc("name")]);
+        let (sql, params) = qs.dates_sql("joined", DateKind::Month, true, pg());
+        assert_eq!(
+            sql,
+            "SELECT DISTINCT \"datefield\" FROM (SELECT CAST(DATE_TRUNC('month', \"joined\") \
+             AS DATE) AS \"datefield\" FROM \"auth_user\" WHERE (\"age\" > $1 AND \"joined\" \
+             IS NOT NULL)) AS \"periods\" ORDER BY \"datefield\" DESC"
+        );
+        assert_eq!(params.len(), 1);
+
+        let offset = FixedOffset::east_opt(3600);
+        let (sql, _) = qs.datetimes_sql(
+            "joined",
+            DateTimeKind::Hour,
+            false,
+            offset,
+            DatabaseBackendType::SQLite,
+        );
+        assert!(
+            sql.contains("(SELECT STRFTIME('%Y-%m-%d %H:00:00'"),
+            "{sql}"
+        );
+        assert!(sql.contains("'+3600 seconds'"), "{sql}");
+        assert!(sql.ends_with("ORDER BY \"datefield\" ASC"), "{sql}");
+
+        // An annotated date field is kept, and annotations don't split the
+        // distinct dates.
+        let qs = mgr
+            .all()
+            .annotate(
+                "first_seen",
+                Expression::func("MIN", vec![Expression::col("joined")]),
+            )
+            .annotate(
+                "name_length",
+                Expression::func("LENGTH", vec![Expression::col("name")]),
+            )
+            .filter(Q::filter("name_length", Lookup::Gt(Value::from(3))));
+        let (sql, _) = qs.dates_sql("first_seen", DateKind::Year, false, pg());
+        assert!(
+            sql.starts_with("SELECT DISTINCT \"datefield\" FROM (SELECT"),
+            "{sql}"
+        );
+        assert!(sql.contains("MIN(\"joined\") AS \"first_seen\""), "{sql}");
+        assert!(sql.contains("LENGTH(\"name\") AS \"name_length\""), "{sql}");
+    }
+
+    #[test]
+    fn test_count_distinct_keeps_annotations() {
+        let mgr = Manager::<User>::new();
+        let qs = mgr
+            .all()
+            .values(vec!["name"])
+            .annotate(
+                "name_length",
+                Expression::func("LENGTH", vec![Expression::col("name")]),
+            )
+            .distinct();
+        let (sql, _) = qs.count_sql(pg());
+        assert_eq!(
+            sql,
+            "SELECT COUNT(*) AS \"count\" FROM (SELECT DISTINCT \"name\", \
+             LENGTH(\"name\") AS \"name_length\" FROM \"auth_user\") AS \"subquery\""
+        );
+    }
+
+    #[test]
+    fn test_count_and_exists_strip_unneeded_clauses() {
+        let mgr = Manager::<User>::new();
+        let qs = mgr
+            .all()
+            .annotate(
+                "name_length",
+                Expression::func("LENGTH", vec![Expression::col("name")]),
+            )
+            .annotate(
+                "upper_name",
+                Expression::func("UPPER", vec![Expression::col("name")]),
+            )
+            .filter(Q::filter("name_length", Lookup::Gt(Value::from(3))))
+            .select_related_with(vec![SelectRelatedField {
+                field_name: "profile".to_string(),
+                related_table: "auth_profile".to_string(),
+                fk_column: "profile_id".to_string(),
+                related_column: "id".to_string(),
+                alias: "profile".to_string(),
+            }])
+            .order_by(vec![OrderBy::desc("age")]);
+
+        for (sql, _) in [qs.count_sql(pg()), qs.exists_sql(pg())] {
+            assert!(sql.contains("LENGTH(\"name\") AS \"name_length\""), "{sql}");
+            assert!(sql.contains("WHERE \"name_length\" > $"), "{sql}");
+            assert!(!sql.contains("UPPER"), "{sql}");
+            assert!(!sql.contains("JOIN"), "{sql}");
+            assert!(!sql.contains("ORDER BY"), "{sql}");
+        }
+        // The full query is unchanged.
+        let (sql, _) = qs.to_sql(pg());
+        assert!(sql.contains("UPPER") && sql.contains("LEFT JOIN") && sql.contains("ORDER BY"));
+    }
+
     #[test]
     fn test_queryset_first_sql() {
         let mgr = Manager::<User>::new();
@@ -980,6 +2035,48 @@ mod tests {
         assert!(sql.contains("UPPER(\"name\") AS \"name_upper\""));
     }
 
+    #[test]
+    fn test_queryset_annotate_name_collisions() {
+        let mgr = Manager::<User>::new();
+        let qs = mgr.all().annotate("age", Expression::value(1));
+        assert!(!qs.query().annotations.contains_key("age"));
+        let Err(err) = qs.check_built() else {
+            panic!("annotation named after a field should be rejected");
+        };
+        assert!(err.to_string().contains("conflicts with a field"));
+
+        let annotated = || mgr.all().annotate("n", Expression::value(1));
+        assert!(annotated().check_built().is_ok());
+        assert!(annotated()
+            .annotate("n", Expression::value(2))
+            .check_built()
+            .is_err());
+        assert!(annotated()
+            .alias("n", Expression::value(2))
+            .check_built()
+            .is_err());
+        assert!(mgr
+            .all()
+            .alias("name", Expression::value(1))
+            .check_built()
+            .is_err());
+    }
+
+    #[test]
+    fn test_queryset_alias_filters_and_orders_without_selecting() {
+        let mgr = Manager::<User>::new();
+        let qs = mgr
+            .all()
+            .alias("double_age", Expression::col("age") * Expression::value(2))
+            .filter(Q::filter("double_age", Lookup::Gt(Value::from(40))))
+            .order_by(vec![OrderBy::desc("double_age")]);
+        let (sql, params) = qs.to_sql(pg());
+        assert!(!sql.contains("AS \"double_age\""));
+        assert!(sql.contains("WHERE (\"age\" * $1) > $2"));
+        assert!(sql.contains("ORDER BY (\"age\" * $3) DESC"));
+        assert_eq!(params.len(), 3);
+    }
+
     #[test]
     fn test_queryset_aggregate_sql() {
         let mgr = Manager::<User>::new();
@@ -1352,4 +2449,188 @@ mod tests {
         assert_eq!(result.len(), 0);
         assert!(result.is_empty());
     }
+
+    // ── Query comments ───────────────────────────────────────────────
+
+    #[test]
+    fn test_comment_appended_to_select() {
+        let qs = Manager::<User>::new()
+            .filter(Q::filter("age", Lookup::Gt(Value::from(18))))
+            .comment("controller=UserList");
+        let (sql, params) = qs.to_sql(DatabaseBackendType::PostgreSQL);
+        assert!(sql.ends_with(" /*controller='UserList'*/"), "{sql}");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_comment_on_count_and_delete() {
+        let qs = Manager::<User>::new().all().comment("job=cleanup");
+        let (count_sql, _) = qs.count_sql(DatabaseBackendType::SQLite);
+        assert!(count_sql.contains("/*job='cleanup'*/"));
+
+        let (delete_sql, _) = qs.delete().to_sql(DatabaseBackendType::SQLite);
+        assert!(delete_sql.starts_with("DELETE"));
+        assert!(delete_sql.contains("/*job='cleanup'*/"));
+    }
+
+    #[test]
+    fn test_hint_on_mysql_select() {
+        let qs = Manager::<User>::new()
+            .all()
+            .hint("INDEX(auth_user idx_age)");
+        let (sql, _) = qs.to_sql(DatabaseBackendType::MySQL);
+        assert!(
+            sql.starts_with("SELECT /*+ INDEX(auth_user idx_age) */ "),
+            "{sql}"
+        );
+        assert_eq!(qs.query_comment().hints().len(), 1);
+    }
+
+    // ── Subquery filters ─────────────────────────────────────────────
+
+    #[test]
+    fn test_filter_in_subquery_renumbers_params() {
+        let posts = QuerySet::<Post>::new(None)
+            .filter(Q::filter("title", Lookup::Exact(Value::from("Hello"))))
+            .values(vec!["author_id"]);
+        let qs = QuerySet::<User>::new(None)
+            .filter(Q::filter("age", Lookup::Gt(Value::from(30))))
+            .filter_in("id", posts)
+            .unwrap();
+        let (sql, params) = qs.to_sql(pg());
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"auth_user\" WHERE (\"age\" > $1 AND \"id\" IN \
+             (SELECT \"author_id\" FROM \"blog_post\" WHERE \"title\" = $2))"
+        );
+        assert_eq!(params, vec![Value::from(30), Value::from("Hello")]);
+
+        let (sql, _) = qs.to_sql(sqlite());
+        assert!(sql.contains("\"age\" > ? AND \"id\" IN (SELECT"));
+    }
+
+    #[test]
+    fn test_filter_in_projects_primary_key() {
+        let users = QuerySet::<User>::new(None).order_by(vec![OrderBy::asc("name")]);
+        let qs = QuerySet::<Post>::new(None)
+            .filter_in("author_id", users)
+            .unwrap();
+        let (sql, _) = qs.to_sql(pg());
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"blog_post\" WHERE \"author_id\" IN (SELECT \"id\" FROM \"auth_user\")"
+        );
+    }
+
+    #[test]
+    fn test_filter_in_rejects_multiple_columns() {
+        let users = QuerySet::<User>::new(None).values(vec!["id", "name"]);
+        assert!(QuerySet::<Post>::new(None)
+            .filter_in("author_id", users)
+            .is_err());
+
+        let annotated = QuerySet::<User>::new(None)
+            .values(vec!["id"])
+            .annotate("n", Expression::Value(Value::from(1)));
+        assert!(QuerySet::<Post>::new(None)
+            .filter_in("author_id", annotated)
+            .is_err());
+    }
+
+    #[test]
+    fn test_filter_exists_correlated() {
+        let posts = QuerySet::<Post>::new(None)
+            .filter_outer_ref("author_id", OuterRef::new("id"))
+            .filter(Q::filter("title", Lookup::Exact(Value::from("Hello"))));
+        let qs = QuerySet::<User>::new(None)
+            .filter(Q::filter("name", Lookup::Exact(Value::from("Alice"))))
+            .filter_exists(posts);
+        let (sql, params) = qs.to_sql(pg());
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"auth_user\" WHERE (\"name\" = $1 AND EXISTS \
+             (SELECT 1 AS \"__exists__\" FROM \"blog_post\" WHERE \
+             (\"blog_post\".\"author_id\" = \"auth_user\".\"id\" AND \"title\" = $2)))"
+        );
+        assert_eq!(params, vec![Value::from("Alice"), Value::from("Hello")]);
+    }
+
+    #[test]
+    fn test_filter_in_correlated() {
+        let posts = QuerySet::<Post>::new(None)
+            .filter_outer_ref("author_id", OuterRef::new("id"))
+            .values(vec!["id"]);
+        let qs = QuerySet::<User>::new(None).filter_in("id", posts).unwrap();
+        let (sql, _) = qs.to_sql(pg());
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"auth_user\" WHERE \"id\" IN (SELECT \"id\" FROM \"blog_post\" \
+             WHERE \"blog_post\".\"author_id\" = \"auth_user\".\"id\")"
+        );
+    }
+
+    #[test]
+    fn test_exclude_exists() {
+        let posts = QuerySet::<Post>::new(None).filter_outer_ref("author_id", OuterRef::new("id"));
+        let (sql, _) = QuerySet::<User>::new(None)
+            .exclude_exists(posts)
+            .to_sql(sqlite());
+        assert!(sql.contains("WHERE NOT EXISTS (SELECT 1"));
+    }
+
+    struct AuthRouter;
+
+    impl crate::router::DatabaseRouter for AuthRouter {
+        fn db_for_read(&self, app_label: &str, _model_name: &str) -> Option<String> {
+            (app_label == "auth").then(|| "users".to_string())
+        }
+    }
+
+    #[test]
+    fn test_check_databases_rejects_cross_db_subquery() {
+        let mut router = RouterChain::new();
+        router.add_router(Box::new(AuthRouter));
+
+        let users = QuerySet::<User>::new(None).values(vec!["id"]);
+        let qs = QuerySet::<Post>::new(None)
+            .filter_in("author_id", users)
+            .unwrap();
+        let err = qs.check_databases(&router).unwrap_err().to_string();
+        assert!(err.contains("'auth.user' (database 'users')"), "{err}");
+        assert!(err.contains("'blog.post' (database 'default')"), "{err}");
+
+        // Pinning both sides to one database is fine.
+        let users = QuerySet::<User>::new(None).using("users");
+        let qs = QuerySet::<Post>::new(None)
+            .using("users")
+            .filter_exists(users);
+        assert!(qs.check_databases(&router).is_ok());
+        assert!(QuerySet::<Post>::new(None)
+            .check_databases(&RouterChain::new())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_separate_query_moves_relation_out_of_join() {
+        let relation = |name: &str| crate::query::compiler::SelectRelatedField {
+            field_name: name.to_string(),
+            related_table: "auth_user".to_string(),
+            fk_column: format!("{name}_id"),
+            related_column: "id".to_string(),
+            alias: name.to_string(),
+        };
+        let qs = QuerySet::<Post>::new(None)
+            .select_related_with(vec![relation("author")])
+            .separate_query(vec!["author", "editor"])
+            .select_related_with(vec![relation("editor"), relation("reviewer")]);
+        let names: Vec<_> = qs
+            .separate_relations()
+            .iter()
+            .map(|f| f.field_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["author", "editor"]);
+        let (sql, _) = qs.to_sql(pg());
+        assert!(sql.contains("AS \"reviewer\""), "{sql}");
+        assert!(!sql.contains("AS \"author\""), "{sql}");
+    }
 }
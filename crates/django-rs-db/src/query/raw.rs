@@ -176,6 +176,17 @@ impl RawSql {
         db.query(&self.sql, &self.params).await
     }
 
+    /// Executes the SQL as a query and deserializes each row into `T`.
+    ///
+    /// See [`Row::deserialize`] for how columns map to `T`.
+    pub async fn fetch_as<T: serde::de::DeserializeOwned>(
+        &self,
+        db: &dyn DbExecutor,
+    ) -> DjangoResult<Vec<T>> {
+        let rows = db.query(&self.sql, &self.params).await?;
+        rows.iter().map(Row::deserialize).collect()
+    }
+
     /// Executes the SQL as a query and returns the first row, or None.
     pub async fn fetch_one(&self, db: &dyn DbExecutor) -> DjangoResult<Option<Row>> {
         let rows = db.query(&self.sql, &self.params).await?;
@@ -258,6 +269,7 @@ mod tests {
                 ],
                 constraints: vec![],
                 inheritance_type: crate::query::compiler::InheritanceType::None,
+                db_schema: None,
             });
             &META
         }
@@ -0,0 +1,466 @@
+//! Hierarchical (tree) models stored as adjacency lists.
+//!
+//! Categories, org charts and threaded comments are usually modelled with a
+//! nullable self-referencing `parent` foreign key. [`TreeModel`] adds tree
+//! navigation on top of such a model: parent, children and siblings
+//! accessors, whole-branch [`ancestors`](TreeModel::ancestors) and
+//! [`descendants`](TreeModel::descendants) queries, and subtree moves.
+//!
+//! Branch queries run as a single `WITH RECURSIVE` common table expression,
+//! compiled per backend by [`TreeQuery`]. Descendants come back in depth-first
+//! order, with siblings sorted by the model's [`order_field`](TreeModel::order_field)
+//! (or primary key), which is also the order to display an indented tree in.
+//!
+//! Because nodes only store their parent, moving a node moves its whole
+//! subtree with it; no other rows need rewriting.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::query::compiler::DatabaseBackendType;
+//! use django_rs_db::query::tree::TreeQuery;
+//! use django_rs_db::value::Value;
+//!
+//! let tree = TreeQuery::new("shop_category", "id", "parent_id").order_field("position");
+//! let (sql, params) = tree.ancestors(&Value::Int(7), DatabaseBackendType::PostgreSQL);
+//! assert!(sql.starts_with("WITH RECURSIVE \"tree_cte\" AS ("));
+//! assert_eq!(params, vec![Value::Int(7)]);
+//! ```
+
+use async_trait::async_trait;
+use django_rs_core::{DjangoError, DjangoResult};
+
+use crate::executor::{refresh_model, DbExecutor};
+use crate::model::Model;
+use crate::query::compiler::{DatabaseBackendType, OrderBy, Query, SqlCompiler, WhereNode};
+use crate::query::lookups::Lookup;
+use crate::transactions::atomic;
+use crate::value::Value;
+
+/// Name of the recursive CTE in generated queries.
+const CTE: &str = "tree_cte";
+
+/// Column holding a node's depth in [`TreeQuery::descendants`] results.
+pub const DEPTH_COLUMN: &str = "tree_depth";
+
+/// Recursion limit guarding against cycles in corrupt data.
+pub const MAX_TREE_DEPTH: usize = 1000;
+
+/// Width that sibling sort keys are zero-padded to in the ordering path,
+/// enough for any `i64`.
+const SORT_KEY_WIDTH: usize = 20;
+
+/// Compiles recursive tree queries for one adjacency-list table.
+#[derive(Debug, Clone)]
+pub struct TreeQuery {
+    table: String,
+    pk_field: String,
+    parent_field: String,
+    order_field: Option<String>,
+}
+
+impl TreeQuery {
+    /// Creates a tree query over `table`, whose rows point to their parent
+    /// through `parent_field`.
+    pub fn new(
+        table: impl Into<String>,
+        pk_field: impl Into<String>,
+        parent_field: impl Into<String>,
+    ) -> Self {
+        Self {
+            table: table.into(),
+            pk_field: pk_field.into(),
+            parent_field: parent_field.into(),
+            order_field: None,
+        }
+    }
+
+    /// Sorts siblings by `field` (then by primary key) instead of by primary
+    /// key alone. The field must hold non-negative integers.
+    #[must_use]
+    pub fn order_field(mut self, field: impl Into<String>) -> Self {
+        self.order_field = Some(field.into());
+        self
+    }
+
+    /// Compiles a query for the ancestors of the node `pk`, root first.
+    pub fn ancestors(&self, pk: &Value, backend: DatabaseBackendType) -> (String, Vec<Value>) {
+        let table = backend.quote_table_name(&self.table);
+        let (pk_col, parent_col) = (&self.pk_field, &self.parent_field);
+        let ph = SqlCompiler::new(backend).placeholder(1);
+        let sql = format!(
+            "WITH RECURSIVE \"{CTE}\" AS (\
+             SELECT \"t\".*, 0 AS \"{DEPTH_COLUMN}\" FROM {table} AS \"t\" \
+             WHERE \"t\".\"{pk_col}\" = {ph} \
+             UNION ALL \
+             SELECT \"t\".*, \"{CTE}\".\"{DEPTH_COLUMN}\" + 1 FROM {table} AS \"t\" \
+             INNER JOIN \"{CTE}\" ON \"t\".\"{pk_col}\" = \"{CTE}\".\"{parent_col}\" \
+             WHERE \"{CTE}\".\"{DEPTH_COLUMN}\" < {MAX_TREE_DEPTH}) \
+             SELECT * FROM \"{CTE}\" WHERE \"{DEPTH_COLUMN}\" > 0 \
+             ORDER BY \"{DEPTH_COLUMN}\" DESC"
+        );
+        (sql, vec![pk.clone()])
+    }
+
+    /// Compiles a query for the descendants of the node `root` in depth-first
+    /// order, or for the whole forest when `root` is `None`.
+    ///
+    /// Each row carries its [`DEPTH_COLUMN`]: children of `root` have depth 1;
+    /// in the whole forest, root nodes have depth 0.
+    pub fn descendants(
+        &self,
+        root: Option<&Value>,
+        backend: DatabaseBackendType,
+    ) -> (String, Vec<Value>) {
+        let table = backend.quote_table_name(&self.table);
+        let (pk_col, parent_col) = (&self.pk_field, &self.parent_field);
+        let (anchor_filter, params, depth) = match root {
+            Some(pk) => (
+                format!("= {}", SqlCompiler::new(backend).placeholder(1)),
+                vec![pk.clone()],
+                1,
+            ),
+            None => ("IS NULL".to_string(), Vec::new(), 0),
+        };
+        let key = self.sort_key(backend);
+        let (anchor_path, step_path) = match backend {
+            DatabaseBackendType::MySQL => (
+                format!("CAST({key} AS CHAR(10000))"),
+                format!("CONCAT(\"{CTE}\".\"tree_path\", '/', {key})"),
+            ),
+            DatabaseBackendType::PostgreSQL | DatabaseBackendType::SQLite => (
+                key.clone(),
+                format!("\"{CTE}\".\"tree_path\" || '/' || {key}"),
+            ),
+        };
+        let sql = format!(
+            "WITH RECURSIVE \"{CTE}\" AS (\
+             SELECT \"t\".*, {depth} AS \"{DEPTH_COLUMN}\", {anchor_path} AS \"tree_path\" \
+             FROM {table} AS \"t\" WHERE \"t\".\"{parent_col}\" {anchor_filter} \
+             UNION ALL \
+             SELECT \"t\".*, \"{CTE}\".\"{DEPTH_COLUMN}\" + 1, {step_path} \
+             FROM {table} AS \"t\" \
+             INNER JOIN \"{CTE}\" ON \"t\".\"{parent_col}\" = \"{CTE}\".\"{pk_col}\" \
+             WHERE \"{CTE}\".\"{DEPTH_COLUMN}\" < {MAX_TREE_DEPTH}) \
+             SELECT * FROM \"{CTE}\" ORDER BY \"tree_path\""
+        );
+        (sql, params)
+    }
+
+    /// Returns the SQL for a row's position among its siblings as a
+    /// zero-padded string, so that paths sort correctly as text.
+    fn sort_key(&self, backend: DatabaseBackendType) -> String {
+        let pad = |column: &str| {
+            let column = format!("\"t\".\"{column}\"");
+            match backend {
+                DatabaseBackendType::PostgreSQL => {
+                    format!("LPAD(CAST({column} AS TEXT), {SORT_KEY_WIDTH}, '0')")
+                }
+                DatabaseBackendType::MySQL => {
+                    format!("LPAD(CAST({column} AS CHAR), {SORT_KEY_WIDTH}, '0')")
+                }
+                DatabaseBackendType::SQLite => format!(
+                    "SUBSTR('{}' || {column}, -{SORT_KEY_WIDTH}, {SORT_KEY_WIDTH})",
+                    "0".repeat(SORT_KEY_WIDTH)
+                ),
+            }
+        };
+        let pk = pad(&self.pk_field);
+        match (&self.order_field, backend) {
+            (None, _) => pk,
+            (Some(order), DatabaseBackendType::MySQL) => format!("CONCAT({}, {pk})", pad(order)),
+            (Some(order), _) => format!("{} || {pk}", pad(order)),
+        }
+    }
+}
+
+/// A node of a tree listing, with its depth for indentation.
+#[derive(Debug, Clone)]
+pub struct TreeNode<M> {
+    /// The model instance.
+    pub node: M,
+    /// The node's depth; root nodes have depth 0.
+    pub depth: usize,
+}
+
+/// Tree navigation for models with a self-referencing parent field.
+///
+/// Implementing the trait only requires naming the parent field when it is
+/// not `parent_id`:
+///
+/// ```ignore
+/// impl TreeModel for Category {
+///     fn order_field() -> Option<&'static str> {
+///         Some("position")
+///     }
+/// }
+///
+/// let crumbs = category.ancestors(&db).await?;
+/// for entry in Category::tree(&db).await? {
+///     println!("{}{}", "  ".repeat(entry.depth), entry.node.name);
+/// }
+/// ```
+#[async_trait]
+pub trait TreeModel: Model + Sized {
+    /// Returns the name of the parent foreign key column.
+    fn parent_field() -> &'static str {
+        "parent_id"
+    }
+
+    /// Returns the integer column siblings are ordered by, if any.
+    ///
+    /// Without one, siblings are ordered by primary key and
+    /// [`move_to`](TreeModel::move_to) ignores positions.
+    fn order_field() -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the primary key of this node's parent, or `None` for roots.
+    fn parent_pk(&self) -> Option<Value> {
+        field_value(self, Self::parent_field()).filter(|v| *v != Value::Null)
+    }
+
+    /// Returns the [`TreeQuery`] compiling this model's branch queries.
+    fn tree_query() -> TreeQuery {
+        let query = TreeQuery::new(
+            Self::table_name(),
+            Self::pk_field_name(),
+            Self::parent_field(),
+        );
+        match Self::order_field() {
+            Some(field) => query.order_field(field),
+            None => query,
+        }
+    }
+
+    /// Fetches this node's parent.
+    async fn parent(&self, db: &dyn DbExecutor) -> DjangoResult<Option<Self>> {
+        let Some(parent) = self.parent_pk() else {
+            return Ok(None);
+        };
+        let rows = select::<Self>(db, pk_is::<Self>(&parent)).await?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// Fetches this node's direct children in sibling order.
+    async fn children(&self, db: &dyn DbExecutor) -> DjangoResult<Vec<Self>> {
+        select::<Self>(db, parent_is::<Self>(Some(&node_pk(self)?))).await
+    }
+
+    /// Fetches the other children of this node's parent in sibling order.
+    ///
+    /// The siblings of a root node are the other roots.
+    async fn siblings(&self, db: &dyn DbExecutor) -> DjangoResult<Vec<Self>> {
+        let pk = node_pk(self)?;
+        let filter = WhereNode::And(vec![
+            parent_is::<Self>(self.parent_pk().as_ref()),
+            WhereNode::Not(Box::new(pk_is::<Self>(&pk))),
+        ]);
+        select::<Self>(db, filter).await
+    }
+
+    /// Fetches this node's ancestors, from the root down to its parent.
+    async fn ancestors(&self, db: &dyn DbExecutor) -> DjangoResult<Vec<Self>> {
+        let (sql, params) = Self::tree_query().ancestors(&node_pk(self)?, db.backend_type());
+        db.query(&sql, &params)
+            .await?
+            .iter()
+            .map(Self::from_row)
+            .collect()
+    }
+
+    /// Fetches all nodes below this one in depth-first order.
+    async fn descendants(&self, db: &dyn DbExecutor) -> DjangoResult<Vec<Self>> {
+        let (sql, params) =
+            Self::tree_query().descendants(Some(&node_pk(self)?), db.backend_type());
+        db.query(&sql, &params)
+            .await?
+            .iter()
+            .map(Self::from_row)
+            .collect()
+    }
+
+    /// Fetches the whole forest in depth-first order, with each node's depth.
+    async fn tree(db: &dyn DbExecutor) -> DjangoResult<Vec<TreeNode<Self>>> {
+        let (sql, params) = Self::tree_query().descendants(None, db.backend_type());
+        db.query(&sql, &params)
+            .await?
+            .iter()
+            .map(|row| {
+                let depth: i64 = row.get(DEPTH_COLUMN)?;
+                Ok(TreeNode {
+                    node: Self::from_row(row)?,
+                    depth: usize::try_from(depth).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Moves this node, and with it its subtree, under `parent` (or to the
+    /// roots when `None`), then reloads it.
+    ///
+    /// With an [`order_field`](TreeModel::order_field), the node is placed at
+    /// index `position` among its new siblings (last when `None`) and the
+    /// siblings are renumbered from 0. The updates run in one transaction,
+    /// so a failure leaves the tree as it was.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::DatabaseError`] if this node is unsaved, or if
+    /// `parent` is the node itself or one of its descendants.
+    async fn move_to(
+        &mut self,
+        parent: Option<Value>,
+        position: Option<usize>,
+        db: &dyn DbExecutor,
+    ) -> DjangoResult<()> {
+        let pk = node_pk(self)?;
+        if let Some(parent) = &parent {
+            let descendants = self.descendants(db).await?;
+            if *parent == pk || descendants.iter().any(|d| d.pk() == Some(parent)) {
+                return Err(DjangoError::DatabaseError(format!(
+                    "Cannot move node {pk:?} into its own subtree"
+                )));
+            }
+        }
+
+        atomic(db, |txn| async move {
+            let txn: &dyn DbExecutor = txn.as_ref();
+            let compiler = SqlCompiler::new(txn.backend_type());
+            let mut fields = vec![(Self::parent_field(), parent.clone().unwrap_or(Value::Null))];
+            if let Some(order_field) = Self::order_field() {
+                let mut siblings = select::<Self>(txn, parent_is::<Self>(parent.as_ref())).await?;
+                siblings.retain(|s| s.pk() != Some(&pk));
+                let index = position.map_or(siblings.len(), |p| p.min(siblings.len()));
+                for (i, sibling) in siblings.iter().enumerate() {
+                    let order = Value::from(sibling_order(if i < index { i } else { i + 1 }));
+                    if field_value(sibling, order_field).as_ref() == Some(&order) {
+                        continue;
+                    }
+                    let sibling_pk = node_pk(sibling)?;
+                    let (sql, params) = compiler.compile_update(
+                        Self::table_name(),
+                        &[(order_field, order)],
+                        &pk_is::<Self>(&sibling_pk),
+                    );
+                    txn.execute_sql(&sql, &params).await?;
+                }
+                fields.push((order_field, Value::from(sibling_order(index))));
+            }
+
+            let (sql, params) =
+                compiler.compile_update(Self::table_name(), &fields, &pk_is::<Self>(&pk));
+            txn.execute_sql(&sql, &params).await?;
+            Ok(())
+        })
+        .await?;
+        refresh_model(self, db).await
+    }
+}
+
+/// Returns the value of field `name` on `model`.
+fn field_value<M: Model>(model: &M, name: &str) -> Option<Value> {
+    model
+        .field_values()
+        .into_iter()
+        .find_map(|(field, value)| (field == name).then_some(value))
+}
+
+/// Returns the primary key of a saved node.
+fn node_pk<M: Model>(model: &M) -> DjangoResult<Value> {
+    model.pk().cloned().ok_or_else(|| {
+        DjangoError::DatabaseError("Tree operations require a saved node".to_string())
+    })
+}
+
+/// Converts a sibling index to an order field value.
+fn sibling_order(index: usize) -> i64 {
+    i64::try_from(index).unwrap_or(i64::MAX)
+}
+
+fn pk_is<M: Model>(pk: &Value) -> WhereNode {
+    WhereNode::Condition {
+        column: M::pk_field_name().to_string(),
+        lookup: Lookup::Exact(pk.clone()),
+    }
+}
+
+fn parent_is<M: TreeModel>(parent: Option<&Value>) -> WhereNode {
+    WhereNode::Condition {
+        column: M::parent_field().to_string(),
+        lookup: parent.map_or(Lookup::IsNull(true), |pk| Lookup::Exact(pk.clone())),
+    }
+}
+
+/// Fetches the nodes matching `filter` in sibling order.
+async fn select<M: TreeModel>(db: &dyn DbExecutor, filter: WhereNode) -> DjangoResult<Vec<M>> {
+    let mut query = Query::new(M::table_name());
+    query.where_clause = Some(filter);
+    query.order_by = M::order_field()
+        .into_iter()
+        .chain([M::pk_field_name()])
+        .map(OrderBy::asc)
+        .collect();
+    let (sql, params) = SqlCompiler::new(db.backend_type()).compile_select(&query);
+    db.query(&sql, &params)
+        .await?
+        .iter()
+        .map(M::from_row)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category_tree() -> TreeQuery {
+        TreeQuery::new("shop_category", "id", "parent_id")
+    }
+
+    #[test]
+    fn test_ancestors_sql() {
+        let (sql, params) =
+            category_tree().ancestors(&Value::Int(5), DatabaseBackendType::PostgreSQL);
+        assert_eq!(
+            sql,
+            "WITH RECURSIVE \"tree_cte\" AS (\
+             SELECT \"t\".*, 0 AS \"tree_depth\" FROM \"shop_category\" AS \"t\" \
+             WHERE \"t\".\"id\" = $1 \
+             UNION ALL \
+             SELECT \"t\".*, \"tree_cte\".\"tree_depth\" + 1 FROM \"shop_category\" AS \"t\" \
+             INNER JOIN \"tree_cte\" ON \"t\".\"id\" = \"tree_cte\".\"parent_id\" \
+             WHERE \"tree_cte\".\"tree_depth\" < 1000) \
+             SELECT * FROM \"tree_cte\" WHERE \"tree_depth\" > 0 ORDER BY \"tree_depth\" DESC"
+        );
+        assert_eq!(params, vec![Value::Int(5)]);
+    }
+
+    #[test]
+    fn test_descendants_sql_per_backend() {
+        let tree = category_tree().order_field("position");
+
+        let (sql, params) = tree.descendants(Some(&Value::Int(1)), DatabaseBackendType::PostgreSQL);
+        assert!(sql.contains("1 AS \"tree_depth\""));
+        assert!(sql.contains("WHERE \"t\".\"parent_id\" = $1 UNION ALL"));
+        assert!(sql.contains(
+            "LPAD(CAST(\"t\".\"position\" AS TEXT), 20, '0') || \
+             LPAD(CAST(\"t\".\"id\" AS TEXT), 20, '0') AS \"tree_path\""
+        ));
+        assert!(sql.ends_with("SELECT * FROM \"tree_cte\" ORDER BY \"tree_path\""));
+        assert_eq!(params, vec![Value::Int(1)]);
+
+        let (sql, _) = tree.descendants(Some(&Value::Int(1)), DatabaseBackendType::MySQL);
+        assert!(sql.contains("AS \"tree_path\""));
+        assert!(sql.contains("CAST(CONCAT(LPAD(CAST(\"t\".\"position\" AS CHAR), 20, '0')"));
+        assert!(sql.contains("CONCAT(\"tree_cte\".\"tree_path\", '/', CONCAT("));
+        assert!(sql.contains("\"parent_id\" = ?"));
+
+        let (sql, params) = category_tree().descendants(None, DatabaseBackendType::SQLite);
+        assert!(sql.contains("0 AS \"tree_depth\""));
+        assert!(sql.contains("WHERE \"t\".\"parent_id\" IS NULL UNION ALL"));
+        assert!(sql.contains(
+            "\"tree_cte\".\"tree_path\" || '/' || SUBSTR('00000000000000000000' || \"t\".\"id\", -20, 20)"
+        ));
+        assert!(params.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+//! The registry of installed models.
+//!
+//! Management commands that work on every model — `check`, `dumpdata`,
+//! `loaddata` — find them in [`MODELS`]. Each app registers its models once
+//! at startup, typically from
+//! [`AppConfig::ready`](django_rs_core::apps::AppConfig::ready):
+//!
+//! ```
+//! use django_rs_core::apps::AppConfig;
+//! # use django_rs_db::model::{Model, ModelMeta};
+//! # use django_rs_db::query::compiler::Row;
+//! # use django_rs_db::value::Value;
+//! # struct Post;
+//! # impl Model for Post {
+//! #     fn meta() -> &'static ModelMeta { unimplemented!() }
+//! #     fn table_name() -> &'static str { "blog_post" }
+//! #     fn app_label() -> &'static str { "blog" }
+//! #     fn pk(&self) -> Option<&Value> { None }
+//! #     fn set_pk(&mut self, _: Value) {}
+//! #     fn field_values(&self) -> Vec<(&'static str, Value)> { vec![] }
+//! #     fn from_row(_: &Row) -> Result<Self, django_rs_core::DjangoError> { Ok(Self) }
+//! # }
+//!
+//! struct BlogConfig;
+//!
+//! impl AppConfig for BlogConfig {
+//!     fn name(&self) -> &str {
+//!         "blog"
+//!     }
+//!
+//!     fn ready(&self) {
+//!         django_rs_db::registry::MODELS.register::<Post>();
+//!     }
+//! }
+//! ```
+//!
+//! Tests build their own [`ModelRegistry`] instead, so the models one test
+//! registers are not seen by the others.
+
+use std::sync::{PoisonError, RwLock};
+
+use crate::model::{Model, ModelMeta};
+
+/// The models registered by the installed apps.
+pub static MODELS: ModelRegistry = ModelRegistry::new();
+
+/// A set of models, in registration order.
+#[derive(Default)]
+pub struct ModelRegistry {
+    models: RwLock<Vec<&'static ModelMeta>>,
+}
+
+impl ModelRegistry {
+    /// Creates an empty registry.
+    pub const fn new() -> Self {
+        Self {
+            models: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Registers a model. Registering a model twice has no effect.
+    pub fn register<M: Model>(&self) {
+        self.register_meta(M::meta());
+    }
+
+    /// Registers a model by its metadata.
+    pub fn register_meta(&self, meta: &'static ModelMeta) {
+        let mut models = self.models.write().unwrap_or_else(PoisonError::into_inner);
+        if !models.iter().any(|m| std::ptr::eq(*m, meta)) {
+            models.push(meta);
+        }
+    }
+
+    /// Returns the registered models, in registration order.
+    pub fn models(&self) -> Vec<&'static ModelMeta> {
+        self.models
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the model labelled `app_label.model_name`, matching the model
+    /// name case-insensitively.
+    pub fn get_model(&self, app_label: &str, model_name: &str) -> Option<&'static ModelMeta> {
+        self.find(|m| m.app_label == app_label && m.model_name.eq_ignore_ascii_case(model_name))
+    }
+
+    /// Returns the model a relation's `to` refers to: an
+    /// `app_label.model_name` label, matched case-insensitively, or a table
+    /// name.
+    pub fn resolve(&self, to: &str) -> Option<&'static ModelMeta> {
+        self.find(|m| {
+            format!("{}.{}", m.app_label, m.model_name).eq_ignore_ascii_case(to)
+                || m.db_table.eq_ignore_ascii_case(to)
+        })
+    }
+
+    /// Returns the model stored in `table`.
+    pub fn get_by_table(&self, table: &str) -> Option<&'static ModelMeta> {
+        self.find(|m| !m.abstract_model && m.db_table == table)
+    }
+
+    /// Returns the concrete models of `app_label`, in registration order.
+    pub fn app_models(&self, app_label: &str) -> Vec<&'static ModelMeta> {
+        self.models()
+            .into_iter()
+            .filter(|m| !m.abstract_model && m.app_label == app_label)
+            .collect()
+    }
+
+    fn find(&self, pred: impl Fn(&ModelMeta) -> bool) -> Option<&'static ModelMeta> {
+        self.models
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .copied()
+            .find(|m| pred(m))
+    }
+}
+
+impl std::fmt::Debug for ModelRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let labels: Vec<String> = self
+            .models()
+            .iter()
+            .map(|m| format!("{}.{}", m.app_label, m.model_name))
+            .collect();
+        f.debug_struct("ModelRegistry")
+            .field("models", &labels)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::{FieldDef, FieldType};
+    use crate::query::compiler::InheritanceType;
+    use std::sync::LazyLock;
+
+    static CODE: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+        app_label: "geo_data",
+        model_name: "country",
+        db_table: "geo_data_country".to_string(),
+        verbose_name: "country".to_string(),
+        verbose_name_plural: "countries".to_string(),
+        ordering: vec![],
+        unique_together: vec![],
+        indexes: vec![],
+        abstract_model: false,
+        fields: vec![FieldDef::new("code", FieldType::CharField)
+            .max_length(2)
+            .primary_key()],
+        constraints: vec![],
+        inheritance_type: InheritanceType::None,
+        db_schema: None,
+    });
+
+    #[test]
+    fn test_model_registry_lookups() {
+        let registry = ModelRegistry::new();
+        registry.register_meta(&CODE);
+        registry.register_meta(&CODE);
+
+        assert_eq!(registry.models().len(), 1);
+        let meta = registry.get_model("geo_data", "Country").unwrap();
+        assert_eq!(meta.pk_column(), "code");
+        assert!(registry.get_by_table("geo_data_country").is_some());
+        assert!(registry.get_by_table("geo_data_city").is_none());
+        assert!(registry.resolve("geo_data.Country").is_some());
+        assert_eq!(registry.app_models("geo_data").len(), 1);
+        assert!(registry.app_models("geo").is_empty());
+        assert!(MODELS.get_model("geo_data", "country").is_none());
+    }
+}
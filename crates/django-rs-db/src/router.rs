@@ -31,8 +31,58 @@
 //! assert_eq!(chain.db_for_read("blog", "article"), "replica");
 //! assert_eq!(chain.db_for_write("blog", "article"), "default");
 //! ```
+//!
+//! ## Read-your-writes pinning
+//!
+//! A replica lags behind the primary, so a read right after a write may not
+//! see it. Inside a [`PrimaryPin`] scope, a write through the ORM or a write
+//! routed with [`RouterChain::db_for_write`] pins the scope, and
+//! from then on reads are routed like writes, to the primary. Web requests get
+//! a scope from `ReadYourWritesMiddleware`, which also keeps the following
+//! requests of the same client pinned for a while, so a form that saves and
+//! redirects shows the saved data.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use django_rs_db::router::{DatabaseRouter, PrimaryPin, RouterChain};
+//!
+//! struct ReadReplicaRouter;
+//!
+//! impl DatabaseRouter for ReadReplicaRouter {
+//!     fn db_for_read(&self, _app_label: &str, _model_name: &str) -> Option<String> {
+//!         Some("replica".to_string())
+//!     }
+//! }
+//!
+//! let mut chain = RouterChain::new();
+//! chain.add_router(Box::new(ReadReplicaRouter));
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! Arc::new(PrimaryPin::new())
+//!     .scope(async {
+//!         assert_eq!(chain.db_for_read("blog", "article"), "replica");
+//!         chain.db_for_write("blog", "article");
+//!         assert_eq!(chain.db_for_read("blog", "article"), "default");
+//!     })
+//!     .await;
+//! # });
+//! ```
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// The routers installed at startup, mirroring Django's `DATABASE_ROUTERS`.
+///
+/// System checks consult it to find relations between models routed to
+/// different databases.
+pub static ROUTER: RwLock<RouterChain> = RwLock::new(RouterChain::new());
+
+tokio::task_local! {
+    static CURRENT_PIN: Arc<PrimaryPin>;
+}
 
 /// Trait for database routers.
 ///
@@ -102,7 +152,7 @@ impl Default for RouterChain {
 
 impl RouterChain {
     /// Creates a new empty router chain.
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             routers: Vec::new(),
         }
@@ -116,7 +166,12 @@ impl RouterChain {
     /// Returns the database alias to use for read operations.
     ///
     /// Evaluates each router in order. If none returns a value, uses `"default"`.
+    /// While the current [`PrimaryPin`] is pinned, returns the write database
+    /// instead.
     pub fn db_for_read(&self, app_label: &str, model_name: &str) -> String {
+        if PrimaryPin::current().is_some_and(|pin| pin.is_pinned()) {
+            return self.write_alias(app_label, model_name);
+        }
         for router in &self.routers {
             if let Some(db) = router.db_for_read(app_label, model_name) {
                 return db;
@@ -128,7 +183,14 @@ impl RouterChain {
     /// Returns the database alias to use for write operations.
     ///
     /// Evaluates each router in order. If none returns a value, uses `"default"`.
+    /// Records the write in the current [`PrimaryPin`], if any.
     pub fn db_for_write(&self, app_label: &str, model_name: &str) -> String {
+        record_write();
+        self.write_alias(app_label, model_name)
+    }
+
+    /// Returns the alias the routers pick for writes.
+    fn write_alias(&self, app_label: &str, model_name: &str) -> String {
         for router in &self.routers {
             if let Some(db) = router.db_for_write(app_label, model_name) {
                 return db;
@@ -169,6 +231,74 @@ impl RouterChain {
     }
 }
 
+/// Read-your-writes state of a request or task.
+///
+/// While a pin is pinned, [`RouterChain::db_for_read`] sends reads made in
+/// its [`scope`](Self::scope) to the write database. A pin becomes pinned
+/// when a write is routed in its scope, or is created pinned with
+/// [`pinned`](Self::pinned) when the work must see earlier writes, e.g. a
+/// request shortly after one that saved.
+///
+/// The pin is task-local: work moved to another task with `tokio::spawn`
+/// must be scoped again.
+#[derive(Debug, Default)]
+pub struct PrimaryPin {
+    pinned: AtomicBool,
+    written: AtomicBool,
+}
+
+impl PrimaryPin {
+    /// Creates a pin that is not pinned yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a pin that is pinned from the start.
+    pub fn pinned() -> Self {
+        Self {
+            pinned: AtomicBool::new(true),
+            written: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the pin of the current task, if one is in scope.
+    pub fn current() -> Option<Arc<Self>> {
+        CURRENT_PIN.try_with(Arc::clone).ok()
+    }
+
+    /// Runs `future` with this pin as the current one.
+    pub fn scope<F: Future>(self: Arc<Self>, future: F) -> impl Future<Output = F::Output> {
+        CURRENT_PIN.scope(self, future)
+    }
+
+    /// Returns `true` if reads go to the write database.
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if a write was recorded.
+    pub fn has_written(&self) -> bool {
+        self.written.load(Ordering::Acquire)
+    }
+
+    /// Records a write, pinning later reads to the write database.
+    ///
+    /// [`RouterChain::db_for_write`] and the ORM's write paths (saving,
+    /// deleting, `update_exec`, the bulk operations) call this for the
+    /// current pin; code that writes with raw SQL can call it directly.
+    pub fn record_write(&self) {
+        self.written.store(true, Ordering::Release);
+        self.pinned.store(true, Ordering::Release);
+    }
+}
+
+/// Records a write in the current [`PrimaryPin`], if one is in scope.
+pub(crate) fn record_write() {
+    if let Some(pin) = PrimaryPin::current() {
+        pin.record_write();
+    }
+}
+
 /// Configuration for multiple named database connections.
 ///
 /// This is the Rust equivalent of Django's `DATABASES` setting. Each entry
@@ -386,6 +516,44 @@ mod tests {
         assert!(chain.allow_migrate("default", "blog", "article"));
     }
 
+    #[tokio::test]
+    async fn test_pin_routes_reads_to_primary_after_write() {
+        let mut chain = RouterChain::new();
+        chain.add_router(Box::new(AuthRouter));
+        chain.add_router(Box::new(ReadReplicaRouter));
+
+        let pin = Arc::new(PrimaryPin::new());
+        pin.clone()
+            .scope(async {
+                assert_eq!(chain.db_for_read("blog", "article"), "replica");
+                assert_eq!(chain.db_for_write("blog", "article"), "default");
+                assert_eq!(chain.db_for_read("blog", "article"), "default");
+                // Pinned reads follow each model's write routing.
+                assert_eq!(chain.db_for_read("auth", "user"), "auth_db");
+            })
+            .await;
+        assert!(pin.has_written());
+
+        // Outside the scope, routing is unaffected.
+        assert_eq!(chain.db_for_read("blog", "article"), "replica");
+    }
+
+    #[tokio::test]
+    async fn test_pinned_from_start() {
+        let mut chain = RouterChain::new();
+        chain.add_router(Box::new(ReadReplicaRouter));
+
+        let pin = Arc::new(PrimaryPin::pinned());
+        pin.clone()
+            .scope(async {
+                assert_eq!(chain.db_for_read("blog", "article"), "default");
+            })
+            .await;
+        assert!(pin.is_pinned());
+        assert!(!pin.has_written());
+        assert!(PrimaryPin::current().is_none());
+    }
+
     // ── DatabasesConfig tests ────────────────────────────────────────
 
     #[test]
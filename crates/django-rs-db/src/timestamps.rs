@@ -0,0 +1,211 @@
+//! Automatic `auto_now` / `auto_now_add` timestamps.
+//!
+//! Fields marked [`auto_now`](crate::fields::FieldDef::auto_now) are set to
+//! the current time on every write, and fields marked
+//! [`auto_now_add`](crate::fields::FieldDef::auto_now_add) when the row is
+//! created. Unlike Django, this applies to every write path:
+//!
+//! - [`save_model`](crate::executor::save_model) and
+//!   [`create_model`](crate::executor::create_model);
+//! - [`bulk_create`](crate::query::bulk::bulk_create) and
+//!   [`bulk_update`](crate::query::bulk::bulk_update), where `auto_now`
+//!   fields are added to the updated fields;
+//! - [`Manager::create`](crate::query::Manager::create);
+//! - [`Manager::upsert`](crate::query::Manager::upsert), where an update on
+//!   conflict leaves `auto_now_add` fields alone;
+//! - [`QuerySet::update`](crate::query::QuerySet::update), where `auto_now`
+//!   fields given explicitly keep their value.
+//!
+//! Bulk writes and queryset updates can opt out with
+//! [`BulkCreateOptions::skip_auto_now`](crate::query::bulk::BulkCreateOptions::skip_auto_now),
+//! [`BulkUpdateOptions::skip_auto_now`](crate::query::bulk::BulkUpdateOptions::skip_auto_now)
+//! and [`QuerySet::skip_auto_now`](crate::query::QuerySet::skip_auto_now),
+//! e.g. when importing rows that carry their own timestamps.
+//!
+//! Timestamps are naive UTC. `DateField`s receive the current date.
+//!
+//! # Time-stamped models
+//!
+//! `#[model(timestamped)]` on a `#[derive(Model)]` struct treats its
+//! `created_at` and `updated_at` fields as `auto_now_add` and `auto_now`, and
+//! implements [`TimeStampedModel`]:
+//!
+//! ```ignore
+//! #[derive(Model)]
+//! #[model(app = "blog", timestamped)]
+//! pub struct Post {
+//!     #[field(primary_key, auto)]
+//!     pub id: i64,
+//!     pub title: String,
+//!     pub created_at: NaiveDateTime,
+//!     pub updated_at: NaiveDateTime,
+//! }
+//! ```
+
+use chrono::{NaiveDateTime, Utc};
+
+use crate::fields::{FieldDef, FieldType};
+use crate::model::Model;
+use crate::value::Value;
+
+/// A model with `created_at` and `updated_at` timestamps maintained by the
+/// ORM.
+///
+/// Implemented by `#[derive(Model)]` for structs marked
+/// `#[model(timestamped)]`.
+pub trait TimeStampedModel: Model {
+    /// The name of the creation timestamp field.
+    const CREATED_AT_FIELD: &'static str = "created_at";
+    /// The name of the modification timestamp field.
+    const UPDATED_AT_FIELD: &'static str = "updated_at";
+
+    /// Returns when the instance was created, if it has been saved.
+    fn created_at(&self) -> Option<NaiveDateTime>;
+
+    /// Returns when the instance was last saved, if it has been saved.
+    fn updated_at(&self) -> Option<NaiveDateTime>;
+}
+
+/// Returns the current time, as stored in automatic timestamps.
+pub fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+/// Returns the value an automatic timestamp field takes at `now`.
+pub fn timestamp_value(field: &FieldDef, now: NaiveDateTime) -> Value {
+    match field.field_type {
+        FieldType::DateField => Value::Date(now.date()),
+        _ => Value::DateTime(now),
+    }
+}
+
+/// Stamps the values of a row about to be inserted: every `auto_now` and
+/// `auto_now_add` field is set to `now`, replacing any given value.
+pub fn stamp_insert(
+    fields: &[FieldDef],
+    values: &mut Vec<(&'static str, Value)>,
+    now: NaiveDateTime,
+) {
+    stamp(
+        fields.iter().filter(|f| f.auto_now || f.auto_now_add),
+        values,
+        now,
+        true,
+    );
+}
+
+/// Stamps the values of a row about to be updated: every `auto_now` field is
+/// set to `now`, replacing any given value.
+pub fn stamp_update(
+    fields: &[FieldDef],
+    values: &mut Vec<(&'static str, Value)>,
+    now: NaiveDateTime,
+) {
+    stamp(fields.iter().filter(|f| f.auto_now), values, now, true);
+}
+
+/// Adds `now` for every `auto_now` field missing from `values`, keeping
+/// explicitly given values.
+pub fn fill_auto_now(
+    fields: &[FieldDef],
+    values: &mut Vec<(&'static str, Value)>,
+    now: NaiveDateTime,
+) {
+    stamp(fields.iter().filter(|f| f.auto_now), values, now, false);
+}
+
+/// Returns the names of the `auto_now` fields.
+pub fn auto_now_fields(fields: &[FieldDef]) -> Vec<&'static str> {
+    fields
+        .iter()
+        .filter(|f| f.auto_now)
+        .map(|f| f.name)
+        .collect()
+}
+
+fn stamp<'a>(
+    fields: impl Iterator<Item = &'a FieldDef>,
+    values: &mut Vec<(&'static str, Value)>,
+    now: NaiveDateTime,
+    overwrite: bool,
+) {
+    for field in fields {
+        let value = timestamp_value(field, now);
+        match values.iter_mut().find(|(name, _)| *name == field.name) {
+            Some(entry) if overwrite => entry.1 = value,
+            Some(_) => {}
+            None => values.push((field.name, value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<FieldDef> {
+        vec![
+            FieldDef::new("title", FieldType::CharField),
+            FieldDef::new("created_at", FieldType::DateTimeField).auto_now_add(),
+            FieldDef::new("updated_at", FieldType::DateTimeField).auto_now(),
+            FieldDef::new("touched_on", FieldType::DateField).auto_now(),
+        ]
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_stamp_insert_sets_all_automatic_fields() {
+        let now = at("2024-05-01 12:00:00");
+        let mut values = vec![
+            ("title", Value::from("Hello")),
+            ("created_at", Value::DateTime(at("2000-01-01 00:00:00"))),
+        ];
+        stamp_insert(&fields(), &mut values, now);
+        assert_eq!(
+            values,
+            vec![
+                ("title", Value::from("Hello")),
+                ("created_at", Value::DateTime(now)),
+                ("updated_at", Value::DateTime(now)),
+                ("touched_on", Value::Date(now.date())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stamp_update_leaves_creation_time() {
+        let now = at("2024-05-01 12:00:00");
+        let created = Value::DateTime(at("2024-01-01 00:00:00"));
+        let mut values = vec![("created_at", created.clone())];
+        stamp_update(&fields(), &mut values, now);
+        assert_eq!(values[0], ("created_at", created));
+        assert_eq!(values[1], ("updated_at", Value::DateTime(now)));
+    }
+
+    #[test]
+    fn test_fill_auto_now_keeps_explicit_values() {
+        let now = at("2024-05-01 12:00:00");
+        let explicit = Value::DateTime(at("2023-03-03 03:03:03"));
+        let mut values = vec![("updated_at", explicit.clone())];
+        fill_auto_now(&fields(), &mut values, now);
+        assert_eq!(
+            values,
+            vec![
+                ("updated_at", explicit),
+                ("touched_on", Value::Date(now.date())),
+            ]
+        );
+        assert_eq!(auto_now_fields(&fields()), vec!["updated_at", "touched_on"]);
+    }
+
+    #[test]
+    fn test_auto_now_builders() {
+        let field = FieldDef::new("updated_at", FieldType::DateTimeField).auto_now();
+        assert!(field.auto_now && !field.auto_now_add);
+        assert!(!field.editable);
+        assert!(field.blank);
+    }
+}
@@ -14,6 +14,20 @@
 //! Nested calls to `atomic()` create savepoints rather than nested transactions,
 //! matching Django's behavior.
 //!
+//! A manager built with [`TransactionManager::without_savepoints`] skips
+//! them, like Django's `atomic(savepoint=False)`: nested blocks cost no round
+//! trips, and a failing nested block marks the whole transaction for
+//! rollback instead of undoing only its own work. This is the fast path for
+//! short request transactions, and the safe one behind poolers that don't
+//! keep savepoints across statements.
+//!
+//! Under `SERIALIZABLE` (or `REPEATABLE READ` on PostgreSQL) the database
+//! aborts transactions that conflict with concurrent ones, and the client is
+//! expected to run them again. [`atomic_retry()`] does so when the error is a
+//! serialization failure or deadlock (see [`is_serialization_failure`]),
+//! waiting a little longer before each attempt. [`retry_metrics`] counts the
+//! retries.
+//!
 //! # Examples
 //!
 //! ```
@@ -31,13 +45,20 @@ use crate::executor::DbExecutor;
 use crate::query::compiler::{DatabaseBackendType, Row};
 use crate::value::Value;
 use django_rs_core::{DjangoError, DjangoResult};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 /// Counter for generating unique savepoint names.
 static SAVEPOINT_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Number of transactions [`atomic_retry()`] ran again.
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+
+/// Number of transactions [`atomic_retry()`] gave up on.
+static RETRIES_EXHAUSTED: AtomicU64 = AtomicU64::new(0);
+
 /// Transaction isolation levels supported by the major database backends.
 ///
 /// These correspond to the standard SQL isolation levels:
@@ -140,6 +161,11 @@ pub struct TransactionManager<'a> {
     savepoints: Arc<Mutex<Vec<Savepoint>>>,
     /// Callbacks registered to run after the outermost transaction commits.
     on_commit_callbacks: Arc<Mutex<OnCommitCallbacks>>,
+    /// Whether nested blocks create savepoints.
+    use_savepoints: bool,
+    /// Set when a nested block without a savepoint rolls back, so the
+    /// outermost transaction can only be rolled back.
+    needs_rollback: Arc<AtomicBool>,
 }
 
 impl<'a> TransactionManager<'a> {
@@ -150,9 +176,39 @@ impl<'a> TransactionManager<'a> {
             depth: Arc::new(Mutex::new(0)),
             savepoints: Arc::new(Mutex::new(Vec::new())),
             on_commit_callbacks: Arc::new(Mutex::new(Vec::new())),
+            use_savepoints: true,
+            needs_rollback: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Makes nested blocks run without savepoints.
+    ///
+    /// Nested `begin`/`commit` then only track depth, and a nested
+    /// `rollback` marks the transaction so that the outermost `commit`
+    /// rolls back instead.
+    #[must_use]
+    pub const fn without_savepoints(mut self) -> Self {
+        self.use_savepoints = false;
+        self
+    }
+
+    /// Returns `true` if nested blocks create savepoints.
+    pub const fn uses_savepoints(&self) -> bool {
+        self.use_savepoints
+    }
+
+    /// Returns `true` if the transaction can only be rolled back, because a
+    /// nested block without a savepoint failed.
+    pub fn needs_rollback(&self) -> bool {
+        self.needs_rollback.load(Ordering::SeqCst)
+    }
+
+    /// Marks the transaction as needing rollback, like Django's
+    /// `transaction.set_rollback(True)`.
+    pub fn set_rollback(&self, rollback: bool) {
+        self.needs_rollback.store(rollback, Ordering::SeqCst);
+    }
+
     /// Returns the current transaction nesting depth.
     pub async fn depth(&self) -> u32 {
         *self.depth.lock().await
@@ -172,7 +228,7 @@ impl<'a> TransactionManager<'a> {
         if *depth == 0 {
             // Start a new transaction
             self.db.execute_sql("BEGIN", &[]).await?;
-        } else {
+        } else if self.use_savepoints {
             // Create a savepoint for nested transaction
             let sp = Savepoint::new();
             let sql = format!("SAVEPOINT {}", sp.name);
@@ -188,16 +244,17 @@ impl<'a> TransactionManager<'a> {
         let mut depth = self.depth.lock().await;
         if *depth == 0 {
             let backend = self.db.backend_type();
-            // For SQLite, set pragma before beginning
-            if backend == DatabaseBackendType::SQLite {
-                self.db.execute_sql(&level.set_sql(backend), &[]).await?;
+            if backend == DatabaseBackendType::PostgreSQL {
+                // PG sets the level of the current transaction
                 self.db.execute_sql("BEGIN", &[]).await?;
+                self.db.execute_sql(&level.set_sql(backend), &[]).await?;
             } else {
-                // For PG/MySQL, begin then set isolation
-                self.db.execute_sql("BEGIN", &[]).await?;
+                // SQLite's pragma and MySQL's SET TRANSACTION must come
+                // before the transaction starts
                 self.db.execute_sql(&level.set_sql(backend), &[]).await?;
+                self.db.execute_sql("BEGIN", &[]).await?;
             }
-        } else {
+        } else if self.use_savepoints {
             // Nested: savepoints inherit the outer isolation level
             let sp = Savepoint::new();
             let sql = format!("SAVEPOINT {}", sp.name);
@@ -218,6 +275,15 @@ impl<'a> TransactionManager<'a> {
         }
 
         if *depth == 1 {
+            if self.needs_rollback.swap(false, Ordering::SeqCst) {
+                self.db.execute_sql("ROLLBACK", &[]).await?;
+                *depth = 0;
+                self.on_commit_callbacks.lock().await.clear();
+                return Err(DjangoError::DatabaseError(
+                    "Transaction rolled back: a nested atomic block without a savepoint failed"
+                        .to_string(),
+                ));
+            }
             // Commit the outermost transaction
             self.db.execute_sql("COMMIT", &[]).await?;
             *depth = 0;
@@ -257,8 +323,13 @@ impl<'a> TransactionManager<'a> {
             // Rollback the entire transaction
             self.db.execute_sql("ROLLBACK", &[]).await?;
             *depth = 0;
+            self.needs_rollback.store(false, Ordering::SeqCst);
             // Clear on_commit callbacks since transaction was rolled back
             self.on_commit_callbacks.lock().await.clear();
+        } else if !self.use_savepoints {
+            // Nothing to roll back to: the outer transaction must fail.
+            self.needs_rollback.store(true, Ordering::SeqCst);
+            *depth -= 1;
         } else {
             // Rollback to savepoint
             let mut savepoints = self.savepoints.lock().await;
@@ -433,6 +504,139 @@ where
     }
 }
 
+/// Executes a closure within a transaction with a specific isolation level,
+/// running it again if the database aborts it as a serialization failure or
+/// deadlock.
+///
+/// Each attempt runs in a fresh transaction, so the closure may be called up
+/// to `max_retries + 1` times and must not have effects outside the
+/// database. The delay before the first retry is `backoff`, doubling with
+/// each further retry. Other errors, and the last serialization failure once
+/// the retries are used up, are returned as is.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::time::Duration;
+/// use django_rs_db::transactions::{atomic_retry, IsolationLevel};
+///
+/// atomic_retry(db, IsolationLevel::Serializable, 3, Duration::from_millis(10), |txn| async move {
+///     txn.execute_sql("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[]).await?;
+///     Ok(())
+/// })
+/// .await?;
+/// ```
+pub async fn atomic_retry<'a, F, Fut, T>(
+    db: &'a dyn DbExecutor,
+    level: IsolationLevel,
+    max_retries: u32,
+    backoff: Duration,
+    mut f: F,
+) -> DjangoResult<T>
+where
+    F: FnMut(Arc<TransactionManager<'a>>) -> Fut,
+    Fut: std::future::Future<Output = DjangoResult<T>>,
+{
+    let backend = db.backend_type();
+    let mut attempt = 0;
+    loop {
+        let txn = Arc::new(TransactionManager::new(db));
+        txn.begin_with_isolation(level).await?;
+
+        let error = match f(Arc::clone(&txn)).await {
+            Ok(result) => match txn.commit().await {
+                Ok(()) => return Ok(result),
+                Err(e) => e,
+            },
+            Err(e) => e,
+        };
+        // Also ends the transaction if COMMIT itself failed.
+        let _ = txn.rollback().await;
+
+        if !is_serialization_failure(backend, &error) {
+            return Err(error);
+        }
+        if attempt >= max_retries {
+            RETRIES_EXHAUSTED.fetch_add(1, Ordering::Relaxed);
+            return Err(error);
+        }
+        RETRIES.fetch_add(1, Ordering::Relaxed);
+        tokio::time::sleep(backoff.saturating_mul(2u32.saturating_pow(attempt))).await;
+        attempt += 1;
+    }
+}
+
+/// Returns `true` if `error` means the transaction was aborted because of a
+/// concurrent one, and may succeed if run again.
+///
+/// The error codes checked depend on the backend:
+/// - PostgreSQL: SQLSTATE `40001` (serialization failure) and `40P01`
+///   (deadlock detected)
+/// - MySQL: error `1213` (deadlock) and SQLSTATE `40001`
+/// - SQLite: `SQLITE_BUSY` ("database is locked")
+pub fn is_serialization_failure(backend: DatabaseBackendType, error: &DjangoError) -> bool {
+    let (DjangoError::DatabaseError(message) | DjangoError::OperationalError(message)) = error
+    else {
+        return false;
+    };
+    match backend {
+        DatabaseBackendType::PostgreSQL => {
+            message.contains("SQLSTATE 40001") || message.contains("SQLSTATE 40P01")
+        }
+        DatabaseBackendType::MySQL => {
+            message.contains("ERROR 40001 ") || message.contains("(1213)")
+        }
+        DatabaseBackendType::SQLite => message.contains("database is locked"),
+    }
+}
+
+/// Counts of transactions retried by [`atomic_retry()`] since the process
+/// started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryMetrics {
+    /// How many times a transaction was run again.
+    pub retries: u64,
+    /// How many transactions still failed after their last retry.
+    pub exhausted: u64,
+}
+
+/// Returns the current [`RetryMetrics`].
+pub fn retry_metrics() -> RetryMetrics {
+    RetryMetrics {
+        retries: RETRIES.load(Ordering::Relaxed),
+        exhausted: RETRIES_EXHAUSTED.load(Ordering::Relaxed),
+    }
+}
+
+/// Executes a closure within a transaction whose nested blocks don't create
+/// savepoints.
+///
+/// Works like [`atomic()`], but the closure's manager is built with
+/// [`TransactionManager::without_savepoints`]: if a nested block rolls back,
+/// the whole transaction is rolled back and an error is returned.
+pub async fn atomic_without_savepoints<'a, F, Fut, T>(
+    db: &'a dyn DbExecutor,
+    f: F,
+) -> DjangoResult<T>
+where
+    F: FnOnce(Arc<TransactionManager<'a>>) -> Fut,
+    Fut: std::future::Future<Output = DjangoResult<T>>,
+{
+    let txn = Arc::new(TransactionManager::new(db).without_savepoints());
+    txn.begin().await?;
+
+    match f(Arc::clone(&txn)).await {
+        Ok(result) => {
+            txn.commit().await?;
+            Ok(result)
+        }
+        Err(e) => {
+            let _ = txn.rollback().await;
+            Err(e)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,6 +648,8 @@ mod tests {
     struct MockDb {
         backend: DatabaseBackendType,
         statements: TokioMutex<Vec<String>>,
+        /// Errors returned by the next COMMITs, in order.
+        commit_errors: TokioMutex<Vec<DjangoError>>,
     }
 
     impl MockDb {
@@ -451,6 +657,14 @@ mod tests {
             Self {
                 backend,
                 statements: TokioMutex::new(Vec::new()),
+                commit_errors: TokioMutex::new(Vec::new()),
+            }
+        }
+
+        fn failing_commits(backend: DatabaseBackendType, errors: Vec<DjangoError>) -> Self {
+            Self {
+                commit_errors: TokioMutex::new(errors),
+                ..Self::new(backend)
             }
         }
 
@@ -467,6 +681,10 @@ mod tests {
 
         async fn execute_sql(&self, sql: &str, _params: &[Value]) -> DjangoResult<u64> {
             self.statements.lock().await.push(sql.to_string());
+            let mut commit_errors = self.commit_errors.lock().await;
+            if sql == "COMMIT" && !commit_errors.is_empty() {
+                return Err(commit_errors.remove(0));
+            }
             Ok(1)
         }
 
@@ -902,4 +1120,183 @@ mod tests {
         assert!(stmts[4].starts_with("RELEASE SAVEPOINT"));
         assert_eq!(stmts[5], "COMMIT");
     }
+
+    #[tokio::test]
+    async fn test_nested_blocks_without_savepoints() {
+        let db = MockDb::new(DatabaseBackendType::PostgreSQL);
+        let result = atomic_without_savepoints(&db, |txn| async move {
+            txn.begin().await?;
+            txn.execute_sql("INSERT INTO t VALUES (1)", &[]).await?;
+            txn.commit().await?;
+            Ok(())
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(
+            db.statements().await,
+            vec!["BEGIN", "INSERT INTO t VALUES (1)", "COMMIT"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_rollback_without_savepoints_fails_transaction() {
+        let db = MockDb::new(DatabaseBackendType::PostgreSQL);
+        let ran = Arc::new(AtomicUsize::new(0));
+        let ran_clone = Arc::clone(&ran);
+        let result = atomic_without_savepoints(&db, |txn| async move {
+            txn.on_commit(move || {
+                ran_clone.fetch_add(1, Ordering::SeqCst);
+            })
+            .await;
+            txn.begin().await?;
+            txn.execute_sql("INSERT INTO t VALUES (1)", &[]).await?;
+            txn.rollback().await?;
+            assert!(txn.needs_rollback());
+            Ok(())
+        })
+        .await;
+
+        assert!(matches!(result, Err(DjangoError::DatabaseError(_))));
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            db.statements().await,
+            vec!["BEGIN", "INSERT INTO t VALUES (1)", "ROLLBACK"]
+        );
+    }
+
+    fn serialization_failure() -> DjangoError {
+        DjangoError::DatabaseError(
+            "db error: ERROR: could not serialize access due to concurrent update (SQLSTATE 40001)"
+                .to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_isolation_level_mysql_is_set_before_begin() {
+        let db = MockDb::new(DatabaseBackendType::MySQL);
+        atomic_with_isolation(
+            &db,
+            IsolationLevel::ReadCommitted,
+            |_txn| async move { Ok(()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.statements().await,
+            vec![
+                "SET TRANSACTION ISOLATION LEVEL READ COMMITTED",
+                "BEGIN",
+                "COMMIT"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_serialization_failure() {
+        use DatabaseBackendType::{MySQL, PostgreSQL, SQLite};
+        let error = |message: &str| DjangoError::DatabaseError(message.to_string());
+
+        assert!(is_serialization_failure(
+            PostgreSQL,
+            &serialization_failure()
+        ));
+        assert!(is_serialization_failure(
+            PostgreSQL,
+            &error("db error: ERROR: deadlock detected (SQLSTATE 40P01)")
+        ));
+        assert!(!is_serialization_failure(
+            PostgreSQL,
+            &error("db error: ERROR: duplicate key value (SQLSTATE 23505)")
+        ));
+        assert!(is_serialization_failure(
+            MySQL,
+            &error("Server error: `ERROR 40001 (1213): Deadlock found when trying to get lock'")
+        ));
+        assert!(is_serialization_failure(
+            SQLite,
+            &DjangoError::OperationalError("database is locked".to_string())
+        ));
+        assert!(!is_serialization_failure(
+            SQLite,
+            &DjangoError::NotFound("database is locked".to_string())
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_atomic_retry_reruns_serialization_failures() {
+        let db = MockDb::failing_commits(
+            DatabaseBackendType::PostgreSQL,
+            vec![serialization_failure(), serialization_failure()],
+        );
+        let attempts = AtomicUsize::new(0);
+        let before = retry_metrics();
+
+        let result = atomic_retry(
+            &db,
+            IsolationLevel::Serializable,
+            3,
+            Duration::from_millis(1),
+            |txn| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    txn.execute_sql("UPDATE t SET a = a + 1", &[]).await?;
+                    Ok("done")
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(retry_metrics().retries >= before.retries + 2);
+        let stmts = db.statements().await;
+        assert_eq!(stmts.iter().filter(|s| *s == "ROLLBACK").count(), 2);
+        assert_eq!(stmts.last().unwrap(), "COMMIT");
+    }
+
+    #[tokio::test]
+    async fn test_atomic_retry_gives_up() {
+        let db = MockDb::failing_commits(
+            DatabaseBackendType::PostgreSQL,
+            vec![serialization_failure(), serialization_failure()],
+        );
+        let attempts = AtomicUsize::new(0);
+        let before = retry_metrics();
+
+        let result = atomic_retry(
+            &db,
+            IsolationLevel::Serializable,
+            1,
+            Duration::ZERO,
+            |_txn| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(()) }
+            },
+        )
+        .await;
+
+        assert!(is_serialization_failure(
+            DatabaseBackendType::PostgreSQL,
+            &result.unwrap_err()
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(retry_metrics().exhausted > before.exhausted);
+
+        // Other errors are not retried.
+        attempts.store(0, Ordering::SeqCst);
+        let result: DjangoResult<()> = atomic_retry(
+            &db,
+            IsolationLevel::Serializable,
+            3,
+            Duration::ZERO,
+            |_txn| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move { Err(DjangoError::IntegrityError("duplicate".to_string())) }
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(DjangoError::IntegrityError(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
 }
@@ -6,6 +6,9 @@
 
 use std::fmt;
 
+use crate::fields::FieldType;
+use crate::query::compiler::FromValue;
+
 /// A backend-agnostic representation of a database value.
 ///
 /// `Value` is the universal type used to pass data between the ORM layer
@@ -328,6 +331,88 @@ impl Value {
     }
 }
 
+// ── Custom value types ─────────────────────────────────────────────────
+
+/// A Rust type stored in a single column, converted to and from [`Value`].
+///
+/// Implementing `ValueType` lets an application's domain types (a `Money`
+/// stored as integer cents, a `Url` stored as text) be used directly as model
+/// fields, query parameters and [`Row::get`](crate::query::Row::get) targets
+/// instead of the primitives they are stored as. `#[derive(ValueType)]`
+/// implements it, along with the conversions, for newtypes and for types
+/// stored through `Display`/`FromStr`.
+///
+/// Model fields of a `ValueType` are declared with `#[field(value_type)]`,
+/// which takes the column's field type from [`field_type`](Self::field_type).
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_core::DjangoError;
+/// use django_rs_db::fields::FieldType;
+/// use django_rs_db::query::compiler::{FromValue, Row};
+/// use django_rs_db::value::{Value, ValueType};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// struct Cents(i64);
+///
+/// impl From<Cents> for Value {
+///     fn from(cents: Cents) -> Self {
+///         Value::from(cents.0)
+///     }
+/// }
+///
+/// impl FromValue for Cents {
+///     fn from_value(value: &Value) -> Result<Self, DjangoError> {
+///         i64::from_value(value).map(Cents)
+///     }
+/// }
+///
+/// impl ValueType for Cents {
+///     fn field_type() -> FieldType {
+///         i64::field_type()
+///     }
+/// }
+///
+/// let row = Row::new(vec!["price".to_string()], vec![Value::from(Cents(1999))]);
+/// assert_eq!(row.get::<Cents>("price").unwrap(), Cents(1999));
+/// assert!(matches!(Cents::field_type(), FieldType::BigIntegerField));
+/// ```
+pub trait ValueType: Into<Value> + FromValue {
+    /// Returns the field type of the column storing this type.
+    fn field_type() -> FieldType;
+}
+
+macro_rules! impl_value_type {
+    ($($ty:ty => $field_type:ident),* $(,)?) => {
+        $(
+            impl ValueType for $ty {
+                fn field_type() -> FieldType {
+                    FieldType::$field_type
+                }
+            }
+        )*
+    };
+}
+
+impl_value_type! {
+    bool => BooleanField,
+    i16 => SmallIntegerField,
+    i32 => IntegerField,
+    i64 => BigIntegerField,
+    f32 => FloatField,
+    f64 => FloatField,
+    String => TextField,
+    Vec<u8> => BinaryField,
+    chrono::NaiveDate => DateField,
+    chrono::NaiveDateTime => DateTimeField,
+    chrono::DateTime<chrono::Utc> => DateTimeField,
+    chrono::NaiveTime => TimeField,
+    chrono::Duration => DurationField,
+    uuid::Uuid => UuidField,
+    serde_json::Value => JsonField,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -567,4 +652,20 @@ mod tests {
     fn test_as_hstore_none() {
         assert_eq!(Value::Int(1).as_hstore(), None);
     }
+
+    #[test]
+    fn test_value_type_round_trip() {
+        fn round_trip<T: ValueType + Clone + PartialEq + fmt::Debug>(v: T) {
+            assert_eq!(T::from_value(&v.clone().into()).unwrap(), v);
+        }
+
+        round_trip(7_i16);
+        round_trip(1.5_f32);
+        round_trip(vec![1_u8, 2]);
+        round_trip(chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        round_trip(chrono::Duration::minutes(5));
+        round_trip(serde_json::json!({"a": 1}));
+        assert!(i16::from_value(&Value::Int(70_000)).is_err());
+        assert!(matches!(i16::field_type(), FieldType::SmallIntegerField));
+    }
 }
@@ -9,8 +9,10 @@
 
 use std::collections::HashMap;
 
+use django_rs_db::value::Value;
+
 use crate::fields::FormFieldDef;
-use crate::widgets::{self, Widget};
+use crate::widgets::{self, Widget, WidgetType};
 
 /// A form field bound to data and validation state.
 ///
@@ -26,10 +28,28 @@ pub struct BoundField {
     pub data: Option<String>,
     /// Validation error messages for this field.
     pub errors: Vec<String>,
+    /// The code of each error in `errors`, in the same order; empty for
+    /// errors raised without one.
+    pub error_codes: Vec<String>,
+    /// The initial value, shown while the form is unbound.
+    pub initial: Option<String>,
+    /// Whether the form was bound to submitted data.
+    pub is_bound: bool,
     /// The widget instance used for rendering.
     pub widget: Box<dyn Widget>,
 }
 
+/// A validation error of a [`BoundField`], with its code.
+///
+/// This is the shape of Django's `errors.get_json_data()` entries.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FieldError {
+    /// The error message.
+    pub message: String,
+    /// The error code (e.g., "required"), or empty if unknown.
+    pub code: String,
+}
+
 /// Minimal field definition snapshot stored in a `BoundField`.
 ///
 /// This avoids lifetime issues by owning copies of the relevant metadata.
@@ -45,10 +65,16 @@ pub struct BoundFieldDef {
     pub required: bool,
     /// Whether the field is disabled.
     pub disabled: bool,
+    /// Extra HTML attributes for the widget.
+    pub attrs: HashMap<String, String>,
 }
 
 impl BoundField {
     /// Creates a new `BoundField` from a field definition and current state.
+    ///
+    /// The field counts as bound when `data` is given, its initial value is
+    /// the field definition's and its errors have no codes; the builder
+    /// methods below override these.
     pub fn new(
         field_def: &FormFieldDef,
         data: Option<String>,
@@ -70,35 +96,140 @@ impl BoundField {
                 help_text: field_def.help_text.clone(),
                 required: field_def.required,
                 disabled: field_def.disabled,
+                attrs: field_def.attrs.clone(),
             },
+            is_bound: data.is_some(),
             data,
+            error_codes: vec![String::new(); errors.len()],
             errors,
+            initial: field_def.initial.as_ref().and_then(display_value),
             widget,
         }
     }
 
+    /// Sets whether the form was bound to submitted data.
+    #[must_use]
+    pub fn bound(mut self, is_bound: bool) -> Self {
+        self.is_bound = is_bound;
+        self
+    }
+
+    /// Sets the initial value, e.g. from the form's initial data.
+    #[must_use]
+    pub fn with_initial(mut self, initial: Option<&Value>) -> Self {
+        self.initial = initial.and_then(display_value);
+        self
+    }
+
+    /// Sets the codes of the errors, one per message in `errors`.
+    #[must_use]
+    pub fn with_error_codes(mut self, codes: Vec<String>) -> Self {
+        self.error_codes = codes;
+        self.error_codes.resize(self.errors.len(), String::new());
+        self
+    }
+
+    /// Returns the value the widget shows: the submitted data once the form
+    /// is bound, otherwise the initial value.
+    ///
+    /// Disabled fields always show their initial value, as their submitted
+    /// data is ignored.
+    pub fn value(&self) -> Option<String> {
+        if self.is_bound && !self.field.disabled {
+            self.data.clone()
+        } else {
+            self.initial.clone()
+        }
+    }
+
     /// Renders the widget HTML for this bound field.
     pub fn render(&self, extra_attrs: &HashMap<String, String>) -> String {
-        let mut attrs = extra_attrs.clone();
+        let attrs = self.build_attrs(extra_attrs);
+        self.widget.render(&self.name, &self.value(), &attrs)
+    }
+
+    /// Returns the HTML attributes the widget is rendered with.
+    ///
+    /// The automatic attributes (`id`, `required`, `disabled`,
+    /// `aria-invalid` when the field has errors and `aria-describedby`
+    /// pointing at [`help_text_id`](Self::help_text_id) when it has help
+    /// text) are overridden by the field's own attributes, which are in turn
+    /// overridden by `extra_attrs`. CSS classes from all three are combined.
+    pub fn build_attrs(&self, extra_attrs: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut attrs = HashMap::new();
         let id = self.auto_id();
         if !id.is_empty() {
-            attrs.entry("id".to_string()).or_insert(id);
+            attrs.insert("id".to_string(), id);
+        }
+        if self.field.required && !self.field.disabled && self.uses_required_attribute() {
+            attrs.insert("required".to_string(), "required".to_string());
         }
         if self.field.disabled {
             attrs.insert("disabled".to_string(), "disabled".to_string());
         }
-        self.widget.render(&self.name, &self.data, &attrs)
+        if self.has_errors() {
+            attrs.insert("aria-invalid".to_string(), "true".to_string());
+        }
+        if !self.field.help_text.is_empty() {
+            attrs.insert("aria-describedby".to_string(), self.help_text_id());
+        }
+        let attrs = widgets::merge_attrs(&attrs, &self.field.attrs);
+        widgets::merge_attrs(&attrs, extra_attrs)
+    }
+
+    /// Returns whether the widget can carry the `required` attribute.
+    ///
+    /// A checkbox group would require every box to be checked, and hidden
+    /// inputs can't be filled in by the user.
+    fn uses_required_attribute(&self) -> bool {
+        !matches!(
+            self.widget.widget_type(),
+            WidgetType::CheckboxSelectMultiple | WidgetType::HiddenInput
+        )
     }
 
     /// Renders a `<label>` element for this field.
     pub fn label_tag(&self) -> String {
-        let id = self.auto_id();
-        let label_id = self.widget.id_for_label(&id);
+        self.label_tag_with_suffix("")
+    }
+
+    /// Renders a `<label>` element for this field, with `suffix` (such as
+    /// `":"`) appended to the label text.
+    pub fn label_tag_with_suffix(&self, suffix: &str) -> String {
+        let label_id = self.id_for_label();
         if label_id.is_empty() {
-            format!("<label>{}</label>", self.field.label)
+            format!("<label>{}{suffix}</label>", self.field.label)
         } else {
-            format!(r#"<label for="{label_id}">{}</label>"#, self.field.label)
+            format!(
+                r#"<label for="{label_id}">{}{suffix}</label>"#,
+                self.field.label
+            )
+        }
+    }
+
+    /// Returns the `id` a `<label>` for this field should point at, which
+    /// some widgets place on an inner element.
+    pub fn id_for_label(&self) -> String {
+        self.widget.id_for_label(&self.auto_id())
+    }
+
+    /// Returns the CSS classes for the row containing this field: `extra`,
+    /// plus `error` if the field has errors and `required` if it is
+    /// required.
+    pub fn css_classes(&self, extra: &str) -> String {
+        let mut classes: Vec<&str> = extra.split_whitespace().collect();
+        if self.has_errors() {
+            classes.push("error");
         }
+        if self.field.required {
+            classes.push("required");
+        }
+        classes.join(" ")
+    }
+
+    /// Returns `true` if the field renders as a hidden input.
+    pub fn is_hidden(&self) -> bool {
+        self.widget.widget_type() == WidgetType::HiddenInput
     }
 
     /// Returns the auto-generated HTML `id` for this field.
@@ -106,11 +237,29 @@ impl BoundField {
         format!("id_{}", self.name)
     }
 
+    /// Returns the `id` templates should give the help text element, as
+    /// referenced by the widget's `aria-describedby`.
+    pub fn help_text_id(&self) -> String {
+        format!("{}_helptext", self.auto_id())
+    }
+
     /// Returns `true` if this field has any errors.
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
 
+    /// Returns the errors with their codes.
+    pub fn error_list(&self) -> Vec<FieldError> {
+        self.errors
+            .iter()
+            .zip(&self.error_codes)
+            .map(|(message, code)| FieldError {
+                message: message.clone(),
+                code: code.clone(),
+            })
+            .collect()
+    }
+
     /// Renders the error list as an HTML `<ul>` element.
     pub fn errors_as_ul(&self) -> String {
         if self.errors.is_empty() {
@@ -125,6 +274,14 @@ impl BoundField {
     }
 }
 
+/// Returns how a value is shown in a widget, or `None` for no value.
+fn display_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,4 +397,128 @@ mod tests {
         assert!(html.contains("<textarea"));
         assert!(html.contains("Hello"));
     }
+
+    #[test]
+    fn test_bound_field_attrs_and_accessibility() {
+        let field_def = make_char_field("email")
+            .help_text("We never share it.")
+            .placeholder("you@example.com")
+            .autocomplete("email")
+            .data_attr("validate", "email")
+            .css_class("input")
+            .attr("class", "input-lg");
+        let bf = BoundField::new(&field_def, None, vec!["Enter a valid email.".into()], None);
+
+        let extra = HashMap::from([("class".to_string(), "is-danger".to_string())]);
+        let attrs = bf.build_attrs(&extra);
+        assert_eq!(attrs["required"], "required");
+        assert_eq!(attrs["aria-invalid"], "true");
+        assert_eq!(attrs["aria-describedby"], "id_email_helptext");
+        assert_eq!(attrs["placeholder"], "you@example.com");
+        assert_eq!(attrs["autocomplete"], "email");
+        assert_eq!(attrs["data-validate"], "email");
+        assert_eq!(attrs["class"], "input input-lg is-danger");
+
+        let html = bf.render(&extra);
+        assert!(html.contains(r#"data-validate="email""#));
+        assert!(html.contains(r#"aria-describedby="id_email_helptext""#));
+    }
+
+    #[test]
+    fn test_bound_field_required_attribute_omitted() {
+        let optional = make_char_field("nickname").required(false);
+        let bf = BoundField::new(&optional, None, vec![], None);
+        let attrs = bf.build_attrs(&HashMap::new());
+        assert!(!attrs.contains_key("required"));
+        assert!(!attrs.contains_key("aria-invalid"));
+        assert!(!attrs.contains_key("aria-describedby"));
+
+        let hidden = make_char_field("token").widget(WidgetType::HiddenInput);
+        let bf = BoundField::new(&hidden, None, vec![], None);
+        assert!(!bf.build_attrs(&HashMap::new()).contains_key("required"));
+    }
+
+    #[test]
+    fn test_bound_field_value_uses_initial_until_bound() {
+        let field_def = make_char_field("city").initial(Value::String("Oslo".into()));
+        let unbound = BoundField::new(&field_def, None, vec![], None);
+        assert!(!unbound.is_bound);
+        assert_eq!(unbound.value().as_deref(), Some("Oslo"));
+        assert!(unbound.render(&HashMap::new()).contains(r#"value="Oslo""#));
+
+        let cleared = BoundField::new(&field_def, None, vec![], None).bound(true);
+        assert_eq!(cleared.value(), None);
+
+        let bound = BoundField::new(&field_def, Some("Bergen".into()), vec![], None)
+            .with_initial(Some(&Value::String("Tromsø".into())));
+        assert_eq!(bound.value().as_deref(), Some("Bergen"));
+        assert_eq!(bound.initial.as_deref(), Some("Tromsø"));
+
+        let disabled = make_char_field("city")
+            .initial(Value::String("Oslo".into()))
+            .disabled(true);
+        let bf = BoundField::new(&disabled, Some("Bergen".into()), vec![], None);
+        assert_eq!(bf.value().as_deref(), Some("Oslo"));
+    }
+
+    #[test]
+    fn test_bound_field_id_for_label_and_suffix() {
+        let field_def = make_char_field("size")
+            .label("Size")
+            .widget(WidgetType::RadioSelect);
+        let bf = BoundField::new(&field_def, None, vec![], None);
+        assert_eq!(bf.id_for_label(), "id_size_0");
+        assert_eq!(
+            bf.label_tag_with_suffix(":"),
+            r#"<label for="id_size_0">Size:</label>"#
+        );
+        assert_eq!(bf.label_tag(), r#"<label for="id_size_0">Size</label>"#);
+    }
+
+    #[test]
+    fn test_bound_field_css_classes() {
+        let field_def = make_char_field("email");
+        let bf = BoundField::new(&field_def, None, vec![], None);
+        assert_eq!(bf.css_classes("row  wide"), "row wide required");
+
+        let optional = make_char_field("nickname").required(false);
+        let bf = BoundField::new(&optional, None, vec!["Too long.".into()], None);
+        assert_eq!(bf.css_classes(""), "error");
+    }
+
+    #[test]
+    fn test_bound_field_is_hidden() {
+        let hidden = make_char_field("token").widget(WidgetType::HiddenInput);
+        assert!(BoundField::new(&hidden, None, vec![], None).is_hidden());
+        assert!(!BoundField::new(&make_char_field("name"), None, vec![], None).is_hidden());
+    }
+
+    #[test]
+    fn test_bound_field_error_list_with_codes() {
+        let field_def = make_char_field("email");
+        let errors = vec!["This field is required.".to_string(), "Taken.".to_string()];
+
+        let bf = BoundField::new(&field_def, None, errors.clone(), None);
+        assert_eq!(bf.error_list()[1].code, "");
+
+        let bf = BoundField::new(&field_def, None, errors, None)
+            .with_error_codes(vec!["required".into()]);
+        assert_eq!(
+            bf.error_list(),
+            vec![
+                FieldError {
+                    message: "This field is required.".into(),
+                    code: "required".into(),
+                },
+                FieldError {
+                    message: "Taken.".into(),
+                    code: String::new(),
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&bf.error_list()[0]).unwrap(),
+            serde_json::json!({"message": "This field is required.", "code": "required"})
+        );
+    }
 }
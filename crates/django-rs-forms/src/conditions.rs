@@ -0,0 +1,277 @@
+//! Conditional field visibility.
+//!
+//! A [`VisibilityRule`] shows a field only while a [`Condition`] on the
+//! other submitted values holds, e.g. `company_name` only when
+//! `account_type == "business"`. Rules serialize to a declarative condition
+//! tree so a frontend can toggle fields as the user types, and the same rules
+//! are enforced on the server: a field hidden by its rule is neither
+//! validated nor kept in the cleaned data.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_forms::conditions::{Condition, VisibilityRule};
+//! use serde_json::json;
+//!
+//! let rule = VisibilityRule::new(
+//!     "company_name",
+//!     Condition::eq("account_type", "business"),
+//! );
+//!
+//! let data = json!({"account_type": "personal"});
+//! assert!(!rule.is_visible(&|name: &str| data.get(name).cloned()));
+//! assert_eq!(
+//!     serde_json::to_value(&rule).unwrap(),
+//!     json!({
+//!         "field": "company_name",
+//!         "when": {"op": "eq", "field": "account_type", "value": "business"}
+//!     })
+//! );
+//! ```
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A condition on the values of other fields.
+///
+/// Values are compared loosely, by their string form, so the raw strings of
+/// a submitted form match typed JSON values: `"1"` equals `1` and `"true"`
+/// equals `true`. A missing field compares as `null`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Condition {
+    /// The field equals `value`.
+    Eq {
+        /// The field to test.
+        field: String,
+        /// The expected value.
+        value: Value,
+    },
+    /// The field does not equal `value`.
+    Ne {
+        /// The field to test.
+        field: String,
+        /// The rejected value.
+        value: Value,
+    },
+    /// The field equals one of `values`.
+    In {
+        /// The field to test.
+        field: String,
+        /// The accepted values.
+        values: Vec<Value>,
+    },
+    /// The field has a value: not missing, `null`, `false` or empty.
+    IsSet {
+        /// The field to test.
+        field: String,
+    },
+    /// Every condition holds (true when empty).
+    All {
+        /// The combined conditions.
+        conditions: Vec<Condition>,
+    },
+    /// At least one condition holds (false when empty).
+    Any {
+        /// The combined conditions.
+        conditions: Vec<Condition>,
+    },
+    /// The condition does not hold.
+    Not {
+        /// The negated condition.
+        condition: Box<Condition>,
+    },
+}
+
+impl Condition {
+    /// Creates an [`Eq`](Self::Eq) condition.
+    pub fn eq(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::Eq {
+            field: field.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Creates a [`Ne`](Self::Ne) condition.
+    pub fn ne(field: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::Ne {
+            field: field.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Creates an [`In`](Self::In) condition.
+    pub fn one_of<V: Into<Value>>(field: impl Into<String>, values: Vec<V>) -> Self {
+        Self::In {
+            field: field.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Creates an [`IsSet`](Self::IsSet) condition.
+    pub fn is_set(field: impl Into<String>) -> Self {
+        Self::IsSet {
+            field: field.into(),
+        }
+    }
+
+    /// Combines conditions that must all hold.
+    pub fn all(conditions: Vec<Condition>) -> Self {
+        Self::All { conditions }
+    }
+
+    /// Combines conditions of which one must hold.
+    pub fn any(conditions: Vec<Condition>) -> Self {
+        Self::Any { conditions }
+    }
+
+    /// Negates a condition.
+    pub fn negate(condition: Condition) -> Self {
+        Self::Not {
+            condition: Box::new(condition),
+        }
+    }
+
+    /// Evaluates the condition, reading field values through `lookup`.
+    pub fn evaluate(&self, lookup: &dyn Fn(&str) -> Option<Value>) -> bool {
+        let get = |field: &str| lookup(field).unwrap_or(Value::Null);
+        match self {
+            Self::Eq { field, value } => loose_eq(&get(field), value),
+            Self::Ne { field, value } => !loose_eq(&get(field), value),
+            Self::In { field, values } => {
+                let actual = get(field);
+                values.iter().any(|v| loose_eq(&actual, v))
+            }
+            Self::IsSet { field } => match get(field) {
+                Value::Null | Value::Bool(false) => false,
+                Value::String(s) => !s.trim().is_empty(),
+                Value::Array(items) => !items.is_empty(),
+                Value::Object(map) => !map.is_empty(),
+                Value::Bool(true) | Value::Number(_) => true,
+            },
+            Self::All { conditions } => conditions.iter().all(|c| c.evaluate(lookup)),
+            Self::Any { conditions } => conditions.iter().any(|c| c.evaluate(lookup)),
+            Self::Not { condition } => !condition.evaluate(lookup),
+        }
+    }
+}
+
+/// Compares two values by their string form; `null` matches only `null` or
+/// an empty string.
+fn loose_eq(actual: &Value, expected: &Value) -> bool {
+    fn text(value: &Value) -> String {
+        match value {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+    actual == expected || text(actual) == text(expected)
+}
+
+/// Shows `field` only while `when` holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisibilityRule {
+    /// The field whose visibility is controlled.
+    pub field: String,
+    /// The condition under which the field is shown.
+    pub when: Condition,
+}
+
+impl VisibilityRule {
+    /// Creates a rule showing `field` only while `when` holds.
+    pub fn new(field: impl Into<String>, when: Condition) -> Self {
+        Self {
+            field: field.into(),
+            when,
+        }
+    }
+
+    /// Returns `true` if the field is shown for the given values.
+    pub fn is_visible(&self, lookup: &dyn Fn(&str) -> Option<Value>) -> bool {
+        self.when.evaluate(lookup)
+    }
+}
+
+/// Returns the fields hidden by `rules` for the given values.
+///
+/// A field with several rules is shown only if all of them hold.
+pub fn hidden_fields(
+    rules: &[VisibilityRule],
+    lookup: &dyn Fn(&str) -> Option<Value>,
+) -> HashSet<String> {
+    rules
+        .iter()
+        .filter(|rule| !rule.is_visible(lookup))
+        .map(|rule| rule.field.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lookup(data: &Value) -> impl Fn(&str) -> Option<Value> + '_ {
+        move |name| data.get(name).cloned()
+    }
+
+    #[test]
+    fn test_condition_evaluate() {
+        let data = json!({
+            "account_type": "business",
+            "employees": "12",
+            "newsletter": true,
+            "notes": "  ",
+        });
+        let get = lookup(&data);
+
+        assert!(Condition::eq("account_type", "business").evaluate(&get));
+        assert!(Condition::eq("employees", 12).evaluate(&get));
+        assert!(Condition::eq("newsletter", "true").evaluate(&get));
+        assert!(Condition::ne("account_type", "personal").evaluate(&get));
+        assert!(Condition::one_of("account_type", vec!["business", "nonprofit"]).evaluate(&get));
+        assert!(Condition::is_set("newsletter").evaluate(&get));
+        assert!(!Condition::is_set("notes").evaluate(&get));
+        assert!(!Condition::is_set("missing").evaluate(&get));
+        assert!(Condition::eq("missing", Value::Null).evaluate(&get));
+        assert!(Condition::all(vec![
+            Condition::eq("account_type", "business"),
+            Condition::negate(Condition::is_set("notes")),
+        ])
+        .evaluate(&get));
+        assert!(!Condition::any(vec![]).evaluate(&get));
+    }
+
+    #[test]
+    fn test_condition_serde_round_trip() {
+        let condition = Condition::any(vec![
+            Condition::eq("a", 1),
+            Condition::negate(Condition::is_set("b")),
+        ]);
+        let json = serde_json::to_value(&condition).unwrap();
+        assert_eq!(
+            json,
+            json!({"op": "any", "conditions": [
+                {"op": "eq", "field": "a", "value": 1},
+                {"op": "not", "condition": {"op": "is_set", "field": "b"}},
+            ]})
+        );
+        let back: Condition = serde_json::from_value(json).unwrap();
+        assert_eq!(back, condition);
+    }
+
+    #[test]
+    fn test_hidden_fields() {
+        let rules = vec![
+            VisibilityRule::new("company_name", Condition::eq("account_type", "business")),
+            VisibilityRule::new("vat_number", Condition::eq("account_type", "business")),
+            VisibilityRule::new("vat_number", Condition::eq("country", "DE")),
+        ];
+        let data = json!({"account_type": "business", "country": "FR"});
+        let hidden = hidden_fields(&rules, &lookup(&data));
+        assert_eq!(hidden, HashSet::from(["vat_number".to_string()]));
+    }
+}
@@ -9,7 +9,7 @@
 
 use std::collections::HashMap;
 
-use django_rs_core::DjangoError;
+use django_rs_core::{DjangoError, ValidationError};
 use django_rs_db::validators::Validator;
 use django_rs_db::value::Value;
 
@@ -136,6 +136,9 @@ pub struct FormFieldDef {
     pub error_messages: HashMap<String, String>,
     /// Whether the field is disabled (rendered but not editable).
     pub disabled: bool,
+    /// Extra HTML attributes for the widget, such as `placeholder`,
+    /// `autocomplete`, `data-*` attributes and CSS classes.
+    pub attrs: HashMap<String, String>,
 }
 
 impl FormFieldDef {
@@ -158,6 +161,7 @@ impl FormFieldDef {
             validators: Vec::new(),
             error_messages: HashMap::new(),
             disabled: false,
+            attrs: HashMap::new(),
         }
     }
 
@@ -208,6 +212,56 @@ impl FormFieldDef {
         self.disabled = disabled;
         self
     }
+
+    /// Sets an HTML attribute on the widget.
+    ///
+    /// Setting `class` adds to the existing classes instead of replacing
+    /// them; see [`css_class`](Self::css_class).
+    pub fn attr(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        if name == "class" {
+            return self.css_class(value);
+        }
+        self.attrs.insert(name, value);
+        self
+    }
+
+    /// Sets several HTML attributes on the widget, as with [`attr`](Self::attr).
+    pub fn attrs<K, V>(self, attrs: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        attrs
+            .into_iter()
+            .fold(self, |field, (name, value)| field.attr(name, value))
+    }
+
+    /// Sets the `placeholder` attribute.
+    pub fn placeholder(self, text: impl Into<String>) -> Self {
+        self.attr("placeholder", text)
+    }
+
+    /// Sets the `autocomplete` attribute (e.g. `"email"` or `"off"`).
+    pub fn autocomplete(self, value: impl Into<String>) -> Self {
+        self.attr("autocomplete", value)
+    }
+
+    /// Sets a `data-*` attribute; `name` is given without the `data-` prefix.
+    pub fn data_attr(self, name: &str, value: impl Into<String>) -> Self {
+        self.attr(format!("data-{name}"), value)
+    }
+
+    /// Adds one or more space-separated CSS classes to the widget.
+    pub fn css_class(mut self, class: impl Into<String>) -> Self {
+        let merged = crate::widgets::merge_classes(
+            self.attrs.get("class").map_or("", String::as_str),
+            &class.into(),
+        );
+        self.attrs.insert("class".to_string(), merged);
+        self
+    }
 }
 
 /// Returns the default widget type for a given form field type.
@@ -246,19 +300,34 @@ pub fn default_widget_for_field_type(field_type: &FormFieldType) -> WidgetType {
 /// 3. Type-specific constraint validation (min/max, regex, choices)
 /// 4. Custom validators
 ///
-/// Returns the cleaned `Value` or a list of error messages.
+/// Returns the cleaned `Value` or a list of error messages. See
+/// [`clean_field_value_with_codes`] for the errors with their codes.
 pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Value, Vec<String>> {
+    clean_field_value_with_codes(field, raw)
+        .map_err(|errors| errors.into_iter().map(|e| e.message).collect())
+}
+
+/// Cleans a raw form input string like [`clean_field_value`], returning
+/// each error with its code.
+///
+/// Codes follow Django's: `required`, `invalid`, `min_length`, `max_length`,
+/// `min_value`, `max_value`, `max_digits`, `max_decimal_places`,
+/// `invalid_choice`, `max_size`, `invalid_extension` and `invalid_image`;
+/// validators keep the codes of the errors they return. A message set with
+/// [`FormFieldDef::error_message`] for a code replaces the default one.
+pub fn clean_field_value_with_codes(
+    field: &FormFieldDef,
+    raw: Option<&str>,
+) -> Result<Value, Vec<ValidationError>> {
     let raw_str = raw.unwrap_or("");
     let is_empty = raw_str.is_empty() || raw.is_none();
 
     // Required check
     if field.required && is_empty {
-        let msg = field
-            .error_messages
-            .get("required")
-            .cloned()
-            .unwrap_or_else(|| "This field is required.".to_string());
-        return Err(vec![msg]);
+        return Err(with_custom_messages(
+            field,
+            vec![ValidationError::new("This field is required.", "required")],
+        ));
     }
 
     // If not required and empty, return Null
@@ -278,17 +347,23 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             let s = if *strip { raw_str.trim() } else { raw_str };
             if let Some(min) = min_length {
                 if s.len() < *min {
-                    errors.push(format!(
-                        "Ensure this value has at least {min} characters (it has {}).",
-                        s.len()
+                    errors.push(ValidationError::new(
+                        format!(
+                            "Ensure this value has at least {min} characters (it has {}).",
+                            s.len()
+                        ),
+                        "min_length",
                     ));
                 }
             }
             if let Some(max) = max_length {
                 if s.len() > *max {
-                    errors.push(format!(
-                        "Ensure this value has at most {max} characters (it has {}).",
-                        s.len()
+                    errors.push(ValidationError::new(
+                        format!(
+                            "Ensure this value has at most {max} characters (it has {}).",
+                            s.len()
+                        ),
+                        "max_length",
                     ));
                 }
             }
@@ -302,20 +377,24 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             Ok(n) => {
                 if let Some(min) = min_value {
                     if n < *min {
-                        errors.push(format!(
-                            "Ensure this value is greater than or equal to {min}."
+                        errors.push(ValidationError::new(
+                            format!("Ensure this value is greater than or equal to {min}."),
+                            "min_value",
                         ));
                     }
                 }
                 if let Some(max) = max_value {
                     if n > *max {
-                        errors.push(format!("Ensure this value is less than or equal to {max}."));
+                        errors.push(ValidationError::new(
+                            format!("Ensure this value is less than or equal to {max}."),
+                            "max_value",
+                        ));
                     }
                 }
                 Value::Int(n)
             }
             Err(_) => {
-                errors.push("Enter a whole number.".to_string());
+                errors.push(ValidationError::new("Enter a whole number.", "invalid"));
                 Value::Null
             }
         },
@@ -327,20 +406,24 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             Ok(n) => {
                 if let Some(min) = min_value {
                     if n < *min {
-                        errors.push(format!(
-                            "Ensure this value is greater than or equal to {min}."
+                        errors.push(ValidationError::new(
+                            format!("Ensure this value is greater than or equal to {min}."),
+                            "min_value",
                         ));
                     }
                 }
                 if let Some(max) = max_value {
                     if n > *max {
-                        errors.push(format!("Ensure this value is less than or equal to {max}."));
+                        errors.push(ValidationError::new(
+                            format!("Ensure this value is less than or equal to {max}."),
+                            "max_value",
+                        ));
                     }
                 }
                 Value::Float(n)
             }
             Err(_) => {
-                errors.push("Enter a number.".to_string());
+                errors.push(ValidationError::new("Enter a number.", "invalid"));
                 Value::Null
             }
         },
@@ -358,19 +441,25 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
                     let total_digits = integer_digits + actual_decimal_places;
 
                     if total_digits > *max_digits as usize {
-                        errors.push(format!(
-                            "Ensure that there are no more than {max_digits} digits in total."
+                        errors.push(ValidationError::new(
+                            format!(
+                                "Ensure that there are no more than {max_digits} digits in total."
+                            ),
+                            "max_digits",
                         ));
                     }
                     if actual_decimal_places > *decimal_places as usize {
-                        errors.push(format!(
+                        errors.push(ValidationError::new(
+                            format!(
                             "Ensure that there are no more than {decimal_places} decimal places."
+                        ),
+                            "max_decimal_places",
                         ));
                     }
                     Value::Float(n)
                 }
                 Err(_) => {
-                    errors.push("Enter a number.".to_string());
+                    errors.push(ValidationError::new("Enter a number.", "invalid"));
                     Value::Null
                 }
             }
@@ -388,7 +477,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
                 "false" | "0" | "no" | "off" => Value::Bool(false),
                 "" | "null" | "none" | "unknown" => Value::Null,
                 _ => {
-                    errors.push("Select a valid choice.".to_string());
+                    errors.push(ValidationError::new(
+                        "Select a valid choice.",
+                        "invalid_choice",
+                    ));
                     Value::Null
                 }
             }
@@ -397,7 +489,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
         FormFieldType::Date => match chrono::NaiveDate::parse_from_str(raw_str, "%Y-%m-%d") {
             Ok(d) => Value::Date(d),
             Err(_) => {
-                errors.push("Enter a valid date (YYYY-MM-DD).".to_string());
+                errors.push(ValidationError::new(
+                    "Enter a valid date (YYYY-MM-DD).",
+                    "invalid",
+                ));
                 Value::Null
             }
         },
@@ -411,7 +506,7 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             match result {
                 Ok(dt) => Value::DateTime(dt),
                 Err(_) => {
-                    errors.push("Enter a valid date/time.".to_string());
+                    errors.push(ValidationError::new("Enter a valid date/time.", "invalid"));
                     Value::Null
                 }
             }
@@ -423,7 +518,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             match result {
                 Ok(t) => Value::Time(t),
                 Err(_) => {
-                    errors.push("Enter a valid time (HH:MM or HH:MM:SS).".to_string());
+                    errors.push(ValidationError::new(
+                        "Enter a valid time (HH:MM or HH:MM:SS).",
+                        "invalid",
+                    ));
                     Value::Null
                 }
             }
@@ -434,7 +532,7 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             if let Some(dur) = parse_duration(raw_str) {
                 Value::Duration(dur)
             } else {
-                errors.push("Enter a valid duration.".to_string());
+                errors.push(ValidationError::new("Enter a valid duration.", "invalid"));
                 Value::Null
             }
         }
@@ -446,7 +544,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             if email_re.is_match(raw_str) {
                 Value::String(raw_str.to_string())
             } else {
-                errors.push("Enter a valid email address.".to_string());
+                errors.push(ValidationError::new(
+                    "Enter a valid email address.",
+                    "invalid",
+                ));
                 Value::String(raw_str.to_string())
             }
         }
@@ -457,7 +558,7 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             if url_re.is_match(raw_str) {
                 Value::String(raw_str.to_string())
             } else {
-                errors.push("Enter a valid URL.".to_string());
+                errors.push(ValidationError::new("Enter a valid URL.", "invalid"));
                 Value::String(raw_str.to_string())
             }
         }
@@ -465,7 +566,7 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
         FormFieldType::Uuid => match uuid::Uuid::parse_str(raw_str) {
             Ok(u) => Value::Uuid(u),
             Err(_) => {
-                errors.push("Enter a valid UUID.".to_string());
+                errors.push(ValidationError::new("Enter a valid UUID.", "invalid"));
                 Value::Null
             }
         },
@@ -475,10 +576,11 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             if slug_re.is_match(raw_str) {
                 Value::String(raw_str.to_string())
             } else {
-                errors.push(
-                    "Enter a valid \"slug\" consisting of letters, numbers, underscores or hyphens."
-                        .to_string(),
-                );
+                errors.push(ValidationError::new(
+                    "Enter a valid \"slug\" consisting of letters, numbers, underscores or \
+                     hyphens.",
+                    "invalid",
+                ));
                 Value::String(raw_str.to_string())
             }
         }
@@ -488,7 +590,7 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             if raw_str.parse::<std::net::IpAddr>().is_ok() {
                 Value::String(raw_str.to_string())
             } else {
-                errors.push("Enter a valid IP address.".to_string());
+                errors.push(ValidationError::new("Enter a valid IP address.", "invalid"));
                 Value::String(raw_str.to_string())
             }
         }
@@ -498,8 +600,11 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
             if valid {
                 Value::String(raw_str.to_string())
             } else {
-                errors.push(format!(
-                    "Select a valid choice. {raw_str} is not one of the available choices."
+                errors.push(ValidationError::new(
+                    format!(
+                        "Select a valid choice. {raw_str} is not one of the available choices."
+                    ),
+                    "invalid_choice",
                 ));
                 Value::String(raw_str.to_string())
             }
@@ -513,8 +618,9 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
                 if choices.iter().any(|(v, _)| v == s) {
                     valid_values.push(Value::String(s.to_string()));
                 } else {
-                    errors.push(format!(
-                        "Select a valid choice. {s} is not one of the available choices."
+                    errors.push(ValidationError::new(
+                        format!("Select a valid choice. {s} is not one of the available choices."),
+                        "invalid_choice",
                     ));
                 }
             }
@@ -530,7 +636,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
                 // In practice, file size comes from the multipart data.
                 // Here we check the string length as a placeholder.
                 if raw_str.len() > *max {
-                    errors.push(format!("File size exceeds maximum of {max} bytes."));
+                    errors.push(ValidationError::new(
+                        format!("File size exceeds maximum of {max} bytes."),
+                        "max_size",
+                    ));
                 }
             }
             if !allowed_extensions.is_empty() {
@@ -540,9 +649,12 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
                     .map(str::to_lowercase)
                     .unwrap_or_default();
                 if !allowed_extensions.iter().any(|e| e.to_lowercase() == ext) {
-                    errors.push(format!(
-                        "File extension not allowed. Allowed extensions: {}.",
-                        allowed_extensions.join(", ")
+                    errors.push(ValidationError::new(
+                        format!(
+                            "File extension not allowed. Allowed extensions: {}.",
+                            allowed_extensions.join(", ")
+                        ),
+                        "invalid_extension",
                     ));
                 }
             }
@@ -558,9 +670,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
                 .unwrap_or_default();
             let image_exts = ["jpg", "jpeg", "png", "gif", "bmp", "webp", "svg"];
             if !image_exts.contains(&ext.as_str()) {
-                errors.push(
-                    "Upload a valid image. The file must have an image extension.".to_string(),
-                );
+                errors.push(ValidationError::new(
+                    "Upload a valid image. The file must have an image extension.",
+                    "invalid_image",
+                ));
             }
             Value::String(raw_str.to_string())
         }
@@ -568,15 +681,18 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
         FormFieldType::TypedChoice { choices, coerce } => {
             let valid = choices.iter().any(|(v, _)| v == raw_str);
             if !valid {
-                errors.push(format!(
-                    "Select a valid choice. {raw_str} is not one of the available choices."
+                errors.push(ValidationError::new(
+                    format!(
+                        "Select a valid choice. {raw_str} is not one of the available choices."
+                    ),
+                    "invalid_choice",
                 ));
                 Value::Null
             } else {
                 match coerce(raw_str) {
                     Ok(v) => v,
                     Err(_) => {
-                        errors.push("Invalid value.".to_string());
+                        errors.push(ValidationError::new("Invalid value.", "invalid"));
                         Value::Null
                     }
                 }
@@ -586,17 +702,22 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
         FormFieldType::Json => match serde_json::from_str::<serde_json::Value>(raw_str) {
             Ok(j) => Value::Json(j),
             Err(_) => {
-                errors.push("Enter valid JSON.".to_string());
+                errors.push(ValidationError::new("Enter valid JSON.", "invalid"));
                 Value::Null
             }
         },
 
         FormFieldType::Regex { regex } => {
-            let re = regex::Regex::new(regex).map_err(|e| vec![format!("Invalid regex: {e}")])?;
+            let re = regex::Regex::new(regex).map_err(|e| {
+                vec![ValidationError::new(
+                    format!("Invalid regex: {e}"),
+                    "invalid",
+                )]
+            })?;
             if re.is_match(raw_str) {
                 Value::String(raw_str.to_string())
             } else {
-                errors.push("Enter a valid value.".to_string());
+                errors.push(ValidationError::new("Enter a valid value.", "invalid"));
                 Value::String(raw_str.to_string())
             }
         }
@@ -605,8 +726,10 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
     // Run custom validators on the cleaned value (only if no type errors so far)
     if errors.is_empty() {
         for validator in &field.validators {
-            if let Err(e) = validator.validate(&value) {
-                errors.push(e.to_string());
+            match validator.validate(&value) {
+                Ok(()) => {}
+                Err(DjangoError::ValidationError(e)) => errors.push(e),
+                Err(e) => errors.push(ValidationError::new(e.to_string(), "invalid")),
             }
         }
     }
@@ -614,8 +737,22 @@ pub fn clean_field_value(field: &FormFieldDef, raw: Option<&str>) -> Result<Valu
     if errors.is_empty() {
         Ok(value)
     } else {
-        Err(errors)
+        Err(with_custom_messages(field, errors))
+    }
+}
+
+/// Replaces error messages with the field's custom message for their code,
+/// if any.
+fn with_custom_messages(
+    field: &FormFieldDef,
+    mut errors: Vec<ValidationError>,
+) -> Vec<ValidationError> {
+    for error in &mut errors {
+        if let Some(message) = field.error_messages.get(&error.code) {
+            error.message.clone_from(message);
+        }
     }
+    errors
 }
 
 /// Parses a simple duration string into a `chrono::Duration`.
@@ -0,0 +1,256 @@
+//! Filter forms bound from query strings.
+//!
+//! A [`FilterForm`] declares which ORM lookup each of its fields drives, e.g.
+//! field `"q"` -> `title__icontains`. After binding it to a request's GET
+//! parameters and validating, it turns the cleaned values into a [`Q`] object
+//! or applies them directly to a [`QuerySet`], so list views don't have to
+//! hand-roll their filtering.
+//!
+//! Every field of a filter form is optional, and fields left blank add no
+//! condition. Use `NullBoolean` rather than `Boolean` for yes/no filters so
+//! that "unset" can be told apart from "false".
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_db::query::lookups::{Lookup, Q};
+//! use django_rs_forms::fields::{FormFieldDef, FormFieldType};
+//! use django_rs_forms::filter_form::FilterForm;
+//! use django_rs_forms::form::Form;
+//! use django_rs_http::QueryDict;
+//!
+//! # async fn example() {
+//! let mut form = FilterForm::new(vec![
+//!     FormFieldDef::new("q", FormFieldType::Char { min_length: None, max_length: None, strip: true }),
+//!     FormFieldDef::new("min_views", FormFieldType::Integer { min_value: None, max_value: None }),
+//! ])
+//! .filter("q", "title__icontains")
+//! .filter("min_views", "views__gte");
+//!
+//! form.bind(&QueryDict::parse("q=rust&min_views="));
+//! assert!(form.is_valid().await);
+//! assert_eq!(form.to_q(), Q::filter("title", Lookup::IContains("rust".into())));
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use django_rs_db::model::Model;
+use django_rs_db::query::lookups::Q;
+use django_rs_db::query::queryset::QuerySet;
+use django_rs_db::value::Value;
+use django_rs_http::QueryDict;
+use django_rs_template::context::ContextValue;
+
+use crate::fields::FormFieldDef;
+use crate::form::{BaseForm, Form};
+
+/// A form whose cleaned fields map declaratively to ORM lookups.
+///
+/// This is the equivalent of a Django filter form whose `filter_queryset`
+/// method builds `Q` objects from `cleaned_data`.
+pub struct FilterForm {
+    form: BaseForm,
+    /// Lookup paths per form field, in declaration order.
+    lookups: Vec<(String, Vec<String>)>,
+}
+
+impl FilterForm {
+    /// Creates a filter form with the given fields, all made optional.
+    pub fn new(fields: Vec<FormFieldDef>) -> Self {
+        Self {
+            form: BaseForm::new(fields.into_iter().map(|f| f.required(false)).collect()),
+            lookups: Vec::new(),
+        }
+    }
+
+    /// Maps a form field to a lookup path such as `"title__icontains"`.
+    ///
+    /// Mapping the same field more than once ORs the lookups together, so a
+    /// search box can match several columns. Fields without a mapping are
+    /// validated but add no condition.
+    pub fn filter(mut self, field: &str, lookup_path: &str) -> Self {
+        match self.lookups.iter_mut().find(|(name, _)| name == field) {
+            Some((_, paths)) => paths.push(lookup_path.to_string()),
+            None => self
+                .lookups
+                .push((field.to_string(), vec![lookup_path.to_string()])),
+        }
+        self
+    }
+
+    /// Sets the form prefix.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.form = self.form.with_prefix(prefix);
+        self
+    }
+
+    /// Returns the underlying form, e.g. for rendering bound fields.
+    pub fn form(&self) -> &BaseForm {
+        &self.form
+    }
+
+    /// Builds the filter from the cleaned data, ANDing the fields together.
+    ///
+    /// Only fields that validated and are not blank contribute. Returns an
+    /// empty `Q::And` when nothing is filtered.
+    pub fn to_q(&self) -> Q {
+        let cleaned = self.form.cleaned_data();
+        let mut conditions: Vec<Q> = self
+            .lookups
+            .iter()
+            .filter_map(|(field, paths)| {
+                let value = cleaned.get(field).filter(|v| !is_blank(v))?;
+                paths
+                    .iter()
+                    .filter_map(|path| Q::from_path(path, value.clone()))
+                    .reduce(|a, b| a | b)
+            })
+            .collect();
+        if conditions.len() == 1 {
+            conditions.remove(0)
+        } else {
+            Q::And(conditions)
+        }
+    }
+
+    /// Applies the filter to a queryset.
+    pub fn apply<M: Model>(&self, queryset: QuerySet<M>) -> QuerySet<M> {
+        let q = self.to_q();
+        if q.is_empty() {
+            queryset
+        } else {
+            queryset.filter(q)
+        }
+    }
+
+    /// Binds `data`, validates, and filters `queryset`.
+    ///
+    /// Returns the validation errors if any field is invalid.
+    pub async fn filter_queryset<M: Model>(
+        &mut self,
+        data: &QueryDict,
+        queryset: QuerySet<M>,
+    ) -> Result<QuerySet<M>, HashMap<String, Vec<String>>> {
+        self.bind(data);
+        if self.is_valid().await {
+            Ok(self.apply(queryset))
+        } else {
+            Err(self.errors().clone())
+        }
+    }
+}
+
+/// Returns `true` for values that mean "no filter".
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::List(values) => values.is_empty(),
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl Form for FilterForm {
+    fn fields(&self) -> &[FormFieldDef] {
+        self.form.fields()
+    }
+
+    fn initial(&self) -> &HashMap<String, Value> {
+        self.form.initial()
+    }
+
+    fn prefix(&self) -> Option<&str> {
+        self.form.prefix()
+    }
+
+    fn bind(&mut self, data: &QueryDict) {
+        self.form.bind(data);
+    }
+
+    fn is_bound(&self) -> bool {
+        self.form.is_bound()
+    }
+
+    async fn is_valid(&mut self) -> bool {
+        self.form.is_valid().await
+    }
+
+    fn errors(&self) -> &HashMap<String, Vec<String>> {
+        self.form.errors()
+    }
+
+    fn cleaned_data(&self) -> &HashMap<String, Value> {
+        self.form.cleaned_data()
+    }
+
+    fn as_context(&self) -> HashMap<String, ContextValue> {
+        self.form.as_context()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::FormFieldType;
+    use django_rs_db::query::lookups::Lookup;
+
+    fn article_filter() -> FilterForm {
+        FilterForm::new(vec![
+            FormFieldDef::new(
+                "q",
+                FormFieldType::Char {
+                    min_length: None,
+                    max_length: None,
+                    strip: true,
+                },
+            ),
+            FormFieldDef::new("published", FormFieldType::NullBoolean),
+            FormFieldDef::new(
+                "min_views",
+                FormFieldType::Integer {
+                    min_value: Some(0),
+                    max_value: None,
+                },
+            ),
+        ])
+        .filter("q", "title__icontains")
+        .filter("q", "body__icontains")
+        .filter("published", "is_published")
+        .filter("min_views", "views__gte")
+    }
+
+    #[tokio::test]
+    async fn test_to_q_combines_filled_fields() {
+        let mut form = article_filter();
+        form.bind(&QueryDict::parse("q=rust&published=false&min_views="));
+        assert!(form.is_valid().await);
+
+        let expected = Q::And(vec![
+            Q::filter("title", Lookup::IContains("rust".into()))
+                | Q::filter("body", Lookup::IContains("rust".into())),
+            Q::filter("is_published", Lookup::Exact(Value::Bool(false))),
+        ]);
+        assert_eq!(form.to_q(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_blank_query_string_filters_nothing() {
+        let mut form = article_filter();
+        form.bind(&QueryDict::parse(""));
+        assert!(form.is_valid().await);
+        assert!(form.to_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_field_is_reported_and_skipped() {
+        let mut form = article_filter();
+        form.bind(&QueryDict::parse("q=rust&min_views=-1"));
+        assert!(!form.is_valid().await);
+        assert!(form.errors().contains_key("min_views"));
+        assert!(matches!(form.to_q(), Q::Or(_)));
+    }
+}
@@ -10,7 +10,7 @@
 //!
 //! This mirrors Django's `django.forms.Form` and `BaseForm`.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 
@@ -19,6 +19,7 @@ use django_rs_http::QueryDict;
 use django_rs_template::context::ContextValue;
 
 use crate::bound_field::BoundField;
+use crate::conditions::{self, VisibilityRule};
 use crate::fields::FormFieldDef;
 use crate::validation;
 
@@ -94,9 +95,11 @@ pub struct BaseForm {
     field_defs: Vec<FormFieldDef>,
     initial_data: HashMap<String, Value>,
     prefix: Option<String>,
+    visibility_rules: Vec<VisibilityRule>,
     bound: bool,
     raw_data: HashMap<String, Option<String>>,
     errors: HashMap<String, Vec<String>>,
+    error_codes: HashMap<String, Vec<String>>,
     cleaned_data: HashMap<String, Value>,
 }
 
@@ -107,9 +110,11 @@ impl BaseForm {
             field_defs: fields,
             initial_data: HashMap::new(),
             prefix: None,
+            visibility_rules: Vec::new(),
             bound: false,
             raw_data: HashMap::new(),
             errors: HashMap::new(),
+            error_codes: HashMap::new(),
             cleaned_data: HashMap::new(),
         }
     }
@@ -126,6 +131,32 @@ impl BaseForm {
         self
     }
 
+    /// Adds a rule showing a field only while a condition on the other
+    /// submitted values holds.
+    ///
+    /// Fields hidden by their rule skip validation and are left out of the
+    /// cleaned data, whatever was submitted for them.
+    pub fn with_visibility_rule(mut self, rule: VisibilityRule) -> Self {
+        self.visibility_rules.push(rule);
+        self
+    }
+
+    /// Returns the visibility rules, for delivery to the frontend.
+    pub fn visibility_rules(&self) -> &[VisibilityRule] {
+        &self.visibility_rules
+    }
+
+    /// Returns the fields hidden by the visibility rules for the bound data.
+    pub fn hidden_fields(&self) -> HashSet<String> {
+        conditions::hidden_fields(&self.visibility_rules, &|name| {
+            self.raw_data
+                .get(name)
+                .cloned()
+                .flatten()
+                .map(serde_json::Value::String)
+        })
+    }
+
     /// Returns bound fields for template iteration.
     pub fn bound_fields(&self) -> Vec<BoundField> {
         self.field_defs
@@ -133,11 +164,32 @@ impl BaseForm {
             .map(|field| {
                 let data = self.raw_data.get(&field.name).cloned().flatten();
                 let errors = self.errors.get(&field.name).cloned().unwrap_or_default();
+                let codes = self
+                    .error_codes
+                    .get(&field.name)
+                    .cloned()
+                    .unwrap_or_default();
+                let initial = self
+                    .initial_data
+                    .get(&field.name)
+                    .or(field.initial.as_ref());
                 BoundField::new(field, data, errors, self.prefix.as_deref())
+                    .bound(self.bound)
+                    .with_initial(initial)
+                    .with_error_codes(codes)
             })
             .collect()
     }
 
+    /// Returns the code of each message in [`errors`](Form::errors), keyed
+    /// and ordered the same way.
+    ///
+    /// Codes are empty for errors returned by [`clean`](Form::clean), which
+    /// only carries messages.
+    pub fn error_codes(&self) -> &HashMap<String, Vec<String>> {
+        &self.error_codes
+    }
+
     /// Returns the non-field (form-level) errors.
     pub fn non_field_errors(&self) -> &[String] {
         self.errors.get("__all__").map_or(&[], Vec::as_slice)
@@ -162,6 +214,7 @@ impl Form for BaseForm {
         self.bound = true;
         self.raw_data.clear();
         self.errors.clear();
+        self.error_codes.clear();
         self.cleaned_data.clear();
 
         for field in &self.field_defs {
@@ -184,19 +237,37 @@ impl Form for BaseForm {
         }
 
         self.errors.clear();
+        self.error_codes.clear();
         self.cleaned_data.clear();
 
         // Step 1: Field-level validation
-        validation::clean_fields(
+        let mut field_errors = HashMap::new();
+        validation::clean_fields_with_codes(
             &self.field_defs,
             &self.raw_data,
             &mut self.cleaned_data,
-            &mut self.errors,
+            &mut field_errors,
         );
+        for (name, errors) in field_errors {
+            let (messages, codes) = errors.into_iter().map(|e| (e.message, e.code)).unzip();
+            self.errors.insert(name.clone(), messages);
+            self.error_codes.insert(name, codes);
+        }
+
+        // Fields hidden by a visibility rule are ignored entirely
+        for name in self.hidden_fields() {
+            self.cleaned_data.remove(&name);
+            self.errors.remove(&name);
+            self.error_codes.remove(&name);
+        }
 
         // Step 2: Form-level cross-field validation (async)
         if let Err(form_errors) = self.clean().await {
             for (key, msgs) in form_errors {
+                self.error_codes
+                    .entry(key.clone())
+                    .or_default()
+                    .extend(msgs.iter().map(|_| String::new()));
                 self.errors.entry(key).or_default().extend(msgs);
             }
         }
@@ -216,6 +287,7 @@ impl Form for BaseForm {
         let mut ctx = HashMap::new();
 
         // Fields as a list of dicts
+        let hidden = self.hidden_fields();
         let fields: Vec<ContextValue> = self
             .bound_fields()
             .iter()
@@ -242,10 +314,58 @@ impl Form for BaseForm {
                     "errors".to_string(),
                     ContextValue::SafeString(bf.errors_as_ul()),
                 );
+                field_ctx.insert(
+                    "error_list".to_string(),
+                    ContextValue::List(
+                        bf.error_list()
+                            .into_iter()
+                            .map(|error| {
+                                ContextValue::Dict(HashMap::from([
+                                    ("message".to_string(), ContextValue::String(error.message)),
+                                    ("code".to_string(), ContextValue::String(error.code)),
+                                ]))
+                            })
+                            .collect(),
+                    ),
+                );
+                field_ctx.insert(
+                    "value".to_string(),
+                    bf.value().map_or(ContextValue::None, ContextValue::String),
+                );
+                field_ctx.insert(
+                    "id_for_label".to_string(),
+                    ContextValue::String(bf.id_for_label()),
+                );
+                field_ctx.insert(
+                    "css_classes".to_string(),
+                    ContextValue::String(bf.css_classes("")),
+                );
+                field_ctx.insert("is_hidden".to_string(), ContextValue::Bool(bf.is_hidden()));
                 field_ctx.insert(
                     "required".to_string(),
                     ContextValue::Bool(bf.field.required),
                 );
+                field_ctx.insert(
+                    "hidden".to_string(),
+                    ContextValue::Bool(hidden.contains(&bf.field.name)),
+                );
+                field_ctx.insert(
+                    "help_text_id".to_string(),
+                    ContextValue::String(bf.help_text_id()),
+                );
+                field_ctx.insert(
+                    "widget".to_string(),
+                    ContextValue::String(bf.widget.widget_type().to_string()),
+                );
+                field_ctx.insert(
+                    "attrs".to_string(),
+                    ContextValue::Dict(
+                        bf.build_attrs(&HashMap::new())
+                            .into_iter()
+                            .map(|(name, value)| (name, ContextValue::String(value)))
+                            .collect(),
+                    ),
+                );
                 ContextValue::Dict(field_ctx)
             })
             .collect();
@@ -404,6 +524,45 @@ mod tests {
         assert_eq!(bfs[0].data, Some("alice".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_form_error_codes() {
+        let mut form = BaseForm::new(vec![
+            FormFieldDef::new(
+                "username",
+                FormFieldType::Char {
+                    min_length: Some(3),
+                    max_length: None,
+                    strip: true,
+                },
+            )
+            .error_message("min_length", "Pick a longer name."),
+            FormFieldDef::new("email", FormFieldType::Email),
+        ]);
+        form.bind(&QueryDict::parse("username=al"));
+        assert!(!form.is_valid().await);
+
+        assert_eq!(form.errors()["username"], vec!["Pick a longer name."]);
+        assert_eq!(form.error_codes()["username"], vec!["min_length"]);
+        assert_eq!(form.error_codes()["email"], vec!["required"]);
+
+        let bfs = form.bound_fields();
+        assert_eq!(bfs[0].error_list()[0].code, "min_length");
+        assert_eq!(bfs[0].css_classes(""), "error required");
+        assert_eq!(bfs[0].value().as_deref(), Some("al"));
+    }
+
+    #[test]
+    fn test_form_bound_fields_show_initial_while_unbound() {
+        let form = make_test_form().with_initial(HashMap::from([(
+            "username".to_string(),
+            Value::String("guest".into()),
+        )]));
+        let bfs = form.bound_fields();
+        assert!(!bfs[0].is_bound);
+        assert_eq!(bfs[0].value().as_deref(), Some("guest"));
+        assert_eq!(bfs[1].value(), None);
+    }
+
     #[tokio::test]
     async fn test_form_as_context() {
         let mut form = make_test_form();
@@ -444,4 +603,50 @@ mod tests {
         assert!(form.is_valid().await);
         assert!(form.errors().is_empty());
     }
+
+    #[tokio::test]
+    async fn test_form_visibility_rules() {
+        use crate::conditions::Condition;
+
+        let mut form = BaseForm::new(vec![
+            FormFieldDef::new(
+                "account_type",
+                FormFieldType::Char {
+                    min_length: None,
+                    max_length: None,
+                    strip: true,
+                },
+            ),
+            FormFieldDef::new(
+                "company_name",
+                FormFieldType::Char {
+                    min_length: Some(2),
+                    max_length: None,
+                    strip: true,
+                },
+            ),
+        ])
+        .with_visibility_rule(VisibilityRule::new(
+            "company_name",
+            Condition::eq("account_type", "business"),
+        ));
+
+        // Hidden: the required field is not validated and its value dropped.
+        form.bind(&QueryDict::parse("account_type=personal&company_name=x"));
+        assert!(form.is_valid().await);
+        assert!(!form.cleaned_data().contains_key("company_name"));
+        assert!(form.hidden_fields().contains("company_name"));
+
+        // Shown: validated as usual.
+        form.bind(&QueryDict::parse("account_type=business"));
+        assert!(!form.is_valid().await);
+        assert!(form.errors().contains_key("company_name"));
+
+        form.bind(&QueryDict::parse("account_type=business&company_name=Acme"));
+        assert!(form.is_valid().await);
+        assert_eq!(
+            form.cleaned_data().get("company_name"),
+            Some(&Value::String("Acme".into()))
+        );
+    }
 }
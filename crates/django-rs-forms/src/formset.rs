@@ -47,7 +47,9 @@ pub struct FormSet {
     /// The formset prefix for HTML name attributes.
     prefix: String,
     /// Errors specific to the formset (not individual forms).
-    non_form_errors: Vec<String>,
+    pub(crate) non_form_errors: Vec<String>,
+    /// The number of pre-populated forms, when known up front.
+    pub(crate) initial_forms: Option<usize>,
     /// Whether the formset has been bound to data.
     is_bound: bool,
 }
@@ -64,6 +66,7 @@ impl FormSet {
             can_order: false,
             prefix: MANAGEMENT_FORM_PREFIX.to_string(),
             non_form_errors: Vec::new(),
+            initial_forms: None,
             is_bound: false,
         }
     }
@@ -109,9 +112,15 @@ impl FormSet {
         self.forms.len()
     }
 
+    /// Returns the formset prefix.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
     /// Returns the number of initial (pre-populated) forms.
     pub fn initial_form_count(&self) -> usize {
-        self.forms.iter().filter(|f| f.is_bound()).count()
+        self.initial_forms
+            .unwrap_or_else(|| self.forms.iter().filter(|f| f.is_bound()).count())
     }
 
     /// Returns the management form data as a `HashMap`.
@@ -18,10 +18,14 @@
 //! - [`form`] - The [`Form`](form::Form) trait and [`BaseForm`](form::BaseForm) implementation
 //! - [`fields`] - Form field definitions and type-level validation
 //! - [`bound_field`] - Bound fields for template rendering
+//! - [`conditions`] - Conditional field visibility rules
 //! - [`widgets`] - Widget trait and 15+ built-in HTML widgets
 //! - [`validation`] - The validation pipeline (`clean_fields`, `full_clean`)
 //! - [`model_form`] - Model-backed form generation from ORM metadata
 //! - [`formset`] - Formsets for managing collections of forms
+//! - [`model_formset`] - Formsets editing a queryset's rows, or a parent's
+//!   children, and saving the changes in one transaction
+//! - [`filter_form`] - GET-bound filter forms that map fields to ORM lookups
 //!
 //! ## Quick Start
 //!
@@ -92,16 +96,22 @@
 #![allow(clippy::too_many_lines)]
 
 pub mod bound_field;
+pub mod conditions;
 pub mod fields;
+pub mod filter_form;
 pub mod form;
 pub mod formset;
 pub mod model_form;
+pub mod model_formset;
 pub mod validation;
 pub mod widgets;
 
 // Re-export commonly used types at the crate root.
+pub use conditions::{Condition, VisibilityRule};
 pub use fields::{FormFieldDef, FormFieldType};
+pub use filter_form::FilterForm;
 pub use form::{BaseForm, Form};
 pub use formset::FormSet;
 pub use model_form::{ModelFormConfig, ModelFormFields};
+pub use model_formset::{inlineformset_factory, modelformset_factory, ModelFormSet};
 pub use widgets::{Widget, WidgetType};
@@ -9,8 +9,10 @@
 
 use std::collections::HashMap;
 
+use django_rs_core::DjangoError;
 use django_rs_db::fields::{FieldDef, FieldType};
 use django_rs_db::model::ModelMeta;
+use django_rs_db::value::Value;
 
 use crate::fields::{FormFieldDef, FormFieldType};
 use crate::widgets::WidgetType;
@@ -162,9 +164,35 @@ pub fn generate_form_fields(config: &ModelFormConfig) -> Vec<FormFieldDef> {
 }
 
 /// Converts an ORM field type to a form field type.
+///
+/// Fields with choices become a select: integer-backed choices coerce the
+/// submitted value back to an integer, all others stay strings.
 fn model_field_to_form_field_type(field_def: &FieldDef) -> FormFieldType {
+    if let Some(model_choices) = &field_def.choices {
+        let choices = model_choices
+            .iter()
+            .map(|(value, label)| (value.to_string(), label.clone()))
+            .collect();
+        return match field_def.field_type {
+            FieldType::IntegerField | FieldType::BigIntegerField | FieldType::SmallIntegerField => {
+                FormFieldType::TypedChoice {
+                    choices,
+                    coerce: |s| {
+                        s.parse::<i64>()
+                            .map(Value::Int)
+                            .map_err(|e| DjangoError::BadRequest(e.to_string()))
+                    },
+                }
+            }
+            _ => FormFieldType::Choice { choices },
+        };
+    }
+
     match &field_def.field_type {
-        FieldType::CharField | FieldType::TextField => FormFieldType::Char {
+        FieldType::CharField
+        | FieldType::TextField
+        | FieldType::EncryptedCharField { .. }
+        | FieldType::EncryptedTextField { .. } => FormFieldType::Char {
             min_length: None,
             max_length: field_def.max_length,
             strip: true,
@@ -237,7 +265,6 @@ mod tests {
     use super::*;
     use django_rs_db::fields::{FieldDef, FieldType};
     use django_rs_db::model::ModelMeta;
-    use django_rs_db::value::Value;
     use std::sync::LazyLock;
 
     static TEST_META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
@@ -274,6 +301,7 @@ mod tests {
         ],
         constraints: vec![],
         inheritance_type: django_rs_db::query::compiler::InheritanceType::None,
+        db_schema: None,
     });
 
     fn get_test_meta() -> &'static ModelMeta {
@@ -452,4 +480,32 @@ mod tests {
         assert_eq!(fields[0].label, "Article Title");
         assert_eq!(fields[0].help_text, "Enter a title");
     }
+
+    #[test]
+    fn test_choices_become_select() {
+        let status = FieldDef::new("status", FieldType::CharField).choices(vec![
+            (Value::from("draft"), "Draft".to_string()),
+            (Value::from("live"), "Published".to_string()),
+        ]);
+        match model_field_to_form_field_type(&status) {
+            FormFieldType::Choice { choices } => assert_eq!(
+                choices,
+                vec![
+                    ("draft".to_string(), "Draft".to_string()),
+                    ("live".to_string(), "Published".to_string()),
+                ]
+            ),
+            other => panic!("Expected Choice, got {other:?}"),
+        }
+
+        let priority = FieldDef::new("priority", FieldType::IntegerField)
+            .choices(vec![(Value::Int(1), "Low".to_string())]);
+        match model_field_to_form_field_type(&priority) {
+            FormFieldType::TypedChoice { choices, coerce } => {
+                assert_eq!(choices, vec![("1".to_string(), "Low".to_string())]);
+                assert_eq!(coerce("1").unwrap(), Value::Int(1));
+            }
+            other => panic!("Expected TypedChoice, got {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,911 @@
+//! Model formsets — formsets bound to the rows of a queryset.
+//!
+//! [`modelformset_factory`] loads a queryset and builds a [`ModelFormSet`]
+//! with one form per row, pre-filled from the instance, followed by `extra`
+//! empty forms. [`inlineformset_factory`] does the same for the children of
+//! one parent object, such as the lines of an invoice, and ties the new
+//! children to that parent.
+//!
+//! Each pre-filled form carries its instance's primary key in a hidden
+//! field, and the submitted forms are matched to the instances by that key
+//! rather than by position, so a row added or removed between rendering
+//! and submitting can't make a form overwrite the wrong instance.
+//!
+//! After binding and validating the submitted data,
+//! [`ModelFormSet::save`] writes every change in one transaction: changed
+//! rows are updated, filled-in extra forms are created and rows whose
+//! `DELETE` box is ticked are deleted. Extra forms left blank are ignored.
+//!
+//! This mirrors Django's `modelformset_factory` and `inlineformset_factory`.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use django_rs_forms::model_form::ModelFormConfig;
+//! use django_rs_forms::model_formset::inlineformset_factory;
+//!
+//! let mut formset = inlineformset_factory(
+//!     &invoice,
+//!     ModelFormConfig::new(Line::meta()),
+//!     "invoice_id",
+//!     &db,
+//!     2,
+//!     true,
+//! )
+//! .await?;
+//! formset.bind(&request_data);
+//! if formset.is_valid().await {
+//!     let saved = formset.save(&db).await?;
+//!     println!("{} lines added", saved.created.len());
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use django_rs_core::{DjangoError, DjangoResult};
+use django_rs_db::executor::{create_model, DbExecutor};
+use django_rs_db::query::compiler::{OrderBy, Row};
+use django_rs_db::query::{Lookup, Manager, QuerySet, Q};
+use django_rs_db::transactions::atomic;
+use django_rs_db::value::Value;
+use django_rs_db::Model;
+use django_rs_http::QueryDict;
+use django_rs_template::context::ContextValue;
+
+use crate::fields::{FormFieldDef, FormFieldType};
+use crate::form::{BaseForm, Form};
+use crate::formset::FormSet;
+use crate::model_form::{generate_form_fields, ModelFormConfig};
+use crate::widgets::WidgetType;
+
+/// The name of the checkbox marking a form's instance for deletion.
+pub const DELETION_FIELD_NAME: &str = "DELETE";
+
+/// A formset whose forms edit the instances of a model.
+///
+/// Form `i` is rendered for the `i`-th instance of the queryset and edits
+/// the instance whose primary key it submits; the forms after the last
+/// instance are extra forms that create new instances.
+pub struct ModelFormSet<M: Model> {
+    /// The forms: one per instance, then the extra forms.
+    pub formset: FormSet,
+    config: ModelFormConfig,
+    instances: Vec<M>,
+    /// The instance each initial form edits, by index into `instances`.
+    matched: Vec<Option<usize>>,
+    /// The foreign key column and value set on every new instance.
+    fk: Option<(String, Value)>,
+    /// Extra forms submitted without any value.
+    blank: HashSet<usize>,
+    /// Forms whose instance is marked for deletion.
+    deleted: HashSet<usize>,
+    validated: bool,
+}
+
+/// The instances written by [`ModelFormSet::save`].
+#[derive(Debug)]
+pub struct SavedModelFormSet<M> {
+    /// Instances created from extra forms, with their primary keys set.
+    pub created: Vec<M>,
+    /// Instances whose changed fields were updated.
+    pub changed: Vec<M>,
+    /// Instances that were deleted.
+    pub deleted: Vec<M>,
+}
+
+/// Builds a formset editing the rows of `queryset`, with `extra` empty forms
+/// for new rows.
+///
+/// Querysets without an ordering are ordered by primary key, so that the
+/// forms line up with the same rows when the submitted data is bound.
+///
+/// # Errors
+///
+/// Returns an error if the queryset cannot be loaded.
+pub async fn modelformset_factory<M: Model>(
+    config: ModelFormConfig,
+    queryset: QuerySet<M>,
+    db: &dyn DbExecutor,
+    extra: usize,
+    can_delete: bool,
+) -> DjangoResult<ModelFormSet<M>> {
+    let queryset = if queryset.query().order_by.is_empty() {
+        queryset.order_by(vec![OrderBy::asc(M::pk_field_name())])
+    } else {
+        queryset
+    };
+    let instances = queryset.execute_query(db).await?;
+    Ok(ModelFormSet::new(config, instances, extra, can_delete))
+}
+
+/// Builds a formset editing the children of `parent` — the rows of the
+/// child model whose `fk` column holds the parent's primary key — with
+/// `extra` empty forms for new children.
+///
+/// The formset's prefix is `"<child model>_set"`, and every child it
+/// creates gets the parent's primary key in `fk`.
+///
+/// # Errors
+///
+/// Returns an error if the parent has no primary key or the children
+/// cannot be loaded.
+pub async fn inlineformset_factory<P: Model, C: Model>(
+    parent: &P,
+    config: ModelFormConfig,
+    fk: &str,
+    db: &dyn DbExecutor,
+    extra: usize,
+    can_delete: bool,
+) -> DjangoResult<ModelFormSet<C>> {
+    let parent_pk = pk_value(parent).ok_or_else(|| {
+        DjangoError::BadRequest(format!(
+            "Cannot edit the {} of an unsaved {}",
+            C::meta().verbose_name_plural,
+            P::meta().verbose_name
+        ))
+    })?;
+    let queryset = Manager::<C>::new().filter(Q::filter(fk, Lookup::Exact(parent_pk.clone())));
+    let formset = modelformset_factory(config, queryset, db, extra, can_delete).await?;
+    Ok(formset
+        .with_prefix(format!("{}_set", C::meta().model_name))
+        .with_fk(fk, parent_pk))
+}
+
+impl<M: Model> ModelFormSet<M> {
+    /// Creates a formset editing the given instances, with `extra` empty
+    /// forms for new ones.
+    pub fn new(config: ModelFormConfig, instances: Vec<M>, extra: usize, can_delete: bool) -> Self {
+        let mut formset = FormSet::new(Vec::new())
+            .with_extra(extra)
+            .with_can_delete(can_delete);
+        formset.initial_forms = Some(instances.len());
+        let mut model_formset = Self {
+            formset,
+            config,
+            matched: (0..instances.len()).map(Some).collect(),
+            instances,
+            fk: None,
+            blank: HashSet::new(),
+            deleted: HashSet::new(),
+            validated: false,
+        };
+        model_formset.build_forms();
+        model_formset
+    }
+
+    /// Sets the formset prefix.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.formset =
+            std::mem::replace(&mut self.formset, FormSet::new(Vec::new())).with_prefix(prefix);
+        self.build_forms();
+        self
+    }
+
+    /// Sets a column written on every instance the formset creates.
+    pub fn with_fk(mut self, column: impl Into<String>, value: Value) -> Self {
+        self.fk = Some((column.into(), value));
+        self
+    }
+
+    /// Returns the instances edited by the initial forms.
+    pub fn instances(&self) -> &[M] {
+        &self.instances
+    }
+
+    /// Returns the instance edited by the form at `index`, or `None` for
+    /// an extra form or a form whose submitted primary key matches no
+    /// instance.
+    pub fn instance(&self, index: usize) -> Option<&M> {
+        self.matched
+            .get(index)
+            .copied()
+            .flatten()
+            .map(|i| &self.instances[i])
+    }
+
+    /// Rebuilds one form per instance plus the extra forms.
+    fn build_forms(&mut self) {
+        let prefix = self.formset.prefix().to_string();
+        let total = self.instances.len() + self.formset.extra;
+        self.formset.forms = (0..total)
+            .map(|i| {
+                let mut fields = generate_form_fields(&self.config);
+                if self.formset.can_delete {
+                    fields.push(
+                        FormFieldDef::new(DELETION_FIELD_NAME, FormFieldType::Boolean)
+                            .required(false),
+                    );
+                }
+                let Some(instance) = self.instances.get(i) else {
+                    let form: Box<dyn Form> =
+                        Box::new(BaseForm::new(fields).with_prefix(format!("{prefix}-{i}")));
+                    return form;
+                };
+                fields.push(
+                    FormFieldDef::new(
+                        M::pk_field_name(),
+                        FormFieldType::Char {
+                            min_length: None,
+                            max_length: None,
+                            strip: true,
+                        },
+                    )
+                    .widget(WidgetType::HiddenInput),
+                );
+                let mut initial: HashMap<String, Value> = instance
+                    .field_values()
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect();
+                if let Some(pk) = pk_value(instance) {
+                    initial.insert(M::pk_field_name().to_string(), pk);
+                }
+                let form: Box<dyn Form> = Box::new(
+                    BaseForm::new(fields)
+                        .with_prefix(format!("{prefix}-{i}"))
+                        .with_initial(initial),
+                );
+                form
+            })
+            .collect();
+    }
+
+    /// Binds the submitted data to the forms, matching each initial form to
+    /// the instance whose primary key it submits.
+    ///
+    /// If the management form's `TOTAL_FORMS` asks for more extra forms
+    /// than were rendered, as when a page adds rows client-side, they are
+    /// added, up to `max_num` forms in all.
+    pub fn bind(&mut self, data: &QueryDict) {
+        let prefix = self.formset.prefix().to_string();
+        let submitted_total = data
+            .get(&format!("{prefix}-TOTAL_FORMS"))
+            .and_then(|total| total.trim().parse::<usize>().ok())
+            .map(|total| total.min(self.formset.max_num));
+        if let Some(total) = submitted_total {
+            let extra = total.saturating_sub(self.instances.len());
+            if extra > self.formset.extra {
+                self.formset.extra = extra;
+                self.build_forms();
+            }
+        }
+
+        self.formset.bind(data);
+        self.validated = false;
+        self.blank.clear();
+        self.deleted.clear();
+        self.matched = (0..self.instances.len())
+            .map(|i| {
+                let pk = data
+                    .get(&format!("{prefix}-{i}-{}", M::pk_field_name()))
+                    .map(str::trim)?;
+                self.instances
+                    .iter()
+                    .position(|instance| pk_value(instance).is_some_and(|v| v.to_string() == pk))
+            })
+            .collect();
+        for (i, form) in self.formset.forms.iter().enumerate() {
+            let raw = |name: &str| data.get(&format!("{prefix}-{i}-{name}")).map(str::trim);
+            if self.formset.can_delete
+                && raw(DELETION_FIELD_NAME).is_some_and(|value| {
+                    matches!(value.to_lowercase().as_str(), "true" | "1" | "yes" | "on")
+                })
+            {
+                self.deleted.insert(i);
+            }
+            let is_extra = i >= self.instances.len();
+            if is_extra
+                && form
+                    .fields()
+                    .iter()
+                    .filter(|field| field.name != DELETION_FIELD_NAME)
+                    .all(|field| match raw(&field.name) {
+                        None | Some("") => true,
+                        Some(value) => field
+                            .initial
+                            .as_ref()
+                            .is_some_and(|initial| initial.to_string() == value),
+                    })
+            {
+                self.blank.insert(i);
+            }
+        }
+    }
+
+    /// Validates every form except blank extra forms and forms marked for
+    /// deletion, then checks that every initial form submitted the primary
+    /// key of a distinct instance and the formset's `min_num` and `max_num`.
+    pub async fn is_valid(&mut self) -> bool {
+        if !self.formset.is_bound() {
+            return false;
+        }
+
+        self.formset.non_form_errors.clear();
+        let mut all_valid = true;
+        let mut seen = HashSet::new();
+        for (i, matched) in self.matched.iter().enumerate() {
+            if !matched.is_some_and(|instance| seen.insert(instance)) {
+                self.formset.non_form_errors.push(format!(
+                    "Form {i} does not edit one of the {} in this formset.",
+                    M::meta().verbose_name_plural
+                ));
+                all_valid = false;
+            }
+        }
+        let mut submitted = 0;
+        for (i, form) in self.formset.forms.iter_mut().enumerate() {
+            if self.blank.contains(&i) || self.deleted.contains(&i) {
+                continue;
+            }
+            submitted += 1;
+            if !form.is_valid().await {
+                all_valid = false;
+            }
+        }
+
+        if submitted < self.formset.min_num {
+            self.formset.non_form_errors.push(format!(
+                "Please submit at least {} forms.",
+                self.formset.min_num
+            ));
+            all_valid = false;
+        }
+        if submitted > self.formset.max_num {
+            self.formset.non_form_errors.push(format!(
+                "Please submit at most {} forms.",
+                self.formset.max_num
+            ));
+            all_valid = false;
+        }
+
+        self.validated = all_valid;
+        all_valid
+    }
+
+    /// Returns formset-level (non-form) errors.
+    pub fn non_form_errors(&self) -> &[String] {
+        self.formset.non_form_errors()
+    }
+
+    /// Generates a template context for the formset.
+    pub fn as_context(&self) -> HashMap<String, ContextValue> {
+        self.formset.as_context()
+    }
+
+    /// Writes the validated changes through `db` in one transaction.
+    ///
+    /// Instances marked for deletion are deleted, instances with changed
+    /// fields have those fields updated, and filled-in extra forms are
+    /// created. If any statement fails, nothing is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the formset has not been successfully validated,
+    /// a new instance cannot be built from its form, or a statement fails.
+    pub async fn save(self, db: &dyn DbExecutor) -> DjangoResult<SavedModelFormSet<M>> {
+        if !self.validated {
+            return Err(DjangoError::BadRequest(
+                "Cannot save a formset that has not been validated".to_string(),
+            ));
+        }
+        let Self {
+            formset,
+            instances,
+            matched,
+            fk,
+            blank,
+            deleted,
+            ..
+        } = self;
+        let initial = instances.len();
+        let mut instances = instances.into_iter().map(Some).collect::<Vec<_>>();
+
+        atomic(db, |txn| async move {
+            let txn: &dyn DbExecutor = txn.as_ref();
+            let mut saved = SavedModelFormSet {
+                created: Vec::new(),
+                changed: Vec::new(),
+                deleted: Vec::new(),
+            };
+            for (i, form) in formset.forms.iter().enumerate() {
+                if i < initial {
+                    let Some(instance) = matched[i].and_then(|index| instances[index].take())
+                    else {
+                        continue;
+                    };
+                    if deleted.contains(&i) {
+                        delete_instance(&instance, txn).await?;
+                        saved.deleted.push(instance);
+                    } else if let Some(instance) =
+                        update_instance(instance, form.as_ref(), txn).await?
+                    {
+                        saved.changed.push(instance);
+                    }
+                } else if !blank.contains(&i) && !deleted.contains(&i) {
+                    saved
+                        .created
+                        .push(create_instance::<M>(form.as_ref(), fk.as_ref(), txn).await?);
+                }
+            }
+            Ok(saved)
+        })
+        .await
+    }
+}
+
+/// Returns an instance's primary key, read from its field values when the
+/// model does not expose it through [`Model::pk`].
+fn pk_value<M: Model>(instance: &M) -> Option<Value> {
+    instance.pk().cloned().or_else(|| {
+        instance
+            .field_values()
+            .into_iter()
+            .find(|(name, _)| *name == M::pk_field_name())
+            .map(|(_, value)| value)
+            .filter(|value| !matches!(value, Value::Null | Value::Int(0)))
+    })
+}
+
+fn missing_pk<M: Model>() -> DjangoError {
+    DjangoError::DatabaseError(format!(
+        "Cannot save a {} without a primary key",
+        M::meta().verbose_name
+    ))
+}
+
+async fn delete_instance<M: Model>(instance: &M, db: &dyn DbExecutor) -> DjangoResult<()> {
+    let pk = pk_value(instance).ok_or_else(missing_pk::<M>)?;
+    Manager::<M>::new()
+        .filter(Q::filter(M::pk_field_name(), Lookup::Exact(pk)))
+        .delete()
+        .delete_exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Updates the fields the form changed, returning the updated instance, or
+/// `None` if nothing changed.
+async fn update_instance<M: Model>(
+    instance: M,
+    form: &dyn Form,
+    db: &dyn DbExecutor,
+) -> DjangoResult<Option<M>> {
+    let current = instance.field_values();
+    let changes: Vec<(&'static str, Value)> = current
+        .iter()
+        .filter(|(name, _)| *name != M::pk_field_name())
+        .filter_map(|(name, value)| {
+            let cleaned = form.cleaned_data().get(*name)?;
+            (cleaned != value).then(|| (*name, cleaned.clone()))
+        })
+        .collect();
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let pk = pk_value(&instance).ok_or_else(missing_pk::<M>)?;
+    Manager::<M>::new()
+        .filter(Q::filter(M::pk_field_name(), Lookup::Exact(pk)))
+        .update(changes.clone())
+        .update_exec(db)
+        .await?;
+
+    let (columns, values) = current
+        .into_iter()
+        .map(|(name, value)| {
+            let value = changes
+                .iter()
+                .find(|(changed, _)| *changed == name)
+                .map_or(value, |(_, changed)| changed.clone());
+            (name.to_string(), value)
+        })
+        .unzip();
+    M::from_row(&Row::new(columns, values)).map(Some)
+}
+
+/// Creates an instance from an extra form's cleaned data.
+async fn create_instance<M: Model>(
+    form: &dyn Form,
+    fk: Option<&(String, Value)>,
+    db: &dyn DbExecutor,
+) -> DjangoResult<M> {
+    let mut object: serde_json::Map<String, serde_json::Value> = form
+        .cleaned_data()
+        .iter()
+        .filter(|(name, _)| name.as_str() != DELETION_FIELD_NAME)
+        .map(|(name, value)| (name.clone(), cleaned_to_json(value)))
+        .collect();
+    if let Some((column, value)) = fk {
+        object.insert(column.clone(), cleaned_to_json(value));
+    }
+    let mut instance = M::from_json(&serde_json::Value::Object(object))?;
+    create_model(&mut instance, db).await?;
+    Ok(instance)
+}
+
+/// Converts a cleaned form value to the JSON [`Model::from_json`] reads.
+fn cleaned_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Int(i) => serde_json::Value::from(*i),
+        Value::Float(f) => serde_json::Value::from(*f),
+        Value::DateTimeTz(dt) => serde_json::Value::String(dt.to_rfc3339()),
+        Value::Duration(d) => d
+            .num_microseconds()
+            .map_or(serde_json::Value::Null, serde_json::Value::from),
+        Value::Json(json) => json.clone(),
+        Value::List(items) => serde_json::Value::Array(items.iter().map(cleaned_to_json).collect()),
+        other => serde_json::Value::String(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_db::fields::{FieldDef, FieldType, OnDelete};
+    use django_rs_db::model::ModelMeta;
+    use django_rs_db::query::compiler::{DatabaseBackendType, InheritanceType};
+    use std::sync::{LazyLock, Mutex};
+
+    static LINE_META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
+        app_label: "shop",
+        model_name: "line",
+        db_table: "shop_line".to_string(),
+        verbose_name: "line".to_string(),
+        verbose_name_plural: "lines".to_string(),
+        ordering: vec![],
+        unique_together: vec![],
+        indexes: vec![],
+        abstract_model: false,
+        fields: vec![
+            FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+            FieldDef::new(
+                "invoice_id",
+                FieldType::ForeignKey {
+                    to: "shop.invoice".to_string(),
+                    on_delete: OnDelete::Cascade,
+                    related_name: None,
+                },
+            ),
+            FieldDef::new("description", FieldType::CharField).max_length(100),
+            FieldDef::new("quantity", FieldType::IntegerField).default(Value::Int(1)),
+        ],
+        constraints: vec![],
+        inheritance_type: InheritanceType::None,
+        db_schema: None,
+    });
+
+    #[derive(Debug)]
+    struct Line {
+        id: i64,
+        invoice_id: i64,
+        description: String,
+        quantity: i64,
+    }
+
+    impl Line {
+        fn new(id: i64, description: &str, quantity: i64) -> Self {
+            Self {
+                id,
+                invoice_id: 7,
+                description: description.to_string(),
+                quantity,
+            }
+        }
+
+        fn row(&self) -> Row {
+            let (columns, values) = self
+                .field_values()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .unzip();
+            Row::new(columns, values)
+        }
+    }
+
+    impl Model for Line {
+        fn meta() -> &'static ModelMeta {
+            &LINE_META
+        }
+        fn table_name() -> &'static str {
+            "shop_line"
+        }
+        fn app_label() -> &'static str {
+            "shop"
+        }
+        fn pk(&self) -> Option<&Value> {
+            None
+        }
+        fn set_pk(&mut self, value: Value) {
+            if let Value::Int(id) = value {
+                self.id = id;
+            }
+        }
+        fn field_values(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("id", Value::Int(self.id)),
+                ("invoice_id", Value::Int(self.invoice_id)),
+                ("description", Value::String(self.description.clone())),
+                ("quantity", Value::Int(self.quantity)),
+            ]
+        }
+        fn from_row(row: &Row) -> DjangoResult<Self> {
+            Ok(Self {
+                id: row.get("id")?,
+                invoice_id: row.get("invoice_id")?,
+                description: row.get("description")?,
+                quantity: row.get("quantity")?,
+            })
+        }
+    }
+
+    /// Answers queries with fixed rows and records every statement.
+    struct RecordingDb {
+        rows: Vec<Row>,
+        statements: Mutex<Vec<(String, Vec<Value>)>>,
+    }
+
+    impl RecordingDb {
+        fn new(lines: &[Line]) -> Self {
+            Self {
+                rows: lines.iter().map(Line::row).collect(),
+                statements: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn statements(&self) -> Vec<(String, Vec<Value>)> {
+            self.statements.lock().unwrap().clone()
+        }
+
+        fn record(&self, sql: &str, params: &[Value]) {
+            self.statements
+                .lock()
+                .unwrap()
+                .push((sql.to_string(), params.to_vec()));
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DbExecutor for RecordingDb {
+        fn backend_type(&self) -> DatabaseBackendType {
+            DatabaseBackendType::PostgreSQL
+        }
+        async fn execute_sql(&self, sql: &str, params: &[Value]) -> DjangoResult<u64> {
+            self.record(sql, params);
+            Ok(1)
+        }
+        async fn query(&self, sql: &str, params: &[Value]) -> DjangoResult<Vec<Row>> {
+            self.record(sql, params);
+            Ok(self.rows.clone())
+        }
+        async fn query_one(&self, sql: &str, params: &[Value]) -> DjangoResult<Row> {
+            self.record(sql, params);
+            self.rows
+                .first()
+                .cloned()
+                .ok_or_else(|| DjangoError::DoesNotExist("no rows".to_string()))
+        }
+        async fn insert_returning_id(&self, sql: &str, params: &[Value]) -> DjangoResult<Value> {
+            self.record(sql, params);
+            Ok(Value::Int(100))
+        }
+    }
+
+    fn config() -> ModelFormConfig {
+        ModelFormConfig::new(&LINE_META)
+    }
+
+    fn lines() -> Vec<Line> {
+        vec![Line::new(1, "Widget", 2), Line::new(2, "Gadget", 5)]
+    }
+
+    #[tokio::test]
+    async fn test_factory_builds_forms_from_queryset() {
+        let db = RecordingDb::new(&lines());
+        let formset = modelformset_factory(config(), Manager::<Line>::new().all(), &db, 1, true)
+            .await
+            .unwrap();
+
+        assert_eq!(formset.instances().len(), 2);
+        assert_eq!(formset.formset.total_form_count(), 3);
+        assert_eq!(formset.formset.initial_form_count(), 2);
+        assert!(db.statements()[0].0.contains("ORDER BY \"id\""));
+
+        let first = &formset.formset.forms[0];
+        assert_eq!(first.prefix(), Some("form-0"));
+        assert_eq!(
+            first.initial().get("description"),
+            Some(&Value::String("Widget".into()))
+        );
+        assert!(first.fields().iter().any(|f| f.name == DELETION_FIELD_NAME));
+        let pk = first.fields().iter().find(|f| f.name == "id").unwrap();
+        assert_eq!(pk.widget, WidgetType::HiddenInput);
+        assert_eq!(first.initial().get("id"), Some(&Value::Int(1)));
+        assert!(formset.formset.forms[2].initial().is_empty());
+        assert!(!formset.formset.forms[2]
+            .fields()
+            .iter()
+            .any(|f| f.name == "id"));
+        assert!(formset.instance(2).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_save_updates_creates_and_deletes() {
+        let db = RecordingDb::new(&lines());
+        let mut formset =
+            modelformset_factory(config(), Manager::<Line>::new().all(), &db, 2, true)
+                .await
+                .unwrap()
+                .with_fk("invoice_id", Value::Int(7));
+        formset.bind(&QueryDict::parse(
+            "form-TOTAL_FORMS=4&form-INITIAL_FORMS=2\
+             &form-0-id=1&form-0-description=Widget&form-0-quantity=3\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5&form-1-DELETE=on\
+             &form-2-description=Sprocket&form-2-quantity=1\
+             &form-3-description=&form-3-quantity=1",
+        ));
+        assert!(formset.is_valid().await);
+
+        let saved = formset.save(&db).await.unwrap();
+        assert_eq!(saved.changed.len(), 1);
+        assert_eq!(saved.changed[0].quantity, 3);
+        assert_eq!(saved.deleted[0].id, 2);
+        assert_eq!(saved.created.len(), 1);
+        assert_eq!(saved.created[0].id, 100);
+        assert_eq!(saved.created[0].description, "Sprocket");
+
+        let statements = db.statements();
+        let sql: Vec<&str> = statements[1..]
+            .iter()
+            .map(|(sql, _)| sql.as_str())
+            .collect();
+        assert_eq!(sql[0], "BEGIN");
+        assert!(sql[1].starts_with("UPDATE \"shop_line\" SET \"quantity\""));
+        assert_eq!(statements[2].1, vec![Value::Int(3), Value::Int(1)]);
+        assert!(sql[2].starts_with("DELETE FROM \"shop_line\""));
+        assert!(sql[3].starts_with("INSERT INTO \"shop_line\""));
+        assert_eq!(sql[4], "COMMIT");
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_rolls_back() {
+        let db = RecordingDb::new(&lines());
+        let mut formset =
+            modelformset_factory(config(), Manager::<Line>::new().all(), &db, 1, false)
+                .await
+                .unwrap();
+        formset.bind(&QueryDict::parse(
+            "form-0-id=1&form-0-description=Widget&form-0-quantity=4\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5\
+             &form-2-description=Orphan",
+        ));
+        assert!(formset.is_valid().await);
+
+        // Without a foreign key value the new line can't be built.
+        let error = formset.save(&db).await.unwrap_err();
+        assert!(error.to_string().contains("invoice_id"));
+        let statements = db.statements();
+        assert_eq!(statements.last().unwrap().0, "ROLLBACK");
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_and_blank_forms_are_skipped() {
+        let db = RecordingDb::new(&lines());
+        let mut formset =
+            modelformset_factory(config(), Manager::<Line>::new().all(), &db, 1, false)
+                .await
+                .unwrap();
+        formset.bind(&QueryDict::parse(
+            "form-0-id=1&form-0-description=Widget&form-0-quantity=2\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5",
+        ));
+        assert!(formset.is_valid().await);
+        let saved = formset.save(&db).await.unwrap();
+        assert!(saved.changed.is_empty() && saved.created.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forms_match_instances_by_pk() {
+        let db = RecordingDb::new(&lines());
+        let mut formset = ModelFormSet::new(config(), lines(), 0, true);
+        // The page was rendered with the rows in the other order.
+        formset.bind(&QueryDict::parse(
+            "form-0-id=2&form-0-description=Gadget&form-0-quantity=6\
+             &form-1-id=1&form-1-description=Widget&form-1-quantity=2&form-1-DELETE=on",
+        ));
+        assert!(formset.is_valid().await);
+        assert_eq!(formset.instance(0).unwrap().id, 2);
+
+        let saved = formset.save(&db).await.unwrap();
+        assert_eq!(saved.changed[0].id, 2);
+        assert_eq!(saved.changed[0].quantity, 6);
+        assert_eq!(saved.deleted[0].id, 1);
+        let statements = db.statements();
+        assert_eq!(statements[1].1, vec![Value::Int(6), Value::Int(2)]);
+        assert_eq!(statements[2].1, vec![Value::Int(1)]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_or_repeated_pk_fails_validation() {
+        let mut formset = ModelFormSet::new(config(), lines(), 0, false);
+        formset.bind(&QueryDict::parse(
+            "form-0-id=9&form-0-description=Widget&form-0-quantity=2\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5",
+        ));
+        assert!(!formset.is_valid().await);
+        assert!(formset.instance(0).is_none());
+        assert_eq!(
+            formset.non_form_errors(),
+            ["Form 0 does not edit one of the lines in this formset."]
+        );
+
+        formset.bind(&QueryDict::parse(
+            "form-0-id=2&form-0-description=Widget&form-0-quantity=2\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5",
+        ));
+        assert!(!formset.is_valid().await);
+        assert_eq!(formset.non_form_errors().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bind_adds_forms_from_total_forms() {
+        let mut formset = ModelFormSet::new(config(), lines(), 1, false);
+        formset.bind(&QueryDict::parse(
+            "form-TOTAL_FORMS=4&form-0-id=1&form-0-description=Widget&form-0-quantity=2\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5\
+             &form-3-description=Bolt",
+        ));
+        assert_eq!(formset.formset.total_form_count(), 4);
+        assert!(formset.is_valid().await);
+        assert_eq!(
+            formset.formset.forms[3].cleaned_data().get("description"),
+            Some(&Value::String("Bolt".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_form_fails_validation() {
+        let mut formset = ModelFormSet::new(config(), lines(), 1, false);
+        formset.bind(&QueryDict::parse(
+            "form-0-id=1&form-0-description=&form-0-quantity=2\
+             &form-1-id=2&form-1-description=Gadget&form-1-quantity=5",
+        ));
+        assert!(!formset.is_valid().await);
+        assert!(formset.formset.forms[0]
+            .errors()
+            .contains_key("description"));
+
+        let db = RecordingDb::new(&[]);
+        assert!(formset.save(&db).await.is_err());
+        assert!(db.statements().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_inline_formset_ties_children_to_parent() {
+        let parent = Line::new(7, "Invoice", 1);
+        let db = RecordingDb::new(&lines());
+        let mut formset =
+            inlineformset_factory::<Line, Line>(&parent, config(), "invoice_id", &db, 1, false)
+                .await
+                .unwrap();
+        let (sql, params) = &db.statements()[0];
+        assert!(sql.contains("WHERE \"invoice_id\" = $1"));
+        assert_eq!(params, &vec![Value::Int(7)]);
+        assert_eq!(formset.formset.prefix(), "line_set");
+        assert!(!formset.formset.forms[0]
+            .fields()
+            .iter()
+            .any(|f| f.name == "invoice_id"));
+
+        formset.bind(&QueryDict::parse(
+            "line_set-0-id=1&line_set-0-description=Widget&line_set-0-quantity=2\
+             &line_set-1-id=2&line_set-1-description=Gadget&line_set-1-quantity=5\
+             &line_set-2-description=Nut&line_set-2-quantity=9",
+        ));
+        assert!(formset.is_valid().await);
+        let saved = formset.save(&db).await.unwrap();
+        assert_eq!(saved.created[0].invoice_id, 7);
+        assert_eq!(saved.created[0].quantity, 9);
+    }
+}
@@ -11,16 +11,18 @@
 
 use std::collections::HashMap;
 
+use django_rs_core::ValidationError;
 use django_rs_db::value::Value;
 
-use crate::fields::{clean_field_value, FormFieldDef};
+use crate::fields::{clean_field_value_with_codes, FormFieldDef};
 use crate::form::Form;
 
 /// Performs field-level validation for all fields.
 ///
 /// For each field definition:
 /// 1. Extracts the raw value from the data map
-/// 2. Runs [`clean_field_value`] for type coercion and field-level validation
+/// 2. Runs [`clean_field_value`](crate::fields::clean_field_value) for type
+///    coercion and field-level validation
 /// 3. Populates `cleaned_data` on success or `errors` on failure
 ///
 /// Errors accumulate across all fields (no short-circuiting).
@@ -29,6 +31,23 @@ pub fn clean_fields(
     raw_data: &HashMap<String, Option<String>>,
     cleaned_data: &mut HashMap<String, Value>,
     errors: &mut HashMap<String, Vec<String>>,
+) {
+    let mut coded = HashMap::new();
+    clean_fields_with_codes(field_defs, raw_data, cleaned_data, &mut coded);
+    errors.extend(
+        coded.into_iter().map(|(name, field_errors)| {
+            (name, field_errors.into_iter().map(|e| e.message).collect())
+        }),
+    );
+}
+
+/// Performs field-level validation like [`clean_fields`], keeping the code
+/// of each error.
+pub fn clean_fields_with_codes(
+    field_defs: &[FormFieldDef],
+    raw_data: &HashMap<String, Option<String>>,
+    cleaned_data: &mut HashMap<String, Value>,
+    errors: &mut HashMap<String, Vec<ValidationError>>,
 ) {
     for field in field_defs {
         if field.disabled {
@@ -41,7 +60,7 @@ pub fn clean_fields(
 
         let raw = raw_data.get(&field.name).and_then(|v| v.as_deref());
 
-        match clean_field_value(field, raw) {
+        match clean_field_value_with_codes(field, raw) {
             Ok(value) => {
                 cleaned_data.insert(field.name.clone(), value);
             }
@@ -11,6 +11,7 @@ use std::collections::HashMap;
 use std::fmt;
 
 use django_rs_http::QueryDict;
+use django_rs_template::context::escape_html;
 
 /// Enumerates all built-in widget types.
 ///
@@ -112,18 +113,51 @@ pub trait Widget: Send + Sync + fmt::Debug {
 }
 
 /// Formats an HTML attributes map into a string like ` key="value" key2="value2"`.
+///
+/// Attribute values are HTML-escaped.
 fn render_attrs(attrs: &HashMap<String, String>) -> String {
     if attrs.is_empty() {
         return String::new();
     }
     let mut parts: Vec<String> = attrs
         .iter()
-        .map(|(k, v)| format!(r#" {k}="{v}""#))
+        .map(|(k, v)| format!(r#" {k}="{}""#, escape_html(v)))
         .collect();
     parts.sort(); // deterministic output for testing
     parts.join("")
 }
 
+/// Merges `overrides` into `base` attributes.
+///
+/// Later values replace earlier ones, except `class`, whose classes are
+/// combined.
+pub fn merge_attrs(
+    base: &HashMap<String, String>,
+    overrides: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged = base.clone();
+    for (name, value) in overrides {
+        let value = match (name.as_str(), merged.get(name)) {
+            ("class", Some(existing)) => merge_classes(existing, value),
+            _ => value.clone(),
+        };
+        merged.insert(name.clone(), value);
+    }
+    merged
+}
+
+/// Appends the space-separated classes of `extra` to `classes`, skipping
+/// duplicates.
+pub fn merge_classes(classes: &str, extra: &str) -> String {
+    let mut merged: Vec<&str> = classes.split_whitespace().collect();
+    for class in extra.split_whitespace() {
+        if !merged.contains(&class) {
+            merged.push(class);
+        }
+    }
+    merged.join(" ")
+}
+
 // ---------------------------------------------------------------------------
 // Built-in widgets
 // ---------------------------------------------------------------------------
@@ -1113,4 +1147,28 @@ mod tests {
         let w = CheckboxSelectMultiple::new(vec![]);
         assert_eq!(w.id_for_label("id_items"), "id_items_0");
     }
+
+    #[test]
+    fn test_merge_attrs_combines_classes() {
+        let base = HashMap::from([
+            ("class".to_string(), "input".to_string()),
+            ("placeholder".to_string(), "Name".to_string()),
+        ]);
+        let overrides = HashMap::from([
+            ("class".to_string(), "input wide".to_string()),
+            ("placeholder".to_string(), "Full name".to_string()),
+        ]);
+        let merged = merge_attrs(&base, &overrides);
+        assert_eq!(merged["class"], "input wide");
+        assert_eq!(merged["placeholder"], "Full name");
+    }
+
+    #[test]
+    fn test_render_attrs_escapes_values() {
+        let attrs = HashMap::from([("placeholder".to_string(), r#"Say "hi" <b>"#.to_string())]);
+        assert_eq!(
+            render_attrs(&attrs),
+            r#" placeholder="Say &quot;hi&quot; &lt;b&gt;""#
+        );
+    }
 }
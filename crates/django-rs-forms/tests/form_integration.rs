@@ -105,6 +105,7 @@ static ARTICLE_META: LazyLock<ModelMeta> = LazyLock::new(|| ModelMeta {
     ],
     constraints: vec![],
     inheritance_type: InheritanceType::None,
+    db_schema: None,
 });
 
 fn get_article_meta() -> &'static ModelMeta {
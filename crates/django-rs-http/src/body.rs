@@ -0,0 +1,271 @@
+//! Streaming request bodies.
+//!
+//! [`BodyStream`] yields a request body chunk by chunk instead of buffering it
+//! in memory, so large uploads can be piped straight to a storage backend.
+//! Chunks are only pulled from the connection when the consumer asks for the
+//! next one, which gives natural backpressure: a slow writer slows down the
+//! client rather than growing a buffer.
+//!
+//! A `BodyStream` can be consumed as a [`Stream`] of [`Bytes`], as a
+//! [`tokio::io::AsyncRead`], or copied into any [`tokio::io::AsyncWrite`]
+//! with [`BodyStream::copy_to`].
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_http::body::BodyStream;
+//!
+//! # async fn example() {
+//! let mut stream = BodyStream::from_chunks(vec![b"hello ".to_vec(), b"world".to_vec()]);
+//! let mut out = Vec::new();
+//! let copied = stream.copy_to(&mut out).await.unwrap();
+//! assert_eq!(copied, 11);
+//! assert_eq!(out, b"hello world");
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use django_rs_core::{DjangoError, DjangoResult};
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+/// A boxed stream of body chunks.
+type BoxedChunks = Pin<Box<dyn Stream<Item = Result<Bytes, DjangoError>> + Send>>;
+
+/// A request body delivered as a stream of chunks.
+pub struct BodyStream {
+    /// Wrapped in a mutex only so `BodyStream` (and so `HttpRequest`) is
+    /// `Sync`; polling goes through `&mut self` and never locks.
+    inner: Mutex<BoxedChunks>,
+    limit: Option<usize>,
+    bytes_read: usize,
+    /// The unread remainder of the last chunk, used by `AsyncRead`.
+    pending: Bytes,
+}
+
+impl BodyStream {
+    /// Creates a body stream from any stream of byte chunks.
+    pub fn new(stream: BoxedChunks) -> Self {
+        Self {
+            inner: Mutex::new(stream),
+            limit: None,
+            bytes_read: 0,
+            pending: Bytes::new(),
+        }
+    }
+
+    /// Creates a body stream reading from an Axum request body.
+    pub fn from_axum(body: axum::body::Body) -> Self {
+        Self::new(Box::pin(AxumBodyStream(body.into_data_stream())))
+    }
+
+    /// Creates a body stream that yields the given chunks in order.
+    pub fn from_chunks<B: Into<Bytes>>(chunks: impl IntoIterator<Item = B>) -> Self {
+        Self::new(Box::pin(ChunkStream(
+            chunks.into_iter().map(Into::into).collect(),
+        )))
+    }
+
+    /// Creates a body stream over an already buffered body.
+    pub fn from_bytes(body: impl Into<Bytes>) -> Self {
+        let body = body.into();
+        if body.is_empty() {
+            Self::from_chunks(Vec::<Bytes>::new())
+        } else {
+            Self::from_chunks([body])
+        }
+    }
+
+    /// Limits the total body size to `max_bytes`.
+    ///
+    /// Reading past the limit yields a [`DjangoError::BadRequest`], like
+    /// Django's `DATA_UPLOAD_MAX_MEMORY_SIZE` check.
+    #[must_use]
+    pub const fn limit(mut self, max_bytes: usize) -> Self {
+        self.limit = Some(max_bytes);
+        self
+    }
+
+    /// Returns the number of body bytes read so far.
+    pub const fn bytes_read(&self) -> usize {
+        self.bytes_read
+    }
+
+    /// Polls for the next chunk, enforcing the size limit.
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<DjangoResult<Bytes>>> {
+        if !self.pending.is_empty() {
+            return Poll::Ready(Some(Ok(std::mem::take(&mut self.pending))));
+        }
+        let inner = self.inner.get_mut().unwrap_or_else(PoisonError::into_inner);
+        match inner.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                self.bytes_read += chunk.len();
+                match self.limit {
+                    Some(limit) if self.bytes_read > limit => Poll::Ready(Some(Err(
+                        DjangoError::BadRequest(format!("Request body exceeded {limit} bytes")),
+                    ))),
+                    _ => Poll::Ready(Some(Ok(chunk))),
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Returns the next chunk of the body, or `None` at the end.
+    pub async fn next_chunk(&mut self) -> Option<DjangoResult<Bytes>> {
+        std::future::poll_fn(|cx| self.poll_chunk(cx)).await
+    }
+
+    /// Reads the rest of the body into memory.
+    pub async fn collect(mut self) -> DjangoResult<Vec<u8>> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.next_chunk().await {
+            body.extend_from_slice(&chunk?);
+        }
+        Ok(body)
+    }
+
+    /// Copies the rest of the body into `writer`, one chunk at a time.
+    ///
+    /// Returns the number of bytes written.
+    pub async fn copy_to<W: AsyncWrite + Unpin + ?Sized>(
+        &mut self,
+        writer: &mut W,
+    ) -> DjangoResult<u64> {
+        let mut written = 0u64;
+        while let Some(chunk) = self.next_chunk().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream")
+            .field("limit", &self.limit)
+            .field("bytes_read", &self.bytes_read)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = DjangoResult<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_chunk(cx)
+    }
+}
+
+impl AsyncRead for BodyStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        // An empty chunk is not the end of the body, so skip it rather than
+        // return a zero-byte read.
+        let mut chunk = loop {
+            match this.poll_chunk(cx) {
+                Poll::Ready(Some(Ok(chunk))) if chunk.is_empty() => {}
+                Poll::Ready(Some(Ok(chunk))) => break chunk,
+                Poll::Ready(Some(Err(e))) => {
+                    return Poll::Ready(Err(std::io::Error::other(e.to_string())));
+                }
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Pending => return Poll::Pending,
+            }
+        };
+        let n = chunk.len().min(buf.remaining());
+        buf.put_slice(&chunk.split_to(n));
+        this.pending = chunk;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Adapts an Axum body to a stream of `DjangoError` results.
+struct AxumBodyStream(axum::body::BodyDataStream);
+
+impl Stream for AxumBodyStream {
+    type Item = Result<Bytes, DjangoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|item| item.map(|r| r.map_err(|e| DjangoError::BadRequest(e.to_string()))))
+    }
+}
+
+/// A stream over chunks already in memory.
+struct ChunkStream(VecDeque<Bytes>);
+
+impl Stream for ChunkStream {
+    type Item = Result<Bytes, DjangoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.0.pop_front().map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_next_chunk_and_collect() {
+        let mut stream = BodyStream::from_chunks(vec!["ab", "cd"]);
+        assert_eq!(stream.next_chunk().await.unwrap().unwrap(), "ab");
+        assert_eq!(stream.bytes_read(), 2);
+        assert_eq!(stream.collect().await.unwrap(), b"cd");
+    }
+
+    #[tokio::test]
+    async fn test_limit_rejects_oversized_body() {
+        let stream = BodyStream::from_chunks(vec!["1234", "5678"]).limit(6);
+        let err = stream.collect().await.unwrap_err();
+        assert!(matches!(err, DjangoError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_async_read_with_small_buffer() {
+        let mut stream = BodyStream::from_chunks(vec!["hello ", "world"]);
+        let mut buf = [0u8; 4];
+        let mut out = Vec::new();
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(out, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_async_read_skips_empty_chunks() {
+        let mut stream = BodyStream::from_chunks(vec!["hello", "", "", " world"]);
+        let mut out = Vec::new();
+        stream.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_from_axum_body() {
+        let stream = BodyStream::from_axum(axum::body::Body::from("payload"));
+        assert_eq!(stream.collect().await.unwrap(), b"payload");
+    }
+}
@@ -0,0 +1,375 @@
+//! Checks rejecting malformed requests.
+//!
+//! A request whose query string, headers, cookies or body can't be read
+//! faithfully is refused with `400 Bad Request` before it reaches a view,
+//! rather than being silently repaired (lossy decoding, dropped cookies) or
+//! surfacing as an opaque server error. Each refusal is a [`MalformedRequest`]
+//! saying what was wrong, which converts into [`DjangoError::BadRequest`].
+//!
+//! The checks run on the request head ([`check_parts`]) and, for buffered
+//! bodies, on the body ([`check_body`]):
+//!
+//! - percent-encoded query parameters must decode to UTF-8;
+//! - the query string and a form body may hold at most
+//!   [`RequestLimits::max_number_fields`] fields each;
+//! - no header value may exceed [`RequestLimits::max_header_size`] bytes;
+//! - the `Cookie` header must be visible ASCII, with well-formed names;
+//! - text bodies (forms, JSON, `text/*`) must be UTF-8 unless they declare
+//!   another charset.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_http::hardening::{check_parts, MalformedRequestKind, RequestLimits};
+//!
+//! let (parts, ()) = http::Request::get("/search/?q=%FF").body(()).unwrap().into_parts();
+//! let err = check_parts(&parts, &RequestLimits::default()).unwrap_err();
+//! assert_eq!(err.kind, MalformedRequestKind::QueryString);
+//! ```
+
+use std::fmt;
+
+use django_rs_core::DjangoError;
+use http::header::{CONTENT_TYPE, COOKIE};
+
+/// Limits on the size of a request's parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// The largest accepted header value, in bytes.
+    pub max_header_size: usize,
+    /// The most fields accepted in the query string, and separately in a
+    /// form body. Mirrors Django's `DATA_UPLOAD_MAX_NUMBER_FIELDS`.
+    pub max_number_fields: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_header_size: 8 * 1024,
+            max_number_fields: 1000,
+        }
+    }
+}
+
+/// What was wrong with a [`MalformedRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MalformedRequestKind {
+    /// A query parameter doesn't decode to UTF-8.
+    QueryString,
+    /// The query string or form body has too many fields.
+    TooManyFields,
+    /// A header value is over the size limit.
+    HeaderTooLarge,
+    /// The `Cookie` header can't be parsed.
+    InvalidCookie,
+    /// A text body isn't valid in its charset.
+    InvalidBody,
+    /// The body couldn't be read from the connection.
+    UnreadableBody,
+}
+
+impl MalformedRequestKind {
+    /// Returns the kind's name, as used in logs.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::QueryString => "malformed_query_string",
+            Self::TooManyFields => "too_many_fields",
+            Self::HeaderTooLarge => "header_too_large",
+            Self::InvalidCookie => "invalid_cookie",
+            Self::InvalidBody => "invalid_body",
+            Self::UnreadableBody => "unreadable_body",
+        }
+    }
+}
+
+/// A request refused as malformed, the analogue of Django's
+/// `SuspiciousOperation` family answered with `400 Bad Request`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedRequest {
+    /// What was wrong.
+    pub kind: MalformedRequestKind,
+    /// A description of the problem, for logs.
+    pub message: String,
+}
+
+impl MalformedRequest {
+    /// Creates a refusal of the given kind.
+    pub fn new(kind: MalformedRequestKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for MalformedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for MalformedRequest {}
+
+impl From<MalformedRequest> for DjangoError {
+    fn from(err: MalformedRequest) -> Self {
+        Self::BadRequest(err.message)
+    }
+}
+
+/// Checks the request head: query string, header sizes and cookies.
+///
+/// # Errors
+///
+/// Returns the first problem found.
+pub fn check_parts(
+    parts: &http::request::Parts,
+    limits: &RequestLimits,
+) -> Result<(), MalformedRequest> {
+    for (name, value) in &parts.headers {
+        if value.len() > limits.max_header_size {
+            return Err(MalformedRequest::new(
+                MalformedRequestKind::HeaderTooLarge,
+                format!(
+                    "Header '{name}' is {} bytes, over the limit of {}",
+                    value.len(),
+                    limits.max_header_size
+                ),
+            ));
+        }
+    }
+
+    if let Some(query) = parts.uri.query() {
+        check_fields(
+            query,
+            limits,
+            "query string",
+            MalformedRequestKind::QueryString,
+        )?;
+    }
+
+    for value in parts.headers.get_all(COOKIE) {
+        let header = value.to_str().map_err(|_| {
+            MalformedRequest::new(
+                MalformedRequestKind::InvalidCookie,
+                "Cookie header contains non-ASCII or control characters",
+            )
+        })?;
+        check_cookie_header(header)?;
+    }
+    Ok(())
+}
+
+/// Checks a buffered body against the request's declared content type.
+///
+/// # Errors
+///
+/// Returns the first problem found.
+pub fn check_body(
+    parts: &http::request::Parts,
+    body: &[u8],
+    limits: &RequestLimits,
+) -> Result<(), MalformedRequest> {
+    if body.is_empty() {
+        return Ok(());
+    }
+    let Some(content_type) = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return Ok(());
+    };
+    let mut params = content_type.split(';');
+    let media_type = params
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let charset = params.find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_ascii_lowercase())
+    });
+
+    let is_form = media_type == "application/x-www-form-urlencoded";
+    let is_text = is_form
+        || media_type == "application/json"
+        || media_type.ends_with("+json")
+        || media_type.starts_with("text/");
+    // Bodies declaring another charset are the view's to decode.
+    if !is_text
+        || charset
+            .as_deref()
+            .is_some_and(|c| c != "utf-8" && c != "utf8")
+    {
+        return Ok(());
+    }
+
+    let text = std::str::from_utf8(body).map_err(|e| {
+        MalformedRequest::new(
+            MalformedRequestKind::InvalidBody,
+            format!("Request body is not valid UTF-8: {e}"),
+        )
+    })?;
+    if is_form {
+        check_fields(text, limits, "form body", MalformedRequestKind::InvalidBody)?;
+    }
+    Ok(())
+}
+
+/// Checks the fields of a urlencoded string: their count and that each
+/// decodes to UTF-8, failing with `invalid` if one doesn't.
+fn check_fields(
+    encoded: &str,
+    limits: &RequestLimits,
+    source: &str,
+    invalid: MalformedRequestKind,
+) -> Result<(), MalformedRequest> {
+    let mut count = 0;
+    for pair in encoded.split('&').filter(|pair| !pair.is_empty()) {
+        count += 1;
+        if count > limits.max_number_fields {
+            return Err(MalformedRequest::new(
+                MalformedRequestKind::TooManyFields,
+                format!(
+                    "The {source} has more than {} fields",
+                    limits.max_number_fields
+                ),
+            ));
+        }
+        let decoded = percent_encoding::percent_decode_str(pair);
+        if decoded.decode_utf8().is_err() {
+            let field = pair.split_once('=').map_or(pair, |(key, _)| key);
+            return Err(MalformedRequest::new(
+                invalid,
+                format!("Field '{field}' of the {source} does not decode to UTF-8"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that each `name=value` pair of a `Cookie` header has a usable name.
+fn check_cookie_header(header: &str) -> Result<(), MalformedRequest> {
+    for pair in header.split(';') {
+        let Some((name, _)) = pair.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        let bad_char = name
+            .chars()
+            .find(|c| c.is_ascii_whitespace() || matches!(c, '"' | ',' | '\\'));
+        if name.is_empty() || bad_char.is_some() {
+            return Err(MalformedRequest::new(
+                MalformedRequestKind::InvalidCookie,
+                format!("Cookie name '{name}' is malformed"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(builder: http::request::Builder) -> http::request::Parts {
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    fn kind_of(result: Result<(), MalformedRequest>) -> Option<MalformedRequestKind> {
+        result.err().map(|err| err.kind)
+    }
+
+    #[test]
+    fn test_check_parts_query_string() {
+        let limits = RequestLimits::default();
+        let ok = parts(http::Request::get("/?q=caf%C3%A9&page=2&&flag"));
+        assert!(check_parts(&ok, &limits).is_ok());
+
+        let bad = parts(http::Request::get("/?q=%FF%FE"));
+        assert_eq!(
+            kind_of(check_parts(&bad, &limits)),
+            Some(MalformedRequestKind::QueryString)
+        );
+
+        let tight = RequestLimits {
+            max_number_fields: 2,
+            ..RequestLimits::default()
+        };
+        let many = parts(http::Request::get("/?a=1&b=2&c=3"));
+        assert_eq!(
+            kind_of(check_parts(&many, &tight)),
+            Some(MalformedRequestKind::TooManyFields)
+        );
+    }
+
+    #[test]
+    fn test_check_parts_header_size() {
+        let limits = RequestLimits {
+            max_header_size: 16,
+            ..RequestLimits::default()
+        };
+        let big = parts(http::Request::get("/").header("x-padding", "a".repeat(17)));
+        let err = check_parts(&big, &limits).unwrap_err();
+        assert_eq!(err.kind, MalformedRequestKind::HeaderTooLarge);
+        assert!(err.message.contains("x-padding"), "{err}");
+    }
+
+    #[test]
+    fn test_check_parts_cookies() {
+        let limits = RequestLimits::default();
+        let ok = parts(http::Request::get("/").header("cookie", "a=1; __Host-b=2; flag"));
+        assert!(check_parts(&ok, &limits).is_ok());
+
+        for header in ["=orphan", "a b=1", "na\"me=1"] {
+            let bad = parts(http::Request::get("/").header("cookie", header));
+            assert_eq!(
+                kind_of(check_parts(&bad, &limits)),
+                Some(MalformedRequestKind::InvalidCookie),
+                "{header}"
+            );
+        }
+
+        let binary = http::HeaderValue::from_bytes(b"a=\xff").unwrap();
+        let bad = parts(http::Request::get("/").header("cookie", binary));
+        assert_eq!(
+            kind_of(check_parts(&bad, &limits)),
+            Some(MalformedRequestKind::InvalidCookie)
+        );
+    }
+
+    #[test]
+    fn test_check_body() {
+        let limits = RequestLimits::default();
+        let with_type = |ct: &str| parts(http::Request::post("/").header("content-type", ct));
+
+        let form = with_type("application/x-www-form-urlencoded");
+        assert!(check_body(&form, b"a=1&b=caf%C3%A9", &limits).is_ok());
+        assert_eq!(
+            kind_of(check_body(&form, b"a=%FF", &limits)),
+            Some(MalformedRequestKind::InvalidBody)
+        );
+
+        let json = with_type("application/json");
+        assert_eq!(
+            kind_of(check_body(&json, b"{\"a\": \"\xff\"}", &limits)),
+            Some(MalformedRequestKind::InvalidBody)
+        );
+
+        // Binary and explicitly non-UTF-8 bodies are left to the view.
+        let latin1 = with_type("text/plain; charset=iso-8859-1");
+        assert!(check_body(&latin1, b"caf\xe9", &limits).is_ok());
+        let binary = with_type("application/octet-stream");
+        assert!(check_body(&binary, b"\xff\xfe", &limits).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_request_into_django_error() {
+        let err: DjangoError =
+            MalformedRequest::new(MalformedRequestKind::InvalidCookie, "bad cookie").into();
+        assert!(matches!(&err, DjangoError::BadRequest(msg) if msg == "bad cookie"));
+        assert_eq!(err.status_code(), 400);
+    }
+}
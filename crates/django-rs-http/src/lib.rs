@@ -6,8 +6,12 @@
 //! ## Modules
 //!
 //! - [`request`] - `HttpRequest` type with Django-compatible API
+//! - [`body`] - `BodyStream` for reading large request bodies with bounded memory
+//! - [`hardening`] - Checks refusing malformed requests with `400 Bad Request`
 //! - [`response`] - `HttpResponse`, `JsonResponse`, redirect responses, and more
 //! - [`querydict`] - `QueryDict` for immutable-by-default query/form parameters
+//! - [`pagination`] - Paginated JSON list responses with `Link` headers
+//! - [`services`] - Type-keyed container of application services injected into requests
 //! - [`urls`] - URL pattern definitions, routing, path converters, and reverse resolution
 //!
 //! ## Quick Start
@@ -43,17 +47,21 @@
 // Response factory types (JsonResponse, etc.) deliberately return HttpResponse from new().
 #![allow(clippy::new_ret_no_self)]
 
+pub mod body;
 pub mod cookies;
+pub mod hardening;
+pub mod pagination;
 pub mod querydict;
 pub mod request;
 pub mod response;
+pub mod services;
 pub mod upload;
 pub mod urls;
 
 // Re-export primary types at the crate root for convenience.
 pub use cookies::{Cookie, CookieError, SameSite};
 pub use querydict::QueryDict;
-pub use request::HttpRequest;
+pub use request::{HttpRequest, RequestData};
 pub use response::{
     FileResponse, HttpResponse, HttpResponseForbidden, HttpResponseNotAllowed,
     HttpResponseNotFound, HttpResponsePermanentRedirect, HttpResponseRedirect,
@@ -0,0 +1,390 @@
+//! Paginated JSON responses for API list endpoints.
+//!
+//! A [`PaginatedResponse`] is the standard envelope of a page of results:
+//!
+//! ```json
+//! {"count": 42, "next": "/api/articles/?page=3", "previous": "/api/articles/", "results": [...]}
+//! ```
+//!
+//! Its [`into_response`](PaginatedResponse::into_response) also sets an
+//! [RFC 5988](https://www.rfc-editor.org/rfc/rfc5988) `Link` header with the
+//! `first`, `prev`, `next` and `last` pages, so clients can page through a
+//! list without parsing the body. Page URLs come from [`PageUrls`], built from
+//! the current request or by reversing a named route.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_http::pagination::{PageUrls, PaginatedResponse};
+//! use django_rs_http::HttpRequest;
+//!
+//! let request = HttpRequest::builder()
+//!     .path("/api/articles/")
+//!     .query_string("search=rust&page=2")
+//!     .build();
+//! let articles: Vec<u32> = (1..=25).collect();
+//!
+//! let page = PaginatedResponse::paginate(articles, 2, 10, &PageUrls::from_request(&request));
+//! assert_eq!(page.count, 25);
+//! assert_eq!(page.results, (11..=20).collect::<Vec<_>>());
+//! assert_eq!(
+//!     page.next.as_deref(),
+//!     Some("http://localhost/api/articles/?search=rust&page=3")
+//! );
+//! assert_eq!(
+//!     page.previous.as_deref(),
+//!     Some("http://localhost/api/articles/?search=rust")
+//! );
+//!
+//! let response = page.into_response();
+//! assert!(response.headers().contains_key("link"));
+//! ```
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use django_rs_core::DjangoResult;
+use http::header::{HeaderValue, LINK};
+use serde::{Deserialize, Serialize};
+
+use crate::querydict::{percent_decode, percent_encode};
+use crate::request::HttpRequest;
+use crate::response::{HttpResponse, JsonResponse};
+use crate::urls::resolver::URLResolver;
+use crate::urls::reverse::reverse;
+
+/// The default query parameter selecting the page.
+pub const DEFAULT_PAGE_PARAM: &str = "page";
+
+/// Builds the URLs of the pages of a list endpoint.
+///
+/// Page 1 is linked without a page parameter; other query parameters (search
+/// terms, filters) are kept in their original order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageUrls {
+    path: String,
+    /// Encoded `key=value` pairs.
+    query: Vec<String>,
+    page_param: String,
+}
+
+impl PageUrls {
+    /// Creates page URLs for `path` with the given encoded query string.
+    pub fn new(path: impl Into<String>, query_string: &str) -> Self {
+        Self {
+            path: path.into(),
+            query: query_string
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(str::to_string)
+                .collect(),
+            page_param: DEFAULT_PAGE_PARAM.to_string(),
+        }
+    }
+
+    /// Creates absolute page URLs for the URL of `request`.
+    pub fn from_request(request: &HttpRequest) -> Self {
+        let full_path = request.get_full_path();
+        let query_string = full_path.split_once('?').map_or("", |(_, query)| query);
+        Self::new(
+            request.build_absolute_uri(Some(request.path())),
+            query_string,
+        )
+    }
+
+    /// Creates page URLs for the named route `viewname`, reversed through
+    /// `resolver` as [`reverse`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::NotFound`](django_rs_core::DjangoError::NotFound)
+    /// if the route cannot be reversed with these arguments.
+    pub fn reverse<S: BuildHasher>(
+        viewname: &str,
+        args: &[&str],
+        kwargs: &HashMap<&str, &str, S>,
+        resolver: &URLResolver,
+    ) -> DjangoResult<Self> {
+        Ok(Self::new(reverse(viewname, args, kwargs, resolver)?, ""))
+    }
+
+    /// Sets the query parameter selecting the page (default `page`).
+    #[must_use]
+    pub fn page_param(mut self, name: impl Into<String>) -> Self {
+        self.page_param = name.into();
+        self
+    }
+
+    /// Adds a query parameter to every page URL.
+    #[must_use]
+    pub fn query_param(mut self, key: &str, value: &str) -> Self {
+        self.query
+            .push(format!("{}={}", percent_encode(key), percent_encode(value)));
+        self
+    }
+
+    /// Returns the URL of page `page`.
+    pub fn url(&self, page: usize) -> String {
+        let mut query: Vec<String> = self
+            .query
+            .iter()
+            .filter(|pair| {
+                let key = pair.split_once('=').map_or(pair.as_str(), |(key, _)| key);
+                percent_decode(key) != self.page_param
+            })
+            .cloned()
+            .collect();
+        if page > 1 {
+            query.push(format!("{}={page}", percent_encode(&self.page_param)));
+        }
+        if query.is_empty() {
+            self.path.clone()
+        } else {
+            format!("{}?{}", self.path, query.join("&"))
+        }
+    }
+}
+
+/// The links from one page of a list to the others.
+///
+/// `first` and `previous` are set except on the first page, `next` and
+/// `last` except on the last.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageLinks {
+    /// The first page.
+    pub first: Option<String>,
+    /// The page before this one.
+    pub previous: Option<String>,
+    /// The page after this one.
+    pub next: Option<String>,
+    /// The last page.
+    pub last: Option<String>,
+}
+
+impl PageLinks {
+    /// Returns the links of page `page` of `total_pages`.
+    pub fn new(urls: &PageUrls, page: usize, total_pages: usize) -> Self {
+        let has_previous = page > 1;
+        let has_next = page < total_pages;
+        Self {
+            first: has_previous.then(|| urls.url(1)),
+            previous: has_previous.then(|| urls.url(page - 1)),
+            next: has_next.then(|| urls.url(page + 1)),
+            last: has_next.then(|| urls.url(total_pages)),
+        }
+    }
+
+    /// Returns the value of an RFC 5988 `Link` header, or `None` if there is
+    /// only one page.
+    pub fn header_value(&self) -> Option<String> {
+        let links: Vec<String> = [
+            (&self.first, "first"),
+            (&self.previous, "prev"),
+            (&self.next, "next"),
+            (&self.last, "last"),
+        ]
+        .into_iter()
+        .filter_map(|(url, rel)| url.as_ref().map(|url| format!("<{url}>; rel=\"{rel}\"")))
+        .collect();
+        (!links.is_empty()).then(|| links.join(", "))
+    }
+
+    /// Sets the `Link` header on `response`, if there is more than one page.
+    pub fn apply(&self, response: &mut HttpResponse) {
+        if let Some(value) = self
+            .header_value()
+            .and_then(|value| HeaderValue::from_str(&value).ok())
+        {
+            response.headers_mut().insert(LINK, value);
+        }
+    }
+}
+
+/// A page of results in the standard list envelope: `count`, `next`,
+/// `previous` and `results`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedResponse<T> {
+    /// The number of results across all pages.
+    pub count: usize,
+    /// The URL of the next page, if any.
+    pub next: Option<String>,
+    /// The URL of the previous page, if any.
+    pub previous: Option<String>,
+    /// The results on this page.
+    pub results: Vec<T>,
+    #[serde(skip)]
+    links: PageLinks,
+}
+
+impl<T> PaginatedResponse<T> {
+    /// Creates the response for page `page` of `count` results, `results`
+    /// being that page's results.
+    ///
+    /// A `page_size` of 0 is treated as 1, and `page` is clamped to the
+    /// existing pages.
+    pub fn new(
+        results: Vec<T>,
+        count: usize,
+        page: usize,
+        page_size: usize,
+        urls: &PageUrls,
+    ) -> Self {
+        let total_pages = total_pages(count, page_size);
+        let links = PageLinks::new(urls, page.clamp(1, total_pages), total_pages);
+        Self {
+            count,
+            next: links.next.clone(),
+            previous: links.previous.clone(),
+            results,
+            links,
+        }
+    }
+
+    /// Creates the response for page `page` of `items`, which hold every
+    /// result.
+    pub fn paginate(items: Vec<T>, page: usize, page_size: usize, urls: &PageUrls) -> Self {
+        let count = items.len();
+        let page_size = page_size.max(1);
+        let page = page.clamp(1, total_pages(count, page_size));
+        let results = items
+            .into_iter()
+            .skip((page - 1) * page_size)
+            .take(page_size)
+            .collect();
+        Self::new(results, count, page, page_size, urls)
+    }
+
+    /// Returns the links to the other pages.
+    pub const fn links(&self) -> &PageLinks {
+        &self.links
+    }
+}
+
+impl<T: Serialize> PaginatedResponse<T> {
+    /// Returns a JSON response with the `Link` header set.
+    pub fn into_response(self) -> HttpResponse {
+        let mut response = JsonResponse::new(&self);
+        self.links.apply(&mut response);
+        response
+    }
+}
+
+/// Returns the number of pages `count` results take, at least 1.
+pub fn total_pages(count: usize, page_size: usize) -> usize {
+    count.div_ceil(page_size.max(1)).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::urls::pattern::path;
+    use crate::urls::resolver::{root, URLEntry};
+    use std::sync::Arc;
+
+    fn urls() -> PageUrls {
+        PageUrls::new("/api/articles/", "q=hello%20world&page=4")
+    }
+
+    #[test]
+    fn test_page_urls() {
+        let urls = urls();
+        assert_eq!(urls.url(1), "/api/articles/?q=hello%20world");
+        assert_eq!(urls.url(3), "/api/articles/?q=hello%20world&page=3");
+        assert_eq!(PageUrls::new("/a/", "").url(1), "/a/");
+    }
+
+    #[test]
+    fn test_page_urls_custom_param() {
+        let urls = PageUrls::new("/a/", "p=2&page=9")
+            .page_param("p")
+            .query_param("sort", "-date");
+        assert_eq!(urls.url(3), "/a/?page=9&sort=%2Ddate&p=3");
+    }
+
+    #[test]
+    fn test_page_urls_from_request() {
+        let request = HttpRequest::builder()
+            .path("/api/articles/")
+            .query_string("page=2")
+            .build();
+        let urls = PageUrls::from_request(&request);
+        assert_eq!(urls.url(1), "http://localhost/api/articles/");
+    }
+
+    #[test]
+    fn test_page_urls_reverse() {
+        let handler = Arc::new(|_req: HttpRequest| -> crate::BoxFuture {
+            Box::pin(async { HttpResponse::ok("ok") })
+        });
+        let resolver = root(vec![URLEntry::Pattern(
+            path("authors/<int:id>/books/", handler, Some("author-books")).unwrap(),
+        )])
+        .unwrap();
+        let kwargs = HashMap::from([("id", "7")]);
+        let urls = PageUrls::reverse("author-books", &[], &kwargs, &resolver).unwrap();
+        assert_eq!(urls.url(2), "/authors/7/books/?page=2");
+        assert!(PageUrls::reverse("missing", &[], &kwargs, &resolver).is_err());
+    }
+
+    #[test]
+    fn test_page_links() {
+        let urls = PageUrls::new("/a/", "");
+        assert_eq!(PageLinks::new(&urls, 1, 1), PageLinks::default());
+        assert_eq!(PageLinks::new(&urls, 1, 1).header_value(), None);
+
+        let links = PageLinks::new(&urls, 2, 3);
+        assert_eq!(
+            links.header_value().unwrap(),
+            "</a/>; rel=\"first\", </a/>; rel=\"prev\", \
+             </a/?page=3>; rel=\"next\", </a/?page=3>; rel=\"last\""
+        );
+
+        let links = PageLinks::new(&urls, 1, 5);
+        assert_eq!(
+            links.header_value().unwrap(),
+            "</a/?page=2>; rel=\"next\", </a/?page=5>; rel=\"last\""
+        );
+    }
+
+    #[test]
+    fn test_paginate() {
+        let page = PaginatedResponse::paginate((1..=25).collect(), 3, 10, &urls());
+        assert_eq!(page.count, 25);
+        assert_eq!(page.results, (21..=25).collect::<Vec<_>>());
+        assert_eq!(page.next, None);
+        assert_eq!(
+            page.previous.as_deref(),
+            Some("/api/articles/?q=hello%20world&page=2")
+        );
+
+        // Out-of-range pages are clamped
+        let page = PaginatedResponse::paginate((1..=25).collect(), 9, 10, &urls());
+        assert_eq!(page.results, (21..=25).collect::<Vec<_>>());
+        let page = PaginatedResponse::paginate(Vec::<u32>::new(), 0, 0, &urls());
+        assert!(page.results.is_empty());
+        assert_eq!(page.links(), &PageLinks::default());
+    }
+
+    #[test]
+    fn test_into_response() {
+        let page = PaginatedResponse::new(vec!["a", "b"], 4, 1, 2, &urls());
+        let response = page.into_response();
+        assert_eq!(response.content_type(), "application/json");
+        assert_eq!(
+            response.headers().get(LINK).unwrap(),
+            "</api/articles/?q=hello%20world&page=2>; rel=\"next\", \
+             </api/articles/?q=hello%20world&page=2>; rel=\"last\""
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(&response.content_bytes().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "count": 4,
+                "next": "/api/articles/?q=hello%20world&page=2",
+                "previous": null,
+                "results": ["a", "b"],
+            })
+        );
+    }
+}
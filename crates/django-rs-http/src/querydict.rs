@@ -197,7 +197,7 @@ impl QueryDict {
 }
 
 /// Decodes a percent-encoded string.
-fn percent_decode(input: &str) -> String {
+pub(crate) fn percent_decode(input: &str) -> String {
     // Replace + with space (form encoding), then decode percent sequences
     let plus_decoded = input.replace('+', " ");
     percent_encoding::percent_decode_str(&plus_decoded)
@@ -206,7 +206,7 @@ fn percent_decode(input: &str) -> String {
 }
 
 /// Percent-encodes a string for use in a URL query.
-fn percent_encode(input: &str) -> String {
+pub(crate) fn percent_encode(input: &str) -> String {
     // Encode using the query encoding set (allows some chars unencoded)
     percent_encoding::utf8_percent_encode(input, percent_encoding::NON_ALPHANUMERIC).to_string()
 }
@@ -4,11 +4,16 @@
 //! to the request method, path, headers, query parameters, POST data, and metadata.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use http::{HeaderMap, Method};
+use django_rs_core::flags::{FlagContext, FlagSubject};
+use django_rs_core::{DjangoError, DjangoResult};
+use http::{Extensions, HeaderMap, Method};
 
+use crate::body::BodyStream;
 use crate::cookies::{self, CookieError};
 use crate::querydict::QueryDict;
+use crate::services::Services;
 use crate::upload::UploadedFile;
 use crate::urls::resolver::ResolverMatch;
 
@@ -48,7 +53,11 @@ pub struct HttpRequest {
     resolver_match: Option<ResolverMatch>,
     scheme: String,
     cached_cookies: std::sync::OnceLock<HashMap<String, String>>,
+    cached_data: std::sync::OnceLock<Result<RequestData, String>>,
     files: HashMap<String, Vec<UploadedFile>>,
+    body_stream: Option<BodyStream>,
+    body_stream_taken: bool,
+    extensions: Extensions,
 }
 
 impl HttpRequest {
@@ -65,6 +74,7 @@ impl HttpRequest {
         let method = parts.method;
         let uri = parts.uri;
         let headers = parts.headers;
+        let extensions = parts.extensions;
 
         let path = uri.path().to_string();
         let path_info = path.clone();
@@ -76,17 +86,6 @@ impl HttpRequest {
             .and_then(|v| v.to_str().ok())
             .map(String::from);
 
-        // Parse POST data from form-encoded body
-        let post = if content_type
-            .as_deref()
-            .is_some_and(|ct| ct.starts_with("application/x-www-form-urlencoded"))
-        {
-            let body_str = String::from_utf8_lossy(&body);
-            QueryDict::parse(&body_str)
-        } else {
-            QueryDict::new()
-        };
-
         // Build META dict
         let mut meta = HashMap::new();
 
@@ -127,33 +126,7 @@ impl HttpRequest {
             "http".to_string()
         };
 
-        // Parse multipart data if content type is multipart/form-data
-        let (post, files) = if content_type
-            .as_deref()
-            .is_some_and(|ct| ct.starts_with("multipart/form-data"))
-        {
-            if let Some(boundary) = content_type
-                .as_deref()
-                .and_then(crate::upload::extract_boundary)
-            {
-                match crate::upload::parse_multipart(&body, boundary) {
-                    Ok(multipart) => {
-                        let mut post_dict = QueryDict::new_mutable();
-                        for (name, values) in &multipart.fields {
-                            for value in values {
-                                let _ = post_dict.append(name, value);
-                            }
-                        }
-                        (post_dict, multipart.files)
-                    }
-                    Err(_) => (post, HashMap::new()),
-                }
-            } else {
-                (post, HashMap::new())
-            }
-        } else {
-            (post, HashMap::new())
-        };
+        let (post, files) = parse_form_body(content_type.as_deref(), &body);
 
         Self {
             method,
@@ -169,10 +142,35 @@ impl HttpRequest {
             resolver_match: None,
             scheme,
             cached_cookies: std::sync::OnceLock::new(),
+            cached_data: std::sync::OnceLock::new(),
             files,
+            body_stream: None,
+            body_stream_taken: false,
+            extensions,
         }
     }
 
+    /// Creates an `HttpRequest` whose body is read lazily from `body`.
+    ///
+    /// Nothing is buffered up front: the view can pipe the upload to storage
+    /// with [`body_stream`](Self::body_stream), or call
+    /// [`read_body`](Self::read_body) to buffer it and parse POST data and
+    /// files as [`from_axum`](Self::from_axum) would.
+    pub fn from_axum_streaming(parts: http::request::Parts, body: BodyStream) -> Self {
+        let content_length = parts
+            .headers
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let mut request = Self::from_axum(parts, Vec::new());
+        match content_length {
+            Some(len) => request.meta.insert("CONTENT_LENGTH".to_string(), len),
+            None => request.meta.remove("CONTENT_LENGTH"),
+        };
+        request.body_stream = Some(body);
+        request
+    }
+
     /// Returns the HTTP method.
     pub const fn method(&self) -> &Method {
         &self.method
@@ -183,6 +181,15 @@ impl HttpRequest {
         &self.path
     }
 
+    /// Rewrites the request path, as a middleware may before the view runs.
+    ///
+    /// The request is routed by its new path.
+    pub fn set_path(&mut self, path: &str) {
+        self.path = path.to_string();
+        self.path_info = path.to_string();
+        self.meta.insert("PATH_INFO".to_string(), path.to_string());
+    }
+
     /// Returns the path info, which is the path portion suitable for routing.
     pub fn path_info(&self) -> &str {
         &self.path_info
@@ -227,11 +234,149 @@ impl HttpRequest {
         &mut self.meta
     }
 
+    /// Returns the typed per-request state attached by middleware.
+    ///
+    /// Unlike [`meta`](Self::meta), which holds strings, extensions hold
+    /// values of any type, one per type, so middleware can hand real structs
+    /// such as the current user or the message store to later middleware
+    /// and views. Extensions set on the incoming Axum request are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_http::HttpRequest;
+    ///
+    /// #[derive(Clone)]
+    /// struct Tenant(&'static str);
+    ///
+    /// let mut request = HttpRequest::builder().build();
+    /// request.extensions_mut().insert(Tenant("acme"));
+    ///
+    /// assert_eq!(request.extensions().get::<Tenant>().unwrap().0, "acme");
+    /// ```
+    pub const fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Returns a mutable reference to the typed per-request state.
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Returns the application service registered for `T`, if any.
+    ///
+    /// Services are registered on the application and injected into every
+    /// request; see [`services`](crate::services).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use django_rs_http::HttpRequest;
+    ///
+    /// struct Clock(u64);
+    ///
+    /// let request = HttpRequest::builder().service(Arc::new(Clock(1_700_000_000))).build();
+    /// assert_eq!(request.service::<Clock>().unwrap().0, 1_700_000_000);
+    /// ```
+    pub fn service<T: ?Sized + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.extensions.get::<Services>()?.get::<T>()
+    }
+
+    /// Returns the services injected into this request, if any.
+    pub fn services(&self) -> Option<&Services> {
+        self.extensions.get::<Services>()
+    }
+
+    /// Returns the services injected into this request for changing,
+    /// starting an empty container if there is none.
+    pub fn services_mut(&mut self) -> &mut Services {
+        if self.extensions.get::<Services>().is_none() {
+            self.extensions.insert(Services::new());
+        }
+        self.extensions
+            .get_mut::<Services>()
+            .expect("services were just inserted")
+    }
+
     /// Returns the raw request body bytes.
+    ///
+    /// For a streaming request this is empty until
+    /// [`read_body`](Self::read_body) has buffered the body.
     pub fn body(&self) -> &[u8] {
         &self.body
     }
 
+    /// Returns `true` if the body has not been read from the connection yet.
+    pub const fn is_streaming(&self) -> bool {
+        self.body_stream.is_some()
+    }
+
+    /// Takes the request body as a stream of chunks.
+    ///
+    /// For a streaming request this hands over the unread body, so it can
+    /// be piped to storage with bounded memory. For a buffered request it
+    /// streams the buffered bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::BadRequest`] if the streaming body was already
+    /// taken, mirroring Django's `RawPostDataException`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_http::body::BodyStream;
+    /// use django_rs_http::HttpRequest;
+    ///
+    /// # async fn example() {
+    /// let mut request = HttpRequest::builder()
+    ///     .method(http::Method::PUT)
+    ///     .body_stream(BodyStream::from_chunks(vec!["part1-", "part2"]))
+    ///     .build();
+    ///
+    /// let mut file = Vec::new();
+    /// request.body_stream().unwrap().copy_to(&mut file).await.unwrap();
+    /// assert_eq!(file, b"part1-part2");
+    /// # }
+    /// ```
+    pub fn body_stream(&mut self) -> DjangoResult<BodyStream> {
+        if let Some(stream) = self.body_stream.take() {
+            self.body_stream_taken = true;
+            return Ok(stream);
+        }
+        if self.body_stream_taken {
+            return Err(body_already_read());
+        }
+        Ok(BodyStream::from_bytes(self.body.clone()))
+    }
+
+    /// Buffers a streaming body in memory and returns it.
+    ///
+    /// After buffering, [`body`](Self::body), [`post`](Self::post),
+    /// [`files`](Self::files) and [`data`](Self::data) behave exactly as for
+    /// a request created with [`from_axum`](Self::from_axum). For a buffered
+    /// request this simply returns the body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails or exceeds its size limit, or if
+    /// the body was already taken with [`body_stream`](Self::body_stream).
+    pub async fn read_body(&mut self) -> DjangoResult<&[u8]> {
+        if let Some(stream) = self.body_stream.take() {
+            self.body = stream.collect().await?;
+            let (post, files) = parse_form_body(self.content_type.as_deref(), &self.body);
+            self.post = post;
+            self.files = files;
+            self.cached_data = std::sync::OnceLock::new();
+            self.meta
+                .insert("CONTENT_LENGTH".to_string(), self.body.len().to_string());
+        } else if self.body_stream_taken {
+            return Err(body_already_read());
+        }
+        Ok(&self.body)
+    }
+
     /// Returns the resolver match information, if the URL has been resolved.
     pub const fn resolver_match(&self) -> Option<&ResolverMatch> {
         self.resolver_match.as_ref()
@@ -359,6 +504,177 @@ impl HttpRequest {
     pub const fn files(&self) -> &HashMap<String, Vec<UploadedFile>> {
         &self.files
     }
+
+    /// Returns the parsed request body, regardless of the HTTP method.
+    ///
+    /// The body is parsed according to its `Content-Type`:
+    /// `application/x-www-form-urlencoded` and `multipart/form-data` produce
+    /// [`RequestData::Form`], `application/json` (and `+json` types) produce
+    /// [`RequestData::Json`], and anything else (or an empty body) produces
+    /// [`RequestData::Empty`]. The result is cached after the first call.
+    ///
+    /// Unlike [`post`](Self::post), this is meant for REST-style views that
+    /// accept the same payload on POST, PUT, and PATCH.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::BadRequest`] if a JSON body is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use django_rs_http::HttpRequest;
+    ///
+    /// let request = HttpRequest::builder()
+    ///     .method(http::Method::PATCH)
+    ///     .content_type("application/json")
+    ///     .body(br#"{"title": "Updated"}"#.to_vec())
+    ///     .build();
+    ///
+    /// let data = request.data().unwrap();
+    /// assert_eq!(data.get("title").as_deref(), Some("Updated"));
+    /// ```
+    pub fn data(&self) -> DjangoResult<&RequestData> {
+        self.cached_data
+            .get_or_init(|| self.parse_data())
+            .as_ref()
+            .map_err(|e| DjangoError::BadRequest(e.clone()))
+    }
+
+    /// Parses the body for [`data`](Self::data).
+    fn parse_data(&self) -> Result<RequestData, String> {
+        let media_type = self
+            .content_type
+            .as_deref()
+            .and_then(|ct| ct.split(';').next())
+            .map(|ct| ct.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        if media_type == "application/x-www-form-urlencoded" {
+            return Ok(RequestData::Form(QueryDict::parse(
+                &String::from_utf8_lossy(&self.body),
+            )));
+        }
+        if media_type == "multipart/form-data" {
+            return Ok(RequestData::Form(self.post.clone()));
+        }
+        if media_type == "application/json" || media_type.ends_with("+json") {
+            if self.body.iter().all(u8::is_ascii_whitespace) {
+                return Ok(RequestData::Empty);
+            }
+            return serde_json::from_slice(&self.body)
+                .map(RequestData::Json)
+                .map_err(|e| format!("Malformed JSON body: {e}"));
+        }
+        Ok(RequestData::Empty)
+    }
+}
+
+/// Evaluates feature flags for the logged-in user, read from
+/// `META["USER_ID"]` and `META["USER_AUTHENTICATED"]` as set by the
+/// authentication middleware.
+impl FlagSubject for HttpRequest {
+    fn flag_context(&self) -> FlagContext {
+        let authenticated = self
+            .meta
+            .get("USER_AUTHENTICATED")
+            .is_some_and(|v| v == "true");
+        FlagContext {
+            user: self.meta.get("USER_ID").filter(|_| authenticated).cloned(),
+        }
+    }
+}
+
+/// The error returned when a streaming body is read twice.
+fn body_already_read() -> DjangoError {
+    DjangoError::BadRequest(
+        "You cannot access the body after reading from the request's data stream".to_string(),
+    )
+}
+
+/// A request body parsed according to its content type.
+///
+/// Returned by [`HttpRequest::data`]. Form bodies keep their multi-value
+/// semantics; JSON bodies keep their full structure.
+#[derive(Debug, Clone)]
+pub enum RequestData {
+    /// Form data from a urlencoded or multipart body.
+    Form(QueryDict),
+    /// A parsed JSON body.
+    Json(serde_json::Value),
+    /// No body, or a content type that isn't parsed.
+    Empty,
+}
+
+impl RequestData {
+    /// Returns the value for `key` as a string.
+    ///
+    /// For form data this is the last value for the key. For a JSON object,
+    /// strings are returned as-is and other non-null values in their JSON
+    /// representation.
+    pub fn get(&self, key: &str) -> Option<String> {
+        match self {
+            Self::Form(qd) => qd.get(key).map(String::from),
+            Self::Json(value) => value.get(key).and_then(json_to_string),
+            Self::Empty => None,
+        }
+    }
+
+    /// Returns all values for `key` as strings.
+    ///
+    /// JSON arrays yield one entry per element.
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        match self {
+            Self::Form(qd) => qd.get_list(key).cloned().unwrap_or_default(),
+            Self::Json(value) => match value.get(key) {
+                Some(serde_json::Value::Array(items)) => {
+                    items.iter().filter_map(json_to_string).collect()
+                }
+                Some(other) => json_to_string(other).into_iter().collect(),
+                None => Vec::new(),
+            },
+            Self::Empty => Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the body contained the given key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        match self {
+            Self::Form(qd) => qd.contains_key(key),
+            Self::Json(value) => value.get(key).is_some(),
+            Self::Empty => false,
+        }
+    }
+
+    /// Returns the form data, if the body was form-encoded.
+    pub const fn as_form(&self) -> Option<&QueryDict> {
+        match self {
+            Self::Form(qd) => Some(qd),
+            _ => None,
+        }
+    }
+
+    /// Returns the JSON value, if the body was JSON.
+    pub const fn as_json(&self) -> Option<&serde_json::Value> {
+        match self {
+            Self::Json(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if there was no parsed body.
+    pub const fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+}
+
+/// Converts a scalar JSON value to its string form, treating `null` as absent.
+fn json_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
 }
 
 /// Builder for constructing [`HttpRequest`] instances in tests.
@@ -374,7 +690,9 @@ pub struct HttpRequestBuilder {
     headers: HeaderMap,
     meta: HashMap<String, String>,
     body: Vec<u8>,
+    body_stream: Option<BodyStream>,
     scheme: String,
+    extensions: Extensions,
 }
 
 impl Default for HttpRequestBuilder {
@@ -387,7 +705,9 @@ impl Default for HttpRequestBuilder {
             headers: HeaderMap::new(),
             meta: HashMap::new(),
             body: Vec::new(),
+            body_stream: None,
             scheme: "http".to_string(),
+            extensions: Extensions::new(),
         }
     }
 }
@@ -447,6 +767,13 @@ impl HttpRequestBuilder {
         self
     }
 
+    /// Sets a streaming request body, read lazily by the view.
+    #[must_use]
+    pub fn body_stream(mut self, stream: BodyStream) -> Self {
+        self.body_stream = Some(stream);
+        self
+    }
+
     /// Sets the scheme (http or https).
     #[must_use]
     pub fn scheme(mut self, scheme: &str) -> Self {
@@ -454,21 +781,31 @@ impl HttpRequestBuilder {
         self
     }
 
+    /// Adds a typed extension, replacing any previous value of its type.
+    #[must_use]
+    pub fn extension<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
+    /// Adds an application service, replacing any previous service of its
+    /// type.
+    #[must_use]
+    pub fn service<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        if self.extensions.get::<Services>().is_none() {
+            self.extensions.insert(Services::new());
+        }
+        self.extensions
+            .get_mut::<Services>()
+            .expect("services were just inserted")
+            .insert(service);
+        self
+    }
+
     /// Builds the [`HttpRequest`].
     pub fn build(self) -> HttpRequest {
         let get = QueryDict::parse(&self.query_string);
 
-        let post = if self
-            .content_type
-            .as_deref()
-            .is_some_and(|ct| ct.starts_with("application/x-www-form-urlencoded"))
-        {
-            let body_str = String::from_utf8_lossy(&self.body);
-            QueryDict::parse(&body_str)
-        } else {
-            QueryDict::new()
-        };
-
         let path_info = self.path.clone();
 
         let mut meta = self.meta;
@@ -479,35 +816,7 @@ impl HttpRequestBuilder {
         meta.entry("QUERY_STRING".to_string())
             .or_insert_with(|| self.query_string.clone());
 
-        // Parse multipart data if content type is multipart/form-data
-        let (post, files) = if self
-            .content_type
-            .as_deref()
-            .is_some_and(|ct| ct.starts_with("multipart/form-data"))
-        {
-            if let Some(boundary) = self
-                .content_type
-                .as_deref()
-                .and_then(crate::upload::extract_boundary)
-            {
-                match crate::upload::parse_multipart(&self.body, boundary) {
-                    Ok(multipart) => {
-                        let mut post_dict = QueryDict::new_mutable();
-                        for (name, values) in &multipart.fields {
-                            for value in values {
-                                let _ = post_dict.append(name, value);
-                            }
-                        }
-                        (post_dict, multipart.files)
-                    }
-                    Err(_) => (post, HashMap::new()),
-                }
-            } else {
-                (post, HashMap::new())
-            }
-        } else {
-            (post, HashMap::new())
-        };
+        let (post, files) = parse_form_body(self.content_type.as_deref(), &self.body);
 
         HttpRequest {
             method: self.method,
@@ -523,9 +832,46 @@ impl HttpRequestBuilder {
             resolver_match: None,
             scheme: self.scheme,
             cached_cookies: std::sync::OnceLock::new(),
+            cached_data: std::sync::OnceLock::new(),
             files,
+            body_stream: self.body_stream,
+            body_stream_taken: false,
+            extensions: self.extensions,
+        }
+    }
+}
+
+/// Parses POST parameters and uploaded files from a buffered body.
+///
+/// Urlencoded bodies fill the POST dict; multipart bodies fill both the
+/// POST dict and the uploaded files. Other content types yield nothing.
+fn parse_form_body(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> (QueryDict, HashMap<String, Vec<UploadedFile>>) {
+    let Some(content_type) = content_type else {
+        return (QueryDict::new(), HashMap::new());
+    };
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        return (
+            QueryDict::parse(&String::from_utf8_lossy(body)),
+            HashMap::new(),
+        );
+    }
+    if content_type.starts_with("multipart/form-data") {
+        if let Some(boundary) = crate::upload::extract_boundary(content_type) {
+            if let Ok(multipart) = crate::upload::parse_multipart(body, boundary) {
+                let mut post_dict = QueryDict::new_mutable();
+                for (name, values) in &multipart.fields {
+                    for value in values {
+                        let _ = post_dict.append(name, value);
+                    }
+                }
+                return (post_dict, multipart.files);
+            }
         }
     }
+    (QueryDict::new(), HashMap::new())
 }
 
 #[cfg(test)]
@@ -697,6 +1043,15 @@ mod tests {
         assert_eq!(req.path_info(), "/articles/2024/");
     }
 
+    #[test]
+    fn test_set_path() {
+        let mut req = HttpRequest::builder().path("/old/").build();
+        req.set_path("/new/");
+        assert_eq!(req.path(), "/new/");
+        assert_eq!(req.path_info(), "/new/");
+        assert_eq!(req.meta().get("PATH_INFO").unwrap(), "/new/");
+    }
+
     #[test]
     fn test_scheme() {
         let req = HttpRequest::builder().build();
@@ -716,6 +1071,7 @@ mod tests {
             app_names: Vec::new(),
             namespaces: Vec::new(),
             route: "test/".to_string(),
+            extensions: http::Extensions::new(),
         };
         req.set_resolver_match(resolver_match);
         assert!(req.resolver_match().is_some());
@@ -779,6 +1135,40 @@ mod tests {
         assert_eq!(req.post().get("value"), Some("123"));
     }
 
+    #[test]
+    fn test_extensions() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Tenant(u32);
+
+        let request = http::Request::builder()
+            .uri("http://example.com/")
+            .extension(Tenant(1))
+            .body(())
+            .unwrap();
+        let (parts, ()) = request.into_parts();
+        let mut req = HttpRequest::from_axum(parts, Vec::new());
+        assert_eq!(req.extensions().get::<Tenant>(), Some(&Tenant(1)));
+
+        req.extensions_mut().insert(Tenant(2));
+        assert_eq!(req.extensions().get::<Tenant>(), Some(&Tenant(2)));
+
+        let req = HttpRequest::builder().extension(Tenant(3)).build();
+        assert_eq!(req.extensions().get::<Tenant>(), Some(&Tenant(3)));
+        assert!(req.extensions().get::<String>().is_none());
+    }
+
+    #[test]
+    fn test_flag_context_uses_authenticated_user() {
+        let req = HttpRequest::builder()
+            .meta("USER_ID", "7")
+            .meta("USER_AUTHENTICATED", "true")
+            .build();
+        assert_eq!(req.flag_context(), FlagContext::for_user("7"));
+
+        let req = HttpRequest::builder().meta("USER_ID", "7").build();
+        assert_eq!(req.flag_context(), FlagContext::anonymous());
+    }
+
     #[test]
     fn test_build_absolute_uri_relative_no_leading_slash() {
         let req = HttpRequest::builder()
@@ -790,6 +1180,54 @@ mod tests {
         );
     }
 
+    // ── Streaming body tests ────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_streaming_request_buffers_on_demand() {
+        let (parts, ()) = http::Request::builder()
+            .method(Method::POST)
+            .uri("/upload/")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .header("content-length", "19")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let stream = BodyStream::from_chunks(vec!["name=test", "&value=123"]);
+        let mut req = HttpRequest::from_axum_streaming(parts, stream);
+
+        assert!(req.is_streaming());
+        assert!(req.body().is_empty());
+        assert_eq!(
+            req.meta().get("CONTENT_LENGTH").map(String::as_str),
+            Some("19")
+        );
+
+        assert_eq!(req.read_body().await.unwrap(), b"name=test&value=123");
+        assert!(!req.is_streaming());
+        assert_eq!(req.post().get("value"), Some("123"));
+        assert_eq!(req.data().unwrap().get("name").as_deref(), Some("test"));
+    }
+
+    #[tokio::test]
+    async fn test_body_stream_can_only_be_taken_once() {
+        let mut req = HttpRequest::builder()
+            .method(Method::PUT)
+            .body_stream(BodyStream::from_chunks(vec!["abc"]))
+            .build();
+        let stream = req.body_stream().unwrap();
+        assert_eq!(stream.collect().await.unwrap(), b"abc");
+        assert!(req.body_stream().is_err());
+        assert!(req.read_body().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_body_stream_of_buffered_request() {
+        let mut req = HttpRequest::builder().body(b"buffered".to_vec()).build();
+        let stream = req.body_stream().unwrap();
+        assert_eq!(stream.collect().await.unwrap(), b"buffered");
+        assert_eq!(req.body(), b"buffered");
+    }
+
     // ── Cookie integration tests ────────────────────────────────────
 
     #[test]
@@ -905,4 +1343,86 @@ mod tests {
         let files = req.files().get("files").unwrap();
         assert_eq!(files.len(), 2);
     }
+
+    #[test]
+    fn test_data_urlencoded_put() {
+        let req = HttpRequest::builder()
+            .method(Method::PUT)
+            .content_type("application/x-www-form-urlencoded")
+            .body(b"name=Alice&tag=a&tag=b".to_vec())
+            .build();
+        let data = req.data().unwrap();
+        assert_eq!(data.get("name").as_deref(), Some("Alice"));
+        assert_eq!(data.get_list("tag"), vec!["a", "b"]);
+        assert!(data.as_form().is_some());
+    }
+
+    #[test]
+    fn test_data_json_patch() {
+        let req = HttpRequest::builder()
+            .method(Method::PATCH)
+            .content_type("application/json; charset=utf-8")
+            .body(br#"{"title": "Hi", "count": 3, "tags": ["x", "y"], "gone": null}"#.to_vec())
+            .build();
+        let data = req.data().unwrap();
+        assert_eq!(data.get("title").as_deref(), Some("Hi"));
+        assert_eq!(data.get("count").as_deref(), Some("3"));
+        assert_eq!(data.get_list("tags"), vec!["x", "y"]);
+        assert!(data.get("gone").is_none());
+        assert!(data.contains_key("gone"));
+        assert_eq!(data.as_json().unwrap()["count"], 3);
+    }
+
+    #[test]
+    fn test_data_json_vendor_type() {
+        let req = HttpRequest::builder()
+            .content_type("application/vnd.api+json")
+            .body(br#"{"a": true}"#.to_vec())
+            .build();
+        assert_eq!(req.data().unwrap().get("a").as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn test_data_malformed_json() {
+        let req = HttpRequest::builder()
+            .method(Method::PUT)
+            .content_type("application/json")
+            .body(b"{not json".to_vec())
+            .build();
+        assert!(matches!(req.data(), Err(DjangoError::BadRequest(_))));
+        // The error is cached too
+        assert!(req.data().is_err());
+    }
+
+    #[test]
+    fn test_data_empty_and_unknown() {
+        let req = HttpRequest::builder()
+            .content_type("application/json")
+            .build();
+        assert!(req.data().unwrap().is_empty());
+
+        let req = HttpRequest::builder()
+            .content_type("text/plain")
+            .body(b"hello".to_vec())
+            .build();
+        assert!(req.data().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_data_multipart() {
+        let boundary = "b789";
+        let body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"field\"\r\n\
+             \r\n\
+             value\r\n\
+             --{boundary}--\r\n"
+        );
+        let req = HttpRequest::builder()
+            .method(Method::PATCH)
+            .content_type(&format!("multipart/form-data; boundary={boundary}"))
+            .body(body.into_bytes())
+            .build();
+        assert_eq!(req.data().unwrap().get("field").as_deref(), Some("value"));
+    }
 }
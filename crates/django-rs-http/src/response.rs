@@ -486,8 +486,9 @@ impl StreamingHttpResponse {
     }
 }
 
-/// Infers a MIME type from a file extension.
-fn mime_from_extension(ext: &str) -> &'static str {
+/// Infers a MIME type from a file extension, falling back to
+/// `application/octet-stream`.
+pub fn mime_from_extension(ext: &str) -> &'static str {
     match ext.to_lowercase().as_str() {
         "html" | "htm" => "text/html",
         "css" => "text/css",
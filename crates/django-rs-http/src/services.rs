@@ -0,0 +1,194 @@
+//! Application services injected into each request.
+//!
+//! A [`Services`] container maps a type to one shared instance of it: a
+//! database executor, a cache, a mailer, or any other `Arc`'d service the
+//! views need. The application registers its services once, and every
+//! request carries the container, so a view asks the request for what it
+//! needs with [`HttpRequest::service`](crate::HttpRequest::service) instead
+//! of reaching for a global. Tests build the request with mock services
+//! instead.
+//!
+//! Services are keyed by type, so a trait object is registered and looked up
+//! as `dyn Trait`:
+//!
+//! ```
+//! use std::sync::Arc;
+//! use django_rs_http::services::Services;
+//!
+//! trait Mailer: Send + Sync {
+//!     fn send(&self, to: &str) -> bool;
+//! }
+//!
+//! struct ConsoleMailer;
+//!
+//! impl Mailer for ConsoleMailer {
+//!     fn send(&self, _to: &str) -> bool {
+//!         true
+//!     }
+//! }
+//!
+//! let mut services = Services::new();
+//! services.insert::<dyn Mailer>(Arc::new(ConsoleMailer));
+//! services.insert(Arc::new(String::from("https://cdn.example.com")));
+//!
+//! let mailer = services.get::<dyn Mailer>().unwrap();
+//! assert!(mailer.send("alice@example.com"));
+//! assert_eq!(services.get::<String>().unwrap().as_str(), "https://cdn.example.com");
+//! assert!(services.get::<u32>().is_none());
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A type-keyed container of shared services.
+///
+/// Cloning is cheap: clones share the registered services until one of them
+/// is changed.
+#[derive(Clone, Default)]
+pub struct Services {
+    /// Each value is an `Arc<T>` boxed as `Any`, keyed by `TypeId::of::<T>()`.
+    entries: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    /// The registered type names, for `Debug`.
+    names: Arc<HashMap<TypeId, &'static str>>,
+}
+
+impl Services {
+    /// Creates an empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a service, replacing any service of the same type.
+    pub fn insert<T: ?Sized + Send + Sync + 'static>(&mut self, service: Arc<T>) {
+        let id = TypeId::of::<T>();
+        Arc::make_mut(&mut self.entries).insert(id, Arc::new(service));
+        Arc::make_mut(&mut self.names).insert(id, std::any::type_name::<T>());
+    }
+
+    /// Registers a service, replacing any service of the same type, and
+    /// returns the container.
+    #[must_use]
+    pub fn with<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.insert(service);
+        self
+    }
+
+    /// Returns the service registered for `T`, if any.
+    pub fn get<T: ?Sized + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.entries
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref::<Arc<T>>())
+            .cloned()
+    }
+
+    /// Returns `true` if a service is registered for `T`.
+    pub fn contains<T: ?Sized + Send + Sync + 'static>(&self) -> bool {
+        self.entries.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Removes the service registered for `T`, returning it.
+    pub fn remove<T: ?Sized + Send + Sync + 'static>(&mut self) -> Option<Arc<T>> {
+        let id = TypeId::of::<T>();
+        let service = self.get::<T>()?;
+        Arc::make_mut(&mut self.entries).remove(&id);
+        Arc::make_mut(&mut self.names).remove(&id);
+        Some(service)
+    }
+
+    /// Registers every service in `overrides`, replacing services of the
+    /// same types.
+    pub fn extend(&mut self, overrides: &Self) {
+        if overrides.is_empty() {
+            return;
+        }
+        let entries = Arc::make_mut(&mut self.entries);
+        for (id, service) in overrides.entries.iter() {
+            entries.insert(*id, service.clone());
+        }
+        let names = Arc::make_mut(&mut self.names);
+        for (id, name) in overrides.names.iter() {
+            names.insert(*id, name);
+        }
+    }
+
+    /// Returns the number of registered services.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no services are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Services {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&str> = self.names.values().copied().collect();
+        names.sort_unstable();
+        f.debug_struct("Services")
+            .field("types", &names)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    struct English;
+
+    impl Greeter for English {
+        fn greet(&self) -> String {
+            "hello".to_string()
+        }
+    }
+
+    struct French;
+
+    impl Greeter for French {
+        fn greet(&self) -> String {
+            "bonjour".to_string()
+        }
+    }
+
+    #[test]
+    fn test_concrete_and_trait_object_services() {
+        let services = Services::new()
+            .with(Arc::new(42_u32))
+            .with::<dyn Greeter>(Arc::new(English));
+        assert_eq!(*services.get::<u32>().unwrap(), 42);
+        assert_eq!(services.get::<dyn Greeter>().unwrap().greet(), "hello");
+        assert!(services.get::<English>().is_none());
+        assert!(services.contains::<dyn Greeter>());
+        assert_eq!(services.len(), 2);
+    }
+
+    #[test]
+    fn test_insert_replaces_and_clones_are_independent() {
+        let mut services = Services::new().with::<dyn Greeter>(Arc::new(English));
+        let original = services.clone();
+        services.insert::<dyn Greeter>(Arc::new(French));
+        assert_eq!(services.get::<dyn Greeter>().unwrap().greet(), "bonjour");
+        assert_eq!(original.get::<dyn Greeter>().unwrap().greet(), "hello");
+    }
+
+    #[test]
+    fn test_extend_overrides() {
+        let mut services = Services::new()
+            .with(Arc::new(1_u32))
+            .with::<dyn Greeter>(Arc::new(English));
+        services.extend(&Services::new().with::<dyn Greeter>(Arc::new(French)));
+        assert_eq!(services.get::<dyn Greeter>().unwrap().greet(), "bonjour");
+        assert_eq!(*services.get::<u32>().unwrap(), 1);
+
+        assert_eq!(*services.remove::<u32>().unwrap(), 1);
+        assert!(services.remove::<u32>().is_none());
+        assert!(format!("{services:?}").contains("Greeter"));
+    }
+}
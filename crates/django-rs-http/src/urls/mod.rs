@@ -4,7 +4,7 @@
 //!
 //! - [`pattern`]: URL pattern definitions via `path()` and `re_path()`
 //! - [`converters`]: Path type converters (`int`, `str`, `slug`, `uuid`, `path`)
-//! - [`resolver`]: Hierarchical URL resolution with namespace support
+//! - [`resolver`]: Hierarchical URL resolution with namespace and host support
 //! - [`reverse`]: Reverse URL generation from named patterns
 //!
 //! # Examples
@@ -40,6 +40,8 @@ pub struct URLPattern {
     converters: Vec<ConverterEntry>,
     /// The handler function to invoke on match
     callback: RouteHandler,
+    /// Metadata copied into the [`ResolverMatch`](super::resolver::ResolverMatch)
+    extensions: http::Extensions,
 }
 
 impl fmt::Debug for URLPattern {
@@ -79,6 +81,20 @@ impl URLPattern {
         &self.callback
     }
 
+    /// Returns the metadata attached to this pattern.
+    pub const fn extensions(&self) -> &http::Extensions {
+        &self.extensions
+    }
+
+    /// Attaches a value to this pattern, which middleware can read from the
+    /// [`ResolverMatch`](super::resolver::ResolverMatch) of requests routed
+    /// through it.
+    #[must_use]
+    pub fn with_extension<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
     /// Attempts to match the given path against this pattern.
     ///
     /// Returns `Some((matched_kwargs, remaining_path))` on success, where
@@ -219,6 +235,7 @@ pub fn path(route: &str, callback: RouteHandler, name: Option<&str>) -> DjangoRe
         name: name.map(String::from),
         converters: converter_list,
         callback,
+        extensions: http::Extensions::new(),
     })
 }
 
@@ -256,6 +273,7 @@ pub fn re_path(
         name: name.map(String::from),
         converters: Vec::new(),
         callback,
+        extensions: http::Extensions::new(),
     })
 }
 
@@ -313,6 +331,7 @@ pub fn path_prefix(route: &str) -> DjangoResult<URLPattern> {
         name: None,
         converters: converter_list,
         callback: dummy_handler,
+        extensions: http::Extensions::new(),
     })
 }
 
@@ -3,11 +3,18 @@
 //! This module provides [`URLResolver`] for hierarchical URL resolution, including
 //! namespace support. It mirrors Django's `django.urls.URLResolver` and the
 //! `include()` function.
+//!
+//! Resolvers can also be scoped to a host with [`host()`], so that one URL
+//! configuration serves `api.example.com`, `admin.example.com` or per-tenant
+//! subdomains. Placeholders captured from the host are merged into the match
+//! kwargs.
 
 use std::collections::HashMap;
 use std::fmt;
+use std::fmt::Write;
 
 use django_rs_core::{DjangoError, DjangoResult};
+use regex::Regex;
 
 use super::converters::PathConverter;
 use super::pattern::{self, ConverterEntry, RouteHandler, URLPattern};
@@ -35,6 +42,8 @@ pub struct ResolverMatch {
     pub namespaces: Vec<String>,
     /// The matched route string.
     pub route: String,
+    /// The metadata attached to the matched pattern.
+    pub extensions: http::Extensions,
 }
 
 impl fmt::Debug for ResolverMatch {
@@ -83,6 +92,90 @@ impl fmt::Debug for URLEntry {
     }
 }
 
+/// A host pattern such as `"api.{domain}"` or `"{tenant}.example.com"`.
+///
+/// Each `{name}` placeholder captures one or more dot-separated labels of the
+/// host. Matching ignores the port and ASCII case.
+#[derive(Debug, Clone)]
+pub struct HostPattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl HostPattern {
+    /// Compiles a host pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::ImproperlyConfigured`] if a placeholder is
+    /// unclosed or its name is not a valid identifier.
+    pub fn new(pattern: &str) -> DjangoResult<Self> {
+        let mut regex_str = String::from("^");
+        let mut remaining = pattern;
+        while let Some(start) = remaining.find('{') {
+            regex_str.push_str(&regex::escape(&remaining[..start].to_ascii_lowercase()));
+            let end = remaining[start..].find('}').ok_or_else(|| {
+                DjangoError::ImproperlyConfigured(format!(
+                    "Unclosed brace in host pattern: {pattern}"
+                ))
+            })? + start;
+            let name = &remaining[start + 1..end];
+            let valid = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(DjangoError::ImproperlyConfigured(format!(
+                    "Invalid placeholder '{{{name}}}' in host pattern: {pattern}"
+                )));
+            }
+            write!(regex_str, r"(?P<{name}>[a-z0-9-]+(?:\.[a-z0-9-]+)*?)").ok();
+            remaining = &remaining[end + 1..];
+        }
+        regex_str.push_str(&regex::escape(&remaining.to_ascii_lowercase()));
+        regex_str.push('$');
+        let regex = Regex::new(&regex_str).map_err(|e| {
+            DjangoError::ImproperlyConfigured(format!("Invalid host pattern '{pattern}': {e}"))
+        })?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    /// Returns the pattern string.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Matches a host (optionally with a port), returning the captured
+    /// placeholders.
+    pub fn match_host(&self, host: &str) -> Option<HashMap<String, String>> {
+        let host = strip_port(host).to_ascii_lowercase();
+        let captures = self.regex.captures(&host)?;
+        Some(
+            self.regex
+                .capture_names()
+                .flatten()
+                .filter_map(|name| {
+                    captures
+                        .name(name)
+                        .map(|m| (name.to_string(), m.as_str().to_string()))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Strips the port from a host, keeping bracketed IPv6 addresses intact.
+fn strip_port(host: &str) -> &str {
+    match host.rfind(':') {
+        Some(i) if !host[i..].contains(']') => &host[..i],
+        _ => host,
+    }
+}
+
 /// A URL resolver that matches a prefix and delegates to child patterns.
 ///
 /// Resolvers form a tree structure where each level matches a portion of the
@@ -97,6 +190,8 @@ pub struct URLResolver {
     namespace: Option<String>,
     /// The application namespace for this resolver
     app_name: Option<String>,
+    /// The host this resolver is restricted to, if any
+    host: Option<HostPattern>,
 }
 
 impl fmt::Debug for URLResolver {
@@ -106,6 +201,7 @@ impl fmt::Debug for URLResolver {
             .field("url_patterns", &self.url_patterns)
             .field("namespace", &self.namespace)
             .field("app_name", &self.app_name)
+            .field("host", &self.host.as_ref().map(HostPattern::as_str))
             .finish()
     }
 }
@@ -123,9 +219,22 @@ impl URLResolver {
             url_patterns,
             namespace: namespace.map(String::from),
             app_name: app_name.map(String::from),
+            host: None,
         }
     }
 
+    /// Restricts this resolver to requests whose host matches `host`.
+    #[must_use]
+    pub fn with_host(mut self, host: HostPattern) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Returns the host pattern this resolver is restricted to, if any.
+    pub const fn host_pattern(&self) -> Option<&HostPattern> {
+        self.host.as_ref()
+    }
+
     /// Returns the prefix pattern.
     pub const fn pattern(&self) -> &URLPattern {
         &self.pattern
@@ -150,16 +259,44 @@ impl URLResolver {
     ///
     /// Tries each child pattern/resolver in order. For nested resolvers,
     /// the matched prefix is stripped and the remainder is passed to the child.
+    /// Resolvers restricted to a host never match; use
+    /// [`resolve_host`](Self::resolve_host) when the request host is known.
     ///
     /// # Errors
     ///
     /// Returns [`DjangoError::NotFound`] if no pattern matches the path.
     pub fn resolve(&self, path: &str) -> DjangoResult<ResolverMatch> {
+        self.resolve_inner(None, path)
+    }
+
+    /// Resolves a URL path requested on the given host.
+    ///
+    /// Like [`resolve`](Self::resolve), but resolvers restricted to a host
+    /// pattern are tried when `host` matches it, and the placeholders captured
+    /// from the host are added to the match kwargs. Path kwargs take
+    /// precedence over host kwargs of the same name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::NotFound`] if no pattern matches the host and path.
+    pub fn resolve_host(&self, host: &str, path: &str) -> DjangoResult<ResolverMatch> {
+        self.resolve_inner(Some(host), path)
+    }
+
+    fn resolve_inner(&self, host: Option<&str>, path: &str) -> DjangoResult<ResolverMatch> {
+        let not_found = || DjangoError::NotFound(format!("No URL pattern matches '{path}'"));
+        let host_kwargs = match &self.host {
+            Some(pattern) => host
+                .and_then(|h| pattern.match_host(h))
+                .ok_or_else(not_found)?,
+            None => HashMap::new(),
+        };
+
         // First, match the prefix
-        let (prefix_kwargs, remaining) = self
-            .pattern
-            .match_path(path)
-            .ok_or_else(|| DjangoError::NotFound(format!("No URL pattern matches '{path}'")))?;
+        let (mut prefix_kwargs, remaining) = self.pattern.match_path(path).ok_or_else(not_found)?;
+        for (k, v) in host_kwargs {
+            prefix_kwargs.entry(k).or_insert(v);
+        }
 
         // Try each child pattern
         for entry in &self.url_patterns {
@@ -190,11 +327,12 @@ impl URLResolver {
                             app_names,
                             namespaces,
                             route,
+                            extensions: child_pattern.extensions().clone(),
                         });
                     }
                 }
                 URLEntry::Resolver(child_resolver) => {
-                    if let Ok(mut resolver_match) = child_resolver.resolve(&remaining) {
+                    if let Ok(mut resolver_match) = child_resolver.resolve_inner(host, &remaining) {
                         // Merge prefix kwargs
                         for (k, v) in &prefix_kwargs {
                             resolver_match
@@ -337,6 +475,43 @@ pub fn include(
     ))
 }
 
+/// Creates a resolver that only matches requests for the given host.
+///
+/// `{name}` placeholders in the host pattern capture one or more labels and
+/// are merged into the kwargs of the match. Requests must be resolved with
+/// [`URLResolver::resolve_host`] for host resolvers to apply.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_http::urls::pattern::path;
+/// use django_rs_http::urls::resolver::{host, root, URLEntry};
+/// use django_rs_http::{HttpRequest, HttpResponse};
+/// use std::sync::Arc;
+///
+/// let handler = Arc::new(|_req: HttpRequest| -> django_rs_http::BoxFuture {
+///     Box::pin(async { HttpResponse::ok("dashboard") })
+/// });
+/// let tenant = host(
+///     "{tenant}.example.com",
+///     vec![URLEntry::Pattern(path("dashboard/", handler, Some("dashboard")).unwrap())],
+/// )
+/// .unwrap();
+/// let resolver = root(vec![URLEntry::Resolver(tenant)]).unwrap();
+///
+/// let m = resolver.resolve_host("acme.example.com:8000", "dashboard/").unwrap();
+/// assert_eq!(m.kwargs["tenant"], "acme");
+/// assert!(resolver.resolve_host("example.org", "dashboard/").is_err());
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the host pattern is invalid.
+pub fn host(host_pattern: &str, patterns: Vec<URLEntry>) -> DjangoResult<URLResolver> {
+    let host = HostPattern::new(host_pattern)?;
+    Ok(root(patterns)?.with_host(host))
+}
+
 /// Creates a root resolver (matches empty prefix) with the given URL entries.
 ///
 /// This is typically used at the top level of the URL configuration.
@@ -526,4 +701,78 @@ mod tests {
         assert_eq!(m.kwargs.get("version").unwrap(), "v2");
         assert_eq!(m.url_name.as_deref(), Some("posts"));
     }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_host_pattern_matching() {
+        let pattern = HostPattern::new("api.{domain}").unwrap();
+        let kwargs = pattern.match_host("API.example.com:8443").unwrap();
+        assert_eq!(kwargs["domain"], "example.com");
+        assert!(pattern.match_host("www.example.com").is_none());
+
+        let tenant = HostPattern::new("{tenant}.example.com").unwrap();
+        assert_eq!(
+            tenant.match_host("acme.example.com").unwrap()["tenant"],
+            "acme"
+        );
+        assert!(tenant.match_host("example.com").is_none());
+
+        assert!(HostPattern::new("{tenant.example.com").is_err());
+        assert!(HostPattern::new("{1st}.example.com").is_err());
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_resolve_host_routes_by_subdomain() {
+        let api = host(
+            "api.{domain}",
+            vec![URLEntry::Pattern(
+                path("users/<int:id>/", dummy_handler(), Some("api-user")).unwrap(),
+            )],
+        )
+        .unwrap();
+        let tenant = host(
+            "{tenant}.example.com",
+            vec![URLEntry::Resolver(
+                include(
+                    "admin/",
+                    vec![URLEntry::Pattern(
+                        path("", dummy_handler(), Some("index")).unwrap(),
+                    )],
+                    Some("admin"),
+                    None,
+                )
+                .unwrap(),
+            )],
+        )
+        .unwrap();
+        let resolver = root(vec![
+            URLEntry::Resolver(api),
+            URLEntry::Resolver(tenant),
+            URLEntry::Pattern(path("users/<int:id>/", dummy_handler(), Some("user")).unwrap()),
+        ])
+        .unwrap();
+
+        let m = resolver
+            .resolve_host("api.example.com", "users/7/")
+            .unwrap();
+        assert_eq!(m.url_name.as_deref(), Some("api-user"));
+        assert_eq!(m.kwargs["id"], "7");
+        assert_eq!(m.kwargs["domain"], "example.com");
+
+        let m = resolver.resolve_host("acme.example.com", "admin/").unwrap();
+        assert_eq!(m.view_name(), "admin:index");
+        assert_eq!(m.kwargs["tenant"], "acme");
+
+        // Other hosts, and host-less resolution, fall through to the plain patterns.
+        let m = resolver
+            .resolve_host("www.example.org", "users/7/")
+            .unwrap();
+        assert_eq!(m.url_name.as_deref(), Some("user"));
+        assert_eq!(
+            resolver.resolve("users/7/").unwrap().url_name.as_deref(),
+            Some("user")
+        );
+        assert!(resolver.resolve("admin/").is_err());
+    }
 }
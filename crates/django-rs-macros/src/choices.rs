@@ -0,0 +1,174 @@
+//! `#[derive(Choices)]` implementation.
+//!
+//! Generates a `django_rs_db::fields::Choices` implementation for a
+//! unit-variant enum, together with the `Value` conversions needed to store
+//! the enum directly in a model field.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+/// Per-variant attributes parsed from `#[choice(...)]`.
+#[derive(Debug, Default)]
+struct ChoiceAttrs {
+    /// Stored value: a string or integer literal. Defaults to the
+    /// variant name in `snake_case`.
+    value: Option<syn::Lit>,
+
+    /// Display label. Defaults to the variant name split into words.
+    label: Option<String>,
+}
+
+impl ChoiceAttrs {
+    /// Parses the `#[choice(...)]` attributes of a variant.
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("choice")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("value") {
+                    parsed.value = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("label") {
+                    parsed.label = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else {
+                    return Err(meta.error("unsupported choice attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+/// Generates the `Choices` implementation for the given derive input.
+pub fn derive_choices_impl(input: &DeriveInput) -> TokenStream {
+    let enum_name = &input.ident;
+    let enum_name_str = enum_name.to_string();
+    let syn::Data::Enum(data) = &input.data else {
+        return syn::Error::new_spanned(enum_name, "#[derive(Choices)] only supports enums")
+            .to_compile_error();
+    };
+
+    let mut idents = Vec::new();
+    let mut values = Vec::new();
+    let mut labels = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return syn::Error::new_spanned(
+                variant,
+                "#[derive(Choices)] only supports unit variants",
+            )
+            .to_compile_error();
+        }
+        let attrs = match ChoiceAttrs::parse(&variant.attrs) {
+            Ok(attrs) => attrs,
+            Err(e) => return e.to_compile_error(),
+        };
+        let ident = &variant.ident;
+        let value = match &attrs.value {
+            Some(syn::Lit::Str(s)) => {
+                let s = s.value();
+                quote! { django_rs_db::value::Value::String(#s.to_string()) }
+            }
+            Some(syn::Lit::Int(i)) => match i.base10_parse::<i64>() {
+                Ok(n) => quote! { django_rs_db::value::Value::Int(#n) },
+                Err(e) => return e.to_compile_error(),
+            },
+            Some(other) => {
+                return syn::Error::new_spanned(
+                    other,
+                    "choice value must be a string or integer literal",
+                )
+                .to_compile_error();
+            }
+            None => {
+                let s = to_snake_case(&ident.to_string());
+                quote! { django_rs_db::value::Value::String(#s.to_string()) }
+            }
+        };
+        let label = attrs.label.unwrap_or_else(|| to_label(&ident.to_string()));
+        idents.push(ident);
+        values.push(value);
+        labels.push(label);
+    }
+
+    quote! {
+        impl django_rs_db::fields::Choices for #enum_name {
+            fn variants() -> &'static [Self] {
+                &[#(Self::#idents),*]
+            }
+
+            fn value(&self) -> django_rs_db::value::Value {
+                match self {
+                    #(Self::#idents => #values,)*
+                }
+            }
+
+            fn label(&self) -> &'static str {
+                match self {
+                    #(Self::#idents => #labels,)*
+                }
+            }
+        }
+
+        impl From<#enum_name> for django_rs_db::value::Value {
+            fn from(choice: #enum_name) -> Self {
+                django_rs_db::fields::Choices::value(&choice)
+            }
+        }
+
+        impl django_rs_db::query::compiler::FromValue for #enum_name {
+            fn from_value(
+                value: &django_rs_db::value::Value,
+            ) -> Result<Self, django_rs_core::DjangoError> {
+                <Self as django_rs_db::fields::Choices>::from_value(value).ok_or_else(|| {
+                    django_rs_core::DjangoError::DatabaseError(format!(
+                        "'{}' is not a valid {}",
+                        value, #enum_name_str
+                    ))
+                })
+            }
+        }
+    }
+}
+
+/// Converts a `CamelCase` identifier to `snake_case`.
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Converts a `CamelCase` identifier to a sentence-case label.
+fn to_label(name: &str) -> String {
+    let snake = to_snake_case(name).replace('_', " ");
+    let mut chars = snake.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().collect::<String>() + chars.as_str()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Draft"), "draft");
+        assert_eq!(to_snake_case("InReview"), "in_review");
+    }
+
+    #[test]
+    fn test_to_label() {
+        assert_eq!(to_label("Draft"), "Draft");
+        assert_eq!(to_label("InReview"), "In review");
+    }
+}
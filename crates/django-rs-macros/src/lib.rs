@@ -12,6 +12,9 @@
 //! - **`#[derive(Model)]`** — Generates a `django_rs_db::model::Model` implementation
 //! - **`#[derive(Form)]`** — Generates form field definitions and a `BaseForm` constructor
 //! - **`#[derive(Admin)]`** — Generates admin configuration methods
+//! - **`#[derive(Choices)]`** — Generates a `django_rs_db::fields::Choices` implementation for an enum
+//! - **`#[derive(ValueType)]`** — Generates a `django_rs_db::value::ValueType` implementation and
+//!   `Value` conversions for an application type
 //!
 //! ## Function-like Macros
 //!
@@ -26,11 +29,13 @@
 extern crate proc_macro;
 
 mod admin;
+mod choices;
 mod form;
 mod model;
 mod string_list;
 mod urls;
 mod utils;
+mod value_type;
 
 use proc_macro::TokenStream;
 
@@ -44,6 +49,12 @@ use proc_macro::TokenStream;
 /// - `verbose_name_plural = "..."` — Human-readable plural name
 /// - `abstract_model` — No database table is created
 /// - `ordering = ["-created_at", "name"]` — Default query ordering
+/// - `db_schema = "billing"` — Database schema; the table is referenced as `"billing"."table"`
+/// - `timestamped` — `created_at` and `updated_at` become `auto_now_add` and `auto_now`, and
+///   `TimeStampedModel` is implemented
+/// - `index(name = "...", fields = [...], expressions = [...], condition = "...", include = [...], unique)` —
+///   A model-level index; repeat for several. `expressions` are SQL expressions such as
+///   `"LOWER(email)"` and `condition` makes a partial index. Indexes with either need a `name`
 ///
 /// # Field-level attributes (`#[field(...)]`)
 ///
@@ -63,6 +74,13 @@ use proc_macro::TokenStream;
 /// - `auto_now_add` — Set timestamp on creation
 /// - `editable = false` — Not editable in forms
 /// - `db_column = "col"` — Override database column name
+/// - `choices = MyEnum` — Restrict values to a `#[derive(Choices)]` enum and
+///   generate a `get_<field>_display()` method
+/// - `value_type` — Take the field type from the field's `ValueType` implementation
+/// - `encrypted` — Encrypt the `String` value before storage and decrypt it on load
+///   (`EncryptedCharField` with `max_length`, `EncryptedTextField` otherwise)
+/// - `hash_field = "field"` — With `encrypted`, store the value's blind index in
+///   `field` so exact lookups work
 ///
 /// # Example
 ///
@@ -125,6 +143,89 @@ pub fn derive_form(input: TokenStream) -> TokenStream {
     form::derive_form_impl(input).into()
 }
 
+/// Derive macro for implementing the `Choices` trait on a unit-variant enum.
+///
+/// The enum must also derive `Clone`. Besides `Choices`, this generates
+/// `From<Enum> for Value` and `FromValue for Enum`, so the enum can be used
+/// directly as a model field type.
+///
+/// # Variant-level attributes (`#[choice(...)]`)
+///
+/// - `value = "..."` or `value = 1` — Stored value (defaults to the variant name in `snake_case`)
+/// - `label = "..."` — Display label (defaults to the variant name split into words)
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Choices)]
+/// pub enum Status {
+///     Draft,
+///     #[choice(label = "Awaiting review")]
+///     InReview,
+///     #[choice(value = "live")]
+///     Published,
+/// }
+///
+/// #[derive(Model)]
+/// #[model(table = "blog_post", app = "blog")]
+/// pub struct Post {
+///     #[field(primary_key, auto)]
+///     pub id: i64,
+///
+///     #[field(choices = Status)]
+///     pub status: Status,
+/// }
+///
+/// assert_eq!(post.get_status_display(), "Awaiting review");
+/// ```
+#[proc_macro_derive(Choices, attributes(choice))]
+pub fn derive_choices(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    choices::derive_choices_impl(&input).into()
+}
+
+/// Derive macro for implementing the `ValueType` trait on an application type.
+///
+/// Besides `ValueType`, this generates `From<T> for Value` and `FromValue for T`,
+/// so the type can be used as a model field (with `#[field(value_type)]`), as a
+/// query parameter, and with `Row::get`.
+///
+/// A struct with a single field is stored as that field, whose type must itself
+/// be a `ValueType`. Any other type needs `#[value(string)]`.
+///
+/// # Type-level attributes (`#[value(...)]`)
+///
+/// - `string` — Store the type as text through its `Display` and `FromStr`
+///   implementations
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Debug, Clone, Copy, PartialEq, ValueType)]
+/// pub struct Money(i64);
+///
+/// #[derive(Debug, Clone, PartialEq, ValueType)]
+/// #[value(string)]
+/// pub struct Website(url::Url);
+///
+/// #[derive(Model)]
+/// #[model(table = "shop_product", app = "shop")]
+/// pub struct Product {
+///     #[field(primary_key, auto)]
+///     pub id: i64,
+///
+///     #[field(value_type)]
+///     pub price: Money,
+/// }
+///
+/// let price: Money = row.get("price")?;
+/// ```
+#[proc_macro_derive(ValueType, attributes(value))]
+pub fn derive_value_type(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    value_type::derive_value_type_impl(&input).into()
+}
+
 /// Derive macro for generating admin panel configuration.
 ///
 /// # Struct-level attributes (`#[admin(...)]`)
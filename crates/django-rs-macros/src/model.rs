@@ -4,7 +4,11 @@
 //! trait for a struct, including `ModelMeta`, field definitions, value
 //! conversions, and row deserialization.
 
-use darling::{FromDeriveInput, FromField};
+// darling's derived `FromField` parser ends its attribute loop with a
+// `continue`, which `clippy::needless_continue` reports on `FieldOpts`.
+#![allow(clippy::needless_continue)]
+
+use darling::{FromDeriveInput, FromField, FromMeta};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{DeriveInput, Type};
@@ -41,10 +45,44 @@ pub struct ModelOpts {
     /// Default ordering (e.g., `["-created_at", "name"]`).
     #[darling(default)]
     pub ordering: Option<StringList>,
+
+    /// Database schema holding the table (e.g., `"billing"`).
+    pub db_schema: Option<String>,
+
+    /// Treats `created_at`/`updated_at` as `auto_now_add`/`auto_now` and
+    /// implements `TimeStampedModel`.
+    pub timestamped: darling::util::Flag,
+
+    /// Model-level indexes, one `index(...)` per index.
+    #[darling(multiple, rename = "index")]
+    pub indexes: Vec<IndexOpts>,
+}
+
+/// A model-level index parsed from `#[model(index(...))]`.
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+pub struct IndexOpts {
+    /// The index name; required with `expressions` or `condition`.
+    pub name: Option<String>,
+
+    /// The indexed columns.
+    pub fields: StringList,
+
+    /// SQL expressions to index (e.g., `["LOWER(email)"]`).
+    pub expressions: StringList,
+
+    /// The SQL condition of a partial index (e.g., `"deleted_at IS NULL"`).
+    pub condition: Option<String>,
+
+    /// Columns to include in a covering index.
+    pub include: StringList,
+
+    /// Unique index.
+    pub unique: bool,
 }
 
 /// Per-field attributes parsed from `#[field(...)]`.
-#[derive(Debug, FromField)]
+#[derive(Debug, Clone, FromField)]
 #[darling(attributes(field))]
 pub struct FieldOpts {
     pub ident: Option<syn::Ident>,
@@ -108,13 +146,23 @@ pub struct FieldOpts {
     pub auto_now_add: bool,
 
     /// Whether the field is editable.
-    #[darling(default)]
-    #[allow(dead_code)]
     pub editable: Option<bool>,
 
     /// Database column name override.
     #[darling(default)]
     pub db_column: Option<String>,
+
+    /// A `Choices` enum restricting the allowed values.
+    pub choices: Option<syn::Path>,
+
+    /// Takes the field type from the field's `ValueType` implementation.
+    pub value_type: darling::util::Flag,
+
+    /// Encrypts the value before storage and decrypts it on load.
+    pub encrypted: darling::util::Flag,
+
+    /// The field holding an encrypted field's blind index.
+    pub hash_field: Option<String>,
 }
 
 /// Generates the `Model` trait implementation for the given derive input.
@@ -128,10 +176,28 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
     let model_name_lower = struct_name.to_string().to_lowercase();
 
     let app_label = opts.app.as_deref().unwrap_or("app");
-    let table_name = opts
+    let bare_table_name = opts
         .table
         .clone()
         .unwrap_or_else(|| format!("{app_label}_{model_name_lower}"));
+    let table_name = opts.db_schema.as_ref().map_or_else(
+        || bare_table_name.clone(),
+        |schema| format!("{schema}.{bare_table_name}"),
+    );
+    let db_schema_meta = opts.db_schema.as_ref().map_or_else(
+        || quote! { None },
+        |schema| quote! { Some(#schema.to_string()) },
+    );
+    let db_schema_token = opts
+        .db_schema
+        .as_ref()
+        .map_or_else(TokenStream::new, |schema| {
+            quote! {
+                fn db_schema() -> Option<&'static str> {
+                    Some(#schema)
+                }
+            }
+        });
     let verbose = opts
         .verbose_name
         .clone()
@@ -142,12 +208,28 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
         .unwrap_or_else(|| format!("{verbose}s"));
     let abstract_model = opts.abstract_model;
 
-    let fields = opts
+    let mut fields: Vec<FieldOpts> = opts
         .data
         .as_ref()
         .take_struct()
         .expect("#[derive(Model)] only supports named structs")
-        .fields;
+        .fields
+        .into_iter()
+        .cloned()
+        .collect();
+
+    let timestamped_impl = if opts.timestamped.is_present() {
+        match timestamped_impl(struct_name, &mut fields) {
+            Ok(tokens) => tokens,
+            Err(e) => return e.to_compile_error(),
+        }
+    } else {
+        TokenStream::new()
+    };
+    let auto_timestamp_tokens: Vec<TokenStream> = fields
+        .iter()
+        .filter_map(auto_timestamp_assignment)
+        .collect();
 
     // Generate ordering tokens
     let ordering_tokens = match &opts.ordering {
@@ -169,7 +251,7 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
     };
 
     // Generate FieldDef entries
-    let field_def_tokens: Vec<TokenStream> = fields.iter().map(|f| generate_field_def(f)).collect();
+    let field_def_tokens: Vec<TokenStream> = fields.iter().map(generate_field_def).collect();
 
     // Generate field_values() entries
     let field_value_tokens: Vec<TokenStream> = fields
@@ -249,6 +331,20 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
         .map(|f| {
             let ident = f.ident.as_ref().unwrap();
             let name_str = ident.to_string();
+            if f.value_type.is_present() {
+                // ValueType implies FromValue, whatever the type is called
+                return quote! { #ident: row.get(#name_str)? };
+            }
+            if f.encrypted.is_present() {
+                let column = f.db_column.clone().unwrap_or_else(|| name_str.clone());
+                return quote! {
+                    #ident: django_rs_db::encryption::decrypt_column(
+                        row,
+                        <Self as django_rs_db::model::Model>::table_name(),
+                        #column,
+                    )?
+                };
+            }
             generate_from_row_field(ident, &name_str, &f.ty)
         })
         .collect();
@@ -259,7 +355,7 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
         .filter(|f| f.db_index)
         .map(|f| {
             let field_name = f.ident.as_ref().unwrap().to_string();
-            let idx_name = format!("idx_{}_{}", table_name, field_name);
+            let idx_name = format!("idx_{bare_table_name}_{field_name}");
             quote! {
                 django_rs_db::model::Index {
                     name: Some(#idx_name.to_string()),
@@ -281,7 +377,7 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
         .filter(|f| f.unique && !f.primary_key)
         .map(|f| {
             let field_name = f.ident.as_ref().unwrap().to_string();
-            let idx_name = format!("uniq_{}_{}", table_name, field_name);
+            let idx_name = format!("uniq_{bare_table_name}_{field_name}");
             quote! {
                 django_rs_db::model::Index {
                     name: Some(#idx_name.to_string()),
@@ -297,7 +393,44 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
         })
         .collect();
 
-    let all_indexes = [index_tokens, unique_index_tokens].concat();
+    let model_index_tokens =
+        match model_index_tokens(struct_name, &opts.indexes, &fields, &bare_table_name) {
+            Ok(tokens) => tokens,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    let all_indexes = [index_tokens, unique_index_tokens, model_index_tokens].concat();
+
+    // Generate get_<field>_display() methods for fields with choices
+    let display_tokens: Vec<TokenStream> = fields
+        .iter()
+        .filter_map(|f| {
+            let choices = f.choices.as_ref()?;
+            let ident = f.ident.as_ref().unwrap();
+            let method = syn::Ident::new(&format!("get_{ident}_display"), ident.span());
+            let doc = format!("Returns the display label for the `{ident}` field.");
+            Some(quote! {
+                #[doc = #doc]
+                pub fn #method(&self) -> String {
+                    let value = django_rs_db::value::Value::from(self.#ident.clone());
+                    match <#choices as django_rs_db::fields::Choices>::label_for(&value) {
+                        Some(label) => label.to_string(),
+                        None if value.is_null() => String::new(),
+                        None => value.to_string(),
+                    }
+                }
+            })
+        })
+        .collect();
+    let display_impl = if display_tokens.is_empty() {
+        TokenStream::new()
+    } else {
+        quote! {
+            impl #struct_name {
+                #(#display_tokens)*
+            }
+        }
+    };
 
     let expanded = quote! {
         impl django_rs_db::model::Model for #struct_name {
@@ -317,6 +450,7 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
                         fields: vec![#(#field_def_tokens),*],
                         constraints: vec![],
                         inheritance_type: django_rs_db::query::compiler::InheritanceType::None,
+                        db_schema: #db_schema_meta,
                     }
                 });
                 &META
@@ -326,6 +460,8 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
                 #table_name
             }
 
+            #db_schema_token
+
             fn app_label() -> &'static str {
                 #app_label
             }
@@ -338,6 +474,11 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
                 ]
             }
 
+            #[allow(unused_variables)]
+            fn apply_auto_timestamps(&mut self, now: django_rs_db::chrono::NaiveDateTime, created: bool) {
+                #(#auto_timestamp_tokens)*
+            }
+
             fn from_row(row: &django_rs_db::query::compiler::Row) -> Result<Self, django_rs_core::DjangoError>
             where
                 Self: Sized,
@@ -347,11 +488,160 @@ pub fn derive_model_impl(input: DeriveInput) -> TokenStream {
                 })
             }
         }
+
+        #display_impl
+
+        #timestamped_impl
     };
 
     expanded
 }
 
+/// Generates the `Index` entries for `#[model(index(...))]` attributes.
+///
+/// Like Django, functional and partial indexes must be named; plain ones
+/// default to `idx_{table}_{fields}`.
+fn model_index_tokens(
+    struct_name: &syn::Ident,
+    indexes: &[IndexOpts],
+    fields: &[FieldOpts],
+    bare_table_name: &str,
+) -> Result<Vec<TokenStream>, syn::Error> {
+    let error = |msg: String| syn::Error::new_spanned(struct_name, msg);
+    indexes
+        .iter()
+        .map(|idx| {
+            let columns = &idx.fields.0;
+            let expressions = &idx.expressions.0;
+            let include = &idx.include.0;
+            if columns.is_empty() && expressions.is_empty() {
+                return Err(error(
+                    "#[model(index(...))] requires `fields` or `expressions`".to_string(),
+                ));
+            }
+            for column in columns.iter().chain(include) {
+                if !fields
+                    .iter()
+                    .any(|f| f.ident.as_ref().is_some_and(|i| i == column))
+                {
+                    return Err(error(format!(
+                        "#[model(index(...))] refers to unknown field `{column}`"
+                    )));
+                }
+            }
+            let name = match &idx.name {
+                Some(name) => name.clone(),
+                None if expressions.is_empty() && idx.condition.is_none() => {
+                    format!("idx_{bare_table_name}_{}", columns.join("_"))
+                }
+                None => {
+                    return Err(error(
+                        "an index with `expressions` or a `condition` requires a `name`"
+                            .to_string(),
+                    ))
+                }
+            };
+            let unique = idx.unique;
+            let condition = idx.condition.as_ref().map_or_else(
+                || quote! { None },
+                |cond| quote! { Some(#cond.to_string()) },
+            );
+            Ok(quote! {
+                django_rs_db::model::Index {
+                    name: Some(#name.to_string()),
+                    fields: vec![#(#columns.to_string()),*],
+                    unique: #unique,
+                    index_type: django_rs_db::model::IndexType::BTree,
+                    concurrently: false,
+                    expressions: vec![#(#expressions.to_string()),*],
+                    include: vec![#(#include.to_string()),*],
+                    condition: #condition,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Marks the `created_at` and `updated_at` fields of a `#[model(timestamped)]`
+/// struct as automatic and generates its `TimeStampedModel` implementation.
+fn timestamped_impl(
+    struct_name: &syn::Ident,
+    fields: &mut [FieldOpts],
+) -> Result<TokenStream, syn::Error> {
+    let mut accessors = Vec::new();
+    for name in ["created_at", "updated_at"] {
+        let field = fields
+            .iter_mut()
+            .find(|f| f.ident.as_ref().is_some_and(|i| i == name))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    struct_name,
+                    format!("#[model(timestamped)] requires a `{name}` field"),
+                )
+            })?;
+        if !type_to_string(&field.ty).contains("NaiveDateTime") {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                format!("`{name}` must be a `NaiveDateTime` or `Option<NaiveDateTime>`"),
+            ));
+        }
+        if name == "created_at" {
+            field.auto_now_add = true;
+        } else {
+            field.auto_now = true;
+        }
+
+        let ident = syn::Ident::new(name, proc_macro2::Span::call_site());
+        let value = if is_option_type(&field.ty) {
+            quote! { self.#ident }
+        } else {
+            quote! { Some(self.#ident) }
+        };
+        accessors.push(quote! {
+            fn #ident(&self) -> Option<django_rs_db::chrono::NaiveDateTime> {
+                #value
+            }
+        });
+    }
+    Ok(quote! {
+        impl django_rs_db::timestamps::TimeStampedModel for #struct_name {
+            #(#accessors)*
+        }
+    })
+}
+
+/// Generates the statement setting an automatic timestamp field in
+/// `apply_auto_timestamps`, if the field is one and its type is supported.
+fn auto_timestamp_assignment(f: &FieldOpts) -> Option<TokenStream> {
+    if !f.auto_now && !f.auto_now_add {
+        return None;
+    }
+    let ident = f.ident.as_ref()?;
+    let type_str = type_to_string(unwrap_option_type(&f.ty).unwrap_or(&f.ty));
+    let value = if type_str.contains("NaiveDateTime") {
+        quote! { now }
+    } else if type_str.contains("NaiveDate") {
+        quote! { now.date() }
+    } else {
+        return None;
+    };
+    let value = if is_option_type(&f.ty) {
+        quote! { Some(#value) }
+    } else {
+        value
+    };
+    let condition = if f.auto_now {
+        quote! { true }
+    } else {
+        quote! { created }
+    };
+    Some(quote! {
+        if #condition {
+            self.#ident = #value;
+        }
+    })
+}
+
 /// Generates the code to extract a field value from a `Row`.
 ///
 /// For types that implement `FromValue` (i64, i32, f64, bool, String, Uuid, Option<T>),
@@ -479,6 +769,18 @@ fn generate_field_def(f: &FieldOpts) -> TokenStream {
     if let Some(ref col) = f.db_column {
         chain.push(quote! { .column(#col) });
     }
+    if let Some(ref choices) = f.choices {
+        chain.push(quote! { .choices_from::<#choices>() });
+    }
+    if let Some(editable) = f.editable {
+        chain.push(quote! { .editable(#editable) });
+    }
+    if f.auto_now {
+        chain.push(quote! { .auto_now() });
+    }
+    if f.auto_now_add {
+        chain.push(quote! { .auto_now_add() });
+    }
 
     quote! {
         django_rs_db::fields::FieldDef::new(#name_str, #field_type)
@@ -510,6 +812,27 @@ fn infer_field_type(f: &FieldOpts) -> TokenStream {
     let inner_type = unwrap_option_type(&f.ty).unwrap_or(&f.ty);
     let type_str = type_to_string(inner_type);
 
+    if f.value_type.is_present() {
+        return quote! {
+            <#inner_type as django_rs_db::value::ValueType>::field_type()
+        };
+    }
+
+    if f.encrypted.is_present() {
+        let hash_field = f.hash_field.as_ref().map_or_else(
+            || quote! { None },
+            |name| quote! { Some(#name.to_string()) },
+        );
+        if f.max_length.is_some() {
+            return quote! {
+                django_rs_db::fields::FieldType::EncryptedCharField { hash_field: #hash_field }
+            };
+        }
+        return quote! {
+            django_rs_db::fields::FieldType::EncryptedTextField { hash_field: #hash_field }
+        };
+    }
+
     // Auto fields
     if f.auto {
         if type_str == "i64" {
@@ -518,18 +841,30 @@ fn infer_field_type(f: &FieldOpts) -> TokenStream {
         return quote! { django_rs_db::fields::FieldType::AutoField };
     }
 
-    // auto_now / auto_now_add -> DateTimeField
+    // auto_now / auto_now_add -> DateTimeField (or DateField for dates)
     if f.auto_now || f.auto_now_add {
+        if type_str.contains("NaiveDate") && !type_str.contains("NaiveDateTime") {
+            return quote! { django_rs_db::fields::FieldType::DateField };
+        }
         return quote! { django_rs_db::fields::FieldType::DateTimeField };
     }
 
+    if let Some(ref choices) = f.choices {
+        let is_primitive = matches!(type_str.as_str(), "i16" | "i32" | "i64" | "String");
+        if !is_primitive {
+            return quote! {
+                <#choices as django_rs_db::fields::Choices>::field_type()
+            };
+        }
+    }
+
     match type_str.as_str() {
         "i32" => quote! { django_rs_db::fields::FieldType::IntegerField },
         "i16" => quote! { django_rs_db::fields::FieldType::SmallIntegerField },
         "i64" => quote! { django_rs_db::fields::FieldType::BigIntegerField },
         "f64" | "f32" => quote! { django_rs_db::fields::FieldType::FloatField },
         "bool" => quote! { django_rs_db::fields::FieldType::BooleanField },
-        "String" if f.max_length.is_some() => {
+        "String" if f.max_length.is_some() || f.choices.is_some() => {
             quote! { django_rs_db::fields::FieldType::CharField }
         }
         "String" => quote! { django_rs_db::fields::FieldType::TextField },
@@ -0,0 +1,131 @@
+//! `#[derive(ValueType)]` implementation.
+//!
+//! Generates a `django_rs_db::value::ValueType` implementation for an
+//! application type, together with the `Value` conversions needed to use it
+//! as a model field, query parameter or `Row::get` target.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+/// Generates the `ValueType` implementation for the given derive input.
+pub fn derive_value_type_impl(input: &DeriveInput) -> TokenStream {
+    let string = match stores_as_string(&input.attrs) {
+        Ok(string) => string,
+        Err(e) => return e.to_compile_error(),
+    };
+    if let syn::Data::Union(_) = input.data {
+        return syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(ValueType)] does not support unions",
+        )
+        .to_compile_error();
+    }
+
+    if string {
+        return string_impl(&input.ident);
+    }
+
+    let field = match &input.data {
+        syn::Data::Struct(data) if data.fields.len() == 1 => data.fields.iter().next(),
+        _ => None,
+    };
+    let Some(field) = field else {
+        return syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(ValueType)] needs a struct with exactly one field, \
+             or #[value(string)] to store the type through Display and FromStr",
+        )
+        .to_compile_error();
+    };
+    newtype_impl(&input.ident, field)
+}
+
+/// Returns whether the type-level attributes include `#[value(string)]`,
+/// which stores the type as text through `Display` and `FromStr` instead of
+/// through its single inner field.
+fn stores_as_string(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut string = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("value")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("string") {
+                string = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported value attribute, expected `string`"))
+            }
+        })?;
+    }
+    Ok(string)
+}
+
+/// Converts through the single inner field, whose type is itself a
+/// `ValueType`.
+fn newtype_impl(name: &syn::Ident, field: &syn::Field) -> TokenStream {
+    let inner = &field.ty;
+    let member = field.ident.clone().map_or_else(
+        || syn::Member::Unnamed(syn::Index::from(0)),
+        syn::Member::Named,
+    );
+
+    quote! {
+        impl From<#name> for django_rs_db::value::Value {
+            fn from(value: #name) -> Self {
+                django_rs_db::value::Value::from(value.#member)
+            }
+        }
+
+        impl django_rs_db::query::compiler::FromValue for #name {
+            fn from_value(
+                value: &django_rs_db::value::Value,
+            ) -> Result<Self, django_rs_core::DjangoError> {
+                <#inner as django_rs_db::query::compiler::FromValue>::from_value(value)
+                    .map(|inner| Self { #member: inner })
+            }
+        }
+
+        impl django_rs_db::value::ValueType for #name {
+            fn field_type() -> django_rs_db::fields::FieldType {
+                <#inner as django_rs_db::value::ValueType>::field_type()
+            }
+        }
+    }
+}
+
+/// Converts through `Display` and `FromStr`, storing the type as text.
+fn string_impl(name: &syn::Ident) -> TokenStream {
+    let name_str = name.to_string();
+
+    quote! {
+        impl From<#name> for django_rs_db::value::Value {
+            fn from(value: #name) -> Self {
+                django_rs_db::value::Value::String(value.to_string())
+            }
+        }
+
+        impl django_rs_db::query::compiler::FromValue for #name {
+            fn from_value(
+                value: &django_rs_db::value::Value,
+            ) -> Result<Self, django_rs_core::DjangoError> {
+                match value {
+                    django_rs_db::value::Value::String(s) => s.parse::<Self>().map_err(|e| {
+                        django_rs_core::DjangoError::DatabaseError(format!(
+                            "'{}' is not a valid {}: {}",
+                            s, #name_str, e
+                        ))
+                    }),
+                    _ => Err(django_rs_core::DjangoError::DatabaseError(format!(
+                        "Expected String, got {:?}",
+                        value
+                    ))),
+                }
+            }
+        }
+
+        impl django_rs_db::value::ValueType for #name {
+            fn field_type() -> django_rs_db::fields::FieldType {
+                django_rs_db::fields::FieldType::TextField
+            }
+        }
+    }
+}
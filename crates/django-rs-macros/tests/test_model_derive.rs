@@ -4,11 +4,11 @@
 //! produces correct metadata, field definitions, value conversions,
 //! and row deserialization.
 
-use django_rs_db::fields::{FieldType, OnDelete};
+use django_rs_db::fields::{Choices, FieldType, OnDelete};
 use django_rs_db::model::Model;
 use django_rs_db::query::compiler::Row;
 use django_rs_db::value::Value;
-use django_rs_macros::Model;
+use django_rs_macros::{Choices, Model, ValueType};
 
 // ── Basic model with all common field types ─────────────────────────────
 
@@ -662,3 +662,508 @@ fn test_default_value() {
     assert!(status.default.is_some());
     assert_eq!(status.default, Some(Value::String("draft".to_string())));
 }
+
+// ── Model in a database schema ──────────────────────────────────────────
+
+#[derive(Model)]
+#[model(table = "invoice", app = "billing", db_schema = "billing")]
+pub struct Invoice {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    #[field(db_index)]
+    pub number: i64,
+}
+
+#[test]
+fn test_db_schema_qualifies_table_name() {
+    assert_eq!(Invoice::db_schema(), Some("billing"));
+    assert_eq!(Invoice::table_name(), "billing.invoice");
+    assert_eq!(Invoice::meta().db_table, "billing.invoice");
+    assert_eq!(Invoice::meta().db_schema.as_deref(), Some("billing"));
+    assert_eq!(
+        Invoice::meta().indexes[0].name.as_deref(),
+        Some("idx_invoice_number")
+    );
+    assert_eq!(Post::db_schema(), None);
+    assert_eq!(Post::meta().db_schema, None);
+}
+
+// ── Model with functional and partial indexes ───────────────────────────
+
+#[derive(Model)]
+#[model(
+    table = "accounts_member",
+    app = "accounts",
+    index(fields = ["team_id", "joined_at"]),
+    index(
+        name = "uniq_member_email_live",
+        expressions = ["LOWER(email)"],
+        condition = "deleted_at IS NULL",
+        unique
+    )
+)]
+pub struct Member {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    pub team_id: i64,
+
+    #[field(max_length = 254)]
+    pub email: String,
+
+    pub joined_at: chrono::NaiveDateTime,
+
+    #[field(null)]
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+}
+
+#[test]
+fn test_model_level_indexes() {
+    let indexes = &Member::meta().indexes;
+    assert_eq!(indexes.len(), 2);
+
+    assert_eq!(
+        indexes[0].name.as_deref(),
+        Some("idx_accounts_member_team_id_joined_at")
+    );
+    assert_eq!(indexes[0].fields, vec!["team_id", "joined_at"]);
+    assert!(!indexes[0].unique);
+
+    assert_eq!(indexes[1].name.as_deref(), Some("uniq_member_email_live"));
+    assert!(indexes[1].fields.is_empty());
+    assert_eq!(indexes[1].expressions, vec!["LOWER(email)"]);
+    assert_eq!(indexes[1].condition.as_deref(), Some("deleted_at IS NULL"));
+    assert!(indexes[1].unique);
+}
+
+// ── Model with choices ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Choices)]
+pub enum TicketStatus {
+    Open,
+    #[choice(label = "Awaiting review")]
+    InReview,
+    #[choice(value = "done")]
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Choices)]
+pub enum TicketPriority {
+    #[choice(value = 1)]
+    Low,
+    #[choice(value = 2)]
+    High,
+}
+
+#[derive(Model)]
+#[model(table = "tickets", app = "support")]
+pub struct Ticket {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    #[field(choices = TicketStatus)]
+    pub status: TicketStatus,
+
+    #[field(choices = TicketPriority)]
+    pub priority: Option<TicketPriority>,
+}
+
+#[test]
+fn test_choices_derive_values_and_labels() {
+    assert_eq!(
+        TicketStatus::choices(),
+        vec![
+            (Value::String("open".to_string()), "Open".to_string()),
+            (
+                Value::String("in_review".to_string()),
+                "Awaiting review".to_string()
+            ),
+            (Value::String("done".to_string()), "Closed".to_string()),
+        ]
+    );
+    assert_eq!(
+        TicketPriority::from_value(&Value::Int(2)),
+        Some(TicketPriority::High)
+    );
+}
+
+#[test]
+fn test_choices_field_def() {
+    let meta = Ticket::meta();
+    let status = meta.fields.iter().find(|f| f.name == "status").unwrap();
+    assert!(matches!(status.field_type, FieldType::CharField));
+    assert_eq!(status.max_length, Some(9));
+    assert_eq!(status.choices.as_ref().unwrap().len(), 3);
+
+    let priority = meta.fields.iter().find(|f| f.name == "priority").unwrap();
+    assert!(matches!(priority.field_type, FieldType::IntegerField));
+    assert!(priority.null);
+}
+
+#[test]
+fn test_choices_validation_rejects_unknown_value() {
+    let status = Ticket::meta()
+        .fields
+        .iter()
+        .find(|f| f.name == "status")
+        .unwrap();
+    assert!(status.validate(&Value::String("open".to_string())).is_ok());
+    assert!(status
+        .validate(&Value::String("archived".to_string()))
+        .is_err());
+}
+
+#[test]
+fn test_choices_get_display() {
+    let ticket = Ticket {
+        id: 1,
+        status: TicketStatus::InReview,
+        priority: Some(TicketPriority::High),
+    };
+    assert_eq!(ticket.get_status_display(), "Awaiting review");
+    assert_eq!(ticket.get_priority_display(), "High");
+
+    let ticket = Ticket {
+        priority: None,
+        ..ticket
+    };
+    assert_eq!(ticket.get_priority_display(), "");
+}
+
+#[test]
+fn test_choices_from_row() {
+    let row = Row::new(
+        vec![
+            "id".to_string(),
+            "status".to_string(),
+            "priority".to_string(),
+        ],
+        vec![
+            Value::Int(1),
+            Value::String("done".to_string()),
+            Value::Null,
+        ],
+    );
+    let ticket = Ticket::from_row(&row).unwrap();
+    assert_eq!(ticket.status, TicketStatus::Closed);
+    assert_eq!(ticket.priority, None);
+
+    let bad = Row::new(
+        vec![
+            "id".to_string(),
+            "status".to_string(),
+            "priority".to_string(),
+        ],
+        vec![
+            Value::Int(1),
+            Value::String("nope".to_string()),
+            Value::Null,
+        ],
+    );
+    assert!(Ticket::from_row(&bad).is_err());
+}
+
+// ── Model with custom value types ───────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueType)]
+pub struct Cents(i64);
+
+#[derive(Debug, Clone, PartialEq, Eq, ValueType)]
+pub struct Sku {
+    code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ValueType)]
+#[value(string)]
+pub struct Dimensions {
+    width: u32,
+    height: u32,
+}
+
+impl std::fmt::Display for Dimensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl std::str::FromStr for Dimensions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s.split_once('x').ok_or("expected WIDTHxHEIGHT")?;
+        Ok(Self {
+            width: width.parse().map_err(|e| format!("{e}"))?,
+            height: height.parse().map_err(|e| format!("{e}"))?,
+        })
+    }
+}
+
+#[derive(Model)]
+#[model(table = "products", app = "shop")]
+pub struct Product {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    #[field(value_type)]
+    pub price: Cents,
+
+    #[field(value_type)]
+    pub sku: Sku,
+
+    #[field(value_type)]
+    pub size: Option<Dimensions>,
+}
+
+#[test]
+fn test_value_type_derive_conversions() {
+    use django_rs_db::query::compiler::FromValue;
+
+    assert_eq!(Value::from(Cents(1999)), Value::Int(1999));
+    assert_eq!(Cents::from_value(&Value::Int(5)).unwrap(), Cents(5));
+    assert!(Cents::from_value(&Value::String("5".to_string())).is_err());
+    assert_eq!(
+        Value::from(Sku {
+            code: "AB-1".to_string()
+        }),
+        Value::String("AB-1".to_string())
+    );
+
+    let size = Dimensions {
+        width: 3,
+        height: 4,
+    };
+    assert_eq!(Value::from(size.clone()), Value::String("3x4".to_string()));
+    assert_eq!(
+        Dimensions::from_value(&Value::String("3x4".to_string())).unwrap(),
+        size
+    );
+    let err = Dimensions::from_value(&Value::String("big".to_string())).unwrap_err();
+    assert!(err.to_string().contains("not a valid Dimensions"));
+}
+
+#[test]
+fn test_value_type_field_def() {
+    let meta = Product::meta();
+    let field = |name: &str| meta.fields.iter().find(|f| f.name == name).unwrap();
+    assert!(matches!(
+        field("price").field_type,
+        FieldType::BigIntegerField
+    ));
+    assert!(matches!(field("sku").field_type, FieldType::TextField));
+    assert!(matches!(field("size").field_type, FieldType::TextField));
+    assert!(field("size").null);
+}
+
+#[test]
+fn test_value_type_model_round_trip() {
+    let product = Product {
+        id: 1,
+        price: Cents(250),
+        sku: Sku {
+            code: "AB-1".to_string(),
+        },
+        size: None,
+    };
+    let values = product.field_values();
+    assert!(values.contains(&("price", Value::Int(250))));
+    assert!(values.contains(&("size", Value::Null)));
+
+    let row = Row::new(
+        vec![
+            "id".to_string(),
+            "price".to_string(),
+            "sku".to_string(),
+            "size".to_string(),
+        ],
+        vec![
+            Value::Int(1),
+            Value::Int(250),
+            Value::String("AB-1".to_string()),
+            Value::String("10x20".to_string()),
+        ],
+    );
+    let loaded = Product::from_row(&row).unwrap();
+    assert_eq!(loaded.price, Cents(250));
+    assert_eq!(loaded.sku.code, "AB-1");
+    assert_eq!(
+        loaded.size,
+        Some(Dimensions {
+            width: 10,
+            height: 20
+        })
+    );
+    assert_eq!(row.get::<Cents>("price").unwrap(), Cents(250));
+}
+
+// ── Timestamped model ───────────────────────────────────────────────────
+
+#[derive(Model)]
+#[model(app = "shop", timestamped)]
+pub struct Order {
+    #[field(primary_key, auto)]
+    pub id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+    #[field(auto_now)]
+    pub touched_on: chrono::NaiveDate,
+}
+
+#[test]
+fn test_timestamped_model_fields_are_automatic() {
+    use django_rs_db::timestamps::TimeStampedModel;
+
+    let meta = Order::meta();
+    let field = |name: &str| meta.fields.iter().find(|f| f.name == name).unwrap();
+    assert!(field("created_at").auto_now_add);
+    assert!(field("updated_at").auto_now);
+    assert!(matches!(
+        field("touched_on").field_type,
+        FieldType::DateField
+    ));
+
+    let start = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let mut order = Order {
+        id: 1,
+        created_at: start,
+        updated_at: None,
+        touched_on: start.date(),
+    };
+    let now = start + chrono::Duration::days(3);
+    order.apply_auto_timestamps(now, false);
+    assert_eq!(order.created_at(), Some(start));
+    assert_eq!(order.updated_at(), Some(now));
+    assert_eq!(order.touched_on, now.date());
+
+    order.apply_auto_timestamps(now, true);
+    assert_eq!(order.created_at(), Some(now));
+}
+
+// ── JSON serialization ──────────────────────────────────────────────────
+
+#[derive(Model)]
+#[model(table = "api_story", app = "api")]
+pub struct Story {
+    #[field(primary_key, auto)]
+    pub id: i64,
+    #[field(max_length = 100)]
+    pub title: String,
+    #[field(editable = false)]
+    pub views: i64,
+    pub published_on: Option<chrono::NaiveDate>,
+    #[field(foreign_key = "auth_user")]
+    pub author_id: i64,
+}
+
+#[test]
+fn test_editable_attribute() {
+    let meta = Story::meta();
+    let field = |name: &str| meta.fields.iter().find(|f| f.name == name).unwrap();
+    assert!(!field("views").editable);
+    assert!(field("title").editable);
+}
+
+#[test]
+fn test_model_json_round_trip() {
+    use django_rs_db::json::Expand;
+
+    let story = Story {
+        id: 4,
+        title: "Hello".to_string(),
+        views: 10,
+        published_on: chrono::NaiveDate::from_ymd_opt(2024, 5, 1),
+        author_id: 2,
+    };
+    let json = story.to_json(None, &Expand::new());
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "id": 4,
+            "title": "Hello",
+            "views": 10,
+            "published_on": "2024-05-01",
+            "author_id": 2,
+        })
+    );
+
+    let copy = Story::from_json(&json).unwrap();
+    assert_eq!(copy.id, 0);
+    assert_eq!(copy.views, 0);
+    assert_eq!(copy.title, "Hello");
+    assert_eq!(copy.published_on, story.published_on);
+    assert_eq!(copy.author_id, 2);
+}
+
+// ── Encrypted fields ────────────────────────────────────────────────────
+
+#[derive(Model)]
+#[model(table = "crm_customer", app = "crm")]
+pub struct Customer {
+    #[field(primary_key, auto)]
+    pub id: i64,
+    #[field(encrypted, max_length = 254, hash_field = "email_hash")]
+    pub email: String,
+    #[field(max_length = 64, editable = false)]
+    pub email_hash: String,
+    #[field(encrypted)]
+    pub notes: Option<String>,
+}
+
+#[test]
+fn test_encrypted_fields() {
+    use django_rs_db::encryption::{self, FieldKeyring};
+
+    let meta = Customer::meta();
+    let field = |name: &str| meta.fields.iter().find(|f| f.name == name).unwrap();
+    assert!(matches!(
+        field("email").field_type,
+        FieldType::EncryptedCharField { hash_field: Some(ref h) } if h == "email_hash"
+    ));
+    assert!(matches!(
+        field("notes").field_type,
+        FieldType::EncryptedTextField { hash_field: None }
+    ));
+
+    encryption::set_keyring(
+        FieldKeyring::new()
+            .with_key("k1", &[7; 32])
+            .unwrap()
+            .with_hash_key("hash-key"),
+    );
+    let customer = Customer {
+        id: 1,
+        email: "ada@example.com".to_string(),
+        email_hash: String::new(),
+        notes: None,
+    };
+    let mut values = customer.non_pk_field_values();
+    encryption::encrypt_values(Customer::table_name(), &meta.fields, &mut values).unwrap();
+    let stored = |name: &str| values.iter().find(|(n, _)| *n == name).unwrap().1.clone();
+    assert_ne!(
+        stored("email"),
+        Value::String("ada@example.com".to_string())
+    );
+    assert_eq!(stored("notes"), Value::Null);
+
+    let row = Row::new(
+        vec![
+            "id".to_string(),
+            "email".to_string(),
+            "email_hash".to_string(),
+            "notes".to_string(),
+        ],
+        vec![
+            Value::Int(1),
+            stored("email"),
+            stored("email_hash"),
+            Value::Null,
+        ],
+    );
+    let loaded = Customer::from_row(&row).unwrap();
+    assert_eq!(loaded.email, "ada@example.com");
+    assert_eq!(loaded.email_hash.len(), 64);
+    assert_eq!(loaded.notes, None);
+}
@@ -2,7 +2,12 @@
 //!
 //! Signal dispatcher for the django-rs framework. Provides a decoupled event system
 //! allowing components to send and receive notifications without direct dependencies.
-//! Supports pre/post save, pre/post delete, request started/finished, and custom signals.
+//! Supports pre/post save, pre/post delete, request started/finished, connection
+//! created, and custom signals, including database notifications ([`DbNotification`]).
+//! Receivers can be ordered by priority, and a cancellable dispatch lets a
+//! receiver veto an operation by returning [`Stop`]. High-frequency signals
+//! can hand their payloads to a background worker through a bounded queue
+//! instead of running receivers inline (see [`queue`]).
 //!
 //! ## Usage
 //!
@@ -29,6 +34,11 @@ use std::sync::{Arc, RwLock};
 
 use once_cell::sync::Lazy;
 
+pub mod queue;
+
+use queue::SignalQueue;
+pub use queue::{Dispatch, OverflowPolicy, QueueConfig, QueueFull, QueueMetrics};
+
 /// The type signature for a signal receiver callback.
 ///
 /// Receivers accept a reference to the signal payload and may optionally
@@ -36,10 +46,66 @@ use once_cell::sync::Lazy;
 /// can be dispatched from any thread.
 pub type SignalReceiver<T> = Arc<dyn Fn(&T) -> Option<Box<dyn Any + Send>> + Send + Sync>;
 
+/// A value a receiver returns to stop a cancellable dispatch.
+///
+/// When a signal is sent with [`Signal::send_cancellable`], a receiver that
+/// returns `Some(Box::new(Stop::new(..)))` (or [`Stop::veto`]) prevents all
+/// later receivers from running. Plain [`Signal::send`] treats it like any
+/// other return value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stop {
+    /// Why the receiver stopped the dispatch.
+    pub reason: String,
+}
+
+impl Stop {
+    /// Creates a stop value with the given reason.
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns a stop value boxed as a receiver return value.
+    pub fn veto(reason: impl Into<String>) -> Option<Box<dyn Any + Send>> {
+        Some(Box::new(Self::new(reason)))
+    }
+}
+
+/// The error returned by [`Signal::send_cancellable`] when a receiver
+/// stopped the dispatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stopped {
+    /// The ID of the receiver that returned [`Stop`].
+    pub receiver_id: String,
+    /// The reason the receiver gave.
+    pub reason: String,
+}
+
+impl std::fmt::Display for Stopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "signal dispatch stopped by '{}': {}",
+            self.receiver_id, self.reason
+        )
+    }
+}
+
+impl std::error::Error for Stopped {}
+
+/// A connected receiver together with its dispatch priority.
+struct ReceiverEntry<T: 'static> {
+    id: String,
+    priority: i32,
+    callback: SignalReceiver<T>,
+}
+
 /// A signal that can be connected to and dispatched.
 ///
-/// Each signal carries a payload type `T`. Receivers are called in the order
-/// they were connected.
+/// Each signal carries a payload type `T`. Receivers are called in order of
+/// descending priority; receivers with the same priority are called in the
+/// order they were connected.
 ///
 /// # Examples
 ///
@@ -57,7 +123,9 @@ pub type SignalReceiver<T> = Arc<dyn Fn(&T) -> Option<Box<dyn Any + Send>> + Sen
 /// signal.send(&"hello".to_string());
 /// ```
 pub struct Signal<T: 'static> {
-    receivers: RwLock<Vec<(String, SignalReceiver<T>)>>,
+    receivers: Arc<RwLock<Vec<ReceiverEntry<T>>>>,
+    /// Set when payloads are dispatched through a background worker.
+    queue: RwLock<Option<SignalQueue<T>>>,
 }
 
 impl<T: 'static> Default for Signal<T> {
@@ -67,27 +135,83 @@ impl<T: 'static> Default for Signal<T> {
 }
 
 impl<T: 'static> Signal<T> {
+    /// The priority used by [`connect`](Self::connect).
+    pub const DEFAULT_PRIORITY: i32 = 0;
+
     /// Creates a new signal with no connected receivers.
     pub fn new() -> Self {
         Self {
-            receivers: RwLock::new(Vec::new()),
+            receivers: Arc::new(RwLock::new(Vec::new())),
+            queue: RwLock::new(None),
         }
     }
 
-    /// Connects a receiver to this signal.
+    /// Connects a receiver to this signal with the default priority.
     ///
     /// The `receiver_id` is used to identify the receiver for later disconnection.
     /// If a receiver with the same ID is already connected, it is replaced.
     pub fn connect(&self, receiver_id: impl Into<String>, callback: SignalReceiver<T>) {
+        self.connect_with_priority(receiver_id, Self::DEFAULT_PRIORITY, callback);
+    }
+
+    /// Connects a receiver that runs before all receivers with a lower
+    /// priority.
+    ///
+    /// Receivers with equal priority run in connection order. Replacing a
+    /// receiver with the same priority keeps its position; replacing it with
+    /// a different priority moves it.
+    ///
+    /// ```
+    /// use django_rs_signals::Signal;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let signal: Signal<()> = Signal::new();
+    /// let order = Arc::new(Mutex::new(Vec::new()));
+    ///
+    /// let o = order.clone();
+    /// signal.connect("cache", Arc::new(move |(): &()| {
+    ///     o.lock().unwrap().push("cache");
+    ///     None
+    /// }));
+    /// let o = order.clone();
+    /// signal.connect_with_priority("audit", 10, Arc::new(move |(): &()| {
+    ///     o.lock().unwrap().push("audit");
+    ///     None
+    /// }));
+    ///
+    /// signal.send(&());
+    /// assert_eq!(*order.lock().unwrap(), vec!["audit", "cache"]);
+    /// ```
+    pub fn connect_with_priority(
+        &self,
+        receiver_id: impl Into<String>,
+        priority: i32,
+        callback: SignalReceiver<T>,
+    ) {
         let id = receiver_id.into();
         let mut receivers = self.receivers.write().expect("signal lock poisoned");
 
         // Replace if already connected with this ID
-        if let Some(entry) = receivers.iter_mut().find(|(rid, _)| *rid == id) {
-            entry.1 = callback;
-        } else {
-            receivers.push((id, callback));
+        if let Some(pos) = receivers.iter().position(|entry| entry.id == id) {
+            if receivers[pos].priority == priority {
+                receivers[pos].callback = callback;
+                return;
+            }
+            receivers.remove(pos);
         }
+
+        let pos = receivers
+            .iter()
+            .position(|entry| entry.priority < priority)
+            .unwrap_or(receivers.len());
+        receivers.insert(
+            pos,
+            ReceiverEntry {
+                id,
+                priority,
+                callback,
+            },
+        );
     }
 
     /// Disconnects the receiver with the given ID.
@@ -96,28 +220,173 @@ impl<T: 'static> Signal<T> {
     pub fn disconnect(&self, receiver_id: &str) -> bool {
         let mut receivers = self.receivers.write().expect("signal lock poisoned");
         let len_before = receivers.len();
-        receivers.retain(|(id, _)| id != receiver_id);
+        receivers.retain(|entry| entry.id != receiver_id);
         receivers.len() < len_before
     }
 
     /// Sends the signal to all connected receivers.
     ///
-    /// Receivers are called in connection order. Returns a vector of the
-    /// return values from each receiver.
+    /// Receivers are called in priority order. Returns a vector of the
+    /// return values from each receiver. The receivers run inline even when
+    /// the signal is queued; use [`dispatch`](Self::dispatch) to honour the
+    /// queue.
     pub fn send(&self, sender: &T) -> Vec<Option<Box<dyn Any + Send>>> {
         let receivers = self.receivers.read().expect("signal lock poisoned");
         receivers
             .iter()
-            .map(|(_, callback)| callback(sender))
+            .map(|entry| (entry.callback)(sender))
             .collect()
     }
 
+    /// Sends the signal, stopping at the first receiver that returns [`Stop`].
+    ///
+    /// Returns the return values of all receivers if none stopped the
+    /// dispatch. Otherwise no later receiver runs and the [`Stopped`] error
+    /// names the receiver that stopped it.
+    ///
+    /// This is the veto pattern for `pre_save`: connect validation receivers
+    /// with a high priority, and have the code that saves abort when the
+    /// dispatch is stopped.
+    ///
+    /// ```
+    /// use django_rs_signals::{PreSave, Signal, Stop};
+    /// use std::sync::Arc;
+    ///
+    /// let pre_save: Signal<PreSave> = Signal::new();
+    /// pre_save.connect_with_priority("read_only", 100, Arc::new(|_: &PreSave| {
+    ///     Stop::veto("the database is in read-only mode")
+    /// }));
+    /// pre_save.connect("slugify", Arc::new(|_: &PreSave| {
+    ///     unreachable!("vetoed saves are never slugified")
+    /// }));
+    ///
+    /// let stopped = pre_save.send_cancellable(&PreSave).unwrap_err();
+    /// assert_eq!(stopped.receiver_id, "read_only");
+    /// assert_eq!(stopped.reason, "the database is in read-only mode");
+    /// ```
+    pub fn send_cancellable(
+        &self,
+        sender: &T,
+    ) -> Result<Vec<Option<Box<dyn Any + Send>>>, Stopped> {
+        let receivers = self.receivers.read().expect("signal lock poisoned");
+        let mut results = Vec::with_capacity(receivers.len());
+        for entry in receivers.iter() {
+            let result = (entry.callback)(sender);
+            if let Some(stop) = result.as_ref().and_then(|r| r.downcast_ref::<Stop>()) {
+                return Err(Stopped {
+                    receiver_id: entry.id.clone(),
+                    reason: stop.reason.clone(),
+                });
+            }
+            results.push(result);
+        }
+        drop(receivers);
+        Ok(results)
+    }
+
     /// Returns the number of connected receivers.
     pub fn receiver_count(&self) -> usize {
         self.receivers.read().expect("signal lock poisoned").len()
     }
 }
 
+impl<T: Send + 'static> Signal<T> {
+    /// Switches the signal to queued dispatch: payloads passed to
+    /// [`dispatch`](Self::dispatch) are queued for a background worker
+    /// thread, which runs the receivers.
+    ///
+    /// Replacing an existing queue starts a new one with fresh metrics; the
+    /// old worker finishes the payloads it holds and exits.
+    pub fn enable_queue(&self, config: QueueConfig) {
+        let queue = SignalQueue::start(config, Arc::clone(&self.receivers));
+        *self.queue.write().expect("signal lock poisoned") = Some(queue);
+    }
+
+    /// Switches the signal back to inline dispatch. Payloads already queued
+    /// are still delivered.
+    ///
+    /// Returns `true` if the signal was queued.
+    pub fn disable_queue(&self) -> bool {
+        self.queue
+            .write()
+            .expect("signal lock poisoned")
+            .take()
+            .is_some()
+    }
+
+    /// Returns `true` if the signal dispatches through a queue.
+    pub fn is_queued(&self) -> bool {
+        self.queue.read().expect("signal lock poisoned").is_some()
+    }
+
+    fn current_queue(&self) -> Option<SignalQueue<T>> {
+        self.queue.read().expect("signal lock poisoned").clone()
+    }
+
+    /// Dispatches a payload the way the signal is configured: queued for
+    /// the worker if [`enable_queue`](Self::enable_queue) was called,
+    /// otherwise sent to the receivers straight away.
+    ///
+    /// Never waits. If the queue is full the payload is dropped, or, under
+    /// [`OverflowPolicy::TryAgain`], returned in the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueFull`] with the payload if the queue is full and its
+    /// policy is [`OverflowPolicy::TryAgain`].
+    pub fn dispatch(&self, payload: T) -> Result<Dispatch, QueueFull<T>> {
+        if let Some(queue) = self.current_queue() {
+            queue.try_send(payload)
+        } else {
+            self.send(&payload);
+            Ok(Dispatch::Inline)
+        }
+    }
+
+    /// Dispatches a payload like [`dispatch`](Self::dispatch), but waits
+    /// for space when the queue is full, whatever its overflow policy, so a
+    /// backlog slows the caller down instead of losing events.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueFull`] with the payload if the queue was disabled
+    /// while waiting.
+    pub async fn dispatch_wait(&self, payload: T) -> Result<Dispatch, QueueFull<T>> {
+        if let Some(queue) = self.current_queue() {
+            queue.send(payload).await
+        } else {
+            self.send(&payload);
+            Ok(Dispatch::Inline)
+        }
+    }
+
+    /// Blocks until every payload queued so far has been delivered.
+    ///
+    /// Returns immediately if the signal is not queued. Useful at shutdown
+    /// and in tests; in async code, use [`flush_async`](Self::flush_async).
+    pub fn flush(&self) {
+        if let Some(queue) = self.current_queue() {
+            queue.wait_drained();
+        }
+    }
+
+    /// Waits until every payload queued so far has been delivered, without
+    /// blocking the runtime's thread.
+    ///
+    /// Returns immediately if the signal is not queued.
+    pub async fn flush_async(&self) {
+        if let Some(queue) = self.current_queue() {
+            queue.drained().await;
+        }
+    }
+
+    /// Returns the queue's depth and counters, or `None` if the signal is
+    /// not queued.
+    pub fn queue_metrics(&self) -> Option<QueueMetrics> {
+        self.current_queue().map(|queue| queue.metrics())
+    }
+}
+
 // ── Pre-defined signal types ─────────────────────────────────────────
 
 /// Signal sent before a model instance is saved.
@@ -144,6 +413,47 @@ pub struct RequestStarted;
 /// Signal sent when an HTTP request finishes processing.
 pub struct RequestFinished;
 
+/// Signal sent after a database backend opens a new connection.
+///
+/// Fired once the connection's session setup (time zone, `search_path`,
+/// `sql_mode`, init commands) has been applied.
+#[derive(Debug, Clone)]
+pub struct ConnectionCreated {
+    /// The backend vendor, e.g. `"postgresql"` or `"sqlite"`.
+    pub vendor: String,
+    /// The database name or file path.
+    pub database: String,
+}
+
+/// Payload of a notification pushed by the database, such as a Postgres
+/// `NOTIFY`.
+///
+/// Listeners dispatch it through the custom signal named after its channel:
+///
+/// ```
+/// use django_rs_signals::{DbNotification, SIGNALS};
+/// use std::sync::Arc;
+///
+/// SIGNALS.get_or_create_custom("cache_invalidate").connect(
+///     "clear_cache",
+///     Arc::new(|payload| {
+///         if let Some(notification) = payload.downcast_ref::<DbNotification>() {
+///             println!("Invalidate {}", notification.payload);
+///         }
+///         None
+///     }),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct DbNotification {
+    /// The channel the notification was sent on.
+    pub channel: String,
+    /// The notification payload; empty if none was given.
+    pub payload: String,
+    /// The process ID of the database session that sent it.
+    pub process_id: i32,
+}
+
 // ── Global signal registry ───────────────────────────────────────────
 
 /// A type-erased signal that can carry any payload.
@@ -172,6 +482,8 @@ pub struct SignalRegistry {
     pub request_started: Signal<RequestStarted>,
     /// Fired when a request finishes.
     pub request_finished: Signal<RequestFinished>,
+    /// Fired when a database connection is created.
+    pub connection_created: Signal<ConnectionCreated>,
     /// Custom named signals.
     custom: CustomSignalMap,
 }
@@ -188,6 +500,7 @@ impl SignalRegistry {
             post_init: Signal::new(),
             request_started: Signal::new(),
             request_finished: Signal::new(),
+            connection_created: Signal::new(),
             custom: RwLock::new(HashMap::new()),
         }
     }
@@ -386,4 +699,264 @@ mod tests {
         let signal: Signal<i32> = Signal::default();
         assert_eq!(signal.receiver_count(), 0);
     }
+
+    fn recorder(
+        order: &Arc<std::sync::Mutex<Vec<&'static str>>>,
+        name: &'static str,
+    ) -> SignalReceiver<()> {
+        let order = order.clone();
+        Arc::new(move |(): &()| {
+            order.lock().unwrap().push(name);
+            None
+        })
+    }
+
+    #[test]
+    fn test_signal_priority_order() {
+        let signal: Signal<()> = Signal::new();
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        signal.connect("default_a", recorder(&order, "default_a"));
+        signal.connect_with_priority("low", -5, recorder(&order, "low"));
+        signal.connect_with_priority("high", 10, recorder(&order, "high"));
+        signal.connect("default_b", recorder(&order, "default_b"));
+
+        signal.send(&());
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["high", "default_a", "default_b", "low"]
+        );
+    }
+
+    #[test]
+    fn test_signal_replace_with_new_priority_moves_receiver() {
+        let signal: Signal<()> = Signal::new();
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        signal.connect("a", recorder(&order, "a"));
+        signal.connect("b", recorder(&order, "b"));
+        signal.connect("a", recorder(&order, "a"));
+        signal.send(&());
+        assert_eq!(*order.lock().unwrap(), vec!["a", "b"]);
+
+        order.lock().unwrap().clear();
+        signal.connect_with_priority("b", 1, recorder(&order, "b"));
+        signal.send(&());
+        assert_eq!(signal.receiver_count(), 2);
+        assert_eq!(*order.lock().unwrap(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_send_cancellable_stops_later_receivers() {
+        let signal: Signal<()> = Signal::new();
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        signal.connect_with_priority("first", 1, recorder(&order, "first"));
+        signal.connect("veto", Arc::new(|(): &()| Stop::veto("not allowed")));
+        signal.connect("after", recorder(&order, "after"));
+
+        let stopped = signal.send_cancellable(&()).unwrap_err();
+        assert_eq!(stopped.receiver_id, "veto");
+        assert_eq!(stopped.reason, "not allowed");
+        assert_eq!(
+            stopped.to_string(),
+            "signal dispatch stopped by 'veto': not allowed"
+        );
+        assert_eq!(*order.lock().unwrap(), vec!["first"]);
+    }
+
+    #[test]
+    fn test_send_cancellable_without_stop() {
+        let signal: Signal<i32> = Signal::new();
+        signal.connect(
+            "doubler",
+            Arc::new(|val: &i32| Some(Box::new(val * 2) as Box<dyn Any + Send>)),
+        );
+        signal.connect("none", Arc::new(|_: &i32| None));
+
+        let results = signal.send_cancellable(&21).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].as_ref().unwrap().downcast_ref::<i32>(),
+            Some(&42)
+        );
+    }
+
+    /// Connects a receiver that reports each payload it starts on, then
+    /// blocks until the test releases it.
+    fn gated(
+        signal: &Signal<u32>,
+    ) -> (std::sync::mpsc::Receiver<u32>, std::sync::mpsc::Sender<()>) {
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let release_rx = std::sync::Mutex::new(release_rx);
+        signal.connect(
+            "gate",
+            Arc::new(move |n: &u32| {
+                started_tx.send(*n).unwrap();
+                release_rx.lock().unwrap().recv().unwrap();
+                None
+            }),
+        );
+        (started_rx, release_tx)
+    }
+
+    #[test]
+    fn test_dispatch_is_inline_until_queued() {
+        let signal: Signal<u32> = Signal::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let c = count.clone();
+        signal.connect(
+            "count",
+            Arc::new(move |_: &u32| {
+                c.fetch_add(1, Ordering::SeqCst);
+                None
+            }),
+        );
+
+        assert_eq!(signal.dispatch(1).unwrap(), Dispatch::Inline);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert!(signal.queue_metrics().is_none());
+
+        signal.enable_queue(QueueConfig::new(8));
+        assert!(signal.is_queued());
+        for n in 0..5 {
+            assert_eq!(signal.dispatch(n).unwrap(), Dispatch::Queued);
+        }
+        signal.flush();
+        assert_eq!(count.load(Ordering::SeqCst), 6);
+        let metrics = signal.queue_metrics().unwrap();
+        assert_eq!(
+            (metrics.enqueued, metrics.processed, metrics.depth),
+            (5, 5, 0)
+        );
+        assert_eq!(metrics.capacity, 8);
+
+        assert!(signal.disable_queue());
+        assert_eq!(signal.dispatch(1).unwrap(), Dispatch::Inline);
+        assert_eq!(count.load(Ordering::SeqCst), 7);
+    }
+
+    #[test]
+    fn test_full_queue_drops_payloads() {
+        let signal: Signal<u32> = Signal::new();
+        let (started, release) = gated(&signal);
+        signal.enable_queue(QueueConfig::new(1));
+
+        assert_eq!(signal.dispatch(1).unwrap(), Dispatch::Queued);
+        assert_eq!(started.recv().unwrap(), 1);
+        assert_eq!(signal.dispatch(2).unwrap(), Dispatch::Queued);
+        assert_eq!(signal.dispatch(3).unwrap(), Dispatch::Dropped);
+
+        let metrics = signal.queue_metrics().unwrap();
+        assert_eq!(
+            (metrics.depth, metrics.peak_depth, metrics.dropped),
+            (1, 1, 1)
+        );
+
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+        signal.flush();
+        assert_eq!(started.try_iter().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(signal.queue_metrics().unwrap().processed, 2);
+    }
+
+    #[test]
+    fn test_full_queue_hands_payload_back() {
+        let signal: Signal<u32> = Signal::new();
+        let (started, release) = gated(&signal);
+        signal.enable_queue(QueueConfig::new(1).overflow(OverflowPolicy::TryAgain));
+
+        signal.dispatch(1).unwrap();
+        started.recv().unwrap();
+        signal.dispatch(2).unwrap();
+        let full = signal.dispatch(3).unwrap_err();
+        assert_eq!(full.into_inner(), 3);
+        assert_eq!(signal.queue_metrics().unwrap().rejected, 1);
+
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+        signal.flush();
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_wait_waits_for_space() {
+        let signal: Arc<Signal<u32>> = Arc::new(Signal::new());
+        let (started, release) = gated(&signal);
+        signal.enable_queue(QueueConfig::new(1));
+
+        signal.dispatch(1).unwrap();
+        started.recv().unwrap();
+        signal.dispatch(2).unwrap();
+
+        let waiting = tokio::spawn({
+            let signal = signal.clone();
+            async move { signal.dispatch_wait(3).await.unwrap() }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        release.send(()).unwrap();
+        assert_eq!(waiting.await.unwrap(), Dispatch::Queued);
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+        signal.flush_async().await;
+        assert_eq!(signal.queue_metrics().unwrap().dropped, 0);
+        assert_eq!(signal.queue_metrics().unwrap().processed, 3);
+    }
+
+    #[test]
+    fn test_queue_worker_survives_panicking_receiver() {
+        let signal: Signal<u32> = Signal::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let c = count.clone();
+        signal.connect_with_priority(
+            "flaky",
+            10,
+            Arc::new(move |n: &u32| {
+                assert!(*n != 1, "receiver failed");
+                c.fetch_add(1, Ordering::SeqCst);
+                None
+            }),
+        );
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let s = seen.clone();
+        signal.connect(
+            "audit",
+            Arc::new(move |n: &u32| {
+                s.lock().unwrap().push(*n);
+                None
+            }),
+        );
+        signal.enable_queue(QueueConfig::default());
+
+        signal.dispatch(1).unwrap();
+        signal.dispatch(2).unwrap();
+        signal.flush();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        // The receiver after the one that panicked still saw both payloads.
+        assert_eq!(*seen.lock().unwrap(), vec![1, 2]);
+        assert_eq!(signal.queue_metrics().unwrap().panicked, 1);
+        assert_eq!(signal.receiver_count(), 2);
+    }
+
+    #[test]
+    fn test_plain_send_ignores_stop() {
+        let signal: Signal<()> = Signal::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let c = count.clone();
+
+        signal.connect("veto", Arc::new(|(): &()| Stop::veto("ignored")));
+        signal.connect(
+            "after",
+            Arc::new(move |(): &()| {
+                c.fetch_add(1, Ordering::SeqCst);
+                None
+            }),
+        );
+
+        let results = signal.send(&());
+        assert_eq!(results.len(), 2);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,350 @@
+//! Queued signal dispatch.
+//!
+//! [`Signal::send`](crate::Signal::send) runs every receiver before it
+//! returns, so a slow receiver on a high-frequency signal such as
+//! `request_finished` adds its latency to every request. A signal can
+//! instead be switched to queued dispatch with
+//! [`Signal::enable_queue`](crate::Signal::enable_queue): payloads sent with
+//! [`Signal::dispatch`](crate::Signal::dispatch) are pushed onto a bounded
+//! channel and a background worker runs the receivers.
+//!
+//! The channel's capacity bounds the memory held by a backlog. When it is
+//! full, the signal's [`OverflowPolicy`] decides what happens: the payload is
+//! dropped, or handed back so the caller can try again later. Async callers
+//! can wait for space instead with
+//! [`Signal::dispatch_wait`](crate::Signal::dispatch_wait). [`QueueMetrics`]
+//! report the queue depth and how many payloads were processed, dropped and
+//! rejected.
+//!
+//! Queued receivers run on the worker thread, after `dispatch` returns, and
+//! their return values are discarded, so a queued signal cannot be vetoed.
+//! [`Signal::send`](crate::Signal::send) and the other `send` methods
+//! always run the receivers inline; the framework fires its own signals
+//! with `dispatch`, so they honour the queue.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_signals::queue::{OverflowPolicy, QueueConfig};
+//! use django_rs_signals::{Dispatch, Signal};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//!
+//! let signal: Signal<u64> = Signal::new();
+//! let total = Arc::new(AtomicUsize::new(0));
+//! let t = total.clone();
+//! signal.connect("sum", Arc::new(move |n: &u64| {
+//!     t.fetch_add(*n as usize, Ordering::SeqCst);
+//!     None
+//! }));
+//!
+//! signal.enable_queue(QueueConfig::new(1024).overflow(OverflowPolicy::Drop));
+//! assert!(matches!(signal.dispatch(5), Ok(Dispatch::Queued)));
+//!
+//! signal.flush();
+//! assert_eq!(total.load(Ordering::SeqCst), 5);
+//! assert_eq!(signal.queue_metrics().unwrap().processed, 1);
+//! ```
+
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+
+use tokio::sync::{mpsc, Notify};
+
+use crate::{ReceiverEntry, SignalReceiver};
+
+/// What [`Signal::dispatch`](crate::Signal::dispatch) does when the queue is
+/// full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the payload and count it in [`QueueMetrics::dropped`].
+    ///
+    /// Suits events that are only useful while fresh, such as metrics.
+    #[default]
+    Drop,
+    /// Return the payload in a [`QueueFull`] error, so the caller can try
+    /// again later or handle it inline, and count it in
+    /// [`QueueMetrics::rejected`].
+    TryAgain,
+}
+
+/// How a signal's dispatch queue is set up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// The most payloads waiting for the worker at once.
+    pub capacity: usize,
+    /// What happens to a payload sent while the queue is full.
+    pub overflow: OverflowPolicy,
+}
+
+impl QueueConfig {
+    /// Creates a config for a queue holding up to `capacity` payloads,
+    /// dropping payloads sent while it is full.
+    ///
+    /// A capacity of zero is raised to one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            overflow: OverflowPolicy::default(),
+        }
+    }
+
+    /// Sets the overflow policy.
+    #[must_use]
+    pub const fn overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+/// What happened to a payload passed to
+/// [`Signal::dispatch`](crate::Signal::dispatch).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dispatch {
+    /// The signal is not queued; the receivers already ran.
+    Inline,
+    /// The payload is waiting for the worker.
+    Queued,
+    /// The queue was full and the payload was discarded.
+    Dropped,
+}
+
+/// The error returned when a payload is sent to a full queue whose policy
+/// is [`OverflowPolicy::TryAgain`]. Holds the payload.
+pub struct QueueFull<T>(pub T);
+
+impl<T> QueueFull<T> {
+    /// Returns the payload that could not be queued.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::fmt::Debug for QueueFull<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("QueueFull(..)")
+    }
+}
+
+impl<T> std::fmt::Display for QueueFull<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("signal queue is full")
+    }
+}
+
+impl<T> std::error::Error for QueueFull<T> {}
+
+/// A snapshot of a signal queue's counters.
+///
+/// Counters start at zero when the queue is enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueMetrics {
+    /// The queue's capacity.
+    pub capacity: usize,
+    /// How many payloads are waiting for the worker.
+    pub depth: usize,
+    /// The largest depth seen.
+    pub peak_depth: usize,
+    /// How many payloads were queued.
+    pub enqueued: u64,
+    /// How many queued payloads the receivers have run for.
+    pub processed: u64,
+    /// How many payloads were discarded because the queue was full.
+    pub dropped: u64,
+    /// How many payloads were handed back because the queue was full.
+    pub rejected: u64,
+    /// How many payloads had a receiver panic. The other receivers still
+    /// run for that payload, and the worker carries on with the next one.
+    pub panicked: u64,
+}
+
+/// Counters shared by a queue's senders and its worker.
+#[derive(Debug, Default)]
+struct QueueStats {
+    peak_depth: AtomicUsize,
+    enqueued: AtomicU64,
+    processed: Mutex<u64>,
+    drained: Condvar,
+    drained_async: Notify,
+    dropped: AtomicU64,
+    rejected: AtomicU64,
+    panicked: AtomicU64,
+}
+
+/// The sending half of a signal's queue, and its counters.
+///
+/// Clones share the queue, so a signal can copy it out of its lock before
+/// waiting on it.
+pub(crate) struct SignalQueue<T> {
+    sender: mpsc::Sender<T>,
+    overflow: OverflowPolicy,
+    stats: Arc<QueueStats>,
+}
+
+impl<T> Clone for SignalQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            overflow: self.overflow,
+            stats: Arc::clone(&self.stats),
+        }
+    }
+}
+
+impl<T: Send + 'static> SignalQueue<T> {
+    /// Creates a queue and starts the worker thread running `receivers` for
+    /// each payload. The worker exits once every clone of the queue is
+    /// dropped and the payloads already queued have been processed.
+    pub(crate) fn start(
+        config: QueueConfig,
+        receivers: Arc<RwLock<Vec<ReceiverEntry<T>>>>,
+    ) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<T>(config.capacity);
+        let stats = Arc::new(QueueStats::default());
+        let worker_stats = Arc::clone(&stats);
+        std::thread::Builder::new()
+            .name("signal-queue".to_string())
+            .spawn(move || {
+                while let Some(payload) = receiver.blocking_recv() {
+                    // Receivers run outside the lock, so one that panics
+                    // doesn't poison it.
+                    let callbacks: Vec<SignalReceiver<T>> = receivers
+                        .read()
+                        .expect("signal lock poisoned")
+                        .iter()
+                        .map(|entry| Arc::clone(&entry.callback))
+                        .collect();
+                    // Each receiver is isolated, so one that panics doesn't
+                    // keep the others from seeing the payload.
+                    let mut panicked = false;
+                    for callback in &callbacks {
+                        let result =
+                            std::panic::catch_unwind(AssertUnwindSafe(|| callback(&payload)));
+                        panicked |= result.is_err();
+                    }
+                    worker_stats.record_processed(panicked);
+                }
+            })
+            .expect("failed to spawn signal queue worker");
+        Self {
+            sender,
+            overflow: config.overflow,
+            stats,
+        }
+    }
+
+    /// Queues a payload without waiting, applying the overflow policy if
+    /// the queue is full.
+    pub(crate) fn try_send(&self, payload: T) -> Result<Dispatch, QueueFull<T>> {
+        match self.sender.try_send(payload) {
+            Ok(()) => {
+                self.record_enqueued();
+                Ok(Dispatch::Queued)
+            }
+            Err(mpsc::error::TrySendError::Full(payload)) => match self.overflow {
+                OverflowPolicy::Drop => {
+                    self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    Ok(Dispatch::Dropped)
+                }
+                OverflowPolicy::TryAgain => {
+                    self.stats.rejected.fetch_add(1, Ordering::Relaxed);
+                    Err(QueueFull(payload))
+                }
+            },
+            // The worker only stops once the queue is dropped.
+            Err(mpsc::error::TrySendError::Closed(payload)) => Err(QueueFull(payload)),
+        }
+    }
+
+    /// Queues a payload, waiting for space if the queue is full.
+    pub(crate) async fn send(&self, payload: T) -> Result<Dispatch, QueueFull<T>> {
+        match self.sender.send(payload).await {
+            Ok(()) => {
+                self.record_enqueued();
+                Ok(Dispatch::Queued)
+            }
+            Err(mpsc::error::SendError(payload)) => Err(QueueFull(payload)),
+        }
+    }
+
+    fn record_enqueued(&self) {
+        self.stats.enqueued.fetch_add(1, Ordering::SeqCst);
+        self.stats
+            .peak_depth
+            .fetch_max(self.depth(), Ordering::Relaxed);
+    }
+
+    /// Returns the number of payloads waiting for the worker.
+    fn depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Blocks until every payload queued so far has been processed.
+    pub(crate) fn wait_drained(&self) {
+        let target = self.stats.enqueued.load(Ordering::SeqCst);
+        let processed = self
+            .stats
+            .processed
+            .lock()
+            .expect("signal queue lock poisoned");
+        drop(
+            self.stats
+                .drained
+                .wait_while(processed, |processed| *processed < target)
+                .expect("signal queue lock poisoned"),
+        );
+    }
+
+    /// Waits until every payload queued so far has been processed, without
+    /// blocking the thread.
+    pub(crate) async fn drained(&self) {
+        let target = self.stats.enqueued.load(Ordering::SeqCst);
+        loop {
+            // Registered before checking, so a notification sent in between
+            // isn't missed.
+            let notified = self.stats.drained_async.notified();
+            if *self
+                .stats
+                .processed
+                .lock()
+                .expect("signal queue lock poisoned")
+                >= target
+            {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> QueueMetrics {
+        let stats = &self.stats;
+        QueueMetrics {
+            capacity: self.sender.max_capacity(),
+            depth: self.depth(),
+            peak_depth: stats.peak_depth.load(Ordering::Relaxed),
+            enqueued: stats.enqueued.load(Ordering::SeqCst),
+            processed: *stats.processed.lock().expect("signal queue lock poisoned"),
+            dropped: stats.dropped.load(Ordering::Relaxed),
+            rejected: stats.rejected.load(Ordering::Relaxed),
+            panicked: stats.panicked.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl QueueStats {
+    fn record_processed(&self, panicked: bool) {
+        if panicked {
+            self.panicked.fetch_add(1, Ordering::Relaxed);
+        }
+        *self.processed.lock().expect("signal queue lock poisoned") += 1;
+        self.drained.notify_all();
+        self.drained_async.notify_waiters();
+    }
+}
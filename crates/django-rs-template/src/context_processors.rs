@@ -3,13 +3,132 @@
 //! Context processors add variables to the template context automatically
 //! based on the current request. They mirror Django's context processors
 //! such as `django.template.context_processors.debug`.
+//!
+//! Processors installed on an [`Engine`](crate::engine::Engine) run whenever
+//! a template is rendered for a request with
+//! [`Engine::render_for_request`](crate::engine::Engine::render_for_request).
+//! [`from_settings`] builds the list named in the `context_processors`
+//! option of the first `TEMPLATES` entry:
+//!
+//! | Name | Adds |
+//! |------|------|
+//! | `django_rs.template.context_processors.debug` | `debug`, `sql_queries` (only with `DEBUG`) |
+//! | `django_rs.template.context_processors.request` | `request` |
+//! | `django_rs.auth.context_processors.auth` | `user` |
+//! | `django_rs.messages.context_processors.messages` | `messages`, `DEFAULT_MESSAGE_LEVELS` |
+//! | `django_rs.template.context_processors.csrf` | `csrf_token` (always installed) |
+//! | `django_rs.template.context_processors.i18n` | `LANGUAGE_CODE`, `LANGUAGE_BIDI`, `LANGUAGES` |
+//! | `django_rs.template.context_processors.static` | `STATIC_URL` |
+//! | `django_rs.template.context_processors.media` | `MEDIA_URL` |
+//!
+//! Django's own dotted paths (e.g. `django.contrib.auth.context_processors.auth`)
+//! are accepted too. Crates that keep the request state a processor needs can
+//! supply their own implementation of a name with [`from_settings_with`]; the
+//! view layer does so for `auth` and `messages`, reading its request
+//! extensions.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use django_rs_core::error::DjangoError;
+use django_rs_core::settings::Settings;
 use django_rs_http::HttpRequest;
 
 use crate::context::ContextValue;
 
+/// The `context_processors` installed when the `TEMPLATES` options do not
+/// list any.
+pub const DEFAULT_CONTEXT_PROCESSORS: &[&str] = &[
+    "django_rs.template.context_processors.debug",
+    "django_rs.template.context_processors.request",
+    "django_rs.auth.context_processors.auth",
+    "django_rs.messages.context_processors.messages",
+    "django_rs.template.context_processors.static",
+    "django_rs.template.context_processors.media",
+];
+
+/// Builds the context processors configured in `settings`.
+///
+/// Reads the `context_processors` option of the first `TEMPLATES` entry,
+/// falling back to [`DEFAULT_CONTEXT_PROCESSORS`]. The CSRF processor is
+/// always included, as in Django, so that `{% csrf_token %}` works.
+///
+/// # Errors
+///
+/// Returns `ImproperlyConfigured` if the option is not a list of strings or
+/// names an unknown processor.
+pub fn from_settings(settings: &Settings) -> Result<Vec<Arc<dyn ContextProcessor>>, DjangoError> {
+    from_settings_with(settings, &[])
+}
+
+/// Builds the context processors configured in `settings` like
+/// [`from_settings`], using the processors in `overrides` for the short
+/// names they are paired with (e.g. `"auth"`).
+///
+/// # Errors
+///
+/// Returns `ImproperlyConfigured` if the option is not a list of strings or
+/// names an unknown processor.
+pub fn from_settings_with(
+    settings: &Settings,
+    overrides: &[(&str, Arc<dyn ContextProcessor>)],
+) -> Result<Vec<Arc<dyn ContextProcessor>>, DjangoError> {
+    let names: Vec<String> = match settings
+        .templates
+        .first()
+        .and_then(|t| t.options.get("context_processors"))
+    {
+        Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+            DjangoError::ImproperlyConfigured(
+                "TEMPLATES option 'context_processors' must be a list of strings".to_string(),
+            )
+        })?,
+        None => DEFAULT_CONTEXT_PROCESSORS
+            .iter()
+            .map(|name| (*name).to_string())
+            .collect(),
+    };
+
+    let mut processors: Vec<Arc<dyn ContextProcessor>> = Vec::new();
+    let mut has_csrf = false;
+    for name in &names {
+        let short = name
+            .rsplit_once(".context_processors.")
+            .map_or(name.as_str(), |(_, short)| short);
+        if let Some((_, processor)) = overrides.iter().find(|(n, _)| *n == short) {
+            processors.push(Arc::clone(processor));
+            continue;
+        }
+        let processor: Arc<dyn ContextProcessor> = match short {
+            "debug" if settings.debug => Arc::new(DebugContextProcessor),
+            "debug" => continue,
+            "request" => Arc::new(RequestContextProcessor),
+            "auth" => Arc::new(AuthContextProcessor),
+            "messages" => Arc::new(MessagesContextProcessor),
+            "csrf" => {
+                has_csrf = true;
+                Arc::new(CsrfContextProcessor)
+            }
+            "i18n" => Arc::new(
+                I18nContextProcessor::new(settings.language_code.clone())
+                    .languages(settings.languages.clone()),
+            ),
+            "static" => Arc::new(StaticContextProcessor::new(settings.static_url.clone())),
+            "media" => Arc::new(MediaContextProcessor::new(settings.media_url.clone())),
+            _ => {
+                return Err(DjangoError::ImproperlyConfigured(format!(
+                    "Unknown context processor '{name}'"
+                )))
+            }
+        };
+        processors.push(processor);
+    }
+    if !has_csrf {
+        processors.push(Arc::new(CsrfContextProcessor));
+    }
+    Ok(processors)
+}
+
 /// A context processor that adds variables to every template context.
 ///
 /// Implementations inspect the request and return a map of variable names
@@ -97,20 +216,32 @@ impl ContextProcessor for MediaContextProcessor {
     }
 }
 
-/// Adds `csrf_token` to the context.
+/// Adds `csrf_token` to the context, for `{% csrf_token %}`.
 ///
-/// In a real application this would generate a cryptographically secure token.
-/// This implementation uses a placeholder for demonstration.
+/// Uses the masked token the CSRF middleware stored in `META["CSRF_TOKEN"]`,
+/// then its secret in `META["CSRF_COOKIE"]`, then the `csrftoken` cookie. Without either, e.g. when the middleware is not
+/// installed, a random token is generated.
 pub struct CsrfContextProcessor;
 
 impl ContextProcessor for CsrfContextProcessor {
-    fn process(&self, _request: &HttpRequest) -> HashMap<String, ContextValue> {
-        use rand::Rng;
-        let token: String = rand::thread_rng()
-            .sample_iter(&rand::distributions::Alphanumeric)
-            .take(64)
-            .map(char::from)
-            .collect();
+    fn process(&self, request: &HttpRequest) -> HashMap<String, ContextValue> {
+        let meta = request.meta();
+        let token = meta
+            .get("CSRF_TOKEN")
+            .or_else(|| meta.get("CSRF_COOKIE"))
+            .map(String::as_str)
+            .or_else(|| request.cookie("csrftoken"))
+            .map_or_else(
+                || {
+                    use rand::Rng;
+                    rand::thread_rng()
+                        .sample_iter(&rand::distributions::Alphanumeric)
+                        .take(64)
+                        .map(char::from)
+                        .collect()
+                },
+                str::to_string,
+            );
 
         let mut ctx = HashMap::new();
         ctx.insert("csrf_token".to_string(), ContextValue::String(token));
@@ -145,6 +276,197 @@ impl ContextProcessor for RequestContextProcessor {
     }
 }
 
+/// Adds `user` to the context.
+///
+/// `user` is a dict with `is_authenticated`, `is_anonymous` and, for a
+/// logged-in user, `id` and `pk`, read from `META["USER_ID"]` and
+/// `META["USER_AUTHENTICATED"]` as set by the authentication middleware.
+pub struct AuthContextProcessor;
+
+impl ContextProcessor for AuthContextProcessor {
+    fn process(&self, request: &HttpRequest) -> HashMap<String, ContextValue> {
+        let meta = request.meta();
+        let user_id = meta
+            .get("USER_ID")
+            .filter(|_| meta.get("USER_AUTHENTICATED").is_some_and(|v| v == "true"));
+        user_context(user_id.map(String::as_str))
+    }
+}
+
+/// Builds the `user` variable of [`AuthContextProcessor`] for the id of the
+/// logged-in user, or `None` for an anonymous request.
+pub fn user_context(user_id: Option<&str>) -> HashMap<String, ContextValue> {
+    let mut user = HashMap::new();
+    user.insert(
+        "is_authenticated".to_string(),
+        ContextValue::Bool(user_id.is_some()),
+    );
+    user.insert(
+        "is_anonymous".to_string(),
+        ContextValue::Bool(user_id.is_none()),
+    );
+    if let Some(id) = user_id {
+        user.insert("id".to_string(), ContextValue::String(id.to_string()));
+        user.insert("pk".to_string(), ContextValue::String(id.to_string()));
+    }
+
+    let mut ctx = HashMap::new();
+    ctx.insert("user".to_string(), ContextValue::Dict(user));
+    ctx
+}
+
+/// The numeric message levels, by name.
+const MESSAGE_LEVELS: [(&str, i64); 5] = [
+    ("debug", 10),
+    ("info", 20),
+    ("success", 25),
+    ("warning", 30),
+    ("error", 40),
+];
+
+/// Adds `messages` and `DEFAULT_MESSAGE_LEVELS` to the context.
+///
+/// `messages` lists the flash messages stored in the session (key
+/// `_messages` of `META["SESSION_DATA"]`), including those added during the
+/// request. Each is a dict with `message`, `level`, `level_tag`,
+/// `extra_tags` and `tags`, where `tags` joins the extra tags and the level
+/// tag, as in Django.
+pub struct MessagesContextProcessor;
+
+impl ContextProcessor for MessagesContextProcessor {
+    fn process(&self, request: &HttpRequest) -> HashMap<String, ContextValue> {
+        let stored: Vec<serde_json::Value> = request
+            .meta()
+            .get("SESSION_DATA")
+            .and_then(|data| serde_json::from_str::<serde_json::Value>(data).ok())
+            .and_then(|data| serde_json::from_value(data.get("_messages")?.clone()).ok())
+            .unwrap_or_default();
+
+        messages_context(stored.iter().map(|message| {
+            let field = |name: &str| message.get(name).and_then(|v| v.as_str());
+            (
+                field("message").unwrap_or(""),
+                field("level").unwrap_or("info"),
+                field("extra_tags").unwrap_or(""),
+            )
+        }))
+    }
+}
+
+/// Builds the `messages` and `DEFAULT_MESSAGE_LEVELS` variables of
+/// [`MessagesContextProcessor`] from `(message, level, extra_tags)` triples,
+/// where `level` is a level name such as `"info"`.
+pub fn messages_context<'a>(
+    messages: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+) -> HashMap<String, ContextValue> {
+    let messages = messages
+        .into_iter()
+        .map(|(text, level, extra_tags)| {
+            let level_tag = level.to_lowercase();
+            let level = MESSAGE_LEVELS
+                .iter()
+                .find(|(name, _)| *name == level_tag)
+                .map_or(0, |(_, level)| *level);
+            let tags = if extra_tags.is_empty() {
+                level_tag.clone()
+            } else {
+                format!("{extra_tags} {level_tag}")
+            };
+
+            let mut dict = HashMap::new();
+            dict.insert(
+                "message".to_string(),
+                ContextValue::String(text.to_string()),
+            );
+            dict.insert("level".to_string(), ContextValue::Integer(level));
+            dict.insert("level_tag".to_string(), ContextValue::String(level_tag));
+            dict.insert(
+                "extra_tags".to_string(),
+                ContextValue::String(extra_tags.to_string()),
+            );
+            dict.insert("tags".to_string(), ContextValue::String(tags));
+            ContextValue::Dict(dict)
+        })
+        .collect();
+
+    let levels = MESSAGE_LEVELS
+        .iter()
+        .map(|(name, level)| (name.to_uppercase(), ContextValue::Integer(*level)))
+        .collect();
+
+    let mut ctx = HashMap::new();
+    ctx.insert("messages".to_string(), ContextValue::List(messages));
+    ctx.insert(
+        "DEFAULT_MESSAGE_LEVELS".to_string(),
+        ContextValue::Dict(levels),
+    );
+    ctx
+}
+
+/// Adds `LANGUAGE_CODE`, `LANGUAGE_BIDI` and `LANGUAGES` to the context.
+///
+/// The language is the one the locale middleware detected
+/// (`META["LANGUAGE_CODE"]`), or the configured default. `LANGUAGES` lists
+/// the available languages as `[code, name]` pairs, so a language switcher
+/// can loop over them with `{% for code, name in LANGUAGES %}`.
+pub struct I18nContextProcessor {
+    /// The language used when the request has none.
+    pub default_language: String,
+    /// The available languages, as `(code, name)` pairs.
+    pub languages: Vec<(String, String)>,
+}
+
+impl I18nContextProcessor {
+    /// Creates a new `I18nContextProcessor` with the given default language.
+    pub fn new(default_language: impl Into<String>) -> Self {
+        Self {
+            default_language: default_language.into(),
+            languages: Vec::new(),
+        }
+    }
+
+    /// Sets the available languages, as `(code, name)` pairs.
+    #[must_use]
+    pub fn languages(mut self, languages: Vec<(String, String)>) -> Self {
+        self.languages = languages;
+        self
+    }
+}
+
+impl Default for I18nContextProcessor {
+    fn default() -> Self {
+        Self::new("en-us")
+    }
+}
+
+impl ContextProcessor for I18nContextProcessor {
+    fn process(&self, request: &HttpRequest) -> HashMap<String, ContextValue> {
+        let language = request
+            .meta()
+            .get("LANGUAGE_CODE")
+            .unwrap_or(&self.default_language)
+            .clone();
+        let base = language.split(['-', '_']).next().unwrap_or_default();
+        let bidi = matches!(base, "ar" | "fa" | "he" | "ur" | "ckb" | "ug");
+
+        let mut ctx = HashMap::new();
+        ctx.insert("LANGUAGE_CODE".to_string(), ContextValue::String(language));
+        ctx.insert("LANGUAGE_BIDI".to_string(), ContextValue::Bool(bidi));
+        let languages = self
+            .languages
+            .iter()
+            .map(|(code, name)| {
+                ContextValue::List(vec![
+                    ContextValue::String(code.clone()),
+                    ContextValue::String(name.clone()),
+                ])
+            })
+            .collect();
+        ctx.insert("LANGUAGES".to_string(), ContextValue::List(languages));
+        ctx
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,4 +551,179 @@ mod tests {
         let ctx = cp.process(&make_request());
         assert_eq!(ctx.get("MEDIA_URL").unwrap().to_display_string(), "/media/");
     }
+
+    #[test]
+    fn test_csrf_context_processor_uses_middleware_token() {
+        let request = HttpRequest::builder()
+            .meta("CSRF_TOKEN", "masked")
+            .meta("CSRF_COOKIE", "from-middleware")
+            .build();
+        let ctx = CsrfContextProcessor.process(&request);
+        assert_eq!(ctx["csrf_token"].to_display_string(), "masked");
+
+        let request = HttpRequest::builder()
+            .meta("CSRF_COOKIE", "from-middleware")
+            .header("cookie", "csrftoken=from-cookie")
+            .build();
+        let ctx = CsrfContextProcessor.process(&request);
+        assert_eq!(ctx["csrf_token"].to_display_string(), "from-middleware");
+
+        let request = HttpRequest::builder()
+            .header("cookie", "csrftoken=from-cookie")
+            .build();
+        let ctx = CsrfContextProcessor.process(&request);
+        assert_eq!(ctx["csrf_token"].to_display_string(), "from-cookie");
+    }
+
+    #[test]
+    fn test_auth_context_processor() {
+        let ctx = AuthContextProcessor.process(&make_request());
+        let user = ctx.get("user").unwrap();
+        assert!(!user.resolve_path("is_authenticated").unwrap().is_truthy());
+        assert!(user.resolve_path("is_anonymous").unwrap().is_truthy());
+        assert!(user.resolve_path("id").is_none());
+
+        let request = HttpRequest::builder()
+            .meta("USER_ID", "42")
+            .meta("USER_AUTHENTICATED", "true")
+            .build();
+        let ctx = AuthContextProcessor.process(&request);
+        let user = ctx.get("user").unwrap();
+        assert!(user.resolve_path("is_authenticated").unwrap().is_truthy());
+        assert_eq!(user.resolve_path("pk").unwrap().to_display_string(), "42");
+    }
+
+    #[test]
+    fn test_messages_context_processor() {
+        let session = serde_json::json!({"_messages": [
+            {"level": "Success", "message": "Saved", "extra_tags": ""},
+            {"level": "Error", "message": "Oops", "extra_tags": "sticky"},
+        ]});
+        let request = HttpRequest::builder()
+            .meta("SESSION_DATA", &session.to_string())
+            .build();
+        let ctx = MessagesContextProcessor.process(&request);
+
+        let Some(ContextValue::List(messages)) = ctx.get("messages") else {
+            panic!("Expected messages list in context");
+        };
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0]
+                .resolve_path("message")
+                .unwrap()
+                .to_display_string(),
+            "Saved"
+        );
+        assert_eq!(
+            messages[0].resolve_path("level").unwrap().as_integer(),
+            Some(25)
+        );
+        assert_eq!(
+            messages[1]
+                .resolve_path("tags")
+                .unwrap()
+                .to_display_string(),
+            "sticky error"
+        );
+        assert_eq!(
+            ctx["DEFAULT_MESSAGE_LEVELS"]
+                .resolve_path("ERROR")
+                .unwrap()
+                .as_integer(),
+            Some(40)
+        );
+
+        let ctx = MessagesContextProcessor.process(&make_request());
+        assert_eq!(ctx["messages"].len(), Some(0));
+    }
+
+    #[test]
+    fn test_i18n_context_processor() {
+        let cp = I18nContextProcessor::default();
+        let ctx = cp.process(&make_request());
+        assert_eq!(ctx["LANGUAGE_CODE"].to_display_string(), "en-us");
+        assert!(!ctx["LANGUAGE_BIDI"].is_truthy());
+
+        let request = HttpRequest::builder().meta("LANGUAGE_CODE", "he").build();
+        let ctx = cp.process(&request);
+        assert_eq!(ctx["LANGUAGE_CODE"].to_display_string(), "he");
+        assert!(ctx["LANGUAGE_BIDI"].is_truthy());
+        assert_eq!(ctx["LANGUAGES"].len(), Some(0));
+
+        let cp = cp.languages(vec![
+            ("en".to_string(), "English".to_string()),
+            ("fr".to_string(), "French".to_string()),
+        ]);
+        let ctx = cp.process(&request);
+        let ContextValue::List(languages) = &ctx["LANGUAGES"] else {
+            panic!("LANGUAGES is not a list");
+        };
+        assert!(matches!(
+            &languages[1],
+            ContextValue::List(pair) if pair[0].to_display_string() == "fr"
+                && pair[1].to_display_string() == "French"
+        ));
+    }
+
+    fn processor_keys(settings: &Settings) -> Vec<String> {
+        let request = make_request();
+        let mut keys: Vec<String> = from_settings(settings)
+            .unwrap()
+            .iter()
+            .flat_map(|p| p.process(&request).into_keys())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_from_settings_defaults() {
+        let settings = Settings {
+            debug: false,
+            ..Settings::default()
+        };
+        assert_eq!(
+            processor_keys(&settings),
+            vec![
+                "DEFAULT_MESSAGE_LEVELS",
+                "MEDIA_URL",
+                "STATIC_URL",
+                "csrf_token",
+                "messages",
+                "request",
+                "user"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_settings_option_list() {
+        let mut settings = Settings::default();
+        settings.templates[0].options.insert(
+            "context_processors".to_string(),
+            serde_json::json!([
+                "django.template.context_processors.i18n",
+                "django_rs.template.context_processors.debug",
+            ]),
+        );
+        assert_eq!(
+            processor_keys(&settings),
+            vec![
+                "LANGUAGES",
+                "LANGUAGE_BIDI",
+                "LANGUAGE_CODE",
+                "csrf_token",
+                "debug",
+                "sql_queries"
+            ]
+        );
+
+        settings.templates[0].options.insert(
+            "context_processors".to_string(),
+            serde_json::json!(["myapp.context_processors.cart"]),
+        );
+        let err = from_settings(&settings).err().unwrap();
+        assert!(err.to_string().contains("myapp.context_processors.cart"));
+    }
 }
@@ -6,19 +6,71 @@
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use django_rs_core::error::DjangoError;
 
 use crate::context::Context;
-use crate::lexer;
+use crate::context_processors::{self, ContextProcessor};
+use crate::lexer::{self, LexerOptions};
 use crate::loaders::{FileSystemLoader, StringLoader, TemplateLoader};
 use crate::parser::{self, Node, Template};
+use crate::sandbox::{Sandbox, SandboxedRenderer};
+use crate::staticfiles::{StaticFilesStorage, StaticStorage};
+use crate::thumbnails::{ThumbnailBackend, ThumbnailSpec};
 
 /// A trait for rendering templates, used to break circular dependencies
 /// between the parser/renderer and the engine.
 pub trait TemplateRenderer: Send + Sync {
     /// Renders a named template with the given context.
     fn render_template(&self, name: &str, context: &mut Context) -> Result<String, DjangoError>;
+
+    /// Resolves a `{% static %}` path to a URL.
+    ///
+    /// Returns `None` when no static configuration is set, in which case
+    /// the tag falls back to `STATIC_URL` from the context.
+    fn static_url(&self, _path: &str) -> Option<Result<String, DjangoError>> {
+        None
+    }
+
+    /// Returns the configured `STATIC_URL`, if any.
+    fn static_prefix(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns the configured `MEDIA_URL`, if any.
+    fn media_prefix(&self) -> Option<String> {
+        None
+    }
+
+    /// Resolves a `{% thumbnail %}` variant to a URL.
+    ///
+    /// Returns `None` when no thumbnail backend is set, in which case the tag
+    /// falls back to the variant's name under `MEDIA_URL`.
+    fn thumbnail_url(
+        &self,
+        _name: &str,
+        _spec: &ThumbnailSpec,
+    ) -> Option<Result<String, DjangoError>> {
+        None
+    }
+
+    /// Called before each iteration of a `{% for %}` loop.
+    ///
+    /// Returning an error stops rendering; sandboxed engines use this to
+    /// bound loops.
+    fn count_loop_iteration(&self) -> Result<(), DjangoError> {
+        Ok(())
+    }
+
+    /// Called with the length of each piece of text or variable output as it
+    /// is rendered, including output a loop or a tag later discards.
+    ///
+    /// Returning an error stops rendering; sandboxed engines add the lengths
+    /// up to bound the output.
+    fn check_output(&self, _len: usize) -> Result<(), DjangoError> {
+        Ok(())
+    }
 }
 
 /// The template engine. Manages loaders, caches, and rendering.
@@ -47,8 +99,20 @@ pub struct Engine {
     auto_escape: bool,
     /// Whether debug mode is enabled.
     debug: bool,
+    /// Whitespace handling applied when tokenizing templates.
+    lexer_options: LexerOptions,
     /// An in-memory string loader for programmatically added templates.
     string_loader: StringLoader,
+    /// Storage resolving `{% static %}` paths, if configured.
+    static_storage: Option<Arc<dyn StaticFilesStorage>>,
+    /// URL prefix for `{% media %}` paths, if configured.
+    media_url: Option<String>,
+    /// Backend resolving `{% thumbnail %}` variants, if configured.
+    thumbnail_backend: Option<Arc<dyn ThumbnailBackend>>,
+    /// Processors run by `render_for_request`.
+    context_processors: Vec<Arc<dyn ContextProcessor>>,
+    /// Restrictions for untrusted templates, if any.
+    sandbox: Option<Sandbox>,
 }
 
 impl Engine {
@@ -59,7 +123,13 @@ impl Engine {
             loaders: Vec::new(),
             auto_escape: true,
             debug: false,
+            lexer_options: LexerOptions::default(),
             string_loader: StringLoader::new(),
+            static_storage: None,
+            media_url: None,
+            thumbnail_backend: None,
+            context_processors: Vec::new(),
+            sandbox: None,
         }
     }
 
@@ -74,6 +144,12 @@ impl Engine {
         if let Some(auto_escape) = settings.options.get("auto_escape") {
             engine.auto_escape = auto_escape.as_bool().unwrap_or(true);
         }
+        if let Some(trim_blocks) = settings.options.get("trim_blocks") {
+            engine.lexer_options.trim_blocks = trim_blocks.as_bool().unwrap_or(false);
+        }
+        if let Some(lstrip_blocks) = settings.options.get("lstrip_blocks") {
+            engine.lexer_options.lstrip_blocks = lstrip_blocks.as_bool().unwrap_or(false);
+        }
 
         engine
     }
@@ -96,11 +172,110 @@ impl Engine {
         self.auto_escape = enabled;
     }
 
+    /// Sets whether the first newline after each block tag or comment is
+    /// removed. Off by default, as in Django.
+    pub fn set_trim_blocks(&mut self, enabled: bool) {
+        self.lexer_options.trim_blocks = enabled;
+    }
+
+    /// Sets whether spaces and tabs before a block tag or comment at the start
+    /// of a line are removed. Off by default, as in Django.
+    pub fn set_lstrip_blocks(&mut self, enabled: bool) {
+        self.lexer_options.lstrip_blocks = enabled;
+    }
+
     /// Sets whether debug mode is enabled.
     pub fn set_debug(&mut self, enabled: bool) {
         self.debug = enabled;
     }
 
+    /// Sets `STATIC_URL`, serving static files unhashed from `url`.
+    pub fn set_static_url(&mut self, url: impl Into<String>) {
+        self.static_storage = Some(Arc::new(StaticStorage::new(url)));
+    }
+
+    /// Sets the storage used to resolve `{% static %}` paths, such as a
+    /// [`ManifestStaticStorage`](crate::staticfiles::ManifestStaticStorage)
+    /// for hashed file names.
+    pub fn set_static_storage(&mut self, storage: Arc<dyn StaticFilesStorage>) {
+        self.static_storage = Some(storage);
+    }
+
+    /// Sets `MEDIA_URL` for `{% media %}` and `{% get_media_prefix %}`.
+    pub fn set_media_url(&mut self, url: impl Into<String>) {
+        self.media_url = Some(url.into());
+    }
+
+    /// Sets the backend that resolves and generates `{% thumbnail %}` variants.
+    pub fn set_thumbnail_backend(&mut self, backend: Arc<dyn ThumbnailBackend>) {
+        self.thumbnail_backend = Some(backend);
+    }
+
+    /// Configures `STATIC_URL` and `MEDIA_URL` from the project settings.
+    ///
+    /// An already configured static storage is kept, so a manifest storage
+    /// set before this call stays active.
+    pub fn configure_static(&mut self, settings: &django_rs_core::settings::Settings) {
+        if self.static_storage.is_none() {
+            self.set_static_url(settings.static_url.clone());
+        }
+        self.set_media_url(settings.media_url.clone());
+    }
+
+    /// Restricts the templates this engine renders; see
+    /// [`sandbox`](crate::sandbox).
+    pub fn set_sandbox(&mut self, sandbox: Sandbox) {
+        self.sandbox = Some(sandbox);
+    }
+
+    /// Returns the sandbox restricting this engine, if any.
+    pub fn sandbox(&self) -> Option<&Sandbox> {
+        self.sandbox.as_ref()
+    }
+
+    /// Adds a context processor run by
+    /// [`render_for_request`](Self::render_for_request).
+    pub fn add_context_processor(&mut self, processor: Arc<dyn ContextProcessor>) {
+        self.context_processors.push(processor);
+    }
+
+    /// Returns `true` if any context processor is installed.
+    pub fn has_context_processors(&self) -> bool {
+        !self.context_processors.is_empty()
+    }
+
+    /// Replaces the context processors with those configured in the project
+    /// settings; see [`context_processors::from_settings`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ImproperlyConfigured` for an unknown processor name; the
+    /// installed processors are then left unchanged.
+    pub fn configure_context_processors(
+        &mut self,
+        settings: &django_rs_core::settings::Settings,
+    ) -> Result<(), DjangoError> {
+        self.configure_context_processors_with(settings, &[])
+    }
+
+    /// Replaces the context processors like
+    /// [`configure_context_processors`](Self::configure_context_processors),
+    /// using the processors in `overrides` for the names they are paired
+    /// with; see [`context_processors::from_settings_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ImproperlyConfigured` for an unknown processor name; the
+    /// installed processors are then left unchanged.
+    pub fn configure_context_processors_with(
+        &mut self,
+        settings: &django_rs_core::settings::Settings,
+        overrides: &[(&str, Arc<dyn ContextProcessor>)],
+    ) -> Result<(), DjangoError> {
+        self.context_processors = context_processors::from_settings_with(settings, overrides)?;
+        Ok(())
+    }
+
     /// Adds an in-memory template.
     pub fn add_string_template(&self, name: &str, source: &str) {
         self.string_loader.add(name, source);
@@ -125,10 +300,33 @@ impl Engine {
         )))
     }
 
+    /// Returns the names of all templates the engine's loaders can find,
+    /// sorted and without duplicates.
+    pub fn template_names(&self) -> Vec<String> {
+        let mut names = self.string_loader.template_names();
+        for loader in &self.loaders {
+            names.extend(loader.template_names());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Loads and tokenizes a template by name.
+    pub(crate) fn tokenize_template(&self, name: &str) -> Result<Vec<lexer::Token>, DjangoError> {
+        let source = self.load_source(name)?;
+        lexer::tokenize_with(&source, &self.lexer_options)
+    }
+
     /// Loads and parses a template by name.
+    ///
+    /// A sandboxed engine refuses templates using tags or filters its
+    /// sandbox does not allow, unless they are whitelisted.
     pub fn get_template(&self, name: &str) -> Result<Template, DjangoError> {
-        let source = self.load_source(name)?;
-        let tokens = lexer::tokenize(&source)?;
+        let tokens = self.tokenize_template(name)?;
+        if let Some(sandbox) = self.sandbox.as_ref().filter(|s| !s.allows_template(name)) {
+            sandbox.check_tokens(name, &tokens)?;
+        }
         parser::parse(name, &tokens)
     }
 
@@ -138,23 +336,79 @@ impl Engine {
         name: &str,
         context: &mut Context,
     ) -> Result<String, DjangoError> {
+        let _span = tracing::info_span!("template.render", template = name).entered();
         context.set_auto_escape(self.auto_escape);
         let template = self.get_template(name)?;
-        self.render_template_obj(&template, context)
+        match &self.sandbox {
+            Some(sandbox) => {
+                let renderer = SandboxedRenderer::new(self, sandbox);
+                let output = self.render_template_obj(&template, context, &renderer)?;
+                renderer.check_size(output.len())?;
+                Ok(output)
+            }
+            None => self.render_template_obj(&template, context, self),
+        }
     }
 
-    /// Renders a parsed template with the given context.
-    fn render_template_obj(
+    /// Renders a template by name for a request.
+    ///
+    /// The variables of the installed context processors are added first;
+    /// variables already in `context` win over them.
+    ///
+    /// ```
+    /// use django_rs_http::HttpRequest;
+    /// use django_rs_template::context::Context;
+    /// use django_rs_template::context_processors::RequestContextProcessor;
+    /// use django_rs_template::engine::Engine;
+    /// use std::sync::Arc;
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.add_context_processor(Arc::new(RequestContextProcessor));
+    /// engine.add_string_template("path.html", "{{ request.path }}");
+    ///
+    /// let request = HttpRequest::builder().path("/about/").build();
+    /// let html = engine
+    ///     .render_for_request("path.html", &mut Context::new(), &request)
+    ///     .unwrap();
+    /// assert_eq!(html, "/about/");
+    /// ```
+    pub fn render_for_request(
+        &self,
+        name: &str,
+        context: &mut Context,
+        request: &django_rs_http::HttpRequest,
+    ) -> Result<String, DjangoError> {
+        for processor in &self.context_processors {
+            for (key, value) in processor.process(request) {
+                if context.get(&key).is_none() {
+                    context.set(key, value);
+                }
+            }
+        }
+        self.render_to_string(name, context)
+    }
+
+    /// Renders a parsed template with the given context, through `renderer`.
+    pub(crate) fn render_template_obj(
         &self,
         template: &Template,
         context: &mut Context,
+        renderer: &dyn TemplateRenderer,
     ) -> Result<String, DjangoError> {
         if let Some(ref parent_name) = template.parent {
             // Template inheritance
-            self.render_with_inheritance(template, parent_name, context)
+            self.render_with_inheritance(template, parent_name, context, renderer)
         } else {
-            parser::render_nodes(&template.nodes, context, self)
+            parser::render_nodes(&template.nodes, context, renderer)
+        }
+    }
+
+    /// Loads the parent of a template, if the sandbox allows it.
+    fn get_parent_template(&self, parent_name: &str) -> Result<Template, DjangoError> {
+        if let Some(sandbox) = &self.sandbox {
+            sandbox.check_template(parent_name)?;
         }
+        self.get_template(parent_name)
     }
 
     /// Renders a template with inheritance (extends).
@@ -163,21 +417,22 @@ impl Engine {
         child: &Template,
         parent_name: &str,
         context: &mut Context,
+        renderer: &dyn TemplateRenderer,
     ) -> Result<String, DjangoError> {
         // Collect block definitions from the child
         let child_blocks = collect_blocks(&child.nodes);
 
         // Load and parse the parent
-        let parent = self.get_template(parent_name)?;
+        let parent = self.get_parent_template(parent_name)?;
 
         if let Some(ref grandparent_name) = parent.parent {
             // Multi-level inheritance: merge blocks and recurse
             let parent_blocks = collect_blocks(&parent.nodes);
             let merged = merge_blocks(parent_blocks, child_blocks);
-            self.render_inherited_template(&parent, grandparent_name, &merged, context)
+            self.render_inherited_template(&parent, grandparent_name, &merged, context, renderer)
         } else {
             // Direct parent: render parent with child's block overrides
-            self.render_parent_with_blocks(&parent.nodes, &child_blocks, context)
+            Self::render_parent_with_blocks(&parent.nodes, &child_blocks, context, renderer)
         }
     }
 
@@ -188,24 +443,25 @@ impl Engine {
         parent_name: &str,
         blocks: &HashMap<String, Vec<&Node>>,
         context: &mut Context,
+        renderer: &dyn TemplateRenderer,
     ) -> Result<String, DjangoError> {
-        let parent = self.get_template(parent_name)?;
+        let parent = self.get_parent_template(parent_name)?;
 
         if let Some(ref grandparent_name) = parent.parent {
             let parent_blocks = collect_blocks(&parent.nodes);
             let merged = merge_block_refs(parent_blocks, blocks);
-            self.render_inherited_template(&parent, grandparent_name, &merged, context)
+            self.render_inherited_template(&parent, grandparent_name, &merged, context, renderer)
         } else {
-            self.render_parent_with_blocks(&parent.nodes, blocks, context)
+            Self::render_parent_with_blocks(&parent.nodes, blocks, context, renderer)
         }
     }
 
     /// Renders parent nodes, replacing block definitions with child overrides.
     fn render_parent_with_blocks(
-        &self,
         parent_nodes: &[Node],
         child_blocks: &HashMap<String, Vec<&Node>>,
         context: &mut Context,
+        renderer: &dyn TemplateRenderer,
     ) -> Result<String, DjangoError> {
         let mut output = String::new();
 
@@ -214,7 +470,7 @@ impl Engine {
                 Node::BlockDefNode { name, content } => {
                     if let Some(child_content) = child_blocks.get(name) {
                         // Check if child content uses block.super
-                        let parent_rendered = parser::render_nodes(content, context, self)?;
+                        let parent_rendered = parser::render_nodes(content, context, renderer)?;
                         context.push();
                         context.set(
                             "block",
@@ -228,19 +484,19 @@ impl Engine {
                             }),
                         );
                         for child_node in child_content {
-                            output.push_str(&render_single_node(child_node, context, self)?);
+                            output.push_str(&render_single_node(child_node, context, renderer)?);
                         }
                         context.pop();
                     } else {
                         // No override — render parent's default content
-                        output.push_str(&parser::render_nodes(content, context, self)?);
+                        output.push_str(&parser::render_nodes(content, context, renderer)?);
                     }
                 }
                 Node::ExtendsNode { .. } => {
                     // Skip extends nodes in rendering
                 }
                 _ => {
-                    output.push_str(&render_single_node(node, context, self)?);
+                    output.push_str(&render_single_node(node, context, renderer)?);
                 }
             }
         }
@@ -257,8 +513,35 @@ impl Default for Engine {
 
 impl TemplateRenderer for Engine {
     fn render_template(&self, name: &str, context: &mut Context) -> Result<String, DjangoError> {
+        let _span = tracing::info_span!("template.render", template = name).entered();
         let template = self.get_template(name)?;
-        self.render_template_obj(&template, context)
+        self.render_template_obj(&template, context, self)
+    }
+
+    fn static_url(&self, path: &str) -> Option<Result<String, DjangoError>> {
+        self.static_storage
+            .as_ref()
+            .map(|storage| storage.url(path))
+    }
+
+    fn static_prefix(&self) -> Option<String> {
+        self.static_storage
+            .as_ref()
+            .map(|storage| storage.base_url().to_string())
+    }
+
+    fn media_prefix(&self) -> Option<String> {
+        self.media_url.clone()
+    }
+
+    fn thumbnail_url(
+        &self,
+        name: &str,
+        spec: &ThumbnailSpec,
+    ) -> Option<Result<String, DjangoError>> {
+        self.thumbnail_backend
+            .as_ref()
+            .map(|backend| backend.thumbnail_url(name, spec))
     }
 }
 
@@ -691,6 +974,11 @@ mod tests {
         let result = engine.render_to_string("test.html", &mut ctx).unwrap();
         assert!(result.contains("abc123"));
         assert!(result.contains("csrfmiddlewaretoken"));
+
+        // Without a token the tag renders nothing
+        let mut ctx = Context::new();
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert_eq!(result, "");
     }
 
     #[test]
@@ -749,6 +1037,89 @@ mod tests {
         assert_eq!(result, "/static/css/style.css");
     }
 
+    #[test]
+    fn test_engine_static_tag_with_manifest_storage() {
+        let mut engine = Engine::new();
+        engine.set_static_storage(Arc::new(
+            crate::staticfiles::ManifestStaticStorage::new("/assets/")
+                .with_entry("css/site.css", "css/site.1a2b3c.css"),
+        ));
+        engine.add_string_template(
+            "test.html",
+            r#"{% static "css/site.css" %}|{% static "css/site.css" as u %}{{ u }}|{% get_static_prefix %}"#,
+        );
+        engine.add_string_template("missing.html", r#"{% static "js/app.js" %}"#);
+
+        let mut ctx = Context::new();
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert_eq!(
+            result,
+            "/assets/css/site.1a2b3c.css|/assets/css/site.1a2b3c.css|/assets/"
+        );
+        assert!(engine.render_to_string("missing.html", &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_engine_thumbnail_tag() {
+        struct Thumbs;
+        impl ThumbnailBackend for Thumbs {
+            fn thumbnail_url(
+                &self,
+                name: &str,
+                spec: &ThumbnailSpec,
+            ) -> Result<String, DjangoError> {
+                Ok(format!(
+                    "https://img.example.com/{}",
+                    crate::thumbnails::variant_name(name, spec)
+                ))
+            }
+        }
+
+        let mut engine = Engine::new();
+        engine.set_media_url("/media/");
+        engine.add_string_template(
+            "test.html",
+            r#"<img src="{% thumbnail photo "200x100" crop %}">{% thumbnail photo "64x64" as t %}[{{ t }}]"#,
+        );
+        engine.add_string_template("bad.html", r#"{% thumbnail photo "big" %}"#);
+        let mut ctx = Context::new();
+        ctx.set("photo", ContextValue::from("photos/cat.jpg"));
+
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert_eq!(
+            result,
+            r#"<img src="/media/photos/cat.200x100_crop.jpg">[/media/photos/cat.64x64.jpg]"#
+        );
+        assert!(engine.render_to_string("bad.html", &mut ctx).is_err());
+
+        engine.set_thumbnail_backend(Arc::new(Thumbs));
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert!(result
+            .starts_with(r#"<img src="https://img.example.com/photos/cat.200x100_crop.jpg">"#));
+    }
+
+    #[test]
+    fn test_engine_configure_static_from_settings() {
+        let settings = django_rs_core::settings::Settings {
+            static_url: "https://cdn.example.com/static/".to_string(),
+            media_url: "/uploads/".to_string(),
+            ..Default::default()
+        };
+        let mut engine = Engine::new();
+        engine.configure_static(&settings);
+        engine.add_string_template(
+            "test.html",
+            r#"{% static "a.js" %} {% media "avatars/me.png" %} {% get_media_prefix as m %}{{ m }}"#,
+        );
+
+        let mut ctx = Context::new();
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert_eq!(
+            result,
+            "https://cdn.example.com/static/a.js /uploads/avatars/me.png /uploads/"
+        );
+    }
+
     #[test]
     fn test_engine_firstof() {
         let engine = Engine::new();
@@ -841,6 +1212,43 @@ mod tests {
         assert_eq!(result, "<b>bold</b>");
     }
 
+    #[test]
+    fn test_engine_whitespace_control() {
+        let source = "<ul>\n  {% for i in items %}\n  <li>{{ i }}</li>\n  {% endfor %}\n</ul>";
+        let mut ctx = Context::new();
+        ctx.set(
+            "items",
+            ContextValue::List(vec![ContextValue::from("a"), ContextValue::from("b")]),
+        );
+
+        let engine = Engine::new();
+        engine.add_string_template("list.html", source);
+        engine.add_string_template("inline.html", "<p>\n  {{- name -}}\n</p>");
+        ctx.set("name", ContextValue::from("x"));
+        assert_eq!(
+            engine.render_to_string("list.html", &mut ctx).unwrap(),
+            "<ul>\n  \n  <li>a</li>\n  \n  <li>b</li>\n  \n</ul>"
+        );
+        assert_eq!(
+            engine.render_to_string("inline.html", &mut ctx).unwrap(),
+            "<p>x</p>"
+        );
+
+        let mut settings = django_rs_core::settings::TemplateSettings::default();
+        settings
+            .options
+            .insert("trim_blocks".to_string(), serde_json::json!(true));
+        settings
+            .options
+            .insert("lstrip_blocks".to_string(), serde_json::json!(true));
+        let engine = Engine::from_settings(&settings);
+        engine.add_string_template("list.html", source);
+        assert_eq!(
+            engine.render_to_string("list.html", &mut ctx).unwrap(),
+            "<ul>\n  <li>a</li>\n  <li>b</li>\n</ul>"
+        );
+    }
+
     #[test]
     fn test_engine_comment_inline() {
         let engine = Engine::new();
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::sync::OnceLock;
 
 use django_rs_core::error::DjangoError;
+use django_rs_core::utils::text::{phone2numeric, truncate_html_chars, truncate_html_words, wrap};
 
 use crate::context::{escape_html, ContextValue};
 
@@ -43,6 +44,18 @@ impl FilterRegistry {
         self.filters.insert(filter.name().to_string(), filter);
     }
 
+    /// Returns the names of the registered filters, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.filters.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns `true` if a filter with this name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.filters.contains_key(name)
+    }
+
     /// Applies a named filter to a value.
     pub fn apply(
         &self,
@@ -106,6 +119,7 @@ fn register_all(r: &mut FilterRegistry) {
     r.register(Box::new(SliceFilter));
     r.register(Box::new(DictsortFilter));
     r.register(Box::new(DictsortreversedFilter));
+    r.register(Box::new(MakeListFilter));
     r.register(Box::new(RandomFilter));
     r.register(Box::new(UnorderedListFilter));
 
@@ -114,6 +128,7 @@ fn register_all(r: &mut FilterRegistry) {
     r.register(Box::new(FloatformatFilter));
     r.register(Box::new(FilesizeformatFilter));
     r.register(Box::new(DivisiblebyFilter));
+    r.register(Box::new(GetDigitFilter));
 
     // Date filters
     r.register(Box::new(DateFilter));
@@ -500,23 +515,7 @@ impl Filter for WordwrapFilter {
     ) -> Result<ContextValue, DjangoError> {
         let s = value.to_display_string();
         let width = args.first().and_then(|a| a.as_integer()).unwrap_or(79) as usize;
-        let mut result = String::new();
-        let mut line_len = 0;
-
-        for word in s.split_whitespace() {
-            if line_len > 0 && line_len + word.len() + 1 > width {
-                result.push('\n');
-                line_len = 0;
-            }
-            if line_len > 0 {
-                result.push(' ');
-                line_len += 1;
-            }
-            result.push_str(word);
-            line_len += word.len();
-        }
-
-        Ok(ContextValue::String(result))
+        Ok(ContextValue::String(wrap(&s, width)))
     }
 }
 
@@ -649,70 +648,105 @@ impl Filter for SliceFilter {
     fn name(&self) -> &'static str {
         "slice"
     }
+    /// Slices with Python syntax: `"1:3"`, `"::2"`, `"-2:"`, `"::-1"`.
+    /// A single number is the stop, as in Django. An invalid slice returns
+    /// the value unchanged; strings are sliced by character.
     fn apply(
         &self,
         value: &ContextValue,
         args: &[ContextValue],
     ) -> Result<ContextValue, DjangoError> {
-        let slice_str = args
+        let spec = args
             .first()
             .map(|a| a.to_display_string())
             .unwrap_or_default();
-        let parts: Vec<&str> = slice_str.split(':').collect();
-
-        match value {
-            ContextValue::List(list) => {
-                let len = list.len() as i64;
-                let start = parts
-                    .first()
-                    .and_then(|s| s.parse::<i64>().ok())
-                    .unwrap_or(0);
-                let end = parts
-                    .get(1)
-                    .and_then(|s| s.parse::<i64>().ok())
-                    .unwrap_or(len);
-
-                let start = normalize_index(start, len);
-                let end = normalize_index(end, len);
-
-                if start >= end {
-                    return Ok(ContextValue::List(Vec::new()));
-                }
+        let Some((start, stop, step)) = parse_slice(&spec) else {
+            return Ok(value.clone());
+        };
 
-                Ok(ContextValue::List(list[start..end].to_vec()))
-            }
+        Ok(match value {
+            ContextValue::List(list) => ContextValue::List(
+                slice_indices(list.len(), start, stop, step)
+                    .into_iter()
+                    .map(|i| list[i].clone())
+                    .collect(),
+            ),
             ContextValue::String(s) | ContextValue::SafeString(s) => {
-                let len = s.len() as i64;
-                let start = parts
-                    .first()
-                    .and_then(|p| p.parse::<i64>().ok())
-                    .unwrap_or(0);
-                let end = parts
-                    .get(1)
-                    .and_then(|p| p.parse::<i64>().ok())
-                    .unwrap_or(len);
-
-                let start = normalize_index(start, len);
-                let end = normalize_index(end, len);
-
-                if start >= end || start >= s.len() {
-                    return Ok(ContextValue::String(String::new()));
+                let chars: Vec<char> = s.chars().collect();
+                let sliced: String = slice_indices(chars.len(), start, stop, step)
+                    .into_iter()
+                    .map(|i| chars[i])
+                    .collect();
+                if matches!(value, ContextValue::SafeString(_)) {
+                    ContextValue::SafeString(sliced)
+                } else {
+                    ContextValue::String(sliced)
                 }
-                let end = end.min(s.len());
-
-                Ok(ContextValue::String(s[start..end].to_string()))
             }
-            _ => Ok(value.clone()),
-        }
+            _ => value.clone(),
+        })
     }
 }
 
-fn normalize_index(idx: i64, len: i64) -> usize {
-    if idx < 0 {
-        (len + idx).max(0) as usize
+/// A parsed `start:stop:step` slice; `None` parts take Python's defaults.
+type SliceSpec = (Option<i64>, Option<i64>, i64);
+
+/// Parses Python slice syntax, or `None` if it's invalid.
+fn parse_slice(spec: &str) -> Option<SliceSpec> {
+    let parts = spec
+        .split(':')
+        .map(|part| {
+            let part = part.trim();
+            if part.is_empty() {
+                Ok(None)
+            } else {
+                part.parse::<i64>().map(Some)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    match parts.as_slice() {
+        // Like Python's `slice(n)`, a lone number is the stop.
+        [stop] => Some((None, *stop, 1)),
+        [start, stop] => Some((*start, *stop, 1)),
+        [start, stop, step] => match step.unwrap_or(1) {
+            0 => None,
+            step => Some((*start, *stop, step)),
+        },
+        _ => None,
+    }
+}
+
+/// Returns the indices a slice selects from a sequence of length `len`,
+/// following Python's `slice.indices`.
+fn slice_indices(len: usize, start: Option<i64>, stop: Option<i64>, step: i64) -> Vec<usize> {
+    let len = i64::try_from(len).unwrap_or(i64::MAX);
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |bound: Option<i64>, default: i64| {
+        bound.map_or(default, |b| {
+            if b < 0 {
+                (b + len).max(lower)
+            } else {
+                b.min(upper)
+            }
+        })
+    };
+    let (mut i, stop) = if step > 0 {
+        (clamp(start, lower), clamp(stop, upper))
     } else {
-        idx.min(len) as usize
+        (clamp(start, upper), clamp(stop, lower))
+    };
+    let mut indices = Vec::new();
+    while (step > 0 && i < stop) || (step < 0 && i > stop) {
+        // Indices stay within `lower..=upper`, so they're never negative.
+        indices.push(usize::try_from(i).unwrap_or_default());
+        // A step past `i64`'s range can't land on another index.
+        let Some(next) = i.checked_add(step) else {
+            break;
+        };
+        i = next;
     }
+    indices
 }
 
 struct DictsortFilter;
@@ -725,28 +759,7 @@ impl Filter for DictsortFilter {
         value: &ContextValue,
         args: &[ContextValue],
     ) -> Result<ContextValue, DjangoError> {
-        let key = args
-            .first()
-            .map(|a| a.to_display_string())
-            .unwrap_or_default();
-        match value {
-            ContextValue::List(list) => {
-                let mut sorted = list.clone();
-                sorted.sort_by(|a, b| {
-                    let a_val = a
-                        .resolve_path(&key)
-                        .map(|v| v.to_display_string())
-                        .unwrap_or_default();
-                    let b_val = b
-                        .resolve_path(&key)
-                        .map(|v| v.to_display_string())
-                        .unwrap_or_default();
-                    a_val.cmp(&b_val)
-                });
-                Ok(ContextValue::List(sorted))
-            }
-            _ => Ok(value.clone()),
-        }
+        Ok(dictsort(value, args, false))
     }
 }
 
@@ -760,28 +773,102 @@ impl Filter for DictsortreversedFilter {
         value: &ContextValue,
         args: &[ContextValue],
     ) -> Result<ContextValue, DjangoError> {
-        let key = args
-            .first()
-            .map(|a| a.to_display_string())
-            .unwrap_or_default();
-        match value {
-            ContextValue::List(list) => {
-                let mut sorted = list.clone();
-                sorted.sort_by(|a, b| {
-                    let a_val = a
-                        .resolve_path(&key)
-                        .map(|v| v.to_display_string())
-                        .unwrap_or_default();
-                    let b_val = b
-                        .resolve_path(&key)
-                        .map(|v| v.to_display_string())
-                        .unwrap_or_default();
-                    b_val.cmp(&a_val)
-                });
-                Ok(ContextValue::List(sorted))
+        Ok(dictsort(value, args, true))
+    }
+}
+
+/// Sorts a list of maps (or lists) by the value at a dotted key path.
+///
+/// As in Django, the result is an empty string if the value isn't a list,
+/// an item lacks the key, the path names a private (`_`-prefixed) key, or
+/// the keys can't be compared with each other. The sort is stable in both
+/// directions.
+fn dictsort(value: &ContextValue, args: &[ContextValue], reverse: bool) -> ContextValue {
+    let empty = ContextValue::String(String::new());
+    let ContextValue::List(list) = value else {
+        return empty;
+    };
+    let path = args
+        .first()
+        .map(|a| a.to_display_string())
+        .unwrap_or_default();
+    if path.is_empty() || path.split('.').any(|part| part.starts_with('_')) {
+        return empty;
+    }
+    let Some(keys) = list
+        .iter()
+        .map(|item| {
+            path.split('.')
+                .try_fold(item, |current, part| current.resolve_path(part))
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return empty;
+    };
+
+    let mut order: Vec<usize> = (0..list.len()).collect();
+    let mut comparable = true;
+    order.sort_by(|&a, &b| {
+        let (a, b) = if reverse { (b, a) } else { (a, b) };
+        compare_values(keys[a], keys[b]).unwrap_or_else(|| {
+            comparable = false;
+            std::cmp::Ordering::Equal
+        })
+    });
+    if !comparable {
+        return empty;
+    }
+    ContextValue::List(order.into_iter().map(|i| list[i].clone()).collect())
+}
+
+/// Orders two values the way Python would, or `None` if their types can't
+/// be compared: numbers (and booleans) numerically, strings by code point,
+/// lists element by element.
+fn compare_values(a: &ContextValue, b: &ContextValue) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (
+            ContextValue::String(a) | ContextValue::SafeString(a),
+            ContextValue::String(b) | ContextValue::SafeString(b),
+        ) => Some(a.cmp(b)),
+        (ContextValue::List(a), ContextValue::List(b)) => {
+            for (x, y) in a.iter().zip(b) {
+                match compare_values(x, y)? {
+                    std::cmp::Ordering::Equal => {}
+                    ordering => return Some(ordering),
+                }
             }
-            _ => Ok(value.clone()),
+            Some(a.len().cmp(&b.len()))
         }
+        (
+            ContextValue::Integer(_) | ContextValue::Bool(_),
+            ContextValue::Integer(_) | ContextValue::Bool(_),
+        ) => Some(python_int(a)?.cmp(&python_int(b)?)),
+        (
+            ContextValue::Integer(_) | ContextValue::Float(_) | ContextValue::Bool(_),
+            ContextValue::Integer(_) | ContextValue::Float(_) | ContextValue::Bool(_),
+        ) => number(a)?.partial_cmp(&number(b)?),
+        _ => None,
+    }
+}
+
+/// Returns a numeric value as an `f64`; strings aren't numbers here.
+fn number(value: &ContextValue) -> Option<f64> {
+    match value {
+        ContextValue::Bool(b) => Some(f64::from(u8::from(*b))),
+        ContextValue::Integer(_) | ContextValue::Float(_) => value.as_float(),
+        _ => None,
+    }
+}
+
+/// Coerces a value the way Python's `int()` does: floats truncate toward
+/// zero, booleans are 0 or 1, and strings must hold an integer.
+fn python_int(value: &ContextValue) -> Option<i64> {
+    match value {
+        ContextValue::Integer(i) => Some(*i),
+        ContextValue::Float(f) if f.is_finite() => Some(f.trunc() as i64),
+        ContextValue::Bool(b) => Some(i64::from(*b)),
+        ContextValue::String(s) | ContextValue::SafeString(s) => s.trim().parse().ok(),
+        _ => None,
     }
 }
 
@@ -838,6 +925,10 @@ impl Filter for AddFilter {
     fn name(&self) -> &'static str {
         "add"
     }
+    /// Adds the argument as Django does: both sides are first coerced with
+    /// `int()` (so floats truncate and `"3"` counts as 3); failing that,
+    /// strings and lists are concatenated; anything else gives an empty
+    /// string.
     fn apply(
         &self,
         value: &ContextValue,
@@ -845,18 +936,24 @@ impl Filter for AddFilter {
     ) -> Result<ContextValue, DjangoError> {
         let arg = args.first().unwrap_or(&ContextValue::Integer(0));
 
-        // Try numeric addition first
-        if let (Some(a), Some(b)) = (value.as_integer(), arg.as_integer()) {
-            return Ok(ContextValue::Integer(a + b));
-        }
-        if let (Some(a), Some(b)) = (value.as_float(), arg.as_float()) {
-            return Ok(ContextValue::Float(a + b));
+        if let (Some(a), Some(b)) = (python_int(value), python_int(arg)) {
+            if let Some(sum) = a.checked_add(b) {
+                return Ok(ContextValue::Integer(sum));
+            }
         }
-
-        // Fall back to string concatenation
-        let a = value.to_display_string();
-        let b = arg.to_display_string();
-        Ok(ContextValue::String(format!("{a}{b}")))
+        Ok(match (value, arg) {
+            (ContextValue::SafeString(a), ContextValue::SafeString(b)) => {
+                ContextValue::SafeString(format!("{a}{b}"))
+            }
+            (
+                ContextValue::String(a) | ContextValue::SafeString(a),
+                ContextValue::String(b) | ContextValue::SafeString(b),
+            ) => ContextValue::String(format!("{a}{b}")),
+            (ContextValue::List(a), ContextValue::List(b)) => {
+                ContextValue::List(a.iter().chain(b).cloned().collect())
+            }
+            _ => ContextValue::String(String::new()),
+        })
     }
 }
 
@@ -929,17 +1026,77 @@ impl Filter for DivisiblebyFilter {
     fn name(&self) -> &'static str {
         "divisibleby"
     }
+    /// Returns whether the value is divisible by the argument, both coerced
+    /// with `int()`. Values that aren't integers, and a zero divisor, give
+    /// `False`.
     fn apply(
         &self,
         value: &ContextValue,
         args: &[ContextValue],
     ) -> Result<ContextValue, DjangoError> {
-        let n = value.as_integer().unwrap_or(0);
-        let divisor = args.first().and_then(|a| a.as_integer()).unwrap_or(1);
-        if divisor == 0 {
-            return Ok(ContextValue::Bool(false));
+        let divisible = match (python_int(value), args.first().and_then(python_int)) {
+            (Some(n), Some(divisor)) if divisor != 0 => n.wrapping_rem(divisor) == 0,
+            _ => false,
+        };
+        Ok(ContextValue::Bool(divisible))
+    }
+}
+
+struct GetDigitFilter;
+impl Filter for GetDigitFilter {
+    fn name(&self) -> &'static str {
+        "get_digit"
+    }
+    /// Returns the digit at the given position counting from the right,
+    /// where 1 is the last digit, or 0 past the leftmost digit. The value is
+    /// returned unchanged if it or the position isn't an integer, or the
+    /// position is less than 1.
+    fn apply(
+        &self,
+        value: &ContextValue,
+        args: &[ContextValue],
+    ) -> Result<ContextValue, DjangoError> {
+        let (Some(number), Some(position)) = (python_int(value), args.first().and_then(python_int))
+        else {
+            return Ok(value.clone());
+        };
+        let Ok(position) = usize::try_from(position) else {
+            return Ok(value.clone());
+        };
+        if position < 1 {
+            return Ok(value.clone());
         }
-        Ok(ContextValue::Bool(n % divisor == 0))
+        let digit = number
+            .unsigned_abs()
+            .to_string()
+            .chars()
+            .rev()
+            .nth(position - 1)
+            .and_then(|c| c.to_digit(10))
+            .unwrap_or(0);
+        Ok(ContextValue::Integer(i64::from(digit)))
+    }
+}
+
+struct MakeListFilter;
+impl Filter for MakeListFilter {
+    fn name(&self) -> &'static str {
+        "make_list"
+    }
+    /// Splits the value's string form into a list of characters; `123`
+    /// becomes `["1", "2", "3"]`.
+    fn apply(
+        &self,
+        value: &ContextValue,
+        _args: &[ContextValue],
+    ) -> Result<ContextValue, DjangoError> {
+        Ok(ContextValue::List(
+            value
+                .to_display_string()
+                .chars()
+                .map(|c| ContextValue::String(c.to_string()))
+                .collect(),
+        ))
     }
 }
 
@@ -1301,21 +1458,7 @@ impl Filter for Phone2numericFilter {
         _args: &[ContextValue],
     ) -> Result<ContextValue, DjangoError> {
         let s = value.to_display_string();
-        let result: String = s
-            .chars()
-            .map(|c| match c.to_ascii_uppercase() {
-                'A' | 'B' | 'C' => '2',
-                'D' | 'E' | 'F' => '3',
-                'G' | 'H' | 'I' => '4',
-                'J' | 'K' | 'L' => '5',
-                'M' | 'N' | 'O' => '6',
-                'P' | 'Q' | 'R' | 'S' => '7',
-                'T' | 'U' | 'V' => '8',
-                'W' | 'X' | 'Y' | 'Z' => '9',
-                other => other,
-            })
-            .collect();
-        Ok(ContextValue::String(result))
+        Ok(ContextValue::String(phone2numeric(&s)))
     }
 }
 
@@ -1551,193 +1694,6 @@ fn context_value_to_json_string(value: &ContextValue) -> String {
     }
 }
 
-/// Truncates HTML content to a maximum number of visible characters,
-/// preserving and properly closing any open HTML tags.
-fn truncate_html_chars(html: &str, max_chars: usize) -> String {
-    let mut result = String::new();
-    let mut visible_count = 0;
-    let mut open_tags: Vec<String> = Vec::new();
-    let mut chars = html.chars().peekable();
-    let ellipsis = " ...";
-
-    while let Some(c) = chars.next() {
-        if visible_count >= max_chars {
-            break;
-        }
-
-        if c == '<' {
-            // Read the entire tag
-            let mut tag = String::from('<');
-            for tc in chars.by_ref() {
-                tag.push(tc);
-                if tc == '>' {
-                    break;
-                }
-            }
-
-            // Determine if it's opening, closing, or self-closing
-            let tag_inner = tag.trim_start_matches('<').trim_end_matches('>').trim();
-            if tag_inner.starts_with('/') {
-                // Closing tag
-                let tag_name = tag_inner
-                    .trim_start_matches('/')
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("");
-                if let Some(pos) = open_tags.iter().rposition(|t| t == tag_name) {
-                    open_tags.remove(pos);
-                }
-                result.push_str(&tag);
-            } else if tag_inner.ends_with('/')
-                || is_void_element(tag_inner.split_whitespace().next().unwrap_or(""))
-            {
-                // Self-closing or void element
-                result.push_str(&tag);
-            } else {
-                // Opening tag
-                let tag_name = tag_inner
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("")
-                    .to_string();
-                if !tag_name.is_empty() {
-                    open_tags.push(tag_name);
-                }
-                result.push_str(&tag);
-            }
-        } else {
-            result.push(c);
-            visible_count += 1;
-        }
-    }
-
-    if visible_count >= max_chars && chars.peek().is_some() {
-        result.push_str(ellipsis);
-    }
-
-    // Close any open tags in reverse order
-    for tag in open_tags.iter().rev() {
-        result.push_str(&format!("</{tag}>"));
-    }
-
-    result
-}
-
-/// Truncates HTML content to a maximum number of words,
-/// preserving and properly closing any open HTML tags.
-fn truncate_html_words(html: &str, max_words: usize) -> String {
-    let mut result = String::new();
-    let mut word_count = 0;
-    let mut open_tags: Vec<String> = Vec::new();
-    let mut chars = html.chars().peekable();
-    let mut in_word = false;
-    let ellipsis = " ...";
-    let mut truncated = false;
-
-    while let Some(c) = chars.next() {
-        if c == '<' {
-            // Read the entire tag
-            let mut tag = String::from('<');
-            for tc in chars.by_ref() {
-                tag.push(tc);
-                if tc == '>' {
-                    break;
-                }
-            }
-
-            let tag_inner = tag.trim_start_matches('<').trim_end_matches('>').trim();
-            if tag_inner.starts_with('/') {
-                let tag_name = tag_inner
-                    .trim_start_matches('/')
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("");
-                if let Some(pos) = open_tags.iter().rposition(|t| t == tag_name) {
-                    open_tags.remove(pos);
-                }
-                result.push_str(&tag);
-            } else if tag_inner.ends_with('/')
-                || is_void_element(tag_inner.split_whitespace().next().unwrap_or(""))
-            {
-                result.push_str(&tag);
-            } else {
-                let tag_name = tag_inner
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("")
-                    .to_string();
-                if !tag_name.is_empty() {
-                    open_tags.push(tag_name);
-                }
-                result.push_str(&tag);
-            }
-            in_word = false;
-        } else if c.is_whitespace() {
-            if in_word {
-                in_word = false;
-            }
-            // Only add whitespace if we haven't hit the limit
-            if word_count < max_words {
-                result.push(c);
-            }
-        } else {
-            if !in_word {
-                word_count += 1;
-                in_word = true;
-                if word_count > max_words {
-                    // We've started a new word past the limit; stop
-                    truncated = true;
-                    break;
-                }
-            }
-            result.push(c);
-        }
-    }
-
-    // Check if there's more content we didn't consume
-    if !truncated && chars.peek().is_none() {
-        // We consumed everything; no truncation needed, but close open tags
-        let trimmed = result.trim_end().to_string();
-        let mut final_result = trimmed;
-        for tag in open_tags.iter().rev() {
-            final_result.push_str(&format!("</{tag}>"));
-        }
-        return final_result;
-    }
-
-    // Trim trailing whitespace before adding ellipsis
-    let trimmed = result.trim_end().to_string();
-    let mut final_result = trimmed;
-    final_result.push_str(ellipsis);
-
-    // Close any open tags
-    for tag in open_tags.iter().rev() {
-        final_result.push_str(&format!("</{tag}>"));
-    }
-    final_result
-}
-
-/// Returns true if the given tag name is an HTML void element (self-closing).
-fn is_void_element(tag: &str) -> bool {
-    matches!(
-        tag.to_lowercase().as_str(),
-        "area"
-            | "base"
-            | "br"
-            | "col"
-            | "embed"
-            | "hr"
-            | "img"
-            | "input"
-            | "link"
-            | "meta"
-            | "param"
-            | "source"
-            | "track"
-            | "wbr"
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2051,6 +2007,179 @@ mod tests {
         assert_eq!(result, ContextValue::Bool(false));
     }
 
+    #[test]
+    fn test_add_coercion() {
+        let add = |value, arg| apply_filter("add", value, vec![arg]);
+        assert_eq!(
+            add(ContextValue::from("3"), ContextValue::Integer(4)),
+            ContextValue::Integer(7)
+        );
+        assert_eq!(
+            add(ContextValue::Float(2.9), ContextValue::Integer(1)),
+            ContextValue::Integer(3)
+        );
+        assert_eq!(
+            add(ContextValue::from("2.5"), ContextValue::Integer(1)).to_display_string(),
+            ""
+        );
+        assert_eq!(
+            add(
+                ContextValue::List(vec![ContextValue::Integer(1)]),
+                ContextValue::List(vec![ContextValue::Integer(2)]),
+            ),
+            ContextValue::List(vec![ContextValue::Integer(1), ContextValue::Integer(2)])
+        );
+        assert_eq!(
+            add(ContextValue::None, ContextValue::Integer(1)).to_display_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_divisibleby_coercion() {
+        let divisibleby = |value, arg| apply_filter("divisibleby", value, vec![arg]);
+        assert_eq!(
+            divisibleby(ContextValue::from("21"), ContextValue::from("7")),
+            ContextValue::Bool(true)
+        );
+        assert_eq!(
+            divisibleby(ContextValue::from("abc"), ContextValue::Integer(3)),
+            ContextValue::Bool(false)
+        );
+        assert_eq!(
+            divisibleby(ContextValue::Integer(4), ContextValue::Integer(0)),
+            ContextValue::Bool(false)
+        );
+    }
+
+    #[test]
+    fn test_get_digit() {
+        let get_digit = |value, arg| apply_filter("get_digit", value, vec![arg]);
+        assert_eq!(
+            get_digit(ContextValue::Integer(123_456_789), ContextValue::Integer(2)),
+            ContextValue::Integer(8)
+        );
+        assert_eq!(
+            get_digit(ContextValue::from("123"), ContextValue::Integer(5)),
+            ContextValue::Integer(0)
+        );
+        assert_eq!(
+            get_digit(ContextValue::Integer(123), ContextValue::Integer(0)),
+            ContextValue::Integer(123)
+        );
+        assert_eq!(
+            get_digit(ContextValue::from("abc"), ContextValue::Integer(1)),
+            ContextValue::from("abc")
+        );
+    }
+
+    #[test]
+    fn test_slice_python_syntax() {
+        let slice = |value: &str, spec: &str| {
+            apply_filter(
+                "slice",
+                ContextValue::from(value),
+                vec![ContextValue::from(spec)],
+            )
+            .to_display_string()
+        };
+        assert_eq!(slice("abcdef", "2"), "ab");
+        assert_eq!(slice("abcdef", "-2:"), "ef");
+        assert_eq!(slice("abcdef", "::2"), "ace");
+        assert_eq!(slice("abcdef", "::-1"), "fedcba");
+        assert_eq!(slice("abcdef", "4:1:-1"), "edc");
+        assert_eq!(slice("héllo", "1:3"), "él");
+        assert_eq!(slice("abcdef", "::0"), "abcdef");
+        assert_eq!(slice("abcdef", "a:b"), "abcdef");
+    }
+
+    #[test]
+    fn test_slice_huge_step() {
+        let slice = |value: &str, spec: &str| {
+            apply_filter(
+                "slice",
+                ContextValue::from(value),
+                vec![ContextValue::from(spec)],
+            )
+            .to_display_string()
+        };
+        assert_eq!(slice("abcdef", &format!("1::{}", i64::MAX)), "b");
+        assert_eq!(slice("abcdef", &format!("::{}", i64::MAX)), "a");
+        assert_eq!(slice("abcdef", &format!("::{}", i64::MIN)), "f");
+        assert_eq!(slice("abcdef", &format!("2::{}", i64::MIN)), "c");
+    }
+
+    #[test]
+    fn test_make_list() {
+        let result = apply_filter("make_list", ContextValue::Integer(123), vec![]);
+        assert_eq!(
+            result,
+            ContextValue::List(vec![
+                ContextValue::from("1"),
+                ContextValue::from("2"),
+                ContextValue::from("3"),
+            ])
+        );
+    }
+
+    fn people() -> ContextValue {
+        let person = |name: &str, age: i64, city: &str| {
+            let address = HashMap::from([("city".to_string(), ContextValue::from(city))]);
+            ContextValue::Dict(HashMap::from([
+                ("name".to_string(), ContextValue::from(name)),
+                ("age".to_string(), ContextValue::Integer(age)),
+                ("address".to_string(), ContextValue::Dict(address)),
+            ]))
+        };
+        ContextValue::List(vec![
+            person("Carol", 9, "Oslo"),
+            person("Alice", 30, "Lima"),
+            person("Bob", 100, "Oslo"),
+        ])
+    }
+
+    fn names(sorted: &ContextValue) -> Vec<String> {
+        let ContextValue::List(items) = sorted else {
+            panic!("Expected List, got {sorted:?}");
+        };
+        items
+            .iter()
+            .map(|item| item.resolve_path("name").unwrap().to_display_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_dictsort() {
+        // Numbers sort numerically, not as strings.
+        let sorted = apply_filter("dictsort", people(), vec![ContextValue::from("age")]);
+        assert_eq!(names(&sorted), vec!["Carol", "Alice", "Bob"]);
+
+        let sorted = apply_filter(
+            "dictsort",
+            people(),
+            vec![ContextValue::from("address.city")],
+        );
+        assert_eq!(names(&sorted), vec!["Alice", "Carol", "Bob"]);
+
+        let sorted = apply_filter(
+            "dictsortreversed",
+            people(),
+            vec![ContextValue::from("address.city")],
+        );
+        assert_eq!(names(&sorted), vec!["Carol", "Bob", "Alice"]);
+
+        for path in ["missing", "_private", "address._city"] {
+            let sorted = apply_filter("dictsort", people(), vec![ContextValue::from(path)]);
+            assert_eq!(sorted.to_display_string(), "", "{path}");
+        }
+        let sorted = apply_filter(
+            "dictsort",
+            ContextValue::from("abc"),
+            vec![ContextValue::from("name")],
+        );
+        assert_eq!(sorted.to_display_string(), "");
+    }
+
     #[test]
     fn test_default() {
         let result = apply_filter(
@@ -2215,6 +2344,13 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_registry_names() {
+        let names = default_registry().names();
+        assert!(names.contains(&"dictsort"));
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
     #[test]
     fn test_slice_negative() {
         let result = apply_filter(
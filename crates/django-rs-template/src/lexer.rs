@@ -19,6 +19,19 @@ pub enum Token {
     Comment(String),
 }
 
+/// Whitespace handling options for [`tokenize_with`].
+///
+/// Both options default to `false`, which keeps Django's behavior of
+/// preserving all text around tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// Removes the first newline after a block tag or comment.
+    pub trim_blocks: bool,
+    /// Removes spaces and tabs from the start of a line up to a block tag or
+    /// comment.
+    pub lstrip_blocks: bool,
+}
+
 /// Tokenizes a Django template source string into a sequence of [`Token`]s.
 ///
 /// Handles `{{ variable }}`, `{% tag %}`, and `{# comment #}` syntax.
@@ -27,75 +40,144 @@ pub enum Token {
 ///
 /// Returns a `TemplateSyntaxError` if a tag or variable is opened but never closed.
 pub fn tokenize(source: &str) -> Result<Vec<Token>, DjangoError> {
+    tokenize_with(source, &LexerOptions::default())
+}
+
+/// Tokenizes a template source string with the given whitespace options.
+///
+/// Independently of the options, a `-` just inside a tag's delimiters and
+/// set off from its content by whitespace (`{{- x -}}`, `{%- tag -%}`,
+/// `{#- text -#}`) removes all whitespace, including newlines, before or
+/// after the tag. A `+` (`{%+ tag +%}`) keeps
+/// the whitespace that `lstrip_blocks` or `trim_blocks` would remove.
+///
+/// # Errors
+///
+/// Returns a `TemplateSyntaxError` if a tag or variable is opened but never closed.
+pub fn tokenize_with(source: &str, options: &LexerOptions) -> Result<Vec<Token>, DjangoError> {
     let mut tokens = Vec::new();
     let mut remaining = source;
+    // Whitespace to remove from the start of the next text, as requested by
+    // the previous tag.
+    let mut strip_next = Strip::None;
 
     while !remaining.is_empty() {
-        // Find the next template tag opening
-        let next_open = find_next_open(remaining);
-
-        match next_open {
-            None => {
-                // Rest is plain text
-                if !remaining.is_empty() {
-                    tokens.push(Token::Text(remaining.to_string()));
-                }
-                break;
-            }
-            Some((pos, tag_type)) => {
-                // Push any text before the tag
-                if pos > 0 {
-                    tokens.push(Token::Text(remaining[..pos].to_string()));
-                }
-
-                let after_open = &remaining[pos + 2..]; // skip the 2-char opener
-
-                match tag_type {
-                    TagType::Variable => {
-                        // Look for }}
-                        if let Some(end) = after_open.find("}}") {
-                            let content = after_open[..end].trim().to_string();
-                            tokens.push(Token::Variable(content));
-                            remaining = &after_open[end + 2..];
-                        } else {
-                            return Err(DjangoError::TemplateSyntaxError(
-                                "Unclosed variable tag: expected '}}' ".to_string(),
-                            ));
-                        }
-                    }
-                    TagType::Block => {
-                        // Look for %}
-                        if let Some(end) = after_open.find("%}") {
-                            let content = after_open[..end].trim();
-                            let token = parse_block_content(content);
-                            tokens.push(token);
-                            remaining = &after_open[end + 2..];
-                        } else {
-                            return Err(DjangoError::TemplateSyntaxError(
-                                "Unclosed block tag: expected '%}'".to_string(),
-                            ));
-                        }
-                    }
-                    TagType::Comment => {
-                        // Look for #}
-                        if let Some(end) = after_open.find("#}") {
-                            let content = after_open[..end].trim().to_string();
-                            tokens.push(Token::Comment(content));
-                            remaining = &after_open[end + 2..];
-                        } else {
-                            return Err(DjangoError::TemplateSyntaxError(
-                                "Unclosed comment tag: expected '#}'".to_string(),
-                            ));
-                        }
-                    }
-                }
-            }
+        let line_start = remaining.len() == source.len()
+            || source[..source.len() - remaining.len()].ends_with('\n');
+
+        let Some((pos, tag_type)) = find_next_open(remaining) else {
+            push_text(&mut tokens, strip_next.apply(remaining));
+            break;
+        };
+
+        let after_open = &remaining[pos + 2..]; // skip the 2-char opener
+        let (close, unclosed) = match tag_type {
+            TagType::Variable => ("}}", "Unclosed variable tag: expected '}}' "),
+            TagType::Block => ("%}", "Unclosed block tag: expected '%}'"),
+            TagType::Comment => ("#}", "Unclosed comment tag: expected '#}'"),
+        };
+        let Some(end) = after_open.find(close) else {
+            return Err(DjangoError::TemplateSyntaxError(unclosed.to_string()));
+        };
+        let is_block = !matches!(tag_type, TagType::Variable);
+        let (content, left, right) = split_markers(&after_open[..end], is_block);
+
+        // Push any text before the tag
+        let mut text = strip_next.apply(&remaining[..pos]);
+        if left == Some('-') {
+            text = text.trim_end();
+        } else if left.is_none() && is_block && options.lstrip_blocks {
+            text = lstrip_line(text, line_start || remaining[..pos].contains('\n'));
         }
+        push_text(&mut tokens, text);
+
+        let content = content.trim();
+        tokens.push(match tag_type {
+            TagType::Variable => Token::Variable(content.to_string()),
+            TagType::Block => parse_block_content(content),
+            TagType::Comment => Token::Comment(content.to_string()),
+        });
+
+        strip_next = match right {
+            Some('-') => Strip::All,
+            None if is_block && options.trim_blocks => Strip::Newline,
+            _ => Strip::None,
+        };
+        remaining = &after_open[end + 2..];
     }
 
     Ok(tokens)
 }
 
+/// Whitespace to remove from the start of a text segment.
+#[derive(Debug, Clone, Copy)]
+enum Strip {
+    None,
+    /// A single leading newline (`trim_blocks`).
+    Newline,
+    /// All leading whitespace (`-%}`).
+    All,
+}
+
+impl Strip {
+    fn apply(self, text: &str) -> &str {
+        match self {
+            Self::None => text,
+            Self::Newline => text
+                .strip_prefix("\r\n")
+                .or_else(|| text.strip_prefix('\n'))
+                .unwrap_or(text),
+            Self::All => text.trim_start(),
+        }
+    }
+}
+
+fn push_text(tokens: &mut Vec<Token>, text: &str) {
+    if !text.is_empty() {
+        tokens.push(Token::Text(text.to_string()));
+    }
+}
+
+/// Splits a tag's inner content into the content and its optional `-`
+/// markers (and `+` markers, for blocks and comments) on the left and right.
+///
+/// A marker must be set off from the content by whitespace, so dashes that
+/// decorate a comment (`{#---- section ----#}`) are not taken as markers.
+fn split_markers(inner: &str, allow_plus: bool) -> (&str, Option<char>, Option<char>) {
+    let is_marker = |c: char| c == '-' || (allow_plus && c == '+');
+    let spaced = |c: Option<char>| c.map_or(true, char::is_whitespace);
+    let mut chars = inner.chars();
+    let left = chars
+        .next()
+        .filter(|&c| is_marker(c) && spaced(chars.next()));
+    let inner = if left.is_some() { &inner[1..] } else { inner };
+    let mut chars = inner.chars();
+    let right = chars
+        .next_back()
+        .filter(|&c| is_marker(c) && spaced(chars.next_back()));
+    let inner = if right.is_some() {
+        &inner[..inner.len() - 1]
+    } else {
+        inner
+    };
+    (inner, left, right)
+}
+
+/// Removes the spaces and tabs that precede a tag on its line, if nothing
+/// else does. `at_line_start` tells whether the line starts within `text`
+/// (or `text` itself starts a line).
+fn lstrip_line(text: &str, at_line_start: bool) -> &str {
+    if !at_line_start {
+        return text;
+    }
+    let line = text.rfind('\n').map_or(0, |i| i + 1);
+    if text[line..].chars().all(|c| c == ' ' || c == '\t') {
+        &text[..line]
+    } else {
+        text
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum TagType {
     Variable, // {{
@@ -341,6 +423,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trim_markers() {
+        let tokens = tokenize("a  {{- x -}}\n b {%- if y +%} c {#- note -#}\nd").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Text("a".to_string()),
+                Token::Variable("x".to_string()),
+                Token::Text("b".to_string()),
+                Token::Block("if".to_string(), vec!["y".to_string()]),
+                Token::Text(" c".to_string()),
+                Token::Comment("note".to_string()),
+                Token::Text("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trim_markers_need_to_touch_delimiters() {
+        let tokens = tokenize("a {{ -1 }} {% if x - y %} {{+x}}").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Text("a ".to_string()),
+                Token::Variable("-1".to_string()),
+                Token::Text(" ".to_string()),
+                Token::Block(
+                    "if".to_string(),
+                    vec!["x".to_string(), "-".to_string(), "y".to_string()]
+                ),
+                Token::Text(" ".to_string()),
+                Token::Variable("+x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dash_decorated_comment_is_not_a_trim_marker() {
+        let tokens = tokenize("a\n{#---- section ----#}\nb {{-1}}").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Text("a\n".to_string()),
+                Token::Comment("---- section ----".to_string()),
+                Token::Text("\nb ".to_string()),
+                Token::Variable("-1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trim_blocks_and_lstrip_blocks() {
+        let source = "<ul>\n  {% for i in items %}\n  <li>{{ i }}</li>\n  {% endfor %}\n</ul>\n";
+        let options = LexerOptions {
+            trim_blocks: true,
+            lstrip_blocks: true,
+        };
+        let tokens = tokenize_with(source, &options).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Text("<ul>\n".to_string()),
+                Token::Block(
+                    "for".to_string(),
+                    vec!["i".to_string(), "in".to_string(), "items".to_string()]
+                ),
+                Token::Text("  <li>".to_string()),
+                Token::Variable("i".to_string()),
+                Token::Text("</li>\n".to_string()),
+                Token::Block("endfor".to_string(), vec![]),
+                Token::Text("</ul>\n".to_string()),
+            ]
+        );
+
+        // Both are off by default, and `+` opts a tag out.
+        assert_eq!(
+            tokenize(source).unwrap()[0],
+            Token::Text("<ul>\n  ".to_string())
+        );
+        let tokens = tokenize_with("  {%+ if x +%}\nA  {% endif %}", &options).unwrap();
+        assert_eq!(tokens[0], Token::Text("  ".to_string()));
+        assert_eq!(tokens[2], Token::Text("\nA  ".to_string()));
+    }
+
     #[test]
     fn test_text_with_braces() {
         // A single brace should be treated as text
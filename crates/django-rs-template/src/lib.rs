@@ -11,9 +11,19 @@
 //! - **40+ built-in filters**: `lower`, `upper`, `truncatechars`, `date`, etc.
 //! - **20+ built-in tags**: `if`, `for`, `with`, `include`, `csrf_token`, etc.
 //! - **Auto-escaping**: HTML entities escaped by default, `safe` filter to bypass
+//! - **Whitespace control**: `{{- -}}` / `{%- -%}` trim markers and opt-in
+//!   `trim_blocks` / `lstrip_blocks` engine options
 //! - **Context processors**: Automatically inject variables from request data
 //! - **Template loaders**: Load from filesystem, app directories, or strings
 //! - **Fragment caching**: Cache rendered template fragments
+//! - **Static files**: `{% static %}` and `{% media %}` resolved through a
+//!   configurable storage, including hashed names from a manifest
+//! - **Thumbnails**: `{% thumbnail %}` resolves resized image variants through
+//!   a configurable backend
+//! - **Sandboxing**: Render user-provided templates with allowlisted tags and
+//!   filters, whitelisted includes, and bounded loops, output and time
+//! - **Validation**: Check all templates for syntax errors, unknown tags and
+//!   filters, and broken inheritance without rendering them
 //!
 //! ## Quick Start
 //!
@@ -96,7 +106,11 @@ pub mod lexer;
 pub mod library;
 pub mod loaders;
 pub mod parser;
+pub mod sandbox;
+pub mod staticfiles;
 pub mod tags;
+pub mod thumbnails;
+pub mod validation;
 
 // Re-export the most commonly used types.
 pub use context::{Context, ContextValue};
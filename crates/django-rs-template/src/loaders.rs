@@ -5,7 +5,7 @@
 //! with built-in implementations for filesystem and string-based loading.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
 use django_rs_core::error::DjangoError;
@@ -21,6 +21,35 @@ pub trait TemplateLoader: Send + Sync {
     ///
     /// Returns `TemplateDoesNotExist` if the template cannot be found.
     fn load(&self, name: &str) -> Result<String, DjangoError>;
+
+    /// Returns the names of all templates this loader can find.
+    ///
+    /// Used by tooling such as template validation. Loaders that cannot
+    /// enumerate their templates return an empty list.
+    fn template_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Collects the paths of all files below `dir`, relative to it and joined
+/// with `/`. Hidden files and directories are skipped.
+fn collect_template_names(dir: &Path, prefix: &str, names: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let name = format!("{prefix}{file_name}");
+        let path = entry.path();
+        if path.is_dir() {
+            collect_template_names(&path, &format!("{name}/"), names);
+        } else {
+            names.push(name);
+        }
+    }
 }
 
 /// Loads templates from one or more directories on the filesystem.
@@ -58,6 +87,14 @@ impl TemplateLoader for FileSystemLoader {
             self.dirs
         )))
     }
+
+    fn template_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for dir in &self.dirs {
+            collect_template_names(dir, "", &mut names);
+        }
+        names
+    }
 }
 
 /// Loads templates from `<app>/templates/` directories.
@@ -94,6 +131,14 @@ impl TemplateLoader for AppDirectoriesLoader {
             "Template '{name}' not found in app directories"
         )))
     }
+
+    fn template_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for dir in &self.dirs {
+            collect_template_names(&dir.join("templates"), "", &mut names);
+        }
+        names
+    }
 }
 
 /// Loads templates from an in-memory map of name to source strings.
@@ -147,6 +192,10 @@ impl TemplateLoader for StringLoader {
                 ))
             })
     }
+
+    fn template_names(&self) -> Vec<String> {
+        self.templates.read().unwrap().keys().cloned().collect()
+    }
 }
 
 #[cfg(test)]
@@ -210,4 +259,20 @@ mod tests {
         // Cleanup
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_filesystem_loader_template_names() {
+        let dir = std::env::temp_dir().join("django_rs_test_loader_names");
+        let _ = std::fs::create_dir_all(dir.join("blog"));
+        std::fs::write(dir.join("base.html"), "").unwrap();
+        std::fs::write(dir.join("blog/post.html"), "").unwrap();
+        std::fs::write(dir.join(".swp"), "").unwrap();
+
+        let loader = FileSystemLoader::new(vec![dir.clone()]);
+        let mut names = loader.template_names();
+        names.sort();
+        assert_eq!(names, vec!["base.html", "blog/post.html"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -5,9 +5,11 @@
 //! delegation to tag parsers.
 
 use django_rs_core::error::DjangoError;
+use django_rs_core::flags::{flag_enabled, FlagContext};
 
 use crate::context::{escape_html, Context, ContextValue};
 use crate::lexer::Token;
+use crate::thumbnails::{variant_name, ThumbnailSpec};
 
 /// A parsed filter call with a name and optional arguments.
 #[derive(Debug, Clone)]
@@ -150,6 +152,54 @@ fn find_filter_colon(s: &str) -> Option<usize> {
     None
 }
 
+/// The names of all block tags the parser understands, including the
+/// intermediate and closing tags of block constructs.
+pub const BUILTIN_TAGS: &[&str] = &[
+    "autoescape",
+    "block",
+    "blocktrans",
+    "comment",
+    "csrf_token",
+    "cycle",
+    "debug",
+    "elif",
+    "else",
+    "empty",
+    "endautoescape",
+    "endblock",
+    "endblocktrans",
+    "endcomment",
+    "endfor",
+    "endif",
+    "endifchanged",
+    "endifequal",
+    "endifflag",
+    "endspaceless",
+    "endverbatim",
+    "endwith",
+    "extends",
+    "firstof",
+    "for",
+    "get_media_prefix",
+    "get_static_prefix",
+    "if",
+    "ifchanged",
+    "ifequal",
+    "ifflag",
+    "include",
+    "load",
+    "lorem",
+    "media",
+    "now",
+    "spaceless",
+    "static",
+    "thumbnail",
+    "trans",
+    "url",
+    "verbatim",
+    "with",
+];
+
 /// A node in the parsed template tree.
 pub enum Node {
     /// A literal text segment.
@@ -244,10 +294,41 @@ pub enum Node {
         /// Positional arguments.
         args: Vec<Expression>,
     },
-    /// `{% static "path" %}` — outputs a static file URL.
+    /// `{% static "path" [as var] %}` — outputs a static file URL.
     StaticNode {
         /// The static file path.
         path: Expression,
+        /// Optional variable name to assign the URL to.
+        as_var: Option<String>,
+    },
+    /// `{% media "path" [as var] %}` — outputs a media file URL.
+    MediaNode {
+        /// The media file path.
+        path: Expression,
+        /// Optional variable name to assign the URL to.
+        as_var: Option<String>,
+    },
+    /// `{% thumbnail "path" "WxH" [crop] [as var] %}` — outputs the URL of a
+    /// resized image variant.
+    ThumbnailNode {
+        /// The original image path.
+        path: Expression,
+        /// The `"WIDTHxHEIGHT"` size.
+        size: Expression,
+        /// Whether to crop to the exact size.
+        crop: bool,
+        /// Optional variable name to assign the URL to.
+        as_var: Option<String>,
+    },
+    /// `{% get_static_prefix [as var] %}` — outputs `STATIC_URL`.
+    GetStaticPrefixNode {
+        /// Optional variable name to assign the prefix to.
+        as_var: Option<String>,
+    },
+    /// `{% get_media_prefix [as var] %}` — outputs `MEDIA_URL`.
+    GetMediaPrefixNode {
+        /// Optional variable name to assign the prefix to.
+        as_var: Option<String>,
     },
     /// `{% ifequal a b %}...{% endifequal %}` — deprecated equality check.
     IfEqualNode {
@@ -269,6 +350,16 @@ pub enum Node {
         /// Else body.
         else_body: Vec<Node>,
     },
+    /// `{% ifflag name %}...{% endifflag %}` — renders when a feature flag is
+    /// on for the context's `user`.
+    IfFlagNode {
+        /// The flag name.
+        name: Expression,
+        /// Body for an enabled flag.
+        body: Vec<Node>,
+        /// Body for a disabled flag.
+        else_body: Vec<Node>,
+    },
     /// `{% load %}` — loads a template tag library (no-op in this implementation).
     LoadNode,
     /// `{% lorem %}` — generates lorem ipsum text.
@@ -536,19 +627,54 @@ impl<'a> ParserState<'a> {
                 Ok(Some(Node::NowNode { format }))
             }
             "url" => self.parse_url(args),
-            "static" => {
-                let path = if let Some(arg) = args.first() {
+            "static" | "media" => {
+                let (rest, as_var) = split_as_var(args);
+                let path = if let Some(arg) = rest.first() {
                     parse_expression(arg)?
                 } else {
+                    return Err(DjangoError::TemplateSyntaxError(format!(
+                        "{{% {tag_name} %}} requires a path"
+                    )));
+                };
+                self.pos += 1;
+                if tag_name == "static" {
+                    Ok(Some(Node::StaticNode { path, as_var }))
+                } else {
+                    Ok(Some(Node::MediaNode { path, as_var }))
+                }
+            }
+            "thumbnail" => {
+                let (rest, as_var) = split_as_var(args);
+                let crop = rest.last().is_some_and(|flag| flag == "crop");
+                let rest = if crop { &rest[..rest.len() - 1] } else { rest };
+                let [path, size] = rest else {
                     return Err(DjangoError::TemplateSyntaxError(
-                        "{% static %} requires a path".to_string(),
+                        "{% thumbnail %} requires a path and a size".to_string(),
                     ));
                 };
+                let path = parse_expression(path)?;
+                let size = parse_expression(size)?;
                 self.pos += 1;
-                Ok(Some(Node::StaticNode { path }))
+                Ok(Some(Node::ThumbnailNode {
+                    path,
+                    size,
+                    crop,
+                    as_var,
+                }))
+            }
+            "get_static_prefix" => {
+                let (_, as_var) = split_as_var(args);
+                self.pos += 1;
+                Ok(Some(Node::GetStaticPrefixNode { as_var }))
+            }
+            "get_media_prefix" => {
+                let (_, as_var) = split_as_var(args);
+                self.pos += 1;
+                Ok(Some(Node::GetMediaPrefixNode { as_var }))
             }
             "ifequal" => self.parse_ifequal(args),
             "ifchanged" => self.parse_ifchanged(args),
+            "ifflag" => self.parse_ifflag(args),
             "load" => {
                 self.pos += 1;
                 Ok(Some(Node::LoadNode))
@@ -896,6 +1022,43 @@ impl<'a> ParserState<'a> {
         }))
     }
 
+    fn parse_ifflag(&mut self, args: &[String]) -> Result<Option<Node>, DjangoError> {
+        let name = if let Some(arg) = args.first() {
+            parse_expression(arg)?
+        } else {
+            return Err(DjangoError::TemplateSyntaxError(
+                "{% ifflag %} requires a flag name".to_string(),
+            ));
+        };
+
+        self.pos += 1;
+        let body = self.parse_nodes(&["else", "endifflag"])?;
+
+        let else_body = if self.pos < self.tokens.len() {
+            if let Token::Block(tag, _) = &self.tokens[self.pos] {
+                if tag == "else" {
+                    self.pos += 1;
+                    let else_nodes = self.parse_nodes(&["endifflag"])?;
+                    self.pos += 1;
+                    else_nodes
+                } else {
+                    self.pos += 1;
+                    Vec::new()
+                }
+            } else {
+                Vec::new()
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(Some(Node::IfFlagNode {
+            name,
+            body,
+            else_body,
+        }))
+    }
+
     fn parse_lorem(&mut self, args: &[String]) -> Result<Option<Node>, DjangoError> {
         let count = args.first().and_then(|a| a.parse().ok()).unwrap_or(1);
         let method = args.get(1).cloned().unwrap_or_else(|| "p".to_string());
@@ -1033,6 +1196,33 @@ pub fn strip_quotes(s: &str) -> String {
     }
 }
 
+/// Splits a trailing `as name` off tag arguments.
+fn split_as_var(args: &[String]) -> (&[String], Option<String>) {
+    match args {
+        [rest @ .., kw, name] if kw == "as" => (rest, Some(name.clone())),
+        _ => (args, None),
+    }
+}
+
+/// Reads a URL prefix such as `STATIC_URL` from the context.
+fn context_prefix(context: &Context, key: &str, default: &str) -> String {
+    context
+        .get(key)
+        .map(|v| v.to_display_string())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Stores `value` in the context when an `as` variable is given, otherwise
+/// returns it as tag output.
+fn assign_or_output(context: &mut Context, as_var: Option<&str>, value: String) -> String {
+    if let Some(var) = as_var {
+        context.set(var, ContextValue::String(value));
+        String::new()
+    } else {
+        value
+    }
+}
+
 /// Renders a node tree to a string.
 pub fn render_nodes(
     nodes: &[Node],
@@ -1042,7 +1232,12 @@ pub fn render_nodes(
     let mut output = String::new();
 
     for node in nodes {
-        output.push_str(&render_node(node, context, engine)?);
+        let rendered = render_node(node, context, engine)?;
+        // Tags report their own text and variables as they render them
+        if matches!(node, Node::Text(_) | Node::Variable { .. }) {
+            engine.check_output(rendered.len())?;
+        }
+        output.push_str(&rendered);
     }
 
     Ok(output)
@@ -1145,6 +1340,10 @@ fn render_node(
                 .get("csrf_token")
                 .map(|v| v.to_display_string())
                 .unwrap_or_default();
+            // Without a token there is nothing a form could submit
+            if token.is_empty() {
+                return Ok(String::new());
+            }
             Ok(format!(
                 r#"<input type="hidden" name="csrfmiddlewaretoken" value="{token}">"#
             ))
@@ -1197,13 +1396,55 @@ fn render_node(
             let name_val = name.resolve(context);
             Ok(name_val.to_display_string())
         }
-        Node::StaticNode { path } => {
-            let path_val = path.resolve(context);
-            let static_url = context
-                .get("STATIC_URL")
-                .map(|v| v.to_display_string())
-                .unwrap_or_else(|| "/static/".to_string());
-            Ok(format!("{}{}", static_url, path_val.to_display_string()))
+        Node::StaticNode { path, as_var } => {
+            let path_val = path.resolve(context).to_display_string();
+            let url = match engine.static_url(&path_val) {
+                Some(url) => url?,
+                None => format!(
+                    "{}{}",
+                    context_prefix(context, "STATIC_URL", "/static/"),
+                    path_val
+                ),
+            };
+            Ok(assign_or_output(context, as_var.as_deref(), url))
+        }
+        Node::MediaNode { path, as_var } => {
+            let path_val = path.resolve(context).to_display_string();
+            let prefix = engine
+                .media_prefix()
+                .unwrap_or_else(|| context_prefix(context, "MEDIA_URL", "/media/"));
+            let url = crate::staticfiles::join_url(&prefix, &path_val);
+            Ok(assign_or_output(context, as_var.as_deref(), url))
+        }
+        Node::ThumbnailNode {
+            path,
+            size,
+            crop,
+            as_var,
+        } => {
+            let path_val = path.resolve(context).to_display_string();
+            let spec = ThumbnailSpec::parse(&size.resolve(context).to_display_string(), *crop)?;
+            let url = if let Some(url) = engine.thumbnail_url(&path_val, &spec) {
+                url?
+            } else {
+                let prefix = engine
+                    .media_prefix()
+                    .unwrap_or_else(|| context_prefix(context, "MEDIA_URL", "/media/"));
+                crate::staticfiles::join_url(&prefix, &variant_name(&path_val, &spec))
+            };
+            Ok(assign_or_output(context, as_var.as_deref(), url))
+        }
+        Node::GetStaticPrefixNode { as_var } => {
+            let prefix = engine
+                .static_prefix()
+                .unwrap_or_else(|| context_prefix(context, "STATIC_URL", "/static/"));
+            Ok(assign_or_output(context, as_var.as_deref(), prefix))
+        }
+        Node::GetMediaPrefixNode { as_var } => {
+            let prefix = engine
+                .media_prefix()
+                .unwrap_or_else(|| context_prefix(context, "MEDIA_URL", "/media/"));
+            Ok(assign_or_output(context, as_var.as_deref(), prefix))
         }
         Node::IfEqualNode {
             left,
@@ -1249,6 +1490,25 @@ fn render_node(
                 render_nodes(else_body, context, engine)
             }
         }
+        Node::IfFlagNode {
+            name,
+            body,
+            else_body,
+        } => {
+            let name = name.resolve(context).to_display_string();
+            // `user.id` is only set for a logged-in user.
+            let flag_context = FlagContext {
+                user: context
+                    .get("user.id")
+                    .map(ContextValue::to_display_string)
+                    .filter(|id| !id.is_empty()),
+            };
+            if flag_enabled(&name, &flag_context) {
+                render_nodes(body, context, engine)
+            } else {
+                render_nodes(else_body, context, engine)
+            }
+        }
         Node::LoadNode => Ok(String::new()),
         Node::LoremNode { count, method } => Ok(generate_lorem(*count, method)),
         Node::DebugNode => {
@@ -1337,6 +1597,7 @@ fn render_for_node(
     let parent_loop = context.get("forloop").cloned();
 
     for (idx, item) in list.iter().enumerate() {
+        engine.count_loop_iteration()?;
         context.push();
 
         // Set loop variable(s)
@@ -1833,4 +2094,37 @@ mod tests {
         let result = engine.render_to_string("bad.html", &mut ctx);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_ifflag_tag() {
+        use django_rs_core::flags::{Flag, FLAGS};
+
+        FLAGS.set("parser_test_flag", Flag::off().with_user("7", true));
+        let engine = crate::engine::Engine::new();
+        engine.add_string_template(
+            "test.html",
+            r#"{% ifflag "parser_test_flag" %}new{% else %}old{% endifflag %}"#,
+        );
+
+        let mut ctx = Context::new();
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert_eq!(result, "old");
+
+        let mut user = HashMap::new();
+        user.insert("id".to_string(), ContextValue::from("7"));
+        ctx.set("user", ContextValue::Dict(user));
+        let result = engine.render_to_string("test.html", &mut ctx).unwrap();
+        assert_eq!(result, "new");
+
+        FLAGS.remove("parser_test_flag");
+    }
+
+    #[test]
+    fn test_ifflag_tag_error_no_args() {
+        let engine = crate::engine::Engine::new();
+        engine.add_string_template("bad.html", "{% ifflag %}{% endifflag %}");
+        let mut ctx = Context::new();
+        let result = engine.render_to_string("bad.html", &mut ctx);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,552 @@
+//! Sandboxed rendering of untrusted templates.
+//!
+//! Templates written by users, such as customized email templates, must not
+//! reach the full engine: `{% include %}` can read any template, `{% debug %}`
+//! dumps the context, `|safe` turns off escaping, and a few nested loops are
+//! enough to tie up a worker. An [`Engine`] given a [`Sandbox`] with
+//! [`Engine::set_sandbox`] only renders what the sandbox allows:
+//!
+//! - tags and filters outside the allowlists are rejected when the template
+//!   is parsed;
+//! - `{% include %}` and `{% extends %}` may only load whitelisted templates,
+//!   which are trusted and exempt from the allowlists;
+//! - loops may run a bounded number of iterations in total;
+//! - the text and variables rendered may not add up to more than a size
+//!   limit, counting what loops and tags discard;
+//! - rendering is stopped once it takes longer than a timeout.
+//!
+//! Limits are checked as rendering progresses, between nodes and loop
+//! iterations, so a single slow filter call is not interrupted.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_template::context::{Context, ContextValue};
+//! use django_rs_template::engine::Engine;
+//! use django_rs_template::sandbox::Sandbox;
+//!
+//! let mut engine = Engine::new();
+//! engine.set_sandbox(Sandbox::new().allow_template("email/footer.html"));
+//! engine.add_string_template("email/footer.html", "-- The team");
+//! engine.add_string_template(
+//!     "welcome.html",
+//!     r#"Hi {{ name|title }}! {% include "email/footer.html" %}"#,
+//! );
+//! engine.add_string_template("sneaky.html", "{% debug %}");
+//!
+//! let mut ctx = Context::new();
+//! ctx.set("name", ContextValue::from("ada"));
+//! let html = engine.render_to_string("welcome.html", &mut ctx).unwrap();
+//! assert_eq!(html, "Hi Ada! -- The team");
+//! assert!(engine.render_to_string("sneaky.html", &mut ctx).is_err());
+//! ```
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use django_rs_core::error::DjangoError;
+
+use crate::context::Context;
+use crate::engine::{Engine, TemplateRenderer};
+use crate::lexer::Token;
+use crate::parser;
+use crate::thumbnails::ThumbnailSpec;
+
+/// Tags allowed by [`Sandbox::new`].
+pub const DEFAULT_ALLOWED_TAGS: &[&str] = &[
+    "autoescape",
+    "block",
+    "blocktrans",
+    "comment",
+    "cycle",
+    "firstof",
+    "for",
+    "if",
+    "ifchanged",
+    "ifequal",
+    "now",
+    "spaceless",
+    "trans",
+    "verbatim",
+    "with",
+];
+
+/// Filters allowed by [`Sandbox::new`].
+///
+/// These are the built-in filters except `safe`, and except `center`,
+/// `ljust`, `rjust`, `stringformat` and `floatformat`, whose width or
+/// precision argument would let a template allocate any amount of memory in
+/// one call.
+pub const DEFAULT_ALLOWED_FILTERS: &[&str] = &[
+    "add",
+    "addslashes",
+    "capfirst",
+    "cut",
+    "date",
+    "default",
+    "default_if_none",
+    "dictsort",
+    "dictsortreversed",
+    "divisibleby",
+    "escape",
+    "escapejs",
+    "filesizeformat",
+    "first",
+    "get_digit",
+    "iriencode",
+    "join",
+    "json_script",
+    "last",
+    "length",
+    "length_is",
+    "linebreaks",
+    "linebreaksbr",
+    "linenumbers",
+    "lower",
+    "make_list",
+    "phone2numeric",
+    "pluralize",
+    "random",
+    "slice",
+    "slugify",
+    "striptags",
+    "time",
+    "timesince",
+    "timeuntil",
+    "title",
+    "truncatechars",
+    "truncatechars_html",
+    "truncatewords",
+    "truncatewords_html",
+    "unordered_list",
+    "upper",
+    "urlize",
+    "wordwrap",
+    "yesno",
+];
+
+/// Tags that only appear inside another tag, and are allowed with it.
+const INTERMEDIATE_TAGS: &[&str] = &["elif", "else", "empty"];
+
+/// Restrictions applied to every template an engine renders.
+///
+/// Closing tags (`endfor`) and intermediate tags (`else`, `elif`, `empty`)
+/// are allowed along with their opening tag. `{% include %}` and
+/// `{% extends %}` are not controlled by the tag allowlist but by the
+/// template whitelist, which is empty by default. Whitelisted templates are
+/// the application's own, and may use any tag or filter.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    tags: HashSet<String>,
+    filters: HashSet<String>,
+    templates: HashSet<String>,
+    max_loop_iterations: usize,
+    max_output_size: usize,
+    timeout: Duration,
+}
+
+impl Sandbox {
+    /// Creates a sandbox allowing [`DEFAULT_ALLOWED_TAGS`] and
+    /// [`DEFAULT_ALLOWED_FILTERS`], no includes, 10,000 loop iterations,
+    /// 1 MB of output and one second of rendering.
+    pub fn new() -> Self {
+        Self {
+            tags: DEFAULT_ALLOWED_TAGS
+                .iter()
+                .copied()
+                .map(String::from)
+                .collect(),
+            filters: DEFAULT_ALLOWED_FILTERS
+                .iter()
+                .copied()
+                .map(String::from)
+                .collect(),
+            templates: HashSet::new(),
+            max_loop_iterations: 10_000,
+            max_output_size: 1024 * 1024,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Replaces the tag allowlist.
+    #[must_use]
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().copied().map(String::from).collect();
+        self
+    }
+
+    /// Adds a tag to the allowlist.
+    #[must_use]
+    pub fn allow_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.insert(tag.into());
+        self
+    }
+
+    /// Replaces the filter allowlist.
+    #[must_use]
+    pub fn filters(mut self, filters: &[&str]) -> Self {
+        self.filters = filters.iter().copied().map(String::from).collect();
+        self
+    }
+
+    /// Adds a filter to the allowlist.
+    #[must_use]
+    pub fn allow_filter(mut self, filter: impl Into<String>) -> Self {
+        self.filters.insert(filter.into());
+        self
+    }
+
+    /// Allows `{% include %}` and `{% extends %}` to load a template, which
+    /// is trusted to use any tag or filter.
+    #[must_use]
+    pub fn allow_template(mut self, name: impl Into<String>) -> Self {
+        self.templates.insert(name.into());
+        self
+    }
+
+    /// Sets the total number of loop iterations a render may run.
+    #[must_use]
+    pub const fn max_loop_iterations(mut self, iterations: usize) -> Self {
+        self.max_loop_iterations = iterations;
+        self
+    }
+
+    /// Sets the maximum size of the output, in bytes.
+    #[must_use]
+    pub const fn max_output_size(mut self, bytes: usize) -> Self {
+        self.max_output_size = bytes;
+        self
+    }
+
+    /// Sets how long a render may take.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns `true` if `tag` may be used.
+    pub fn allows_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+            || tag
+                .strip_prefix("end")
+                .is_some_and(|opening| self.tags.contains(opening))
+            || INTERMEDIATE_TAGS.contains(&tag)
+    }
+
+    /// Returns `true` if `filter` may be used.
+    pub fn allows_filter(&self, filter: &str) -> bool {
+        self.filters.contains(filter)
+    }
+
+    /// Returns `true` if `{% include %}` and `{% extends %}` may load `name`.
+    pub fn allows_template(&self, name: &str) -> bool {
+        self.templates.contains(name)
+    }
+
+    /// Checks that a tokenized template only uses allowed tags and filters,
+    /// and only includes or extends whitelisted templates.
+    ///
+    /// Includes of a variable are checked when rendered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemplateSyntaxError` for a tag or filter that is not
+    /// allowed, and `SuspiciousOperation` for a template that is not
+    /// whitelisted.
+    pub fn check_tokens(&self, name: &str, tokens: &[Token]) -> Result<(), DjangoError> {
+        let mut in_verbatim = false;
+        for token in tokens {
+            match token {
+                Token::Block(tag, _) if in_verbatim => in_verbatim = tag != "endverbatim",
+                Token::Block(tag, args) if tag == "include" || tag == "extends" => {
+                    if let Some(target) = args
+                        .first()
+                        .filter(|arg| arg.starts_with('"') || arg.starts_with('\''))
+                    {
+                        self.check_template(&parser::strip_quotes(target))?;
+                    }
+                }
+                Token::Block(tag, _) => {
+                    if !self.allows_tag(tag) {
+                        return Err(DjangoError::TemplateSyntaxError(format!(
+                            "Tag '{tag}' is not allowed in sandboxed template '{name}'"
+                        )));
+                    }
+                    in_verbatim = tag == "verbatim";
+                }
+                Token::Variable(expression) => {
+                    let (_, filters) = parser::parse_variable_expression(expression)?;
+                    if let Some(filter) = filters.iter().find(|f| !self.allows_filter(&f.name)) {
+                        return Err(DjangoError::TemplateSyntaxError(format!(
+                            "Filter '{}' is not allowed in sandboxed template '{name}'",
+                            filter.name
+                        )));
+                    }
+                }
+                Token::Text(_) | Token::Comment(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `{% include %}` and `{% extends %}` may load `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SuspiciousOperation` if the template is not whitelisted.
+    pub fn check_template(&self, name: &str) -> Result<(), DjangoError> {
+        if self.allows_template(name) {
+            Ok(())
+        } else {
+            Err(DjangoError::SuspiciousOperation(format!(
+                "Template '{name}' may not be loaded from a sandboxed template"
+            )))
+        }
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The renderer of one sandboxed render, tracking its loop iterations,
+/// output and deadline.
+pub(crate) struct SandboxedRenderer<'a> {
+    engine: &'a Engine,
+    sandbox: &'a Sandbox,
+    deadline: Instant,
+    iterations: AtomicUsize,
+    output: AtomicUsize,
+}
+
+impl<'a> SandboxedRenderer<'a> {
+    /// Starts a render; the timeout runs from now.
+    pub(crate) fn new(engine: &'a Engine, sandbox: &'a Sandbox) -> Self {
+        Self {
+            engine,
+            sandbox,
+            deadline: Instant::now() + sandbox.timeout,
+            iterations: AtomicUsize::new(0),
+            output: AtomicUsize::new(0),
+        }
+    }
+
+    /// Fails if `len` bytes of output exceed the sandbox's limit.
+    pub(crate) fn check_size(&self, len: usize) -> Result<(), DjangoError> {
+        if len > self.sandbox.max_output_size {
+            return Err(DjangoError::SuspiciousOperation(format!(
+                "Sandboxed template output exceeds {} bytes",
+                self.sandbox.max_output_size
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_deadline(&self) -> Result<(), DjangoError> {
+        if Instant::now() >= self.deadline {
+            return Err(DjangoError::SuspiciousOperation(format!(
+                "Sandboxed template took longer than {:?} to render",
+                self.sandbox.timeout
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl TemplateRenderer for SandboxedRenderer<'_> {
+    fn render_template(&self, name: &str, context: &mut Context) -> Result<String, DjangoError> {
+        self.sandbox.check_template(name)?;
+        let template = self.engine.get_template(name)?;
+        self.engine.render_template_obj(&template, context, self)
+    }
+
+    fn static_url(&self, path: &str) -> Option<Result<String, DjangoError>> {
+        self.engine.static_url(path)
+    }
+
+    fn static_prefix(&self) -> Option<String> {
+        self.engine.static_prefix()
+    }
+
+    fn media_prefix(&self) -> Option<String> {
+        self.engine.media_prefix()
+    }
+
+    fn thumbnail_url(
+        &self,
+        name: &str,
+        spec: &ThumbnailSpec,
+    ) -> Option<Result<String, DjangoError>> {
+        self.engine.thumbnail_url(name, spec)
+    }
+
+    fn count_loop_iteration(&self) -> Result<(), DjangoError> {
+        let iterations = self.iterations.fetch_add(1, Ordering::Relaxed) + 1;
+        if iterations > self.sandbox.max_loop_iterations {
+            return Err(DjangoError::SuspiciousOperation(format!(
+                "Sandboxed template ran more than {} loop iterations",
+                self.sandbox.max_loop_iterations
+            )));
+        }
+        self.check_deadline()
+    }
+
+    fn check_output(&self, len: usize) -> Result<(), DjangoError> {
+        let total = self.output.fetch_add(len, Ordering::Relaxed) + len;
+        self.check_size(total)?;
+        self.check_deadline()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::ContextValue;
+
+    fn engine(sandbox: Sandbox) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_sandbox(sandbox);
+        engine
+    }
+
+    fn render(engine: &Engine, source: &str) -> Result<String, DjangoError> {
+        engine.add_string_template("user.html", source);
+        let mut ctx = Context::new();
+        ctx.set(
+            "items",
+            ContextValue::List((1..=5).map(ContextValue::Integer).collect()),
+        );
+        ctx.set("name", ContextValue::from("<b>ada</b>"));
+        engine.render_to_string("user.html", &mut ctx)
+    }
+
+    #[test]
+    fn test_allowed_tags_and_filters_render() {
+        let engine = engine(Sandbox::new());
+        let html = render(
+            &engine,
+            "{% for i in items %}{% if forloop.last %}{{ i }}{% else %}{{ i }},{% endif %}{% endfor %} {{ name|upper }}",
+        )
+        .unwrap();
+        assert_eq!(html, "1,2,3,4,5 &lt;B&gt;ADA&lt;/B&gt;");
+    }
+
+    #[test]
+    fn test_disallowed_tags_and_filters_are_rejected() {
+        let engine = engine(Sandbox::new());
+        for source in ["{% debug %}", "{% load humanize %}", "{{ name|safe }}"] {
+            let err = render(&engine, source).unwrap_err();
+            assert!(
+                matches!(err, DjangoError::TemplateSyntaxError(_)),
+                "{source}: {err}"
+            );
+        }
+        // Inside verbatim, tags are only text.
+        assert_eq!(
+            render(&engine, "{% verbatim %}{% debug %}{% endverbatim %}").unwrap(),
+            "{% debug %}"
+        );
+
+        let engine = self::engine(Sandbox::new().tags(&["if"]).allow_filter("safe"));
+        assert_eq!(render(&engine, "{{ name|safe }}").unwrap(), "<b>ada</b>");
+        assert!(render(&engine, "{% for i in items %}{% endfor %}").is_err());
+    }
+
+    #[test]
+    fn test_include_and_extends_need_whitelisted_templates() {
+        let engine = engine(Sandbox::new().allow_template("base.html"));
+        engine.add_string_template(
+            "base.html",
+            "[{% block body %}{% endblock %}{{ footer|safe }}]",
+        );
+        engine.add_string_template("secret.html", "{{ SECRET_KEY }}");
+
+        let html = render(
+            &engine,
+            r#"{% extends "base.html" %}{% block body %}hi{% endblock %}"#,
+        )
+        .unwrap();
+        assert_eq!(html, "[hi]");
+        assert_eq!(
+            render(&engine, r#"{% include "base.html" %}"#).unwrap(),
+            "[]"
+        );
+
+        let err = render(&engine, r#"{% include "secret.html" %}"#).unwrap_err();
+        assert!(matches!(err, DjangoError::SuspiciousOperation(_)));
+        let err = render(
+            &engine,
+            r#"{% with page="secret.html" %}{% include page %}{% endwith %}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, DjangoError::SuspiciousOperation(_)));
+    }
+
+    #[test]
+    fn test_loop_iterations_and_output_size_are_bounded() {
+        let engine = engine(Sandbox::new().max_loop_iterations(20));
+        let nested = "{% for i in items %}{% for j in items %}.{% endfor %}{% endfor %}";
+        let err = render(&engine, nested).unwrap_err();
+        assert!(err.to_string().contains("more than 20 loop iterations"));
+
+        let engine = self::engine(Sandbox::new().max_output_size(8));
+        assert_eq!(render(&engine, "12345678").unwrap(), "12345678");
+        let err = render(&engine, "{% for i in items %}{{ i }}{{ i }}{% endfor %}").unwrap_err();
+        assert!(err.to_string().contains("exceeds 8 bytes"));
+    }
+
+    #[test]
+    fn test_loop_output_is_bounded_while_the_loop_runs() {
+        let engine = engine(Sandbox::new().max_output_size(8).max_loop_iterations(4));
+        // The output passes the limit on the third iteration, before the loop
+        // runs out of iterations.
+        let err = render(&engine, "{% for i in items %}abcd{% endfor %}").unwrap_err();
+        assert!(err.to_string().contains("exceeds 8 bytes"), "{err}");
+    }
+
+    #[test]
+    fn test_width_filters_are_not_allowed_by_default() {
+        let engine = engine(Sandbox::new());
+        for filter in ["center", "ljust", "rjust"] {
+            let err =
+                render(&engine, &format!("{{{{ name|{filter}:\"999999999\" }}}}")).unwrap_err();
+            assert!(
+                matches!(err, DjangoError::TemplateSyntaxError(_)),
+                "{filter}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_filters_are_not_allowed_by_default() {
+        let engine = engine(Sandbox::new());
+        for template in [
+            "{{ name|stringformat:\"999999999s\" }}",
+            "{{ name|stringformat:\".999999999f\" }}",
+            "{{ name|floatformat:999999999 }}",
+        ] {
+            let err = render(&engine, template).unwrap_err();
+            assert!(
+                matches!(err, DjangoError::TemplateSyntaxError(_)),
+                "{template}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timeout() {
+        let engine = engine(Sandbox::new().timeout(Duration::ZERO));
+        let err = render(&engine, "{% for i in items %}{{ i }}{% endfor %}").unwrap_err();
+        assert!(err.to_string().contains("longer than"));
+    }
+
+    #[test]
+    fn test_unsandboxed_engine_is_unrestricted() {
+        let engine = Engine::new();
+        assert_eq!(render(&engine, "{{ name|safe }}").unwrap(), "<b>ada</b>");
+    }
+}
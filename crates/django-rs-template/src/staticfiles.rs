@@ -0,0 +1,199 @@
+//! Static file storage backends used by the `{% static %}` tag.
+//!
+//! A [`StaticFilesStorage`] turns a relative static path such as
+//! `"css/site.css"` into the URL a browser should request. [`StaticStorage`]
+//! simply joins the path onto `STATIC_URL`, while [`ManifestStaticStorage`]
+//! maps each path to its content-hashed name through a `staticfiles.json`
+//! manifest, mirroring Django's `ManifestStaticFilesStorage`.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_template::staticfiles::{ManifestStaticStorage, StaticFilesStorage};
+//!
+//! let storage = ManifestStaticStorage::new("/static/")
+//!     .with_entry("css/site.css", "css/site.55e7cbb9ba48.css");
+//! assert_eq!(
+//!     storage.url("css/site.css").unwrap(),
+//!     "/static/css/site.55e7cbb9ba48.css"
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use django_rs_core::DjangoError;
+
+/// The manifest file name written next to collected static files.
+pub const MANIFEST_NAME: &str = "staticfiles.json";
+
+/// Resolves static file paths to URLs.
+pub trait StaticFilesStorage: Send + Sync {
+    /// Returns the URL prefix static files are served from (`STATIC_URL`).
+    fn base_url(&self) -> &str;
+
+    /// Returns the URL for the static file `name`.
+    fn url(&self, name: &str) -> Result<String, DjangoError>;
+}
+
+/// Joins a relative path onto a URL prefix.
+pub fn join_url(base_url: &str, name: &str) -> String {
+    let name = name.trim_start_matches('/');
+    if base_url.is_empty() || base_url.ends_with('/') {
+        format!("{base_url}{name}")
+    } else {
+        format!("{base_url}/{name}")
+    }
+}
+
+/// Serves static files unchanged from `STATIC_URL`.
+///
+/// This is the equivalent of Django's `StaticFilesStorage`.
+#[derive(Debug, Clone)]
+pub struct StaticStorage {
+    base_url: String,
+}
+
+impl StaticStorage {
+    /// Creates a storage serving files from `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+        }
+    }
+}
+
+impl StaticFilesStorage for StaticStorage {
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, name: &str) -> Result<String, DjangoError> {
+        Ok(join_url(&self.base_url, name))
+    }
+}
+
+/// Serves static files under their content-hashed names.
+///
+/// The manifest maps original paths to hashed paths, as produced by
+/// `collectstatic` with Django's `ManifestStaticFilesStorage`. Paths missing
+/// from the manifest are an error unless strict mode is turned off, in which
+/// case they are served unhashed.
+#[derive(Debug, Clone)]
+pub struct ManifestStaticStorage {
+    base_url: String,
+    paths: HashMap<String, String>,
+    strict: bool,
+}
+
+impl ManifestStaticStorage {
+    /// Creates a storage with an empty manifest.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            paths: HashMap::new(),
+            strict: true,
+        }
+    }
+
+    /// Creates a storage from the JSON contents of a `staticfiles.json` manifest.
+    pub fn from_json(base_url: impl Into<String>, json: &str) -> Result<Self, DjangoError> {
+        let manifest: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+            DjangoError::ImproperlyConfigured(format!("Invalid staticfiles manifest: {e}"))
+        })?;
+        let paths = manifest
+            .get("paths")
+            .and_then(|p| p.as_object())
+            .ok_or_else(|| {
+                DjangoError::ImproperlyConfigured(
+                    "Staticfiles manifest has no \"paths\" object".to_string(),
+                )
+            })?
+            .iter()
+            .filter_map(|(name, hashed)| Some((name.clone(), hashed.as_str()?.to_string())))
+            .collect();
+        Ok(Self {
+            paths,
+            ..Self::new(base_url)
+        })
+    }
+
+    /// Loads the `staticfiles.json` manifest from `static_root`.
+    pub fn load(base_url: impl Into<String>, static_root: &Path) -> Result<Self, DjangoError> {
+        let json = std::fs::read_to_string(static_root.join(MANIFEST_NAME))?;
+        Self::from_json(base_url, &json)
+    }
+
+    /// Adds a manifest entry mapping `name` to `hashed_name`.
+    #[must_use]
+    pub fn with_entry(mut self, name: impl Into<String>, hashed_name: impl Into<String>) -> Self {
+        self.paths.insert(name.into(), hashed_name.into());
+        self
+    }
+
+    /// Sets whether paths missing from the manifest are an error.
+    #[must_use]
+    pub const fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the hashed name for `name`, if it is in the manifest.
+    pub fn hashed_name(&self, name: &str) -> Option<&str> {
+        self.paths.get(name).map(String::as_str)
+    }
+}
+
+impl StaticFilesStorage for ManifestStaticStorage {
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, name: &str) -> Result<String, DjangoError> {
+        let clean = name.trim_start_matches('/');
+        match self.hashed_name(clean) {
+            Some(hashed) => Ok(join_url(&self.base_url, hashed)),
+            None if self.strict => Err(DjangoError::ImproperlyConfigured(format!(
+                "Missing staticfiles manifest entry for '{clean}'"
+            ))),
+            None => Ok(join_url(&self.base_url, clean)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_url() {
+        assert_eq!(join_url("/static/", "css/a.css"), "/static/css/a.css");
+        assert_eq!(join_url("/static", "/css/a.css"), "/static/css/a.css");
+        assert_eq!(
+            join_url("https://cdn.example.com/", "a.js"),
+            "https://cdn.example.com/a.js"
+        );
+    }
+
+    #[test]
+    fn test_manifest_from_json() {
+        let storage = ManifestStaticStorage::from_json(
+            "/static/",
+            r#"{"paths": {"css/site.css": "css/site.abc123.css"}, "version": "1.1"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            storage.url("css/site.css").unwrap(),
+            "/static/css/site.abc123.css"
+        );
+        assert!(ManifestStaticStorage::from_json("/static/", "{}").is_err());
+    }
+
+    #[test]
+    fn test_manifest_missing_entry() {
+        let storage = ManifestStaticStorage::new("/static/");
+        assert!(storage.url("js/app.js").is_err());
+        let lenient = storage.strict(false);
+        assert_eq!(lenient.url("js/app.js").unwrap(), "/static/js/app.js");
+    }
+}
@@ -10,6 +10,7 @@
 //! - `{% if %}` / `{% elif %}` / `{% else %}` / `{% endif %}` — conditional rendering
 //! - `{% for %}` / `{% empty %}` / `{% endfor %}` — iteration with `forloop` context
 //! - `{% ifchanged %}` / `{% endifchanged %}` — render only when value changes
+//! - `{% ifflag "name" %}` / `{% else %}` / `{% endifflag %}` — render when a feature flag is on
 //! - `{% with %}` / `{% endwith %}` — create scoped variable assignments
 //!
 //! ### Template Composition
@@ -74,6 +75,9 @@ pub fn builtin_tag_names() -> Vec<&'static str> {
         "csrf_token",
         "url",
         "static",
+        "media",
+        "get_static_prefix",
+        "get_media_prefix",
         "spaceless",
         "endspaceless",
         "comment",
@@ -90,6 +94,8 @@ pub fn builtin_tag_names() -> Vec<&'static str> {
         "endifequal",
         "ifchanged",
         "endifchanged",
+        "ifflag",
+        "endifflag",
         "autoescape",
         "endautoescape",
         "trans",
@@ -0,0 +1,118 @@
+//! Image thumbnail support for the `{% thumbnail %}` tag.
+//!
+//! `{% thumbnail path "200x200" [crop] [as var] %}` outputs the URL of a
+//! resized variant of the image stored at `path`. A [`ThumbnailBackend`]
+//! configured on the engine decides where variants live and makes sure they
+//! exist; without one, the tag assumes variants were generated ahead of time
+//! and points at [`variant_name`] under `MEDIA_URL`.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_template::thumbnails::{variant_name, ThumbnailSpec};
+//!
+//! let spec = ThumbnailSpec::parse("200x150", true).unwrap();
+//! assert_eq!(variant_name("photos/cat.jpg", &spec), "photos/cat.200x150_crop.jpg");
+//! ```
+
+use django_rs_core::DjangoError;
+
+/// The size and mode of an image variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThumbnailSpec {
+    /// Maximum width in pixels.
+    pub width: u32,
+    /// Maximum height in pixels.
+    pub height: u32,
+    /// Whether to crop to exactly `width` x `height` instead of fitting
+    /// inside it while keeping the aspect ratio.
+    pub crop: bool,
+}
+
+impl ThumbnailSpec {
+    /// Creates a spec.
+    pub const fn new(width: u32, height: u32, crop: bool) -> Self {
+        Self {
+            width,
+            height,
+            crop,
+        }
+    }
+
+    /// Parses a `"WIDTHxHEIGHT"` size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::TemplateSyntaxError`] if the size is malformed
+    /// or either dimension is zero.
+    pub fn parse(size: &str, crop: bool) -> Result<Self, DjangoError> {
+        let invalid = || {
+            DjangoError::TemplateSyntaxError(format!(
+                "Invalid thumbnail size '{size}', expected WIDTHxHEIGHT"
+            ))
+        };
+        let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+        let width: u32 = width.trim().parse().map_err(|_| invalid())?;
+        let height: u32 = height.trim().parse().map_err(|_| invalid())?;
+        if width == 0 || height == 0 {
+            return Err(invalid());
+        }
+        Ok(Self::new(width, height, crop))
+    }
+
+    /// Returns the suffix identifying this variant, e.g. `"200x150_crop"`.
+    pub fn suffix(&self) -> String {
+        if self.crop {
+            format!("{}x{}_crop", self.width, self.height)
+        } else {
+            format!("{}x{}", self.width, self.height)
+        }
+    }
+}
+
+/// Returns the deterministic storage name of a variant of `name`.
+///
+/// The variant is stored alongside the original, with the spec's suffix
+/// inserted before the extension.
+pub fn variant_name(name: &str, spec: &ThumbnailSpec) -> String {
+    let (dir, file) = name.rsplit_once('/').map_or(("", name), |(d, f)| (d, f));
+    let file = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem}.{}.{ext}", spec.suffix()),
+        _ => format!("{file}.{}", spec.suffix()),
+    };
+    if dir.is_empty() {
+        file
+    } else {
+        format!("{dir}/{file}")
+    }
+}
+
+/// Resolves image variants to URLs for the `{% thumbnail %}` tag.
+pub trait ThumbnailBackend: Send + Sync {
+    /// Returns the URL of the `spec` variant of the image `name`, arranging
+    /// for the variant to be generated if it does not exist yet.
+    fn thumbnail_url(&self, name: &str, spec: &ThumbnailSpec) -> Result<String, DjangoError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = ThumbnailSpec::parse("200x100", false).unwrap();
+        assert_eq!(spec, ThumbnailSpec::new(200, 100, false));
+        assert_eq!(spec.suffix(), "200x100");
+        assert!(ThumbnailSpec::parse("200", false).is_err());
+        assert!(ThumbnailSpec::parse("0x100", false).is_err());
+        assert!(ThumbnailSpec::parse("wide x tall", false).is_err());
+    }
+
+    #[test]
+    fn test_variant_name() {
+        let crop = ThumbnailSpec::new(64, 64, true);
+        assert_eq!(variant_name("a/b/cat.png", &crop), "a/b/cat.64x64_crop.png");
+        assert_eq!(variant_name("cat", &crop), "cat.64x64_crop");
+        assert_eq!(variant_name(".hidden", &crop), ".hidden.64x64_crop");
+    }
+}
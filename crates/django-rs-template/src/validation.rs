@@ -0,0 +1,360 @@
+//! Template validation.
+//!
+//! [`validate_templates`] checks every template an [`Engine`] can find without
+//! rendering it, so that mistakes surface in CI instead of at request time.
+//! It reports:
+//!
+//! - syntax errors,
+//! - tags the parser does not know,
+//! - filters that are neither built in nor provided by a registered
+//!   [`Library`](crate::library::Library),
+//! - `{% extends %}` targets that do not exist,
+//! - blocks overridden in a child template but defined in none of its parents.
+//!
+//! The `validatetemplates` management command runs it over the configured
+//! template engines.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_template::engine::Engine;
+//! use django_rs_template::validation::{validate_templates, IssueKind};
+//!
+//! let engine = Engine::new();
+//! engine.add_string_template("base.html", "{% block content %}{% endblock %}");
+//! engine.add_string_template(
+//!     "page.html",
+//!     r#"{% extends "base.html" %}{% block sidebar %}{{ title|shout }}{% endblock %}"#,
+//! );
+//!
+//! let issues = validate_templates(&engine);
+//! let kinds: Vec<IssueKind> = issues.iter().map(|issue| issue.kind).collect();
+//! assert_eq!(kinds, vec![IssueKind::UnknownFilter, IssueKind::UndefinedBlock]);
+//! ```
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use django_rs_core::error::DjangoError;
+
+use crate::engine::Engine;
+use crate::lexer::Token;
+use crate::parser::{self, Node, BUILTIN_TAGS};
+
+/// The kind of problem found in a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IssueKind {
+    /// The template cannot be tokenized or parsed.
+    SyntaxError,
+    /// A `{% tag %}` the parser does not know.
+    UnknownTag,
+    /// A `|filter` that is not registered.
+    UnknownFilter,
+    /// The `{% extends %}` target does not exist.
+    MissingParent,
+    /// A block is overridden but not defined in any parent template.
+    UndefinedBlock,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SyntaxError => "syntax error",
+            Self::UnknownTag => "unknown tag",
+            Self::UnknownFilter => "unknown filter",
+            Self::MissingParent => "missing parent",
+            Self::UndefinedBlock => "undefined block",
+        })
+    }
+}
+
+/// A problem found in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateIssue {
+    /// The name of the template.
+    pub template: String,
+    /// What kind of problem it is.
+    pub kind: IssueKind,
+    /// A human-readable description.
+    pub message: String,
+}
+
+impl fmt::Display for TemplateIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.template, self.message, self.kind)
+    }
+}
+
+/// Validates every template the engine's loaders can find.
+///
+/// Issues are grouped by template, in template name order.
+pub fn validate_templates(engine: &Engine) -> Vec<TemplateIssue> {
+    engine
+        .template_names()
+        .iter()
+        .flat_map(|name| validate_template(engine, name))
+        .collect()
+}
+
+/// Validates a single template.
+///
+/// Unknown tags and syntax errors stop the validation of the template, since
+/// it cannot be parsed. A missing or broken grandparent is reported for the
+/// template that extends it, not for its descendants.
+pub fn validate_template(engine: &Engine, name: &str) -> Vec<TemplateIssue> {
+    let issue = |kind, message| TemplateIssue {
+        template: name.to_string(),
+        kind,
+        message,
+    };
+
+    let tokens = match engine.tokenize_template(name) {
+        Ok(tokens) => tokens,
+        Err(e) => return vec![issue(IssueKind::SyntaxError, e.to_string())],
+    };
+    let unknown_tags = unknown_tags(&tokens);
+    if !unknown_tags.is_empty() {
+        return unknown_tags
+            .into_iter()
+            .map(|tag| issue(IssueKind::UnknownTag, format!("unknown tag '{tag}'")))
+            .collect();
+    }
+    let template = match parser::parse(name, &tokens) {
+        Ok(template) => template,
+        Err(e) => return vec![issue(IssueKind::SyntaxError, e.to_string())],
+    };
+
+    let mut issues = Vec::new();
+    let mut filters = BTreeSet::new();
+    walk(&template.nodes, &mut |node| {
+        if let Node::Variable { filters: calls, .. } = node {
+            filters.extend(calls.iter().map(|call| call.name.clone()));
+        }
+    });
+    for filter in filters.into_iter().filter(|f| !filter_exists(f)) {
+        issues.push(issue(
+            IssueKind::UnknownFilter,
+            format!("unknown filter '{filter}'"),
+        ));
+    }
+
+    // Parents named by a variable are only known at render time.
+    let Some(parent) = literal_parent(&tokens) else {
+        return issues;
+    };
+    if let Err(DjangoError::TemplateDoesNotExist(_)) = engine.tokenize_template(&parent) {
+        issues.push(issue(
+            IssueKind::MissingParent,
+            format!("extends '{parent}', which does not exist"),
+        ));
+    } else if let Some(defined) = ancestor_blocks(engine, &parent) {
+        // Blocks nested in an overriding block are new definitions; only the
+        // outermost ones must exist in a parent.
+        let mut overridden = BTreeSet::new();
+        outer_blocks(&template.nodes, &mut overridden);
+        for block in overridden.difference(&defined) {
+            issues.push(issue(
+                IssueKind::UndefinedBlock,
+                format!("block '{block}' is not defined in any parent template"),
+            ));
+        }
+    }
+    issues
+}
+
+/// Returns the tags used outside `{% verbatim %}` that the parser does not know.
+fn unknown_tags(tokens: &[Token]) -> BTreeSet<String> {
+    let mut unknown = BTreeSet::new();
+    let mut in_verbatim = false;
+    for token in tokens {
+        let Token::Block(tag, _) = token else {
+            continue;
+        };
+        if in_verbatim {
+            in_verbatim = tag != "endverbatim";
+        } else if tag == "verbatim" {
+            in_verbatim = true;
+        } else if !BUILTIN_TAGS.contains(&tag.as_str()) {
+            unknown.insert(tag.clone());
+        }
+    }
+    unknown
+}
+
+/// Returns the `{% extends %}` target if it is a string literal.
+fn literal_parent(tokens: &[Token]) -> Option<String> {
+    tokens.iter().find_map(|token| match token {
+        Token::Block(tag, args) if tag == "extends" => args
+            .first()
+            .filter(|arg| arg.starts_with('"') || arg.starts_with('\''))
+            .map(|arg| parser::strip_quotes(arg)),
+        _ => None,
+    })
+}
+
+/// Returns the blocks defined by `parent` and its ancestors, or `None` if one
+/// of them cannot be loaded or parsed.
+fn ancestor_blocks(engine: &Engine, parent: &str) -> Option<BTreeSet<String>> {
+    let mut blocks = BTreeSet::new();
+    let mut seen = HashSet::new();
+    let mut next = Some(parent.to_string());
+    while let Some(name) = next {
+        if !seen.insert(name.clone()) {
+            break;
+        }
+        let template = engine.get_template(&name).ok()?;
+        walk(&template.nodes, &mut |node| {
+            if let Node::BlockDefNode { name, .. } = node {
+                blocks.insert(name.clone());
+            }
+        });
+        next = template.parent;
+    }
+    Some(blocks)
+}
+
+/// Returns `true` if the filter is built in or provided by a registered library.
+fn filter_exists(name: &str) -> bool {
+    if crate::filters::default_registry().contains(name) {
+        return true;
+    }
+    let libraries = crate::library::global_registry().read().unwrap();
+    libraries
+        .names()
+        .into_iter()
+        .filter_map(|library| libraries.get(library))
+        .any(|library| library.has_filter(name))
+}
+
+/// Collects the names of the blocks not nested in another block.
+fn outer_blocks(nodes: &[Node], blocks: &mut BTreeSet<String>) {
+    for node in nodes {
+        if let Node::BlockDefNode { name, .. } = node {
+            blocks.insert(name.clone());
+        } else {
+            for body in children(node) {
+                outer_blocks(body, blocks);
+            }
+        }
+    }
+}
+
+/// Calls `visit` for every node in the tree, parents before children.
+fn walk(nodes: &[Node], visit: &mut impl FnMut(&Node)) {
+    for node in nodes {
+        visit(node);
+        for body in children(node) {
+            walk(body, visit);
+        }
+    }
+}
+
+/// Returns the node lists nested in a node.
+fn children(node: &Node) -> Vec<&[Node]> {
+    match node {
+        Node::BlockDefNode { content: body, .. }
+        | Node::WithNode { body, .. }
+        | Node::SpacelessNode { body }
+        | Node::AutoescapeNode { body, .. } => vec![body],
+        Node::IfNode { branches } => branches.iter().map(|(_, body)| body.as_slice()).collect(),
+        Node::ForNode {
+            body, empty_body, ..
+        } => vec![body, empty_body],
+        Node::IfEqualNode {
+            body, else_body, ..
+        }
+        | Node::IfChangedNode {
+            body, else_body, ..
+        }
+        | Node::IfFlagNode {
+            body, else_body, ..
+        } => vec![body, else_body],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(issues: &[TemplateIssue]) -> Vec<(&str, IssueKind)> {
+        issues
+            .iter()
+            .map(|issue| (issue.template.as_str(), issue.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_templates_have_no_issues() {
+        let engine = Engine::new();
+        engine.add_string_template(
+            "base.html",
+            "{% block content %}{% if x %}{% block inner %}{% endblock %}{% endif %}{% endblock %}",
+        );
+        engine.add_string_template(
+            "page.html",
+            r#"{% extends "base.html" %}{% block inner %}{{ name|upper }}{% endblock %}"#,
+        );
+        engine.add_string_template("raw.html", "{% verbatim %}{% mytag %}{% endverbatim %}");
+        engine.add_string_template("dynamic.html", "{% extends layout %}");
+        assert_eq!(validate_templates(&engine), vec![]);
+    }
+
+    #[test]
+    fn test_reports_syntax_errors_and_unknown_tags() {
+        let engine = Engine::new();
+        engine.add_string_template("broken.html", "{% if %}{% endif %}");
+        engine.add_string_template("tags.html", "{% mytag %}{% other %}{% mytag %}");
+        let issues = validate_templates(&engine);
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                ("broken.html", IssueKind::SyntaxError),
+                ("tags.html", IssueKind::UnknownTag),
+                ("tags.html", IssueKind::UnknownTag),
+            ]
+        );
+        assert_eq!(issues[1].message, "unknown tag 'mytag'");
+    }
+
+    #[test]
+    fn test_reports_missing_parent_and_undefined_blocks() {
+        let engine = Engine::new();
+        engine.add_string_template("base.html", "{% block content %}{% endblock %}");
+        engine.add_string_template(
+            "middle.html",
+            r#"{% extends "base.html" %}{% block content %}{% block extra %}{% endblock %}{% endblock %}"#,
+        );
+        engine.add_string_template(
+            "leaf.html",
+            r#"{% extends "middle.html" %}{% block extra %}{% endblock %}{% block footer %}{% endblock %}"#,
+        );
+        engine.add_string_template("orphan.html", r#"{% extends "gone.html" %}"#);
+
+        let issues = validate_templates(&engine);
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                ("leaf.html", IssueKind::UndefinedBlock),
+                ("orphan.html", IssueKind::MissingParent),
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "leaf.html: block 'footer' is not defined in any parent template (undefined block)"
+        );
+    }
+
+    #[test]
+    fn test_library_filters_are_known() {
+        let mut library = crate::library::Library::new("validation_test_lib");
+        library.register_filter("validation_test_double", |value, _| value.repeat(2));
+        crate::library::register_library(library);
+
+        let engine = Engine::new();
+        engine.add_string_template("a.html", "{{ x|validation_test_double }}{{ x|nope }}");
+        let issues = validate_templates(&engine);
+        assert_eq!(kinds(&issues), vec![("a.html", IssueKind::UnknownFilter)]);
+        assert_eq!(issues[0].message, "unknown filter 'nope'");
+    }
+}
@@ -1,9 +1,18 @@
-//! Query counting assertion for database tests.
+//! Query assertions for database tests.
 //!
 //! Provides [`assert_num_queries`] which counts the number of SQL queries
 //! executed during an async closure and asserts that the count matches an
 //! expected value. This is essential for detecting N+1 query problems.
 //!
+//! When the count alone isn't enough, [`CaptureQueriesContext`] records the
+//! SQL and parameters of every query executed after it is created, so tests
+//! can match statements by substring or regex, check their order, and filter
+//! by database alias.
+//!
+//! [`assert_index_used`] guards performance-critical queries: it asks the
+//! database for the query's plan with `EXPLAIN` and fails when the table is
+//! read with a sequential scan instead of through the expected index.
+//!
 //! ## Example
 //!
 //! ```rust,no_run
@@ -26,11 +35,222 @@
 //!     .await;
 //! }
 //! ```
+//!
+//! Capturing queries:
+//!
+//! ```rust,no_run
+//! use django_rs_test::test_database::TestDatabase;
+//! use django_rs_test::assert_queries::CaptureQueriesContext;
+//! use django_rs_db::DbExecutor;
+//!
+//! async fn example() {
+//!     let db = TestDatabase::new();
+//!     db.execute_raw("CREATE TABLE blog_comment (id INTEGER PRIMARY KEY)")
+//!         .await
+//!         .unwrap();
+//!
+//!     let ctx = CaptureQueriesContext::new(&db);
+//!     db.query("SELECT * FROM blog_comment", &[]).await.unwrap();
+//!
+//!     assert_eq!(ctx.len(), 1);
+//!     ctx.assert_any_contains("FROM blog_comment");
+//!     ctx.assert_none_matches(r"(?i)^UPDATE");
+//! }
+//! ```
 
+use std::fmt::Write as _;
 use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use django_rs_db::model::Model;
+use django_rs_db::query::compiler::DatabaseBackendType;
+use django_rs_db::value::Value;
+use django_rs_db::{DbExecutor, QuerySet};
+use regex::Regex;
 
 use crate::test_database::TestDatabase;
 
+/// Orders queries across databases, so captures spanning several aliases
+/// keep execution order.
+static QUERY_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// A SQL statement recorded by a [`TestDatabase`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedQuery {
+    /// The alias of the database the query ran against.
+    pub alias: String,
+    /// The SQL text.
+    pub sql: String,
+    /// The bound parameters.
+    pub params: Vec<Value>,
+    sequence: u64,
+}
+
+impl CapturedQuery {
+    /// Records a query, stamping it with the next sequence number.
+    pub(crate) fn new(alias: &str, sql: &str, params: &[Value]) -> Self {
+        Self {
+            alias: alias.to_string(),
+            sql: sql.to_string(),
+            params: params.to_vec(),
+            sequence: QUERY_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Captures the queries executed against one or more test databases.
+///
+/// Only queries executed after the context is created are captured. This
+/// mirrors Django's `CaptureQueriesContext`; the assertion methods panic with
+/// the captured SQL listed, so failures show what actually ran.
+pub struct CaptureQueriesContext<'a> {
+    databases: Vec<(&'a TestDatabase, usize)>,
+}
+
+impl<'a> CaptureQueriesContext<'a> {
+    /// Starts capturing queries executed against `db`.
+    pub fn new(db: &'a TestDatabase) -> Self {
+        Self {
+            databases: vec![(db, db.queries_logged())],
+        }
+    }
+
+    /// Also captures queries executed against `db` from now on.
+    #[must_use]
+    pub fn with_database(mut self, db: &'a TestDatabase) -> Self {
+        self.databases.push((db, db.queries_logged()));
+        self
+    }
+
+    /// Returns the captured queries in execution order.
+    pub fn captured_queries(&self) -> Vec<CapturedQuery> {
+        let mut queries: Vec<CapturedQuery> = self
+            .databases
+            .iter()
+            .flat_map(|(db, start)| db.queries_since(*start))
+            .collect();
+        queries.sort_by_key(|q| q.sequence);
+        queries.dedup_by_key(|q| q.sequence);
+        queries
+    }
+
+    /// Returns the captured queries run against the database `alias`.
+    pub fn for_alias(&self, alias: &str) -> Vec<CapturedQuery> {
+        self.captured_queries()
+            .into_iter()
+            .filter(|q| q.alias == alias)
+            .collect()
+    }
+
+    /// Returns the number of captured queries.
+    pub fn len(&self) -> usize {
+        self.captured_queries().len()
+    }
+
+    /// Returns `true` if no queries were captured.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Asserts that some captured query contains `needle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no captured query contains `needle`.
+    pub fn assert_any_contains(&self, needle: &str) {
+        let queries = self.captured_queries();
+        assert!(
+            queries.iter().any(|q| q.sql.contains(needle)),
+            "No captured query contains {needle:?}.{}",
+            describe(&queries)
+        );
+    }
+
+    /// Asserts that no captured query contains `needle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a captured query contains `needle`.
+    pub fn assert_none_contains(&self, needle: &str) {
+        let queries = self.captured_queries();
+        assert!(
+            !queries.iter().any(|q| q.sql.contains(needle)),
+            "A captured query contains {needle:?}.{}",
+            describe(&queries)
+        );
+    }
+
+    /// Asserts that some captured query matches the regex `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regex or no captured query matches.
+    pub fn assert_any_matches(&self, pattern: &str) {
+        let re = compile(pattern);
+        let queries = self.captured_queries();
+        assert!(
+            queries.iter().any(|q| re.is_match(&q.sql)),
+            "No captured query matches /{pattern}/.{}",
+            describe(&queries)
+        );
+    }
+
+    /// Asserts that no captured query matches the regex `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regex or a captured query matches.
+    pub fn assert_none_matches(&self, pattern: &str) {
+        let re = compile(pattern);
+        let queries = self.captured_queries();
+        assert!(
+            !queries.iter().any(|q| re.is_match(&q.sql)),
+            "A captured query matches /{pattern}/.{}",
+            describe(&queries)
+        );
+    }
+
+    /// Asserts that queries containing each of `needles` ran in that order.
+    ///
+    /// Other queries may run in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the needles cannot be matched, in order, against the
+    /// captured queries.
+    pub fn assert_in_order(&self, needles: &[&str]) {
+        let queries = self.captured_queries();
+        let mut remaining = queries.iter();
+        for needle in needles {
+            assert!(
+                remaining.any(|q| q.sql.contains(needle)),
+                "No captured query containing {needle:?} ran after the previous \
+                 match; expected order {needles:?}.{}",
+                describe(&queries)
+            );
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Regex {
+    Regex::new(pattern).unwrap_or_else(|e| panic!("Invalid query pattern /{pattern}/: {e}"))
+}
+
+/// Lists captured queries for assertion messages.
+fn describe(queries: &[CapturedQuery]) -> String {
+    if queries.is_empty() {
+        return " No queries were captured.".to_string();
+    }
+    let mut out = String::from(" Captured queries were:");
+    for (i, q) in queries.iter().enumerate() {
+        let _ = write!(out, "\n{}. [{}] {}", i + 1, q.alias, q.sql);
+        if !q.params.is_empty() {
+            let _ = write!(out, " -- params: {:?}", q.params);
+        }
+    }
+    out
+}
+
 /// Asserts that exactly `expected_count` SQL queries are executed during the
 /// async closure.
 ///
@@ -77,10 +297,89 @@ where
     );
 }
 
+/// Returns whether a line of a query plan reads `table` sequentially.
+fn is_sequential_scan(line: &str, backend: DatabaseBackendType, table: &str) -> bool {
+    let scanned = match backend {
+        // SQLite: "SCAN blog_post" (or "SCAN TABLE blog_post" before 3.36),
+        // while index reads say "SEARCH ..." or "SCAN ... USING INDEX".
+        DatabaseBackendType::SQLite => line
+            .trim()
+            .strip_prefix("SCAN ")
+            .filter(|rest| !rest.contains(" USING "))
+            .map(|rest| rest.strip_prefix("TABLE ").unwrap_or(rest)),
+        // PostgreSQL: "Seq Scan on blog_post  (cost=...)" or "Seq Scan on blog_post t".
+        DatabaseBackendType::PostgreSQL | DatabaseBackendType::MySQL => {
+            line.split_once("Seq Scan on ").map(|(_, rest)| rest)
+        }
+    };
+    scanned.is_some_and(|rest| {
+        rest.split_whitespace()
+            .next()
+            .is_some_and(|name| name.trim_matches('"') == table)
+    })
+}
+
+/// Checks a query plan reads `table` through `index_name` and never with a
+/// sequential scan, returning the reason when it does not.
+fn check_index_used(
+    plan: &str,
+    backend: DatabaseBackendType,
+    table: &str,
+    index_name: &str,
+) -> Result<(), String> {
+    if let Some(line) = plan
+        .lines()
+        .find(|line| is_sequential_scan(line, backend, table))
+    {
+        return Err(format!(
+            "Expected the query to use index '{index_name}', but it scans '{table}' \
+             sequentially ({}).\nQuery plan:\n{plan}",
+            line.trim()
+        ));
+    }
+    if !plan.contains(index_name) {
+        return Err(format!(
+            "Expected the query to use index '{index_name}', but the plan does not \
+             mention it.\nQuery plan:\n{plan}"
+        ));
+    }
+    Ok(())
+}
+
+/// Asserts that the database answers `queryset` through the index
+/// `index_name`, without a sequential scan of the model's table.
+///
+/// The plan comes from [`QuerySet::explain`]. Supported on SQLite and
+/// PostgreSQL. PostgreSQL prefers sequential scans for the small tables
+/// typical of tests, so run `SET enable_seqscan = off` on the connection
+/// first to see the plan it would use on a large table.
+///
+/// # Panics
+///
+/// Panics with the query plan if the index is not used, if the table is
+/// scanned sequentially, if `EXPLAIN` fails, or on MySQL.
+pub async fn assert_index_used<M: Model>(
+    db: &dyn DbExecutor,
+    queryset: &QuerySet<M>,
+    index_name: &str,
+) {
+    let backend = db.backend_type();
+    assert!(
+        backend != DatabaseBackendType::MySQL,
+        "assert_index_used supports PostgreSQL and SQLite only"
+    );
+    let plan = queryset
+        .explain(db)
+        .await
+        .unwrap_or_else(|e| panic!("EXPLAIN failed: {e}"));
+    if let Err(message) = check_index_used(&plan, backend, M::table_name(), index_name) {
+        panic!("{message}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use django_rs_db::value::Value;
     use django_rs_db::DbExecutor;
 
     #[tokio::test]
@@ -200,4 +499,194 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_capture_records_sql_and_params() {
+        let db = TestDatabase::new();
+        db.execute_raw("CREATE TABLE cq (id INTEGER PRIMARY KEY, val TEXT)")
+            .await
+            .unwrap();
+
+        let ctx = CaptureQueriesContext::new(&db);
+        assert!(ctx.is_empty());
+        db.execute_sql("INSERT INTO cq (val) VALUES (?)", &[Value::from("a")])
+            .await
+            .unwrap();
+        db.query("SELECT * FROM cq", &[]).await.unwrap();
+
+        let queries = ctx.captured_queries();
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0].sql, "INSERT INTO cq (val) VALUES (?)");
+        assert_eq!(queries[0].params, vec![Value::from("a")]);
+        assert_eq!(queries[0].alias, "default");
+        ctx.assert_any_contains("SELECT * FROM cq");
+        ctx.assert_none_contains("CREATE TABLE");
+        ctx.assert_any_matches(r"^INSERT INTO \w+");
+        ctx.assert_none_matches(r"(?i)delete");
+        ctx.assert_in_order(&["INSERT", "SELECT"]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Captured queries were:\n1. [default] SELECT * FROM blog_comment")]
+    async fn test_capture_failure_lists_queries() {
+        let db = TestDatabase::new();
+        db.execute_raw("CREATE TABLE blog_comment (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+
+        let ctx = CaptureQueriesContext::new(&db);
+        db.query("SELECT * FROM blog_comment", &[]).await.unwrap();
+        ctx.assert_none_contains("blog_comment");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "expected order [\"SELECT\", \"INSERT\"]")]
+    async fn test_capture_order_assertion_fails() {
+        let db = TestDatabase::new();
+        db.execute_raw("CREATE TABLE co (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+
+        let ctx = CaptureQueriesContext::new(&db);
+        db.execute_sql("INSERT INTO co (id) VALUES (?)", &[Value::from(1)])
+            .await
+            .unwrap();
+        db.query("SELECT * FROM co", &[]).await.unwrap();
+        ctx.assert_in_order(&["SELECT", "INSERT"]);
+    }
+
+    #[tokio::test]
+    async fn test_capture_filters_by_alias() {
+        let primary = TestDatabase::new();
+        let replica = TestDatabase::new().with_alias("replica");
+        for db in [&primary, &replica] {
+            db.execute_raw("CREATE TABLE ca (id INTEGER PRIMARY KEY)")
+                .await
+                .unwrap();
+        }
+
+        let ctx = CaptureQueriesContext::new(&primary).with_database(&replica);
+        replica.query("SELECT * FROM ca", &[]).await.unwrap();
+        primary
+            .execute_sql("INSERT INTO ca (id) VALUES (?)", &[Value::from(1)])
+            .await
+            .unwrap();
+
+        assert_eq!(ctx.len(), 2);
+        ctx.assert_in_order(&["SELECT", "INSERT"]);
+        let replica_queries = ctx.for_alias("replica");
+        assert_eq!(replica_queries.len(), 1);
+        assert_eq!(replica_queries[0].sql, "SELECT * FROM ca");
+        assert_eq!(ctx.for_alias("default").len(), 1);
+    }
+
+    // ── assert_index_used ───────────────────────────────────────────
+
+    struct Post;
+
+    impl Model for Post {
+        fn meta() -> &'static django_rs_db::model::ModelMeta {
+            use std::sync::LazyLock;
+            static META: LazyLock<django_rs_db::model::ModelMeta> =
+                LazyLock::new(|| django_rs_db::model::ModelMeta {
+                    app_label: "blog",
+                    model_name: "post",
+                    db_table: "blog_post".to_string(),
+                    verbose_name: "post".to_string(),
+                    verbose_name_plural: "posts".to_string(),
+                    ordering: vec![],
+                    unique_together: vec![],
+                    indexes: vec![],
+                    abstract_model: false,
+                    fields: vec![],
+                    constraints: vec![],
+                    inheritance_type: django_rs_db::query::compiler::InheritanceType::None,
+                    db_schema: None,
+                });
+            &META
+        }
+
+        fn table_name() -> &'static str {
+            "blog_post"
+        }
+
+        fn app_label() -> &'static str {
+            "blog"
+        }
+
+        fn pk(&self) -> Option<&Value> {
+            None
+        }
+
+        fn set_pk(&mut self, _value: Value) {}
+
+        fn field_values(&self) -> Vec<(&'static str, Value)> {
+            vec![]
+        }
+
+        fn from_row(
+            _row: &django_rs_db::query::compiler::Row,
+        ) -> django_rs_core::DjangoResult<Self> {
+            Ok(Self)
+        }
+    }
+
+    async fn post_database() -> TestDatabase {
+        let db = TestDatabase::new();
+        db.execute_raw("CREATE TABLE blog_post (id INTEGER PRIMARY KEY, slug TEXT, body TEXT)")
+            .await
+            .unwrap();
+        db.execute_raw("CREATE INDEX blog_post_slug_idx ON blog_post (slug)")
+            .await
+            .unwrap();
+        db
+    }
+
+    fn posts_where(field: &str) -> QuerySet<Post> {
+        use django_rs_db::query::lookups::{Lookup, Q};
+        django_rs_db::Manager::<Post>::new()
+            .all()
+            .filter(Q::filter(field, Lookup::Exact(Value::from("hello"))))
+    }
+
+    #[test]
+    fn test_check_index_used_postgres_plans() {
+        let pg = DatabaseBackendType::PostgreSQL;
+        let index_scan = "Index Scan using blog_post_slug_idx on blog_post  (cost=0.15..8.17 rows=1 width=72)\n  Index Cond: (slug = 'hello'::text)";
+        assert!(check_index_used(index_scan, pg, "blog_post", "blog_post_slug_idx").is_ok());
+
+        let seq_scan = "Seq Scan on blog_post  (cost=0.00..1.01 rows=1 width=72)\n  Filter: (slug = 'hello'::text)";
+        let err = check_index_used(seq_scan, pg, "blog_post", "blog_post_slug_idx").unwrap_err();
+        assert!(err.contains("scans 'blog_post' sequentially"));
+        assert!(err.contains("Seq Scan on blog_post"));
+
+        let other_index = "Index Scan using blog_post_pkey on blog_post";
+        let err = check_index_used(other_index, pg, "blog_post", "blog_post_slug_idx").unwrap_err();
+        assert!(err.contains("does not mention it"));
+    }
+
+    #[test]
+    fn test_check_index_used_ignores_scans_of_other_tables() {
+        let plan = "SCAN auth_user\nSEARCH blog_post USING INDEX blog_post_slug_idx (slug=?)";
+        assert!(check_index_used(
+            plan,
+            DatabaseBackendType::SQLite,
+            "blog_post",
+            "blog_post_slug_idx"
+        )
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_assert_index_used_passes_on_indexed_lookup() {
+        let db = post_database().await;
+        assert_index_used(&db, &posts_where("slug"), "blog_post_slug_idx").await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "scans 'blog_post' sequentially")]
+    async fn test_assert_index_used_fails_on_sequential_scan() {
+        let db = post_database().await;
+        assert_index_used(&db, &posts_where("body"), "blog_post_slug_idx").await;
+    }
 }
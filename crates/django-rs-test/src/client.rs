@@ -22,6 +22,7 @@
 //! ```
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use axum::Router;
 use bytes::Bytes;
@@ -31,6 +32,7 @@ use tower::ServiceExt;
 
 use django_rs_auth::user::AbstractUser;
 use django_rs_core::DjangoError;
+use django_rs_http::services::Services;
 use django_rs_views::SessionData;
 
 /// A test client for making simulated HTTP requests against an Axum application.
@@ -43,6 +45,7 @@ use django_rs_views::SessionData;
 /// - **`force_login`** - Simulate an authenticated user without going through login.
 /// - **`enforce_csrf_checks`** - Toggle CSRF enforcement for testing.
 /// - **`cookies`** - Inspect the accumulated cookie jar.
+/// - **`override_service`** - Replace an application service with a mock.
 pub struct TestClient {
     app: Router,
     cookies: HashMap<String, String>,
@@ -53,6 +56,8 @@ pub struct TestClient {
     enforce_csrf: bool,
     /// The currently logged-in user, if any.
     logged_in_user: Option<AbstractUser>,
+    /// Services that replace the application's services of the same types.
+    service_overrides: Services,
 }
 
 impl TestClient {
@@ -64,6 +69,7 @@ impl TestClient {
             session: SessionData::new("test-session".to_string()),
             enforce_csrf: false,
             logged_in_user: None,
+            service_overrides: Services::new(),
         }
     }
 
@@ -119,6 +125,23 @@ impl TestClient {
         self.enforce_csrf
     }
 
+    /// Replaces the application service of type `T` with `service` for
+    /// subsequent requests.
+    ///
+    /// The override travels with each request, so a
+    /// [`DjangoApp`](django_rs_views::server::DjangoApp) built exactly as in
+    /// production hands the mock to its views through
+    /// [`HttpRequest::service`](django_rs_http::HttpRequest::service).
+    pub fn override_service<T: ?Sized + Send + Sync + 'static>(&mut self, service: Arc<T>) {
+        self.service_overrides.insert(service);
+    }
+
+    /// Removes all service overrides, so the application's own services are
+    /// used again.
+    pub fn clear_service_overrides(&mut self) {
+        self.service_overrides = Services::new();
+    }
+
     /// Returns a reference to the cookie jar.
     ///
     /// Contains all cookies set manually and those received from responses.
@@ -274,7 +297,10 @@ impl TestClient {
     }
 
     /// Sends the request through the Axum router and builds a `TestResponse`.
-    async fn send(&mut self, req: Request<axum::body::Body>) -> TestResponse {
+    async fn send(&mut self, mut req: Request<axum::body::Body>) -> TestResponse {
+        if !self.service_overrides.is_empty() {
+            req.extensions_mut().insert(self.service_overrides.clone());
+        }
         let response = self
             .app
             .clone()
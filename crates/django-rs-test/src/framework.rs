@@ -10,12 +10,16 @@
 //! - [`assert_redirects`] - Assert response is a redirect to a specific URL
 //! - [`assert_template_used`] - Assert a specific template was used (checks header)
 //! - [`assert_form_error`] - Assert form validation errors are present
+//! - [`assert_html_equal`] - Assert two HTML documents are equal, ignoring formatting
+//! - [`assert_in_html`] - Assert an HTML fragment occurs in an HTML document
+//! - [`select`] - Query the response body with a CSS selector
 
 use std::collections::HashMap;
 
 use axum::Router;
 
 use crate::client::{TestClient, TestResponse};
+use crate::html::{parse_html, Element};
 
 /// A test case that provides a test client and settings overrides.
 ///
@@ -184,6 +188,102 @@ pub fn assert_not_has_header(response: &TestResponse, header_name: &str) {
     );
 }
 
+/// Asserts that two HTML documents are equal, ignoring whitespace,
+/// attribute order and other formatting. See [`crate::html`] for the rules.
+///
+/// This mirrors Django's `assertHTMLEqual`.
+///
+/// # Panics
+///
+/// Panics if either argument is not valid HTML or the documents differ.
+pub fn assert_html_equal(actual: &str, expected: &str) {
+    let actual_dom = parse_html(actual)
+        .unwrap_or_else(|e| panic!("First argument is not valid HTML: {e}\n{actual}"));
+    let expected_dom = parse_html(expected)
+        .unwrap_or_else(|e| panic!("Second argument is not valid HTML: {e}\n{expected}"));
+    assert!(
+        actual_dom == expected_dom,
+        "HTML is not equal.\nActual:\n{actual_dom}\nExpected:\n{expected_dom}"
+    );
+}
+
+/// Asserts that two HTML documents are not equal.
+///
+/// # Panics
+///
+/// Panics if either argument is not valid HTML or the documents are equal.
+pub fn assert_html_not_equal(actual: &str, expected: &str) {
+    let actual_dom = parse_html(actual)
+        .unwrap_or_else(|e| panic!("First argument is not valid HTML: {e}\n{actual}"));
+    let expected_dom = parse_html(expected)
+        .unwrap_or_else(|e| panic!("Second argument is not valid HTML: {e}\n{expected}"));
+    assert!(
+        actual_dom != expected_dom,
+        "HTML is unexpectedly equal:\n{actual_dom}"
+    );
+}
+
+/// Asserts that the HTML fragment `needle` occurs in `haystack`, exactly
+/// `count` times if given, or at least once otherwise.
+///
+/// Elements are compared as in [`assert_html_equal`], so the fragment
+/// matches however the page is formatted. This mirrors Django's
+/// `assertInHTML`.
+///
+/// # Panics
+///
+/// Panics if either argument is not valid HTML or the count does not match.
+pub fn assert_in_html(needle: &str, haystack: &str, count: Option<usize>) {
+    let needle_dom = parse_html(needle)
+        .unwrap_or_else(|e| panic!("First argument is not valid HTML: {e}\n{needle}"));
+    let haystack_dom = parse_html(haystack)
+        .unwrap_or_else(|e| panic!("Second argument is not valid HTML: {e}\n{haystack}"));
+    let real_count = haystack_dom.count(&needle_dom);
+    match count {
+        Some(count) => assert_eq!(
+            real_count, count,
+            "Found {real_count} instances of '{needle_dom}' in the HTML (expected {count}).\n\
+             HTML:\n{haystack_dom}"
+        ),
+        None => assert!(
+            real_count > 0,
+            "Couldn't find '{needle_dom}' in the HTML.\nHTML:\n{haystack_dom}"
+        ),
+    }
+}
+
+/// Returns the elements of the response body that match a CSS selector, in
+/// document order.
+///
+/// See [`crate::html`] for the supported selectors.
+///
+/// ```
+/// use django_rs_test::client::TestResponse;
+/// use django_rs_test::framework::select;
+///
+/// # let response = TestResponse {
+/// #     status: http::StatusCode::OK,
+/// #     headers: http::HeaderMap::new(),
+/// #     body: br#"<ul id="items"><li class="done">Write</li><li>Test</li></ul>"#.to_vec(),
+/// #     cookies: std::collections::HashMap::new(),
+/// # };
+/// let items = select(&response, "#items > li");
+/// assert_eq!(items.len(), 2);
+/// assert_eq!(items[1].text(), "Test");
+/// assert!(items[0].has_class("done"));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the body is not valid HTML or the selector is invalid.
+pub fn select(response: &TestResponse, selector: &str) -> Vec<Element> {
+    let body = response.text();
+    parse_html(&body)
+        .unwrap_or_else(|e| panic!("Response body is not valid HTML: {e}\n{body}"))
+        .select(selector)
+        .unwrap_or_else(|e| panic!("{e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,6 +476,67 @@ mod tests {
         assert_not_has_header(&response, "x-custom");
     }
 
+    // ── HTML assertion tests ──────────────────────────────────────────
+
+    #[test]
+    fn test_assert_html_equal_passes() {
+        assert_html_equal(
+            r#"<p class="b a">Hi <br/><em>there</em></p>"#,
+            "<p class='a b'>\n  Hi <br> <em>there</em>\n</p>",
+        );
+        assert_html_not_equal("<p>Hi</p>", "<p>Bye</p>");
+    }
+
+    #[test]
+    #[should_panic(expected = "HTML is not equal")]
+    fn test_assert_html_equal_fails() {
+        assert_html_equal("<p>Hi</p>", "<p><b>Hi</b></p>");
+    }
+
+    #[test]
+    #[should_panic(expected = "First argument is not valid HTML")]
+    fn test_assert_html_equal_invalid() {
+        assert_html_equal("<p>Hi</b>", "<p>Hi</p>");
+    }
+
+    #[test]
+    fn test_assert_in_html_passes() {
+        let page = r#"<table><tr><td>1</td><td class="total">10</td></tr><tr><td class="total">10</td></tr></table>"#;
+        assert_in_html("<td class='total'>10</td>", page, None);
+        assert_in_html("<td class='total'> 10 </td>", page, Some(2));
+        assert_in_html("<td>2</td>", page, Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Couldn't find")]
+    fn test_assert_in_html_fails() {
+        assert_in_html("<td>2</td>", "<table><tr><td>1</td></tr></table>", None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Found 1 instances")]
+    fn test_assert_in_html_fails_count() {
+        assert_in_html("<td>1</td>", "<table><tr><td>1</td></tr></table>", Some(2));
+    }
+
+    #[test]
+    fn test_select() {
+        let body = r#"<form><input name="q" value="rust"><button disabled>Go</button></form>"#;
+        let response = make_response(StatusCode::OK, body, vec![]);
+        let inputs = select(&response, "form input[name=q]");
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].attr("value"), Some("rust"));
+        assert_eq!(select(&response, "button[disabled]")[0].text(), "Go");
+        assert!(select(&response, "textarea").is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid CSS selector")]
+    fn test_select_invalid_selector() {
+        let response = make_response(StatusCode::OK, "<p></p>", vec![]);
+        select(&response, "p >");
+    }
+
     // ── Integration test with TestClient ──────────────────────────────
 
     #[tokio::test]
@@ -0,0 +1,868 @@
+//! A lenient HTML parser for DOM-aware test assertions.
+//!
+//! This mirrors Django's `django.test.html`: HTML is parsed into a tree of
+//! [`Element`]s and text, and two trees compare equal when they have the
+//! same structure regardless of formatting:
+//!
+//! - Runs of whitespace are collapsed, and whitespace at the start and end
+//!   of an element's content is ignored.
+//! - Attribute order does not matter, nor does the order of the classes in
+//!   a `class` attribute.
+//! - A bare attribute equals one with an empty value, and a boolean
+//!   attribute also equals one whose value is its name, so `<input checked>`
+//!   equals `<input checked="checked">`.
+//! - Character references are decoded, and comments are dropped.
+//!
+//! Elements can also be looked up with CSS selectors via
+//! [`Element::select`]. Type, `#id`, `.class`, `*` and attribute selectors
+//! (`[a]`, `[a=v]`, `[a~=v]`, `[a^=v]`, `[a$=v]`, `[a*=v]`) are supported,
+//! combined with the descendant and child (`>`) combinators and grouped
+//! with commas.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_test::html::parse_html;
+//!
+//! let page = parse_html(r#"<ul class="nav"><li><a href="/">Home</a></li></ul>"#).unwrap();
+//! let expected = parse_html("<ul  class='nav'>\n  <li> <a href='/'>Home</a> </li>\n</ul>").unwrap();
+//! assert_eq!(page, expected);
+//!
+//! let links = page.select("ul.nav > li a").unwrap();
+//! assert_eq!(links[0].attr("href"), Some("/"));
+//! assert_eq!(links[0].text(), "Home");
+//! ```
+
+use std::fmt;
+
+/// Elements that never have content or an end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Attributes whose presence alone is their value.
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+    "truespeed",
+];
+
+/// Elements whose content is raw text rather than markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// An error from parsing HTML or a CSS selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlError {
+    message: String,
+}
+
+impl HtmlError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for HtmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for HtmlError {}
+
+/// A node of a parsed HTML tree.
+#[derive(Debug, Clone)]
+pub enum Node {
+    /// An element.
+    Element(Element),
+    /// Text, with character references decoded and whitespace collapsed.
+    Text(String),
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Element(a), Self::Element(b)) => a == b,
+            (Self::Text(a), Self::Text(b)) => a.trim() == b.trim(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Node {}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Element(element) => element.fmt(f),
+            Self::Text(text) => f.write_str(text.trim()),
+        }
+    }
+}
+
+/// An HTML element.
+///
+/// [`parse_html`] returns a root element with an empty name, whose children
+/// are the top-level nodes of the document.
+#[derive(Debug, Clone)]
+pub struct Element {
+    name: String,
+    /// Sorted by name; bare attributes have an empty value.
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn new(name: String, mut attributes: Vec<(String, String)>) -> Self {
+        for (attr, value) in &mut attributes {
+            if BOOLEAN_ATTRIBUTES.contains(&attr.as_str()) && value.eq_ignore_ascii_case(attr) {
+                value.clear();
+            } else if attr == "class" {
+                let mut classes: Vec<&str> = value.split_ascii_whitespace().collect();
+                classes.sort_unstable();
+                classes.dedup();
+                *value = classes.join(" ");
+            }
+        }
+        attributes.sort_by(|a, b| a.0.cmp(&b.0));
+        Self {
+            name,
+            attributes,
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns the tag name, in lowercase. The root of a parsed document has
+    /// an empty name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value of an attribute, or `None` if it is not set.
+    ///
+    /// Bare attributes have an empty value, and the classes of `class` are
+    /// sorted.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attr, _)| attr.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the attributes, sorted by name.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes
+            .iter()
+            .map(|(attr, value)| (attr.as_str(), value.as_str()))
+    }
+
+    /// Returns `true` if the element has the given class.
+    pub fn has_class(&self, class: &str) -> bool {
+        self.attr("class")
+            .is_some_and(|classes| classes.split(' ').any(|c| c == class))
+    }
+
+    /// Returns the child nodes.
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// Returns the text content of the element and its descendants, with
+    /// whitespace collapsed and trimmed.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        collapse_whitespace(&text).trim().to_string()
+    }
+
+    fn collect_text(&self, out: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(element) => element.collect_text(out),
+            }
+        }
+    }
+
+    /// Returns the descendants matching a CSS selector, in document order.
+    pub fn select(&self, selector: &str) -> Result<Vec<Self>, HtmlError> {
+        let selector = SelectorList::parse(selector)?;
+        let mut found = Vec::new();
+        let mut ancestors = vec![self];
+        self.select_into(&selector, &mut ancestors, &mut found);
+        Ok(found)
+    }
+
+    fn select_into<'a>(
+        &'a self,
+        selector: &SelectorList,
+        ancestors: &mut Vec<&'a Self>,
+        found: &mut Vec<Self>,
+    ) {
+        for child in &self.children {
+            if let Node::Element(element) = child {
+                if selector.matches(element, ancestors) {
+                    found.push(element.clone());
+                }
+                ancestors.push(element);
+                element.select_into(selector, ancestors, found);
+                ancestors.pop();
+            }
+        }
+    }
+
+    /// Returns how many times `needle` occurs in this element, following
+    /// Django's `assertInHTML`.
+    ///
+    /// `needle` is usually the root returned by [`parse_html`]. A text
+    /// needle counts its occurrences in text nodes. A needle of several
+    /// top-level nodes counts the runs of consecutive siblings equal to
+    /// them.
+    pub fn count(&self, needle: &Self) -> usize {
+        if needle.name.is_empty() {
+            let needle: Vec<&Node> = needle.significant_children().collect();
+            self.count_nodes(&needle)
+        } else {
+            self.count_nodes(&[&Node::Element(needle.clone())])
+        }
+    }
+
+    fn count_nodes(&self, needle: &[&Node]) -> usize {
+        match needle {
+            [] => return 0,
+            [Node::Element(element)] if self == element => return 1,
+            [_, _, ..] if self.significant_children().eq(needle.iter().copied()) => return 1,
+            _ => {}
+        }
+        let mut count = 0;
+        let mut matched = 0;
+        for child in self.significant_children() {
+            match child {
+                Node::Text(text) => {
+                    if let [Node::Text(needle)] = needle {
+                        count += text.trim().matches(needle.trim()).count();
+                    }
+                }
+                Node::Element(element) => {
+                    count += element.count_nodes(needle);
+                    if needle.len() > 1 {
+                        if *needle[matched] == *child {
+                            matched += 1;
+                            if matched == needle.len() {
+                                count += 1;
+                                matched = 0;
+                            }
+                        } else {
+                            matched = 0;
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Returns the children that take part in comparisons, leaving out
+    /// whitespace-only text.
+    fn significant_children(&self) -> impl Iterator<Item = &Node> {
+        self.children
+            .iter()
+            .filter(|child| !matches!(child, Node::Text(text) if text.trim().is_empty()))
+    }
+}
+
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.attributes == other.attributes
+            && self.significant_children().eq(other.significant_children())
+    }
+}
+
+impl Eq for Element {}
+
+/// Writes the element as normalized HTML.
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.name.is_empty() {
+            write!(f, "<{}", self.name)?;
+            for (attr, value) in &self.attributes {
+                if value.is_empty() {
+                    write!(f, " {attr}")?;
+                } else {
+                    write!(f, " {attr}=\"{}\"", value.replace('"', "&quot;"))?;
+                }
+            }
+            f.write_str(">")?;
+            if VOID_ELEMENTS.contains(&self.name.as_str()) {
+                return Ok(());
+            }
+        }
+        for (i, child) in self.significant_children().enumerate() {
+            if i > 0 && self.name.is_empty() {
+                f.write_str("\n")?;
+            }
+            child.fmt(f)?;
+        }
+        if !self.name.is_empty() {
+            write!(f, "</{}>", self.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses an HTML document or fragment.
+///
+/// The parser is lenient: unclosed elements are closed at the end of their
+/// parent or of the input. An end tag without a matching open element is
+/// an error.
+pub fn parse_html(html: &str) -> Result<Element, HtmlError> {
+    Parser {
+        input: html,
+        pos: 0,
+        stack: vec![Element::new(String::new(), Vec::new())],
+    }
+    .parse()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// The open elements, starting with the root.
+    stack: Vec<Element>,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<Element, HtmlError> {
+        while self.pos < self.input.len() {
+            let rest = &self.input[self.pos..];
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                self.pos += 4 + comment.find("-->").map_or(comment.len(), |end| end + 3);
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                self.pos += rest.find('>').map_or(rest.len(), |end| end + 1);
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.tag_name();
+                self.pos += self.input[self.pos..]
+                    .find('>')
+                    .map_or(self.input.len() - self.pos, |end| end + 1);
+                self.close(&name)?;
+            } else if rest.starts_with('<')
+                && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                self.pos += 1;
+                self.start_tag()?;
+            } else {
+                // A `<` that starts no tag is text.
+                let skip = usize::from(rest.starts_with('<'));
+                let end = rest[skip..].find('<').map_or(rest.len(), |end| end + skip);
+                self.text(&decode_entities(&rest[..end]));
+                self.pos += end;
+            }
+        }
+        while self.stack.len() > 1 {
+            self.pop();
+        }
+        Ok(self.stack.pop().expect("the root is never closed"))
+    }
+
+    fn tag_name(&mut self) -> String {
+        let rest = &self.input[self.pos..];
+        let end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .unwrap_or(rest.len());
+        self.pos += end;
+        rest[..end].to_ascii_lowercase()
+    }
+
+    fn start_tag(&mut self) -> Result<(), HtmlError> {
+        let name = self.tag_name();
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        let self_closing = loop {
+            self.skip_whitespace();
+            let rest = &self.input[self.pos..];
+            if rest.is_empty() {
+                return Err(HtmlError::new(format!("Unterminated start tag <{name}>")));
+            }
+            if let Some(after) = rest.strip_prefix("/>") {
+                self.pos = self.input.len() - after.len();
+                break true;
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break false;
+            }
+            if rest.starts_with('/') {
+                self.pos += 1;
+                continue;
+            }
+            let (attr, value) = self.attribute();
+            if !attributes.iter().any(|(existing, _)| *existing == attr) {
+                attributes.push((attr, value));
+            }
+        };
+
+        let element = Element::new(name, attributes);
+        if self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+            self.current().children.push(Node::Element(element));
+            return Ok(());
+        }
+        let raw_text = RAW_TEXT_ELEMENTS.contains(&element.name.as_str());
+        let end_tag = format!("</{}", element.name);
+        self.stack.push(element);
+        if raw_text {
+            let rest = &self.input[self.pos..];
+            let end = rest
+                .to_ascii_lowercase()
+                .find(&end_tag)
+                .unwrap_or(rest.len());
+            if !rest[..end].is_empty() {
+                self.current()
+                    .children
+                    .push(Node::Text(rest[..end].to_string()));
+            }
+            self.pos += end;
+        }
+        Ok(())
+    }
+
+    fn attribute(&mut self) -> (String, String) {
+        let rest = &self.input[self.pos..];
+        let end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let name = rest[..end].to_ascii_lowercase();
+        self.pos += end;
+        self.skip_whitespace();
+        if !self.input[self.pos..].starts_with('=') {
+            return (name, String::new());
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let value = if let Some(quote @ ('"' | '\'')) = rest.chars().next() {
+            let end = rest[1..].find(quote).map_or(rest.len(), |end| end + 1);
+            self.pos += (end + 1).min(rest.len());
+            &rest[1..end]
+        } else {
+            let end = rest
+                .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                .unwrap_or(rest.len());
+            self.pos += end;
+            &rest[..end]
+        };
+        (name, decode_entities(value))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn current(&mut self) -> &mut Element {
+        self.stack.last_mut().expect("the root is never closed")
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = collapse_whitespace(text);
+        let children = &mut self.current().children;
+        if let Some(Node::Text(previous)) = children.last_mut() {
+            *previous = collapse_whitespace(&(previous.clone() + &text));
+        } else {
+            children.push(Node::Text(text));
+        }
+    }
+
+    /// Closes the innermost open element named `name`, and any elements
+    /// opened inside it.
+    fn close(&mut self, name: &str) -> Result<(), HtmlError> {
+        let Some(open) = self.stack[1..].iter().rposition(|e| e.name == name) else {
+            return Err(HtmlError::new(format!("Unexpected end tag </{name}>")));
+        };
+        while self.stack.len() > open + 1 {
+            self.pop();
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self) {
+        let element = self.stack.pop().expect("the root is never closed");
+        self.current().children.push(Node::Element(element));
+    }
+}
+
+/// Replaces runs of whitespace with a single space.
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+/// Decodes numeric and common named character references. Unknown
+/// references are kept as they are.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity.strip_prefix('#').and_then(|number| {
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => number.parse().ok()?,
+                    };
+                    char::from_u32(code)
+                }),
+            };
+            c.map(|c| (c, end + 1))
+        });
+        if let Some((c, len)) = decoded {
+            out.push(c);
+            rest = &rest[len..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Comma-separated selectors; an element matches if any of them does.
+struct SelectorList(Vec<Selector>);
+
+/// Compound selectors joined by combinators, e.g. `ul.nav > li a`.
+struct Selector {
+    /// The rightmost compound selector, which the element itself matches.
+    subject: Compound,
+    /// The compound selectors to its left, nearest first, each with the
+    /// combinator that joins it to the one on its right.
+    ancestors: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<AttributeSelector>,
+}
+
+struct AttributeSelector {
+    name: String,
+    /// The operator (`=`, `~=`, `^=`, `$=` or `*=`) and value, if any.
+    test: Option<(String, String)>,
+}
+
+impl SelectorList {
+    fn parse(selector: &str) -> Result<Self, HtmlError> {
+        selector
+            .split(',')
+            .map(|part| Selector::parse(part.trim(), selector))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    fn matches(&self, element: &Element, ancestors: &[&Element]) -> bool {
+        self.0
+            .iter()
+            .any(|selector| selector.matches(element, ancestors))
+    }
+}
+
+impl Selector {
+    fn parse(part: &str, selector: &str) -> Result<Self, HtmlError> {
+        let invalid = || HtmlError::new(format!("Invalid CSS selector '{selector}'"));
+        let mut compounds: Vec<(Combinator, Compound)> = Vec::new();
+        let mut combinator = Combinator::Descendant;
+        let mut rest = part;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('>') {
+                if compounds.is_empty() || combinator == Combinator::Child {
+                    return Err(invalid());
+                }
+                combinator = Combinator::Child;
+                rest = after.trim_start();
+                continue;
+            }
+            let (compound, after) = Compound::parse(rest).ok_or_else(invalid)?;
+            compounds.push((combinator, compound));
+            combinator = Combinator::Descendant;
+            rest = after.trim_start();
+        }
+        if combinator == Combinator::Child {
+            return Err(invalid());
+        }
+        // Each compound holds the combinator joining it to the compound on
+        // its left; shift them so each ancestor holds the one on its right.
+        let (mut next, subject) = compounds.pop().ok_or_else(invalid)?;
+        let mut ancestors = Vec::with_capacity(compounds.len());
+        while let Some((combinator, compound)) = compounds.pop() {
+            ancestors.push((next, compound));
+            next = combinator;
+        }
+        Ok(Self { subject, ancestors })
+    }
+
+    fn matches(&self, element: &Element, ancestors: &[&Element]) -> bool {
+        self.subject.matches(element) && Self::matches_ancestors(&self.ancestors, ancestors)
+    }
+
+    /// Matches the remaining compound selectors against the ancestors, the
+    /// nearest last.
+    fn matches_ancestors(selectors: &[(Combinator, Compound)], ancestors: &[&Element]) -> bool {
+        let Some(((combinator, compound), rest)) = selectors.split_first() else {
+            return true;
+        };
+        match combinator {
+            Combinator::Child => ancestors.split_last().is_some_and(|(parent, above)| {
+                compound.matches(parent) && Self::matches_ancestors(rest, above)
+            }),
+            Combinator::Descendant => (0..ancestors.len()).rev().any(|i| {
+                compound.matches(ancestors[i]) && Self::matches_ancestors(rest, &ancestors[..i])
+            }),
+        }
+    }
+}
+
+impl Compound {
+    /// Parses a compound selector from the start of `input`, returning it
+    /// and the rest of the input.
+    fn parse(input: &str) -> Option<(Self, &str)> {
+        let mut compound = Self::default();
+        let mut rest = input;
+        let ident_end = |s: &str| {
+            s.find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(s.len())
+        };
+        if let Some(after) = rest.strip_prefix('*') {
+            rest = after;
+        } else {
+            let end = ident_end(rest);
+            if end > 0 {
+                compound.tag = Some(rest[..end].to_ascii_lowercase());
+                rest = &rest[end..];
+            }
+        }
+        loop {
+            if let Some(after) = rest.strip_prefix('#') {
+                let end = ident_end(after);
+                if end == 0 {
+                    return None;
+                }
+                compound.id = Some(after[..end].to_string());
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let end = ident_end(after);
+                if end == 0 {
+                    return None;
+                }
+                compound.classes.push(after[..end].to_string());
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']')?;
+                compound
+                    .attributes
+                    .push(AttributeSelector::parse(&after[..end])?);
+                rest = &after[end + 1..];
+            } else {
+                break;
+            }
+        }
+        (rest.len() < input.len()).then_some((compound, rest))
+    }
+
+    fn matches(&self, element: &Element) -> bool {
+        !element.name.is_empty()
+            && self.tag.as_ref().map_or(true, |tag| *tag == element.name)
+            && self
+                .id
+                .as_ref()
+                .map_or(true, |id| element.attr("id") == Some(id))
+            && self.classes.iter().all(|class| element.has_class(class))
+            && self.attributes.iter().all(|attr| attr.matches(element))
+    }
+}
+
+impl AttributeSelector {
+    fn parse(input: &str) -> Option<Self> {
+        let Some(eq) = input.find('=') else {
+            let name = input.trim();
+            return (!name.is_empty()).then(|| Self {
+                name: name.to_ascii_lowercase(),
+                test: None,
+            });
+        };
+        let (name, operator) = match input[..eq].chars().last() {
+            Some(op @ ('~' | '^' | '$' | '*')) => (&input[..eq - 1], format!("{op}=")),
+            _ => (&input[..eq], "=".to_string()),
+        };
+        let value = input[eq + 1..].trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        let name = name.trim();
+        (!name.is_empty()).then(|| Self {
+            name: name.to_ascii_lowercase(),
+            test: Some((operator, value.to_string())),
+        })
+    }
+
+    fn matches(&self, element: &Element) -> bool {
+        let Some(actual) = element.attr(&self.name) else {
+            return false;
+        };
+        let Some((operator, value)) = &self.test else {
+            return true;
+        };
+        match operator.as_str() {
+            "~=" => actual.split_ascii_whitespace().any(|word| word == value),
+            "^=" => !value.is_empty() && actual.starts_with(value.as_str()),
+            "$=" => !value.is_empty() && actual.ends_with(value.as_str()),
+            "*=" => !value.is_empty() && actual.contains(value.as_str()),
+            _ => actual == value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(html: &str) -> Element {
+        parse_html(html).unwrap()
+    }
+
+    #[test]
+    fn test_equal_ignores_formatting() {
+        assert_eq!(
+            parse(r#"<p class="b a" id="x">Hello   <b>world</b></p>"#),
+            parse("<p id='x' class='a b'>\n  Hello <b>world</b>\n</p>"),
+        );
+        assert_eq!(
+            parse("<input type=checkbox checked disabled=\"disabled\">"),
+            parse(r#"<input checked="checked" disabled type="checkbox" />"#),
+        );
+        assert_eq!(
+            parse("<p>a &amp; b<!-- note --></p>"),
+            parse("<p>a &#38; b</p>")
+        );
+        assert_ne!(parse("<p>Hello</p>"), parse("<p>Hello!</p>"));
+        assert_ne!(parse("<p>Hello</p>"), parse("<div>Hello</div>"));
+        assert_ne!(parse(r#"<input value="a">"#), parse(r#"<input value="b">"#));
+    }
+
+    #[test]
+    fn test_parse_is_lenient() {
+        let dom = parse("<ul><li>One<li>Two</ul><p>Unclosed");
+        assert_eq!(
+            dom.to_string(),
+            "<ul><li>One<li>Two</li></li></ul>\n<p>Unclosed</p>"
+        );
+        assert_eq!(
+            parse("<script>if (a < b) { x = '</p>'; }</script>").text(),
+            "if (a < b) { x = '</p>'; }"
+        );
+        let err = parse_html("<div></span></div>").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected end tag </span>");
+    }
+
+    #[test]
+    fn test_count() {
+        let dom =
+            parse("<ul><li>One</li><li class='x'>Two</li><li>One</li></ul><p>One and One</p>");
+        assert_eq!(dom.count(&parse("<li>One</li>")), 2);
+        assert_eq!(dom.count(&parse("<li class='x'> Two </li>")), 1);
+        assert_eq!(dom.count(&parse("<li>Two</li>")), 0);
+        assert_eq!(dom.count(&parse("One")), 4);
+        assert_eq!(dom.count(&parse("<li class='x'>Two</li><li>One</li>")), 1);
+    }
+
+    #[test]
+    fn test_select() {
+        let dom = parse(
+            r#"<div id="main">
+                 <ul class="nav top"><li><a href="/">Home</a></li><li><a href="/blog/" rel="next">Blog</a></li></ul>
+                 <p>See <a href="https://example.com">elsewhere</a></p>
+               </div>"#,
+        );
+        let hrefs = |selector: &str| -> Vec<String> {
+            dom.select(selector)
+                .unwrap()
+                .iter()
+                .map(|a| a.attr("href").unwrap_or_default().to_string())
+                .collect()
+        };
+        assert_eq!(hrefs("a"), ["/", "/blog/", "https://example.com"]);
+        assert_eq!(hrefs("ul.nav a"), ["/", "/blog/"]);
+        assert_eq!(hrefs("#main > p > a"), ["https://example.com"]);
+        assert!(hrefs("#main > a").is_empty());
+        assert_eq!(hrefs("a[rel=next], p a"), ["/blog/", "https://example.com"]);
+        assert_eq!(hrefs("a[href^='https:']"), ["https://example.com"]);
+        assert_eq!(hrefs("[class~=top] li a"), ["/", "/blog/"]);
+
+        let nav = &dom.select("div ul").unwrap()[0];
+        assert_eq!(nav.name(), "ul");
+        assert_eq!(nav.attr("class"), Some("nav top"));
+        assert_eq!(nav.text(), "HomeBlog");
+        assert_eq!(nav.select("li > a").unwrap().len(), 2);
+
+        assert!(dom.select("ul >").is_err());
+        assert!(dom.select("a[").is_err());
+        assert!(dom.select("li:hover").is_err());
+    }
+}
@@ -8,11 +8,13 @@
 //!
 //! - [`client`] - HTTP test client wrapping Axum Router
 //! - [`framework`] - Test case structure and assertion helpers
+//! - [`html`] - HTML parsing for DOM-aware assertions and CSS selector queries
 //! - [`test_database`] - In-memory SQLite database for ORM tests
-//! - [`request_factory`] - Build `HttpRequest` objects without routing
+//! - [`request_factory`] - Build `HttpRequest` objects without routing, with mock services
 //! - [`override_settings`] - Temporarily swap settings in tests
 //! - [`mail_outbox`] - Capture emails sent during tests
-//! - [`assert_queries`] - Assert the number of SQL queries executed
+//! - [`assert_queries`] - Assert the number and SQL of queries executed, and
+//!   that queries use an index
 //! - [`live_server`] - Spawn a real HTTP server for integration tests
 //!
 //! ## Design Principles
@@ -49,6 +51,7 @@
 pub mod assert_queries;
 pub mod client;
 pub mod framework;
+pub mod html;
 pub mod live_server;
 pub mod mail_outbox;
 pub mod override_settings;
@@ -59,12 +62,15 @@ pub mod test_database;
 // Re-export primary types at the crate root for convenience.
 pub use client::{TestClient, TestResponse};
 pub use framework::{
-    assert_contains, assert_form_error, assert_has_header, assert_not_contains,
-    assert_not_has_header, assert_redirects, assert_status, assert_template_used, TestCase,
+    assert_contains, assert_form_error, assert_has_header, assert_html_equal,
+    assert_html_not_equal, assert_in_html, assert_not_contains, assert_not_has_header,
+    assert_redirects, assert_status, assert_template_used, select, TestCase,
 };
 
 // Re-export new infrastructure types.
-pub use assert_queries::{assert_max_queries, assert_num_queries};
+pub use assert_queries::{
+    assert_index_used, assert_max_queries, assert_num_queries, CaptureQueriesContext, CapturedQuery,
+};
 pub use live_server::LiveServerTestCase;
 pub use mail_outbox::{EmailMessage, MailOutbox};
 pub use override_settings::{get_settings, override_settings, SettingsOverride};
@@ -15,9 +15,12 @@
 //! ```
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use django_rs_auth::user::AbstractUser;
+use django_rs_http::services::Services;
 use django_rs_http::HttpRequest;
+use django_rs_views::CurrentUser;
 use http::Method;
 
 /// A factory for building [`HttpRequest`] objects without routing or middleware.
@@ -29,6 +32,8 @@ pub struct RequestFactory {
     default_headers: HashMap<String, String>,
     /// Default META entries applied to every request.
     default_meta: HashMap<String, String>,
+    /// Services injected into every request.
+    services: Services,
 }
 
 impl Default for RequestFactory {
@@ -43,6 +48,7 @@ impl RequestFactory {
         Self {
             default_headers: HashMap::new(),
             default_meta: HashMap::new(),
+            services: Services::new(),
         }
     }
 
@@ -61,6 +67,15 @@ impl RequestFactory {
         self
     }
 
+    /// Adds a service injected into all requests, so a view under test gets
+    /// it, typically a mock, from
+    /// [`HttpRequest::service`](django_rs_http::HttpRequest::service).
+    #[must_use]
+    pub fn with_service<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.services.insert(service);
+        self
+    }
+
     /// Builds a GET request to the given path.
     pub fn get(&self, path: &str) -> HttpRequest {
         self.build_request(Method::GET, path, None, None)
@@ -123,8 +138,12 @@ impl RequestFactory {
     /// Attaches user information to the request via META entries.
     ///
     /// Sets `USER_USERNAME`, `USER_EMAIL`, `USER_IS_AUTHENTICATED`,
-    /// `USER_IS_STAFF`, and `USER_IS_SUPERUSER` in the request META.
+    /// `USER_IS_STAFF`, and `USER_IS_SUPERUSER` in the request META, and the
+    /// [`CurrentUser`] the authentication middleware would attach.
     pub fn with_user(request: &mut HttpRequest, user: &AbstractUser) {
+        request
+            .extensions_mut()
+            .insert(CurrentUser::authenticated(user.username.clone()).with_staff(user.is_staff));
         let meta = request.meta_mut();
         meta.insert("USER_USERNAME".to_string(), user.username.clone());
         meta.insert("USER_EMAIL".to_string(), user.email.clone());
@@ -174,6 +193,10 @@ impl RequestFactory {
             builder = builder.meta(key, value);
         }
 
+        if !self.services.is_empty() {
+            builder = builder.extension(self.services.clone());
+        }
+
         if let Some(ct) = content_type {
             builder = builder.content_type(ct);
         }
@@ -206,6 +229,27 @@ mod tests {
         assert_eq!(req.path(), "/articles/");
     }
 
+    #[test]
+    fn test_factory_with_service() {
+        trait Clock: Send + Sync {
+            fn now(&self) -> u64;
+        }
+        struct FixedClock;
+        impl Clock for FixedClock {
+            fn now(&self) -> u64 {
+                1_700_000_000
+            }
+        }
+
+        let factory = RequestFactory::new().with_service::<dyn Clock>(Arc::new(FixedClock));
+        let req = factory.get("/");
+        assert_eq!(req.service::<dyn Clock>().unwrap().now(), 1_700_000_000);
+        assert!(RequestFactory::new()
+            .get("/")
+            .service::<dyn Clock>()
+            .is_none());
+    }
+
     #[test]
     fn test_factory_default() {
         let factory = RequestFactory::default();
@@ -298,6 +342,10 @@ mod tests {
         assert_eq!(req.meta().get("USER_IS_AUTHENTICATED").unwrap(), "true");
         assert_eq!(req.meta().get("USER_IS_STAFF").unwrap(), "true");
         assert_eq!(req.meta().get("USER_IS_SUPERUSER").unwrap(), "false");
+        assert_eq!(
+            req.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::authenticated("testuser").with_staff(true))
+        );
     }
 
     #[test]
@@ -17,28 +17,125 @@
 //!         .unwrap();
 //! }
 //! ```
-
+//!
+//! ## Test databases on disk
+//!
+//! The `test` management command runs tests against databases named after the
+//! configured ones: `db.sqlite3` becomes `test_db.sqlite3`, and with
+//! `--parallel` each shard gets its own copy (`test_db_1.sqlite3`, ...). The
+//! command removes these files before and after the run unless `--keepdb` is
+//! given. Tests open their shard's database with
+//! [`TestDatabase::for_settings`].
+
+use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use django_rs_core::DjangoResult;
+pub use django_rs_core::settings::TEST_SHARD_ENV;
+
+use django_rs_core::settings::DatabaseSettings;
+use django_rs_core::{DjangoError, DjangoResult};
+use django_rs_db::audit::annotate_sql;
 use django_rs_db::model::ModelMeta;
 use django_rs_db::query::compiler::{DatabaseBackendType, Row};
 use django_rs_db::value::Value;
 use django_rs_db::DbExecutor;
 use django_rs_db_backends::sqlite::SqliteBackend;
 
+use crate::assert_queries::CapturedQuery;
+
+/// The alias a [`TestDatabase`] reports unless one is set.
+pub const DEFAULT_DB_ALIAS: &str = "default";
+
+/// Returns the shard number of this test process, if it runs in a parallel
+/// `test` run.
+pub fn current_shard() -> Option<usize> {
+    std::env::var(TEST_SHARD_ENV).ok()?.parse().ok()
+}
+
+/// Returns the name of the test database for a database called `name`.
+///
+/// The name gets a `test_` prefix and, for a shard, a `_{shard}` suffix. For
+/// file paths these apply to the file stem, keeping the directory and
+/// extension. `:memory:` is returned unchanged.
+pub fn test_database_name(name: &str, shard: Option<usize>) -> String {
+    if name == ":memory:" {
+        return name.to_string();
+    }
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .map_or_else(|| name.into(), |s| s.to_string_lossy());
+    let mut file_name = shard.map_or_else(
+        || format!("test_{stem}"),
+        |shard| format!("test_{stem}_{shard}"),
+    );
+    if let Some(ext) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&ext.to_string_lossy());
+    }
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Returns `settings` pointing at its test database for the given shard.
+pub fn test_database_settings(
+    settings: &DatabaseSettings,
+    shard: Option<usize>,
+) -> DatabaseSettings {
+    DatabaseSettings {
+        name: test_database_name(&settings.name, shard),
+        ..settings.clone()
+    }
+}
+
+/// Removes the SQLite test database of `settings` for the given shard,
+/// along with its WAL and shared-memory files.
+///
+/// Returns `true` if a database file was removed. Test databases of other
+/// engines are left to the tests that create them.
+///
+/// # Errors
+///
+/// Returns an I/O error if an existing file cannot be removed.
+pub fn destroy_test_database(
+    settings: &DatabaseSettings,
+    shard: Option<usize>,
+) -> std::io::Result<bool> {
+    if !settings.engine.contains("sqlite") {
+        return Ok(false);
+    }
+    let name = test_database_name(&settings.name, shard);
+    if name == ":memory:" {
+        return Ok(false);
+    }
+    let mut removed = false;
+    for suffix in ["", "-wal", "-shm"] {
+        match std::fs::remove_file(format!("{name}{suffix}")) {
+            Ok(()) => removed |= suffix.is_empty(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(removed)
+}
+
 /// An in-memory SQLite database for testing.
 ///
 /// Wraps a [`SqliteBackend`] with an `Arc` for thread-safe sharing and adds a
-/// query counter for use with [`assert_num_queries`](crate::assert_num_queries).
+/// query counter for use with [`assert_num_queries`](crate::assert_num_queries)
+/// and a query log for use with
+/// [`CaptureQueriesContext`](crate::assert_queries::CaptureQueriesContext).
 ///
 /// The database is created fresh in memory for each `TestDatabase::new()` call,
 /// providing complete test isolation.
 #[derive(Clone)]
 pub struct TestDatabase {
     backend: Arc<SqliteBackend>,
+    alias: String,
     query_count: Arc<AtomicUsize>,
+    queries: Arc<Mutex<Vec<CapturedQuery>>>,
 }
 
 impl TestDatabase {
@@ -49,12 +146,54 @@ impl TestDatabase {
     /// Panics if the in-memory database cannot be created.
     pub fn new() -> Self {
         let backend = SqliteBackend::memory().expect("Failed to create in-memory SQLite database");
+        Self::from_backend(backend)
+    }
+
+    /// Opens the test database of `settings` for this process's shard.
+    ///
+    /// The database file is created if missing and kept as is otherwise, so
+    /// tables survive between tests of the same run (and between runs with
+    /// `--keepdb`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::ImproperlyConfigured`] if `settings` is not a
+    /// SQLite database, or the error raised opening the file.
+    pub fn for_settings(settings: &DatabaseSettings) -> DjangoResult<Self> {
+        if !settings.engine.contains("sqlite") {
+            return Err(DjangoError::ImproperlyConfigured(format!(
+                "TestDatabase requires a SQLite database, not '{}'",
+                settings.engine
+            )));
+        }
+        let name = test_database_name(&settings.name, current_shard());
+        Ok(Self::from_backend(SqliteBackend::open(name)?))
+    }
+
+    fn from_backend(backend: SqliteBackend) -> Self {
         Self {
             backend: Arc::new(backend),
+            alias: DEFAULT_DB_ALIAS.to_string(),
             query_count: Arc::new(AtomicUsize::new(0)),
+            queries: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// Sets the database alias recorded with each executed query.
+    ///
+    /// Use distinct aliases when a test works with several databases so
+    /// captured queries can be filtered per alias.
+    #[must_use]
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = alias.into();
+        self
+    }
+
+    /// Returns the database alias.
+    pub fn alias(&self) -> &str {
+        &self.alias
+    }
+
     /// Creates a table from the given [`ModelMeta`].
     ///
     /// Generates a `CREATE TABLE` statement from the field definitions in the
@@ -102,7 +241,7 @@ impl TestDatabase {
     ///
     /// Returns an error if the SQL execution fails.
     pub async fn execute_raw(&self, sql: &str) -> DjangoResult<u64> {
-        self.query_count.fetch_add(1, Ordering::Relaxed);
+        self.record(sql, &[]);
         self.backend.execute_sql(sql, &[]).await
     }
 
@@ -116,6 +255,38 @@ impl TestDatabase {
         self.query_count.store(0, Ordering::Relaxed);
     }
 
+    /// Returns every query executed against this database, in order.
+    pub fn executed_queries(&self) -> Vec<CapturedQuery> {
+        self.queries.lock().unwrap().clone()
+    }
+
+    /// Returns the queries executed after the first `start` ones.
+    pub(crate) fn queries_since(&self, start: usize) -> Vec<CapturedQuery> {
+        self.queries
+            .lock()
+            .unwrap()
+            .get(start..)
+            .map(<[CapturedQuery]>::to_vec)
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of queries in the log.
+    pub(crate) fn queries_logged(&self) -> usize {
+        self.queries.lock().unwrap().len()
+    }
+
+    /// Counts and logs a query about to be executed.
+    /// Captures a statement as the backend will run it, including the
+    /// comment for the current [`AuditContext`](django_rs_db::AuditContext).
+    fn record(&self, sql: &str, params: &[Value]) {
+        self.query_count.fetch_add(1, Ordering::Relaxed);
+        let sql = annotate_sql(sql, DatabaseBackendType::SQLite);
+        self.queries
+            .lock()
+            .unwrap()
+            .push(CapturedQuery::new(&self.alias, &sql, params));
+    }
+
     /// Returns a reference to the inner `SqliteBackend`.
     pub fn backend(&self) -> &SqliteBackend {
         &self.backend
@@ -185,22 +356,22 @@ impl DbExecutor for TestDatabase {
     }
 
     async fn execute_sql(&self, sql: &str, params: &[Value]) -> DjangoResult<u64> {
-        self.query_count.fetch_add(1, Ordering::Relaxed);
+        self.record(sql, params);
         self.backend.execute_sql(sql, params).await
     }
 
     async fn query(&self, sql: &str, params: &[Value]) -> DjangoResult<Vec<Row>> {
-        self.query_count.fetch_add(1, Ordering::Relaxed);
+        self.record(sql, params);
         self.backend.query(sql, params).await
     }
 
     async fn query_one(&self, sql: &str, params: &[Value]) -> DjangoResult<Row> {
-        self.query_count.fetch_add(1, Ordering::Relaxed);
+        self.record(sql, params);
         self.backend.query_one(sql, params).await
     }
 
     async fn insert_returning_id(&self, sql: &str, params: &[Value]) -> DjangoResult<Value> {
-        self.query_count.fetch_add(1, Ordering::Relaxed);
+        self.record(sql, params);
         self.backend.insert_returning_id(sql, params).await
     }
 }
@@ -229,6 +400,7 @@ mod tests {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         }
     }
 
@@ -259,6 +431,26 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_executed_queries_carry_audit_context() {
+        let db = TestDatabase::new();
+        db.execute_raw("CREATE TABLE test (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        let rows = django_rs_db::AuditContext::new()
+            .user_id("42")
+            .request_id("req-1")
+            .scope(db.query("SELECT id FROM test", &[]))
+            .await
+            .unwrap();
+        assert!(rows.is_empty());
+        let queries = db.executed_queries();
+        assert_eq!(
+            queries.last().unwrap().sql,
+            "SELECT id FROM test /*request_id='req-1',user_id='42'*/"
+        );
+    }
+
     #[tokio::test]
     async fn test_setup_table_from_meta() {
         let db = TestDatabase::new();
@@ -359,6 +551,46 @@ mod tests {
         assert_eq!(db.query_count(), 1);
     }
 
+    #[test]
+    fn test_test_database_name() {
+        assert_eq!(test_database_name("shop", None), "test_shop");
+        assert_eq!(
+            test_database_name("db.sqlite3", Some(2)),
+            "test_db_2.sqlite3"
+        );
+        assert_eq!(
+            test_database_name("data/db.sqlite3", None),
+            "data/test_db.sqlite3"
+        );
+        assert_eq!(test_database_name(":memory:", Some(1)), ":memory:");
+    }
+
+    #[tokio::test]
+    async fn test_for_settings_and_destroy() {
+        let dir = std::env::temp_dir().join(format!("django_rs_testdb_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let settings = DatabaseSettings {
+            name: dir.join("app.sqlite3").to_string_lossy().into_owned(),
+            ..DatabaseSettings::default()
+        };
+
+        let db = TestDatabase::for_settings(&settings).unwrap();
+        db.execute_raw("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        drop(db);
+        assert!(destroy_test_database(&settings, current_shard()).unwrap());
+        assert!(!destroy_test_database(&settings, current_shard()).unwrap());
+
+        let postgres = DatabaseSettings {
+            engine: "django_rs.db.backends.postgresql".to_string(),
+            ..settings
+        };
+        assert!(TestDatabase::for_settings(&postgres).is_err());
+        assert!(!destroy_test_database(&postgres, None).unwrap());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[tokio::test]
     async fn test_clone_shares_state() {
         let db = TestDatabase::new();
@@ -881,3 +881,46 @@ async fn test_client_preserves_set_cookie_headers() {
         "Cookie should be stored in client jar"
     );
 }
+
+/// 26. Views get application services from the request, and the `TestClient`
+/// can replace them with mocks.
+#[tokio::test]
+async fn test_client_overrides_application_services() {
+    trait Mailer: Send + Sync {
+        fn backend(&self) -> &'static str;
+    }
+    struct SmtpMailer;
+    impl Mailer for SmtpMailer {
+        fn backend(&self) -> &'static str {
+            "smtp"
+        }
+    }
+    struct MockMailer;
+    impl Mailer for MockMailer {
+        fn backend(&self) -> &'static str {
+            "mock"
+        }
+    }
+
+    let handler = make_handler(|req| {
+        req.service::<dyn Mailer>().map_or_else(
+            || HttpResponse::server_error("no mailer"),
+            |mailer| HttpResponse::ok(mailer.backend()),
+        )
+    });
+    let patterns = vec![URLEntry::Pattern(
+        path("mail/", handler, Some("mail")).unwrap(),
+    )];
+    let app = DjangoApp::new(Settings::default())
+        .urls(root(patterns).unwrap())
+        .service::<dyn Mailer>(Arc::new(SmtpMailer));
+    let mut client = TestClient::new(app.into_axum_router());
+
+    assert_eq!(client.get("/mail/").await.text(), "smtp");
+
+    client.override_service::<dyn Mailer>(Arc::new(MockMailer));
+    assert_eq!(client.get("/mail/").await.text(), "mock");
+
+    client.clear_service_overrides();
+    assert_eq!(client.get("/mail/").await.text(), "smtp");
+}
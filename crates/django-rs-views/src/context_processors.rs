@@ -0,0 +1,118 @@
+//! Context processors backed by the view layer's request extensions.
+//!
+//! The template crate's `auth` and `messages` processors read the string
+//! entries middleware copies into META. The processors here read the
+//! [`CurrentUser`] and [`MessageStore`] extensions instead, which follow a
+//! login or a message added by the view itself. [`DjangoApp`] installs them
+//! in place of the META-based ones; [`overrides`] lists them for engines
+//! configured by hand.
+//!
+//! [`DjangoApp`]: crate::server::DjangoApp
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use django_rs_http::HttpRequest;
+use django_rs_template::context::ContextValue;
+use django_rs_template::context_processors::{
+    messages_context, user_context, AuthContextProcessor, ContextProcessor,
+    MessagesContextProcessor,
+};
+
+use crate::middleware::builtin::{CurrentUser, MessageStore};
+
+/// Adds `user` to the context from the request's [`CurrentUser`].
+///
+/// Falls back to the template crate's [`AuthContextProcessor`] when the
+/// authentication middleware did not run.
+pub struct CurrentUserContextProcessor;
+
+impl ContextProcessor for CurrentUserContextProcessor {
+    fn process(&self, request: &HttpRequest) -> HashMap<String, ContextValue> {
+        match request.extensions().get::<CurrentUser>() {
+            Some(user) => user_context(user.user_id.as_deref()),
+            None => AuthContextProcessor.process(request),
+        }
+    }
+}
+
+/// Adds `messages` and `DEFAULT_MESSAGE_LEVELS` to the context from the
+/// request's [`MessageStore`].
+///
+/// Falls back to the template crate's [`MessagesContextProcessor`] when the
+/// message middleware did not run.
+pub struct MessageStoreContextProcessor;
+
+impl ContextProcessor for MessageStoreContextProcessor {
+    fn process(&self, request: &HttpRequest) -> HashMap<String, ContextValue> {
+        let Some(store) = request.extensions().get::<MessageStore>() else {
+            return MessagesContextProcessor.process(request);
+        };
+        let messages: Vec<_> = store
+            .stored
+            .iter()
+            .chain(&store.added)
+            .map(|message| (message, message.level.to_string()))
+            .collect();
+        messages_context(messages.iter().map(|(message, level)| {
+            (
+                message.message.as_str(),
+                level.as_str(),
+                message.extra_tags.as_str(),
+            )
+        }))
+    }
+}
+
+/// Returns the processors of this module keyed by the name they implement.
+///
+/// Pass the list to
+/// [`Engine::configure_context_processors_with`](django_rs_template::engine::Engine::configure_context_processors_with).
+pub fn overrides() -> Vec<(&'static str, Arc<dyn ContextProcessor>)> {
+    vec![
+        ("auth", Arc::new(CurrentUserContextProcessor)),
+        ("messages", Arc::new(MessageStoreContextProcessor)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::builtin::{Message, MessageLevel};
+
+    #[test]
+    fn test_current_user_context_processor() {
+        let request = HttpRequest::builder()
+            .meta("USER_AUTHENTICATED", "false")
+            .extension(CurrentUser::authenticated("7"))
+            .build();
+        let ctx = CurrentUserContextProcessor.process(&request);
+        let ContextValue::Dict(user) = &ctx["user"] else {
+            panic!("user is not a dict");
+        };
+        assert!(matches!(user["is_authenticated"], ContextValue::Bool(true)));
+        assert!(matches!(&user["pk"], ContextValue::String(pk) if pk == "7"));
+    }
+
+    #[test]
+    fn test_message_store_context_processor() {
+        let store = MessageStore {
+            stored: vec![],
+            added: vec![Message {
+                level: MessageLevel::Warning,
+                message: "Low disk".to_string(),
+                extra_tags: "sticky".to_string(),
+            }],
+        };
+        let request = HttpRequest::builder().extension(store).build();
+        let ctx = MessageStoreContextProcessor.process(&request);
+        let ContextValue::List(messages) = &ctx["messages"] else {
+            panic!("messages is not a list");
+        };
+        let ContextValue::Dict(message) = &messages[0] else {
+            panic!("message is not a dict");
+        };
+        assert!(matches!(message["level"], ContextValue::Integer(30)));
+        assert!(matches!(&message["tags"], ContextValue::String(tags) if tags == "sticky warning"));
+    }
+}
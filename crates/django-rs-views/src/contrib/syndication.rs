@@ -18,15 +18,11 @@
 //!     fn link(&self) -> String { "https://example.com/".to_string() }
 //!     fn description(&self) -> String { "Latest blog posts".to_string() }
 //!     fn items(&self) -> Vec<FeedItem> {
-//!         vec![FeedItem {
-//!             title: "First Post".to_string(),
-//!             link: "https://example.com/post/1/".to_string(),
-//!             description: "My first post.".to_string(),
-//!             pub_date: None,
-//!             author: None,
-//!             guid: None,
-//!             categories: Vec::new(),
-//!         }]
+//!         vec![FeedItem::new(
+//!             "First Post",
+//!             "https://example.com/post/1/",
+//!             "My first post.",
+//!         )]
 //!     }
 //! }
 //!
@@ -37,10 +33,78 @@
 //! let atom = generate_atom(&BlogFeed);
 //! assert!(atom.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\""));
 //! ```
+//!
+//! ## Feed Views
+//!
+//! A [`FeedView`] serves a feed built from a [`QuerySet`]: each object is
+//! mapped to a [`FeedItem`] by a closure, and the newest publication date
+//! becomes the feed's `Last-Modified`. Responses carry an `ETag`, and
+//! conditional requests are answered with `304 Not Modified`. The same
+//! handler can be mounted twice; URLs whose last segment is `atom` get Atom,
+//! all others RSS 2.0.
+//!
+//! ```ignore
+//! let feed = FeedView::new(
+//!     Article::objects().order_by(vec![OrderBy::desc("published")]).limit(20),
+//!     db,
+//!     |article: &Article| {
+//!         let mut item = FeedItem::new(
+//!             &article.title,
+//!             format!("https://example.com/articles/{}/", article.slug),
+//!             &article.summary,
+//!         );
+//!         if let Some(ref audio) = article.audio_url {
+//!             item = item.with_enclosure(Enclosure::new(audio, article.audio_size, "audio/mpeg"));
+//!         }
+//!         item
+//!     },
+//! )
+//! .title("Latest articles")
+//! .link("https://example.com/articles/")
+//! .description("New articles on example.com")
+//! .item_pubdate(|article: &Article| Some(article.published))
+//! .handler();
+//!
+//! let patterns = vec![
+//!     path("articles/feed/", feed.clone(), Some("article-feed"))?,
+//!     path("articles/feed/atom/", feed, Some("article-atom"))?,
+//! ];
+//! ```
 
 use std::fmt::Write;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use django_rs_db::executor::DbExecutor;
+use django_rs_db::model::Model;
+use django_rs_db::QuerySet;
+use django_rs_http::urls::pattern::RouteHandler;
+use django_rs_http::{HttpRequest, HttpResponse};
+use sha2::{Digest, Sha256};
+
+use crate::views::static_serve::{is_not_modified, set_cache_headers};
+
+/// A media file attached to a feed item, such as a podcast episode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enclosure {
+    /// The URL of the file.
+    pub url: String,
+    /// The size of the file in bytes.
+    pub length: u64,
+    /// The MIME type of the file (e.g., "audio/mpeg").
+    pub mime_type: String,
+}
 
-use django_rs_http::HttpResponse;
+impl Enclosure {
+    /// Creates an enclosure.
+    pub fn new(url: impl Into<String>, length: u64, mime_type: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            length,
+            mime_type: mime_type.into(),
+        }
+    }
+}
 
 /// A single item/entry in a feed.
 #[derive(Debug, Clone)]
@@ -59,6 +123,9 @@ pub struct FeedItem {
     pub guid: Option<String>,
     /// Category labels for this item.
     pub categories: Vec<String>,
+    /// Media files attached to this item. RSS allows only one, so RSS feeds
+    /// include the first.
+    pub enclosures: Vec<Enclosure>,
 }
 
 impl FeedItem {
@@ -76,6 +143,7 @@ impl FeedItem {
             author: None,
             guid: None,
             categories: Vec::new(),
+            enclosures: Vec::new(),
         }
     }
 
@@ -106,6 +174,13 @@ impl FeedItem {
         self.categories = categories;
         self
     }
+
+    /// Adds an enclosure.
+    #[must_use]
+    pub fn with_enclosure(mut self, enclosure: Enclosure) -> Self {
+        self.enclosures.push(enclosure);
+        self
+    }
 }
 
 /// Trait for defining feed content.
@@ -144,6 +219,12 @@ pub trait Feed {
     fn feed_url(&self) -> Option<String> {
         None
     }
+
+    /// When the feed last changed. Atom feeds use the Unix epoch if this is
+    /// `None`.
+    fn updated(&self) -> Option<DateTime<Utc>> {
+        None
+    }
 }
 
 /// Generates an RSS 2.0 XML document from a feed.
@@ -188,6 +269,14 @@ pub fn generate_rss(feed: &dyn Feed) -> String {
         let _ = writeln!(xml, "    <copyright>{}</copyright>", xml_escape(&copyright));
     }
 
+    if let Some(updated) = feed.updated() {
+        let _ = writeln!(
+            xml,
+            "    <lastBuildDate>{}</lastBuildDate>",
+            updated.to_rfc2822()
+        );
+    }
+
     if let Some(feed_url) = feed.feed_url() {
         let _ = writeln!(
             xml,
@@ -221,6 +310,16 @@ pub fn generate_rss(feed: &dyn Feed) -> String {
             let _ = writeln!(xml, "      <category>{}</category>", xml_escape(category));
         }
 
+        if let Some(enclosure) = item.enclosures.first() {
+            let _ = writeln!(
+                xml,
+                "      <enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>",
+                xml_escape(&enclosure.url),
+                enclosure.length,
+                xml_escape(&enclosure.mime_type)
+            );
+        }
+
         xml.push_str("    </item>\n");
     }
 
@@ -273,11 +372,12 @@ pub fn generate_atom(feed: &dyn Feed) -> String {
     // Generate a feed ID from the link
     let _ = writeln!(xml, "  <id>{}</id>", xml_escape(&feed.link()));
 
-    // Updated timestamp (use current time if not available)
+    // Updated timestamp. The epoch stands in for an unknown date so that the
+    // same feed always renders the same document.
     let _ = writeln!(
         xml,
         "  <updated>{}</updated>",
-        chrono::Utc::now().to_rfc3339()
+        feed.updated().unwrap_or(DateTime::UNIX_EPOCH).to_rfc3339()
     );
 
     if let Some(author) = feed.author_name() {
@@ -317,6 +417,16 @@ pub fn generate_atom(feed: &dyn Feed) -> String {
             let _ = writeln!(xml, "    <category term=\"{}\"/>", xml_escape(category));
         }
 
+        for enclosure in &item.enclosures {
+            let _ = writeln!(
+                xml,
+                "    <link rel=\"enclosure\" href=\"{}\" length=\"{}\" type=\"{}\"/>",
+                xml_escape(&enclosure.url),
+                enclosure.length,
+                xml_escape(&enclosure.mime_type)
+            );
+        }
+
         xml.push_str("  </entry>\n");
     }
 
@@ -344,6 +454,272 @@ pub fn atom_response(feed: &dyn Feed) -> HttpResponse {
     response
 }
 
+/// The format a [`FeedView`] renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    /// RSS 2.0.
+    Rss,
+    /// Atom 1.0.
+    Atom,
+}
+
+impl FeedFormat {
+    /// Returns the format a feed URL asks for: Atom when the last path
+    /// segment is `atom`, `atom.xml` or ends in `.atom`, RSS otherwise.
+    pub fn from_path(path: &str) -> Self {
+        let segment = path.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+        let is_atom = segment == "atom"
+            || segment == "atom.xml"
+            || std::path::Path::new(segment)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("atom"));
+        if is_atom {
+            Self::Atom
+        } else {
+            Self::Rss
+        }
+    }
+
+    /// Returns the response content type.
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml",
+            Self::Atom => "application/atom+xml",
+        }
+    }
+
+    /// Formats an item date as this format expects: RFC 2822 for RSS,
+    /// RFC 3339 for Atom.
+    fn format_date(self, date: DateTime<Utc>) -> String {
+        match self {
+            Self::Rss => date.to_rfc2822(),
+            Self::Atom => date.to_rfc3339(),
+        }
+    }
+}
+
+/// Maps an object of a feed view's queryset to a feed item.
+type ItemFn<M> = Box<dyn Fn(&M) -> FeedItem + Send + Sync>;
+
+/// Returns the publication date of an object of a feed view's queryset.
+type PubDateFn<M> = Box<dyn Fn(&M) -> Option<DateTime<Utc>> + Send + Sync>;
+
+/// A view serving a feed of the objects of a queryset.
+///
+/// See the [module documentation](self#feed-views) for an example.
+pub struct FeedView<M: Model> {
+    queryset: QuerySet<M>,
+    db: Arc<dyn DbExecutor>,
+    item: ItemFn<M>,
+    item_pubdate: Option<PubDateFn<M>>,
+    title: String,
+    link: String,
+    description: String,
+    language: Option<String>,
+    author_name: Option<String>,
+    copyright: Option<String>,
+    format: Option<FeedFormat>,
+}
+
+impl<M: Model> FeedView<M> {
+    /// Creates a view of the objects of `queryset`, each mapped to a feed
+    /// item by `item`.
+    ///
+    /// The queryset is run on every request, so it should be ordered and
+    /// limited to the items the feed shows.
+    pub fn new(
+        queryset: QuerySet<M>,
+        db: Arc<dyn DbExecutor>,
+        item: impl Fn(&M) -> FeedItem + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            queryset,
+            db,
+            item: Box::new(item),
+            item_pubdate: None,
+            title: String::new(),
+            link: String::new(),
+            description: String::new(),
+            language: None,
+            author_name: None,
+            copyright: None,
+            format: None,
+        }
+    }
+
+    /// Sets the feed title.
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the feed link (URL of the site).
+    #[must_use]
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.link = link.into();
+        self
+    }
+
+    /// Sets the feed description.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Sets the feed language code (e.g., "en-us").
+    #[must_use]
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Sets the feed author name (for Atom feeds).
+    #[must_use]
+    pub fn author_name(mut self, author_name: impl Into<String>) -> Self {
+        self.author_name = Some(author_name.into());
+        self
+    }
+
+    /// Sets the feed copyright.
+    #[must_use]
+    pub fn copyright(mut self, copyright: impl Into<String>) -> Self {
+        self.copyright = Some(copyright.into());
+        self
+    }
+
+    /// Sets the closure returning each object's publication date.
+    ///
+    /// The date fills in the item's `pub_date` in the feed's format, and the
+    /// newest one is the feed's `Last-Modified`.
+    #[must_use]
+    pub fn item_pubdate(
+        mut self,
+        pubdate: impl Fn(&M) -> Option<DateTime<Utc>> + Send + Sync + 'static,
+    ) -> Self {
+        self.item_pubdate = Some(Box::new(pubdate));
+        self
+    }
+
+    /// Always renders `format`, whatever the URL.
+    #[must_use]
+    pub const fn format(mut self, format: FeedFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Converts the view into a handler for a URL pattern.
+    pub fn handler(self) -> RouteHandler {
+        let view = Arc::new(self);
+        Arc::new(move |request: HttpRequest| {
+            let view = view.clone();
+            Box::pin(async move { view.serve(&request).await })
+        })
+    }
+
+    /// Renders the feed for `request`, or `304 Not Modified` if the
+    /// client's copy is current.
+    ///
+    /// Only `GET` and `HEAD` are allowed.
+    pub async fn serve(&self, request: &HttpRequest) -> HttpResponse {
+        if request.method() != http::Method::GET && request.method() != http::Method::HEAD {
+            return HttpResponse::not_allowed(&["GET", "HEAD"]);
+        }
+        let objects = match self.queryset.execute_query(&*self.db).await {
+            Ok(objects) => objects,
+            Err(e) => {
+                tracing::error!("Failed to load feed: {e}");
+                return HttpResponse::server_error("Failed to load feed");
+            }
+        };
+
+        let format = self
+            .format
+            .unwrap_or_else(|| FeedFormat::from_path(request.path()));
+        let mut updated: Option<DateTime<Utc>> = None;
+        let items = objects
+            .iter()
+            .map(|object| {
+                let mut item = (self.item)(object);
+                if let Some(date) = self.item_pubdate.as_ref().and_then(|f| f(object)) {
+                    item.pub_date = Some(format.format_date(date));
+                    updated = updated.max(Some(date));
+                }
+                item
+            })
+            .collect();
+        let feed = LoadedFeed {
+            view: self,
+            items,
+            updated,
+            feed_url: request.build_absolute_uri(None),
+        };
+        let xml = match format {
+            FeedFormat::Rss => generate_rss(&feed),
+            FeedFormat::Atom => generate_atom(&feed),
+        };
+
+        let etag = format!("\"{:x}\"", Sha256::digest(xml.as_bytes()));
+        let last_modified = updated.map(|d| d.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+        let mut response = if is_not_modified(request, &etag, updated) {
+            HttpResponse::new(http::StatusCode::NOT_MODIFIED, "")
+        } else {
+            let mut response = HttpResponse::ok(xml);
+            response.set_content_type(format.content_type());
+            response
+        };
+        set_cache_headers(&mut response, &etag, last_modified.as_deref());
+        response
+    }
+}
+
+/// A feed view with its items loaded for one request.
+struct LoadedFeed<'a, M: Model> {
+    view: &'a FeedView<M>,
+    items: Vec<FeedItem>,
+    updated: Option<DateTime<Utc>>,
+    feed_url: String,
+}
+
+impl<M: Model> Feed for LoadedFeed<'_, M> {
+    fn title(&self) -> String {
+        self.view.title.clone()
+    }
+
+    fn link(&self) -> String {
+        self.view.link.clone()
+    }
+
+    fn description(&self) -> String {
+        self.view.description.clone()
+    }
+
+    fn items(&self) -> Vec<FeedItem> {
+        self.items.clone()
+    }
+
+    fn language(&self) -> Option<String> {
+        self.view.language.clone()
+    }
+
+    fn author_name(&self) -> Option<String> {
+        self.view.author_name.clone()
+    }
+
+    fn copyright(&self) -> Option<String> {
+        self.view.copyright.clone()
+    }
+
+    fn feed_url(&self) -> Option<String> {
+        Some(self.feed_url.clone())
+    }
+
+    fn updated(&self) -> Option<DateTime<Utc>> {
+        self.updated
+    }
+}
+
 /// Escapes special XML characters.
 fn xml_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -356,6 +732,13 @@ fn xml_escape(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use django_rs_core::DjangoError;
+    use django_rs_db::fields::{FieldDef, FieldType};
+    use django_rs_db::model::ModelMeta;
+    use django_rs_db::query::compiler::{InheritanceType, OrderBy, Row};
+    use django_rs_db::value::Value;
+    use django_rs_db::Manager;
+    use django_rs_db_backends::SqliteBackend;
 
     struct TestFeed;
 
@@ -663,5 +1046,291 @@ mod tests {
         assert!(MinimalFeed.author_name().is_none());
         assert!(MinimalFeed.copyright().is_none());
         assert!(MinimalFeed.feed_url().is_none());
+        assert!(MinimalFeed.updated().is_none());
+    }
+
+    // ── Enclosures ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_enclosures() {
+        struct PodcastFeed;
+        impl Feed for PodcastFeed {
+            fn title(&self) -> String {
+                "Podcast".to_string()
+            }
+            fn link(&self) -> String {
+                "https://example.com/".to_string()
+            }
+            fn description(&self) -> String {
+                "Episodes".to_string()
+            }
+            fn items(&self) -> Vec<FeedItem> {
+                vec![FeedItem::new("Pilot", "https://example.com/1/", "")
+                    .with_enclosure(Enclosure::new(
+                        "https://example.com/1.mp3",
+                        1024,
+                        "audio/mpeg",
+                    ))
+                    .with_enclosure(Enclosure::new(
+                        "https://example.com/1.ogg",
+                        900,
+                        "audio/ogg",
+                    ))]
+            }
+        }
+
+        let rss = generate_rss(&PodcastFeed);
+        assert!(rss.contains(
+            "<enclosure url=\"https://example.com/1.mp3\" length=\"1024\" type=\"audio/mpeg\"/>"
+        ));
+        assert!(!rss.contains("1.ogg"));
+
+        let atom = generate_atom(&PodcastFeed);
+        assert!(atom.contains(
+            "<link rel=\"enclosure\" href=\"https://example.com/1.mp3\" length=\"1024\" type=\"audio/mpeg\"/>"
+        ));
+        assert!(atom.contains("href=\"https://example.com/1.ogg\""));
+    }
+
+    // ── FeedView ─────────────────────────────────────────────────────
+
+    struct Episode {
+        id: i64,
+        title: String,
+        published: DateTime<Utc>,
+        audio_size: i64,
+    }
+
+    impl Model for Episode {
+        fn meta() -> &'static ModelMeta {
+            use std::sync::OnceLock;
+            static META: OnceLock<ModelMeta> = OnceLock::new();
+            META.get_or_init(|| ModelMeta {
+                app_label: "podcast",
+                model_name: "episode",
+                db_table: "podcast_episode".to_string(),
+                verbose_name: "episode".to_string(),
+                verbose_name_plural: "episodes".to_string(),
+                ordering: vec![],
+                unique_together: vec![],
+                indexes: vec![],
+                abstract_model: false,
+                fields: vec![
+                    FieldDef::new("id", FieldType::BigAutoField).primary_key(),
+                    FieldDef::new("title", FieldType::CharField).max_length(100),
+                    FieldDef::new("published", FieldType::DateTimeField),
+                    FieldDef::new("audio_size", FieldType::BigIntegerField),
+                ],
+                constraints: vec![],
+                inheritance_type: InheritanceType::None,
+                db_schema: None,
+            })
+        }
+
+        fn table_name() -> &'static str {
+            "podcast_episode"
+        }
+
+        fn app_label() -> &'static str {
+            "podcast"
+        }
+
+        fn pk(&self) -> Option<&Value> {
+            None
+        }
+
+        fn set_pk(&mut self, _value: Value) {}
+
+        fn field_values(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("id", Value::Int(self.id)),
+                ("title", Value::String(self.title.clone())),
+                ("published", Value::DateTimeTz(self.published)),
+                ("audio_size", Value::Int(self.audio_size)),
+            ]
+        }
+
+        fn from_row(row: &Row) -> Result<Self, DjangoError> {
+            let published: String = row.get("published")?;
+            Ok(Self {
+                id: row.get("id")?,
+                title: row.get("title")?,
+                published: DateTime::parse_from_rfc3339(&published)
+                    .map_err(|e| DjangoError::DatabaseError(e.to_string()))?
+                    .with_timezone(&Utc),
+                audio_size: row.get("audio_size")?,
+            })
+        }
+    }
+
+    async fn episode_feed() -> RouteHandler {
+        episode_view(true)
+            .await
+            .item_pubdate(|episode: &Episode| Some(episode.published))
+            .handler()
+    }
+
+    async fn episode_view(create_table: bool) -> FeedView<Episode> {
+        let db = SqliteBackend::memory().unwrap();
+        let statements = [
+            "CREATE TABLE podcast_episode (id INTEGER PRIMARY KEY, title TEXT NOT NULL, \
+             published TEXT NOT NULL, audio_size INTEGER NOT NULL)",
+            "INSERT INTO podcast_episode VALUES \
+             (1, 'Pilot', '2024-06-01T09:00:00+00:00', 1000), \
+             (2, 'Sequel', '2024-06-15T12:00:00+00:00', 2000)",
+        ];
+        if create_table {
+            for sql in statements {
+                DbExecutor::execute_sql(&db, sql, &[]).await.unwrap();
+            }
+        }
+        FeedView::new(
+            Manager::<Episode>::new()
+                .all()
+                .order_by(vec![OrderBy::desc("published")]),
+            Arc::new(db),
+            |episode: &Episode| {
+                FeedItem::new(
+                    &episode.title,
+                    format!("https://example.com/episodes/{}/", episode.id),
+                    format!("Episode {}", episode.id),
+                )
+                .with_enclosure(Enclosure::new(
+                    format!("https://example.com/episodes/{}.mp3", episode.id),
+                    episode.audio_size.unsigned_abs(),
+                    "audio/mpeg",
+                ))
+            },
+        )
+        .title("Episodes")
+        .link("https://example.com/episodes/")
+        .description("New episodes")
+    }
+
+    async fn get(handler: &RouteHandler, request: HttpRequest) -> HttpResponse {
+        handler(request).await
+    }
+
+    fn body(response: &HttpResponse) -> String {
+        String::from_utf8(response.content_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_feed_format_from_path() {
+        assert_eq!(FeedFormat::from_path("/feed/"), FeedFormat::Rss);
+        assert_eq!(FeedFormat::from_path("/feed/rss.xml"), FeedFormat::Rss);
+        assert_eq!(FeedFormat::from_path("/feed/atom/"), FeedFormat::Atom);
+        assert_eq!(FeedFormat::from_path("/feed/atom.xml"), FeedFormat::Atom);
+        assert_eq!(FeedFormat::from_path("/episodes.atom"), FeedFormat::Atom);
+    }
+
+    #[tokio::test]
+    async fn test_feed_view_rss() {
+        let feed = episode_feed().await;
+        let response = get(&feed, HttpRequest::builder().path("/feed/").build()).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.content_type(), "application/rss+xml");
+        assert_eq!(
+            response.headers()[http::header::LAST_MODIFIED],
+            "Sat, 15 Jun 2024 12:00:00 GMT"
+        );
+        assert!(response.headers().contains_key(http::header::ETAG));
+
+        let xml = body(&response);
+        assert!(xml.contains("<title>Episodes</title>"));
+        assert!(xml.contains("<lastBuildDate>Sat, 15 Jun 2024 12:00:00 +0000</lastBuildDate>"));
+        assert!(xml.contains("<pubDate>Sat, 1 Jun 2024 09:00:00 +0000</pubDate>"));
+        assert!(xml.contains("url=\"https://example.com/episodes/2.mp3\" length=\"2000\""));
+        assert!(xml.find("Sequel").unwrap() < xml.find("Pilot").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_feed_view_atom_from_url() {
+        let feed = episode_feed().await;
+        let request = HttpRequest::builder().path("/feed/atom/").build();
+        let response = get(&feed, request).await;
+        assert_eq!(response.content_type(), "application/atom+xml");
+
+        let xml = body(&response);
+        assert!(xml.contains("<updated>2024-06-15T12:00:00+00:00</updated>"));
+        assert!(xml.contains("<updated>2024-06-01T09:00:00+00:00</updated>"));
+        assert!(xml.contains("/feed/atom/\" rel=\"self\""));
+        assert!(xml.contains("rel=\"enclosure\" href=\"https://example.com/episodes/1.mp3\""));
+    }
+
+    #[tokio::test]
+    async fn test_feed_view_conditional_get() {
+        let feed = episode_feed().await;
+        let response = get(&feed, HttpRequest::builder().path("/feed/").build()).await;
+        let etag = response.headers()[http::header::ETAG].to_str().unwrap();
+
+        let request = HttpRequest::builder()
+            .path("/feed/")
+            .header("if-none-match", etag)
+            .build();
+        let response = get(&feed, request).await;
+        assert_eq!(response.status(), http::StatusCode::NOT_MODIFIED);
+        assert!(body(&response).is_empty());
+
+        let request = HttpRequest::builder()
+            .path("/feed/")
+            .header("if-modified-since", "Sat, 15 Jun 2024 12:00:00 GMT")
+            .build();
+        let response = get(&feed, request).await;
+        assert_eq!(response.status(), http::StatusCode::NOT_MODIFIED);
+
+        let request = HttpRequest::builder()
+            .path("/feed/")
+            .header("if-modified-since", "Fri, 14 Jun 2024 12:00:00 GMT")
+            .build();
+        assert_eq!(get(&feed, request).await.status(), http::StatusCode::OK);
+
+        // The Atom rendering has its own validator.
+        let request = HttpRequest::builder()
+            .path("/feed/atom/")
+            .header("if-none-match", etag)
+            .build();
+        assert_eq!(get(&feed, request).await.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_feed_view_atom_without_dates_is_cacheable() {
+        let feed = episode_view(true).await.handler();
+        let request = || HttpRequest::builder().path("/feed/atom/").build();
+        let first = get(&feed, request()).await;
+        assert!(body(&first).contains("<updated>1970-01-01T00:00:00+00:00</updated>"));
+        let etag = first.headers()[http::header::ETAG].to_str().unwrap();
+        assert_eq!(
+            get(&feed, request()).await.headers()[http::header::ETAG],
+            etag
+        );
+
+        let request = HttpRequest::builder()
+            .path("/feed/atom/")
+            .header("if-none-match", etag)
+            .build();
+        assert_eq!(
+            get(&feed, request).await.status(),
+            http::StatusCode::NOT_MODIFIED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feed_view_hides_database_errors() {
+        let feed = episode_view(false).await.handler();
+        let response = get(&feed, HttpRequest::builder().path("/feed/").build()).await;
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body(&response), "Failed to load feed");
+    }
+
+    #[tokio::test]
+    async fn test_feed_view_rejects_post() {
+        let feed = episode_feed().await;
+        let request = HttpRequest::builder()
+            .method(http::Method::POST)
+            .path("/feed/")
+            .build();
+        let response = get(&feed, request).await;
+        assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
     }
 }
@@ -0,0 +1,415 @@
+//! Custom error responses.
+//!
+//! [`ErrorHandlers`] maps a status code to the handler building the response
+//! for errors with that status, like Django's `handler400`, `handler404` and
+//! `handler500`. [`DjangoApp`](crate::server::DjangoApp) consults it for
+//! malformed requests (400), unresolved URLs (404) and routing failures
+//! (500); codes without a handler keep the built-in responses.
+//!
+//! Each error is answered in a [`ResponseFormat`]. Requests under a path
+//! prefix registered with [`ErrorHandlers::group_format`], such as the prefix
+//! an API's URLs are included under, always get that format; other requests
+//! get JSON when their `Accept` header prefers it to HTML. JSON errors are
+//! RFC 9457 problem details (`application/problem+json`), and HTML errors
+//! render the `<status>.html` template when the engine has one. Either way
+//! the response carries `X-Content-Type-Options: nosniff`, so browsers never
+//! reinterpret an error body.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_core::DjangoError;
+//! use django_rs_http::HttpResponse;
+//! use django_rs_views::error_handlers::{ErrorHandlers, ResponseFormat};
+//!
+//! let mut handlers = ErrorHandlers::new();
+//! handlers.register(400, |_err: &DjangoError| HttpResponse::bad_request("Please check your request"));
+//! handlers.group_format("/api/", ResponseFormat::Json);
+//!
+//! let response = handlers.respond(&DjangoError::BadRequest("bad cookie".into()), || {
+//!     HttpResponse::bad_request("Bad Request (400)")
+//! });
+//! assert_eq!(response.status().as_u16(), 400);
+//!
+//! let error = DjangoError::NotFound("No such order".into());
+//! let response = handlers.respond_to("/api/orders/9/", None, &error, || {
+//!     HttpResponse::not_found("Not Found")
+//! });
+//! assert_eq!(response.content_type(), "application/problem+json");
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use django_rs_core::DjangoError;
+use django_rs_http::HttpResponse;
+use django_rs_template::context::{Context, ContextValue};
+use django_rs_template::engine::Engine;
+use http::{HeaderValue, StatusCode};
+
+/// The tracing target under which refused, suspicious requests are logged,
+/// like Django's `django.security` loggers.
+pub const SECURITY_LOG_TARGET: &str = "django_rs::security";
+
+/// Builds the response for an error.
+pub type ErrorHandler = Arc<dyn Fn(&DjangoError) -> HttpResponse + Send + Sync>;
+
+/// The format an error response is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResponseFormat {
+    /// An HTML page, for browsers.
+    Html,
+    /// An `application/problem+json` body, for API clients.
+    Json,
+}
+
+impl ResponseFormat {
+    /// Picks the format an `Accept` header prefers.
+    ///
+    /// JSON is chosen only when a JSON media type (`application/json` or any
+    /// `+json` type) has a higher quality than HTML; a missing header or
+    /// `*/*` gets HTML, as browsers expect.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Html;
+        };
+        let mut json_q = 0.0_f32;
+        let mut html_q = 0.0_f32;
+        for range in accept.split(',') {
+            let mut params = range.split(';');
+            let media_type = params.next().unwrap_or("").trim().to_ascii_lowercase();
+            let q = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if media_type == "application/json" || media_type.ends_with("+json") {
+                json_q = json_q.max(q);
+            } else if matches!(
+                media_type.as_str(),
+                "text/html" | "application/xhtml+xml" | "text/*" | "*/*"
+            ) {
+                html_q = html_q.max(q);
+            }
+        }
+        if json_q > html_q {
+            Self::Json
+        } else {
+            Self::Html
+        }
+    }
+}
+
+/// Error handlers keyed by HTTP status code, with the response format of
+/// each route group.
+#[derive(Clone, Default)]
+pub struct ErrorHandlers {
+    handlers: HashMap<(ResponseFormat, u16), ErrorHandler>,
+    groups: Vec<(String, ResponseFormat)>,
+    pub(crate) engine: Option<Arc<Engine>>,
+    pub(crate) debug: bool,
+}
+
+impl ErrorHandlers {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the handler for HTML errors with the given status code,
+    /// replacing any previous one.
+    pub fn register(
+        &mut self,
+        status: u16,
+        handler: impl Fn(&DjangoError) -> HttpResponse + Send + Sync + 'static,
+    ) {
+        self.handlers
+            .insert((ResponseFormat::Html, status), Arc::new(handler));
+    }
+
+    /// Registers the handler for JSON errors with the given status code,
+    /// replacing the default problem details body.
+    pub fn register_json(
+        &mut self,
+        status: u16,
+        handler: impl Fn(&DjangoError) -> HttpResponse + Send + Sync + 'static,
+    ) {
+        self.handlers
+            .insert((ResponseFormat::Json, status), Arc::new(handler));
+    }
+
+    /// Answers errors for requests under `prefix` in `format`, whatever
+    /// their `Accept` header says.
+    ///
+    /// `prefix` is a request path such as `"/api/"`. When groups overlap,
+    /// the longest prefix wins.
+    pub fn group_format(&mut self, prefix: &str, format: ResponseFormat) {
+        self.groups.retain(|(p, _)| p != prefix);
+        self.groups.push((prefix.to_string(), format));
+    }
+
+    /// Returns the HTML handler for the given status code, if any.
+    pub fn get(&self, status: u16) -> Option<&ErrorHandler> {
+        self.handlers.get(&(ResponseFormat::Html, status))
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Returns the format errors for a request to `path` with the given
+    /// `Accept` header are answered in.
+    pub fn format_for(&self, path: &str, accept: Option<&str>) -> ResponseFormat {
+        self.groups
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or_else(
+                || ResponseFormat::from_accept(accept),
+                |(_, format)| *format,
+            )
+    }
+
+    /// Builds the HTML response for `error` with the handler registered for
+    /// its status code, or with `default` when there is none.
+    pub fn respond(
+        &self,
+        error: &DjangoError,
+        default: impl FnOnce() -> HttpResponse,
+    ) -> HttpResponse {
+        self.get(error.status_code())
+            .map_or_else(default, |handler| handler(error))
+    }
+
+    /// Builds the response for `error` raised by a request to `path`, in the
+    /// format chosen by [`format_for`](Self::format_for).
+    ///
+    /// A handler registered for the format and status code wins. Otherwise
+    /// JSON errors get a problem details body, and HTML errors render the
+    /// `<status>.html` template, with `request_path` and, for client errors,
+    /// `exception` in its context, falling back to `default`.
+    pub fn respond_to(
+        &self,
+        path: &str,
+        accept: Option<&str>,
+        error: &DjangoError,
+        default: impl FnOnce() -> HttpResponse,
+    ) -> HttpResponse {
+        let status = error.status_code();
+        let format = self.format_for(path, accept);
+        let mut response = match (self.handlers.get(&(format, status)), format) {
+            (Some(handler), _) => handler(error),
+            (None, ResponseFormat::Json) => self.problem_response(error),
+            (None, ResponseFormat::Html) => {
+                self.render_template(path, error).unwrap_or_else(default)
+            }
+        };
+        response.headers_mut().insert(
+            http::header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        );
+        response
+    }
+
+    /// Builds an RFC 9457 problem details response. The error message is
+    /// shown for client errors, and for server errors only in debug mode.
+    fn problem_response(&self, error: &DjangoError) -> HttpResponse {
+        let status =
+            StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut problem = serde_json::json!({
+            "type": "about:blank",
+            "title": status.canonical_reason().unwrap_or("Error"),
+            "status": status.as_u16(),
+        });
+        if self.debug || status.is_client_error() {
+            problem["detail"] = serde_json::Value::String(error_detail(error));
+        }
+        let mut response = HttpResponse::new(status, problem.to_string());
+        response.set_content_type("application/problem+json");
+        response
+    }
+
+    /// Renders the `<status>.html` template, if there is an engine with one.
+    fn render_template(&self, path: &str, error: &DjangoError) -> Option<HttpResponse> {
+        let engine = self.engine.as_ref()?;
+        let status = StatusCode::from_u16(error.status_code()).ok()?;
+        let template_name = format!("{}.html", status.as_u16());
+        let mut context = Context::new();
+        context.set("request_path", ContextValue::from(path));
+        if status.is_client_error() {
+            context.set("exception", ContextValue::from(error_detail(error)));
+        }
+        match engine.render_to_string(&template_name, &mut context) {
+            Ok(html) => Some(HttpResponse::new(status, html)),
+            Err(DjangoError::TemplateDoesNotExist(_)) => None,
+            Err(e) => {
+                tracing::warn!("Failed to render {template_name}: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Returns an error's message without the variant's prefix.
+fn error_detail(error: &DjangoError) -> String {
+    match error {
+        DjangoError::NotFound(msg)
+        | DjangoError::BadRequest(msg)
+        | DjangoError::PermissionDenied(msg)
+        | DjangoError::SuspiciousOperation(msg) => msg.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl std::fmt::Debug for ErrorHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut codes: Vec<_> = self.handlers.keys().collect();
+        codes.sort_unstable_by_key(|(format, code)| (*code, *format == ResponseFormat::Json));
+        f.debug_struct("ErrorHandlers")
+            .field("codes", &codes)
+            .field("groups", &self.groups)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respond_uses_registered_handler() {
+        let mut handlers = ErrorHandlers::new();
+        handlers.register(404, |err| HttpResponse::not_found(format!("custom: {err}")));
+        assert_eq!(handlers.len(), 1);
+
+        let response = handlers.respond(&DjangoError::NotFound("/x/".into()), || {
+            HttpResponse::not_found("default")
+        });
+        assert!(response.content_bytes().unwrap().starts_with(b"custom"));
+
+        let response = handlers.respond(&DjangoError::BadRequest("bad".into()), || {
+            HttpResponse::bad_request("default")
+        });
+        assert_eq!(response.content_bytes().unwrap(), b"default");
+    }
+
+    #[test]
+    fn test_format_from_accept() {
+        use ResponseFormat::{Html, Json};
+        assert_eq!(ResponseFormat::from_accept(None), Html);
+        assert_eq!(ResponseFormat::from_accept(Some("*/*")), Html);
+        assert_eq!(ResponseFormat::from_accept(Some("application/json")), Json);
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/vnd.api+json")),
+            Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some(
+                "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+            )),
+            Html
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("text/html;q=0.5, application/json")),
+            Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/json;q=0.5, text/html")),
+            Html
+        );
+    }
+
+    #[test]
+    fn test_format_for_prefers_longest_group() {
+        let mut handlers = ErrorHandlers::new();
+        handlers.group_format("/api/", ResponseFormat::Json);
+        handlers.group_format("/api/docs/", ResponseFormat::Html);
+
+        assert_eq!(
+            handlers.format_for("/api/orders/", Some("text/html")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            handlers.format_for("/api/docs/intro/", Some("application/json")),
+            ResponseFormat::Html
+        );
+        assert_eq!(
+            handlers.format_for("/shop/", Some("application/json")),
+            ResponseFormat::Json
+        );
+        assert_eq!(handlers.format_for("/shop/", None), ResponseFormat::Html);
+    }
+
+    #[test]
+    fn test_respond_to_json_problem() {
+        let mut handlers = ErrorHandlers::new();
+        handlers.group_format("/api/", ResponseFormat::Json);
+        handlers.register(404, |_| HttpResponse::not_found("<h1>custom</h1>"));
+
+        let error = DjangoError::NotFound("No order 9".into());
+        let response = handlers.respond_to("/api/orders/9/", None, &error, || {
+            HttpResponse::not_found("default")
+        });
+        assert_eq!(response.status().as_u16(), 404);
+        assert_eq!(response.content_type(), "application/problem+json");
+        assert_eq!(
+            response.headers().get("x-content-type-options").unwrap(),
+            "nosniff"
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(&response.content_bytes().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": 404,
+                "detail": "No order 9",
+            })
+        );
+
+        // Server errors only explain themselves in debug mode.
+        let error = DjangoError::InternalServerError("db password wrong".into());
+        let response = handlers.respond_to("/api/", None, &error, || unreachable!());
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        assert!(!body.contains("detail"));
+
+        handlers.register_json(404, |_| HttpResponse::not_found("{}"));
+        let error = DjangoError::NotFound("x".into());
+        let response = handlers.respond_to("/api/", None, &error, || unreachable!());
+        assert_eq!(response.content_bytes().unwrap(), b"{}");
+    }
+
+    #[test]
+    fn test_respond_to_html_renders_status_template() {
+        let engine = Engine::new();
+        engine.add_string_template("404.html", "<p>{{ request_path }}: {{ exception }}</p>");
+        let handlers = ErrorHandlers {
+            engine: Some(Arc::new(engine)),
+            ..ErrorHandlers::new()
+        };
+
+        let error = DjangoError::NotFound("gone".into());
+        let response = handlers.respond_to("/shop/x/", Some("text/html"), &error, || {
+            HttpResponse::not_found("default")
+        });
+        assert_eq!(response.status().as_u16(), 404);
+        assert_eq!(response.content_bytes().unwrap(), b"<p>/shop/x/: gone</p>");
+        assert_eq!(
+            response.headers().get("x-content-type-options").unwrap(),
+            "nosniff"
+        );
+
+        // Without a 500.html the default response is used.
+        let error = DjangoError::InternalServerError("boom".into());
+        let response = handlers.respond_to("/shop/", None, &error, || {
+            HttpResponse::server_error("default")
+        });
+        assert_eq!(response.content_bytes().unwrap(), b"default");
+    }
+}
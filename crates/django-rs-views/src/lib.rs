@@ -7,9 +7,11 @@
 //! ## Modules
 //!
 //! - [`middleware`] - Middleware trait and pipeline, built-in middleware components
+//! - [`context_processors`] - Template context processors reading request extensions
 //! - [`views`] - Function-based views, class-based views, and generic CRUD views
 //! - [`session`] - Session framework with pluggable backends
 //! - [`server`] - HTTP server integration via Axum
+//! - [`error_handlers`] - Custom responses for 400, 404 and 500 errors
 //! - [`contrib`] - Sites, Redirects, Flatpages, and Syndication frameworks
 //!
 //! ## Quick Start
@@ -44,7 +46,9 @@
 #![allow(clippy::implicit_hasher)]
 #![allow(clippy::option_if_let_else)]
 
+pub mod context_processors;
 pub mod contrib;
+pub mod error_handlers;
 pub mod middleware;
 pub mod pagination;
 pub mod server;
@@ -54,14 +58,16 @@ pub mod views;
 // Re-export the most commonly used types at the crate root.
 pub use middleware::builtin::{
     add_message, add_message_with_tags, error, get_messages, info, success, warning,
-    AuthenticationMiddleware, CacheMiddleware, LocaleMiddleware, LoginRequiredMiddleware, Message,
-    MessageLevel, MessageMiddleware,
+    AuditContextMiddleware, AuthenticationMiddleware, CacheMiddleware, CurrentUser,
+    LocaleMiddleware, LoginRequiredMiddleware, Message, MessageLevel, MessageMiddleware,
+    MessageStore, PageCacheBackend, ReadYourWritesMiddleware, SessionVerifier, TimeoutMiddleware,
 };
-pub use middleware::{Middleware, MiddlewarePipeline};
+pub use middleware::{Middleware, MiddlewareCondition, MiddlewarePipeline};
 pub use server::DjangoApp;
 pub use session::{
     CookieSessionBackend, DatabaseSessionBackend, FileSessionBackend, InMemorySessionBackend,
-    SessionBackend, SessionData, SessionMiddleware, SignedCookieSessionBackend,
+    SessionBackend, SessionData, SessionExpiry, SessionMiddleware, SharedSession,
+    SignedCookieSessionBackend,
 };
 pub use views::{
     bind_form_from_request, cleaned_data_as_strings, extract_post_data, form_context_to_json,
@@ -69,5 +75,5 @@ pub use views::{
     CreateView, DateDetailView, DateMixin, DayArchiveView, DeleteView, DetailView, FormView,
     ListView, LoginRequiredMixin, MonthArchiveView, PermissionRequiredMixin, RedirectView,
     TemplateResponseMixin, TemplateView, TodayArchiveView, UpdateView, View, ViewFunction,
-    YearArchiveView,
+    WizardStep, YearArchiveView,
 };
@@ -7,16 +7,25 @@
 //! - [`GZipMiddleware`] - Compresses response bodies using gzip
 //! - [`ConditionalGetMiddleware`] - Handles ETag and Last-Modified conditional requests
 //! - [`CorsMiddleware`] - Adds CORS headers for cross-origin requests
+//! - [`TimeoutMiddleware`] - Returns 504 when a view exceeds its deadline
+//! - [`AuditContextMiddleware`] - Attributes a request's queries to its user and request id
+//! - [`ReadYourWritesMiddleware`] - Pins a client's reads to the primary after it writes
 
 use async_trait::async_trait;
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use std::collections::HashMap;
 use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
 
 use django_rs_core::DjangoError;
+use django_rs_db::{AuditContext, PrimaryPin};
+use django_rs_http::cookies::{Cookie, SameSite};
 use django_rs_http::{HttpRequest, HttpResponse};
 
 use super::Middleware;
+use crate::session::SessionData;
 
 // ── SecurityMiddleware ──────────────────────────────────────────────────
 
@@ -499,42 +508,153 @@ impl Middleware for CorsMiddleware {
 
 // ── AuthenticationMiddleware ────────────────────────────────────────
 
+/// The user making a request, attached as a request extension by
+/// [`AuthenticationMiddleware`].
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_http::HttpRequest;
+/// use django_rs_views::CurrentUser;
+///
+/// let request = HttpRequest::builder()
+///     .extension(CurrentUser::authenticated("42"))
+///     .build();
+/// let user = request.extensions().get::<CurrentUser>().unwrap();
+/// assert!(user.is_authenticated());
+/// assert_eq!(user.user_id.as_deref(), Some("42"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CurrentUser {
+    /// The id of the logged-in user, or `None` for an anonymous request.
+    pub user_id: Option<String>,
+    /// Whether the logged-in user is staff. Only known when the user was
+    /// loaded, so `false` unless a [`SessionVerifier`] or login set it.
+    pub is_staff: bool,
+}
+
+impl CurrentUser {
+    /// Creates the current user for a logged-in user.
+    pub fn authenticated(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: Some(user_id.into()),
+            is_staff: false,
+        }
+    }
+
+    /// Creates the current user for an anonymous request.
+    pub const fn anonymous() -> Self {
+        Self {
+            user_id: None,
+            is_staff: false,
+        }
+    }
+
+    /// Sets whether the user is staff.
+    #[must_use]
+    pub const fn with_staff(mut self, is_staff: bool) -> Self {
+        self.is_staff = is_staff;
+        self
+    }
+
+    /// Returns `true` if a user is logged in.
+    pub const fn is_authenticated(&self) -> bool {
+        self.user_id.is_some()
+    }
+}
+
+/// Checks that a logged-in session still belongs to its user.
+///
+/// [`AuthenticationMiddleware`] cannot load users itself; the auth crate's
+/// `SessionHashVerifier` implements this trait over an auth backend.
+#[async_trait]
+pub trait SessionVerifier: Send + Sync {
+    /// Returns the user `user_id` if they exist, may log in, and
+    /// `session_hash` is their current session auth hash.
+    async fn verify(&self, user_id: &str, session_hash: &str) -> Option<CurrentUser>;
+}
+
 /// Middleware that loads user information from the session.
 ///
 /// Reads the `_auth_user_id` key from the session data (set by `SessionMiddleware`)
-/// and populates `META["USER_ID"]` and `META["USER_AUTHENTICATED"]`. This mirrors
-/// Django's `AuthenticationMiddleware`.
+/// and attaches a [`CurrentUser`] to the request's extensions. `META["USER_ID"]`
+/// and `META["USER_AUTHENTICATED"]` are still populated for code that checks
+/// the string flags. This mirrors Django's `AuthenticationMiddleware`.
+///
+/// With a [`SessionVerifier`] (see [`with_verifier`](Self::with_verifier)),
+/// the session's `_auth_user_hash` is checked on every request, and a
+/// session whose hash is missing or no longer matches, for example because
+/// the password was changed, is flushed and the request is anonymous.
+/// Without one the session's user id is trusted as is.
 ///
 /// This middleware must be placed after `SessionMiddleware` in the pipeline.
-#[derive(Debug, Clone, Default)]
-pub struct AuthenticationMiddleware;
+#[derive(Clone, Default)]
+pub struct AuthenticationMiddleware {
+    verifier: Option<Arc<dyn SessionVerifier>>,
+}
+
+impl AuthenticationMiddleware {
+    /// Creates the middleware without session verification.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verifies each session's auth hash with `verifier`.
+    #[must_use]
+    pub fn with_verifier(mut self, verifier: Arc<dyn SessionVerifier>) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+}
+
+impl std::fmt::Debug for AuthenticationMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthenticationMiddleware")
+            .field("verifies_sessions", &self.verifier.is_some())
+            .finish()
+    }
+}
 
 #[async_trait]
 impl Middleware for AuthenticationMiddleware {
     async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
-        // Read session data from META (set by SessionMiddleware)
-        let session_data_str = request
-            .meta()
-            .get("SESSION_DATA")
-            .cloned()
-            .unwrap_or_else(|| "{}".to_string());
-
-        let session_data: std::collections::HashMap<String, serde_json::Value> =
-            serde_json::from_str(&session_data_str).unwrap_or_default();
+        let session = SessionData::from_request(request);
+        let user_id = session.get("_auth_user_id").map(|value| match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            other => other.to_string(),
+        });
+        let user = match (user_id, &self.verifier) {
+            (Some(user_id), Some(verifier)) => {
+                let hash = session.get("_auth_user_hash").and_then(|v| v.as_str());
+                let user = match hash {
+                    Some(hash) => verifier.verify(&user_id, hash).await,
+                    None => None,
+                };
+                if user.is_none() {
+                    let mut session = session;
+                    session.flush();
+                    session.save_to_request(request);
+                }
+                user.map(|user| CurrentUser {
+                    user_id: Some(user_id),
+                    ..user
+                })
+            }
+            (user_id, _) => user_id.map(CurrentUser::authenticated),
+        };
 
-        // Look for "_auth_user_id" key in session data
-        if let Some(user_id_val) = session_data.get("_auth_user_id") {
-            let user_id = match user_id_val {
-                serde_json::Value::String(s) => s.clone(),
-                serde_json::Value::Number(n) => n.to_string(),
-                other => other.to_string(),
-            };
-            let meta = request.meta_mut();
-            meta.insert("USER_ID".to_string(), user_id);
+        let meta = request.meta_mut();
+        if let Some(user) = user.filter(CurrentUser::is_authenticated) {
+            if let Some(user_id) = &user.user_id {
+                meta.insert("USER_ID".to_string(), user_id.clone());
+            }
             meta.insert("USER_AUTHENTICATED".to_string(), "true".to_string());
+            request.extensions_mut().insert(user);
         } else {
-            let meta = request.meta_mut();
+            meta.remove("USER_ID");
             meta.insert("USER_AUTHENTICATED".to_string(), "false".to_string());
+            request.extensions_mut().insert(CurrentUser::anonymous());
         }
 
         None
@@ -557,6 +677,203 @@ impl Middleware for AuthenticationMiddleware {
     }
 }
 
+// ── AuditContextMiddleware ─────────────────────────────────────────
+
+/// Middleware that attributes the database work of a request to its user.
+///
+/// Attaches an [`AuditContext`] to the request's extensions with the
+/// [`CurrentUser`] id and a request id, taken from the configured header
+/// (`X-Request-ID` by default) or generated. The pipeline runs the view inside
+/// that context, so every statement it issues carries a sqlcommenter comment
+/// such as `/*request_id='…',user_id='42'*/`, and signal receivers and audit
+/// log writers can read it with [`AuditContext::current`]. The request id is
+/// echoed in the response header.
+///
+/// This middleware must be placed after `AuthenticationMiddleware` in the
+/// pipeline.
+#[derive(Debug, Clone)]
+pub struct AuditContextMiddleware {
+    /// The header carrying an upstream request id.
+    pub request_id_header: String,
+}
+
+impl Default for AuditContextMiddleware {
+    fn default() -> Self {
+        Self {
+            request_id_header: "X-Request-ID".to_string(),
+        }
+    }
+}
+
+impl AuditContextMiddleware {
+    /// Creates the middleware, reading request ids from `X-Request-ID`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads request ids from `header` instead of `X-Request-ID`.
+    #[must_use]
+    pub fn with_request_id_header(mut self, header: &str) -> Self {
+        self.request_id_header = header.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl Middleware for AuditContextMiddleware {
+    async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
+        let request_id = request
+            .headers()
+            .get(self.request_id_header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| !value.is_empty())
+            .map_or_else(|| uuid::Uuid::new_v4().to_string(), str::to_string);
+        let mut context = AuditContext::new().request_id(request_id);
+        if let Some(user_id) = request
+            .extensions()
+            .get::<CurrentUser>()
+            .and_then(|user| user.user_id.clone())
+        {
+            context = context.user_id(user_id);
+        }
+        request.extensions_mut().insert(context);
+        None
+    }
+
+    async fn process_response(
+        &self,
+        request: &HttpRequest,
+        mut response: HttpResponse,
+    ) -> HttpResponse {
+        let request_id = request
+            .extensions()
+            .get::<AuditContext>()
+            .and_then(|context| context.request_id.as_deref());
+        if let Some(value) = request_id.and_then(|id| http::header::HeaderValue::from_str(id).ok())
+        {
+            if let Ok(name) =
+                http::header::HeaderName::from_bytes(self.request_id_header.as_bytes())
+            {
+                response.headers_mut().insert(name, value);
+            }
+        }
+        response
+    }
+
+    async fn process_exception(
+        &self,
+        _request: &HttpRequest,
+        _error: &DjangoError,
+    ) -> Option<HttpResponse> {
+        None
+    }
+}
+
+// ── ReadYourWritesMiddleware ───────────────────────────────────────
+
+/// Middleware that pins a client's database reads to the primary after it
+/// writes, so it never reads its own changes back from a stale replica.
+///
+/// Each request runs in a [`PrimaryPin`] scope: once the view writes through
+/// the ORM or routes a write through
+/// [`RouterChain::db_for_write`](django_rs_db::RouterChain::db_for_write),
+/// its later reads go to the primary. Requests with an unsafe method
+/// (`POST`, `PUT`, `PATCH`, `DELETE`) read from the primary from the start.
+/// After a request that wrote or used an unsafe method, a cookie keeps the
+/// client's requests pinned for [`duration`](Self::duration), so the page a
+/// form redirects to after saving shows the saved data. Set the duration to
+/// the expected replication lag.
+///
+/// This mirrors the pinning router middleware of `django-multidb-router`.
+#[derive(Debug, Clone)]
+pub struct ReadYourWritesMiddleware {
+    /// How long a client's requests stay pinned after a write.
+    pub duration: Duration,
+    /// The name of the cookie marking a pinned client.
+    pub cookie_name: String,
+}
+
+impl Default for ReadYourWritesMiddleware {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(15),
+            cookie_name: "db_pin_writes".to_string(),
+        }
+    }
+}
+
+impl ReadYourWritesMiddleware {
+    /// Creates the middleware, pinning clients for 15 seconds after a write.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pins clients for `duration` after a write instead of 15 seconds.
+    #[must_use]
+    pub const fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Names the pinning cookie `name` instead of `db_pin_writes`.
+    #[must_use]
+    pub fn with_cookie_name(mut self, name: &str) -> Self {
+        self.cookie_name = name.to_string();
+        self
+    }
+}
+
+/// Returns `true` if `method` may change data.
+fn is_unsafe_method(method: &http::Method) -> bool {
+    !matches!(
+        *method,
+        http::Method::GET | http::Method::HEAD | http::Method::OPTIONS | http::Method::TRACE
+    )
+}
+
+#[async_trait]
+impl Middleware for ReadYourWritesMiddleware {
+    async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
+        let pin =
+            if is_unsafe_method(request.method()) || request.cookie(&self.cookie_name).is_some() {
+                PrimaryPin::pinned()
+            } else {
+                PrimaryPin::new()
+            };
+        request.extensions_mut().insert(Arc::new(pin));
+        None
+    }
+
+    async fn process_response(
+        &self,
+        request: &HttpRequest,
+        mut response: HttpResponse,
+    ) -> HttpResponse {
+        let wrote = request
+            .extensions()
+            .get::<Arc<PrimaryPin>>()
+            .is_some_and(|pin| pin.has_written());
+        if wrote || is_unsafe_method(request.method()) {
+            response.set_cookie(
+                Cookie::new(&self.cookie_name, "1")
+                    .max_age(self.duration.as_secs())
+                    .httponly(true)
+                    .secure(request.is_secure())
+                    .samesite(SameSite::Lax),
+            );
+        }
+        response
+    }
+
+    async fn process_exception(
+        &self,
+        _request: &HttpRequest,
+        _error: &DjangoError,
+    ) -> Option<HttpResponse> {
+        None
+    }
+}
+
 // ── MessageMiddleware ──────────────────────────────────────────────
 
 /// Message severity levels matching Django's message framework.
@@ -605,10 +922,24 @@ pub struct Message {
     pub extra_tags: String,
 }
 
+/// The flash messages of the current request, attached as a request
+/// extension by [`MessageMiddleware`].
+///
+/// Use [`add_message`] and [`get_messages`] rather than editing it directly:
+/// they also keep the session in sync.
+#[derive(Debug, Clone, Default)]
+pub struct MessageStore {
+    /// Messages loaded from the session at the start of the request.
+    pub stored: Vec<Message>,
+    /// Messages added while handling this request.
+    pub added: Vec<Message>,
+}
+
 /// Middleware that manages the messages framework — stores and retrieves flash messages.
 ///
-/// On request, loads existing messages from the session (key `_messages`). On response,
-/// saves any newly added messages back to the session. This mirrors Django's
+/// On request, loads existing messages from the session (key `_messages`) into a
+/// [`MessageStore`] request extension. Messages added during the request are
+/// written to the session as they are added. This mirrors Django's
 /// `MessageMiddleware`.
 ///
 /// This middleware must be placed after `SessionMiddleware` in the pipeline.
@@ -629,42 +960,27 @@ impl Middleware for MessageMiddleware {
             serde_json::from_str(&session_data_str).unwrap_or_default();
 
         // Extract messages from session (key: "_messages")
-        let messages_json = if let Some(messages_val) = session_data.get("_messages") {
-            serde_json::to_string(messages_val).unwrap_or_else(|_| "[]".to_string())
-        } else {
-            "[]".to_string()
-        };
+        let stored = session_data
+            .get("_messages")
+            .and_then(|messages_val| serde_json::from_value(messages_val.clone()).ok())
+            .unwrap_or_default();
 
-        let meta = request.meta_mut();
-        meta.insert("_messages_store".to_string(), messages_json);
-        // Track newly added messages separately
-        meta.insert("_messages_added".to_string(), "[]".to_string());
+        request.extensions_mut().insert(MessageStore {
+            stored,
+            added: Vec::new(),
+        });
 
         None
     }
 
     async fn process_response(
         &self,
-        request: &HttpRequest,
+        _request: &HttpRequest,
         response: HttpResponse,
     ) -> HttpResponse {
-        let meta = request.meta();
-
-        // Check if any new messages were added during the request
-        let added_json = meta
-            .get("_messages_added")
-            .cloned()
-            .unwrap_or_else(|| "[]".to_string());
-
-        let added: Vec<Message> = serde_json::from_str(&added_json).unwrap_or_default();
-        if added.is_empty() {
-            return response;
-        }
-
-        // We need to signal to SessionMiddleware that session data changed.
-        // Since we cannot modify request in process_response, the messages
-        // are persisted by the view layer calling add_message which updates
-        // SESSION_DATA and SESSION_MODIFIED directly.
+        // Since we cannot modify the request in process_response, new messages
+        // are persisted by add_message, which saves them to the request's
+        // session.
         response
     }
 
@@ -703,44 +1019,27 @@ pub fn add_message_with_tags(
     };
 
     // Add to the added messages tracker
-    let added_json = request
-        .meta()
-        .get("_messages_added")
-        .cloned()
-        .unwrap_or_else(|| "[]".to_string());
-    let mut added: Vec<Message> = serde_json::from_str(&added_json).unwrap_or_default();
-    added.push(msg.clone());
-    let new_added = serde_json::to_string(&added).unwrap_or_else(|_| "[]".to_string());
-
-    // Also write into the session data so SessionMiddleware will persist them
-    let session_data_str = request
-        .meta()
-        .get("SESSION_DATA")
-        .cloned()
-        .unwrap_or_else(|| "{}".to_string());
-    let mut session_data: std::collections::HashMap<String, serde_json::Value> =
-        serde_json::from_str(&session_data_str).unwrap_or_default();
+    request
+        .extensions_mut()
+        .get_or_insert_default::<MessageStore>()
+        .added
+        .push(msg.clone());
+
+    // Also write into the session so SessionMiddleware will persist them
+    let mut session = SessionData::from_request(request);
 
     // Merge existing messages from session with newly added
-    let mut all_messages: Vec<Message> = if let Some(existing) = session_data.get("_messages") {
-        serde_json::from_value(existing.clone()).unwrap_or_default()
-    } else {
-        Vec::new()
-    };
+    let mut all_messages: Vec<Message> = session
+        .get("_messages")
+        .and_then(|existing| serde_json::from_value(existing.clone()).ok())
+        .unwrap_or_default();
     all_messages.push(msg);
 
-    session_data.insert(
-        "_messages".to_string(),
+    session.set(
+        "_messages",
         serde_json::to_value(&all_messages).unwrap_or(serde_json::Value::Array(vec![])),
     );
-
-    let new_session_data =
-        serde_json::to_string(&session_data).unwrap_or_else(|_| "{}".to_string());
-
-    let meta = request.meta_mut();
-    meta.insert("_messages_added".to_string(), new_added);
-    meta.insert("SESSION_DATA".to_string(), new_session_data);
-    meta.insert("SESSION_MODIFIED".to_string(), "true".to_string());
+    session.save_to_request(request);
 }
 
 /// Retrieves and consumes all pending messages from the request.
@@ -748,25 +1047,12 @@ pub fn add_message_with_tags(
 /// After calling this function, the messages are cleared from the store.
 /// Subsequent calls will return an empty list until new messages are added.
 pub fn get_messages(request: &HttpRequest) -> Vec<Message> {
-    // Get messages from the store (loaded by MessageMiddleware)
-    let store_json = request
-        .meta()
-        .get("_messages_store")
-        .cloned()
-        .unwrap_or_else(|| "[]".to_string());
-    let stored: Vec<Message> = serde_json::from_str(&store_json).unwrap_or_default();
-
-    // Also include newly added messages from this request
-    let added_json = request
-        .meta()
-        .get("_messages_added")
-        .cloned()
-        .unwrap_or_else(|| "[]".to_string());
-    let added: Vec<Message> = serde_json::from_str(&added_json).unwrap_or_default();
-
-    let mut all = stored;
-    all.extend(added);
-    all
+    // Messages loaded by MessageMiddleware, then those added during this request
+    request
+        .extensions()
+        .get::<MessageStore>()
+        .map(|store| store.stored.iter().chain(&store.added).cloned().collect())
+        .unwrap_or_default()
 }
 
 /// Convenience function: adds an info-level message.
@@ -791,11 +1077,14 @@ pub fn error(request: &mut HttpRequest, message: &str) {
 
 // ── LocaleMiddleware ───────────────────────────────────────────────
 
+/// The session key that stores the language chosen by the user.
+pub const LANGUAGE_SESSION_KEY: &str = "_language";
+
 /// Middleware that detects the user's preferred language and sets it on the request.
 ///
 /// Checks language preference in this order:
-/// 1. Session key `_language`
-/// 2. Cookie `django_language`
+/// 1. Session key [`LANGUAGE_SESSION_KEY`]
+/// 2. The language cookie (`django_language` by default)
 /// 3. `Accept-Language` header
 /// 4. Default language code (`en`)
 ///
@@ -809,6 +1098,8 @@ pub struct LocaleMiddleware {
     pub default_language: String,
     /// Supported language codes (e.g., `["en", "fr", "de"]`).
     pub supported_languages: Vec<String>,
+    /// The name of the cookie that stores the chosen language.
+    pub cookie_name: String,
 }
 
 impl Default for LocaleMiddleware {
@@ -816,11 +1107,26 @@ impl Default for LocaleMiddleware {
         Self {
             default_language: "en".to_string(),
             supported_languages: vec!["en".to_string()],
+            cookie_name: "django_language".to_string(),
         }
     }
 }
 
 impl LocaleMiddleware {
+    /// Creates a `LocaleMiddleware` for the `LANGUAGE_CODE`, `LANGUAGES` and
+    /// `LANGUAGE_COOKIE_NAME` settings.
+    pub fn from_settings(settings: &django_rs_core::Settings) -> Self {
+        Self {
+            default_language: settings.language_code.clone(),
+            supported_languages: settings
+                .languages
+                .iter()
+                .map(|(code, _)| code.clone())
+                .collect(),
+            cookie_name: settings.language_cookie_name.clone(),
+        }
+    }
+
     /// Parses the `Accept-Language` header and returns the best matching language.
     ///
     /// Supports quality values (e.g., `en-US,en;q=0.9,fr;q=0.8`).
@@ -882,7 +1188,7 @@ impl Middleware for LocaleMiddleware {
         let session_data: std::collections::HashMap<String, serde_json::Value> =
             serde_json::from_str(&session_data_str).unwrap_or_default();
 
-        if let Some(serde_json::Value::String(lang)) = session_data.get("_language") {
+        if let Some(serde_json::Value::String(lang)) = session_data.get(LANGUAGE_SESSION_KEY) {
             if self.supported_languages.iter().any(|s| s == lang) {
                 request
                     .meta_mut()
@@ -892,7 +1198,7 @@ impl Middleware for LocaleMiddleware {
         }
 
         // 2. Check cookie
-        if let Some(lang) = Self::get_cookie_value(request, "django_language") {
+        if let Some(lang) = Self::get_cookie_value(request, &self.cookie_name) {
             if self.supported_languages.contains(&lang) {
                 request.meta_mut().insert("LANGUAGE_CODE".to_string(), lang);
                 return None;
@@ -1063,32 +1369,105 @@ impl Middleware for LoginRequiredMiddleware {
 
 // ── CacheMiddleware ────────────────────────────────────────────────
 
-/// Full-page caching middleware that caches GET/HEAD responses in memory.
+/// Storage for the pages cached by [`CacheMiddleware`].
+///
+/// Each page is stored as two entries, its metadata and its body, which are
+/// read and written together. The middleware keeps pages in process with
+/// [`LocalPageCache`] by default; the cli crate's `VersionedCache`
+/// implements this trait over any cache backend, so pages can be shared
+/// between processes and are keyed with the cache's prefix and version.
+#[async_trait]
+pub trait PageCacheBackend: Send + Sync {
+    /// Returns the entries found for `keys`, skipping missing or expired
+    /// ones.
+    async fn get_many(&self, keys: &[&str]) -> Result<HashMap<String, Vec<u8>>, DjangoError>;
+
+    /// Stores every entry, expiring them after `timeout`.
+    async fn set_many(
+        &self,
+        entries: HashMap<String, Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<(), DjangoError>;
+}
+
+/// In-process [`PageCacheBackend`] used by [`CacheMiddleware`] by default.
+///
+/// Entries live in a thread-safe map and are lost on restart.
+#[derive(Debug, Clone, Default)]
+pub struct LocalPageCache {
+    entries: Arc<tokio::sync::RwLock<HashMap<String, LocalPageEntry>>>,
+}
+
+/// An entry of a [`LocalPageCache`] with its expiration time.
+#[derive(Debug, Clone)]
+struct LocalPageEntry {
+    value: Vec<u8>,
+    expires_at: std::time::Instant,
+}
+
+impl LocalPageCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PageCacheBackend for LocalPageCache {
+    async fn get_many(&self, keys: &[&str]) -> Result<HashMap<String, Vec<u8>>, DjangoError> {
+        let now = std::time::Instant::now();
+        let entries = self.entries.read().await;
+        Ok(keys
+            .iter()
+            .filter_map(|key| {
+                let entry = entries.get(*key)?;
+                (entry.expires_at > now).then(|| ((*key).to_string(), entry.value.clone()))
+            })
+            .collect())
+    }
+
+    async fn set_many(
+        &self,
+        entries: HashMap<String, Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<(), DjangoError> {
+        let expires_at = std::time::Instant::now() + timeout;
+        let mut stored = self.entries.write().await;
+        stored.retain(|_, entry| entry.expires_at > std::time::Instant::now());
+        for (key, value) in entries {
+            stored.insert(key, LocalPageEntry { value, expires_at });
+        }
+        Ok(())
+    }
+}
+
+/// Full-page caching middleware that caches GET/HEAD responses.
 ///
 /// Combines the functionality of Django's `UpdateCacheMiddleware` and
 /// `FetchFromCacheMiddleware` into a single middleware. Only cacheable
-/// responses (200 OK, no `Cache-Control: private`) are cached.
+/// responses (200 OK, no `Cache-Control: private`, no `Set-Cookie`) are
+/// cached.
 ///
-/// The cache is stored in-memory using a thread-safe map. Cache keys
-/// are derived from the request URL and the `key_prefix`.
-#[derive(Debug, Clone)]
+/// Pages are kept in a [`PageCacheBackend`], in process unless another is
+/// set with [`with_cache`](Self::with_cache). Cache keys are derived from
+/// the request URL and the `key_prefix`. A failing cache is treated as a
+/// miss, so the page is served uncached.
+#[derive(Clone)]
 pub struct CacheMiddleware {
     /// Cache timeout in seconds.
     pub cache_timeout: u64,
     /// Prefix prepended to all cache keys.
     pub key_prefix: String,
-    /// Internal cache storage.
-    cache: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, CachedResponse>>>,
+    /// Where pages are stored.
+    cache: Arc<dyn PageCacheBackend>,
 }
 
-/// A cached HTTP response with its expiration time.
-#[derive(Debug, Clone)]
-struct CachedResponse {
-    status: http::StatusCode,
-    headers: http::HeaderMap,
-    body: Vec<u8>,
+/// The metadata entry of a cached page; the body is stored separately.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CachedPage {
+    status: u16,
     content_type: String,
-    expires_at: std::time::Instant,
+    headers: Vec<(String, Vec<u8>)>,
 }
 
 impl Default for CacheMiddleware {
@@ -1096,11 +1475,20 @@ impl Default for CacheMiddleware {
         Self {
             cache_timeout: 600,
             key_prefix: String::new(),
-            cache: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            cache: Arc::new(LocalPageCache::new()),
         }
     }
 }
 
+impl std::fmt::Debug for CacheMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheMiddleware")
+            .field("cache_timeout", &self.cache_timeout)
+            .field("key_prefix", &self.key_prefix)
+            .finish_non_exhaustive()
+    }
+}
+
 impl CacheMiddleware {
     /// Creates a new `CacheMiddleware` with the given timeout (in seconds).
     pub fn new(cache_timeout: u64) -> Self {
@@ -1117,10 +1505,22 @@ impl CacheMiddleware {
         self
     }
 
+    /// Stores pages in `cache` instead of in process.
+    #[must_use]
+    pub fn with_cache(mut self, cache: Arc<dyn PageCacheBackend>) -> Self {
+        self.cache = cache;
+        self
+    }
+
     fn cache_key(&self, request: &HttpRequest) -> String {
         format!("{}:{}", self.key_prefix, request.path())
     }
 
+    /// Returns the keys of a page's metadata and body entries.
+    fn entry_keys(key: &str) -> (String, String) {
+        (format!("{key}:meta"), format!("{key}:body"))
+    }
+
     fn is_cacheable_request(request: &HttpRequest) -> bool {
         matches!(*request.method(), http::Method::GET | http::Method::HEAD)
     }
@@ -1130,6 +1530,11 @@ impl CacheMiddleware {
             return false;
         }
 
+        // A cached cookie would be replayed to every later visitor
+        if response.headers().contains_key(http::header::SET_COOKIE) {
+            return false;
+        }
+
         // Don't cache responses with Cache-Control: private or no-cache
         if let Some(cc) = response
             .headers()
@@ -1156,26 +1561,38 @@ impl Middleware for CacheMiddleware {
             return None;
         }
 
-        let key = self.cache_key(request);
-        let cache = self.cache.read().await;
-
-        if let Some(cached) = cache.get(&key) {
-            if cached.expires_at > std::time::Instant::now() {
-                // Cache hit — return cached response
-                let mut resp = HttpResponse::with_bytes(cached.status, cached.body.clone());
-                resp.set_content_type(&cached.content_type);
-                for (name, value) in &cached.headers {
-                    resp.headers_mut().insert(name.clone(), value.clone());
-                }
-                resp.headers_mut().insert(
-                    http::header::HeaderName::from_static("x-cache"),
-                    http::header::HeaderValue::from_static("HIT"),
-                );
-                return Some(resp);
+        let (meta_key, body_key) = Self::entry_keys(&self.cache_key(request));
+        let mut entries = match self.cache.get_many(&[&meta_key, &body_key]).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!(error = %e, "Page cache read failed");
+                return None;
+            }
+        };
+        let page: CachedPage = serde_json::from_slice(&entries.remove(&meta_key)?).ok()?;
+        let body = entries.remove(&body_key)?;
+        let status = http::StatusCode::from_u16(page.status).ok()?;
+
+        // Cache hit — return cached response
+        let mut resp = HttpResponse::with_bytes(status, body);
+        resp.set_content_type(&page.content_type);
+        let mut headers = http::HeaderMap::new();
+        for (name, value) in page.headers {
+            if let (Ok(name), Ok(value)) = (
+                http::header::HeaderName::from_bytes(name.as_bytes()),
+                http::header::HeaderValue::from_bytes(&value),
+            ) {
+                headers.append(name, value);
             }
         }
-
-        None
+        // Extending replaces each header already on the response but keeps
+        // every stored value, so repeated headers like `Link` survive.
+        resp.headers_mut().extend(headers);
+        resp.headers_mut().insert(
+            http::header::HeaderName::from_static("x-cache"),
+            http::header::HeaderValue::from_static("HIT"),
+        );
+        Some(resp)
     }
 
     async fn process_response(
@@ -1187,18 +1604,28 @@ impl Middleware for CacheMiddleware {
             return response;
         }
 
-        let key = self.cache_key(request);
-        let cached = CachedResponse {
-            status: response.status(),
-            headers: response.headers().clone(),
-            body: response.content_bytes().unwrap_or_default(),
+        let (meta_key, body_key) = Self::entry_keys(&self.cache_key(request));
+        let page = CachedPage {
+            status: response.status().as_u16(),
             content_type: response.content_type().to_string(),
-            expires_at: std::time::Instant::now()
-                + std::time::Duration::from_secs(self.cache_timeout),
+            headers: response
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+                .collect(),
         };
-
-        let mut cache = self.cache.write().await;
-        cache.insert(key, cached);
+        let entries = HashMap::from([
+            (meta_key, serde_json::to_vec(&page).unwrap_or_default()),
+            (body_key, response.content_bytes().unwrap_or_default()),
+        ]);
+        if let Err(e) = self
+            .cache
+            .set_many(entries, Duration::from_secs(self.cache_timeout))
+            .await
+        {
+            tracing::warn!(error = %e, "Page cache write failed");
+            return response;
+        }
 
         // Add cache miss header
         let mut resp = response;
@@ -1219,6 +1646,139 @@ impl Middleware for CacheMiddleware {
     }
 }
 
+// ── TimeoutMiddleware ──────────────────────────────────────────────
+
+/// Middleware that bounds how long a view may run before the client gets a
+/// 504 Gateway Timeout.
+///
+/// The pipeline races the view handler against the timeout returned by
+/// [`Middleware::view_timeout`]. When the deadline passes, the handler future
+/// is dropped, which cancels any downstream work it was awaiting, and the
+/// structured response from [`view_timeout_response`] is returned instead.
+///
+/// Individual routes can override the default by URL name. Overrides are
+/// looked up by the fully-qualified view name (e.g. `"api:report"`) first,
+/// then by the bare URL name.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use django_rs_views::middleware::builtin::TimeoutMiddleware;
+///
+/// let mw = TimeoutMiddleware::new(Duration::from_secs(30))
+///     .with_route_timeout("reports:export", Duration::from_secs(120));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeoutMiddleware {
+    /// The timeout applied to every view without a route override.
+    pub timeout: Duration,
+    /// Per-route timeouts keyed by URL name or fully-qualified view name.
+    pub route_timeouts: HashMap<String, Duration>,
+}
+
+impl Default for TimeoutMiddleware {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+impl TimeoutMiddleware {
+    /// Creates a new `TimeoutMiddleware` with the given default timeout.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            route_timeouts: HashMap::new(),
+        }
+    }
+
+    /// Overrides the timeout for the route with the given URL name.
+    #[must_use]
+    pub fn with_route_timeout(mut self, route_name: &str, timeout: Duration) -> Self {
+        self.route_timeouts.insert(route_name.to_string(), timeout);
+        self
+    }
+
+    /// Returns the timeout that applies to the given request.
+    pub fn timeout_for(&self, request: &HttpRequest) -> Duration {
+        request
+            .resolver_match()
+            .and_then(|m| {
+                self.route_timeouts
+                    .get(&m.view_name())
+                    .or_else(|| m.url_name.as_ref().and_then(|n| self.route_timeouts.get(n)))
+            })
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+}
+
+#[async_trait]
+impl Middleware for TimeoutMiddleware {
+    async fn process_request(&self, _request: &mut HttpRequest) -> Option<HttpResponse> {
+        None
+    }
+
+    async fn process_response(
+        &self,
+        _request: &HttpRequest,
+        response: HttpResponse,
+    ) -> HttpResponse {
+        response
+    }
+
+    async fn process_exception(
+        &self,
+        _request: &HttpRequest,
+        _error: &DjangoError,
+    ) -> Option<HttpResponse> {
+        None
+    }
+
+    fn view_timeout(&self, request: &HttpRequest) -> Option<Duration> {
+        Some(self.timeout_for(request))
+    }
+}
+
+/// Builds the 504 Gateway Timeout response for a view that exceeded `timeout`.
+///
+/// The body is a JSON object with `error`, `detail`, `route`, and
+/// `timeout_ms` keys. A `tracing` warning including the route name is emitted
+/// so slow views can be identified from logs.
+pub fn view_timeout_response(request: &HttpRequest, timeout: Duration) -> HttpResponse {
+    let route = request
+        .resolver_match()
+        .map(django_rs_http::urls::resolver::ResolverMatch::view_name)
+        .filter(|name| !name.is_empty());
+    gateway_timeout_response(route.as_deref(), request.path(), timeout)
+}
+
+/// Builds the 504 response from the route name and path of the timed-out request.
+pub(crate) fn gateway_timeout_response(
+    route: Option<&str>,
+    path: &str,
+    timeout: Duration,
+) -> HttpResponse {
+    let timeout_ms = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+
+    tracing::warn!(
+        route = route.unwrap_or("<unnamed>"),
+        path,
+        timeout_ms,
+        "view exceeded its timeout; returning 504"
+    );
+
+    django_rs_http::JsonResponse::with_status(
+        http::StatusCode::GATEWAY_TIMEOUT,
+        &serde_json::json!({
+            "error": "gateway_timeout",
+            "detail": format!("The view did not respond within {timeout_ms} ms."),
+            "route": route,
+            "timeout_ms": timeout_ms,
+        }),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1721,11 +2281,151 @@ mod tests {
         );
     }
 
+    // ── AuditContextMiddleware tests ────────────────────────────────
+
+    #[tokio::test]
+    async fn test_audit_context_middleware_uses_user_and_request_id() {
+        let mw = AuditContextMiddleware::new();
+        let mut request = HttpRequest::builder()
+            .header("x-request-id", "req-9")
+            .extension(CurrentUser::authenticated("42"))
+            .build();
+        assert!(mw.process_request(&mut request).await.is_none());
+        assert_eq!(
+            request.extensions().get::<AuditContext>(),
+            Some(&AuditContext::new().request_id("req-9").user_id("42"))
+        );
+
+        let response = mw.process_response(&request, HttpResponse::ok("")).await;
+        assert_eq!(response.headers().get("x-request-id").unwrap(), "req-9");
+    }
+
+    #[tokio::test]
+    async fn test_audit_context_middleware_generates_request_id() {
+        let mw = AuditContextMiddleware::new().with_request_id_header("X-Trace");
+        let mut request = HttpRequest::builder()
+            .extension(CurrentUser::anonymous())
+            .build();
+        mw.process_request(&mut request).await;
+        let context = request.extensions().get::<AuditContext>().unwrap();
+        assert!(context.user_id.is_none());
+        assert_eq!(context.request_id.as_ref().unwrap().len(), 36);
+    }
+
+    #[tokio::test]
+    async fn test_audit_context_in_scope_for_view() {
+        use crate::middleware::{MiddlewarePipeline, ViewHandler};
+
+        let handler: ViewHandler = Box::new(|_req| {
+            Box::pin(async {
+                let context = AuditContext::current().unwrap_or_default();
+                HttpResponse::ok(context.user_id.unwrap_or_default())
+            })
+        });
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(AuditContextMiddleware::new());
+        let request = HttpRequest::builder()
+            .extension(CurrentUser::authenticated("7"))
+            .build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.content_bytes().unwrap(), b"7".as_slice());
+    }
+
+    // ── ReadYourWritesMiddleware tests ──────────────────────────────
+
+    fn set_cookie_header(response: &HttpResponse) -> Option<&str> {
+        response
+            .headers()
+            .get(http::header::SET_COOKIE)
+            .map(|value| value.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_read_your_writes_pins_from_cookie() {
+        let mw = ReadYourWritesMiddleware::new();
+        let mut request = HttpRequest::builder()
+            .header("cookie", "db_pin_writes=1")
+            .build();
+        mw.process_request(&mut request).await;
+        assert!(request
+            .extensions()
+            .get::<Arc<PrimaryPin>>()
+            .unwrap()
+            .is_pinned());
+
+        let mut request = HttpRequest::builder().build();
+        mw.process_request(&mut request).await;
+        assert!(!request
+            .extensions()
+            .get::<Arc<PrimaryPin>>()
+            .unwrap()
+            .is_pinned());
+        let response = mw.process_response(&request, HttpResponse::ok("")).await;
+        assert!(set_cookie_header(&response).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_your_writes_post_sets_cookie() {
+        let mw = ReadYourWritesMiddleware::new()
+            .with_duration(Duration::from_secs(5))
+            .with_cookie_name("pin");
+        let mut request = HttpRequest::builder().method(http::Method::POST).build();
+        mw.process_request(&mut request).await;
+        assert!(request
+            .extensions()
+            .get::<Arc<PrimaryPin>>()
+            .unwrap()
+            .is_pinned());
+
+        let response = mw.process_response(&request, HttpResponse::ok("")).await;
+        let cookie = set_cookie_header(&response).unwrap();
+        assert!(cookie.starts_with("pin=1"));
+        assert!(cookie.contains("Max-Age=5"));
+        assert!(cookie.contains("HttpOnly"));
+    }
+
+    #[tokio::test]
+    async fn test_read_your_writes_pins_reads_after_write_in_view() {
+        use crate::middleware::{MiddlewarePipeline, ViewHandler};
+        use django_rs_db::{DatabaseRouter, RouterChain};
+
+        struct ReplicaRouter;
+
+        impl DatabaseRouter for ReplicaRouter {
+            fn db_for_read(&self, _app_label: &str, _model_name: &str) -> Option<String> {
+                Some("replica".to_string())
+            }
+        }
+
+        let handler: ViewHandler = Box::new(|_req| {
+            Box::pin(async {
+                let mut chain = RouterChain::new();
+                chain.add_router(Box::new(ReplicaRouter));
+                let before = chain.db_for_read("blog", "post");
+                chain.db_for_write("blog", "post");
+                let after = chain.db_for_read("blog", "post");
+                HttpResponse::ok(format!("{before},{after}"))
+            })
+        });
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(ReadYourWritesMiddleware::new());
+        let response = pipeline
+            .process(HttpRequest::builder().build(), &handler)
+            .await;
+        assert_eq!(
+            response.content_bytes().unwrap(),
+            b"replica,default".as_slice()
+        );
+        assert!(set_cookie_header(&response)
+            .unwrap()
+            .starts_with("db_pin_writes=1"));
+    }
+
     // ── AuthenticationMiddleware tests ──────────────────────────────
 
     #[tokio::test]
     async fn test_auth_middleware_user_in_session() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let session_data = serde_json::json!({"_auth_user_id": "42"});
         let mut request = HttpRequest::builder()
             .meta("SESSION_DATA", &session_data.to_string())
@@ -1734,11 +2434,15 @@ mod tests {
         assert!(result.is_none());
         assert_eq!(request.meta().get("USER_ID").unwrap(), "42");
         assert_eq!(request.meta().get("USER_AUTHENTICATED").unwrap(), "true");
+        assert_eq!(
+            request.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::authenticated("42"))
+        );
     }
 
     #[tokio::test]
     async fn test_auth_middleware_user_numeric_id() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let session_data = serde_json::json!({"_auth_user_id": 99});
         let mut request = HttpRequest::builder()
             .meta("SESSION_DATA", &session_data.to_string())
@@ -1750,7 +2454,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_auth_middleware_no_user_in_session() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let session_data = serde_json::json!({"theme": "dark"});
         let mut request = HttpRequest::builder()
             .meta("SESSION_DATA", &session_data.to_string())
@@ -1758,11 +2462,16 @@ mod tests {
         mw.process_request(&mut request).await;
         assert!(request.meta().get("USER_ID").is_none());
         assert_eq!(request.meta().get("USER_AUTHENTICATED").unwrap(), "false");
+        assert!(!request
+            .extensions()
+            .get::<CurrentUser>()
+            .unwrap()
+            .is_authenticated());
     }
 
     #[tokio::test]
     async fn test_auth_middleware_empty_session() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         mw.process_request(&mut request).await;
         assert_eq!(request.meta().get("USER_AUTHENTICATED").unwrap(), "false");
@@ -1770,7 +2479,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_auth_middleware_no_session_data() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let mut request = HttpRequest::builder().build();
         mw.process_request(&mut request).await;
         assert_eq!(request.meta().get("USER_AUTHENTICATED").unwrap(), "false");
@@ -1778,7 +2487,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_auth_middleware_invalid_session_json() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let mut request = HttpRequest::builder()
             .meta("SESSION_DATA", "not-json")
             .build();
@@ -1788,7 +2497,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_auth_middleware_passthrough_response() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let request = HttpRequest::builder().build();
         let response = HttpResponse::ok("test");
         let result = mw.process_response(&request, response).await;
@@ -1797,11 +2506,58 @@ mod tests {
 
     #[tokio::test]
     async fn test_auth_middleware_does_not_short_circuit() {
-        let mw = AuthenticationMiddleware;
+        let mw = AuthenticationMiddleware::new();
         let mut request = HttpRequest::builder().build();
         assert!(mw.process_request(&mut request).await.is_none());
     }
 
+    /// Accepts only the hash `"current"`.
+    struct FixedHashVerifier;
+
+    #[async_trait]
+    impl SessionVerifier for FixedHashVerifier {
+        async fn verify(&self, user_id: &str, session_hash: &str) -> Option<CurrentUser> {
+            (session_hash == "current")
+                .then(|| CurrentUser::authenticated(user_id).with_staff(true))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_verifies_session_hash() {
+        let mw = AuthenticationMiddleware::new().with_verifier(Arc::new(FixedHashVerifier));
+        let session = serde_json::json!({"_auth_user_id": "42", "_auth_user_hash": "current"});
+        let mut request = HttpRequest::builder()
+            .meta("SESSION_DATA", &session.to_string())
+            .build();
+        mw.process_request(&mut request).await;
+        assert_eq!(request.meta().get("USER_ID").unwrap(), "42");
+        assert_eq!(request.meta().get("USER_AUTHENTICATED").unwrap(), "true");
+        assert_eq!(
+            request.extensions().get::<CurrentUser>(),
+            Some(&CurrentUser::authenticated("42").with_staff(true))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_flushes_session_with_stale_hash() {
+        let mw = AuthenticationMiddleware::new().with_verifier(Arc::new(FixedHashVerifier));
+        for session in [
+            serde_json::json!({"_auth_user_id": "42", "_auth_user_hash": "old", "theme": "dark"}),
+            serde_json::json!({"_auth_user_id": "42", "theme": "dark"}),
+        ] {
+            let mut request = HttpRequest::builder()
+                .meta("SESSION_KEY", "old-key")
+                .meta("SESSION_DATA", &session.to_string())
+                .build();
+            mw.process_request(&mut request).await;
+            assert!(request.meta().get("USER_ID").is_none());
+            assert_eq!(request.meta().get("USER_AUTHENTICATED").unwrap(), "false");
+            let session = SessionData::from_request(&request);
+            assert!(session.is_empty());
+            assert_eq!(session.previous_key(), Some("old-key"));
+        }
+    }
+
     // ── MessageMiddleware tests ────────────────────────────────────
 
     #[tokio::test]
@@ -1809,7 +2565,8 @@ mod tests {
         let mw = MessageMiddleware;
         let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         mw.process_request(&mut request).await;
-        assert_eq!(request.meta().get("_messages_store").unwrap(), "[]");
+        assert!(request.extensions().get::<MessageStore>().is_some());
+        assert!(get_messages(&request).is_empty());
     }
 
     #[tokio::test]
@@ -1821,17 +2578,14 @@ mod tests {
             .meta("SESSION_DATA", &session.to_string())
             .build();
         mw.process_request(&mut request).await;
-        let store = request.meta().get("_messages_store").unwrap();
-        assert!(store.contains("Hello"));
+        let messages = get_messages(&request);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].message, "Hello");
     }
 
     #[tokio::test]
     async fn test_add_message_to_request() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         add_message(&mut request, MessageLevel::Info, "Test message");
         let messages = get_messages(&request);
         assert_eq!(messages.len(), 1);
@@ -1840,11 +2594,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_add_multiple_messages() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         add_message(&mut request, MessageLevel::Info, "Info msg");
         add_message(&mut request, MessageLevel::Warning, "Warn msg");
         add_message(&mut request, MessageLevel::Error, "Error msg");
@@ -1871,11 +2621,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_convenience_info_message() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         info(&mut request, "Info message");
         let messages = get_messages(&request);
         assert_eq!(messages.len(), 1);
@@ -1884,11 +2630,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_convenience_success_message() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         success(&mut request, "Success!");
         let messages = get_messages(&request);
         assert_eq!(messages[0].level, MessageLevel::Success);
@@ -1896,11 +2638,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_convenience_warning_message() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         warning(&mut request, "Watch out!");
         let messages = get_messages(&request);
         assert_eq!(messages[0].level, MessageLevel::Warning);
@@ -1908,11 +2646,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_convenience_error_message() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         error(&mut request, "Something broke");
         let messages = get_messages(&request);
         assert_eq!(messages[0].level, MessageLevel::Error);
@@ -1923,8 +2657,6 @@ mod tests {
         let mut request = HttpRequest::builder()
             .meta("SESSION_DATA", "{}")
             .meta("SESSION_MODIFIED", "false")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
             .build();
         add_message(&mut request, MessageLevel::Info, "Test");
         assert_eq!(request.meta().get("SESSION_MODIFIED").unwrap(), "true");
@@ -1932,11 +2664,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_message_with_extra_tags() {
-        let mut request = HttpRequest::builder()
-            .meta("SESSION_DATA", "{}")
-            .meta("_messages_store", "[]")
-            .meta("_messages_added", "[]")
-            .build();
+        let mut request = HttpRequest::builder().meta("SESSION_DATA", "{}").build();
         add_message_with_tags(&mut request, MessageLevel::Info, "Tagged", "important bold");
         let messages = get_messages(&request);
         assert_eq!(messages[0].extra_tags, "important bold");
@@ -2232,6 +2960,65 @@ mod tests {
         assert!(result.headers().get("x-cache").is_none());
     }
 
+    #[tokio::test]
+    async fn test_cache_middleware_hit_keeps_repeated_headers() {
+        let mw = CacheMiddleware::new(600);
+        let mut request = HttpRequest::builder()
+            .method(http::Method::GET)
+            .path("/linked/")
+            .build();
+        let mut response = HttpResponse::ok("linked");
+        response.headers_mut().append(
+            http::header::LINK,
+            http::header::HeaderValue::from_static("</a.css>; rel=preload"),
+        );
+        response.headers_mut().append(
+            http::header::LINK,
+            http::header::HeaderValue::from_static("</b.js>; rel=preload"),
+        );
+        mw.process_response(&request, response).await;
+
+        let cached = mw.process_request(&mut request).await.unwrap();
+        let links: Vec<_> = cached
+            .headers()
+            .get_all(http::header::LINK)
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        assert_eq!(links, ["</a.css>; rel=preload", "</b.js>; rel=preload"]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_skips_set_cookie_response() {
+        let mw = CacheMiddleware::new(600);
+        let mut request = HttpRequest::builder()
+            .method(http::Method::GET)
+            .path("/login/")
+            .build();
+        let mut response = HttpResponse::ok("welcome");
+        response.headers_mut().append(
+            http::header::SET_COOKIE,
+            http::header::HeaderValue::from_static("sessionid=abc; Path=/"),
+        );
+        response.headers_mut().append(
+            http::header::SET_COOKIE,
+            http::header::HeaderValue::from_static("csrftoken=xyz; Path=/"),
+        );
+        let result = mw.process_response(&request, response).await;
+        assert!(result.headers().get("x-cache").is_none());
+        assert_eq!(
+            result
+                .headers()
+                .get_all(http::header::SET_COOKIE)
+                .iter()
+                .count(),
+            2
+        );
+
+        // Nothing was stored, so the next visitor doesn't get the cookies
+        assert!(mw.process_request(&mut request).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_cache_middleware_skips_non_200() {
         let mw = CacheMiddleware::new(600);
@@ -2282,4 +3069,56 @@ mod tests {
         let result = mw.process_request(&mut request).await;
         assert!(result.is_some());
     }
+
+    // ── TimeoutMiddleware tests ──────────────────────────────────────
+
+    fn resolved_request(url_name: &str) -> HttpRequest {
+        use django_rs_http::urls::pattern::path;
+        use django_rs_http::urls::resolver::{root, URLEntry};
+
+        let handler: django_rs_http::urls::pattern::RouteHandler =
+            std::sync::Arc::new(|_req| Box::pin(async { HttpResponse::ok("ok") }));
+        let resolver = root(vec![URLEntry::Pattern(
+            path("report/", handler, Some(url_name)).unwrap(),
+        )])
+        .unwrap();
+        let mut request = HttpRequest::builder().path("/report/").build();
+        request.set_resolver_match(resolver.resolve("report/").unwrap());
+        request
+    }
+
+    #[test]
+    fn test_timeout_middleware_default_timeout() {
+        let mw = TimeoutMiddleware::new(Duration::from_secs(5));
+        let request = HttpRequest::builder().path("/any/").build();
+        assert_eq!(mw.view_timeout(&request), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_timeout_middleware_route_override() {
+        let mw = TimeoutMiddleware::new(Duration::from_secs(5))
+            .with_route_timeout("report", Duration::from_secs(60));
+        assert_eq!(
+            mw.timeout_for(&resolved_request("report")),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            mw.timeout_for(&resolved_request("other")),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_view_timeout_response_body() {
+        let request = resolved_request("report");
+        let response = view_timeout_response(&request, Duration::from_millis(250));
+        assert_eq!(response.status(), http::StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(response.content_type(), "application/json");
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&response.content_bytes().unwrap()).unwrap();
+        assert_eq!(body["error"], "gateway_timeout");
+        assert_eq!(body["route"], "report");
+        assert_eq!(body["timeout_ms"], 250);
+    }
 }
@@ -10,15 +10,25 @@
 //! Middleware is processed in order for requests (first added = first to process)
 //! and in reverse order for responses (first added = last to process). This
 //! matches Django's "onion" model.
+//!
+//! ## Conditional Middleware
+//!
+//! [`MiddlewarePipeline::add_when`] attaches a [`MiddlewareCondition`] so a
+//! middleware only runs for matching requests, e.g. skipping sessions and
+//! locale handling for health checks and static assets.
 
 pub mod builtin;
 
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use tracing::Instrument;
 
 use django_rs_core::DjangoError;
+use django_rs_db::{AuditContext, PrimaryPin};
 use django_rs_http::{HttpRequest, HttpResponse};
 
 /// The type for an async view handler function used in the pipeline.
@@ -83,6 +93,148 @@ pub trait Middleware: Send + Sync {
         request: &HttpRequest,
         error: &DjangoError,
     ) -> Option<HttpResponse>;
+
+    /// Returns the maximum time the view handler may take for this request.
+    ///
+    /// The pipeline races the view handler against the shortest timeout
+    /// returned by any middleware. When it expires, the handler future is
+    /// dropped (cancelling its downstream work) and a 504 Gateway Timeout
+    /// response is sent through `process_response` instead. The default
+    /// implementation imposes no timeout.
+    fn view_timeout(&self, _request: &HttpRequest) -> Option<Duration> {
+        None
+    }
+}
+
+/// Decides whether a conditionally added middleware runs for a request.
+///
+/// Conditions are checked once per request, against the request as it
+/// enters the pipeline. A middleware whose condition doesn't match is skipped
+/// entirely: none of its hooks run and its [`Middleware::view_timeout`] is
+/// ignored.
+///
+/// Route-name conditions match the request's resolver match, so they only
+/// apply when URL resolution has happened before the pipeline runs; requests
+/// without one don't match.
+///
+/// # Examples
+///
+/// ```
+/// use django_rs_views::middleware::MiddlewareCondition;
+/// use django_rs_http::HttpRequest;
+///
+/// let skip_static = MiddlewareCondition::path_prefix("/static/").negate();
+/// let request = HttpRequest::builder().path("/static/app.css").build();
+/// assert!(!skip_static.matches(&request));
+/// ```
+#[derive(Clone)]
+pub struct MiddlewareCondition {
+    predicate: Arc<dyn Fn(&HttpRequest) -> bool + Send + Sync>,
+}
+
+impl MiddlewareCondition {
+    /// Matches requests whose path starts with `prefix`.
+    pub fn path_prefix(prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        Self::custom(move |request| request.path().starts_with(&prefix))
+    }
+
+    /// Matches requests whose path starts with any of `prefixes`.
+    pub fn path_prefixes<I, S>(prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let prefixes: Vec<String> = prefixes.into_iter().map(Into::into).collect();
+        Self::custom(move |request| prefixes.iter().any(|p| request.path().starts_with(p)))
+    }
+
+    /// Matches requests whose resolved view name satisfies `matcher`.
+    ///
+    /// The view name includes namespaces, e.g. `"api:v1:user-detail"`.
+    pub fn route_name(matcher: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self::custom(move |request| {
+            request
+                .resolver_match()
+                .is_some_and(|m| m.url_name.is_some() && matcher(&m.view_name()))
+        })
+    }
+
+    /// Matches requests resolved to one of the given view names.
+    pub fn route_names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        Self::route_name(move |name| names.iter().any(|n| n == name))
+    }
+
+    /// Matches requests for which `predicate` returns `true`.
+    pub fn custom(predicate: impl Fn(&HttpRequest) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Returns a condition matching exactly the requests this one doesn't.
+    #[must_use]
+    pub fn negate(self) -> Self {
+        Self::custom(move |request| !self.matches(request))
+    }
+
+    /// Returns `true` if the condition matches `request`.
+    pub fn matches(&self, request: &HttpRequest) -> bool {
+        (self.predicate)(request)
+    }
+}
+
+impl std::fmt::Debug for MiddlewareCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MiddlewareCondition")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A middleware in the pipeline along with the condition it runs under.
+struct PipelineEntry {
+    middleware: Box<dyn Middleware>,
+    condition: Option<MiddlewareCondition>,
+    /// The middleware's type name, recorded on its spans.
+    name: &'static str,
+}
+
+impl PipelineEntry {
+    fn new<M: Middleware + 'static>(middleware: M, condition: Option<MiddlewareCondition>) -> Self {
+        // `crate::module::Name<Params>` -> `Name`
+        let path = std::any::type_name::<M>()
+            .split('<')
+            .next()
+            .unwrap_or_default();
+        Self {
+            middleware: Box::new(middleware),
+            condition,
+            name: path.rsplit("::").next().unwrap_or(path),
+        }
+    }
+
+    fn applies_to(&self, request: &HttpRequest) -> bool {
+        self.condition
+            .as_ref()
+            .map_or(true, |condition| condition.matches(request))
+    }
+
+    async fn process_response(
+        &self,
+        request: &HttpRequest,
+        response: HttpResponse,
+    ) -> HttpResponse {
+        let span = tracing::info_span!("middleware.process_response", middleware = self.name);
+        self.middleware
+            .process_response(request, response)
+            .instrument(span)
+            .await
+    }
 }
 
 /// A pipeline of middleware components that processes requests and responses.
@@ -93,14 +245,18 @@ pub trait Middleware: Send + Sync {
 /// # Examples
 ///
 /// ```
-/// use django_rs_views::middleware::MiddlewarePipeline;
-/// use django_rs_views::middleware::builtin::SecurityMiddleware;
+/// use django_rs_views::middleware::{MiddlewareCondition, MiddlewarePipeline};
+/// use django_rs_views::middleware::builtin::{LocaleMiddleware, SecurityMiddleware};
 ///
 /// let mut pipeline = MiddlewarePipeline::new();
 /// pipeline.add(SecurityMiddleware::default());
+/// pipeline.add_when(
+///     LocaleMiddleware::default(),
+///     MiddlewareCondition::path_prefixes(["/health", "/static/"]).negate(),
+/// );
 /// ```
 pub struct MiddlewarePipeline {
-    middlewares: Vec<Box<dyn Middleware>>,
+    middlewares: Vec<PipelineEntry>,
 }
 
 impl Default for MiddlewarePipeline {
@@ -119,7 +275,18 @@ impl MiddlewarePipeline {
 
     /// Adds a middleware to the end of the pipeline.
     pub fn add(&mut self, middleware: impl Middleware + 'static) {
-        self.middlewares.push(Box::new(middleware));
+        self.middlewares.push(PipelineEntry::new(middleware, None));
+    }
+
+    /// Adds a middleware to the end of the pipeline that only runs for
+    /// requests matching `condition`.
+    pub fn add_when(
+        &mut self,
+        middleware: impl Middleware + 'static,
+        condition: MiddlewareCondition,
+    ) {
+        self.middlewares
+            .push(PipelineEntry::new(middleware, Some(condition)));
     }
 
     /// Returns the number of middleware components in the pipeline.
@@ -134,19 +301,39 @@ impl MiddlewarePipeline {
 
     /// Processes a request through the full middleware pipeline and view handler.
     ///
+    /// Middleware added with a [`MiddlewareCondition`] that doesn't match the
+    /// incoming request is left out of every phase.
+    ///
     /// 1. Calls `process_request` on each middleware in order. If any returns
     ///    `Some(response)`, short-circuits and runs `process_response` in reverse
     ///    on only the middleware that already ran.
-    /// 2. Calls the view handler with a rebuilt request.
+    /// 2. Calls the view handler with a rebuilt request, racing it against the
+    ///    shortest [`Middleware::view_timeout`] if any middleware sets one. If
+    ///    the request carries an [`AuditContext`] or a shared [`PrimaryPin`]
+    ///    extension, the view runs in its scope.
     /// 3. Calls `process_response` on each middleware in reverse order.
+    ///
+    /// Each middleware phase and the view run in their own tracing span.
     pub async fn process(&self, mut request: HttpRequest, handler: &ViewHandler) -> HttpResponse {
+        let active: Vec<&PipelineEntry> = self
+            .middlewares
+            .iter()
+            .filter(|entry| entry.applies_to(&request))
+            .collect();
+
         // Phase 1: process_request (forward order)
-        for (i, mw) in self.middlewares.iter().enumerate() {
-            if let Some(response) = mw.process_request(&mut request).await {
+        for (i, entry) in active.iter().enumerate() {
+            let span = tracing::info_span!("middleware.process_request", middleware = entry.name);
+            if let Some(response) = entry
+                .middleware
+                .process_request(&mut request)
+                .instrument(span)
+                .await
+            {
                 // Short-circuit: run process_response on already-processed middleware
                 let mut resp = response;
-                for j in (0..=i).rev() {
-                    resp = self.middlewares[j].process_response(&request, resp).await;
+                for entry in active[..=i].iter().rev() {
+                    resp = entry.process_response(&request, resp).await;
                 }
                 return resp;
             }
@@ -155,12 +342,36 @@ impl MiddlewarePipeline {
         // Phase 2: call the view handler
         // Build the handler request from the current (possibly modified) request state
         let handler_request = rebuild_request(&request);
-        let response = handler(handler_request).await;
+        let timeout = active
+            .iter()
+            .filter_map(|entry| entry.middleware.view_timeout(&request))
+            .min();
+        let view = handler(handler_request).instrument(tracing::info_span!("view"));
+        let view = async {
+            match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, view).await {
+                    Ok(response) => response,
+                    Err(_) => builtin::view_timeout_response(&request, timeout),
+                },
+                None => view.await,
+            }
+        };
+        let pin = request.extensions().get::<Arc<PrimaryPin>>().cloned();
+        let view = async {
+            match pin {
+                Some(pin) => pin.scope(view).await,
+                None => view.await,
+            }
+        };
+        let response = match request.extensions().get::<AuditContext>() {
+            Some(context) => context.clone().scope(view).await,
+            None => view.await,
+        };
 
         // Phase 3: process_response (reverse order)
         let mut resp = response;
-        for mw in self.middlewares.iter().rev() {
-            resp = mw.process_response(&request, resp).await;
+        for entry in active.iter().rev() {
+            resp = entry.process_response(&request, resp).await;
         }
 
         resp
@@ -170,7 +381,7 @@ impl MiddlewarePipeline {
 /// Rebuilds an `HttpRequest` from an existing one to pass ownership to the handler.
 ///
 /// This creates a new request with the same method, path, query string, headers,
-/// and metadata as the original.
+/// metadata and extensions as the original.
 fn rebuild_request(request: &HttpRequest) -> HttpRequest {
     let mut builder = HttpRequest::builder()
         .method(request.method().clone())
@@ -194,6 +405,7 @@ fn rebuild_request(request: &HttpRequest) -> HttpRequest {
     }
 
     let mut req = builder.build();
+    *req.extensions_mut() = request.extensions().clone();
     if let Some(resolver_match) = request.resolver_match() {
         req.set_resolver_match(resolver_match.clone());
     }
@@ -212,7 +424,6 @@ impl std::fmt::Debug for MiddlewarePipeline {
 mod tests {
     use super::*;
     use std::sync::atomic::{AtomicUsize, Ordering};
-    use std::sync::Arc;
 
     struct OrderTracker {
         name: String,
@@ -469,6 +680,14 @@ mod tests {
         assert!(pipeline.is_empty());
     }
 
+    #[test]
+    fn test_pipeline_entry_span_name() {
+        let entry = PipelineEntry::new(PassthroughMiddleware, None);
+        assert_eq!(entry.name, "PassthroughMiddleware");
+        let entry = PipelineEntry::new(builtin::SecurityMiddleware::default(), None);
+        assert_eq!(entry.name, "SecurityMiddleware");
+    }
+
     #[tokio::test]
     async fn test_pipeline_debug() {
         let mut pipeline = MiddlewarePipeline::new();
@@ -532,4 +751,123 @@ mod tests {
             "hello"
         );
     }
+
+    #[tokio::test]
+    async fn test_pipeline_view_timeout_returns_504_and_cancels_view() {
+        use crate::middleware::builtin::TimeoutMiddleware;
+        use std::sync::atomic::AtomicBool;
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_in_view = finished.clone();
+        let handler: ViewHandler = Box::new(move |_req| {
+            let finished = finished_in_view.clone();
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                finished.store(true, Ordering::SeqCst);
+                HttpResponse::ok("too late")
+            })
+        });
+
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(HeaderAddingMiddleware {
+            header_name: "x-custom",
+            header_value: "test-value",
+        });
+        pipeline.add(TimeoutMiddleware::new(Duration::from_millis(20)));
+
+        let request = HttpRequest::builder().path("/slow/").build();
+        let response = pipeline.process(request, &handler).await;
+
+        assert_eq!(response.status(), http::StatusCode::GATEWAY_TIMEOUT);
+        // The 504 still flows through process_response
+        assert!(response.headers().contains_key("x-custom"));
+        assert!(!finished.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_view_within_timeout() {
+        use crate::middleware::builtin::TimeoutMiddleware;
+
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(TimeoutMiddleware::new(Duration::from_secs(5)));
+        let handler = make_handler();
+        let request = HttpRequest::builder().build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_add_when_skips_non_matching_requests() {
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add_when(
+            ShortCircuitMiddleware,
+            MiddlewareCondition::path_prefix("/admin/"),
+        );
+        pipeline.add_when(
+            HeaderAddingMiddleware {
+                header_name: "x-session",
+                header_value: "loaded",
+            },
+            MiddlewareCondition::path_prefixes(["/health", "/static/"]).negate(),
+        );
+        let handler = make_handler();
+
+        let request = HttpRequest::builder().path("/admin/users/").build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+        let request = HttpRequest::builder().path("/health").build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(!response.headers().contains_key("x-session"));
+
+        let request = HttpRequest::builder().path("/blog/").build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(response.headers().contains_key("x-session"));
+    }
+
+    #[tokio::test]
+    async fn test_skipped_middleware_timeout_is_ignored() {
+        use crate::middleware::builtin::TimeoutMiddleware;
+
+        let handler: ViewHandler = Box::new(|_req| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                HttpResponse::ok("done")
+            })
+        });
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add_when(
+            TimeoutMiddleware::new(Duration::from_millis(5)),
+            MiddlewareCondition::custom(|request| request.method() == http::Method::POST),
+        );
+
+        let request = HttpRequest::builder().path("/report/").build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
+    #[test]
+    fn test_route_name_condition() {
+        use django_rs_http::urls::resolver::ResolverMatch;
+
+        let condition = MiddlewareCondition::route_names(["api:health"]);
+        let mut request = HttpRequest::builder().path("/api/health/").build();
+        assert!(!condition.matches(&request));
+
+        request.set_resolver_match(ResolverMatch {
+            func: Arc::new(|_req| Box::pin(async { HttpResponse::ok("") })),
+            args: vec![],
+            kwargs: std::collections::HashMap::new(),
+            url_name: Some("health".into()),
+            app_names: vec!["api".into()],
+            namespaces: vec!["api".into()],
+            route: "api/health/".into(),
+            extensions: http::Extensions::new(),
+        });
+        assert!(condition.matches(&request));
+        assert!(MiddlewareCondition::route_name(|name| name.starts_with("api:")).matches(&request));
+        assert!(!MiddlewareCondition::route_names(["health"]).matches(&request));
+    }
 }
@@ -40,13 +40,19 @@ use axum::body::Body;
 use axum::extract::Request;
 use axum::response::IntoResponse;
 use axum::routing::any;
+use tracing::Instrument;
 
 use django_rs_core::{DjangoError, Settings};
+use django_rs_http::body::BodyStream;
+use django_rs_http::hardening::{self, MalformedRequest, MalformedRequestKind, RequestLimits};
+use django_rs_http::services::Services;
 use django_rs_http::urls::resolver::URLResolver;
 use django_rs_http::{HttpRequest, HttpResponse};
 use django_rs_template::engine::Engine;
 
+use crate::error_handlers::{ErrorHandlers, ResponseFormat, SECURITY_LOG_TARGET};
 use crate::middleware::{Middleware, MiddlewarePipeline, ViewHandler};
+use crate::views::static_serve::StaticServe;
 
 /// The main application type for django-rs.
 ///
@@ -60,6 +66,11 @@ pub struct DjangoApp {
     middleware: MiddlewarePipeline,
     settings: Settings,
     engine: Option<Arc<Engine>>,
+    stream_body_threshold: Option<usize>,
+    request_limits: RequestLimits,
+    error_handlers: ErrorHandlers,
+    services: Services,
+    config_error: Option<DjangoError>,
 }
 
 impl DjangoApp {
@@ -70,6 +81,11 @@ impl DjangoApp {
             middleware: MiddlewarePipeline::new(),
             settings,
             engine: None,
+            stream_body_threshold: None,
+            request_limits: RequestLimits::default(),
+            error_handlers: ErrorHandlers::new(),
+            services: Services::new(),
+            config_error: None,
         }
     }
 
@@ -87,9 +103,99 @@ impl DjangoApp {
         self
     }
 
+    /// Registers a service injected into every request, replacing any
+    /// service of the same type.
+    ///
+    /// Views get it back with [`HttpRequest::service`]. Register trait
+    /// objects under the trait, e.g. `.service::<dyn DbExecutor>(db)`, so
+    /// tests can swap in a mock implementation.
+    #[must_use]
+    pub fn service<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.services.insert(service);
+        self
+    }
+
+    /// Registers every service in `services`, replacing services of the
+    /// same types.
+    #[must_use]
+    pub fn services(mut self, services: &Services) -> Self {
+        self.services.extend(services);
+        self
+    }
+
+    /// Streams request bodies larger than `threshold` bytes instead of
+    /// buffering them.
+    ///
+    /// Requests whose `Content-Length` exceeds the threshold, or that use
+    /// chunked transfer encoding, reach the view with an unread body; see
+    /// [`HttpRequest::body_stream`] and [`HttpRequest::read_body`].
+    #[must_use]
+    pub const fn stream_request_bodies_over(mut self, threshold: usize) -> Self {
+        self.stream_body_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the limits malformed requests are refused against; see
+    /// [`hardening`].
+    #[must_use]
+    pub const fn request_limits(mut self, limits: RequestLimits) -> Self {
+        self.request_limits = limits;
+        self
+    }
+
+    /// Registers the handler building responses for errors with the given
+    /// status code: 400 for malformed requests, 404 for unresolved URLs and
+    /// 500 for routing failures.
+    #[must_use]
+    pub fn error_handler(
+        mut self,
+        status: u16,
+        handler: impl Fn(&DjangoError) -> HttpResponse + Send + Sync + 'static,
+    ) -> Self {
+        self.error_handlers.register(status, handler);
+        self
+    }
+
+    /// Registers the handler building JSON responses for errors with the
+    /// given status code, in place of the default problem details body.
+    #[must_use]
+    pub fn json_error_handler(
+        mut self,
+        status: u16,
+        handler: impl Fn(&DjangoError) -> HttpResponse + Send + Sync + 'static,
+    ) -> Self {
+        self.error_handlers.register_json(status, handler);
+        self
+    }
+
+    /// Answers errors for requests under the path `prefix`, such as the
+    /// prefix an API's URLs are included under, in `format`.
+    ///
+    /// Other requests are answered in the format their `Accept` header
+    /// prefers; see [`ErrorHandlers::format_for`].
+    #[must_use]
+    pub fn error_format(mut self, prefix: &str, format: ResponseFormat) -> Self {
+        self.error_handlers.group_format(prefix, format);
+        self
+    }
+
     /// Sets the template engine for this application.
     #[must_use]
-    pub fn engine(mut self, engine: Engine) -> Self {
+    ///
+    /// Unless context processors were already added to the engine, the ones
+    /// listed under `TEMPLATES[0].OPTIONS["context_processors"]` are installed
+    /// from the application settings, with `auth` and `messages` read from
+    /// request extensions (see [`crate::context_processors`]). An unknown
+    /// processor name is reported by [`run`](Self::run).
+    pub fn engine(mut self, mut engine: Engine) -> Self {
+        if !engine.has_context_processors() {
+            if let Err(e) = engine.configure_context_processors_with(
+                &self.settings,
+                &crate::context_processors::overrides(),
+            ) {
+                self.config_error = Some(e);
+            }
+        }
         self.engine = Some(Arc::new(engine));
         self
     }
@@ -99,6 +205,11 @@ impl DjangoApp {
         &self.settings
     }
 
+    /// Returns the services injected into every request.
+    pub const fn registered_services(&self) -> &Services {
+        &self.services
+    }
+
     /// Returns a reference to the template engine, if configured.
     pub fn template_engine(&self) -> Option<&Arc<Engine>> {
         self.engine.as_ref()
@@ -117,55 +228,154 @@ impl DjangoApp {
     /// Converts the application into an Axum router.
     ///
     /// The router handles all incoming requests by running them through the
-    /// middleware pipeline and URL resolver.
+    /// middleware pipeline and URL resolver. Malformed requests are refused
+    /// with `400 Bad Request` before the pipeline runs, and logged under
+    /// [`SECURITY_LOG_TARGET`].
+    ///
+    /// In debug mode, requests under `STATIC_URL` are answered from
+    /// `STATICFILES_DIRS` and `STATIC_ROOT` without going through the
+    /// pipeline; see [`StaticServe::from_settings`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the application is misconfigured, e.g. the settings name an
+    /// unknown context processor. [`run`](Self::run) reports the same error
+    /// instead.
     pub fn into_axum_router(self) -> axum::Router {
+        if let Some(e) = &self.config_error {
+            panic!("DjangoApp is misconfigured: {e}");
+        }
+        let static_files = StaticServe::from_settings(&self.settings).map(Arc::new);
         let url_conf = Arc::new(self.url_conf);
         let middleware = Arc::new(self.middleware);
-        let settings = Arc::new(self.settings);
+        let stream_body_threshold = self.stream_body_threshold;
+        let limits = self.request_limits;
+        let mut error_handlers = self.error_handlers;
+        error_handlers.engine = self.engine;
+        error_handlers.debug = self.settings.debug;
+        let error_handlers = Arc::new(error_handlers);
+        let services = self.services;
 
         let handler = move |req: Request<Body>| {
             let url_conf = url_conf.clone();
             let middleware = middleware.clone();
-            let settings = settings.clone();
+            let error_handlers = error_handlers.clone();
+            let static_files = static_files.clone();
+            let services = services.clone();
 
             async move {
                 let (parts, body) = req.into_parts();
-                let body_bytes = axum::body::to_bytes(body, usize::MAX)
-                    .await
-                    .unwrap_or_default()
-                    .to_vec();
-
-                let django_request = HttpRequest::from_axum(parts, body_bytes);
+                let span = request_span(&parts);
+                let path = parts.uri.path().to_string();
+                let accept = parts
+                    .headers
+                    .get(http::header::ACCEPT)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                let django_request = if should_stream_body(&parts, stream_body_threshold) {
+                    hardening::check_parts(&parts, &limits).map(|()| {
+                        HttpRequest::from_axum_streaming(parts, BodyStream::from_axum(body))
+                    })
+                } else {
+                    read_request(parts, body, &limits).await
+                };
+                let mut django_request = match django_request {
+                    Ok(request) => request,
+                    Err(err) => {
+                        let response =
+                            bad_request_response(&err, &path, accept.as_deref(), &error_handlers);
+                        span.record("http.status_code", response.status().as_u16());
+                        return response.into_response();
+                    }
+                };
+                if !services.is_empty() {
+                    // Services already on the request, e.g. from a test, win.
+                    let mut request_services = services;
+                    if let Some(existing) = django_request.services() {
+                        request_services.extend(existing);
+                    }
+                    django_request.extensions_mut().insert(request_services);
+                }
+
+                if let Some(static_files) = static_files
+                    .as_deref()
+                    .filter(|static_files| static_files.matches(django_request.path()))
+                {
+                    let response = static_files
+                        .serve(&django_request)
+                        .instrument(span.clone())
+                        .await;
+                    span.record("http.status_code", response.status().as_u16());
+                    return response.into_response();
+                }
+
+                // Resolve the route before the middleware pipeline runs so that
+                // middleware (e.g. per-route timeouts) can see the matched view.
+                // The view handler resolves again if a middleware rewrote the
+                // path or host in between.
+                let mut resolved_for = None;
+                if let Some(url_conf) = url_conf.as_ref() {
+                    let path = django_request.path().to_string();
+                    let host = django_request.get_host().to_string();
+                    if let Ok(resolver_match) =
+                        url_conf.resolve_host(&host, strip_leading_slash(&path))
+                    {
+                        django_request.set_resolver_match(resolver_match);
+                        resolved_for = Some((host, path));
+                    }
+                }
 
                 let view_handler: ViewHandler = Box::new(move |mut request: HttpRequest| {
                     let url_conf = url_conf.clone();
-                    let _settings = settings.clone();
+                    let error_handlers = error_handlers.clone();
+                    let resolved_for = resolved_for.clone();
 
                     Box::pin(async move {
                         let Some(url_conf) = url_conf.as_ref() else {
                             return HttpResponse::server_error("No URL configuration provided");
                         };
 
-                        // Strip leading slash for URL resolution. Django's URL
-                        // patterns don't include a leading slash (e.g. "articles/"
-                        // not "/articles/"), but HTTP request paths always start
-                        // with "/". This mirrors Django's WSGIHandler behavior.
                         let path = request.path().to_string();
-                        let path = path.strip_prefix('/').unwrap_or(&path);
-                        match url_conf.resolve(path) {
+                        let host = request.get_host().to_string();
+                        let still_matches = resolved_for
+                            .as_ref()
+                            .is_some_and(|(h, p)| *h == host && *p == path);
+                        if let Some(resolver_match) =
+                            request.resolver_match().filter(|_| still_matches)
+                        {
+                            let handler = resolver_match.func.clone();
+                            return handler(request).await;
+                        }
+
+                        let accept = request
+                            .headers()
+                            .get(http::header::ACCEPT)
+                            .and_then(|v| v.to_str().ok());
+                        match url_conf.resolve_host(&host, strip_leading_slash(&path)) {
                             Ok(resolver_match) => {
                                 request.set_resolver_match(resolver_match.clone());
                                 let handler = &resolver_match.func;
                                 handler(request).await
                             }
-                            Err(DjangoError::NotFound(msg)) => HttpResponse::not_found(msg),
-                            Err(e) => HttpResponse::server_error(format!("Routing error: {e}")),
+                            Err(DjangoError::NotFound(msg)) => {
+                                let error = DjangoError::NotFound(msg.clone());
+                                error_handlers.respond_to(&path, accept, &error, || {
+                                    HttpResponse::not_found(msg)
+                                })
+                            }
+                            Err(e) => error_handlers.respond_to(&path, accept, &e, || {
+                                HttpResponse::server_error(format!("Routing error: {e}"))
+                            }),
                         }
                     })
                         as std::pin::Pin<Box<dyn std::future::Future<Output = HttpResponse> + Send>>
                 });
 
-                let response = middleware.process(django_request, &view_handler).await;
+                let response = middleware
+                    .process(django_request, &view_handler)
+                    .instrument(span.clone())
+                    .await;
+                span.record("http.status_code", response.status().as_u16());
                 response.into_response()
             }
         };
@@ -177,13 +387,24 @@ impl DjangoApp {
 
     /// Runs the application as an HTTP server on the given address.
     ///
-    /// This starts a Tokio-based HTTP server using Axum.
+    /// This starts a Tokio-based HTTP server using Axum. With the `otel`
+    /// feature and `settings.otel.enabled`, spans are exported over OTLP while
+    /// the server runs.
     ///
     /// # Errors
     ///
-    /// Returns an error if the server fails to bind to the address or encounters
-    /// a runtime error.
-    pub async fn run(self, addr: &str) -> Result<(), DjangoError> {
+    /// Returns an error if the application is misconfigured, or if the server
+    /// fails to bind to the address or encounters a runtime error.
+    pub async fn run(mut self, addr: &str) -> Result<(), DjangoError> {
+        if let Some(e) = self.config_error.take() {
+            return Err(e);
+        }
+        #[cfg(feature = "otel")]
+        let _otel = if self.settings.otel.enabled {
+            Some(django_rs_core::otel::setup_tracing(&self.settings)?)
+        } else {
+            None
+        };
         let debug = self.settings.debug;
         let router = self.into_axum_router();
         let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
@@ -202,6 +423,98 @@ impl DjangoApp {
     }
 }
 
+/// Creates the span a request is handled in.
+///
+/// With the `otel` feature, the span joins the caller's trace when the request
+/// carries a W3C `traceparent` header.
+fn request_span(parts: &http::request::Parts) -> tracing::Span {
+    let span = tracing::info_span!(
+        "http.request",
+        http.method = %parts.method,
+        http.target = parts.uri.path(),
+        http.status_code = tracing::field::Empty,
+    );
+    #[cfg(feature = "otel")]
+    {
+        let headers: std::collections::HashMap<String, String> = ["traceparent", "tracestate"]
+            .into_iter()
+            .filter_map(|name| {
+                let value = parts.headers.get(name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+        django_rs_core::otel::set_parent_from_headers(&span, &headers);
+    }
+    span
+}
+
+/// Buffers the body and builds the request, if the request is well formed.
+async fn read_request(
+    parts: http::request::Parts,
+    body: Body,
+    limits: &RequestLimits,
+) -> Result<HttpRequest, MalformedRequest> {
+    hardening::check_parts(&parts, limits)?;
+    let body = axum::body::to_bytes(body, usize::MAX).await.map_err(|e| {
+        MalformedRequest::new(
+            MalformedRequestKind::UnreadableBody,
+            format!("Failed to read the request body: {e}"),
+        )
+    })?;
+    hardening::check_body(&parts, &body, limits)?;
+    Ok(HttpRequest::from_axum(parts, body.to_vec()))
+}
+
+/// Logs a refused request and builds its `400 Bad Request` response.
+///
+/// The registered 400 handler builds the response if there is one; otherwise
+/// the problem is described only in debug mode.
+fn bad_request_response(
+    err: &MalformedRequest,
+    path: &str,
+    accept: Option<&str>,
+    error_handlers: &ErrorHandlers,
+) -> HttpResponse {
+    tracing::warn!(
+        target: SECURITY_LOG_TARGET,
+        kind = err.kind.as_str(),
+        "Refused malformed request: {err}"
+    );
+    let error = DjangoError::from(err.clone());
+    error_handlers.respond_to(path, accept, &error, || {
+        if error_handlers.debug {
+            HttpResponse::bad_request(format!("Bad Request (400): {err}"))
+        } else {
+            HttpResponse::bad_request("Bad Request (400)")
+        }
+    })
+}
+
+/// Strips the leading slash from a request path for URL resolution.
+///
+/// Django's URL patterns don't include a leading slash (e.g. "articles/" not
+/// "/articles/"), but HTTP request paths always start with "/". This mirrors
+/// Django's `WSGIHandler` behavior.
+fn strip_leading_slash(path: &str) -> &str {
+    path.strip_prefix('/').unwrap_or(path)
+}
+
+/// Decides whether a request body should be streamed rather than buffered.
+fn should_stream_body(parts: &http::request::Parts, threshold: Option<usize>) -> bool {
+    let Some(threshold) = threshold else {
+        return false;
+    };
+    match parts
+        .headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        Some(len) => len > threshold,
+        None => parts.headers.contains_key(http::header::TRANSFER_ENCODING),
+    }
+}
+
 impl std::fmt::Debug for DjangoApp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DjangoApp")
@@ -209,6 +522,11 @@ impl std::fmt::Debug for DjangoApp {
             .field("middleware_count", &self.middleware.len())
             .field("has_engine", &self.engine.is_some())
             .field("debug", &self.settings.debug)
+            .field("stream_body_threshold", &self.stream_body_threshold)
+            .field("request_limits", &self.request_limits)
+            .field("error_handlers", &self.error_handlers)
+            .field("services", &self.services)
+            .field("config_error", &self.config_error)
             .finish()
     }
 }
@@ -225,6 +543,39 @@ mod tests {
         assert!(app.settings().debug);
     }
 
+    #[tokio::test]
+    async fn test_run_fails_on_unknown_context_processor() {
+        let mut settings = Settings::default();
+        settings.templates[0].options.insert(
+            "context_processors".to_string(),
+            serde_json::json!(["myapp.context_processors.cart"]),
+        );
+        let app = DjangoApp::new(settings).engine(Engine::new());
+        let err = app.run("127.0.0.1:0").await.unwrap_err();
+        assert!(err.to_string().contains("myapp.context_processors.cart"));
+    }
+
+    #[test]
+    fn test_should_stream_body() {
+        let parts = |header: (&str, &str)| {
+            http::Request::builder()
+                .method("POST")
+                .header(header.0, header.1)
+                .body(())
+                .unwrap()
+                .into_parts()
+                .0
+        };
+        let large = parts(("content-length", "5000"));
+        let small = parts(("content-length", "10"));
+        let chunked = parts(("transfer-encoding", "chunked"));
+
+        assert!(should_stream_body(&large, Some(1024)));
+        assert!(!should_stream_body(&small, Some(1024)));
+        assert!(should_stream_body(&chunked, Some(1024)));
+        assert!(!should_stream_body(&large, None));
+    }
+
     #[test]
     fn test_django_app_with_urls() {
         let resolver = django_rs_http::urls::resolver::root(vec![]).unwrap();
@@ -285,6 +636,233 @@ mod tests {
         // Should still create a router (will return 500 for missing URL conf)
     }
 
+    #[tokio::test]
+    async fn test_django_app_refuses_malformed_requests() {
+        use django_rs_http::urls::pattern::path;
+        use django_rs_http::urls::resolver::{root, URLEntry};
+        use tower::ServiceExt;
+
+        let handler = Arc::new(|_req: HttpRequest| -> django_rs_http::BoxFuture {
+            Box::pin(async { HttpResponse::ok("ok") })
+        });
+        let resolver = root(vec![URLEntry::Pattern(path("", handler, None).unwrap())]).unwrap();
+        let router = DjangoApp::new(Settings::default())
+            .urls(resolver)
+            .error_handler(400, |_| HttpResponse::bad_request("custom 400"))
+            .into_axum_router();
+
+        let send = |request: http::Request<Body>| {
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status.as_u16(), String::from_utf8_lossy(&body).into_owned())
+            }
+        };
+
+        let ok = http::Request::get("/?q=caf%C3%A9")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send(ok).await, (200, "ok".to_string()));
+
+        let requests = [
+            http::Request::get("/?q=%FF").body(Body::empty()).unwrap(),
+            http::Request::get("/")
+                .header("cookie", "a b=1")
+                .body(Body::empty())
+                .unwrap(),
+            http::Request::post("/")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(&b"name=\xff"[..]))
+                .unwrap(),
+        ];
+        for request in requests {
+            assert_eq!(send(request).await, (400, "custom 400".to_string()));
+        }
+
+        let missing = http::Request::get("/nowhere/").body(Body::empty()).unwrap();
+        assert_eq!(send(missing).await.0, 404);
+    }
+
+    #[tokio::test]
+    async fn test_django_app_injects_services() {
+        use django_rs_http::urls::pattern::path;
+        use django_rs_http::urls::resolver::{root, URLEntry};
+        use tower::ServiceExt;
+
+        trait Greeter: Send + Sync {
+            fn greet(&self) -> String;
+        }
+        struct English;
+        impl Greeter for English {
+            fn greet(&self) -> String {
+                "hello".to_string()
+            }
+        }
+
+        let handler = Arc::new(|req: HttpRequest| -> django_rs_http::BoxFuture {
+            Box::pin(async move {
+                match req.service::<dyn Greeter>() {
+                    Some(greeter) => HttpResponse::ok(greeter.greet()),
+                    None => HttpResponse::server_error("no greeter"),
+                }
+            })
+        });
+        let resolver = root(vec![URLEntry::Pattern(path("", handler, None).unwrap())]).unwrap();
+        let app = DjangoApp::new(Settings::default())
+            .urls(resolver)
+            .service::<dyn Greeter>(Arc::new(English));
+        assert!(app.registered_services().contains::<dyn Greeter>());
+
+        let response = app
+            .into_axum_router()
+            .oneshot(http::Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_django_app_routes_rewritten_path() {
+        use django_rs_http::urls::pattern::path;
+        use django_rs_http::urls::resolver::{root, URLEntry};
+        use tower::ServiceExt;
+
+        struct Rewrite;
+        #[async_trait::async_trait]
+        impl Middleware for Rewrite {
+            async fn process_request(&self, request: &mut HttpRequest) -> Option<HttpResponse> {
+                if request.path() == "/old/" {
+                    request.set_path("/new/");
+                }
+                None
+            }
+
+            async fn process_response(
+                &self,
+                _request: &HttpRequest,
+                response: HttpResponse,
+            ) -> HttpResponse {
+                response
+            }
+
+            async fn process_exception(
+                &self,
+                _request: &HttpRequest,
+                _error: &DjangoError,
+            ) -> Option<HttpResponse> {
+                None
+            }
+        }
+
+        let view = |body: &'static str| -> django_rs_http::urls::pattern::RouteHandler {
+            Arc::new(move |_req: HttpRequest| -> django_rs_http::BoxFuture {
+                Box::pin(async move { HttpResponse::ok(body) })
+            })
+        };
+        let resolver = root(vec![
+            URLEntry::Pattern(path("old/", view("old"), None).unwrap()),
+            URLEntry::Pattern(path("new/", view("new"), None).unwrap()),
+        ])
+        .unwrap();
+        let app = DjangoApp::new(Settings::default())
+            .urls(resolver)
+            .middleware(Rewrite);
+
+        let response = app
+            .into_axum_router()
+            .oneshot(http::Request::get("/old/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"new");
+    }
+
+    #[tokio::test]
+    async fn test_django_app_negotiates_error_format() {
+        use django_rs_http::urls::resolver::root;
+        use tower::ServiceExt;
+
+        let router = DjangoApp::new(Settings::default())
+            .urls(root(vec![]).unwrap())
+            .error_format("/api/", ResponseFormat::Json)
+            .into_axum_router();
+        let send = |uri: &str, accept: &str| {
+            let request = http::Request::get(uri)
+                .header("accept", accept)
+                .body(Body::empty())
+                .unwrap();
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                response.headers()["content-type"]
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            }
+        };
+
+        assert!(send("/api/missing/", "text/html")
+            .await
+            .starts_with("application/problem+json"));
+        assert!(send("/missing/", "application/json")
+            .await
+            .starts_with("application/problem+json"));
+        assert!(send("/missing/", "text/html,*/*;q=0.8")
+            .await
+            .starts_with("text/html"));
+    }
+
+    #[tokio::test]
+    async fn test_django_app_serves_static_files_in_debug() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("site.css"), "body {}").unwrap();
+        let settings = Settings {
+            staticfiles_dirs: vec![dir.path().to_path_buf()],
+            ..Settings::default()
+        };
+        let get = |uri: &str| http::Request::get(uri).body(Body::empty()).unwrap();
+
+        let router = DjangoApp::new(settings.clone()).into_axum_router();
+        let response = router.oneshot(get("/static/site.css")).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/css; charset=utf-8"
+        );
+
+        let settings = Settings {
+            debug: false,
+            ..settings
+        };
+        let router = DjangoApp::new(settings).into_axum_router();
+        let response = router.oneshot(get("/static/site.css")).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_bad_request_response_hides_detail_outside_debug() {
+        let err = MalformedRequest::new(MalformedRequestKind::InvalidCookie, "bad cookie name");
+        let mut handlers = ErrorHandlers::new();
+        handlers.debug = true;
+        let debug = bad_request_response(&err, "/", None, &handlers);
+        assert_eq!(debug.status().as_u16(), 400);
+        assert!(String::from_utf8_lossy(&debug.content_bytes().unwrap()).contains("bad cookie"));
+        handlers.debug = false;
+        let production = bad_request_response(&err, "/", None, &handlers);
+        assert_eq!(production.content_bytes().unwrap(), b"Bad Request (400)");
+    }
+
     #[tokio::test]
     async fn test_django_app_run_invalid_address() {
         let app = DjangoApp::new(Settings::default());
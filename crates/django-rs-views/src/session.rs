@@ -14,10 +14,13 @@
 //! pipeline, loading sessions from the cookie on request and saving them on response.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tokio::sync::RwLock;
 
 use django_rs_core::DjangoError;
@@ -25,11 +28,79 @@ use django_rs_http::{HttpRequest, HttpResponse};
 
 use crate::middleware::Middleware;
 
+/// How long the server keeps a session before it expires, in seconds
+/// (two weeks), matching Django's `SESSION_COOKIE_AGE`.
+pub const DEFAULT_SESSION_AGE: i64 = 14 * 24 * 60 * 60;
+
+/// The session data key under which a custom expiry is stored.
+///
+/// Keeping the expiry in the data, as Django does, lets every backend
+/// persist it without a schema change.
+pub const SESSION_EXPIRY_KEY: &str = "_session_expiry";
+
+/// When a session expires, as set by [`SessionData::set_expiry`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum SessionExpiry {
+    /// The default age, [`DEFAULT_SESSION_AGE`].
+    #[default]
+    Default,
+    /// After the given number of seconds of inactivity.
+    Age(i64),
+    /// At a fixed moment.
+    At(DateTime<Utc>),
+    /// When the user closes the browser. The server still discards the
+    /// session after the default age.
+    BrowserClose,
+}
+
+impl SessionExpiry {
+    /// Returns the expiry `max(0, seconds)` seconds from now.
+    pub fn after(duration: Duration) -> Self {
+        Self::Age(duration.num_seconds().max(0))
+    }
+
+    /// Returns when a session saved now would expire.
+    pub fn expire_date(&self) -> DateTime<Utc> {
+        match self {
+            Self::Default | Self::BrowserClose => {
+                Utc::now() + Duration::seconds(DEFAULT_SESSION_AGE)
+            }
+            Self::Age(seconds) => Utc::now() + Duration::seconds(*seconds),
+            Self::At(at) => *at,
+        }
+    }
+}
+
 /// Data associated with a user session.
 ///
 /// Contains the session key, a map of key-value data, an expiration timestamp,
 /// and a flag indicating whether the session has been modified.
-#[derive(Debug, Clone)]
+///
+/// Besides the raw JSON accessors, [`get_as`](Self::get_as) and
+/// [`set_as`](Self::set_as) convert values to and from any serde type. Reads
+/// mark the session as accessed and writes mark it as modified, so the
+/// middleware only saves sessions that changed.
+///
+/// Inside a view, load the session from the request with
+/// [`from_request`](Self::from_request) and write it back with
+/// [`save_to_request`](Self::save_to_request):
+///
+/// ```
+/// use django_rs_http::HttpRequest;
+/// use django_rs_views::session::{SessionData, SessionExpiry};
+///
+/// let mut request = HttpRequest::builder().build();
+/// let mut session = SessionData::from_request(&request);
+/// session.set_as("cart", &vec![3_u32, 7]).unwrap();
+/// session.set_expiry(SessionExpiry::BrowserClose);
+/// session.save_to_request(&mut request);
+///
+/// let session = SessionData::from_request(&request);
+/// assert_eq!(session.get_as::<Vec<u32>>("cart").unwrap(), Some(vec![3, 7]));
+/// assert!(session.get_expire_at_browser_close());
+/// ```
+#[derive(Debug)]
 pub struct SessionData {
     /// The unique session key identifying this session.
     pub session_key: String,
@@ -39,42 +110,161 @@ pub struct SessionData {
     pub expire_date: DateTime<Utc>,
     /// Whether the session data has been modified since last save.
     pub modified: bool,
+    /// Whether the session data has been read or written.
+    accessed: AtomicBool,
+    /// The key the session had before [`cycle_key`](Self::cycle_key) or
+    /// [`flush`](Self::flush), whose stored copy must be deleted.
+    previous_key: Option<String>,
+}
+
+impl Clone for SessionData {
+    fn clone(&self) -> Self {
+        Self {
+            session_key: self.session_key.clone(),
+            data: self.data.clone(),
+            expire_date: self.expire_date,
+            modified: self.modified,
+            accessed: AtomicBool::new(self.accessed()),
+            previous_key: self.previous_key.clone(),
+        }
+    }
 }
 
 impl SessionData {
     /// Creates a new empty session with the given key and default expiration.
     pub fn new(session_key: String) -> Self {
-        Self {
-            session_key,
-            data: HashMap::new(),
-            expire_date: Utc::now() + Duration::weeks(2),
-            modified: false,
-        }
+        Self::with_lifetime(session_key, DEFAULT_SESSION_AGE)
     }
 
     /// Creates a new empty session with a specified lifetime.
     pub fn with_lifetime(session_key: String, lifetime_seconds: i64) -> Self {
+        Self::loaded(
+            session_key,
+            HashMap::new(),
+            Utc::now() + Duration::seconds(lifetime_seconds),
+        )
+    }
+
+    /// Creates an unmodified session from stored parts.
+    fn loaded(
+        session_key: String,
+        data: HashMap<String, serde_json::Value>,
+        expire_date: DateTime<Utc>,
+    ) -> Self {
         Self {
             session_key,
-            data: HashMap::new(),
-            expire_date: Utc::now() + Duration::seconds(lifetime_seconds),
+            data,
+            expire_date,
             modified: false,
+            accessed: AtomicBool::new(false),
+            previous_key: None,
+        }
+    }
+
+    /// Builds the session of a request from its [`SharedSession`] extension,
+    /// or from the `SESSION_*` META entries when it has none.
+    ///
+    /// Without [`SessionMiddleware`], this returns an empty session with a
+    /// fresh key.
+    pub fn from_request(request: &HttpRequest) -> Self {
+        match request.extensions().get::<SharedSession>() {
+            Some(shared) => shared.lock().clone(),
+            None => Self::from_meta(request),
+        }
+    }
+
+    /// Builds the session of a request from its `SESSION_*` META entries.
+    fn from_meta(request: &HttpRequest) -> Self {
+        let meta = request.meta();
+        let session_key = meta
+            .get("SESSION_KEY")
+            .cloned()
+            .unwrap_or_else(generate_session_key);
+        let data = meta
+            .get("SESSION_DATA")
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default();
+        let mut session = Self::loaded(session_key, data, Utc::now());
+        session.expire_date = session.get_expiry().expire_date();
+        session.modified = meta_modified(request);
+        session.accessed.store(
+            meta.get("SESSION_ACCESSED").is_some_and(|v| v == "true"),
+            Ordering::Relaxed,
+        );
+        session.previous_key = meta.get("SESSION_PREVIOUS_KEY").cloned();
+        session
+    }
+
+    /// Writes the session back into the request, for [`SessionMiddleware`]
+    /// to save on the way out.
+    ///
+    /// The request's [`SharedSession`] extension, if any, is updated along
+    /// with the `SESSION_*` META entries, so the change also reaches the
+    /// middleware when the view was handed a copy of the request.
+    pub fn save_to_request(&self, request: &mut HttpRequest) {
+        if let Some(shared) = request.extensions().get::<SharedSession>() {
+            *shared.lock() = self.clone();
+        }
+        let data = serde_json::to_string(&self.data).unwrap_or_else(|_| "{}".to_string());
+        let meta = request.meta_mut();
+        meta.insert("SESSION_KEY".to_string(), self.session_key.clone());
+        meta.insert("SESSION_DATA".to_string(), data);
+        meta.insert("SESSION_MODIFIED".to_string(), self.modified.to_string());
+        meta.insert("SESSION_ACCESSED".to_string(), self.accessed().to_string());
+        if let Some(previous) = &self.previous_key {
+            meta.insert("SESSION_PREVIOUS_KEY".to_string(), previous.clone());
         }
     }
 
     /// Gets a value from the session by key.
     pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.accessed.store(true, Ordering::Relaxed);
         self.data.get(key)
     }
 
+    /// Gets a value from the session, deserialized into `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::SerializationError`] if the stored value is not
+    /// a valid `T`.
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, DjangoError> {
+        self.get(key)
+            .map(|value| {
+                T::deserialize(value).map_err(|e| {
+                    DjangoError::SerializationError(format!("Session key '{key}': {e}"))
+                })
+            })
+            .transpose()
+    }
+
     /// Sets a value in the session.
     pub fn set(&mut self, key: &str, value: serde_json::Value) {
+        self.accessed.store(true, Ordering::Relaxed);
         self.data.insert(key.to_string(), value);
         self.modified = true;
     }
 
+    /// Sets a value in the session from any serializable type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DjangoError::SerializationError`] if `value` cannot be
+    /// converted to JSON.
+    pub fn set_as<T: Serialize + ?Sized>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), DjangoError> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| DjangoError::SerializationError(format!("Session key '{key}': {e}")))?;
+        self.set(key, value);
+        Ok(())
+    }
+
     /// Removes a value from the session.
     pub fn remove(&mut self, key: &str) -> Option<serde_json::Value> {
+        self.accessed.store(true, Ordering::Relaxed);
         let result = self.data.remove(key);
         if result.is_some() {
             self.modified = true;
@@ -87,12 +277,74 @@ impl SessionData {
         Utc::now() > self.expire_date
     }
 
+    /// Sets when the session expires and updates [`expire_date`](Self::expire_date).
+    ///
+    /// The expiry is stored in the session data under
+    /// [`SESSION_EXPIRY_KEY`]; `SessionExpiry::Default` removes it.
+    pub fn set_expiry(&mut self, expiry: SessionExpiry) {
+        if expiry == SessionExpiry::Default {
+            self.remove(SESSION_EXPIRY_KEY);
+        } else if let Ok(value) = serde_json::to_value(expiry) {
+            self.set(SESSION_EXPIRY_KEY, value);
+        }
+        self.expire_date = expiry.expire_date();
+    }
+
+    /// Returns the expiry set with [`set_expiry`](Self::set_expiry).
+    pub fn get_expiry(&self) -> SessionExpiry {
+        self.data
+            .get(SESSION_EXPIRY_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of seconds until the session expires, never
+    /// negative.
+    pub fn get_expiry_age(&self) -> i64 {
+        (self.expire_date - Utc::now()).num_seconds().max(0)
+    }
+
+    /// Returns `true` if the session cookie expires when the browser closes.
+    pub fn get_expire_at_browser_close(&self) -> bool {
+        self.get_expiry() == SessionExpiry::BrowserClose
+    }
+
     /// Clears all data from the session.
     pub fn clear(&mut self) {
+        self.accessed.store(true, Ordering::Relaxed);
         self.data.clear();
         self.modified = true;
     }
 
+    /// Clears the session and gives it a new key, as on logout.
+    ///
+    /// The middleware deletes the stored copy under the old key.
+    pub fn flush(&mut self) {
+        self.clear();
+        self.expire_date = SessionExpiry::Default.expire_date();
+        self.cycle_key();
+    }
+
+    /// Gives the session a new key while keeping its data, as on login, to
+    /// prevent session fixation.
+    ///
+    /// The middleware deletes the stored copy under the old key.
+    pub fn cycle_key(&mut self) {
+        let old_key = std::mem::replace(&mut self.session_key, generate_session_key());
+        self.previous_key.get_or_insert(old_key);
+        self.modified = true;
+    }
+
+    /// Returns the key the session had before it was cycled or flushed.
+    pub fn previous_key(&self) -> Option<&str> {
+        self.previous_key.as_deref()
+    }
+
+    /// Returns `true` if the session data has been read or written.
+    pub fn accessed(&self) -> bool {
+        self.accessed.load(Ordering::Relaxed)
+    }
+
     /// Returns the number of entries in the session data.
     pub fn len(&self) -> usize {
         self.data.len()
@@ -104,6 +356,32 @@ impl SessionData {
     }
 }
 
+/// The session of the current request, attached as a request extension by
+/// [`SessionMiddleware`].
+///
+/// Request extensions are shared with the view's copy of the request, so a
+/// session the view saves with [`SessionData::save_to_request`] is the one
+/// the middleware stores on the way out.
+#[derive(Debug, Clone)]
+pub struct SharedSession {
+    state: Arc<Mutex<SessionData>>,
+}
+
+impl SharedSession {
+    /// Wraps `session` for sharing between copies of a request.
+    pub fn new(session: SessionData) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(session)),
+        }
+    }
+
+    /// Locks the session. A panic while the lock was held leaves plain data
+    /// behind, so a poisoned lock is recovered rather than propagated.
+    pub fn lock(&self) -> MutexGuard<'_, SessionData> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
 /// A backend for storing and retrieving session data.
 ///
 /// This trait mirrors Django's session backend interface. Implementations
@@ -292,12 +570,11 @@ impl SessionBackend for DatabaseSessionBackend {
         let data: HashMap<String, serde_json::Value> =
             serde_json::from_str(&data_str).unwrap_or_default();
 
-        Ok(SessionData {
-            session_key: session_key.to_string(),
+        Ok(SessionData::loaded(
+            session_key.to_string(),
             data,
             expire_date,
-            modified: false,
-        })
+        ))
     }
 
     async fn save(&self, session: &SessionData) -> Result<String, DjangoError> {
@@ -504,12 +781,7 @@ impl SessionBackend for SignedCookieSessionBackend {
             .unwrap_or("")
             .to_string();
 
-        Ok(SessionData {
-            session_key: key,
-            data: session_data,
-            expire_date,
-            modified: false,
-        })
+        Ok(SessionData::loaded(key, session_data, expire_date))
     }
 
     async fn save(&self, session: &SessionData) -> Result<String, DjangoError> {
@@ -634,12 +906,11 @@ impl SessionBackend for FileSessionBackend {
             )));
         }
 
-        Ok(SessionData {
-            session_key: envelope.session_key,
-            data: envelope.data,
+        Ok(SessionData::loaded(
+            envelope.session_key,
+            envelope.data,
             expire_date,
-            modified: false,
-        })
+        ))
     }
 
     async fn save(&self, session: &SessionData) -> Result<String, DjangoError> {
@@ -724,14 +995,16 @@ impl SessionBackend for FileSessionBackend {
 /// On each response, saves modified session data back to the backend and sets
 /// the session cookie.
 ///
-/// Session data is serialized into the request's META dictionary:
+/// The loaded session is attached to the request as a [`SharedSession`]
+/// extension and mirrored into the request's META dictionary:
 /// - `SESSION_KEY`: the session key string
 /// - `SESSION_DATA`: JSON-serialized session data
 /// - `SESSION_MODIFIED`: "true" or "false"
 /// - `SESSION_IS_NEW`: "true" if a new session was created
 ///
-/// Views can access and modify session data via the META entries. On response,
-/// modified sessions are saved and the session cookie is set/updated.
+/// Views read and change the session through [`SessionData::from_request`]
+/// and [`SessionData::save_to_request`]. On response, modified sessions are
+/// saved and the session cookie is set/updated.
 ///
 /// This mirrors Django's `SessionMiddleware`.
 pub struct SessionMiddleware {
@@ -811,10 +1084,20 @@ impl SessionMiddleware {
     }
 
     /// Builds the Set-Cookie header value for the session cookie.
-    fn build_set_cookie(&self, session_key: &str) -> String {
+    ///
+    /// A session with an [`Age`](SessionExpiry::Age) or
+    /// [`At`](SessionExpiry::At) expiry gets a persistent cookie; otherwise
+    /// the cookie lasts until the browser closes.
+    fn build_set_cookie(&self, session_key: &str, session: &SessionData) -> String {
         use std::fmt::Write;
         let mut cookie = format!("{}={}", self.cookie_name, session_key);
         let _ = write!(cookie, "; Path={}", self.cookie_path);
+        if matches!(
+            session.get_expiry(),
+            SessionExpiry::Age(_) | SessionExpiry::At(_)
+        ) {
+            let _ = write!(cookie, "; Max-Age={}", session.get_expiry_age());
+        }
         if self.cookie_httponly {
             cookie.push_str("; HttpOnly");
         }
@@ -826,6 +1109,22 @@ impl SessionMiddleware {
         }
         cookie
     }
+
+    /// Builds the Set-Cookie header value that deletes the session cookie.
+    fn build_delete_cookie(&self) -> String {
+        format!(
+            "{}=; Path={}; Max-Age=0",
+            self.cookie_name, self.cookie_path
+        )
+    }
+}
+
+fn set_cookie_header(response: &mut HttpResponse, cookie: &str) {
+    if let Ok(header_value) = http::header::HeaderValue::from_str(cookie) {
+        response
+            .headers_mut()
+            .append(http::header::SET_COOKIE, header_value);
+    }
 }
 
 #[async_trait]
@@ -861,6 +1160,8 @@ impl Middleware for SessionMiddleware {
             meta.insert("SESSION_MODIFIED".to_string(), "false".to_string());
             meta.insert("SESSION_IS_NEW".to_string(), "true".to_string());
         }
+        let session = SharedSession::new(SessionData::from_meta(request));
+        request.extensions_mut().insert(session);
         None
     }
 
@@ -869,54 +1170,64 @@ impl Middleware for SessionMiddleware {
         request: &HttpRequest,
         response: HttpResponse,
     ) -> HttpResponse {
-        let meta = request.meta();
-        let Some(session_key) = meta.get("SESSION_KEY") else {
+        if !request.meta().contains_key("SESSION_KEY") {
             return response;
+        }
+        // Code that edits the META entries directly, rather than saving a
+        // session, still gets its change stored.
+        let session = match request.extensions().get::<SharedSession>() {
+            Some(shared) if shared.lock().modified || !meta_modified(request) => {
+                shared.lock().clone()
+            }
+            _ => SessionData::from_meta(request),
         };
-        let session_key = session_key.clone();
-
-        let session_data_str = meta
-            .get("SESSION_DATA")
-            .cloned()
-            .unwrap_or_else(|| "{}".to_string());
-        let modified = meta.get("SESSION_MODIFIED").is_some_and(|v| v == "true");
-        let is_new = meta.get("SESSION_IS_NEW").is_some_and(|v| v == "true");
-
-        // Only save and set cookie if session was modified or is new with data
-        let data: HashMap<String, serde_json::Value> =
-            serde_json::from_str(&session_data_str).unwrap_or_default();
+        let is_new = request
+            .meta()
+            .get("SESSION_IS_NEW")
+            .is_some_and(|v| v == "true");
+        let mut resp = response;
+
+        // The response depends on the cookie if the view looked at the session
+        if session.accessed() || session.modified {
+            resp.headers_mut().append(
+                http::header::VARY,
+                http::header::HeaderValue::from_static("Cookie"),
+            );
+        }
 
-        let should_save = modified || (is_new && !data.is_empty());
+        // A cycled or flushed session must not stay reachable under its old key
+        if let Some(previous) = session.previous_key() {
+            let _ = self.backend.delete(previous).await;
+        }
 
-        if should_save {
-            let mut session = SessionData::new(session_key.clone());
-            session.data = data;
+        if session.modified && session.is_empty() {
+            // Nothing left to keep: drop the cookie instead of saving an empty session
+            if !is_new {
+                let _ = self.backend.delete(&session.session_key).await;
+                set_cookie_header(&mut resp, &self.build_delete_cookie());
+            }
+            return resp;
+        }
 
-            // Save session to backend
+        // Only save when the data changed, or when a new session received data
+        if session.modified || (is_new && !session.is_empty()) {
             let _ = self.backend.save(&session).await;
-
-            // Set the session cookie
-            let cookie_value = self.build_set_cookie(&session_key);
-            let mut resp = response;
-            if let Ok(header_value) = http::header::HeaderValue::from_str(&cookie_value) {
-                resp.headers_mut()
-                    .insert(http::header::SET_COOKIE, header_value);
-            }
+            set_cookie_header(
+                &mut resp,
+                &self.build_set_cookie(&session.session_key, &session),
+            );
             return resp;
         }
 
         // If session already existed (not new), always set the cookie to maintain it
         if !is_new {
-            let cookie_value = self.build_set_cookie(&session_key);
-            let mut resp = response;
-            if let Ok(header_value) = http::header::HeaderValue::from_str(&cookie_value) {
-                resp.headers_mut()
-                    .insert(http::header::SET_COOKIE, header_value);
-            }
-            return resp;
+            set_cookie_header(
+                &mut resp,
+                &self.build_set_cookie(&session.session_key, &session),
+            );
         }
 
-        response
+        resp
     }
 
     async fn process_exception(
@@ -928,6 +1239,14 @@ impl Middleware for SessionMiddleware {
     }
 }
 
+/// Returns whether the request's `SESSION_MODIFIED` META entry is set.
+fn meta_modified(request: &HttpRequest) -> bool {
+    request
+        .meta()
+        .get("SESSION_MODIFIED")
+        .is_some_and(|v| v == "true")
+}
+
 /// Generates a random session key.
 pub fn generate_session_key() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -1029,6 +1348,83 @@ mod tests {
         assert!(session.expire_date > Utc::now());
     }
 
+    #[test]
+    fn test_session_data_typed_accessors() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Cart {
+            items: Vec<u32>,
+        }
+
+        let mut session = SessionData::new("test".to_string());
+        assert!(!session.accessed());
+        assert_eq!(session.get_as::<Cart>("cart").unwrap(), None);
+        assert!(session.accessed());
+        assert!(!session.modified);
+
+        session.set_as("cart", &Cart { items: vec![1, 2] }).unwrap();
+        assert!(session.modified);
+        assert_eq!(
+            session.get_as::<Cart>("cart").unwrap(),
+            Some(Cart { items: vec![1, 2] })
+        );
+        assert!(session.get_as::<String>("cart").is_err());
+    }
+
+    #[test]
+    fn test_session_data_set_expiry() {
+        let mut session = SessionData::new("test".to_string());
+        assert_eq!(session.get_expiry(), SessionExpiry::Default);
+        assert!(session.get_expiry_age() > DEFAULT_SESSION_AGE - 5);
+
+        session.set_expiry(SessionExpiry::after(Duration::minutes(5)));
+        assert_eq!(session.get_expiry(), SessionExpiry::Age(300));
+        assert!((295..=300).contains(&session.get_expiry_age()));
+        assert!(session.modified);
+
+        let at = Utc::now() + Duration::hours(1);
+        session.set_expiry(SessionExpiry::At(at));
+        assert_eq!(session.expire_date, at);
+
+        session.set_expiry(SessionExpiry::BrowserClose);
+        assert!(session.get_expire_at_browser_close());
+
+        session.set_expiry(SessionExpiry::Default);
+        assert!(session.is_empty());
+    }
+
+    #[test]
+    fn test_session_data_cycle_key_and_flush() {
+        let mut session = SessionData::new("original".to_string());
+        session.set("user", serde_json::json!("alice"));
+        session.modified = false;
+
+        session.cycle_key();
+        assert_ne!(session.session_key, "original");
+        assert_eq!(session.previous_key(), Some("original"));
+        assert_eq!(session.get("user"), Some(&serde_json::json!("alice")));
+        assert!(session.modified);
+
+        session.flush();
+        assert!(session.is_empty());
+        assert_eq!(session.previous_key(), Some("original"));
+    }
+
+    #[test]
+    fn test_session_data_request_round_trip() {
+        let mut request = HttpRequest::builder().build();
+        let mut session = SessionData::from_request(&request);
+        assert!(!session.modified);
+        session.set_as("n", &3).unwrap();
+        session.cycle_key();
+        session.save_to_request(&mut request);
+
+        let loaded = SessionData::from_request(&request);
+        assert_eq!(loaded.session_key, session.session_key);
+        assert_eq!(loaded.get_as::<i32>("n").unwrap(), Some(3));
+        assert!(loaded.modified);
+        assert_eq!(loaded.previous_key(), session.previous_key());
+    }
+
     // ── InMemorySessionBackend tests ────────────────────────────────
 
     #[tokio::test]
@@ -1182,6 +1578,126 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_session_middleware_cycle_key_and_expiry() {
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let mut stored = SessionData::new("old-key".to_string());
+        stored.set("user", serde_json::json!("alice"));
+        backend.save(&stored).await.unwrap();
+
+        let mw = SessionMiddleware::new(SharedBackend(backend.clone()));
+        let mut request = HttpRequest::builder()
+            .header("cookie", "sessionid=old-key")
+            .build();
+        mw.process_request(&mut request).await;
+
+        let mut session = SessionData::from_request(&request);
+        session.cycle_key();
+        session.set_expiry(SessionExpiry::Age(600));
+        session.save_to_request(&mut request);
+
+        let response = mw.process_response(&request, HttpResponse::ok("ok")).await;
+        let cookie = response.headers()[http::header::SET_COOKIE]
+            .to_str()
+            .unwrap();
+        assert!(cookie.starts_with(&format!("sessionid={}", session.session_key)));
+        assert!(cookie.contains("Max-Age=600") || cookie.contains("Max-Age=599"));
+        assert_eq!(response.headers()[http::header::VARY], "Cookie");
+        assert!(!backend.exists("old-key").await.unwrap());
+        let saved = backend.load(&session.session_key).await.unwrap();
+        assert_eq!(saved.get("user"), Some(&serde_json::json!("alice")));
+    }
+
+    #[tokio::test]
+    async fn test_session_middleware_skips_untouched_sessions() {
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let mw = SessionMiddleware::new(SharedBackend(backend.clone()));
+        let mut request = HttpRequest::builder().build();
+        mw.process_request(&mut request).await;
+
+        let response = mw.process_response(&request, HttpResponse::ok("ok")).await;
+        assert!(response.headers().get(http::header::SET_COOKIE).is_none());
+        assert!(response.headers().get(http::header::VARY).is_none());
+        let key = request.meta()["SESSION_KEY"].clone();
+        assert!(!backend.exists(&key).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_session_middleware_flush_deletes_cookie() {
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let mut stored = SessionData::new("old-key".to_string());
+        stored.set("user", serde_json::json!("alice"));
+        backend.save(&stored).await.unwrap();
+
+        let mw = SessionMiddleware::new(SharedBackend(backend.clone()));
+        let mut request = HttpRequest::builder()
+            .header("cookie", "sessionid=old-key")
+            .build();
+        mw.process_request(&mut request).await;
+        let mut session = SessionData::from_request(&request);
+        session.flush();
+        session.save_to_request(&mut request);
+
+        let response = mw.process_response(&request, HttpResponse::ok("ok")).await;
+        let cookie = response.headers()[http::header::SET_COOKIE]
+            .to_str()
+            .unwrap();
+        assert!(cookie.contains("Max-Age=0"));
+        assert!(!backend.exists("old-key").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_session_middleware_saves_view_writes_through_pipeline() {
+        use crate::middleware::{MiddlewarePipeline, ViewHandler};
+
+        let backend = Arc::new(InMemorySessionBackend::new());
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(SessionMiddleware::new(SharedBackend(backend.clone())));
+        let handler: ViewHandler = Box::new(|mut request| {
+            Box::pin(async move {
+                let mut session = SessionData::from_request(&request);
+                session.set("cart", serde_json::json!([3, 7]));
+                session.save_to_request(&mut request);
+                HttpResponse::ok("ok")
+            })
+        });
+
+        let response = pipeline
+            .process(HttpRequest::builder().build(), &handler)
+            .await;
+        let cookie = response.headers()[http::header::SET_COOKIE]
+            .to_str()
+            .unwrap();
+        let key = cookie
+            .strip_prefix("sessionid=")
+            .and_then(|rest| rest.split(';').next())
+            .unwrap();
+        let saved = backend.load(key).await.unwrap();
+        assert_eq!(saved.get("cart"), Some(&serde_json::json!([3, 7])));
+    }
+
+    /// Lets a test keep a handle on the backend the middleware owns.
+    struct SharedBackend(Arc<InMemorySessionBackend>);
+
+    #[async_trait]
+    impl SessionBackend for SharedBackend {
+        async fn load(&self, session_key: &str) -> Result<SessionData, DjangoError> {
+            self.0.load(session_key).await
+        }
+        async fn save(&self, session: &SessionData) -> Result<String, DjangoError> {
+            self.0.save(session).await
+        }
+        async fn delete(&self, session_key: &str) -> Result<(), DjangoError> {
+            self.0.delete(session_key).await
+        }
+        async fn exists(&self, session_key: &str) -> Result<bool, DjangoError> {
+            self.0.exists(session_key).await
+        }
+        async fn clear_expired(&self) -> Result<(), DjangoError> {
+            self.0.clear_expired().await
+        }
+    }
+
     // ── generate_session_key tests ──────────────────────────────────
 
     #[test]
@@ -20,13 +20,22 @@
 //! to use for date-based filtering. Dates in the queryset are expected
 //! to be ISO 8601 format strings (e.g., `"2026-02-15"` or
 //! `"2026-02-15T10:30:00"`).
+//!
+//! The `date_list` of [`ArchiveIndexView`] and [`YearArchiveView`] holds the
+//! distinct years or months that have objects. By default it is derived from
+//! the fetched objects; views backed by a model can override `get_date_list`
+//! with [`queryset_date_list`] to have the database compute it.
 
 use std::collections::HashMap;
 
 use async_trait::async_trait;
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Duration, NaiveDate};
 
 use django_rs_core::DjangoError;
+use django_rs_db::executor::DbExecutor;
+use django_rs_db::model::Model;
+use django_rs_db::value::Value;
+use django_rs_db::{DateKind, Lookup, QuerySet, Q};
 use django_rs_http::{HttpRequest, HttpResponse};
 use django_rs_template::context::{Context, ContextValue};
 use django_rs_template::engine::Engine;
@@ -36,18 +45,26 @@ use crate::pagination::Paginator;
 
 /// Renders a template with the given name and serde_json context using the engine.
 ///
-/// If no engine is provided, falls back to a JSON representation.
+/// With a request, the engine's context processors add their variables. If no
+/// engine is provided, falls back to a JSON representation.
 fn render_with_engine(
     template_name: &str,
     context: &HashMap<String, serde_json::Value>,
     engine: Option<&Engine>,
+    request: Option<&HttpRequest>,
 ) -> HttpResponse {
     if let Some(engine) = engine {
         let mut template_context = Context::new();
         for (key, value) in context {
             template_context.set(key.clone(), ContextValue::from(value.clone()));
         }
-        match engine.render_to_string(template_name, &mut template_context) {
+        let rendered = match request {
+            Some(request) => {
+                engine.render_for_request(template_name, &mut template_context, request)
+            }
+            None => engine.render_to_string(template_name, &mut template_context),
+        };
+        match rendered {
             Ok(html) => {
                 let mut response = HttpResponse::ok(html);
                 response.set_content_type("text/html");
@@ -140,19 +157,76 @@ fn sort_by_date_desc(objects: &mut [serde_json::Value], date_field: &str) {
 
 /// Collects unique dates from objects, sorted newest first.
 fn collect_date_list(objects: &[serde_json::Value], date_field: &str) -> Vec<String> {
-    let mut dates: Vec<NaiveDate> = objects
+    format_date_list(&collect_periods(objects, date_field, DateKind::Day))
+}
+
+/// Truncates `date` to the start of its `kind` period.
+fn truncate_date(date: NaiveDate, kind: DateKind) -> NaiveDate {
+    match kind {
+        DateKind::Year => date.with_ordinal(1).unwrap_or(date),
+        DateKind::Month => date.with_day(1).unwrap_or(date),
+        DateKind::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+        DateKind::Day => date,
+    }
+}
+
+/// Collects the distinct `kind` periods that objects fall in, newest first.
+fn collect_periods(
+    objects: &[serde_json::Value],
+    date_field: &str,
+    kind: DateKind,
+) -> Vec<NaiveDate> {
+    let mut periods: Vec<NaiveDate> = objects
         .iter()
         .filter_map(|obj| extract_date(obj, date_field))
+        .map(|d| truncate_date(d, kind))
         .collect();
-    dates.sort_unstable();
-    dates.dedup();
-    dates.reverse(); // Newest first
+    periods.sort_unstable();
+    periods.dedup();
+    periods.reverse(); // Newest first
+    periods
+}
+
+/// Formats dates for the template context as `YYYY-MM-DD` strings.
+fn format_date_list(dates: &[NaiveDate]) -> Vec<String> {
     dates
         .iter()
         .map(|d| d.format("%Y-%m-%d").to_string())
         .collect()
 }
 
+/// Lists the distinct `kind` periods of `date_field` in `queryset`, newest
+/// first, computed by the database.
+///
+/// Unless `allow_future`, objects dated after today are left out, as the
+/// archive views do. Use this to override `get_date_list` on a view backed
+/// by a model:
+///
+/// ```ignore
+/// async fn get_date_list(
+///     &self,
+///     _objects: &[serde_json::Value],
+/// ) -> Result<Vec<NaiveDate>, DjangoError> {
+///     let articles = Manager::<Article>::new().all();
+///     queryset_date_list(articles, &*self.db, "pub_date", DateKind::Year, false).await
+/// }
+/// ```
+pub async fn queryset_date_list<M: Model>(
+    queryset: QuerySet<M>,
+    db: &dyn DbExecutor,
+    date_field: &str,
+    kind: DateKind,
+    allow_future: bool,
+) -> Result<Vec<NaiveDate>, DjangoError> {
+    let queryset = if allow_future {
+        queryset
+    } else {
+        let tomorrow = chrono::Utc::now().date_naive() + Duration::days(1);
+        queryset.filter(Q::filter(date_field, Lookup::Lt(Value::Date(tomorrow))))
+    };
+    queryset.dates_exec(db, date_field, kind, true).await
+}
+
 // ── Trait: DateMixin (shared config) ──────────────────────────────────
 
 /// Provides the date field configuration shared by all archive views.
@@ -179,7 +253,8 @@ pub trait DateMixin {
 ///
 /// The template context includes:
 /// - `object_list`: The objects for the current page
-/// - `date_list`: Unique dates that have objects
+/// - `date_list`: The years (see [`date_list_period`](Self::date_list_period))
+///   that have objects, newest first
 /// - `latest`: The most recent object, if any
 #[async_trait]
 pub trait ArchiveIndexView: View + ContextMixin + DateMixin + Send + Sync {
@@ -204,6 +279,26 @@ pub trait ArchiveIndexView: View + ContextMixin + DateMixin + Send + Sync {
     /// Retrieves all objects to display.
     async fn get_queryset(&self) -> Result<Vec<serde_json::Value>, DjangoError>;
 
+    /// Returns the period the `date_list` is made of. Default: years.
+    fn date_list_period(&self) -> DateKind {
+        DateKind::Year
+    }
+
+    /// Returns the periods that have objects, newest first.
+    ///
+    /// The default collects them from `objects`, the fetched objects with
+    /// future ones already left out unless allowed.
+    async fn get_date_list(
+        &self,
+        objects: &[serde_json::Value],
+    ) -> Result<Vec<NaiveDate>, DjangoError> {
+        Ok(collect_periods(
+            objects,
+            self.date_field(),
+            self.date_list_period(),
+        ))
+    }
+
     /// Handles GET requests for the archive index.
     async fn archive_index(&self, request: HttpRequest) -> HttpResponse {
         match self.get_queryset().await {
@@ -220,8 +315,12 @@ pub trait ArchiveIndexView: View + ContextMixin + DateMixin + Send + Sync {
                 // Sort newest first
                 sort_by_date_desc(&mut objects, date_field);
 
-                // Collect date list
-                let date_list = collect_date_list(&objects, date_field);
+                let date_list = match self.get_date_list(&objects).await {
+                    Ok(dates) => format_date_list(&dates),
+                    Err(e) => {
+                        return HttpResponse::server_error(format!("Error fetching dates: {e}"))
+                    }
+                };
                 let latest = objects.first().cloned();
 
                 let mut context = self.get_context_data(&HashMap::new());
@@ -262,7 +361,7 @@ pub trait ArchiveIndexView: View + ContextMixin + DateMixin + Send + Sync {
                 }
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching objects: {e}")),
         }
@@ -279,7 +378,8 @@ pub trait ArchiveIndexView: View + ContextMixin + DateMixin + Send + Sync {
 /// The template context includes:
 /// - `object_list`: The objects for the year
 /// - `year`: The year as a string
-/// - `date_list`: Unique dates within the year
+/// - `date_list`: The months (see [`date_list_period`](Self::date_list_period))
+///   within the year that have objects, newest first
 #[async_trait]
 pub trait YearArchiveView: View + ContextMixin + DateMixin + Send + Sync {
     /// Returns the model name for this archive view.
@@ -298,8 +398,29 @@ pub trait YearArchiveView: View + ContextMixin + DateMixin + Send + Sync {
     /// Retrieves all objects to filter.
     async fn get_queryset(&self) -> Result<Vec<serde_json::Value>, DjangoError>;
 
+    /// Returns the period the `date_list` is made of. Default: months.
+    fn date_list_period(&self) -> DateKind {
+        DateKind::Month
+    }
+
+    /// Returns the periods within `year` that have objects, newest first.
+    ///
+    /// The default collects them from `objects`, the fetched objects of the
+    /// year with future ones already left out unless allowed.
+    async fn get_date_list(
+        &self,
+        _year: i32,
+        objects: &[serde_json::Value],
+    ) -> Result<Vec<NaiveDate>, DjangoError> {
+        Ok(collect_periods(
+            objects,
+            self.date_field(),
+            self.date_list_period(),
+        ))
+    }
+
     /// Handles GET requests for a year archive.
-    async fn year_archive(&self, _request: HttpRequest, year: i32) -> HttpResponse {
+    async fn year_archive(&self, request: HttpRequest, year: i32) -> HttpResponse {
         match self.get_queryset().await {
             Ok(objects) => {
                 let date_field = self.date_field();
@@ -312,7 +433,12 @@ pub trait YearArchiveView: View + ContextMixin + DateMixin + Send + Sync {
                 }
 
                 sort_by_date_desc(&mut filtered, date_field);
-                let date_list = collect_date_list(&filtered, date_field);
+                let date_list = match self.get_date_list(year, &filtered).await {
+                    Ok(dates) => format_date_list(&dates),
+                    Err(e) => {
+                        return HttpResponse::server_error(format!("Error fetching dates: {e}"))
+                    }
+                };
 
                 let mut context = self.get_context_data(&HashMap::new());
                 context.insert("year".to_string(), serde_json::json!(year.to_string()));
@@ -323,7 +449,7 @@ pub trait YearArchiveView: View + ContextMixin + DateMixin + Send + Sync {
                 context.insert("date_list".to_string(), serde_json::json!(date_list));
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching objects: {e}")),
         }
@@ -361,7 +487,7 @@ pub trait MonthArchiveView: View + ContextMixin + DateMixin + Send + Sync {
     async fn get_queryset(&self) -> Result<Vec<serde_json::Value>, DjangoError>;
 
     /// Handles GET requests for a month archive.
-    async fn month_archive(&self, _request: HttpRequest, year: i32, month: u32) -> HttpResponse {
+    async fn month_archive(&self, request: HttpRequest, year: i32, month: u32) -> HttpResponse {
         match self.get_queryset().await {
             Ok(objects) => {
                 let date_field = self.date_field();
@@ -389,7 +515,7 @@ pub trait MonthArchiveView: View + ContextMixin + DateMixin + Send + Sync {
                 context.insert("date_list".to_string(), serde_json::json!(date_list));
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching objects: {e}")),
         }
@@ -427,7 +553,7 @@ pub trait DayArchiveView: View + ContextMixin + DateMixin + Send + Sync {
     /// Handles GET requests for a day archive.
     async fn day_archive(
         &self,
-        _request: HttpRequest,
+        request: HttpRequest,
         year: i32,
         month: u32,
         day: u32,
@@ -460,7 +586,7 @@ pub trait DayArchiveView: View + ContextMixin + DateMixin + Send + Sync {
                 );
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching objects: {e}")),
         }
@@ -496,7 +622,7 @@ pub trait TodayArchiveView: View + ContextMixin + DateMixin + Send + Sync {
     async fn get_queryset(&self) -> Result<Vec<serde_json::Value>, DjangoError>;
 
     /// Handles GET requests for today's archive.
-    async fn today_archive(&self, _request: HttpRequest) -> HttpResponse {
+    async fn today_archive(&self, request: HttpRequest) -> HttpResponse {
         let today = chrono::Utc::now().date_naive();
 
         match self.get_queryset().await {
@@ -516,7 +642,7 @@ pub trait TodayArchiveView: View + ContextMixin + DateMixin + Send + Sync {
                 );
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching objects: {e}")),
         }
@@ -558,7 +684,7 @@ pub trait DateDetailView: View + ContextMixin + DateMixin + Send + Sync {
     /// Handles GET requests for a date-validated detail view.
     async fn date_detail(
         &self,
-        _request: HttpRequest,
+        request: HttpRequest,
         year: i32,
         month: u32,
         day: u32,
@@ -596,7 +722,7 @@ pub trait DateDetailView: View + ContextMixin + DateMixin + Send + Sync {
                 );
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(DjangoError::NotFound(msg) | DjangoError::DoesNotExist(msg)) => {
                 HttpResponse::not_found(msg)
@@ -728,6 +854,29 @@ mod tests {
         assert!(dates.is_empty());
     }
 
+    #[test]
+    fn test_collect_periods() {
+        let objects = vec![
+            serde_json::json!({"pub_date": "2026-02-15"}),
+            serde_json::json!({"pub_date": "2026-02-18T10:00:00"}),
+            serde_json::json!({"pub_date": "2025-11-03"}),
+        ];
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            collect_periods(&objects, "pub_date", DateKind::Year),
+            vec![date(2026, 1, 1), date(2025, 1, 1)]
+        );
+        assert_eq!(
+            collect_periods(&objects, "pub_date", DateKind::Month),
+            vec![date(2026, 2, 1), date(2025, 11, 1)]
+        );
+        // The 15th is a Sunday, the 18th a Wednesday
+        assert_eq!(
+            collect_periods(&objects, "pub_date", DateKind::Week),
+            vec![date(2026, 2, 16), date(2026, 2, 9), date(2025, 11, 3)]
+        );
+    }
+
     // ── ArchiveIndexView tests ────────────────────────────────────────
 
     struct TestArchiveIndexView {
@@ -799,6 +948,22 @@ mod tests {
         assert!(body.contains("date_list"));
     }
 
+    #[tokio::test]
+    async fn test_archive_index_date_list_is_years() {
+        let view = TestArchiveIndexView {
+            items: vec![
+                serde_json::json!({"title": "Older", "pub_date": "2024-05-03"}),
+                serde_json::json!({"title": "Newer", "pub_date": "2025-06-15"}),
+            ],
+            paginate: None,
+        };
+        let request = HttpRequest::builder().method(http::Method::GET).build();
+        let response = view.dispatch(request).await;
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        assert!(body.contains("\"2025-01-01\""));
+        assert!(body.contains("\"2024-01-01\""));
+    }
+
     #[tokio::test]
     async fn test_archive_index_sorted_newest_first() {
         let view = TestArchiveIndexView {
@@ -915,6 +1080,24 @@ mod tests {
         assert!(body.contains("\"year\""));
     }
 
+    #[tokio::test]
+    async fn test_year_archive_date_list_is_months() {
+        let view = TestYearArchiveView {
+            items: vec![
+                serde_json::json!({"title": "A", "pub_date": "2025-06-15"}),
+                serde_json::json!({"title": "B", "pub_date": "2025-06-20"}),
+                serde_json::json!({"title": "C", "pub_date": "2025-02-10"}),
+            ],
+        };
+        let request = HttpRequest::builder().method(http::Method::GET).build();
+        let response = view.year_archive(request, 2025).await;
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        let june = body.find("\"2025-06-01\"").unwrap();
+        let february = body.find("\"2025-02-01\"").unwrap();
+        assert!(june < february);
+        assert_eq!(body.matches("2025-06-01").count(), 1);
+    }
+
     // ── MonthArchiveView tests ────────────────────────────────────────
 
     struct TestMonthArchiveView {
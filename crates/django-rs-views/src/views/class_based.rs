@@ -62,11 +62,28 @@ pub trait View: Send + Sync {
         ]
     }
 
+    /// Checks whether the request may access this view at all.
+    ///
+    /// Called by [`dispatch`](View::dispatch) before any method handler.
+    /// Returning `Some(response)` short-circuits the request, e.g. with a login
+    /// redirect or a 403. Views using
+    /// [`LoginRequiredMixin`](super::function::LoginRequiredMixin) or
+    /// [`PermissionRequiredMixin`](super::function::PermissionRequiredMixin)
+    /// override this to call `check_login` or `check_permission`. Allows
+    /// everything by default.
+    fn check_access(&self, _request: &HttpRequest) -> Option<HttpResponse> {
+        None
+    }
+
     /// Dispatches the request to the appropriate HTTP method handler.
     ///
-    /// This is the main entry point for the view. It checks the request method
+    /// This is the main entry point for the view. It runs
+    /// [`check_access`](View::check_access), then checks the request method
     /// and calls the corresponding handler method.
     async fn dispatch(&self, request: HttpRequest) -> HttpResponse {
+        if let Some(response) = self.check_access(&request) {
+            return response;
+        }
         match *request.method() {
             http::Method::GET => self.get(request).await,
             http::Method::POST => self.post(request).await,
@@ -187,6 +204,27 @@ pub trait TemplateResponseMixin: View {
         &self,
         context: HashMap<String, serde_json::Value>,
         engine: Option<&Engine>,
+    ) -> HttpResponse {
+        self.render_engine_response(context, engine, None)
+    }
+
+    /// Renders the template like `render_to_response_with_engine`, additionally
+    /// running the engine's context processors against `request`.
+    fn render_to_response_for_request(
+        &self,
+        context: HashMap<String, serde_json::Value>,
+        engine: Option<&Engine>,
+        request: &HttpRequest,
+    ) -> HttpResponse {
+        self.render_engine_response(context, engine, Some(request))
+    }
+
+    /// Shared implementation of the engine-backed render methods.
+    fn render_engine_response(
+        &self,
+        context: HashMap<String, serde_json::Value>,
+        engine: Option<&Engine>,
+        request: Option<&HttpRequest>,
     ) -> HttpResponse {
         let Some(engine) = engine else {
             return self.render_to_response(context);
@@ -198,7 +236,13 @@ pub trait TemplateResponseMixin: View {
             template_context.set(key, ContextValue::from(value));
         }
 
-        match engine.render_to_string(template_name, &mut template_context) {
+        let rendered = match request {
+            Some(request) => {
+                engine.render_for_request(template_name, &mut template_context, request)
+            }
+            None => engine.render_to_string(template_name, &mut template_context),
+        };
+        match rendered {
             Ok(html) => {
                 let mut response = HttpResponse::ok(html);
                 response.set_content_type("text/html");
@@ -293,9 +337,9 @@ impl TemplateResponseMixin for TemplateView {
 
 #[async_trait]
 impl View for TemplateView {
-    async fn get(&self, _request: HttpRequest) -> HttpResponse {
+    async fn get(&self, request: HttpRequest) -> HttpResponse {
         let context = self.get_context_data(&HashMap::new());
-        self.render_to_response_with_engine(context, self.engine.as_deref())
+        self.render_to_response_for_request(context, self.engine.as_deref(), &request)
     }
 }
 
@@ -523,6 +567,33 @@ mod tests {
         assert_eq!(context.get("id").unwrap(), &serde_json::json!("42"));
     }
 
+    #[tokio::test]
+    async fn test_template_view_runs_context_processors() {
+        let mut engine = Engine::new();
+        engine.add_string_template(
+            "account.html",
+            "{{ user.is_authenticated }}|{{ csrf_token }}|{{ title }}",
+        );
+        engine.add_context_processor(Arc::new(
+            django_rs_template::context_processors::AuthContextProcessor,
+        ));
+        engine.add_context_processor(Arc::new(
+            django_rs_template::context_processors::CsrfContextProcessor,
+        ));
+        let view = TemplateView::new("account.html")
+            .with_engine(Arc::new(engine))
+            .with_context("title", serde_json::json!("Account"));
+        let request = HttpRequest::builder()
+            .method(http::Method::GET)
+            .meta("USER_AUTHENTICATED", "true")
+            .meta("USER_ID", "7")
+            .meta("CSRF_COOKIE", "tok123")
+            .build();
+        let response = view.dispatch(request).await;
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        assert_eq!(body, "True|tok123|Account");
+    }
+
     #[tokio::test]
     async fn test_redirect_view_temporary() {
         let view = RedirectView::new("/new-url/");
@@ -138,6 +138,14 @@ pub fn form_errors(form: &dyn Form) -> HashMap<String, Vec<String>> {
 /// This avoids the need to clone `FormFieldDef` values (which contain trait objects).
 pub type FormFactory = Arc<dyn Fn() -> BaseForm + Send + Sync>;
 
+/// Type alias for an access check run before a view handles a request.
+///
+/// Plays the role of [`View::check_access`](super::class_based::View::check_access)
+/// for views configured with builders: returning `Some(response)`, e.g. from
+/// [`LoginRequiredMixin::check_login`](super::function::LoginRequiredMixin::check_login),
+/// answers the request without running the view.
+pub type AccessCheck = Arc<dyn Fn(&HttpRequest) -> Option<HttpResponse> + Send + Sync>;
+
 /// A generic view for displaying and processing a form.
 ///
 /// Mirrors Django's `FormView` generic class-based view. On GET requests,
@@ -166,6 +174,7 @@ pub struct FormView {
     success_url: String,
     initial: HashMap<String, String>,
     engine: Option<Arc<Engine>>,
+    access_check: Option<AccessCheck>,
 }
 
 impl FormView {
@@ -177,6 +186,7 @@ impl FormView {
             success_url: success_url.to_string(),
             initial: HashMap::new(),
             engine: None,
+            access_check: None,
         }
     }
 
@@ -201,6 +211,14 @@ impl FormView {
         self
     }
 
+    /// Sets the access check run before every request, so that a login
+    /// redirect or a 403 is returned instead of the form.
+    #[must_use]
+    pub fn access_check(mut self, check: AccessCheck) -> Self {
+        self.access_check = Some(check);
+        self
+    }
+
     /// Returns the template name.
     pub fn template_name(&self) -> &str {
         &self.template_name
@@ -222,11 +240,16 @@ impl FormView {
 
     /// Dispatches the request to the appropriate handler.
     ///
+    /// Runs the [`access_check`](Self::access_check) first, then:
+    ///
     /// - GET: Renders the form template with an empty form
     /// - POST: Validates the form, calls `form_valid` or `form_invalid`
     pub async fn dispatch(&self, request: &HttpRequest) -> HttpResponse {
+        if let Some(response) = self.access_check.as_ref().and_then(|check| check(request)) {
+            return response;
+        }
         match *request.method() {
-            http::Method::GET | http::Method::HEAD => self.render_form(None),
+            http::Method::GET | http::Method::HEAD => self.render_form(None, Some(request)),
             http::Method::POST => self.process_form(request).await,
             _ => HttpResponse::not_allowed(&["GET", "POST"]),
         }
@@ -242,6 +265,17 @@ impl FormView {
         &self,
         errors: &HashMap<String, Vec<String>>,
         form_context: &HashMap<String, ContextValue>,
+    ) -> HttpResponse {
+        self.render_invalid(errors, form_context, None)
+    }
+
+    /// Re-renders the template with errors, running the engine's context
+    /// processors when the originating request is known.
+    fn render_invalid(
+        &self,
+        errors: &HashMap<String, Vec<String>>,
+        form_context: &HashMap<String, ContextValue>,
+        request: Option<&HttpRequest>,
     ) -> HttpResponse {
         let mut context: HashMap<String, serde_json::Value> = HashMap::new();
 
@@ -266,13 +300,14 @@ impl FormView {
             );
         }
 
-        self.render_template(&context)
+        self.render_template(&context, request)
     }
 
     /// Renders the form template (for GET requests or re-render on error).
     fn render_form(
         &self,
         extra_context: Option<HashMap<String, serde_json::Value>>,
+        request: Option<&HttpRequest>,
     ) -> HttpResponse {
         let form = self.create_form();
         let form_ctx = form.as_context();
@@ -296,7 +331,7 @@ impl FormView {
             context.extend(extra);
         }
 
-        self.render_template(&context)
+        self.render_template(&context, request)
     }
 
     /// Processes a POST request: binds, validates, and dispatches.
@@ -315,18 +350,28 @@ impl FormView {
         } else {
             let errors = form.errors().clone();
             let form_ctx = form.as_context();
-            self.form_invalid(&errors, &form_ctx)
+            self.render_invalid(&errors, &form_ctx, Some(request))
         }
     }
 
     /// Renders the template with the given context.
-    fn render_template(&self, context: &HashMap<String, serde_json::Value>) -> HttpResponse {
+    fn render_template(
+        &self,
+        context: &HashMap<String, serde_json::Value>,
+        request: Option<&HttpRequest>,
+    ) -> HttpResponse {
         if let Some(ref engine) = self.engine {
             let mut template_context = Context::new();
             for (key, value) in context {
                 template_context.set(key.clone(), ContextValue::from(value.clone()));
             }
-            match engine.render_to_string(&self.template_name, &mut template_context) {
+            let rendered = match request {
+                Some(request) => {
+                    engine.render_for_request(&self.template_name, &mut template_context, request)
+                }
+                None => engine.render_to_string(&self.template_name, &mut template_context),
+            };
+            match rendered {
                 Ok(html) => {
                     let mut response = HttpResponse::ok(html);
                     response.set_content_type("text/html");
@@ -483,6 +528,20 @@ mod tests {
         assert_eq!(json.get("name"), Some(&serde_json::json!("test")));
     }
 
+    #[test]
+    fn test_form_context_to_json_exposes_widget_attrs() {
+        let form = BaseForm::new(vec![FormFieldDef::new("email", FormFieldType::Email)
+            .placeholder("you@example.com")
+            .css_class("input")]);
+
+        let json = form_context_to_json(&form.as_context());
+        let field = &json["fields"][0];
+        assert_eq!(field["widget"], "EmailInput");
+        assert_eq!(field["attrs"]["placeholder"], "you@example.com");
+        assert_eq!(field["attrs"]["class"], "input");
+        assert_eq!(field["attrs"]["required"], "required");
+    }
+
     #[test]
     fn test_context_value_to_json_types() {
         assert_eq!(
@@ -597,6 +656,36 @@ mod tests {
         assert!(body.contains("contact.html"));
     }
 
+    struct StaffOnly;
+    impl crate::views::function::LoginRequiredMixin for StaffOnly {}
+
+    #[tokio::test]
+    async fn test_formview_access_check_runs_before_handler() {
+        use crate::views::function::LoginRequiredMixin;
+
+        let view =
+            make_form_view().access_check(Arc::new(|request| StaffOnly.check_login(request)));
+        let request = HttpRequest::builder()
+            .method(http::Method::POST)
+            .path("/contact/")
+            .content_type("application/x-www-form-urlencoded")
+            .body(b"name=Alice&email=alice@example.com".to_vec())
+            .build();
+        let response = view.dispatch(&request).await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+        assert_eq!(
+            response.headers().get(http::header::LOCATION).unwrap(),
+            "/accounts/login/?next=/contact/"
+        );
+
+        let request = HttpRequest::builder()
+            .method(http::Method::GET)
+            .meta("USER_AUTHENTICATED", "true")
+            .build();
+        let response = view.dispatch(&request).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_formview_post_valid_redirects() {
         let view = make_form_view();
@@ -21,6 +21,7 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use django_rs_http::{HttpRequest, HttpResponse, HttpResponseRedirect};
 
@@ -219,12 +220,88 @@ pub fn permission_required(perm: &str, login_url: &str, view: ViewFunction) -> V
     })
 }
 
+/// Wraps a view function so it returns 504 Gateway Timeout if it runs longer
+/// than `timeout`.
+///
+/// The wrapped view's future is dropped when the deadline passes, cancelling
+/// any work it was still awaiting. Use this for per-view deadlines; for a
+/// site-wide default with per-route overrides see
+/// [`TimeoutMiddleware`](crate::middleware::builtin::TimeoutMiddleware).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use django_rs_views::views::function::{ViewFunction, with_timeout};
+/// use django_rs_http::{HttpRequest, HttpResponse};
+///
+/// let my_view: ViewFunction = Box::new(|_req| {
+///     Box::pin(async { HttpResponse::ok("Report") })
+/// });
+///
+/// let bounded = with_timeout(Duration::from_secs(5), my_view);
+/// ```
+pub fn with_timeout(timeout: Duration, view: ViewFunction) -> ViewFunction {
+    let view = Arc::new(view);
+
+    Box::new(move |request: HttpRequest| {
+        let view = view.clone();
+
+        Box::pin(async move {
+            let route = request
+                .resolver_match()
+                .map(django_rs_http::urls::resolver::ResolverMatch::view_name)
+                .filter(|name| !name.is_empty());
+            let path = request.path().to_string();
+
+            match tokio::time::timeout(timeout, view(request)).await {
+                Ok(response) => response,
+                Err(_) => crate::middleware::builtin::gateway_timeout_response(
+                    route.as_deref(),
+                    &path,
+                    timeout,
+                ),
+            }
+        })
+    })
+}
+
 /// Trait for class-based views that require authentication.
 ///
 /// Implementing this trait on a view ensures that only authenticated users
-/// can access it. Unauthenticated users are redirected to the login URL.
+/// can access it. Unauthenticated users are redirected to the login URL, or
+/// get a 403 when [`raise_exception`](Self::raise_exception) is set. Wire it
+/// into dispatch by overriding [`View::check_access`]:
+///
+/// ```
+/// use async_trait::async_trait;
+/// use django_rs_http::{HttpRequest, HttpResponse};
+/// use django_rs_views::views::class_based::View;
+/// use django_rs_views::views::function::LoginRequiredMixin;
+///
+/// struct DashboardView;
+///
+/// impl LoginRequiredMixin for DashboardView {
+///     fn login_url(&self) -> &str {
+///         "/staff/login/"
+///     }
+/// }
+///
+/// #[async_trait]
+/// impl View for DashboardView {
+///     fn check_access(&self, request: &HttpRequest) -> Option<HttpResponse> {
+///         self.check_login(request)
+///     }
+///
+///     async fn get(&self, _request: HttpRequest) -> HttpResponse {
+///         HttpResponse::ok("dashboard")
+///     }
+/// }
+/// ```
 ///
 /// This mirrors Django's `LoginRequiredMixin`.
+///
+/// [`View::check_access`]: super::class_based::View::check_access
 pub trait LoginRequiredMixin {
     /// Returns the login URL to redirect unauthenticated users to.
     fn login_url(&self) -> &str {
@@ -236,54 +313,116 @@ pub trait LoginRequiredMixin {
         "next"
     }
 
-    /// Checks whether the request is from an authenticated user.
-    fn check_login(&self, request: &HttpRequest) -> Option<HttpResponse> {
-        let is_authenticated = request
+    /// Returns whether to respond with 403 instead of redirecting to the
+    /// login URL. Defaults to `false`.
+    fn raise_exception(&self) -> bool {
+        false
+    }
+
+    /// Returns whether the request is from an authenticated user.
+    fn is_authenticated(&self, request: &HttpRequest) -> bool {
+        request
             .meta()
             .get("USER_AUTHENTICATED")
-            .is_some_and(|v| v == "true");
+            .is_some_and(|v| v == "true")
+    }
+
+    /// Builds the response for a request that is not allowed through.
+    ///
+    /// Returns 403 if [`raise_exception`](Self::raise_exception) is set or the
+    /// user is already authenticated (logging in again would not help), and
+    /// a redirect to the login URL otherwise.
+    fn handle_no_permission(&self, request: &HttpRequest) -> HttpResponse {
+        if self.raise_exception() || self.is_authenticated(request) {
+            return HttpResponse::forbidden("Permission denied");
+        }
+        let current_path = request.get_full_path();
+        let login_url = self.login_url();
+        let field = self.redirect_field_name();
+        let redirect_url = format!("{login_url}?{field}={current_path}");
+        HttpResponseRedirect::new(&redirect_url)
+    }
 
-        if is_authenticated {
+    /// Checks whether the request is from an authenticated user.
+    fn check_login(&self, request: &HttpRequest) -> Option<HttpResponse> {
+        if self.is_authenticated(request) {
             None
         } else {
-            let current_path = request.get_full_path();
-            let login_url = self.login_url();
-            let field = self.redirect_field_name();
-            let redirect_url = format!("{login_url}?{field}={current_path}");
-            Some(HttpResponseRedirect::new(&redirect_url))
+            Some(self.handle_no_permission(request))
         }
     }
 }
 
 /// Trait for class-based views that require specific permissions.
 ///
+/// The user must be authenticated and hold every permission returned by
+/// [`permissions_required`](Self::permissions_required); superusers hold all
+/// permissions. Anonymous users are handled as in [`LoginRequiredMixin`],
+/// authenticated users without the permissions get a 403. Wire it into
+/// dispatch by overriding `View::check_access` to call
+/// [`check_permission`](Self::check_permission).
+///
 /// This mirrors Django's `PermissionRequiredMixin`.
 pub trait PermissionRequiredMixin: LoginRequiredMixin {
     /// Returns the required permission string.
-    fn permission_required(&self) -> &str;
-
-    /// Checks whether the request's user has the required permission.
-    fn check_permission(&self, request: &HttpRequest) -> Option<HttpResponse> {
-        // First check login
-        if let Some(response) = self.check_login(request) {
-            return Some(response);
-        }
+    ///
+    /// Override this for a single permission, or
+    /// [`permissions_required`](Self::permissions_required) for several.
+    fn permission_required(&self) -> &str {
+        ""
+    }
 
+    /// Returns all permissions the user must hold.
+    ///
+    /// Defaults to [`permission_required`](Self::permission_required).
+    fn permissions_required(&self) -> Vec<&str> {
         let perm = self.permission_required();
-        let has_permission = request
-            .meta()
-            .get("USER_PERMISSIONS")
-            .is_some_and(|perms| perms.split(',').any(|p| p.trim() == perm));
+        if perm.is_empty() {
+            Vec::new()
+        } else {
+            vec![perm]
+        }
+    }
 
+    /// Returns whether the request's user holds all required permissions.
+    fn has_permission(&self, request: &HttpRequest) -> bool {
         let is_superuser = request
             .meta()
             .get("USER_IS_SUPERUSER")
             .is_some_and(|v| v == "true");
+        if is_superuser {
+            return true;
+        }
+        let granted: Vec<&str> = request
+            .meta()
+            .get("USER_PERMISSIONS")
+            .map(|perms| perms.split(',').map(str::trim).collect())
+            .unwrap_or_default();
+        self.permissions_required()
+            .iter()
+            .all(|perm| granted.contains(perm))
+    }
 
-        if has_permission || is_superuser {
+    /// Checks whether the request's user has the required permissions.
+    ///
+    /// A view that names no permission is misconfigured and always answers
+    /// with a 500, rather than silently letting everyone in.
+    fn check_permission(&self, request: &HttpRequest) -> Option<HttpResponse> {
+        // First check login
+        if let Some(response) = self.check_login(request) {
+            return Some(response);
+        }
+
+        if self.permissions_required().is_empty() {
+            return Some(HttpResponse::server_error(
+                "PermissionRequiredMixin is missing permission_required",
+            ));
+        }
+
+        if self.has_permission(request) {
             None
         } else {
-            Some(HttpResponse::forbidden("Permission denied"))
+            Some(self.handle_no_permission(request))
         }
     }
 }
@@ -660,6 +799,30 @@ mod tests {
         assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
     }
 
+    #[test]
+    fn test_perm_mixin_superuser() {
+        let view = TestPermView;
+        let request = HttpRequest::builder()
+            .meta("USER_AUTHENTICATED", "true")
+            .meta("USER_IS_SUPERUSER", "true")
+            .build();
+        assert!(view.check_permission(&request).is_none());
+    }
+
+    struct UnconfiguredPermView;
+    impl LoginRequiredMixin for UnconfiguredPermView {}
+    impl PermissionRequiredMixin for UnconfiguredPermView {}
+
+    #[test]
+    fn test_perm_mixin_without_permission_is_misconfigured() {
+        let view = UnconfiguredPermView;
+        let request = HttpRequest::builder()
+            .meta("USER_AUTHENTICATED", "true")
+            .build();
+        let response = view.check_permission(&request).unwrap();
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[test]
     fn test_perm_mixin_unauthenticated() {
         let view = TestPermView;
@@ -667,4 +830,26 @@ mod tests {
         let response = view.check_permission(&request).unwrap();
         assert_eq!(response.status(), http::StatusCode::FOUND);
     }
+
+    #[tokio::test]
+    async fn test_with_timeout_fast_view() {
+        let view = with_timeout(Duration::from_secs(5), make_view());
+        let request = HttpRequest::builder().build();
+        let response = view(request).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_slow_view() {
+        let slow: ViewFunction = Box::new(|_req| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                HttpResponse::ok("too late")
+            })
+        });
+        let view = with_timeout(Duration::from_millis(20), slow);
+        let request = HttpRequest::builder().path("/slow/").build();
+        let response = view(request).await;
+        assert_eq!(response.status(), http::StatusCode::GATEWAY_TIMEOUT);
+    }
 }
@@ -29,18 +29,26 @@ use crate::pagination::Paginator;
 
 /// Renders a template with the given name and serde_json context using the engine.
 ///
-/// If no engine is provided, falls back to a JSON representation.
+/// With a request, the engine's context processors add their variables. If no
+/// engine is provided, falls back to a JSON representation.
 fn render_with_engine(
     template_name: &str,
     context: &HashMap<String, serde_json::Value>,
     engine: Option<&Engine>,
+    request: Option<&HttpRequest>,
 ) -> HttpResponse {
     if let Some(engine) = engine {
         let mut template_context = Context::new();
         for (key, value) in context {
             template_context.set(key.clone(), ContextValue::from(value.clone()));
         }
-        match engine.render_to_string(template_name, &mut template_context) {
+        let rendered = match request {
+            Some(request) => {
+                engine.render_for_request(template_name, &mut template_context, request)
+            }
+            None => engine.render_to_string(template_name, &mut template_context),
+        };
+        match rendered {
             Ok(html) => {
                 let mut response = HttpResponse::ok(html);
                 response.set_content_type("text/html");
@@ -151,7 +159,7 @@ pub trait ListView: View + ContextMixin + Send + Sync {
                 }
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching objects: {e}")),
         }
@@ -199,18 +207,14 @@ pub trait DetailView: View + ContextMixin + Send + Sync {
     ) -> Result<serde_json::Value, DjangoError>;
 
     /// Handles GET requests for the detail view.
-    async fn detail(
-        &self,
-        _request: HttpRequest,
-        kwargs: &HashMap<String, String>,
-    ) -> HttpResponse {
+    async fn detail(&self, request: HttpRequest, kwargs: &HashMap<String, String>) -> HttpResponse {
         match self.get_object(kwargs).await {
             Ok(object) => {
                 let mut context = self.get_context_data(kwargs);
                 context.insert("object".to_string(), object);
 
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), Some(&request))
             }
             Err(DjangoError::NotFound(msg) | DjangoError::DoesNotExist(msg)) => {
                 HttpResponse::not_found(msg)
@@ -260,7 +264,7 @@ pub trait CreateView: View + ContextMixin + Send + Sync {
     async fn render_form(&self) -> HttpResponse {
         let context = self.get_context_data(&HashMap::new());
         let template = self.template_name();
-        render_with_engine(&template, &context, self.engine())
+        render_with_engine(&template, &context, self.engine(), None)
     }
 
     /// Renders the form with errors after a failed POST.
@@ -269,7 +273,7 @@ pub trait CreateView: View + ContextMixin + Send + Sync {
         let errors_json: serde_json::Value = serde_json::to_value(&errors).unwrap_or_default();
         context.insert("errors".to_string(), errors_json);
         let template = self.template_name();
-        render_with_engine(&template, &context, self.engine())
+        render_with_engine(&template, &context, self.engine(), None)
     }
 }
 
@@ -326,7 +330,7 @@ pub trait UpdateView: View + ContextMixin + Send + Sync {
                 let mut context = self.get_context_data(kwargs);
                 context.insert("object".to_string(), object);
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), None)
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching object: {e}")),
         }
@@ -342,7 +346,7 @@ pub trait UpdateView: View + ContextMixin + Send + Sync {
         let errors_json: serde_json::Value = serde_json::to_value(&errors).unwrap_or_default();
         context.insert("errors".to_string(), errors_json);
         let template = self.template_name();
-        render_with_engine(&template, &context, self.engine())
+        render_with_engine(&template, &context, self.engine(), None)
     }
 }
 
@@ -401,7 +405,7 @@ pub trait DeleteView: View + ContextMixin + Send + Sync {
                 let mut context = self.get_context_data(kwargs);
                 context.insert("object".to_string(), object);
                 let template = self.template_name();
-                render_with_engine(&template, &context, self.engine())
+                render_with_engine(&template, &context, self.engine(), None)
             }
             Err(e) => HttpResponse::server_error(format!("Error fetching object: {e}")),
         }
@@ -999,4 +1003,122 @@ mod tests {
         // is_paginated should be false
         assert!(body.contains("is_paginated"));
     }
+
+    // ── Access mixins in dispatch ───────────────────────────────────
+
+    use crate::views::function::{LoginRequiredMixin, PermissionRequiredMixin};
+
+    struct ProtectedListView {
+        raise_exception: bool,
+    }
+
+    impl ContextMixin for ProtectedListView {
+        fn get_context_data(
+            &self,
+            _kwargs: &HashMap<String, String>,
+        ) -> HashMap<String, serde_json::Value> {
+            HashMap::new()
+        }
+    }
+
+    impl LoginRequiredMixin for ProtectedListView {
+        fn login_url(&self) -> &str {
+            "/staff/login/"
+        }
+
+        fn raise_exception(&self) -> bool {
+            self.raise_exception
+        }
+    }
+
+    impl PermissionRequiredMixin for ProtectedListView {
+        fn permissions_required(&self) -> Vec<&str> {
+            vec!["blog.view_article", "blog.change_article"]
+        }
+    }
+
+    #[async_trait]
+    impl View for ProtectedListView {
+        fn check_access(&self, request: &HttpRequest) -> Option<HttpResponse> {
+            self.check_permission(request)
+        }
+
+        async fn get(&self, request: HttpRequest) -> HttpResponse {
+            self.list(request).await
+        }
+    }
+
+    #[async_trait]
+    impl ListView for ProtectedListView {
+        fn model_name(&self) -> &str {
+            "article"
+        }
+
+        async fn get_queryset(&self) -> Result<Vec<serde_json::Value>, DjangoError> {
+            Ok(vec![serde_json::json!({"title": "Secret"})])
+        }
+    }
+
+    fn protected_request(permissions: Option<&str>) -> HttpRequest {
+        let mut builder = HttpRequest::builder()
+            .method(http::Method::GET)
+            .path("/articles/");
+        if let Some(permissions) = permissions {
+            builder = builder
+                .meta("USER_AUTHENTICATED", "true")
+                .meta("USER_PERMISSIONS", permissions);
+        }
+        builder.build()
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_redirects_anonymous_to_view_login_url() {
+        let view = ProtectedListView {
+            raise_exception: false,
+        };
+        let response = view.dispatch(protected_request(None)).await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+        let location = response
+            .headers()
+            .get(http::header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(location, "/staff/login/?next=/articles/");
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_raise_exception_forbids_anonymous() {
+        let view = ProtectedListView {
+            raise_exception: true,
+        };
+        let response = view.dispatch(protected_request(None)).await;
+        assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_forbids_user_missing_a_permission() {
+        let view = ProtectedListView {
+            raise_exception: false,
+        };
+        let response = view
+            .dispatch(protected_request(Some("blog.view_article")))
+            .await;
+        assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_allows_permitted_user() {
+        let handler = ProtectedListView {
+            raise_exception: false,
+        }
+        .as_view();
+        let response = handler(protected_request(Some(
+            "blog.view_article, blog.change_article",
+        )))
+        .await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        assert!(body.contains("Secret"));
+    }
 }
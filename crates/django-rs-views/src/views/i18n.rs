@@ -0,0 +1,386 @@
+//! Language switching, mirroring Django's `django.views.i18n.set_language`.
+//!
+//! [`SetLanguage`] handles a `POST` with a `language` field: if the language
+//! is one of the supported languages, it is stored in the language cookie
+//! (and in the session, if there is one), where
+//! [`LocaleMiddleware`](crate::middleware::builtin::LocaleMiddleware) picks
+//! it up on the next request. The user is then redirected to the `next`
+//! field, or back to the referring page. Redirect targets on other hosts are
+//! ignored.
+//!
+//! [`add_language_form_template`] installs a language switcher form at
+//! [`LANGUAGE_FORM_TEMPLATE_NAME`]. It posts to the view with a CSRF token
+//! and lists `LANGUAGES` from the `i18n` context processor.
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_core::Settings;
+//! use django_rs_http::urls::pattern::path;
+//! use django_rs_template::engine::Engine;
+//! use django_rs_views::views::i18n::{add_language_form_template, set_language};
+//!
+//! let settings = Settings::default();
+//! let pattern = path("i18n/setlang/", set_language(&settings), Some("set_language")).unwrap();
+//!
+//! let engine = Engine::new();
+//! add_language_form_template(&engine);
+//! // {% include "i18n/set_language_form.html" with set_language_url="/i18n/setlang/" %}
+//! ```
+
+use std::sync::Arc;
+
+use django_rs_core::Settings;
+use django_rs_http::cookies::{Cookie, SameSite};
+use django_rs_http::urls::pattern::RouteHandler;
+use django_rs_http::{HttpRequest, HttpResponse, HttpResponseRedirect};
+use django_rs_template::engine::Engine;
+
+use crate::middleware::builtin::LANGUAGE_SESSION_KEY;
+use crate::session::{SessionData, SharedSession};
+
+/// The name [`add_language_form_template`] installs the switcher form under.
+pub const LANGUAGE_FORM_TEMPLATE_NAME: &str = "i18n/set_language_form.html";
+
+/// The source of the language switcher form.
+///
+/// It posts to `set_language_url` (`/i18n/setlang/` by default), sends
+/// `redirect_to` as `next` when set, and preselects `LANGUAGE_CODE`.
+pub const LANGUAGE_FORM_TEMPLATE: &str = r#"<form action="{{ set_language_url|default:"/i18n/setlang/" }}" method="post">{% csrf_token %}
+{% if redirect_to %}<input name="next" type="hidden" value="{{ redirect_to }}">
+{% endif %}<select name="language">
+{% for code, name in LANGUAGES %}<option value="{{ code }}"{% if code == LANGUAGE_CODE %} selected{% endif %}>{{ name }}</option>
+{% endfor %}</select>
+<button type="submit">Go</button>
+</form>
+"#;
+
+/// Installs the language switcher form in `engine` as
+/// [`LANGUAGE_FORM_TEMPLATE_NAME`].
+///
+/// A template of the same name in a template directory takes precedence
+/// only if added to the engine afterwards.
+pub fn add_language_form_template(engine: &Engine) {
+    engine.add_string_template(LANGUAGE_FORM_TEMPLATE_NAME, LANGUAGE_FORM_TEMPLATE);
+}
+
+/// The view that changes the user's language.
+#[derive(Debug, Clone)]
+pub struct SetLanguage {
+    languages: Vec<String>,
+    cookie_name: String,
+    cookie_age: Option<u64>,
+}
+
+impl SetLanguage {
+    /// Creates a view accepting the given language codes.
+    pub fn new(languages: Vec<&str>) -> Self {
+        Self {
+            languages: languages.into_iter().map(String::from).collect(),
+            cookie_name: "django_language".to_string(),
+            cookie_age: None,
+        }
+    }
+
+    /// Creates a view for the `LANGUAGES`, `LANGUAGE_COOKIE_NAME` and
+    /// `LANGUAGE_COOKIE_AGE` settings.
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            languages: settings
+                .languages
+                .iter()
+                .map(|(code, _)| code.clone())
+                .collect(),
+            cookie_name: settings.language_cookie_name.clone(),
+            cookie_age: settings.language_cookie_age,
+        }
+    }
+
+    /// Sets the name of the language cookie.
+    #[must_use]
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Sets the max age of the language cookie, in seconds. By default the
+    /// cookie lasts until the browser is closed.
+    #[must_use]
+    pub const fn cookie_age(mut self, seconds: u64) -> Self {
+        self.cookie_age = Some(seconds);
+        self
+    }
+
+    /// Returns the supported language matching `code`, if any.
+    ///
+    /// A regional variant falls back to its generic language, so `fr-ca`
+    /// matches `fr` when only `fr` is supported.
+    pub fn supported_language(&self, code: &str) -> Option<&str> {
+        let find = |code: &str| {
+            self.languages
+                .iter()
+                .find(|language| language.eq_ignore_ascii_case(code))
+                .map(String::as_str)
+        };
+        find(code).or_else(|| find(code.split(['-', '_']).next()?))
+    }
+
+    /// Converts the view into a handler for a URL pattern.
+    pub fn handler(self) -> RouteHandler {
+        let view = Arc::new(self);
+        Arc::new(move |request: HttpRequest| {
+            let view = view.clone();
+            Box::pin(async move { view.set_language(request) })
+        })
+    }
+
+    /// Sets the language posted in `request` and redirects.
+    ///
+    /// Only `POST` is allowed. An unsupported language is ignored. Without a
+    /// safe `next` or `Referer` to redirect to, requests that do not accept
+    /// HTML get `204 No Content` and others are redirected to `/`.
+    pub fn set_language(&self, mut request: HttpRequest) -> HttpResponse {
+        if request.method() != http::Method::POST {
+            return HttpResponse::not_allowed(&["POST"]);
+        }
+        let (language, next) = match request.data() {
+            Ok(data) => (data.get("language"), data.get("next")),
+            Err(_) => (None, None),
+        };
+        let next = next
+            .or_else(|| request.get().get("next").map(String::from))
+            .filter(|next| is_safe_redirect(next, &request));
+        let referer = request
+            .headers()
+            .get(http::header::REFERER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|referer| is_safe_redirect(referer, &request))
+            .map(String::from);
+
+        let mut response = match next.or(referer) {
+            Some(url) => HttpResponseRedirect::new(&url),
+            None if accepts_html(&request) => HttpResponseRedirect::new("/"),
+            None => HttpResponse::new(http::StatusCode::NO_CONTENT, ""),
+        };
+
+        let Some(language) = language
+            .as_deref()
+            .and_then(|code| self.supported_language(code))
+        else {
+            return response;
+        };
+        let mut cookie = Cookie::new(&self.cookie_name, language).samesite(SameSite::Lax);
+        if let Some(age) = self.cookie_age {
+            cookie = cookie.max_age(age);
+        }
+        response.set_cookie(cookie);
+
+        if request.extensions().get::<SharedSession>().is_some()
+            || request.meta().contains_key("SESSION_KEY")
+        {
+            let mut session = SessionData::from_request(&request);
+            session.set(LANGUAGE_SESSION_KEY, serde_json::json!(language));
+            session.save_to_request(&mut request);
+        }
+        response
+    }
+}
+
+/// Creates the `set_language` view for `settings`.
+///
+/// This is [`SetLanguage::from_settings`] as a URL pattern handler.
+pub fn set_language(settings: &Settings) -> RouteHandler {
+    SetLanguage::from_settings(settings).handler()
+}
+
+/// Returns `true` if `url` is a path on this site, or an absolute URL for
+/// the request's host. Plain `http` is refused on a secure request.
+fn is_safe_redirect(url: &str, request: &HttpRequest) -> bool {
+    if url.is_empty() || url.chars().any(|c| c.is_control() || c == '\\') {
+        return false;
+    }
+    if url.starts_with('/') {
+        return !url.starts_with("//");
+    }
+    let rest = url.strip_prefix("https://").or_else(|| {
+        if request.is_secure() {
+            None
+        } else {
+            url.strip_prefix("http://")
+        }
+    });
+    rest.and_then(|rest| rest.split(['/', '?', '#']).next())
+        .is_some_and(|host| host.eq_ignore_ascii_case(request.get_host()))
+}
+
+/// Returns `true` unless the request's `Accept` header rules out HTML.
+fn accepts_html(request: &HttpRequest) -> bool {
+    request
+        .headers()
+        .get(http::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |accept| {
+            accept.contains("text/html") || accept.contains("*/*")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_template::context::Context;
+
+    fn post(body: &str) -> django_rs_http::request::HttpRequestBuilder {
+        HttpRequest::builder()
+            .method(http::Method::POST)
+            .path("/i18n/setlang/")
+            .meta("HTTP_HOST", "example.com")
+            .content_type("application/x-www-form-urlencoded")
+            .body(body.as_bytes().to_vec())
+    }
+
+    fn view() -> SetLanguage {
+        SetLanguage::new(vec!["en", "fr", "pt-br"]).cookie_age(3600)
+    }
+
+    fn location(response: &HttpResponse) -> &str {
+        response.headers()["location"].to_str().unwrap()
+    }
+
+    fn set_cookie(response: &HttpResponse) -> Option<&str> {
+        response
+            .headers()
+            .get("set-cookie")
+            .map(|v| v.to_str().unwrap())
+    }
+
+    #[test]
+    fn test_sets_cookie_and_redirects_to_next() {
+        let response = view().set_language(post("language=fr&next=/blog/").build());
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+        assert_eq!(location(&response), "/blog/");
+        let cookie = set_cookie(&response).unwrap();
+        assert!(cookie.starts_with("django_language=fr"));
+        assert!(cookie.contains("Max-Age=3600"));
+        assert!(cookie.contains("SameSite=Lax"));
+    }
+
+    #[test]
+    fn test_regional_variant_falls_back_to_generic_language() {
+        let view = view();
+        assert_eq!(view.supported_language("FR-ca"), Some("fr"));
+        assert_eq!(view.supported_language("pt-BR"), Some("pt-br"));
+        assert_eq!(view.supported_language("pt"), None);
+        assert_eq!(view.supported_language("de"), None);
+    }
+
+    #[test]
+    fn test_unsupported_language_is_ignored() {
+        let response = view().set_language(post("language=de&next=/blog/").build());
+        assert_eq!(location(&response), "/blog/");
+        assert!(set_cookie(&response).is_none());
+    }
+
+    #[test]
+    fn test_redirects_only_to_this_site() {
+        let response = view().set_language(
+            post("language=fr&next=https://evil.com/")
+                .header("referer", "http://example.com/about/")
+                .build(),
+        );
+        assert_eq!(location(&response), "http://example.com/about/");
+
+        let response = view().set_language(
+            post("language=fr&next=//evil.com/")
+                .header("referer", "https://example.com@evil.com/")
+                .build(),
+        );
+        assert_eq!(location(&response), "/");
+
+        let response = view().set_language(
+            post("language=fr")
+                .header("accept", "application/json")
+                .build(),
+        );
+        assert_eq!(response.status(), http::StatusCode::NO_CONTENT);
+        assert!(set_cookie(&response).is_some());
+    }
+
+    #[test]
+    fn test_requires_post() {
+        let request = HttpRequest::builder()
+            .path("/i18n/setlang/")
+            .query_string("language=fr")
+            .build();
+        let response = view().set_language(request);
+        assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn test_stores_language_in_session_through_pipeline() {
+        use crate::middleware::{MiddlewarePipeline, ViewHandler};
+        use crate::session::{InMemorySessionBackend, SessionMiddleware};
+
+        let view = Arc::new(view());
+        let handler: ViewHandler = Box::new(move |request| {
+            let view = Arc::clone(&view);
+            Box::pin(async move {
+                if request.method() == http::Method::POST {
+                    return view.set_language(request);
+                }
+                let session = SessionData::from_request(&request);
+                let language = session.get(LANGUAGE_SESSION_KEY).cloned();
+                HttpResponse::ok(language.unwrap_or_default().to_string())
+            })
+        });
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(SessionMiddleware::new(InMemorySessionBackend::new()));
+
+        let response = pipeline
+            .process(post("language=fr&next=/blog/").build(), &handler)
+            .await;
+        let cookies: Vec<&str> = response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        assert!(cookies.iter().any(|c| c.starts_with("django_language=fr")));
+        let session_cookie = cookies
+            .iter()
+            .find(|cookie| cookie.starts_with("sessionid="))
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap()
+            .to_string();
+
+        let request = HttpRequest::builder()
+            .header("cookie", &session_cookie)
+            .build();
+        let response = pipeline.process(request, &handler).await;
+        assert_eq!(response.content_bytes().unwrap(), b"\"fr\"".as_slice());
+    }
+
+    #[test]
+    fn test_language_form_template() {
+        let engine = Engine::new();
+        add_language_form_template(&engine);
+        let mut context = Context::new();
+        context.set("csrf_token", "tok123".into());
+        context.set("LANGUAGE_CODE", "fr".into());
+        context.set("redirect_to", "/blog/".into());
+        context.set(
+            "LANGUAGES",
+            serde_json::json!([["en", "English"], ["fr", "Français"]]).into(),
+        );
+
+        let html = engine
+            .render_to_string(LANGUAGE_FORM_TEMPLATE_NAME, &mut context)
+            .unwrap();
+        assert!(html.contains(r#"action="/i18n/setlang/""#));
+        assert!(html.contains(r#"name="csrfmiddlewaretoken" value="tok123""#));
+        assert!(html.contains(r#"<input name="next" type="hidden" value="/blog/">"#));
+        assert!(html.contains(r#"<option value="en">English</option>"#));
+        assert!(html.contains(r#"<option value="fr" selected>Français</option>"#));
+    }
+}
@@ -6,14 +6,20 @@
 //! - [`function`] - Function-based views and decorator patterns
 //! - [`class_based`] - The `View` trait, `TemplateView`, `RedirectView`
 //! - [`generic`] - Generic CRUD views (`ListView`, `DetailView`, `CreateView`, etc.)
+//! - [`i18n`] - Language switching (`django.views.i18n.set_language`)
 //! - [`form_view`] - Form-view integration helpers
 //! - [`archive`] - Date-based archive views (`ArchiveIndexView`, `YearArchiveView`, etc.)
+//! - [`static_serve`] - Development static file serving (`django.views.static.serve`)
+//! - [`wizard`] - Multi-step form wizards (`FormWizardView`)
 
 pub mod archive;
 pub mod class_based;
 pub mod form_view;
 pub mod function;
 pub mod generic;
+pub mod i18n;
+pub mod static_serve;
+pub mod wizard;
 
 pub use archive::{
     ArchiveIndexView, DateDetailView, DateMixin, DayArchiveView, MonthArchiveView,
@@ -22,10 +28,14 @@ pub use archive::{
 pub use class_based::{ContextMixin, RedirectView, TemplateResponseMixin, TemplateView, View};
 pub use form_view::{
     bind_form_from_request, cleaned_data_as_strings, extract_post_data, form_context_to_json,
-    form_errors, FormView,
+    form_errors, AccessCheck, FormView,
 };
 pub use function::{
     login_required, login_required_redirect, permission_required, require_get,
-    require_http_methods, require_post, LoginRequiredMixin, PermissionRequiredMixin, ViewFunction,
+    require_http_methods, require_post, with_timeout, LoginRequiredMixin, PermissionRequiredMixin,
+    ViewFunction,
 };
 pub use generic::{CreateView, DeleteView, DetailView, ListView, UpdateView};
+pub use i18n::{add_language_form_template, set_language, SetLanguage};
+pub use static_serve::{static_serve, StaticServe};
+pub use wizard::{FormWizardView, WizardStep};
@@ -0,0 +1,491 @@
+//! Static file serving for development.
+//!
+//! [`StaticServe`] serves the files under one or more directories at a URL
+//! prefix, mirroring Django's `django.views.static.serve`. Responses carry the
+//! content type inferred from the file extension, an `ETag` and a
+//! `Last-Modified` header, and conditional requests are answered with
+//! `304 Not Modified`. Request paths are resolved component by component and
+//! checked against the served directory, so `..` segments, absolute paths and
+//! symlinks cannot reach files outside it.
+//!
+//! Like Django's view, this is meant for development only: files are read
+//! whole on every request. In `DEBUG`, [`DjangoApp`](crate::server::DjangoApp)
+//! serves `STATIC_URL` from `STATICFILES_DIRS` and `STATIC_ROOT` without any
+//! URL pattern, through [`StaticServe::from_settings`].
+//!
+//! # Examples
+//!
+//! ```
+//! use django_rs_http::urls::pattern::path;
+//! use django_rs_views::views::static_serve::{static_serve, StaticServe};
+//!
+//! let handler = static_serve("/media/", "uploads");
+//! let pattern = path("media/<path:path>", handler, Some("media")).unwrap();
+//!
+//! let with_indexes = StaticServe::new("/docs/", "target/doc")
+//!     .show_indexes(true)
+//!     .handler();
+//! ```
+
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use django_rs_core::Settings;
+use django_rs_http::response::mime_from_extension;
+use django_rs_http::urls::pattern::RouteHandler;
+use django_rs_http::{HttpRequest, HttpResponse};
+use django_rs_template::context::escape_html;
+
+/// Serves the files under a directory at a URL prefix.
+#[derive(Debug, Clone)]
+pub struct StaticServe {
+    url_prefix: String,
+    directories: Vec<PathBuf>,
+    show_indexes: bool,
+}
+
+impl StaticServe {
+    /// Creates a server for the files under `directory`, requested at paths
+    /// starting with `url_prefix`.
+    pub fn new(url_prefix: impl Into<String>, directory: impl Into<PathBuf>) -> Self {
+        Self {
+            url_prefix: url_prefix.into(),
+            directories: vec![directory.into()],
+            show_indexes: false,
+        }
+    }
+
+    /// Adds a directory searched after the previous ones, like an extra
+    /// entry of `STATICFILES_DIRS`.
+    #[must_use]
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directories.push(directory.into());
+        self
+    }
+
+    /// Lists the contents of directories instead of answering
+    /// `404 Not Found`.
+    #[must_use]
+    pub const fn show_indexes(mut self, show_indexes: bool) -> Self {
+        self.show_indexes = show_indexes;
+        self
+    }
+
+    /// Returns the server for `STATIC_URL` in debug mode.
+    ///
+    /// The directories are `STATICFILES_DIRS` followed by `STATIC_ROOT`.
+    /// Returns `None` when debug mode is off, when `STATIC_URL` is not a
+    /// local path (such as a CDN URL), or when there is nothing to serve.
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        if !settings.debug || !settings.static_url.starts_with('/') {
+            return None;
+        }
+        let mut directories = settings.staticfiles_dirs.clone();
+        directories.extend(settings.static_root.clone());
+        if directories.is_empty() {
+            return None;
+        }
+        Some(Self {
+            url_prefix: settings.static_url.clone(),
+            directories,
+            show_indexes: false,
+        })
+    }
+
+    /// Returns `true` if `path` is under this server's URL prefix.
+    pub fn matches(&self, path: &str) -> bool {
+        path.starts_with(&self.url_prefix)
+    }
+
+    /// Converts the server into a view handler for a URL pattern.
+    pub fn handler(self) -> RouteHandler {
+        let serve = Arc::new(self);
+        Arc::new(move |request: HttpRequest| {
+            let serve = serve.clone();
+            Box::pin(async move { serve.serve(&request).await })
+        })
+    }
+
+    /// Serves the file or directory `request` asks for.
+    ///
+    /// Only `GET` and `HEAD` are allowed. Paths outside the URL prefix or the
+    /// served directories answer `404 Not Found`.
+    pub async fn serve(&self, request: &HttpRequest) -> HttpResponse {
+        if request.method() != http::Method::GET && request.method() != http::Method::HEAD {
+            return HttpResponse::not_allowed(&["GET", "HEAD"]);
+        }
+        let Some(relative) = request
+            .path()
+            .strip_prefix(&self.url_prefix)
+            .and_then(decode_path)
+        else {
+            return HttpResponse::not_found("Not Found");
+        };
+
+        for directory in &self.directories {
+            let Some(full_path) = safe_join(directory, &relative).await else {
+                continue;
+            };
+            let Ok(metadata) = tokio::fs::metadata(&full_path).await else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if !self.show_indexes {
+                    return HttpResponse::not_found("Directory indexes are not allowed here.");
+                }
+                if !request.path().ends_with('/') {
+                    return HttpResponse::redirect(&format!("{}/", request.path()));
+                }
+                return directory_index(request.path(), &full_path).await;
+            }
+            return serve_file(request, &full_path, &metadata).await;
+        }
+
+        HttpResponse::not_found(format!(
+            "\u{201c}{}\u{201d} does not exist",
+            escape_html(&relative.to_string_lossy())
+        ))
+    }
+}
+
+/// Creates a view serving the files under `directory` at `url_prefix`.
+///
+/// This is [`StaticServe::new`] without directory indexes.
+pub fn static_serve(url_prefix: impl Into<String>, directory: impl Into<PathBuf>) -> RouteHandler {
+    StaticServe::new(url_prefix, directory).handler()
+}
+
+/// Percent-decodes the path after the URL prefix and keeps its normal
+/// components, refusing any that could climb out of the served directory.
+fn decode_path(path: &str) -> Option<PathBuf> {
+    let decoded = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()?;
+    if decoded.contains('\0') || decoded.contains('\\') {
+        return None;
+    }
+    let mut relative = PathBuf::new();
+    for component in Path::new(decoded.as_ref()).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(relative)
+}
+
+/// Joins `relative` onto `directory`, returning `None` if the result does not
+/// exist or resolves outside `directory` through a symlink.
+async fn safe_join(directory: &Path, relative: &Path) -> Option<PathBuf> {
+    let root = tokio::fs::canonicalize(directory).await.ok()?;
+    let full_path = tokio::fs::canonicalize(root.join(relative)).await.ok()?;
+    full_path.starts_with(&root).then_some(full_path)
+}
+
+/// Builds the response for a file, or `304 Not Modified` if the client's
+/// copy is current.
+async fn serve_file(
+    request: &HttpRequest,
+    path: &Path,
+    metadata: &std::fs::Metadata,
+) -> HttpResponse {
+    let modified: Option<DateTime<Utc>> = metadata.modified().ok().map(DateTime::from);
+    let mtime = modified.map_or(0, |m| m.timestamp());
+    let etag = format!("W/\"{:x}-{:x}\"", metadata.len(), mtime);
+    let last_modified = modified.map(|m| m.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+
+    if is_not_modified(request, &etag, modified) {
+        let mut response = HttpResponse::new(http::StatusCode::NOT_MODIFIED, "");
+        set_cache_headers(&mut response, &etag, last_modified.as_deref());
+        return response;
+    }
+
+    let data = match tokio::fs::read(path).await {
+        Ok(data) => data,
+        Err(e) => return HttpResponse::server_error(format!("Failed to read file: {e}")),
+    };
+    let mut response = HttpResponse::with_bytes(http::StatusCode::OK, data);
+    response.set_content_type(
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map_or("application/octet-stream", mime_from_extension),
+    );
+    set_cache_headers(&mut response, &etag, last_modified.as_deref());
+    response
+}
+
+/// Returns `true` if `If-None-Match` lists `etag`, or, without
+/// `If-None-Match`, if `If-Modified-Since` is not before the modification
+/// time.
+pub(crate) fn is_not_modified(
+    request: &HttpRequest,
+    etag: &str,
+    modified: Option<DateTime<Utc>>,
+) -> bool {
+    let header = |name| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|v: &http::HeaderValue| v.to_str().ok())
+    };
+    if let Some(if_none_match) = header(http::header::IF_NONE_MATCH) {
+        let weak = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+        return if_none_match
+            .split(',')
+            .any(|tag| tag.trim() == "*" || weak(tag) == weak(etag));
+    }
+    match (header(http::header::IF_MODIFIED_SINCE), modified) {
+        (Some(since), Some(modified)) => DateTime::parse_from_rfc2822(since)
+            .is_ok_and(|since| modified.timestamp() <= since.timestamp()),
+        _ => false,
+    }
+}
+
+/// Sets the validators and asks browsers to revalidate on every use, so
+/// edited files show up on the next reload.
+pub(crate) fn set_cache_headers(
+    response: &mut HttpResponse,
+    etag: &str,
+    last_modified: Option<&str>,
+) {
+    let headers = response.headers_mut();
+    if let Ok(value) = http::HeaderValue::from_str(etag) {
+        headers.insert(http::header::ETAG, value);
+    }
+    if let Some(value) = last_modified.and_then(|v| http::HeaderValue::from_str(v).ok()) {
+        headers.insert(http::header::LAST_MODIFIED, value);
+    }
+    headers.insert(
+        http::header::CACHE_CONTROL,
+        http::HeaderValue::from_static("no-cache"),
+    );
+}
+
+/// Renders an HTML listing of `directory`, directories first.
+async fn directory_index(url_path: &str, directory: &Path) -> HttpResponse {
+    let mut entries = match tokio::fs::read_dir(directory).await {
+        Ok(entries) => entries,
+        Err(e) => return HttpResponse::server_error(format!("Failed to read directory: {e}")),
+    };
+    let mut names: Vec<(bool, String)> = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let is_dir = entry.file_type().await.is_ok_and(|t| t.is_dir());
+        names.push((!is_dir, name));
+    }
+    names.sort();
+
+    let title = escape_html(&format!("Index of {url_path}"));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n<ul>\n"
+    );
+    if url_path.trim_end_matches('/').contains('/') {
+        html.push_str("<li><a href=\"../\">../</a></li>\n");
+    }
+    for (is_file, name) in names {
+        let display = if is_file { name } else { format!("{name}/") };
+        let href = percent_encoding::utf8_percent_encode(&display, HREF_ENCODE_SET);
+        let _ = writeln!(
+            html,
+            "<li><a href=\"{href}\">{}</a></li>",
+            escape_html(&display)
+        );
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+    HttpResponse::ok(html)
+}
+
+/// Characters escaped in directory listing links; `/` is kept so directory
+/// links end in a slash.
+const HREF_ENCODE_SET: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'.')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'~');
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mtime_secs(path: &Path) -> i64 {
+        let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+        let secs = modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        i64::try_from(secs).unwrap()
+    }
+
+    fn site() -> (tempfile::TempDir, StaticServe) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("css")).unwrap();
+        std::fs::write(dir.path().join("css/site.css"), "body {}").unwrap();
+        std::fs::write(dir.path().join("app.js"), "run()").unwrap();
+        let serve = StaticServe::new("/static/", dir.path());
+        (dir, serve)
+    }
+
+    fn get(path: &str) -> HttpRequest {
+        HttpRequest::builder().path(path).build()
+    }
+
+    #[tokio::test]
+    async fn test_serves_file_with_content_type_and_validators() {
+        let (_dir, serve) = site();
+        let response = serve.serve(&get("/static/css/site.css")).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.content_type(), "text/css");
+        assert_eq!(response.content_bytes().unwrap(), b"body {}");
+        assert!(response.headers().contains_key(http::header::ETAG));
+        assert!(response.headers().contains_key(http::header::LAST_MODIFIED));
+    }
+
+    #[tokio::test]
+    async fn test_if_none_match_returns_not_modified() {
+        let (_dir, serve) = site();
+        let response = serve.serve(&get("/static/app.js")).await;
+        let etag = response.headers()[http::header::ETAG].to_str().unwrap();
+
+        let request = HttpRequest::builder()
+            .path("/static/app.js")
+            .header("if-none-match", etag)
+            .build();
+        let response = serve.serve(&request).await;
+        assert_eq!(response.status(), http::StatusCode::NOT_MODIFIED);
+
+        let request = HttpRequest::builder()
+            .path("/static/app.js")
+            .header("if-none-match", "\"other\"")
+            .build();
+        assert_eq!(serve.serve(&request).await.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_if_modified_since() {
+        let (dir, serve) = site();
+        let mtime = mtime_secs(&dir.path().join("app.js"));
+        let date = |secs: i64| {
+            DateTime::from_timestamp(secs, 0)
+                .unwrap()
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string()
+        };
+
+        let request = HttpRequest::builder()
+            .path("/static/app.js")
+            .header("if-modified-since", &date(mtime))
+            .build();
+        assert_eq!(
+            serve.serve(&request).await.status(),
+            http::StatusCode::NOT_MODIFIED
+        );
+
+        let request = HttpRequest::builder()
+            .path("/static/app.js")
+            .header("if-modified-since", &date(mtime - 60))
+            .build();
+        assert_eq!(serve.serve(&request).await.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_path_traversal_is_refused() {
+        let outer = tempfile::tempdir().unwrap();
+        std::fs::write(outer.path().join("secret.txt"), "secret").unwrap();
+        let root = outer.path().join("public");
+        std::fs::create_dir(&root).unwrap();
+        let serve = StaticServe::new("/static/", &root);
+
+        for path in [
+            "/static/../secret.txt",
+            "/static/%2e%2e/secret.txt",
+            "/static/%2e%2e%2fsecret.txt",
+            "/static/..%5csecret.txt",
+            "/static//etc/passwd",
+        ] {
+            let response = serve.serve(&get(path)).await;
+            assert_eq!(response.status(), http::StatusCode::NOT_FOUND, "{path}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_out_of_directory_is_refused() {
+        let outer = tempfile::tempdir().unwrap();
+        std::fs::write(outer.path().join("secret.txt"), "secret").unwrap();
+        let root = outer.path().join("public");
+        std::fs::create_dir(&root).unwrap();
+        std::os::unix::fs::symlink(outer.path().join("secret.txt"), root.join("link.txt")).unwrap();
+
+        let serve = StaticServe::new("/static/", &root);
+        let response = serve.serve(&get("/static/link.txt")).await;
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_directory_index() {
+        let (_dir, serve) = site();
+        let response = serve.serve(&get("/static/")).await;
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+
+        let serve = serve.show_indexes(true);
+        let response = serve.serve(&get("/static/")).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        let css = body.find("href=\"css/\"").unwrap();
+        let js = body.find("href=\"app.js\"").unwrap();
+        assert!(css < js);
+
+        let response = serve.serve(&get("/static/css")).await;
+        assert_eq!(response.status(), http::StatusCode::FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_later_directories_and_methods() {
+        let (_dir, serve) = site();
+        let extra = tempfile::tempdir().unwrap();
+        std::fs::write(extra.path().join("extra.txt"), "more").unwrap();
+        let serve = serve.directory(extra.path());
+
+        let response = serve.serve(&get("/static/extra.txt")).await;
+        assert_eq!(response.content_bytes().unwrap(), b"more");
+        assert_eq!(
+            serve.serve(&get("/static/missing.txt")).await.status(),
+            http::StatusCode::NOT_FOUND
+        );
+
+        let post = HttpRequest::builder()
+            .method(http::Method::POST)
+            .path("/static/app.js")
+            .build();
+        assert_eq!(
+            serve.serve(&post).await.status(),
+            http::StatusCode::METHOD_NOT_ALLOWED
+        );
+    }
+
+    #[test]
+    fn test_from_settings() {
+        let mut settings = Settings {
+            staticfiles_dirs: vec![PathBuf::from("assets")],
+            ..Settings::default()
+        };
+        let serve = StaticServe::from_settings(&settings).unwrap();
+        assert!(serve.matches("/static/app.js"));
+        assert!(!serve.matches("/media/app.js"));
+
+        settings.static_url = "https://cdn.example.com/".to_string();
+        assert!(StaticServe::from_settings(&settings).is_none());
+
+        settings.static_url = "/static/".to_string();
+        settings.debug = false;
+        assert!(StaticServe::from_settings(&settings).is_none());
+    }
+}
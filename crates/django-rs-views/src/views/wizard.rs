@@ -0,0 +1,812 @@
+//! Multi-step form wizards for django-rs.
+//!
+//! [`FormWizardView`] splits a long form into a sequence of steps, each backed
+//! by its own [`BaseForm`], mirroring django-formtools' `SessionWizardView`.
+//! The submitted data of every step is kept in the session, so users can go
+//! back to an earlier step without losing what they entered. Steps may be
+//! skipped based on the answers to earlier ones, and once the last step is
+//! valid the [`done`](FormWizardView::done) handler receives the cleaned data
+//! of all steps.
+//!
+//! ## Requests
+//!
+//! - **GET / HEAD** - Discards any stored progress and renders the first step
+//! - **POST** - Validates the current step and moves to the next one, or calls
+//!   the done handler after the last step once the stored data of every step
+//!   validates again. A step the user has not reached yet cannot be posted.
+//! - **POST with `wizard_goto_step`** - Keeps the current step's data without
+//!   validating it and renders the named earlier step
+//!
+//! `SessionMiddleware` must run before the wizard for progress to survive
+//! between requests.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use django_rs_db::value::Value;
+use django_rs_forms::form::{BaseForm, Form};
+use django_rs_http::{HttpRequest, HttpResponse, QueryDict};
+use django_rs_template::context::{Context, ContextValue};
+use django_rs_template::engine::Engine;
+use serde::{Deserialize, Serialize};
+
+use super::form_view::{extract_post_data, form_context_to_json, AccessCheck, FormFactory};
+use crate::session::SessionData;
+
+/// The POST field naming the step a form was rendered for.
+pub const CURRENT_STEP_FIELD: &str = "wizard_current_step";
+
+/// The POST field requesting a jump to an earlier step.
+pub const GOTO_STEP_FIELD: &str = "wizard_goto_step";
+
+/// The cleaned data of a wizard's completed steps, keyed by step name and
+/// then by field name.
+pub type WizardData = HashMap<String, HashMap<String, Value>>;
+
+/// Decides from the cleaned data of earlier steps whether a step is shown.
+pub type StepCondition = Arc<dyn Fn(&WizardData) -> bool + Send + Sync>;
+
+/// Handles a completed wizard, receiving the cleaned data of every shown step.
+pub type WizardDoneHandler =
+    Arc<dyn Fn(WizardData) -> Pin<Box<dyn Future<Output = HttpResponse> + Send>> + Send + Sync>;
+
+/// One step of a [`FormWizardView`].
+pub struct WizardStep {
+    name: String,
+    form_factory: FormFactory,
+    template_name: Option<String>,
+    condition: Option<StepCondition>,
+}
+
+impl WizardStep {
+    /// Creates a step whose form is built by `form_factory`.
+    pub fn new(name: &str, form_factory: FormFactory) -> Self {
+        Self {
+            name: name.to_string(),
+            form_factory,
+            template_name: None,
+            condition: None,
+        }
+    }
+
+    /// Renders this step with its own template instead of the wizard's.
+    #[must_use]
+    pub fn template_name(mut self, template_name: &str) -> Self {
+        self.template_name = Some(template_name.to_string());
+        self
+    }
+
+    /// Shows this step only while `condition` holds for the cleaned data of
+    /// the earlier steps.
+    #[must_use]
+    pub fn condition(mut self, condition: StepCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Returns the step name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The progress of one wizard, as stored in the session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WizardState {
+    current_step: Option<String>,
+    /// The URL-encoded data last submitted for each step.
+    step_data: HashMap<String, String>,
+    cleaned_data: WizardData,
+}
+
+/// A view that collects one form over several steps.
+///
+/// Mirrors django-formtools' `SessionWizardView`. Each wizard keeps its
+/// progress under its own session key, derived from the name given to
+/// [`new`](Self::new).
+///
+/// Templates receive the step's `form` and `errors` as [`FormView`]
+/// templates do, plus `wizard.steps` with `current`, `index`, `step1`,
+/// `count`, `first`, `last`, `prev`, `next` and `all`. A step form must
+/// post back `wizard_current_step`; a button named `wizard_goto_step` with a
+/// step name as its value goes back to that step.
+///
+/// [`FormView`]: super::form_view::FormView
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use django_rs_db::value::Value;
+/// use django_rs_forms::fields::{FormFieldDef, FormFieldType};
+/// use django_rs_forms::form::BaseForm;
+/// use django_rs_http::HttpResponse;
+/// use django_rs_views::views::wizard::{FormWizardView, WizardStep};
+///
+/// let wizard = FormWizardView::new("signup", "signup/step.html")
+///     .step(WizardStep::new("account", Arc::new(|| BaseForm::new(vec![
+///         FormFieldDef::new("email", FormFieldType::Email),
+///     ]))))
+///     .step(
+///         WizardStep::new("company", Arc::new(|| BaseForm::new(vec![
+///             FormFieldDef::new("company", FormFieldType::Char {
+///                 min_length: None, max_length: Some(100), strip: true,
+///             }),
+///         ])))
+///         .template_name("signup/company.html")
+///         .condition(Arc::new(|data| {
+///             data.get("account")
+///                 .and_then(|account| account.get("email"))
+///                 .is_some_and(|email| {
+///                     matches!(email, Value::String(e) if !e.ends_with("@gmail.com"))
+///                 })
+///         })),
+///     )
+///     .done(Arc::new(|_data| Box::pin(async { HttpResponse::redirect("/welcome/") })));
+///
+/// assert_eq!(wizard.step_names(), vec!["account", "company"]);
+/// ```
+pub struct FormWizardView {
+    name: String,
+    template_name: String,
+    steps: Vec<WizardStep>,
+    engine: Option<Arc<Engine>>,
+    done: Option<WizardDoneHandler>,
+    access_check: Option<AccessCheck>,
+}
+
+impl FormWizardView {
+    /// Creates a wizard with no steps, rendered with `template_name` unless a
+    /// step has its own template.
+    pub fn new(name: &str, template_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            template_name: template_name.to_string(),
+            steps: Vec::new(),
+            engine: None,
+            done: None,
+            access_check: None,
+        }
+    }
+
+    /// Appends a step.
+    #[must_use]
+    pub fn step(mut self, step: WizardStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Sets the template engine for rendering.
+    #[must_use]
+    pub fn engine(mut self, engine: Arc<Engine>) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Sets the handler called once the last step is valid.
+    #[must_use]
+    pub fn done(mut self, handler: WizardDoneHandler) -> Self {
+        self.done = Some(handler);
+        self
+    }
+
+    /// Sets the access check run before every request, so that a login
+    /// redirect or a 403 is returned instead of a step.
+    #[must_use]
+    pub fn access_check(mut self, check: AccessCheck) -> Self {
+        self.access_check = Some(check);
+        self
+    }
+
+    /// Returns the names of all steps, including conditional ones.
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(WizardStep::name).collect()
+    }
+
+    /// Returns the session key under which this wizard's progress is stored.
+    pub fn session_key(&self) -> String {
+        format!("wizard_{}", self.name)
+    }
+
+    /// Dispatches the request to the appropriate handler.
+    ///
+    /// Takes the request mutably because the wizard's progress is written
+    /// back to the request's session. Runs the
+    /// [`access_check`](Self::access_check) first.
+    pub async fn dispatch(&self, request: &mut HttpRequest) -> HttpResponse {
+        if let Some(response) = self.access_check.as_ref().and_then(|check| check(request)) {
+            return response;
+        }
+        match *request.method() {
+            http::Method::GET | http::Method::HEAD => {
+                let mut session = SessionData::from_request(request);
+                self.save_state(&mut session, None, request);
+                match self.active_steps(&WizardData::new()).first() {
+                    Some(first) => self.render_step(first, &WizardState::default(), None, request),
+                    None => HttpResponse::server_error("FormWizardView has no steps"),
+                }
+            }
+            http::Method::POST => self.process_step(request).await,
+            _ => HttpResponse::not_allowed(&["GET", "POST"]),
+        }
+    }
+
+    /// Returns the steps shown for the given cleaned data, in order.
+    fn active_steps(&self, cleaned_data: &WizardData) -> Vec<&WizardStep> {
+        self.steps
+            .iter()
+            .filter(|step| {
+                step.condition
+                    .as_ref()
+                    .map_or(true, |condition| condition(cleaned_data))
+            })
+            .collect()
+    }
+
+    fn load_state(&self, session: &SessionData) -> WizardState {
+        session
+            .get_as::<WizardState>(&self.session_key())
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Stores `state` in the session, or clears it when `None`.
+    fn save_state(
+        &self,
+        session: &mut SessionData,
+        state: Option<&WizardState>,
+        request: &mut HttpRequest,
+    ) {
+        match state {
+            Some(state) => {
+                if let Err(e) = session.set_as(&self.session_key(), state) {
+                    tracing::warn!("Could not store wizard progress: {e}");
+                }
+            }
+            None => {
+                session.remove(&self.session_key());
+            }
+        }
+        session.save_to_request(request);
+    }
+
+    /// Validates the posted step and moves the wizard along.
+    async fn process_step(&self, request: &mut HttpRequest) -> HttpResponse {
+        let mut session = SessionData::from_request(request);
+        let mut state = self.load_state(&session);
+        let post = extract_post_data(request);
+
+        let active = self.active_steps(&state.cleaned_data);
+        let Some(first) = active.first() else {
+            return HttpResponse::server_error("FormWizardView has no steps");
+        };
+        // The posted step wins over the stored one, so that forms re-submitted
+        // after using the browser's back button land on the right step.
+        let current_name = post
+            .get(CURRENT_STEP_FIELD)
+            .or(state.current_step.as_deref())
+            .unwrap_or(&first.name)
+            .to_string();
+        let Some(index) = active.iter().position(|step| step.name == current_name) else {
+            // Stale or unknown step: start over.
+            self.save_state(&mut session, None, request);
+            return self.render_step(first, &WizardState::default(), None, request);
+        };
+        // A step the user has not reached yet cannot be submitted; show the
+        // step they are on instead.
+        let reached = active
+            .iter()
+            .rposition(|step| {
+                state.current_step.as_deref() == Some(step.name.as_str())
+                    || state.step_data.contains_key(&step.name)
+            })
+            .unwrap_or_default();
+        if index > reached {
+            return self.render_step(active[reached], &state, None, request);
+        }
+        let current = active[index];
+
+        if let Some(target) = post.get(GOTO_STEP_FIELD) {
+            if let Some(target) = active[..=index].iter().find(|step| step.name == target) {
+                state
+                    .step_data
+                    .insert(current.name.clone(), step_data(&post));
+                state.current_step = Some(target.name.clone());
+                self.save_state(&mut session, Some(&state), request);
+                return self.render_step(target, &state, None, request);
+            }
+        }
+
+        let mut form = (current.form_factory)();
+        form.bind(&post);
+        if !form.is_valid().await {
+            state.current_step = Some(current.name.clone());
+            self.save_state(&mut session, Some(&state), request);
+            return self.render_step(current, &state, Some(form), request);
+        }
+
+        state
+            .step_data
+            .insert(current.name.clone(), step_data(&post));
+        state
+            .cleaned_data
+            .insert(current.name.clone(), form.cleaned_data().clone());
+
+        // Conditions may depend on the data just submitted.
+        let active = self.active_steps(&state.cleaned_data);
+        let position = active
+            .iter()
+            .position(|step| step.name == current.name)
+            .unwrap_or(index);
+        if let Some(next) = active.get(position + 1) {
+            state.current_step = Some(next.name.clone());
+            self.save_state(&mut session, Some(&state), request);
+            return self.render_step(next, &state, None, request);
+        }
+
+        // The last step is valid. Validate the stored data of every step
+        // again, so the done handler only receives data that passed its form.
+        let mut data = WizardData::new();
+        for step in self.active_steps(&state.cleaned_data) {
+            let mut form = (step.form_factory)();
+            form.bind(&QueryDict::parse(
+                state.step_data.get(&step.name).map_or("", String::as_str),
+            ));
+            if !form.is_valid().await {
+                state.current_step = Some(step.name.clone());
+                self.save_state(&mut session, Some(&state), request);
+                return self.render_step(step, &state, Some(form), request);
+            }
+            data.insert(step.name.clone(), form.cleaned_data().clone());
+        }
+        self.save_state(&mut session, None, request);
+        match &self.done {
+            Some(done) => done(data).await,
+            None => HttpResponse::server_error("FormWizardView has no done handler"),
+        }
+    }
+
+    /// Renders a step, with its previously submitted data unless a freshly
+    /// validated `form` is given.
+    fn render_step(
+        &self,
+        step: &WizardStep,
+        state: &WizardState,
+        form: Option<BaseForm>,
+        request: &HttpRequest,
+    ) -> HttpResponse {
+        let form = form.unwrap_or_else(|| {
+            let mut form = (step.form_factory)();
+            if let Some(data) = state.step_data.get(&step.name) {
+                form.bind(&QueryDict::parse(data));
+            }
+            form
+        });
+
+        let mut context: HashMap<String, serde_json::Value> = HashMap::new();
+        context.insert(
+            "form".to_string(),
+            serde_json::to_value(form_context_to_json(&form.as_context())).unwrap_or_default(),
+        );
+        context.insert(
+            "errors".to_string(),
+            serde_json::to_value(form.errors()).unwrap_or_default(),
+        );
+        context.insert(
+            "wizard".to_string(),
+            serde_json::json!({
+                "name": self.name,
+                "steps": self.steps_context(step, &state.cleaned_data),
+            }),
+        );
+
+        let template_name = step.template_name.as_deref().unwrap_or(&self.template_name);
+        self.render_template(template_name, &context, request)
+    }
+
+    /// Builds the `wizard.steps` template variable.
+    fn steps_context(&self, step: &WizardStep, cleaned_data: &WizardData) -> serde_json::Value {
+        let active = self.active_steps(cleaned_data);
+        let index = active
+            .iter()
+            .position(|s| s.name == step.name)
+            .unwrap_or_default();
+        let name_at = |i: Option<usize>| {
+            i.and_then(|i| active.get(i))
+                .map(|s| serde_json::Value::String(s.name.clone()))
+                .unwrap_or_default()
+        };
+        serde_json::json!({
+            "current": step.name,
+            "index": index,
+            "step1": index + 1,
+            "count": active.len(),
+            "first": name_at(Some(0)),
+            "last": name_at(active.len().checked_sub(1)),
+            "prev": name_at(index.checked_sub(1)),
+            "next": name_at(Some(index + 1)),
+            "all": active.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Renders the template with the given context.
+    fn render_template(
+        &self,
+        template_name: &str,
+        context: &HashMap<String, serde_json::Value>,
+        request: &HttpRequest,
+    ) -> HttpResponse {
+        if let Some(ref engine) = self.engine {
+            let mut template_context = Context::new();
+            for (key, value) in context {
+                template_context.set(key.clone(), ContextValue::from(value.clone()));
+            }
+            match engine.render_for_request(template_name, &mut template_context, request) {
+                Ok(html) => {
+                    let mut response = HttpResponse::ok(html);
+                    response.set_content_type("text/html");
+                    response
+                }
+                Err(e) => HttpResponse::server_error(format!("Template error: {e}")),
+            }
+        } else {
+            // Fallback: JSON representation
+            let body = serde_json::to_string_pretty(context).unwrap_or_default();
+            let html = format!(
+                "<!-- Template: {template_name} -->\n<html><body><pre>{body}</pre></body></html>"
+            );
+            let mut response = HttpResponse::ok(html);
+            response.set_content_type("text/html");
+            response
+        }
+    }
+}
+
+/// Returns the posted data of a step without the wizard's own fields, for
+/// storing in the session.
+fn step_data(post: &QueryDict) -> String {
+    let mut data = QueryDict::new_mutable();
+    for key in post.keys() {
+        if matches!(
+            key.as_str(),
+            CURRENT_STEP_FIELD | GOTO_STEP_FIELD | "csrfmiddlewaretoken"
+        ) {
+            continue;
+        }
+        for value in post.get_list(key).into_iter().flatten() {
+            let _ = data.append(key, value);
+        }
+    }
+    data.urlencode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use django_rs_forms::fields::{FormFieldDef, FormFieldType};
+
+    fn char_field(name: &str) -> FormFieldDef {
+        FormFieldDef::new(
+            name,
+            FormFieldType::Char {
+                min_length: Some(1),
+                max_length: Some(100),
+                strip: true,
+            },
+        )
+    }
+
+    /// A wizard whose "company" step only shows for business accounts; the
+    /// done handler echoes the collected data as `step.field=value` lines.
+    fn make_wizard() -> FormWizardView {
+        FormWizardView::new("signup", "signup.html")
+            .step(WizardStep::new(
+                "account",
+                Arc::new(|| BaseForm::new(vec![char_field("name"), char_field("kind")])),
+            ))
+            .step(
+                WizardStep::new(
+                    "company",
+                    Arc::new(|| BaseForm::new(vec![char_field("company")])),
+                )
+                .template_name("signup_company.html")
+                .condition(Arc::new(|data| {
+                    data.get("account")
+                        .and_then(|account| account.get("kind"))
+                        .is_some_and(|kind| *kind == Value::from("business"))
+                })),
+            )
+            .step(WizardStep::new(
+                "confirm",
+                Arc::new(|| BaseForm::new(vec![char_field("agree")])),
+            ))
+            .done(Arc::new(|data| {
+                Box::pin(async move {
+                    let mut lines: Vec<String> = data
+                        .iter()
+                        .flat_map(|(step, fields)| {
+                            fields
+                                .iter()
+                                .map(move |(field, value)| format!("{step}.{field}={value}"))
+                        })
+                        .collect();
+                    lines.sort();
+                    HttpResponse::ok(lines.join("\n"))
+                })
+            }))
+    }
+
+    /// Sends a request carrying `session` and returns the response body and
+    /// the session afterwards.
+    async fn send(
+        wizard: &FormWizardView,
+        method: http::Method,
+        body: &str,
+        session: &str,
+    ) -> (HttpResponse, String, String) {
+        let mut request = HttpRequest::builder()
+            .method(method)
+            .content_type("application/x-www-form-urlencoded")
+            .body(body.as_bytes().to_vec())
+            .meta("SESSION_DATA", session)
+            .build();
+        let response = wizard.dispatch(&mut request).await;
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        let session = request.meta().get("SESSION_DATA").cloned().unwrap();
+        (response, body, session)
+    }
+
+    #[tokio::test]
+    async fn test_get_renders_first_step_and_resets_progress() {
+        let wizard = make_wizard();
+        let stale = serde_json::json!({
+            "wizard_signup": {"current_step": "confirm", "step_data": {}, "cleaned_data": {}},
+            "other": 1,
+        });
+        let (response, body, session) =
+            send(&wizard, http::Method::GET, "", &stale.to_string()).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(body.contains("<!-- Template: signup.html -->"));
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("\"count\": 2"));
+        let session: serde_json::Value = serde_json::from_str(&session).unwrap();
+        assert!(session.get("wizard_signup").is_none());
+        assert_eq!(session["other"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_step_is_re_rendered_with_errors() {
+        let wizard = make_wizard();
+        let (response, body, session) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=account&name=Ada",
+            "{}",
+        )
+        .await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("\"kind\": ["));
+        let session: serde_json::Value = serde_json::from_str(&session).unwrap();
+        assert_eq!(session["wizard_signup"]["current_step"], "account");
+    }
+
+    #[tokio::test]
+    async fn test_wizard_skips_conditional_step_and_calls_done() {
+        let wizard = make_wizard();
+        let (_, body, session) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=account&name=Ada&kind=personal",
+            "{}",
+        )
+        .await;
+        assert!(body.contains("\"current\": \"confirm\""));
+        assert!(body.contains("\"prev\": \"account\""));
+
+        let (response, body, session) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=confirm&agree=yes",
+            &session,
+        )
+        .await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            body,
+            "account.kind=personal\naccount.name=Ada\nconfirm.agree=yes"
+        );
+        let session: serde_json::Value = serde_json::from_str(&session).unwrap();
+        assert!(session.get("wizard_signup").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_conditional_step_uses_its_own_template() {
+        let wizard = make_wizard();
+        let (_, body, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=account&name=Ada&kind=business",
+            "{}",
+        )
+        .await;
+        assert!(body.contains("<!-- Template: signup_company.html -->"));
+        assert!(body.contains("\"current\": \"company\""));
+        assert!(body.contains("\"count\": 3"));
+    }
+
+    #[tokio::test]
+    async fn test_going_back_keeps_entered_data() {
+        let wizard = make_wizard();
+        let (_, _, session) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=account&name=Ada&kind=business",
+            "{}",
+        )
+        .await;
+        // Half-filled company step, then back to the first step.
+        let (_, body, session) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=company&company=Analytical&wizard_goto_step=account",
+            &session,
+        )
+        .await;
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("value=\\\"Ada\\\""));
+
+        // Re-submitting the first step shows the company step as left.
+        let (_, body, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=account&name=Ada&kind=business",
+            &session,
+        )
+        .await;
+        assert!(body.contains("\"current\": \"company\""));
+        assert!(body.contains("value=\\\"Analytical\\\""));
+        assert!(!body.contains("wizard_goto_step"));
+    }
+
+    #[tokio::test]
+    async fn test_cannot_jump_ahead() {
+        let wizard = make_wizard();
+        let (_, body, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=account&wizard_goto_step=confirm",
+            "{}",
+        )
+        .await;
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("\"name\": ["));
+    }
+
+    #[tokio::test]
+    async fn test_cannot_submit_unreached_step() {
+        let wizard = make_wizard();
+        let (response, body, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=confirm&agree=yes",
+            "{}",
+        )
+        .await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("\"errors\": {}"));
+    }
+
+    #[tokio::test]
+    async fn test_done_revalidates_stored_steps() {
+        let wizard = make_wizard();
+        // Progress claims the first step was completed with invalid data.
+        let session = serde_json::json!({
+            "wizard_signup": {
+                "current_step": "confirm",
+                "step_data": {"account": "name=Ada"},
+                "cleaned_data": {},
+            },
+        });
+        let (_, body, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=confirm&agree=yes",
+            &session.to_string(),
+        )
+        .await;
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("\"kind\": ["));
+    }
+
+    #[tokio::test]
+    async fn test_progress_survives_session_pipeline() {
+        use crate::middleware::{MiddlewarePipeline, ViewHandler};
+        use crate::session::{InMemorySessionBackend, SessionMiddleware};
+
+        let wizard = Arc::new(make_wizard());
+        let handler: ViewHandler = Box::new(move |mut request| {
+            let wizard = Arc::clone(&wizard);
+            Box::pin(async move { wizard.dispatch(&mut request).await })
+        });
+        let mut pipeline = MiddlewarePipeline::new();
+        pipeline.add(SessionMiddleware::new(InMemorySessionBackend::new()));
+        let post = |body: &str, cookie: Option<&str>| {
+            let mut builder = HttpRequest::builder()
+                .method(http::Method::POST)
+                .content_type("application/x-www-form-urlencoded")
+                .body(body.as_bytes().to_vec());
+            if let Some(cookie) = cookie {
+                builder = builder.header("cookie", cookie);
+            }
+            builder.build()
+        };
+
+        let response = pipeline
+            .process(
+                post("wizard_current_step=account&name=Ada&kind=personal", None),
+                &handler,
+            )
+            .await;
+        let cookie = response.headers()[http::header::SET_COOKIE]
+            .to_str()
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap()
+            .to_string();
+
+        let response = pipeline
+            .process(
+                post("wizard_current_step=confirm&agree=yes", Some(&cookie)),
+                &handler,
+            )
+            .await;
+        let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            "account.kind=personal\naccount.name=Ada\nconfirm.agree=yes"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_step_starts_over() {
+        let wizard = make_wizard();
+        let (_, body, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=company&company=Acme",
+            "{}",
+        )
+        .await;
+        assert!(body.contains("\"current\": \"account\""));
+        assert!(body.contains("\"errors\": {}"));
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed() {
+        let wizard = make_wizard();
+        let (response, _, _) = send(&wizard, http::Method::DELETE, "", "{}").await;
+        assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn test_missing_done_handler() {
+        let wizard = FormWizardView::new("one", "one.html").step(WizardStep::new(
+            "only",
+            Arc::new(|| BaseForm::new(vec![char_field("x")])),
+        ));
+        let (response, _, _) = send(
+            &wizard,
+            http::Method::POST,
+            "wizard_current_step=only&x=1",
+            "{}",
+        )
+        .await;
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
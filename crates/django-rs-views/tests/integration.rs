@@ -1233,7 +1233,7 @@ async fn test_detail_view_with_nested_object() {
 
 use django_rs_views::middleware::builtin::{
     AuthenticationMiddleware, CacheMiddleware, LocaleMiddleware, LoginRequiredMiddleware,
-    MessageLevel, MessageMiddleware,
+    MessageLevel, MessageMiddleware, MessageStore,
 };
 
 #[tokio::test]
@@ -1249,7 +1249,7 @@ async fn test_session_auth_pipeline_authenticated_user() {
 
     let mut pipeline = MiddlewarePipeline::new();
     pipeline.add(SessionMiddleware::new(backend));
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
 
     let handler: django_rs_views::middleware::ViewHandler = Box::new(|req| {
         Box::pin(async move {
@@ -1278,7 +1278,7 @@ async fn test_session_auth_pipeline_anonymous_user() {
 
     let mut pipeline = MiddlewarePipeline::new();
     pipeline.add(SessionMiddleware::new(backend));
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
 
     let handler: django_rs_views::middleware::ViewHandler = Box::new(|req| {
         Box::pin(async move {
@@ -1308,7 +1308,7 @@ async fn test_session_auth_messages_pipeline() {
 
     let mut pipeline = MiddlewarePipeline::new();
     pipeline.add(SessionMiddleware::new(backend));
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
     pipeline.add(MessageMiddleware);
 
     let handler: django_rs_views::middleware::ViewHandler = Box::new(|req| {
@@ -1318,7 +1318,7 @@ async fn test_session_auth_messages_pipeline() {
                 .get("USER_AUTHENTICATED")
                 .cloned()
                 .unwrap_or_default();
-            let has_store = req.meta().contains_key("_messages_store");
+            let has_store = req.extensions().get::<MessageStore>().is_some();
             HttpResponse::ok(&format!("auth={authed},msgs={has_store}"))
         })
     });
@@ -1339,7 +1339,7 @@ async fn test_login_required_blocks_anonymous() {
 
     let mut pipeline = MiddlewarePipeline::new();
     pipeline.add(SessionMiddleware::new(backend));
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
     pipeline.add(LoginRequiredMiddleware::default());
 
     let handler: django_rs_views::middleware::ViewHandler =
@@ -1367,7 +1367,7 @@ async fn test_login_required_allows_authenticated() {
 
     let mut pipeline = MiddlewarePipeline::new();
     pipeline.add(SessionMiddleware::new(backend));
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
     pipeline.add(LoginRequiredMiddleware::default());
 
     let handler: django_rs_views::middleware::ViewHandler =
@@ -1390,6 +1390,7 @@ async fn test_locale_middleware_in_pipeline() {
     pipeline.add(LocaleMiddleware {
         default_language: "en".to_string(),
         supported_languages: vec!["en".to_string(), "fr".to_string()],
+        ..Default::default()
     });
 
     let handler: django_rs_views::middleware::ViewHandler = Box::new(|req| {
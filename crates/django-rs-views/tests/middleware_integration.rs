@@ -14,7 +14,7 @@ use django_rs_core::DjangoError;
 use django_rs_http::{HttpRequest, HttpResponse};
 use django_rs_views::middleware::builtin::{
     AuthenticationMiddleware, CommonMiddleware, GZipMiddleware, LocaleMiddleware,
-    LoginRequiredMiddleware, MessageMiddleware, SecurityMiddleware,
+    LoginRequiredMiddleware, MessageMiddleware, MessageStore, SecurityMiddleware,
 };
 use django_rs_views::middleware::{Middleware, MiddlewarePipeline, ViewHandler};
 
@@ -211,7 +211,7 @@ async fn test_common_middleware_appends_trailing_slash() {
 async fn test_auth_middleware_sets_user_on_request() {
     let session_data = serde_json::json!({"_auth_user_id": "42"});
     let mut pipeline = MiddlewarePipeline::new();
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
 
     let handler: ViewHandler = Box::new(|req| {
         Box::pin(async move {
@@ -241,7 +241,7 @@ async fn test_auth_middleware_sets_user_on_request() {
 #[tokio::test]
 async fn test_login_required_blocks_anonymous() {
     let mut pipeline = MiddlewarePipeline::new();
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
     pipeline.add(LoginRequiredMiddleware::default());
 
     let request = HttpRequest::builder().path("/dashboard/").build();
@@ -266,7 +266,7 @@ async fn test_login_required_blocks_anonymous() {
 async fn test_login_required_allows_authenticated() {
     let session_data = serde_json::json!({"_auth_user_id": "1"});
     let mut pipeline = MiddlewarePipeline::new();
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
     pipeline.add(LoginRequiredMiddleware::default());
 
     let request = HttpRequest::builder()
@@ -289,9 +289,10 @@ async fn test_message_middleware_initializes_message_store() {
 
     let handler: ViewHandler = Box::new(|req| {
         Box::pin(async move {
-            let has_store = req.meta().contains_key("_messages_store");
-            let has_added = req.meta().contains_key("_messages_added");
-            HttpResponse::ok(&format!("store={has_store},added={has_added}"))
+            let store = req.extensions().get::<MessageStore>();
+            let has_store = store.is_some();
+            let added = store.map_or(0, |store| store.added.len());
+            HttpResponse::ok(format!("store={has_store},added={added}"))
         })
     });
 
@@ -299,7 +300,7 @@ async fn test_message_middleware_initializes_message_store() {
     let response = pipeline.process(request, &handler).await;
     let body = String::from_utf8(response.content_bytes().unwrap()).unwrap();
     assert!(body.contains("store=true"));
-    assert!(body.contains("added=true"));
+    assert!(body.contains("added=0"));
 }
 
 // ═════════════════════════════════════════════════════════════════════
@@ -312,6 +313,7 @@ async fn test_locale_middleware_sets_language() {
     pipeline.add(LocaleMiddleware {
         default_language: "en".to_string(),
         supported_languages: vec!["en".to_string(), "fr".to_string(), "de".to_string()],
+        ..Default::default()
     });
 
     let handler: ViewHandler = Box::new(|req| {
@@ -506,7 +508,7 @@ async fn test_full_pipeline_security_auth_common() {
         hsts_seconds: 31_536_000,
         ..Default::default()
     });
-    pipeline.add(AuthenticationMiddleware);
+    pipeline.add(AuthenticationMiddleware::new());
     pipeline.add(LoginRequiredMiddleware::default());
     pipeline.add(CommonMiddleware::default());
 
@@ -78,6 +78,7 @@ impl Model for Post {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -179,6 +180,7 @@ impl Model for Comment {
             ],
             constraints: vec![],
             inheritance_type: InheritanceType::None,
+            db_schema: None,
         });
         &META
     }
@@ -0,0 +1,30 @@
+//! Deriving `Model` in a crate that reaches the ORM only through `django-rs`.
+//!
+//! This crate has no `chrono` dependency of its own, so the generated code
+//! must name chrono types through `django_rs_db`.
+
+// The derive refers to these crates by name.
+use django_rs::prelude::*;
+use django_rs::{core as django_rs_core, db as django_rs_db};
+
+#[derive(Debug, Clone, ModelDerive)]
+#[model(app = "blog", ordering = ["name"])]
+pub struct Tag {
+    #[field(primary_key, auto)]
+    pub id: i64,
+
+    #[field(max_length = 50)]
+    pub name: String,
+}
+
+#[test]
+fn test_model_derive_without_chrono() {
+    assert_eq!(Tag::table_name(), "blog_tag");
+    let mut tag = Tag {
+        id: 0,
+        name: "rust".to_string(),
+    };
+    let now = django_rs_db::chrono::Utc::now().naive_utc();
+    tag.apply_auto_timestamps(now, true);
+    assert_eq!(tag.name, "rust");
+}